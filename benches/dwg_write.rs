@@ -0,0 +1,37 @@
+//! DWG writer throughput.
+//!
+//! Run with `cargo bench --bench dwg_write`; compare against
+//! `--no-default-features` to see the effect of parallel page compression.
+
+use acadrust::entities::{EntityType, Line};
+use acadrust::io::dwg::DwgWriter;
+use acadrust::types::{DxfVersion, Vector3};
+use acadrust::CadDocument;
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+
+fn document(lines: usize) -> CadDocument {
+    let mut doc = CadDocument::with_version(DxfVersion::AC1018);
+    for i in 0..lines {
+        let x = i as f64;
+        let line = Line::from_points(Vector3::new(x, 0.0, 0.0), Vector3::new(x, 1.0, 0.0));
+        doc.add_entity(EntityType::Line(line)).unwrap();
+    }
+    doc
+}
+
+fn bench_write(c: &mut Criterion) {
+    let mut group = c.benchmark_group("dwg_write");
+    group.sample_size(10);
+    for lines in [10_000usize, 100_000] {
+        let doc = document(lines);
+        let size = DwgWriter::write(&doc).unwrap().len();
+        group.throughput(Throughput::Bytes(size as u64));
+        group.bench_function(format!("ac1018_{lines}_lines"), |b| {
+            b.iter(|| DwgWriter::write(&doc).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_write);
+criterion_main!(benches);
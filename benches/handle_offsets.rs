@@ -0,0 +1,53 @@
+//! Handle → offset resolution.
+//!
+//! Compares lookups in the sorted [`HandleOffsetTable`] against a `HashMap`
+//! with the same contents, and times encoding/decoding the handle section.
+
+use std::collections::HashMap;
+
+use acadrust::io::dwg::reader::DwgHandleReader;
+use acadrust::io::dwg::writer::DwgHandleWriter;
+use acadrust::io::dwg::HandleOffsetTable;
+use acadrust::types::DxfVersion;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+const HANDLES: u64 = 200_000;
+
+fn entries() -> Vec<(u64, i64)> {
+    // Handles are sparse in real files; offsets grow with the objects section
+    (0..HANDLES).map(|i| (0x100 + i * 3, (i * 48) as i64)).collect()
+}
+
+fn bench_lookup(c: &mut Criterion) {
+    let entries = entries();
+    let table = HandleOffsetTable::from_unsorted(entries.clone());
+    let map: HashMap<u64, i64> = entries.iter().copied().collect();
+    // Visit handles in a scattered order, as the object reader's queue does
+    let probes: Vec<u64> = (0..HANDLES).map(|i| entries[((i * 7919) % HANDLES) as usize].0).collect();
+
+    let mut group = c.benchmark_group("handle_lookup");
+    group.bench_function("sorted_table", |b| {
+        b.iter(|| probes.iter().filter_map(|&h| table.get(black_box(h))).sum::<i64>())
+    });
+    group.bench_function("hash_map", |b| {
+        b.iter(|| probes.iter().filter_map(|h| map.get(black_box(h))).sum::<i64>())
+    });
+    group.finish();
+}
+
+fn bench_section(c: &mut Criterion) {
+    let table = HandleOffsetTable::from_unsorted(entries());
+    let data = DwgHandleWriter::new(DxfVersion::AC1018).write(&table, 0).unwrap();
+
+    let mut group = c.benchmark_group("handle_section");
+    group.bench_function("write", |b| {
+        b.iter(|| DwgHandleWriter::new(DxfVersion::AC1018).write(&table, 0).unwrap())
+    });
+    group.bench_function("read", |b| {
+        b.iter(|| DwgHandleReader::new(DxfVersion::AC1018, data.clone()).read().unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_lookup, bench_section);
+criterion_main!(benches);
@@ -0,0 +1,75 @@
+//! Read, write and round-trip throughput across file versions.
+//!
+//! Each version gets a generated drawing from
+//! [`acadrust::bench::sample_document`], serialized once as ASCII DXF and,
+//! for versions the DWG writer supports, as DWG.
+
+use std::io::Cursor;
+
+use acadrust::bench::sample_document;
+use acadrust::io::dwg::{DwgReader, DwgWriter};
+use acadrust::io::dxf::{DxfReader, DxfWriter};
+use acadrust::types::DxfVersion;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+const ENTITIES: usize = 20_000;
+
+const VERSIONS: [DxfVersion; 4] = [
+    DxfVersion::AC1015,
+    DxfVersion::AC1018,
+    DxfVersion::AC1024,
+    DxfVersion::AC1032,
+];
+
+fn bench_dxf(c: &mut Criterion) {
+    let mut group = c.benchmark_group("dxf");
+    group.sample_size(10);
+    for version in VERSIONS {
+        let doc = sample_document(version, ENTITIES);
+        let bytes = DxfWriter::new(doc.clone()).write_to_vec().unwrap();
+        group.throughput(Throughput::Bytes(bytes.len() as u64));
+        let id = version.as_str();
+
+        group.bench_with_input(BenchmarkId::new("read", id), &bytes, |b, bytes| {
+            b.iter(|| DxfReader::from_bytes(bytes.clone()).unwrap().read().unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("write", id), &doc, |b, doc| {
+            b.iter(|| DxfWriter::new(doc.clone()).write_to_vec().unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("roundtrip", id), &bytes, |b, bytes| {
+            b.iter(|| {
+                let doc = DxfReader::from_bytes(bytes.clone()).unwrap().read().unwrap();
+                DxfWriter::new(doc).write_to_vec().unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+fn bench_dwg(c: &mut Criterion) {
+    let mut group = c.benchmark_group("dwg");
+    group.sample_size(10);
+    for version in VERSIONS {
+        let doc = sample_document(version, ENTITIES);
+        let bytes = DwgWriter::write(&doc).unwrap();
+        group.throughput(Throughput::Bytes(bytes.len() as u64));
+        let id = version.as_str();
+
+        group.bench_with_input(BenchmarkId::new("read", id), &bytes, |b, bytes| {
+            b.iter(|| DwgReader::from_reader(Cursor::new(bytes.clone())).unwrap().read().unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("write", id), &doc, |b, doc| {
+            b.iter(|| DwgWriter::write(doc).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("roundtrip", id), &bytes, |b, bytes| {
+            b.iter(|| {
+                let doc = DwgReader::from_reader(Cursor::new(bytes.clone())).unwrap().read().unwrap();
+                DwgWriter::write(&doc).unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_dxf, bench_dwg);
+criterion_main!(benches);
@@ -0,0 +1,76 @@
+/*
+ * acadrust C API
+ *
+ * Build the shared library with
+ *     cargo rustc --release --lib --features ffi --crate-type cdylib
+ *
+ * Documents are opaque; free every document returned by acad_document_new,
+ * acad_document_open or acad_document_read with acad_document_free.
+ *
+ * Functions returning a string copy it into a caller buffer snprintf style:
+ * at most len - 1 bytes plus a NUL are written and the full length is
+ * returned, so a call with len == 0 sizes the buffer. Strings are UTF-8.
+ */
+
+#ifndef ACADRUST_H
+#define ACADRUST_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct AcadDocument AcadDocument;
+
+typedef enum AcadStatus {
+    ACAD_OK = 0,
+    /* A required pointer argument was NULL or a string was not UTF-8 */
+    ACAD_INVALID_ARGUMENT = 1,
+    ACAD_IO = 2,
+    /* Not a readable DWG/DXF/DXB file, or unsupported version */
+    ACAD_INVALID_FORMAT = 3,
+    /* No entity with the given handle */
+    ACAD_NOT_FOUND = 4,
+    ACAD_ERROR = 5,
+    ACAD_PANIC = 6
+} AcadStatus;
+
+typedef enum AcadFormat {
+    ACAD_FORMAT_DXF = 0,
+    ACAD_FORMAT_DXF_BINARY = 1,
+    ACAD_FORMAT_DWG = 2
+} AcadFormat;
+
+/* Message of the last error on this thread, or NULL; valid until the next
+ * failing call on the same thread */
+const char *acad_last_error(void);
+
+/* Documents */
+AcadDocument *acad_document_new(void);
+AcadStatus acad_document_open(const char *path, AcadDocument **out);
+AcadStatus acad_document_read(const uint8_t *data, size_t len, AcadDocument **out);
+/* format is an AcadFormat value, others fail with ACAD_INVALID_ARGUMENT;
+ * version is e.g. "AC1018", or NULL to keep the document's version */
+AcadStatus acad_document_write(const AcadDocument *doc, const char *path, uint32_t format, const char *version);
+void acad_document_free(AcadDocument *doc);
+size_t acad_document_version(const AcadDocument *doc, char *buf, size_t len);
+
+/* Entities, indexed in ascending handle order */
+size_t acad_document_entity_count(const AcadDocument *doc);
+/* 0 if index is out of range */
+uint64_t acad_document_entity_handle(const AcadDocument *doc, size_t index);
+/* DXF type name, e.g. "LINE"; 0 if the entity does not exist */
+size_t acad_entity_type(const AcadDocument *doc, uint64_t handle, char *buf, size_t len);
+size_t acad_entity_layer(const AcadDocument *doc, uint64_t handle, char *buf, size_t len);
+/* index: ACI (0 ByBlock, 256 ByLayer) or -1 for true color;
+ * rgb (may be NULL): 0xRRGGBB, or 0xFFFFFFFF for ByLayer/ByBlock */
+AcadStatus acad_entity_color(const AcadDocument *doc, uint64_t handle, int16_t *index, uint32_t *rgb);
+AcadStatus acad_entity_bounds(const AcadDocument *doc, uint64_t handle, double min[3], double max[3]);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* ACADRUST_H */
@@ -0,0 +1,193 @@
+//! Displayed layer, color and lineweight of entities
+//!
+//! An entity's color and lineweight may be `ByLayer`, taken from its
+//! layer, or `ByBlock`, taken from the insert showing the block it belongs
+//! to; an entity on layer `0` inside a block takes the insert's layer. The
+//! values shown therefore depend on the chain of inserts an entity is seen
+//! through, given as a [`BlockContext`]:
+//!
+//! ```rust,ignore
+//! // A line of block "DOOR", inserted by `door` inside the block inserted by `house`
+//! let context = BlockContext::new().inside(house).inside(door);
+//! let color = doc.effective_color(line, &context).unwrap();
+//! let weight = doc.effective_line_weight(line, &context).unwrap();
+//! ```
+//!
+//! Values that stay unresolved, `ByBlock` outside any insert or `ByLayer`
+//! on a missing layer, fall back to color 7 and the default lineweight, as
+//! drawing programs show them.
+
+use crate::document::CadDocument;
+use crate::entities::{Entity, EntityType};
+use crate::types::{Color, Handle, LineWeight};
+
+/// Inserts an entity is seen through, outermost first.
+///
+/// Dimensions, which show their anonymous block like inserts, may be listed
+/// as well. Entities of model space or a layout have an empty context.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BlockContext {
+    /// Handles of the inserts, outermost first
+    pub inserts: Vec<Handle>,
+}
+
+impl BlockContext {
+    /// No insert: the entity is drawn directly in model space or a layout
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The context seen through `insert`, nested in this one
+    pub fn inside(mut self, insert: Handle) -> Self {
+        self.inserts.push(insert);
+        self
+    }
+}
+
+/// Layer, color and lineweight an entity is displayed with
+#[derive(Debug, Clone, PartialEq)]
+pub struct EffectiveStyle {
+    /// Layer name, the insert's for entities on layer `0` of a block
+    pub layer: String,
+    /// Color, neither `ByLayer` nor `ByBlock`
+    pub color: Color,
+    /// Lineweight, neither `ByLayer` nor `ByBlock`
+    pub line_weight: LineWeight,
+}
+
+impl CadDocument {
+    /// Style `entity` is displayed with, inside a block shown by an insert
+    /// displayed with `parent`, or outside any block if `parent` is `None`
+    pub fn resolve_style(&self, entity: &dyn Entity, parent: Option<&EffectiveStyle>) -> EffectiveStyle {
+        let layer_name = match (parent, entity.layer()) {
+            (Some(parent), "0") => parent.layer.clone(),
+            (_, own) => own.to_string(),
+        };
+        let layer = self.layers.get(&layer_name);
+
+        let color = match entity.color() {
+            Color::ByLayer => layer.map_or(Color::ByLayer, |l| l.color),
+            Color::ByBlock => parent.map_or(Color::ByBlock, |p| p.color),
+            color => color,
+        };
+        let color = match color {
+            Color::ByLayer | Color::ByBlock => Color::Index(7),
+            color => color,
+        };
+
+        let line_weight = match entity.line_weight() {
+            LineWeight::ByLayer => layer.map_or(LineWeight::Default, |l| l.line_weight),
+            LineWeight::ByBlock => parent.map_or(LineWeight::Default, |p| p.line_weight),
+            weight => weight,
+        };
+        let line_weight = match line_weight {
+            LineWeight::ByLayer | LineWeight::ByBlock => LineWeight::Default,
+            weight => weight,
+        };
+
+        EffectiveStyle { layer: layer_name, color, line_weight }
+    }
+
+    /// Style the entity with `handle` is displayed with in `context`;
+    /// `None` if it or an insert of the context is not found
+    pub fn effective_style(&self, handle: Handle, context: &BlockContext) -> Option<EffectiveStyle> {
+        let mut parent = None;
+        for &insert in &context.inserts {
+            let insert = self.find_any_entity(insert)?;
+            parent = Some(self.resolve_style(insert.as_entity(), parent.as_ref()));
+        }
+        let entity = self.find_any_entity(handle)?;
+        Some(self.resolve_style(entity.as_entity(), parent.as_ref()))
+    }
+
+    /// Color the entity with `handle` is displayed with in `context`
+    pub fn effective_color(&self, handle: Handle, context: &BlockContext) -> Option<Color> {
+        self.effective_style(handle, context).map(|style| style.color)
+    }
+
+    /// Lineweight the entity with `handle` is displayed with in `context`
+    pub fn effective_line_weight(&self, handle: Handle, context: &BlockContext) -> Option<LineWeight> {
+        self.effective_style(handle, context).map(|style| style.line_weight)
+    }
+
+    /// Entity with `handle`, in model space, a layout or a block definition
+    fn find_any_entity(&self, handle: Handle) -> Option<&EntityType> {
+        if handle.is_null() {
+            return None;
+        }
+        self.get_entity(handle).or_else(|| {
+            self.block_records
+                .iter()
+                .flat_map(|record| record.entities.iter())
+                .find(|e| e.common().handle == handle)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::{Insert, Line};
+    use crate::tables::{BlockRecord, Layer};
+    use crate::types::Vector3;
+
+    #[test]
+    fn test_effective_color_and_line_weight_through_nested_inserts() {
+        let mut doc = CadDocument::new();
+        let mut walls = Layer::new("WALLS");
+        walls.color = Color::Index(1);
+        walls.line_weight = LineWeight::Value(50);
+        doc.layers.add(walls).unwrap();
+        let mut doors = Layer::new("DOORS");
+        doors.color = Color::Index(3);
+        doors.line_weight = LineWeight::Value(25);
+        doc.layers.add(doors).unwrap();
+
+        // DOOR: a ByBlock line and a ByLayer line on layer 0
+        let mut door = BlockRecord::new("DOOR");
+        door.handle = doc.allocate_handle();
+        let mut by_block = Line::from_points(Vector3::ZERO, Vector3::new(1.0, 0.0, 0.0));
+        by_block.common.handle = doc.allocate_handle();
+        by_block.common.color = Color::ByBlock;
+        by_block.common.line_weight = LineWeight::ByBlock;
+        let mut by_layer = by_block.clone();
+        by_layer.common.handle = doc.allocate_handle();
+        by_layer.common.color = Color::ByLayer;
+        by_layer.common.line_weight = LineWeight::ByLayer;
+        let (by_block_handle, by_layer_handle) = (by_block.common.handle, by_layer.common.handle);
+        door.entities.push(EntityType::Line(by_block));
+        door.entities.push(EntityType::Line(by_layer));
+        doc.block_records.add(door).unwrap();
+
+        // HOUSE: a ByBlock insert of DOOR on layer DOORS
+        let mut house = BlockRecord::new("HOUSE");
+        house.handle = doc.allocate_handle();
+        let mut inner = Insert::new("DOOR", Vector3::ZERO);
+        inner.common.handle = doc.allocate_handle();
+        inner.common.layer = "DOORS".to_string();
+        inner.common.color = Color::ByBlock;
+        inner.common.line_weight = LineWeight::ByBlock;
+        let inner_handle = inner.common.handle;
+        house.entities.push(EntityType::Insert(inner));
+        doc.block_records.add(house).unwrap();
+
+        let mut outer = Insert::new("HOUSE", Vector3::ZERO);
+        outer.common.layer = "WALLS".to_string();
+        outer.common.color = Color::Index(5);
+        let outer = doc.add_entity(EntityType::Insert(outer)).unwrap();
+
+        let context = BlockContext::new().inside(outer).inside(inner_handle);
+        // ByBlock goes up to the outer insert: its color and its layer's weight
+        assert_eq!(doc.effective_color(by_block_handle, &context), Some(Color::Index(5)));
+        assert_eq!(doc.effective_line_weight(by_block_handle, &context), Some(LineWeight::Value(50)));
+        // Layer 0 takes the inner insert's layer
+        let style = doc.effective_style(by_layer_handle, &context).unwrap();
+        assert_eq!(style.layer, "DOORS");
+        assert_eq!((style.color, style.line_weight), (Color::Index(3), LineWeight::Value(25)));
+
+        // Outside any insert ByBlock shows as color 7 and the default weight
+        assert_eq!(doc.effective_color(by_block_handle, &BlockContext::new()), Some(Color::Index(7)));
+        assert_eq!(doc.effective_line_weight(by_block_handle, &BlockContext::new()), Some(LineWeight::Default));
+        assert_eq!(doc.effective_color(Handle::new(0xFFFFF), &context), None);
+    }
+}
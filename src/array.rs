@@ -0,0 +1,230 @@
+//! Rectangular and polar arrays
+//!
+//! Copies of an entity laid out on a grid or around a center, added to the
+//! document next to the original. The original is the first item of the
+//! array and stays where it is.
+//!
+//! ```rust,ignore
+//! use acadrust::array;
+//!
+//! let bolt = doc.add_entity(EntityType::Circle(Circle::from_coords(10.0, 0.0, 0.0, 1.0)))?;
+//! array::polar(&mut doc, bolt, Vector3::ZERO, 6, std::f64::consts::TAU)?;
+//! array::rectangular(&mut doc, bolt, 3, 4, 25.0, 20.0)?;
+//! ```
+
+use std::f64::consts::TAU;
+
+use crate::document::CadDocument;
+use crate::entities::{Entity, EntityType};
+use crate::error::{DxfError, Result};
+use crate::types::{Handle, Transform, Vector3};
+
+/// Copy the entity `source` into a grid of `rows` × `columns` items,
+/// `dx` apart along X and `dy` apart along Y.
+///
+/// Returns the handles of the copies, row by row; the original is the item
+/// in the first row and column. Fails when `source` is not an entity of
+/// the document.
+pub fn rectangular(
+    document: &mut CadDocument,
+    source: Handle,
+    rows: usize,
+    columns: usize,
+    dx: f64,
+    dy: f64,
+) -> Result<Vec<Handle>> {
+    let entity = source_entity(document, source)?;
+    let cells = (0..rows).flat_map(|row| (0..columns).map(move |column| (row, column)));
+    cells
+        .skip(1)
+        .map(|(row, column)| {
+            let offset = Vector3::new(column as f64 * dx, row as f64 * dy, 0.0);
+            add_copy(document, &entity, &Transform::from_translation(offset), 0.0)
+        })
+        .collect()
+}
+
+/// Copy the entity `source` around `center` into `count` items spread over
+/// `angle` radians, counterclockwise for a positive angle, rotating each
+/// copy with its position.
+///
+/// A full turn spaces the items evenly around the circle; a smaller angle
+/// puts the last item at the end of the arc. Returns the handles of the
+/// copies in order; the original is the first item. Fails when `source`
+/// is not an entity of the document.
+pub fn polar(
+    document: &mut CadDocument,
+    source: Handle,
+    center: Vector3,
+    count: usize,
+    angle: f64,
+) -> Result<Vec<Handle>> {
+    let entity = source_entity(document, source)?;
+    let step = if angle.abs() >= TAU - 1e-9 {
+        angle / count.max(1) as f64
+    } else {
+        angle / count.saturating_sub(1).max(1) as f64
+    };
+    (1..count)
+        .map(|item| {
+            let rotation = step * item as f64;
+            let transform = Transform::from_translation(-center)
+                .then(&Transform::from_rotation(Vector3::UNIT_Z, rotation))
+                .then(&Transform::from_translation(center));
+            add_copy(document, &entity, &transform, rotation)
+        })
+        .collect()
+}
+
+fn source_entity(document: &CadDocument, source: Handle) -> Result<EntityType> {
+    document
+        .get_entity(source)
+        .cloned()
+        .ok_or(DxfError::ObjectNotFound(source.value()))
+}
+
+/// Add a copy of `entity` moved by `transform`, which turns it by
+/// `rotation` about the Z axis
+fn add_copy(document: &mut CadDocument, entity: &EntityType, transform: &Transform, rotation: f64) -> Result<Handle> {
+    let mut copy = entity.clone();
+    copy.as_entity_mut().apply_transform(transform);
+    // Angles the transform leaves as they are
+    let turn = |angle: &mut f64| *angle = (*angle + rotation).rem_euclid(TAU);
+    match &mut copy {
+        EntityType::Arc(arc) => {
+            turn(&mut arc.start_angle);
+            turn(&mut arc.end_angle);
+        }
+        EntityType::Text(text) => turn(&mut text.rotation),
+        EntityType::MText(mtext) => turn(&mut mtext.rotation),
+        EntityType::Shape(shape) => turn(&mut shape.rotation),
+        EntityType::AttributeDefinition(attdef) => turn(&mut attdef.rotation),
+        EntityType::AttributeEntity(attribute) => turn(&mut attribute.rotation),
+        EntityType::Insert(insert) => {
+            turn(&mut insert.rotation);
+            for attribute in &mut insert.attributes {
+                attribute.apply_transform(transform);
+                turn(&mut attribute.rotation);
+            }
+        }
+        _ => {}
+    }
+
+    // The copy gets its own handles and no links of the original
+    let common = copy.common_mut();
+    common.handle = Handle::NULL;
+    common.reactors.clear();
+    common.xdictionary_handle = None;
+    match &mut copy {
+        EntityType::Insert(insert) => {
+            for attribute in &mut insert.attributes {
+                attribute.common.handle = document.allocate_handle();
+            }
+        }
+        EntityType::Polyline(polyline) => {
+            for vertex in &mut polyline.vertices {
+                vertex.handle = Handle::NULL;
+            }
+            polyline.seqend_handle = None;
+        }
+        EntityType::Polyline2D(polyline) => {
+            for vertex in &mut polyline.vertices {
+                vertex.handle = Handle::NULL;
+            }
+            polyline.seqend_handle = None;
+        }
+        EntityType::Polyline3D(polyline) => {
+            for vertex in &mut polyline.vertices {
+                vertex.handle = Handle::NULL;
+            }
+            polyline.seqend_handle = None;
+        }
+        EntityType::PolyfaceMesh(mesh) => {
+            for vertex in &mut mesh.vertices {
+                vertex.common.handle = Handle::NULL;
+            }
+            for face in &mut mesh.faces {
+                face.common.handle = Handle::NULL;
+            }
+            mesh.seqend_handle = None;
+        }
+        EntityType::PolygonMesh(mesh) => {
+            for vertex in &mut mesh.vertices {
+                vertex.common.handle = Handle::NULL;
+            }
+        }
+        _ => {}
+    }
+    document.add_entity(copy)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::{Arc, AttributeEntity, Circle, Insert};
+    use std::f64::consts::FRAC_PI_2;
+
+    fn near(a: Vector3, b: Vector3) -> bool {
+        a.distance(&b) < 1e-9
+    }
+
+    #[test]
+    fn test_rectangular_array() {
+        let mut doc = CadDocument::new();
+        let source = doc.add_entity(EntityType::Circle(Circle::from_coords(1.0, 1.0, 0.0, 0.5))).unwrap();
+        let copies = rectangular(&mut doc, source, 2, 3, 10.0, 5.0).unwrap();
+        assert_eq!(copies.len(), 5);
+        let centers: Vec<Vector3> = copies
+            .iter()
+            .map(|h| match doc.get_entity(*h) {
+                Some(EntityType::Circle(circle)) => circle.center,
+                other => panic!("{other:?}"),
+            })
+            .collect();
+        assert_eq!(centers[1], Vector3::new(21.0, 1.0, 0.0));
+        assert_eq!(centers[2], Vector3::new(1.0, 6.0, 0.0));
+        assert_eq!(centers[4], Vector3::new(21.0, 6.0, 0.0));
+        assert_eq!(doc.entity_count(), 6);
+
+        assert!(rectangular(&mut doc, Handle::new(0xFFFF), 2, 2, 1.0, 1.0).is_err());
+    }
+
+    #[test]
+    fn test_polar_array_rotates_items() {
+        let mut doc = CadDocument::new();
+        let arc = Arc::from_coords(10.0, 0.0, 0.0, 1.0, 0.0, FRAC_PI_2);
+        let source = doc.add_entity(EntityType::Arc(arc)).unwrap();
+        let copies = polar(&mut doc, source, Vector3::ZERO, 4, TAU).unwrap();
+        assert_eq!(copies.len(), 3);
+        let Some(EntityType::Arc(quarter)) = doc.get_entity(copies[0]) else { panic!() };
+        assert!(near(quarter.center, Vector3::new(0.0, 10.0, 0.0)));
+        assert!((quarter.start_angle - FRAC_PI_2).abs() < 1e-12);
+        assert!((quarter.end_angle - 2.0 * FRAC_PI_2).abs() < 1e-12);
+
+        // Three items over a half turn: the last one at its end
+        let copies = polar(&mut doc, source, Vector3::ZERO, 3, TAU / 2.0).unwrap();
+        let Some(EntityType::Arc(last)) = doc.get_entity(copies[1]) else { panic!() };
+        assert!(near(last.center, Vector3::new(-10.0, 0.0, 0.0)));
+    }
+
+    #[test]
+    fn test_copies_get_own_handles() {
+        let mut doc = CadDocument::new();
+        let mut insert = Insert::new("BOLT", Vector3::new(5.0, 0.0, 0.0));
+        let mut attribute = AttributeEntity::new("SIZE".to_string(), "M8".to_string());
+        attribute.insertion_point = Vector3::new(5.0, 1.0, 0.0);
+        attribute.common.handle = doc.allocate_handle();
+        insert.attributes.push(attribute);
+        insert.common.reactors.push(Handle::new(0x99));
+        let source = doc.add_entity(EntityType::Insert(insert)).unwrap();
+
+        let copies = polar(&mut doc, source, Vector3::ZERO, 2, TAU).unwrap();
+        let Some(EntityType::Insert(copy)) = doc.get_entity(copies[0]) else { panic!() };
+        let Some(EntityType::Insert(original)) = doc.get_entity(source) else { panic!() };
+        assert!(copy.common.reactors.is_empty());
+        assert_ne!(copy.attributes[0].common.handle, original.attributes[0].common.handle);
+        assert!(near(copy.insert_point, Vector3::new(-5.0, 0.0, 0.0)));
+        assert!(near(copy.attributes[0].insertion_point, Vector3::new(-5.0, -1.0, 0.0)));
+        assert!((copy.rotation - TAU / 2.0).abs() < 1e-12);
+    }
+}
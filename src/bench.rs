@@ -0,0 +1,213 @@
+//! Read profiling and benchmark corpora.
+//!
+//! [`profile_read`] reads a DXF or DWG file and reports how long each
+//! [`ReadPhase`] took, using the readers' progress callbacks, so timings can
+//! be compared between versions and machines without a profiler.
+//! [`sample_document`] generates the synthetic drawings used by the
+//! criterion benches in `benches/`.
+//!
+//! ```rust,ignore
+//! let (doc, profile) = acadrust::bench::profile_read("plan.dwg")?;
+//! println!("{profile}");
+//! ```
+
+use std::cell::RefCell;
+use std::fmt;
+use std::path::Path;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use crate::detect::{detect, FileFormat};
+use crate::document::CadDocument;
+use crate::entities::{Arc, Circle, EntityType, Line, LwPolyline, Text};
+use crate::error::Result;
+use crate::io::dwg::DwgReader;
+use crate::io::dxb::DxbReader;
+use crate::io::dxf::DxfReader;
+use crate::io::progress::{ReadPhase, ReadProgress};
+use crate::tables::Layer;
+use crate::types::{DxfVersion, Vector2, Vector3};
+
+/// Time spent in one phase of a read.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PhaseTiming {
+    /// Phase reported by the reader.
+    pub phase: ReadPhase,
+    /// Wall-clock time until the next phase started.
+    pub elapsed: Duration,
+}
+
+/// Result of [`profile_read`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReadProfile {
+    /// Detected file format.
+    pub format: FileFormat,
+    /// File size in bytes.
+    pub bytes: u64,
+    /// Phases in the order they were entered.
+    pub phases: Vec<PhaseTiming>,
+    /// Wall-clock time of the whole read, including opening the file.
+    pub total: Duration,
+    /// Entities in the document.
+    pub entities: usize,
+}
+
+impl ReadProfile {
+    /// Total time spent in `phase`; phases may be entered more than once.
+    pub fn phase(&self, phase: ReadPhase) -> Option<Duration> {
+        self.phases
+            .iter()
+            .filter(|t| t.phase == phase)
+            .map(|t| t.elapsed)
+            .reduce(|a, b| a + b)
+    }
+
+    /// Read throughput in MiB/s.
+    pub fn throughput(&self) -> f64 {
+        let secs = self.total.as_secs_f64();
+        if secs == 0.0 {
+            return 0.0;
+        }
+        self.bytes as f64 / (1024.0 * 1024.0) / secs
+    }
+}
+
+impl fmt::Display for ReadProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:?}, {} bytes, {} entities: {:.3} ms ({:.1} MiB/s)",
+            self.format,
+            self.bytes,
+            self.entities,
+            self.total.as_secs_f64() * 1000.0,
+            self.throughput()
+        )?;
+        for timing in &self.phases {
+            writeln!(f, "  {:<12} {:>10.3} ms", format!("{:?}", timing.phase), timing.elapsed.as_secs_f64() * 1000.0)?;
+        }
+        Ok(())
+    }
+}
+
+/// Read a DXF, DWG or DXB file and time each phase of the read.
+///
+/// The format is found with [`detect`]. The DXB reader reports no
+/// progress, so DXB profiles have no phases.
+pub fn profile_read<P: AsRef<Path>>(path: P) -> Result<(CadDocument, ReadProfile)> {
+    let path = path.as_ref();
+    let start = Instant::now();
+    let bytes = std::fs::metadata(path)?.len();
+    let format = detect(path)?.format;
+
+    let recorder = PhaseRecorder::default();
+    let sink = recorder.clone();
+    let document = match format {
+        FileFormat::Dxf => DxfReader::from_file(path)?
+            .with_progress(move |p| sink.observe(p))
+            .read()?,
+        FileFormat::Dwg => DwgReader::from_file(path)?
+            .with_progress(move |p| sink.observe(p))
+            .read()?,
+        FileFormat::Dxb => DxbReader::from_file(path)?.read()?,
+    };
+    let end = Instant::now();
+
+    let profile = ReadProfile {
+        format,
+        bytes,
+        phases: recorder.finish(end),
+        total: end - start,
+        entities: document.entity_count(),
+    };
+    Ok((document, profile))
+}
+
+/// Phase start times collected from a progress callback.
+#[derive(Clone, Default)]
+struct PhaseRecorder {
+    starts: Rc<RefCell<Vec<(ReadPhase, Instant)>>>,
+}
+
+impl PhaseRecorder {
+    fn observe(&self, progress: &ReadProgress) {
+        let mut starts = self.starts.borrow_mut();
+        if starts.last().map(|&(phase, _)| phase) != Some(progress.phase) {
+            starts.push((progress.phase, Instant::now()));
+        }
+    }
+
+    /// Turn start times into durations; the last phase ends at `end`.
+    fn finish(self, end: Instant) -> Vec<PhaseTiming> {
+        let starts = self.starts.borrow();
+        starts
+            .iter()
+            .enumerate()
+            .map(|(i, &(phase, start))| {
+                let next = starts.get(i + 1).map_or(end, |&(_, t)| t);
+                PhaseTiming {
+                    phase,
+                    elapsed: next - start,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Build a synthetic drawing with roughly `entities` model-space entities.
+///
+/// Lines, circles, arcs, light-weight polylines and text are spread over
+/// eight layers on a grid, which exercises the common entity readers and
+/// writers without depending on sample files.
+pub fn sample_document(version: DxfVersion, entities: usize) -> CadDocument {
+    const LAYERS: usize = 8;
+
+    let mut doc = CadDocument::with_version(version);
+    for i in 0..LAYERS {
+        let _ = doc.layers.add(Layer::new(format!("LAYER_{i}")));
+    }
+
+    let columns = (entities as f64).sqrt().ceil().max(1.0) as usize;
+    for i in 0..entities {
+        let x = (i % columns) as f64 * 10.0;
+        let y = (i / columns) as f64 * 10.0;
+        let mut entity = match i % 5 {
+            0 => EntityType::Line(Line::from_coords(x, y, 0.0, x + 8.0, y + 8.0, 0.0)),
+            1 => EntityType::Circle(Circle::from_coords(x + 4.0, y + 4.0, 0.0, 3.0)),
+            2 => EntityType::Arc(Arc::from_coords(x + 4.0, y + 4.0, 0.0, 3.0, 0.0, std::f64::consts::PI)),
+            3 => EntityType::LwPolyline(LwPolyline::from_points(vec![
+                Vector2::new(x, y),
+                Vector2::new(x + 8.0, y),
+                Vector2::new(x + 8.0, y + 8.0),
+                Vector2::new(x, y + 8.0),
+            ])),
+            _ => EntityType::Text(Text::with_value(format!("T{i}"), Vector3::new(x, y, 0.0)).with_height(2.5)),
+        };
+        entity.as_entity_mut().set_layer(format!("LAYER_{}", i % LAYERS));
+        let _ = doc.add_entity(entity);
+    }
+    doc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::dxf::DxfWriter;
+
+    #[test]
+    fn test_profile_read_reports_phases() {
+        let doc = sample_document(DxfVersion::AC1015, 50);
+        let path = std::env::temp_dir().join(format!("acadrust_profile_{}.dxf", std::process::id()));
+        DxfWriter::new(doc).write_to_file(&path).unwrap();
+
+        let (doc, profile) = profile_read(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(profile.format, FileFormat::Dxf);
+        assert_eq!(profile.entities, doc.entity_count());
+        assert_eq!(profile.entities, 50);
+        assert!(profile.phase(ReadPhase::Entities).is_some());
+        let phases: Duration = profile.phases.iter().map(|t| t.elapsed).sum();
+        assert!(phases <= profile.total);
+    }
+}
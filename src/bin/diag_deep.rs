@@ -33,7 +33,7 @@ fn test_version(version: DxfVersion, is_r2010_plus: bool) {
         .find(|b| b.is_model_space())
         .map(|b| b.handle.value())
         .unwrap();
-    let ms_offset = *handle_map.get(&ms_handle).unwrap() as usize;
+    let ms_offset = handle_map.get(ms_handle).unwrap() as usize;
     println!("Model Space block record: handle={:#X}, offset={}", ms_handle, ms_offset);
 
     // Parse the BLOCK_RECORD at ms_offset
@@ -45,12 +45,12 @@ fn test_version(version: DxfVersion, is_r2010_plus: bool) {
         .find(|b| b.is_model_space())
         .map(|b| b.block_entity_handle.value())
         .unwrap();
-    let blk_offset = *handle_map.get(&blk_handle).unwrap() as usize;
+    let blk_offset = handle_map.get(blk_handle).unwrap() as usize;
     println!("\n--- Inspecting BLOCK entity at offset {} ---", blk_offset);
     inspect_object(&objects_data, blk_offset, blk_handle, is_r2010_plus);
 
     // Check the Line entity
-    let ent_offset = *handle_map.get(&entity_handle.value()).unwrap() as usize;
+    let ent_offset = handle_map.get(entity_handle.value()).unwrap() as usize;
     println!("\n--- Inspecting Line entity at offset {} ---", ent_offset);
     inspect_object(&objects_data, ent_offset, entity_handle.value(), is_r2010_plus);
 
@@ -59,7 +59,7 @@ fn test_version(version: DxfVersion, is_r2010_plus: bool) {
         .find(|b| b.is_model_space())
         .map(|b| b.block_end_handle.value())
         .unwrap();
-    let endblk_offset = *handle_map.get(&endblk_handle).unwrap() as usize;
+    let endblk_offset = handle_map.get(endblk_handle).unwrap() as usize;
     println!("\n--- Inspecting ENDBLK entity at offset {} ---", endblk_offset);
     inspect_object(&objects_data, endblk_offset, endblk_handle, is_r2010_plus);
 }
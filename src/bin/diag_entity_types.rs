@@ -78,12 +78,12 @@ fn main() {
             mtext.insertion_point = Vector3::new(30.0, 0.0, 0.0);
             mtext.height = 2.5;
             mtext.rectangle_width = 25.0;
-            doc.add_entity(EntityType::MText(mtext));
-            doc.add_entity(EntityType::Dimension(Dimension::Aligned(DimensionAligned::new(Vector3::new(0.0, 60.0, 0.0), Vector3::new(20.0, 60.0, 0.0)))));
-            doc.add_entity(EntityType::Dimension(Dimension::Linear(DimensionLinear::new(Vector3::new(0.0, 90.0, 0.0), Vector3::new(15.0, 98.0, 0.0)))));
-            doc.add_entity(EntityType::Dimension(Dimension::Radius(DimensionRadius::new(Vector3::new(10.0, 125.0, 0.0), Vector3::new(18.0, 125.0, 0.0)))));
-            doc.add_entity(EntityType::Dimension(Dimension::Diameter(DimensionDiameter::new(Vector3::new(40.0, 125.0, 0.0), Vector3::new(50.0, 125.0, 0.0)))));
-            doc.add_entity(EntityType::Dimension(Dimension::Angular3Pt(DimensionAngular3Pt::new(Vector3::new(0.0, 150.0, 0.0), Vector3::new(10.0, 160.0, 0.0), Vector3::new(20.0, 150.0, 0.0)))));
+            doc.add_entity(EntityType::MText(Box::new(mtext)));
+            doc.add_entity(EntityType::Dimension(Box::new(Dimension::Aligned(DimensionAligned::new(Vector3::new(0.0, 60.0, 0.0), Vector3::new(20.0, 60.0, 0.0))))));
+            doc.add_entity(EntityType::Dimension(Box::new(Dimension::Linear(DimensionLinear::new(Vector3::new(0.0, 90.0, 0.0), Vector3::new(15.0, 98.0, 0.0))))));
+            doc.add_entity(EntityType::Dimension(Box::new(Dimension::Radius(DimensionRadius::new(Vector3::new(10.0, 125.0, 0.0), Vector3::new(18.0, 125.0, 0.0))))));
+            doc.add_entity(EntityType::Dimension(Box::new(Dimension::Diameter(DimensionDiameter::new(Vector3::new(40.0, 125.0, 0.0), Vector3::new(50.0, 125.0, 0.0))))));
+            doc.add_entity(EntityType::Dimension(Box::new(Dimension::Angular3Pt(DimensionAngular3Pt::new(Vector3::new(0.0, 150.0, 0.0), Vector3::new(10.0, 160.0, 0.0), Vector3::new(20.0, 150.0, 0.0))))));
             doc
         });
         // FAIL on AC1027/AC1032: 5text + mtext + 6 dims (add angular2ln) = 12 entities
@@ -99,13 +99,13 @@ fn main() {
             mtext.insertion_point = Vector3::new(30.0, 0.0, 0.0);
             mtext.height = 2.5;
             mtext.rectangle_width = 25.0;
-            doc.add_entity(EntityType::MText(mtext));
-            doc.add_entity(EntityType::Dimension(Dimension::Aligned(DimensionAligned::new(Vector3::new(0.0, 60.0, 0.0), Vector3::new(20.0, 60.0, 0.0)))));
-            doc.add_entity(EntityType::Dimension(Dimension::Linear(DimensionLinear::new(Vector3::new(0.0, 90.0, 0.0), Vector3::new(15.0, 98.0, 0.0)))));
-            doc.add_entity(EntityType::Dimension(Dimension::Radius(DimensionRadius::new(Vector3::new(10.0, 125.0, 0.0), Vector3::new(18.0, 125.0, 0.0)))));
-            doc.add_entity(EntityType::Dimension(Dimension::Diameter(DimensionDiameter::new(Vector3::new(40.0, 125.0, 0.0), Vector3::new(50.0, 125.0, 0.0)))));
-            doc.add_entity(EntityType::Dimension(Dimension::Angular3Pt(DimensionAngular3Pt::new(Vector3::new(0.0, 150.0, 0.0), Vector3::new(10.0, 160.0, 0.0), Vector3::new(20.0, 150.0, 0.0)))));
-            doc.add_entity(EntityType::Dimension(Dimension::Angular2Ln(DimensionAngular2Ln::new(Vector3::new(30.0, 150.0, 0.0), Vector3::new(40.0, 160.0, 0.0), Vector3::new(50.0, 150.0, 0.0)))));
+            doc.add_entity(EntityType::MText(Box::new(mtext)));
+            doc.add_entity(EntityType::Dimension(Box::new(Dimension::Aligned(DimensionAligned::new(Vector3::new(0.0, 60.0, 0.0), Vector3::new(20.0, 60.0, 0.0))))));
+            doc.add_entity(EntityType::Dimension(Box::new(Dimension::Linear(DimensionLinear::new(Vector3::new(0.0, 90.0, 0.0), Vector3::new(15.0, 98.0, 0.0))))));
+            doc.add_entity(EntityType::Dimension(Box::new(Dimension::Radius(DimensionRadius::new(Vector3::new(10.0, 125.0, 0.0), Vector3::new(18.0, 125.0, 0.0))))));
+            doc.add_entity(EntityType::Dimension(Box::new(Dimension::Diameter(DimensionDiameter::new(Vector3::new(40.0, 125.0, 0.0), Vector3::new(50.0, 125.0, 0.0))))));
+            doc.add_entity(EntityType::Dimension(Box::new(Dimension::Angular3Pt(DimensionAngular3Pt::new(Vector3::new(0.0, 150.0, 0.0), Vector3::new(10.0, 160.0, 0.0), Vector3::new(20.0, 150.0, 0.0))))));
+            doc.add_entity(EntityType::Dimension(Box::new(Dimension::Angular2Ln(DimensionAngular2Ln::new(Vector3::new(30.0, 150.0, 0.0), Vector3::new(40.0, 160.0, 0.0), Vector3::new(50.0, 150.0, 0.0))))));
             doc
         });
     }
@@ -154,14 +154,14 @@ fn run_tests(version: DxfVersion) {
         ..Default::default()
     })));
 
-    test_entity("MText", version, make_doc(version, EntityType::MText(MText {
+    test_entity("MText", version, make_doc(version, EntityType::MText(Box::new(MText {
         common: EntityCommon::default(),
         value: "Hello MText".to_string(),
         insertion_point: Vector3::new(0.0, 0.0, 0.0),
         height: 2.5,
         rectangle_width: 50.0,
         ..Default::default()
-    })));
+    }))));
 
     test_entity("Ellipse", version, make_doc(version, EntityType::Ellipse(Ellipse {
         common: EntityCommon::default(),
@@ -207,7 +207,7 @@ fn run_tests(version: DxfVersion) {
             Vector3::new(10.0, 0.0, 0.0), Vector3::new(15.0, 10.0, 0.0),
         ];
         spline.knots = vec![0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0];
-        doc.add_entity(EntityType::Spline(spline));
+        doc.add_entity(EntityType::Spline(Box::new(spline)));
         doc
     });
 
@@ -227,13 +227,13 @@ fn run_tests(version: DxfVersion) {
         attributes: vec![],
     })));
 
-    test_entity("Viewport", version, make_doc(version, EntityType::Viewport(Viewport {
+    test_entity("Viewport", version, make_doc(version, EntityType::Viewport(Box::new(Viewport {
         common: EntityCommon::default(),
         center: Vector3::new(5.0, 5.0, 0.0),
         width: 10.0,
         height: 10.0,
         ..Default::default()
-    })));
+    }))));
 
     test_entity("DimLinear", version, {
         let mut doc = CadDocument::new();
@@ -242,7 +242,7 @@ fn run_tests(version: DxfVersion) {
         dim.base.definition_point = Vector3::new(10.0, 0.0, 0.0);
         dim.base.text_middle_point = Vector3::new(5.0, 5.0, 0.0);
         dim.definition_point = Vector3::new(10.0, 5.0, 0.0);
-        doc.add_entity(EntityType::Dimension(Dimension::Linear(dim)));
+        doc.add_entity(EntityType::Dimension(Box::new(Dimension::Linear(dim))));
         doc
     });
 
@@ -255,7 +255,7 @@ fn run_tests(version: DxfVersion) {
             Vector3::new(5.0, 5.0, 0.0),
             Vector3::new(10.0, 5.0, 0.0),
         ];
-        doc.add_entity(EntityType::Leader(leader));
+        doc.add_entity(EntityType::Leader(Box::new(leader)));
         doc
     });
 
@@ -280,7 +280,7 @@ fn run_tests(version: DxfVersion) {
         let mut doc = CadDocument::new();
         doc.version = version;
         let hatch = Hatch::default();
-        doc.add_entity(EntityType::Hatch(hatch));
+        doc.add_entity(EntityType::Hatch(Box::new(hatch)));
         doc
     });
 
@@ -311,7 +311,7 @@ fn run_tests(version: DxfVersion) {
             Vector3::new(0.0, 0.0, 0.0),
             Vector3::new(10.0, 5.0, 0.0),
         );
-        doc.add_entity(EntityType::Dimension(Dimension::Aligned(dim)));
+        doc.add_entity(EntityType::Dimension(Box::new(Dimension::Aligned(dim))));
         doc
     });
 
@@ -322,7 +322,7 @@ fn run_tests(version: DxfVersion) {
             Vector3::new(5.0, 5.0, 0.0),
             Vector3::new(10.0, 5.0, 0.0),
         );
-        doc.add_entity(EntityType::Dimension(Dimension::Radius(dim)));
+        doc.add_entity(EntityType::Dimension(Box::new(Dimension::Radius(dim))));
         doc
     });
 
@@ -333,7 +333,7 @@ fn run_tests(version: DxfVersion) {
             Vector3::new(5.0, 5.0, 0.0),
             Vector3::new(12.0, 5.0, 0.0),
         );
-        doc.add_entity(EntityType::Dimension(Dimension::Diameter(dim)));
+        doc.add_entity(EntityType::Dimension(Box::new(Dimension::Diameter(dim))));
         doc
     });
 
@@ -345,7 +345,7 @@ fn run_tests(version: DxfVersion) {
             Vector3::new(5.0, 5.0, 0.0),
             Vector3::new(10.0, 0.0, 0.0),
         );
-        doc.add_entity(EntityType::Dimension(Dimension::Angular3Pt(dim)));
+        doc.add_entity(EntityType::Dimension(Box::new(Dimension::Angular3Pt(dim))));
         doc
     });
 
@@ -356,7 +356,7 @@ fn run_tests(version: DxfVersion) {
             Vector3::new(0.0, 0.0, 0.0),
             Vector3::new(5.0, 5.0, 0.0),
         );
-        doc.add_entity(EntityType::Dimension(Dimension::Ordinate(dim)));
+        doc.add_entity(EntityType::Dimension(Box::new(Dimension::Ordinate(dim))));
         doc
     });
 
@@ -368,7 +368,7 @@ fn run_tests(version: DxfVersion) {
             Vector3::new(5.0, 5.0, 0.0),
             Vector3::new(10.0, 0.0, 0.0),
         );
-        doc.add_entity(EntityType::Dimension(Dimension::Angular2Ln(dim)));
+        doc.add_entity(EntityType::Dimension(Box::new(Dimension::Angular2Ln(dim))));
         doc
     });
 
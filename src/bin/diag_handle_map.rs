@@ -46,7 +46,7 @@ fn test_version(version: DxfVersion, label: &str) {
             }
 
             // Check if entity handle is present
-            if handle_map.contains_key(&entity_handle.value()) {
+            if handle_map.contains_key(entity_handle.value()) {
                 println!("✓ Entity handle {:#X} IS in handle map", entity_handle.value());
             } else {
                 println!("✗ Entity handle {:#X} NOT in handle map!", entity_handle.value());
@@ -270,7 +270,7 @@ fn build_all_entities(version: DxfVersion) -> CadDocument {
     spline.degree = 3;
     spline.knots = vec![0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0];
     spline.common.layer = "Polylines".to_string();
-    doc.add_entity(EntityType::Spline(spline)).unwrap();
+    doc.add_entity(EntityType::Spline(Box::new(spline))).unwrap();
 
     // === Row 3: Text ===
     x = 0.0;
@@ -295,7 +295,7 @@ fn build_all_entities(version: DxfVersion) -> CadDocument {
     mtext.rectangle_width = 20.0;
     mtext.common.layer = "Text".to_string();
     mtext.common.color = Color::BLUE;
-    doc.add_entity(EntityType::MText(mtext)).unwrap();
+    doc.add_entity(EntityType::MText(Box::new(mtext))).unwrap();
 
     // === Row 4: Dimensions ===
     x = 0.0;
@@ -305,28 +305,28 @@ fn build_all_entities(version: DxfVersion) -> CadDocument {
         Vector3::new(x, y, 0.0),
         Vector3::new(x + 15.0, y, 0.0),
     ));
-    doc.add_entity(EntityType::Dimension(dim_al)).unwrap();
+    doc.add_entity(EntityType::Dimension(Box::new(dim_al))).unwrap();
     x += sp;
 
     let dim_lin = Dimension::Linear(DimensionLinear::new(
         Vector3::new(x, y, 0.0),
         Vector3::new(x + 12.0, y + 8.0, 0.0),
     ));
-    doc.add_entity(EntityType::Dimension(dim_lin)).unwrap();
+    doc.add_entity(EntityType::Dimension(Box::new(dim_lin))).unwrap();
     x += sp;
 
     let dim_rad = Dimension::Radius(DimensionRadius::new(
         Vector3::new(x + 7.0, y + 5.0, 0.0),
         Vector3::new(x + 12.0, y + 5.0, 0.0),
     ));
-    doc.add_entity(EntityType::Dimension(dim_rad)).unwrap();
+    doc.add_entity(EntityType::Dimension(Box::new(dim_rad))).unwrap();
     x += sp;
 
     let dim_dia = Dimension::Diameter(DimensionDiameter::new(
         Vector3::new(x + 7.0, y + 5.0, 0.0),
         Vector3::new(x + 14.0, y + 5.0, 0.0),
     ));
-    doc.add_entity(EntityType::Dimension(dim_dia)).unwrap();
+    doc.add_entity(EntityType::Dimension(Box::new(dim_dia))).unwrap();
 
     // === Row 5: More dimensions + leaders ===
     x = 0.0;
@@ -337,14 +337,14 @@ fn build_all_entities(version: DxfVersion) -> CadDocument {
         Vector3::new(x + 7.0, y + 7.0, 0.0),
         Vector3::new(x + 14.0, y, 0.0),
     ));
-    doc.add_entity(EntityType::Dimension(dim_a3)).unwrap();
+    doc.add_entity(EntityType::Dimension(Box::new(dim_a3))).unwrap();
     x += sp;
 
     let dim_ord = Dimension::Ordinate(DimensionOrdinate::x_ordinate(
         Vector3::new(x, y, 0.0),
         Vector3::new(x + 5.0, y + 5.0, 0.0),
     ));
-    doc.add_entity(EntityType::Dimension(dim_ord)).unwrap();
+    doc.add_entity(EntityType::Dimension(Box::new(dim_ord))).unwrap();
     x += sp;
 
     let mut leader = Leader::new();
@@ -356,7 +356,7 @@ fn build_all_entities(version: DxfVersion) -> CadDocument {
     leader.arrow_enabled = true;
     leader.creation_type = LeaderCreationType::NoAnnotation;
     leader.common.layer = "Annotations".to_string();
-    doc.add_entity(EntityType::Leader(leader)).unwrap();
+    doc.add_entity(EntityType::Leader(Box::new(leader))).unwrap();
     x += sp;
 
     let mut tolerance = Tolerance::new();
@@ -374,14 +374,14 @@ fn build_all_entities(version: DxfVersion) -> CadDocument {
     hatch.is_solid = true;
     hatch.common.color = Color::from_index(1);
     hatch.paths.push(make_rect_boundary(x, y, 10.0, 10.0));
-    doc.add_entity(EntityType::Hatch(hatch)).unwrap();
+    doc.add_entity(EntityType::Hatch(Box::new(hatch))).unwrap();
     x += sp;
 
     let mut phatch = Hatch::new();
     phatch.pattern = HatchPattern::new("ANSI31");
     phatch.is_solid = false;
     phatch.paths.push(make_rect_boundary(x, y, 12.0, 8.0));
-    doc.add_entity(EntityType::Hatch(phatch)).unwrap();
+    doc.add_entity(EntityType::Hatch(Box::new(phatch))).unwrap();
 
     // === Row 7: Solids, faces, construction ===
     x = 0.0;
@@ -422,7 +422,7 @@ fn build_all_entities(version: DxfVersion) -> CadDocument {
     viewport.height = 10.0;
     viewport.view_center = Vector3::new(0.0, 0.0, 0.0);
     viewport.view_height = 100.0;
-    doc.add_entity(EntityType::Viewport(viewport)).unwrap();
+    doc.add_entity(EntityType::Viewport(Box::new(viewport))).unwrap();
     x += sp;
 
     let wipeout = Wipeout::rectangular(Vector3::new(x, y, 0.0), 12.0, 8.0);
@@ -510,7 +510,7 @@ fn build_basic_geometry(version: DxfVersion) -> CadDocument {
     spline.degree = 3;
     spline.knots = vec![0.0, 0.0, 0.0, 0.0, 0.5, 1.0, 1.0, 1.0, 1.0];
     spline.common.layer = "Polylines".to_string();
-    doc.add_entity(EntityType::Spline(spline)).unwrap();
+    doc.add_entity(EntityType::Spline(Box::new(spline))).unwrap();
 
     doc
 }
@@ -543,63 +543,63 @@ fn build_text_and_dims(version: DxfVersion) -> CadDocument {
     mtext.height = 2.5;
     mtext.rectangle_width = 25.0;
     mtext.common.layer = "Text".to_string();
-    doc.add_entity(EntityType::MText(mtext)).unwrap();
+    doc.add_entity(EntityType::MText(Box::new(mtext))).unwrap();
 
     // All 7 dimension types
     y = sp * 2.0;
 
     // 1. Aligned
-    doc.add_entity(EntityType::Dimension(Dimension::Aligned(DimensionAligned::new(
+    doc.add_entity(EntityType::Dimension(Box::new(Dimension::Aligned(DimensionAligned::new(
         Vector3::new(0.0, y, 0.0),
         Vector3::new(20.0, y, 0.0),
-    )))).unwrap();
+    ))))).unwrap();
 
     // 2. Linear
     y += sp;
-    doc.add_entity(EntityType::Dimension(Dimension::Linear(DimensionLinear::new(
+    doc.add_entity(EntityType::Dimension(Box::new(Dimension::Linear(DimensionLinear::new(
         Vector3::new(0.0, y, 0.0),
         Vector3::new(15.0, y + 8.0, 0.0),
-    )))).unwrap();
+    ))))).unwrap();
 
     // 3. Radius
     y += sp;
-    doc.add_entity(EntityType::Dimension(Dimension::Radius(DimensionRadius::new(
+    doc.add_entity(EntityType::Dimension(Box::new(Dimension::Radius(DimensionRadius::new(
         Vector3::new(10.0, y + 5.0, 0.0),
         Vector3::new(18.0, y + 5.0, 0.0),
-    )))).unwrap();
+    ))))).unwrap();
 
     // 4. Diameter
-    doc.add_entity(EntityType::Dimension(Dimension::Diameter(DimensionDiameter::new(
+    doc.add_entity(EntityType::Dimension(Box::new(Dimension::Diameter(DimensionDiameter::new(
         Vector3::new(sp + 10.0, y + 5.0, 0.0),
         Vector3::new(sp + 20.0, y + 5.0, 0.0),
-    )))).unwrap();
+    ))))).unwrap();
 
     // 5. Angular 3-point
     y += sp;
-    doc.add_entity(EntityType::Dimension(Dimension::Angular3Pt(DimensionAngular3Pt::new(
+    doc.add_entity(EntityType::Dimension(Box::new(Dimension::Angular3Pt(DimensionAngular3Pt::new(
         Vector3::new(0.0, y, 0.0),
         Vector3::new(10.0, y + 10.0, 0.0),
         Vector3::new(20.0, y, 0.0),
-    )))).unwrap();
+    ))))).unwrap();
 
     // 6. Angular 2-line
-    doc.add_entity(EntityType::Dimension(Dimension::Angular2Ln(DimensionAngular2Ln::new(
+    doc.add_entity(EntityType::Dimension(Box::new(Dimension::Angular2Ln(DimensionAngular2Ln::new(
         Vector3::new(sp, y, 0.0),
         Vector3::new(sp + 10.0, y + 10.0, 0.0),
         Vector3::new(sp + 20.0, y, 0.0),
-    )))).unwrap();
+    ))))).unwrap();
 
     // 7. Ordinate (X and Y)
     y += sp;
-    doc.add_entity(EntityType::Dimension(Dimension::Ordinate(DimensionOrdinate::x_ordinate(
+    doc.add_entity(EntityType::Dimension(Box::new(Dimension::Ordinate(DimensionOrdinate::x_ordinate(
         Vector3::new(10.0, y, 0.0),
         Vector3::new(10.0, y + 6.0, 0.0),
-    )))).unwrap();
+    ))))).unwrap();
 
-    doc.add_entity(EntityType::Dimension(Dimension::Ordinate(DimensionOrdinate::y_ordinate(
+    doc.add_entity(EntityType::Dimension(Box::new(Dimension::Ordinate(DimensionOrdinate::y_ordinate(
         Vector3::new(sp + 10.0, y, 0.0),
         Vector3::new(sp + 16.0, y + 5.0, 0.0),
-    )))).unwrap();
+    ))))).unwrap();
 
     // Leader
     let mut leader = Leader::new();
@@ -611,7 +611,7 @@ fn build_text_and_dims(version: DxfVersion) -> CadDocument {
     leader.arrow_enabled = true;
     leader.creation_type = LeaderCreationType::NoAnnotation;
     leader.common.layer = "Annotations".to_string();
-    doc.add_entity(EntityType::Leader(leader)).unwrap();
+    doc.add_entity(EntityType::Leader(Box::new(leader))).unwrap();
 
     // Tolerance
     let mut tol = Tolerance::new();
@@ -638,7 +638,7 @@ fn build_hatches(version: DxfVersion) -> CadDocument {
     h1.is_solid = true;
     h1.common.color = Color::from_index(1);
     h1.paths.push(make_rect_boundary(x, 0.0, 15.0, 15.0));
-    doc.add_entity(EntityType::Hatch(h1)).unwrap();
+    doc.add_entity(EntityType::Hatch(Box::new(h1))).unwrap();
     x += sp;
 
     // 2. Solid fill — triangle
@@ -651,7 +651,7 @@ fn build_hatches(version: DxfVersion) -> CadDocument {
     tri.edges.push(BoundaryEdge::Line(LineEdge { start: Vector2::new(x + 15.0, 0.0), end: Vector2::new(x + 7.5, 13.0) }));
     tri.edges.push(BoundaryEdge::Line(LineEdge { start: Vector2::new(x + 7.5, 13.0), end: Vector2::new(x, 0.0) }));
     h2.paths.push(tri);
-    doc.add_entity(EntityType::Hatch(h2)).unwrap();
+    doc.add_entity(EntityType::Hatch(Box::new(h2))).unwrap();
     x += sp;
 
     // 3. Pattern fill — ANSI31
@@ -660,7 +660,7 @@ fn build_hatches(version: DxfVersion) -> CadDocument {
     h3.is_solid = false;
     h3.pattern_scale = 1.0;
     h3.paths.push(make_rect_boundary(x, 0.0, 15.0, 15.0));
-    doc.add_entity(EntityType::Hatch(h3)).unwrap();
+    doc.add_entity(EntityType::Hatch(Box::new(h3))).unwrap();
     x += sp;
 
     // 4. Pattern fill — different angle
@@ -670,7 +670,7 @@ fn build_hatches(version: DxfVersion) -> CadDocument {
     h4.pattern_scale = 2.0;
     h4.pattern_angle = PI / 4.0;
     h4.paths.push(make_rect_boundary(x, 0.0, 15.0, 15.0));
-    doc.add_entity(EntityType::Hatch(h4)).unwrap();
+    doc.add_entity(EntityType::Hatch(Box::new(h4))).unwrap();
 
     // Row 2 — hatch with circular arc boundary
     x = 0.0;
@@ -689,7 +689,7 @@ fn build_hatches(version: DxfVersion) -> CadDocument {
         counter_clockwise: true,
     }));
     h5.paths.push(arc_boundary);
-    doc.add_entity(EntityType::Hatch(h5)).unwrap();
+    doc.add_entity(EntityType::Hatch(Box::new(h5))).unwrap();
 
     doc
 }
@@ -733,7 +733,7 @@ fn build_blocks_attribs(version: DxfVersion) -> CadDocument {
         attdef.insertion_point = Vector3::new(0.0, 30.0 + i as f64 * 5.0, 0.0);
         attdef.height = 2.5;
         attdef.common.layer = "Annotations".to_string();
-        doc.add_entity(EntityType::AttributeDefinition(attdef)).unwrap();
+        doc.add_entity(EntityType::AttributeDefinition(Box::new(attdef))).unwrap();
     }
 
     doc
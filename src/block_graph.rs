@@ -0,0 +1,278 @@
+//! Block nesting and circular references
+//!
+//! [`CadDocument::block_dependency_graph`] follows the block references of
+//! every block record, layouts included: INSERT (and MINSERT) entities and
+//! the anonymous blocks of dimensions. The graph reports how often each
+//! block is referenced, how deeply blocks nest, which references point to
+//! no block, and any circular references. A block that contains itself,
+//! directly or through other blocks, makes AutoCAD reject the file.
+//!
+//! ```rust,ignore
+//! let graph = doc.block_dependency_graph();
+//! for cycle in &graph.cycles {
+//!     eprintln!("circular reference between {}", cycle.join(", "));
+//! }
+//! let unused: Vec<&str> = graph.unused().collect();
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::document::CadDocument;
+use crate::entities::EntityType;
+
+/// One block record in a [`BlockDependencyGraph`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BlockNode {
+    /// Blocks referenced by entities of this block, with the number of
+    /// references to each
+    pub references: BTreeMap<String, usize>,
+    /// Blocks with entities referencing this block
+    pub referenced_by: BTreeSet<String>,
+    /// Number of references to this block from all blocks and layouts
+    pub usage_count: usize,
+    /// Levels of blocks nested below this one: 0 for a block without
+    /// references, `None` for a block in or above a circular reference
+    pub depth: Option<usize>,
+    /// Whether this is the block of model space or a paper space layout
+    pub is_layout: bool,
+}
+
+/// Result of [`CadDocument::block_dependency_graph`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BlockDependencyGraph {
+    /// Every block record, by name
+    pub blocks: BTreeMap<String, BlockNode>,
+    /// Circular references: groups of blocks that reach each other
+    /// through their references, each sorted by name
+    pub cycles: Vec<Vec<String>>,
+    /// Names referenced by entities without a block record, with the
+    /// number of references
+    pub missing: BTreeMap<String, usize>,
+}
+
+impl BlockDependencyGraph {
+    /// Whether some block contains itself
+    pub fn has_cycles(&self) -> bool {
+        !self.cycles.is_empty()
+    }
+
+    /// Blocks that are not layouts and are not referenced anywhere, the
+    /// candidates for a purge
+    pub fn unused(&self) -> impl Iterator<Item = &str> {
+        self.blocks
+            .iter()
+            .filter(|(_, node)| !node.is_layout && node.usage_count == 0)
+            .map(|(name, _)| name.as_str())
+    }
+
+    /// Block names with every block after the blocks it references, or
+    /// `None` if there is a circular reference
+    pub fn topological_order(&self) -> Option<Vec<&str>> {
+        if self.has_cycles() {
+            return None;
+        }
+        let mut order: Vec<(&str, usize)> = self
+            .blocks
+            .iter()
+            .map(|(name, node)| (name.as_str(), node.depth.unwrap_or(0)))
+            .collect();
+        order.sort_by_key(|&(_, depth)| depth);
+        Some(order.into_iter().map(|(name, _)| name).collect())
+    }
+}
+
+impl CadDocument {
+    /// Build the graph of block references between the block records of
+    /// the document
+    pub fn block_dependency_graph(&self) -> BlockDependencyGraph {
+        let mut graph = BlockDependencyGraph::default();
+        for record in self.block_records.iter() {
+            graph.blocks.insert(
+                record.name.clone(),
+                BlockNode {
+                    is_layout: record.is_model_space() || record.is_paper_space() || record.is_layout(),
+                    ..BlockNode::default()
+                },
+            );
+        }
+
+        for record in self.block_records.iter() {
+            for entity in self.record_entities(record) {
+                let name = match entity {
+                    EntityType::Insert(insert) => &insert.block_name,
+                    EntityType::Dimension(dimension) => &dimension.base().block_name,
+                    _ => continue,
+                };
+                if name.is_empty() {
+                    continue;
+                }
+                let Some(target) = self.block_records.get(name) else {
+                    *graph.missing.entry(name.clone()).or_default() += 1;
+                    continue;
+                };
+                let target = target.name.clone();
+                *graph.blocks.get_mut(&record.name).unwrap().references.entry(target.clone()).or_default() += 1;
+                let node = graph.blocks.get_mut(&target).unwrap();
+                node.usage_count += 1;
+                node.referenced_by.insert(record.name.clone());
+            }
+        }
+
+        graph.cycles = find_cycles(&graph.blocks);
+        let names: Vec<String> = graph.blocks.keys().cloned().collect();
+        let mut depths = BTreeMap::new();
+        for name in &names {
+            depth(&graph.blocks, name, &mut depths, &mut BTreeSet::new());
+        }
+        for (name, depth) in depths {
+            graph.blocks.get_mut(&name).unwrap().depth = depth;
+        }
+        graph
+    }
+}
+
+/// Nesting depth of `name`, memoized in `depths`; `None` when a circular
+/// reference is reached from it
+fn depth(
+    blocks: &BTreeMap<String, BlockNode>,
+    name: &str,
+    depths: &mut BTreeMap<String, Option<usize>>,
+    visiting: &mut BTreeSet<String>,
+) -> Option<usize> {
+    if let Some(&known) = depths.get(name) {
+        return known;
+    }
+    if !visiting.insert(name.to_string()) {
+        return None;
+    }
+    let mut result = Some(0);
+    for child in blocks[name].references.keys() {
+        result = match (result, depth(blocks, child, depths, visiting)) {
+            (Some(current), Some(below)) => Some(current.max(below + 1)),
+            _ => None,
+        };
+    }
+    visiting.remove(name);
+    depths.insert(name.to_string(), result);
+    result
+}
+
+/// Strongly connected groups of blocks that reach themselves (Tarjan)
+fn find_cycles(blocks: &BTreeMap<String, BlockNode>) -> Vec<Vec<String>> {
+    struct Search<'a> {
+        blocks: &'a BTreeMap<String, BlockNode>,
+        index: BTreeMap<&'a str, usize>,
+        stack: Vec<&'a str>,
+        on_stack: BTreeSet<&'a str>,
+        cycles: Vec<Vec<String>>,
+    }
+
+    impl<'a> Search<'a> {
+        fn visit(&mut self, name: &'a str) -> usize {
+            let number = self.index.len();
+            self.index.insert(name, number);
+            self.stack.push(name);
+            self.on_stack.insert(name);
+            let mut low = number;
+            for child in self.blocks[name].references.keys() {
+                let child = child.as_str();
+                if let Some(&child_number) = self.index.get(child) {
+                    if self.on_stack.contains(child) {
+                        low = low.min(child_number);
+                    }
+                } else {
+                    low = low.min(self.visit(child));
+                }
+            }
+            if low == number {
+                let mut group = Vec::new();
+                while let Some(member) = self.stack.pop() {
+                    self.on_stack.remove(member);
+                    group.push(member.to_string());
+                    if member == name {
+                        break;
+                    }
+                }
+                if group.len() > 1 || self.blocks[name].references.contains_key(name) {
+                    group.sort();
+                    self.cycles.push(group);
+                }
+            }
+            low
+        }
+    }
+
+    let mut search = Search {
+        blocks,
+        index: BTreeMap::new(),
+        stack: Vec::new(),
+        on_stack: BTreeSet::new(),
+        cycles: Vec::new(),
+    };
+    for name in blocks.keys() {
+        if !search.index.contains_key(name.as_str()) {
+            search.visit(name);
+        }
+    }
+    search.cycles.sort();
+    search.cycles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::Insert;
+    use crate::tables::BlockRecord;
+    use crate::types::Vector3;
+
+    fn add_block(doc: &mut CadDocument, name: &str, inserts: &[&str]) {
+        let mut block = BlockRecord::new(name);
+        for target in inserts {
+            block.entities.push(EntityType::Insert(Insert::new(*target, Vector3::ZERO)));
+        }
+        doc.block_records.add(block).unwrap();
+    }
+
+    #[test]
+    fn test_nesting_and_usage() {
+        let mut doc = CadDocument::new();
+        add_block(&mut doc, "BOLT", &[]);
+        add_block(&mut doc, "FLANGE", &["BOLT", "bolt", "NUT"]);
+        add_block(&mut doc, "PIPE", &["FLANGE", "FLANGE"]);
+        add_block(&mut doc, "SPARE", &[]);
+        doc.add_entity(EntityType::Insert(Insert::new("PIPE", Vector3::ZERO))).unwrap();
+
+        let graph = doc.block_dependency_graph();
+        assert!(!graph.has_cycles());
+        assert_eq!(graph.blocks["FLANGE"].references["BOLT"], 2);
+        assert_eq!(graph.blocks["FLANGE"].usage_count, 2);
+        assert_eq!(graph.blocks["PIPE"].referenced_by, BTreeSet::from(["*Model_Space".to_string()]));
+        assert_eq!(graph.blocks["BOLT"].depth, Some(0));
+        assert_eq!(graph.blocks["PIPE"].depth, Some(2));
+        assert_eq!(graph.blocks["*Model_Space"].depth, Some(3));
+        assert_eq!(graph.missing["NUT"], 1);
+        assert_eq!(graph.unused().collect::<Vec<_>>(), vec!["SPARE"]);
+
+        let order = graph.topological_order().unwrap();
+        let position = |name: &str| order.iter().position(|n| *n == name).unwrap();
+        assert!(position("BOLT") < position("FLANGE") && position("FLANGE") < position("PIPE"));
+    }
+
+    #[test]
+    fn test_circular_references() {
+        let mut doc = CadDocument::new();
+        add_block(&mut doc, "A", &["B"]);
+        add_block(&mut doc, "B", &["C"]);
+        add_block(&mut doc, "C", &["A", "D"]);
+        add_block(&mut doc, "D", &[]);
+        add_block(&mut doc, "SELF", &["SELF"]);
+        add_block(&mut doc, "TOP", &["A"]);
+
+        let graph = doc.block_dependency_graph();
+        assert_eq!(graph.cycles, vec![vec!["A", "B", "C"], vec!["SELF"]]);
+        assert_eq!(graph.blocks["A"].depth, None);
+        assert_eq!(graph.blocks["TOP"].depth, None);
+        assert_eq!(graph.blocks["D"].depth, Some(0));
+        assert!(graph.topological_order().is_none());
+    }
+}
@@ -13,6 +13,7 @@ use std::collections::HashMap;
 /// These flags control what operations are allowed on proxy entities/objects
 /// when the application that created them is not available.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ProxyFlags(pub u16);
 
 impl ProxyFlags {
@@ -67,6 +68,7 @@ impl From<i32> for ProxyFlags {
 /// - 280: Was-a-zombie flag
 /// - 281: Is-an-entity flag (1 = can appear in ENTITIES/BLOCKS, 0 = OBJECTS only)
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DxfClass {
     /// DXF class name (group code 1) — e.g. "ACDBPLACEHOLDER"
     pub dxf_name: String,
@@ -140,12 +142,33 @@ impl DxfClass {
 /// Collection of DXF class definitions, keyed by DXF name (case-insensitive).
 ///
 /// Corresponds to ACadSharp's `DxfClassCollection`.
+///
+/// Serialized as the list of classes; the name index is rebuilt.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(from = "Vec<DxfClass>", into = "Vec<DxfClass>"))]
 pub struct DxfClassCollection {
     entries: Vec<DxfClass>,
     name_index: HashMap<String, usize>,
 }
 
+impl From<Vec<DxfClass>> for DxfClassCollection {
+    fn from(entries: Vec<DxfClass>) -> Self {
+        let name_index = entries
+            .iter()
+            .enumerate()
+            .map(|(i, class)| (class.dxf_name.to_uppercase(), i))
+            .collect();
+        Self { entries, name_index }
+    }
+}
+
+impl From<DxfClassCollection> for Vec<DxfClass> {
+    fn from(collection: DxfClassCollection) -> Self {
+        collection.entries
+    }
+}
+
 impl DxfClassCollection {
     /// Create an empty class collection
     pub fn new() -> Self {
@@ -257,7 +280,7 @@ fn default_classes() -> Vec<DxfClass> {
 
     // Object classes (item_class_id = 499)
     for &(dxf, cpp) in &[
-        ("DICTIONARYWDFLT", "AcDbDictionaryWithDefault"),
+        ("ACDBDICTIONARYWDFLT", "AcDbDictionaryWithDefault"),
         ("ACDBPLACEHOLDER", "AcDbPlaceHolder"),
         ("LAYOUT", "AcDbLayout"),
         ("DICTIONARYVAR", "AcDbDictionaryVar"),
@@ -286,6 +309,16 @@ fn default_classes() -> Vec<DxfClass> {
         ("PLOTSETTINGS", "AcDbPlotSettings"),
         ("GROUP", "AcDbGroup"),
         ("MLINESTYLE", "AcDbMlineStyle"),
+        ("SUN", "AcDbSun"),
+        ("SOLID_BACKGROUND", "AcDbSolidBackground"),
+        ("GRADIENT_BACKGROUND", "AcDbGradientBackground"),
+        ("IMAGE_BACKGROUND", "AcDbImageBackground"),
+        ("SKYLIGHT_BACKGROUND", "AcDbSkyBackground"),
+        ("GROUND_PLANE_BACKGROUND", "AcDbGroundPlaneBackground"),
+        ("IBL_BACKGROUND", "AcDbIBLBackground"),
+        ("RENDERSETTINGS", "AcDbRenderSettings"),
+        ("MENTALRAYRENDERSETTINGS", "AcDbMentalRayRenderSettings"),
+        ("RAPIDRTRENDERSETTINGS", "AcDbRapidRTRenderSettings"),
     ] {
         let mut c = DxfClass::new(dxf, cpp);
         c.proxy_flags = ProxyFlags::ALL_OPERATIONS_ALLOWED;
@@ -0,0 +1,593 @@
+//! Document equivalence checks for round-trip testing
+//!
+//! [`compare`] pairs up the entities of two documents and lists, per entity,
+//! the fields that differ beyond a [`Tolerance`]. [`assert_equivalent`]
+//! panics with that report, for use in tests:
+//!
+//! ```rust,ignore
+//! use acadrust::compare::{assert_equivalent, Tolerance};
+//!
+//! let original = DxfReader::from_file("reference.dxf")?.read()?;
+//! let bytes = DxfWriter::new(original.clone()).write_to_vec()?;
+//! let reread = DxfReader::from_bytes(bytes)?.read()?;
+//! assert_equivalent(&original, &reread, Tolerance::default());
+//! ```
+//!
+//! Entities are matched by type and then by position (start point, center,
+//! insertion point, …), so handles may differ between the documents. The
+//! layer and the defining geometry of each entity are compared.
+//!
+//! The same tolerance applies to single values through [`ApproxEq`], for
+//! assertions of one's own:
+//!
+//! ```rust,ignore
+//! use acadrust::compare::{assert_approx_eq, ApproxEq, Tolerance};
+//!
+//! assert!(line.end.approx_eq(&expected, Tolerance::ulps(4)));
+//! assert_approx_eq(&reread_entity.bounding_box(), &entity.bounding_box(), Tolerance::new(1e-9));
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::document::CadDocument;
+use crate::entities::EntityType;
+use crate::types::{BoundingBox2D, BoundingBox3D, Handle, Vector2, Vector3};
+
+/// Largest differences that still count as equal.
+///
+/// Two values are equal when they differ by at most the absolute tolerance
+/// or are at most `ulps` representable doubles apart; the latter scales
+/// with the magnitude of the values.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tolerance {
+    /// For coordinates, lengths and scale factors
+    pub linear: f64,
+    /// For angles, in radians
+    pub angular: f64,
+    /// Units in the last place, for lengths and angles alike
+    pub ulps: u64,
+}
+
+impl Tolerance {
+    /// The same absolute tolerance for lengths and angles
+    pub fn new(tolerance: f64) -> Self {
+        Self { linear: tolerance, angular: tolerance, ulps: 0 }
+    }
+
+    /// Values at most `ulps` representable doubles apart, whatever their
+    /// magnitude
+    pub fn ulps(ulps: u64) -> Self {
+        Self { linear: 0.0, angular: 0.0, ulps }
+    }
+
+    /// Also accept values at most `ulps` representable doubles apart
+    pub fn with_ulps(mut self, ulps: u64) -> Self {
+        self.ulps = ulps;
+        self
+    }
+
+    /// Whether two coordinates, lengths or scale factors are equal
+    pub fn linear_eq(&self, a: f64, b: f64) -> bool {
+        self.eq(a, b, self.linear)
+    }
+
+    /// Whether two angles are equal
+    pub fn angular_eq(&self, a: f64, b: f64) -> bool {
+        self.eq(a, b, self.angular)
+    }
+
+    fn eq(&self, a: f64, b: f64, absolute: f64) -> bool {
+        a == b || (a - b).abs() <= absolute || ulps_between(a, b).is_some_and(|ulps| ulps <= self.ulps)
+    }
+}
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Self::new(1e-6)
+    }
+}
+
+/// Number of representable doubles between `a` and `b`, `None` if either
+/// is NaN
+fn ulps_between(a: f64, b: f64) -> Option<u64> {
+    if a.is_nan() || b.is_nan() {
+        return None;
+    }
+    // Map the bit patterns to integers ordered like the values
+    let ordered = |x: f64| {
+        let bits = x.to_bits() as i64;
+        if bits < 0 { i64::MIN.wrapping_sub(bits) } else { bits }
+    };
+    Some(ordered(a).abs_diff(ordered(b)))
+}
+
+/// Equality within a [`Tolerance`]
+pub trait ApproxEq {
+    /// Whether `self` and `other` are equal within `tolerance`
+    fn approx_eq(&self, other: &Self, tolerance: Tolerance) -> bool;
+}
+
+impl ApproxEq for f64 {
+    fn approx_eq(&self, other: &Self, tolerance: Tolerance) -> bool {
+        tolerance.linear_eq(*self, *other)
+    }
+}
+
+impl ApproxEq for Vector2 {
+    fn approx_eq(&self, other: &Self, tolerance: Tolerance) -> bool {
+        tolerance.linear_eq(self.x, other.x) && tolerance.linear_eq(self.y, other.y)
+    }
+}
+
+impl ApproxEq for Vector3 {
+    fn approx_eq(&self, other: &Self, tolerance: Tolerance) -> bool {
+        tolerance.linear_eq(self.x, other.x)
+            && tolerance.linear_eq(self.y, other.y)
+            && tolerance.linear_eq(self.z, other.z)
+    }
+}
+
+impl ApproxEq for BoundingBox2D {
+    fn approx_eq(&self, other: &Self, tolerance: Tolerance) -> bool {
+        self.min.approx_eq(&other.min, tolerance) && self.max.approx_eq(&other.max, tolerance)
+    }
+}
+
+impl ApproxEq for BoundingBox3D {
+    fn approx_eq(&self, other: &Self, tolerance: Tolerance) -> bool {
+        self.min.approx_eq(&other.min, tolerance) && self.max.approx_eq(&other.max, tolerance)
+    }
+}
+
+/// Entities are equal when [`compare_entities`] finds no difference
+impl ApproxEq for EntityType {
+    fn approx_eq(&self, other: &Self, tolerance: Tolerance) -> bool {
+        compare_entities(self, other, tolerance).is_empty()
+    }
+}
+
+/// Documents are equal when [`compare`] finds them equivalent
+impl ApproxEq for CadDocument {
+    fn approx_eq(&self, other: &Self, tolerance: Tolerance) -> bool {
+        compare(self, other, tolerance).is_equivalent()
+    }
+}
+
+impl<T: ApproxEq> ApproxEq for Option<T> {
+    fn approx_eq(&self, other: &Self, tolerance: Tolerance) -> bool {
+        match (self, other) {
+            (Some(a), Some(b)) => a.approx_eq(b, tolerance),
+            (a, b) => a.is_none() && b.is_none(),
+        }
+    }
+}
+
+impl<T: ApproxEq> ApproxEq for [T] {
+    fn approx_eq(&self, other: &Self, tolerance: Tolerance) -> bool {
+        self.len() == other.len() && self.iter().zip(other).all(|(a, b)| a.approx_eq(b, tolerance))
+    }
+}
+
+/// Panic with both values unless they are equal within `tolerance`
+#[track_caller]
+pub fn assert_approx_eq<T: ApproxEq + fmt::Debug + ?Sized>(left: &T, right: &T, tolerance: Tolerance) {
+    if !left.approx_eq(right, tolerance) {
+        panic!("values differ beyond {tolerance:?}:\n  left: {left:?}\n right: {right:?}");
+    }
+}
+
+/// A field that differs between two matched entities
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldDiff {
+    /// Field name, e.g. `start` or `vertex[2].bulge`
+    pub field: String,
+    /// Value in the left document
+    pub left: String,
+    /// Value in the right document
+    pub right: String,
+}
+
+impl fmt::Display for FieldDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} vs {}", self.field, self.left, self.right)
+    }
+}
+
+/// Differences found for one entity
+#[derive(Debug, Clone, PartialEq)]
+pub struct EntityDiff {
+    /// DXF type name, e.g. `LINE`
+    pub entity_type: &'static str,
+    /// Handle in the left document; `None` if the entity only exists on the right
+    pub left: Option<Handle>,
+    /// Handle in the right document; `None` if the entity only exists on the left
+    pub right: Option<Handle>,
+    /// Differing fields of a matched pair
+    pub fields: Vec<FieldDiff>,
+}
+
+impl fmt::Display for EntityDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.left, self.right) {
+            (Some(left), None) => write!(f, "{} {} is missing on the right", self.entity_type, left),
+            (None, Some(right)) => write!(f, "{} {} is missing on the left", self.entity_type, right),
+            (left, right) => {
+                let handle = |h: Option<Handle>| h.map_or_else(|| "?".to_string(), |h| h.to_string());
+                write!(f, "{} {} vs {}:", self.entity_type, handle(left), handle(right))?;
+                for field in &self.fields {
+                    write!(f, "\n    {}", field)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Result of [`compare`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ComparisonReport {
+    /// One item per unmatched entity or matched pair with differences
+    pub entities: Vec<EntityDiff>,
+}
+
+impl ComparisonReport {
+    /// Whether no differences were found
+    pub fn is_equivalent(&self) -> bool {
+        self.entities.is_empty()
+    }
+}
+
+impl fmt::Display for ComparisonReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_equivalent() {
+            return write!(f, "documents are equivalent");
+        }
+        write!(f, "{} entities differ:", self.entities.len())?;
+        for entity in &self.entities {
+            write!(f, "\n  {}", entity)?;
+        }
+        Ok(())
+    }
+}
+
+/// Compare the entities of two documents
+pub fn compare(left: &CadDocument, right: &CadDocument, tolerance: Tolerance) -> ComparisonReport {
+    let left_groups = sorted_entities_by_type(left);
+    let mut right_groups = sorted_entities_by_type(right);
+    let mut report = ComparisonReport::default();
+
+    for (entity_type, lefts) in left_groups {
+        let rights = right_groups.remove(entity_type).unwrap_or_default();
+        for i in 0..lefts.len().max(rights.len()) {
+            let (a, b) = (lefts.get(i), rights.get(i));
+            let fields = match (a, b) {
+                (Some(a), Some(b)) => compare_entities(a, b, tolerance),
+                _ => Vec::new(),
+            };
+            if a.is_none() || b.is_none() || !fields.is_empty() {
+                report.entities.push(EntityDiff {
+                    entity_type,
+                    left: a.map(|e| e.common().handle),
+                    right: b.map(|e| e.common().handle),
+                    fields,
+                });
+            }
+        }
+    }
+    for (entity_type, rights) in right_groups {
+        for b in rights {
+            report.entities.push(EntityDiff {
+                entity_type,
+                left: None,
+                right: Some(b.common().handle),
+                fields: Vec::new(),
+            });
+        }
+    }
+    report
+}
+
+/// Panic with a per-entity, per-field report unless the documents are
+/// equivalent within `tolerance`
+#[track_caller]
+pub fn assert_equivalent(left: &CadDocument, right: &CadDocument, tolerance: Tolerance) {
+    let report = compare(left, right, tolerance);
+    if !report.is_equivalent() {
+        panic!("{}", report);
+    }
+}
+
+/// Compare two entities field by field.
+///
+/// Entities of different types only have their type reported.
+pub fn compare_entities(a: &EntityType, b: &EntityType, tolerance: Tolerance) -> Vec<FieldDiff> {
+    let mut d = Differ { tolerance, diffs: Vec::new() };
+
+    let (type_a, type_b) = (a.as_entity().entity_type(), b.as_entity().entity_type());
+    if type_a != type_b {
+        d.push("type", type_a, type_b);
+        return d.diffs;
+    }
+    d.value("layer", &a.common().layer, &b.common().layer);
+
+    match (a, b) {
+        (EntityType::Line(ea), EntityType::Line(eb)) => {
+            d.vec3("start", &ea.start, &eb.start);
+            d.vec3("end", &ea.end, &eb.end);
+        }
+        (EntityType::Circle(ea), EntityType::Circle(eb)) => {
+            d.vec3("center", &ea.center, &eb.center);
+            d.linear("radius", ea.radius, eb.radius);
+        }
+        (EntityType::Arc(ea), EntityType::Arc(eb)) => {
+            d.vec3("center", &ea.center, &eb.center);
+            d.linear("radius", ea.radius, eb.radius);
+            d.angular("start_angle", ea.start_angle, eb.start_angle);
+            d.angular("end_angle", ea.end_angle, eb.end_angle);
+        }
+        (EntityType::Ellipse(ea), EntityType::Ellipse(eb)) => {
+            d.vec3("center", &ea.center, &eb.center);
+            d.vec3("major_axis", &ea.major_axis, &eb.major_axis);
+            d.linear("minor_axis_ratio", ea.minor_axis_ratio, eb.minor_axis_ratio);
+            d.angular("start_parameter", ea.start_parameter, eb.start_parameter);
+            d.angular("end_parameter", ea.end_parameter, eb.end_parameter);
+        }
+        (EntityType::Point(ea), EntityType::Point(eb)) => {
+            d.vec3("location", &ea.location, &eb.location);
+        }
+        (EntityType::Text(ea), EntityType::Text(eb)) => {
+            d.vec3("insertion_point", &ea.insertion_point, &eb.insertion_point);
+            d.linear("height", ea.height, eb.height);
+            d.value("value", &ea.value, &eb.value);
+        }
+        (EntityType::MText(ea), EntityType::MText(eb)) => {
+            d.vec3("insertion_point", &ea.insertion_point, &eb.insertion_point);
+            d.linear("height", ea.height, eb.height);
+            d.value("value", &ea.value, &eb.value);
+        }
+        (EntityType::LwPolyline(ea), EntityType::LwPolyline(eb)) => {
+            if d.count("vertex_count", ea.vertices.len(), eb.vertices.len()) {
+                for (i, (va, vb)) in ea.vertices.iter().zip(&eb.vertices).enumerate() {
+                    if !d.close(va.location.x, vb.location.x) || !d.close(va.location.y, vb.location.y) {
+                        d.push(
+                            &format!("vertex[{i}]"),
+                            format!("({},{})", va.location.x, va.location.y),
+                            format!("({},{})", vb.location.x, vb.location.y),
+                        );
+                    }
+                    d.linear(&format!("vertex[{i}].bulge"), va.bulge, vb.bulge);
+                }
+            }
+            d.value("is_closed", &ea.is_closed, &eb.is_closed);
+        }
+        (EntityType::Spline(ea), EntityType::Spline(eb)) => {
+            d.value("degree", &ea.degree, &eb.degree);
+            if d.count("control_points_count", ea.control_points.len(), eb.control_points.len()) {
+                for (i, (pa, pb)) in ea.control_points.iter().zip(&eb.control_points).enumerate() {
+                    d.vec3(&format!("control_point[{i}]"), pa, pb);
+                }
+            }
+            d.count("knots_count", ea.knots.len(), eb.knots.len());
+        }
+        (EntityType::Insert(ea), EntityType::Insert(eb)) => {
+            d.value("block_name", &ea.block_name, &eb.block_name);
+            d.vec3("insert_point", &ea.insert_point, &eb.insert_point);
+            d.linear("x_scale", ea.x_scale, eb.x_scale);
+            d.linear("y_scale", ea.y_scale, eb.y_scale);
+            d.linear("z_scale", ea.z_scale, eb.z_scale);
+            d.angular("rotation", ea.rotation, eb.rotation);
+        }
+        (EntityType::Ray(ea), EntityType::Ray(eb)) => {
+            d.vec3("base_point", &ea.base_point, &eb.base_point);
+            d.vec3("direction", &ea.direction, &eb.direction);
+        }
+        (EntityType::XLine(ea), EntityType::XLine(eb)) => {
+            d.vec3("base_point", &ea.base_point, &eb.base_point);
+            d.vec3("direction", &ea.direction, &eb.direction);
+        }
+        (EntityType::Solid(ea), EntityType::Solid(eb)) => {
+            d.vec3("first_corner", &ea.first_corner, &eb.first_corner);
+            d.vec3("second_corner", &ea.second_corner, &eb.second_corner);
+            d.vec3("third_corner", &ea.third_corner, &eb.third_corner);
+            d.vec3("fourth_corner", &ea.fourth_corner, &eb.fourth_corner);
+        }
+        (EntityType::Face3D(ea), EntityType::Face3D(eb)) => {
+            d.vec3("first_corner", &ea.first_corner, &eb.first_corner);
+            d.vec3("second_corner", &ea.second_corner, &eb.second_corner);
+            d.vec3("third_corner", &ea.third_corner, &eb.third_corner);
+            d.vec3("fourth_corner", &ea.fourth_corner, &eb.fourth_corner);
+        }
+        (EntityType::Hatch(ea), EntityType::Hatch(eb)) => {
+            d.value("pattern_name", &ea.pattern.name, &eb.pattern.name);
+            d.value("is_solid", &ea.is_solid, &eb.is_solid);
+            d.linear("pattern_scale", ea.pattern_scale, eb.pattern_scale);
+        }
+        (EntityType::Leader(ea), EntityType::Leader(eb)) => {
+            let same_count = d.count("vertex_count", ea.vertices.len(), eb.vertices.len());
+            if same_count {
+                for (i, (va, vb)) in ea.vertices.iter().zip(&eb.vertices).enumerate() {
+                    d.vec3(&format!("vertex[{i}]"), va, vb);
+                }
+            }
+        }
+        (EntityType::Tolerance(ea), EntityType::Tolerance(eb)) => {
+            d.vec3("insertion_point", &ea.insertion_point, &eb.insertion_point);
+            d.value("text", &ea.text, &eb.text);
+        }
+        (EntityType::Shape(ea), EntityType::Shape(eb)) => {
+            d.vec3("insertion_point", &ea.insertion_point, &eb.insertion_point);
+            d.linear("size", ea.size, eb.size);
+        }
+        (EntityType::Viewport(ea), EntityType::Viewport(eb)) => {
+            d.vec3("center", &ea.center, &eb.center);
+            d.linear("width", ea.width, eb.width);
+            d.linear("height", ea.height, eb.height);
+        }
+        _ => {}
+    }
+    d.diffs
+}
+
+/// Collects field differences
+struct Differ {
+    tolerance: Tolerance,
+    diffs: Vec<FieldDiff>,
+}
+
+impl Differ {
+    fn push(&mut self, field: &str, left: impl ToString, right: impl ToString) {
+        self.diffs.push(FieldDiff { field: field.to_string(), left: left.to_string(), right: right.to_string() });
+    }
+
+    fn close(&self, a: f64, b: f64) -> bool {
+        self.tolerance.linear_eq(a, b)
+    }
+
+    fn linear(&mut self, field: &str, a: f64, b: f64) {
+        if !self.close(a, b) {
+            self.push(field, a, b);
+        }
+    }
+
+    fn angular(&mut self, field: &str, a: f64, b: f64) {
+        if !self.tolerance.angular_eq(a, b) {
+            self.push(field, a, b);
+        }
+    }
+
+    fn vec3(&mut self, field: &str, a: &Vector3, b: &Vector3) {
+        if !a.approx_eq(b, self.tolerance) {
+            self.push(field, format!("({},{},{})", a.x, a.y, a.z), format!("({},{},{})", b.x, b.y, b.z));
+        }
+    }
+
+    fn value<T: PartialEq + fmt::Debug>(&mut self, field: &str, a: &T, b: &T) {
+        if a != b {
+            self.push(field, format!("{a:?}"), format!("{b:?}"));
+        }
+    }
+
+    /// Record differing lengths; true if they are equal
+    fn count(&mut self, field: &str, a: usize, b: usize) -> bool {
+        if a != b {
+            self.push(field, a, b);
+        }
+        a == b
+    }
+}
+
+/// Entities grouped by DXF type name, each group ordered by position
+fn sorted_entities_by_type(doc: &CadDocument) -> BTreeMap<&'static str, Vec<&EntityType>> {
+    let mut groups: BTreeMap<&'static str, Vec<&EntityType>> = BTreeMap::new();
+    for entity in doc.entities() {
+        groups.entry(entity.as_entity().entity_type()).or_default().push(entity);
+    }
+    for entities in groups.values_mut() {
+        entities.sort_by(|a, b| {
+            entity_sort_key(a)
+                .partial_cmp(&entity_sort_key(b))
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.common().handle.cmp(&b.common().handle))
+        });
+    }
+    groups
+}
+
+/// Rough geometric key for pairing up entities of two documents; entities
+/// without a defining point fall back to their handle
+pub fn entity_sort_key(e: &EntityType) -> (f64, f64, f64) {
+    let point = |p: &Vector3| (p.x, p.y, p.z);
+    match e {
+        EntityType::Line(l) => point(&l.start),
+        EntityType::Circle(c) => point(&c.center),
+        EntityType::Arc(a) => point(&a.center),
+        EntityType::Ellipse(el) => point(&el.center),
+        EntityType::Point(p) => point(&p.location),
+        EntityType::Text(t) => point(&t.insertion_point),
+        EntityType::MText(t) => point(&t.insertion_point),
+        EntityType::LwPolyline(lw) => lw.vertices.first().map_or((0.0, 0.0, 0.0), |v| (v.location.x, v.location.y, lw.elevation)),
+        EntityType::Spline(s) => s.control_points.first().map_or((0.0, 0.0, 0.0), point),
+        EntityType::Insert(ins) => point(&ins.insert_point),
+        EntityType::Ray(r) => point(&r.base_point),
+        EntityType::XLine(xl) => point(&xl.base_point),
+        EntityType::Solid(s) => point(&s.first_corner),
+        EntityType::Face3D(f) => point(&f.first_corner),
+        EntityType::Hatch(h) => (h.elevation, h.pattern_angle, h.pattern_scale),
+        EntityType::Leader(l) => l.vertices.first().map_or((0.0, 0.0, 0.0), point),
+        EntityType::Tolerance(t) => point(&t.insertion_point),
+        EntityType::Shape(s) => point(&s.insertion_point),
+        EntityType::Viewport(vp) => point(&vp.center),
+        EntityType::Dimension(d) => point(&d.base().definition_point),
+        _ => (e.common().handle.value() as f64, 0.0, 0.0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::{Circle, Line};
+    use crate::io::dxf::{DxfReader, DxfWriter};
+
+    #[test]
+    fn test_roundtrip_is_equivalent() {
+        let mut doc = CadDocument::new();
+        doc.add_entity(EntityType::Line(Line::from_coords(0.0, 0.0, 0.0, 10.0, 5.0, 0.0))).unwrap();
+        doc.add_entity(EntityType::Circle(Circle::from_coords(3.0, 4.0, 0.0, 2.5))).unwrap();
+
+        let bytes = DxfWriter::new(doc.clone()).write_to_vec().unwrap();
+        let reread = DxfReader::from_bytes(bytes).unwrap().read().unwrap();
+        assert_equivalent(&doc, &reread, Tolerance::default());
+    }
+
+    #[test]
+    fn test_report_lists_fields_and_missing_entities() {
+        let mut left = CadDocument::new();
+        let line = left.add_entity(EntityType::Line(Line::from_coords(0.0, 0.0, 0.0, 10.0, 5.0, 0.0))).unwrap();
+        let circle = left.add_entity(EntityType::Circle(Circle::from_coords(3.0, 4.0, 0.0, 2.5))).unwrap();
+        let mut right = CadDocument::new();
+        right.add_entity(EntityType::Line(Line::from_coords(0.0, 0.0, 0.0, 10.0, 5.001, 0.0))).unwrap();
+
+        let report = compare(&left, &right, Tolerance::default());
+        assert_eq!(report.entities.len(), 2);
+        assert_eq!((report.entities[0].entity_type, report.entities[0].right), ("CIRCLE", None));
+        assert_eq!(report.entities[0].left, Some(circle));
+        let fields = &report.entities[1].fields;
+        assert_eq!(report.entities[1].left, Some(line));
+        assert_eq!(fields.len(), 1);
+        assert_eq!(fields[0].to_string(), "end: (10,5,0) vs (10,5.001,0)");
+
+        assert!(compare(&left, &right, Tolerance::new(0.01)).entities.len() == 1);
+        let check = std::panic::AssertUnwindSafe(|| assert_equivalent(&left, &right, Tolerance::default()));
+        let panic = std::panic::catch_unwind(check);
+        assert!(panic.is_err());
+    }
+
+    #[test]
+    fn test_approx_eq_absolute_and_ulps() {
+        let next = f64::from_bits(1.0f64.to_bits() + 2);
+        assert!(!Tolerance::ulps(1).linear_eq(1.0, next));
+        assert!(Tolerance::ulps(2).linear_eq(1.0, next));
+        assert!(Tolerance::ulps(2).linear_eq(-0.0, 0.0));
+        assert!(Tolerance::ulps(2).linear_eq(-f64::MIN_POSITIVE * f64::EPSILON, f64::MIN_POSITIVE * f64::EPSILON));
+        assert!(!Tolerance::ulps(u64::MAX).linear_eq(f64::NAN, f64::NAN));
+        // ULPs scale with the magnitude, absolute tolerances do not
+        assert!(Tolerance::ulps(4).linear_eq(1e12, 1e12 + 1e-4));
+        assert!(!Tolerance::new(1e-6).linear_eq(1e12, 1e12 + 1e-4));
+        assert!(Tolerance::new(1e-6).with_ulps(4).linear_eq(1e12, 1e12 + 1e-4));
+
+        let tolerance = Tolerance::new(1e-6);
+        assert!(Vector2::new(1.0, 2.0).approx_eq(&Vector2::new(1.0, 2.0 + 1e-7), tolerance));
+        assert!(!Vector3::new(1.0, 2.0, 3.0).approx_eq(&Vector3::new(1.0, 2.0, 3.001), tolerance));
+        let bounds = BoundingBox3D::new(Vector3::ZERO, Vector3::new(1.0, 1.0, 1.0));
+        let moved = BoundingBox3D::new(Vector3::ZERO, Vector3::new(1.0, 1.0 + 1e-9, 1.0));
+        assert_approx_eq(&bounds, &moved, tolerance);
+        assert!(!bounds.approx_eq(&moved, Tolerance::ulps(1)));
+
+        let line = EntityType::Line(Line::from_coords(0.0, 0.0, 0.0, 10.0, 5.0, 0.0));
+        let close = EntityType::Line(Line::from_coords(0.0, 0.0, 0.0, 10.0, 5.0 + 1e-9, 0.0));
+        assert!(line.approx_eq(&close, tolerance));
+        assert!(!line.approx_eq(&close, Tolerance::new(0.0)));
+        assert!([line.clone()][..].approx_eq(&[close][..], tolerance));
+    }
+}
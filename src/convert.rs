@@ -0,0 +1,159 @@
+//! One-call file format conversion
+//!
+//! [`convert_file`] reads a DWG, DXF (ASCII or binary) or DXB file,
+//! optionally changes the drawing version and writes it as DWG or DXF:
+//!
+//! ```rust,ignore
+//! use acadrust::convert::{convert_file, ConvertOptions};
+//! use acadrust::DxfVersion;
+//!
+//! convert_file("in.dwg", "out.dxf", &ConvertOptions::default().with_version(DxfVersion::AC1018))?;
+//! # Ok::<(), acadrust::error::DxfError>(())
+//! ```
+
+use std::io::Cursor;
+use std::path::Path;
+
+use crate::detect::{detect, FileFormat};
+use crate::document::CadDocument;
+use crate::error::{DxfError, Result};
+use crate::io::dwg::{DwgReader, DwgReaderConfiguration, DwgWriter};
+use crate::io::dxb::DxbReader;
+use crate::io::dxf::{DxfReader, DxfReaderConfiguration, DxfWriter};
+use crate::types::DxfVersion;
+
+/// Output file format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// ASCII DXF
+    Dxf,
+    /// Binary DXF
+    DxfBinary,
+    /// DWG
+    Dwg,
+}
+
+impl OutputFormat {
+    /// Format implied by a file extension (`dxf` or `dwg`, case-insensitive)
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<Self> {
+        let extension = path.as_ref().extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "dxf" => Some(OutputFormat::Dxf),
+            "dwg" => Some(OutputFormat::Dwg),
+            _ => None,
+        }
+    }
+}
+
+/// Options for [`convert_file`] and [`convert_bytes`]
+#[derive(Debug, Clone, Default)]
+pub struct ConvertOptions {
+    /// Output format; `None` picks it from the output file extension
+    pub format: Option<OutputFormat>,
+    /// Version to write; `None` keeps the version of the input
+    pub version: Option<DxfVersion>,
+    /// Read the input in failsafe mode, keeping what can be parsed
+    pub failsafe: bool,
+}
+
+impl ConvertOptions {
+    /// Write the given format regardless of the output extension
+    pub fn with_format(mut self, format: OutputFormat) -> Self {
+        self.format = Some(format);
+        self
+    }
+
+    /// Write the given version
+    pub fn with_version(mut self, version: DxfVersion) -> Self {
+        self.version = Some(version);
+        self
+    }
+}
+
+/// Convert `input` to `output`.
+///
+/// The input format is detected from the file contents; the output format
+/// comes from [`ConvertOptions::format`] or else the output extension.
+pub fn convert_file<P: AsRef<Path>, Q: AsRef<Path>>(input: P, output: Q, options: &ConvertOptions) -> Result<()> {
+    let format = match options.format {
+        Some(format) => format,
+        None => OutputFormat::from_path(&output).ok_or_else(|| {
+            DxfError::Custom(format!(
+                "Cannot tell the output format from '{}'; set ConvertOptions::format",
+                output.as_ref().display()
+            ))
+        })?,
+    };
+    let bytes = std::fs::read(input)?;
+    let converted = convert_bytes(bytes, format, options)?;
+    std::fs::write(output, converted)?;
+    Ok(())
+}
+
+/// Convert the contents of a DWG, DXF or DXB file to `format` in memory
+pub fn convert_bytes(input: Vec<u8>, format: OutputFormat, options: &ConvertOptions) -> Result<Vec<u8>> {
+    let mut doc = read_any(input, options.failsafe)?;
+    if let Some(version) = options.version {
+        if version == DxfVersion::Unknown {
+            return Err(DxfError::UnsupportedVersion("Unknown".to_string()));
+        }
+        doc.version = version;
+    }
+    match format {
+        OutputFormat::Dxf => DxfWriter::new(doc).write_to_vec(),
+        OutputFormat::DxfBinary => DxfWriter::new_binary(doc).write_to_vec(),
+        OutputFormat::Dwg => DwgWriter::write(&doc),
+    }
+}
+
+/// Read a document from file contents of any supported format
+pub(crate) fn read_any(input: Vec<u8>, failsafe: bool) -> Result<CadDocument> {
+    match detect(&input)?.format {
+        FileFormat::Dxb => DxbReader::from_bytes(input)?.read(),
+        FileFormat::Dwg => {
+            let config = DwgReaderConfiguration { failsafe, ..Default::default() };
+            DwgReader::from_reader(Cursor::new(input))?.with_config(config).read()
+        }
+        FileFormat::Dxf => {
+            let config = DxfReaderConfiguration { failsafe };
+            DxfReader::from_bytes(input)?.with_configuration(config).read()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::{EntityType, Line};
+
+    fn sample() -> Vec<u8> {
+        let mut doc = CadDocument::with_version(DxfVersion::AC1015);
+        doc.add_entity(EntityType::Line(Line::from_coords(0.0, 0.0, 0.0, 5.0, 5.0, 0.0))).unwrap();
+        DxfWriter::new(doc).write_to_vec().unwrap()
+    }
+
+    #[test]
+    fn test_convert_between_formats() {
+        let options = ConvertOptions::default().with_version(DxfVersion::AC1018);
+
+        let binary = convert_bytes(sample(), OutputFormat::DxfBinary, &options).unwrap();
+        assert!(binary.starts_with(b"AutoCAD Binary DXF"));
+
+        let dwg = convert_bytes(binary, OutputFormat::Dwg, &options).unwrap();
+        assert!(dwg.starts_with(b"AC1018"));
+
+        let ascii = convert_bytes(dwg, OutputFormat::Dxf, &ConvertOptions::default()).unwrap();
+        let doc = DxfReader::from_bytes(ascii).unwrap().read().unwrap();
+        assert_eq!(doc.version, DxfVersion::AC1018);
+        assert_eq!(doc.entities().filter(|e| matches!(e, EntityType::Line(_))).count(), 1);
+    }
+
+    #[test]
+    fn test_output_format_from_path() {
+        assert_eq!(OutputFormat::from_path("a/b.DWG"), Some(OutputFormat::Dwg));
+        assert_eq!(OutputFormat::from_path("b.dxf"), Some(OutputFormat::Dxf));
+        assert_eq!(OutputFormat::from_path("b.pdf"), None);
+        let err = convert_file("missing.dxf", "out.txt", &ConvertOptions::default());
+        assert!(err.is_err());
+    }
+}
@@ -0,0 +1,235 @@
+//! Cheap file format and version detection
+//!
+//! [`detect`] looks only at the magic bytes and the start of the header
+//! (`$ACADVER`, `$DWGCODEPAGE`), so uploads can be routed or rejected before
+//! committing to a full read.
+
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use crate::error::{DxfError, Result};
+use crate::io::dxb::DxbReader;
+use crate::types::DxfVersion;
+
+/// How much of a file is inspected; the DXF header variables of interest
+/// come first in the HEADER section
+const SNIFF_LENGTH: u64 = 64 * 1024;
+
+const BINARY_DXF_SENTINEL: &[u8] = b"AutoCAD Binary DXF";
+
+/// CAD file format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileFormat {
+    /// DWG drawing
+    Dwg,
+    /// DXF, ASCII or binary
+    Dxf,
+    /// DXB binary drawing interchange
+    Dxb,
+}
+
+/// What [`detect`] found out about a file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileInfo {
+    /// File format
+    pub format: FileFormat,
+    /// Drawing version; `Unknown` if the file does not say or the version is
+    /// not supported (e.g. R12 and older)
+    pub version: DxfVersion,
+    /// Whether the content is binary (always for DWG and DXB)
+    pub is_binary: bool,
+    /// Drawing code page as a `$DWGCODEPAGE` name such as `ANSI_1252`, if
+    /// the file records one
+    pub code_page: Option<String>,
+}
+
+/// File to inspect: a path or the file contents
+#[derive(Debug, Clone, Copy)]
+pub enum DetectInput<'a> {
+    /// Path of the file; only its start is read
+    Path(&'a Path),
+    /// Contents (or the first part of the contents) of the file
+    Bytes(&'a [u8]),
+}
+
+impl<'a> From<&'a Path> for DetectInput<'a> {
+    fn from(path: &'a Path) -> Self {
+        DetectInput::Path(path)
+    }
+}
+
+impl<'a> From<&'a PathBuf> for DetectInput<'a> {
+    fn from(path: &'a PathBuf) -> Self {
+        DetectInput::Path(path)
+    }
+}
+
+/// A string is taken as a path
+impl<'a> From<&'a str> for DetectInput<'a> {
+    fn from(path: &'a str) -> Self {
+        DetectInput::Path(Path::new(path))
+    }
+}
+
+impl<'a> From<&'a [u8]> for DetectInput<'a> {
+    fn from(bytes: &'a [u8]) -> Self {
+        DetectInput::Bytes(bytes)
+    }
+}
+
+impl<'a, const N: usize> From<&'a [u8; N]> for DetectInput<'a> {
+    fn from(bytes: &'a [u8; N]) -> Self {
+        DetectInput::Bytes(bytes)
+    }
+}
+
+impl<'a> From<&'a Vec<u8>> for DetectInput<'a> {
+    fn from(bytes: &'a Vec<u8>) -> Self {
+        DetectInput::Bytes(bytes)
+    }
+}
+
+/// Identify a DWG, DXF or DXB file without parsing it.
+///
+/// ```rust,ignore
+/// let info = acadrust::detect("upload.bin")?;
+/// if info.format == acadrust::FileFormat::Dwg && info.version < acadrust::DxfVersion::AC1015 {
+///     // reject
+/// }
+/// ```
+pub fn detect<'a>(input: impl Into<DetectInput<'a>>) -> Result<FileInfo> {
+    match input.into() {
+        DetectInput::Bytes(bytes) => detect_bytes(bytes),
+        DetectInput::Path(path) => {
+            let mut head = Vec::new();
+            File::open(path)?.take(SNIFF_LENGTH).read_to_end(&mut head)?;
+            detect_bytes(&head)
+        }
+    }
+}
+
+fn detect_bytes(bytes: &[u8]) -> Result<FileInfo> {
+    // Contents are given the same look as a file
+    let bytes = &bytes[..bytes.len().min(SNIFF_LENGTH as usize)];
+    if DxbReader::is_dxb(bytes) {
+        return Ok(FileInfo { format: FileFormat::Dxb, version: DxfVersion::Unknown, is_binary: true, code_page: None });
+    }
+    if bytes.len() >= 6 && bytes.starts_with(b"AC10") && bytes[4..6].iter().all(u8::is_ascii_digit) {
+        let version = std::str::from_utf8(&bytes[..6]).ok().and_then(DxfVersion::parse).unwrap_or(DxfVersion::Unknown);
+        // Code page index at 0x13 from R13 on
+        let code_page = match (version, bytes.get(0x13..0x15)) {
+            (DxfVersion::Unknown, _) | (_, None) => None,
+            (_, Some(b)) => dwg_code_page_name(u16::from_le_bytes([b[0], b[1]])).map(str::to_string),
+        };
+        return Ok(FileInfo { format: FileFormat::Dwg, version, is_binary: true, code_page });
+    }
+    if bytes.starts_with(BINARY_DXF_SENTINEL) {
+        return Ok(FileInfo {
+            format: FileFormat::Dxf,
+            version: binary_variable(bytes, b"$ACADVER").map_or(DxfVersion::Unknown, |v| DxfVersion::from_version_string(&v)),
+            is_binary: true,
+            code_page: binary_variable(bytes, b"$DWGCODEPAGE"),
+        });
+    }
+    text_dxf(bytes).ok_or_else(|| DxfError::InvalidFormat("Not a DWG, DXF or DXB file".to_string()))
+}
+
+/// Value of a header variable in a binary DXF: the name's string is
+/// followed by a 2-byte (1-byte before R13) group code and a NUL-terminated
+/// string value
+fn binary_variable(bytes: &[u8], name: &[u8]) -> Option<String> {
+    let start = bytes.windows(name.len() + 1).position(|w| &w[..name.len()] == name && w[name.len()] == 0)?;
+    let mut rest = &bytes[start + name.len() + 1..];
+    rest = match rest {
+        [_, 0, tail @ ..] => tail,
+        [_, tail @ ..] => tail,
+        [] => return None,
+    };
+    let len = rest.iter().position(|&b| b == 0)?;
+    Some(String::from_utf8_lossy(&rest[..len]).trim().to_string())
+}
+
+fn text_dxf(bytes: &[u8]) -> Option<FileInfo> {
+    let text = String::from_utf8_lossy(bytes);
+    let text = text.strip_prefix('\u{feff}').unwrap_or(&text);
+    let mut lines = text.lines().map(str::trim).skip_while(|l| l.is_empty());
+
+    // Must open with a comment or a section
+    let first = (lines.next()?, lines.next()?);
+    if !matches!(first, ("999", _) | ("0", "SECTION")) {
+        return None;
+    }
+
+    let mut info = FileInfo { format: FileFormat::Dxf, version: DxfVersion::Unknown, is_binary: false, code_page: None };
+    while let Some(line) = lines.next() {
+        match line {
+            "$ACADVER" => info.version = DxfVersion::from_version_string(lines.nth(1).unwrap_or_default()),
+            "$DWGCODEPAGE" => info.code_page = lines.nth(1).map(str::to_string),
+            "ENDSEC" => break,
+            _ => {}
+        }
+    }
+    Some(info)
+}
+
+/// `$DWGCODEPAGE` name for the code page index in a DWG file header
+fn dwg_code_page_name(index: u16) -> Option<&'static str> {
+    const NAMES: [&str; 45] = [
+        "", "ASCII", "ISO8859-1", "ISO8859-2", "ISO8859-3", "ISO8859-4", "ISO8859-5", "ISO8859-6",
+        "ISO8859-7", "ISO8859-8", "ISO8859-9", "DOS437", "DOS850", "DOS852", "DOS855", "DOS857",
+        "DOS860", "DOS861", "DOS863", "DOS864", "DOS865", "DOS869", "DOS932", "MACINTOSH", "BIG5",
+        "KSC5601", "JOHAB", "DOS866", "ANSI_1250", "ANSI_1251", "ANSI_1252", "GB2312", "ANSI_1253",
+        "ANSI_1254", "ANSI_1255", "ANSI_1256", "ANSI_1257", "ANSI_874", "ANSI_932", "ANSI_936",
+        "ANSI_949", "ANSI_950", "ANSI_1361", "UTF16", "ANSI_1258",
+    ];
+    NAMES.get(index as usize).copied().filter(|name| !name.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::CadDocument;
+    use crate::io::dwg::DwgWriter;
+    use crate::io::dxf::DxfWriter;
+
+    #[test]
+    fn test_detect_written_files() {
+        let doc = CadDocument::with_version(DxfVersion::AC1018);
+
+        let ascii = DxfWriter::new(doc.clone()).write_to_vec().unwrap();
+        let info = detect(&ascii).unwrap();
+        assert_eq!(info.format, FileFormat::Dxf);
+        assert_eq!(info.version, DxfVersion::AC1018);
+        assert!(!info.is_binary);
+        assert_eq!(info.code_page.as_deref(), Some("ANSI_1252"));
+
+        let binary = DxfWriter::new_binary(doc.clone()).write_to_vec().unwrap();
+        let info = detect(&binary).unwrap();
+        assert!(info.is_binary);
+        assert_eq!(info.version, DxfVersion::AC1018);
+        assert_eq!(info.code_page.as_deref(), Some("ANSI_1252"));
+
+        let dwg = DwgWriter::write(&doc).unwrap();
+        let info = detect(&dwg).unwrap();
+        assert_eq!(info.format, FileFormat::Dwg);
+        assert_eq!(info.version, DxfVersion::AC1018);
+    }
+
+    #[test]
+    fn test_detect_other_inputs() {
+        assert_eq!(detect(b"AutoCAD DXB 1.0\r\n\x1A\0\0").unwrap().format, FileFormat::Dxb);
+        let r12 = detect(b"AC1009\0\0\0\0\0").unwrap();
+        assert_eq!((r12.format, r12.version), (FileFormat::Dwg, DxfVersion::Unknown));
+        assert!(detect(b"%PDF-1.7").is_err());
+        assert!(detect("does/not/exist.dxf").is_err());
+    }
+
+    #[test]
+    fn test_detect_inspects_the_start_only() {
+        let filler = format!("  9\n$FILLER\n  1\n{}\n", "x".repeat(SNIFF_LENGTH as usize));
+        let dxf = format!("  0\nSECTION\n  2\nHEADER\n{filler}  9\n$ACADVER\n  1\nAC1018\n  0\nENDSEC\n");
+        let info = detect(dxf.as_bytes()).unwrap();
+        assert_eq!((info.format, info.version), (FileFormat::Dxf, DxfVersion::Unknown));
+    }
+}
@@ -1,15 +1,23 @@
 //! CAD document structure
 
 use crate::classes::DxfClassCollection;
-use crate::entities::{EntityCommon, EntityType};
-use crate::objects::ObjectType;
+use crate::entities::{EntityCommon, EntityType, RasterImage, Underlay, UnderlayDefinition};
+use crate::objects::{
+    Background, Dictionary, DictionaryCloningFlags, DimAssoc, FrameDisplay, GeoData, ImageDefinition,
+    ImageDefinitionReactor, Layout, MultiLeaderStyle, ObjectType, RasterVariables, Scale, Sun, TableStyle, WipeoutVariables, XRecord, XRecordEntry,
+};
+use crate::tables::layer::OverrideKind;
 use crate::tables::*;
 use crate::types::{DxfVersion, Color, Handle, Vector2, Vector3};
+use crate::error::DxfError;
 use crate::Result;
-use std::collections::HashMap;
+use indexmap::IndexMap;
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
 
 /// DWG header variables containing drawing settings
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct HeaderVariables {
     // ==================== Version-specific Flags ====================
     /// REQUIREDVERSIONS (R2013+) - Bit coded required versions
@@ -653,6 +661,12 @@ pub struct HeaderVariables {
     pub paper_ucs_base_handle: Handle,
     /// UCSBASE - Model space UCS base handle
     pub ucs_base_handle: Handle,
+
+    // ==================== Unrecognized variables ====================
+    /// Header variables not modeled above, keyed by name (e.g. `$TDUCREATE`),
+    /// holding their raw code/value pairs in file order.
+    /// Written back at the end of the HEADER section.
+    pub unknown_variables: IndexMap<String, Vec<(i32, String)>>,
 }
 
 impl Default for HeaderVariables {
@@ -997,11 +1011,16 @@ impl Default for HeaderVariables {
             cpsnid_handle: Handle::NULL,
             paper_ucs_base_handle: Handle::NULL,
             ucs_base_handle: Handle::NULL,
+
+            unknown_variables: IndexMap::new(),
         }
     }
 }
 
 /// A CAD document containing all drawing data
+///
+/// With the `serde` feature the document implements `Serialize` and
+/// `Deserialize`; see [`SERDE_SCHEMA_VERSION`] for the layout.
 #[derive(Debug, Clone)]
 pub struct CadDocument {
     /// Document version
@@ -1040,17 +1059,24 @@ pub struct CadDocument {
     /// DXF class definitions (CLASSES section)
     pub classes: DxfClassCollection,
 
+    /// Thumbnail image, from the DWG preview or DXF THUMBNAILIMAGE section
+    pub preview: Option<crate::preview::DwgPreview>,
+
     /// Notifications collected during the last read/write operation
     pub notifications: crate::notification::NotificationCollection,
 
     /// All entities in the document (indexed by handle)
-    entities: HashMap<Handle, EntityType>,
+    pub(crate) entities: HashMap<Handle, EntityType>,
 
     /// All objects in the document (indexed by handle)
     pub objects: HashMap<Handle, ObjectType>,
 
     /// Next handle to assign
     next_handle: u64,
+
+    /// Dimension style the header `$DIM*` variables were last in line
+    /// with; header values differing from it were set explicitly
+    dim_baseline: DimStyle,
 }
 
 impl CadDocument {
@@ -1069,16 +1095,19 @@ impl CadDocument {
             vports: Table::new(),
             ucss: Table::new(),
             classes: DxfClassCollection::new(),
+            preview: None,
             notifications: crate::notification::NotificationCollection::new(),
             entities: HashMap::new(),
             objects: HashMap::new(),
             // Start handle allocation above reserved table handles (0x1-0xA)
             // Table handles are well-known fixed values used by AutoCAD
             next_handle: 0x10,
+            dim_baseline: DimStyle::standard(),
         };
         
         // Initialize with standard entries
         doc.initialize_defaults();
+        doc.dim_baseline = DimStyle::from_header("", &doc.header);
         doc
     }
 
@@ -1268,6 +1297,23 @@ impl CadDocument {
             paper_layout_handle,
             crate::objects::ObjectType::Layout(paper_layout),
         );
+
+        // ACAD_MLEADERSTYLE dictionary — contains Standard, which new
+        // multileaders refer to
+        let mleaderstyle_dict_handle = self.allocate_handle();
+        let mut mleader_style = MultiLeaderStyle::standard();
+        mleader_style.handle = self.allocate_handle();
+        mleader_style.owner_handle = mleaderstyle_dict_handle;
+        mleader_style.text_style_handle = Some(self.header.current_text_style_handle);
+        let mut mleaderstyle_dict = crate::objects::Dictionary::new();
+        mleaderstyle_dict.handle = mleaderstyle_dict_handle;
+        mleaderstyle_dict.owner = root_handle;
+        mleaderstyle_dict.add_entry(MultiLeaderStyle::STANDARD, mleader_style.handle);
+        self.objects.insert(mleaderstyle_dict_handle, ObjectType::Dictionary(mleaderstyle_dict));
+        self.objects.insert(mleader_style.handle, ObjectType::MultiLeaderStyle(mleader_style));
+        if let Some(ObjectType::Dictionary(root_dict)) = self.objects.get_mut(&root_handle) {
+            root_dict.add_entry("ACAD_MLEADERSTYLE", mleaderstyle_dict_handle);
+        }
     }
 
     /// Allocate a new unique handle
@@ -1282,8 +1328,24 @@ impl CadDocument {
         self.next_handle
     }
 
+    /// Set the next handle value; see [`crate::handles::repair`]
+    pub(crate) fn set_next_handle(&mut self, next_handle: u64) {
+        self.next_handle = next_handle;
+    }
+
     /// Add an entity to the document
     pub fn add_entity(&mut self, mut entity: EntityType) -> Result<Handle> {
+        if let EntityType::MultiLeader(multileader) = &mut entity {
+            if multileader.style_handle.is_none() {
+                multileader.style_handle = self.multileader_style(MultiLeaderStyle::STANDARD).map(|s| s.handle);
+            }
+        }
+        if let EntityType::Table(table) = &mut entity {
+            if table.table_style_handle.is_none() {
+                table.table_style_handle = self.table_style(TableStyle::STANDARD).map(|s| s.handle);
+            }
+        }
+
         // Allocate a handle if the entity doesn't have one
         let handle = if entity.as_entity().handle().is_null() {
             let h = self.allocate_handle();
@@ -1323,12 +1385,1428 @@ impl CadDocument {
         self.entities.values()
     }
 
+    /// Iterate over all entities in handle order.
+    ///
+    /// Unlike [`entities`](Self::entities), the order is the same on every
+    /// run; the writers use it so that identical documents give identical
+    /// files.
+    pub fn sorted_entities(&self) -> impl Iterator<Item = &EntityType> {
+        let mut entities: Vec<&EntityType> = self.entities.values().collect();
+        entities.sort_by_key(|e| e.common().handle);
+        entities.into_iter()
+    }
+
+    /// Iterate over all objects in handle order, see
+    /// [`sorted_entities`](Self::sorted_entities)
+    pub fn sorted_objects(&self) -> impl Iterator<Item = (Handle, &ObjectType)> {
+        let mut objects: Vec<(Handle, &ObjectType)> = self.objects.iter().map(|(&h, o)| (h, o)).collect();
+        objects.sort_by_key(|(handle, _)| *handle);
+        objects.into_iter()
+    }
+
     /// Iterate over all entities mutably
     pub fn entities_mut(&mut self) -> impl Iterator<Item = &mut EntityType> {
         self.entities.values_mut()
     }
 
-    /// Resolve handle references after reading a DXF file.
+    /// Iterate over the entities of model space.
+    ///
+    /// Document entities without an owning block record count as model
+    /// space, as they are written there.
+    pub fn model_space(&self) -> impl Iterator<Item = &EntityType> {
+        self.block_records
+            .get("*Model_Space")
+            .into_iter()
+            .flat_map(|record| self.record_entities(record))
+    }
+
+    /// Iterate over the entities of the paper space layout named `layout`,
+    /// or `None` if there is no such layout
+    pub fn paper_space(&self, layout: &str) -> Option<impl Iterator<Item = &EntityType>> {
+        let block_record = self.objects.values().find_map(|object| match object {
+            ObjectType::Layout(l) if l.name.eq_ignore_ascii_case(layout) => Some(l.block_record),
+            _ => None,
+        })?;
+        let record = self
+            .block_records
+            .iter()
+            .find(|record| record.handle == block_record && record.is_paper_space())?;
+        Some(self.record_entities(record))
+    }
+
+    /// Iterate over the entities of the block record named `name`, or
+    /// `None` if there is no such block.
+    ///
+    /// Lazily read blocks yield nothing until [`Self::load_blocks`] is
+    /// called.
+    pub fn block_entities(&self, name: &str) -> Option<impl Iterator<Item = &EntityType>> {
+        let record = self.block_records.get(name)?;
+        Some(self.record_entities(record))
+    }
+
+    /// Iterate over the entities of every block record: model space, the
+    /// paper space layouts and the block definitions
+    pub fn all_entities(&self) -> impl Iterator<Item = &EntityType> {
+        self.block_records.iter().flat_map(|record| self.record_entities(record))
+    }
+
+    /// Entities of `record`, then the document entities it owns that it
+    /// does not list itself, in handle order
+    pub(crate) fn record_entities<'a>(&'a self, record: &'a BlockRecord) -> impl Iterator<Item = &'a EntityType> {
+        let records: HashSet<Handle> = self
+            .block_records
+            .iter()
+            .map(|b| b.handle)
+            .filter(|h| !h.is_null())
+            .collect();
+        let listed: HashSet<Handle> = record.entities.iter().map(|e| e.common().handle).collect();
+        let mut owned: Vec<&EntityType> = self
+            .entities
+            .values()
+            .filter(|e| {
+                let owner = e.common().owner_handle;
+                let in_record = if records.contains(&owner) {
+                    owner == record.handle
+                } else {
+                    record.is_model_space()
+                };
+                in_record && !listed.contains(&e.common().handle)
+            })
+            .collect();
+        owned.sort_by_key(|e| e.common().handle);
+        record.entities.iter().chain(owned)
+    }
+
+    /// Place a page of a PDF file as an underlay and return its handle.
+    ///
+    /// The definition is looked up in, or added to, the
+    /// `ACAD_PDFDEFINITIONS` dictionary; underlays of the same file and
+    /// page share one definition.
+    pub fn attach_pdf_underlay(
+        &mut self,
+        path: impl Into<String>,
+        page: impl Into<String>,
+        insertion: Vector3,
+        scale: f64,
+    ) -> Result<Handle> {
+        let definition = UnderlayDefinition::pdf(&path.into(), &page.into());
+        let definition_handle = self.underlay_definition(definition)?;
+
+        let mut underlay = Underlay::pdf_at(insertion);
+        underlay.set_scale(scale);
+        underlay.definition_handle = definition_handle;
+        let handle = self.add_entity(EntityType::Underlay(underlay))?;
+
+        if let Some(ObjectType::UnderlayDefinition(definition)) = self.objects.get_mut(&definition_handle) {
+            definition.reactors.push(handle);
+        }
+        Ok(handle)
+    }
+
+    /// Handle of the definition in its named object dictionary entry with
+    /// the same file and page, adding the definition (and the dictionary)
+    /// when there is none
+    fn underlay_definition(&mut self, mut definition: UnderlayDefinition) -> Result<Handle> {
+        let dictionary_handle = self.named_dictionary_slot(definition.underlay_type.dictionary_name())?;
+        let Some(ObjectType::Dictionary(dictionary)) = self.objects.get(&dictionary_handle) else {
+            return Err(DxfError::ObjectNotFound(dictionary_handle.value()));
+        };
+
+        let existing = dictionary.entries.iter().find_map(|(_, handle)| match self.objects.get(handle) {
+            Some(ObjectType::UnderlayDefinition(d))
+                if d.file_path == definition.file_path && d.page_name == definition.page_name =>
+            {
+                Some(*handle)
+            }
+            _ => None,
+        });
+        if let Some(handle) = existing {
+            return Ok(handle);
+        }
+
+        let name = underlay_entry_name(dictionary, &definition);
+        definition.handle = self.allocate_handle();
+        definition.owner_handle = dictionary_handle;
+        definition.reactors = vec![dictionary_handle];
+        definition.name = name.clone();
+        let handle = definition.handle;
+        self.objects.insert(handle, ObjectType::UnderlayDefinition(definition));
+        if let Some(ObjectType::Dictionary(dictionary)) = self.objects.get_mut(&dictionary_handle) {
+            dictionary.add_entry(name, handle);
+        }
+        Ok(handle)
+    }
+
+    /// List every underlay definition in the named object dictionary of
+    /// its type (`ACAD_PDFDEFINITIONS` and so on), which AutoCAD looks
+    /// definitions up in, and add the underlays to the reactors of their
+    /// definition.
+    ///
+    /// Both writers apply this to what they write.
+    pub fn link_underlay_definitions(&mut self) -> Result<()> {
+        let listed = self.listed_objects();
+        let unlisted: Vec<UnderlayDefinition> = self
+            .sorted_objects()
+            .filter_map(|(_, object)| match object {
+                ObjectType::UnderlayDefinition(definition) if !listed.contains(&definition.handle) => {
+                    Some(definition.clone())
+                }
+                _ => None,
+            })
+            .collect();
+        for mut definition in unlisted {
+            let dictionary_handle = self.named_dictionary_slot(definition.underlay_type.dictionary_name())?;
+            let Some(ObjectType::Dictionary(dictionary)) = self.objects.get_mut(&dictionary_handle) else {
+                return Err(DxfError::ObjectNotFound(dictionary_handle.value()));
+            };
+            if definition.name.is_empty() || dictionary.get(&definition.name).is_some() {
+                definition.name = underlay_entry_name(dictionary, &definition);
+            }
+            dictionary.add_entry(definition.name.clone(), definition.handle);
+            definition.owner_handle = dictionary_handle;
+            if !definition.reactors.contains(&dictionary_handle) {
+                definition.reactors.insert(0, dictionary_handle);
+            }
+            self.objects.insert(definition.handle, ObjectType::UnderlayDefinition(definition));
+        }
+
+        let references: Vec<(Handle, Handle)> = self
+            .underlays()
+            .map(|underlay| (underlay.definition_handle, underlay.common.handle))
+            .collect();
+        for (definition, underlay) in references {
+            if let Some(ObjectType::UnderlayDefinition(definition)) = self.objects.get_mut(&definition) {
+                if !underlay.is_null() && !definition.reactors.contains(&underlay) {
+                    definition.reactors.push(underlay);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether [`CadDocument::link_underlay_definitions`] has nothing to add
+    fn underlay_definitions_linked(&self) -> bool {
+        let listed = self.listed_objects();
+        let definitions_listed = self.objects.values().all(|object| match object {
+            ObjectType::UnderlayDefinition(definition) => listed.contains(&definition.handle),
+            _ => true,
+        });
+        definitions_listed
+            && self.underlays().all(|underlay| match self.objects.get(&underlay.definition_handle) {
+                Some(ObjectType::UnderlayDefinition(definition)) => {
+                    underlay.common.handle.is_null() || definition.reactors.contains(&underlay.common.handle)
+                }
+                _ => true,
+            })
+    }
+
+    /// Name underlay definitions after their dictionary entry, after reading
+    pub(crate) fn resolve_underlay_definitions(&mut self) {
+        let names: HashMap<Handle, String> = self
+            .objects
+            .values()
+            .filter_map(|object| match object {
+                ObjectType::Dictionary(dictionary) => Some(dictionary),
+                _ => None,
+            })
+            .flat_map(|dictionary| dictionary.entries.iter().map(|(name, handle)| (*handle, name.clone())))
+            .collect();
+        for object in self.objects.values_mut() {
+            if let ObjectType::UnderlayDefinition(definition) = object {
+                if let Some(name) = names.get(&definition.handle) {
+                    definition.name = name.clone();
+                }
+            }
+        }
+    }
+
+    /// Handles of the objects some dictionary lists
+    fn listed_objects(&self) -> HashSet<Handle> {
+        self.objects
+            .values()
+            .filter_map(|object| match object {
+                ObjectType::Dictionary(dictionary) => Some(dictionary),
+                _ => None,
+            })
+            .flat_map(|dictionary| dictionary.entries.iter().map(|(_, handle)| *handle))
+            .collect()
+    }
+
+    fn underlays(&self) -> impl Iterator<Item = &Underlay> {
+        let block_entities = self.block_records.iter().flat_map(|record| record.entities.iter());
+        self.sorted_entities().chain(block_entities).filter_map(|entity| match entity {
+            EntityType::Underlay(underlay) => Some(underlay),
+            _ => None,
+        })
+    }
+
+    /// Give every table without a block the anonymous `*T` block of its
+    /// grid lines and cell texts (see [`crate::entities::Table::block_entities`]),
+    /// the geometry AutoCAD displays the table with.
+    ///
+    /// Tables that already have a block keep it; clear
+    /// `block_record_handle` after editing a table to have its block drawn
+    /// anew.  Both writers apply this to what they write.
+    pub fn build_table_blocks(&mut self) -> Result<()> {
+        let records: HashSet<Handle> = self.block_records.iter().map(|record| record.handle).collect();
+        let needs_block = |entity: &EntityType| match entity {
+            EntityType::Table(table) => !table.block_record_handle.is_some_and(|h| records.contains(&h)),
+            _ => false,
+        };
+        let document_tables: Vec<Handle> = self
+            .sorted_entities()
+            .filter(|entity| needs_block(entity))
+            .map(|entity| entity.common().handle)
+            .collect();
+        let block_tables: Vec<(String, usize)> = self
+            .block_records
+            .iter()
+            .flat_map(|record| {
+                let positions = record.entities.iter().enumerate().filter(|(_, e)| needs_block(e));
+                positions.map(|(i, _)| (record.name.clone(), i)).collect::<Vec<_>>()
+            })
+            .collect();
+
+        let mut number = 0;
+        let mut build = |document: &mut CadDocument, table: &crate::entities::Table| -> Result<Handle> {
+            loop {
+                number += 1;
+                if document.block_records.get(&format!("*T{number}")).is_none() {
+                    break;
+                }
+            }
+            let mut record = BlockRecord::new(format!("*T{number}"));
+            record.handle = document.allocate_handle();
+            record.block_entity_handle = document.allocate_handle();
+            record.block_end_handle = document.allocate_handle();
+            record.flags.anonymous = true;
+            for mut entity in table.block_entities() {
+                let common = entity.common_mut();
+                common.handle = document.allocate_handle();
+                common.owner_handle = record.handle;
+                record.entities.push(entity);
+            }
+            let handle = record.handle;
+            document.block_records.add(record).map_err(DxfError::Custom)?;
+            Ok(handle)
+        };
+
+        for handle in document_tables {
+            let Some(EntityType::Table(table)) = self.entities.get(&handle).cloned() else {
+                continue;
+            };
+            let block = build(self, &table)?;
+            if let Some(EntityType::Table(table)) = self.entities.get_mut(&handle) {
+                table.block_record_handle = Some(block);
+            }
+        }
+        for (name, index) in block_tables {
+            let Some(EntityType::Table(table)) = self.block_records.get(&name).map(|r| r.entities[index].clone()) else {
+                continue;
+            };
+            let block = build(self, &table)?;
+            if let Some(EntityType::Table(table)) = self.block_records.get_mut(&name).map(|r| &mut r.entities[index]) {
+                table.block_record_handle = Some(block);
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether [`CadDocument::build_table_blocks`] has nothing to add
+    fn table_blocks_built(&self) -> bool {
+        let records: HashSet<Handle> = self.block_records.iter().map(|record| record.handle).collect();
+        self.entities.values().chain(self.block_records.iter().flat_map(|r| r.entities.iter())).all(|entity| match entity {
+            EntityType::Table(table) => table.block_record_handle.is_some_and(|h| records.contains(&h)),
+            _ => true,
+        })
+    }
+
+    /// Give every raster image an `IMAGEDEF` in `ACAD_IMAGE_DICT` and an
+    /// `IMAGEDEF_REACTOR` of its own, the objects AutoCAD needs to resolve
+    /// the image file.
+    ///
+    /// Images of the same file share a definition, and `ACAD_IMAGE_VARS` is
+    /// added when the drawing has none.  Both writers apply this to what
+    /// they write.
+    pub fn link_raster_images(&mut self) -> Result<()> {
+        let unlinked: Vec<RasterImage> = self
+            .raster_images()
+            .filter(|image| !image.common.handle.is_null() && !self.raster_image_linked(image))
+            .cloned()
+            .collect();
+        let mut links = HashMap::new();
+        for image in unlinked {
+            let definition_handle = match image.definition_handle {
+                Some(handle) if matches!(self.objects.get(&handle), Some(ObjectType::ImageDefinition(_))) => handle,
+                _ => self.image_definition(&image)?,
+            };
+            let reactor_handle = match image.definition_reactor_handle {
+                Some(handle) if matches!(self.objects.get(&handle), Some(ObjectType::ImageDefinitionReactor(_))) => handle,
+                _ => {
+                    let mut reactor = ImageDefinitionReactor::new(image.common.handle);
+                    reactor.handle = self.allocate_handle();
+                    reactor.owner = image.common.handle;
+                    let handle = reactor.handle;
+                    self.objects.insert(handle, ObjectType::ImageDefinitionReactor(reactor));
+                    handle
+                }
+            };
+            if let Some(ObjectType::ImageDefinition(definition)) = self.objects.get_mut(&definition_handle) {
+                if !definition.reactors.contains(&reactor_handle) {
+                    definition.reactors.push(reactor_handle);
+                }
+            }
+            links.insert(image.common.handle, (definition_handle, reactor_handle));
+        }
+
+        let entities = self.entities.values_mut();
+        let block_entities = self.block_records.iter_mut().flat_map(|record| record.entities.iter_mut());
+        for entity in entities.chain(block_entities) {
+            if let EntityType::RasterImage(image) = entity {
+                if let Some(&(definition, reactor)) = links.get(&image.common.handle) {
+                    image.definition_handle = Some(definition);
+                    image.definition_reactor_handle = Some(reactor);
+                }
+            }
+        }
+
+        if self.raster_images().next().is_some() && self.raster_variables().is_none() {
+            self.set_raster_variables(RasterVariables::new())?;
+        }
+        Ok(())
+    }
+
+    /// Whether [`CadDocument::link_raster_images`] has nothing to add
+    fn raster_images_linked(&self) -> bool {
+        let mut images = self.raster_images().filter(|image| !image.common.handle.is_null()).peekable();
+        if images.peek().is_none() {
+            return true;
+        }
+        self.raster_variables().is_some() && images.all(|image| self.raster_image_linked(image))
+    }
+
+    fn raster_image_linked(&self, image: &RasterImage) -> bool {
+        let definition = image.definition_handle.and_then(|handle| match self.objects.get(&handle) {
+            Some(ObjectType::ImageDefinition(definition)) => Some(definition),
+            _ => None,
+        });
+        let reactor = image
+            .definition_reactor_handle
+            .filter(|handle| matches!(self.objects.get(handle), Some(ObjectType::ImageDefinitionReactor(_))));
+        matches!((definition, reactor), (Some(definition), Some(reactor)) if definition.reactors.contains(&reactor))
+    }
+
+    /// Tie raster images to their `IMAGEDEF` and `IMAGEDEF_REACTOR` after
+    /// reading.
+    ///
+    /// An image whose reactor handle names no reactor takes the reactor
+    /// pointing back at it; definitions list the reactors of their images,
+    /// and images without a file path take the one of their definition.
+    pub(crate) fn resolve_raster_images(&mut self) {
+        let reactors: HashMap<Handle, Handle> = self
+            .objects
+            .iter()
+            .filter_map(|(handle, object)| match object {
+                ObjectType::ImageDefinitionReactor(reactor) if !reactor.image_handle.is_null() => {
+                    Some((reactor.image_handle, *handle))
+                }
+                _ => None,
+            })
+            .collect();
+
+        let objects = &self.objects;
+        let mut links = Vec::new();
+        let block_entities = self.block_records.iter_mut().flat_map(|record| record.entities.iter_mut());
+        for entity in self.entities.values_mut().chain(block_entities) {
+            let EntityType::RasterImage(image) = entity else { continue };
+            let reactor_found = image
+                .definition_reactor_handle
+                .is_some_and(|handle| matches!(objects.get(&handle), Some(ObjectType::ImageDefinitionReactor(_))));
+            if !reactor_found {
+                image.definition_reactor_handle = reactors.get(&image.common.handle).copied();
+            }
+            if let Some(ObjectType::ImageDefinition(definition)) = image.definition_handle.and_then(|h| objects.get(&h)) {
+                if image.file_path.is_empty() {
+                    image.file_path = definition.file_name.clone();
+                }
+            }
+            if let (Some(definition), Some(reactor)) = (image.definition_handle, image.definition_reactor_handle) {
+                links.push((definition, reactor, image.common.handle));
+            }
+        }
+
+        for (definition, reactor, image) in links {
+            if let Some(ObjectType::ImageDefinitionReactor(object)) = self.objects.get_mut(&reactor) {
+                if object.image_handle.is_null() {
+                    object.image_handle = image;
+                }
+            }
+            if let Some(ObjectType::ImageDefinition(object)) = self.objects.get_mut(&definition) {
+                if !object.reactors.contains(&reactor) {
+                    object.reactors.push(reactor);
+                }
+            }
+        }
+    }
+
+    /// Raster images of model space and of every block
+    fn raster_images(&self) -> impl Iterator<Item = &RasterImage> {
+        let block_entities = self.block_records.iter().flat_map(|record| record.entities.iter());
+        self.sorted_entities().chain(block_entities).filter_map(|entity| match entity {
+            EntityType::RasterImage(image) => Some(&**image),
+            _ => None,
+        })
+    }
+
+    /// Handle of the definition of the image's file in `ACAD_IMAGE_DICT`,
+    /// adding the definition (and the dictionary) when there is none
+    fn image_definition(&mut self, image: &RasterImage) -> Result<Handle> {
+        let dictionary_handle = self.named_dictionary_slot("ACAD_IMAGE_DICT")?;
+        let Some(ObjectType::Dictionary(dictionary)) = self.objects.get(&dictionary_handle) else {
+            return Err(DxfError::ObjectNotFound(dictionary_handle.value()));
+        };
+
+        let existing = dictionary.entries.iter().find_map(|(_, handle)| match self.objects.get(handle) {
+            Some(ObjectType::ImageDefinition(d)) if d.file_name == image.file_path => Some(*handle),
+            _ => None,
+        });
+        if let Some(handle) = existing {
+            return Ok(handle);
+        }
+
+        // AutoCAD names entries after the file
+        let base = std::path::Path::new(&image.file_path)
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("Image")
+            .to_string();
+        let mut name = base.clone();
+        let mut suffix = 1;
+        while dictionary.get(&name).is_some() {
+            suffix += 1;
+            name = format!("{base}({suffix})");
+        }
+
+        let mut definition =
+            ImageDefinition::with_dimensions(image.file_path.clone(), image.size.x as u32, image.size.y as u32);
+        definition.handle = self.allocate_handle();
+        definition.owner = dictionary_handle;
+        definition.reactors = vec![dictionary_handle];
+        definition.is_loaded = true;
+        let handle = definition.handle;
+        self.objects.insert(handle, ObjectType::ImageDefinition(definition));
+        if let Some(ObjectType::Dictionary(dictionary)) = self.objects.get_mut(&dictionary_handle) {
+            dictionary.add_entry(name, handle);
+        }
+        Ok(handle)
+    }
+
+    /// Multileader style by name, as listed in `ACAD_MLEADERSTYLE`
+    pub fn multileader_style(&self, name: &str) -> Option<&MultiLeaderStyle> {
+        let listed = self
+            .named_dictionary("ACAD_MLEADERSTYLE")
+            .and_then(|dictionary| dictionary.entries.iter().find(|(key, _)| key.eq_ignore_ascii_case(name)))
+            .and_then(|(_, handle)| match self.objects.get(handle) {
+                Some(ObjectType::MultiLeaderStyle(style)) => Some(style),
+                _ => None,
+            });
+        listed.or_else(|| {
+            self.objects.values().find_map(|object| match object {
+                ObjectType::MultiLeaderStyle(style) if style.name.eq_ignore_ascii_case(name) => Some(style),
+                _ => None,
+            })
+        })
+    }
+
+    /// Table style by name, as listed in `ACAD_TABLESTYLE`
+    pub fn table_style(&self, name: &str) -> Option<&TableStyle> {
+        let dictionary = self.named_dictionary("ACAD_TABLESTYLE")?;
+        let (_, handle) = dictionary.entries.iter().find(|(key, _)| key.eq_ignore_ascii_case(name))?;
+        match self.objects.get(handle) {
+            Some(ObjectType::TableStyle(style)) => Some(style),
+            _ => None,
+        }
+    }
+
+    /// Add a table style to `ACAD_TABLESTYLE`, creating the dictionary when
+    /// the drawing has none, and return its handle.
+    ///
+    /// A style of the same name is replaced and keeps its handle, so tables
+    /// already linked to it pick up the new look.
+    pub fn add_table_style(&mut self, mut style: TableStyle) -> Result<Handle> {
+        let dictionary_handle = self.named_dictionary_slot("ACAD_TABLESTYLE")?;
+
+        style.handle = match self.table_style(&style.name) {
+            Some(existing) => existing.handle,
+            None => self.allocate_handle(),
+        };
+        style.owner_handle = dictionary_handle;
+        let handle = style.handle;
+        if let Some(ObjectType::Dictionary(dictionary)) = self.objects.get_mut(&dictionary_handle) {
+            if !dictionary.entries.iter().any(|(_, h)| *h == handle) {
+                dictionary.add_entry(style.name.clone(), handle);
+            }
+        }
+        self.objects.insert(handle, ObjectType::TableStyle(style));
+        Ok(handle)
+    }
+
+    /// Layouts of the document in tab order, `Model` first.
+    pub fn layouts(&self) -> Vec<&Layout> {
+        let mut layouts: Vec<&Layout> = self
+            .objects
+            .values()
+            .filter_map(|object| match object {
+                ObjectType::Layout(layout) => Some(layout),
+                _ => None,
+            })
+            .collect();
+        layouts.sort_by_key(|layout| layout.tab_order);
+        layouts
+    }
+
+    /// Layout by name, e.g. `"Layout1"`, for its plot settings and
+    /// [`Layout::effective_plot_area`]
+    pub fn layout(&self, name: &str) -> Option<&Layout> {
+        self.layouts().into_iter().find(|layout| layout.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Scales of the scale list (`ACAD_SCALELIST`), in list order.
+    ///
+    /// Falls back to every SCALE object when the list dictionary is not
+    /// loaded, as for drawings read from DWG.
+    pub fn scales(&self) -> Vec<&Scale> {
+        let scale = |handle: &Handle| match self.objects.get(handle) {
+            Some(ObjectType::Scale(scale)) => Some(scale),
+            _ => None,
+        };
+        match self.named_dictionary("ACAD_SCALELIST") {
+            Some(dictionary) => dictionary.entries.iter().filter_map(|(_, handle)| scale(handle)).collect(),
+            None => self
+                .objects
+                .values()
+                .filter_map(|object| match object {
+                    ObjectType::Scale(scale) => Some(scale),
+                    _ => None,
+                })
+                .collect(),
+        }
+    }
+
+    /// Scale of the scale list by name, e.g. `"1:50"`
+    pub fn scale(&self, name: &str) -> Option<&Scale> {
+        self.scales().into_iter().find(|scale| scale.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Add a scale to `ACAD_SCALELIST`, creating the dictionary when the
+    /// drawing has none, and return its handle.
+    ///
+    /// A scale of the same name is replaced and keeps its handle.  New
+    /// entries are keyed `A0`, `A1`, ... as AutoCAD does.
+    pub fn add_scale(&mut self, mut scale: Scale) -> Result<Handle> {
+        let dictionary_handle = self.named_dictionary_slot("ACAD_SCALELIST")?;
+        if let Some(existing) = self.scale(&scale.name) {
+            scale.handle = existing.handle;
+            scale.owner_handle = existing.owner_handle;
+            let handle = scale.handle;
+            self.objects.insert(handle, ObjectType::Scale(scale));
+            return Ok(handle);
+        }
+
+        scale.handle = self.allocate_handle();
+        scale.owner_handle = dictionary_handle;
+        let handle = scale.handle;
+        self.objects.insert(handle, ObjectType::Scale(scale));
+        if let Some(ObjectType::Dictionary(dictionary)) = self.objects.get_mut(&dictionary_handle) {
+            let key = (0..).map(|n| format!("A{n}")).find(|key| dictionary.get(key).is_none()).unwrap_or_default();
+            dictionary.add_entry(key, handle);
+        }
+        Ok(handle)
+    }
+
+    /// Dictionary stored under `name` in the named object dictionary
+    fn named_dictionary(&self, name: &str) -> Option<&crate::objects::Dictionary> {
+        let Some(ObjectType::Dictionary(root)) = self.objects.get(&self.header.named_objects_dict_handle) else {
+            return None;
+        };
+        match self.objects.get(&root.get(name)?) {
+            Some(ObjectType::Dictionary(dictionary)) => Some(dictionary),
+            _ => None,
+        }
+    }
+
+    /// Plot style of `ACAD_PLOTSTYLENAME` by name, falling back to the
+    /// dictionary's default entry for names it does not list
+    pub fn plot_style_handle(&self, name: &str) -> Option<Handle> {
+        match self.objects.get(&self.header.acad_plotstylename_dict_handle)? {
+            ObjectType::DictionaryWithDefault(dictionary) => dictionary.resolve(name),
+            ObjectType::Dictionary(dictionary) => dictionary.get(name),
+            _ => None,
+        }
+    }
+
+    /// Image frame, quality and units settings (`ACAD_IMAGE_VARS`)
+    pub fn raster_variables(&self) -> Option<&RasterVariables> {
+        self.objects.values().find_map(|object| match object {
+            ObjectType::RasterVariables(variables) => Some(variables),
+            _ => None,
+        })
+    }
+
+    /// Replace the image settings, listing them in the named object
+    /// dictionary when the drawing has none yet
+    pub fn set_raster_variables(&mut self, mut variables: RasterVariables) -> Result<()> {
+        (variables.handle, variables.owner) = match self.raster_variables() {
+            Some(existing) => (existing.handle, existing.owner),
+            None => self.named_object_slot("ACAD_IMAGE_VARS")?,
+        };
+        self.objects.insert(variables.handle, ObjectType::RasterVariables(variables));
+        Ok(())
+    }
+
+    /// Wipeout frame setting (`ACAD_WIPEOUT_VARS`)
+    pub fn wipeout_variables(&self) -> Option<&WipeoutVariables> {
+        self.objects.values().find_map(|object| match object {
+            ObjectType::WipeoutVariables(variables) => Some(variables),
+            _ => None,
+        })
+    }
+
+    /// Replace the wipeout settings, listing them in the named object
+    /// dictionary when the drawing has none yet
+    pub fn set_wipeout_variables(&mut self, mut variables: WipeoutVariables) -> Result<()> {
+        (variables.handle, variables.owner) = match self.wipeout_variables() {
+            Some(existing) => (existing.handle, existing.owner),
+            None => self.named_object_slot("ACAD_WIPEOUT_VARS")?,
+        };
+        self.objects.insert(variables.handle, ObjectType::WipeoutVariables(variables));
+        Ok(())
+    }
+
+    /// Frame display of raster images (`IMAGEFRAME`); AutoCAD displays
+    /// and plots them when the drawing has no `ACAD_IMAGE_VARS`
+    pub fn image_frame(&self) -> FrameDisplay {
+        self.raster_variables().map(RasterVariables::frame).unwrap_or_default()
+    }
+
+    /// Set the frame display of raster images, kept in `ACAD_IMAGE_VARS`
+    pub fn set_image_frame(&mut self, frame: FrameDisplay) -> Result<()> {
+        let mut variables = self.raster_variables().cloned().unwrap_or_default();
+        variables.display_image_frame = frame as i16;
+        self.set_raster_variables(variables)
+    }
+
+    /// Frame display of wipeouts (`WIPEOUTFRAME`); hidden when the drawing
+    /// has no `ACAD_WIPEOUT_VARS`
+    pub fn wipeout_frame(&self) -> FrameDisplay {
+        self.wipeout_variables().map(WipeoutVariables::frame).unwrap_or(FrameDisplay::Hidden)
+    }
+
+    /// Set the frame display of wipeouts, kept in `ACAD_WIPEOUT_VARS`
+    pub fn set_wipeout_frame(&mut self, frame: FrameDisplay) -> Result<()> {
+        let mut variables = self.wipeout_variables().cloned().unwrap_or_default();
+        variables.display_frame = frame as i16;
+        self.set_wipeout_variables(variables)
+    }
+
+    /// Geographic location of the drawing (`ACAD_GEOGRAPHICDATA`)
+    pub fn geodata(&self) -> Option<&GeoData> {
+        self.objects.values().find_map(|object| match object {
+            ObjectType::GeoData(geodata) => Some(geodata),
+            _ => None,
+        })
+    }
+
+    /// Place the drawing on the earth and return the GEODATA handle.
+    ///
+    /// Replaces the existing location, keeping its handle.  A new one is
+    /// stored under `ACAD_GEOGRAPHICDATA` in the extension dictionary of
+    /// model space, which is created when missing, and hosted by model
+    /// space.  See [`crate::objects::CoordinateSystem`] for definitions.
+    pub fn set_geodata(&mut self, mut geodata: GeoData) -> Result<Handle> {
+        let model_space = self.header.model_space_block_handle;
+        geodata.host_block = model_space;
+        if let Some(existing) = self.geodata() {
+            (geodata.handle, geodata.owner) = (existing.handle, existing.owner);
+            let handle = geodata.handle;
+            self.objects.insert(handle, ObjectType::GeoData(geodata));
+            return Ok(handle);
+        }
+
+        let xdictionary = self
+            .block_records
+            .get("*Model_Space")
+            .ok_or(DxfError::ObjectNotFound(model_space.value()))?
+            .xdictionary_handle
+            .filter(|handle| matches!(self.objects.get(handle), Some(ObjectType::Dictionary(_))));
+        let xdictionary = match xdictionary {
+            Some(handle) => handle,
+            None => {
+                let mut dictionary = crate::objects::Dictionary::new();
+                dictionary.handle = self.allocate_handle();
+                dictionary.owner = model_space;
+                dictionary.hard_owner = true;
+                let handle = dictionary.handle;
+                self.objects.insert(handle, ObjectType::Dictionary(dictionary));
+                if let Some(record) = self.block_records.get_mut("*Model_Space") {
+                    record.xdictionary_handle = Some(handle);
+                }
+                handle
+            }
+        };
+
+        geodata.handle = self.allocate_handle();
+        geodata.owner = xdictionary;
+        let handle = geodata.handle;
+        self.objects.insert(handle, ObjectType::GeoData(geodata));
+        if let Some(ObjectType::Dictionary(dictionary)) = self.objects.get_mut(&xdictionary) {
+            dictionary.add_entry("ACAD_GEOGRAPHICDATA", handle);
+        }
+        Ok(handle)
+    }
+
+    /// SUN object by handle, e.g. the `sun_handle` of a viewport
+    pub fn sun(&self, handle: Handle) -> Option<&Sun> {
+        match self.objects.get(&handle) {
+            Some(ObjectType::Sun(sun)) => Some(sun),
+            _ => None,
+        }
+    }
+
+    /// Light the viewport table entry `vport_name` with `sun` and return
+    /// the SUN handle.
+    ///
+    /// Replaces the viewport's existing sun, keeping its handle; a new sun
+    /// is owned by the viewport.
+    pub fn set_viewport_sun(&mut self, vport_name: &str, mut sun: Sun) -> Result<Handle> {
+        let vport = self
+            .vports
+            .get(vport_name)
+            .ok_or_else(|| DxfError::Custom(format!("Viewport not found: {vport_name}")))?;
+        sun.owner = vport.handle;
+        sun.handle = match self.sun(vport.sun_handle) {
+            Some(existing) => existing.handle,
+            None => self.allocate_handle(),
+        };
+        let handle = sun.handle;
+        self.objects.insert(handle, ObjectType::Sun(sun));
+        if let Some(vport) = self.vports.get_mut(vport_name) {
+            vport.sun_handle = handle;
+        }
+        Ok(handle)
+    }
+
+    /// Background object by handle, e.g. the `background_handle` of a
+    /// viewport
+    pub fn background(&self, handle: Handle) -> Option<&Background> {
+        match self.objects.get(&handle) {
+            Some(ObjectType::Background(background)) => Some(background),
+            _ => None,
+        }
+    }
+
+    /// Add a background to `ACAD_BACKGROUND` under `name`, creating the
+    /// dictionary when the drawing has none, and return its handle.
+    ///
+    /// A background of the same name is replaced and keeps its handle.
+    pub fn add_background(&mut self, name: &str, mut background: Background) -> Result<Handle> {
+        let dictionary_handle = self.named_dictionary_slot("ACAD_BACKGROUND")?;
+        let existing = self.named_dictionary("ACAD_BACKGROUND").and_then(|dictionary| dictionary.get(name));
+        background.owner = dictionary_handle;
+        background.handle = match existing {
+            Some(handle) => handle,
+            None => self.allocate_handle(),
+        };
+        let handle = background.handle;
+        self.objects.insert(handle, ObjectType::Background(background));
+        if existing.is_none() {
+            if let Some(ObjectType::Dictionary(dictionary)) = self.objects.get_mut(&dictionary_handle) {
+                dictionary.add_entry(name, handle);
+            }
+        }
+        Ok(handle)
+    }
+
+    /// The DIMASSOC linking the points of the dimension `dimension` to
+    /// geometry: the one stored under `ACAD_DIMASSOC` in its extension
+    /// dictionary, or else any DIMASSOC naming the dimension
+    pub fn dimension_association(&self, dimension: Handle) -> Option<&DimAssoc> {
+        let as_association = |handle: Handle| match self.objects.get(&handle) {
+            Some(ObjectType::DimAssoc(association)) => Some(association),
+            _ => None,
+        };
+        let stored = self
+            .get_entity(dimension)?
+            .common()
+            .xdictionary_handle
+            .and_then(|xdictionary| match self.objects.get(&xdictionary) {
+                Some(ObjectType::Dictionary(dictionary)) => dictionary.get("ACAD_DIMASSOC"),
+                _ => None,
+            })
+            .and_then(as_association);
+        stored.or_else(|| {
+            self.objects.values().find_map(|object| match object {
+                ObjectType::DimAssoc(association) if association.dimension == dimension => Some(association),
+                _ => None,
+            })
+        })
+    }
+
+    /// Link the points of the dimension `dimension` to geometry and return
+    /// the DIMASSOC handle.
+    ///
+    /// The association is stored under `ACAD_DIMASSOC` in the extension
+    /// dictionary of the dimension, which is created when missing, and
+    /// replaces an existing one, keeping its handle.  The linked geometry
+    /// gets the association among its reactors.
+    pub fn associate_dimension(&mut self, dimension: Handle, mut association: DimAssoc) -> Result<Handle> {
+        let Some(EntityType::Dimension(_)) = self.get_entity(dimension) else {
+            return Err(DxfError::ObjectNotFound(dimension.value()));
+        };
+        let previous: Vec<Handle> = match self.dimension_association(dimension) {
+            Some(existing) => {
+                association.handle = existing.handle;
+                existing.geometry().collect()
+            }
+            None => {
+                association.handle = self.allocate_handle();
+                Vec::new()
+            }
+        };
+        let handle = association.handle;
+        for geometry in previous {
+            if let Some(entity) = self.get_entity_mut(geometry) {
+                entity.common_mut().reactors.retain(|reactor| *reactor != handle);
+            }
+        }
+        let geometry: Vec<Handle> = association.geometry().collect();
+        for geometry in geometry {
+            if let Some(entity) = self.get_entity_mut(geometry) {
+                let reactors = &mut entity.common_mut().reactors;
+                if !reactors.contains(&handle) {
+                    reactors.push(handle);
+                }
+            }
+        }
+
+        let xdictionary = self.entity_extension_dictionary(dimension);
+        association.owner = xdictionary;
+        association.dimension = dimension;
+        self.objects.insert(handle, ObjectType::DimAssoc(association));
+        if let Some(ObjectType::Dictionary(dictionary)) = self.objects.get_mut(&xdictionary) {
+            if dictionary.get("ACAD_DIMASSOC").is_none() {
+                dictionary.add_entry("ACAD_DIMASSOC", handle);
+            }
+        }
+        Ok(handle)
+    }
+
+    /// Extension dictionary of the entity `entity`, created when missing
+    fn entity_extension_dictionary(&mut self, entity: Handle) -> Handle {
+        let existing = self
+            .get_entity(entity)
+            .and_then(|e| e.common().xdictionary_handle)
+            .filter(|handle| matches!(self.objects.get(handle), Some(ObjectType::Dictionary(_))));
+        if let Some(handle) = existing {
+            return handle;
+        }
+        let mut dictionary = crate::objects::Dictionary::new();
+        dictionary.handle = self.allocate_handle();
+        dictionary.owner = entity;
+        dictionary.hard_owner = true;
+        let handle = dictionary.handle;
+        self.objects.insert(handle, ObjectType::Dictionary(dictionary));
+        if let Some(e) = self.get_entity_mut(entity) {
+            e.common_mut().xdictionary_handle = Some(handle);
+        }
+        handle
+    }
+
+    /// Handle and owner for the object stored under `name` in the named
+    /// object dictionary, adding the entry when it is missing
+    fn named_object_slot(&mut self, name: &str) -> Result<(Handle, Handle)> {
+        let root_handle = self.header.named_objects_dict_handle;
+        let Some(ObjectType::Dictionary(root)) = self.objects.get(&root_handle) else {
+            return Err(DxfError::ObjectNotFound(root_handle.value()));
+        };
+        if let Some(handle) = root.get(name) {
+            return Ok((handle, root_handle));
+        }
+        let handle = self.allocate_handle();
+        if let Some(ObjectType::Dictionary(root)) = self.objects.get_mut(&root_handle) {
+            root.add_entry(name, handle);
+        }
+        Ok((handle, root_handle))
+    }
+
+    /// Handle of the dictionary stored under `name` in the named object
+    /// dictionary, adding an empty one when it is missing
+    fn named_dictionary_slot(&mut self, name: &str) -> Result<Handle> {
+        if let Some(dictionary) = self.named_dictionary(name) {
+            return Ok(dictionary.handle);
+        }
+        let mut dictionary = crate::objects::Dictionary::new();
+        (dictionary.handle, dictionary.owner) = self.named_object_slot(name)?;
+        let handle = dictionary.handle;
+        self.objects.insert(handle, ObjectType::Dictionary(dictionary));
+        Ok(handle)
+    }
+
+    /// The dynamic block an anonymous `*U` block was made from, found
+    /// through the `AcDbBlockRepBTag` extended data of its block record
+    pub fn dynamic_block_definition(&self, block_name: &str) -> Option<&BlockRecord> {
+        let handle = self.block_records.get(block_name)?.dynamic_block_handle()?;
+        self.block_records.iter().find(|record| record.handle == handle)
+    }
+
+    /// Fill in the title blocks of the drawing and return the number of
+    /// attributes set.
+    ///
+    /// Every attribute of a block reference whose tag is a key of `values`
+    /// (ignoring case) gets the value.  Text, multiline text and attributes
+    /// that show an updated attribute through a field
+    /// (`\AcObjProp Object(...).TextString` as their whole text) are
+    /// refreshed with it.  FIELD objects are kept as unknown objects, so
+    /// their cached values are left for CAD applications to re-evaluate.
+    pub fn update_title_block(&mut self, values: &HashMap<String, String>) -> usize {
+        let value_of = |tag: &str| values.iter().find(|(key, _)| key.eq_ignore_ascii_case(tag)).map(|(_, v)| v);
+
+        let mut count = 0;
+        let mut updated: HashMap<Handle, String> = HashMap::new();
+        for entity in self.entities.values_mut() {
+            let EntityType::Insert(insert) = entity else { continue };
+            for attribute in &mut insert.attributes {
+                if let Some(value) = value_of(&attribute.tag) {
+                    attribute.set_value(value.clone());
+                    updated.insert(attribute.common.handle, value.clone());
+                    count += 1;
+                }
+            }
+        }
+        updated.remove(&Handle::NULL);
+
+        // Field -> displayed text: FIELD (TextString) -> FIELD (_text) ->
+        // ACAD_FIELD dictionary -> extension dictionary -> entity
+        let refreshed: HashMap<Handle, String> = self
+            .objects
+            .values()
+            .filter_map(|object| {
+                let pairs = field_pairs(object)?;
+                let code = pairs.iter().find(|(c, _)| *c == 2)?;
+                if !code.1.contains(".TextString") {
+                    return None;
+                }
+                let value = pairs
+                    .iter()
+                    .filter(|(c, _)| *c == 331)
+                    .find_map(|(_, v)| updated.get(&parse_handle(v)?))?;
+                let text_field = raw_owner(pairs)?;
+                let text_pairs = field_pairs(self.objects.get(&text_field)?)?;
+                if !text_pairs.iter().any(|(c, v)| *c == 2 && v.trim() == "%<\\_FldIdx 0>%") {
+                    return None;
+                }
+                let Some(ObjectType::Dictionary(fields)) = self.objects.get(&raw_owner(text_pairs)?) else {
+                    return None;
+                };
+                Some((fields.owner, value.clone()))
+            })
+            .collect();
+        if refreshed.is_empty() {
+            return count;
+        }
+        for entity in self.entities.values_mut() {
+            match entity {
+                EntityType::Text(text) => {
+                    if let Some(value) = text.common.xdictionary_handle.and_then(|h| refreshed.get(&h)) {
+                        text.value = value.clone();
+                    }
+                }
+                EntityType::MText(mtext) => {
+                    if let Some(value) = mtext.common.xdictionary_handle.and_then(|h| refreshed.get(&h)) {
+                        mtext.value = value.clone();
+                    }
+                }
+                EntityType::Insert(insert) => {
+                    for attribute in &mut insert.attributes {
+                        if let Some(value) = attribute.common.xdictionary_handle.and_then(|h| refreshed.get(&h)) {
+                            attribute.set_value(value.clone());
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        count
+    }
+
+    /// Fill the viewport overrides of every layer from the XRECORDs in its
+    /// extension dictionary.  Both readers apply this.
+    pub(crate) fn load_layer_overrides(&mut self) {
+        let stored: Vec<(String, Vec<LayerViewportOverride>)> = self
+            .layers
+            .iter()
+            .map(|layer| (layer.name.clone(), self.stored_layer_overrides(layer)))
+            .collect();
+        for (name, overrides) in stored {
+            if let Some(layer) = self.layers.get_mut(&name) {
+                layer.overrides = overrides;
+            }
+        }
+    }
+
+    /// Write the viewport overrides of every layer to the XRECORDs AutoCAD
+    /// keeps them in, adding the layer's extension dictionary when it has
+    /// none and removing the records of properties no longer overridden.
+    ///
+    /// Overrides naming a line type not in the table are left out.  Both
+    /// writers apply this to what they write.
+    pub fn store_layer_overrides(&mut self) {
+        let changed: Vec<String> = self
+            .layers
+            .iter()
+            .filter(|layer| !self.layer_overrides_stored(layer))
+            .map(|layer| layer.name.clone())
+            .collect();
+        for name in changed {
+            let Some(layer) = self.layers.get(&name) else {
+                continue;
+            };
+            let records: Vec<(OverrideKind, Vec<XRecordEntry>)> = OverrideKind::ALL
+                .into_iter()
+                .map(|kind| (kind, kind.entries(&layer.overrides, |name| self.line_types.get(name).map(|t| t.handle))))
+                .collect();
+            let layer_handle = layer.handle;
+            let existing = layer
+                .xdictionary_handle
+                .filter(|h| matches!(self.objects.get(h), Some(ObjectType::Dictionary(_))));
+            let dictionary_handle = match existing {
+                Some(handle) => handle,
+                None if records.iter().all(|(_, entries)| entries.is_empty()) => continue,
+                None => {
+                    let mut dictionary = Dictionary::new();
+                    dictionary.handle = self.allocate_handle();
+                    dictionary.owner = layer_handle;
+                    dictionary.hard_owner = true;
+                    let handle = dictionary.handle;
+                    self.objects.insert(handle, ObjectType::Dictionary(dictionary));
+                    if let Some(layer) = self.layers.get_mut(&name) {
+                        layer.xdictionary_handle = Some(handle);
+                    }
+                    handle
+                }
+            };
+
+            for (kind, entries) in records {
+                let Some(ObjectType::Dictionary(dictionary)) = self.objects.get(&dictionary_handle) else {
+                    break;
+                };
+                let record = dictionary
+                    .get(kind.key())
+                    .filter(|h| matches!(self.objects.get(h), Some(ObjectType::XRecord(_))));
+                match (record, entries.is_empty()) {
+                    (Some(handle), true) => {
+                        self.objects.remove(&handle);
+                        if let Some(ObjectType::Dictionary(dictionary)) = self.objects.get_mut(&dictionary_handle) {
+                            dictionary.entries.retain(|(key, _)| key != kind.key());
+                        }
+                    }
+                    (Some(handle), false) => {
+                        if let Some(ObjectType::XRecord(xrecord)) = self.objects.get_mut(&handle) {
+                            xrecord.entries = entries;
+                        }
+                    }
+                    (None, false) => {
+                        let mut xrecord = XRecord::new();
+                        xrecord.handle = self.allocate_handle();
+                        xrecord.owner = dictionary_handle;
+                        xrecord.cloning_flags = DictionaryCloningFlags::KeepExisting;
+                        xrecord.entries = entries;
+                        let handle = xrecord.handle;
+                        self.objects.insert(handle, ObjectType::XRecord(xrecord));
+                        if let Some(ObjectType::Dictionary(dictionary)) = self.objects.get_mut(&dictionary_handle) {
+                            dictionary.entries.retain(|(key, _)| key != kind.key());
+                            dictionary.add_entry(kind.key(), handle);
+                        }
+                    }
+                    (None, true) => {}
+                }
+            }
+        }
+    }
+
+    /// Viewport overrides kept in the extension dictionary of `layer`
+    fn stored_layer_overrides(&self, layer: &Layer) -> Vec<LayerViewportOverride> {
+        let mut overrides = Vec::new();
+        let dictionary = layer.xdictionary_handle.and_then(|h| self.objects.get(&h));
+        let Some(ObjectType::Dictionary(dictionary)) = dictionary else {
+            return overrides;
+        };
+        let line_type = |handle: Handle| self.line_types.iter().find(|t| t.handle == handle).map(|t| t.name.clone());
+        for kind in OverrideKind::ALL {
+            if let Some(ObjectType::XRecord(xrecord)) = dictionary.get(kind.key()).and_then(|h| self.objects.get(&h)) {
+                kind.apply(xrecord, &mut overrides, line_type);
+            }
+        }
+        overrides
+    }
+
+    /// Whether [`CadDocument::store_layer_overrides`] has nothing to change
+    /// for `layer`
+    fn layer_overrides_stored(&self, layer: &Layer) -> bool {
+        let stored = self.stored_layer_overrides(layer);
+        let mut overrides = layer.overrides.iter().filter(|o| !o.is_empty());
+        overrides.clone().count() == stored.len() && overrides.all(|o| stored.contains(o))
+    }
+
+    /// Decode the entities of every block record that was read lazily.
+    ///
+    /// See [`BlockRecord::load_entities`].
+    pub fn load_blocks(&mut self) -> Result<()> {
+        for record in self.block_records.iter_mut() {
+            record.load_entities()?;
+        }
+        Ok(())
+    }
+
+    /// Check whether any block record still has entities to decode
+    pub fn has_pending_blocks(&self) -> bool {
+        self.block_records.iter().any(|b| !b.is_loaded())
+    }
+
+    /// The document as the writers need it: every block record loaded, the
+    /// mandatory table entries present unless `ensure_defaults` is off,
+    /// the header dimension variables in line with the current dimension
+    /// style, conflicting handles repaired (see [`crate::handles`]), raster
+    /// images and underlays linked to their definitions, tables given
+    /// their blocks and
+    /// layer viewport overrides stored.  Clones only when something has
+    /// to change; the writers take `&self`.
+    pub(crate) fn prepared_for_write(&self, ensure_defaults: bool) -> Result<Cow<'_, CadDocument>> {
+        if !self.has_pending_blocks()
+            && (!ensure_defaults || self.defaults_present())
+            && self.dim_variables_in_sync()
+            && !crate::handles::needs_repair(self)
+            && self.raster_images_linked()
+            && self.underlay_definitions_linked()
+            && self.table_blocks_built()
+            && self.layers.iter().all(|layer| self.layer_overrides_stored(layer))
+        {
+            return Ok(Cow::Borrowed(self));
+        }
+        let mut document = self.clone();
+        document.load_blocks()?;
+        if ensure_defaults {
+            document.ensure_defaults();
+        }
+        document.sync_dim_variables();
+        if crate::handles::needs_repair(&document) {
+            crate::handles::repair(&mut document);
+        }
+        document.link_raster_images()?;
+        document.link_underlay_definitions()?;
+        document.build_table_blocks()?;
+        document.store_layer_overrides();
+        Ok(Cow::Owned(document))
+    }
+
+    /// Add the entries AutoCAD requires of every drawing when the document
+    /// lacks them: layer `0`, the `ByLayer`, `ByBlock` and `Continuous`
+    /// line types, text style `Standard`, application ID `ACAD` and the
+    /// `*Model_Space` and `*Paper_Space` block records.
+    ///
+    /// Entries already present are kept as they are.  Header variables
+    /// pointing to no entry (`$CLAYER`, `$CELTYPE`, `$TEXTSTYLE`, ...) are
+    /// pointed at the new ones, and a new layout block takes over the
+    /// `Model`, or first paper space, layout that has lost its block.  Both
+    /// writers apply this to what they write unless told not to.
+    pub fn ensure_defaults(&mut self) {
+        if let Some(layer) = self.add_default_entry(|doc| &mut doc.layers, Layer::layer_0()) {
+            if !self.layers.iter().any(|l| l.handle == self.header.current_layer_handle) {
+                self.header.current_layer_handle = layer;
+            }
+        }
+
+        let dangling = |doc: &Self, handle: Handle| !doc.line_types.iter().any(|l| l.handle == handle);
+        if let Some(by_layer) = self.add_default_entry(|doc| &mut doc.line_types, LineType::by_layer()) {
+            if dangling(self, self.header.bylayer_linetype_handle) {
+                self.header.bylayer_linetype_handle = by_layer;
+            }
+            if dangling(self, self.header.current_linetype_handle) {
+                self.header.current_linetype_handle = by_layer;
+            }
+        }
+        if let Some(by_block) = self.add_default_entry(|doc| &mut doc.line_types, LineType::by_block()) {
+            if dangling(self, self.header.byblock_linetype_handle) {
+                self.header.byblock_linetype_handle = by_block;
+            }
+        }
+        if let Some(continuous) = self.add_default_entry(|doc| &mut doc.line_types, LineType::continuous()) {
+            if dangling(self, self.header.continuous_linetype_handle) {
+                self.header.continuous_linetype_handle = continuous;
+            }
+        }
+
+        if let Some(standard) = self.add_default_entry(|doc| &mut doc.text_styles, TextStyle::standard()) {
+            if !self.text_styles.iter().any(|s| s.handle == self.header.current_text_style_handle) {
+                self.header.current_text_style_handle = standard;
+            }
+        }
+        self.add_default_entry(|doc| &mut doc.app_ids, AppId::acad());
+
+        for model in [true, false] {
+            let record = if model { BlockRecord::model_space() } else { BlockRecord::paper_space() };
+            let Some(handle) = self.add_default_entry(|doc| &mut doc.block_records, record) else {
+                continue;
+            };
+            let block_entity = self.allocate_handle();
+            let block_end = self.allocate_handle();
+            let records: HashSet<Handle> = self.block_records.iter().map(|r| r.handle).collect();
+            let layout = self
+                .objects
+                .values_mut()
+                .filter_map(|object| match object {
+                    ObjectType::Layout(layout) if !records.contains(&layout.block_record) => Some(layout),
+                    _ => None,
+                })
+                .filter(|layout| (layout.name == "Model") == model)
+                .min_by_key(|layout| layout.tab_order);
+            let layout_handle = layout.map_or(Handle::NULL, |layout| {
+                layout.block_record = handle;
+                layout.handle
+            });
+            let header_handle = if model {
+                &mut self.header.model_space_block_handle
+            } else {
+                &mut self.header.paper_space_block_handle
+            };
+            if !records.contains(header_handle) {
+                *header_handle = handle;
+            }
+            let name = if model { "*Model_Space" } else { "*Paper_Space" };
+            if let Some(record) = self.block_records.get_mut(name) {
+                record.block_entity_handle = block_entity;
+                record.block_end_handle = block_end;
+                record.layout = layout_handle;
+            }
+        }
+    }
+
+    /// Whether [`CadDocument::ensure_defaults`] has nothing to add
+    fn defaults_present(&self) -> bool {
+        self.layers.contains("0")
+            && ["ByLayer", "ByBlock", "Continuous"].iter().all(|name| self.line_types.contains(name))
+            && self.text_styles.contains("Standard")
+            && self.app_ids.contains("ACAD")
+            && self.block_records.contains("*Model_Space")
+            && self.block_records.contains("*Paper_Space")
+    }
+
+    /// Give `entry` a handle and add it to the table `table` selects, unless
+    /// the table has an entry of that name.  Returns the handle given.
+    fn add_default_entry<T: TableEntry>(&mut self, table: fn(&mut Self) -> &mut Table<T>, mut entry: T) -> Option<Handle> {
+        if table(self).contains(entry.name()) {
+            return None;
+        }
+        let handle = self.allocate_handle();
+        entry.set_handle(handle);
+        table(self).add(entry).ok();
+        Some(handle)
+    }
+
+    /// The current dimension style: `$DIMSTYLE` by handle, then by name,
+    /// then the `Standard` style
+    pub fn current_dim_style(&self) -> Option<&DimStyle> {
+        self.referenced_dim_style().or_else(|| self.dim_styles.get("Standard"))
+    }
+
+    fn referenced_dim_style(&self) -> Option<&DimStyle> {
+        let handle = self.header.current_dimstyle_handle;
+        self.dim_styles
+            .iter()
+            .find(|style| !handle.is_null() && style.handle == handle)
+            .or_else(|| self.dim_styles.get(&self.header.current_dimstyle_name))
+    }
+
+    /// Copy the current dimension style into the header `$DIMSTYLE` and
+    /// `$DIM*` variables.
+    ///
+    /// AutoCAD takes the DIMSTYLE table entry as the definition of the
+    /// current settings and header values apart from it as overrides.  A
+    /// variable still holding the value it had when the header was last in
+    /// line with a style, or when the document was created, follows the
+    /// current style; one set otherwise, on the header or in the file read,
+    /// is kept.  Both writers apply this to what they write.  Nothing
+    /// changes when no current style can be found.
+    pub fn sync_dim_variables(&mut self) {
+        let Some(style) = self.current_dim_style().cloned() else {
+            return;
+        };
+        let header = &mut self.header;
+        header.current_dimstyle_handle = style.handle;
+        header.current_dimstyle_name = style.name.clone();
+        style.apply_to_unedited_header(&self.dim_baseline, header);
+
+        // Arrow block names follow the handles when these resolve
+        let arrows = [
+            (header.dim_arrow_block_handle, &mut header.dim_arrow_block),
+            (header.dim_arrow_block1_handle, &mut header.dim_arrow_block1),
+            (header.dim_arrow_block2_handle, &mut header.dim_arrow_block2),
+            (header.dim_leader_block_handle, &mut header.dim_leader_arrow_block),
+        ];
+        for (handle, name) in arrows {
+            if handle.is_null() {
+                continue;
+            }
+            if let Some(record) = self.block_records.iter().find(|b| b.handle == handle) {
+                *name = record.name.clone();
+            }
+        }
+        self.dim_baseline = style;
+    }
+
+    /// Whether [`Self::sync_dim_variables`] would leave the header as it is
+    pub fn dim_variables_in_sync(&self) -> bool {
+        match self.current_dim_style() {
+            Some(style) => {
+                self.header.current_dimstyle_handle == style.handle
+                    && self.header.current_dimstyle_name == style.name
+                    && style.matches_unedited_header(&self.dim_baseline, &self.header)
+            }
+            None => true,
+        }
+    }
+
+    /// Tie `$DIMSTYLE` to the dimension style table after reading.
+    ///
+    /// DXF files name the current style and DWG files point to it by handle;
+    /// both are filled in here.  A current style missing from the table is
+    /// created from the header `$DIM*` variables, its only definition in the
+    /// file.  Header values the file gives apart from the style are kept as
+    /// overrides by [`Self::sync_dim_variables`].
+    pub(crate) fn resolve_dim_style(&mut self) {
+        if let Some((handle, name)) = self.referenced_dim_style().map(|s| (s.handle, s.name.clone())) {
+            self.header.current_dimstyle_handle = handle;
+            self.header.current_dimstyle_name = name;
+        } else if !self.header.current_dimstyle_name.is_empty() {
+            let mut style = DimStyle::from_header(self.header.current_dimstyle_name.clone(), &self.header);
+            // Handles below $HANDSEED may be in use in the file
+            self.next_handle = self.next_handle.max(self.header.handle_seed);
+            style.handle = self.allocate_handle();
+            self.header.current_dimstyle_handle = style.handle;
+            self.dim_styles.add(style).ok();
+        }
+        self.reset_dim_baseline();
+    }
+
+    /// Take the current dimension style as the one the header was last in
+    /// line with
+    fn reset_dim_baseline(&mut self) {
+        if let Some(style) = self.current_dim_style() {
+            self.dim_baseline = style.clone();
+        }
+    }
+
+    /// Resolve owner and handle references after reading a DXF file.
     ///
     /// This performs a simplified version of ACadSharp's two-phase build:
     ///
@@ -1341,6 +2819,11 @@ impl CadDocument {
     ///    values.
     ///
     /// Call this once after loading (the DXF reader calls it automatically).
+    ///
+    /// Layer, linetype, text style, dimension style and block names are not
+    /// checked here: use [`Self::missing_references`] to list the ones that
+    /// do not exist and [`Self::add_missing_references`] to create
+    /// placeholders for them.
     pub fn resolve_references(&mut self) {
         // --- 1. Find the max handle in use across the whole document ---
         let mut max_handle: u64 = self.next_handle;
@@ -1472,10 +2955,176 @@ pub fn get_common_mut(entity: &mut EntityType) -> &mut EntityCommon {
     }
 }
 
+/// Free entry name for `definition` in `dictionary`: AutoCAD names entries
+/// "<file name> - <page>"
+fn underlay_entry_name(dictionary: &crate::objects::Dictionary, definition: &UnderlayDefinition) -> String {
+    let stem = std::path::Path::new(&definition.file_path)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("Underlay");
+    let base = format!("{stem} - {}", definition.page_name);
+    let mut name = base.clone();
+    let mut suffix = 1;
+    while dictionary.get(&name).is_some() {
+        suffix += 1;
+        name = format!("{base} ({suffix})");
+    }
+    name
+}
+
+/// Raw pairs of a FIELD object
+fn field_pairs(object: &ObjectType) -> Option<&[(i32, String)]> {
+    match object {
+        ObjectType::Unknown { type_name, raw_pairs, .. } if type_name == "FIELD" => Some(raw_pairs),
+        _ => None,
+    }
+}
+
+/// Owner (the first code 330 outside a `102` group) of an object kept as
+/// raw pairs
+fn raw_owner(pairs: &[(i32, String)]) -> Option<Handle> {
+    let mut in_group = false;
+    for (code, value) in pairs {
+        match code {
+            102 => in_group = value.starts_with('{'),
+            330 if !in_group => return parse_handle(value),
+            100 => return None,
+            _ => {}
+        }
+    }
+    None
+}
+
+fn parse_handle(value: &str) -> Option<Handle> {
+    u64::from_str_radix(value.trim(), 16).ok().map(Handle::new)
+}
+
 impl Default for CadDocument {
     fn default() -> Self {
         Self::new()
     }
 }
 
+/// Version of the serialized document layout
+///
+/// A serialized document is a struct with a `schema_version` field followed
+/// by the public tables, `entities` and `objects` as maps from hex handle
+/// strings to the externally tagged entity/object (`{"LINE_HANDLE":
+/// {"Line": {...}}}`, sorted by handle) and `next_handle`. Handles are hex
+/// strings throughout. Notifications are not serialized; lazily read blocks
+/// are decoded into the output. The version is bumped whenever a change
+/// would stop older documents from deserializing.
+#[cfg(feature = "serde")]
+pub const SERDE_SCHEMA_VERSION: u32 = 1;
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use std::collections::{BTreeMap, HashMap};
+
+    use serde::ser::SerializeStruct;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::*;
+
+    /// Map serialized in handle order, for stable output
+    struct Sorted<'a, V>(&'a HashMap<Handle, V>);
+
+    impl<V: Serialize> Serialize for Sorted<'_, V> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+            let mut items: Vec<_> = self.0.iter().collect();
+            items.sort_by_key(|(handle, _)| **handle);
+            serializer.collect_map(items)
+        }
+    }
 
+    impl Serialize for CadDocument {
+        fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+            // Blocks read lazily are decoded in a copy, as the writers do
+            let loaded;
+            let document = if self.has_pending_blocks() {
+                let mut copy = self.clone();
+                copy.load_blocks().map_err(serde::ser::Error::custom)?;
+                loaded = copy;
+                &loaded
+            } else {
+                self
+            };
+
+            let mut s = serializer.serialize_struct("CadDocument", 17)?;
+            s.serialize_field("schema_version", &SERDE_SCHEMA_VERSION)?;
+            s.serialize_field("version", &document.version)?;
+            s.serialize_field("header", &document.header)?;
+            s.serialize_field("layers", &document.layers)?;
+            s.serialize_field("line_types", &document.line_types)?;
+            s.serialize_field("text_styles", &document.text_styles)?;
+            s.serialize_field("block_records", &document.block_records)?;
+            s.serialize_field("dim_styles", &document.dim_styles)?;
+            s.serialize_field("app_ids", &document.app_ids)?;
+            s.serialize_field("views", &document.views)?;
+            s.serialize_field("vports", &document.vports)?;
+            s.serialize_field("ucss", &document.ucss)?;
+            s.serialize_field("classes", &document.classes)?;
+            s.serialize_field("preview", &document.preview)?;
+            s.serialize_field("entities", &Sorted(&document.entities))?;
+            s.serialize_field("objects", &Sorted(&document.objects))?;
+            s.serialize_field("next_handle", &document.next_handle)?;
+            s.end()
+        }
+    }
+
+    #[derive(Deserialize)]
+    struct DocumentData {
+        schema_version: u32,
+        version: DxfVersion,
+        header: HeaderVariables,
+        layers: Table<Layer>,
+        line_types: Table<LineType>,
+        text_styles: Table<TextStyle>,
+        block_records: Table<BlockRecord>,
+        dim_styles: Table<DimStyle>,
+        app_ids: Table<AppId>,
+        views: Table<View>,
+        vports: Table<VPort>,
+        ucss: Table<Ucs>,
+        classes: DxfClassCollection,
+        #[serde(default)]
+        preview: Option<crate::preview::DwgPreview>,
+        entities: BTreeMap<Handle, EntityType>,
+        objects: BTreeMap<Handle, ObjectType>,
+        next_handle: u64,
+    }
+
+    impl<'de> Deserialize<'de> for CadDocument {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+            let data = DocumentData::deserialize(deserializer)?;
+            if data.schema_version > SERDE_SCHEMA_VERSION {
+                return Err(serde::de::Error::custom(format!(
+                    "document schema version {} is newer than the supported version {}",
+                    data.schema_version, SERDE_SCHEMA_VERSION
+                )));
+            }
+            let mut document = CadDocument {
+                version: data.version,
+                header: data.header,
+                layers: data.layers,
+                line_types: data.line_types,
+                text_styles: data.text_styles,
+                block_records: data.block_records,
+                dim_styles: data.dim_styles,
+                app_ids: data.app_ids,
+                views: data.views,
+                vports: data.vports,
+                ucss: data.ucss,
+                classes: data.classes,
+                preview: data.preview,
+                notifications: Default::default(),
+                entities: data.entities.into_iter().collect(),
+                objects: data.objects.into_iter().collect(),
+                next_handle: data.next_handle,
+                dim_baseline: DimStyle::standard(),
+            };
+            document.reset_dim_baseline();
+            Ok(document)
+        }
+    }
+}
@@ -1,10 +1,11 @@
 //! Arc entity
 
 use super::{Entity, EntityCommon};
-use crate::types::{BoundingBox3D, Color, Handle, LineWeight, Transparency, Vector3};
+use crate::types::{Angle, BoundingBox3D, Color, Handle, LineWeight, Transparency, Vector3};
 
 /// An arc entity (portion of a circle)
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Arc {
     /// Common entity data
     pub common: EntityCommon,
@@ -64,6 +65,26 @@ impl Arc {
         Arc::from_center_radius_angles(Vector3::new(x, y, z), radius, start_angle, end_angle)
     }
 
+    /// Start angle
+    pub fn start_angle(&self) -> Angle {
+        Angle::from_radians(self.start_angle)
+    }
+
+    /// Set the start angle
+    pub fn set_start_angle(&mut self, angle: Angle) {
+        self.start_angle = angle.radians();
+    }
+
+    /// End angle
+    pub fn end_angle(&self) -> Angle {
+        Angle::from_radians(self.end_angle)
+    }
+
+    /// Set the end angle
+    pub fn set_end_angle(&mut self, angle: Angle) {
+        self.end_angle = angle.radians();
+    }
+
     /// Get the sweep angle (angular extent) in radians
     pub fn sweep_angle(&self) -> f64 {
         let mut sweep = self.end_angle - self.start_angle;
@@ -1,11 +1,12 @@
 //! AttributeDefinition entity - Block attribute template
 
 use crate::entities::{Entity, EntityCommon};
-use crate::types::{BoundingBox3D, Color, Handle, LineWeight, Transparency, Vector3};
+use crate::types::{Angle, BoundingBox3D, Color, Handle, LineWeight, Transparency, Vector3};
 use std::f64::consts::PI;
 
 /// Attribute flags
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AttributeFlags {
     /// Attribute is invisible
     pub invisible: bool,
@@ -49,6 +50,7 @@ impl AttributeFlags {
 
 /// Text horizontal alignment
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum HorizontalAlignment {
     /// Left alignment
     #[default]
@@ -86,6 +88,7 @@ impl HorizontalAlignment {
 
 /// Text vertical alignment
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum VerticalAlignment {
     /// Baseline alignment
     #[default]
@@ -117,6 +120,7 @@ impl VerticalAlignment {
 
 /// Multiline attribute type
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MTextFlag {
     /// Single-line attribute
     #[default]
@@ -167,6 +171,7 @@ impl MTextFlag {
 /// attdef.height = 2.5;
 /// ```
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AttributeDefinition {
     /// Common entity properties
     pub common: EntityCommon,
@@ -283,6 +288,26 @@ impl AttributeDefinition {
         self.rotation * 180.0 / PI
     }
 
+    /// Rotation angle
+    pub fn rotation(&self) -> Angle {
+        Angle::from_radians(self.rotation)
+    }
+
+    /// Set the rotation angle
+    pub fn set_rotation(&mut self, angle: Angle) {
+        self.rotation = angle.radians();
+    }
+
+    /// Oblique angle
+    pub fn oblique_angle(&self) -> Angle {
+        Angle::from_radians(self.oblique_angle)
+    }
+
+    /// Set the oblique angle
+    pub fn set_oblique_angle(&mut self, angle: Angle) {
+        self.oblique_angle = angle.radians();
+    }
+
     /// Set the text style
     pub fn set_text_style(&mut self, style: impl Into<String>) {
         self.text_style = style.into();
@@ -4,7 +4,7 @@ use crate::entities::{Entity, EntityCommon};
 use crate::entities::attribute_definition::{
     AttributeFlags, HorizontalAlignment, VerticalAlignment, MTextFlag
 };
-use crate::types::{BoundingBox3D, Color, Handle, LineWeight, Transparency, Vector3};
+use crate::types::{Angle, BoundingBox3D, Color, Handle, LineWeight, Transparency, Vector3};
 use std::f64::consts::PI;
 
 /// Attribute entity - contains the actual value for a block attribute
@@ -29,6 +29,7 @@ use std::f64::consts::PI;
 /// attrib.height = 2.5;
 /// ```
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AttributeEntity {
     /// Common entity properties
     pub common: EntityCommon,
@@ -172,6 +173,26 @@ impl AttributeEntity {
         self.rotation * 180.0 / PI
     }
 
+    /// Rotation angle
+    pub fn rotation(&self) -> Angle {
+        Angle::from_radians(self.rotation)
+    }
+
+    /// Set the rotation angle
+    pub fn set_rotation(&mut self, angle: Angle) {
+        self.rotation = angle.radians();
+    }
+
+    /// Oblique angle
+    pub fn oblique_angle(&self) -> Angle {
+        Angle::from_radians(self.oblique_angle)
+    }
+
+    /// Set the oblique angle
+    pub fn set_oblique_angle(&mut self, angle: Angle) {
+        self.oblique_angle = angle.radians();
+    }
+
     /// Set the text style
     pub fn set_text_style(&mut self, style: impl Into<String>) {
         self.text_style = style.into();
@@ -1,10 +1,12 @@
 //! Dimension entity types
 
 use crate::entities::EntityCommon;
+use crate::tables::DimStyle;
 use crate::types::Vector3;
 
 /// Dimension type flags
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DimensionType {
     /// Rotated, horizontal, or vertical linear dimension
     Linear = 0,
@@ -24,6 +26,7 @@ pub enum DimensionType {
 
 /// Attachment point type for dimension text
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum AttachmentPointType {
     TopLeft = 1,
     TopCenter = 2,
@@ -36,11 +39,29 @@ pub enum AttachmentPointType {
     BottomRight = 9,
 }
 
+impl AttachmentPointType {
+    /// Create from DXF value (group code 71)
+    pub fn from_value(value: i16) -> Self {
+        match value {
+            1 => AttachmentPointType::TopLeft,
+            2 => AttachmentPointType::TopCenter,
+            3 => AttachmentPointType::TopRight,
+            4 => AttachmentPointType::MiddleLeft,
+            6 => AttachmentPointType::MiddleRight,
+            7 => AttachmentPointType::BottomLeft,
+            8 => AttachmentPointType::BottomCenter,
+            9 => AttachmentPointType::BottomRight,
+            _ => AttachmentPointType::MiddleCenter,
+        }
+    }
+}
+
 /// Base dimension entity
 /// 
 /// All dimension types share common properties and behavior.
 /// Specific dimension types extend this base with additional properties.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DimensionBase {
     pub common: EntityCommon,
     /// Definition point for the dimension line (in WCS)
@@ -135,6 +156,7 @@ impl Default for DimensionBase {
 /// 
 /// Measures the distance between two points along a line parallel to those points.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DimensionAligned {
     pub base: DimensionBase,
     /// First definition point (in WCS)
@@ -199,6 +221,7 @@ impl Default for DimensionAligned {
 /// Measures the horizontal or vertical distance between two points,
 /// or the distance along a rotated axis.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DimensionLinear {
     pub base: DimensionBase,
     /// First definition point (in WCS)
@@ -281,6 +304,7 @@ impl Default for DimensionLinear {
 ///
 /// Measures the radius of a circle or arc.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DimensionRadius {
     pub base: DimensionBase,
     /// Definition point (point on arc/circle) - in WCS
@@ -326,6 +350,7 @@ impl Default for DimensionRadius {
 ///
 /// Measures the diameter of a circle or arc.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DimensionDiameter {
     pub base: DimensionBase,
     /// Definition point (opposite side of diameter) - in WCS
@@ -376,6 +401,7 @@ impl Default for DimensionDiameter {
 ///
 /// Measures the angle between two lines.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DimensionAngular2Ln {
     pub base: DimensionBase,
     /// Arc definition point (dimension arc location) - in WCS
@@ -444,6 +470,7 @@ pub type DimensionAngular2Line = DimensionAngular2Ln;
 ///
 /// Measures the angle defined by three points.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DimensionAngular3Pt {
     pub base: DimensionBase,
     /// Definition point (arc location) - in WCS
@@ -508,6 +535,7 @@ pub type DimensionAngular3Point = DimensionAngular3Pt;
 ///
 /// Measures the X or Y ordinate of a point.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DimensionOrdinate {
     pub base: DimensionBase,
     /// Definition point (origin) - in WCS
@@ -573,6 +601,7 @@ impl Default for DimensionOrdinate {
 
 /// Unified dimension enum for all dimension types
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Dimension {
     Aligned(DimensionAligned),
     Linear(DimensionLinear),
@@ -622,6 +651,30 @@ impl Dimension {
             Dimension::Ordinate(d) => d.measurement(),
         }
     }
+
+    /// The measurement as AutoCAD displays it with `style`: angles per the
+    /// angular unit settings, lengths with the `DIMPOST` affixes, alternate
+    /// units and the `R` or `%%c` prefix of radius and diameter dimensions
+    pub fn formatted_measurement(&self, style: &DimStyle) -> String {
+        let value = self.measurement();
+        match self {
+            Dimension::Angular2Ln(_) | Dimension::Angular3Pt(_) => style.format_angular(value),
+            Dimension::Radius(_) => style.format_dimension_text(value, "R"),
+            Dimension::Diameter(_) => style.format_dimension_text(value, "%%c"),
+            _ => style.format_dimension_text(value, ""),
+        }
+    }
+
+    /// The text shown on the dimension: the formatted measurement, or the
+    /// text override with `<>` standing for the measurement. A single space
+    /// suppresses the text.
+    pub fn display_text(&self, style: &DimStyle) -> String {
+        match self.base().text.as_str() {
+            "" => self.formatted_measurement(style),
+            " " => String::new(),
+            text => text.replace("<>", &self.formatted_measurement(style)),
+        }
+    }
 }
 
 impl super::Entity for Dimension {
@@ -0,0 +1,239 @@
+//! Re-measuring associative dimensions from their geometry
+//!
+//! The DIMASSOC of a dimension names, for each definition point, the
+//! geometry and object snap the point was placed with. Reassociating snaps
+//! the points again on the geometry as it is now:
+//!
+//! - linear and aligned: points 1 and 2 are the extension line origins
+//! - angular 3-point: points 1 and 2 are on the legs, point 3 the vertex
+//! - angular 2-line: points 1 and 2 are the two lines
+//! - radius and diameter: point 1 is the circle or arc
+//! - ordinate: point 1 is the feature location
+
+use super::dimension::Dimension;
+use super::EntityType;
+use crate::document::CadDocument;
+use crate::objects::{ObjectSnapType, OsnapPointRef};
+use crate::types::Vector3;
+
+impl Dimension {
+    /// Moves the definition points of an associative dimension onto its
+    /// linked geometry and updates the measurement.
+    ///
+    /// Returns `false`, leaving the dimension untouched, when it has no
+    /// DIMASSOC in `document` or none of its linked geometry is found.
+    pub fn reassociate(&mut self, document: &CadDocument) -> bool {
+        let Some(association) = document.dimension_association(self.base().common.handle) else {
+            return false;
+        };
+        let geometry = |index: usize| {
+            let reference = association.points[index].as_ref()?;
+            Some((document.get_entity(reference.object()?)?, reference))
+        };
+        let snap = |index: usize| geometry(index).and_then(|(entity, reference)| snap_point(entity, reference));
+
+        let mut moved = false;
+        let mut update = |target: &mut Vector3, point: Option<Vector3>| {
+            if let Some(point) = point {
+                *target = point;
+                moved = true;
+            }
+        };
+        match self {
+            Dimension::Linear(d) => {
+                update(&mut d.first_point, snap(0));
+                update(&mut d.second_point, snap(1));
+            }
+            Dimension::Aligned(d) => {
+                let offset = signed_offset(d.first_point, d.second_point, d.definition_point);
+                update(&mut d.first_point, snap(0));
+                update(&mut d.second_point, snap(1));
+                if d.first_point.distance(&d.second_point) > 0.0 {
+                    d.set_offset(offset);
+                }
+            }
+            Dimension::Angular3Pt(d) => {
+                update(&mut d.first_point, snap(0));
+                update(&mut d.second_point, snap(1));
+                update(&mut d.angle_vertex, snap(2));
+            }
+            Dimension::Angular2Ln(d) => {
+                if let Some((EntityType::Line(line), _)) = geometry(0) {
+                    update(&mut d.first_point, Some(line.start));
+                    update(&mut d.second_point, Some(line.end));
+                }
+                if let Some((EntityType::Line(line), _)) = geometry(1) {
+                    update(&mut d.angle_vertex, Some(line.start));
+                    update(&mut d.definition_point, Some(line.end));
+                }
+            }
+            Dimension::Radius(d) => {
+                if let Some((center, radius)) = geometry(0).and_then(|(entity, _)| circle_of(entity)) {
+                    let direction = direction_or_x(d.definition_point - d.angle_vertex);
+                    update(&mut d.angle_vertex, Some(center));
+                    update(&mut d.definition_point, Some(center + direction * radius));
+                }
+            }
+            Dimension::Diameter(d) => {
+                if let Some((center, radius)) = geometry(0).and_then(|(entity, _)| circle_of(entity)) {
+                    let direction = direction_or_x(d.definition_point - d.angle_vertex);
+                    update(&mut d.angle_vertex, Some(center - direction * radius));
+                    update(&mut d.definition_point, Some(center + direction * radius));
+                }
+            }
+            Dimension::Ordinate(d) => update(&mut d.feature_location, snap(0)),
+        }
+
+        if moved {
+            let measurement = self.measurement();
+            self.base_mut().actual_measurement = measurement;
+        }
+        moved
+    }
+}
+
+/// The point `reference` snaps to on `entity`, for the snap modes that
+/// follow from the geometry alone
+fn snap_point(entity: &EntityType, reference: &OsnapPointRef) -> Option<Vector3> {
+    let nearest = |candidates: &[Vector3]| {
+        candidates
+            .iter()
+            .copied()
+            .min_by(|a, b| a.distance(&reference.point).total_cmp(&b.distance(&reference.point)))
+    };
+    let on_circle = |center: Vector3, radius: f64, angle: f64| {
+        center + Vector3::new(angle.cos(), angle.sin(), 0.0) * radius
+    };
+    let quadrants = |center: Vector3, radius: f64| {
+        [0.0, 1.0, 2.0, 3.0].map(|quarter: f64| on_circle(center, radius, quarter * std::f64::consts::FRAC_PI_2))
+    };
+
+    match (reference.osnap_type, entity) {
+        (ObjectSnapType::Endpoint, EntityType::Line(line)) => nearest(&[line.start, line.end]),
+        (ObjectSnapType::Endpoint, EntityType::Arc(arc)) => nearest(&[arc.start_point(), arc.end_point()]),
+        (ObjectSnapType::Endpoint, EntityType::LwPolyline(polyline)) => {
+            let vertices: Vec<Vector3> = polyline
+                .vertices
+                .iter()
+                .map(|v| Vector3::new(v.location.x, v.location.y, polyline.elevation))
+                .collect();
+            nearest(&vertices)
+        }
+        (ObjectSnapType::StartPoint, EntityType::Line(line)) => Some(line.start),
+        (ObjectSnapType::StartPoint, EntityType::Arc(arc)) => Some(arc.start_point()),
+        (ObjectSnapType::Midpoint, EntityType::Line(line)) => Some(line.midpoint()),
+        (ObjectSnapType::Midpoint, EntityType::Arc(arc)) => Some(arc.midpoint()),
+        (ObjectSnapType::Center, entity) => circle_of(entity).map(|(center, _)| center).or(match entity {
+            EntityType::Ellipse(ellipse) => Some(ellipse.center),
+            _ => None,
+        }),
+        (ObjectSnapType::Node, EntityType::Point(point)) => Some(point.location),
+        (ObjectSnapType::Quadrant, entity) => {
+            let (center, radius) = circle_of(entity)?;
+            nearest(&quadrants(center, radius))
+        }
+        (ObjectSnapType::Insertion, EntityType::Insert(insert)) => Some(insert.insert_point),
+        (ObjectSnapType::Insertion, EntityType::Text(text)) => Some(text.insertion_point),
+        (ObjectSnapType::Insertion, EntityType::MText(mtext)) => Some(mtext.insertion_point),
+        // Curve parameters: the distance from the start of a line, the
+        // angle on a circle or arc
+        (ObjectSnapType::Nearest, EntityType::Line(line)) => {
+            Some(line.start + line.direction() * reference.near_parameter)
+        }
+        (ObjectSnapType::Nearest, entity) => {
+            let (center, radius) = circle_of(entity)?;
+            Some(on_circle(center, radius, reference.near_parameter))
+        }
+        _ => None,
+    }
+}
+
+/// Center and radius of a circle or arc
+fn circle_of(entity: &EntityType) -> Option<(Vector3, f64)> {
+    match entity {
+        EntityType::Circle(circle) => Some((circle.center, circle.radius)),
+        EntityType::Arc(arc) => Some((arc.center, arc.radius)),
+        _ => None,
+    }
+}
+
+/// Distance of `definition` from the line through `first` and `second`,
+/// positive on its left
+fn signed_offset(first: Vector3, second: Vector3, definition: Vector3) -> f64 {
+    let direction = second - first;
+    let left = Vector3::new(-direction.y, direction.x, 0.0).normalize();
+    (definition - second).dot(&left)
+}
+
+fn direction_or_x(vector: Vector3) -> Vector3 {
+    if vector.length() > 0.0 { vector.normalize() } else { Vector3::UNIT_X }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::{Circle, DimensionAligned, DimensionRadius, Line};
+    use crate::objects::{DimAssoc, OsnapPointRef};
+    use crate::types::Handle;
+
+    fn add(document: &mut CadDocument, entity: EntityType) -> Handle {
+        document.add_entity(entity).unwrap()
+    }
+
+    #[test]
+    fn test_reassociate_aligned_dimension() {
+        let mut document = CadDocument::new();
+        let line = add(
+            &mut document,
+            EntityType::Line(Line::from_points(Vector3::ZERO, Vector3::new(10.0, 0.0, 0.0))),
+        );
+        let mut aligned = DimensionAligned::new(Vector3::ZERO, Vector3::new(10.0, 0.0, 0.0));
+        aligned.set_offset(2.0);
+        let dimension = add(&mut document, EntityType::Dimension(Box::new(Dimension::Aligned(aligned))));
+        let association = DimAssoc::new(dimension)
+            .with_point(0, OsnapPointRef::new(ObjectSnapType::Endpoint, line, Vector3::ZERO))
+            .with_point(1, OsnapPointRef::new(ObjectSnapType::Endpoint, line, Vector3::new(10.0, 0.0, 0.0)));
+        let handle = document.associate_dimension(dimension, association).unwrap();
+        assert_eq!(document.get_entity(line).unwrap().common().reactors, vec![handle]);
+
+        if let Some(EntityType::Line(line)) = document.get_entity_mut(line) {
+            line.start = Vector3::new(1.0, 1.0, 0.0);
+            line.end = Vector3::new(9.0, 7.0, 0.0);
+        }
+        let Some(EntityType::Dimension(mut dim)) = document.get_entity(dimension).cloned() else {
+            panic!("dimension missing");
+        };
+        assert!(dim.reassociate(&document));
+        let Dimension::Aligned(aligned) = &*dim else { unreachable!() };
+        assert_eq!(aligned.first_point, Vector3::new(1.0, 1.0, 0.0));
+        assert_eq!(aligned.second_point, Vector3::new(9.0, 7.0, 0.0));
+        assert!((aligned.base.actual_measurement - 10.0).abs() < 1e-12);
+        assert!((aligned.offset() - 2.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_reassociate_radius_dimension() {
+        let mut document = CadDocument::new();
+        let circle = add(&mut document, EntityType::Circle(Circle::from_center_radius(Vector3::ZERO, 1.0)));
+        let radius = DimensionRadius::new(Vector3::ZERO, Vector3::new(0.0, 1.0, 0.0));
+        let dimension = add(&mut document, EntityType::Dimension(Box::new(Dimension::Radius(radius))));
+        let reference = OsnapPointRef::new(ObjectSnapType::Nearest, circle, Vector3::new(0.0, 1.0, 0.0));
+        document.associate_dimension(dimension, DimAssoc::new(dimension).with_point(0, reference)).unwrap();
+
+        if let Some(EntityType::Circle(circle)) = document.get_entity_mut(circle) {
+            circle.center = Vector3::new(5.0, 5.0, 0.0);
+            circle.radius = 3.0;
+        }
+        let Some(EntityType::Dimension(mut dim)) = document.get_entity(dimension).cloned() else {
+            panic!("dimension missing");
+        };
+        assert!(dim.reassociate(&document));
+        let Dimension::Radius(radius) = &*dim else { unreachable!() };
+        assert_eq!(radius.angle_vertex, Vector3::new(5.0, 5.0, 0.0));
+        assert_eq!(radius.definition_point, Vector3::new(5.0, 8.0, 0.0));
+        assert_eq!(dim.base().actual_measurement, 3.0);
+
+        let mut unlinked = Dimension::Radius(DimensionRadius::new(Vector3::ZERO, Vector3::UNIT_X));
+        assert!(!unlinked.reassociate(&document));
+    }
+}
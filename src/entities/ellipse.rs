@@ -1,10 +1,15 @@
 //! Ellipse entity
 
-use super::{Entity, EntityCommon};
-use crate::types::{BoundingBox3D, Color, Handle, LineWeight, Transparency, Vector3};
+use std::f64::consts::TAU;
+
+use super::{Arc, Circle, Entity, EntityCommon, EntityType, LwPolyline, LwVertex};
+use crate::types::geometry::ocs_matrix;
+use crate::render::tessellate::ellipse_points;
+use crate::types::{Angle, BoundingBox3D, Color, Handle, LineWeight, Transparency, Vector2, Vector3};
 
 /// An ellipse entity
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Ellipse {
     /// Common entity data
     pub common: EntityCommon,
@@ -14,9 +19,10 @@ pub struct Ellipse {
     pub major_axis: Vector3,
     /// Ratio of minor axis to major axis
     pub minor_axis_ratio: f64,
-    /// Start parameter (0 = start of ellipse)
+    /// Start parameter in radians (0 = start of ellipse); not the angle
+    /// of the start point, see [`Ellipse::angle_at_parameter`]
     pub start_parameter: f64,
-    /// End parameter (2π = full ellipse)
+    /// End parameter in radians (2π = full ellipse)
     pub end_parameter: f64,
     /// Normal vector
     pub normal: Vector3,
@@ -56,10 +62,191 @@ impl Ellipse {
         self.major_axis_length() * self.minor_axis_ratio
     }
 
+    /// Start parameter
+    pub fn start_parameter(&self) -> Angle {
+        Angle::from_radians(self.start_parameter)
+    }
+
+    /// Set the start parameter
+    pub fn set_start_parameter(&mut self, parameter: Angle) {
+        self.start_parameter = parameter.radians();
+    }
+
+    /// End parameter
+    pub fn end_parameter(&self) -> Angle {
+        Angle::from_radians(self.end_parameter)
+    }
+
+    /// Set the end parameter
+    pub fn set_end_parameter(&mut self, parameter: Angle) {
+        self.end_parameter = parameter.radians();
+    }
+
+    /// Parameter of the point seen at `angle` from the center, measured
+    /// from the major axis
+    pub fn parameter_at_angle(&self, angle: Angle) -> Angle {
+        // x = a·cos t, y = b·sin t, so tan θ = (b/a)·tan t
+        let parameter = (angle.sin() / self.minor_axis_ratio).atan2(angle.cos());
+        Angle::from_radians(parameter).normalized()
+    }
+
+    /// Angle from the major axis of the point at `parameter`
+    pub fn angle_at_parameter(&self, parameter: Angle) -> Angle {
+        let angle = (self.minor_axis_ratio * parameter.sin()).atan2(parameter.cos());
+        Angle::from_radians(angle).normalized()
+    }
+
     /// Check if this is a full ellipse
     pub fn is_full(&self) -> bool {
         (self.end_parameter - self.start_parameter - 2.0 * std::f64::consts::PI).abs() < 1e-10
     }
+
+    /// Length of the elliptical arc from the start to the end parameter
+    pub fn length(&self) -> f64 {
+        let (a, b) = (self.major_axis_length(), self.minor_axis_length());
+        let mut sweep = (self.end_parameter - self.start_parameter).rem_euclid(2.0 * std::f64::consts::PI);
+        if sweep < 1e-12 {
+            sweep = 2.0 * std::f64::consts::PI;
+        }
+        // Composite Simpson's rule over the speed along the parameter
+        const STEPS: usize = 512;
+        let speed = |t: f64| (a * t.sin()).hypot(b * t.cos());
+        let h = sweep / STEPS as f64;
+        let sum: f64 = (0..=STEPS)
+            .map(|i| {
+                let weight = if i == 0 || i == STEPS { 1.0 } else if i % 2 == 1 { 4.0 } else { 2.0 };
+                weight * speed(self.start_parameter + h * i as f64)
+            })
+            .sum();
+        sum * h / 3.0
+    }
+
+    /// Area of the full ellipse
+    pub fn area(&self) -> f64 {
+        std::f64::consts::PI * self.major_axis_length() * self.minor_axis_length()
+    }
+
+    /// Ellipse traced by an arc scaled by `x_scale` and `y_scale` along the
+    /// axes of its OCS, as an arc in a block inserted with unequal scales.
+    ///
+    /// The scaling is about the OCS origin and negative scales mirror the
+    /// arc. For a circle, pass an arc from 0 to 2π.
+    pub fn from_arc_scaled(arc: &Arc, x_scale: f64, y_scale: f64) -> Self {
+        let (rx, ry) = ((arc.radius * x_scale).abs(), (arc.radius * y_scale).abs());
+        let (major, minor) = if rx >= ry {
+            (Vector3::new(rx, 0.0, 0.0), Vector3::new(0.0, ry, 0.0))
+        } else {
+            (Vector3::new(0.0, ry, 0.0), Vector3::new(-rx, 0.0, 0.0))
+        };
+        // Parameter of the image of the arc point at `angle`
+        let parameter = |angle: f64| {
+            let p = Vector3::new(x_scale * angle.cos(), y_scale * angle.sin(), 0.0) * arc.radius;
+            let (a, b) = (major.length(), minor.length());
+            let (cos, sin) = (p.dot(&major) / (a * a), if b > 0.0 { p.dot(&minor) / (b * b) } else { 0.0 });
+            sin.atan2(cos).rem_euclid(TAU)
+        };
+        let (mut start, mut end) = if arc.sweep_angle() >= TAU - 1e-9 {
+            (0.0, TAU)
+        } else {
+            (parameter(arc.start_angle), parameter(arc.end_angle))
+        };
+        if x_scale * y_scale < 0.0 {
+            std::mem::swap(&mut start, &mut end);
+        }
+        if end <= start {
+            end += TAU;
+        }
+
+        let ocs = ocs_matrix(arc.normal);
+        let center = Vector3::new(arc.center.x * x_scale, arc.center.y * y_scale, arc.center.z);
+        Ellipse {
+            common: arc.common.clone(),
+            center: ocs.transform_point(center),
+            major_axis: ocs.transform_point(major),
+            minor_axis_ratio: if rx.max(ry) > 0.0 { rx.min(ry) / rx.max(ry) } else { 1.0 },
+            start_parameter: start,
+            end_parameter: end,
+            normal: arc.normal,
+        }
+    }
+
+    /// Check if the minor axis is within `tolerance` of the major one, so
+    /// the ellipse can be written as a circle or an arc
+    pub fn is_circular(&self, tolerance: f64) -> bool {
+        self.major_axis_length() - self.minor_axis_length().abs() <= tolerance
+    }
+
+    /// The circle or arc a circular ellipse traces, for formats and versions
+    /// without ELLIPSE; `None` if the ellipse is not circular within
+    /// `tolerance`.
+    ///
+    /// The result is in the OCS of the ellipse normal and keeps the common
+    /// entity data, handle included, so it can replace the ellipse.
+    pub fn to_arc_or_circle(&self, tolerance: f64) -> Option<EntityType> {
+        if !self.is_circular(tolerance) {
+            return None;
+        }
+        let to_ocs = ocs_matrix(self.normal).transpose();
+        let center = to_ocs.transform_point(self.center);
+        let radius = (self.major_axis_length() + self.minor_axis_length().abs()) / 2.0;
+        if self.is_full() {
+            return Some(EntityType::Circle(Circle {
+                common: self.common.clone(),
+                center,
+                radius,
+                thickness: 0.0,
+                normal: self.normal,
+            }));
+        }
+        let major = to_ocs.transform_point(self.major_axis);
+        let rotation = major.y.atan2(major.x);
+        // A negative ratio runs the ellipse clockwise in its plane
+        let (start, end) = if self.minor_axis_ratio < 0.0 {
+            (rotation - self.end_parameter, rotation - self.start_parameter)
+        } else {
+            (rotation + self.start_parameter, rotation + self.end_parameter)
+        };
+        Some(EntityType::Arc(Arc {
+            common: self.common.clone(),
+            center,
+            radius,
+            start_angle: start.rem_euclid(TAU),
+            end_angle: end.rem_euclid(TAU),
+            thickness: 0.0,
+            normal: self.normal,
+        }))
+    }
+
+    /// Approximate the ellipse with a polyline of straight segments that
+    /// stays within `tolerance` of it, closed for a full ellipse.
+    ///
+    /// The polyline is in the OCS of the ellipse normal and keeps the
+    /// common entity data, handle included, so it can replace the ellipse.
+    pub fn to_polyline(&self, tolerance: f64) -> LwPolyline {
+        let mut points = ellipse_points(
+            self.center,
+            self.major_axis,
+            self.normal,
+            self.minor_axis_ratio,
+            self.start_parameter,
+            self.end_parameter,
+            tolerance,
+        );
+        let closed = self.is_full();
+        if closed {
+            points.pop();
+        }
+        let to_ocs = ocs_matrix(self.normal).transpose();
+        let points: Vec<Vector3> = points.into_iter().map(|p| to_ocs.transform_point(p)).collect();
+        LwPolyline {
+            common: self.common.clone(),
+            vertices: points.iter().map(|p| LwVertex::new(Vector2::new(p.x, p.y))).collect(),
+            is_closed: closed,
+            elevation: points.first().map_or(0.0, |p| p.z),
+            normal: self.normal,
+            ..LwPolyline::new()
+        }
+    }
 }
 
 impl Default for Ellipse {
@@ -180,6 +367,100 @@ mod tests {
         assert_eq!(ellipse.major_axis_length(), 10.0);
         assert_eq!(ellipse.minor_axis_length(), 5.0);
     }
+
+    #[test]
+    fn test_ellipse_length_and_area() {
+        let mut ellipse = Ellipse::from_center_axes(Vector3::ZERO, Vector3::new(2.0, 0.0, 0.0), 1.0);
+        assert!((ellipse.length() - 4.0 * std::f64::consts::PI).abs() < 1e-9);
+        ellipse.minor_axis_ratio = 0.5;
+        // Ramanujan's second approximation, well within the tolerance here
+        let (a, b) = (2.0, 1.0);
+        let h = ((a - b) / (a + b)) * ((a - b) / (a + b));
+        let perimeter = std::f64::consts::PI * (a + b) * (1.0 + 3.0 * h / (10.0 + (4.0 - 3.0 * h).sqrt()));
+        assert!((ellipse.length() - perimeter).abs() < 1e-6);
+        assert!((ellipse.area() - 2.0 * std::f64::consts::PI).abs() < 1e-12);
+        ellipse.end_parameter = std::f64::consts::PI;
+        assert!((ellipse.length() - perimeter / 2.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_from_arc_scaled() {
+        use std::f64::consts::FRAC_PI_2;
+        let arc = Arc::from_coords(1.0, 0.0, 0.0, 1.0, 0.0, FRAC_PI_2);
+        let ellipse = Ellipse::from_arc_scaled(&arc, 1.0, 3.0);
+        assert_eq!(ellipse.center, Vector3::new(1.0, 0.0, 0.0));
+        assert_eq!(ellipse.major_axis, Vector3::new(0.0, 3.0, 0.0));
+        assert!((ellipse.minor_axis_ratio - 1.0 / 3.0).abs() < 1e-12);
+        let points = ellipse.to_polyline(1e-3);
+        let (first, last) = (points.vertices[0].location, points.vertices.last().unwrap().location);
+        assert!(first.distance(&Vector2::new(2.0, 0.0)) < 1e-12);
+        assert!(last.distance(&Vector2::new(1.0, 3.0)) < 1e-12);
+
+        // Mirrored: the quarter now runs from (-2, 0) to (-1, 3)
+        let mirrored = Ellipse::from_arc_scaled(&arc, -1.0, 3.0).to_polyline(1e-3);
+        let (first, last) = (mirrored.vertices[0].location, mirrored.vertices.last().unwrap().location);
+        assert!(first.distance(&Vector2::new(-1.0, 3.0)) < 1e-12);
+        assert!(last.distance(&Vector2::new(-2.0, 0.0)) < 1e-12);
+
+        let circle = Arc::from_coords(0.0, 0.0, 0.0, 2.0, 0.0, TAU);
+        let full = Ellipse::from_arc_scaled(&circle, 2.0, 1.0);
+        assert!(full.is_full());
+        assert!((full.area() - 8.0 * std::f64::consts::PI).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_circular_ellipse_to_arc() {
+        let mut ellipse = Ellipse::from_center_axes(Vector3::new(1.0, 2.0, 3.0), Vector3::new(0.0, 2.0, 0.0), 1.0);
+        assert!(matches!(ellipse.to_arc_or_circle(1e-9), Some(EntityType::Circle(c)) if c.radius == 2.0));
+
+        ellipse.start_parameter = 0.0;
+        ellipse.end_parameter = std::f64::consts::PI;
+        let Some(EntityType::Arc(arc)) = ellipse.to_arc_or_circle(1e-9) else { panic!() };
+        assert!((arc.start_angle - std::f64::consts::FRAC_PI_2).abs() < 1e-12);
+        assert!((arc.end_angle - 1.5 * std::f64::consts::PI).abs() < 1e-12);
+        assert_eq!(arc.center, Vector3::new(1.0, 2.0, 3.0));
+
+        // Seen from below, the same half circle in the flipped OCS
+        ellipse.normal = -Vector3::UNIT_Z;
+        let Some(EntityType::Arc(flipped)) = ellipse.to_arc_or_circle(1e-9) else { panic!() };
+        assert_eq!(flipped.center, Vector3::new(-1.0, 2.0, -3.0));
+        assert!((flipped.start_angle - std::f64::consts::FRAC_PI_2).abs() < 1e-12);
+
+        ellipse.minor_axis_ratio = 0.99;
+        assert!(ellipse.to_arc_or_circle(1e-3).is_none());
+        assert!(ellipse.to_arc_or_circle(0.05).is_some());
+    }
+
+    #[test]
+    fn test_to_polyline_within_tolerance() {
+        let ellipse = Ellipse::from_center_axes(Vector3::ZERO, Vector3::new(10.0, 0.0, 0.0), 0.5);
+        let polyline = ellipse.to_polyline(0.01);
+        assert!(polyline.is_closed);
+        assert!(polyline.vertices.len() >= 8);
+        for pair in polyline.vertices.windows(2) {
+            let mid = (pair[0].location + pair[1].location) * 0.5;
+            // Distance from the chord midpoint to the ellipse, roughly
+            let radial = (mid.x / 10.0).hypot(mid.y / 5.0);
+            assert!((1.0 - radial) * 5.0 < 0.01 + 1e-9);
+        }
+        assert!(ellipse.area() - polyline.area() < ellipse.length() * 0.01);
+    }
+
+    #[test]
+    fn test_ellipse_parameters_and_angles() {
+        let ellipse = Ellipse::from_center_axes(Vector3::ZERO, Vector3::new(2.0, 0.0, 0.0), 0.5);
+        // The point at parameter 45° is (√2, √2/2), seen at atan(0.5) from the center
+        let parameter = Angle::from_degrees(45.0);
+        let angle = ellipse.angle_at_parameter(parameter);
+        assert!((angle.radians() - 0.5f64.atan()).abs() < 1e-12);
+        assert!((ellipse.parameter_at_angle(angle).radians() - parameter.radians()).abs() < 1e-12);
+        assert!((ellipse.parameter_at_angle(Angle::from_degrees(270.0)).degrees() - 270.0).abs() < 1e-9);
+
+        let mut arc = ellipse.clone();
+        arc.set_end_parameter(Angle::STRAIGHT);
+        assert_eq!(arc.end_parameter, std::f64::consts::PI);
+        assert_eq!(arc.start_parameter(), Angle::ZERO);
+    }
 }
 
 
@@ -0,0 +1,214 @@
+//! Pattern fill of hatches
+//!
+//! Each pattern line is a family of parallel lines through its base point,
+//! one every `offset`, dashed after its dash lengths from the base point
+//! on. The lines are clipped to the boundary rings with the even-odd rule,
+//! after dropping the islands the hatch style leaves empty.
+
+use super::hatch::{Hatch, HatchPatternLine, HatchStyleType};
+use super::Entity;
+use crate::render::tessellate::hatch_path_points;
+use crate::types::geometry::point_in_polygon;
+use crate::types::Vector2;
+
+/// Upper bound on fill segments per hatch, to keep tiny pattern spacings
+/// in check
+const MAX_SEGMENTS: usize = 200_000;
+
+impl Hatch {
+    /// Line segments of the pattern fill, in the hatch's OCS: the lines
+    /// AutoCAD draws for a pattern hatch.
+    ///
+    /// Dots of the pattern are segments of zero length. Solid hatches have
+    /// no fill lines.
+    pub fn generate_fill_lines(&self) -> Vec<(Vector2, Vector2)> {
+        let extents = self.bounding_box();
+        self.fill_lines((extents.max - extents.min).length() * 1e-5)
+    }
+
+    /// [`Hatch::generate_fill_lines`] with boundary curves divided into
+    /// chords within `tolerance`
+    pub(crate) fn fill_lines(&self, tolerance: f64) -> Vec<(Vector2, Vector2)> {
+        if self.is_solid {
+            return Vec::new();
+        }
+        let rings = self.filled_rings(tolerance);
+        let mut segments = Vec::new();
+        for line in &self.pattern.lines {
+            fill_pattern_line(line, &rings, &mut segments);
+        }
+        segments
+    }
+
+    /// Boundary rings enclosing the hatched area under the even-odd rule
+    fn filled_rings(&self, tolerance: f64) -> Vec<Vec<Vector2>> {
+        let rings: Vec<Vec<Vector2>> = self
+            .paths
+            .iter()
+            .map(|path| hatch_path_points(path, tolerance.max(1e-9)))
+            .filter(|ring| ring.len() >= 3)
+            .collect();
+        let max_depth = match self.style {
+            HatchStyleType::Normal => usize::MAX,
+            HatchStyleType::Outer => 1,
+            HatchStyleType::Ignore => 0,
+        };
+        let depths: Vec<usize> = rings
+            .iter()
+            .enumerate()
+            .map(|(i, ring)| {
+                rings
+                    .iter()
+                    .enumerate()
+                    .filter(|&(j, other)| j != i && point_in_polygon(other, ring[0]))
+                    .count()
+            })
+            .collect();
+        rings
+            .into_iter()
+            .zip(depths)
+            .filter(|&(_, depth)| depth <= max_depth)
+            .map(|(ring, _)| ring)
+            .collect()
+    }
+}
+
+/// Add the segments of one pattern line family inside `rings`
+fn fill_pattern_line(line: &HatchPatternLine, rings: &[Vec<Vector2>], segments: &mut Vec<(Vector2, Vector2)>) {
+    let direction = Vector2::new(line.angle.cos(), line.angle.sin());
+    let across = Vector2::new(-direction.y, direction.x);
+    let spacing = line.offset.dot(&across);
+    if spacing.abs() < 1e-9 || rings.is_empty() {
+        return;
+    }
+
+    // Lines of the family that can cross the boundary
+    let distances = rings.iter().flatten().map(|p| (*p - line.base_point).dot(&across));
+    let (low, high) = distances.fold((f64::MAX, f64::MIN), |(lo, hi), d| (lo.min(d), hi.max(d)));
+    let (first, last) = {
+        let (a, b) = (low / spacing, high / spacing);
+        (a.min(b).ceil() as i64, a.max(b).floor() as i64)
+    };
+
+    for k in first..=last {
+        if segments.len() >= MAX_SEGMENTS {
+            return;
+        }
+        let origin = line.base_point + line.offset * k as f64;
+        let mut crossings: Vec<f64> = Vec::new();
+        for ring in rings {
+            for (i, &p) in ring.iter().enumerate() {
+                let q = ring[(i + 1) % ring.len()];
+                let (dp, dq) = ((p - origin).dot(&across), (q - origin).dot(&across));
+                if (dp > 0.0) != (dq > 0.0) {
+                    let hit = p + (q - p) * (dp / (dp - dq));
+                    crossings.push((hit - origin).dot(&direction));
+                }
+            }
+        }
+        crossings.sort_by(f64::total_cmp);
+        let at = |t: f64| origin + direction * t;
+        for span in crossings.chunks_exact(2) {
+            dash_span(&line.dash_lengths, span[0], span[1], &mut |a, b| segments.push((at(a), at(b))));
+        }
+    }
+}
+
+/// Call `emit` with the dashes of the pattern, repeated from 0 along the
+/// line, that fall within `start..end`
+fn dash_span(dashes: &[f64], start: f64, end: f64, emit: &mut impl FnMut(f64, f64)) {
+    let period: f64 = dashes.iter().map(|d| d.abs()).sum();
+    if dashes.is_empty() || period < 1e-9 || (end - start) / period > MAX_SEGMENTS as f64 {
+        emit(start, end);
+        return;
+    }
+    let mut position = (start / period).floor() * period;
+    while position <= end {
+        for &dash in dashes {
+            let next = position + dash.abs();
+            if dash >= 0.0 {
+                let (a, b) = (position.max(start), next.min(end));
+                // Dots on the far end belong to the next span
+                if a < b || (dash == 0.0 && a == b && a < end) {
+                    emit(a, b);
+                }
+            }
+            position = next;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::{BoundaryEdge, BoundaryPath, HatchPattern, PolylineEdge};
+
+    fn square(size: f64, offset: f64) -> BoundaryPath {
+        let ring = [(0.0, 0.0), (size, 0.0), (size, size), (0.0, size)]
+            .into_iter()
+            .map(|(x, y)| Vector2::new(x + offset, y + offset))
+            .collect();
+        let mut path = BoundaryPath::new();
+        path.add_edge(BoundaryEdge::Polyline(PolylineEdge::new(ring, true)));
+        path
+    }
+
+    fn hatch(angle: f64, dashes: Vec<f64>) -> Hatch {
+        let mut pattern = HatchPattern::new("TEST");
+        pattern.add_line(HatchPatternLine {
+            angle,
+            base_point: Vector2::new(0.0, 0.5),
+            offset: Vector2::new(0.0, 1.0),
+            dash_lengths: dashes,
+        });
+        let mut hatch = Hatch::with_pattern(pattern);
+        hatch.add_path(square(10.0, 0.0));
+        hatch
+    }
+
+    #[test]
+    fn test_horizontal_lines() {
+        let lines = hatch(0.0, Vec::new()).generate_fill_lines();
+        assert_eq!(lines.len(), 10);
+        for (i, (a, b)) in lines.iter().enumerate() {
+            assert!((a.y - (0.5 + i as f64)).abs() < 1e-12);
+            assert!(a.x.abs() < 1e-9 && (b.x - 10.0).abs() < 1e-9);
+        }
+        assert!(Hatch::solid().generate_fill_lines().is_empty());
+    }
+
+    #[test]
+    fn test_islands_by_style() {
+        let mut hatch = hatch(0.0, Vec::new());
+        hatch.add_path(square(4.0, 3.0));
+        hatch.add_path(square(2.0, 4.0));
+        let length = |hatch: &Hatch| -> f64 { hatch.generate_fill_lines().iter().map(|(a, b)| a.distance(b)).sum() };
+
+        // Outer square with a hole, and an island in the hole
+        assert!((length(&hatch) - (100.0 - 16.0 + 4.0)).abs() < 1e-9);
+        hatch.style = HatchStyleType::Outer;
+        assert!((length(&hatch) - (100.0 - 16.0)).abs() < 1e-9);
+        hatch.style = HatchStyleType::Ignore;
+        assert!((length(&hatch) - 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_dashes_and_dots() {
+        // Dash 1, gap 0.5, dot, gap 0.5: period 2 from x = 0
+        let lines = hatch(0.0, vec![1.0, -0.5, 0.0, -0.5]).generate_fill_lines();
+        let row: Vec<_> = lines.iter().filter(|(a, _)| (a.y - 0.5).abs() < 1e-9).collect();
+        assert_eq!(row.len(), 10);
+        assert!((row[0].0.x).abs() < 1e-9 && (row[0].1.x - 1.0).abs() < 1e-9);
+        assert!((row[1].0.x - 1.5).abs() < 1e-9 && row[1].0 == row[1].1);
+
+        // At 45° every line starts where it meets the boundary
+        let diagonal = hatch(std::f64::consts::FRAC_PI_4, Vec::new()).generate_fill_lines();
+        assert!(!diagonal.is_empty());
+        for (a, b) in diagonal {
+            for p in [a, b] {
+                let on_edge = [p.x, p.y].iter().any(|c| c.abs() < 1e-9 || (c - 10.0).abs() < 1e-9);
+                assert!(on_edge, "{p:?}");
+            }
+        }
+    }
+}
@@ -2,13 +2,14 @@
 
 use crate::entities::{Entity, EntityCommon};
 use crate::entities::attribute_entity::AttributeEntity;
-use crate::types::{BoundingBox3D, Color, Handle, LineWeight, Transparency, Vector3};
+use crate::types::{Angle, BoundingBox3D, Color, Handle, LineWeight, Transparency, Vector3};
 
 /// Insert entity - a reference to a block definition
 ///
 /// An Insert entity places an instance of a block at a specified location
 /// with optional scaling, rotation, and array properties.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Insert {
     pub common: EntityCommon,
     /// Block name (references a BlockRecord)
@@ -79,6 +80,16 @@ impl Insert {
         self
     }
 
+    /// Rotation angle
+    pub fn rotation(&self) -> Angle {
+        Angle::from_radians(self.rotation)
+    }
+
+    /// Set the rotation angle
+    pub fn set_rotation(&mut self, angle: Angle) {
+        self.rotation = angle.radians();
+    }
+
     /// Builder: Set the normal vector
     pub fn with_normal(mut self, normal: Vector3) -> Self {
         self.normal = normal;
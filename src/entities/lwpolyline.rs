@@ -1,10 +1,12 @@
 //! Lightweight polyline entity (2D polyline with bulges)
 
 use super::{Entity, EntityCommon};
+use crate::types::geometry::ocs_matrix;
 use crate::types::{BoundingBox3D, Color, Handle, LineWeight, Transparency, Vector2, Vector3};
 
 /// A vertex in a lightweight polyline
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LwVertex {
     /// Location of the vertex (2D)
     pub location: Vector2,
@@ -47,6 +49,7 @@ impl LwVertex {
 
 /// A lightweight (2D) polyline entity
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LwPolyline {
     /// Common entity data
     pub common: EntityCommon,
@@ -56,11 +59,11 @@ pub struct LwPolyline {
     pub is_closed: bool,
     /// Constant width (if all segments have same width)
     pub constant_width: f64,
-    /// Elevation (Z coordinate)
+    /// Elevation: Z coordinate of the vertices in the OCS
     pub elevation: f64,
-    /// Thickness (extrusion in Z direction)
+    /// Thickness: extrusion along the normal
     pub thickness: f64,
-    /// Normal vector
+    /// Normal (extrusion direction) defining the OCS of the vertices
     pub normal: Vector3,
 }
 
@@ -110,6 +113,57 @@ impl LwPolyline {
     pub fn close(&mut self) {
         self.is_closed = true;
     }
+
+    /// Length along the segments and bulge arcs
+    pub fn length(&self) -> f64 {
+        let vertices: Vec<(Vector2, f64)> = self.vertices.iter().map(|v| (v.location, v.bulge)).collect();
+        bulge_length(&vertices, self.is_closed)
+    }
+
+    /// Area enclosed by the polyline as if it were closed
+    pub fn area(&self) -> f64 {
+        let vertices: Vec<(Vector2, f64)> = self.vertices.iter().map(|v| (v.location, v.bulge)).collect();
+        bulge_area(&vertices)
+    }
+}
+
+/// Length of a polyline with bulges
+pub(crate) fn bulge_length(vertices: &[(Vector2, f64)], closed: bool) -> f64 {
+    let n = vertices.len();
+    let segments = if closed { n } else { n.saturating_sub(1) };
+    (0..segments)
+        .map(|i| {
+            let ((start, bulge), (end, _)) = (vertices[i], vertices[(i + 1) % n]);
+            let chord = start.distance(&end);
+            if bulge.abs() < 1e-12 || chord < 1e-12 {
+                return chord;
+            }
+            // Included angle 4·atan(bulge), radius from the chord
+            let angle = 4.0 * bulge.abs().atan();
+            angle * chord / (2.0 * (angle / 2.0).sin())
+        })
+        .sum()
+}
+
+/// Area enclosed by a closed polyline with bulges
+pub(crate) fn bulge_area(vertices: &[(Vector2, f64)]) -> f64 {
+    let n = vertices.len();
+    let signed: f64 = (0..n)
+        .map(|i| {
+            let ((start, bulge), (end, _)) = (vertices[i], vertices[(i + 1) % n]);
+            let mut area = (start.x * end.y - end.x * start.y) / 2.0;
+            let chord = start.distance(&end);
+            if bulge.abs() >= 1e-12 && chord >= 1e-12 {
+                // Circular segment between the chord and the arc; a
+                // counter-clockwise arc lies to the right of the chord
+                let angle = 4.0 * bulge.abs().atan();
+                let radius = chord / (2.0 * (angle / 2.0).sin());
+                area += bulge.signum() * radius * radius / 2.0 * (angle - angle.sin());
+            }
+            area
+        })
+        .sum();
+    signed.abs()
 }
 
 impl Default for LwPolyline {
@@ -172,15 +226,23 @@ impl Entity for LwPolyline {
             return BoundingBox3D::from_point(Vector3::ZERO);
         }
 
-        let points: Vec<Vector3> = self
-            .vertices
-            .iter()
-            .map(|v| Vector3::new(v.location.x, v.location.y, self.elevation))
-            .collect();
+        // Vertices in WCS, and their copies at the far side of the thickness
+        let to_wcs = ocs_matrix(self.normal);
+        let extrusion = self.normal.normalize() * self.thickness;
+        let mut points = Vec::new();
+        for v in &self.vertices {
+            let point = to_wcs.transform_point(Vector3::new(v.location.x, v.location.y, self.elevation));
+            points.push(point);
+            if self.thickness != 0.0 {
+                points.push(point + extrusion);
+            }
+        }
         BoundingBox3D::from_points(&points).unwrap()
     }
 
     fn translate(&mut self, offset: Vector3) {
+        // The offset is in WCS, the vertices and elevation in the OCS
+        let offset = ocs_matrix(self.normal).transpose().transform_point(offset);
         for vertex in &mut self.vertices {
             vertex.location.x += offset.x;
             vertex.location.y += offset.y;
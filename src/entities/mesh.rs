@@ -3,7 +3,7 @@
 //! The Mesh entity represents a subdivision surface mesh that can be
 //! smoothed at various levels for high-quality curved surface display.
 
-use crate::entities::{Entity, EntityCommon};
+use crate::entities::{Entity, EntityCommon, PolyfaceFace, PolyfaceMesh, PolyfaceVertex};
 use crate::types::{BoundingBox3D, Color, Handle, LineWeight, Transparency, Vector3};
 
 // ============================================================================
@@ -12,6 +12,7 @@ use crate::types::{BoundingBox3D, Color, Handle, LineWeight, Transparency, Vecto
 
 /// An edge in a mesh, defined by two vertex indices.
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MeshEdge {
     /// Index of the start vertex.
     pub start: usize,
@@ -93,6 +94,7 @@ impl Default for MeshEdge {
 ///
 /// Faces can have any number of vertices (3 for triangles, 4 for quads, etc.).
 #[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MeshFace {
     /// Indices of vertices that form this face (in order).
     pub vertices: Vec<usize>,
@@ -212,6 +214,7 @@ impl From<Vec<usize>> for MeshFace {
 /// mesh.subdivision_level = 2;
 /// ```
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Mesh {
     /// Common entity data.
     pub common: EntityCommon,
@@ -623,6 +626,226 @@ impl Mesh {
             });
         }
     }
+
+    /// Calculates the unit normal of a face, or `None` for degenerate faces.
+    ///
+    /// Uses Newell's method, so non-planar and concave faces get the
+    /// average normal of their outline.
+    pub fn face_normal(&self, face: &MeshFace) -> Option<Vector3> {
+        let normal = self.newell_normal(face);
+        let len = normal.length();
+        if len > 1e-10 {
+            Some(normal / len)
+        } else {
+            None
+        }
+    }
+
+    /// Calculates the normals of all faces, in face order.
+    pub fn face_normals(&self) -> Vec<Option<Vector3>> {
+        self.faces.iter().map(|f| self.face_normal(f)).collect()
+    }
+
+    /// Calculates a normal per vertex from the faces sharing it.
+    ///
+    /// Face normals are weighted by face area; vertices not used by any
+    /// face get a zero vector.
+    pub fn vertex_normals(&self) -> Vec<Vector3> {
+        let mut normals = vec![Vector3::ZERO; self.vertices.len()];
+        for face in &self.faces {
+            let normal = self.newell_normal(face);
+            for &index in &face.vertices {
+                if let Some(n) = normals.get_mut(index) {
+                    *n = *n + normal;
+                }
+            }
+        }
+        for normal in &mut normals {
+            if normal.length() > 1e-10 {
+                *normal = normal.normalize();
+            } else {
+                *normal = Vector3::ZERO;
+            }
+        }
+        normals
+    }
+
+    /// Area-weighted normal of a face outline (twice the area in length).
+    fn newell_normal(&self, face: &MeshFace) -> Vector3 {
+        let points: Vec<Vector3> = face.vertices.iter().filter_map(|&i| self.vertex(i)).collect();
+        let mut normal = Vector3::ZERO;
+        for (i, current) in points.iter().enumerate() {
+            let next = points[(i + 1) % points.len()];
+            normal.x += (current.y - next.y) * (current.z + next.z);
+            normal.y += (current.z - next.z) * (current.x + next.x);
+            normal.z += (current.x - next.x) * (current.y + next.y);
+        }
+        normal
+    }
+
+    /// Merges vertices closer than `tolerance` and returns how many were removed.
+    ///
+    /// Faces and edges are remapped to the remaining vertices. Faces that
+    /// collapse below three vertices and edges that collapse to a point are
+    /// dropped, as are repeated edges.
+    pub fn weld_vertices(&mut self, tolerance: f64) -> usize {
+        use std::collections::{HashMap, HashSet};
+
+        let cell_size = tolerance.max(1e-12);
+        let cell = |v: &Vector3| {
+            (
+                (v.x / cell_size).floor() as i64,
+                (v.y / cell_size).floor() as i64,
+                (v.z / cell_size).floor() as i64,
+            )
+        };
+
+        let mut grid: HashMap<(i64, i64, i64), Vec<usize>> = HashMap::new();
+        let mut welded: Vec<Vector3> = Vec::with_capacity(self.vertices.len());
+        let mut remap = Vec::with_capacity(self.vertices.len());
+
+        for vertex in &self.vertices {
+            let (cx, cy, cz) = cell(vertex);
+            let mut found = None;
+            'search: for dx in -1..=1 {
+                for dy in -1..=1 {
+                    for dz in -1..=1 {
+                        let Some(candidates) = grid.get(&(cx + dx, cy + dy, cz + dz)) else {
+                            continue;
+                        };
+                        if let Some(&index) = candidates
+                            .iter()
+                            .find(|&&i| welded[i].distance(vertex) <= tolerance)
+                        {
+                            found = Some(index);
+                            break 'search;
+                        }
+                    }
+                }
+            }
+            let index = found.unwrap_or_else(|| {
+                welded.push(*vertex);
+                grid.entry((cx, cy, cz)).or_default().push(welded.len() - 1);
+                welded.len() - 1
+            });
+            remap.push(index);
+        }
+
+        let removed = self.vertices.len() - welded.len();
+        if removed == 0 {
+            return 0;
+        }
+        self.vertices = welded;
+
+        for face in &mut self.faces {
+            let mut indices: Vec<usize> = face.vertices.iter().map(|&i| remap[i]).collect();
+            indices.dedup();
+            while indices.len() > 1 && indices.first() == indices.last() {
+                indices.pop();
+            }
+            face.vertices = indices;
+        }
+        self.faces.retain(|f| f.vertex_count() >= 3);
+
+        let mut seen = HashSet::new();
+        self.edges.retain_mut(|edge| {
+            edge.start = remap[edge.start];
+            edge.end = remap[edge.end];
+            let key = (edge.start.min(edge.end), edge.start.max(edge.end));
+            edge.start != edge.end && seen.insert(key)
+        });
+
+        removed
+    }
+
+    /// Creates a mesh from a polyface mesh.
+    ///
+    /// Layer, color, line weight and transparency are carried over; edges
+    /// are computed from the faces.
+    pub fn from_polyface(polyface: &PolyfaceMesh) -> Self {
+        let mut mesh = Self::new();
+        mesh.common = converted_common(&polyface.common);
+        mesh.vertices = polyface.vertices.iter().map(|v| v.location).collect();
+
+        let count = polyface.vertices.len();
+        for face in &polyface.faces {
+            let indices: Vec<usize> = face
+                .vertex_indices()
+                .into_iter()
+                .filter(|&i| i >= 1 && i as usize <= count)
+                .map(|i| i as usize - 1)
+                .collect();
+            if indices.len() >= 3 {
+                mesh.faces.push(MeshFace::new(indices));
+            }
+        }
+
+        mesh.compute_edges();
+        mesh
+    }
+
+    /// Creates a polyface mesh from this mesh.
+    ///
+    /// Faces with more than four vertices are split into triangle fans
+    /// whose inner edges are invisible. Creases and subdivision settings
+    /// have no polyface equivalent and are dropped. Returns `None` when
+    /// the mesh has more vertices than polyface indices can address.
+    pub fn to_polyface(&self) -> Option<PolyfaceMesh> {
+        if self.vertices.len() > i16::MAX as usize {
+            return None;
+        }
+
+        let mut polyface = PolyfaceMesh::new();
+        polyface.common = converted_common(&self.common);
+        for &vertex in &self.vertices {
+            polyface.add_vertex(PolyfaceVertex::new(vertex));
+        }
+
+        let count = self.vertices.len();
+        for face in &self.faces {
+            if face.vertices.iter().any(|&i| i >= count) {
+                continue;
+            }
+            let index = |i: usize| face.vertices[i] as i16 + 1;
+            match face.vertex_count() {
+                0..=2 => {}
+                3 => polyface.add_triangle(index(0), index(1), index(2)),
+                4 => polyface.add_quad(index(0), index(1), index(2), index(3)),
+                n => {
+                    for i in 1..n - 1 {
+                        polyface.add_face(PolyfaceFace::triangle_with_visibility(
+                            index(0),
+                            index(i),
+                            index(i + 1),
+                            i != 1,
+                            false,
+                            i + 1 != n - 1,
+                        ));
+                    }
+                }
+            }
+        }
+
+        Some(polyface)
+    }
+}
+
+/// Display properties of a converted entity, without handle or ownership
+fn converted_common(common: &EntityCommon) -> EntityCommon {
+    EntityCommon {
+        layer: common.layer.clone(),
+        color: common.color,
+        line_weight: common.line_weight,
+        transparency: common.transparency,
+        invisible: common.invisible,
+        ..Default::default()
+    }
+}
+
+impl From<&PolyfaceMesh> for Mesh {
+    fn from(polyface: &PolyfaceMesh) -> Self {
+        Self::from_polyface(polyface)
+    }
 }
 
 impl Default for Mesh {
@@ -1047,5 +1270,72 @@ mod tests {
         assert_eq!(mesh.subdivision_level, 2);
         assert!(!mesh.blend_crease);
     }
-}
+    #[test]
+    fn test_mesh_normals() {
+        let mesh = Mesh::create_unit_cube();
+        let normals = mesh.face_normals();
+        assert_eq!(normals[0], Some(Vector3::new(0.0, 0.0, -1.0)));
+        assert_eq!(normals[1], Some(Vector3::new(0.0, 0.0, 1.0)));
 
+        let corner = mesh.vertex_normals()[6];
+        let expected = Vector3::new(1.0, 1.0, 1.0).normalize();
+        assert!((corner - expected).length() < 1e-9);
+    }
+
+    #[test]
+    fn test_mesh_weld_vertices() {
+        // Two triangles of a square with separate copies of the shared corners
+        let mut mesh = Mesh::from_triangles(
+            vec![
+                Vector3::ZERO,
+                Vector3::new(1.0, 0.0, 0.0),
+                Vector3::new(1.0, 1.0, 0.0),
+                Vector3::new(1e-9, 0.0, 0.0),
+                Vector3::new(1.0, 1.0, 0.0),
+                Vector3::new(0.0, 1.0, 0.0),
+            ],
+            &[(0, 1, 2), (3, 4, 5)],
+        );
+        mesh.add_triangle(0, 3, 1);
+        mesh.compute_edges();
+
+        assert_eq!(mesh.weld_vertices(1e-6), 2);
+        assert_eq!(mesh.vertex_count(), 4);
+        assert_eq!(mesh.face_count(), 2);
+        assert_eq!(mesh.faces[1].vertices, vec![0, 2, 3]);
+        assert_eq!(mesh.edge_count(), 5);
+    }
+
+    #[test]
+    fn test_mesh_polyface_conversion() {
+        let mut polyface = PolyfaceMesh::create_pyramid(Vector3::ZERO, 2.0, 1.0);
+        polyface.common.layer = "SOLIDS".to_string();
+
+        let mesh = Mesh::from(&polyface);
+        assert_eq!(mesh.common.layer, "SOLIDS");
+        assert_eq!(mesh.vertex_count(), 5);
+        assert_eq!(mesh.faces[0].vertices, vec![0, 1, 2, 3]);
+        assert_eq!(mesh.faces[1].vertices, vec![0, 1, 4]);
+        assert_eq!(mesh.edge_count(), 8);
+
+        let back = mesh.to_polyface().unwrap();
+        assert_eq!(back.vertex_count(), 5);
+        assert_eq!(back.faces, polyface.faces);
+
+        // A pentagon becomes a fan of three triangles with hidden inner edges
+        let mut pentagon = Mesh::new();
+        for i in 0..5 {
+            let angle = i as f64 * std::f64::consts::TAU / 5.0;
+            pentagon.add_vertex(Vector3::new(angle.cos(), angle.sin(), 0.0));
+        }
+        pentagon.add_face(MeshFace::new(vec![0, 1, 2, 3, 4]));
+        let fan = pentagon.to_polyface().unwrap();
+        assert_eq!(fan.face_count(), 3);
+        assert_eq!(
+            fan.faces.iter().map(|f| (f.index1, f.index2, f.index3)).collect::<Vec<_>>(),
+            vec![(1, 2, -3), (-1, 3, -4), (-1, 4, 5)]
+        );
+        let area = 2.5 * (std::f64::consts::TAU / 5.0).sin();
+        assert!((fan.total_area() - area).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,282 @@
+//! Catmull–Clark evaluation of subdivision meshes
+//!
+//! AutoCAD displays a MESH smoothed to its subdivision level; the stored
+//! vertices and faces are only the control cage. Each subdivision step
+//! splits every n-sided face into n quads and repositions the vertices:
+//!
+//! - a face point at the centroid of each face
+//! - an edge point averaging the edge ends and its two face points
+//! - the old vertices moved toward the average of their neighbourhood
+//!
+//! Creased edges use the sharp rules instead: their edge point is the
+//! midpoint, and a vertex on two creases only moves along them while a
+//! vertex on three or more stays put. A crease value of `n` is sharp for
+//! `n` levels and blends into smooth below 1; negative values are sharp at
+//! every level. Boundary edges are always sharp.
+
+use std::collections::{HashMap, HashSet};
+
+use super::mesh::{Mesh, MeshEdge, MeshFace};
+use crate::types::Vector3;
+
+impl Mesh {
+    /// Evaluates the mesh to its stored subdivision level.
+    pub fn subdivided(&self) -> Mesh {
+        self.subdivide(self.subdivision_level.max(0) as u32)
+    }
+
+    /// Evaluates the mesh to the given number of subdivision levels.
+    ///
+    /// Returns the refined mesh with a subdivision level of 0: quad faces,
+    /// edges for every face side and the remaining crease values.
+    pub fn subdivide(&self, levels: u32) -> Mesh {
+        let mut cage = Cage::from_mesh(self);
+        for _ in 0..levels {
+            cage = cage.step();
+        }
+
+        let mut mesh = Mesh {
+            subdivision_level: 0,
+            vertices: cage.vertices,
+            faces: cage.faces.into_iter().map(MeshFace::new).collect(),
+            edges: Vec::new(),
+            ..self.clone()
+        };
+        let mut seen = HashSet::new();
+        let mut edges = Vec::new();
+        for face in &mesh.faces {
+            for (a, b) in face.edges() {
+                let key = edge_key(a, b);
+                if seen.insert(key) {
+                    let mut edge = MeshEdge::new(key.0, key.1);
+                    if let Some(&sharpness) = cage.creases.get(&key) {
+                        edge.crease = Some(if sharpness.is_infinite() { -1.0 } else { sharpness });
+                    }
+                    edges.push(edge);
+                }
+            }
+        }
+        mesh.edges = edges;
+        mesh
+    }
+}
+
+/// Working copy of the control cage between subdivision steps
+struct Cage {
+    vertices: Vec<Vector3>,
+    faces: Vec<Vec<usize>>,
+    /// Sharpness of creased edges, infinite for edges sharp at every level
+    creases: HashMap<(usize, usize), f64>,
+}
+
+/// Edge of the cage with its adjacent faces
+struct CageEdge {
+    start: usize,
+    end: usize,
+    faces: Vec<usize>,
+}
+
+impl Cage {
+    fn from_mesh(mesh: &Mesh) -> Self {
+        let count = mesh.vertices.len();
+        let faces = mesh
+            .faces
+            .iter()
+            .filter(|f| f.vertex_count() >= 3 && f.vertices.iter().all(|&i| i < count))
+            .map(|f| f.vertices.clone())
+            .collect();
+        let creases = mesh
+            .edges
+            .iter()
+            .filter(|e| e.crease_value() != 0.0)
+            .map(|e| {
+                let crease = e.crease_value();
+                (edge_key(e.start, e.end), if crease < 0.0 { f64::INFINITY } else { crease })
+            })
+            .collect();
+        Self { vertices: mesh.vertices.clone(), faces, creases }
+    }
+
+    fn sharpness(&self, edge: &CageEdge) -> f64 {
+        if edge.faces.len() != 2 {
+            return f64::INFINITY;
+        }
+        self.creases.get(&edge_key(edge.start, edge.end)).copied().unwrap_or(0.0)
+    }
+
+    /// One Catmull–Clark step. New vertices are laid out as the moved old
+    /// vertices, then one point per edge, then one per face.
+    fn step(&self) -> Self {
+        let face_points: Vec<Vector3> = self
+            .faces
+            .iter()
+            .map(|face| {
+                let sum = face.iter().fold(Vector3::ZERO, |acc, &i| acc + self.vertices[i]);
+                sum / face.len() as f64
+            })
+            .collect();
+
+        // Edges in order of first appearance
+        let mut edges: Vec<CageEdge> = Vec::new();
+        let mut edge_index: HashMap<(usize, usize), usize> = HashMap::new();
+        let mut vertex_edges: Vec<Vec<usize>> = vec![Vec::new(); self.vertices.len()];
+        let mut vertex_faces: Vec<Vec<usize>> = vec![Vec::new(); self.vertices.len()];
+        for (face_idx, face) in self.faces.iter().enumerate() {
+            for (i, &a) in face.iter().enumerate() {
+                let b = face[(i + 1) % face.len()];
+                let index = *edge_index.entry(edge_key(a, b)).or_insert_with(|| {
+                    edges.push(CageEdge { start: a, end: b, faces: Vec::new() });
+                    vertex_edges[a].push(edges.len() - 1);
+                    vertex_edges[b].push(edges.len() - 1);
+                    edges.len() - 1
+                });
+                edges[index].faces.push(face_idx);
+                vertex_faces[a].push(face_idx);
+            }
+        }
+
+        let edge_points: Vec<Vector3> = edges
+            .iter()
+            .map(|edge| {
+                let midpoint = (self.vertices[edge.start] + self.vertices[edge.end]) * 0.5;
+                let sharpness = self.sharpness(edge);
+                if sharpness >= 1.0 {
+                    return midpoint;
+                }
+                let smooth = (self.vertices[edge.start]
+                    + self.vertices[edge.end]
+                    + face_points[edge.faces[0]]
+                    + face_points[edge.faces[1]])
+                    * 0.25;
+                lerp(smooth, midpoint, sharpness)
+            })
+            .collect();
+
+        let vertex_points = self.vertices.iter().enumerate().map(|(v, &point)| {
+            let incident = &vertex_edges[v];
+            if incident.is_empty() {
+                return point;
+            }
+
+            let sharp: Vec<(&CageEdge, f64)> = incident
+                .iter()
+                .map(|&e| (&edges[e], self.sharpness(&edges[e])))
+                .filter(|(_, s)| *s > 0.0)
+                .collect();
+            let sharp_point = match sharp.len() {
+                0 | 1 => None,
+                2 => {
+                    let other = |edge: &CageEdge| {
+                        self.vertices[if edge.start == v { edge.end } else { edge.start }]
+                    };
+                    Some((other(sharp[0].0) + point * 6.0 + other(sharp[1].0)) / 8.0)
+                }
+                _ => Some(point),
+            };
+            let weight = sharp.iter().map(|(_, s)| s.min(1.0)).sum::<f64>() / sharp.len().max(1) as f64;
+            if let (Some(sharp_point), true) = (sharp_point, weight >= 1.0) {
+                return sharp_point;
+            }
+
+            let n = incident.len() as f64;
+            let faces = &vertex_faces[v];
+            let q = faces.iter().fold(Vector3::ZERO, |acc, &f| acc + face_points[f]) / faces.len() as f64;
+            let r = incident.iter().fold(Vector3::ZERO, |acc, &e| {
+                acc + (self.vertices[edges[e].start] + self.vertices[edges[e].end]) * 0.5
+            }) / n;
+            let smooth = (q + r * 2.0 + point * (n - 3.0)) / n;
+            match sharp_point {
+                Some(sharp_point) => lerp(smooth, sharp_point, weight),
+                None => smooth,
+            }
+        });
+
+        let edge_base = self.vertices.len();
+        let face_base = edge_base + edges.len();
+        let mut vertices: Vec<Vector3> = vertex_points.collect();
+        vertices.extend(edge_points);
+        vertices.extend(face_points);
+
+        let mut faces = Vec::new();
+        for (face_idx, face) in self.faces.iter().enumerate() {
+            let n = face.len();
+            let edge_point = |i: usize| edge_base + edge_index[&edge_key(face[i % n], face[(i + 1) % n])];
+            for (i, &corner) in face.iter().enumerate() {
+                faces.push(vec![corner, edge_point(i), face_base + face_idx, edge_point(i + n - 1)]);
+            }
+        }
+
+        let mut creases = HashMap::new();
+        for (index, edge) in edges.iter().enumerate() {
+            let Some(&sharpness) = self.creases.get(&edge_key(edge.start, edge.end)) else {
+                continue;
+            };
+            let remaining = sharpness - 1.0;
+            if remaining > 0.0 {
+                creases.insert(edge_key(edge.start, edge_base + index), remaining);
+                creases.insert(edge_key(edge_base + index, edge.end), remaining);
+            }
+        }
+
+        Self { vertices, faces, creases }
+    }
+}
+
+fn edge_key(a: usize, b: usize) -> (usize, usize) {
+    (a.min(b), a.max(b))
+}
+
+fn lerp(from: Vector3, to: Vector3, t: f64) -> Vector3 {
+    from + (to - from) * t
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_near(actual: Vector3, expected: Vector3) {
+        assert!((actual - expected).length() < 1e-9, "{actual:?} != {expected:?}");
+    }
+
+    #[test]
+    fn test_subdivide_cube() {
+        let cube = Mesh::create_unit_cube();
+        let smooth = cube.subdivide(1);
+        assert_eq!(smooth.vertex_count(), 8 + 12 + 6);
+        assert_eq!(smooth.face_count(), 24);
+        assert!(smooth.is_all_quads());
+        assert_eq!(smooth.edge_count(), 48);
+
+        // Corners pull in to 5/18 along each axis, face centres stay put
+        let corner = 5.0 / 18.0;
+        assert_near(smooth.vertices[6], Vector3::new(corner, corner, corner));
+        assert_near(smooth.vertices[8 + 12 + 1], Vector3::new(0.0, 0.0, 0.5));
+
+        let mut stored = cube.clone();
+        stored.subdivision_level = 2;
+        assert_eq!(stored.subdivided().face_count(), 96);
+        assert_eq!(cube.subdivided().faces, cube.faces);
+    }
+
+    #[test]
+    fn test_subdivide_creased_cube() {
+        let mut cube = Mesh::create_unit_cube();
+        cube.compute_edges();
+        for edge in &mut cube.edges {
+            edge.set_crease(-1.0);
+        }
+
+        let smooth = cube.subdivide(2);
+        assert_near(smooth.vertices[6], Vector3::new(0.5, 0.5, 0.5));
+        assert!(smooth.vertices.iter().all(|v| v.x.abs().max(v.y.abs()).max(v.z.abs()) > 0.5 - 1e-9));
+        assert!(smooth.edges.iter().filter(|e| e.crease == Some(-1.0)).count() == 12 * 4);
+
+        // A crease of 1 is sharp for the first level only
+        for edge in &mut cube.edges {
+            edge.set_crease(1.0);
+        }
+        let once = cube.subdivide(1);
+        assert_near(once.vertices[6], Vector3::new(0.5, 0.5, 0.5));
+        assert!(once.edges.iter().all(|e| e.crease.is_none()));
+    }
+}
@@ -10,11 +10,16 @@ pub mod ellipse;
 pub mod polyline;
 pub mod polyline3d;
 pub mod lwpolyline;
+mod polyline_convert;
+mod polyline_fit;
 pub mod text;
 pub mod mtext;
 pub mod spline;
+mod spline_edit;
 pub mod dimension;
+mod dimension_associativity;
 pub mod hatch;
+mod hatch_fill;
 pub mod solid;
 pub mod face3d;
 pub mod insert;
@@ -28,12 +33,16 @@ pub mod leader;
 pub mod multileader;
 pub mod mline;
 pub mod mesh;
+mod mesh_subdivision;
 pub mod raster_image;
 pub mod solid3d;
 pub mod table;
+mod table_block;
 pub mod tolerance;
+pub mod tolerance_frame;
 pub mod polyface_mesh;
 pub mod wipeout;
+mod triangles;
 pub mod shape;
 pub mod underlay;
 pub mod seqend;
@@ -50,12 +59,14 @@ pub use polyline::{Polyline, Polyline2D, Vertex2D, Vertex3D, PolylineFlags, Vert
 pub use polyline3d::{Polyline3D, Vertex3DPolyline, Polyline3DFlags};
 pub use lwpolyline::{LwPolyline, LwVertex};
 pub use text::{Text, TextHorizontalAlignment, TextVerticalAlignment};
-pub use mtext::{MText, AttachmentPoint, DrawingDirection};
+pub use mtext::{MText, AttachmentPoint, BackgroundFillFlags, ColumnType, DrawingDirection, MTextColumns};
 pub use spline::{Spline, SplineFlags};
 pub use dimension::*;
 pub use hatch::*;
 pub use solid::Solid;
 pub use face3d::{Face3D, InvisibleEdgeFlags};
+pub use triangles::Triangle;
+pub(crate) use triangles::outline;
 pub use insert::Insert;
 pub use block::{Block, BlockEnd};
 pub use ray::Ray;
@@ -94,6 +105,10 @@ pub use table::{
     BorderPropertyFlags, ContentLayoutFlags, BreakOptionFlags,
 };
 pub use tolerance::{Tolerance, gdt_symbols};
+pub use tolerance_frame::{
+    DatumReference, FeatureControlFrame, FeatureControlFrameBuilder, FrameRow, GdtSymbol,
+    MaterialCondition, ToleranceValue,
+};
 pub use polyface_mesh::{
     PolyfaceMesh, PolyfaceVertex, PolyfaceFace,
     PolyfaceMeshFlags, PolyfaceVertexFlags, PolyfaceSmoothType,
@@ -195,6 +210,7 @@ pub trait Entity {
 
 /// Common entity data shared by all entities
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EntityCommon {
     /// Unique handle
     pub handle: Handle,
@@ -251,7 +267,12 @@ impl Default for EntityCommon {
 }
 
 /// Enumeration of all entity types for type-safe storage
+///
+/// `MText`, `Spline`, `Dimension`, `Hatch`, `Viewport`, `AttributeDefinition`,
+/// `AttributeEntity`, `Leader`, `MultiLeader`, `RasterImage` and `Table`
+/// are boxed.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum EntityType {
     /// Point entity
     Point(Point),
@@ -274,13 +295,13 @@ pub enum EntityType {
     /// Text entity
     Text(Text),
     /// Multi-line text entity
-    MText(MText),
+    MText(Box<MText>),
     /// Spline entity
-    Spline(Spline),
+    Spline(Box<Spline>),
     /// Dimension entity
-    Dimension(Dimension),
+    Dimension(Box<Dimension>),
     /// Hatch entity
-    Hatch(Hatch),
+    Hatch(Box<Hatch>),
     /// Solid entity
     Solid(Solid),
     /// 3D Face entity
@@ -296,21 +317,21 @@ pub enum EntityType {
     /// XLine entity (construction line, infinite)
     XLine(XLine),
     /// Viewport entity (paper space viewport)
-    Viewport(Viewport),
+    Viewport(Box<Viewport>),
     /// Attribute definition entity
-    AttributeDefinition(AttributeDefinition),
+    AttributeDefinition(Box<AttributeDefinition>),
     /// Attribute entity (block attribute instance)
-    AttributeEntity(AttributeEntity),
+    AttributeEntity(Box<AttributeEntity>),
     /// Leader entity
-    Leader(Leader),
+    Leader(Box<Leader>),
     /// MultiLeader entity
-    MultiLeader(MultiLeader),
+    MultiLeader(Box<MultiLeader>),
     /// MLine (multiline) entity
     MLine(MLine),
     /// Mesh entity
     Mesh(Mesh),
     /// RasterImage entity
-    RasterImage(RasterImage),
+    RasterImage(Box<RasterImage>),
     /// Solid3D entity
     Solid3D(Solid3D),
     /// Region entity
@@ -318,7 +339,7 @@ pub enum EntityType {
     /// Body entity
     Body(Body),
     /// Table entity
-    Table(Table),
+    Table(Box<Table>),
     /// Tolerance entity (geometric tolerancing)
     Tolerance(Tolerance),
     /// PolyfaceMesh entity
@@ -353,10 +374,10 @@ impl EntityType {
             EntityType::Polyline3D(e) => e,
             EntityType::LwPolyline(e) => e,
             EntityType::Text(e) => e,
-            EntityType::MText(e) => e,
-            EntityType::Spline(e) => e,
-            EntityType::Dimension(e) => e,
-            EntityType::Hatch(e) => e,
+            EntityType::MText(e) => e.as_ref(),
+            EntityType::Spline(e) => e.as_ref(),
+            EntityType::Dimension(e) => e.as_ref(),
+            EntityType::Hatch(e) => e.as_ref(),
             EntityType::Solid(e) => e,
             EntityType::Face3D(e) => e,
             EntityType::Insert(e) => e,
@@ -364,18 +385,18 @@ impl EntityType {
             EntityType::BlockEnd(e) => e,
             EntityType::Ray(e) => e,
             EntityType::XLine(e) => e,
-            EntityType::Viewport(e) => e,
-            EntityType::AttributeDefinition(e) => e,
-            EntityType::AttributeEntity(e) => e,
-            EntityType::Leader(e) => e,
-            EntityType::MultiLeader(e) => e,
+            EntityType::Viewport(e) => e.as_ref(),
+            EntityType::AttributeDefinition(e) => e.as_ref(),
+            EntityType::AttributeEntity(e) => e.as_ref(),
+            EntityType::Leader(e) => e.as_ref(),
+            EntityType::MultiLeader(e) => e.as_ref(),
             EntityType::MLine(e) => e,
             EntityType::Mesh(e) => e,
-            EntityType::RasterImage(e) => e,
+            EntityType::RasterImage(e) => e.as_ref(),
             EntityType::Solid3D(e) => e,
             EntityType::Region(e) => e,
             EntityType::Body(e) => e,
-            EntityType::Table(e) => e,
+            EntityType::Table(e) => e.as_ref(),
             EntityType::Tolerance(e) => e,
             EntityType::PolyfaceMesh(e) => e,
             EntityType::Wipeout(e) => e,
@@ -400,11 +421,11 @@ impl EntityType {
             EntityType::Polyline2D(e) => e,
             EntityType::Polyline3D(e) => e,
             EntityType::LwPolyline(e) => e,
-            EntityType::MText(e) => e,
+            EntityType::MText(e) => e.as_mut(),
             EntityType::Text(e) => e,
-            EntityType::Spline(e) => e,
-            EntityType::Dimension(e) => e,
-            EntityType::Hatch(e) => e,
+            EntityType::Spline(e) => e.as_mut(),
+            EntityType::Dimension(e) => e.as_mut(),
+            EntityType::Hatch(e) => e.as_mut(),
             EntityType::Solid(e) => e,
             EntityType::Face3D(e) => e,
             EntityType::Insert(e) => e,
@@ -412,18 +433,18 @@ impl EntityType {
             EntityType::BlockEnd(e) => e,
             EntityType::Ray(e) => e,
             EntityType::XLine(e) => e,
-            EntityType::Viewport(e) => e,
-            EntityType::AttributeDefinition(e) => e,
-            EntityType::AttributeEntity(e) => e,
-            EntityType::Leader(e) => e,
-            EntityType::MultiLeader(e) => e,
+            EntityType::Viewport(e) => e.as_mut(),
+            EntityType::AttributeDefinition(e) => e.as_mut(),
+            EntityType::AttributeEntity(e) => e.as_mut(),
+            EntityType::Leader(e) => e.as_mut(),
+            EntityType::MultiLeader(e) => e.as_mut(),
             EntityType::MLine(e) => e,
             EntityType::Mesh(e) => e,
-            EntityType::RasterImage(e) => e,
+            EntityType::RasterImage(e) => e.as_mut(),
             EntityType::Solid3D(e) => e,
             EntityType::Region(e) => e,
             EntityType::Body(e) => e,
-            EntityType::Table(e) => e,
+            EntityType::Table(e) => e.as_mut(),
             EntityType::Tolerance(e) => e,
             EntityType::PolyfaceMesh(e) => e,
             EntityType::Wipeout(e) => e,
@@ -483,5 +504,64 @@ impl EntityType {
             EntityType::Unknown(e) => &e.common,
         }
     }
+
+    /// Get a mutable reference to the entity's common data
+    pub fn common_mut(&mut self) -> &mut EntityCommon {
+        match self {
+            EntityType::Point(e) => &mut e.common,
+            EntityType::Line(e) => &mut e.common,
+            EntityType::Circle(e) => &mut e.common,
+            EntityType::Arc(e) => &mut e.common,
+            EntityType::Ellipse(e) => &mut e.common,
+            EntityType::Polyline(e) => &mut e.common,
+            EntityType::Polyline2D(e) => &mut e.common,
+            EntityType::Polyline3D(e) => &mut e.common,
+            EntityType::LwPolyline(e) => &mut e.common,
+            EntityType::Text(e) => &mut e.common,
+            EntityType::MText(e) => &mut e.common,
+            EntityType::Spline(e) => &mut e.common,
+            EntityType::Dimension(e) => &mut e.base_mut().common,
+            EntityType::Hatch(e) => &mut e.common,
+            EntityType::Solid(e) => &mut e.common,
+            EntityType::Face3D(e) => &mut e.common,
+            EntityType::Insert(e) => &mut e.common,
+            EntityType::Block(e) => &mut e.common,
+            EntityType::BlockEnd(e) => &mut e.common,
+            EntityType::Ray(e) => &mut e.common,
+            EntityType::XLine(e) => &mut e.common,
+            EntityType::Viewport(e) => &mut e.common,
+            EntityType::AttributeDefinition(e) => &mut e.common,
+            EntityType::AttributeEntity(e) => &mut e.common,
+            EntityType::Leader(e) => &mut e.common,
+            EntityType::MultiLeader(e) => &mut e.common,
+            EntityType::MLine(e) => &mut e.common,
+            EntityType::Mesh(e) => &mut e.common,
+            EntityType::RasterImage(e) => &mut e.common,
+            EntityType::Solid3D(e) => &mut e.common,
+            EntityType::Region(e) => &mut e.common,
+            EntityType::Body(e) => &mut e.common,
+            EntityType::Table(e) => &mut e.common,
+            EntityType::Tolerance(e) => &mut e.common,
+            EntityType::PolyfaceMesh(e) => &mut e.common,
+            EntityType::Wipeout(e) => &mut e.common,
+            EntityType::Shape(e) => &mut e.common,
+            EntityType::Underlay(e) => &mut e.common,
+            EntityType::Seqend(e) => &mut e.common,
+            EntityType::Ole2Frame(e) => &mut e.common,
+            EntityType::PolygonMesh(e) => &mut e.common,
+            EntityType::Unknown(e) => &mut e.common,
+        }
+    }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entity_type_size() {
+        // Boxed variants keep the enum near the size of the largest inline type
+        assert!(std::mem::size_of::<EntityType>() <= std::mem::size_of::<Text>() + 8);
+        assert!(std::mem::size_of::<EntityType>() < std::mem::size_of::<MultiLeader>());
+    }
+}
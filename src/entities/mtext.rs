@@ -1,10 +1,12 @@
 //! Multi-line text entity
 
 use super::{Entity, EntityCommon};
-use crate::types::{BoundingBox3D, Color, Handle, LineWeight, Transparency, Vector3};
+use crate::types::{Angle, BoundingBox3D, Color, Handle, LineWeight, Transparency, Vector3};
+use bitflags::bitflags;
 
 /// Attachment point for MText
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum AttachmentPoint {
     /// Top left
     TopLeft,
@@ -28,6 +30,7 @@ pub enum AttachmentPoint {
 
 /// Drawing direction for MText
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DrawingDirection {
     /// Left to right
     LeftToRight,
@@ -37,8 +40,76 @@ pub enum DrawingDirection {
     ByStyle,
 }
 
+bitflags! {
+    /// Background fill settings of an MText (DXF code 90)
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct BackgroundFillFlags: i32 {
+        /// No background.
+        const NONE = 0x0;
+        /// Fill with the background color.
+        const USE_BACKGROUND_FILL = 0x1;
+        /// Fill with the drawing window color.
+        const USE_WINDOW_COLOR = 0x2;
+        /// Frame around the text (R2018+).
+        const TEXT_FRAME = 0x10;
+    }
+}
+
+/// Column layout of an MText (DXF code 75)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ColumnType {
+    /// Single column
+    #[default]
+    NoColumns = 0,
+    /// Columns of the same height, broken with `\N`
+    Static = 1,
+    /// Text flows from column to column
+    Dynamic = 2,
+}
+
+impl ColumnType {
+    /// Create from DXF value
+    pub fn from_value(value: i16) -> Self {
+        match value {
+            1 => ColumnType::Static,
+            2 => ColumnType::Dynamic,
+            _ => ColumnType::NoColumns,
+        }
+    }
+}
+
+/// Columns of an MText
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MTextColumns {
+    /// Column layout
+    /// DXF code: 75
+    pub column_type: ColumnType,
+    /// Number of columns
+    /// DXF code: 76
+    pub count: i32,
+    /// Column width
+    /// DXF code: 48
+    pub width: f64,
+    /// Space between columns
+    /// DXF code: 49
+    pub gutter: f64,
+    /// Columns take the height of the text
+    /// DXF code: 79
+    pub auto_height: bool,
+    /// Columns run right to left
+    /// DXF code: 78
+    pub flow_reversed: bool,
+    /// Height of each column, for dynamic columns without auto height
+    /// DXF code: 50
+    pub heights: Vec<f64>,
+}
+
 /// A multi-line text entity
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MText {
     /// Common entity data
     pub common: EntityCommon,
@@ -64,6 +135,16 @@ pub struct MText {
     pub line_spacing_factor: f64,
     /// Normal vector
     pub normal: Vector3,
+    /// Background fill settings
+    pub background_fill: BackgroundFillFlags,
+    /// Background fill color
+    pub background_color: Color,
+    /// Size of the fill box relative to the text height
+    pub background_scale: f64,
+    /// Transparency of the background fill
+    pub background_transparency: Transparency,
+    /// Column layout
+    pub columns: MTextColumns,
 }
 
 impl MText {
@@ -82,6 +163,11 @@ impl MText {
             drawing_direction: DrawingDirection::LeftToRight,
             line_spacing_factor: 1.0,
             normal: Vector3::UNIT_Z,
+            background_fill: BackgroundFillFlags::NONE,
+            background_color: Color::ByBlock,
+            background_scale: 1.5,
+            background_transparency: Transparency::OPAQUE,
+            columns: MTextColumns::default(),
         }
     }
 
@@ -105,6 +191,38 @@ impl MText {
         self.rectangle_width = width;
         self
     }
+
+    /// Rotation angle
+    pub fn rotation(&self) -> Angle {
+        Angle::from_radians(self.rotation)
+    }
+
+    /// Set the rotation angle
+    pub fn set_rotation(&mut self, angle: Angle) {
+        self.rotation = angle.radians();
+    }
+
+    /// Whether the text has a background fill
+    pub fn has_background_fill(&self) -> bool {
+        self.background_fill
+            .intersects(BackgroundFillFlags::USE_BACKGROUND_FILL | BackgroundFillFlags::USE_WINDOW_COLOR)
+    }
+
+    /// Text of each column: the value split at the `\N` column breaks
+    pub fn column_values(&self) -> Vec<&str> {
+        let mut columns = Vec::new();
+        let mut start = 0;
+        let mut chars = self.value.char_indices();
+        while let Some((i, c)) = chars.next() {
+            // Skipping the character after a backslash also skips `\\`
+            if c == '\\' && chars.next().is_some_and(|(_, next)| next == 'N') {
+                columns.push(&self.value[start..i]);
+                start = i + 2;
+            }
+        }
+        columns.push(&self.value[start..]);
+        columns
+    }
 }
 
 impl Default for MText {
@@ -1,10 +1,12 @@
 //! Polyline entities (2D and 3D polylines)
 
+use super::lwpolyline::{bulge_area, bulge_length};
 use super::{Entity, EntityCommon};
 use crate::types::{BoundingBox3D, Color, Handle, LineWeight, Transparency, Vector2, Vector3};
 
 /// Polyline flags (matches DXF group code 70)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PolylineFlags {
     bits: u16,
 }
@@ -75,6 +77,7 @@ impl std::ops::BitOrAssign for PolylineFlags {
 
 /// Vertex flags (matches DXF group code 70)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct VertexFlags {
     bits: u8,
 }
@@ -103,6 +106,7 @@ impl VertexFlags {
 
 /// Smooth surface type (matches DXF group code 75)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SmoothSurfaceType {
     #[default]
     None = 0,
@@ -124,7 +128,10 @@ impl From<i16> for SmoothSurfaceType {
 
 /// A vertex in a 2D polyline
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Vertex2D {
+    /// Handle of the VERTEX entity (NULL until written or read)
+    pub handle: Handle,
     /// Location of the vertex (X, Y in OCS, Z is elevation)
     pub location: Vector3,
     /// Vertex flags
@@ -144,6 +151,7 @@ pub struct Vertex2D {
 impl Vertex2D {
     pub fn new(location: Vector3) -> Self {
         Self {
+            handle: Handle::NULL,
             location,
             flags: VertexFlags::new(),
             start_width: 0.0,
@@ -172,7 +180,10 @@ impl Vertex2D {
 
 /// A vertex in a 3D polyline
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Vertex3D {
+    /// Handle of the VERTEX entity (NULL until written or read)
+    pub handle: Handle,
     /// Location of the vertex
     pub location: Vector3,
     /// Vertex flags
@@ -183,6 +194,7 @@ impl Vertex3D {
     /// Create a new vertex
     pub fn new(location: Vector3) -> Self {
         Self {
+            handle: Handle::NULL,
             location,
             flags: VertexFlags::new(),
         }
@@ -196,6 +208,7 @@ impl Vertex3D {
 
 /// A 2D polyline entity (heavy polyline with vertices)
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Polyline2D {
     /// Common entity data
     pub common: EntityCommon,
@@ -215,6 +228,8 @@ pub struct Polyline2D {
     pub normal: Vector3,
     /// Vertices
     pub vertices: Vec<Vertex2D>,
+    /// SEQEND handle (for DXF/DWG compatibility)
+    pub seqend_handle: Option<Handle>,
 }
 
 impl Polyline2D {
@@ -229,6 +244,7 @@ impl Polyline2D {
             elevation: 0.0,
             normal: Vector3::new(0.0, 0.0, 1.0),
             vertices: Vec::new(),
+            seqend_handle: None,
         }
     }
     
@@ -243,6 +259,23 @@ impl Polyline2D {
     pub fn close(&mut self) {
         self.flags.set_closed(true);
     }
+
+    /// Length along the segments and bulge arcs
+    pub fn length(&self) -> f64 {
+        bulge_length(&self.bulge_vertices(), self.is_closed())
+    }
+
+    /// Area enclosed by the polyline as if it were closed
+    pub fn area(&self) -> f64 {
+        bulge_area(&self.bulge_vertices())
+    }
+
+    fn bulge_vertices(&self) -> Vec<(Vector2, f64)> {
+        self.vertices
+            .iter()
+            .map(|v| (Vector2::new(v.location.x, v.location.y), v.bulge))
+            .collect()
+    }
 }
 
 impl Default for Polyline2D {
@@ -253,6 +286,7 @@ impl Default for Polyline2D {
 
 /// A 3D polyline entity
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Polyline {
     /// Common entity data
     pub common: EntityCommon,
@@ -260,6 +294,8 @@ pub struct Polyline {
     pub flags: PolylineFlags,
     /// Vertices of the polyline
     pub vertices: Vec<Vertex3D>,
+    /// SEQEND handle (for DXF/DWG compatibility)
+    pub seqend_handle: Option<Handle>,
 }
 
 impl Polyline {
@@ -271,6 +307,7 @@ impl Polyline {
             common: EntityCommon::new(),
             flags,
             vertices: Vec::new(),
+            seqend_handle: None,
         }
     }
 
@@ -306,6 +343,15 @@ impl Polyline {
     pub fn close(&mut self) {
         self.flags.set_closed(true);
     }
+
+    /// Total length of the segments
+    pub fn length(&self) -> f64 {
+        let n = self.vertices.len();
+        let segments = if self.is_closed() { n } else { n.saturating_sub(1) };
+        (0..segments)
+            .map(|i| self.vertices[i].location.distance(&self.vertices[(i + 1) % n].location))
+            .sum()
+    }
 }
 
 impl Default for Polyline {
@@ -0,0 +1,271 @@
+//! Conversions between lightweight, 2D and 3D polylines
+//!
+//! - [`LwPolyline`] ↔ [`Polyline2D`] keeps vertices, bulges, widths,
+//!   elevation, thickness and normal. Curve fit polylines become their
+//!   tangent arcs and spline fit ones the chords of their curve, as
+//!   LWPOLYLINE has no fit.
+//! - [`Polyline2D`] → [`Polyline3D`] moves the points of the drawn curve
+//!   from OCS to WCS; bulge arcs are divided into chords.
+//! - [`Polyline3D`] → [`Polyline2D`] works for planar polylines only.
+//!
+//! The result carries the common entity data, handle included, so it can
+//! replace the original in the document.
+
+use super::lwpolyline::{LwPolyline, LwVertex};
+use super::polyline::{PolylineFlags, Polyline2D, Vertex2D, VertexFlags};
+use super::polyline3d::{Polyline3D, Vertex3DPolyline};
+use super::polyline_fit::{curve_fit, is_curve_fit};
+use crate::types::geometry::ocs_matrix;
+use crate::types::{Vector2, Vector3};
+
+impl LwPolyline {
+    /// Heavy 2D polyline with the same vertices, bulges and widths
+    pub fn to_polyline_2d(&self) -> Polyline2D {
+        let mut flags = PolylineFlags::new();
+        flags.set_closed(self.is_closed);
+        Polyline2D {
+            common: self.common.clone(),
+            flags,
+            start_width: self.constant_width,
+            end_width: self.constant_width,
+            thickness: self.thickness,
+            elevation: self.elevation,
+            normal: self.normal,
+            vertices: self
+                .vertices
+                .iter()
+                .map(|v| {
+                    Vertex2D::new(Vector3::new(v.location.x, v.location.y, self.elevation))
+                        .with_bulge(v.bulge)
+                        .with_width(v.start_width, v.end_width)
+                })
+                .collect(),
+            ..Polyline2D::new()
+        }
+    }
+
+    /// 3D polyline through the polyline in WCS, with bulge arcs divided
+    /// into chords no farther than `tolerance` from the arc; widths and
+    /// thickness are dropped
+    pub fn to_polyline_3d(&self, tolerance: f64) -> Polyline3D {
+        self.to_polyline_2d().to_polyline_3d(tolerance)
+    }
+}
+
+impl Polyline2D {
+    /// Lightweight polyline drawn the same way
+    ///
+    /// Vertex widths of 0 take the polyline's default widths; when no
+    /// vertex has its own, equal default widths become the constant width.
+    pub fn to_lwpolyline(&self) -> LwPolyline {
+        let has = |flag: VertexFlags| self.vertices.iter().any(|v| v.flags.bits() & flag.bits() != 0);
+        let width = |own: f64, default: f64| if own != 0.0 { own } else { default };
+        let vertex = |v: &Vertex2D| LwVertex {
+            start_width: width(v.start_width, self.start_width),
+            end_width: width(v.end_width, self.end_width),
+            ..LwVertex::with_bulge(Vector2::new(v.location.x, v.location.y), v.bulge)
+        };
+        let plain = |location: Vector2, bulge: f64| LwVertex {
+            start_width: self.start_width,
+            end_width: self.end_width,
+            ..LwVertex::with_bulge(location, bulge)
+        };
+
+        let mut vertices: Vec<LwVertex> = if let Some(points) = self.spline_fit_points() {
+            points
+                .into_iter()
+                .map(|p| plain(Vector2::new(p.x, p.y), 0.0))
+                .collect()
+        } else if is_curve_fit(self.flags) && !has(VertexFlags::EXTRA_VERTEX) {
+            curve_fit(&self.vertices, self.is_closed())
+                .into_iter()
+                .map(|(location, bulge)| plain(location, bulge))
+                .collect()
+        } else {
+            self.vertices.iter().map(vertex).collect()
+        };
+
+        let own_widths = self.vertices.iter().any(|v| v.start_width != 0.0 || v.end_width != 0.0);
+        let constant_width = if self.start_width == self.end_width && !own_widths {
+            for v in &mut vertices {
+                v.start_width = 0.0;
+                v.end_width = 0.0;
+            }
+            self.start_width
+        } else {
+            0.0
+        };
+        LwPolyline {
+            common: self.common.clone(),
+            vertices,
+            is_closed: self.is_closed(),
+            constant_width,
+            elevation: self.elevation,
+            thickness: self.thickness,
+            normal: self.normal,
+        }
+    }
+
+    /// 3D polyline through the curve the polyline is drawn as, in WCS,
+    /// with bulge arcs divided into chords no farther than `tolerance` from
+    /// the arc; widths and thickness are dropped
+    pub fn to_polyline_3d(&self, tolerance: f64) -> Polyline3D {
+        let to_wcs = ocs_matrix(self.normal);
+        let mut polyline = Polyline3D {
+            common: self.common.clone(),
+            ..Polyline3D::new()
+        };
+        polyline.flags.closed = self.is_closed();
+        polyline.vertices = self
+            .smoothed_points(tolerance)
+            .into_iter()
+            .map(|p| Vertex3DPolyline {
+                layer: self.common.layer.clone(),
+                ..Vertex3DPolyline::new(to_wcs.transform_point(p))
+            })
+            .collect();
+        polyline
+    }
+}
+
+impl Polyline3D {
+    /// 2D polyline in the plane of the polyline, or `None` when its points
+    /// are farther than `tolerance` from a common plane
+    ///
+    /// A polyline parallel to the XY plane keeps the Z axis as its normal.
+    /// Spline fit polylines become the chords of their curve.
+    pub fn to_polyline_2d(&self, tolerance: f64) -> Option<Polyline2D> {
+        let points = self.smoothed_points();
+        let normal = plane_normal(&points, tolerance)?;
+        let to_ocs = ocs_matrix(normal).transpose();
+        let points: Vec<Vector3> = points.into_iter().map(|p| to_ocs.transform_point(p)).collect();
+        let elevation = points.first().map_or(0.0, |p| p.z);
+
+        let mut flags = PolylineFlags::new();
+        flags.set_closed(self.is_closed());
+        Some(Polyline2D {
+            common: self.common.clone(),
+            flags,
+            elevation,
+            normal,
+            vertices: points
+                .into_iter()
+                .map(|p| Vertex2D::new(Vector3::new(p.x, p.y, elevation)))
+                .collect(),
+            ..Polyline2D::new()
+        })
+    }
+
+    /// Lightweight polyline in the plane of the polyline, or `None` when it
+    /// is not planar within `tolerance`
+    pub fn to_lwpolyline(&self, tolerance: f64) -> Option<LwPolyline> {
+        self.to_polyline_2d(tolerance).map(|polyline| polyline.to_lwpolyline())
+    }
+}
+
+/// Normal of the plane through `points`, pointing up where it can, or
+/// `None` if a point is farther than `tolerance` from it
+fn plane_normal(points: &[Vector3], tolerance: f64) -> Option<Vector3> {
+    let Some(&origin) = points.first() else {
+        return Some(Vector3::UNIT_Z);
+    };
+    let within = |normal: Vector3| points.iter().all(|p| (*p - origin).dot(&normal).abs() <= tolerance);
+    if within(Vector3::UNIT_Z) {
+        return Some(Vector3::UNIT_Z);
+    }
+
+    // Newell's method, then the plane through the line for collinear points
+    let n = points.len();
+    let mut normal = (0..n).fold(Vector3::ZERO, |sum, i| sum + points[i].cross(&points[(i + 1) % n]));
+    if normal.length() < 1e-12 {
+        let far = points.iter().max_by(|a, b| a.distance(&origin).total_cmp(&b.distance(&origin)))?;
+        let direction = (*far - origin).normalize();
+        normal = Vector3::UNIT_Z - direction * direction.z;
+        if normal.length() < 1e-12 {
+            normal = Vector3::UNIT_X;
+        }
+    }
+    let normal = normal.normalize();
+    let normal = if normal.z < 0.0 { -normal } else { normal };
+    within(normal).then_some(normal)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slot() -> LwPolyline {
+        let mut slot = LwPolyline::new();
+        slot.add_point(Vector2::new(0.0, 0.0));
+        slot.add_point_with_bulge(Vector2::new(4.0, 0.0), 1.0);
+        slot.add_point(Vector2::new(4.0, 2.0));
+        slot.add_point_with_bulge(Vector2::new(0.0, 2.0), 1.0);
+        slot.is_closed = true;
+        slot.elevation = 3.0;
+        slot.thickness = 0.5;
+        slot.constant_width = 0.25;
+        slot.common.layer = "SLOTS".to_string();
+        slot
+    }
+
+    #[test]
+    fn test_lwpolyline_round_trip() {
+        let slot = slot();
+        let heavy = slot.to_polyline_2d();
+        assert!(heavy.is_closed());
+        assert_eq!(heavy.vertices[1].bulge, 1.0);
+        assert_eq!(heavy.vertices[2].location, Vector3::new(4.0, 2.0, 3.0));
+        assert_eq!(heavy.start_width, 0.25);
+
+        let back = heavy.to_lwpolyline();
+        assert_eq!(back.vertices, slot.vertices);
+        assert_eq!(back.constant_width, 0.25);
+        assert_eq!(back.elevation, 3.0);
+        assert_eq!(back.thickness, 0.5);
+        assert_eq!(back.common.layer, "SLOTS");
+
+        // Tapered segments keep their widths per vertex
+        let mut tapered = heavy.clone();
+        tapered.vertices[0] = tapered.vertices[0].clone().with_width(1.0, 0.0);
+        let lw = tapered.to_lwpolyline();
+        assert_eq!(lw.constant_width, 0.0);
+        assert_eq!((lw.vertices[0].start_width, lw.vertices[0].end_width), (1.0, 0.25));
+        assert_eq!(lw.vertices[1].start_width, 0.25);
+    }
+
+    #[test]
+    fn test_curve_fit_keeps_arcs() {
+        let mut fitted = Polyline2D::new();
+        for (x, y) in [(0.0, 0.0), (5.0, 5.0), (10.0, 0.0)] {
+            fitted.add_vertex(Vertex2D::from_point(Vector2::new(x, y)));
+        }
+        fitted.flags |= PolylineFlags::CURVE_FIT;
+        let lw = fitted.to_lwpolyline();
+        assert!(lw.vertices.len() > 3);
+        assert!(lw.vertices.iter().any(|v| v.bulge != 0.0));
+        assert_eq!(lw.vertices.last().unwrap().location, Vector2::new(10.0, 0.0));
+    }
+
+    #[test]
+    fn test_polyline_3d_round_trip() {
+        let mut slot = slot();
+        slot.normal = Vector3::new(1.0, 0.0, 0.0);
+        let polyline = slot.to_polyline_3d(0.02);
+        assert!(polyline.is_closed());
+        // Two half circles of 8 chords each between the straight sides
+        assert_eq!(polyline.vertex_count(), 18);
+        assert!(polyline.vertices.iter().all(|v| (v.position.x - 3.0).abs() < 1e-12));
+        assert!((polyline.length() - (8.0 + 32.0 * (std::f64::consts::PI / 16.0).sin())).abs() < 1e-9);
+
+        let flat = polyline.to_lwpolyline(1e-9).unwrap();
+        assert_eq!(flat.normal, Vector3::UNIT_X);
+        assert!((flat.elevation - 3.0).abs() < 1e-12);
+        assert!((flat.area() - polyline.to_polyline_2d(1e-9).unwrap().area()).abs() < 1e-12);
+        assert!((flat.area() - (8.0 + 16.0 * (std::f64::consts::PI / 8.0).sin() / 2.0)).abs() < 1e-9);
+
+        let mut bent = polyline.clone();
+        bent.vertices[1].position.x += 0.1;
+        assert!(bent.to_polyline_2d(1e-3).is_none());
+        assert!(bent.to_polyline_2d(0.2).is_some());
+    }
+}
@@ -0,0 +1,638 @@
+//! Polyline simplification and fit smoothing
+//!
+//! `smoothed_points` evaluates the curve AutoCAD draws for a polyline:
+//!
+//! - spline fit (flag 4): a B-spline with the polyline's vertices as
+//!   control frame, quadratic or cubic after its smooth surface type, or a
+//!   single Bezier curve; fitted vertices (flag 8) stored with the frame
+//!   are used as they are
+//! - curve fit (flag 2): a pair of tangent arcs between each pair of
+//!   vertices, with the vertex tangents of flag 2 vertices or else the
+//!   direction through their neighbours; stored extra vertices (flag 1)
+//!   and their bulges are used as they are
+//! - otherwise the vertices with their bulge arcs
+//!
+//! Spline spans are divided into [`SEGMENTS`] chords, the `$SPLINESEGS`
+//! default, and bulge arcs into chords within a tolerance. Closed
+//! polylines do not repeat their first point.
+//!
+//! `simplify` removes vertices within a distance of the line through their
+//! neighbours (Douglas–Peucker). Bulged segments are kept; fitted
+//! polylines are replaced by their simplified smoothed points.
+
+use super::lwpolyline::LwPolyline;
+use super::polyline::{
+    Polyline, Polyline2D, PolylineFlags, SmoothSurfaceType, Vertex2D, Vertex3D, VertexFlags,
+};
+use super::polyline3d::{self, Polyline3D, Vertex3DPolyline};
+use crate::render::tessellate::bulge_polyline;
+use crate::types::{Vector2, Vector3};
+
+/// Chords per spline span
+const SEGMENTS: usize = 8;
+
+/// Curve of a spline fit polyline
+#[derive(Clone, Copy)]
+enum SplineKind {
+    BSpline(usize),
+    Bezier,
+}
+
+impl From<SmoothSurfaceType> for SplineKind {
+    fn from(smooth: SmoothSurfaceType) -> Self {
+        match smooth {
+            SmoothSurfaceType::QuadraticBSpline => SplineKind::BSpline(2),
+            SmoothSurfaceType::Bezier => SplineKind::Bezier,
+            _ => SplineKind::BSpline(3),
+        }
+    }
+}
+
+impl From<polyline3d::SmoothSurfaceType> for SplineKind {
+    fn from(smooth: polyline3d::SmoothSurfaceType) -> Self {
+        SmoothSurfaceType::from(smooth.to_value()).into()
+    }
+}
+
+impl LwPolyline {
+    /// Removes vertices closer than `tolerance` to the line through the
+    /// vertices kept around them, keeping the ends of bulged segments.
+    ///
+    /// Returns the number of vertices removed.
+    pub fn simplify(&mut self, tolerance: f64) -> usize {
+        let points: Vec<Vector3> = self
+            .vertices
+            .iter()
+            .map(|v| Vector3::new(v.location.x, v.location.y, 0.0))
+            .collect();
+        let bulges: Vec<f64> = self.vertices.iter().map(|v| v.bulge).collect();
+        let keep = douglas_peucker(
+            &points,
+            &bulged_ends(&bulges, self.is_closed),
+            self.is_closed,
+            tolerance,
+        );
+        retain(&mut self.vertices, &keep)
+    }
+}
+
+impl Polyline2D {
+    /// Points along the curve the polyline is drawn as, honoring its
+    /// curve and spline fit, in OCS at the polyline's elevation
+    ///
+    /// Bulge arcs are divided into chords no farther than `tolerance` from
+    /// the arc.
+    pub fn smoothed_points(&self, tolerance: f64) -> Vec<Vector3> {
+        let closed = self.is_closed();
+        let at_elevation = |p: Vector2| Vector3::new(p.x, p.y, self.elevation);
+        let has = |flag: VertexFlags| {
+            self.vertices
+                .iter()
+                .any(|v| v.flags.bits() & flag.bits() != 0)
+        };
+        let bulged = |vertices: &[&Vertex2D]| {
+            let vertices: Vec<(Vector2, f64)> = vertices
+                .iter()
+                .map(|v| (planar(v.location), v.bulge))
+                .collect();
+            bulge_polyline(&vertices, closed, tolerance)
+                .into_iter()
+                .map(at_elevation)
+                .collect()
+        };
+
+        if let Some(points) = self.spline_fit_points() {
+            return points;
+        }
+        if is_curve_fit(self.flags) {
+            if has(VertexFlags::EXTRA_VERTEX) {
+                return bulged(&self.vertices.iter().collect::<Vec<_>>());
+            }
+            let vertices: Vec<(Vector2, f64)> = curve_fit(&self.vertices, closed);
+            return bulge_polyline(&vertices, closed, tolerance)
+                .into_iter()
+                .map(at_elevation)
+                .collect();
+        }
+        bulged(&self.vertices.iter().collect::<Vec<_>>())
+    }
+
+    /// Points along the curve of a spline fit polyline, in OCS at the
+    /// polyline's elevation, or `None` when it is not spline fit
+    pub(super) fn spline_fit_points(&self) -> Option<Vec<Vector3>> {
+        if !self.flags.is_spline_fit() {
+            return None;
+        }
+        let at_elevation = |v: &Vertex2D| Vector3::new(v.location.x, v.location.y, self.elevation);
+        let fitted: Vec<Vector3> = self
+            .vertices
+            .iter()
+            .filter(|v| v.flags.bits() & VertexFlags::SPLINE_VERTEX.bits() != 0)
+            .map(at_elevation)
+            .collect();
+        if !fitted.is_empty() {
+            return Some(fitted);
+        }
+        let frame: Vec<Vector3> = self.vertices.iter().map(at_elevation).collect();
+        Some(spline_fit(&frame, self.smooth_surface.into(), self.is_closed()))
+    }
+
+    /// Removes vertices closer than `tolerance` to the line through the
+    /// vertices kept around them, keeping the ends of bulged segments.
+    ///
+    /// A curve or spline fit polyline is first replaced by a plain
+    /// polyline through its smoothed points. Returns the number of
+    /// vertices removed.
+    pub fn simplify(&mut self, tolerance: f64) -> usize {
+        if self.flags.is_spline_fit() || is_curve_fit(self.flags) {
+            let points = self.smoothed_points(tolerance);
+            self.vertices = points.into_iter().map(Vertex2D::new).collect();
+            self.flags = PolylineFlags::from_bits(
+                self.flags.bits()
+                    & !(PolylineFlags::CURVE_FIT.bits() | PolylineFlags::SPLINE_FIT.bits()),
+            );
+            self.smooth_surface = SmoothSurfaceType::None;
+        }
+        let points: Vec<Vector3> = self
+            .vertices
+            .iter()
+            .map(|v| Vector3::new(v.location.x, v.location.y, 0.0))
+            .collect();
+        let bulges: Vec<f64> = self.vertices.iter().map(|v| v.bulge).collect();
+        let keep = douglas_peucker(
+            &points,
+            &bulged_ends(&bulges, self.is_closed()),
+            self.is_closed(),
+            tolerance,
+        );
+        retain(&mut self.vertices, &keep)
+    }
+}
+
+impl Polyline {
+    /// Points along the curve the polyline is drawn as, honoring its
+    /// spline fit, in WCS
+    pub fn smoothed_points(&self) -> Vec<Vector3> {
+        let spline_vertex = |v: &&Vertex3D| v.flags.bits() & VertexFlags::SPLINE_VERTEX.bits() != 0;
+        if !self.flags.is_spline_fit() {
+            return self.vertices.iter().map(|v| v.location).collect();
+        }
+        if self.vertices.iter().any(|v| spline_vertex(&v)) {
+            return self
+                .vertices
+                .iter()
+                .filter(spline_vertex)
+                .map(|v| v.location)
+                .collect();
+        }
+        let frame: Vec<Vector3> = self.vertices.iter().map(|v| v.location).collect();
+        spline_fit(&frame, SplineKind::BSpline(3), self.is_closed())
+    }
+
+    /// Removes vertices closer than `tolerance` to the line through the
+    /// vertices kept around them.
+    ///
+    /// A spline fit polyline is first replaced by a plain polyline through
+    /// its smoothed points. Returns the number of vertices removed.
+    pub fn simplify(&mut self, tolerance: f64) -> usize {
+        if self.flags.is_spline_fit() {
+            self.vertices = self
+                .smoothed_points()
+                .into_iter()
+                .map(Vertex3D::new)
+                .collect();
+            self.flags =
+                PolylineFlags::from_bits(self.flags.bits() & !PolylineFlags::SPLINE_FIT.bits());
+        }
+        let points: Vec<Vector3> = self.vertices.iter().map(|v| v.location).collect();
+        let keep = douglas_peucker(
+            &points,
+            &vec![false; points.len()],
+            self.is_closed(),
+            tolerance,
+        );
+        retain(&mut self.vertices, &keep)
+    }
+}
+
+impl Polyline3D {
+    /// Points along the curve the polyline is drawn as, honoring its
+    /// spline fit and smooth surface type, in WCS
+    pub fn smoothed_points(&self) -> Vec<Vector3> {
+        let spline_vertex =
+            |v: &&Vertex3DPolyline| v.flags & VertexFlags::SPLINE_VERTEX.bits() as i32 != 0;
+        if !self.flags.spline_fit {
+            return self.positions();
+        }
+        if self.vertices.iter().any(|v| spline_vertex(&v)) {
+            return self
+                .vertices
+                .iter()
+                .filter(spline_vertex)
+                .map(|v| v.position)
+                .collect();
+        }
+        spline_fit(&self.positions(), self.smooth_type.into(), self.is_closed())
+    }
+
+    /// Removes vertices closer than `tolerance` to the line through the
+    /// vertices kept around them.
+    ///
+    /// A spline fit polyline is first replaced by a plain polyline through
+    /// its smoothed points. Returns the number of vertices removed.
+    pub fn simplify(&mut self, tolerance: f64) -> usize {
+        if self.flags.spline_fit {
+            let layer = self.common.layer.clone();
+            self.vertices = self
+                .smoothed_points()
+                .into_iter()
+                .map(|p| Vertex3DPolyline {
+                    layer: layer.clone(),
+                    ..Vertex3DPolyline::new(p)
+                })
+                .collect();
+            self.flags.spline_fit = false;
+            self.smooth_type = polyline3d::SmoothSurfaceType::None;
+        }
+        let keep = douglas_peucker(
+            &self.positions(),
+            &vec![false; self.vertices.len()],
+            self.is_closed(),
+            tolerance,
+        );
+        retain(&mut self.vertices, &keep)
+    }
+}
+
+fn planar(p: Vector3) -> Vector2 {
+    Vector2::new(p.x, p.y)
+}
+
+pub(super) fn is_curve_fit(flags: PolylineFlags) -> bool {
+    flags.bits() & PolylineFlags::CURVE_FIT.bits() != 0
+}
+
+fn retain<T>(items: &mut Vec<T>, keep: &[bool]) -> usize {
+    let before = items.len();
+    let mut index = 0;
+    items.retain(|_| {
+        index += 1;
+        keep[index - 1]
+    });
+    before - items.len()
+}
+
+/// Vertices at either end of a bulged segment
+fn bulged_ends(bulges: &[f64], closed: bool) -> Vec<bool> {
+    let n = bulges.len();
+    (0..n)
+        .map(|i| {
+            let previous = if i > 0 {
+                Some(i - 1)
+            } else if closed {
+                n.checked_sub(1)
+            } else {
+                None
+            };
+            bulges[i].abs() > 1e-12 || previous.is_some_and(|p| bulges[p].abs() > 1e-12)
+        })
+        .collect()
+}
+
+/// Douglas–Peucker simplification: which of `points` to keep so that no
+/// removed point is farther than `tolerance` from the kept polyline.
+/// `fixed` points and the ends of an open polyline are always kept.
+fn douglas_peucker(points: &[Vector3], fixed: &[bool], closed: bool, tolerance: f64) -> Vec<bool> {
+    let n = points.len();
+    if n <= 2 {
+        return vec![true; n];
+    }
+    let mut ring = points.to_vec();
+    let mut keep: Vec<bool> = fixed.to_vec();
+    keep[0] = true;
+    if closed {
+        ring.push(points[0]);
+        keep.push(true);
+        // A second anchor, so the ring is not split at a single point
+        if !keep[1..n].contains(&true) {
+            let farthest = (1..n).max_by(|&a, &b| {
+                points[a]
+                    .distance(&points[0])
+                    .total_cmp(&points[b].distance(&points[0]))
+            });
+            keep[farthest.unwrap()] = true;
+        }
+    } else {
+        keep[n - 1] = true;
+    }
+
+    let anchors: Vec<usize> = (0..ring.len()).filter(|&i| keep[i]).collect();
+    let mut spans: Vec<(usize, usize)> = anchors.windows(2).map(|w| (w[0], w[1])).collect();
+    while let Some((start, end)) = spans.pop() {
+        let farthest = (start + 1..end)
+            .map(|i| (i, segment_distance(ring[i], ring[start], ring[end])))
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+        if let Some((i, distance)) = farthest {
+            if distance > tolerance {
+                keep[i] = true;
+                spans.push((start, i));
+                spans.push((i, end));
+            }
+        }
+    }
+    keep.truncate(n);
+    keep
+}
+
+fn segment_distance(p: Vector3, a: Vector3, b: Vector3) -> f64 {
+    let ab = b - a;
+    let length2 = ab.dot(&ab);
+    if length2 < 1e-24 {
+        return p.distance(&a);
+    }
+    let t = ((p - a).dot(&ab) / length2).clamp(0.0, 1.0);
+    p.distance(&(a + ab * t))
+}
+
+/// Bulged vertices of the tangent arc pairs through the vertices of a
+/// curve fit polyline
+pub(super) fn curve_fit(vertices: &[Vertex2D], closed: bool) -> Vec<(Vector2, f64)> {
+    let points: Vec<Vector2> = vertices.iter().map(|v| planar(v.location)).collect();
+    let n = points.len();
+    if n < 2 {
+        return points.into_iter().map(|p| (p, 0.0)).collect();
+    }
+    let direction = |a: Vector2, b: Vector2| {
+        let d = b - a;
+        if d.length() > 1e-12 {
+            d.normalize()
+        } else {
+            Vector2::new(1.0, 0.0)
+        }
+    };
+    let reflect = |tangent: Vector2, chord: Vector2| chord * (2.0 * chord.dot(&tangent)) - tangent;
+
+    let mut tangents: Vec<Option<Vector2>> = vertices
+        .iter()
+        .map(|v| {
+            (v.flags.bits() & VertexFlags::CURVE_FIT_TANGENT.bits() != 0)
+                .then(|| Vector2::new(v.curve_tangent.cos(), v.curve_tangent.sin()))
+        })
+        .collect();
+    for i in 0..n {
+        if tangents[i].is_some() || (!closed && (i == 0 || i == n - 1)) {
+            continue;
+        }
+        let (previous, next) = (points[(i + n - 1) % n], points[(i + 1) % n]);
+        tangents[i] = Some(direction(
+            Vector2::new(0.0, 0.0),
+            direction(previous, points[i]) + direction(points[i], next),
+        ));
+    }
+    if !closed {
+        let first = direction(points[0], points[1]);
+        let last = direction(points[n - 2], points[n - 1]);
+        if n == 2 {
+            tangents[0] = tangents[0].or(Some(first));
+            tangents[1] = tangents[1].or(Some(last));
+        } else {
+            tangents[0] = tangents[0].or(tangents[1].map(|t| reflect(t, first)));
+            tangents[n - 1] = tangents[n - 1].or(tangents[n - 2].map(|t| reflect(t, last)));
+        }
+    }
+    let tangents: Vec<Vector2> = tangents
+        .into_iter()
+        .map(|t| t.unwrap_or(Vector2::new(1.0, 0.0)))
+        .collect();
+
+    let segments = if closed { n } else { n - 1 };
+    let mut fitted = Vec::with_capacity(2 * n);
+    for i in 0..segments {
+        let j = (i + 1) % n;
+        let (join, first, second) = biarc(points[i], tangents[i], points[j], tangents[j]);
+        fitted.push((points[i], first));
+        if let Some(join) = join {
+            fitted.push((join, second));
+        }
+    }
+    if !closed {
+        fitted.push((points[n - 1], 0.0));
+    }
+    fitted
+}
+
+/// Two arcs from `p0` leaving along `t0` to `p1` arriving along `t1`,
+/// tangent where they meet: the join point and the bulges of both arcs
+fn biarc(p0: Vector2, t0: Vector2, p1: Vector2, t1: Vector2) -> (Option<Vector2>, f64, f64) {
+    let chord = p1 - p0;
+    let t = t0 + t1;
+    let a = 2.0 * (1.0 - t0.dot(&t1));
+    let b = 2.0 * chord.dot(&t);
+    let c = chord.dot(&chord);
+    let d = if a.abs() < 1e-12 {
+        if b.abs() < 1e-12 {
+            return (None, 0.0, 0.0);
+        }
+        c / b
+    } else {
+        (-b + (b * b + 4.0 * a * c).sqrt()) / (2.0 * a)
+    };
+    let (q0, q1) = (p0 + t0 * d, p1 - t1 * d);
+    let join = (q0 + q1) * 0.5;
+    // The bulge of an arc is the tangent of half the angle between its
+    // start tangent and its chord
+    let bulge = |tangent: Vector2, chord: Vector2| {
+        (tangent.cross(&chord).atan2(tangent.dot(&chord)) / 2.0).tan()
+    };
+    let t_join = q1 - q0;
+    (Some(join), bulge(t0, join - p0), bulge(t_join, p1 - join))
+}
+
+/// Points of a spline fit through a control frame
+fn spline_fit(frame: &[Vector3], kind: SplineKind, closed: bool) -> Vec<Vector3> {
+    let n = frame.len();
+    if n < 3 {
+        return frame.to_vec();
+    }
+    match kind {
+        SplineKind::Bezier => {
+            let mut control = frame.to_vec();
+            if closed {
+                control.push(frame[0]);
+            }
+            let samples = SEGMENTS * (control.len() - 1);
+            let mut points: Vec<Vector3> = (0..=samples)
+                .map(|s| de_casteljau(&control, s as f64 / samples as f64))
+                .collect();
+            if closed {
+                points.pop();
+            }
+            points
+        }
+        SplineKind::BSpline(degree) => {
+            let degree = degree.min(n - 1);
+            let (control, knots, spans) = if closed {
+                // Periodic: the first `degree` points again, uniform knots
+                let control: Vec<Vector3> = frame.iter().chain(&frame[..degree]).copied().collect();
+                let knots: Vec<f64> = (0..control.len() + degree + 1).map(|k| k as f64).collect();
+                (control, knots, n)
+            } else {
+                // Clamped at both ends, through the first and last vertex
+                let spans = n - degree;
+                let knots: Vec<f64> = (0..n + degree + 1)
+                    .map(|k| k.saturating_sub(degree).min(spans) as f64)
+                    .collect();
+                (frame.to_vec(), knots, spans)
+            };
+            let start = knots[degree];
+            let samples = SEGMENTS * spans;
+            let count = if closed { samples } else { samples + 1 };
+            (0..count)
+                .map(|s| {
+                    de_boor(
+                        degree,
+                        &knots,
+                        &control,
+                        start + spans as f64 * s as f64 / samples as f64,
+                    )
+                })
+                .collect()
+        }
+    }
+}
+
+fn de_casteljau(control: &[Vector3], t: f64) -> Vector3 {
+    let mut points = control.to_vec();
+    for level in 1..points.len() {
+        for i in 0..points.len() - level {
+            points[i] = points[i] * (1.0 - t) + points[i + 1] * t;
+        }
+    }
+    points[0]
+}
+
+fn de_boor(degree: usize, knots: &[f64], control: &[Vector3], t: f64) -> Vector3 {
+    let mut k = degree;
+    while k < control.len() - 1 && t >= knots[k + 1] {
+        k += 1;
+    }
+    let mut d: Vec<Vector3> = (0..=degree).map(|j| control[k - degree + j]).collect();
+    for r in 1..=degree {
+        for j in (r..=degree).rev() {
+            let i = k - degree + j;
+            let alpha = (t - knots[i]) / (knots[i + degree + 1 - r] - knots[i]);
+            d[j] = d[j - 1] * (1.0 - alpha) + d[j] * alpha;
+        }
+    }
+    d[degree]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::LwVertex;
+
+    #[test]
+    fn test_simplify_noisy_trace() {
+        let mut trace = LwPolyline::from_points(
+            (0..=10)
+                .map(|i| Vector2::new(i as f64, if i % 2 == 0 { 0.01 } else { -0.01 }))
+                .collect(),
+        );
+        trace.add_point(Vector2::new(10.0, 5.0));
+        assert_eq!(trace.simplify(0.05), 9);
+        let kept: Vec<Vector2> = trace.vertices.iter().map(|v| v.location).collect();
+        assert_eq!(
+            kept,
+            vec![
+                Vector2::new(0.0, 0.01),
+                Vector2::new(10.0, 0.01),
+                Vector2::new(10.0, 5.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_simplify_keeps_arcs_and_closed_rings() {
+        let mut slot = LwPolyline::from_points(vec![
+            Vector2::new(0.0, 0.0),
+            Vector2::new(1.0, 0.0),
+            Vector2::new(2.0, 0.0),
+            Vector2::new(2.0, 2.0),
+            Vector2::new(1.0, 2.0),
+            Vector2::new(0.0, 2.0),
+        ]);
+        slot.vertices[4] = LwVertex::with_bulge(Vector2::new(1.0, 2.0), 0.5);
+        slot.is_closed = true;
+        assert_eq!(slot.simplify(0.1), 1);
+        let kept: Vec<Vector2> = slot.vertices.iter().map(|v| v.location).collect();
+        assert_eq!(kept[..2], [Vector2::new(0.0, 0.0), Vector2::new(2.0, 0.0)]);
+        assert_eq!(kept.len(), 5);
+    }
+
+    #[test]
+    fn test_curve_fit_follows_circle() {
+        // Three points on the circle around (1, 0): the fitted arcs are
+        // that circle
+        let mut poly = Polyline2D::new();
+        for (x, y) in [(0.0, 0.0), (1.0, 1.0), (2.0, 0.0)] {
+            poly.add_vertex(Vertex2D::new(Vector3::new(x, y, 0.0)));
+        }
+        poly.flags |= PolylineFlags::CURVE_FIT;
+        let points = poly.smoothed_points(1e-3);
+        assert!(points.len() > 8);
+        assert_eq!(points.last(), Some(&Vector3::new(2.0, 0.0, 0.0)));
+        for p in &points {
+            assert!(
+                (p.distance(&Vector3::new(1.0, 0.0, 0.0)) - 1.0).abs() < 1e-9,
+                "{p:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_spline_fit_by_smooth_type() {
+        let frame = [(0.0, 0.0), (1.0, 2.0), (3.0, 2.0), (4.0, 0.0)];
+        let mut poly = Polyline2D::new();
+        for (x, y) in frame {
+            poly.add_vertex(Vertex2D::new(Vector3::new(x, y, 0.0)));
+        }
+        poly.elevation = 2.0;
+        poly.flags |= PolylineFlags::SPLINE_FIT;
+
+        poly.smooth_surface = SmoothSurfaceType::CubicBSpline;
+        let cubic = poly.smoothed_points(0.01);
+        assert_eq!(cubic.len(), SEGMENTS + 1);
+        assert_eq!(cubic[0], Vector3::new(0.0, 0.0, 2.0));
+        assert!(cubic[SEGMENTS].distance(&Vector3::new(4.0, 0.0, 2.0)) < 1e-12);
+        // Symmetric frame: the middle of the curve is at 3/4 of its height
+        assert!(cubic[SEGMENTS / 2].distance(&Vector3::new(2.0, 1.5, 2.0)) < 1e-12);
+
+        poly.smooth_surface = SmoothSurfaceType::QuadraticBSpline;
+        assert_eq!(poly.smoothed_points(0.01).len(), 2 * SEGMENTS + 1);
+
+        poly.close();
+        assert_eq!(poly.smoothed_points(0.01).len(), 4 * SEGMENTS);
+
+        let removed = poly.simplify(0.25);
+        assert!(removed > 0);
+        assert_eq!(removed + poly.vertices.len(), 4 * SEGMENTS);
+        assert!(!poly.flags.is_spline_fit());
+    }
+
+    #[test]
+    fn test_stored_spline_vertices_win() {
+        let mut poly = Polyline3D::from_points(vec![
+            Vector3::ZERO,
+            Vector3::new(1.0, 1.0, 1.0),
+            Vector3::new(2.0, 0.0, 0.0),
+        ]);
+        poly.flags.spline_fit = true;
+        // Three vertices make one quadratic span
+        assert_eq!(poly.smoothed_points().len(), SEGMENTS + 1);
+        let mut fitted = Vertex3DPolyline::new(Vector3::new(1.0, 0.5, 0.5));
+        fitted.flags |= VertexFlags::SPLINE_VERTEX.bits() as i32;
+        poly.add_vertex_full(fitted);
+        assert_eq!(poly.smoothed_points(), vec![Vector3::new(1.0, 0.5, 0.5)]);
+    }
+}
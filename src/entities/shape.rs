@@ -5,7 +5,7 @@
 //! defined in compiled shape files.
 
 use crate::entities::{Entity, EntityCommon};
-use crate::types::{BoundingBox3D, Color, Handle, LineWeight, Transparency, Vector3};
+use crate::types::{Angle, BoundingBox3D, Color, Handle, LineWeight, Transparency, Vector3};
 
 // ============================================================================
 // Shape Entity
@@ -41,6 +41,7 @@ use crate::types::{BoundingBox3D, Color, Handle, LineWeight, Transparency, Vecto
 /// shape.style_name = "MYSHAPES".to_string(); // TextStyle name
 /// ```
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Shape {
     /// Common entity data.
     pub common: EntityCommon,
@@ -192,6 +193,26 @@ impl Shape {
         self.oblique_angle.to_degrees()
     }
 
+    /// Gets the rotation angle.
+    pub fn rotation(&self) -> Angle {
+        Angle::from_radians(self.rotation)
+    }
+
+    /// Sets the rotation angle.
+    pub fn set_rotation(&mut self, angle: Angle) {
+        self.rotation = angle.radians();
+    }
+
+    /// Gets the oblique angle.
+    pub fn oblique_angle(&self) -> Angle {
+        Angle::from_radians(self.oblique_angle)
+    }
+
+    /// Sets the oblique angle.
+    pub fn set_oblique_angle(&mut self, angle: Angle) {
+        self.oblique_angle = angle.radians();
+    }
+
     /// Returns the effective width (size * relative_x_scale).
     pub fn width(&self) -> f64 {
         self.size * self.relative_x_scale
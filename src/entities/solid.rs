@@ -8,6 +8,7 @@ use crate::types::{BoundingBox3D, Color, Handle, LineWeight, Transparency, Vecto
 /// A Solid entity is a filled shape defined by 3 or 4 corner points.
 /// If only 3 corners are provided, the fourth corner is the same as the third.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Solid {
     pub common: EntityCommon,
     /// First corner point (in OCS)
@@ -81,23 +82,7 @@ impl Solid {
 
     /// Calculate the area of the solid
     pub fn area(&self) -> f64 {
-        if self.is_triangle() {
-            // Triangle area using cross product
-            let v1 = self.second_corner - self.first_corner;
-            let v2 = self.third_corner - self.first_corner;
-            v1.cross(&v2).length() * 0.5
-        } else {
-            // Quadrilateral area (sum of two triangles)
-            let v1 = self.second_corner - self.first_corner;
-            let v2 = self.third_corner - self.first_corner;
-            let area1 = v1.cross(&v2).length() * 0.5;
-
-            let v3 = self.third_corner - self.first_corner;
-            let v4 = self.fourth_corner - self.first_corner;
-            let area2 = v3.cross(&v4).length() * 0.5;
-
-            area1 + area2
-        }
+        self.as_triangles().iter().map(|t| t.area()).sum()
     }
 }
 
@@ -5,6 +5,7 @@ use crate::types::{BoundingBox3D, Color, Handle, LineWeight, Transparency, Vecto
 
 /// Spline flags
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SplineFlags {
     /// Is the spline closed?
     pub closed: bool,
@@ -39,6 +40,7 @@ impl Default for SplineFlags {
 
 /// A spline entity (NURBS curve)
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Spline {
     /// Common entity data
     pub common: EntityCommon,
@@ -56,6 +58,16 @@ pub struct Spline {
     pub fit_points: Vec<Vector3>,
     /// Normal vector
     pub normal: Vector3,
+    /// Tolerance of the knot values
+    pub knot_tolerance: f64,
+    /// Tolerance of the control point positions
+    pub control_point_tolerance: f64,
+    /// Distance the curve may stray from the fit points
+    pub fit_tolerance: f64,
+    /// Tangent at the first fit point, zero if undefined
+    pub start_tangent: Vector3,
+    /// Tangent at the last fit point, zero if undefined
+    pub end_tangent: Vector3,
 }
 
 impl Spline {
@@ -70,6 +82,11 @@ impl Spline {
             weights: Vec::new(),
             fit_points: Vec::new(),
             normal: Vector3::UNIT_Z,
+            knot_tolerance: 1e-7,
+            control_point_tolerance: 1e-7,
+            fit_tolerance: 1e-10,
+            start_tangent: Vector3::ZERO,
+            end_tangent: Vector3::ZERO,
         }
     }
 
@@ -109,6 +126,11 @@ impl Spline {
     pub fn add_knot(&mut self, knot: f64) {
         self.knots.push(knot);
     }
+
+    /// Whether the control points carry weights other than 1
+    pub fn is_weighted(&self) -> bool {
+        self.weights.len() == self.control_points.len() && self.weights.iter().any(|w| (w - 1.0).abs() > 1e-12)
+    }
 }
 
 impl Default for Spline {
@@ -0,0 +1,479 @@
+//! Knot and control point editing of splines
+//!
+//! The edits keep the curve exactly and work on the control points in
+//! homogeneous coordinates, so rational splines keep their weights:
+//!
+//! - knot insertion (Boehm's algorithm) adds a knot and a control point
+//! - degree elevation raises every knot's multiplicity by one and solves
+//!   for the new control points at the Greville abscissae
+//! - splitting inserts a knot up to the degree and cuts the control
+//!   polygon there; periodic splines are clamped first
+//!
+//! The fit points of an edited spline no longer describe its control
+//! points and are dropped, as AutoCAD does when control points are edited.
+
+use super::spline::Spline;
+use crate::types::{Handle, Vector3};
+
+/// Relative distance under which two knots are the same
+const KNOT_TOLERANCE: f64 = 1e-10;
+
+/// A control point in homogeneous coordinates: the point times its
+/// weight, and the weight
+type Homogeneous = [f64; 4];
+
+/// The spline's curve data, checked for consistency
+#[derive(Clone)]
+struct Nurbs {
+    degree: usize,
+    knots: Vec<f64>,
+    points: Vec<Homogeneous>,
+}
+
+impl Spline {
+    /// Parameter range of the curve, or `None` when the degree, knots and
+    /// control points do not make a valid spline
+    pub fn domain(&self) -> Option<(f64, f64)> {
+        let nurbs = Nurbs::of(self)?;
+        Some((nurbs.knots[nurbs.degree], nurbs.knots[nurbs.points.len()]))
+    }
+
+    /// Point of the curve at parameter `u`, clamped to the domain
+    pub fn point_at(&self, u: f64) -> Option<Vector3> {
+        let nurbs = Nurbs::of(self)?;
+        Some(project(nurbs.evaluate(u)))
+    }
+
+    /// Inserts the knot `u` once, adding a control point without changing
+    /// the curve.
+    ///
+    /// Returns `false`, leaving the spline untouched, when the spline is not
+    /// valid, `u` is outside its domain or the knot already has the
+    /// spline's degree as multiplicity.
+    pub fn insert_knot(&mut self, u: f64) -> bool {
+        let Some(mut nurbs) = Nurbs::of(self) else {
+            return false;
+        };
+        let (start, end) = nurbs.domain();
+        let u = nurbs.snap(u);
+        if u < start || u > end || nurbs.multiplicity(u) >= nurbs.degree {
+            return false;
+        }
+        nurbs.insert(u);
+        nurbs.store(self);
+        true
+    }
+
+    /// Raises the degree by one without changing the curve.
+    ///
+    /// Periodic splines become clamped splines over the same domain.
+    /// Returns `false`, leaving the spline untouched, when the spline is not
+    /// valid.
+    pub fn elevate_degree(&mut self) -> bool {
+        let Some(nurbs) = Nurbs::of(self) else {
+            return false;
+        };
+        nurbs.clamped().elevated().store(self);
+        self.flags.periodic = false;
+        true
+    }
+
+    /// Splits the spline at parameter `u` into the curves before and after.
+    ///
+    /// Both pieces are open and keep the spline's entity data; the second
+    /// has no handle. Returns `None` when the spline is not valid or `u` is
+    /// not strictly inside its domain.
+    pub fn split_at(&self, u: f64) -> Option<(Spline, Spline)> {
+        let nurbs = Nurbs::of(self)?.clamped();
+        let (start, end) = nurbs.domain();
+        let u = nurbs.snap(u);
+        if u <= start || u >= end {
+            return None;
+        }
+        let (before, after) = nurbs.split(u);
+        let mut first = self.clone();
+        let mut second = self.clone();
+        for (spline, nurbs) in [(&mut first, before), (&mut second, after)] {
+            nurbs.store(spline);
+            spline.flags.closed = false;
+            spline.flags.periodic = false;
+        }
+        second.common.handle = Handle::NULL;
+        Some((first, second))
+    }
+
+    /// Reverses the direction of the curve: the control points, weights,
+    /// knots and fit points run the other way, and the end tangents swap
+    /// ends and flip.
+    pub fn reverse(&mut self) {
+        self.control_points.reverse();
+        self.weights.reverse();
+        self.fit_points.reverse();
+        (self.start_tangent, self.end_tangent) = (-self.end_tangent, -self.start_tangent);
+        if let (Some(&first), Some(&last)) = (self.knots.first(), self.knots.last()) {
+            self.knots = self.knots.iter().rev().map(|k| first + last - k).collect();
+        }
+    }
+}
+
+impl Nurbs {
+    fn of(spline: &Spline) -> Option<Nurbs> {
+        let degree = usize::try_from(spline.degree).ok().filter(|&d| d >= 1)?;
+        let n = spline.control_points.len();
+        let valid = n > degree
+            && spline.knots.len() == n + degree + 1
+            && spline.knots.windows(2).all(|w| w[0] <= w[1])
+            && spline.knots[degree] < spline.knots[n]
+            && (spline.weights.is_empty() || spline.weights.len() == n);
+        if !valid {
+            return None;
+        }
+        let points = spline
+            .control_points
+            .iter()
+            .enumerate()
+            .map(|(i, p)| {
+                let w = spline.weights.get(i).copied().unwrap_or(1.0);
+                [p.x * w, p.y * w, p.z * w, w]
+            })
+            .collect();
+        Some(Nurbs { degree, knots: spline.knots.clone(), points })
+    }
+
+    /// Write the curve back into `spline`, keeping weights only when they
+    /// are not all 1
+    fn store(self, spline: &mut Spline) {
+        let rational = self.points.iter().any(|p| (p[3] - 1.0).abs() > 1e-12);
+        spline.degree = self.degree as i32;
+        spline.control_points = self.points.iter().map(|&p| project(p)).collect();
+        spline.weights = if rational || !spline.weights.is_empty() {
+            self.points.iter().map(|p| p[3]).collect()
+        } else {
+            Vec::new()
+        };
+        spline.flags.rational = rational;
+        spline.knots = self.knots;
+        spline.fit_points.clear();
+    }
+
+    fn domain(&self) -> (f64, f64) {
+        (self.knots[self.degree], self.knots[self.points.len()])
+    }
+
+    /// `u`, or the knot it is within the tolerance of
+    fn snap(&self, u: f64) -> f64 {
+        let scale = self.knots.last().unwrap() - self.knots[0];
+        self.knots
+            .iter()
+            .copied()
+            .find(|k| (k - u).abs() <= KNOT_TOLERANCE * scale.max(1.0))
+            .unwrap_or(u)
+    }
+
+    fn multiplicity(&self, u: f64) -> usize {
+        self.knots.iter().filter(|&&k| k == u).count()
+    }
+
+    /// Index `k` of the knot span `knots[k] <= u < knots[k + 1]` in the
+    /// domain; the last span for the end of the domain
+    fn span(&self, u: f64) -> usize {
+        let n = self.points.len();
+        let mut k = self.degree;
+        while k < n - 1 && u >= self.knots[k + 1] {
+            k += 1;
+        }
+        k
+    }
+
+    fn evaluate(&self, u: f64) -> Homogeneous {
+        let (start, end) = self.domain();
+        let u = u.clamp(start, end);
+        let p = self.degree;
+        let k = self.span(u);
+        let mut d: Vec<Homogeneous> = self.points[k - p..=k].to_vec();
+        for r in 1..=p {
+            for j in (r..=p).rev() {
+                let i = k - p + j;
+                let denominator = self.knots[i + p + 1 - r] - self.knots[i];
+                let alpha = if denominator > 0.0 { (u - self.knots[i]) / denominator } else { 0.0 };
+                d[j] = blend(d[j - 1], d[j], alpha);
+            }
+        }
+        d[p]
+    }
+
+    /// Boehm's knot insertion
+    fn insert(&mut self, u: f64) {
+        let p = self.degree;
+        let k = self.span(u);
+        // Knots equal to `u` in the span; none when `u` ends the domain
+        let s = self.knots[..=k].iter().rev().take_while(|&&knot| knot == u).count();
+        let mut points = Vec::with_capacity(self.points.len() + 1);
+        points.extend_from_slice(&self.points[..=k - p]);
+        for i in k - p + 1..=k - s {
+            let alpha = (u - self.knots[i]) / (self.knots[i + p] - self.knots[i]);
+            points.push(blend(self.points[i - 1], self.points[i], alpha));
+        }
+        points.extend_from_slice(&self.points[k - s..]);
+        self.points = points;
+        self.knots.insert(k + 1, u);
+    }
+
+    /// Split at an interior parameter into the curves before and after
+    fn split(mut self, u: f64) -> (Nurbs, Nurbs) {
+        let p = self.degree;
+        while self.multiplicity(u) < p {
+            self.insert(u);
+        }
+        // With `u` at knots[s..s + p], the curve passes through points[s - 1]
+        let s = self.knots.iter().position(|&k| k == u).unwrap();
+        let mut before_knots = self.knots[..s + p].to_vec();
+        before_knots.push(u);
+        let mut after_knots = vec![u];
+        after_knots.extend_from_slice(&self.knots[s..]);
+        (
+            Nurbs { degree: p, knots: before_knots, points: self.points[..s].to_vec() },
+            Nurbs { degree: p, knots: after_knots, points: self.points[s - 1..].to_vec() },
+        )
+    }
+
+    /// The same curve with its end knots repeated degree + 1 times
+    fn clamped(self) -> Nurbs {
+        let (start, end) = self.domain();
+        let p = self.degree;
+        let mut nurbs = self;
+        if nurbs.knots[..=p].iter().any(|&k| k != start) {
+            while nurbs.multiplicity(start) < p {
+                nurbs.insert(start);
+            }
+            let s = nurbs.knots.iter().position(|&k| k == start).unwrap();
+            let mut knots = vec![start];
+            knots.extend_from_slice(&nurbs.knots[s..]);
+            nurbs = Nurbs { degree: p, knots, points: nurbs.points[s - 1..].to_vec() };
+        }
+        let n = nurbs.points.len();
+        if nurbs.knots[n..].iter().any(|&k| k != end) {
+            while nurbs.multiplicity(end) < p {
+                nurbs.insert(end);
+            }
+            let s = nurbs.knots.iter().position(|&k| k == end).unwrap();
+            let mut knots = nurbs.knots[..s + p].to_vec();
+            knots.push(end);
+            nurbs = Nurbs { degree: p, knots, points: nurbs.points[..s].to_vec() };
+        }
+        nurbs
+    }
+
+    /// Degree elevation of a clamped curve: every knot one more time, and
+    /// the control points that interpolate the curve at the Greville
+    /// abscissae of the new knots
+    fn elevated(&self) -> Nurbs {
+        let q = self.degree + 1;
+        let mut knots = Vec::with_capacity(self.knots.len() * 2);
+        for (i, &k) in self.knots.iter().enumerate() {
+            knots.push(k);
+            if self.knots.get(i + 1) != Some(&k) {
+                knots.push(k);
+            }
+        }
+        let n = knots.len() - q - 1;
+        let mut elevated = Nurbs { degree: q, knots, points: vec![[0.0; 4]; n] };
+
+        let greville: Vec<f64> =
+            (0..n).map(|i| elevated.knots[i + 1..=i + q].iter().sum::<f64>() / q as f64).collect();
+        let mut matrix = vec![vec![0.0; n]; n];
+        for (row, &u) in greville.iter().enumerate() {
+            let k = elevated.span(u);
+            for (j, value) in elevated.basis(k, u).into_iter().enumerate() {
+                matrix[row][k - q + j] = value;
+            }
+        }
+        let values: Vec<Homogeneous> = greville.iter().map(|&u| self.evaluate(u)).collect();
+        elevated.points = solve(matrix, values);
+        elevated
+    }
+
+    /// The degree + 1 basis functions that are not zero on span `k` at `u`
+    fn basis(&self, k: usize, u: f64) -> Vec<f64> {
+        let p = self.degree;
+        let mut values = vec![0.0; p + 1];
+        let mut left = vec![0.0; p + 1];
+        let mut right = vec![0.0; p + 1];
+        values[0] = 1.0;
+        for j in 1..=p {
+            left[j] = u - self.knots[k + 1 - j];
+            right[j] = self.knots[k + j] - u;
+            let mut saved = 0.0;
+            for r in 0..j {
+                let temp = values[r] / (right[r + 1] + left[j - r]);
+                values[r] = saved + right[r + 1] * temp;
+                saved = left[j - r] * temp;
+            }
+            values[j] = saved;
+        }
+        values
+    }
+}
+
+fn blend(a: Homogeneous, b: Homogeneous, t: f64) -> Homogeneous {
+    [0, 1, 2, 3].map(|i| a[i] * (1.0 - t) + b[i] * t)
+}
+
+fn project(p: Homogeneous) -> Vector3 {
+    let w = if p[3].abs() > 1e-300 { p[3] } else { 1.0 };
+    Vector3::new(p[0] / w, p[1] / w, p[2] / w)
+}
+
+/// Solve `matrix · x = values` by Gaussian elimination with partial
+/// pivoting
+fn solve(mut matrix: Vec<Vec<f64>>, mut values: Vec<Homogeneous>) -> Vec<Homogeneous> {
+    let n = values.len();
+    for column in 0..n {
+        let pivot = (column..n)
+            .max_by(|&a, &b| matrix[a][column].abs().total_cmp(&matrix[b][column].abs()))
+            .unwrap();
+        matrix.swap(column, pivot);
+        values.swap(column, pivot);
+        for row in column + 1..n {
+            let factor = matrix[row][column] / matrix[column][column];
+            if factor == 0.0 {
+                continue;
+            }
+            let (above, below) = matrix.split_at_mut(row);
+            for (target, source) in below[0][column..].iter_mut().zip(&above[column][column..]) {
+                *target -= factor * source;
+            }
+            let source = values[column];
+            values[row] = [0, 1, 2, 3].map(|i| values[row][i] - factor * source[i]);
+        }
+    }
+    for row in (0..n).rev() {
+        let mut value = values[row];
+        for c in row + 1..n {
+            value = [0, 1, 2, 3].map(|i| value[i] - matrix[row][c] * values[c][i]);
+        }
+        values[row] = value.map(|x| x / matrix[row][row]);
+    }
+    values
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A cubic with an interior knot, and a rational quadratic quarter
+    /// circle
+    fn samples() -> Vec<Spline> {
+        let mut cubic = Spline::from_control_points(
+            3,
+            vec![
+                Vector3::new(0.0, 0.0, 0.0),
+                Vector3::new(1.0, 2.0, 0.0),
+                Vector3::new(3.0, 3.0, 1.0),
+                Vector3::new(5.0, 1.0, 0.0),
+                Vector3::new(6.0, 0.0, 2.0),
+            ],
+        );
+        cubic.knots = vec![0.0, 0.0, 0.0, 0.0, 0.4, 1.0, 1.0, 1.0, 1.0];
+        let mut arc = Spline::from_control_points(
+            2,
+            vec![Vector3::new(1.0, 0.0, 0.0), Vector3::new(1.0, 1.0, 0.0), Vector3::new(0.0, 1.0, 0.0)],
+        );
+        arc.knots = vec![0.0, 0.0, 0.0, 1.0, 1.0, 1.0];
+        arc.weights = vec![1.0, std::f64::consts::FRAC_1_SQRT_2, 1.0];
+        vec![cubic, arc]
+    }
+
+    fn assert_same_curve(a: &Spline, b: &Spline) {
+        let (start, end) = a.domain().unwrap();
+        assert_eq!(b.domain(), Some((start, end)));
+        for i in 0..=20 {
+            let u = start + (end - start) * i as f64 / 20.0;
+            let (pa, pb) = (a.point_at(u).unwrap(), b.point_at(u).unwrap());
+            assert!(pa.distance(&pb) < 1e-9, "at {u}: {pa:?} != {pb:?}");
+        }
+    }
+
+    #[test]
+    fn test_insert_knot() {
+        for original in samples() {
+            let mut spline = original.clone();
+            assert!(spline.insert_knot(0.7));
+            assert!(spline.insert_knot(0.7));
+            assert_eq!(spline.control_points.len(), original.control_points.len() + 2);
+            assert_same_curve(&original, &spline);
+            assert!(!spline.insert_knot(1.5));
+        }
+        let mut arc = samples().remove(1);
+        assert!(arc.insert_knot(0.5));
+        assert!((arc.point_at(0.3).unwrap().length() - 1.0).abs() < 1e-12);
+        // Within the knot tolerance, the same knot: at most the degree
+        assert!(arc.insert_knot(0.5 + 1e-13));
+        assert_eq!(arc.knots.iter().filter(|&&k| k == 0.5).count(), 2);
+        assert!(!arc.insert_knot(0.5));
+    }
+
+    #[test]
+    fn test_elevate_degree() {
+        for original in samples() {
+            let mut spline = original.clone();
+            assert!(spline.elevate_degree());
+            assert_eq!(spline.degree, original.degree + 1);
+            assert_same_curve(&original, &spline);
+        }
+        let mut cubic = samples().remove(0);
+        cubic.elevate_degree();
+        assert_eq!(cubic.knots, vec![0.0, 0.0, 0.0, 0.0, 0.0, 0.4, 0.4, 1.0, 1.0, 1.0, 1.0, 1.0]);
+        assert!(cubic.weights.is_empty());
+    }
+
+    #[test]
+    fn test_split_and_reverse() {
+        for original in samples() {
+            let (first, second) = original.split_at(0.25).unwrap();
+            assert_eq!(first.domain(), Some((0.0, 0.25)));
+            assert_eq!(second.domain(), Some((0.25, 1.0)));
+            for u in [0.0, 0.1, 0.25] {
+                assert!(first.point_at(u).unwrap().distance(&original.point_at(u).unwrap()) < 1e-12);
+            }
+            for u in [0.25, 0.6, 1.0] {
+                assert!(second.point_at(u).unwrap().distance(&original.point_at(u).unwrap()) < 1e-12);
+            }
+            assert!(original.split_at(1.0).is_none());
+
+            let mut original = original;
+            original.start_tangent = Vector3::new(1.0, 0.0, 0.0);
+            original.end_tangent = Vector3::new(0.0, 1.0, 0.0);
+            let mut reversed = original.clone();
+            reversed.reverse();
+            for u in [0.0, 0.3, 0.9] {
+                let (a, b) = (original.point_at(u).unwrap(), reversed.point_at(1.0 - u).unwrap());
+                assert!(a.distance(&b) < 1e-12);
+            }
+            assert_eq!(reversed.start_tangent, Vector3::new(0.0, -1.0, 0.0));
+            assert_eq!(reversed.end_tangent, Vector3::new(-1.0, 0.0, 0.0));
+        }
+    }
+
+    #[test]
+    fn test_periodic_spline() {
+        // Uniform closed cubic: the first three points repeated at the end
+        let square = [(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 2.0)];
+        let mut points: Vec<Vector3> = square.iter().map(|&(x, y)| Vector3::new(x, y, 0.0)).collect();
+        points.extend_from_slice(&points.clone()[..3]);
+        let mut periodic = Spline::from_control_points(3, points);
+        periodic.knots = (0..11).map(f64::from).collect();
+        periodic.flags.periodic = true;
+        periodic.flags.closed = true;
+
+        let mut elevated = periodic.clone();
+        assert!(elevated.elevate_degree());
+        assert!(!elevated.flags.periodic);
+        assert_same_curve(&periodic, &elevated);
+
+        let (first, second) = periodic.split_at(5.0).unwrap();
+        assert!(first.point_at(3.0).unwrap().distance(&periodic.point_at(3.0).unwrap()) < 1e-12);
+        assert!(second.point_at(7.0).unwrap().distance(&periodic.point_at(7.0).unwrap()) < 1e-12);
+        assert!(!second.flags.closed);
+    }
+}
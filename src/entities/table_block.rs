@@ -0,0 +1,196 @@
+//! Geometry of the anonymous block behind a table
+//!
+//! AutoCAD draws an ACAD_TABLE through an anonymous `*T` block holding the
+//! grid lines and cell texts, and shows nothing for a table without one.
+//! The geometry is in the table's own coordinates, with the insertion
+//! point at the top left corner of the table and rows going down; the
+//! table places the block like an INSERT.
+
+use super::mtext::{AttachmentPoint, MText};
+use super::table::Table;
+use super::{EntityCommon, EntityType, Line};
+use crate::types::{Color, Vector3};
+
+impl Table {
+    /// The lines and texts AutoCAD expects in the block of the table.
+    ///
+    /// Cell edges inside a merged range are left out; each non-empty
+    /// text cell gets an MText centered in the cell, or in its merged
+    /// range. Block cells are not drawn.
+    pub fn block_entities(&self) -> Vec<EntityType> {
+        let rows = self.rows.len();
+        let cols = self.columns.len();
+        let mut entities = Vec::new();
+        if rows == 0 || cols == 0 {
+            return entities;
+        }
+
+        // Top and left edge of every row and column, and the far edges
+        let mut ys = vec![0.0];
+        for row in &self.rows {
+            ys.push(ys.last().unwrap() - row.height);
+        }
+        let mut xs = vec![0.0];
+        for column in &self.columns {
+            xs.push(xs.last().unwrap() + column.width);
+        }
+
+        // Cell owning each grid position: itself or the top left cell of
+        // the merged range it lies in
+        let mut owners: Vec<(usize, usize)> = (0..rows * cols).map(|i| (i / cols, i % cols)).collect();
+        for (r, row) in self.rows.iter().enumerate() {
+            for (c, cell) in row.cells.iter().enumerate().take(cols) {
+                if !cell.is_merged() || owners[r * cols + c] != (r, c) {
+                    continue;
+                }
+                let bottom = (r + cell.merge_height.max(1) as usize).min(rows);
+                let right = (c + cell.merge_width.max(1) as usize).min(cols);
+                for rr in r..bottom {
+                    for cc in c..right {
+                        owners[rr * cols + cc] = (r, c);
+                    }
+                }
+            }
+        }
+        let owner = |r: usize, c: usize| owners[r * cols + c];
+
+        // Horizontal edges, joined along each grid line
+        for (r, &y) in ys.iter().enumerate() {
+            let drawn: Vec<bool> = (0..cols)
+                .map(|c| r == 0 || r == rows || owner(r - 1, c) != owner(r, c))
+                .collect();
+            for (from, to) in runs(&drawn) {
+                entities.push(grid_line(Vector3::new(xs[from], y, 0.0), Vector3::new(xs[to], y, 0.0)));
+            }
+        }
+        // Vertical edges
+        for (c, &x) in xs.iter().enumerate() {
+            let drawn: Vec<bool> = (0..rows)
+                .map(|r| c == 0 || c == cols || owner(r, c - 1) != owner(r, c))
+                .collect();
+            for (from, to) in runs(&drawn) {
+                entities.push(grid_line(Vector3::new(x, ys[from], 0.0), Vector3::new(x, ys[to], 0.0)));
+            }
+        }
+
+        for (r, row) in self.rows.iter().enumerate() {
+            for (c, cell) in row.cells.iter().enumerate().take(cols) {
+                let text = cell.text_value();
+                if owner(r, c) != (r, c) || text.is_empty() {
+                    continue;
+                }
+                let bottom = (r + cell.merge_height.max(1) as usize).min(rows);
+                let right = (c + cell.merge_width.max(1) as usize).min(cols);
+                let margins = cell
+                    .style
+                    .as_ref()
+                    .map(|style| style.margin_left + style.margin_right)
+                    .unwrap_or(0.12);
+                let center = Vector3::new((xs[c] + xs[right]) / 2.0, (ys[r] + ys[bottom]) / 2.0, 0.0);
+                let mut mtext = MText::with_value(text, center)
+                    .with_height(cell.content().map(|content| content.text_height).unwrap_or(0.18))
+                    .with_width((xs[right] - xs[c] - margins).max(0.0));
+                mtext.attachment_point = AttachmentPoint::MiddleCenter;
+                mtext.rotation = cell.rotation;
+                mtext.common = block_common();
+                entities.push(EntityType::MText(Box::new(mtext)));
+            }
+        }
+        entities
+    }
+}
+
+/// Common data of the block entities, drawn in the color of the table
+fn block_common() -> EntityCommon {
+    EntityCommon {
+        color: Color::ByBlock,
+        ..EntityCommon::new()
+    }
+}
+
+fn grid_line(start: Vector3, end: Vector3) -> EntityType {
+    let mut line = Line::from_points(start, end);
+    line.common = block_common();
+    EntityType::Line(line)
+}
+
+/// Index ranges `from..to` of the runs of `true` in `drawn`
+fn runs(drawn: &[bool]) -> Vec<(usize, usize)> {
+    let mut runs = Vec::new();
+    let mut start = None;
+    for (i, &on) in drawn.iter().chain([&false]).enumerate() {
+        match (on, start) {
+            (true, None) => start = Some(i),
+            (false, Some(from)) => {
+                runs.push((from, i));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    runs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::CellRange;
+
+    fn lines(entities: &[EntityType]) -> Vec<(Vector3, Vector3)> {
+        entities
+            .iter()
+            .filter_map(|e| match e {
+                EntityType::Line(line) => Some((line.start, line.end)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_grid_and_texts() {
+        let mut table = Table::new(Vector3::new(5.0, 5.0, 0.0), 2, 3);
+        table.set_uniform_row_height(1.0);
+        table.set_uniform_column_width(2.0);
+        table.set_cell_text(1, 2, "X");
+
+        let entities = table.block_entities();
+        // Three horizontal and four vertical grid lines, unbroken
+        let grid = lines(&entities);
+        assert_eq!(grid.len(), 7);
+        assert!(grid.contains(&(Vector3::new(0.0, -2.0, 0.0), Vector3::new(6.0, -2.0, 0.0))));
+        assert!(grid.contains(&(Vector3::new(6.0, 0.0, 0.0), Vector3::new(6.0, -2.0, 0.0))));
+
+        let texts: Vec<&MText> = entities
+            .iter()
+            .filter_map(|e| match e {
+                EntityType::MText(mtext) => Some(mtext.as_ref()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(texts.len(), 1);
+        assert_eq!(texts[0].insertion_point, Vector3::new(5.0, -1.5, 0.0));
+        assert_eq!(texts[0].attachment_point, AttachmentPoint::MiddleCenter);
+    }
+
+    #[test]
+    fn test_merged_cells_break_the_grid() {
+        let mut table = Table::new(Vector3::ZERO, 3, 3);
+        table.set_uniform_row_height(1.0);
+        table.set_uniform_column_width(1.0);
+        table.merge_cells(CellRange::new(0, 0, 1, 1));
+        table.set_cell_text(0, 0, "Merged");
+
+        let entities = table.block_entities();
+        let grid = lines(&entities);
+        // The edges inside the merged range stop at its border
+        assert!(grid.contains(&(Vector3::new(2.0, -1.0, 0.0), Vector3::new(3.0, -1.0, 0.0))));
+        assert!(grid.contains(&(Vector3::new(1.0, -2.0, 0.0), Vector3::new(1.0, -3.0, 0.0))));
+        assert!(!grid.iter().any(|(a, b)| a.y == -1.0 && b.y == -1.0 && a.x < 2.0));
+
+        let text = entities.iter().find_map(|e| match e {
+            EntityType::MText(mtext) => Some(mtext),
+            _ => None,
+        });
+        assert_eq!(text.unwrap().insertion_point, Vector3::new(1.0, -1.0, 0.0));
+    }
+}
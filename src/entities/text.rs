@@ -1,40 +1,79 @@
 //! Text entity
 
 use super::{Entity, EntityCommon};
-use crate::types::{BoundingBox3D, Color, Handle, LineWeight, Transparency, Vector3};
+use crate::types::{Angle, BoundingBox3D, Color, Handle, LineWeight, Transparency, Vector3};
 
 /// Text horizontal alignment
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TextHorizontalAlignment {
     /// Left aligned
-    Left,
+    Left = 0,
     /// Center aligned
-    Center,
+    Center = 1,
     /// Right aligned
-    Right,
+    Right = 2,
     /// Aligned (fit between two points)
-    Aligned,
+    Aligned = 3,
     /// Middle (centered horizontally and vertically)
-    Middle,
+    Middle = 4,
     /// Fit (fit between two points, adjust height)
-    Fit,
+    Fit = 5,
+}
+
+impl TextHorizontalAlignment {
+    /// Create from DXF value (group code 72)
+    pub fn from_value(value: i16) -> Self {
+        match value {
+            1 => TextHorizontalAlignment::Center,
+            2 => TextHorizontalAlignment::Right,
+            3 => TextHorizontalAlignment::Aligned,
+            4 => TextHorizontalAlignment::Middle,
+            5 => TextHorizontalAlignment::Fit,
+            _ => TextHorizontalAlignment::Left,
+        }
+    }
+
+    /// Convert to DXF value
+    pub fn to_value(&self) -> i16 {
+        *self as i16
+    }
 }
 
 /// Text vertical alignment
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TextVerticalAlignment {
     /// Baseline
-    Baseline,
+    Baseline = 0,
     /// Bottom
-    Bottom,
+    Bottom = 1,
     /// Middle
-    Middle,
+    Middle = 2,
     /// Top
-    Top,
+    Top = 3,
+}
+
+impl TextVerticalAlignment {
+    /// Create from DXF value (group code 73)
+    pub fn from_value(value: i16) -> Self {
+        match value {
+            1 => TextVerticalAlignment::Bottom,
+            2 => TextVerticalAlignment::Middle,
+            3 => TextVerticalAlignment::Top,
+            _ => TextVerticalAlignment::Baseline,
+        }
+    }
+
+    /// Convert to DXF value
+    pub fn to_value(&self) -> i16 {
+        *self as i16
+    }
 }
 
 /// A single-line text entity
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Text {
     /// Common entity data
     pub common: EntityCommon,
@@ -101,6 +140,26 @@ impl Text {
         self.rotation = rotation;
         self
     }
+
+    /// Rotation angle
+    pub fn rotation(&self) -> Angle {
+        Angle::from_radians(self.rotation)
+    }
+
+    /// Set the rotation angle
+    pub fn set_rotation(&mut self, angle: Angle) {
+        self.rotation = angle.radians();
+    }
+
+    /// Oblique angle
+    pub fn oblique_angle(&self) -> Angle {
+        Angle::from_radians(self.oblique_angle)
+    }
+
+    /// Set the oblique angle
+    pub fn set_oblique_angle(&mut self, angle: Angle) {
+        self.oblique_angle = angle.radians();
+    }
 }
 
 impl Default for Text {
@@ -3,7 +3,7 @@
 //! The Tolerance entity represents a geometric dimensioning and tolerancing
 //! (GD&T) feature control frame annotation.
 
-use crate::entities::{Entity, EntityCommon};
+use crate::entities::{Entity, EntityCommon, FeatureControlFrame};
 use crate::types::{BoundingBox3D, Color, Handle, LineWeight, Transparency, Vector3};
 
 // ============================================================================
@@ -24,13 +24,16 @@ use crate::types::{BoundingBox3D, Color, Handle, LineWeight, Transparency, Vecto
 ///
 /// The tolerance text uses a special format for GDT symbols:
 /// - `{\Fgdt;X}` - Font switch to GDT font with symbol X
-///   - `j` - Perpendicularity
-///   - `n` - Angularity
-///   - `s` - Symmetry
-///   - `p` - Position
+///   - `j` - Position
+///   - `b` - Perpendicularity
+///   - `a` - Angularity
+///   - `n` - Diameter
+///   - see [`gdt_symbols`] for the full set
 /// - `%%v` - Special character/separator
 /// - `^J` - Line separator (newline within frame)
 ///
+/// [`Tolerance::frame`] parses the text into a [`FeatureControlFrame`].
+///
 /// # Example
 ///
 /// ```ignore
@@ -39,10 +42,11 @@ use crate::types::{BoundingBox3D, Color, Handle, LineWeight, Transparency, Vecto
 ///
 /// let mut tol = Tolerance::new();
 /// tol.insertion_point = Vector3::new(10.0, 10.0, 0.0);
-/// tol.text = "{\\Fgdt;p}%%v0.5%%v%%v%%v%%v".to_string();
+/// tol.text = "{\\Fgdt;j}%%v0.5%%v%%v%%v%%v".to_string();
 /// tol.dimension_style_name = "Standard".to_string();
 /// ```
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Tolerance {
     /// Common entity data.
     pub common: EntityCommon,
@@ -201,6 +205,16 @@ impl Tolerance {
         self.text.matches("^J").count() + 1
     }
 
+    /// Parses the tolerance text into a structured frame.
+    pub fn frame(&self) -> FeatureControlFrame {
+        FeatureControlFrame::parse(&self.text)
+    }
+
+    /// Replaces the tolerance text with a structured frame.
+    pub fn set_frame(&mut self, frame: &FeatureControlFrame) {
+        self.text = frame.to_string();
+    }
+
     /// Creates a simple position tolerance string.
     ///
     /// # Arguments
@@ -406,31 +420,31 @@ impl Entity for Tolerance {
 /// These are used with the `{\Fgdt;X}` format in tolerance text.
 pub mod gdt_symbols {
     /// Straightness symbol.
-    pub const STRAIGHTNESS: char = 'a';
+    pub const STRAIGHTNESS: char = 'u';
     /// Flatness symbol.
     pub const FLATNESS: char = 'c';
     /// Circularity/Roundness symbol.
-    pub const CIRCULARITY: char = 'g';
+    pub const CIRCULARITY: char = 'e';
     /// Cylindricity symbol.
-    pub const CYLINDRICITY: char = 'e';
+    pub const CYLINDRICITY: char = 'g';
     /// Profile of a line symbol.
     pub const LINE_PROFILE: char = 'k';
     /// Profile of a surface symbol.
     pub const SURFACE_PROFILE: char = 'd';
     /// Parallelism symbol.
-    pub const PARALLELISM: char = 'h';
+    pub const PARALLELISM: char = 'f';
     /// Perpendicularity symbol.
-    pub const PERPENDICULARITY: char = 'j';
+    pub const PERPENDICULARITY: char = 'b';
     /// Angularity symbol.
-    pub const ANGULARITY: char = 'n';
+    pub const ANGULARITY: char = 'a';
     /// Position symbol.
-    pub const POSITION: char = 'p';
+    pub const POSITION: char = 'j';
     /// Concentricity symbol.
-    pub const CONCENTRICITY: char = 'u';
+    pub const CONCENTRICITY: char = 'r';
     /// Symmetry symbol.
     pub const SYMMETRY: char = 'i';
     /// Circular runout symbol.
-    pub const CIRCULAR_RUNOUT: char = 'r';
+    pub const CIRCULAR_RUNOUT: char = 'h';
     /// Total runout symbol.
     pub const TOTAL_RUNOUT: char = 't';
     /// Diameter symbol.
@@ -441,6 +455,8 @@ pub mod gdt_symbols {
     pub const LMC: char = 'l';
     /// RFS (Regardless of Feature Size) symbol.
     pub const RFS: char = 's';
+    /// Projected tolerance zone symbol.
+    pub const PROJECTED_ZONE: char = 'p';
 }
 
 // ============================================================================
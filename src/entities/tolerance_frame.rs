@@ -0,0 +1,468 @@
+//! Structured feature control frames
+//!
+//! A [`Tolerance`](super::Tolerance) entity stores its frame as text: rows
+//! separated by `^J`, cells separated by `%%v`, GD&T symbols written as
+//! characters of the `gdt` font, `{\Fgdt;j}`. A row holds the geometric
+//! characteristic, up to two tolerances and up to three datum references:
+//!
+//! ```text
+//! {\Fgdt;j}%%v{\Fgdt;n}0.5{\Fgdt;m}%%v%%vA%%vB{\Fgdt;m}%%vC
+//! ```
+//!
+//! [`FeatureControlFrame`] parses that text into rows and writes it back;
+//! [`FeatureControlFrameBuilder`] assembles frames in code:
+//!
+//! ```rust,ignore
+//! use acadrust::entities::{FeatureControlFrame, GdtSymbol, MaterialCondition, ToleranceValue};
+//!
+//! let frame = FeatureControlFrame::builder()
+//!     .row(GdtSymbol::Position)
+//!     .tolerance(ToleranceValue::new("0.5").diameter().material(MaterialCondition::Maximum))
+//!     .datum("A")
+//!     .datum("B")
+//!     .build();
+//! tolerance.text = frame.to_string();
+//! ```
+
+use std::fmt;
+
+use super::tolerance::gdt_symbols;
+
+/// Row separator of the frame text
+const ROW_SEPARATOR: &str = "^J";
+/// Cell separator of the frame text
+const CELL_SEPARATOR: &str = "%%v";
+
+/// Geometric characteristic of a frame row
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GdtSymbol {
+    Straightness,
+    Flatness,
+    Circularity,
+    Cylindricity,
+    LineProfile,
+    SurfaceProfile,
+    Angularity,
+    Perpendicularity,
+    Parallelism,
+    Position,
+    Concentricity,
+    Symmetry,
+    CircularRunout,
+    TotalRunout,
+}
+
+impl GdtSymbol {
+    const ALL: [GdtSymbol; 14] = [
+        GdtSymbol::Straightness,
+        GdtSymbol::Flatness,
+        GdtSymbol::Circularity,
+        GdtSymbol::Cylindricity,
+        GdtSymbol::LineProfile,
+        GdtSymbol::SurfaceProfile,
+        GdtSymbol::Angularity,
+        GdtSymbol::Perpendicularity,
+        GdtSymbol::Parallelism,
+        GdtSymbol::Position,
+        GdtSymbol::Concentricity,
+        GdtSymbol::Symmetry,
+        GdtSymbol::CircularRunout,
+        GdtSymbol::TotalRunout,
+    ];
+
+    /// Character of the symbol in the `gdt` font
+    pub fn code(self) -> char {
+        match self {
+            GdtSymbol::Straightness => gdt_symbols::STRAIGHTNESS,
+            GdtSymbol::Flatness => gdt_symbols::FLATNESS,
+            GdtSymbol::Circularity => gdt_symbols::CIRCULARITY,
+            GdtSymbol::Cylindricity => gdt_symbols::CYLINDRICITY,
+            GdtSymbol::LineProfile => gdt_symbols::LINE_PROFILE,
+            GdtSymbol::SurfaceProfile => gdt_symbols::SURFACE_PROFILE,
+            GdtSymbol::Angularity => gdt_symbols::ANGULARITY,
+            GdtSymbol::Perpendicularity => gdt_symbols::PERPENDICULARITY,
+            GdtSymbol::Parallelism => gdt_symbols::PARALLELISM,
+            GdtSymbol::Position => gdt_symbols::POSITION,
+            GdtSymbol::Concentricity => gdt_symbols::CONCENTRICITY,
+            GdtSymbol::Symmetry => gdt_symbols::SYMMETRY,
+            GdtSymbol::CircularRunout => gdt_symbols::CIRCULAR_RUNOUT,
+            GdtSymbol::TotalRunout => gdt_symbols::TOTAL_RUNOUT,
+        }
+    }
+
+    /// Symbol for a `gdt` font character
+    pub fn from_code(code: char) -> Option<Self> {
+        Self::ALL.into_iter().find(|symbol| symbol.code() == code)
+    }
+}
+
+/// Material condition modifier of a tolerance or datum reference
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MaterialCondition {
+    /// Maximum material condition, Ⓜ
+    Maximum,
+    /// Least material condition, Ⓛ
+    Least,
+    /// Regardless of feature size, Ⓢ
+    RegardlessOfFeatureSize,
+}
+
+impl MaterialCondition {
+    /// Character of the modifier in the `gdt` font
+    pub fn code(self) -> char {
+        match self {
+            MaterialCondition::Maximum => gdt_symbols::MMC,
+            MaterialCondition::Least => gdt_symbols::LMC,
+            MaterialCondition::RegardlessOfFeatureSize => gdt_symbols::RFS,
+        }
+    }
+
+    /// Modifier for a `gdt` font character
+    pub fn from_code(code: char) -> Option<Self> {
+        [Self::Maximum, Self::Least, Self::RegardlessOfFeatureSize]
+            .into_iter()
+            .find(|condition| condition.code() == code)
+    }
+}
+
+/// Tolerance cell of a frame row
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ToleranceValue {
+    /// Tolerance zone is cylindrical, shown as a leading ⌀
+    pub diameter: bool,
+    /// Tolerance value as written, e.g. `0.05`
+    pub value: String,
+    /// Material condition modifier
+    pub material: Option<MaterialCondition>,
+    /// Projected tolerance zone, Ⓟ
+    pub projected: bool,
+}
+
+impl ToleranceValue {
+    /// Tolerance without modifiers
+    pub fn new(value: impl Into<String>) -> Self {
+        Self { value: value.into(), ..Self::default() }
+    }
+
+    /// Builder: mark the tolerance zone as cylindrical
+    pub fn diameter(mut self) -> Self {
+        self.diameter = true;
+        self
+    }
+
+    /// Builder: set the material condition modifier
+    pub fn material(mut self, material: MaterialCondition) -> Self {
+        self.material = Some(material);
+        self
+    }
+
+    /// Builder: mark the tolerance zone as projected
+    pub fn projected(mut self) -> Self {
+        self.projected = true;
+        self
+    }
+}
+
+impl From<&str> for ToleranceValue {
+    fn from(value: &str) -> Self {
+        Self::new(value)
+    }
+}
+
+impl From<f64> for ToleranceValue {
+    fn from(value: f64) -> Self {
+        Self::new(value.to_string())
+    }
+}
+
+/// Datum reference cell of a frame row
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DatumReference {
+    /// Datum letter or compound reference, e.g. `A` or `A-B`
+    pub datum: String,
+    /// Material condition modifier
+    pub material: Option<MaterialCondition>,
+}
+
+impl DatumReference {
+    /// Datum reference without modifier
+    pub fn new(datum: impl Into<String>) -> Self {
+        Self { datum: datum.into(), material: None }
+    }
+
+    /// Builder: set the material condition modifier
+    pub fn material(mut self, material: MaterialCondition) -> Self {
+        self.material = Some(material);
+        self
+    }
+}
+
+impl From<&str> for DatumReference {
+    fn from(datum: &str) -> Self {
+        Self::new(datum)
+    }
+}
+
+/// One row of a feature control frame
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FrameRow {
+    /// Geometric characteristic
+    pub symbol: Option<GdtSymbol>,
+    /// Tolerances, at most two
+    pub tolerances: Vec<ToleranceValue>,
+    /// Datum references in order of precedence, at most three
+    pub datums: Vec<DatumReference>,
+}
+
+impl FrameRow {
+    /// Row for a geometric characteristic
+    pub fn new(symbol: GdtSymbol) -> Self {
+        Self { symbol: Some(symbol), ..Self::default() }
+    }
+
+    /// Parse one row of frame text.
+    ///
+    /// Cells are told apart by content rather than position: a cell with a
+    /// diameter symbol or starting with a number is a tolerance, any other
+    /// non-empty cell a datum reference.
+    pub fn parse(text: &str) -> Self {
+        let mut row = Self::default();
+        for (index, cell) in text.split(CELL_SEPARATOR).enumerate() {
+            let tokens = tokenize(cell);
+            if index == 0 {
+                if let [Token::Symbol(code)] = tokens.as_slice() {
+                    if let Some(symbol) = GdtSymbol::from_code(*code) {
+                        row.symbol = Some(symbol);
+                        continue;
+                    }
+                }
+            }
+            row.add_cell(&tokens);
+        }
+        row
+    }
+
+    fn add_cell(&mut self, tokens: &[Token]) {
+        let mut value = String::new();
+        let mut diameter = false;
+        let mut material = None;
+        let mut projected = false;
+        for token in tokens {
+            match token {
+                Token::Text(text) => value.push_str(text),
+                Token::Symbol(code) if *code == gdt_symbols::DIAMETER && value.is_empty() => diameter = true,
+                Token::Symbol(code) if *code == gdt_symbols::PROJECTED_ZONE => projected = true,
+                Token::Symbol(code) => match MaterialCondition::from_code(*code) {
+                    Some(condition) => material = Some(condition),
+                    None => value.push_str(&symbol_text(*code)),
+                },
+            }
+        }
+        let value = value.trim().to_string();
+        if value.is_empty() && !diameter {
+            return;
+        }
+
+        let numeric = value
+            .trim_start_matches(['+', '-', '±'])
+            .starts_with(|c: char| c.is_ascii_digit() || c == '.' || c == ',');
+        if diameter || projected || numeric {
+            self.tolerances.push(ToleranceValue { diameter, value, material, projected });
+        } else {
+            self.datums.push(DatumReference { datum: value, material });
+        }
+    }
+}
+
+impl fmt::Display for FrameRow {
+    /// Cells in the order symbol, two tolerances, three datums, without
+    /// trailing empty cells
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut cells = vec![self.symbol.map(|s| symbol_text(s.code())).unwrap_or_default()];
+        for index in 0..2 {
+            cells.push(self.tolerances.get(index).map(tolerance_text).unwrap_or_default());
+        }
+        cells.extend(self.datums.iter().take(3).map(|datum| {
+            let material = datum.material.map(|m| symbol_text(m.code())).unwrap_or_default();
+            format!("{}{material}", datum.datum)
+        }));
+        while cells.len() > 1 && cells.last().is_some_and(String::is_empty) {
+            cells.pop();
+        }
+        f.write_str(&cells.join(CELL_SEPARATOR))
+    }
+}
+
+fn tolerance_text(tolerance: &ToleranceValue) -> String {
+    let mut text = String::new();
+    if tolerance.diameter {
+        text.push_str(&symbol_text(gdt_symbols::DIAMETER));
+    }
+    text.push_str(&tolerance.value);
+    if let Some(material) = tolerance.material {
+        text.push_str(&symbol_text(material.code()));
+    }
+    if tolerance.projected {
+        text.push_str(&symbol_text(gdt_symbols::PROJECTED_ZONE));
+    }
+    text
+}
+
+fn symbol_text(code: char) -> String {
+    format!("{{\\Fgdt;{code}}}")
+}
+
+/// Piece of a frame cell
+#[derive(Debug, PartialEq)]
+enum Token {
+    Text(String),
+    /// Character of the `gdt` font
+    Symbol(char),
+}
+
+/// Split a cell into text and `{\Fgdt;X}` symbols
+fn tokenize(cell: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut rest = cell;
+    while let Some(start) = rest.find("{\\Fgdt;") {
+        let after = &rest[start + 7..];
+        let Some(end) = after.find('}') else { break };
+        if start > 0 {
+            tokens.push(Token::Text(rest[..start].to_string()));
+        }
+        tokens.extend(after[..end].chars().map(Token::Symbol));
+        rest = &after[end + 1..];
+    }
+    if !rest.is_empty() {
+        tokens.push(Token::Text(rest.to_string()));
+    }
+    tokens
+}
+
+/// Feature control frame of a [`Tolerance`](super::Tolerance) entity
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FeatureControlFrame {
+    /// Rows from top to bottom
+    pub rows: Vec<FrameRow>,
+}
+
+impl FeatureControlFrame {
+    /// Start building a frame
+    pub fn builder() -> FeatureControlFrameBuilder {
+        FeatureControlFrameBuilder::new()
+    }
+
+    /// Parse the text of a tolerance entity
+    pub fn parse(text: &str) -> Self {
+        if text.is_empty() {
+            return Self::default();
+        }
+        Self { rows: text.split(ROW_SEPARATOR).map(FrameRow::parse).collect() }
+    }
+
+    /// Datum references of all rows, in order of appearance
+    pub fn datums(&self) -> impl Iterator<Item = &DatumReference> {
+        self.rows.iter().flat_map(|row| row.datums.iter())
+    }
+}
+
+impl fmt::Display for FeatureControlFrame {
+    /// The text to store in a tolerance entity
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, row) in self.rows.iter().enumerate() {
+            if index > 0 {
+                f.write_str(ROW_SEPARATOR)?;
+            }
+            write!(f, "{row}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Builder for [`FeatureControlFrame`]s, row by row
+#[derive(Debug, Clone, Default)]
+pub struct FeatureControlFrameBuilder {
+    frame: FeatureControlFrame,
+}
+
+impl FeatureControlFrameBuilder {
+    /// Creates a new builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts a new row for a geometric characteristic.
+    pub fn row(mut self, symbol: GdtSymbol) -> Self {
+        self.frame.rows.push(FrameRow::new(symbol));
+        self
+    }
+
+    /// Adds a tolerance to the current row.
+    pub fn tolerance(mut self, tolerance: impl Into<ToleranceValue>) -> Self {
+        self.current_row().tolerances.push(tolerance.into());
+        self
+    }
+
+    /// Adds a datum reference to the current row.
+    pub fn datum(mut self, datum: impl Into<DatumReference>) -> Self {
+        self.current_row().datums.push(datum.into());
+        self
+    }
+
+    /// Builds the frame.
+    pub fn build(self) -> FeatureControlFrame {
+        self.frame
+    }
+
+    fn current_row(&mut self) -> &mut FrameRow {
+        if self.frame.rows.is_empty() {
+            self.frame.rows.push(FrameRow::default());
+        }
+        self.frame.rows.last_mut().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_frame() {
+        let text = "{\\Fgdt;j}%%v{\\Fgdt;n}0.5{\\Fgdt;m}%%v%%vA%%vB{\\Fgdt;m}%%vC^J{\\Fgdt;c}%%v0.1";
+        let frame = FeatureControlFrame::parse(text);
+        assert_eq!(frame.rows.len(), 2);
+
+        let position = &frame.rows[0];
+        assert_eq!(position.symbol, Some(GdtSymbol::Position));
+        assert_eq!(
+            position.tolerances,
+            [ToleranceValue::new("0.5").diameter().material(MaterialCondition::Maximum)]
+        );
+        let datums: Vec<&str> = frame.datums().map(|d| d.datum.as_str()).collect();
+        assert_eq!(datums, ["A", "B", "C"]);
+        assert_eq!(position.datums[1].material, Some(MaterialCondition::Maximum));
+
+        assert_eq!(frame.rows[1].symbol, Some(GdtSymbol::Flatness));
+        assert_eq!(frame.to_string(), text);
+    }
+
+    #[test]
+    fn test_build_frame() {
+        let frame = FeatureControlFrame::builder()
+            .row(GdtSymbol::Perpendicularity)
+            .tolerance(0.05)
+            .datum(DatumReference::new("A").material(MaterialCondition::Least))
+            .row(GdtSymbol::Position)
+            .tolerance(ToleranceValue::new("0.2").projected())
+            .build();
+        let text = frame.to_string();
+        assert_eq!(text, "{\\Fgdt;b}%%v0.05%%v%%vA{\\Fgdt;l}^J{\\Fgdt;j}%%v0.2{\\Fgdt;p}");
+        assert_eq!(FeatureControlFrame::parse(&text), frame);
+    }
+}
@@ -0,0 +1,182 @@
+//! Triangulation of filled planar entities
+//!
+//! SOLID (and TRACE) corners run 1-2-4-3 around their outline, 3DFACE
+//! corners 1-2-3-4; both split along the 1-4 or 1-3 diagonal into two
+//! triangles, or one when the last two corners coincide. WIPEOUT
+//! boundaries are ear-clipped. Triangles are in WCS and keep the
+//! visibility of the outline edges they lie on; diagonals are invisible.
+
+use super::face3d::Face3D;
+use super::solid::Solid;
+use super::wipeout::{Wipeout, WipeoutClipType};
+use crate::types::geometry::{ocs_matrix, signed_area, triangulate};
+use crate::types::{Vector2, Vector3};
+
+/// A triangle of a filled entity
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Triangle {
+    /// Corners, in the winding of the entity outline; counter-clockwise
+    /// in the U-V plane for a wipeout
+    pub corners: [Vector3; 3],
+    /// Whether the edge from each corner to the next is drawn
+    pub visible_edges: [bool; 3],
+}
+
+impl Triangle {
+    /// Area of the triangle
+    pub fn area(&self) -> f64 {
+        let [a, b, c] = self.corners;
+        (b - a).cross(&(c - a)).length() / 2.0
+    }
+}
+
+impl Solid {
+    /// The one or two triangles the solid is filled with
+    pub fn as_triangles(&self) -> Vec<Triangle> {
+        let ocs = ocs_matrix(self.normal);
+        let [a, b, c, d] = [self.first_corner, self.second_corner, self.third_corner, self.fourth_corner]
+            .map(|p| ocs.transform_point(p));
+        let closing = self.is_triangle();
+        let mut triangles = Vec::with_capacity(2);
+        push(&mut triangles, [a, b, d], [true, true, closing]);
+        push(&mut triangles, [a, d, c], [false, true, true]);
+        triangles
+    }
+}
+
+impl Face3D {
+    /// The one or two triangles of the face, with its invisible edges
+    pub fn as_triangles(&self) -> Vec<Triangle> {
+        let [a, b, c, d] = [self.first_corner, self.second_corner, self.third_corner, self.fourth_corner];
+        let edges = &self.invisible_edges;
+        let [first, second, third, fourth] = [
+            !edges.is_first_invisible(),
+            !edges.is_second_invisible(),
+            !edges.is_third_invisible(),
+            !edges.is_fourth_invisible(),
+        ];
+        let closing = if self.is_triangle() { fourth } else { false };
+        let mut triangles = Vec::with_capacity(2);
+        push(&mut triangles, [a, b, c], [first, second, closing]);
+        push(&mut triangles, [a, c, d], [false, third, fourth]);
+        triangles
+    }
+}
+
+impl Wipeout {
+    /// Triangles covering the masked area inside the clip boundary, with
+    /// the frame edges visible
+    pub fn as_triangles(&self) -> Vec<Triangle> {
+        let mut boundary = self.clip_boundary_vertices.clone();
+        if self.clip_type == WipeoutClipType::Rectangular && boundary.len() == 2 {
+            let (lower, upper) = (boundary[0], boundary[1]);
+            boundary = vec![lower, Vector2::new(upper.x, lower.y), upper, Vector2::new(lower.x, upper.y)];
+        }
+        // The boundary may repeat its first vertex at the end
+        if boundary.len() > 3 && boundary.first() == boundary.last() {
+            boundary.pop();
+        }
+        if signed_area(&boundary) < 0.0 {
+            boundary.reverse();
+        }
+
+        let n = boundary.len();
+        let world = |p: Vector2| self.insertion_point + self.u_vector * p.x + self.v_vector * p.y;
+        let outline = |from: usize, to: usize| (from + 1) % n == to;
+        let mut triangles = Vec::with_capacity(n.saturating_sub(2));
+        for [i, j, k] in triangulate(&boundary) {
+            let corners = [i, j, k].map(|v| world(boundary[v]));
+            push(&mut triangles, corners, [outline(i, j), outline(j, k), outline(k, i)]);
+        }
+        triangles
+    }
+}
+
+/// Start corners of the visible edges, in order
+///
+/// For the triangles of a solid this is its outline, corners 1-2-4-3.
+pub(crate) fn outline(triangles: &[Triangle]) -> Vec<Vector3> {
+    triangles
+        .iter()
+        .flat_map(|t| (0..3).filter(|&i| t.visible_edges[i]).map(|i| t.corners[i]))
+        .collect()
+}
+
+/// Add a triangle unless it has no area
+fn push(triangles: &mut Vec<Triangle>, corners: [Vector3; 3], visible_edges: [bool; 3]) {
+    let triangle = Triangle { corners, visible_edges };
+    if triangle.area() > 1e-12 {
+        triangles.push(triangle);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::InvisibleEdgeFlags;
+
+    #[test]
+    fn test_solid_triangles() {
+        // Corners in SOLID order: the unit square is 1-2-4-3
+        let solid = Solid::new(
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(1.0, 0.0, 0.0),
+            Vector3::new(0.0, 1.0, 0.0),
+            Vector3::new(1.0, 1.0, 0.0),
+        );
+        let triangles = solid.as_triangles();
+        assert_eq!(triangles.len(), 2);
+        assert!((triangles.iter().map(Triangle::area).sum::<f64>() - 1.0).abs() < 1e-12);
+        assert_eq!(triangles[0].visible_edges, [true, true, false]);
+
+        let triangle = Solid::triangle(Vector3::ZERO, Vector3::new(2.0, 0.0, 0.0), Vector3::new(0.0, 2.0, 0.0));
+        let triangles = triangle.as_triangles();
+        assert_eq!(triangles.len(), 1);
+        assert_eq!(triangles[0].visible_edges, [true, true, true]);
+
+        assert_eq!(outline(&solid.as_triangles()), vec![solid.first_corner, solid.second_corner, solid.fourth_corner, solid.third_corner]);
+
+        // Flipped OCS: the corners land mirrored in X
+        let flipped = Solid { normal: -Vector3::UNIT_Z, ..triangle };
+        assert_eq!(flipped.as_triangles()[0].corners[1], Vector3::new(-2.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn test_face_triangles_keep_invisible_edges() {
+        let mut flags = InvisibleEdgeFlags::new();
+        flags.set_second_invisible(true);
+        let face = Face3D::new(
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(1.0, 0.0, 0.0),
+            Vector3::new(1.0, 1.0, 1.0),
+            Vector3::new(0.0, 1.0, 1.0),
+        )
+        .with_invisible_edges(flags);
+        let triangles = face.as_triangles();
+        assert_eq!(triangles.len(), 2);
+        assert_eq!(triangles[0].visible_edges, [true, false, false]);
+        assert_eq!(triangles[1].visible_edges, [false, true, true]);
+        assert!((triangles.iter().map(Triangle::area).sum::<f64>() - face.area()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_wipeout_triangles() {
+        let rectangle = Wipeout::rectangular(Vector3::new(10.0, 20.0, 0.0), 4.0, 2.0);
+        let triangles = rectangle.as_triangles();
+        assert_eq!(triangles.len(), 2);
+        assert!((triangles.iter().map(Triangle::area).sum::<f64>() - 8.0).abs() < 1e-12);
+        assert_eq!(triangles.iter().flat_map(|t| t.visible_edges).filter(|v| *v).count(), 4);
+
+        // A clockwise L shape, with the first vertex repeated
+        let l_shape = [(0.0, 0.0), (0.0, 2.0), (1.0, 2.0), (1.0, 1.0), (2.0, 1.0), (2.0, 0.0), (0.0, 0.0)]
+            .map(|(x, y)| Vector2::new(x, y));
+        let wipeout = Wipeout::polygonal(&l_shape, 0.0);
+        let triangles = wipeout.as_triangles();
+        assert_eq!(triangles.len(), 4);
+        assert!((triangles.iter().map(Triangle::area).sum::<f64>() - wipeout.area()).abs() < 1e-9);
+        for triangle in &triangles {
+            let [a, b, c] = triangle.corners;
+            assert!((b - a).cross(&(c - a)).z > 0.0);
+        }
+    }
+}
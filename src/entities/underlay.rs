@@ -4,7 +4,7 @@
 //! as reference images in a drawing.
 
 use crate::entities::{Entity, EntityCommon};
-use crate::types::{BoundingBox3D, Color, Handle, LineWeight, Transparency, Vector2, Vector3};
+use crate::types::{Angle, BoundingBox3D, Color, Handle, LineWeight, Transparency, Vector2, Vector3};
 
 use bitflags::bitflags;
 
@@ -15,6 +15,7 @@ use bitflags::bitflags;
 bitflags! {
     /// Display flags for underlay entities.
     #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct UnderlayDisplayFlags: u8 {
         /// No flags.
         const NONE = 0;
@@ -35,6 +36,7 @@ bitflags! {
 
 /// Type of underlay.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum UnderlayType {
     /// PDF file underlay.
     #[default]
@@ -69,6 +71,15 @@ impl UnderlayType {
         "AcDbUnderlayReference"
     }
 
+    /// Returns the named object dictionary entry holding the definitions.
+    pub fn dictionary_name(&self) -> &'static str {
+        match self {
+            UnderlayType::Pdf => "ACAD_PDFDEFINITIONS",
+            UnderlayType::Dwf => "ACAD_DWFDEFINITIONS",
+            UnderlayType::Dgn => "ACAD_DGNDEFINITIONS",
+        }
+    }
+
     /// Returns the definition subclass marker.
     pub fn definition_subclass_marker(&self) -> &'static str {
         match self {
@@ -88,6 +99,7 @@ impl UnderlayType {
 /// This is a non-graphical object that stores the path to the external
 /// file and page/sheet information.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UnderlayDefinition {
     /// Object handle.
     pub handle: Handle,
@@ -196,6 +208,7 @@ impl Default for UnderlayDefinition {
 /// Represents an underlay reference placed in the drawing.
 /// This is a common structure for PDF, DWF, and DGN underlays.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Underlay {
     /// Common entity data.
     pub common: EntityCommon,
@@ -361,6 +374,16 @@ impl Underlay {
         self.rotation.to_degrees()
     }
 
+    /// Gets the rotation.
+    pub fn rotation(&self) -> Angle {
+        Angle::from_radians(self.rotation)
+    }
+
+    /// Sets the rotation.
+    pub fn set_rotation(&mut self, angle: Angle) {
+        self.rotation = angle.radians();
+    }
+
     /// Sets a rectangular clip boundary.
     pub fn set_rectangular_clip(&mut self, min: Vector2, max: Vector2) {
         self.clip_boundary_vertices = vec![
@@ -2,25 +2,28 @@
 //!
 //! When the reader encounters an entity type that is not directly supported,
 //! it can still capture the common entity properties (handle, layer, color, …)
-//! wrapped in this type.  The entity-specific codes are discarded — this
-//! matches the ACadSharp `UnknownEntity` behavior.
+//! wrapped in this type.
 //!
-//! Unknown entities are **never written back** to DXF output, following the
-//! same convention as ACadSharp.
+//! Entities read from DXF also keep their raw code/value pairs, which the DXF
+//! writer re-emits verbatim so the entity survives a round-trip.  Entities
+//! without raw pairs (e.g. read from DWG) are not written back.
 
 use crate::entities::{Entity, EntityCommon};
 use crate::types::{BoundingBox3D, Color, Handle, LineWeight, Transform, Transparency, Vector3};
 
 /// An entity whose type is not directly supported by the library.
 ///
-/// Preserves the DXF type name (e.g. `"ACAD_PROXY_ENTITY"`) and common entity
-/// properties.  Entity-specific codes are discarded.
+/// Preserves the DXF type name (e.g. `"ACAD_PROXY_ENTITY"`), common entity
+/// properties and, when read from DXF, the raw code/value pairs.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UnknownEntity {
     /// Common entity data (handle, layer, color, reactors, …).
     pub common: EntityCommon,
     /// The DXF type name as it appeared in the file (e.g. `"ACAD_PROXY_ENTITY"`).
     pub dxf_name: String,
+    /// Raw code/value pairs following the `0` type marker, in file order.
+    pub raw_pairs: Vec<(i32, String)>,
 }
 
 impl UnknownEntity {
@@ -29,6 +32,7 @@ impl UnknownEntity {
         Self {
             common: EntityCommon::new(),
             dxf_name: dxf_name.into(),
+            raw_pairs: Vec::new(),
         }
     }
 }
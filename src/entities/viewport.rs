@@ -1,10 +1,12 @@
 //! Viewport entity - Paper space viewport for model space views
 
 use crate::entities::{Entity, EntityCommon};
-use crate::types::{BoundingBox3D, Color, Handle, LineWeight, Transparency, Vector3};
+use crate::types::{Angle, BoundingBox3D, Color, Handle, LineWeight, Transparency, Vector3};
+use crate::objects::Scale;
 
 /// Viewport status flags
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ViewportStatusFlags {
     /// Viewport is on (visible)
     pub is_on: bool,
@@ -96,6 +98,7 @@ impl ViewportStatusFlags {
 
 /// Render mode for viewport display
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ViewportRenderMode {
     /// 2D Wireframe
     #[default]
@@ -136,6 +139,7 @@ impl ViewportRenderMode {
 
 /// Grid display flags
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GridFlags {
     /// Grid beyond limits
     pub beyond_limits: bool,
@@ -192,6 +196,7 @@ impl GridFlags {
 /// viewport.custom_scale = 0.5; // 1:2 scale
 /// ```
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Viewport {
     /// Common entity properties
     pub common: EntityCommon,
@@ -225,9 +230,9 @@ pub struct Viewport {
     pub back_clip_z: f64,
     /// View height (in model space units)
     pub view_height: f64,
-    /// Snap angle
+    /// Snap angle in radians
     pub snap_angle: f64,
-    /// View twist angle
+    /// View twist angle in radians
     pub twist_angle: f64,
     /// Circle zoom percent (1-20000)
     pub circle_sides: i16,
@@ -265,6 +270,8 @@ pub struct Viewport {
     pub shade_plot_handle: Handle,
     /// Visual style handle
     pub visual_style_handle: Handle,
+    /// SUN object handle, owned by the viewport
+    pub sun_handle: Handle,
     /// Default lighting on
     pub default_lighting: bool,
     /// Default lighting type
@@ -277,6 +284,11 @@ pub struct Viewport {
     pub ambient_color: i32,
     /// Custom scale factor
     pub custom_scale: f64,
+    /// Annotation scale, a SCALE object of `ACAD_SCALELIST`.
+    ///
+    /// The VIEWPORT record has no field for it, so it is not written;
+    /// `custom_scale` carries the factor into the file.
+    pub annotation_scale: Option<Handle>,
 }
 
 impl Viewport {
@@ -319,15 +331,37 @@ impl Viewport {
             background_handle: Handle::NULL,
             shade_plot_handle: Handle::NULL,
             visual_style_handle: Handle::NULL,
+            sun_handle: Handle::NULL,
             default_lighting: true,
             default_lighting_type: 1,
             brightness: 0.0,
             contrast: 0.0,
             ambient_color: 0,
             custom_scale: 1.0,
+            annotation_scale: None,
         }
     }
 
+    /// Snap angle
+    pub fn snap_angle(&self) -> Angle {
+        Angle::from_radians(self.snap_angle)
+    }
+
+    /// Set the snap angle
+    pub fn set_snap_angle(&mut self, angle: Angle) {
+        self.snap_angle = angle.radians();
+    }
+
+    /// View twist angle
+    pub fn twist_angle(&self) -> Angle {
+        Angle::from_radians(self.twist_angle)
+    }
+
+    /// Set the view twist angle
+    pub fn set_twist_angle(&mut self, angle: Angle) {
+        self.twist_angle = angle.radians();
+    }
+
     /// Create a viewport with specific size and position
     pub fn with_size(center: Vector3, width: f64, height: f64) -> Self {
         let mut vp = Self::new();
@@ -381,6 +415,15 @@ impl Viewport {
         }
     }
 
+    /// Show model space at a scale of the drawing's scale list.
+    ///
+    /// Sets the annotation scale to the scale's handle and zooms to its
+    /// factor, which also becomes the custom scale.
+    pub fn set_standard_scale(&mut self, scale: Scale) {
+        self.annotation_scale = Some(scale.handle);
+        self.set_scale(scale.factor());
+    }
+
     /// Lock the viewport (prevent zoom/pan)
     pub fn lock(&mut self) {
         self.status.locked = true;
@@ -522,6 +565,7 @@ impl Viewport {
 
 /// Standard view directions
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum StandardView {
     /// Top view (looking down Z)
     Top,
@@ -670,6 +714,19 @@ mod tests {
         assert!((vp.view_height - 400.0).abs() < 1e-10);
     }
 
+    #[test]
+    fn test_viewport_standard_scale() {
+        let mut vp = Viewport::new();
+        vp.height = 100.0;
+        let mut scale = Scale::scale_1_50();
+        scale.handle = Handle::new(0x2A);
+
+        vp.set_standard_scale(scale);
+        assert_eq!(vp.annotation_scale, Some(Handle::new(0x2A)));
+        assert!((vp.custom_scale - 0.02).abs() < 1e-10);
+        assert!((vp.view_height - 5000.0).abs() < 1e-6);
+    }
+
     #[test]
     fn test_viewport_lock() {
         let mut vp = Viewport::new();
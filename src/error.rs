@@ -1,8 +1,39 @@
 //! Error types for acadrust library
 
+use std::fmt;
 use std::io;
 use thiserror::Error;
 
+/// Location in a DXF stream where a read error or notification occurred
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DxfLocation {
+    /// Line number (ASCII DXF) or byte offset (binary DXF)
+    pub position: u64,
+    /// Whether `position` is a byte offset into a binary DXF file
+    pub is_binary: bool,
+    /// Section being read, e.g. `ENTITIES`
+    pub section: Option<String>,
+    /// Entity, object or table entry being read, e.g. `LWPOLYLINE`
+    pub entity: Option<String>,
+}
+
+impl fmt::Display for DxfLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_binary {
+            write!(f, "offset {}", self.position)?;
+        } else {
+            write!(f, "line {}", self.position)?;
+        }
+        if let Some(section) = &self.section {
+            write!(f, ", section {}", section)?;
+        }
+        if let Some(entity) = &self.entity {
+            write!(f, ", {}", entity)?;
+        }
+        Ok(())
+    }
+}
+
 /// Main error type for acadrust operations
 #[derive(Debug, Error)]
 pub enum DxfError {
@@ -73,6 +104,53 @@ pub enum DxfError {
     /// Generic error with custom message
     #[error("{0}")]
     Custom(String),
+
+    /// The read was cancelled through a `CancellationToken`
+    #[error("Read cancelled")]
+    Cancelled,
+
+    /// Error raised while reading a DXF stream, with its location
+    #[error("{source} at {location}")]
+    DxfRead {
+        /// Line or byte offset, section and entity the error was raised at
+        location: DxfLocation,
+        /// The error itself
+        source: Box<DxfError>,
+    },
+}
+
+impl DxfError {
+    /// Attach a DXF stream location to this error.
+    ///
+    /// Errors that already carry a location are returned unchanged, and so
+    /// are I/O errors, which stay [`DxfError::Io`].
+    pub fn with_location(self, location: DxfLocation) -> Self {
+        match self {
+            DxfError::DxfRead { .. } | DxfError::Io(_) => self,
+            other => DxfError::DxfRead {
+                location,
+                source: Box::new(other),
+            },
+        }
+    }
+
+    /// Whether this is an I/O error of the underlying stream, with or
+    /// without a location
+    pub fn is_io(&self) -> bool {
+        match self {
+            DxfError::Io(_) => true,
+            DxfError::DxfRead { source, .. } => source.is_io(),
+            _ => false,
+        }
+    }
+
+    /// Get the DXF stream location of this error, if known
+    pub fn location(&self) -> Option<&DxfLocation> {
+        match self {
+            DxfError::DxfRead { location, .. } => Some(location),
+            _ => None,
+        }
+    }
 }
 
 /// Result type alias for acadrust operations
@@ -119,6 +197,29 @@ mod tests {
         let dxf_err: DxfError = io_err.into();
         assert!(matches!(dxf_err, DxfError::Io(_)));
     }
-}
-
 
+    #[test]
+    fn test_with_location() {
+        let location = DxfLocation {
+            position: 42,
+            is_binary: false,
+            section: Some("ENTITIES".to_string()),
+            entity: Some("LINE".to_string()),
+        };
+        let err = DxfError::Parse("bad value".to_string()).with_location(location.clone());
+        assert_eq!(err.location(), Some(&location));
+        assert_eq!(err.to_string(), "Parse error: bad value at line 42, section ENTITIES, LINE");
+
+        // Already-located errors keep their original location
+        let err = err.with_location(DxfLocation::default());
+        assert_eq!(err.location(), Some(&location));
+
+        // I/O errors keep their variant
+        let err = DxfError::from(io::Error::other("broken pipe")).with_location(location.clone());
+        assert!(matches!(err, DxfError::Io(_)));
+        assert!(err.is_io());
+        let located = DxfError::DxfRead { location, source: Box::new(DxfError::from(io::Error::other("eof"))) };
+        assert!(located.is_io());
+        assert!(!DxfError::Parse("bad".into()).is_io());
+    }
+}
@@ -0,0 +1,464 @@
+//! C API for embedding (`ffi` feature)
+//!
+//! Documents are passed across the boundary as opaque `AcadDocument`
+//! pointers; every function returns an [`AcadStatus`] or a plain value and
+//! never unwinds into C. The matching header is `include/acadrust.h`.
+//!
+//! Build the shared library with
+//! `cargo rustc --release --lib --features ffi --crate-type cdylib`.
+//!
+//! Strings are UTF-8. Functions that return a string copy it into a caller
+//! buffer, `snprintf` style: at most `len - 1` bytes plus a NUL are written
+//! and the full length is returned, so a call with `len == 0` sizes the
+//! buffer.
+
+use std::cell::RefCell;
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use crate::convert::{read_any, OutputFormat};
+use crate::document::CadDocument;
+use crate::entities::Entity;
+use crate::error::{DxfError, Result};
+use crate::io::dwg::DwgWriter;
+use crate::io::dxf::DxfWriter;
+use crate::types::{Color, DxfVersion, Handle};
+
+/// Result of an API call
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AcadStatus {
+    /// Success
+    Ok = 0,
+    /// A required pointer argument was NULL or a string was not UTF-8
+    InvalidArgument = 1,
+    /// File system error
+    Io = 2,
+    /// The input is not a readable DWG/DXF/DXB file, or the version is not
+    /// supported
+    InvalidFormat = 3,
+    /// No entity with the given handle
+    NotFound = 4,
+    /// Any other error
+    Error = 5,
+    /// A Rust panic was caught
+    Panic = 6,
+}
+
+/// Output format for [`acad_document_write`], passed as its `u32` value
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AcadFormat {
+    /// ASCII DXF
+    Dxf = 0,
+    /// Binary DXF
+    DxfBinary = 1,
+    /// DWG
+    Dwg = 2,
+}
+
+/// Opaque document handle
+pub struct AcadDocument {
+    doc: CadDocument,
+    /// Entity handles in ascending order, for index based access
+    handles: Vec<Handle>,
+}
+
+impl AcadDocument {
+    fn new(doc: CadDocument) -> Self {
+        let mut handles: Vec<Handle> = doc.entities().map(|e| e.common().handle).collect();
+        handles.sort();
+        Self { doc, handles }
+    }
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(message: String) {
+    let message = CString::new(message.replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|e| *e.borrow_mut() = Some(message));
+}
+
+fn status_of(error: &DxfError) -> AcadStatus {
+    match error {
+        DxfError::Io(_) => AcadStatus::Io,
+        DxfError::DxfRead { source, .. } => status_of(source),
+        DxfError::InvalidFormat(_)
+        | DxfError::InvalidHeader(_)
+        | DxfError::UnsupportedVersion(_)
+        | DxfError::Parse(_) => AcadStatus::InvalidFormat,
+        _ => AcadStatus::Error,
+    }
+}
+
+/// Run `f`, recording any error or panic for [`acad_last_error`]
+fn guard<F: FnOnce() -> std::result::Result<(), AcadStatus>>(f: F) -> AcadStatus {
+    match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(())) => AcadStatus::Ok,
+        Ok(Err(status)) => status,
+        Err(_) => {
+            set_last_error("panic inside acadrust".to_string());
+            AcadStatus::Panic
+        }
+    }
+}
+
+fn check<T>(result: Result<T>) -> std::result::Result<T, AcadStatus> {
+    result.map_err(|e| {
+        set_last_error(e.to_string());
+        status_of(&e)
+    })
+}
+
+unsafe fn str_arg<'a>(s: *const c_char) -> std::result::Result<&'a str, AcadStatus> {
+    if s.is_null() {
+        set_last_error("NULL string argument".to_string());
+        return Err(AcadStatus::InvalidArgument);
+    }
+    CStr::from_ptr(s).to_str().map_err(|_| {
+        set_last_error("string argument is not valid UTF-8".to_string());
+        AcadStatus::InvalidArgument
+    })
+}
+
+fn format_arg(format: u32) -> std::result::Result<OutputFormat, AcadStatus> {
+    [AcadFormat::Dxf, AcadFormat::DxfBinary, AcadFormat::Dwg]
+        .into_iter()
+        .find(|f| *f as u32 == format)
+        .map(OutputFormat::from)
+        .ok_or_else(|| {
+            set_last_error(format!("unknown output format {format}"));
+            AcadStatus::InvalidArgument
+        })
+}
+
+unsafe fn doc_arg<'a>(doc: *const AcadDocument) -> std::result::Result<&'a AcadDocument, AcadStatus> {
+    doc.as_ref().ok_or_else(|| {
+        set_last_error("NULL document".to_string());
+        AcadStatus::InvalidArgument
+    })
+}
+
+unsafe fn entity_arg<'a>(doc: *const AcadDocument, handle: u64) -> std::result::Result<&'a dyn Entity, AcadStatus> {
+    let doc = doc_arg(doc)?;
+    match doc.doc.get_entity(Handle::new(handle)) {
+        Some(entity) => Ok(entity.as_entity()),
+        None => {
+            set_last_error(format!("no entity with handle {:X}", handle));
+            Err(AcadStatus::NotFound)
+        }
+    }
+}
+
+unsafe fn copy_string(value: &str, buf: *mut c_char, len: usize) -> usize {
+    if !buf.is_null() && len > 0 {
+        let n = value.len().min(len - 1);
+        std::ptr::copy_nonoverlapping(value.as_ptr() as *const c_char, buf, n);
+        *buf.add(n) = 0;
+    }
+    value.len()
+}
+
+unsafe fn store(out: *mut *mut AcadDocument, doc: CadDocument) -> std::result::Result<(), AcadStatus> {
+    *out = Box::into_raw(Box::new(AcadDocument::new(doc)));
+    Ok(())
+}
+
+/// Message of the last error on this thread, or NULL.
+///
+/// The pointer stays valid until the next failing call on the same thread.
+#[no_mangle]
+pub extern "C" fn acad_last_error() -> *const c_char {
+    LAST_ERROR.with(|e| e.borrow().as_ref().map_or(std::ptr::null(), |s| s.as_ptr()))
+}
+
+/// Create an empty document
+#[no_mangle]
+pub extern "C" fn acad_document_new() -> *mut AcadDocument {
+    Box::into_raw(Box::new(AcadDocument::new(CadDocument::new())))
+}
+
+/// Open a DWG, DXF or DXB file; the format is detected from its contents
+///
+/// # Safety
+///
+/// `path` must be NULL or a NUL-terminated string and `out` must be NULL or
+/// valid for a pointer write.
+#[no_mangle]
+pub unsafe extern "C" fn acad_document_open(path: *const c_char, out: *mut *mut AcadDocument) -> AcadStatus {
+    guard(|| {
+        let path = str_arg(path)?;
+        if out.is_null() {
+            set_last_error("NULL output pointer".to_string());
+            return Err(AcadStatus::InvalidArgument);
+        }
+        let bytes = check(std::fs::read(path).map_err(DxfError::from))?;
+        store(out, check(read_any(bytes, false))?)
+    })
+}
+
+/// Read a DWG, DXF or DXB file from memory
+///
+/// # Safety
+///
+/// `data` must be valid for reads of `len` bytes and `out` must be NULL or
+/// valid for a pointer write.
+#[no_mangle]
+pub unsafe extern "C" fn acad_document_read(data: *const u8, len: usize, out: *mut *mut AcadDocument) -> AcadStatus {
+    guard(|| {
+        if data.is_null() || out.is_null() {
+            set_last_error("NULL data or output pointer".to_string());
+            return Err(AcadStatus::InvalidArgument);
+        }
+        let bytes = std::slice::from_raw_parts(data, len).to_vec();
+        store(out, check(read_any(bytes, false))?)
+    })
+}
+
+/// Write the document to `path`.
+///
+/// `format` is an [`AcadFormat`] value, passed as an integer since C may
+/// hand over any value; others fail with `InvalidArgument`. `version` is a
+/// version string such as `"AC1018"`, or NULL to keep the document's
+/// version.
+///
+/// # Safety
+///
+/// `doc` must be NULL or a live document; `path` and `version` must be NULL
+/// or NUL-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn acad_document_write(
+    doc: *const AcadDocument,
+    path: *const c_char,
+    format: u32,
+    version: *const c_char,
+) -> AcadStatus {
+    guard(|| {
+        let doc = doc_arg(doc)?;
+        let path = str_arg(path)?;
+        let format = format_arg(format)?;
+        let mut copy = doc.doc.clone();
+        if !version.is_null() {
+            let version = str_arg(version)?;
+            copy.version = check(
+                DxfVersion::parse(version).ok_or_else(|| DxfError::UnsupportedVersion(version.to_string())),
+            )?;
+        }
+        let bytes = check(match format {
+            OutputFormat::Dxf => DxfWriter::new(copy).write_to_vec(),
+            OutputFormat::DxfBinary => DxfWriter::new_binary(copy).write_to_vec(),
+            OutputFormat::Dwg => DwgWriter::write(&copy),
+        })?;
+        check(std::fs::write(path, bytes).map_err(DxfError::from))
+    })
+}
+
+/// Free a document; NULL is ignored
+///
+/// # Safety
+///
+/// `doc` must be NULL or a document not freed before.
+#[no_mangle]
+pub unsafe extern "C" fn acad_document_free(doc: *mut AcadDocument) {
+    if !doc.is_null() {
+        drop(Box::from_raw(doc));
+    }
+}
+
+/// Version string of the document (e.g. `AC1018`), copied into `buf`
+///
+/// # Safety
+///
+/// `doc` must be NULL or a live document and `buf` valid for `len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn acad_document_version(doc: *const AcadDocument, buf: *mut c_char, len: usize) -> usize {
+    match doc.as_ref() {
+        Some(doc) => copy_string(doc.doc.version.as_str(), buf, len),
+        None => 0,
+    }
+}
+
+/// Number of entities in the document
+///
+/// # Safety
+///
+/// `doc` must be NULL or a live document.
+#[no_mangle]
+pub unsafe extern "C" fn acad_document_entity_count(doc: *const AcadDocument) -> usize {
+    doc.as_ref().map_or(0, |doc| doc.handles.len())
+}
+
+/// Handle of the entity at `index` (ascending handle order), or 0 if out of
+/// range
+///
+/// # Safety
+///
+/// `doc` must be NULL or a live document.
+#[no_mangle]
+pub unsafe extern "C" fn acad_document_entity_handle(doc: *const AcadDocument, index: usize) -> u64 {
+    doc.as_ref().and_then(|doc| doc.handles.get(index)).map_or(0, |h| h.value())
+}
+
+/// DXF type name of an entity (e.g. `LINE`), copied into `buf`; 0 if the
+/// entity does not exist
+///
+/// # Safety
+///
+/// `doc` must be NULL or a live document and `buf` valid for `len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn acad_entity_type(doc: *const AcadDocument, handle: u64, buf: *mut c_char, len: usize) -> usize {
+    match entity_arg(doc, handle) {
+        Ok(entity) => copy_string(entity.entity_type(), buf, len),
+        Err(_) => 0,
+    }
+}
+
+/// Layer name of an entity, copied into `buf`; 0 if the entity does not
+/// exist
+///
+/// # Safety
+///
+/// `doc` must be NULL or a live document and `buf` valid for `len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn acad_entity_layer(doc: *const AcadDocument, handle: u64, buf: *mut c_char, len: usize) -> usize {
+    match entity_arg(doc, handle) {
+        Ok(entity) => copy_string(entity.layer(), buf, len),
+        Err(_) => 0,
+    }
+}
+
+/// Color of an entity.
+///
+/// `index` receives the ACI index (0 ByBlock, 256 ByLayer) or -1 for a true
+/// color; `rgb` (may be NULL) receives `0xRRGGBB`, or `0xFFFFFFFF` for
+/// ByLayer/ByBlock.
+///
+/// # Safety
+///
+/// `doc` must be NULL or a live document; `index` and `rgb` must be NULL or
+/// valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn acad_entity_color(doc: *const AcadDocument, handle: u64, index: *mut i16, rgb: *mut u32) -> AcadStatus {
+    guard(|| {
+        let color: Color = entity_arg(doc, handle)?.color();
+        if !index.is_null() {
+            *index = color.index().map_or(-1, |i| i as i16);
+        }
+        if !rgb.is_null() {
+            *rgb = color.to_rgb().map_or(u32::MAX, |(r, g, b)| u32::from_be_bytes([0, r, g, b]));
+        }
+        Ok(())
+    })
+}
+
+/// Bounding box of an entity as `min[3]` and `max[3]`
+///
+/// # Safety
+///
+/// `doc` must be NULL or a live document; `min` and `max` must be NULL or
+/// valid for writes of three doubles.
+#[no_mangle]
+pub unsafe extern "C" fn acad_entity_bounds(doc: *const AcadDocument, handle: u64, min: *mut f64, max: *mut f64) -> AcadStatus {
+    guard(|| {
+        if min.is_null() || max.is_null() {
+            set_last_error("NULL output pointer".to_string());
+            return Err(AcadStatus::InvalidArgument);
+        }
+        let bounds = entity_arg(doc, handle)?.bounding_box();
+        for (out, v) in [(min, bounds.min), (max, bounds.max)] {
+            *out = v.x;
+            *out.add(1) = v.y;
+            *out.add(2) = v.z;
+        }
+        Ok(())
+    })
+}
+
+impl From<AcadFormat> for OutputFormat {
+    fn from(format: AcadFormat) -> Self {
+        match format {
+            AcadFormat::Dxf => OutputFormat::Dxf,
+            AcadFormat::DxfBinary => OutputFormat::DxfBinary,
+            AcadFormat::Dwg => OutputFormat::Dwg,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::{EntityType, Line};
+
+    #[test]
+    fn test_read_query_write() {
+        let mut doc = CadDocument::new();
+        let mut line = Line::from_coords(0.0, 0.0, 0.0, 3.0, 4.0, 0.0);
+        line.common.color = Color::Index(1);
+        doc.add_entity(EntityType::Line(line)).unwrap();
+        let bytes = DxfWriter::new(doc).write_to_vec().unwrap();
+
+        unsafe {
+            let mut handle = std::ptr::null_mut();
+            assert_eq!(acad_document_read(bytes.as_ptr(), bytes.len(), &mut handle), AcadStatus::Ok);
+            assert_eq!(acad_document_entity_count(handle), 1);
+
+            let entity = acad_document_entity_handle(handle, 0);
+            let mut buf = [0 as c_char; 16];
+            assert_eq!(acad_entity_type(handle, entity, buf.as_mut_ptr(), buf.len()), 4);
+            assert_eq!(CStr::from_ptr(buf.as_ptr()).to_str().unwrap(), "LINE");
+            assert_eq!(acad_entity_layer(handle, entity, std::ptr::null_mut(), 0), 1);
+
+            let (mut index, mut rgb) = (0i16, 0u32);
+            assert_eq!(acad_entity_color(handle, entity, &mut index, &mut rgb), AcadStatus::Ok);
+            assert_eq!((index, rgb), (1, 0xFF0000));
+
+            let (mut min, mut max) = ([0.0; 3], [0.0; 3]);
+            assert_eq!(acad_entity_bounds(handle, entity, min.as_mut_ptr(), max.as_mut_ptr()), AcadStatus::Ok);
+            assert_eq!(max, [3.0, 4.0, 0.0]);
+
+            let path = std::env::temp_dir().join("acadrust_ffi_test.dwg");
+            let c_path = CString::new(path.to_str().unwrap()).unwrap();
+            let version = CString::new("AC1018").unwrap();
+            let status = acad_document_write(handle, c_path.as_ptr(), AcadFormat::Dwg as u32, version.as_ptr());
+            assert_eq!(status, AcadStatus::Ok);
+            acad_document_free(handle);
+
+            let mut reopened = std::ptr::null_mut();
+            assert_eq!(acad_document_open(c_path.as_ptr(), &mut reopened), AcadStatus::Ok);
+            let mut version = [0 as c_char; 8];
+            acad_document_version(reopened, version.as_mut_ptr(), version.len());
+            assert_eq!(CStr::from_ptr(version.as_ptr()).to_str().unwrap(), "AC1018");
+            acad_document_free(reopened);
+            let _ = std::fs::remove_file(path);
+        }
+    }
+
+    #[test]
+    fn test_errors_are_reported() {
+        unsafe {
+            let mut handle = std::ptr::null_mut();
+            let garbage = b"not a drawing";
+            assert_eq!(acad_document_read(garbage.as_ptr(), garbage.len(), &mut handle), AcadStatus::InvalidFormat);
+            assert!(handle.is_null());
+            assert!(!acad_last_error().is_null());
+
+            // A DXF read error carries its location; the status is that of
+            // the error inside
+            let malformed = b"  0\nSECTION\n  2\nENTITIES\n  0\nLINE\nxx\n1.0\n  0\nENDSEC\n  0\nEOF\n";
+            assert_eq!(acad_document_read(malformed.as_ptr(), malformed.len(), &mut handle), AcadStatus::InvalidFormat);
+            assert!(handle.is_null());
+            assert!(CStr::from_ptr(acad_last_error()).to_str().unwrap().contains("line 7"));
+
+            let doc = acad_document_new();
+            assert_eq!(acad_entity_color(doc, 42, std::ptr::null_mut(), std::ptr::null_mut()), AcadStatus::NotFound);
+            let path = CString::new("unused.dxf").unwrap();
+            let status = acad_document_write(doc, path.as_ptr(), 3, std::ptr::null());
+            assert_eq!(status, AcadStatus::InvalidArgument);
+            assert!(CStr::from_ptr(acad_last_error()).to_str().unwrap().contains("format 3"));
+            assert_eq!(acad_entity_type(std::ptr::null(), 42, std::ptr::null_mut(), 0), 0);
+            acad_document_free(doc);
+        }
+    }
+}
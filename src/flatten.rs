@@ -0,0 +1,813 @@
+//! Flattening 3D models into 2D drawings
+//!
+//! [`CadDocument::flatten`] replaces model space by its projection along a
+//! view direction, the way FLATTEN does: curves, the edges of 3DFACEs,
+//! meshes and thick entities, and the display wires of 3DSOLIDs become
+//! lines, arcs, circles and lightweight polylines in the XY plane. Block
+//! references and dimensions are exploded, and text is kept where it faces
+//! the viewer.
+//!
+//! The projection is laid out like a viewport with the same view
+//! direction: looking from (0, -1, 0) gives a front view with X to the
+//! right and Z up. With [`FlattenOptions::hidden_line_removal`], the parts
+//! of edges behind 3DFACEs, meshes, SOLIDs and thick entities are left
+//! out; 3DSOLIDs have no faces without an ACIS kernel and hide nothing.
+//!
+//! ```rust,ignore
+//! use acadrust::flatten::FlattenOptions;
+//!
+//! let options = FlattenOptions { hidden_line_removal: true, ..Default::default() };
+//! doc.flatten_with(Vector3::new(0.0, -1.0, 0.0), &options)?;
+//! ```
+
+use std::f64::consts::{PI, TAU};
+
+use indexmap::IndexMap;
+
+use crate::document::CadDocument;
+use crate::entities::{outline, Arc, Circle, Entity, EntityCommon, EntityType, Line, LwPolyline, LwVertex, Point, Text};
+use crate::io::mesh_export::{entity_part, triangle_normal, MeshExportOptions, MeshPart};
+use crate::render::scene::insert_transform;
+use crate::render::tessellate::{
+    arc_points, bulge_polyline, ccw_sweep, circle_points, ellipse_points, hatch_path_points, spline_points,
+};
+use crate::types::geometry::ocs_matrix;
+use crate::types::{Color, LineWeight, Matrix4, Transform, Transparency, Vector2, Vector3};
+use crate::Result;
+
+/// Deepest block nesting followed, to stop on circular references
+const MAX_NESTING: usize = 32;
+
+/// Largest angle between the faces on either side of a mesh edge for the
+/// edge to count as smooth and be left out
+const SMOOTH_ANGLE: f64 = PI / 9.0;
+
+/// Options for [`CadDocument::flatten_with`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlattenOptions {
+    /// Leave out the parts of edges hidden behind faces
+    pub hidden_line_removal: bool,
+    /// Largest chord deviation of curves that become polylines, in drawing
+    /// units
+    pub tolerance: f64,
+}
+
+impl Default for FlattenOptions {
+    fn default() -> Self {
+        Self {
+            hidden_line_removal: false,
+            tolerance: 0.01,
+        }
+    }
+}
+
+impl CadDocument {
+    /// Replace model space by its projection seen from `view_direction`,
+    /// without hidden line removal; see [`Self::flatten_with`]
+    pub fn flatten(&mut self, view_direction: Vector3) -> Result<usize> {
+        self.flatten_with(view_direction, &FlattenOptions::default())
+    }
+
+    /// Replace model space by its projection seen from `view_direction`,
+    /// which points from the model towards the viewer, and return the
+    /// number of 2D entities it now holds.
+    ///
+    /// Entities with nothing to project, such as images and invisible
+    /// entities, are removed.
+    pub fn flatten_with(&mut self, view_direction: Vector3, options: &FlattenOptions) -> Result<usize> {
+        let direction = if view_direction.length() < 1e-12 { Vector3::UNIT_Z } else { view_direction.normalize() };
+        let view = Transform::from_matrix(Matrix4::from_matrix3(ocs_matrix(direction).transpose()));
+
+        let source: Vec<&EntityType> = self.model_space().collect();
+        let mut projector = Projector::new(self, options);
+        for entity in &source {
+            projector.entity(entity, &view, &Inherited::default(), 0);
+        }
+        let flat = projector.finish();
+        let removed: Vec<_> = source.iter().map(|e| e.common().handle).collect();
+
+        for handle in removed {
+            self.entities.remove(&handle);
+        }
+        let owner = match self.block_records.get_mut("*Model_Space") {
+            Some(record) => {
+                record.entities.clear();
+                record.handle
+            }
+            None => self.header.model_space_block_handle,
+        };
+        let count = flat.len();
+        for mut entity in flat {
+            entity.common_mut().owner_handle = owner;
+            self.add_entity(entity)?;
+        }
+        Ok(count)
+    }
+}
+
+/// Layer and display properties of a flattened entity
+#[derive(Debug, Clone)]
+struct Style {
+    layer: String,
+    color: Color,
+    line_weight: LineWeight,
+    transparency: Transparency,
+}
+
+impl Style {
+    fn common(&self) -> EntityCommon {
+        let mut common = EntityCommon::new();
+        common.layer = self.layer.clone();
+        common.color = self.color;
+        common.line_weight = self.line_weight;
+        common.transparency = self.transparency;
+        common
+    }
+}
+
+/// Properties passed from an INSERT to the entities of its block
+#[derive(Debug, Clone)]
+struct Inherited {
+    layer: Option<String>,
+    color: Color,
+    line_weight: LineWeight,
+}
+
+impl Default for Inherited {
+    fn default() -> Self {
+        Self {
+            layer: None,
+            color: Color::ByBlock,
+            line_weight: LineWeight::ByBlock,
+        }
+    }
+}
+
+/// Projected geometry, in view coordinates
+enum Shape {
+    /// Polyline through points; `exact` stands for it when none of it is
+    /// hidden
+    Path {
+        points: Vec<Vector3>,
+        closed: bool,
+        exact: Option<EntityType>,
+    },
+    /// Point marker
+    Point(Vector3),
+    /// Entity kept as it is, whatever is in front of it
+    Fixed(EntityType),
+}
+
+struct Projector<'a> {
+    doc: &'a CadDocument,
+    options: &'a FlattenOptions,
+    mesh_options: MeshExportOptions,
+    shapes: Vec<(Style, Shape)>,
+    /// Faces that hide edges, as triangles in view coordinates
+    triangles: Vec<[Vector3; 3]>,
+}
+
+impl<'a> Projector<'a> {
+    fn new(doc: &'a CadDocument, options: &'a FlattenOptions) -> Self {
+        Self {
+            doc,
+            options,
+            mesh_options: MeshExportOptions { tolerance: options.tolerance, ..Default::default() },
+            shapes: Vec::new(),
+            triangles: Vec::new(),
+        }
+    }
+
+    /// Curve tolerance in the units of the entity being projected
+    fn tolerance(&self, transform: &Transform) -> f64 {
+        let scale = transform.apply_rotation(Vector3::UNIT_X).length()
+            .max(transform.apply_rotation(Vector3::UNIT_Y).length());
+        if scale > 1e-12 {
+            self.options.tolerance / scale
+        } else {
+            self.options.tolerance
+        }
+    }
+
+    fn style(&self, entity: &dyn Entity, inherited: &Inherited) -> Style {
+        let layer = match (&inherited.layer, entity.layer()) {
+            (Some(parent), "0") => parent.clone(),
+            (_, own) => own.to_string(),
+        };
+        Style {
+            layer,
+            color: match entity.color() {
+                Color::ByBlock => inherited.color,
+                color => color,
+            },
+            line_weight: match entity.line_weight() {
+                LineWeight::ByBlock => inherited.line_weight,
+                weight => weight,
+            },
+            transparency: entity.transparency(),
+        }
+    }
+
+    fn path(&mut self, style: &Style, points: Vec<Vector3>, closed: bool, exact: Option<EntityType>) {
+        if points.len() >= 2 {
+            self.shapes.push((style.clone(), Shape::Path { points, closed, exact }));
+        }
+    }
+
+    fn block(&mut self, name: &str, transform: &Transform, inherited: &Inherited, depth: usize) {
+        if depth >= MAX_NESTING {
+            return;
+        }
+        let doc = self.doc;
+        if let Some(entities) = doc.block_entities(name) {
+            for entity in entities {
+                self.entity(entity, transform, inherited, depth + 1);
+            }
+        }
+    }
+
+    /// Project an entity whose coordinates `transform` maps to the view
+    fn entity(&mut self, entity: &EntityType, transform: &Transform, inherited: &Inherited, depth: usize) {
+        if entity.as_entity().is_invisible() {
+            return;
+        }
+        let style = self.style(entity.as_entity(), inherited);
+        let tolerance = self.tolerance(transform);
+        let view = |p: Vector3| transform.apply(p);
+
+        match entity {
+            EntityType::Insert(insert) => {
+                let inner = Inherited {
+                    layer: Some(style.layer.clone()),
+                    color: style.color,
+                    line_weight: style.line_weight,
+                };
+                for row in 0..insert.row_count.max(1) {
+                    for column in 0..insert.column_count.max(1) {
+                        let transform = insert_transform(insert, row, column).then(transform);
+                        self.block(&insert.block_name, &transform, &inner, depth);
+                    }
+                }
+                for attribute in &insert.attributes {
+                    self.entity(&EntityType::AttributeEntity(Box::new(attribute.clone())), transform, inherited, depth);
+                }
+                return;
+            }
+            EntityType::Dimension(dimension) => {
+                let inner = Inherited {
+                    layer: Some(style.layer.clone()),
+                    color: style.color,
+                    line_weight: style.line_weight,
+                };
+                self.block(&dimension.base().block_name, transform, &inner, depth);
+                return;
+            }
+            EntityType::Face3D(face) => {
+                let triangles = face.as_triangles();
+                for triangle in &triangles {
+                    for i in (0..3).filter(|&i| triangle.visible_edges[i]) {
+                        let (a, b) = (triangle.corners[i], triangle.corners[(i + 1) % 3]);
+                        self.path(&style, vec![view(a), view(b)], false, None);
+                    }
+                }
+                self.triangles.extend(triangles.iter().map(|t| t.corners.map(view)));
+                return;
+            }
+            _ => {}
+        }
+        if let Some(part) = entity_part(entity, &self.mesh_options) {
+            self.part(&part, transform, &style);
+            return;
+        }
+
+        match entity {
+            EntityType::Point(point) => self.shapes.push((style, Shape::Point(view(point.location)))),
+            EntityType::Line(line) => self.path(&style, vec![view(line.start), view(line.end)], false, None),
+            EntityType::Circle(circle) => {
+                let to_view = plane_map(transform, circle.normal, circle.center.z);
+                let center = Vector2::new(circle.center.x, circle.center.y);
+                let points = circle_points(center, circle.radius, tolerance).into_iter().map(&to_view).collect();
+                let exact = plane_similarity(&to_view, center).map(|(scale, _)| {
+                    EntityType::Circle(Circle::from_center_radius(flat(to_view(center)), circle.radius * scale))
+                });
+                self.path(&style, points, true, exact);
+            }
+            EntityType::Arc(arc) => {
+                let to_view = plane_map(transform, arc.normal, arc.center.z);
+                let center = Vector2::new(arc.center.x, arc.center.y);
+                let sweep = ccw_sweep(arc.start_angle, arc.end_angle);
+                let points = arc_points(center, arc.radius, arc.start_angle, sweep, tolerance)
+                    .into_iter()
+                    .map(&to_view)
+                    .collect();
+                let exact = plane_similarity(&to_view, center).map(|(scale, mirrored)| {
+                    let c = to_view(center);
+                    let angle = |a: f64| {
+                        let p = to_view(center + Vector2::new(a.cos(), a.sin()) * arc.radius) - c;
+                        p.y.atan2(p.x).rem_euclid(TAU)
+                    };
+                    let (start, end) = if mirrored {
+                        (angle(arc.end_angle), angle(arc.start_angle))
+                    } else {
+                        (angle(arc.start_angle), angle(arc.end_angle))
+                    };
+                    EntityType::Arc(Arc::from_center_radius_angles(flat(c), arc.radius * scale, start, end))
+                });
+                self.path(&style, points, false, exact);
+            }
+            EntityType::Ellipse(ellipse) => {
+                let points = ellipse_points(
+                    ellipse.center,
+                    ellipse.major_axis,
+                    ellipse.normal,
+                    ellipse.minor_axis_ratio,
+                    ellipse.start_parameter,
+                    ellipse.end_parameter,
+                    tolerance,
+                );
+                let full = ccw_sweep(ellipse.start_parameter, ellipse.end_parameter) >= TAU - 1e-9;
+                let mut points: Vec<Vector3> = points.into_iter().map(view).collect();
+                if full {
+                    points.pop();
+                }
+                self.path(&style, points, full, None);
+            }
+            EntityType::LwPolyline(poly) => {
+                let vertices: Vec<(Vector2, f64)> = poly.vertices.iter().map(|v| (v.location, v.bulge)).collect();
+                self.bulge_path(&style, transform, poly.normal, poly.elevation, &vertices, poly.is_closed);
+            }
+            EntityType::Polyline2D(poly) => {
+                let vertices: Vec<(Vector2, f64)> = poly
+                    .vertices
+                    .iter()
+                    .map(|v| (Vector2::new(v.location.x, v.location.y), v.bulge))
+                    .collect();
+                self.bulge_path(&style, transform, poly.normal, poly.elevation, &vertices, poly.is_closed());
+            }
+            EntityType::Polyline(poly) => {
+                let points = poly.vertices.iter().map(|v| view(v.location)).collect();
+                self.path(&style, points, poly.is_closed(), None);
+            }
+            EntityType::Polyline3D(poly) => {
+                let points = poly.vertices.iter().map(|v| view(v.position)).collect();
+                self.path(&style, points, poly.is_closed(), None);
+            }
+            EntityType::Spline(spline) => {
+                let points = if spline.control_points.is_empty() {
+                    spline.fit_points.clone()
+                } else {
+                    spline_points(
+                        spline.degree.max(1) as usize,
+                        &spline.knots,
+                        &spline.control_points,
+                        &spline.weights,
+                        tolerance,
+                    )
+                };
+                self.path(&style, points.into_iter().map(view).collect(), false, None);
+            }
+            EntityType::Leader(leader) => {
+                self.path(&style, leader.vertices.iter().map(|p| view(*p)).collect(), false, None);
+            }
+            EntityType::Solid(solid) => {
+                let triangles = solid.as_triangles();
+                self.path(&style, outline(&triangles).into_iter().map(view).collect(), true, None);
+                self.triangles.extend(triangles.iter().map(|t| t.corners.map(view)));
+            }
+            EntityType::Hatch(hatch) => {
+                let to_view = plane_map(transform, hatch.normal, hatch.elevation);
+                for path in &hatch.paths {
+                    let points = hatch_path_points(path, tolerance).into_iter().map(&to_view).collect();
+                    self.path(&style, points, true, None);
+                }
+            }
+            EntityType::Text(text) => {
+                let to_view = plane_map(transform, text.normal, text.insertion_point.z);
+                let position = Vector2::new(text.insertion_point.x, text.insertion_point.y);
+                if let Some((scale, rotation)) = text_frame(&to_view, position, text.rotation) {
+                    let mut flat_text = text.clone();
+                    flat_text.insertion_point = flat(to_view(position));
+                    flat_text.alignment_point = text.alignment_point.map(|p| flat(to_view(Vector2::new(p.x, p.y))));
+                    flat_text.height = text.height * scale;
+                    flat_text.rotation = rotation;
+                    flat_text.normal = Vector3::UNIT_Z;
+                    self.shapes.push((style, Shape::Fixed(EntityType::Text(flat_text))));
+                }
+            }
+            EntityType::AttributeEntity(attribute) => {
+                let to_view = plane_map(transform, attribute.normal, attribute.insertion_point.z);
+                let position = Vector2::new(attribute.insertion_point.x, attribute.insertion_point.y);
+                if let Some((scale, rotation)) = text_frame(&to_view, position, attribute.rotation) {
+                    let mut text = Text::with_value(attribute.value.clone(), flat(to_view(position)))
+                        .with_height(attribute.height * scale)
+                        .with_rotation(rotation);
+                    text.width_factor = attribute.width_factor;
+                    text.oblique_angle = attribute.oblique_angle;
+                    text.style = attribute.text_style.clone();
+                    self.shapes.push((style, Shape::Fixed(EntityType::Text(text))));
+                }
+            }
+            EntityType::MText(mtext) => {
+                let to_view = plane_map(transform, mtext.normal, mtext.insertion_point.z);
+                let position = Vector2::new(mtext.insertion_point.x, mtext.insertion_point.y);
+                if let Some((scale, rotation)) = text_frame(&to_view, position, mtext.rotation) {
+                    let mut flat_text = mtext.clone();
+                    flat_text.insertion_point = flat(to_view(position));
+                    flat_text.height = mtext.height * scale;
+                    flat_text.rectangle_width = mtext.rectangle_width * scale;
+                    flat_text.rectangle_height = mtext.rectangle_height.map(|h| h * scale);
+                    flat_text.rotation = rotation;
+                    flat_text.normal = Vector3::UNIT_Z;
+                    self.shapes.push((style, Shape::Fixed(EntityType::MText(flat_text))));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// A polyline with bulges, kept as an LWPOLYLINE when its plane faces
+    /// the viewer
+    fn bulge_path(
+        &mut self,
+        style: &Style,
+        transform: &Transform,
+        normal: Vector3,
+        elevation: f64,
+        vertices: &[(Vector2, f64)],
+        closed: bool,
+    ) {
+        let to_view = plane_map(transform, normal, elevation);
+        let points = bulge_polyline(vertices, closed, self.tolerance(transform))
+            .into_iter()
+            .map(&to_view)
+            .collect();
+        let exact = vertices.first().and_then(|(origin, _)| plane_similarity(&to_view, *origin)).map(|(_, mirrored)| {
+            let mut poly = LwPolyline::new();
+            for (location, bulge) in vertices {
+                let p = to_view(*location);
+                poly.add_vertex(LwVertex::with_bulge(Vector2::new(p.x, p.y), if mirrored { -bulge } else { *bulge }));
+            }
+            poly.is_closed = closed;
+            EntityType::LwPolyline(poly)
+        });
+        self.path(style, points, closed, exact);
+    }
+
+    /// Edges and faces of a mesh: the edges along the outline, creases and
+    /// silhouette, and the wires
+    fn part(&mut self, part: &MeshPart, transform: &Transform, style: &Style) {
+        let vertices: Vec<Vector3> = part.vertices.iter().map(|v| transform.apply(*v)).collect();
+        let mut edges: IndexMap<(usize, usize), Vec<Vector3>> = IndexMap::new();
+        for face in &part.faces {
+            let normal = triangle_normal(vertices[face[0]], vertices[face[1]], vertices[face[2]]);
+            for (k, &i) in face.iter().enumerate() {
+                let j = face[(k + 1) % face.len()];
+                if vertices[i] != vertices[j] {
+                    edges.entry((i.min(j), i.max(j))).or_default().push(normal);
+                }
+            }
+        }
+        for [a, b, c] in part.triangles() {
+            self.triangles.push([vertices[a], vertices[b], vertices[c]]);
+        }
+        for ((i, j), normals) in edges {
+            let shown = match normals[..] {
+                [a, b] => a.dot(&b) < SMOOTH_ANGLE.cos() || (a.z > 0.0) != (b.z > 0.0),
+                _ => true,
+            };
+            if shown {
+                self.path(style, vec![vertices[i], vertices[j]], false, None);
+            }
+        }
+        for line in &part.lines {
+            self.path(style, line.iter().map(|&i| vertices[i]).collect(), false, None);
+        }
+    }
+
+    /// The 2D entities, with hidden parts removed if asked for
+    fn finish(self) -> Vec<EntityType> {
+        let hider = self.options.hidden_line_removal.then(|| Hider::new(&self.triangles));
+        let mut entities = Vec::new();
+        for (style, shape) in self.shapes {
+            let mut push = |mut entity: EntityType| {
+                *entity.common_mut() = style.common();
+                entities.push(entity);
+            };
+            match shape {
+                Shape::Path { points, closed, exact } => {
+                    match hider.as_ref().and_then(|h| h.visible_runs(&points, closed)) {
+                        None => push(exact.unwrap_or_else(|| path_entity(&points, closed))),
+                        Some(runs) => runs.iter().for_each(|run| push(path_entity(run, false))),
+                    }
+                }
+                Shape::Point(p) => {
+                    if !hider.as_ref().is_some_and(|h| h.hides(p)) {
+                        push(EntityType::Point(Point::at(flat(p))));
+                    }
+                }
+                Shape::Fixed(entity) => push(entity),
+            }
+        }
+        entities
+    }
+}
+
+/// Map from points of the plane with `normal` at `elevation` to the view
+fn plane_map(transform: &Transform, normal: Vector3, elevation: f64) -> impl Fn(Vector2) -> Vector3 + '_ {
+    let ocs = ocs_matrix(normal);
+    move |p| transform.apply(ocs.transform_point(Vector3::new(p.x, p.y, elevation)))
+}
+
+/// Scale of `to_view` near `origin`, and whether it mirrors, if it maps
+/// the plane onto the view plane without distortion
+fn plane_similarity(to_view: &impl Fn(Vector2) -> Vector3, origin: Vector2) -> Option<(f64, bool)> {
+    let o = to_view(origin);
+    let x = to_view(origin + Vector2::new(1.0, 0.0)) - o;
+    let y = to_view(origin + Vector2::new(0.0, 1.0)) - o;
+    let scale = x.length();
+    let tolerance = 1e-7 * scale;
+    if scale < 1e-12
+        || (y.length() - scale).abs() > tolerance
+        || x.dot(&y).abs() > tolerance * scale
+        || x.z.abs() > tolerance
+        || y.z.abs() > tolerance
+    {
+        return None;
+    }
+    Some((scale, x.x * y.y - x.y * y.x < 0.0))
+}
+
+/// Scale and view rotation of text at `position` with `rotation`, if its
+/// plane faces the viewer
+fn text_frame(to_view: &impl Fn(Vector2) -> Vector3, position: Vector2, rotation: f64) -> Option<(f64, f64)> {
+    let (scale, _) = plane_similarity(to_view, position)?;
+    let along = to_view(position + Vector2::new(rotation.cos(), rotation.sin())) - to_view(position);
+    Some((scale, along.y.atan2(along.x)))
+}
+
+fn flat(p: Vector3) -> Vector3 {
+    Vector3::new(p.x, p.y, 0.0)
+}
+
+/// LINE for a single segment, LWPOLYLINE otherwise
+fn path_entity(points: &[Vector3], closed: bool) -> EntityType {
+    if let [a, b] = points {
+        if !closed {
+            return EntityType::Line(Line::from_points(flat(*a), flat(*b)));
+        }
+    }
+    let mut poly = LwPolyline::from_points(points.iter().map(|p| Vector2::new(p.x, p.y)).collect());
+    poly.is_closed = closed;
+    EntityType::LwPolyline(poly)
+}
+
+/// Triangle in view coordinates with its 2D bounds
+struct Occluder {
+    corners: [Vector3; 3],
+    min: Vector2,
+    max: Vector2,
+}
+
+/// Hidden line test against the faces of the model
+struct Hider {
+    occluders: Vec<Occluder>,
+    /// Distance below which points count as coincident
+    tolerance: f64,
+}
+
+impl Hider {
+    fn new(triangles: &[[Vector3; 3]]) -> Self {
+        let mut min = Vector3::new(f64::MAX, f64::MAX, f64::MAX);
+        let mut max = Vector3::new(f64::MIN, f64::MIN, f64::MIN);
+        for p in triangles.iter().flatten() {
+            min = Vector3::new(min.x.min(p.x), min.y.min(p.y), min.z.min(p.z));
+            max = Vector3::new(max.x.max(p.x), max.y.max(p.y), max.z.max(p.z));
+        }
+        let extent = if triangles.is_empty() { 1.0 } else { min.distance(&max).max(1.0) };
+        let tolerance = 1e-9 * extent;
+
+        let occluders = triangles
+            .iter()
+            .filter(|[a, b, c]| ((b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)).abs() > tolerance * tolerance)
+            .map(|&corners| {
+                let [a, b, c] = corners;
+                Occluder {
+                    corners,
+                    min: Vector2::new(a.x.min(b.x).min(c.x), a.y.min(b.y).min(c.y)),
+                    max: Vector2::new(a.x.max(b.x).max(c.x), a.y.max(b.y).max(c.y)),
+                }
+            })
+            .collect();
+        Self { occluders, tolerance }
+    }
+
+    /// Whether a face lies in front of `p`
+    fn hides(&self, p: Vector3) -> bool {
+        self.occluders.iter().any(|o| {
+            if p.x < o.min.x - self.tolerance
+                || p.x > o.max.x + self.tolerance
+                || p.y < o.min.y - self.tolerance
+                || p.y > o.max.y + self.tolerance
+            {
+                return false;
+            }
+            let [a, b, c] = o.corners;
+            let area = (b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x);
+            let u = ((b.x - p.x) * (c.y - p.y) - (b.y - p.y) * (c.x - p.x)) / area;
+            let v = ((c.x - p.x) * (a.y - p.y) - (c.y - p.y) * (a.x - p.x)) / area;
+            let w = 1.0 - u - v;
+            let inside = u >= -1e-9 && v >= -1e-9 && w >= -1e-9;
+            inside && u * a.z + v * b.z + w * c.z > p.z + self.tolerance
+        })
+    }
+
+    /// The visible runs of a polyline, or `None` if none of it is hidden
+    fn visible_runs(&self, points: &[Vector3], closed: bool) -> Option<Vec<Vec<Vector3>>> {
+        let n = points.len();
+        let segments = if closed { n } else { n - 1 };
+        let mut runs: Vec<Vec<Vector3>> = Vec::new();
+        let mut run: Vec<Vector3> = Vec::new();
+        let mut hidden = false;
+
+        for i in 0..segments {
+            let (a, b) = (points[i], points[(i + 1) % n]);
+            let at = |t: f64| a + (b - a) * t;
+            let mut cuts = vec![0.0, 1.0];
+            for o in &self.occluders {
+                if a.x.max(b.x) < o.min.x || a.x.min(b.x) > o.max.x || a.y.max(b.y) < o.min.y || a.y.min(b.y) > o.max.y {
+                    continue;
+                }
+                for k in 0..3 {
+                    if let Some(t) = crossing(a, b, o.corners[k], o.corners[(k + 1) % 3]) {
+                        cuts.push(t);
+                    }
+                }
+            }
+            cuts.sort_by(f64::total_cmp);
+            cuts.dedup_by(|x, y| (*x - *y).abs() < 1e-12);
+
+            for span in cuts.windows(2) {
+                if self.hides(at((span[0] + span[1]) / 2.0)) {
+                    hidden = true;
+                    if run.len() >= 2 {
+                        runs.push(std::mem::take(&mut run));
+                    }
+                    run.clear();
+                } else {
+                    if run.is_empty() {
+                        run.push(at(span[0]));
+                    } else if span[0] > 0.0 {
+                        // Continuing along the same segment
+                        run.pop();
+                    }
+                    run.push(at(span[1]));
+                }
+            }
+        }
+        if !hidden {
+            return None;
+        }
+        if run.len() >= 2 {
+            // A closed outline visible across its start joins its first run
+            match runs.first_mut() {
+                Some(first) if closed && first[0] == points[0] && run.last() == Some(&points[0]) => {
+                    run.extend_from_slice(&first[1..]);
+                    *first = run;
+                }
+                _ => runs.push(run),
+            }
+        }
+        Some(runs)
+    }
+}
+
+/// Parameter along `a`–`b` where it crosses `p`–`q` in the view plane
+fn crossing(a: Vector3, b: Vector3, p: Vector3, q: Vector3) -> Option<f64> {
+    let (r, s, ap) = (b - a, q - p, p - a);
+    let denominator = r.x * s.y - r.y * s.x;
+    if denominator.abs() < 1e-15 {
+        return None;
+    }
+    let t = (ap.x * s.y - ap.y * s.x) / denominator;
+    let u = (ap.x * r.y - ap.y * r.x) / denominator;
+    (t > 0.0 && t < 1.0 && (-1e-9..=1.0 + 1e-9).contains(&u)).then_some(t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::{Face3D, Insert};
+    use crate::tables::BlockRecord;
+
+    #[test]
+    fn test_flatten_front_view() {
+        let mut doc = CadDocument::new();
+        // Standing in the XZ plane, centered on (5, 0, 2) in WCS
+        let mut circle = Circle::from_center_radius(Vector3::new(5.0, 2.0, 0.0), 1.0);
+        circle.normal = Vector3::new(0.0, -1.0, 0.0);
+        doc.add_entity(EntityType::Circle(circle)).unwrap();
+        doc.add_entity(EntityType::Line(Line::from_coords(0.0, 0.0, 0.0, 0.0, 10.0, 4.0))).unwrap();
+        // Seen edge-on from the front
+        doc.add_entity(EntityType::Circle(Circle::from_center_radius(Vector3::new(20.0, 0.0, 0.0), 1.0))).unwrap();
+
+        assert_eq!(doc.flatten(Vector3::new(0.0, -1.0, 0.0)).unwrap(), 3);
+        let entities: Vec<&EntityType> = doc.model_space().collect();
+        assert!(entities.iter().any(|e| matches!(
+            e,
+            EntityType::Circle(c) if c.center.distance(&Vector3::new(5.0, 2.0, 0.0)) < 1e-9 && (c.radius - 1.0).abs() < 1e-9
+        )));
+        assert!(entities.iter().any(|e| matches!(
+            e,
+            EntityType::Line(l) if l.start == Vector3::ZERO && l.end.distance(&Vector3::new(0.0, 4.0, 0.0)) < 1e-9
+        )));
+        let edge_on = entities.iter().find_map(|e| match e {
+            EntityType::LwPolyline(p) => Some(p),
+            _ => None,
+        });
+        let edge_on = edge_on.unwrap();
+        assert!(edge_on.is_closed);
+        assert!(edge_on.vertices.iter().all(|v| v.location.y.abs() < 1e-9));
+    }
+
+    #[test]
+    fn test_flatten_explodes_inserts() {
+        let mut doc = CadDocument::new();
+        let mut block = BlockRecord::new("PEG");
+        let mut arc = Arc::from_center_radius_angles(Vector3::ZERO, 1.0, 0.0, PI / 2.0);
+        arc.common.color = Color::ByBlock;
+        block.entities.push(EntityType::Arc(arc));
+        doc.block_records.add(block).unwrap();
+        let mut insert = Insert::new("PEG", Vector3::new(10.0, 0.0, 0.0));
+        insert.x_scale = -2.0;
+        insert.y_scale = 2.0;
+        insert.common.color = Color::RED;
+        doc.add_entity(EntityType::Insert(insert)).unwrap();
+
+        doc.flatten(Vector3::UNIT_Z).unwrap();
+        let entities: Vec<&EntityType> = doc.model_space().collect();
+        let [EntityType::Arc(arc)] = entities[..] else {
+            panic!("expected one arc, got {entities:?}");
+        };
+        // Mirrored in X: the quarter arc now runs from 90° to 180°
+        assert_eq!(arc.common.color, Color::RED);
+        assert!((arc.radius - 2.0).abs() < 1e-9);
+        assert!((arc.start_angle - PI / 2.0).abs() < 1e-9 && (arc.end_angle - PI).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_hidden_line_removal() {
+        let mut doc = CadDocument::new();
+        // Face at z = 1 covering x in 0..10, above a line at z = 0
+        doc.add_entity(EntityType::Face3D(Face3D::new(
+            Vector3::new(0.0, -5.0, 1.0),
+            Vector3::new(10.0, -5.0, 1.0),
+            Vector3::new(10.0, 5.0, 1.0),
+            Vector3::new(0.0, 5.0, 1.0),
+        )))
+        .unwrap();
+        doc.add_entity(EntityType::Line(Line::from_coords(-5.0, 0.0, 0.0, 15.0, 0.0, 0.0))).unwrap();
+
+        let options = FlattenOptions { hidden_line_removal: true, ..Default::default() };
+        doc.flatten_with(Vector3::UNIT_Z, &options).unwrap();
+        let mut pieces: Vec<(f64, f64)> = doc
+            .model_space()
+            .filter_map(|e| match e {
+                EntityType::Line(l) if l.start.y == 0.0 && l.end.y == 0.0 => Some((l.start.x, l.end.x)),
+                _ => None,
+            })
+            .collect();
+        pieces.sort_by(|a, b| a.0.total_cmp(&b.0));
+        assert_eq!(pieces.len(), 2);
+        assert!((pieces[0].0 + 5.0).abs() < 1e-9 && pieces[0].1.abs() < 1e-9);
+        assert!((pieces[1].0 - 10.0).abs() < 1e-9 && (pieces[1].1 - 15.0).abs() < 1e-9);
+        // The face's own outline stays
+        assert_eq!(doc.model_space().count(), 6);
+    }
+
+    #[test]
+    fn test_hidden_line_removal_of_extruded_box() {
+        let mut doc = CadDocument::new();
+        let mut square = LwPolyline::from_points(vec![
+            Vector2::new(0.0, 0.0),
+            Vector2::new(10.0, 0.0),
+            Vector2::new(10.0, 10.0),
+            Vector2::new(0.0, 10.0),
+        ]);
+        square.is_closed = true;
+        square.thickness = 5.0;
+        doc.add_entity(EntityType::LwPolyline(square)).unwrap();
+
+        let options = FlattenOptions { hidden_line_removal: true, ..Default::default() };
+        doc.flatten_with(Vector3::new(0.0, -1.0, 0.0), &options).unwrap();
+        // Only the outline of the front face is left: the back edges are
+        // behind it and the cap diagonals are smooth
+        let length: f64 = doc
+            .model_space()
+            .map(|e| match e {
+                EntityType::Line(l) => l.length(),
+                other => panic!("unexpected {other:?}"),
+            })
+            .sum();
+        assert!((length - 30.0).abs() < 1e-9, "{length}");
+    }
+}
@@ -0,0 +1,572 @@
+//! Handle conflict detection and repair
+//!
+//! Every table entry, object and entity of a file needs a handle of its own,
+//! and `$HANDSEED` must lie above all of them. Documents assembled in code
+//! can break both: two entities given the same handle, an entity on one of
+//! the handles reserved for the table control objects, or handles at or
+//! above the document's next free handle. The writers emit handles as they
+//! are, so such a document turns into a corrupt file.
+//!
+//! [`check`] reports the problems without changing anything; [`repair`]
+//! moves the offending items to fresh handles and raises the seed. Both
+//! writers repair a copy of the document before writing when needed.
+//!
+//! ```rust,ignore
+//! use acadrust::handles;
+//!
+//! let report = handles::check(&doc);
+//! if !report.is_clean() {
+//!     eprintln!("{report}");
+//! }
+//! ```
+//!
+//! When items share a handle, the first of them in this order keeps it:
+//! block records (with their BLOCK and ENDBLK handles), the other table
+//! entries, objects, model and paper space entities, then block entities,
+//! each group in handle order. References to a shared handle stay with the
+//! item that keeps it; references to an item moved off a reserved handle
+//! follow it. A block entity that also appears among the document entities
+//! with the same handle and type, as the DWG reader mirrors them, is one
+//! item and not a conflict.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use crate::document::CadDocument;
+use crate::entities::EntityType;
+use crate::io::dxf::GroupCodeValueType;
+use crate::objects::{BackgroundKind, ObjectType};
+use crate::types::Handle;
+
+/// Handles of the table control objects written by the DXF writer
+const TABLE_HANDLES: std::ops::RangeInclusive<u64> = 0x1..=0xA;
+
+/// What is wrong with a handle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IssueKind {
+    /// Already used by an item earlier in the precedence order
+    Duplicate,
+    /// One of the handles of the table control objects
+    Reserved,
+}
+
+/// An item whose handle has to change
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HandleIssue {
+    /// The conflicting handle
+    pub handle: Handle,
+    /// Why it conflicts
+    pub kind: IssueKind,
+    /// Description of the item, e.g. `LINE entity`
+    pub item: String,
+    /// Handle the item gets on repair
+    pub replacement: Handle,
+}
+
+/// Result of [`check`] or [`repair`]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct HandleReport {
+    /// Items moved, or to be moved, to a fresh handle
+    pub issues: Vec<HandleIssue>,
+    /// Next free handle of the document before the repair
+    pub previous_seed: u64,
+    /// Next free handle, and `$HANDSEED`, after the repair
+    pub seed: u64,
+}
+
+impl HandleReport {
+    /// Whether the document can be written as it is: no conflicts and no
+    /// handle at or above its next free handle
+    pub fn is_clean(&self) -> bool {
+        self.issues.is_empty() && self.seed == self.previous_seed
+    }
+}
+
+impl fmt::Display for HandleReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_clean() {
+            return write!(f, "no handle conflicts");
+        }
+        for issue in &self.issues {
+            let kind = match issue.kind {
+                IssueKind::Duplicate => "duplicate",
+                IssueKind::Reserved => "reserved",
+            };
+            writeln!(f, "{} {kind} handle {:X} -> {:X}", issue.item, issue.handle.value(), issue.replacement.value())?;
+        }
+        write!(f, "handle seed {:X} -> {:X}", self.previous_seed, self.seed)
+    }
+}
+
+/// List the handle conflicts of `document` and the handles [`repair`]
+/// would give the offending items, without changing the document
+pub fn check(document: &CadDocument) -> HandleReport {
+    plan(document).0
+}
+
+/// Move every conflicting item of `document` to a fresh handle and raise
+/// its next free handle, and `$HANDSEED`, above all handles in use
+pub fn repair(document: &mut CadDocument) -> HandleReport {
+    let (report, replacements, moved) = plan(document);
+
+    for (slot, replacement) in claim_slots(document).into_iter().zip(replacements) {
+        if let Some(replacement) = replacement {
+            *slot = replacement;
+        }
+    }
+    if !moved.is_empty() {
+        redirect_references(document, &moved);
+    }
+    rekey(document);
+
+    document.set_next_handle(report.seed);
+    document.header.handle_seed = report.seed;
+    report
+}
+
+/// Whether [`repair`] would change anything
+pub(crate) fn needs_repair(document: &CadDocument) -> bool {
+    let reserved = reserved_handles(document);
+    let mut seen = HashSet::new();
+    claims(document).into_iter().any(|(handle, _)| {
+        !handle.is_null()
+            && (reserved.contains(&handle) || !seen.insert(handle) || handle.value() >= document.next_handle())
+    })
+}
+
+/// The report, the new handle of each claim in claim order, and where items
+/// on reserved handles went
+fn plan(document: &CadDocument) -> (HandleReport, Vec<Option<Handle>>, HashMap<Handle, Handle>) {
+    let claims = claims(document);
+    let reserved = reserved_handles(document);
+    let highest = claims.iter().map(|(handle, _)| handle.value()).max().unwrap_or(0);
+    let mut next = document.next_handle().max(highest + 1);
+
+    let mut seen = HashSet::new();
+    let mut issues = Vec::new();
+    let mut replacements = Vec::with_capacity(claims.len());
+    let mut moved = HashMap::new();
+    for (handle, item) in claims {
+        let kind = if handle.is_null() {
+            None
+        } else if reserved.contains(&handle) {
+            Some(IssueKind::Reserved)
+        } else if !seen.insert(handle) {
+            Some(IssueKind::Duplicate)
+        } else {
+            None
+        };
+        let Some(kind) = kind else {
+            replacements.push(None);
+            continue;
+        };
+        let replacement = Handle::new(next);
+        next += 1;
+        if kind == IssueKind::Reserved {
+            moved.entry(handle).or_insert(replacement);
+        }
+        issues.push(HandleIssue { handle, kind, item, replacement });
+        replacements.push(Some(replacement));
+    }
+
+    let report = HandleReport { issues, previous_seed: document.next_handle(), seed: next };
+    (report, replacements, moved)
+}
+
+fn reserved_handles(document: &CadDocument) -> HashSet<Handle> {
+    let h = &document.header;
+    TABLE_HANDLES
+        .map(Handle::new)
+        .chain([
+            h.block_control_handle,
+            h.layer_control_handle,
+            h.style_control_handle,
+            h.linetype_control_handle,
+            h.view_control_handle,
+            h.ucs_control_handle,
+            h.vport_control_handle,
+            h.appid_control_handle,
+            h.dimstyle_control_handle,
+            h.vpent_hdr_control_handle,
+        ])
+        .filter(|handle| !handle.is_null())
+        .collect()
+}
+
+/// Keys of a handle map in handle order
+fn sorted_keys<T>(map: &HashMap<Handle, T>) -> Vec<Handle> {
+    let mut keys: Vec<Handle> = map.keys().copied().collect();
+    keys.sort();
+    keys
+}
+
+/// Every handle of the document with a description of its item, in
+/// precedence order; [`claim_slots`] walks the same handles mutably
+fn claims(document: &CadDocument) -> Vec<(Handle, String)> {
+    let mut claims = Vec::new();
+    for record in document.block_records.iter() {
+        let item = format!("block record \"{}\"", record.name);
+        claims.push((record.handle, item.clone()));
+        claims.push((record.block_entity_handle, format!("BLOCK of {item}")));
+        claims.push((record.block_end_handle, format!("ENDBLK of {item}")));
+    }
+    macro_rules! tables {
+        ($($table:ident => $kind:literal),*) => {
+            $(for entry in document.$table.iter() {
+                claims.push((entry.handle, format!(concat!($kind, " \"{}\""), entry.name)));
+            })*
+        };
+    }
+    tables!(
+        layers => "layer", line_types => "line type", text_styles => "text style",
+        dim_styles => "dimension style", app_ids => "application id", views => "view",
+        vports => "viewport", ucss => "UCS"
+    );
+    for key in sorted_keys(&document.objects) {
+        let object = &document.objects[&key];
+        claims.push((object.handle(), format!("{} object", object.type_name())));
+    }
+    for key in sorted_keys(&document.entities) {
+        entity_claims(&document.entities[&key], &mut claims);
+    }
+    for record in document.block_records.iter() {
+        for entity in record.entities.iter().filter(|e| !is_mirror(document, e)) {
+            entity_claims(entity, &mut claims);
+        }
+    }
+    claims
+}
+
+/// Whether a block entity is a copy of the document entity with its handle
+fn is_mirror(document: &CadDocument, entity: &EntityType) -> bool {
+    document.entities.get(&entity.common().handle).is_some_and(|e| {
+        e.as_entity().entity_type() == entity.as_entity().entity_type()
+    })
+}
+
+fn entity_claims(entity: &EntityType, claims: &mut Vec<(Handle, String)>) {
+    let name = entity.as_entity().entity_type();
+    claims.push((entity.common().handle, format!("{name} entity")));
+    sub_handles(entity, |handle, kind| claims.push((handle, format!("{kind} of {name} entity"))));
+}
+
+/// Handles of the vertices, faces, attributes and SEQEND of `entity`
+fn sub_handles(entity: &EntityType, mut sub: impl FnMut(Handle, &str)) {
+    match entity {
+        EntityType::Polyline(e) => {
+            e.vertices.iter().for_each(|v| sub(v.handle, "VERTEX"));
+            if let Some(seqend) = e.seqend_handle {
+                sub(seqend, "SEQEND");
+            }
+        }
+        EntityType::Polyline2D(e) => {
+            e.vertices.iter().for_each(|v| sub(v.handle, "VERTEX"));
+            if let Some(seqend) = e.seqend_handle {
+                sub(seqend, "SEQEND");
+            }
+        }
+        EntityType::Polyline3D(e) => {
+            e.vertices.iter().for_each(|v| sub(v.handle, "VERTEX"));
+            if let Some(seqend) = e.seqend_handle {
+                sub(seqend, "SEQEND");
+            }
+        }
+        EntityType::PolyfaceMesh(e) => {
+            e.vertices.iter().for_each(|v| sub(v.common.handle, "VERTEX"));
+            e.faces.iter().for_each(|f| sub(f.common.handle, "VERTEX"));
+            if let Some(seqend) = e.seqend_handle {
+                sub(seqend, "SEQEND");
+            }
+        }
+        EntityType::PolygonMesh(e) => e.vertices.iter().for_each(|v| sub(v.common.handle, "VERTEX")),
+        EntityType::Insert(e) => e.attributes.iter().for_each(|a| sub(a.common.handle, "ATTRIB")),
+        _ => {}
+    }
+}
+
+/// Handles of the items of `document`, and those reserved for its table
+/// control objects
+pub(crate) fn used_handles(document: &CadDocument) -> HashSet<Handle> {
+    let mut used = reserved_handles(document);
+    used.extend(claims(document).into_iter().map(|(handle, _)| handle).filter(|handle| !handle.is_null()));
+    used
+}
+
+/// Handles of `entity` and of its vertices, faces, attributes and SEQEND
+pub(crate) fn entity_handles(entity: &EntityType) -> Vec<Handle> {
+    let mut handles = vec![entity.common().handle];
+    sub_handles(entity, |handle, _| handles.push(handle));
+    handles.retain(|handle| !handle.is_null());
+    handles
+}
+
+/// The handles of [`claims`], mutably and in the same order
+fn claim_slots(document: &mut CadDocument) -> Vec<&mut Handle> {
+    let mirrors: Vec<Vec<bool>> = document
+        .block_records
+        .iter()
+        .map(|record| record.entities.iter().map(|e| is_mirror(document, e)).collect())
+        .collect();
+    let mut slots = Vec::new();
+    let mut block_entities = Vec::new();
+    for record in document.block_records.iter_mut() {
+        slots.push(&mut record.handle);
+        slots.push(&mut record.block_entity_handle);
+        slots.push(&mut record.block_end_handle);
+        block_entities.push(&mut record.entities);
+    }
+    macro_rules! tables {
+        ($($table:ident),*) => {
+            $(slots.extend(document.$table.iter_mut().map(|entry| &mut entry.handle));)*
+        };
+    }
+    tables!(layers, line_types, text_styles, dim_styles, app_ids, views, vports, ucss);
+
+    let mut objects: Vec<(&Handle, &mut ObjectType)> = document.objects.iter_mut().collect();
+    objects.sort_by_key(|(key, _)| **key);
+    slots.extend(objects.into_iter().map(|(_, object)| object.handle_mut()));
+
+    let mut entities: Vec<(&Handle, &mut EntityType)> = document.entities.iter_mut().collect();
+    entities.sort_by_key(|(key, _)| **key);
+    for (_, entity) in entities {
+        entity_slots(entity, &mut slots);
+    }
+    for (entities, mirrors) in block_entities.into_iter().zip(mirrors) {
+        for (entity, _) in entities.iter_mut().zip(mirrors).filter(|(_, mirror)| !mirror) {
+            entity_slots(entity, &mut slots);
+        }
+    }
+    slots
+}
+
+fn entity_slots<'a>(entity: &'a mut EntityType, slots: &mut Vec<&'a mut Handle>) {
+    match entity {
+        EntityType::Polyline(e) => {
+            slots.push(&mut e.common.handle);
+            slots.extend(e.vertices.iter_mut().map(|v| &mut v.handle));
+            slots.extend(e.seqend_handle.as_mut());
+        }
+        EntityType::Polyline2D(e) => {
+            slots.push(&mut e.common.handle);
+            slots.extend(e.vertices.iter_mut().map(|v| &mut v.handle));
+            slots.extend(e.seqend_handle.as_mut());
+        }
+        EntityType::Polyline3D(e) => {
+            slots.push(&mut e.common.handle);
+            slots.extend(e.vertices.iter_mut().map(|v| &mut v.handle));
+            slots.extend(e.seqend_handle.as_mut());
+        }
+        EntityType::PolyfaceMesh(e) => {
+            slots.push(&mut e.common.handle);
+            slots.extend(e.vertices.iter_mut().map(|v| &mut v.common.handle));
+            slots.extend(e.faces.iter_mut().map(|f| &mut f.common.handle));
+            slots.extend(e.seqend_handle.as_mut());
+        }
+        EntityType::PolygonMesh(e) => {
+            slots.push(&mut e.common.handle);
+            slots.extend(e.vertices.iter_mut().map(|v| &mut v.common.handle));
+        }
+        EntityType::Insert(e) => {
+            slots.push(&mut e.common.handle);
+            slots.extend(e.attributes.iter_mut().map(|a| &mut a.common.handle));
+        }
+        entity => slots.push(&mut entity.common_mut().handle),
+    }
+}
+
+/// Point references to items moved off reserved handles at their new ones
+fn redirect_references(document: &mut CadDocument, moved: &HashMap<Handle, Handle>) {
+    let redirect = |handle: &mut Handle| {
+        if let Some(new) = moved.get(handle) {
+            *handle = *new;
+        }
+    };
+    // Handle-valued raw pairs of unknown items, owner included
+    let redirect_raw = |pairs: &mut Vec<(i32, String)>| {
+        for (code, value) in pairs.iter_mut() {
+            if GroupCodeValueType::from_raw_code(*code) != GroupCodeValueType::Handle {
+                continue;
+            }
+            let Ok(handle) = u64::from_str_radix(value.trim(), 16) else {
+                continue;
+            };
+            if let Some(new) = moved.get(&Handle::new(handle)) {
+                *value = format!("{:X}", new.value());
+            }
+        }
+    };
+    let redirect_entity = |entity: &mut EntityType| {
+        match entity {
+            EntityType::Unknown(unknown) => redirect_raw(&mut unknown.raw_pairs),
+            EntityType::Underlay(underlay) => redirect(&mut underlay.definition_handle),
+            EntityType::Viewport(viewport) => {
+                redirect(&mut viewport.background_handle);
+                redirect(&mut viewport.visual_style_handle);
+                redirect(&mut viewport.sun_handle);
+            }
+            _ => {}
+        }
+        let common = entity.common_mut();
+        redirect(&mut common.owner_handle);
+        common.reactors.iter_mut().for_each(redirect);
+        if let Some(xdictionary) = common.xdictionary_handle.as_mut() {
+            redirect(xdictionary);
+        }
+    };
+
+    document.entities.values_mut().for_each(redirect_entity);
+    for record in document.block_records.iter_mut() {
+        redirect(&mut record.layout);
+        if let Some(xdictionary) = record.xdictionary_handle.as_mut() {
+            redirect(xdictionary);
+        }
+        record.entities.iter_mut().for_each(redirect_entity);
+    }
+    for vport in document.vports.iter_mut() {
+        redirect(&mut vport.background_handle);
+        redirect(&mut vport.visual_style_handle);
+        redirect(&mut vport.sun_handle);
+    }
+    for object in document.objects.values_mut() {
+        if let Some(owner) = object.owner_mut() {
+            redirect(owner);
+        }
+        if let Some(reactors) = object.reactors_mut() {
+            reactors.iter_mut().for_each(redirect);
+        }
+        if let Some(Some(xdictionary)) = object.xdictionary_handle_mut() {
+            redirect(xdictionary);
+        }
+        match object {
+            ObjectType::Dictionary(dict) => dict.entries.iter_mut().for_each(|(_, h)| redirect(h)),
+            ObjectType::DictionaryWithDefault(dict) => dict.entries.iter_mut().for_each(|(_, h)| redirect(h)),
+            ObjectType::Layout(layout) => {
+                redirect(&mut layout.block_record);
+                redirect(&mut layout.viewport);
+            }
+            ObjectType::Group(group) => group.entities.iter_mut().for_each(redirect),
+            ObjectType::Unknown { raw_pairs, .. } => redirect_raw(raw_pairs),
+            ObjectType::Background(background) => {
+                if let BackgroundKind::Sky { sun } = &mut background.kind {
+                    redirect(sun);
+                }
+            }
+            ObjectType::DimAssoc(association) => {
+                redirect(&mut association.dimension);
+                for point in association.points.iter_mut().flatten() {
+                    point.objects.iter_mut().for_each(redirect);
+                    point.intersection_objects.iter_mut().for_each(redirect);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let h = &mut document.header;
+    for handle in [
+        &mut h.current_layer_handle,
+        &mut h.current_text_style_handle,
+        &mut h.current_linetype_handle,
+        &mut h.current_dimstyle_handle,
+        &mut h.current_multiline_style_handle,
+        &mut h.current_material_handle,
+        &mut h.named_objects_dict_handle,
+        &mut h.acad_group_dict_handle,
+        &mut h.acad_mlinestyle_dict_handle,
+        &mut h.acad_layout_dict_handle,
+        &mut h.acad_plotsettings_dict_handle,
+        &mut h.acad_plotstylename_dict_handle,
+        &mut h.acad_material_dict_handle,
+        &mut h.acad_color_dict_handle,
+        &mut h.acad_visualstyle_dict_handle,
+        &mut h.model_space_block_handle,
+        &mut h.paper_space_block_handle,
+    ] {
+        redirect(handle);
+    }
+}
+
+/// Key the entity and object maps by the handles their items now carry
+fn rekey(document: &mut CadDocument) {
+    let entities = std::mem::take(&mut document.entities);
+    let mut entities: Vec<(Handle, EntityType)> = entities.into_iter().collect();
+    entities.sort_by_key(|(key, _)| *key);
+    for (_, entity) in entities {
+        document.entities.entry(entity.common().handle).or_insert(entity);
+    }
+
+    let objects = std::mem::take(&mut document.objects);
+    let mut objects: Vec<(Handle, ObjectType)> = objects.into_iter().collect();
+    objects.sort_by_key(|(key, _)| *key);
+    for (_, object) in objects {
+        document.objects.entry(object.handle()).or_insert(object);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::{Line, Point, UnknownEntity};
+
+    #[test]
+    fn test_repair_conflicts() {
+        let mut doc = CadDocument::new();
+        assert!(check(&doc).is_clean());
+
+        let taken = doc.layers.get("0").unwrap().handle;
+        let mut line = Line::new();
+        line.common.handle = taken;
+        doc.add_entity(EntityType::Line(line)).unwrap();
+        let mut point = Point::new();
+        point.common.handle = Handle::new(0x3);
+        point.common.reactors.push(Handle::new(0x3));
+        doc.add_entity(EntityType::Point(point)).unwrap();
+
+        let before = doc.next_handle();
+        let report = check(&doc);
+        assert_eq!(report.issues.len(), 2);
+        let (reserved, duplicate) = (&report.issues[0], &report.issues[1]);
+        assert_eq!(reserved.kind, IssueKind::Reserved);
+        assert_eq!(duplicate.kind, IssueKind::Duplicate);
+        assert_eq!(duplicate.item, "LINE entity");
+        assert_eq!(doc.next_handle(), before);
+
+        assert_eq!(repair(&mut doc), report);
+        assert!(check(&doc).is_clean());
+        assert_eq!(doc.header.handle_seed, report.seed);
+        let line = doc.get_entity(duplicate.replacement).unwrap();
+        assert!(matches!(line, EntityType::Line(_)));
+        let point = doc.get_entity(reserved.replacement).unwrap();
+        assert_eq!(point.common().reactors, vec![reserved.replacement]);
+        assert_eq!(doc.layers.get("0").unwrap().handle, taken);
+    }
+
+    #[test]
+    fn test_repair_redirects_unknown_raw_handles() {
+        let mut doc = CadDocument::new();
+        let mut point = Point::new();
+        point.common.handle = Handle::new(0x3);
+        doc.add_entity(EntityType::Point(point)).unwrap();
+        let mut unknown = UnknownEntity::new("ACAD_PROXY_ENTITY");
+        unknown.raw_pairs = vec![(100, "AcDbProxyEntity".to_string()), (340, "3".to_string())];
+        doc.add_entity(EntityType::Unknown(unknown)).unwrap();
+        let handle = Handle::new(doc.next_handle());
+        doc.set_next_handle(handle.value() + 1);
+        let raw_pairs = vec![(5, format!("{:X}", handle.value())), (330, "3".to_string()), (340, "3".to_string()), (1, "3".to_string())];
+        doc.objects.insert(handle, ObjectType::Unknown { type_name: "MY_OBJECT".to_string(), handle, raw_pairs });
+
+        let report = repair(&mut doc);
+        let moved = format!("{:X}", report.issues[0].replacement.value());
+        let Some(ObjectType::Unknown { raw_pairs, .. }) = doc.objects.get(&handle) else {
+            panic!("unknown object lost");
+        };
+        assert_eq!(raw_pairs[1], (330, moved.clone()));
+        assert_eq!(raw_pairs[2], (340, moved.clone()));
+        assert_eq!(raw_pairs[3], (1, "3".to_string()));
+        let unknown = doc.entities().find_map(|e| match e {
+            EntityType::Unknown(unknown) => Some(unknown),
+            _ => None,
+        });
+        assert_eq!(unknown.unwrap().raw_pairs[1], (340, moved));
+    }
+}
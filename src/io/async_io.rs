@@ -0,0 +1,121 @@
+//! Async reading and writing with `tokio` (`tokio` feature)
+//!
+//! Bytes move through `tokio::io` traits on the executor; parsing and
+//! serializing run on the blocking thread pool via `spawn_blocking`, so a
+//! large drawing never stalls other tasks.
+//!
+//! ```rust,ignore
+//! use acadrust::io::dxf::{DxfReader, DxfReaderConfiguration};
+//!
+//! let upload = tokio::fs::File::open("upload.dxf").await?;
+//! let doc = DxfReader::read_async(upload, DxfReaderConfiguration::default()).await?;
+//! ```
+
+use std::io::Cursor;
+
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use crate::document::CadDocument;
+use crate::error::{DxfError, Result};
+use crate::io::dwg::{DwgReader, DwgReaderConfiguration, DwgWriter};
+use crate::io::dxf::{DxfReader, DxfReaderConfiguration, DxfWriter};
+
+async fn read_all<R: AsyncRead + Unpin>(mut reader: R) -> Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes).await?;
+    Ok(bytes)
+}
+
+async fn blocking<T, F>(work: F) -> Result<T>
+where
+    F: FnOnce() -> Result<T> + Send + 'static,
+    T: Send + 'static,
+{
+    match tokio::task::spawn_blocking(work).await {
+        Ok(result) => result,
+        Err(e) if e.is_panic() => std::panic::resume_unwind(e.into_panic()),
+        Err(e) => Err(DxfError::Custom(format!("Blocking task failed: {}", e))),
+    }
+}
+
+impl DxfReader {
+    /// Create a DXF reader from a tokio reader.
+    ///
+    /// Only the I/O is async; the returned reader parses on the calling
+    /// thread. Use [`Self::read_async`] to parse on the blocking pool.
+    pub async fn from_async_reader<R: AsyncRead + Unpin>(reader: R) -> Result<Self> {
+        Self::from_bytes(read_all(reader).await?)
+    }
+
+    /// Read a DXF document from a tokio reader, parsing on the blocking pool
+    pub async fn read_async<R: AsyncRead + Unpin>(reader: R, config: DxfReaderConfiguration) -> Result<CadDocument> {
+        let bytes = read_all(reader).await?;
+        blocking(move || DxfReader::from_bytes(bytes)?.with_configuration(config).read()).await
+    }
+}
+
+impl DwgReader<Cursor<Vec<u8>>> {
+    /// Create a DWG reader from a tokio reader.
+    ///
+    /// Only the I/O is async; the returned reader parses on the calling
+    /// thread. Use [`Self::read_async`] to parse on the blocking pool.
+    pub async fn from_async_reader<R: AsyncRead + Unpin>(reader: R) -> Result<Self> {
+        Self::from_reader(Cursor::new(read_all(reader).await?))
+    }
+
+    /// Read a DWG document from a tokio reader, parsing on the blocking pool
+    pub async fn read_async<R: AsyncRead + Unpin>(reader: R, config: DwgReaderConfiguration) -> Result<CadDocument> {
+        let bytes = read_all(reader).await?;
+        blocking(move || DwgReader::from_reader(Cursor::new(bytes))?.with_config(config).read()).await
+    }
+}
+
+impl DxfWriter {
+    /// Write the document to a tokio writer, serializing on the blocking pool
+    pub async fn write_async<W: AsyncWrite + Unpin>(self, mut writer: W) -> Result<()> {
+        let bytes = blocking(move || self.write_to_vec()).await?;
+        writer.write_all(&bytes).await?;
+        writer.flush().await?;
+        Ok(())
+    }
+}
+
+impl DwgWriter {
+    /// Write `doc` as DWG to a tokio writer, serializing on the blocking pool
+    pub async fn write_async<W: AsyncWrite + Unpin>(doc: CadDocument, mut writer: W) -> Result<()> {
+        let bytes = blocking(move || DwgWriter::write(&doc)).await?;
+        writer.write_all(&bytes).await?;
+        writer.flush().await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::{EntityType, Line};
+    use crate::types::DxfVersion;
+
+    #[test]
+    fn test_async_roundtrip() {
+        let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        runtime.block_on(async {
+            let mut doc = CadDocument::with_version(DxfVersion::AC1018);
+            doc.add_entity(EntityType::Line(Line::from_coords(0.0, 0.0, 0.0, 1.0, 1.0, 0.0))).unwrap();
+
+            let mut dxf = Vec::new();
+            DxfWriter::new(doc.clone()).write_async(&mut dxf).await.unwrap();
+            let read = DxfReader::read_async(dxf.as_slice(), DxfReaderConfiguration::default()).await.unwrap();
+            assert_eq!(read.entity_count(), 1);
+
+            let mut dwg = Vec::new();
+            DwgWriter::write_async(doc, &mut dwg).await.unwrap();
+            let read = DwgReader::read_async(dwg.as_slice(), DwgReaderConfiguration::default()).await.unwrap();
+            assert_eq!(read.version, DxfVersion::AC1018);
+            assert_eq!(read.entity_count(), 1);
+
+            let reader = DxfReader::from_async_reader(dxf.as_slice()).await.unwrap();
+            assert_eq!(reader.read().unwrap().entity_count(), 1);
+        });
+    }
+}
@@ -22,14 +22,18 @@
 //! 7. **Update header** — resolve header handle references.
 
 use std::collections::HashMap;
+use std::sync::Arc;
 
 use crate::document::{get_common_mut, CadDocument};
 use crate::entities::EntityType;
-use crate::notification::{Notification, NotificationType};
+use crate::notification::{announce, Notification, NotificationCode, NotificationObserver, NotificationType};
+use crate::objects::{DictionaryWithDefault, ImageDefinitionReactor, ObjectType, PlaceHolder};
+use crate::tables::layer::OverrideKind;
 use crate::tables::*;
 use crate::types::{DxfVersion, Handle};
 
 use super::header_handles::DwgHeaderHandlesCollection;
+use super::reader::lazy_blocks::LazyBlockSource;
 use super::reader::object_reader::templates::{
     CadAppIdTemplateData, CadBlockRecordTemplateData, CadDimStyleTemplateData,
     CadEntityTemplateData, CadLayerTemplateData, CadLineTypeTemplateData, CadTemplate,
@@ -72,11 +76,22 @@ pub struct DwgDocumentBuilder {
     /// Notifications collected during building.
     pub notifications: Vec<Notification>,
 
+    /// Called with each notification as it is recorded.
+    pub observer: Option<NotificationObserver>,
+
     /// Whether to keep unknown entities in the built document.
     pub keep_unknown_entities: bool,
 
     /// Whether to keep unknown non-graphical objects.
     pub keep_unknown_objects: bool,
+
+    /// Raw data for block records whose entities were skipped by the
+    /// object reader; those records are given a pending entity loader.
+    pub(crate) lazy_blocks: Option<Arc<LazyBlockSource>>,
+
+    /// Entities the object reader skipped for the entity filter, with
+    /// their next-entity links (R13–R2000; 0 in later versions).
+    pub(crate) skipped_entities: HashMap<u64, u64>,
 }
 
 impl DwgDocumentBuilder {
@@ -94,8 +109,11 @@ impl DwgDocumentBuilder {
             unassigned: Vec::new(),
             block_names: HashMap::new(),
             notifications: Vec::new(),
+            observer: None,
             keep_unknown_entities: false,
             keep_unknown_objects: false,
+            lazy_blocks: None,
+            skipped_entities: HashMap::new(),
         }
     }
 
@@ -116,6 +134,8 @@ impl DwgDocumentBuilder {
             self.notify(
                 &format!("Repeated handle found {:#X}", handle),
                 NotificationType::Warning,
+                NotificationCode::DuplicateHandle,
+                handle,
             );
             self.unassigned.push(template);
             return;
@@ -301,6 +321,8 @@ impl DwgDocumentBuilder {
                 self.notify(
                     &format!("Table entry handle {:#X} not found", handle),
                     NotificationType::Warning,
+                    NotificationCode::MissingReference,
+                    handle,
                 );
                 return;
             }
@@ -384,7 +406,7 @@ impl DwgDocumentBuilder {
     fn build_block_record(
         &mut self,
         handle: u64,
-        _common: &CadTemplateCommon,
+        common: &CadTemplateCommon,
         data: &CadBlockRecordTemplateData,
     ) {
         let name = self
@@ -396,32 +418,50 @@ impl DwgDocumentBuilder {
         let mut record = BlockRecord::new(&name);
         record.handle = Handle::new(handle);
 
-        let entity_handles = if !data.owned_object_handles.is_empty() {
-            data.owned_object_handles.clone()
-        } else if data.first_entity_handle != 0 {
-            self.walk_entity_chain(data.first_entity_handle, data.last_entity_handle)
-        } else {
-            Vec::new()
-        };
-
-        for entity_handle in &entity_handles {
-            if let Some(entity) = self.build_entity(*entity_handle) {
-                record.entities.push(entity);
+        match &self.lazy_blocks {
+            Some(source) if source.is_deferred(handle) => {
+                let source = Arc::clone(source);
+                let data = data.clone();
+                record.set_pending_entities(PendingEntities::new(move || source.load(&data)));
             }
+            _ => record.entities = self.build_block_entities(data),
         }
 
         record.layout = Handle::new(data.layout_handle);
         record.block_entity_handle = Handle::new(data.block_entity_handle);
         record.block_end_handle = Handle::new(data.end_block_handle);
+        record.flags.anonymous = data.anonymous;
+        record.flags.has_attributes = data.has_attributes;
+        record.flags.is_xref = data.is_xref;
+        record.flags.is_xref_overlay = data.is_xref_overlay;
+        if common.xdict_handle != 0 {
+            record.xdictionary_handle = Some(Handle::new(common.xdict_handle));
+        }
 
         self.document.block_records.remove(&name);
         let _ = self.document.block_records.add(record);
     }
 
+    /// Build the entities owned by a block record, in order.
+    pub(crate) fn build_block_entities(&mut self, data: &CadBlockRecordTemplateData) -> Vec<EntityType> {
+        let entity_handles = if !data.owned_object_handles.is_empty() {
+            data.owned_object_handles.clone()
+        } else if data.first_entity_handle != 0 {
+            self.walk_entity_chain(data.first_entity_handle, data.last_entity_handle)
+        } else {
+            Vec::new()
+        };
+
+        entity_handles
+            .iter()
+            .filter_map(|h| self.build_entity(*h))
+            .collect()
+    }
+
     fn build_layer(
         &mut self,
         handle: u64,
-        _common: &CadTemplateCommon,
+        common: &CadTemplateCommon,
         data: &CadLayerTemplateData,
     ) {
         let mut layer = Layer::new(&data.name);
@@ -440,6 +480,11 @@ impl DwgDocumentBuilder {
             }
         }
 
+        if common.xdict_handle != 0 {
+            layer.xdictionary_handle = Some(Handle::new(common.xdict_handle));
+            layer.overrides = self.layer_overrides(common.xdict_handle);
+        }
+
         self.document.layers.remove(&data.name);
         let _ = self.document.layers.add(layer);
     }
@@ -577,10 +622,28 @@ impl DwgDocumentBuilder {
                 table_type, handle
             ),
             NotificationType::NotImplemented,
+            NotificationCode::TableEntryDeferred,
+            handle,
         );
     }
 
     /// Look up a linetype name by handle from the templates map.
+    /// Viewport overrides of a layer, from the XRECORDs in its extension
+    /// dictionary
+    fn layer_overrides(&self, xdictionary: u64) -> Vec<LayerViewportOverride> {
+        let mut overrides = Vec::new();
+        let Some(CadTemplate::DictionaryObj { dict_data, .. }) = self.templates_map.get(&xdictionary) else {
+            return overrides;
+        };
+        for kind in OverrideKind::ALL {
+            let record = dict_data.entries.iter().find(|(key, _)| key == kind.key());
+            if let Some(CadTemplate::XRecordObj { xrecord, .. }) = record.and_then(|(_, h)| self.templates_map.get(h)) {
+                kind.apply(xrecord, &mut overrides, |h| self.get_linetype_name(h.value()));
+            }
+        }
+        overrides
+    }
+
     fn get_linetype_name(&self, handle: u64) -> Option<String> {
         if let Some(CadTemplate::LineTypeEntry { ltype_data, .. }) = self.templates_map.get(&handle)
         {
@@ -653,6 +716,114 @@ impl DwgDocumentBuilder {
         for entity in entities_to_add {
             let _ = self.document.add_entity(entity);
         }
+
+        // Styles in the file replace the Standard style of a new document
+        if self.templates_map.values().any(|t| matches!(t, CadTemplate::MLeaderStyleObj { .. })) {
+            self.document.objects.retain(|_, o| !matches!(o, ObjectType::MultiLeaderStyle(_)));
+        }
+
+        for template in self.templates_map.values() {
+            let object = match template {
+                CadTemplate::UnderlayDefObj { common, definition } => {
+                    let mut definition = definition.clone();
+                    definition.handle = Handle::new(common.handle);
+                    definition.owner_handle = Handle::new(common.owner_handle);
+                    definition.reactors = common.reactor_handles.iter().map(|&h| Handle::new(h)).collect();
+                    ObjectType::UnderlayDefinition(definition)
+                }
+                CadTemplate::ImageDefObj { common, imgdef_data } => {
+                    let mut definition = imgdef_data.definition.clone();
+                    definition.handle = Handle::new(common.handle);
+                    definition.owner = Handle::new(common.owner_handle);
+                    definition.reactors = common.reactor_handles.iter().map(|&h| Handle::new(h)).collect();
+                    ObjectType::ImageDefinition(definition)
+                }
+                CadTemplate::ImageDefReactorObj { common, reactor_data } => {
+                    let mut reactor = ImageDefinitionReactor::new(Handle::new(reactor_data.image_handle));
+                    reactor.handle = Handle::new(common.handle);
+                    reactor.owner = Handle::new(common.owner_handle);
+                    ObjectType::ImageDefinitionReactor(reactor)
+                }
+                CadTemplate::ScaleObj { common, scale } => {
+                    let mut scale = scale.clone();
+                    scale.handle = Handle::new(common.handle);
+                    scale.owner_handle = Handle::new(common.owner_handle);
+                    ObjectType::Scale(scale)
+                }
+                CadTemplate::DimAssocObj { common, assoc } => {
+                    let mut assoc = assoc.clone();
+                    assoc.handle = Handle::new(common.handle);
+                    assoc.owner = Handle::new(common.owner_handle);
+                    assoc.reactors = common.reactor_handles.iter().map(|&h| Handle::new(h)).collect();
+                    ObjectType::DimAssoc(assoc)
+                }
+                CadTemplate::RasterVariablesObj { common, variables } => {
+                    let mut variables = variables.clone();
+                    variables.handle = Handle::new(common.handle);
+                    variables.owner = Handle::new(common.owner_handle);
+                    ObjectType::RasterVariables(variables)
+                }
+                CadTemplate::MLeaderStyleObj { common, mls_style_data: data } => {
+                    let optional = |h: u64| (h != 0).then(|| Handle::new(h));
+                    let mut style = data.style.clone();
+                    style.handle = Handle::new(common.handle);
+                    style.owner_handle = Handle::new(common.owner_handle);
+                    style.line_type_handle = optional(data.leader_line_type_handle);
+                    style.arrowhead_handle = optional(data.arrowhead_handle);
+                    style.text_style_handle = optional(data.mtext_style_handle);
+                    style.block_content_handle = optional(data.block_content_handle);
+                    // The name is the key of the style in ACAD_MLEADERSTYLE
+                    if let Some(CadTemplate::DictionaryObj { dict_data, .. }) =
+                        self.templates_map.get(&common.owner_handle)
+                    {
+                        if let Some((name, _)) = dict_data.entries.iter().find(|(_, h)| *h == common.handle) {
+                            style.name = name.clone();
+                        }
+                    }
+                    ObjectType::MultiLeaderStyle(style)
+                }
+                CadTemplate::WipeoutVariablesObj { common, variables } => {
+                    let mut variables = variables.clone();
+                    variables.handle = Handle::new(common.handle);
+                    variables.owner = Handle::new(common.owner_handle);
+                    ObjectType::WipeoutVariables(variables)
+                }
+                // Replaces the plain dictionary a new document starts with
+                CadTemplate::DictWithDefault { common, dict_default_data } => {
+                    let data = &dict_default_data.dict_data;
+                    let mut dictionary = DictionaryWithDefault::new();
+                    dictionary.handle = Handle::new(common.handle);
+                    dictionary.owner = Handle::new(common.owner_handle);
+                    dictionary.entries = data.entries.iter().map(|(k, h)| (k.clone(), Handle::new(*h))).collect();
+                    dictionary.default_handle = Handle::new(dict_default_data.default_entry_handle);
+                    dictionary.duplicate_cloning = data.duplicate_cloning;
+                    dictionary.hard_owner = data.hard_owner;
+                    ObjectType::DictionaryWithDefault(dictionary)
+                }
+                CadTemplate::PlaceHolderObj { common } => {
+                    let mut placeholder = PlaceHolder::new();
+                    placeholder.handle = Handle::new(common.handle);
+                    placeholder.owner = Handle::new(common.owner_handle);
+                    ObjectType::PlaceHolder(placeholder)
+                }
+                CadTemplate::CustomObject { common, object } => {
+                    let mut object = object.as_ref().clone();
+                    *object.handle_mut() = Handle::new(common.handle);
+                    if let Some(owner) = object.owner_mut() {
+                        *owner = Handle::new(common.owner_handle);
+                    }
+                    if let Some(reactors) = object.reactors_mut() {
+                        *reactors = common.reactor_handles.iter().map(|&h| Handle::new(h)).collect();
+                    }
+                    if let Some(xdictionary) = object.xdictionary_handle_mut() {
+                        *xdictionary = (common.xdict_handle != 0).then(|| Handle::new(common.xdict_handle));
+                    }
+                    object
+                }
+                _ => continue,
+            };
+            self.document.objects.insert(Handle::new(template.common().handle), object);
+        }
     }
 
     // ------------------------------------------------------------------
@@ -897,11 +1068,17 @@ impl DwgDocumentBuilder {
         let max_iterations = 100_000;
 
         while current != 0 && iterations < max_iterations {
-            result.push(current);
+            // Entities skipped by the entity filter only pass the chain on
+            let skipped = self.skipped_entities.get(&current).copied();
+            if skipped.is_none() {
+                result.push(current);
+            }
             if current == last {
                 break;
             }
-            if let Some(template) = self.templates_map.get(&current) {
+            if let Some(next) = skipped {
+                current = next;
+            } else if let Some(template) = self.templates_map.get(&current) {
                 match template {
                     CadTemplate::Entity { entity_data, .. }
                     | CadTemplate::TextEntity { entity_data, .. }
@@ -929,8 +1106,10 @@ impl DwgDocumentBuilder {
         result
     }
 
-    fn notify(&mut self, message: &str, ntype: NotificationType) {
-        self.notifications.push(Notification::new(ntype, message));
+    fn notify(&mut self, message: &str, ntype: NotificationType, code: NotificationCode, handle: u64) {
+        let notification = Notification::new(ntype, message).with_code(code).with_handle(Handle::new(handle));
+        announce(&notification, self.observer.as_ref());
+        self.notifications.push(notification);
     }
 }
 
@@ -977,6 +1156,12 @@ fn set_template_handle(template: &mut CadTemplate, handle: u64) {
         | CadTemplate::PlotSettingsObj { common, .. }
         | CadTemplate::ScaleObj { common, .. }
         | CadTemplate::XRecordObj { common, .. }
+        | CadTemplate::DimAssocObj { common, .. }
+        | CadTemplate::UnderlayDefObj { common, .. }
+        | CadTemplate::RasterVariablesObj { common, .. }
+        | CadTemplate::WipeoutVariablesObj { common, .. }
+        | CadTemplate::PlaceHolderObj { common, .. }
+        | CadTemplate::CustomObject { common, .. }
         | CadTemplate::GenericObject { common, .. } => {
             common.handle = handle;
         }
@@ -0,0 +1,183 @@
+//! Decoding DWG objects of classes the crate does not know
+//!
+//! Objects whose type is a class of the CLASSES section, and which the
+//! crate does not decode, are read as
+//! [`UnknownEntity`](crate::entities::UnknownEntity) or as generic objects
+//! that are dropped. [`DwgCustomClasses`] lets an application decode such
+//! classes, keyed by DXF class name, without forking the crate.
+//!
+//! The reader reads the data every object starts with, handle, extended
+//! data, owner, reactors and extension dictionary, plus the common entity
+//! data for entity classes, and hands it over as a [`DwgObjectHeader`]
+//! together with the object's streams, positioned at the class data. The
+//! callback reads the class data and returns the template to keep, or
+//! `None` to read the object as if no reader were registered.
+//!
+//! ```rust,ignore
+//! let custom = DwgCustomClasses::new().with_reader("AEC_WALL_STYLE", |_, streams, header| {
+//!     let mut record = XRecord::named("AEC_WALL_STYLE");
+//!     record.add_double(40, streams.object_reader.read_bit_double()?);
+//!     Ok(Some(header.object_template(ObjectType::XRecord(record))))
+//! });
+//! let doc = DwgReader::from_file("walls.dwg")?.with_custom_classes(custom).read()?;
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::classes::DxfClass;
+use crate::entities::{EntityCommon, EntityType};
+use crate::error::Result;
+use crate::objects::ObjectType;
+
+use super::reader::object_reader::templates::{CadEntityTemplateData, CadTemplate, CadTemplateCommon};
+use super::reader::object_reader::StreamSet;
+
+/// Reader of one class: the class, the object's streams positioned at the
+/// class data and the data read before it
+pub type DwgClassReader =
+    dyn Fn(&DxfClass, &mut StreamSet, &DwgObjectHeader) -> Result<Option<CadTemplate>> + Send + Sync;
+
+/// Data read before the class data of an object
+#[derive(Debug, Clone, Default)]
+pub struct DwgObjectHeader {
+    /// Handle, owner, reactors, extension dictionary and extended data
+    pub common: CadTemplateCommon,
+    /// Layer, linetype and other common entity data, for entity classes
+    pub entity: Option<(CadEntityTemplateData, EntityCommon)>,
+}
+
+impl DwgObjectHeader {
+    /// Template of `entity`, whose common data is replaced by the one read
+    pub fn entity_template(&self, mut entity: EntityType) -> CadTemplate {
+        let (entity_data, common) = match &self.entity {
+            Some((entity_data, common)) => (entity_data.clone(), common.clone()),
+            None => (CadEntityTemplateData::default(), EntityCommon::new()),
+        };
+        *entity.common_mut() = common;
+        CadTemplate::Entity { common: self.common.clone(), entity_data, entity }
+    }
+
+    /// Template of `object`; the builder sets its handle, owner, reactors
+    /// and extension dictionary from the data read
+    pub fn object_template(&self, object: ObjectType) -> CadTemplate {
+        CadTemplate::CustomObject { common: self.common.clone(), object: Box::new(object) }
+    }
+}
+
+/// Readers of DWG classes, by upper case DXF class name
+#[derive(Clone, Default)]
+pub struct DwgCustomClasses {
+    readers: BTreeMap<String, Arc<DwgClassReader>>,
+}
+
+impl DwgCustomClasses {
+    /// No custom classes
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read objects of the class named `dxf_name` with `reader`
+    pub fn with_reader(
+        mut self,
+        dxf_name: &str,
+        reader: impl Fn(&DxfClass, &mut StreamSet, &DwgObjectHeader) -> Result<Option<CadTemplate>>
+            + Send
+            + Sync
+            + 'static,
+    ) -> Self {
+        self.readers.insert(dxf_name.to_uppercase(), Arc::new(reader));
+        self
+    }
+
+    /// Whether no reader is registered
+    pub fn is_empty(&self) -> bool {
+        self.readers.is_empty()
+    }
+
+    pub(crate) fn reader(&self, dxf_name: &str) -> Option<Arc<DwgClassReader>> {
+        self.readers.get(&dxf_name.to_uppercase()).cloned()
+    }
+}
+
+impl fmt::Debug for DwgCustomClasses {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DwgCustomClasses")
+            .field("readers", &self.readers.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use super::*;
+    use crate::classes::DxfClassCollection;
+    use crate::document::CadDocument;
+    use crate::io::dwg::builder::DwgDocumentBuilder;
+    use crate::io::dwg::reader::object_reader::DwgObjectReader;
+    use crate::io::dwg::writer::object_writer::DwgObjectWriter;
+    use crate::objects::{WipeoutVariables, XRecord};
+    use crate::types::{DxfVersion, Handle};
+
+    /// Reads the objects of `doc` with WIPEOUTVARIABLES renamed to a class
+    /// the reader does not know
+    fn read_renamed(doc: &CadDocument, handle: Handle, custom: DwgCustomClasses) -> DwgObjectReader {
+        let version = DxfVersion::AC1032;
+        let (data, handle_map) = DwgObjectWriter::new(version, doc).write(doc).unwrap();
+        let classes: Vec<DxfClass> = doc
+            .classes
+            .iter()
+            .cloned()
+            .map(|mut class| {
+                if class.dxf_name == "WIPEOUTVARIABLES" {
+                    class.dxf_name = "ACME_SETTINGS".to_string();
+                }
+                class
+            })
+            .collect();
+        let mut reader = DwgObjectReader::new(version, data, VecDeque::from([handle.value()]), handle_map, &classes);
+        reader.custom_classes = custom;
+        reader.read().unwrap();
+        reader
+    }
+
+    #[test]
+    fn test_custom_class_reader() {
+        let mut doc = CadDocument::new();
+        let mut classes = DxfClassCollection::new();
+        classes.update_defaults();
+        doc.classes = classes;
+        let mut variables = WipeoutVariables::new();
+        variables.handle = doc.allocate_handle();
+        variables.owner = doc.header.named_objects_dict_handle;
+        variables.display_frame = 1;
+        let handle = variables.handle;
+        doc.objects.insert(handle, ObjectType::WipeoutVariables(variables));
+
+        let custom = DwgCustomClasses::new().with_reader("acme_settings", |class, streams, header| {
+            let mut record = XRecord::named(class.dxf_name.clone());
+            record.add_int16(70, streams.object_reader.read_bit_short()?);
+            Ok(Some(header.object_template(ObjectType::XRecord(record))))
+        });
+        let mut reader = read_renamed(&doc, handle, custom);
+        let mut builder = DwgDocumentBuilder::new(DxfVersion::AC1032);
+        builder.add_templates(std::mem::take(&mut reader.templates));
+        builder.build_document();
+        let Some(ObjectType::XRecord(record)) = builder.document.objects.get(&handle) else {
+            panic!("custom object not built");
+        };
+        assert_eq!(record.name, "ACME_SETTINGS");
+        assert_eq!(record.owner, doc.header.named_objects_dict_handle);
+        assert_eq!(record.entries.len(), 1);
+
+        // Declined objects are read as if no reader were registered
+        let custom = DwgCustomClasses::new().with_reader("ACME_SETTINGS", |_, _, _| Ok(None));
+        let reader = read_renamed(&doc, handle, custom);
+        let template = reader.templates.iter().find(|t| t.common().handle == handle.value());
+        assert!(matches!(template, Some(CadTemplate::GenericObject { .. })));
+        assert!(reader.notifications.iter().any(|n| n.message.contains("declined")));
+    }
+}
@@ -0,0 +1,235 @@
+//! Handle → object offset table.
+//!
+//! Both the `AcDb:Handles` reader and the object writer map object handles
+//! to byte offsets. The table is a vector of `(handle, offset)` pairs kept
+//! sorted by handle, so iteration yields handles in ascending order, which
+//! is the order the handle section is encoded in.
+//!
+//! Tables built in one go ([`HandleOffsetTable::from_unsorted`] or
+//! `collect`) also get a radix index: the handle range is split into
+//! power-of-two buckets holding about one entry each, so a lookup is an
+//! index read plus a search of a few neighbouring entries. Tables grown
+//! with [`HandleOffsetTable::insert`] fall back to a binary search.
+
+/// Sorted table mapping object handles to byte offsets.
+#[derive(Debug, Clone, Default)]
+pub struct HandleOffsetTable {
+    entries: Vec<(u64, i64)>,
+    index: Option<RadixIndex>,
+}
+
+/// First entry of each handle bucket.
+#[derive(Debug, Clone)]
+struct RadixIndex {
+    base: u64,
+    shift: u32,
+    /// `starts[b]..starts[b + 1]` are the entries of bucket `b`.
+    starts: Vec<u32>,
+}
+
+impl RadixIndex {
+    fn build(entries: &[(u64, i64)]) -> Option<Self> {
+        let (&(base, _), &(last, _)) = (entries.first()?, entries.last()?);
+        let span = last - base;
+        // Smallest bucket width that needs no more buckets than entries
+        let mut shift = 0;
+        while (span >> shift) >= entries.len() as u64 {
+            shift += 1;
+        }
+        let buckets = (span >> shift) as usize + 1;
+
+        let mut starts = Vec::with_capacity(buckets + 1);
+        for (i, &(handle, _)) in entries.iter().enumerate() {
+            let bucket = ((handle - base) >> shift) as usize;
+            while starts.len() <= bucket {
+                starts.push(i as u32);
+            }
+        }
+        starts.push(entries.len() as u32);
+        Some(Self { base, shift, starts })
+    }
+
+    /// Entry range that may hold `handle`.
+    #[inline]
+    fn range(&self, handle: u64) -> Option<std::ops::Range<usize>> {
+        let bucket = (handle.checked_sub(self.base)? >> self.shift) as usize;
+        let end = *self.starts.get(bucket + 1)?;
+        Some(self.starts[bucket] as usize..end as usize)
+    }
+}
+
+impl HandleOffsetTable {
+    /// Create an empty table.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create an empty table with room for `capacity` entries.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            entries: Vec::with_capacity(capacity),
+            index: None,
+        }
+    }
+
+    /// Build a table from pairs in any order.
+    ///
+    /// When a handle appears more than once the last offset wins.
+    pub fn from_unsorted(mut entries: Vec<(u64, i64)>) -> Self {
+        // Stable sort keeps duplicates in insertion order
+        entries.sort_by_key(|&(handle, _)| handle);
+        let mut table = Self::with_capacity(entries.len());
+        for (handle, offset) in entries {
+            match table.entries.last_mut() {
+                Some(last) if last.0 == handle => last.1 = offset,
+                _ => table.entries.push((handle, offset)),
+            }
+        }
+        table.index = RadixIndex::build(&table.entries);
+        table
+    }
+
+    /// Insert or replace the offset of `handle`, returning the previous one.
+    ///
+    /// Appending in ascending handle order is O(1). Drops the radix index.
+    pub fn insert(&mut self, handle: u64, offset: i64) -> Option<i64> {
+        self.index = None;
+        match self.entries.last() {
+            None => {}
+            Some(&(last, _)) if handle > last => {}
+            _ => {
+                return match self.search(handle) {
+                    Ok(i) => Some(std::mem::replace(&mut self.entries[i].1, offset)),
+                    Err(i) => {
+                        self.entries.insert(i, (handle, offset));
+                        None
+                    }
+                };
+            }
+        }
+        self.entries.push((handle, offset));
+        None
+    }
+
+    /// Offset of `handle`, if present.
+    #[inline]
+    pub fn get(&self, handle: u64) -> Option<i64> {
+        self.search(handle).ok().map(|i| self.entries[i].1)
+    }
+
+    /// Whether `handle` is present.
+    #[inline]
+    pub fn contains_key(&self, handle: u64) -> bool {
+        self.search(handle).is_ok()
+    }
+
+    /// Number of entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the table is empty.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Highest handle in the table.
+    pub fn last_handle(&self) -> Option<u64> {
+        self.entries.last().map(|&(handle, _)| handle)
+    }
+
+    /// Iterate over `(handle, offset)` pairs in ascending handle order.
+    pub fn iter(&self) -> impl Iterator<Item = (u64, i64)> + '_ {
+        self.entries.iter().copied()
+    }
+
+    /// Iterate over handles in ascending order.
+    pub fn keys(&self) -> impl Iterator<Item = u64> + '_ {
+        self.entries.iter().map(|&(handle, _)| handle)
+    }
+
+    /// The sorted `(handle, offset)` pairs.
+    pub fn as_slice(&self) -> &[(u64, i64)] {
+        &self.entries
+    }
+
+    fn search(&self, handle: u64) -> std::result::Result<usize, usize> {
+        if let Some(index) = &self.index {
+            let Some(range) = index.range(handle) else {
+                return Err(0);
+            };
+            let start = range.start;
+            return self.entries[range]
+                .binary_search_by_key(&handle, |&(h, _)| h)
+                .map(|i| start + i)
+                .map_err(|i| start + i);
+        }
+        self.entries.binary_search_by_key(&handle, |&(h, _)| h)
+    }
+}
+
+impl PartialEq for HandleOffsetTable {
+    fn eq(&self, other: &Self) -> bool {
+        self.entries == other.entries
+    }
+}
+
+impl Eq for HandleOffsetTable {}
+
+impl FromIterator<(u64, i64)> for HandleOffsetTable {
+    fn from_iter<I: IntoIterator<Item = (u64, i64)>>(iter: I) -> Self {
+        Self::from_unsorted(iter.into_iter().collect())
+    }
+}
+
+impl<'a> IntoIterator for &'a HandleOffsetTable {
+    type Item = (u64, i64);
+    type IntoIter = std::iter::Copied<std::slice::Iter<'a, (u64, i64)>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.iter().copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_keeps_handles_sorted() {
+        let mut table = HandleOffsetTable::new();
+        assert_eq!(table.insert(0x20, 200), None);
+        assert_eq!(table.insert(0x10, 100), None);
+        assert_eq!(table.insert(0x30, 300), None);
+        assert_eq!(table.insert(0x20, 250), Some(200));
+
+        assert_eq!(table.keys().collect::<Vec<_>>(), vec![0x10, 0x20, 0x30]);
+        assert_eq!(table.get(0x20), Some(250));
+        assert_eq!(table.get(0x15), None);
+        assert!(table.contains_key(0x30));
+        assert_eq!(table.last_handle(), Some(0x30));
+    }
+
+    #[test]
+    fn test_from_unsorted_last_offset_wins() {
+        let table = HandleOffsetTable::from_unsorted(vec![(5, 50), (1, 10), (5, 55), (3, 30)]);
+        assert_eq!(table.as_slice(), &[(1, 10), (3, 30), (5, 55)]);
+    }
+
+    #[test]
+    fn test_indexed_lookup_matches_entries() {
+        // Dense run, a gap, and a far outlier
+        let mut entries: Vec<(u64, i64)> = (0x100..0x400).map(|h| (h, h as i64 * 2)).collect();
+        entries.extend((0x1000..0x1100).step_by(7).map(|h| (h, -(h as i64))));
+        entries.push((u64::MAX, 1));
+        let table = HandleOffsetTable::from_unsorted(entries.clone());
+        assert!(table.index.is_some());
+
+        for &(handle, offset) in &entries {
+            assert_eq!(table.get(handle), Some(offset));
+        }
+        for handle in [0, 0xFF, 0x400, 0x1001, 0x2000, u64::MAX - 1] {
+            assert_eq!(table.get(handle), None);
+        }
+    }
+}
@@ -13,6 +13,7 @@
 //! - [`reed_solomon`] — Byte de-interleaving for AC21 Reed-Solomon encoded data
 //! - [`reference_type`] — DWG handle reference codes and resolution
 //! - [`header_handles`] — Named handle collection for DWG file header references
+//! - [`handle_offsets`] — Sorted handle → object offset table
 //! - [`section_io`] — Version-conditional section reading/writing helpers
 //! - [`file_header`] — DWG file header structures (AC15, AC18, AC21)
 //! - [`object_type`] — DWG object type codes
@@ -24,8 +25,10 @@ pub mod checksum;
 pub mod compression;
 pub mod constants;
 pub mod crc;
+mod custom;
 pub mod encryption;
 pub mod file_header;
+pub mod handle_offsets;
 pub mod header_handles;
 pub mod object_type;
 pub mod reader;
@@ -36,10 +39,12 @@ pub mod writer;
 
 // Re-export commonly used types
 pub use compression::{Compressor, Decompressor};
+pub use custom::{DwgClassReader, DwgCustomClasses, DwgObjectHeader};
 pub use file_header::{
     Dwg21CompressedMetadata, DwgFileHeader, DwgFileHeaderAC15, DwgFileHeaderAC18,
     DwgFileHeaderAC21, DwgLocalSectionMap, DwgSectionDescriptor, DwgSectionLocatorRecord,
 };
+pub use handle_offsets::HandleOffsetTable;
 pub use header_handles::DwgHeaderHandlesCollection;
 pub use object_type::DwgObjectType;
 pub use builder::DwgDocumentBuilder;
@@ -202,6 +202,54 @@ impl DwgObjectType {
     pub fn as_raw(self) -> i16 {
         self as i16
     }
+
+    /// DXF name of an entity type that stands on its own, as matched by an
+    /// [`EntityFilter`](crate::io::EntityFilter).
+    ///
+    /// `None` for non-graphical objects, class-based types, and the
+    /// vertices, attributes, SEQEND and block markers read with their owner.
+    pub fn entity_name(self) -> Option<&'static str> {
+        let name = match self {
+            Self::Text => "TEXT",
+            Self::Attdef => "ATTDEF",
+            Self::Insert | Self::Minsert => "INSERT",
+            Self::Polyline2D | Self::Polyline3D | Self::PolylinePface | Self::PolylineMesh => "POLYLINE",
+            Self::Arc => "ARC",
+            Self::Circle => "CIRCLE",
+            Self::Line => "LINE",
+            Self::DimensionOrdinate
+            | Self::DimensionLinear
+            | Self::DimensionAligned
+            | Self::DimensionAng3Pt
+            | Self::DimensionAng2Ln
+            | Self::DimensionRadius
+            | Self::DimensionDiameter => "DIMENSION",
+            Self::Point => "POINT",
+            Self::Face3D => "3DFACE",
+            Self::Solid => "SOLID",
+            Self::Trace => "TRACE",
+            Self::Shape => "SHAPE",
+            Self::Viewport => "VIEWPORT",
+            Self::Ellipse => "ELLIPSE",
+            Self::Spline => "SPLINE",
+            Self::Region => "REGION",
+            Self::Solid3D => "3DSOLID",
+            Self::Body => "BODY",
+            Self::Ray => "RAY",
+            Self::Xline => "XLINE",
+            Self::OleFrame => "OLEFRAME",
+            Self::Mtext => "MTEXT",
+            Self::Leader => "LEADER",
+            Self::Tolerance => "TOLERANCE",
+            Self::Mline => "MLINE",
+            Self::Ole2Frame => "OLE2FRAME",
+            Self::LwPolyline => "LWPOLYLINE",
+            Self::Hatch => "HATCH",
+            Self::AcadProxyEntity => "ACAD_PROXY_ENTITY",
+            _ => return None,
+        };
+        Some(name)
+    }
 }
 
 #[cfg(test)]
@@ -220,6 +268,14 @@ mod tests {
         assert_eq!(DwgObjectType::from_raw(999), DwgObjectType::Unlisted);
     }
 
+    #[test]
+    fn test_entity_name() {
+        assert_eq!(DwgObjectType::DimensionRadius.entity_name(), Some("DIMENSION"));
+        assert_eq!(DwgObjectType::Minsert.entity_name(), Some("INSERT"));
+        assert_eq!(DwgObjectType::Vertex2D.entity_name(), None);
+        assert_eq!(DwgObjectType::Layer.entity_name(), None);
+    }
+
     #[test]
     fn test_as_raw() {
         assert_eq!(DwgObjectType::Line.as_raw(), 0x13);
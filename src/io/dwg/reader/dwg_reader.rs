@@ -15,13 +15,16 @@ use std::collections::{HashMap, VecDeque};
 use std::fs::File;
 use std::io::{BufReader, Cursor, Read, Seek, SeekFrom};
 use std::path::Path;
+use std::sync::Arc;
 
 use byteorder::{LittleEndian, ReadBytesExt};
 
 use crate::classes::DxfClassCollection;
 use crate::document::CadDocument;
 use crate::error::{DxfError, Result};
-use crate::notification::{Notification, NotificationType};
+use crate::io::progress::{CancellationToken, ProgressReporter, ReadPhase, ReadProgress};
+use crate::io::EntityFilter;
+use crate::notification::{announce, Notification, NotificationCode, NotificationObserver, NotificationType};
 use crate::preview::DwgPreview;
 use crate::summary_info::CadSummaryInfo;
 use crate::types::DxfVersion;
@@ -30,6 +33,7 @@ use super::super::builder::DwgDocumentBuilder;
 use super::super::compression::lz77_ac18::Lz77Ac18Decompressor;
 use super::super::compression::lz77_ac21::Lz77Ac21Decompressor;
 use super::super::compression::Decompressor;
+use super::super::custom::DwgCustomClasses;
 use super::super::constants::{ac18, ac21, section_names};
 use super::super::encryption;
 use super::super::file_header::{
@@ -37,12 +41,14 @@ use super::super::file_header::{
     DwgLocalSectionMap, DwgSectionDescriptor, DwgSectionLocatorRecord,
     Dwg21CompressedMetadata,
 };
+use super::super::handle_offsets::HandleOffsetTable;
 use super::super::header_handles::DwgHeaderHandlesCollection;
 use super::super::reed_solomon;
 use super::{
     DwgAppInfoReader, DwgClassesReader, DwgHandleReader, DwgHeaderReader,
     DwgPreviewReader, DwgSummaryInfoReader,
 };
+use super::lazy_blocks::LazyBlockSource;
 use super::object_reader::DwgObjectReader;
 
 // ---------------------------------------------------------------------------
@@ -110,6 +116,21 @@ pub struct DwgReader<R: Read + Seek> {
 
     /// Notifications collected during reading.
     notifications: Vec<Notification>,
+
+    /// Called with each notification as it is recorded.
+    observer: Option<NotificationObserver>,
+
+    /// Progress callback and cancellation token.
+    progress: ProgressReporter,
+
+    /// Decode block record entities on first access.
+    lazy_blocks: bool,
+
+    /// Entity types to decode.
+    entity_filter: EntityFilter,
+
+    /// Readers of classes the crate does not decode.
+    custom_classes: DwgCustomClasses,
 }
 
 impl DwgReader<BufReader<File>> {
@@ -144,6 +165,11 @@ impl<R: Read + Seek> DwgReader<R> {
             config: DwgReaderConfiguration::default(),
             file_header,
             notifications: Vec::new(),
+            observer: None,
+            progress: ProgressReporter::default(),
+            lazy_blocks: false,
+            entity_filter: EntityFilter::All,
+            custom_classes: DwgCustomClasses::default(),
         })
     }
 
@@ -153,20 +179,88 @@ impl<R: Read + Seek> DwgReader<R> {
         self
     }
 
+    /// Decode only model and paper space entities up front.
+    ///
+    /// Other block records keep their raw object data and decode their
+    /// entities on first call to
+    /// [`BlockRecord::load_entities`](crate::tables::BlockRecord::load_entities)
+    /// (or [`CadDocument::load_blocks`]); the writers load them as needed.
+    /// Lazily loaded entities are not mirrored into the document's entity
+    /// collection. Off by default.
+    pub fn with_lazy_blocks(mut self, lazy: bool) -> Self {
+        self.lazy_blocks = lazy;
+        self
+    }
+
+    /// Decode only the entity types `filter` accepts, by DXF entity name.
+    ///
+    /// Entities of other types are skipped without being decoded.
+    pub fn with_entity_filter(mut self, filter: EntityFilter) -> Self {
+        self.entity_filter = filter;
+        self
+    }
+
+    /// Decode object classes the crate does not decode itself with the
+    /// readers of `custom`, by DXF class name.
+    pub fn with_custom_classes(mut self, custom: DwgCustomClasses) -> Self {
+        self.custom_classes = custom;
+        self
+    }
+
+    /// Report progress while reading.
+    ///
+    /// `callback` is called as the read moves through its phases and, while
+    /// objects are read, whenever the percentage advances by a whole percent.
+    pub fn with_progress<F>(mut self, callback: F) -> Self
+    where
+        F: FnMut(&ReadProgress) + 'static,
+    {
+        self.progress.set_callback(Box::new(callback));
+        self
+    }
+
+    /// Stop reading objects when `token` is cancelled.
+    ///
+    /// The token is checked between objects; a cancelled read builds the
+    /// document from the objects read so far and adds a warning
+    /// notification.
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.progress.set_token(token);
+        self
+    }
+
+    /// Call `observer` with each notification as it is recorded.
+    ///
+    /// The notifications are still collected in
+    /// [`CadDocument::notifications`].
+    pub fn on_notification<F>(mut self, observer: F) -> Self
+    where
+        F: Fn(&Notification) + Send + Sync + 'static,
+    {
+        self.observer = Some(Arc::new(observer));
+        self
+    }
+
     /// Read the entire DWG file and return a [`CadDocument`].
     ///
     /// This is the main entry point for reading DWG files.
     pub fn read(mut self) -> Result<CadDocument> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::info_span!("read_dwg", version = ?self.version).entered();
+
         // Step 1: Read the file header.
+        self.progress.set_phase(ReadPhase::FileHeader, 0.0);
         self.read_file_header()?;
 
         // Step 2: Read section data.
         let summary_info = self.read_summary_info();
+        self.progress.set_phase(ReadPhase::Header, 5.0);
         let (header_vars, header_handles) = self.read_header()?;
         let _acad_maintenance_version = self.file_header.maintenance_version() as i32;
+        self.progress.set_phase(ReadPhase::Classes, 10.0);
         let classes = self.read_classes()?;
         let handle_map = self.read_handles()?;
-        let _preview = self.read_preview();
+        let preview = self.read_preview();
         let _app_info = self.read_app_info();
         // Step 3: Build the handle queue for the object reader.
         let mut handle_queue: VecDeque<u64> = VecDeque::new();
@@ -191,6 +285,7 @@ impl<R: Read + Seek> DwgReader<R> {
             _ => self.get_section_stream(section_names::ACDB_OBJECTS)?,
         };
         let class_entries: Vec<_> = classes.iter().cloned().collect();
+        let object_reader_total = handle_map.len();
         let mut object_reader = DwgObjectReader::new(
             self.version,
             objects_data,
@@ -199,29 +294,75 @@ impl<R: Read + Seek> DwgReader<R> {
             &class_entries,
         );
         object_reader.failsafe = self.config.failsafe;
-        object_reader.read()?;
+        object_reader.entity_filter = self.entity_filter.clone();
+        object_reader.custom_classes = self.custom_classes.clone();
+        object_reader.observer = self.observer.clone();
+        if self.lazy_blocks {
+            let layouts = [header_handles.model_space(), header_handles.paper_space()];
+            object_reader.eager_block_records = Some(layouts.into_iter().flatten().collect());
+        }
+        // Objects account for most of the work: 15% – 95%
+        self.progress.set_object_phase(ReadPhase::Objects, 15.0, 95.0, object_reader_total);
+        object_reader.progress = std::mem::take(&mut self.progress);
+        #[cfg(feature = "tracing")]
+        let objects_span = tracing::debug_span!("section", name = section_names::ACDB_OBJECTS).entered();
+        let cancelled = match object_reader.read() {
+            Ok(()) => false,
+            Err(DxfError::Cancelled) => true,
+            Err(e) => return Err(e),
+        };
+        self.progress = std::mem::take(&mut object_reader.progress);
+        #[cfg(feature = "tracing")]
+        drop(objects_span);
+        let objects_read = object_reader.templates.len();
+        self.progress.set_phase(ReadPhase::Building, 95.0);
         // Step 5: Build the document.
         let mut builder = DwgDocumentBuilder::new(self.version);
         builder.header_handles = header_handles;
         builder.document.header = header_vars;
         builder.keep_unknown_entities = self.config.keep_unknown_entities;
+        builder.skipped_entities = std::mem::take(&mut object_reader.skipped_entities);
+        builder.observer = self.observer.clone();
 
         // Store summary info if available.
         // CadDocument doesn't embed summary info directly; it is available
         // externally via the reader. For now we skip storing it.
         let _ = summary_info;
         builder.document.classes = classes;
+        builder.document.preview = preview.filter(|p| !p.is_empty());
 
-        builder.add_templates(object_reader.templates);
+        if self.lazy_blocks {
+            let source = LazyBlockSource::from_reader(&mut object_reader, self.version, class_entries);
+            builder.lazy_blocks = Some(Arc::new(source));
+        }
+
+        builder.add_templates(std::mem::take(&mut object_reader.templates));
         builder.build_document();
+        builder.document.resolve_dim_style();
+        builder.document.resolve_raster_images();
+        builder.document.resolve_underlay_definitions();
 
         // Collect all notifications.
-        let doc = builder.document;
-        // Propagate notifications if the document supports them.
-        // For now we just drop them.
-        let _ = builder.notifications;
-        let _ = object_reader.notifications;
-        let _ = self.notifications;
+        let mut doc = builder.document;
+        doc.notifications.extend_announced(self.notifications);
+        doc.notifications.extend_announced(object_reader.notifications);
+        doc.notifications.extend_announced(builder.notifications);
+        if cancelled {
+            doc.notifications.set_observer(self.observer);
+            doc.notifications.push(
+                Notification::new(
+                    NotificationType::Warning,
+                    format!(
+                        "Read cancelled after {} of {} objects; document is partial",
+                        objects_read,
+                        object_reader_total
+                    ),
+                )
+                .with_code(NotificationCode::Cancelled),
+            );
+            doc.notifications.set_observer(None);
+        }
+        self.progress.set_phase(ReadPhase::Building, 100.0);
 
         Ok(doc)
     }
@@ -1208,6 +1349,8 @@ impl<R: Read + Seek> DwgReader<R> {
 
     /// Read the header section (system variables + handles).
     fn read_header(&mut self) -> Result<(crate::document::HeaderVariables, DwgHeaderHandlesCollection)> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("section", name = section_names::HEADER).entered();
         let data = self.get_section_stream(section_names::HEADER)?;
         let acad_maint_ver = self.file_header.maintenance_version() as i32;
         let reader = DwgHeaderReader::new(self.version, data);
@@ -1216,6 +1359,8 @@ impl<R: Read + Seek> DwgReader<R> {
 
     /// Read the classes section.
     fn read_classes(&mut self) -> Result<DxfClassCollection> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("section", name = section_names::CLASSES).entered();
         let data = self.get_section_stream(section_names::CLASSES)?;
         let fh_version = self.file_header.version();
         let fh_maint = self.file_header.maintenance_version();
@@ -1224,7 +1369,9 @@ impl<R: Read + Seek> DwgReader<R> {
     }
 
     /// Read the handles (object map) section.
-    fn read_handles(&mut self) -> Result<HashMap<u64, i64>> {
+    fn read_handles(&mut self) -> Result<HandleOffsetTable> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("section", name = section_names::HANDLES).entered();
         let data = self.get_section_stream(section_names::HANDLES)?;
         let reader = DwgHandleReader::new(self.version, data);
         reader.read()
@@ -1289,8 +1436,9 @@ impl<R: Read + Seek> DwgReader<R> {
     // ------------------------------------------------------------------
 
     #[allow(dead_code)]
-    fn notify(&mut self, message: &str, ntype: NotificationType) {
-        self.notifications.push(Notification::new(ntype, message));
+    fn notify(&mut self, notification: Notification) {
+        announce(&notification, self.observer.as_ref());
+        self.notifications.push(notification);
     }
 }
 
@@ -1324,4 +1472,121 @@ mod tests {
         assert!(!config.failsafe);
         assert!(!config.keep_unknown_entities);
     }
+
+    fn sample_dwg() -> Vec<u8> {
+        use crate::entities::{EntityType, Line};
+        use crate::io::dwg::DwgWriter;
+
+        let mut doc = CadDocument::new();
+        for i in 0..50 {
+            let line = Line::from_coords(0.0, 0.0, 0.0, i as f64, 1.0, 0.0);
+            doc.add_entity(EntityType::Line(line)).unwrap();
+        }
+        DwgWriter::write(&doc).unwrap()
+    }
+
+    #[test]
+    fn test_progress_reports_phases() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let sink = seen.clone();
+        DwgReader::from_reader(Cursor::new(sample_dwg()))
+            .unwrap()
+            .with_progress(move |p| sink.borrow_mut().push(*p))
+            .read()
+            .unwrap();
+
+        let seen = seen.borrow();
+        assert_eq!(seen.first().unwrap().phase, ReadPhase::FileHeader);
+        assert!(seen.iter().any(|p| p.phase == ReadPhase::Objects && p.objects_read > 0));
+        assert!(seen.windows(2).all(|w| w[0].percent <= w[1].percent));
+        assert_eq!(seen.last().unwrap().percent, 100.0);
+    }
+
+    #[test]
+    fn test_cancellation_returns_partial_document() {
+        let token = CancellationToken::new();
+        token.cancel();
+        let doc = DwgReader::from_reader(Cursor::new(sample_dwg()))
+            .unwrap()
+            .with_cancellation(token)
+            .read()
+            .unwrap();
+
+        assert!(doc.entity_count() < 50);
+        assert!(doc
+            .notifications
+            .iter()
+            .any(|n| n.notification_type == NotificationType::Warning && n.message.contains("cancelled")));
+    }
+
+    /// DWG file of an insert of block "Bolt", made of three circles
+    fn bolt_drawing() -> Vec<u8> {
+        use crate::entities::{Circle, EntityType, Insert};
+        use crate::io::dwg::DwgWriter;
+        use crate::tables::{BlockRecord, TableEntry};
+        use crate::types::Vector3;
+
+        let mut doc = CadDocument::new();
+        let mut block = BlockRecord::new("Bolt");
+        block.set_handle(doc.allocate_handle());
+        block.block_entity_handle = doc.allocate_handle();
+        block.block_end_handle = doc.allocate_handle();
+        for r in 1..=3 {
+            let mut circle = Circle::from_coords(0.0, 0.0, 0.0, r as f64);
+            circle.common.handle = doc.allocate_handle();
+            block.entities.push(EntityType::Circle(circle));
+        }
+        doc.block_records.add(block).unwrap();
+        doc.add_entity(EntityType::Insert(Insert::new("Bolt", Vector3::ZERO))).unwrap();
+        DwgWriter::write(&doc).unwrap()
+    }
+
+    #[test]
+    fn test_lazy_blocks_decode_on_first_access() {
+        use crate::entities::EntityType;
+        use crate::io::dwg::DwgWriter;
+
+        let bytes = bolt_drawing();
+
+        let mut lazy = DwgReader::from_reader(Cursor::new(bytes.clone()))
+            .unwrap()
+            .with_lazy_blocks(true)
+            .read()
+            .unwrap();
+        assert!(lazy.block_records.get("*Model_Space").unwrap().is_loaded());
+        assert!(lazy.entities().any(|e| matches!(e, EntityType::Insert(_))));
+
+        let bolt = lazy.block_records.get_mut("Bolt").unwrap();
+        assert!(!bolt.is_loaded());
+        assert!(bolt.entities.is_empty());
+        let entities = bolt.load_entities().unwrap();
+        assert_eq!(entities.len(), 3);
+        assert!(entities.iter().all(|e| matches!(e, EntityType::Circle(_))));
+
+        // Writers decode pending blocks themselves
+        let mut lazy = DwgReader::from_reader(Cursor::new(bytes)).unwrap().with_lazy_blocks(true).read().unwrap();
+        assert!(lazy.has_pending_blocks());
+        let rewritten = DwgWriter::write(&lazy).unwrap();
+        let reread = DwgReader::from_reader(Cursor::new(rewritten)).unwrap().read().unwrap();
+        assert_eq!(reread.block_records.get("Bolt").unwrap().entities.len(), 3);
+        lazy.load_blocks().unwrap();
+        assert!(!lazy.has_pending_blocks());
+    }
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serialize_decodes_lazy_blocks() {
+        let lazy = DwgReader::from_reader(Cursor::new(bolt_drawing())).unwrap().with_lazy_blocks(true).read().unwrap();
+        assert!(lazy.has_pending_blocks());
+
+        let json = serde_json::to_string(&lazy).unwrap();
+        assert!(lazy.has_pending_blocks());
+        let back: CadDocument = serde_json::from_str(&json).unwrap();
+        let eager = DwgReader::from_reader(Cursor::new(bolt_drawing())).unwrap().read().unwrap();
+        let bolt = |doc: &CadDocument| serde_json::to_value(&doc.block_records.get("Bolt").unwrap().entities).unwrap();
+        assert_eq!(back.block_records.get("Bolt").unwrap().entities.len(), 3);
+        assert_eq!(bolt(&back), bolt(&eager));
+    }
 }
@@ -8,9 +8,8 @@
 //!
 //! Mirrors ACadSharp's `DwgHandleReader`.
 
-use std::collections::HashMap;
-
 use crate::error::Result;
+use crate::io::dwg::handle_offsets::HandleOffsetTable;
 use crate::io::dwg::reader::stream_reader::IDwgStreamReader;
 use crate::io::dwg::reader::stream_reader_base::get_stream_handler;
 use crate::types::DxfVersion;
@@ -38,10 +37,10 @@ impl DwgHandleReader {
 
     /// Read the handle-to-offset map.
     ///
-    /// Returns a `HashMap<u64, i64>` mapping each object's handle
+    /// Returns a [`HandleOffsetTable`] mapping each object's handle
     /// to its signed byte offset in the file.
-    pub fn read(&self) -> Result<HashMap<u64, i64>> {
-        let mut object_map: HashMap<u64, i64> = HashMap::new();
+    pub fn read(&self) -> Result<HandleOffsetTable> {
+        let mut entries: Vec<(u64, i64)> = Vec::new();
         let mut reader = get_stream_handler(self.version, self.data.clone());
 
         // Repeat until section size == 2 (the last empty section, except CRC):
@@ -80,7 +79,7 @@ impl DwgHandleReader {
                 last_loc += reader.read_signed_modular_char()?;
 
                 if offset > 0 {
-                    object_map.insert(last_handle, last_loc);
+                    entries.push((last_handle, last_loc));
                 }
                 // else: 0 offset, wrong reference — skip
             }
@@ -89,7 +88,7 @@ impl DwgHandleReader {
             let _crc = ((reader.read_byte()? as u32) << 8) + reader.read_byte()? as u32;
         }
 
-        Ok(object_map)
+        Ok(HandleOffsetTable::from_unsorted(entries))
     }
 }
 
@@ -111,4 +110,14 @@ mod tests {
         let map = reader.read().unwrap();
         assert!(map.is_empty());
     }
+
+    #[test]
+    fn test_handle_reader_roundtrip() {
+        use crate::io::dwg::writer::DwgHandleWriter;
+
+        let table: HandleOffsetTable = (1..2000u64).map(|h| (h * 3, (h * 40) as i64)).collect();
+        let data = DwgHandleWriter::new(DxfVersion::AC1018).write(&table, 0).unwrap();
+        let map = DwgHandleReader::new(DxfVersion::AC1018, data).read().unwrap();
+        assert_eq!(map, table);
+    }
 }
@@ -0,0 +1,87 @@
+//! On-demand decoding of block record entities.
+//!
+//! With [`DwgReader::with_lazy_blocks`](super::DwgReader::with_lazy_blocks)
+//! the object reader skips the entities of every block record except model
+//! and paper space.  The raw object data is kept in a [`LazyBlockSource`]
+//! shared by the skipped records, and each record decodes its own entities
+//! the first time [`BlockRecord::load_entities`](crate::tables::BlockRecord::load_entities)
+//! is called.
+
+use std::collections::{HashSet, VecDeque};
+use std::sync::Arc;
+
+use crate::classes::DxfClass;
+use crate::entities::EntityType;
+use crate::error::Result;
+use crate::io::EntityFilter;
+use crate::io::dwg::builder::DwgDocumentBuilder;
+use crate::io::dwg::custom::DwgCustomClasses;
+use crate::io::dwg::handle_offsets::HandleOffsetTable;
+use crate::types::DxfVersion;
+
+use super::object_reader::templates::CadBlockRecordTemplateData;
+use super::object_reader::DwgObjectReader;
+
+/// Raw object records retained for decoding skipped block entities.
+pub(crate) struct LazyBlockSource {
+    version: DxfVersion,
+    data: Arc<[u8]>,
+    handle_map: Arc<HandleOffsetTable>,
+    classes: Vec<DxfClass>,
+    /// Objects decoded by the initial read; not decoded again.
+    read_handles: Arc<HashSet<u64>>,
+    /// Block records whose entities were skipped.
+    deferred: HashSet<u64>,
+    failsafe: bool,
+    entity_filter: EntityFilter,
+    custom_classes: DwgCustomClasses,
+}
+
+impl LazyBlockSource {
+    /// Retain the data of a finished object reader.
+    pub(crate) fn from_reader(reader: &mut DwgObjectReader, version: DxfVersion, classes: Vec<DxfClass>) -> Self {
+        Self {
+            version,
+            data: reader.data(),
+            handle_map: reader.handle_map(),
+            classes,
+            read_handles: Arc::new(reader.read_handles()),
+            deferred: std::mem::take(&mut reader.deferred_blocks),
+            failsafe: reader.failsafe,
+            entity_filter: reader.entity_filter.clone(),
+            custom_classes: reader.custom_classes.clone(),
+        }
+    }
+
+    /// Whether the entities of a block record were skipped.
+    pub(crate) fn is_deferred(&self, block_record: u64) -> bool {
+        self.deferred.contains(&block_record)
+    }
+
+    /// Decode the entities of a skipped block record.
+    pub(crate) fn load(&self, block: &CadBlockRecordTemplateData) -> Result<Vec<EntityType>> {
+        let mut handles: VecDeque<u64> = block.owned_object_handles.iter().copied().collect();
+        if handles.is_empty() && block.first_entity_handle != 0 {
+            // R13–R2000: the rest of the chain is reached through next-entity links
+            handles.push_back(block.first_entity_handle);
+        }
+
+        let mut reader = DwgObjectReader::resume(
+            self.version,
+            Arc::clone(&self.data),
+            handles,
+            Arc::clone(&self.handle_map),
+            &self.classes,
+            Arc::clone(&self.read_handles),
+        );
+        reader.failsafe = self.failsafe;
+        reader.entity_filter = self.entity_filter.clone();
+        reader.custom_classes = self.custom_classes.clone();
+        reader.read()?;
+
+        let mut builder = DwgDocumentBuilder::new(self.version);
+        builder.skipped_entities = std::mem::take(&mut reader.skipped_entities);
+        builder.add_templates(std::mem::take(&mut reader.templates));
+        Ok(builder.build_block_entities(block))
+    }
+}
@@ -24,6 +24,7 @@ pub mod classes_reader;
 pub mod dwg_reader;
 pub mod handle_reader;
 pub mod header_reader;
+pub(crate) mod lazy_blocks;
 pub mod object_reader;
 pub mod preview_reader;
 pub mod summary_info_reader;
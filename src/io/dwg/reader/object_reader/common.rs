@@ -442,6 +442,7 @@ impl DwgObjectReader {
         let entity = crate::entities::UnknownEntity {
             common: entity_common,
             dxf_name: String::new(),
+            raw_pairs: Vec::new(),
         };
 
         Ok(super::templates::CadTemplate::Entity {
@@ -475,6 +476,7 @@ impl DwgObjectReader {
         let entity = crate::entities::UnknownEntity {
             common: entity_common,
             dxf_name: "AcDbProxyEntity".to_string(),
+            raw_pairs: Vec::new(),
         };
 
         Ok(super::templates::CadTemplate::Entity {
@@ -503,7 +505,7 @@ impl DwgObjectReader {
     ) -> Result<super::templates::CadTemplate> {
         let common_tmpl = self.read_common_non_entity_data(streams)?;
 
-        Ok(super::templates::CadTemplate::GenericObject {
+        Ok(super::templates::CadTemplate::PlaceHolderObj {
             common: common_tmpl,
         })
     }
@@ -22,15 +22,20 @@ pub mod read_tables;
 pub mod templates;
 
 use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
 
 use crate::classes::DxfClass;
 use crate::error::Result;
+use crate::io::dwg::custom::{DwgClassReader, DwgCustomClasses, DwgObjectHeader};
+use crate::io::dwg::handle_offsets::HandleOffsetTable;
 use crate::io::dwg::object_type::DwgObjectType;
 use crate::io::dwg::reader::stream_reader::IDwgStreamReader;
 use crate::io::dwg::reader::stream_reader_base::get_stream_handler;
 use crate::io::dwg::section_io::SectionIO;
-use crate::notification::{Notification, NotificationType};
-use crate::types::DxfVersion;
+use crate::io::progress::ProgressReporter;
+use crate::io::EntityFilter;
+use crate::notification::{announce, Notification, NotificationCode, NotificationObserver, NotificationType};
+use crate::types::{DxfVersion, Handle};
 
 use self::templates::CadTemplate;
 
@@ -42,10 +47,10 @@ pub struct DwgObjectReader {
     version: DxfVersion,
     /// Version flags (pre-computed).
     sio: SectionIO,
-    /// Raw section data (`AcDb:AcDbObjects`).
-    data: Vec<u8>,
+    /// Raw section data (`AcDb:AcDbObjects`), shared by every sub-reader.
+    data: Arc<[u8]>,
     /// Object map: handle → byte offset within `data`.
-    handle_map: HashMap<u64, i64>,
+    handle_map: Arc<HandleOffsetTable>,
     /// DXF class map: class_number → class info.
     class_map: HashMap<i16, DxfClass>,
 
@@ -54,7 +59,16 @@ pub struct DwgObjectReader {
     /// Handles already read (prevents duplicates / infinite loops).
     read_objects: HashSet<u64>,
     /// Handles for which a template already exists (from builder).
-    existing_templates: HashSet<u64>,
+    existing_templates: Arc<HashSet<u64>>,
+
+    /// Block records whose entities are read.  When set, the entities of
+    /// every other block record are skipped and the record is listed in
+    /// `deferred_blocks`, for lazy loading.
+    pub(crate) eager_block_records: Option<HashSet<u64>>,
+    /// Block records whose entities were skipped.
+    pub(crate) deferred_blocks: HashSet<u64>,
+    /// Entity handles of the skipped block records; never enqueued.
+    deferred_entities: HashSet<u64>,
 
     /// All templates produced by this reader.
     pub templates: Vec<CadTemplate>,
@@ -62,14 +76,31 @@ pub struct DwgObjectReader {
     /// Diagnostic / warning notifications.
     pub notifications: Vec<Notification>,
 
+    /// Called with each notification as it is recorded.
+    pub observer: Option<NotificationObserver>,
+
     /// Whether to continue on error instead of aborting.
     pub failsafe: bool,
 
+    /// Entity types to decode; other entities are skipped.
+    pub(crate) entity_filter: EntityFilter,
+    /// Entities skipped for the entity filter, with their next-entity
+    /// links (R13–R2000; 0 in later versions).
+    pub(crate) skipped_entities: HashMap<u64, u64>,
+
+    /// Readers of classes the reader does not decode itself.
+    pub(crate) custom_classes: DwgCustomClasses,
+
+    /// Progress callback and cancellation token, checked between objects.
+    pub(crate) progress: ProgressReporter,
+
     // --- Per-object state (set in get_entity_type, used by read methods) ---
     /// Bit position of the start of the current object data (after MS/MC header).
     object_initial_pos: i64,
     /// Size of the current object in bytes (from MS header, excluding CRC).
     object_size: u32,
+    /// Handle and section offset of the object being read, for notifications.
+    current_object: Option<(u64, i64)>,
 }
 
 impl DwgObjectReader {
@@ -85,7 +116,7 @@ impl DwgObjectReader {
         version: DxfVersion,
         data: Vec<u8>,
         handles: VecDeque<u64>,
-        handle_map: HashMap<u64, i64>,
+        handle_map: HandleOffsetTable,
         classes: &[DxfClass],
     ) -> Self {
         let class_map: HashMap<i16, DxfClass> = classes
@@ -96,20 +127,63 @@ impl DwgObjectReader {
         Self {
             sio: SectionIO::new(version),
             version,
-            data,
-            handle_map,
+            data: data.into(),
+            handle_map: Arc::new(handle_map),
             class_map,
             handles,
             read_objects: HashSet::new(),
-            existing_templates: HashSet::new(),
+            existing_templates: Arc::new(HashSet::new()),
+            eager_block_records: None,
+            deferred_blocks: HashSet::new(),
+            deferred_entities: HashSet::new(),
             templates: Vec::new(),
             notifications: Vec::new(),
+            observer: None,
             failsafe: true,
+            entity_filter: EntityFilter::All,
+            skipped_entities: HashMap::new(),
+            custom_classes: DwgCustomClasses::default(),
+            progress: ProgressReporter::default(),
             object_initial_pos: 0,
             object_size: 0,
+            current_object: None,
         }
     }
 
+    /// Create a reader over data already scanned by another reader, for
+    /// decoding objects it skipped.  Objects in `existing` are not read
+    /// again.
+    pub(crate) fn resume(
+        version: DxfVersion,
+        data: Arc<[u8]>,
+        handles: VecDeque<u64>,
+        handle_map: Arc<HandleOffsetTable>,
+        classes: &[DxfClass],
+        existing: Arc<HashSet<u64>>,
+    ) -> Self {
+        let mut reader = Self::new(version, Vec::new(), handles, HandleOffsetTable::new(), classes);
+        reader.data = data;
+        reader.handle_map = handle_map;
+        reader.existing_templates = existing;
+        reader
+    }
+
+    /// Object map shared with readers created by [`Self::resume`].
+    pub(crate) fn handle_map(&self) -> Arc<HandleOffsetTable> {
+        Arc::clone(&self.handle_map)
+    }
+
+    /// Handles read so far, together with those this reader skipped
+    /// because they already existed.
+    pub(crate) fn read_handles(&self) -> HashSet<u64> {
+        self.read_objects.union(&self.existing_templates).copied().collect()
+    }
+
+    /// Raw section data, shared with readers created by [`Self::resume`].
+    pub(crate) fn data(&self) -> Arc<[u8]> {
+        Arc::clone(&self.data)
+    }
+
     /// Read all entities, table entries, and objects from the section.
     ///
     /// Processes the handle queue until empty, building templates for each object.
@@ -123,12 +197,13 @@ impl DwgObjectReader {
             }
 
             // Look up the file offset.
-            let offset = match self.handle_map.get(&handle) {
-                Some(&off) => off,
+            let offset = match self.handle_map.get(handle) {
+                Some(off) => off,
                 None => {
                     continue;
                 }
             };
+            self.current_object = Some((handle, offset));
 
             // Get the object type and set up sub-streams.
             let (obj_type, raw_type, streams) = match self.get_entity_type(offset) {
@@ -140,14 +215,40 @@ impl DwgObjectReader {
                     self.notify(
                         &format!("Failed to get object type for handle {handle:#X}: {e}"),
                         NotificationType::Error,
+                        NotificationCode::ObjectFailed,
                     );
                     continue;
                 }
             };
 
+            #[cfg(feature = "tracing")]
+            let _span = tracing::trace_span!("object", handle, kind = ?obj_type).entered();
+
             // Mark as read before dispatching to avoid infinite loops.
             self.read_objects.insert(handle);
 
+            if !self.entity_filter.is_all() && self.is_filtered_out(obj_type, raw_type) {
+                // Before R2004 the entities of a block are chained through
+                // their next-entity links, which a skipped entity passes on
+                let mut next = 0;
+                if self.version < DxfVersion::AC1018 {
+                    if let Ok(Some(template)) = self.read_object(obj_type, raw_type, &mut streams.unwrap_or_default()) {
+                        next = template.entity_data().map_or(0, |data| data.next_entity);
+                    }
+                    if next != 0 && !self.read_objects.contains(&next) {
+                        self.handles.push_back(next);
+                    }
+                }
+                self.skipped_entities.insert(handle, next);
+                continue;
+            }
+
+            if self.progress.is_active() {
+                let percent = self.progress.percent_of_objects(self.read_objects.len());
+                self.progress.object_read(percent);
+                self.progress.check_cancelled()?;
+            }
+
             // Read the object.
             let template = match self.read_object(obj_type, raw_type, &mut streams.unwrap_or_default()) {
                 Ok(Some(t)) => t,
@@ -155,6 +256,7 @@ impl DwgObjectReader {
                     self.notify(
                         &format!("Object type not implemented: {obj_type:?}"),
                         NotificationType::Warning,
+                        NotificationCode::ObjectSkipped,
                     );
                     continue;
                 }
@@ -165,6 +267,7 @@ impl DwgObjectReader {
                     self.notify(
                         &format!("Failed to read object {obj_type:?} handle {handle:#X}: {e}"),
                         NotificationType::Error,
+                        NotificationCode::ObjectFailed,
                     );
                     continue;
                 }
@@ -180,11 +283,23 @@ impl DwgObjectReader {
     /// Extract all handle references from a template and enqueue any
     /// that haven't been seen yet.
     fn enqueue_template_handles(&mut self, template: &CadTemplate) {
+        if let (Some(eager), CadTemplate::BlockHeader { common, block_data }) =
+            (&self.eager_block_records, template)
+        {
+            if !eager.contains(&common.handle) {
+                self.deferred_blocks.insert(common.handle);
+                self.deferred_entities.insert(block_data.first_entity_handle);
+                self.deferred_entities.insert(block_data.last_entity_handle);
+                self.deferred_entities.extend(&block_data.owned_object_handles);
+            }
+        }
+
         let handles = template.all_handles();
         for h in handles {
             if h != 0
                 && !self.read_objects.contains(&h)
                 && !self.existing_templates.contains(&h)
+                && !self.deferred_entities.contains(&h)
             {
                 self.handles.push_back(h);
             }
@@ -279,9 +394,9 @@ impl DwgObjectReader {
         Ok((obj_type, raw_type, streams))
     }
 
-    /// Create a fresh stream reader over the raw data (clones `self.data`).
+    /// Create a fresh stream reader over the raw data (shares `self.data`).
     fn make_reader(&self) -> Box<dyn IDwgStreamReader> {
-        Box::new(get_stream_handler(self.version, self.data.clone()))
+        Box::new(get_stream_handler(self.version, Arc::clone(&self.data)))
     }
 
     // -----------------------------------------------------------------------
@@ -426,6 +541,7 @@ impl DwgObjectReader {
                 self.notify(
                     &format!("Unlisted object type {obj_type:?} read as UnknownEntity"),
                     NotificationType::Warning,
+                    NotificationCode::UnknownEntity,
                 );
                 Some(t)
             }
@@ -434,6 +550,7 @@ impl DwgObjectReader {
                 self.notify(
                     &format!("Unlisted object type {obj_type:?} read as GenericObject"),
                     NotificationType::Warning,
+                    NotificationCode::UnknownObject,
                 );
                 Some(t)
             }
@@ -466,6 +583,7 @@ impl DwgObjectReader {
                         "Unknown class number {raw_type} — no DxfClass entry found"
                     ),
                     NotificationType::Warning,
+                    NotificationCode::ObjectSkipped,
                 );
                 return Ok(None);
             }
@@ -501,6 +619,7 @@ impl DwgObjectReader {
             "IMAGEDEF_REACTOR" => Some(self.read_image_definition_reactor(streams)?),
             "RASTERVARIABLES" => Some(self.read_raster_variables(streams)?),
             "SCALE" => Some(self.read_scale(streams)?),
+            "DIMASSOC" => Some(self.read_dim_assoc(streams)?),
             "SORTENTSTABLE" => Some(self.read_sort_entities_table(streams)?),
             "MLEADERSTYLE" => Some(self.read_mleader_style(streams)?),
             "VISUALSTYLE" => Some(self.read_visual_style(streams)?),
@@ -508,7 +627,12 @@ impl DwgObjectReader {
             "PLOTSETTINGS" => Some(self.read_plot_settings(streams)?),
             "TABLESTYLE" => Some(self.read_table_style(streams)?),
             "PDFDEFINITION" | "DWFDEFINITION" | "DGNDEFINITION" => {
-                Some(self.read_pdf_definition(streams)?)
+                let utype = match dxf_name.as_str() {
+                    "DWFDEFINITION" => crate::entities::underlay::UnderlayType::Dwf,
+                    "DGNDEFINITION" => crate::entities::underlay::UnderlayType::Dgn,
+                    _ => crate::entities::underlay::UnderlayType::Pdf,
+                };
+                Some(self.read_underlay_definition(streams, utype)?)
             }
             "GEODATA" => Some(self.read_geodata(streams)?),
             "ACAD_EVALUATION_GRAPH" => Some(self.read_evaluation_graph(streams)?),
@@ -518,10 +642,13 @@ impl DwgObjectReader {
 
             // ----- Unknown / unimplemented -----
             _ => {
-                if is_entity {
+                if let Some(reader) = self.custom_classes.reader(&dxf_name) {
+                    Some(self.read_custom_class(&class, reader.as_ref(), streams)?)
+                } else if is_entity {
                     self.notify(
                         &format!("Unlisted entity type '{}' read as UnknownEntity", class.dxf_name),
                         NotificationType::Warning,
+                        NotificationCode::UnknownEntity,
                     );
                     Some(self.read_unknown_entity(streams)?)
                 } else {
@@ -531,6 +658,7 @@ impl DwgObjectReader {
                             class.dxf_name
                         ),
                         NotificationType::Warning,
+                        NotificationCode::UnknownObject,
                     );
                     Some(self.read_unknown_non_graphical_object(streams)?)
                 }
@@ -540,15 +668,72 @@ impl DwgObjectReader {
         Ok(result)
     }
 
+    /// Read an object of a class with a custom reader: the common data,
+    /// then the class data by the reader.  Objects the reader declines are
+    /// read as unknown entities or generic objects.
+    fn read_custom_class(
+        &mut self,
+        class: &DxfClass,
+        reader: &DwgClassReader,
+        streams: &mut StreamSet,
+    ) -> Result<CadTemplate> {
+        let header = if class.is_an_entity {
+            let (common, entity_data, entity) = self.read_common_entity_data(streams)?;
+            DwgObjectHeader { common, entity: Some((entity_data, entity)) }
+        } else {
+            DwgObjectHeader { common: self.read_common_non_entity_data(streams)?, entity: None }
+        };
+
+        if let Some(template) = reader(class, streams, &header)? {
+            return Ok(template);
+        }
+        self.notify(
+            &format!("Custom reader declined class '{}'", class.dxf_name),
+            NotificationType::Warning,
+            if class.is_an_entity { NotificationCode::UnknownEntity } else { NotificationCode::UnknownObject },
+        );
+        Ok(if class.is_an_entity {
+            header.entity_template(crate::entities::EntityType::Unknown(crate::entities::UnknownEntity {
+                common: crate::entities::EntityCommon::new(),
+                dxf_name: String::new(),
+                raw_pairs: Vec::new(),
+            }))
+        } else {
+            CadTemplate::GenericObject { common: header.common }
+        })
+    }
+
     // -----------------------------------------------------------------------
     // Helpers
     // -----------------------------------------------------------------------
 
-    fn notify(&mut self, message: &str, ntype: NotificationType) {
-        self.notifications.push(Notification {
-            message: message.to_string(),
-            notification_type: ntype,
-        });
+    /// Whether the entity filter rejects an object of this type; objects
+    /// that are not entities are always read.
+    fn is_filtered_out(&self, obj_type: DwgObjectType, raw_type: i16) -> bool {
+        let class_name;
+        let name = match obj_type {
+            DwgObjectType::Unlisted => match self.class_map.get(&raw_type) {
+                Some(class) if class.is_an_entity => {
+                    class_name = class.dxf_name.to_uppercase();
+                    class_name.as_str()
+                }
+                _ => return false,
+            },
+            _ => match obj_type.entity_name() {
+                Some(name) => name,
+                None => return false,
+            },
+        };
+        !self.entity_filter.accepts(name)
+    }
+
+    fn notify(&mut self, message: &str, ntype: NotificationType, code: NotificationCode) {
+        let mut notification = Notification::new(ntype, message).with_code(code);
+        if let Some((handle, offset)) = self.current_object {
+            notification = notification.with_handle(Handle::new(handle)).with_offset(offset as u64);
+        }
+        announce(&notification, self.observer.as_ref());
+        self.notifications.push(notification);
     }
 
     /// Update the text reader and the handle reader at the end-of-object
@@ -5,7 +5,7 @@
 
 use crate::entities::*;
 use crate::error::Result;
-use crate::types::{Color, Handle, Vector2, Vector3};
+use crate::types::{Color, Handle, Transparency, Vector2, Vector3};
 
 use super::templates::*;
 use super::{DwgObjectReader, ModelerGeoType, StreamSet};
@@ -43,7 +43,7 @@ impl DwgObjectReader {
             common: common_tmpl,
             entity_data: ent_tmpl,
             text_data: text_tmpl,
-            entity: EntityType::AttributeEntity(attrib),
+            entity: EntityType::AttributeEntity(Box::new(attrib)),
         })
     }
 
@@ -59,7 +59,7 @@ impl DwgObjectReader {
             common: common_tmpl,
             entity_data: ent_tmpl,
             text_data: text_tmpl,
-            entity: EntityType::AttributeDefinition(attdef),
+            entity: EntityType::AttributeDefinition(Box::new(attdef)),
         })
     }
 
@@ -167,22 +167,8 @@ impl DwgObjectReader {
         // Style handle.
         text_tmpl.style_handle = streams.handle_ref()?;
 
-        let h_align = match horizontal {
-            0 => TextHorizontalAlignment::Left,
-            1 => TextHorizontalAlignment::Center,
-            2 => TextHorizontalAlignment::Right,
-            3 => TextHorizontalAlignment::Aligned,
-            4 => TextHorizontalAlignment::Middle,
-            5 => TextHorizontalAlignment::Fit,
-            _ => TextHorizontalAlignment::Left,
-        };
-        let v_align = match vertical {
-            0 => TextVerticalAlignment::Baseline,
-            1 => TextVerticalAlignment::Bottom,
-            2 => TextVerticalAlignment::Middle,
-            3 => TextVerticalAlignment::Top,
-            _ => TextVerticalAlignment::Baseline,
-        };
+        let h_align = TextHorizontalAlignment::from_value(horizontal);
+        let v_align = TextVerticalAlignment::from_value(vertical);
 
         let text = Text {
             common,
@@ -258,8 +244,12 @@ impl DwgObjectReader {
             oblique_angle: text.oblique_angle,
             text_style: String::new(),
             text_generation_flags: 0,
-            horizontal_alignment: attribute_definition::HorizontalAlignment::Left,
-            vertical_alignment: attribute_definition::VerticalAlignment::Baseline,
+            horizontal_alignment: attribute_definition::HorizontalAlignment::from_value(
+                text.horizontal_alignment.to_value(),
+            ),
+            vertical_alignment: attribute_definition::VerticalAlignment::from_value(
+                text.vertical_alignment.to_value(),
+            ),
             flags,
             field_length,
             normal: text.normal,
@@ -332,8 +322,12 @@ impl DwgObjectReader {
             oblique_angle: text.oblique_angle,
             text_style: String::new(),
             text_generation_flags: 0,
-            horizontal_alignment: attribute_definition::HorizontalAlignment::Left,
-            vertical_alignment: attribute_definition::VerticalAlignment::Baseline,
+            horizontal_alignment: attribute_definition::HorizontalAlignment::from_value(
+                text.horizontal_alignment.to_value(),
+            ),
+            vertical_alignment: attribute_definition::VerticalAlignment::from_value(
+                text.vertical_alignment.to_value(),
+            ),
             flags,
             field_length,
             normal: text.normal,
@@ -579,6 +573,7 @@ impl DwgObjectReader {
         let tangent = streams.object_reader.read_bit_double()?;
 
         let vertex = Vertex2D {
+            handle: entity_common.handle,
             location: pt,
             flags: VertexFlags::from_bits(flags),
             start_width: start_width.abs(),
@@ -597,6 +592,7 @@ impl DwgObjectReader {
             entity: EntityType::Unknown(UnknownEntity {
                 common: entity_common,
                 dxf_name: "VERTEX_2D".to_string(),
+                raw_pairs: Vec::new(),
             }),
         })
     }
@@ -618,6 +614,7 @@ impl DwgObjectReader {
             entity: EntityType::Unknown(UnknownEntity {
                 common: entity_common,
                 dxf_name: "VERTEX_3D".to_string(),
+                raw_pairs: Vec::new(),
             }),
         })
     }
@@ -641,6 +638,7 @@ impl DwgObjectReader {
             entity: EntityType::Unknown(UnknownEntity {
                 common: entity_common,
                 dxf_name: "VERTEX_PFACE_FACE".to_string(),
+                raw_pairs: Vec::new(),
             }),
         })
     }
@@ -693,6 +691,7 @@ impl DwgObjectReader {
             thickness,
             normal,
             vertices: Vec::new(), // populated by builder
+            seqend_handle: (poly_tmpl.seqend_handle != 0).then_some(Handle::new(poly_tmpl.seqend_handle)),
         };
 
         Ok(CadTemplate::Polyline {
@@ -737,6 +736,7 @@ impl DwgObjectReader {
             flags: Polyline3DFlags::default(),
             smooth_type: polyline3d::SmoothSurfaceType::None,
             vertices: Vec::new(), // populated by builder
+            seqend_handle: (poly_tmpl.seqend_handle != 0).then_some(Handle::new(poly_tmpl.seqend_handle)),
             ..Default::default()
         };
 
@@ -1184,15 +1184,15 @@ impl DwgObjectReader {
         let _insert_rotation = streams.object_reader.read_bit_double()?;
 
         // R2000+: attachment point, line spacing style/factor.
-        let _attachment_point;
+        let attachment_point;
         let line_spacing_factor;
         if self.sio.r2000_plus {
-            _attachment_point = streams.object_reader.read_bit_short()?;
+            attachment_point = streams.object_reader.read_bit_short()?;
             let _ls_style = streams.object_reader.read_bit_short()?;
             line_spacing_factor = streams.object_reader.read_bit_double()?;
             let _actual_measurement = streams.object_reader.read_bit_double()?;
         } else {
-            _attachment_point = 5; // middle-center
+            attachment_point = 5; // middle-center
             line_spacing_factor = 1.0;
         }
 
@@ -1214,7 +1214,7 @@ impl DwgObjectReader {
             text_middle_point: Vector3::new(text_midpoint.x, text_midpoint.y, elevation),
             insertion_point: Vector3::ZERO,
             dimension_type: dimension::DimensionType::Linear,
-            attachment_point: dimension::AttachmentPointType::MiddleCenter,
+            attachment_point: dimension::AttachmentPointType::from_value(attachment_point),
             text: String::new(),
             user_text: if user_text.is_empty() {
                 None
@@ -1276,7 +1276,7 @@ impl DwgObjectReader {
             common: common_tmpl,
             entity_data: ent_tmpl,
             dim_data: dim_tmpl,
-            entity: EntityType::Dimension(Dimension::Ordinate(dim)),
+            entity: EntityType::Dimension(Box::new(Dimension::Ordinate(dim))),
         })
     }
 
@@ -1310,7 +1310,7 @@ impl DwgObjectReader {
             common: common_tmpl,
             entity_data: ent_tmpl,
             dim_data: dim_tmpl,
-            entity: EntityType::Dimension(Dimension::Linear(dim)),
+            entity: EntityType::Dimension(Box::new(Dimension::Linear(dim))),
         })
     }
 
@@ -1342,7 +1342,7 @@ impl DwgObjectReader {
             common: common_tmpl,
             entity_data: ent_tmpl,
             dim_data: dim_tmpl,
-            entity: EntityType::Dimension(Dimension::Aligned(dim)),
+            entity: EntityType::Dimension(Box::new(Dimension::Aligned(dim))),
         })
     }
 
@@ -1374,7 +1374,7 @@ impl DwgObjectReader {
             common: common_tmpl,
             entity_data: ent_tmpl,
             dim_data: dim_tmpl,
-            entity: EntityType::Dimension(Dimension::Angular3Pt(dim)),
+            entity: EntityType::Dimension(Box::new(Dimension::Angular3Pt(dim))),
         })
     }
 
@@ -1410,7 +1410,7 @@ impl DwgObjectReader {
             common: common_tmpl,
             entity_data: ent_tmpl,
             dim_data: dim_tmpl,
-            entity: EntityType::Dimension(Dimension::Angular2Ln(dim)),
+            entity: EntityType::Dimension(Box::new(Dimension::Angular2Ln(dim))),
         })
     }
 
@@ -1440,7 +1440,7 @@ impl DwgObjectReader {
             common: common_tmpl,
             entity_data: ent_tmpl,
             dim_data: dim_tmpl,
-            entity: EntityType::Dimension(Dimension::Radius(dim)),
+            entity: EntityType::Dimension(Box::new(Dimension::Radius(dim))),
         })
     }
 
@@ -1470,7 +1470,7 @@ impl DwgObjectReader {
             common: common_tmpl,
             entity_data: ent_tmpl,
             dim_data: dim_tmpl,
-            entity: EntityType::Dimension(Dimension::Diameter(dim)),
+            entity: EntityType::Dimension(Box::new(Dimension::Diameter(dim))),
         })
     }
 
@@ -1576,6 +1576,7 @@ impl DwgObjectReader {
             for _ in 0..frozen_count {
                 let h = streams.handle_ref()?;
                 vp_tmpl.frozen_layer_handles.push(h);
+                vp.frozen_layers.push(Handle::new(h));
             }
 
             // Clip boundary handle.
@@ -1603,7 +1604,7 @@ impl DwgObjectReader {
             common: common_tmpl,
             entity_data: ent_tmpl,
             viewport_data: vp_tmpl,
-            entity: EntityType::Viewport(vp),
+            entity: EntityType::Viewport(Box::new(vp)),
         })
     }
 
@@ -1657,12 +1658,13 @@ impl DwgObjectReader {
 
         if self.sio.r2013_plus {
             let flags1 = streams.object_reader.read_bit_long()?;
-            let _knot_param = streams.object_reader.read_bit_long()?;
+            let knot_param = streams.object_reader.read_bit_long()?;
             degree = streams.object_reader.read_bit_long()?;
-            // Determine scenario from flags.
-            scenario = if (flags1 & 1) != 0 { 2 } else { 1 };
+            // Fit point method, unless the knots are custom
+            scenario = if (flags1 & 1) != 0 && knot_param != 15 { 2 } else { 1 };
+            sp_flags.closed = (flags1 & 4) != 0;
         } else {
-            scenario = streams.object_reader.read_bit_short()? as i32;
+            scenario = streams.object_reader.read_bit_long()?;
             degree = streams.object_reader.read_bit_long()?;
         }
 
@@ -1678,15 +1680,16 @@ impl DwgObjectReader {
         let mut control_points = Vec::new();
         let mut fit_points = Vec::new();
         let mut weights = Vec::new();
-        let mut normal = Vector3::UNIT_Z;
+        let defaults = Spline::new();
+        let (mut knot_tolerance, mut control_point_tolerance, mut fit_tolerance) =
+            (defaults.knot_tolerance, defaults.control_point_tolerance, defaults.fit_tolerance);
+        let (mut start_tangent, mut end_tangent) = (Vector3::ZERO, Vector3::ZERO);
 
         if scenario == 2 {
             // Fit point data.
-            let fit_tol = streams.object_reader.read_bit_double()?;
-            let _ = fit_tol;
-            normal = streams.object_reader.read_3bit_double()?;
-            let _tangent_start = streams.object_reader.read_3bit_double()?;
-            let _tangent_end = streams.object_reader.read_3bit_double()?;
+            fit_tolerance = streams.object_reader.read_bit_double()?;
+            start_tangent = streams.object_reader.read_3bit_double()?;
+            end_tangent = streams.object_reader.read_3bit_double()?;
             num_fit_pts = streams.object_reader.read_bit_long()?;
             for _ in 0..num_fit_pts {
                 fit_points.push(streams.object_reader.read_3bit_double()?);
@@ -1696,8 +1699,8 @@ impl DwgObjectReader {
             sp_flags.rational = streams.object_reader.read_bit()?;
             sp_flags.closed = streams.object_reader.read_bit()?;
             sp_flags.periodic = streams.object_reader.read_bit()?;
-            let _knot_tol = streams.object_reader.read_bit_double()?;
-            let _ctrl_tol = streams.object_reader.read_bit_double()?;
+            knot_tolerance = streams.object_reader.read_bit_double()?;
+            control_point_tolerance = streams.object_reader.read_bit_double()?;
             num_knots = streams.object_reader.read_bit_long()?;
             num_ctrl_pts = streams.object_reader.read_bit_long()?;
             weight_present = streams.object_reader.read_bit()?;
@@ -1721,13 +1724,18 @@ impl DwgObjectReader {
             control_points,
             weights,
             fit_points,
-            normal,
+            normal: Vector3::UNIT_Z,
+            knot_tolerance,
+            control_point_tolerance,
+            fit_tolerance,
+            start_tangent,
+            end_tangent,
         };
 
         Ok(CadTemplate::Entity {
             common: common_tmpl,
             entity_data: ent_tmpl,
-            entity: EntityType::Spline(spline),
+            entity: EntityType::Spline(Box::new(spline)),
         })
     }
 
@@ -1932,11 +1940,30 @@ impl DwgObjectReader {
         // Line spacing (R2000+).
         let line_spacing = if self.sio.r2000_plus {
             let _ls_style = streams.object_reader.read_bit_short()?;
-            streams.object_reader.read_bit_double()?
+            let factor = streams.object_reader.read_bit_double()?;
+            let _unknown = streams.object_reader.read_bit()?;
+            factor
         } else {
             1.0
         };
 
+        // Background fill (R2004+).
+        let mut background_fill = BackgroundFillFlags::NONE;
+        let mut background_scale = 1.5;
+        let mut background_color = Color::ByBlock;
+        let mut background_transparency = Transparency::OPAQUE;
+        if self.sio.r2004_plus {
+            background_fill = BackgroundFillFlags::from_bits_retain(streams.object_reader.read_bit_long()?);
+            if background_fill.contains(BackgroundFillFlags::USE_BACKGROUND_FILL)
+                || (self.sio.r2018_plus && background_fill.contains(BackgroundFillFlags::TEXT_FRAME))
+            {
+                background_scale = streams.object_reader.read_bit_double()?;
+                background_color = streams.object_reader.read_cm_color()?;
+                background_transparency =
+                    Transparency::from_alpha_value(streams.object_reader.read_bit_long()? as u32);
+            }
+        }
+
         // Rotation angle.
         let rotation = direction.y.atan2(direction.x);
 
@@ -1944,6 +1971,34 @@ impl DwgObjectReader {
         let style_handle = streams.handle_ref()?;
         let text_tmpl = CadTextEntityTemplateData { style_handle };
 
+        // Columns (R2018+), after a copy of the text geometry
+        let mut columns = MTextColumns::default();
+        if self.sio.r2018_plus && streams.object_reader.read_bit()? {
+            let _version = streams.object_reader.read_bit_short()?;
+            let _default_flag = streams.object_reader.read_bit()?;
+            let _app_handle = streams.handle_ref()?;
+            let _attachment = streams.object_reader.read_bit_long()?;
+            let _direction = streams.object_reader.read_3bit_double()?;
+            let _insertion = streams.object_reader.read_3bit_double()?;
+            for _ in 0..4 {
+                // Rectangle width and height, extents width and height
+                streams.object_reader.read_bit_double()?;
+            }
+            columns.column_type = ColumnType::from_value(streams.object_reader.read_bit_short()?);
+            if columns.column_type != ColumnType::NoColumns {
+                columns.count = streams.object_reader.read_bit_long()?;
+                columns.width = streams.object_reader.read_bit_double()?;
+                columns.gutter = streams.object_reader.read_bit_double()?;
+                columns.auto_height = streams.object_reader.read_bit()?;
+                columns.flow_reversed = streams.object_reader.read_bit()?;
+                if !columns.auto_height && columns.column_type == ColumnType::Dynamic {
+                    for _ in 0..columns.count.max(0) {
+                        columns.heights.push(streams.object_reader.read_bit_double()?);
+                    }
+                }
+            }
+        }
+
         let attach_pt = match attachment {
             1 => mtext::AttachmentPoint::TopLeft,
             2 => mtext::AttachmentPoint::TopCenter,
@@ -1977,13 +2032,18 @@ impl DwgObjectReader {
             drawing_direction: draw_dir,
             line_spacing_factor: line_spacing,
             normal,
+            background_fill,
+            background_color,
+            background_scale,
+            background_transparency,
+            columns,
         };
 
         Ok(CadTemplate::TextEntity {
             common: common_tmpl,
             entity_data: ent_tmpl,
             text_data: text_tmpl,
-            entity: EntityType::MText(mtext),
+            entity: EntityType::MText(Box::new(mtext)),
         })
     }
 
@@ -2004,45 +2064,48 @@ impl DwgObjectReader {
             vertices.push(streams.object_reader.read_3bit_double()?);
         }
 
+        // Origin of the leader plane (the first vertex).
+        let _origin = streams.object_reader.read_3bit_double()?;
         let normal = streams.object_reader.read_3bit_double()?;
         let horizontal_direction = streams.object_reader.read_3bit_double()?;
         let block_offset = streams.object_reader.read_3bit_double()?;
 
-        // R14+: end point.
-        let _end_pt_proj = if self.sio.r13_14_only || self.sio.r2000_plus {
+        // R14+: end point projected back to the annotation.
+        let annotation_offset = if self.sio.r13_14_only || self.sio.r2000_plus {
             streams.object_reader.read_3bit_double()?
         } else {
             Vector3::ZERO
         };
 
-        // R2000+: dimension association data.
-        let dimasz = if self.sio.r2000_plus {
-            streams.object_reader.read_bit_double()?
-        } else {
-            0.0
-        };
+        // R13-R14 only: DIMGAP.
+        if self.sio.r13_14_only {
+            let _dimgap = streams.object_reader.read_bit_double()?;
+        }
 
-        let hookline_on = streams.object_reader.read_bit()?;
-        let arrow_head_on = streams.object_reader.read_bit()?;
+        // Extents of the annotation text box.
+        let text_height = streams.object_reader.read_bit_double()?;
+        let text_width = streams.object_reader.read_bit_double()?;
 
-        let _arrowhead_size = if self.sio.r13_14_only {
-            streams.object_reader.read_bit_double()?
-        } else {
-            0.0
-        };
+        let hookline_on_x_direction = streams.object_reader.read_bit()?;
+        let arrow_head_on = streams.object_reader.read_bit()?;
 
-        let text_width = if self.sio.r13_14_only {
-            streams.object_reader.read_bit_double()?
-        } else {
-            0.0
-        };
-        let text_height = if self.sio.r13_14_only {
-            streams.object_reader.read_bit_double()?
+        let mut dimasz = 0.0;
+        let mut override_color = Color::ByLayer;
+        let hookline_on;
+        if self.sio.r13_14_only {
+            let _arrowhead_type = streams.object_reader.read_bit_short()?;
+            dimasz = streams.object_reader.read_bit_double()?;
+            let _unknown = streams.object_reader.read_bit()?;
+            let _unknown = streams.object_reader.read_bit()?;
+            let _unknown = streams.object_reader.read_bit_short()?;
+            override_color = Color::from_index(streams.object_reader.read_bit_short()?);
+            hookline_on = streams.object_reader.read_bit()?;
+            let _unknown = streams.object_reader.read_bit()?;
         } else {
-            0.0
-        };
-
-        let _color_val = streams.object_reader.read_bit_short()?;
+            let _unknown = streams.object_reader.read_bit_short()?;
+            hookline_on = streams.object_reader.read_bit()?;
+            let _unknown = streams.object_reader.read_bit()?;
+        }
 
         let mut leader_tmpl = CadLeaderTemplateData::default();
         leader_tmpl.dimasz = dimasz;
@@ -2051,43 +2114,34 @@ impl DwgObjectReader {
         // Dimstyle handle.
         leader_tmpl.dimstyle_handle = streams.handle_ref()?;
 
-        let ldr_path = match path_type {
-            0 => leader::LeaderPathType::StraightLine,
-            1 => leader::LeaderPathType::Spline,
-            _ => leader::LeaderPathType::StraightLine,
-        };
-
-        let creation_type = match annotation_type {
-            0 => leader::LeaderCreationType::WithText,
-            1 => leader::LeaderCreationType::WithTolerance,
-            2 => leader::LeaderCreationType::WithBlock,
-            _ => leader::LeaderCreationType::NoAnnotation,
-        };
-
         let leader = Leader {
             common: entity_common,
             dimension_style: String::new(),
             arrow_enabled: arrow_head_on,
-            path_type: ldr_path,
-            creation_type,
-            hookline_direction: leader::HooklineDirection::Same,
+            path_type: leader::LeaderPathType::from_value(path_type),
+            creation_type: leader::LeaderCreationType::from_value(annotation_type),
+            hookline_direction: if hookline_on_x_direction {
+                leader::HooklineDirection::Same
+            } else {
+                leader::HooklineDirection::Opposite
+            },
             hookline_enabled: hookline_on,
             text_height,
             text_width,
             vertices,
-            override_color: Color::ByLayer,
-            annotation_handle: Handle::NULL,
+            override_color,
+            annotation_handle: Handle::new(leader_tmpl.annotation_handle),
             normal,
             horizontal_direction,
             block_offset,
-            annotation_offset: Vector3::ZERO,
+            annotation_offset,
         };
 
         Ok(CadTemplate::Leader {
             common: common_tmpl,
             entity_data: ent_tmpl,
             leader_data: leader_tmpl,
-            entity: EntityType::Leader(leader),
+            entity: EntityType::Leader(Box::new(leader)),
         })
     }
 
@@ -2613,7 +2667,7 @@ impl DwgObjectReader {
             common: common_tmpl,
             entity_data: ent_tmpl,
             hatch_data: hatch_tmpl,
-            entity: EntityType::Hatch(hatch),
+            entity: EntityType::Hatch(Box::new(hatch)),
         })
     }
 
@@ -2671,7 +2725,7 @@ impl DwgObjectReader {
             common: common_tmpl,
             entity_data: ent_tmpl,
             mleader_data: CadMultiLeaderTemplateData::default(),
-            entity: EntityType::MultiLeader(ml),
+            entity: EntityType::MultiLeader(Box::new(ml)),
         })
     }
 
@@ -2823,6 +2877,7 @@ impl DwgObjectReader {
             clip_vertices.push(pt);
         }
 
+        let flags = underlay::UnderlayDisplayFlags::from_bits_truncate(flags_raw);
         let underlay_entity = underlay::Underlay {
             common: entity_common,
             underlay_type,
@@ -2833,11 +2888,11 @@ impl DwgObjectReader {
             z_scale,
             rotation,
             normal,
-            flags: underlay::UnderlayDisplayFlags::from_bits_truncate(flags_raw),
+            flags,
             contrast,
             fade,
             clip_boundary_vertices: clip_vertices,
-            clip_inverted: false,
+            clip_inverted: flags.contains(underlay::UnderlayDisplayFlags::CLIP_INSIDE),
         };
 
         Ok(CadTemplate::Entity {
@@ -2868,7 +2923,7 @@ impl DwgObjectReader {
         Ok(CadTemplate::Entity {
             common: common_tmpl,
             entity_data: ent_tmpl,
-            entity: EntityType::Table(table),
+            entity: EntityType::Table(Box::new(table)),
         })
     }
 
@@ -3020,7 +3075,7 @@ impl DwgObjectReader {
                 common: common_tmpl,
                 entity_data: ent_tmpl,
                 image_data: image_tmpl,
-                entity: EntityType::RasterImage(image_entity),
+                entity: EntityType::RasterImage(Box::new(image_entity)),
             })
         }
     }
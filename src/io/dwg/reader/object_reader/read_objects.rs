@@ -2,7 +2,14 @@
 //!
 //! Mirrors ACadSharp's `DwgObjectReader.Objects.cs`.
 
+use crate::entities::underlay::{UnderlayDefinition, UnderlayType};
 use crate::error::Result;
+use crate::objects::{
+    BlockContentConnectionType, DictionaryCloningFlags, DimAssoc, ImageDefinition, LeaderContentType, LeaderDrawOrderType,
+    MultiLeaderDrawOrderType, MultiLeaderPathType, ObjectSnapType, OsnapPointRef, RasterVariables, ResolutionUnit, Scale, TextAlignmentType, TextAngleType, TextAttachmentDirectionType,
+    TextAttachmentType, WipeoutVariables, XRecord, XRecordEntry, XRecordValue, XRecordValueType,
+};
+use crate::types::{Handle, LineWeight, Vector3};
 
 use super::templates::*;
 use super::{DwgObjectReader, StreamSet};
@@ -58,13 +65,12 @@ impl DwgObjectReader {
         }
 
         // R2000+: cloning flags (BS), hard owner flag (RC).
+        let mut data = CadDictionaryTemplateData::default();
         if self.sio.r2000_plus {
-            let _cloning = streams.object_reader.read_bit_short()?;
-            let _hard_owner = streams.object_reader.read_raw_char()?;
+            data.duplicate_cloning = streams.object_reader.read_bit_short()?;
+            data.hard_owner = streams.object_reader.read_raw_char()? != 0;
         }
 
-        let mut data = CadDictionaryTemplateData::default();
-
         for _ in 0..num_entries {
             let name = streams.read_text()?;
             let handle = streams.handle_ref()?;
@@ -399,102 +405,47 @@ impl DwgObjectReader {
         let start_pos = streams.object_reader.position_in_bits();
         let end_pos = start_pos + (num_data_bytes as i64 * 8);
 
-        // Read data pairs (RS group code + value based on type).
+        // Data pairs: RS group code, then the value in raw form
+        let mut xrecord = XRecord::new();
         while streams.object_reader.position_in_bits() < end_pos {
-            let group_code = streams.object_reader.read_raw_short()?;
-
-            // Read value based on group code type.
-            // GroupCodeValueType lookup from DXF group codes.
-            match Self::group_code_value_type(group_code) {
-                GroupCodeType::String => {
-                    let _val = streams.object_reader.read_text_unicode()?;
-                }
-                GroupCodeType::Point3D => {
-                    let _x = streams.object_reader.read_bit_double()?;
-                    let _y = streams.object_reader.read_bit_double()?;
-                    let _z = streams.object_reader.read_bit_double()?;
-                }
-                GroupCodeType::Double => {
-                    let _val = streams.object_reader.read_bit_double()?;
+            let code = streams.object_reader.read_raw_short()? as i32;
+            let reader = &mut streams.object_reader;
+            let value = match XRecordValueType::from_code(code) {
+                XRecordValueType::String => XRecordValue::String(reader.read_text_unicode()?),
+                XRecordValueType::Point3D => {
+                    XRecordValue::Point3D(reader.read_raw_double()?, reader.read_raw_double()?, reader.read_raw_double()?)
                 }
-                GroupCodeType::Int16 => {
-                    let _val = streams.object_reader.read_bit_short()?;
+                XRecordValueType::Double => XRecordValue::Double(reader.read_raw_double()?),
+                XRecordValueType::Byte => XRecordValue::Byte(reader.read_raw_char()?),
+                XRecordValueType::Bool => XRecordValue::Bool(reader.read_raw_char()? != 0),
+                XRecordValueType::Int16 => XRecordValue::Int16(reader.read_raw_short()?),
+                XRecordValueType::Int32 => XRecordValue::Int32(reader.read_raw_long()?),
+                XRecordValueType::Int64 => XRecordValue::Int64(reader.read_raw_ulong()? as i64),
+                XRecordValueType::Handle | XRecordValueType::ObjectId => {
+                    XRecordValue::Handle(Handle::new(reader.read_raw_ulong()?))
                 }
-                GroupCodeType::Int32 => {
-                    let _val = streams.object_reader.read_bit_long()?;
+                XRecordValueType::Chunk => {
+                    let len = reader.read_raw_char()? as usize;
+                    XRecordValue::Chunk(reader.read_bytes(len)?)
                 }
-                GroupCodeType::Int64 => {
-                    let _val = streams.object_reader.read_bit_long_long()?;
-                }
-                GroupCodeType::Handle => {
-                    let _val = streams.object_reader.handle_reference()?;
-                }
-                GroupCodeType::Bool => {
-                    let _val = streams.object_reader.read_bit()?;
-                }
-                GroupCodeType::Chunk => {
-                    let len = streams.object_reader.read_raw_char()? as usize;
-                    let _val = streams.object_reader.read_bytes(len)?;
-                }
-                GroupCodeType::Unknown => {
-                    // Skip unknown codes.
-                    break;
-                }
-            }
+                // The size of the value is not known
+                XRecordValueType::Unknown => break,
+            };
+            xrecord.add_entry(XRecordEntry::new(code, value));
         }
+        streams.object_reader.set_position_in_bits(end_pos);
 
         // R2000+: cloning flags (BS).
         if self.sio.r2000_plus {
-            let _cloning = streams.object_reader.read_bit_short()?;
+            xrecord.cloning_flags = DictionaryCloningFlags::from_value(streams.object_reader.read_bit_short()?);
         }
 
         Ok(CadTemplate::XRecordObj {
             common: common_tmpl,
+            xrecord,
         })
     }
 
-    /// Map a DXF group code to its value type for XRecord reading.
-    fn group_code_value_type(code: i16) -> GroupCodeType {
-        match code {
-            0..=9 => GroupCodeType::String,
-            10..=39 => GroupCodeType::Point3D,
-            40..=59 => GroupCodeType::Double,
-            60..=79 => GroupCodeType::Int16,
-            90..=99 => GroupCodeType::Int32,
-            100..=102 => GroupCodeType::String,
-            105 => GroupCodeType::Handle,
-            110..=149 => GroupCodeType::Double,
-            160..=169 => GroupCodeType::Int64,
-            170..=179 => GroupCodeType::Int16,
-            210..=239 => GroupCodeType::Double,
-            270..=279 => GroupCodeType::Int16,
-            280..=289 => GroupCodeType::Int16,
-            290..=299 => GroupCodeType::Bool,
-            300..=309 => GroupCodeType::String,
-            310..=319 => GroupCodeType::Chunk,
-            320..=329 => GroupCodeType::Handle,
-            330..=369 => GroupCodeType::Handle,
-            370..=379 => GroupCodeType::Int16,
-            380..=389 => GroupCodeType::Int16,
-            390..=399 => GroupCodeType::Handle,
-            400..=409 => GroupCodeType::Int16,
-            410..=419 => GroupCodeType::String,
-            420..=429 => GroupCodeType::Int32,
-            430..=439 => GroupCodeType::String,
-            440..=449 => GroupCodeType::Int32,
-            450..=459 => GroupCodeType::Int32,
-            460..=469 => GroupCodeType::Double,
-            470..=479 => GroupCodeType::String,
-            480..=481 => GroupCodeType::Handle,
-            999 => GroupCodeType::String,
-            1000..=1009 => GroupCodeType::String,
-            1010..=1059 => GroupCodeType::Double,
-            1060..=1070 => GroupCodeType::Int16,
-            1071 => GroupCodeType::Int32,
-            _ => GroupCodeType::Unknown,
-        }
-    }
-
     // -----------------------------------------------------------------------
     // SCALE
     // -----------------------------------------------------------------------
@@ -509,19 +460,82 @@ impl DwgObjectReader {
         let _unknown = streams.object_reader.read_bit_short()?;
 
         // TV: name.
-        let _name = streams.read_text()?;
+        let name = streams.read_text()?;
 
         // BD: paper units.
-        let _paper_units = streams.object_reader.read_bit_double()?;
+        let paper_units = streams.object_reader.read_bit_double()?;
+
+        // BD: drawing units.
+        let drawing_units = streams.object_reader.read_bit_double()?;
 
-        // BD: drawing units (divided by 10 in C# source).
-        let _drawing_units = streams.object_reader.read_bit_double()?;
+        let mut scale = Scale::new(&name, paper_units, drawing_units);
 
         // B: is unit scale.
-        let _is_unit = streams.object_reader.read_bit()?;
+        scale.is_unit_scale = streams.object_reader.read_bit()?;
 
         Ok(CadTemplate::ScaleObj {
             common: common_tmpl,
+            scale,
+        })
+    }
+
+    // -----------------------------------------------------------------------
+    // DIMASSOC
+    // -----------------------------------------------------------------------
+
+    pub(super) fn read_dim_assoc(
+        &mut self,
+        streams: &mut StreamSet,
+    ) -> Result<CadTemplate> {
+        let common_tmpl = self.read_common_non_entity_data(streams)?;
+
+        // H: dimension (soft pointer).
+        let mut assoc = DimAssoc::new(Handle::new(streams.handle_ref()?));
+
+        // BL: associativity flags, one bit per point reference that follows.
+        let associativity = streams.object_reader.read_bit_long()?;
+        // B: trans space flag.
+        assoc.trans_space = streams.object_reader.read_bit()?;
+        // RC: rotated dimension type.
+        assoc.rotated_type = streams.object_reader.read_raw_char()? as i16;
+
+        for index in 0..assoc.points.len() {
+            if associativity & (1 << index) == 0 {
+                continue;
+            }
+            // TV: class name (AcDbOsnapPointRef).
+            let _class_name = streams.read_text()?;
+            // RC: object snap type.
+            let osnap_type = ObjectSnapType::from_code(streams.object_reader.read_raw_char()? as i16);
+            let mut point = OsnapPointRef::new(osnap_type, Handle::NULL, Vector3::ZERO);
+
+            // BS + H: main objects.
+            let count = streams.object_reader.read_bit_short()?.max(0) as usize;
+            point.objects = (0..count)
+                .map(|_| streams.handle_ref().map(Handle::new))
+                .collect::<Result<_>>()?;
+            // BS: subentity type, BL: graphics system marker.
+            point.subentity_type = streams.object_reader.read_bit_short()?;
+            point.gs_marker = streams.object_reader.read_bit_long()?;
+            // BD: geometry parameter, 3BD: snapped point.
+            point.near_parameter = streams.object_reader.read_bit_double()?;
+            point.point = streams.object_reader.read_3bit_double()?;
+            // BS + H: intersection objects.
+            let count = streams.object_reader.read_bit_short()?.max(0) as usize;
+            point.intersection_objects = (0..count)
+                .map(|_| streams.handle_ref().map(Handle::new))
+                .collect::<Result<_>>()?;
+            point.intersection_subentity_type = streams.object_reader.read_bit_short()?;
+            point.intersection_gs_marker = streams.object_reader.read_bit_long()?;
+            // B: has last point reference.
+            point.has_last_point_ref = streams.object_reader.read_bit()?;
+
+            assoc.points[index] = Some(point);
+        }
+
+        Ok(CadTemplate::DimAssocObj {
+            common: common_tmpl,
+            assoc,
         })
     }
 
@@ -565,27 +579,31 @@ impl DwgObjectReader {
     ) -> Result<CadTemplate> {
         let common_tmpl = self.read_common_non_entity_data(streams)?;
 
+        let mut definition = ImageDefinition::new("");
+
         // BL: class version.
-        let _version = streams.object_reader.read_bit_long()?;
+        definition.class_version = streams.object_reader.read_bit_long()?;
 
         // 2RD: image size (pixels).
-        let _size = streams.object_reader.read_2raw_double()?;
+        let size = streams.object_reader.read_2raw_double()?;
+        definition.size_in_pixels = (size.x as u32, size.y as u32);
 
         // TV: file name.
-        let _file_name = streams.read_text()?;
+        definition.file_name = streams.read_text()?;
 
         // B: is loaded.
-        let _is_loaded = streams.object_reader.read_bit()?;
+        definition.is_loaded = streams.object_reader.read_bit()?;
 
         // RC: units.
-        let _units = streams.object_reader.read_raw_char()?;
+        definition.resolution_unit = ResolutionUnit::from_code(streams.object_reader.read_raw_char()? as i32);
 
         // 2RD: default size (pixel size in AutoCAD units).
-        let _default_size = streams.object_reader.read_2raw_double()?;
+        let pixel_size = streams.object_reader.read_2raw_double()?;
+        definition.pixel_size = (pixel_size.x, pixel_size.y);
 
         Ok(CadTemplate::ImageDefObj {
             common: common_tmpl,
-            imgdef_data: CadImageDefTemplateData {},
+            imgdef_data: CadImageDefTemplateData { definition },
         })
     }
 
@@ -602,11 +620,11 @@ impl DwgObjectReader {
         // BL: class version.
         let _version = streams.object_reader.read_bit_long()?;
 
+        // The image is the owner
+        let image_handle = common_tmpl.owner_handle;
         Ok(CadTemplate::ImageDefReactorObj {
             common: common_tmpl,
-            reactor_data: CadImageDefReactorTemplateData {
-                image_handle: 0,
-            },
+            reactor_data: CadImageDefReactorTemplateData { image_handle },
         })
     }
 
@@ -620,6 +638,7 @@ impl DwgObjectReader {
     ) -> Result<CadTemplate> {
         let common_tmpl = self.read_common_non_entity_data(streams)?;
         let mut mleader_data = CadMLeaderStyleTemplateData::default();
+        let style = &mut mleader_data.style;
 
         // R2010+: version (BS = 2).
         if self.sio.r2010_plus {
@@ -627,128 +646,130 @@ impl DwgObjectReader {
         }
 
         // BS: content type.
-        let _content_type = streams.object_reader.read_bit_short()?;
+        style.content_type = LeaderContentType::from(streams.object_reader.read_bit_short()?);
 
         // BS: multi-leader draw order.
-        let _ml_draw_order = streams.object_reader.read_bit_short()?;
+        style.multileader_draw_order = MultiLeaderDrawOrderType::from(streams.object_reader.read_bit_short()?);
 
         // BS: leader draw order.
-        let _leader_draw_order = streams.object_reader.read_bit_short()?;
+        style.leader_draw_order = LeaderDrawOrderType::from(streams.object_reader.read_bit_short()?);
 
         // BL: max leader segments points.
-        let _max_segments = streams.object_reader.read_bit_long()?;
+        style.max_leader_points = streams.object_reader.read_bit_long()?;
 
         // BD: first segment angle constraint.
-        let _first_angle = streams.object_reader.read_bit_double()?;
+        style.first_segment_angle = streams.object_reader.read_bit_double()?;
 
         // BD: second segment angle constraint.
-        let _second_angle = streams.object_reader.read_bit_double()?;
+        style.second_segment_angle = streams.object_reader.read_bit_double()?;
 
         // BS: path type.
-        let _path_type = streams.object_reader.read_bit_short()?;
+        style.path_type = MultiLeaderPathType::from(streams.object_reader.read_bit_short()?);
 
         // CMC: line color.
-        let _line_color = streams.object_reader.read_cm_color()?;
+        style.line_color = streams.object_reader.read_cm_color()?;
 
         // H: leader line type handle (hard pointer).
         mleader_data.leader_line_type_handle = streams.handle_ref()?;
 
         // BL: leader line weight.
-        let _line_weight = streams.object_reader.read_bit_long()?;
+        style.line_weight = LineWeight::from_value(streams.object_reader.read_bit_long()? as i16);
 
         // B: enable landing.
-        let _landing = streams.object_reader.read_bit()?;
+        style.enable_landing = streams.object_reader.read_bit()?;
 
         // BD: landing gap.
-        let _landing_gap = streams.object_reader.read_bit_double()?;
+        style.landing_gap = streams.object_reader.read_bit_double()?;
 
         // B: enable dogleg.
-        let _dogleg = streams.object_reader.read_bit()?;
+        style.enable_dogleg = streams.object_reader.read_bit()?;
 
         // BD: landing distance.
-        let _landing_distance = streams.object_reader.read_bit_double()?;
+        style.landing_distance = streams.object_reader.read_bit_double()?;
 
         // TV: description.
-        let _description = streams.read_text()?;
+        style.description = streams.read_text()?;
 
         // H: arrowhead handle (hard pointer).
         mleader_data.arrowhead_handle = streams.handle_ref()?;
 
         // BD: arrowhead size.
-        let _arrow_size = streams.object_reader.read_bit_double()?;
+        style.arrowhead_size = streams.object_reader.read_bit_double()?;
 
         // TV: default text contents.
-        let _default_text = streams.read_text()?;
+        style.default_text = streams.read_text()?;
 
         // H: text style handle (hard pointer).
         mleader_data.mtext_style_handle = streams.handle_ref()?;
 
         // BS: text left attachment.
-        let _text_left = streams.object_reader.read_bit_short()?;
+        style.text_left_attachment = TextAttachmentType::from(streams.object_reader.read_bit_short()?);
 
         // BS: text right attachment.
-        let _text_right = streams.object_reader.read_bit_short()?;
+        style.text_right_attachment = TextAttachmentType::from(streams.object_reader.read_bit_short()?);
 
         // BS: text angle.
-        let _text_angle = streams.object_reader.read_bit_short()?;
+        style.text_angle_type = TextAngleType::from(streams.object_reader.read_bit_short()?);
 
         // BS: text alignment.
-        let _text_alignment = streams.object_reader.read_bit_short()?;
+        style.text_alignment = TextAlignmentType::from(streams.object_reader.read_bit_short()?);
 
         // CMC: text color.
-        let _text_color = streams.object_reader.read_cm_color()?;
+        style.text_color = streams.object_reader.read_cm_color()?;
 
         // BD: text height.
-        let _text_height = streams.object_reader.read_bit_double()?;
+        style.text_height = streams.object_reader.read_bit_double()?;
 
         // B: text frame.
-        let _text_frame = streams.object_reader.read_bit()?;
+        style.text_frame = streams.object_reader.read_bit()?;
 
         // B: text align always left.
-        let _align_left = streams.object_reader.read_bit()?;
+        style.text_always_left = streams.object_reader.read_bit()?;
 
         // BD: align space.
-        let _align_space = streams.object_reader.read_bit_double()?;
+        style.align_space = streams.object_reader.read_bit_double()?;
 
         // H: block content handle (hard pointer).
         mleader_data.block_content_handle = streams.handle_ref()?;
 
         // CMC: block content color.
-        let _block_color = streams.object_reader.read_cm_color()?;
+        style.block_content_color = streams.object_reader.read_cm_color()?;
 
         // 3BD: block content scale (x, y, z separate BD).
-        let _scale_x = streams.object_reader.read_bit_double()?;
-        let _scale_y = streams.object_reader.read_bit_double()?;
-        let _scale_z = streams.object_reader.read_bit_double()?;
+        style.block_content_scale_x = streams.object_reader.read_bit_double()?;
+        style.block_content_scale_y = streams.object_reader.read_bit_double()?;
+        style.block_content_scale_z = streams.object_reader.read_bit_double()?;
 
         // B: enable block content scale.
-        let _enable_scale = streams.object_reader.read_bit()?;
+        style.enable_block_scale = streams.object_reader.read_bit()?;
 
         // BD: block content rotation.
-        let _rotation = streams.object_reader.read_bit_double()?;
+        style.block_content_rotation = streams.object_reader.read_bit_double()?;
 
         // B: enable block content rotation.
-        let _enable_rotation = streams.object_reader.read_bit()?;
+        style.enable_block_rotation = streams.object_reader.read_bit()?;
 
         // BS: block content connection.
-        let _connection = streams.object_reader.read_bit_short()?;
+        style.block_content_connection = BlockContentConnectionType::from(streams.object_reader.read_bit_short()?);
 
         // BD: scale factor.
-        let _scale_factor = streams.object_reader.read_bit_double()?;
+        style.scale_factor = streams.object_reader.read_bit_double()?;
 
         // B: overwrite property value.
-        let _overwrite = streams.object_reader.read_bit()?;
+        style.property_changed = streams.object_reader.read_bit()?;
 
         // B: is annotative.
-        let _annotative = streams.object_reader.read_bit()?;
+        style.is_annotative = streams.object_reader.read_bit()?;
 
         // BD: break size.
-        let _break_size = streams.object_reader.read_bit_double()?;
+        style.break_gap_size = streams.object_reader.read_bit_double()?;
 
-        // R2010+: text attachment direction, text top/bottom attachment.
+        // R2010+: text attachment direction, text bottom/top attachment.
         if self.sio.r2010_plus {
-            let _text_attach_dir = streams.object_reader.read_bit_short()?;
-            let _text_top_attach = streams.object_reader.read_bit_short()?;
+            style.text_attachment_direction =
+                TextAttachmentDirectionType::from(streams.object_reader.read_bit_short()?);
+            style.text_bottom_attachment = TextAttachmentType::from(streams.object_reader.read_bit_short()?);
+            style.text_top_attachment = TextAttachmentType::from(streams.object_reader.read_bit_short()?);
         }
 
         // R2013+: unknown flag.
@@ -808,7 +829,7 @@ impl DwgObjectReader {
         streams: &mut StreamSet,
     ) -> Result<CadTemplate> {
         let common_tmpl = self.read_common_non_entity_data(streams)?;
-        Ok(CadTemplate::GenericObject {
+        Ok(CadTemplate::PlaceHolderObj {
             common: common_tmpl,
         })
     }
@@ -822,21 +843,23 @@ impl DwgObjectReader {
         streams: &mut StreamSet,
     ) -> Result<CadTemplate> {
         let common_tmpl = self.read_common_non_entity_data(streams)?;
+        let mut variables = RasterVariables::new();
 
         // BL: class version.
-        let _version = streams.object_reader.read_bit_long()?;
+        variables.class_version = streams.object_reader.read_bit_long()?;
 
         // BS: display frame.
-        let _frame = streams.object_reader.read_bit_short()?;
+        variables.display_image_frame = streams.object_reader.read_bit_short()?;
 
         // BS: display quality.
-        let _quality = streams.object_reader.read_bit_short()?;
+        variables.image_quality = streams.object_reader.read_bit_short()?;
 
         // BS: units.
-        let _units = streams.object_reader.read_bit_short()?;
+        variables.units = streams.object_reader.read_bit_short()?;
 
-        Ok(CadTemplate::GenericObject {
+        Ok(CadTemplate::RasterVariablesObj {
             common: common_tmpl,
+            variables,
         })
     }
 
@@ -872,23 +895,26 @@ impl DwgObjectReader {
     }
 
     // -----------------------------------------------------------------------
-    // PDF_DEFINITION (PdfUnderlayDefinition)
+    // PDF_DEFINITION / DWF_DEFINITION / DGN_DEFINITION (UnderlayDefinition)
     // -----------------------------------------------------------------------
 
-    pub(super) fn read_pdf_definition(
+    pub(super) fn read_underlay_definition(
         &mut self,
         streams: &mut StreamSet,
+        underlay_type: UnderlayType,
     ) -> Result<CadTemplate> {
         let common_tmpl = self.read_common_non_entity_data(streams)?;
+        let mut definition = UnderlayDefinition::new(underlay_type);
 
         // TV: file path.
-        let _file = streams.read_text()?;
+        definition.file_path = streams.read_text()?;
 
-        // TV: page.
-        let _page = streams.read_text()?;
+        // TV: page / sheet / model name.
+        definition.page_name = streams.read_text()?;
 
-        Ok(CadTemplate::GenericObject {
+        Ok(CadTemplate::UnderlayDefObj {
             common: common_tmpl,
+            definition,
         })
     }
 
@@ -929,12 +955,14 @@ impl DwgObjectReader {
         streams: &mut StreamSet,
     ) -> Result<CadTemplate> {
         let common_tmpl = self.read_common_non_entity_data(streams)?;
+        let mut variables = WipeoutVariables::new();
 
         // BS: display frame.
-        let _display_frame = streams.object_reader.read_bit_short()?;
+        variables.display_frame = streams.object_reader.read_bit_short()?;
 
-        Ok(CadTemplate::GenericObject {
+        Ok(CadTemplate::WipeoutVariablesObj {
             common: common_tmpl,
+            variables,
         })
     }
 
@@ -980,18 +1008,3 @@ impl DwgObjectReader {
         })
     }
 }
-
-/// Group code value types for XRecord reading.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum GroupCodeType {
-    String,
-    Point3D,
-    Double,
-    Int16,
-    Int32,
-    Int64,
-    Handle,
-    Bool,
-    Chunk,
-    Unknown,
-}
@@ -120,16 +120,18 @@ impl DwgObjectReader {
         let _xref_dep = self.read_xref_dependant_bit(&mut *streams.object_reader)?;
 
         // Anonymous (B).
-        let _anonymous = streams.object_reader.read_bit()?;
+        block_data.anonymous = streams.object_reader.read_bit()?;
 
         // Has attributes (B).
-        let _has_attr = streams.object_reader.read_bit()?;
+        block_data.has_attributes = streams.object_reader.read_bit()?;
 
         // Is xref (B).
         let is_xref = streams.object_reader.read_bit()?;
+        block_data.is_xref = is_xref;
 
         // Is xref overlay (B).
         let is_xref_overlay = streams.object_reader.read_bit()?;
+        block_data.is_xref_overlay = is_xref_overlay;
 
         // R2000+: load xref (B).
         if self.sio.r2000_plus {
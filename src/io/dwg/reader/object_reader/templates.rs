@@ -8,7 +8,10 @@
 
 use std::collections::HashMap;
 
-use crate::entities::EntityType;
+use crate::entities::{EntityType, UnderlayDefinition};
+use crate::objects::{
+    DimAssoc, ImageDefinition, MultiLeaderStyle, ObjectType, RasterVariables, Scale, WipeoutVariables, XRecord,
+};
 use crate::types::{Color, Vector2, Vector3};
 use crate::xdata::XDataValue;
 
@@ -223,6 +226,10 @@ pub struct CadBlockRecordTemplateData {
     pub layout_handle: u64,
     pub block_entity_handle: u64,
     pub end_block_handle: u64,
+    pub anonymous: bool,
+    pub has_attributes: bool,
+    pub is_xref: bool,
+    pub is_xref_overlay: bool,
 }
 
 /// Template for LAYER table entry.
@@ -346,6 +353,10 @@ pub struct CadVPortTemplateData {
 pub struct CadDictionaryTemplateData {
     /// Entry name → handle pairs (soft owner).
     pub entries: Vec<(String, u64)>,
+    /// Duplicate record cloning flag (R2000+).
+    pub duplicate_cloning: i16,
+    /// Hard owner flag (R2000+).
+    pub hard_owner: bool,
 }
 
 /// Template for DICTIONARY_WITH_DEFAULT objects.
@@ -390,7 +401,9 @@ pub struct CadXRecordTemplateData {
 /// Template for IMAGEDEF objects.
 #[derive(Debug, Clone, Default)]
 pub struct CadImageDefTemplateData {
-    // Reactor handles stored in common.reactor_handles.
+    /// Definition without its handles; the reactor handles are in
+    /// common.reactor_handles.
+    pub definition: ImageDefinition,
 }
 
 /// Template for IMAGEDEF_REACTOR objects.
@@ -415,6 +428,8 @@ pub struct CadScaleTemplateData {
 /// Template for MLEADERSTYLE objects.
 #[derive(Debug, Clone, Default)]
 pub struct CadMLeaderStyleTemplateData {
+    /// Style settings, without the handles below or the name
+    pub style: MultiLeaderStyle,
     pub leader_line_type_handle: u64,
     pub arrowhead_handle: u64,
     pub mtext_style_handle: u64,
@@ -615,9 +630,44 @@ pub enum CadTemplate {
     },
     ScaleObj {
         common: CadTemplateCommon,
+        scale: Scale,
     },
+    /// XRECORD, complete apart from the handles in `common`
     XRecordObj {
         common: CadTemplateCommon,
+        xrecord: XRecord,
+    },
+    /// Dimension associativity, complete apart from the handles in `common`
+    DimAssocObj {
+        common: CadTemplateCommon,
+        assoc: DimAssoc,
+    },
+    /// PDF, DWF or DGN underlay definition, complete apart from the
+    /// handles in `common`
+    UnderlayDefObj {
+        common: CadTemplateCommon,
+        definition: UnderlayDefinition,
+    },
+    /// Image frame, quality and units settings
+    RasterVariablesObj {
+        common: CadTemplateCommon,
+        variables: RasterVariables,
+    },
+    /// Wipeout frame setting
+    WipeoutVariablesObj {
+        common: CadTemplateCommon,
+        variables: WipeoutVariables,
+    },
+    /// ACDBPLACEHOLDER, e.g. the default entry of ACAD_PLOTSTYLENAME
+    PlaceHolderObj {
+        common: CadTemplateCommon,
+    },
+    /// Object of a class decoded by a
+    /// [`DwgCustomClasses`](crate::io::dwg::DwgCustomClasses) reader,
+    /// complete apart from the handles in `common`
+    CustomObject {
+        common: CadTemplateCommon,
+        object: Box<ObjectType>,
     },
     /// Dictionary variable, plain object template, etc.
     GenericObject {
@@ -665,10 +715,36 @@ impl CadTemplate {
             | CadTemplate::PlotSettingsObj { common, .. }
             | CadTemplate::ScaleObj { common, .. }
             | CadTemplate::XRecordObj { common, .. }
+            | CadTemplate::DimAssocObj { common, .. }
+            | CadTemplate::UnderlayDefObj { common, .. }
+            | CadTemplate::RasterVariablesObj { common, .. }
+            | CadTemplate::WipeoutVariablesObj { common, .. }
+            | CadTemplate::PlaceHolderObj { common, .. }
+            | CadTemplate::CustomObject { common, .. }
             | CadTemplate::GenericObject { common, .. } => common,
         }
     }
 
+    /// Access the entity template data, for templates of entities.
+    pub fn entity_data(&self) -> Option<&CadEntityTemplateData> {
+        match self {
+            CadTemplate::Entity { entity_data, .. }
+            | CadTemplate::TextEntity { entity_data, .. }
+            | CadTemplate::Insert { entity_data, .. }
+            | CadTemplate::Polyline { entity_data, .. }
+            | CadTemplate::Dimension { entity_data, .. }
+            | CadTemplate::Leader { entity_data, .. }
+            | CadTemplate::MultiLeader { entity_data, .. }
+            | CadTemplate::Shape { entity_data, .. }
+            | CadTemplate::Viewport { entity_data, .. }
+            | CadTemplate::Hatch { entity_data, .. }
+            | CadTemplate::Solid3D { entity_data, .. }
+            | CadTemplate::Image { entity_data, .. }
+            | CadTemplate::PolyfaceMesh { entity_data, .. } => Some(entity_data),
+            _ => None,
+        }
+    }
+
     /// The handle of the object that this template represents.
     pub fn handle(&self) -> u64 {
         self.common().handle
@@ -687,29 +763,14 @@ impl CadTemplate {
         handles.push(c.xdict_handle);
 
         // Entity data handles.
-        match self {
-            CadTemplate::Entity { entity_data, .. }
-            | CadTemplate::TextEntity { entity_data, .. }
-            | CadTemplate::Insert { entity_data, .. }
-            | CadTemplate::Polyline { entity_data, .. }
-            | CadTemplate::Dimension { entity_data, .. }
-            | CadTemplate::Leader { entity_data, .. }
-            | CadTemplate::MultiLeader { entity_data, .. }
-            | CadTemplate::Shape { entity_data, .. }
-            | CadTemplate::Viewport { entity_data, .. }
-            | CadTemplate::Hatch { entity_data, .. }
-            | CadTemplate::Solid3D { entity_data, .. }
-            | CadTemplate::Image { entity_data, .. }
-            | CadTemplate::PolyfaceMesh { entity_data, .. } => {
-                handles.push(entity_data.prev_entity);
-                handles.push(entity_data.next_entity);
-                handles.push(entity_data.layer_handle);
-                handles.push(entity_data.linetype_handle);
-                handles.push(entity_data.plotstyle_handle);
-                handles.push(entity_data.material_handle);
-                handles.push(entity_data.color_handle);
-            }
-            _ => {}
+        if let Some(entity_data) = self.entity_data() {
+            handles.push(entity_data.prev_entity);
+            handles.push(entity_data.next_entity);
+            handles.push(entity_data.layer_handle);
+            handles.push(entity_data.linetype_handle);
+            handles.push(entity_data.plotstyle_handle);
+            handles.push(entity_data.material_handle);
+            handles.push(entity_data.color_handle);
         }
 
         // Type-specific handles.
@@ -13,6 +13,7 @@ use crate::types::{Color, DxfVersion, Transparency, Vector2, Vector3};
 use super::stream_reader::IDwgStreamReader;
 
 use std::io::{self, Cursor, Read, Seek, SeekFrom};
+use std::sync::Arc;
 
 use encoding_rs::Encoding;
 
@@ -21,7 +22,8 @@ use encoding_rs::Encoding;
 /// All version-specific behavior is handled by overriding individual methods
 /// in the version-specific wrappers.
 pub struct DwgStreamReaderBase {
-    stream: Cursor<Vec<u8>>,
+    /// Shared so that sub-readers over the same section don't copy it.
+    stream: Cursor<Arc<[u8]>>,
     bit_shift: u8,
     last_byte: u8,
     is_empty: bool,
@@ -31,9 +33,11 @@ pub struct DwgStreamReaderBase {
 
 impl DwgStreamReaderBase {
     /// Create a new reader wrapping raw data bytes.
-    pub fn new(data: Vec<u8>, version: DxfVersion) -> Self {
+    ///
+    /// Passing an `Arc<[u8]>` shares the buffer instead of copying it.
+    pub fn new(data: impl Into<Arc<[u8]>>, version: DxfVersion) -> Self {
         Self {
-            stream: Cursor::new(data),
+            stream: Cursor::new(data.into()),
             bit_shift: 0,
             last_byte: 0,
             is_empty: false,
@@ -43,7 +47,7 @@ impl DwgStreamReaderBase {
     }
 
     /// Create from a stream position (for sub-stream use).
-    pub fn new_at(data: Vec<u8>, version: DxfVersion, position: u64) -> Self {
+    pub fn new_at(data: impl Into<Arc<[u8]>>, version: DxfVersion, position: u64) -> Self {
         let mut reader = Self::new(data, version);
         reader.set_position(position);
         reader
@@ -1017,7 +1021,7 @@ impl IDwgStreamReader for DwgStreamReaderBase {
 ///
 /// This is the factory function mirroring ACadSharp's
 /// `DwgStreamReaderBase.GetStreamHandler`.
-pub fn get_stream_handler(version: DxfVersion, data: Vec<u8>) -> DwgStreamReaderBase {
+pub fn get_stream_handler(version: DxfVersion, data: impl Into<Arc<[u8]>>) -> DwgStreamReaderBase {
     DwgStreamReaderBase::new(data, version)
 }
 
@@ -1454,4 +1458,15 @@ mod tests {
         assert_eq!(encoding_from_code_page(0x0B).name(), "Shift_JIS");
         assert_eq!(encoding_from_code_page(0x0C).name(), "GBK");
     }
+
+    #[test]
+    fn test_sub_readers_share_buffer() {
+        let data: Arc<[u8]> = pack_2bit(0b01, &[0x42]).into();
+        let mut first = get_stream_handler(DxfVersion::AC1015, Arc::clone(&data));
+        let mut second = get_stream_handler(DxfVersion::AC1015, Arc::clone(&data));
+        assert_eq!(Arc::strong_count(&data), 3);
+
+        assert_eq!(first.read_bit_short().unwrap(), 0x42);
+        assert_eq!(second.read_bit_short().unwrap(), 0x42);
+    }
 }
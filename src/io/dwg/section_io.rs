@@ -3,7 +3,7 @@
 //! Mirrors ACadSharp's `DwgSectionIO` abstract class which provides
 //! version flags and sentinel checking for all section readers/writers.
 
-use crate::notification::{Notification, NotificationType};
+use crate::notification::{Notification, NotificationCode, NotificationType};
 use crate::types::DxfVersion;
 
 use super::constants::sentinels;
@@ -81,10 +81,13 @@ impl SectionIO {
         if Self::check_sentinel(actual, expected) {
             None
         } else {
-            Some(Notification::new(
-                NotificationType::Warning,
-                format!("Invalid section sentinel found in {}", section_name),
-            ))
+            Some(
+                Notification::new(
+                    NotificationType::Warning,
+                    format!("Invalid section sentinel found in {}", section_name),
+                )
+                .with_code(NotificationCode::InvalidSentinel),
+            )
         }
     }
 
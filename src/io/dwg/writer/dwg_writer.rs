@@ -47,6 +47,17 @@ impl DwgWriter {
 
     /// Write a `CadDocument` with explicit summary info.
     pub fn write_with_info(doc: &CadDocument, summary_info: &CadSummaryInfo) -> Result<Vec<u8>> {
+        Self::write_document(doc, summary_info, true)
+    }
+
+    /// Write a `CadDocument` without adding the mandatory table entries it
+    /// lacks (see [`CadDocument::ensure_defaults`]).
+    pub fn write_without_defaults(doc: &CadDocument) -> Result<Vec<u8>> {
+        Self::write_document(doc, &CadSummaryInfo::default(), false)
+    }
+
+    fn write_document(doc: &CadDocument, summary_info: &CadSummaryInfo, ensure_defaults: bool) -> Result<Vec<u8>> {
+        let doc = &*doc.prepared_for_write(ensure_defaults)?;
         let version = doc.version;
         let sio = SectionIO::new(version);
         let maintenance_version: u8 = 0;
@@ -56,6 +67,10 @@ impl DwgWriter {
         // Build the handles collection from the document.
         let handles = Self::build_header_handles(doc);
 
+        // $HANDSEED must lie above every handle written
+        let mut header = doc.header.clone();
+        header.handle_seed = header.handle_seed.max(doc.next_handle());
+
         // Ensure default DXF classes are present (needed for unlisted types
         // like MULTILEADER, IMAGE, WIPEOUT, etc.).
         let mut classes: Vec<_> = doc.classes.iter().cloned().collect();
@@ -73,7 +88,7 @@ impl DwgWriter {
         // 1. Header section (AcDb:Header)
         // -------------------------------------------------------------------
         let header_data = DwgHeaderWriter::new(version)
-            .write(&doc.header, &handles, maintenance_version)?;
+            .write(&header, &handles, maintenance_version)?;
         file_writer.add_section(section_names::HEADER, header_data, sio.r2004_plus, 0)?;
 
         // -------------------------------------------------------------------
@@ -112,7 +127,7 @@ impl DwgWriter {
         // 5. Aux Header (ALL versions — C# writeAuxHeader() has no version guard)
         // -------------------------------------------------------------------
         let aux_header_data = DwgAuxHeaderWriter::new(version)
-            .write(&doc.header, maintenance_version as i16)?;
+            .write(&header, maintenance_version as i16)?;
         file_writer.add_section(section_names::AUX_HEADER, aux_header_data, sio.r2004_plus, 0)?;
 
         // -------------------------------------------------------------------
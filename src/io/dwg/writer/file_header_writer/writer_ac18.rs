@@ -95,7 +95,7 @@ impl DwgFileHeaderWriterAC18 {
     /// For compressed data, pads to `decompressed_size` before compressing.
     /// For uncompressed data, stores the actual bytes without padding.
     fn apply_compression(
-        &self,
+        compressor: &Lz77Ac18Compressor,
         buffer: &[u8],
         decompressed_size: usize,
         offset: usize,
@@ -107,7 +107,7 @@ impl DwgFileHeaderWriterAC18 {
             let mut holder = Vec::with_capacity(decompressed_size);
             holder.extend_from_slice(&buffer[offset..offset + total_size]);
             holder.resize(decompressed_size, 0);
-            self.compressor.compress(&holder, 0, decompressed_size)
+            compressor.compress(&holder, 0, decompressed_size)
         } else {
             // Uncompressed: store only the actual bytes, no padding
             let data = buffer[offset..offset + total_size].to_vec();
@@ -115,24 +115,48 @@ impl DwgFileHeaderWriterAC18 {
         }
     }
 
-    /// Create a local section page: compress data, write page header + data.
+    /// Compress every page of a section.
+    ///
+    /// Pages are independent LZ77 streams, so with the `parallel` feature
+    /// they are compressed on the rayon thread pool. The result is in page
+    /// order either way.
+    fn compress_pages(
+        &self,
+        buffer: &[u8],
+        decompressed_size: usize,
+        pages: &[(usize, usize)],
+        is_compressed: bool,
+    ) -> Result<Vec<Vec<u8>>> {
+        let compress = |&(offset, total_size): &(usize, usize)| {
+            Self::apply_compression(
+                &self.compressor,
+                buffer,
+                decompressed_size,
+                offset,
+                total_size,
+                is_compressed,
+            )
+        };
+
+        #[cfg(feature = "parallel")]
+        {
+            use rayon::prelude::*;
+            if is_compressed && pages.len() > 1 {
+                return pages.par_iter().map(compress).collect();
+            }
+        }
+
+        pages.iter().map(compress).collect()
+    }
+
+    /// Create a local section page: write page header + compressed data.
     fn create_local_section(
         &mut self,
         descriptor: &mut DwgSectionDescriptor,
-        buffer: &[u8],
-        decompressed_size: usize,
+        compressed_data: &[u8],
         offset: usize,
         total_size: usize,
-        is_compressed: bool,
-    ) -> Result<()> {
-        let compressed_data = self.apply_compression(
-            buffer,
-            decompressed_size,
-            offset,
-            total_size,
-            is_compressed,
-        )?;
-
+    ) {
         self.write_magic_number();
 
         let position = self.output.len();
@@ -143,7 +167,7 @@ impl DwgFileHeaderWriterAC18 {
         local_map.page_number = self.local_sections.len() as i32 + 1;
         local_map.oda_size = checksum::adler32(
             0,
-            &compressed_data,
+            compressed_data,
             0,
             compressed_data.len(),
         ) as u64;
@@ -179,7 +203,7 @@ impl DwgFileHeaderWriterAC18 {
 
         // Write page header + compressed data
         self.output.extend_from_slice(&checksum_buf);
-        self.output.extend_from_slice(&compressed_data);
+        self.output.extend_from_slice(compressed_data);
 
         // Write compression padding (always, to ensure 32-byte alignment
         // so that page sizes in the page map don't drift from magic alignment bytes)
@@ -194,8 +218,6 @@ impl DwgFileHeaderWriterAC18 {
         local_map.size = (self.output.len() - position) as u64;
         descriptor.local_sections.push(local_map.clone());
         self.local_sections.push(local_map);
-
-        Ok(())
     }
 
     /// Build a 32-byte data section page header.
@@ -270,8 +292,10 @@ impl DwgFileHeaderWriterAC18 {
 
     /// Write section descriptors (the section map).
     fn write_descriptors(&mut self) -> Result<()> {
-        let descriptors: Vec<DwgSectionDescriptor> =
+        // In the order the sections were added
+        let mut descriptors: Vec<DwgSectionDescriptor> =
             self.file_header.descriptors.values().cloned().collect();
+        descriptors.sort_by_key(|desc| desc.section_id);
 
         let mut stream = Vec::new();
 
@@ -593,29 +617,19 @@ impl IDwgFileHeaderWriter for DwgFileHeaderWriterAC18 {
 
         let n_local_sections = data.len() / decomp_size;
 
-        let mut offset = 0usize;
-        for _ in 0..n_local_sections {
-            self.create_local_section(
-                &mut descriptor,
-                &data,
-                decomp_size,
-                offset,
-                decomp_size,
-                is_compressed,
-            )?;
-            offset += decomp_size;
-        }
+        let mut pages: Vec<(usize, usize)> = (0..n_local_sections)
+            .map(|i| (i * decomp_size, decomp_size))
+            .collect();
 
+        let offset = n_local_sections * decomp_size;
         let spare_bytes = data.len() % decomp_size;
         if spare_bytes > 0 && !Self::check_empty_bytes(&data, offset, spare_bytes) {
-            self.create_local_section(
-                &mut descriptor,
-                &data,
-                decomp_size,
-                offset,
-                spare_bytes,
-                is_compressed,
-            )?;
+            pages.push((offset, spare_bytes));
+        }
+
+        let compressed = self.compress_pages(&data, decomp_size, &pages, is_compressed)?;
+        for ((offset, total_size), page) in pages.into_iter().zip(&compressed) {
+            self.create_local_section(&mut descriptor, page, offset, total_size);
         }
 
         self.file_header.add_descriptor(descriptor);
@@ -639,3 +653,26 @@ impl IDwgFileHeaderWriter for DwgFileHeaderWriterAC18 {
         Ok(std::mem::take(&mut self.output))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compress_pages_keeps_page_order() {
+        let writer = DwgFileHeaderWriterAC18::new(DxfVersion::AC1018, "AC1018", 30, 0);
+        let data: Vec<u8> = (0..0x7400 * 5 + 100).map(|i| (i % 251) as u8 ^ (i / 0x7400) as u8).collect();
+        let pages: Vec<(usize, usize)> = (0..6)
+            .map(|i| (i * 0x7400, (data.len() - i * 0x7400).min(0x7400)))
+            .collect();
+
+        let compressed = writer.compress_pages(&data, 0x7400, &pages, true).unwrap();
+        for (&(offset, size), page) in pages.iter().zip(&compressed) {
+            let expected = DwgFileHeaderWriterAC18::apply_compression(
+                &writer.compressor, &data, 0x7400, offset, size, true,
+            )
+            .unwrap();
+            assert_eq!(page, &expected);
+        }
+    }
+}
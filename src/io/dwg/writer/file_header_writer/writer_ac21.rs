@@ -319,7 +319,9 @@ impl DwgFileHeaderWriterAC21 {
         // Build the section map data.
         let mut map_data = Vec::new();
 
-        let descriptors: Vec<_> = self.file_header.descriptors.values().cloned().collect();
+        // In the order the sections were added
+        let mut descriptors: Vec<_> = self.file_header.descriptors.values().cloned().collect();
+        descriptors.sort_by_key(|desc| desc.section_id);
         let num_sections = descriptors.len();
 
         // AC21 section map header: number of sections as u64.
@@ -7,10 +7,9 @@
 
 use crate::error::Result;
 use crate::io::dwg::crc;
+use crate::io::dwg::handle_offsets::HandleOffsetTable;
 use crate::types::DxfVersion;
 
-use std::collections::BTreeMap;
-
 /// Writer for the DWG handle-to-offset object map section.
 #[allow(dead_code)]
 pub struct DwgHandleWriter {
@@ -29,7 +28,7 @@ impl DwgHandleWriter {
     /// `section_offset` is subtracted for R18+ (relative offsets); 0 for earlier.
     pub fn write(
         &self,
-        handle_map: &BTreeMap<u64, i64>,
+        handle_map: &HandleOffsetTable,
         section_offset: i32,
     ) -> Result<Vec<u8>> {
         let mut output = Vec::new();
@@ -43,7 +42,7 @@ impl DwgHandleWriter {
         output.push(0);
         output.push(0);
 
-        for (&handle, &offset) in handle_map {
+        for (handle, offset) in handle_map {
             let handle_off = handle - prev_handle;
             let loc = offset + section_offset as i64;
             let loc_diff = loc - prev_loc;
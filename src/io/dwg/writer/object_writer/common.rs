@@ -53,7 +53,8 @@ impl DwgObjectWriter {
         self.objects_stream.push((crc_val >> 8) as u8);
 
         // Record handle → offset
-        self.handle_map.insert(handle, position);
+        self.handle_offsets.push((handle, position));
+        self.max_handle = self.max_handle.max(Some(handle));
     }
 
     /// Write a modular short (MS) to the output.
@@ -176,8 +177,7 @@ impl DwgObjectWriter {
 
         // R13-R2000: prev/next entity handles (linked list)
         if !self.sio.r2004_plus {
-            // no_links = true (no prev/next for simplicity)
-            writer.write_bit(true)?;
+            self.write_entity_links(writer)?;
         }
 
         // Color + transparency
@@ -225,6 +225,24 @@ impl DwgObjectWriter {
         Ok(())
     }
 
+    /// Write the no-links flag and, for an entity of a block's entity chain,
+    /// its previous and next entity handles.
+    ///
+    /// The links set by `write_block_contents` apply to the first entity
+    /// written after them, the chain member itself; the vertices, attributes
+    /// and SEQEND written along with it are not part of the chain.
+    fn write_entity_links(&mut self, writer: &mut dyn IDwgStreamWriter) -> Result<()> {
+        match self.entity_links.take() {
+            Some((prev, next)) => {
+                writer.write_bit(false)?;
+                writer.handle_reference_typed(DwgReferenceType::SoftPointer, prev)?;
+                writer.handle_reference_typed(DwgReferenceType::SoftPointer, next)?;
+            }
+            None => writer.write_bit(true)?,
+        }
+        Ok(())
+    }
+
     // -----------------------------------------------------------------------
     // write_common_non_entity_data
     // -----------------------------------------------------------------------
@@ -267,7 +285,7 @@ impl DwgObjectWriter {
 
         // Reactor handles
         for r in reactors {
-            writer.handle_reference_typed(DwgReferenceType::HardPointer, r.value())?;
+            writer.handle_reference_typed(DwgReferenceType::SoftPointer, r.value())?;
         }
 
         // XDictionary handle (hard owner)
@@ -306,7 +324,7 @@ impl DwgObjectWriter {
 
         // Reactor handles
         for r in &common.reactors {
-            writer.handle_reference_typed(DwgReferenceType::HardPointer, r.value())?;
+            writer.handle_reference_typed(DwgReferenceType::SoftPointer, r.value())?;
         }
 
         // XDictionary handle
@@ -412,7 +430,7 @@ impl DwgObjectWriter {
             writer.save_position_for_size()?;
         }
 
-        writer.handle_reference(handle)?;
+        writer.handle_reference_on_main(handle)?;
 
         // EED — empty
         writer.write_bit_short(0)?;
@@ -440,7 +458,7 @@ impl DwgObjectWriter {
 
         // Reactor handles
         for r in reactors {
-            writer.handle_reference_typed(DwgReferenceType::HardPointer, r.value())?;
+            writer.handle_reference_typed(DwgReferenceType::SoftPointer, r.value())?;
         }
 
         // XDictionary handle (hard owner)
@@ -514,7 +532,7 @@ impl DwgObjectWriter {
         }
 
         if !self.sio.r2004_plus {
-            writer.write_bit(true)?; // no_links
+            self.write_entity_links(writer)?;
         }
 
         writer.write_en_color(common.color, common.transparency, false)?;
@@ -14,16 +14,17 @@ mod write_entities;
 mod write_objects;
 mod write_tables;
 
-use std::collections::{BTreeMap, HashMap};
+use std::collections::HashMap;
 use std::io::SeekFrom;
 
 use crate::document::CadDocument;
 use crate::entities::{Block, BlockEnd};
 use crate::error::Result;
+use crate::io::dwg::handle_offsets::HandleOffsetTable;
 use crate::io::dwg::section_io::SectionIO;
 use crate::io::dwg::writer::merged_writer::{DwgMergedStreamWriter, DwgMergedStreamWriterAC14};
 use crate::io::dwg::writer::stream_writer::IDwgStreamWriter;
-use crate::objects::ObjectType;
+use crate::objects::{Dictionary, DictionaryWithDefault, ObjectType};
 use crate::tables::TableEntry;
 use crate::types::{DxfVersion, Handle, Vector3};
 
@@ -36,10 +37,15 @@ pub struct DwgObjectWriter {
     pub(super) sio: SectionIO,
     /// Accumulated objects data (MS + payload + CRC for each object).
     pub(super) objects_stream: Vec<u8>,
-    /// Map of handle → byte offset within `objects_stream`.
-    pub(super) handle_map: BTreeMap<u64, i64>,
+    /// Handle → byte offset within `objects_stream`, in write order.
+    pub(super) handle_offsets: Vec<(u64, i64)>,
+    /// Highest handle written so far.
+    pub(super) max_handle: Option<u64>,
     /// Bit-size of the last handle stream (for R2010+ MC encoding).
     pub(super) last_handle_size_bits: i64,
+    /// R13-R2000: previous and next entity of the block entity chain, for
+    /// the entity about to be written.
+    pub(super) entity_links: Option<(u64, u64)>,
 
     // Well-known handles -------------------------------------------------
     pub(super) model_space_handle: u64,
@@ -130,8 +136,10 @@ impl DwgObjectWriter {
         DwgObjectWriter {
             sio,
             objects_stream: Vec::with_capacity(64 * 1024),
-            handle_map: BTreeMap::new(),
+            handle_offsets: Vec::new(),
+            max_handle: None,
             last_handle_size_bits: 0,
+            entity_links: None,
             model_space_handle,
             paper_space_handle,
             default_layer_handle,
@@ -151,8 +159,8 @@ impl DwgObjectWriter {
 
     /// Write all objects from the document and return:
     ///   - the objects section data (`Vec<u8>`)
-    ///   - the handle → offset map (`BTreeMap<u64, i64>`)
-    pub fn write(mut self, doc: &CadDocument) -> Result<(Vec<u8>, BTreeMap<u64, i64>)> {
+    ///   - the handle → offset table
+    pub fn write(mut self, doc: &CadDocument) -> Result<(Vec<u8>, HandleOffsetTable)> {
         // R2004+: write 0x0DCA magic prefix (matches ACadSharp)
         if self.sio.r2004_plus {
             self.objects_stream
@@ -182,7 +190,7 @@ impl DwgObjectWriter {
         // 3. Non-graphical objects ------------------------------------------
         self.write_nongraphical_objects(doc)?;
 
-        Ok((self.objects_stream, self.handle_map))
+        Ok((self.objects_stream, HandleOffsetTable::from_unsorted(self.handle_offsets)))
     }
 
     // -----------------------------------------------------------------------
@@ -281,19 +289,22 @@ impl DwgObjectWriter {
         // Block record entries (headers) — written here to match ACadSharp
         let block_ctrl = hdr.block_control_handle.value();
         let blocks: Vec<_> = doc.block_records.iter().cloned().collect();
-        let standalone_entities: Vec<_> = doc.entities().cloned().collect();
+        let standalone_entities: Vec<_> = doc.sorted_entities().cloned().collect();
         for block in &blocks {
             let is_model_space = block.is_model_space();
-            let mut entity_handles: Vec<u64> = block
-                .entities
-                .iter()
-                .map(|e| e.common().handle.value())
-                .collect();
+            let mut entities: Vec<_> = block.entities.iter().collect();
             if is_model_space {
-                for e in &standalone_entities {
-                    entity_handles.push(e.common().handle.value());
-                }
+                entities.extend(&standalone_entities);
             }
+            let entity_handles: Vec<u64> = entities
+                .into_iter()
+                .filter(|e| Self::is_written(e))
+                .map(|e| e.common().handle.value())
+                .collect();
+            // Only while the dictionary is written with the objects
+            let xdictionary = block
+                .xdictionary_handle
+                .filter(|h| matches!(doc.objects.get(h), Some(ObjectType::Dictionary(_))));
             self.write_block_header(
                 block,
                 block_ctrl,
@@ -301,6 +312,7 @@ impl DwgObjectWriter {
                 block.block_entity_handle.value(),
                 block.block_end_handle.value(),
                 block.layout.value(),
+                xdictionary,
             )?;
         }
 
@@ -317,7 +329,10 @@ impl DwgObjectWriter {
         let layer_ctrl = hdr.layer_control_handle.value();
         let layers: Vec<_> = doc.layers.iter().cloned().collect();
         for layer in &layers {
-            self.write_layer(layer, layer_ctrl)?;
+            let xdictionary = layer
+                .xdictionary_handle
+                .filter(|h| matches!(doc.objects.get(h), Some(ObjectType::Dictionary(_))));
+            self.write_layer(layer, layer_ctrl, xdictionary)?;
         }
 
         // ── STYLE_CONTROL + entries ──────────────────────────────────
@@ -452,7 +467,7 @@ impl DwgObjectWriter {
         let blocks: Vec<_> = doc.block_records.iter().cloned().collect();
 
         // Collect standalone entity handles that belong to model space
-        let standalone_entities: Vec<_> = doc.entities().cloned().collect();
+        let standalone_entities: Vec<_> = doc.sorted_entities().cloned().collect();
 
         for block in &blocks {
             let owner_handle = block.handle.value();
@@ -466,16 +481,25 @@ impl DwgObjectWriter {
                 self.write_block(&blk, owner_handle)?;
             }
 
-            // Write owned entities from the block record
-            for entity in &block.entities {
-                self.write_entity(entity, owner_handle)?;
+            // Write owned entities from the block record, then standalone
+            // entities into model space
+            let mut entities: Vec<_> = block.entities.iter().collect();
+            if is_model_space {
+                entities.extend(&standalone_entities);
             }
+            entities.retain(|e| Self::is_written(e));
 
-            // Write standalone entities into model space
-            if is_model_space {
-                for entity in &standalone_entities {
-                    self.write_entity(entity, owner_handle)?;
+            // Before R2004 the block header only points to the first and last
+            // entity; each entity links to its neighbours
+            let chain: Vec<u64> = entities.iter().map(|e| e.common().handle.value()).collect();
+            for (i, entity) in entities.into_iter().enumerate() {
+                if !self.sio.r2004_plus {
+                    let prev = if i > 0 { chain[i - 1] } else { 0 };
+                    let next = chain.get(i + 1).copied().unwrap_or(0);
+                    self.entity_links = Some((prev, next));
                 }
+                self.write_entity(entity, owner_handle)?;
+                self.entity_links = None;
             }
 
             // Write ENDBLK entity (end marker)
@@ -494,195 +518,116 @@ impl DwgObjectWriter {
     ///
     /// Mirrors ACadSharp's `writeObjects()`: a BFS queue seeded with the root
     /// dictionary. Writing a dictionary enqueues its children, so nested
-    /// dictionaries cascade breadth-first.
+    /// dictionaries cascade breadth-first. Objects the root does not reach,
+    /// such as extension dictionaries and their contents, are written the
+    /// same way afterwards, in handle order.
+    ///
+    /// Owners come from the ownership graph, so an object whose owner handle
+    /// was never set is written under the dictionary listing it rather than
+    /// under handle 0.
     fn write_nongraphical_objects(&mut self, doc: &CadDocument) -> Result<()> {
-        use std::collections::VecDeque;
+        use std::collections::{HashSet, VecDeque};
 
+        let owners = doc.ownership_graph().resolved_owners();
         let root_handle = doc.header.named_objects_dict_handle;
-        if root_handle.is_null() {
-            return Ok(());
-        }
 
-        // BFS queue of handles to write
+        let mut remaining: Vec<Handle> = doc.objects.keys().copied().collect();
+        remaining.sort();
+        let mut written: HashSet<Handle> = HashSet::new();
         let mut queue: VecDeque<Handle> = VecDeque::new();
-        let mut written: std::collections::HashSet<Handle> = std::collections::HashSet::new();
 
-        // Seed with root dictionary
-        queue.push_back(root_handle);
-
-        while let Some(obj_handle) = queue.pop_front() {
-            if obj_handle.is_null() || written.contains(&obj_handle) {
-                continue;
-            }
-            written.insert(obj_handle);
-
-            let obj = match doc.objects.get(&obj_handle) {
-                Some(o) => o.clone(),
-                None => continue,
-            };
-
-            // Write the object and enqueue children from dictionaries
-            match &obj {
-                ObjectType::Dictionary(dict) => {
-                    let owner_h = dict.owner.value();
-                    self.write_dictionary(dict, owner_h)?;
-                    // Enqueue all dictionary entries (BFS expansion)
-                    for (_, entry_handle) in &dict.entries {
-                        if !written.contains(entry_handle) {
-                            queue.push_back(*entry_handle);
-                        }
-                    }
-                }
-                ObjectType::DictionaryWithDefault(dict) => {
-                    let owner_h = dict.owner.value();
-                    self.write_dictionary_with_default(dict, owner_h)?;
-                    for (_, entry_handle) in &dict.entries {
-                        if !written.contains(entry_handle) {
-                            queue.push_back(*entry_handle);
-                        }
-                    }
-                }
-                ObjectType::DictionaryVariable(dv) => {
-                    let owner_h = dv.owner_handle.value();
-                    self.write_dictionary_variable(dv, owner_h)?;
-                }
-                ObjectType::XRecord(xr) => {
-                    let owner_h = xr.owner.value();
-                    self.write_xrecord(xr, owner_h)?;
-                }
-                ObjectType::PlotSettings(ps) => {
-                    let owner_h = ps.owner.value();
-                    self.write_plot_settings_obj(ps, owner_h)?;
-                }
-                ObjectType::Layout(layout) => {
-                    let owner_h = layout.owner.value();
-                    self.write_layout(layout, owner_h)?;
-                }
-                ObjectType::Group(group) => {
-                    let owner_h = group.owner.value();
-                    self.write_group(group, owner_h)?;
-                }
-                ObjectType::MLineStyle(style) => {
-                    let owner_h = style.owner.value();
-                    self.write_mline_style(style, owner_h)?;
-                }
-                ObjectType::ImageDefinition(imgdef) => {
-                    let owner_h = imgdef.owner.value();
-                    self.write_image_definition(imgdef, owner_h)?;
-                }
-                ObjectType::ImageDefinitionReactor(reactor) => {
-                    let owner_h = reactor.owner.value();
-                    self.write_image_definition_reactor(reactor, owner_h)?;
-                }
-                ObjectType::MultiLeaderStyle(style) => {
-                    let owner_h = style.owner_handle.value();
-                    self.write_mleader_style(style, owner_h)?;
-                }
-                ObjectType::Scale(scale) => {
-                    let owner_h = scale.owner_handle.value();
-                    self.write_scale(scale, owner_h)?;
-                }
-                ObjectType::SortEntitiesTable(table) => {
-                    let owner_h = table.owner_handle.value();
-                    self.write_sort_entities_table(table, owner_h)?;
-                }
-                ObjectType::RasterVariables(rv) => {
-                    let owner_h = rv.owner.value();
-                    self.write_raster_variables(rv, owner_h)?;
-                }
-                ObjectType::BookColor(bc) => {
-                    let owner_h = bc.owner.value();
-                    self.write_book_color(bc, owner_h)?;
-                }
-                ObjectType::PlaceHolder(ph) => {
-                    let owner_h = ph.owner.value();
-                    self.write_placeholder(ph, owner_h)?;
-                }
-                ObjectType::WipeoutVariables(wv) => {
-                    let owner_h = wv.owner.value();
-                    self.write_wipeout_variables(wv, owner_h)?;
-                }
-                // Other object types — skip for now
-                _ => {}
+        // Seed with root dictionary, then everything it does not reach
+        for root in std::iter::once(root_handle).chain(remaining) {
+            queue.push_back(root);
+            while let Some(obj_handle) = queue.pop_front() {
+                if obj_handle.is_null() || !written.insert(obj_handle) {
+                    continue;
+                }
+                let Some(obj) = doc.objects.get(&obj_handle) else {
+                    continue;
+                };
+
+                // Enqueue all dictionary entries (BFS expansion)
+                if let ObjectType::Dictionary(Dictionary { entries, .. })
+                | ObjectType::DictionaryWithDefault(DictionaryWithDefault { entries, .. }) = obj
+                {
+                    queue.extend(entries.iter().map(|(_, h)| *h).filter(|h| !written.contains(h)));
+                }
+
+                let owner = if obj_handle == root_handle {
+                    0
+                } else {
+                    owners.get(&obj_handle).copied().unwrap_or(Handle::NULL).value()
+                };
+                self.write_object(obj, owner)?;
             }
         }
 
-        // Write any remaining objects not reachable from root dictionary
-        for (handle, obj) in &doc.objects {
-            if written.contains(handle) {
-                continue;
+        Ok(())
+    }
+
+    /// Write one non-graphical object under `owner`
+    fn write_object(&mut self, obj: &ObjectType, owner: u64) -> Result<()> {
+        match obj {
+            ObjectType::Dictionary(dict) => {
+                self.write_dictionary(dict, owner)?;
             }
-            match obj {
-                ObjectType::Dictionary(dict) => {
-                    let owner_h = dict.owner.value();
-                    self.write_dictionary(dict, owner_h)?;
-                }
-                ObjectType::DictionaryWithDefault(dict) => {
-                    let owner_h = dict.owner.value();
-                    self.write_dictionary_with_default(dict, owner_h)?;
-                }
-                ObjectType::DictionaryVariable(dv) => {
-                    let owner_h = dv.owner_handle.value();
-                    self.write_dictionary_variable(dv, owner_h)?;
-                }
-                ObjectType::XRecord(xr) => {
-                    let owner_h = xr.owner.value();
-                    self.write_xrecord(xr, owner_h)?;
-                }
-                ObjectType::PlotSettings(ps) => {
-                    let owner_h = ps.owner.value();
-                    self.write_plot_settings_obj(ps, owner_h)?;
-                }
-                ObjectType::Layout(layout) => {
-                    let owner_h = layout.owner.value();
-                    self.write_layout(layout, owner_h)?;
-                }
-                ObjectType::Group(group) => {
-                    let owner_h = group.owner.value();
-                    self.write_group(group, owner_h)?;
-                }
-                ObjectType::MLineStyle(style) => {
-                    let owner_h = style.owner.value();
-                    self.write_mline_style(style, owner_h)?;
-                }
-                ObjectType::ImageDefinition(imgdef) => {
-                    let owner_h = imgdef.owner.value();
-                    self.write_image_definition(imgdef, owner_h)?;
-                }
-                ObjectType::ImageDefinitionReactor(reactor) => {
-                    let owner_h = reactor.owner.value();
-                    self.write_image_definition_reactor(reactor, owner_h)?;
-                }
-                ObjectType::MultiLeaderStyle(style) => {
-                    let owner_h = style.owner_handle.value();
-                    self.write_mleader_style(style, owner_h)?;
-                }
-                ObjectType::Scale(scale) => {
-                    let owner_h = scale.owner_handle.value();
-                    self.write_scale(scale, owner_h)?;
-                }
-                ObjectType::SortEntitiesTable(table) => {
-                    let owner_h = table.owner_handle.value();
-                    self.write_sort_entities_table(table, owner_h)?;
-                }
-                ObjectType::RasterVariables(rv) => {
-                    let owner_h = rv.owner.value();
-                    self.write_raster_variables(rv, owner_h)?;
-                }
-                ObjectType::BookColor(bc) => {
-                    let owner_h = bc.owner.value();
-                    self.write_book_color(bc, owner_h)?;
-                }
-                ObjectType::PlaceHolder(ph) => {
-                    let owner_h = ph.owner.value();
-                    self.write_placeholder(ph, owner_h)?;
-                }
-                ObjectType::WipeoutVariables(wv) => {
-                    let owner_h = wv.owner.value();
-                    self.write_wipeout_variables(wv, owner_h)?;
-                }
-                _ => {}
+            ObjectType::DictionaryWithDefault(dict) => {
+                self.write_dictionary_with_default(dict, owner)?;
+            }
+            ObjectType::DictionaryVariable(dv) => {
+                self.write_dictionary_variable(dv, owner)?;
+            }
+            ObjectType::XRecord(xr) => {
+                self.write_xrecord(xr, owner)?;
+            }
+            ObjectType::PlotSettings(ps) => {
+                self.write_plot_settings_obj(ps, owner)?;
+            }
+            ObjectType::Layout(layout) => {
+                self.write_layout(layout, owner)?;
+            }
+            ObjectType::Group(group) => {
+                self.write_group(group, owner)?;
+            }
+            ObjectType::MLineStyle(style) => {
+                self.write_mline_style(style, owner)?;
+            }
+            ObjectType::ImageDefinition(imgdef) => {
+                self.write_image_definition(imgdef, owner)?;
+            }
+            ObjectType::ImageDefinitionReactor(reactor) => {
+                self.write_image_definition_reactor(reactor, owner)?;
+            }
+            ObjectType::MultiLeaderStyle(style) => {
+                self.write_mleader_style(style, owner)?;
+            }
+            ObjectType::Scale(scale) => {
+                self.write_scale(scale, owner)?;
+            }
+            ObjectType::DimAssoc(assoc) => {
+                self.write_dim_assoc(assoc, owner)?;
+            }
+            ObjectType::SortEntitiesTable(table) => {
+                self.write_sort_entities_table(table, owner)?;
+            }
+            ObjectType::RasterVariables(rv) => {
+                self.write_raster_variables(rv, owner)?;
+            }
+            ObjectType::BookColor(bc) => {
+                self.write_book_color(bc, owner)?;
+            }
+            ObjectType::PlaceHolder(ph) => {
+                self.write_placeholder(ph, owner)?;
+            }
+            ObjectType::WipeoutVariables(wv) => {
+                self.write_wipeout_variables(wv, owner)?;
+            }
+            ObjectType::UnderlayDefinition(def) => {
+                self.write_underlay_definition(def, owner)?;
             }
+            // Other object types — skip for now
+            _ => {}
         }
 
         Ok(())
@@ -69,6 +69,18 @@ impl DwgObjectWriter {
         }
     }
 
+    /// Whether [`Self::write_entity`] writes `entity` rather than skipping it
+    pub(super) fn is_written(entity: &EntityType) -> bool {
+        !matches!(
+            entity,
+            EntityType::Mesh(_)
+                | EntityType::Polyline(_)
+                | EntityType::Table(_)
+                | EntityType::Underlay(_)
+                | EntityType::Unknown(_)
+        )
+    }
+
     // -----------------------------------------------------------------------
     // Composite polyline writers — write parent + child vertices + SEQEND
     // -----------------------------------------------------------------------
@@ -76,11 +88,7 @@ impl DwgObjectWriter {
     /// Assign unique handles to child objects if they don't have one yet.
     /// Returns the next available handle value.
     fn next_available_handle(&self) -> u64 {
-        self.handle_map
-            .keys()
-            .last()
-            .map(|&h| h + 1)
-            .unwrap_or(0x100)
+        self.max_handle.map(|h| h + 1).unwrap_or(0x100)
     }
 
     /// Write a complete Polyline2D: parent polyline + Vertex2D children + SEQEND.
@@ -96,9 +104,8 @@ impl DwgObjectWriter {
         let mut vertex_handles = Vec::with_capacity(polyline.vertices.len());
         let mut vertex_commons = Vec::with_capacity(polyline.vertices.len());
 
-        for _v in &polyline.vertices {
-            let h = next_h;
-            next_h += 1;
+        for v in &polyline.vertices {
+            let h = if v.handle.value() != 0 { v.handle.value() } else { let h = next_h; next_h += 1; h };
             vertex_handles.push(h);
             let mut vc = EntityCommon::new();
             vc.handle = Handle::new(h);
@@ -107,7 +114,7 @@ impl DwgObjectWriter {
             vertex_commons.push(vc);
         }
 
-        let seqend_h = next_h;
+        let seqend_h = polyline.seqend_handle.map_or(next_h, |h| h.value());
         let mut seqend_common = EntityCommon::new();
         seqend_common.handle = Handle::new(seqend_h);
         seqend_common.layer = polyline.common.layer.clone();
@@ -149,7 +156,7 @@ impl DwgObjectWriter {
             vertex_commons.push(vc);
         }
 
-        let seqend_h = next_h;
+        let seqend_h = polyline.seqend_handle.map_or(next_h, |h| h.value());
         let mut seqend_common = EntityCommon::new();
         seqend_common.handle = Handle::new(seqend_h);
         seqend_common.layer = polyline.common.layer.clone();
@@ -767,6 +774,20 @@ impl DwgObjectWriter {
         if self.sio.r2000_plus {
             writer.write_bit_short(0)?; // line spacing style (0 = at least)
             writer.write_bit_double(mtext.line_spacing_factor)?;
+            writer.write_bit(false)?; // unknown
+        }
+
+        // Background fill (R2004+ only)
+        if self.sio.r2004_plus {
+            let fill = mtext.background_fill;
+            writer.write_bit_long(fill.bits())?;
+            if fill.contains(BackgroundFillFlags::USE_BACKGROUND_FILL)
+                || (self.sio.r2018_plus && fill.contains(BackgroundFillFlags::TEXT_FRAME))
+            {
+                writer.write_bit_double(mtext.background_scale)?;
+                writer.write_cm_color(mtext.background_color)?;
+                writer.write_bit_long(mtext.background_transparency.to_alpha_value())?;
+            }
         }
 
         // Style handle
@@ -775,6 +796,38 @@ impl DwgObjectWriter {
             self.resolve_textstyle_handle(&mtext.style),
         )?;
 
+        // Columns (R2018+ only), after a copy of the text geometry
+        if self.sio.r2018_plus {
+            writer.write_bit(true)?; // not annotative
+            writer.write_bit_short(4)?; // version
+            writer.write_bit(true)?; // default flag
+            writer.handle_reference_typed(DwgReferenceType::HardPointer, 0)?; // registered application
+            writer.write_bit_long(mtext.attachment_point as i32)?;
+            writer.write_3bit_double(direction)?;
+            writer.write_3bit_double(mtext.insertion_point)?;
+            writer.write_bit_double(mtext.rectangle_width)?;
+            writer.write_bit_double(mtext.rectangle_height.unwrap_or(0.0))?;
+            writer.write_bit_double(0.0)?; // extents width
+            writer.write_bit_double(0.0)?; // extents height
+
+            let columns = &mtext.columns;
+            writer.write_bit_short(columns.column_type as i16)?;
+            if columns.column_type != ColumnType::NoColumns {
+                let manual_heights = !columns.auto_height && columns.column_type == ColumnType::Dynamic;
+                let count = if manual_heights { columns.heights.len() as i32 } else { columns.count };
+                writer.write_bit_long(count)?;
+                writer.write_bit_double(columns.width)?;
+                writer.write_bit_double(columns.gutter)?;
+                writer.write_bit(columns.auto_height)?;
+                writer.write_bit(columns.flow_reversed)?;
+                if manual_heights {
+                    for height in &columns.heights {
+                        writer.write_bit_double(*height)?;
+                    }
+                }
+            }
+        }
+
         writer.write_spear_shift()?;
         self.finalize_entity(writer, mtext.common.handle.value());
         Ok(())
@@ -1070,35 +1123,29 @@ impl DwgObjectWriter {
 
         let scenario = if !spline.fit_points.is_empty() { 2 } else { 1 };
 
-        // R2013+: flags as BL
+        // R2013+: method and closed flags, then the knot parametrization;
+        // earlier versions write the scenario itself
         if self.sio.r2013_plus {
             let mut flag_bits: i32 = 0;
-            if spline.flags.closed {
+            if scenario == 2 {
                 flag_bits |= 1;
             }
-            if spline.flags.periodic {
-                flag_bits |= 2;
-            }
-            if spline.flags.rational {
+            if spline.flags.closed || spline.flags.periodic {
                 flag_bits |= 4;
             }
-            if spline.flags.planar {
-                flag_bits |= 8;
-            }
-            if spline.flags.linear {
-                flag_bits |= 16;
-            }
             writer.write_bit_long(flag_bits)?;
             writer.write_bit_long(0)?; // knot parametrization
+        } else {
+            writer.write_bit_long(scenario)?;
         }
 
         writer.write_bit_long(spline.degree)?;
 
         if scenario == 2 {
             // Fit data
-            writer.write_bit_double(0.0)?; // fit tolerance
-            writer.write_3bit_double(Vector3::ZERO)?; // start tangent
-            writer.write_3bit_double(Vector3::ZERO)?; // end tangent
+            writer.write_bit_double(spline.fit_tolerance)?;
+            writer.write_3bit_double(spline.start_tangent)?;
+            writer.write_3bit_double(spline.end_tangent)?;
             writer.write_bit_long(spline.fit_points.len() as i32)?;
             for pt in &spline.fit_points {
                 writer.write_3bit_double(*pt)?;
@@ -1109,8 +1156,8 @@ impl DwgObjectWriter {
             writer.write_bit(spline.flags.closed)?;
             writer.write_bit(spline.flags.periodic)?;
 
-            writer.write_bit_double(1e-10)?; // knot tolerance
-            writer.write_bit_double(1e-10)?; // control point tolerance
+            writer.write_bit_double(spline.knot_tolerance)?;
+            writer.write_bit_double(spline.control_point_tolerance)?;
 
             writer.write_bit_long(spline.knots.len() as i32)?;
             writer.write_bit_long(spline.control_points.len() as i32)?;
@@ -1401,15 +1448,17 @@ impl DwgObjectWriter {
         // B: unknown bit
         writer.write_bit(false)?;
         // BS: annotation_type (creation_type)
-        writer.write_bit_short(leader.creation_type as i16)?;
+        writer.write_bit_short(leader.creation_type.to_value())?;
         // BS: path_type
-        writer.write_bit_short(leader.path_type as i16)?;
+        writer.write_bit_short(leader.path_type.to_value())?;
         // BL: number of points
         writer.write_bit_long(leader.vertices.len() as i32)?;
         // 3BD × N: vertices
         for v in &leader.vertices {
             writer.write_3bit_double(*v)?;
         }
+        // 3BD: origin of the leader plane (first vertex)
+        writer.write_3bit_double(leader.vertices.first().copied().unwrap_or(Vector3::ZERO))?;
         // 3BD: normal (extrusion)
         writer.write_3bit_double(leader.normal)?;
         // 3BD: horizontal direction
@@ -1417,39 +1466,52 @@ impl DwgObjectWriter {
         // 3BD: block offset
         writer.write_3bit_double(leader.block_offset)?;
 
-        // R14+ / R13_14 / R2000+: end_pt_proj
+        // R14+: end point projection (annotation offset)
         if self.sio.r13_14_only || self.sio.r2000_plus {
-            writer.write_3bit_double(
-                leader.vertices.last().copied().unwrap_or(Vector3::ZERO),
-            )?;
+            writer.write_3bit_double(leader.annotation_offset)?;
         }
 
-        // R2000+: dimasz (BD)
-        if self.sio.r2000_plus {
+        // R13/14 only: dimgap (BD)
+        if self.sio.r13_14_only {
             writer.write_bit_double(0.0)?;
         }
 
-        // B: hookline_on
-        writer.write_bit(leader.hookline_enabled)?;
+        // BD: text box height and width
+        writer.write_bit_double(leader.text_height)?;
+        writer.write_bit_double(leader.text_width)?;
+
+        // B: hookline on the horizontal direction
+        writer.write_bit(leader.hookline_direction == HooklineDirection::Same)?;
         // B: arrow_head_on
         writer.write_bit(leader.arrow_enabled)?;
 
-        // R13/14 only: arrowhead_size, text_width, text_height
         if self.sio.r13_14_only {
-            writer.write_bit_double(0.0)?; // arrowhead_size
-            writer.write_bit_double(leader.text_width)?;
-            writer.write_bit_double(leader.text_height)?;
+            writer.write_bit_short(0)?; // arrowhead type
+            writer.write_bit_double(0.0)?; // dimasz
+            writer.write_bit(false)?;
+            writer.write_bit(false)?;
+            writer.write_bit_short(0)?;
+            // BS: by-block color override
+            writer.write_bit_short(leader.override_color.index().unwrap_or(0) as i16)?;
+            // B: hookline
+            writer.write_bit(leader.hookline_enabled)?;
+            writer.write_bit(false)?;
+        } else {
+            writer.write_bit_short(0)?;
+            // B: hookline
+            writer.write_bit(leader.hookline_enabled)?;
+            writer.write_bit(false)?;
         }
 
-        // BS: color_val
-        writer.write_bit_short(0)?;
-
         // Handles: annotation handle (soft pointer), DIMSTYLE (hard pointer)
         writer.handle_reference_typed(
             DwgReferenceType::SoftPointer,
             leader.annotation_handle.value(),
         )?;
-        writer.handle_reference_typed(DwgReferenceType::HardPointer, 0)?;
+        writer.handle_reference_typed(
+            DwgReferenceType::HardPointer,
+            self.resolve_dimstyle_handle(&leader.dimension_style),
+        )?;
 
         writer.write_spear_shift()?;
         self.finalize_entity(writer, leader.common.handle.value());
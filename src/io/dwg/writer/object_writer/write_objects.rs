@@ -2,12 +2,13 @@
 //!
 //! Mirrors ACadSharp's `DwgObjectWriter.Objects.cs`.
 
+use crate::entities::UnderlayDefinition;
 use crate::error::Result;
 use crate::io::dwg::object_type::DwgObjectType;
 use crate::io::dwg::reference_type::DwgReferenceType;
 use crate::io::dwg::writer::stream_writer::IDwgStreamWriter;
 use crate::objects::{
-    BookColor, Dictionary, DictionaryVariable, DictionaryWithDefault, Group,
+    BookColor, DimAssoc, Dictionary, DictionaryVariable, DictionaryWithDefault, Group,
     ImageDefinition, ImageDefinitionReactor, Layout, MLineStyle, MultiLeaderStyle,
     PlaceHolder, PlotSettings, RasterVariables, Scale, SortEntitiesTable,
     WipeoutVariables, XRecord,
@@ -178,8 +179,8 @@ impl DwgObjectWriter {
             DwgObjectType::XRecord,
             handle,
             owner_handle,
-            &[], // reactors
-            None, // xdictionary
+            &xrecord.reactors,
+            xrecord.xdictionary_handle,
         )?;
 
         // Serialize entries to a raw byte buffer (little-endian).
@@ -215,8 +216,7 @@ impl DwgObjectWriter {
 
             match &entry.value {
                 XRecordValue::String(s) => {
-                    // Write as length-prefixed UTF-8 (codepage string in DWG)
-                    Self::write_string_to_buffer(&mut buf, s);
+                    self.write_string_to_buffer(&mut buf, s);
                 }
                 XRecordValue::Point3D(x, y, z) => {
                     buf.extend_from_slice(&x.to_le_bytes());
@@ -257,12 +257,20 @@ impl DwgObjectWriter {
         buf
     }
 
-    /// Write a string into a raw buffer (length-prefixed, codepage).
-    fn write_string_to_buffer(buf: &mut Vec<u8>, s: &str) {
-        let bytes = s.as_bytes();
-        let len = bytes.len() as u16;
-        buf.extend_from_slice(&len.to_le_bytes());
-        buf.extend_from_slice(bytes);
+    /// Write a string into a raw buffer: R2007+ the UTF-16 length and
+    /// characters, before that the byte length, code page and bytes.
+    fn write_string_to_buffer(&self, buf: &mut Vec<u8>, s: &str) {
+        if self.sio.r2007_plus {
+            let chars: Vec<u16> = s.encode_utf16().collect();
+            buf.extend_from_slice(&(chars.len() as u16).to_le_bytes());
+            chars.iter().for_each(|c| buf.extend_from_slice(&c.to_le_bytes()));
+        } else {
+            let (bytes, _, _) = encoding_rs::WINDOWS_1252.encode(s);
+            buf.extend_from_slice(&(bytes.len() as u16).to_le_bytes());
+            // ANSI_1252
+            buf.push(30);
+            buf.extend_from_slice(&bytes);
+        }
     }
 
     // -----------------------------------------------------------------------
@@ -523,8 +531,8 @@ impl DwgObjectWriter {
             DwgObjectType::Group,
             handle,
             owner_handle,
-            &[], // reactors
-            None, // xdictionary
+            &group.reactors,
+            group.xdictionary_handle,
         )?;
 
         // TV: description
@@ -637,7 +645,7 @@ impl DwgObjectWriter {
             "IMAGEDEF",
             handle,
             owner_handle,
-            &[],
+            &imgdef.reactors,
             None,
         )?;
 
@@ -666,6 +674,39 @@ impl DwgObjectWriter {
         Ok(())
     }
 
+    // -----------------------------------------------------------------------
+    // UNDERLAY DEFINITION — unlisted types ("PDFDEFINITION", "DWFDEFINITION",
+    // "DGNDEFINITION")
+    // -----------------------------------------------------------------------
+
+    pub(super) fn write_underlay_definition(
+        &mut self,
+        definition: &UnderlayDefinition,
+        owner_handle: u64,
+    ) -> Result<()> {
+        let handle = definition.handle.value();
+        let (mut writer, _) = self.create_object_writer();
+
+        self.write_common_non_entity_data_unlisted(
+            &mut *writer,
+            definition.entity_name(),
+            handle,
+            owner_handle,
+            &definition.reactors,
+            None,
+        )?;
+
+        // TV: file path
+        writer.write_variable_text(&definition.file_path)?;
+
+        // TV: page / sheet / model name
+        writer.write_variable_text(&definition.page_name)?;
+
+        writer.write_spear_shift()?;
+        self.finalize_object(writer, handle);
+        Ok(())
+    }
+
     // -----------------------------------------------------------------------
     // IMAGE DEFINITION REACTOR — unlisted type ("IMAGEDEF_REACTOR")
     // -----------------------------------------------------------------------
@@ -915,6 +956,69 @@ impl DwgObjectWriter {
         Ok(())
     }
 
+    // -----------------------------------------------------------------------
+    // DIMASSOC — unlisted type ("DIMASSOC")
+    // -----------------------------------------------------------------------
+
+    pub(super) fn write_dim_assoc(
+        &mut self,
+        assoc: &DimAssoc,
+        owner_handle: u64,
+    ) -> Result<()> {
+        let handle = assoc.handle.value();
+        let (mut writer, _) = self.create_object_writer();
+
+        self.write_common_non_entity_data_unlisted(
+            &mut *writer,
+            "DIMASSOC",
+            handle,
+            owner_handle,
+            &assoc.reactors,
+            None,
+        )?;
+
+        // H: dimension (soft pointer)
+        writer.handle_reference_typed(DwgReferenceType::SoftPointer, assoc.dimension.value())?;
+
+        // BL: associativity flags, one bit per point reference that follows
+        writer.write_bit_long(assoc.associativity())?;
+        // B: trans space flag
+        writer.write_bit(assoc.trans_space)?;
+        // RC: rotated dimension type
+        writer.write_byte(assoc.rotated_type as u8)?;
+
+        for point in assoc.points.iter().flatten() {
+            // TV: class name
+            writer.write_variable_text("AcDbOsnapPointRef")?;
+            // RC: object snap type
+            writer.write_byte(point.osnap_type.to_code() as u8)?;
+            // BS + H: main objects
+            writer.write_bit_short(point.objects.len() as i16)?;
+            for object in &point.objects {
+                writer.handle_reference_typed(DwgReferenceType::SoftPointer, object.value())?;
+            }
+            // BS: subentity type, BL: graphics system marker
+            writer.write_bit_short(point.subentity_type)?;
+            writer.write_bit_long(point.gs_marker)?;
+            // BD: geometry parameter, 3BD: snapped point
+            writer.write_bit_double(point.near_parameter)?;
+            writer.write_3bit_double(point.point)?;
+            // BS + H: intersection objects
+            writer.write_bit_short(point.intersection_objects.len() as i16)?;
+            for object in &point.intersection_objects {
+                writer.handle_reference_typed(DwgReferenceType::SoftPointer, object.value())?;
+            }
+            writer.write_bit_short(point.intersection_subentity_type)?;
+            writer.write_bit_long(point.intersection_gs_marker)?;
+            // B: has last point reference
+            writer.write_bit(point.has_last_point_ref)?;
+        }
+
+        writer.write_spear_shift()?;
+        self.finalize_object(writer, handle);
+        Ok(())
+    }
+
     // -----------------------------------------------------------------------
     // SORT ENTITIES TABLE — unlisted type ("SORTENTSTABLE")
     // -----------------------------------------------------------------------
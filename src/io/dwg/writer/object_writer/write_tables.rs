@@ -8,6 +8,7 @@ use crate::io::dwg::object_type::DwgObjectType;
 use crate::io::dwg::reference_type::DwgReferenceType;
 use crate::io::dwg::writer::stream_writer::IDwgStreamWriter;
 use crate::tables::*;
+use crate::types::Handle;
 
 use super::DwgObjectWriter;
 
@@ -149,6 +150,7 @@ impl DwgObjectWriter {
         &mut self,
         layer: &Layer,
         owner_handle: u64,
+        xdictionary: Option<Handle>,
     ) -> Result<()> {
         let handle = layer.handle.value();
         let (mut writer, _) = self.create_object_writer();
@@ -158,7 +160,7 @@ impl DwgObjectWriter {
             handle,
             owner_handle,
             &[],
-            None,
+            xdictionary,
         )?;
 
         // Name (TV)
@@ -367,6 +369,7 @@ impl DwgObjectWriter {
     // BLOCK_HEADER (BLOCK_RECORD) table entry
     // -----------------------------------------------------------------------
 
+    #[allow(clippy::too_many_arguments)]
     pub(super) fn write_block_header(
         &mut self,
         block: &BlockRecord,
@@ -375,6 +378,7 @@ impl DwgObjectWriter {
         block_entity_handle: u64,
         end_block_handle: u64,
         layout_handle: u64,
+        xdictionary: Option<Handle>,
     ) -> Result<()> {
         let handle = block.handle.value();
         let (mut writer, _) = self.create_object_writer();
@@ -384,7 +388,7 @@ impl DwgObjectWriter {
             handle,
             owner_handle,
             &[],
-            None,
+            xdictionary,
         )?;
 
         // Name (TV)
@@ -0,0 +1,474 @@
+//! DXB (binary Drawing Interchange) reader
+//!
+//! DXB is the compact opcode stream written by older AutoCAD releases
+//! (`DXBIN`) and by some CAM and plotter-driver software. A file is the
+//! sentinel `AutoCAD DXB 1.0\r\n\x1A\0` followed by items, each a one-byte
+//! opcode and a fixed set of little-endian fields, terminated by opcode 0.
+//!
+//! Field types:
+//!
+//! - `w` — 16-bit integer
+//! - `n` — 16-bit integer in integer mode, 64-bit float in float mode; all
+//!   coordinates, radii and widths, multiplied by the current scale factor
+//! - `a` — angle: 32-bit integer millionths of a degree in integer mode,
+//!   64-bit float degrees in float mode
+//! - `u` — bulge: 32-bit integer scaled by 2^24 in integer mode, 64-bit float
+//!   in float mode
+//! - `f` — 64-bit float in either mode
+//!
+//! Entity items use the ADS entity type codes (point 2, circle 3, arc 8,
+//! ...); the others start at 128. Traces become SOLID entities and
+//! polylines become LWPOLYLINEs.
+
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use crate::document::CadDocument;
+use crate::entities::{Arc, Circle, EntityType, Face3D, Line, LwPolyline, LwVertex, Point, Solid};
+use crate::error::{DxfError, Result};
+use crate::tables::{Layer, TableEntry};
+use crate::types::{Color, Vector2, Vector3};
+
+const SENTINEL: &[u8] = b"AutoCAD DXB 1.0\r\n\x1A\0";
+
+mod opcode {
+    pub const EOF: u8 = 0;
+    pub const LINE: u8 = 1;
+    pub const POINT: u8 = 2;
+    pub const CIRCLE: u8 = 3;
+    pub const ARC: u8 = 8;
+    pub const TRACE: u8 = 9;
+    pub const SOLID: u8 = 11;
+    pub const SEQEND: u8 = 17;
+    pub const POLYLINE: u8 = 19;
+    pub const VERTEX: u8 = 20;
+    pub const LINE_3D: u8 = 21;
+    pub const FACE: u8 = 22;
+    pub const SCALE_FACTOR: u8 = 128;
+    pub const NEW_LAYER: u8 = 129;
+    pub const LINE_EXTENSION: u8 = 130;
+    pub const TRACE_EXTENSION: u8 = 131;
+    pub const BLOCK_BASE: u8 = 132;
+    pub const BULGE: u8 = 133;
+    pub const WIDTH: u8 = 134;
+    pub const NUMBER_MODE: u8 = 135;
+    pub const NEW_COLOR: u8 = 136;
+}
+
+/// DXB file reader
+pub struct DxbReader {
+    data: Vec<u8>,
+}
+
+impl DxbReader {
+    /// Create a DXB reader from any reader; the stream is read into memory
+    pub fn from_reader<R: Read>(mut reader: R) -> Result<Self> {
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+        Self::from_bytes(data)
+    }
+
+    /// Create a DXB reader over the bytes of a DXB file
+    pub fn from_bytes(data: Vec<u8>) -> Result<Self> {
+        if !Self::is_dxb(&data) {
+            return Err(DxfError::InvalidFormat("Missing DXB sentinel".to_string()));
+        }
+        Ok(Self { data })
+    }
+
+    /// Create a DXB reader from a file path
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::from_reader(File::open(path)?)
+    }
+
+    /// Check if a buffer starts with the DXB sentinel
+    pub fn is_dxb(bytes: &[u8]) -> bool {
+        bytes.starts_with(SENTINEL)
+    }
+
+    /// Convert the opcode stream into a new document's model space
+    pub fn read(self) -> Result<CadDocument> {
+        let mut doc = CadDocument::new();
+        let mut state = State::new(&self.data[SENTINEL.len()..]);
+        state.run(&mut doc)?;
+        Ok(doc)
+    }
+}
+
+/// DXB geometry other than 3D faces lies in the XY plane
+fn flat(p: Vector2) -> Vector3 {
+    Vector3::new(p.x, p.y, 0.0)
+}
+
+/// Polyline being assembled between POLYLINE and SEQEND
+struct OpenPolyline {
+    polyline: LwPolyline,
+    start_width: f64,
+    end_width: f64,
+}
+
+struct State<'a> {
+    data: &'a [u8],
+    pos: usize,
+    float_mode: bool,
+    scale: f64,
+    layer: String,
+    color: Color,
+    last_point: Option<Vector2>,
+    last_trace: Option<[Vector2; 2]>,
+    polyline: Option<OpenPolyline>,
+}
+
+impl<'a> State<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self {
+            data,
+            pos: 0,
+            float_mode: false,
+            scale: 1.0,
+            layer: "0".to_string(),
+            color: Color::ByLayer,
+            last_point: None,
+            last_trace: None,
+            polyline: None,
+        }
+    }
+
+    fn run(&mut self, doc: &mut CadDocument) -> Result<()> {
+        // A missing EOF item is tolerated; the file simply ends
+        while self.pos < self.data.len() {
+            let op = self.data[self.pos];
+            let offset = self.pos;
+            self.pos += 1;
+            match op {
+                opcode::EOF => break,
+                opcode::LINE => {
+                    let (from, to) = (self.point()?, self.point()?);
+                    self.last_point = Some(to);
+                    self.add(doc, EntityType::Line(Line::from_points(flat(from), flat(to))))?;
+                }
+                opcode::LINE_3D => {
+                    let from = Vector3::new(self.number()?, self.number()?, self.number()?);
+                    let to = Vector3::new(self.number()?, self.number()?, self.number()?);
+                    self.last_point = Some(Vector2::new(to.x, to.y));
+                    self.add(doc, EntityType::Line(Line::from_points(from, to)))?;
+                }
+                opcode::LINE_EXTENSION => {
+                    let to = self.point()?;
+                    let from = self.last_point.ok_or_else(|| Self::orphan("line extension", offset))?;
+                    self.last_point = Some(to);
+                    self.add(doc, EntityType::Line(Line::from_points(flat(from), flat(to))))?;
+                }
+                opcode::POINT => {
+                    let p = self.point()?;
+                    self.add(doc, EntityType::Point(Point::at(flat(p))))?;
+                }
+                opcode::CIRCLE => {
+                    let (center, radius) = (self.point()?, self.number()?);
+                    self.add(doc, EntityType::Circle(Circle::from_center_radius(flat(center), radius)))?;
+                }
+                opcode::ARC => {
+                    let (center, radius) = (self.point()?, self.number()?);
+                    let (start, end) = (self.angle()?, self.angle()?);
+                    let arc = Arc::from_center_radius_angles(flat(center), radius, start, end);
+                    self.add(doc, EntityType::Arc(arc))?;
+                }
+                opcode::TRACE | opcode::SOLID => {
+                    let corners = [self.point()?, self.point()?, self.point()?, self.point()?];
+                    if op == opcode::TRACE {
+                        self.last_trace = Some([corners[2], corners[3]]);
+                    }
+                    self.add(doc, Self::solid(corners))?;
+                }
+                opcode::TRACE_EXTENSION => {
+                    let (third, fourth) = (self.point()?, self.point()?);
+                    let [first, second] =
+                        self.last_trace.ok_or_else(|| Self::orphan("trace extension", offset))?;
+                    self.last_trace = Some([third, fourth]);
+                    self.add(doc, Self::solid([first, second, third, fourth]))?;
+                }
+                opcode::FACE => {
+                    let mut corners = [Vector3::ZERO; 4];
+                    for corner in &mut corners {
+                        *corner = Vector3::new(self.number()?, self.number()?, self.number()?);
+                    }
+                    let [a, b, c, d] = corners;
+                    self.add(doc, EntityType::Face3D(Face3D::new(a, b, c, d)))?;
+                }
+                opcode::POLYLINE => {
+                    let closed = self.word()? != 0;
+                    self.end_polyline(doc)?;
+                    let mut polyline = LwPolyline::new();
+                    polyline.is_closed = closed;
+                    self.polyline = Some(OpenPolyline { polyline, start_width: 0.0, end_width: 0.0 });
+                }
+                opcode::VERTEX => {
+                    let p = self.point()?;
+                    let open = self.polyline.as_mut().ok_or_else(|| Self::orphan("vertex", offset))?;
+                    open.polyline.vertices.push(LwVertex {
+                        start_width: open.start_width,
+                        end_width: open.end_width,
+                        ..LwVertex::new(p)
+                    });
+                }
+                opcode::SEQEND => self.end_polyline(doc)?,
+                opcode::BULGE => {
+                    let bulge = self.bulge()?;
+                    // Applies to the segment starting at the last vertex read
+                    let vertex = self
+                        .polyline
+                        .as_mut()
+                        .and_then(|open| open.polyline.vertices.last_mut())
+                        .ok_or_else(|| Self::orphan("bulge", offset))?;
+                    vertex.bulge = bulge;
+                }
+                opcode::WIDTH => {
+                    let (start, end) = (self.number()?, self.number()?);
+                    // Applies to the vertices that follow
+                    let open = self.polyline.as_mut().ok_or_else(|| Self::orphan("width", offset))?;
+                    open.start_width = start;
+                    open.end_width = end;
+                }
+                opcode::SCALE_FACTOR => self.scale = self.double()?,
+                opcode::NUMBER_MODE => self.float_mode = self.word()? != 0,
+                opcode::NEW_COLOR => self.color = Color::from_index(self.word()?),
+                opcode::NEW_LAYER => {
+                    self.layer = self.string()?;
+                    if !doc.layers.contains(&self.layer) {
+                        let mut entry = Layer::new(&self.layer);
+                        entry.set_handle(doc.allocate_handle());
+                        doc.layers.add(entry).map_err(DxfError::Custom)?;
+                    }
+                }
+                opcode::BLOCK_BASE => {
+                    let base = self.point()?;
+                    doc.header.model_space_insertion_base = flat(base);
+                }
+                other => {
+                    return Err(DxfError::InvalidFormat(format!(
+                        "Unknown DXB item type {} at offset {}",
+                        other,
+                        SENTINEL.len() + offset
+                    )))
+                }
+            }
+        }
+        self.end_polyline(doc)
+    }
+
+    fn add(&self, doc: &mut CadDocument, mut entity: EntityType) -> Result<()> {
+        let common = entity.common_mut();
+        common.layer = self.layer.clone();
+        common.color = self.color;
+        doc.add_entity(entity)?;
+        Ok(())
+    }
+
+    fn end_polyline(&mut self, doc: &mut CadDocument) -> Result<()> {
+        match self.polyline.take() {
+            Some(open) if !open.polyline.vertices.is_empty() => self.add(doc, EntityType::LwPolyline(open.polyline)),
+            _ => Ok(()),
+        }
+    }
+
+    fn solid([a, b, c, d]: [Vector2; 4]) -> EntityType {
+        EntityType::Solid(Solid::new(flat(a), flat(b), flat(c), flat(d)))
+    }
+
+    fn orphan(item: &str, offset: usize) -> DxfError {
+        DxfError::InvalidFormat(format!("DXB {} item at offset {} has nothing to extend", item, SENTINEL.len() + offset))
+    }
+
+    fn take<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let bytes = self
+            .data
+            .get(self.pos..self.pos + N)
+            .ok_or_else(|| DxfError::InvalidFormat("Unexpected end of DXB data".to_string()))?;
+        self.pos += N;
+        Ok(bytes.try_into().unwrap())
+    }
+
+    fn word(&mut self) -> Result<i16> {
+        Ok(i16::from_le_bytes(self.take()?))
+    }
+
+    fn double(&mut self) -> Result<f64> {
+        Ok(f64::from_le_bytes(self.take()?))
+    }
+
+    fn number(&mut self) -> Result<f64> {
+        let value = if self.float_mode { self.double()? } else { self.word()? as f64 };
+        Ok(value * self.scale)
+    }
+
+    fn point(&mut self) -> Result<Vector2> {
+        Ok(Vector2::new(self.number()?, self.number()?))
+    }
+
+    /// Angle in radians
+    fn angle(&mut self) -> Result<f64> {
+        let degrees = if self.float_mode {
+            self.double()?
+        } else {
+            i32::from_le_bytes(self.take()?) as f64 / 1_000_000.0
+        };
+        Ok(degrees.to_radians())
+    }
+
+    fn bulge(&mut self) -> Result<f64> {
+        if self.float_mode {
+            self.double()
+        } else {
+            Ok(i32::from_le_bytes(self.take()?) as f64 / (1u32 << 24) as f64)
+        }
+    }
+
+    fn string(&mut self) -> Result<String> {
+        let rest = &self.data[self.pos..];
+        let len = rest
+            .iter()
+            .position(|&b| b == 0)
+            .ok_or_else(|| DxfError::InvalidFormat("Unterminated DXB layer name".to_string()))?;
+        self.pos += len + 1;
+        Ok(String::from_utf8_lossy(&rest[..len]).into_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stream(items: &[&[u8]]) -> Vec<u8> {
+        let mut data = SENTINEL.to_vec();
+        for item in items {
+            data.extend_from_slice(item);
+        }
+        data
+    }
+
+    fn words(op: u8, values: &[i16]) -> Vec<u8> {
+        let mut item = vec![op];
+        values.iter().for_each(|v| item.extend_from_slice(&v.to_le_bytes()));
+        item
+    }
+
+    fn doubles(op: u8, values: &[f64]) -> Vec<u8> {
+        let mut item = vec![op];
+        values.iter().for_each(|v| item.extend_from_slice(&v.to_le_bytes()));
+        item
+    }
+
+    #[test]
+    fn test_integer_mode_lines_and_scale() {
+        let data = stream(&[
+            &doubles(opcode::SCALE_FACTOR, &[0.5]),
+            b"\x81WALLS\0",
+            &words(opcode::NEW_COLOR, &[1]),
+            &words(opcode::LINE, &[0, 0, 10, 0]),
+            &words(opcode::LINE_EXTENSION, &[10, 20]),
+            &[opcode::EOF],
+        ]);
+        let doc = DxbReader::from_bytes(data).unwrap().read().unwrap();
+
+        let mut lines: Vec<_> = doc
+            .entities()
+            .filter_map(|e| match e {
+                EntityType::Line(l) => Some(l),
+                _ => None,
+            })
+            .collect();
+        lines.sort_by(|a, b| a.end.y.total_cmp(&b.end.y));
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[1].start, Vector3::new(5.0, 0.0, 0.0));
+        assert_eq!(lines[1].end, Vector3::new(5.0, 10.0, 0.0));
+        assert_eq!(lines[0].common.layer, "WALLS");
+        assert_eq!(lines[0].common.color, Color::from_index(1));
+        assert!(doc.layers.contains("WALLS"));
+    }
+
+    #[test]
+    fn test_float_mode_polyline_and_face() {
+        let mut bulge = vec![opcode::BULGE];
+        bulge.extend_from_slice(&1.0f64.to_le_bytes());
+        let mut face = vec![opcode::FACE];
+        for v in [0.0, 0.0, 1.0, 1.0, 0.0, 1.0, 1.0, 1.0, 2.0, 0.0, 1.0, 2.0f64] {
+            face.extend_from_slice(&v.to_le_bytes());
+        }
+        let data = stream(&[
+            &words(opcode::NUMBER_MODE, &[1]),
+            &words(opcode::POLYLINE, &[1]),
+            &doubles(opcode::WIDTH, &[0.25, 0.5]),
+            &doubles(opcode::VERTEX, &[0.0, 0.0]),
+            &bulge,
+            &doubles(opcode::VERTEX, &[4.0, 0.0]),
+            &doubles(opcode::VERTEX, &[4.0, 3.0]),
+            &[opcode::SEQEND],
+            &face,
+            &doubles(opcode::ARC, &[1.0, 1.0, 2.0, 0.0, 90.0]),
+        ]);
+        let doc = DxbReader::from_bytes(data).unwrap().read().unwrap();
+        assert_eq!(doc.entity_count(), 3);
+
+        for entity in doc.entities() {
+            match entity {
+                EntityType::LwPolyline(p) => {
+                    assert!(p.is_closed);
+                    assert_eq!(p.vertices.len(), 3);
+                    assert_eq!(p.vertices[0].bulge, 1.0);
+                    assert_eq!(p.vertices[2].end_width, 0.5);
+                }
+                EntityType::Face3D(f) => assert_eq!(f.third_corner, Vector3::new(1.0, 1.0, 2.0)),
+                EntityType::Arc(a) => assert!((a.end_angle - std::f64::consts::FRAC_PI_2).abs() < 1e-12),
+                other => panic!("unexpected {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_item_codes() {
+        // Integer mode items as DXBIN lays them out: a point, a circle, an
+        // arc from 0 to 90 degrees, a solid and a 3D line, each a raw item
+        // code followed by little-endian words (angles as millionths of a
+        // degree)
+        let mut data = SENTINEL.to_vec();
+        data.extend_from_slice(&[
+            0x02, 0x05, 0x00, 0x06, 0x00,
+            0x03, 0x0A, 0x00, 0x0A, 0x00, 0x04, 0x00,
+            0x08, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x80, 0x4A, 0x5D, 0x05,
+            0x0B, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x01, 0x00, 0x01, 0x00,
+            0x15, 0x01, 0x00, 0x02, 0x00, 0x03, 0x00, 0x04, 0x00, 0x05, 0x00, 0x06, 0x00,
+            0x00,
+        ]);
+        let doc = DxbReader::from_bytes(data).unwrap().read().unwrap();
+
+        let mut names: Vec<&str> = doc.entities().map(|e| e.as_entity().entity_type()).collect();
+        names.sort();
+        assert_eq!(names, vec!["ARC", "CIRCLE", "LINE", "POINT", "SOLID"]);
+        for entity in doc.entities() {
+            match entity {
+                EntityType::Point(p) => assert_eq!(p.location, Vector3::new(5.0, 6.0, 0.0)),
+                EntityType::Circle(c) => assert_eq!((c.center, c.radius), (Vector3::new(10.0, 10.0, 0.0), 4.0)),
+                EntityType::Arc(a) => {
+                    assert_eq!(a.radius, 2.0);
+                    assert!((a.end_angle - std::f64::consts::FRAC_PI_2).abs() < 1e-12);
+                }
+                EntityType::Solid(s) => assert_eq!(s.fourth_corner, Vector3::new(1.0, 1.0, 0.0)),
+                EntityType::Line(l) => {
+                    assert_eq!(l.start, Vector3::new(1.0, 2.0, 3.0));
+                    assert_eq!(l.end, Vector3::new(4.0, 5.0, 6.0));
+                }
+                other => panic!("unexpected {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_rejects_bad_input() {
+        assert!(DxbReader::from_bytes(b"AutoCAD Binary DXF".to_vec()).is_err());
+        let truncated = stream(&[&[opcode::LINE, 1, 0]]);
+        assert!(DxbReader::from_bytes(truncated).unwrap().read().is_err());
+        let unknown = stream(&[&[99]]);
+        assert!(DxbReader::from_bytes(unknown).unwrap().read().is_err());
+    }
+}
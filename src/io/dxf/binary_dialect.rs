@@ -0,0 +1,58 @@
+//! Binary DXF dialects
+//!
+//! Binary DXF changed its group code encoding with R13. Both dialects share
+//! the `AutoCAD Binary DXF` sentinel and encode values the same way.
+
+use crate::types::DxfVersion;
+
+/// Group code encoding of a binary DXF file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryDxfDialect {
+    /// R12 and older: 1-byte group codes; codes above 254 are written as the
+    /// byte 255 followed by the code as a 2-byte integer
+    Legacy,
+    /// R13 and later: 2-byte little-endian group codes
+    Standard,
+}
+
+impl BinaryDxfDialect {
+    /// Dialect used by a drawing version.
+    ///
+    /// `Unknown` is what documents read from R12 and older files get, so it
+    /// selects [`Self::Legacy`].
+    pub fn for_version(version: DxfVersion) -> Self {
+        if version < DxfVersion::AC1012 {
+            Self::Legacy
+        } else {
+            Self::Standard
+        }
+    }
+
+    /// Dialect of a file from the first bytes after the sentinel.
+    ///
+    /// A file starts with code 0 (`SECTION`) or 999 (a comment), which is
+    /// `00 'S'` or `FF E7 03` in the legacy dialect and `00 00 'S'` or
+    /// `E7 03` in the standard one.
+    pub(crate) fn detect(probe: [u8; 2]) -> Self {
+        match probe {
+            [0, 0] => Self::Standard,
+            [0, _] | [255, _] => Self::Legacy,
+            _ => Self::Standard,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dialect_selection() {
+        assert_eq!(BinaryDxfDialect::for_version(DxfVersion::Unknown), BinaryDxfDialect::Legacy);
+        assert_eq!(BinaryDxfDialect::for_version(DxfVersion::AC1012), BinaryDxfDialect::Standard);
+        assert_eq!(BinaryDxfDialect::detect([0, b'S']), BinaryDxfDialect::Legacy);
+        assert_eq!(BinaryDxfDialect::detect([255, 0xE7]), BinaryDxfDialect::Legacy);
+        assert_eq!(BinaryDxfDialect::detect([0, 0]), BinaryDxfDialect::Standard);
+        assert_eq!(BinaryDxfDialect::detect([0xE7, 3]), BinaryDxfDialect::Standard);
+    }
+}
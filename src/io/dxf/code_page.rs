@@ -2,6 +2,9 @@
 //!
 //! Maps DXF code page names to `encoding_rs` encodings, following the same
 //! mapping table used by ACadSharp's `CadUtils._dxfEncodingMap`.
+//!
+//! Also handles the `\U+XXXX` and `\M+nXXXX` escapes used by pre-2007 DXF
+//! files to store characters that do not fit in the drawing code page.
 
 use encoding_rs::Encoding;
 
@@ -74,6 +77,140 @@ pub fn encoding_from_code_page(code_page: &str) -> Option<&'static Encoding> {
     }
 }
 
+/// Whether `value` holds a `\U+` or `\M+` escape, in either case.
+pub(crate) fn has_unicode_escape(value: &str) -> bool {
+    value
+        .as_bytes()
+        .windows(3)
+        .any(|w| w[0] == b'\\' && matches!(w[1], b'U' | b'u' | b'M' | b'm') && w[2] == b'+')
+}
+
+/// Decode `\U+XXXX` and `\M+nXXXX` escape sequences into Unicode characters.
+///
+/// `\U+XXXX` holds a UTF-16 code unit in hexadecimal; surrogate pairs written
+/// as two consecutive escapes are combined.  `\M+nXXXX` holds a double-byte
+/// character in one of the Asian code pages identified by `n`
+/// (1 = Shift-JIS, 2 = Big5, 3 = Korean, 5 = GBK). Johab (4) has no
+/// decoder and its escapes are kept as written.
+///
+/// Malformed escapes are left untouched.
+pub fn decode_unicode_escapes(value: &str) -> String {
+    if !has_unicode_escape(value) {
+        return value.to_string();
+    }
+
+    let chars: Vec<char> = value.chars().collect();
+    let mut result = String::with_capacity(value.len());
+    let mut i = 0;
+
+    while i < chars.len() {
+        if chars[i] == '\\' && i + 2 < chars.len() && chars[i + 2] == '+' {
+            match chars[i + 1] {
+                'U' | 'u' => {
+                    if let Some(unit) = parse_hex_unit(&chars, i + 3) {
+                        // High surrogate followed by an escaped low surrogate
+                        if (0xD800..0xDC00).contains(&unit) {
+                            let next = i + 7;
+                            if next + 2 < chars.len()
+                                && chars[next] == '\\'
+                                && matches!(chars[next + 1], 'U' | 'u')
+                                && chars[next + 2] == '+'
+                            {
+                                if let Some(low) = parse_hex_unit(&chars, next + 3) {
+                                    if let Some(ch) = char::decode_utf16([unit, low])
+                                        .next()
+                                        .and_then(|r| r.ok())
+                                    {
+                                        result.push(ch);
+                                        i = next + 7;
+                                        continue;
+                                    }
+                                }
+                            }
+                        } else if let Some(ch) = char::from_u32(unit as u32) {
+                            result.push(ch);
+                            i += 7;
+                            continue;
+                        }
+                    }
+                }
+                'M' | 'm' => {
+                    if let Some(ch) = decode_mif_char(&chars, i + 3) {
+                        result.push(ch);
+                        i += 8;
+                        continue;
+                    }
+                }
+                _ => {}
+            }
+        }
+        result.push(chars[i]);
+        i += 1;
+    }
+
+    result
+}
+
+/// Parse four hexadecimal digits starting at `start`.
+fn parse_hex_unit(chars: &[char], start: usize) -> Option<u16> {
+    if start + 4 > chars.len() {
+        return None;
+    }
+    let hex: String = chars[start..start + 4].iter().collect();
+    u16::from_str_radix(&hex, 16).ok()
+}
+
+/// Decode the `nXXXX` part of a `\M+nXXXX` escape.
+fn decode_mif_char(chars: &[char], start: usize) -> Option<char> {
+    let encoding = match chars.get(start)? {
+        '1' => encoding_rs::SHIFT_JIS,
+        '2' => encoding_rs::BIG5,
+        '3' => encoding_rs::EUC_KR,
+        '5' => encoding_rs::GBK,
+        _ => return None,
+    };
+    let code = parse_hex_unit(chars, start + 1)?.to_be_bytes();
+    let (decoded, _, had_errors) = encoding.decode(&code);
+    if had_errors {
+        return None;
+    }
+    let mut decoded_chars = decoded.chars();
+    match (decoded_chars.next(), decoded_chars.next()) {
+        (Some(ch), None) => Some(ch),
+        _ => None,
+    }
+}
+
+/// Encode a string into the drawing code page.
+///
+/// Characters that cannot be represented in `encoding` are written as
+/// `\U+XXXX` escapes (surrogate pairs for characters outside the BMP), which
+/// is how AutoCAD stores them in pre-2007 files.
+pub fn encode_with_escapes(value: &str, encoding: &'static Encoding) -> Vec<u8> {
+    if value.is_ascii() {
+        return value.as_bytes().to_vec();
+    }
+
+    let mut bytes = Vec::with_capacity(value.len());
+    let mut buf = [0u8; 4];
+    for ch in value.chars() {
+        if ch.is_ascii() {
+            bytes.push(ch as u8);
+            continue;
+        }
+        let (encoded, _, unmappable) = encoding.encode(ch.encode_utf8(&mut buf));
+        if unmappable {
+            let mut units = [0u16; 2];
+            for unit in ch.encode_utf16(&mut units) {
+                bytes.extend_from_slice(format!("\\U+{:04X}", unit).as_bytes());
+            }
+        } else {
+            bytes.extend_from_slice(&encoded);
+        }
+    }
+    bytes
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -115,4 +252,51 @@ mod tests {
         assert_eq!(encoding_from_code_page("ANSI_932"), Some(encoding_rs::SHIFT_JIS));
         assert_eq!(encoding_from_code_page("KOREAN"), Some(encoding_rs::EUC_KR));
     }
+
+    #[test]
+    fn test_decode_unicode_escape() {
+        assert_eq!(decode_unicode_escapes("A\\U+00E9B"), "AéB");
+        assert_eq!(decode_unicode_escapes("\\U+041F\\U+0440"), "Пр");
+        assert_eq!(decode_unicode_escapes("plain"), "plain");
+    }
+
+    #[test]
+    fn test_decode_lowercase_escape_only() {
+        assert_eq!(decode_unicode_escapes("caf\\u+00e9"), "café");
+        assert_eq!(decode_unicode_escapes("\\m+182A0"), "\u{3042}");
+    }
+
+    #[test]
+    fn test_decode_surrogate_pair() {
+        assert_eq!(decode_unicode_escapes("\\U+D83D\\U+DE00"), "\u{1F600}");
+    }
+
+    #[test]
+    fn test_decode_malformed_escape_kept() {
+        assert_eq!(decode_unicode_escapes("\\U+12"), "\\U+12");
+        assert_eq!(decode_unicode_escapes("\\U+ZZZZ"), "\\U+ZZZZ");
+    }
+
+    #[test]
+    fn test_decode_mif_escape() {
+        // 0x82A0 is HIRAGANA LETTER A in Shift-JIS
+        assert_eq!(decode_unicode_escapes("\\M+182A0"), "\u{3042}");
+        // Johab is not decoded as EUC-KR
+        assert_eq!(decode_unicode_escapes("\\M+4B0A1"), "\\M+4B0A1");
+    }
+
+    #[test]
+    fn test_encode_with_escapes() {
+        let bytes = encode_with_escapes("Пр中", encoding_rs::WINDOWS_1251);
+        assert_eq!(&bytes[..2], &[0xCF, 0xF0]);
+        assert_eq!(&bytes[2..], b"\\U+4E2D");
+    }
+
+    #[test]
+    fn test_encode_roundtrip() {
+        let text = "Zürich 東京 \u{1F600}";
+        let bytes = encode_with_escapes(text, encoding_rs::WINDOWS_1252);
+        let (decoded, _, _) = encoding_rs::WINDOWS_1252.decode(&bytes);
+        assert_eq!(decode_unicode_escapes(&decoded), text);
+    }
 }
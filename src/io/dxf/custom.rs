@@ -0,0 +1,149 @@
+//! Reading and writing entity and object types the crate does not know
+//!
+//! Entities and objects of unknown types are kept as raw pairs
+//! ([`UnknownEntity`](crate::entities::UnknownEntity) and
+//! [`ObjectType::Unknown`]) and written back verbatim. [`DxfCustomTypes`]
+//! lets an application take over such types, keyed by DXF name, without
+//! forking the crate:
+//!
+//! - a reader is given the pairs of one record, after its `0` marker, and
+//!   returns the entity or object to keep, or `None` to keep the record as
+//!   unknown;
+//! - a writer is given an unknown entity or object of its type and writes
+//!   the pairs following the `0` marker, which the crate has written.
+//!
+//! Types the crate reads and writes itself are not handed to these hooks.
+//!
+//! ```rust,ignore
+//! let custom = DxfCustomTypes::new()
+//!     .with_object_reader("MY_SETTINGS", |_, pairs| {
+//!         let mut record = XRecord::named("MY_SETTINGS");
+//!         for pair in pairs.iter().filter(|p| p.code == 1) {
+//!             record.add_string(1, pair.value_string.to_string());
+//!         }
+//!         Ok(Some(ObjectType::XRecord(record)))
+//!     })
+//!     .with_entity_writer("MY_MARKER", |entity, owner, writer| {
+//!         let EntityType::Unknown(marker) = entity else { return Ok(()) };
+//!         writer.write_handle(5, marker.common.handle)?;
+//!         writer.write_handle(330, owner)?;
+//!         writer.write_subclass("AcDbEntity")?;
+//!         writer.write_string(8, &marker.common.layer)
+//!     });
+//! let doc = DxfReader::from_file("site.dxf")?.with_custom_types(custom.clone()).read()?;
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
+
+use super::{DxfCodePair, DxfStreamWriter};
+use crate::entities::EntityType;
+use crate::error::Result;
+use crate::objects::ObjectType;
+use crate::types::Handle;
+
+/// Reader of one entity type: DXF name and pairs after the `0` marker
+pub type CustomEntityReader = dyn Fn(&str, &[DxfCodePair]) -> Result<Option<EntityType>> + Send + Sync;
+
+/// Reader of one object type: DXF name and pairs after the `0` marker
+pub type CustomObjectReader = dyn Fn(&str, &[DxfCodePair]) -> Result<Option<ObjectType>> + Send + Sync;
+
+/// Writer of one entity type: the entity, the handle of its block record
+/// and the stream positioned after the `0` marker
+pub type CustomEntityWriter = dyn Fn(&EntityType, Handle, &mut dyn DxfStreamWriter) -> Result<()> + Send + Sync;
+
+/// Writer of one object type: the object and the stream positioned after
+/// the `0` marker
+pub type CustomObjectWriter = dyn Fn(&ObjectType, &mut dyn DxfStreamWriter) -> Result<()> + Send + Sync;
+
+/// Readers and writers of entity and object types, by upper case DXF name
+#[derive(Clone, Default)]
+pub struct DxfCustomTypes {
+    entity_readers: BTreeMap<String, Arc<CustomEntityReader>>,
+    object_readers: BTreeMap<String, Arc<CustomObjectReader>>,
+    entity_writers: BTreeMap<String, Arc<CustomEntityWriter>>,
+    object_writers: BTreeMap<String, Arc<CustomObjectWriter>>,
+}
+
+impl DxfCustomTypes {
+    /// No custom types
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read entities named `dxf_name` with `reader`
+    pub fn with_entity_reader(
+        mut self,
+        dxf_name: &str,
+        reader: impl Fn(&str, &[DxfCodePair]) -> Result<Option<EntityType>> + Send + Sync + 'static,
+    ) -> Self {
+        self.entity_readers.insert(dxf_name.to_uppercase(), Arc::new(reader));
+        self
+    }
+
+    /// Read objects named `dxf_name` with `reader`
+    pub fn with_object_reader(
+        mut self,
+        dxf_name: &str,
+        reader: impl Fn(&str, &[DxfCodePair]) -> Result<Option<ObjectType>> + Send + Sync + 'static,
+    ) -> Self {
+        self.object_readers.insert(dxf_name.to_uppercase(), Arc::new(reader));
+        self
+    }
+
+    /// Write unknown entities named `dxf_name` with `writer`
+    pub fn with_entity_writer(
+        mut self,
+        dxf_name: &str,
+        writer: impl Fn(&EntityType, Handle, &mut dyn DxfStreamWriter) -> Result<()> + Send + Sync + 'static,
+    ) -> Self {
+        self.entity_writers.insert(dxf_name.to_uppercase(), Arc::new(writer));
+        self
+    }
+
+    /// Write unknown objects named `dxf_name` with `writer`
+    pub fn with_object_writer(
+        mut self,
+        dxf_name: &str,
+        writer: impl Fn(&ObjectType, &mut dyn DxfStreamWriter) -> Result<()> + Send + Sync + 'static,
+    ) -> Self {
+        self.object_writers.insert(dxf_name.to_uppercase(), Arc::new(writer));
+        self
+    }
+
+    /// Whether no reader or writer is registered
+    pub fn is_empty(&self) -> bool {
+        self.entity_readers.is_empty()
+            && self.object_readers.is_empty()
+            && self.entity_writers.is_empty()
+            && self.object_writers.is_empty()
+    }
+
+    pub(crate) fn entity_reader(&self, dxf_name: &str) -> Option<&CustomEntityReader> {
+        self.entity_readers.get(&dxf_name.to_uppercase()).map(|r| r.as_ref())
+    }
+
+    pub(crate) fn object_reader(&self, dxf_name: &str) -> Option<&CustomObjectReader> {
+        self.object_readers.get(&dxf_name.to_uppercase()).map(|r| r.as_ref())
+    }
+
+    pub(crate) fn entity_writer(&self, dxf_name: &str) -> Option<&CustomEntityWriter> {
+        self.entity_writers.get(&dxf_name.to_uppercase()).map(|w| w.as_ref())
+    }
+
+    pub(crate) fn object_writer(&self, dxf_name: &str) -> Option<&CustomObjectWriter> {
+        self.object_writers.get(&dxf_name.to_uppercase()).map(|w| w.as_ref())
+    }
+}
+
+impl fmt::Debug for DxfCustomTypes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DxfCustomTypes")
+            .field("entity_readers", &self.entity_readers.keys().collect::<Vec<_>>())
+            .field("object_readers", &self.object_readers.keys().collect::<Vec<_>>())
+            .field("entity_writers", &self.entity_writers.keys().collect::<Vec<_>>())
+            .field("object_writers", &self.object_writers.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
@@ -1,15 +1,22 @@
 //! DXF (Drawing Exchange Format) reading and writing
 
+mod binary_dialect;
+mod custom;
 mod dxf_code;
 mod group_code_value;
 mod reader;
 mod writer;
 pub mod code_page;
 
+pub use binary_dialect::BinaryDxfDialect;
+pub use custom::{CustomEntityReader, CustomEntityWriter, CustomObjectReader, CustomObjectWriter, DxfCustomTypes};
 pub use dxf_code::DxfCode;
 pub use group_code_value::GroupCodeValueType;
-pub use reader::{DxfReader, DxfReaderConfiguration};
-pub use writer::{DxfWriter, DxfStreamWriter, DxfStreamWriterExt, DxfTextWriter, DxfBinaryWriter, SectionWriter};
-pub use writer::{write_dxf, write_binary_dxf, value_type_for_code};
+pub use reader::{
+    DxfBinaryReader, DxfBuffer, DxfCodePair, DxfEntityIter, DxfReader, DxfReaderConfiguration, DxfStreamReader,
+    DxfText, DxfTextReader,
+};
+pub use writer::{DxfWriter, DxfStreamingWriter, DxfStreamWriter, DxfStreamWriterExt, DxfTextWriter, DxfBinaryWriter, SectionWriter};
+pub use writer::{write_dxf, write_binary_dxf, value_type_for_code, entity_subclasses};
 
 
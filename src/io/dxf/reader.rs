@@ -4,18 +4,27 @@ mod stream_reader;
 mod text_reader;
 mod binary_reader;
 mod section_reader;
+mod streaming;
+#[cfg(feature = "parallel")]
+mod parallel;
 
-pub use stream_reader::DxfStreamReader;
-pub use text_reader::DxfTextReader;
+pub use stream_reader::{DxfCodePair, DxfStreamReader, DxfText};
+pub use streaming::DxfEntityIter;
+pub use text_reader::{DxfBuffer, DxfTextReader};
 pub use binary_reader::DxfBinaryReader;
 
 use section_reader::SectionReader;
 
 use crate::document::CadDocument;
-use crate::error::Result;
+use crate::error::{DxfError, Result};
+use crate::io::progress::{CancellationToken, ProgressReporter, ReadPhase, ReadProgress};
+use crate::io::dxf::DxfCustomTypes;
+use crate::io::EntityFilter;
+use crate::notification::{Notification, NotificationCode, NotificationObserver, NotificationType};
 use crate::types::DxfVersion;
 use std::fs::File;
-use std::io::{BufReader, Read, Seek};
+use std::io::{BufReader, Cursor, Read, Seek, SeekFrom};
+use std::ops::ControlFlow;
 use std::path::Path;
 
 /// Configuration for the DXF reader.
@@ -24,104 +33,198 @@ pub struct DxfReaderConfiguration {
     /// When `true`, parse errors within individual entities/objects/sections
     /// are caught and reported as notifications instead of aborting the read.
     ///
+    /// Malformed pairs (stray blank lines, non-numeric group codes) are
+    /// skipped by resynchronizing on the next `0` code, and a truncated file
+    /// keeps whatever was parsed up to that point.
+    ///
     /// Default: `false` (strict mode — errors propagate).
     pub failsafe: bool,
 }
 
 impl Default for DxfReaderConfiguration {
     fn default() -> Self {
-        Self { failsafe: false }
+        Self {
+            failsafe: false,
+        }
     }
 }
 
+/// Start of every binary DXF file
+const BINARY_SENTINEL: &[u8] = b"AutoCAD Binary DXF";
+
 /// DXF file reader
 pub struct DxfReader {
     reader: Box<dyn DxfStreamReader>,
     version: DxfVersion,
     config: DxfReaderConfiguration,
+    progress: ProgressReporter,
+    observer: Option<NotificationObserver>,
+    entity_filter: EntityFilter,
+    custom_types: DxfCustomTypes,
+    #[cfg(feature = "parallel")]
+    parallel: bool,
 }
 
 impl DxfReader {
-    /// Create a new DXF reader from any reader
+    /// Create a new DXF reader from any reader.
+    ///
+    /// The stream is read as it is parsed; use [`Self::from_bytes`] for
+    /// contents already in memory.
     pub fn from_reader<R: Read + Seek + 'static>(reader: R) -> Result<Self> {
         let mut buf_reader = BufReader::new(reader);
+        let stream_length = buf_reader.seek(SeekFrom::End(0))?;
+        buf_reader.seek(SeekFrom::Start(0))?;
 
-        // Detect if binary
-        let is_binary = Self::is_binary(&mut buf_reader)?;
+        let mut head = Vec::new();
+        (&mut buf_reader).take(BINARY_SENTINEL.len() as u64).read_to_end(&mut head)?;
+        buf_reader.seek(SeekFrom::Start(0))?;
 
-        // Create appropriate reader
-        let reader: Box<dyn DxfStreamReader> = if is_binary {
+        let reader: Box<dyn DxfStreamReader> = if Self::is_binary(&head) {
             Box::new(DxfBinaryReader::new(buf_reader)?)
         } else {
-            // Seek back to start for text DXF files
-            buf_reader.seek(std::io::SeekFrom::Start(0))?;
             Box::new(DxfTextReader::new(buf_reader)?)
         };
+        Ok(Self::with_stream(reader, stream_length))
+    }
 
-        Ok(Self {
-            reader,
-            version: DxfVersion::Unknown,
-            config: DxfReaderConfiguration::default(),
-        })
+    /// Create a new DXF reader over the bytes of a DXF file, parsed in place
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<Self> {
+        Self::from_buffer(DxfBuffer::Owned(bytes))
     }
 
-    /// Create a new DXF reader from a file path
+    /// Create a new DXF reader from a file path, reading the file into memory
     pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let mut bytes = Vec::new();
+        File::open(path)?.read_to_end(&mut bytes)?;
+        Self::from_buffer(DxfBuffer::Owned(bytes))
+    }
+
+    /// Create a new DXF reader over a memory map of a file, so only the
+    /// pages being parsed need to be resident.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be truncated or modified while the reader, or any
+    /// value read from it, is alive; doing so is undefined behavior (on most
+    /// platforms a `SIGBUS` when a page past the new end is touched).
+    #[cfg(feature = "mmap")]
+    pub unsafe fn from_file_mapped<P: AsRef<Path>>(path: P) -> Result<Self> {
         let file = File::open(path)?;
-        let mut buf_reader = BufReader::new(file);
-        
-        // Detect if binary
-        let is_binary = Self::is_binary(&mut buf_reader)?;
-        
-        // Create appropriate reader
-        let reader: Box<dyn DxfStreamReader> = if is_binary {
-            Box::new(DxfBinaryReader::new(buf_reader)?)
+        // SAFETY: upheld by the caller, see above
+        let map = unsafe { memmap2::Mmap::map(&file)? };
+        Self::from_buffer(DxfBuffer::Mapped(map))
+    }
+
+    /// Create the appropriate stream reader for a buffered file
+    fn from_buffer(buffer: DxfBuffer) -> Result<Self> {
+        let stream_length = buffer.len() as u64;
+
+        let reader: Box<dyn DxfStreamReader> = if Self::is_binary(&buffer) {
+            Box::new(DxfBinaryReader::new(BufReader::new(Cursor::new(buffer)))?)
         } else {
-            // Seek back to start for text DXF files
-            buf_reader.seek(std::io::SeekFrom::Start(0))?;
-            Box::new(DxfTextReader::new(buf_reader)?)
+            Box::new(DxfTextReader::from_buffer(buffer))
         };
-        
-        Ok(Self {
+        Ok(Self::with_stream(reader, stream_length))
+    }
+
+    fn with_stream(reader: Box<dyn DxfStreamReader>, stream_length: u64) -> Self {
+        Self {
             reader,
             version: DxfVersion::Unknown,
             config: DxfReaderConfiguration::default(),
-        })
-    }
-    
-    /// Check if a stream contains binary DXF data
-    fn is_binary<R: Read + Seek>(reader: &mut R) -> Result<bool> {
-        const SENTINEL: &[u8] = b"AutoCAD Binary DXF";
-        let mut buffer = vec![0u8; SENTINEL.len()];
-        
-        // Try to read the sentinel bytes
-        let bytes_read = reader.read(&mut buffer)?;
-        
-        // Always seek back to start after checking
-        reader.seek(std::io::SeekFrom::Start(0))?;
-        
-        // If file is too small or doesn't match, it's not binary
-        if bytes_read < SENTINEL.len() {
-            return Ok(false);
+            progress: ProgressReporter::with_stream_length(stream_length),
+            observer: None,
+            entity_filter: EntityFilter::All,
+            custom_types: DxfCustomTypes::default(),
+            #[cfg(feature = "parallel")]
+            parallel: false,
         }
-        
-        Ok(buffer == SENTINEL)
+    }
+
+    /// Check if the start of a file is that of binary DXF data
+    fn is_binary(bytes: &[u8]) -> bool {
+        bytes.starts_with(BINARY_SENTINEL)
     }
 
     /// Set the reader configuration.
     pub fn with_configuration(mut self, config: DxfReaderConfiguration) -> Self {
+        self.reader.set_failsafe(config.failsafe);
         self.config = config;
         self
     }
 
+    /// Read only the entity types `filter` accepts.
+    ///
+    /// Entities of other types in the BLOCKS and ENTITIES sections are
+    /// skipped without being parsed.
+    pub fn with_entity_filter(mut self, filter: EntityFilter) -> Self {
+        self.entity_filter = filter;
+        self
+    }
+
+    /// Read entity and object types the crate does not read itself with
+    /// the readers of `custom`, by DXF name.
+    pub fn with_custom_types(mut self, custom: DxfCustomTypes) -> Self {
+        self.custom_types = custom;
+        self
+    }
+
+    /// Report progress while reading.
+    ///
+    /// `callback` is called when each section starts and whenever the
+    /// percentage of the file consumed advances by a whole percent.
+    pub fn with_progress<F>(mut self, callback: F) -> Self
+    where
+        F: FnMut(&ReadProgress) + 'static,
+    {
+        self.progress.set_callback(Box::new(callback));
+        self
+    }
+
+    /// Stop reading when `token` is cancelled.
+    ///
+    /// The token is checked between entities and objects; a cancelled read
+    /// returns the document read so far with a warning notification.
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.progress.set_token(token);
+        self
+    }
+
+    /// Parse the ENTITIES section on the rayon thread pool.
+    ///
+    /// The section of ASCII files is split at entity boundaries and the
+    /// chunks are parsed in parallel; binary files and failsafe reads always
+    /// use the sequential reader. Off by default.
+    #[cfg(feature = "parallel")]
+    pub fn with_parallel(mut self, parallel: bool) -> Self {
+        self.parallel = parallel;
+        self
+    }
+
+    /// Call `observer` with each notification as it is recorded.
+    ///
+    /// Recovery notifications from the tokenizer are passed on when the
+    /// section they occur in has been read. The notifications are still
+    /// collected in [`CadDocument::notifications`].
+    pub fn on_notification<F>(mut self, observer: F) -> Self
+    where
+        F: Fn(&Notification) + Send + Sync + 'static,
+    {
+        self.observer = Some(std::sync::Arc::new(observer));
+        self
+    }
+
     /// Read a DXF file and return a CadDocument
     pub fn read(mut self) -> Result<CadDocument> {
         // Find and read version from header
         self.read_version()?;
+        #[cfg(feature = "tracing")]
+        let _span = tracing::info_span!("read_dxf", version = ?self.version).entered();
 
         // Create document
         let mut document = CadDocument::new();
-        
+        document.notifications.set_observer(self.observer.take());
+
         // Read all sections
         let failsafe = self.config.failsafe;
 
@@ -130,36 +233,57 @@ impl DxfReader {
                 // Read section name
                 if let Some(section_pair) = self.reader.read_pair()? {
                     if section_pair.code == 2 {
-                        let section_name = section_pair.value_string.clone();
-                        let result = match section_name.as_str() {
+                        let section_name = section_pair.value_string;
+                        #[cfg(feature = "tracing")]
+                        let _section = tracing::debug_span!("section", name = %section_name).entered();
+                        if let Some(phase) = section_phase(&section_name) {
+                            let percent = self.progress.percent_of_stream(self.reader.byte_offset());
+                            self.progress.set_phase(phase, percent);
+                        }
+                        let result = self.progress.check_cancelled().and_then(|_| match section_name.as_str() {
                             "HEADER" => self.read_header_section(&mut document),
                             "CLASSES" => self.read_classes_section(&mut document),
                             "TABLES" => self.read_tables_section(&mut document),
                             "BLOCKS" => self.read_blocks_section(&mut document),
                             "ENTITIES" => self.read_entities_section(&mut document),
                             "OBJECTS" => self.read_objects_section(&mut document),
-                            "THUMBNAILIMAGE" => {
-                                document.notifications.notify(
-                                    crate::notification::NotificationType::NotImplemented,
-                                    "THUMBNAILIMAGE section skipped",
-                                );
-                                self.skip_section()
-                            }
+                            "THUMBNAILIMAGE" => self.read_thumbnail_section(&mut document),
                             _ => {
                                 // Skip unknown section
                                 self.skip_section()
                             }
-                        };
+                        });
+
+                        self.collect_reader_notifications(&mut document);
+
+                        // Cancellation keeps what has been read so far
+                        if let Err(DxfError::Cancelled) = result {
+                            document.notifications.push(
+                                Notification::new(
+                                    NotificationType::Warning,
+                                    format!("Read cancelled in {} section at {}", section_name, self.reader.location()),
+                                )
+                                .with_code(NotificationCode::Cancelled)
+                                .with_offset(self.reader.byte_offset()),
+                            );
+                            break;
+                        }
 
                         // In failsafe mode, catch errors and continue
                         if let Err(e) = result {
+                            let e = e.with_location(self.reader.location());
                             if failsafe {
-                                document.notifications.notify(
-                                    crate::notification::NotificationType::Error,
-                                    format!("Error reading {} section: {}", section_name, e),
+                                document.notifications.push(
+                                    Notification::new(
+                                        NotificationType::Error,
+                                        format!("Error reading {} section: {}", section_name, e),
+                                    )
+                                    .with_code(NotificationCode::SectionFailed)
+                                    .with_offset(self.reader.byte_offset()),
                                 );
                                 // Try to skip to the end of the section
                                 let _ = self.skip_section();
+                                self.collect_reader_notifications(&mut document);
                             } else {
                                 return Err(e);
                             }
@@ -171,12 +295,102 @@ impl DxfReader {
             }
         }
 
-        // Post-read resolution: assign owner handles and update next_handle
+        self.collect_reader_notifications(&mut document);
+        self.progress.set_phase(ReadPhase::Building, 100.0);
+
+        // Post-read resolution: assign owner handles and update next_handle,
+        // and in failsafe mode stand in for missing table entries and blocks
         document.resolve_references();
+        if self.config.failsafe {
+            document.add_missing_references();
+        }
+        document.resolve_dim_style();
+        document.resolve_raster_images();
+        document.resolve_underlay_definitions();
+        document.load_layer_overrides();
+        document.notifications.set_observer(None);
 
         Ok(document)
     }
     
+    /// Iterate over the entities of the ENTITIES section without building
+    /// a `CadDocument`.
+    ///
+    /// Entities are parsed one at a time as the iterator is advanced; every
+    /// other section is skipped. Useful for ETL-style processing of files
+    /// too large to load at once.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let mut lines = 0;
+    /// for entity in DxfReader::from_file("huge.dxf")?.entities_iter()? {
+    ///     if let EntityType::Line(_) = entity? {
+    ///         lines += 1;
+    ///     }
+    /// }
+    /// ```
+    pub fn entities_iter(mut self) -> Result<DxfEntityIter> {
+        self.read_version()?;
+        Ok(DxfEntityIter::new(self.reader, self.config.failsafe, self.entity_filter, self.custom_types, self.observer))
+    }
+
+    /// Visit the raw code/value pairs of the requested sections, SAX style.
+    ///
+    /// `callback` receives the section name and each pair between the
+    /// section's name and its `ENDSEC`; the section markers themselves are
+    /// not reported. Sections not listed in `sections` are skipped (an empty
+    /// list visits every section). Return `ControlFlow::Break(())` from the
+    /// callback to stop reading early.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let mut layers = HashSet::new();
+    /// DxfReader::from_file("huge.dxf")?.read_pairs(&["ENTITIES"], |_, pair| {
+    ///     if pair.code == 8 {
+    ///         layers.insert(pair.value_string.to_string());
+    ///     }
+    ///     ControlFlow::Continue(())
+    /// })?;
+    /// ```
+    pub fn read_pairs<F>(mut self, sections: &[&str], mut callback: F) -> Result<()>
+    where
+        F: FnMut(&str, &DxfCodePair) -> ControlFlow<()>,
+    {
+        self.read_version()?;
+
+        while let Some(pair) = self.reader.read_pair()? {
+            if pair.code != 0 {
+                continue;
+            }
+            match pair.value_string.as_str() {
+                "SECTION" => {
+                    let section_name = match self.reader.read_pair()? {
+                        Some(name) if name.code == 2 => name.value_string,
+                        _ => continue,
+                    };
+                    if !sections.is_empty() && !sections.contains(&section_name.as_str()) {
+                        self.skip_section()?;
+                        continue;
+                    }
+                    while let Some(p) = self.reader.read_pair()? {
+                        if p.code == 0 && p.value_string == "ENDSEC" {
+                            break;
+                        }
+                        if callback(&section_name, &p).is_break() {
+                            return Ok(());
+                        }
+                    }
+                }
+                "EOF" => break,
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
     /// Pre-scan the HEADER section for $ACADVER and $DWGCODEPAGE.
     ///
     /// After this call the reader is reset to the beginning and `self.version`
@@ -209,7 +423,7 @@ impl DxfReader {
                                     "$DWGCODEPAGE" => {
                                         if let Some(cp) = self.reader.read_pair()? {
                                             if cp.code == 3 {
-                                                code_page = Some(cp.value_string.clone());
+                                                code_page = Some(cp.value_string.to_string());
                                             }
                                         }
                                     }
@@ -241,42 +455,73 @@ impl DxfReader {
         Ok(())
     }
     
+    /// Move recovery notifications from the stream reader into the document
+    fn collect_reader_notifications(&mut self, document: &mut CadDocument) {
+        for n in self.reader.take_notifications() {
+            document.notifications.push(n);
+        }
+    }
+
     /// Read the HEADER section
     fn read_header_section(&mut self, document: &mut CadDocument) -> Result<()> {
-        let mut section_reader = SectionReader::new(&mut self.reader);
+        let mut section_reader = SectionReader::new(&mut self.reader).with_progress(&mut self.progress);
         section_reader.read_header(document)
     }
 
     /// Read the CLASSES section
     fn read_classes_section(&mut self, document: &mut CadDocument) -> Result<()> {
-        let mut section_reader = SectionReader::new(&mut self.reader);
+        let mut section_reader = SectionReader::new(&mut self.reader).with_progress(&mut self.progress);
         section_reader.read_classes(document)
     }
 
     /// Read the TABLES section
     fn read_tables_section(&mut self, document: &mut CadDocument) -> Result<()> {
-        let mut section_reader = SectionReader::new(&mut self.reader);
+        let mut section_reader = SectionReader::new(&mut self.reader).with_progress(&mut self.progress);
         section_reader.read_tables(document)
     }
 
     /// Read the BLOCKS section
     fn read_blocks_section(&mut self, document: &mut CadDocument) -> Result<()> {
-        let mut section_reader = SectionReader::new(&mut self.reader);
+        let mut section_reader = SectionReader::new(&mut self.reader)
+            .with_progress(&mut self.progress)
+            .with_entity_filter(&self.entity_filter)
+            .with_custom_types(&self.custom_types);
         section_reader.read_blocks(document)
     }
 
     /// Read the ENTITIES section
     fn read_entities_section(&mut self, document: &mut CadDocument) -> Result<()> {
-        let mut section_reader = SectionReader::new(&mut self.reader);
+        #[cfg(feature = "parallel")]
+        if self.parallel && !self.config.failsafe {
+            let start = self.progress.percent_of_stream(self.reader.byte_offset());
+            if let Some(section) = self.reader.read_section_lines()? {
+                let end = self.progress.percent_of_stream(self.reader.byte_offset());
+                let (filter, custom) = (&self.entity_filter, &self.custom_types);
+                return parallel::read_entities(section, filter, custom, &mut self.progress, start..end, document);
+            }
+        }
+
+        let mut section_reader = SectionReader::new(&mut self.reader)
+            .with_progress(&mut self.progress)
+            .with_entity_filter(&self.entity_filter)
+            .with_custom_types(&self.custom_types);
         section_reader.read_entities(document)
     }
 
     /// Read the OBJECTS section
     fn read_objects_section(&mut self, document: &mut CadDocument) -> Result<()> {
-        let mut section_reader = SectionReader::new(&mut self.reader);
+        let mut section_reader = SectionReader::new(&mut self.reader)
+            .with_progress(&mut self.progress)
+            .with_custom_types(&self.custom_types);
         section_reader.read_objects(document)
     }
     
+    /// Read the THUMBNAILIMAGE section
+    fn read_thumbnail_section(&mut self, document: &mut CadDocument) -> Result<()> {
+        let mut section_reader = SectionReader::new(&mut self.reader);
+        section_reader.read_thumbnail(document)
+    }
+
     /// Skip the current section
     fn skip_section(&mut self) -> Result<()> {
         while let Some(pair) = self.reader.read_pair()? {
@@ -288,4 +533,15 @@ impl DxfReader {
     }
 }
 
-
+/// Progress phase reported for a DXF section
+fn section_phase(section_name: &str) -> Option<ReadPhase> {
+    match section_name {
+        "HEADER" => Some(ReadPhase::Header),
+        "CLASSES" => Some(ReadPhase::Classes),
+        "TABLES" => Some(ReadPhase::Tables),
+        "BLOCKS" => Some(ReadPhase::Blocks),
+        "ENTITIES" => Some(ReadPhase::Entities),
+        "OBJECTS" => Some(ReadPhase::Objects),
+        _ => None,
+    }
+}
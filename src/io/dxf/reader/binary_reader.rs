@@ -1,7 +1,11 @@
 //! DXF binary reader
 
-use super::stream_reader::{DxfCodePair, DxfStreamReader};
-use crate::error::{DxfError, Result};
+use super::stream_reader::{DxfCodePair, DxfStreamReader, ReadContext};
+use crate::error::{DxfError, DxfLocation, Result};
+use crate::io::dxf::code_page::decode_unicode_escapes;
+use crate::io::dxf::BinaryDxfDialect;
+use crate::notification::{Notification, NotificationCode, NotificationType};
+use encoding_rs::Encoding;
 use std::io::{BufReader, Read, Seek, SeekFrom};
 
 /// Sentinel for binary DXF files
@@ -12,15 +16,40 @@ pub struct DxfBinaryReader<R: Read + Seek> {
     reader: BufReader<R>,
     position: u64,
     peeked_pair: Option<DxfCodePair>,
-    /// True for pre-AC1012 format (single-byte group codes)
-    /// False for AC1012+ format (two-byte group codes)
-    use_single_byte_codes: bool,
+    /// Group code encoding, detected from the first pair
+    dialect: BinaryDxfDialect,
+    /// Non-UTF8 fallback encoding.  `None` means lossy UTF-8.
+    encoding: Option<&'static Encoding>,
+    /// End the read on a truncated stream instead of returning an error.
+    failsafe: bool,
+    /// Recovery notifications recorded in failsafe mode.
+    notifications: Vec<Notification>,
+    /// Section and entity being read.
+    context: ReadContext,
 }
 
 impl<R: Read + Seek> DxfBinaryReader<R> {
-    /// Create a new DXF binary reader
+    /// Create a new DXF binary reader.
+    ///
+    /// Both the R13+ and the R12 [`BinaryDxfDialect`] are accepted.
     pub fn new(mut reader: BufReader<R>) -> Result<Self> {
-        // Verify sentinel
+        let dialect = Self::read_sentinel(&mut reader)?;
+        
+        Ok(Self {
+            reader,
+            position: BINARY_SENTINEL.len() as u64,
+            peeked_pair: None,
+            dialect,
+            encoding: None,
+            failsafe: false,
+            notifications: Vec::new(),
+            context: ReadContext::default(),
+        })
+    }
+
+    /// Verify the sentinel and detect the dialect, leaving the reader at the
+    /// first pair
+    fn read_sentinel(reader: &mut BufReader<R>) -> Result<BinaryDxfDialect> {
         let mut sentinel = vec![0u8; BINARY_SENTINEL.len()];
         reader.read_exact(&mut sentinel)?;
         
@@ -28,30 +57,15 @@ impl<R: Read + Seek> DxfBinaryReader<R> {
             return Err(DxfError::Parse("Invalid binary DXF sentinel".to_string()));
         }
         
-        // Detect format by checking the first group code
-        // In pre-AC1012, after sentinel we have: [code_byte][string...]
-        // In AC1012+, we have: [code_lo][code_hi][string...]
-        // The first code should be 0 (for SECTION), so:
-        // - Pre-AC1012: byte 0 = 0x00, byte 1 = 'S' (0x53)
-        // - AC1012+: byte 0 = 0x00, byte 1 = 0x00, byte 2 = 'S' (0x53)
         let mut probe = [0u8; 2];
         reader.read_exact(&mut probe)?;
         reader.seek(SeekFrom::Start(BINARY_SENTINEL.len() as u64))?;
-        
-        // If second byte is printable ASCII (like 'S' for SECTION), it's pre-AC1012
-        let use_single_byte_codes = probe[0] == 0 && probe[1] >= 0x20 && probe[1] < 0x7F;
-        
-        Ok(Self {
-            reader,
-            position: BINARY_SENTINEL.len() as u64,
-            peeked_pair: None,
-            use_single_byte_codes,
-        })
+        Ok(BinaryDxfDialect::detect(probe))
     }
     
     /// Read a code/value pair from the binary stream
     fn read_pair_internal(&mut self) -> Result<Option<DxfCodePair>> {
-        let code = if self.use_single_byte_codes {
+        let code = if self.dialect == BinaryDxfDialect::Legacy {
             // Pre-AC1012: single byte codes, with 255 as escape for extended codes
             let mut code_byte = [0u8; 1];
             match self.reader.read_exact(&mut code_byte) {
@@ -83,9 +97,25 @@ impl<R: Read + Seek> DxfBinaryReader<R> {
         };
         
         // Read value based on code type
-        let value = self.read_value_for_code(code)?;
+        let value = match self.read_value_for_code(code) {
+            Ok(value) => value,
+            Err(DxfError::Io(e)) if self.failsafe && e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                self.notifications.push(
+                    Notification::new(
+                        NotificationType::Error,
+                        format!("Unexpected EOF after code {} at {}", code, self.location()),
+                    )
+                    .with_code(NotificationCode::UnexpectedEof)
+                    .with_offset(self.position),
+                );
+                return Ok(None);
+            }
+            Err(e) => return Err(e.with_location(self.location())),
+        };
         
-        Ok(Some(DxfCodePair::new(code, value)))
+        let pair = DxfCodePair::new(code, value);
+        self.context.observe(&pair);
+        Ok(Some(pair))
     }
     
     /// Read a value from the binary stream based on the group code
@@ -109,14 +139,21 @@ impl<R: Read + Seek> DxfBinaryReader<R> {
                     bytes.push(byte[0]);
                 }
                 
-                // Try UTF-8 first, then fall back to lossy conversion for Windows-1252/CP1252
-                match String::from_utf8(bytes.clone()) {
-                    Ok(s) => Ok(s),
-                    Err(_) => {
-                        // Fall back to lossy conversion (replaces invalid bytes with replacement char)
-                        Ok(String::from_utf8_lossy(&bytes).into_owned())
+                // Try UTF-8 first, then use configured encoding or lossy fallback
+                let value = match String::from_utf8(bytes) {
+                    Ok(s) => s,
+                    Err(e) => {
+                        let bytes = e.into_bytes();
+                        if let Some(enc) = self.encoding {
+                            let (decoded, _, _) = enc.decode(&bytes);
+                            decoded.into_owned()
+                        } else {
+                            // Replaces invalid bytes with the replacement char
+                            String::from_utf8_lossy(&bytes).into_owned()
+                        }
                     }
-                }
+                };
+                Ok(decode_unicode_escapes(&value))
             }
             
             GroupCodeValueType::Double => {
@@ -257,23 +294,37 @@ impl<R: Read + Seek> DxfStreamReader for DxfBinaryReader<R> {
         self.reader.seek(SeekFrom::Start(0))?;
         self.position = 0;
         self.peeked_pair = None;
+        self.notifications.clear();
+        self.context.clear();
         
-        // Re-verify sentinel
-        let mut sentinel = vec![0u8; BINARY_SENTINEL.len()];
-        self.reader.read_exact(&mut sentinel)?;
-        
-        if sentinel != BINARY_SENTINEL {
-            return Err(DxfError::Parse("Invalid binary DXF sentinel".to_string()));
-        }
-        
-        // Re-detect format (should be same as before, but just re-skip the probe bytes)
-        let mut probe = [0u8; 2];
-        self.reader.read_exact(&mut probe)?;
-        self.reader.seek(SeekFrom::Start(BINARY_SENTINEL.len() as u64))?;
-        
+        self.dialect = Self::read_sentinel(&mut self.reader)?;
         self.position = BINARY_SENTINEL.len() as u64;
         Ok(())
     }
+
+    fn set_encoding(&mut self, encoding: &'static Encoding) {
+        self.encoding = Some(encoding);
+    }
+
+    fn position(&self) -> u64 {
+        self.position
+    }
+
+    fn location(&self) -> DxfLocation {
+        self.context.location(self.position, true)
+    }
+
+    fn set_failsafe(&mut self, failsafe: bool) {
+        self.failsafe = failsafe;
+    }
+
+    fn is_failsafe(&self) -> bool {
+        self.failsafe
+    }
+
+    fn take_notifications(&mut self) -> Vec<Notification> {
+        std::mem::take(&mut self.notifications)
+    }
 }
 
 
@@ -0,0 +1,242 @@
+//! Parallel parsing of the ENTITIES section
+//!
+//! The raw section lines are split at top-level entity boundaries into
+//! chunks; each chunk is tokenized and parsed on the rayon thread pool by
+//! its own [`SectionReader`], and the results are merged back in file order.
+//! Cancellation is checked before each chunk is parsed and progress is
+//! reported as the chunks are merged.
+
+use super::section_reader::SectionReader;
+use super::stream_reader::{DxfCodePair, DxfStreamReader, ReadContext, SectionLines};
+use super::text_reader::process_string_value;
+use crate::document::CadDocument;
+use crate::entities::EntityType;
+use crate::error::{DxfError, DxfLocation, Result};
+use crate::io::dxf::DxfCustomTypes;
+use crate::io::progress::ProgressReporter;
+use crate::io::EntityFilter;
+use crate::notification::NotificationCollection;
+use rayon::prelude::*;
+use std::ops::Range;
+
+/// Fewest entities worth handing to a worker
+const MIN_CHUNK_ENTITIES: usize = 256;
+
+/// Entities and notifications of one chunk
+type ParsedChunk = (Vec<EntityType>, NotificationCollection);
+
+/// Parse the lines of an ENTITIES section in parallel and add the entities
+/// to `document` in file order.
+///
+/// `percent` is the share of the file the section spans, for progress.
+pub(super) fn read_entities(
+    section: SectionLines,
+    filter: &EntityFilter,
+    custom: &DxfCustomTypes,
+    progress: &mut ProgressReporter,
+    percent: Range<f64>,
+    document: &mut CadDocument,
+) -> Result<()> {
+    let chunks = split_chunks(&section.lines);
+    let token = progress.token().cloned();
+
+    let results: Vec<(usize, Result<ParsedChunk>)> = chunks
+        .into_par_iter()
+        .map(|range| {
+            if token.as_ref().is_some_and(|token| token.is_cancelled()) {
+                return (range.end, Err(DxfError::Cancelled));
+            }
+            let first_line = section.first_line + range.start as u64;
+            (range.end, parse_chunk(&section.lines[range], first_line, filter, custom))
+        })
+        .collect();
+
+    let total_lines = section.lines.len().max(1) as f64;
+    for (end, result) in results {
+        let (entities, notifications) = result?;
+        let reached = percent.start + (percent.end - percent.start) * end as f64 / total_lines;
+        for entity in entities {
+            let _ = document.add_entity(entity);
+            progress.object_read(reached);
+        }
+        for n in notifications {
+            document.notifications.push(n);
+        }
+        progress.check_cancelled()?;
+    }
+    Ok(())
+}
+
+/// Split line pairs into ranges that start at a top-level entity.
+///
+/// Sub-entities (`VERTEX`, `ATTRIB`, `SEQEND`) stay with their owner.
+fn split_chunks(lines: &[String]) -> Vec<Range<usize>> {
+    let starts: Vec<usize> = lines
+        .chunks(2)
+        .enumerate()
+        .filter(|(_, pair)| {
+            pair.len() == 2
+                && pair[0] == "0"
+                && !matches!(pair[1].as_str(), "VERTEX" | "ATTRIB" | "SEQEND")
+        })
+        .map(|(i, _)| i * 2)
+        .collect();
+
+    let workers = rayon::current_num_threads().max(1);
+    let per_chunk = (starts.len() / (workers * 4)).max(MIN_CHUNK_ENTITIES);
+
+    let mut ranges = Vec::new();
+    let mut begin = 0;
+    for start in starts.iter().step_by(per_chunk).skip(1) {
+        ranges.push(begin..*start);
+        begin = *start;
+    }
+    ranges.push(begin..lines.len());
+    ranges
+}
+
+/// Tokenize and parse one chunk of entity lines
+fn parse_chunk(
+    lines: &[String],
+    first_line: u64,
+    filter: &EntityFilter,
+    custom: &DxfCustomTypes,
+) -> Result<ParsedChunk> {
+    let mut pairs = Vec::with_capacity(lines.len() / 2);
+    for (i, pair) in lines.chunks(2).enumerate() {
+        let line = first_line + (i * 2) as u64;
+        let location = || DxfLocation {
+            position: line,
+            section: Some("ENTITIES".to_string()),
+            ..Default::default()
+        };
+        let code = pair[0]
+            .parse::<i32>()
+            .map_err(|_| DxfError::Parse(format!("Invalid DXF code '{}'", pair[0])).with_location(location()))?;
+        let value = pair.get(1).ok_or_else(|| {
+            DxfError::Parse(format!("Unexpected EOF after code {}", code)).with_location(location())
+        })?;
+        pairs.push(DxfCodePair::new(code, process_string_value(value).into_owned()));
+    }
+
+    let mut reader: Box<dyn DxfStreamReader> = Box::new(PairSliceReader::new(pairs, first_line));
+    let mut entities = Vec::new();
+    let mut notifications = NotificationCollection::new();
+
+    while let Some(pair) = reader.read_pair()? {
+        if pair.code == 0 {
+            let mut section_reader = SectionReader::new(&mut reader).with_entity_filter(filter).with_custom_types(custom);
+            if let Some(entity) = section_reader.read_entity(&pair.value_string, &mut notifications)? {
+                entities.push(entity);
+            }
+        }
+    }
+    Ok((entities, notifications))
+}
+
+/// Stream reader over pre-tokenized pairs
+struct PairSliceReader {
+    pairs: std::vec::IntoIter<DxfCodePair>,
+    peeked_pair: Option<DxfCodePair>,
+    first_line: u64,
+    consumed: u64,
+    context: ReadContext,
+}
+
+impl PairSliceReader {
+    fn new(pairs: Vec<DxfCodePair>, first_line: u64) -> Self {
+        Self {
+            pairs: pairs.into_iter(),
+            peeked_pair: None,
+            first_line,
+            consumed: 0,
+            context: ReadContext::in_section("ENTITIES"),
+        }
+    }
+}
+
+impl DxfStreamReader for PairSliceReader {
+    fn read_pair(&mut self) -> Result<Option<DxfCodePair>> {
+        if let Some(pair) = self.peeked_pair.take() {
+            return Ok(Some(pair));
+        }
+        let pair = self.pairs.next();
+        if let Some(pair) = &pair {
+            self.consumed += 1;
+            self.context.observe(pair);
+        }
+        Ok(pair)
+    }
+
+    fn peek_code(&mut self) -> Result<Option<i32>> {
+        if self.peeked_pair.is_none() {
+            self.peeked_pair = self.read_pair()?;
+        }
+        Ok(self.peeked_pair.as_ref().map(|p| p.code))
+    }
+
+    fn push_back(&mut self, pair: DxfCodePair) {
+        self.peeked_pair = Some(pair);
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        Err(DxfError::NotImplemented("reset on a section slice".to_string()))
+    }
+
+    fn position(&self) -> u64 {
+        // Value line of the last pair read
+        (self.first_line + self.consumed * 2).saturating_sub(1)
+    }
+
+    fn location(&self) -> DxfLocation {
+        self.context.location(self.position(), false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entity_lines(count: usize) -> Vec<String> {
+        let mut lines = Vec::new();
+        for i in 0..count {
+            for (code, value) in [("0", "LINE".to_string()), ("8", "0".to_string()), ("10", i.to_string())] {
+                lines.push(code.to_string());
+                lines.push(value);
+            }
+        }
+        lines
+    }
+
+    #[test]
+    fn test_split_keeps_sub_entities_with_owner() {
+        let mut lines = entity_lines(MIN_CHUNK_ENTITIES - 1);
+        for (code, value) in [("0", "POLYLINE"), ("0", "VERTEX"), ("0", "SEQEND"), ("0", "LINE")] {
+            lines.push(code.to_string());
+            lines.push(value.to_string());
+        }
+
+        let chunks = split_chunks(&lines);
+        for range in &chunks[1..] {
+            assert_eq!(lines[range.start], "0");
+            assert!(!matches!(lines[range.start + 1].as_str(), "VERTEX" | "SEQEND"));
+        }
+        assert_eq!(chunks.last().unwrap().end, lines.len());
+    }
+
+    #[test]
+    fn test_parse_chunks_in_order() {
+        let lines = entity_lines(MIN_CHUNK_ENTITIES * 3);
+        let mut document = CadDocument::new();
+        read_entities(SectionLines { first_line: 1, lines }, &EntityFilter::All, &DxfCustomTypes::default(), &mut ProgressReporter::default(), 0.0..100.0, &mut document).unwrap();
+        assert_eq!(document.entity_count(), MIN_CHUNK_ENTITIES * 3);
+    }
+
+    #[test]
+    fn test_invalid_code_reports_line() {
+        let mut lines = entity_lines(2);
+        lines[4] = "x".to_string();
+        let err = read_entities(SectionLines { first_line: 10, lines }, &EntityFilter::All, &DxfCustomTypes::default(), &mut ProgressReporter::default(), 0.0..100.0, &mut CadDocument::new()).unwrap_err();
+        assert_eq!(err.location().map(|l| l.position), Some(14));
+    }
+}
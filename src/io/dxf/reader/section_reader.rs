@@ -1,10 +1,15 @@
 //! DXF section readers
 
-use super::stream_reader::{DxfStreamReader, PointReader};
+use super::stream_reader::{DxfCodePair, DxfStreamReader, PointReader};
 use crate::document::CadDocument;
 use crate::entities::*;
-use crate::error::Result;
+use crate::error::{DxfError, Result};
+use crate::io::progress::ProgressReporter;
+use crate::io::dxf::DxfCustomTypes;
+use crate::io::EntityFilter;
+use crate::notification::{Notification, NotificationCode, NotificationCollection, NotificationType};
 use crate::objects::*;
+use crate::preview::DwgPreview;
 use crate::tables::*;
 use crate::tables::linetype::LineTypeElement;
 use crate::types::*;
@@ -23,20 +28,114 @@ enum MeshReadState {
 /// Section reader for parsing DXF sections
 pub struct SectionReader<'a> {
     reader: &'a mut Box<dyn DxfStreamReader>,
+    progress: Option<&'a mut ProgressReporter>,
+    entity_filter: Option<&'a EntityFilter>,
+    custom_types: Option<&'a DxfCustomTypes>,
 }
 
 impl<'a> SectionReader<'a> {
     /// Create a new section reader
     pub fn new(reader: &'a mut Box<dyn DxfStreamReader>) -> Self {
-        Self { reader }
+        Self { reader, progress: None, entity_filter: None, custom_types: None }
     }
-    
+
+    /// Report progress and check for cancellation after each block, entity
+    /// and object.
+    pub(crate) fn with_progress(mut self, progress: &'a mut ProgressReporter) -> Self {
+        if progress.is_active() {
+            self.progress = Some(progress);
+        }
+        self
+    }
+
+    /// Skip the entities `filter` rejects.
+    pub(crate) fn with_entity_filter(mut self, filter: &'a EntityFilter) -> Self {
+        if !filter.is_all() {
+            self.entity_filter = Some(filter);
+        }
+        self
+    }
+
+    /// Hand the entity and object types `custom` reads to its readers.
+    pub(crate) fn with_custom_types(mut self, custom: &'a DxfCustomTypes) -> Self {
+        if !custom.is_empty() {
+            self.custom_types = Some(custom);
+        }
+        self
+    }
+
+    /// Count one item read, report progress and honour cancellation
+    fn tick(&mut self) -> Result<()> {
+        if let Some(progress) = self.progress.as_deref_mut() {
+            let percent = progress.percent_of_stream(self.reader.byte_offset());
+            progress.object_read(percent);
+            progress.check_cancelled()?;
+        }
+        Ok(())
+    }
+
+    /// Check whether `pair` ends the current section.
+    ///
+    /// In failsafe mode a `SECTION` or `EOF` marker is treated as an implicit
+    /// `ENDSEC` so that files with a missing `ENDSEC` can still be read; the
+    /// marker is pushed back for the caller. Otherwise it is an error.
+    pub(super) fn is_section_end(&mut self, pair: &DxfCodePair, notifications: &mut NotificationCollection) -> Result<bool> {
+        if pair.code != 0 {
+            return Ok(false);
+        }
+        match pair.value_string.as_str() {
+            "ENDSEC" => Ok(true),
+            "SECTION" | "EOF" => self.missing_terminator("ENDSEC", pair, notifications),
+            _ => Ok(false),
+        }
+    }
+
+    /// Check whether `pair` ends the current table.
+    ///
+    /// Like [`Self::is_section_end`], in failsafe mode a section boundary
+    /// also ends a table whose `ENDTAB` is missing.
+    fn is_table_end(&mut self, pair: &DxfCodePair, notifications: &mut NotificationCollection) -> Result<bool> {
+        if pair.code != 0 {
+            return Ok(false);
+        }
+        match pair.value_string.as_str() {
+            "ENDTAB" => Ok(true),
+            "ENDSEC" | "SECTION" | "EOF" => self.missing_terminator("ENDTAB", pair, notifications),
+            _ => Ok(false),
+        }
+    }
+
+    /// Recover from a missing `terminator` before `pair` in failsafe mode,
+    /// or fail with the location
+    fn missing_terminator(
+        &mut self,
+        terminator: &str,
+        pair: &DxfCodePair,
+        notifications: &mut NotificationCollection,
+    ) -> Result<bool> {
+        let location = self.reader.location();
+        if !self.reader.is_failsafe() {
+            return Err(DxfError::Parse(format!("Missing {} before {}", terminator, pair.value_string))
+                .with_location(location));
+        }
+        notifications.push(
+            Notification::new(
+                NotificationType::Warning,
+                format!("Missing {} before {} at {}", terminator, pair.value_string, location),
+            )
+            .with_code(NotificationCode::MissingTerminator)
+            .with_offset(self.reader.byte_offset()),
+        );
+        self.reader.push_back(pair.clone());
+        Ok(true)
+    }
+
     /// Read the HEADER section
     pub fn read_header(&mut self, document: &mut CadDocument) -> Result<()> {
         let hdr = &mut document.header;
 
         while let Some(pair) = self.reader.read_pair()? {
-            if pair.code == 0 && pair.value_string == "ENDSEC" {
+            if self.is_section_end(&pair, &mut document.notifications)? {
                 break;
             }
 
@@ -44,7 +143,7 @@ impl<'a> SectionReader<'a> {
                 continue;
             }
 
-            let var_name = pair.value_string.clone();
+            let var_name = pair.value_string.to_string();
             match var_name.as_str() {
                 // ── Version / Metadata ──
                 "$ACADVER" => {
@@ -59,7 +158,7 @@ impl<'a> SectionReader<'a> {
                     }
                 }
                 "$DWGCODEPAGE" => {
-                    if let Some(p) = self.reader.read_pair()? { hdr.code_page = p.value_string.clone(); }
+                    if let Some(p) = self.reader.read_pair()? { hdr.code_page = p.value_string.to_string(); }
                 }
                 "$HANDSEED" => {
                     if let Some(p) = self.reader.read_pair()? {
@@ -69,25 +168,25 @@ impl<'a> SectionReader<'a> {
                     }
                 }
                 "$LASTSAVEDBY" => {
-                    if let Some(p) = self.reader.read_pair()? { hdr.last_saved_by = p.value_string.clone(); }
+                    if let Some(p) = self.reader.read_pair()? { hdr.last_saved_by = p.value_string.to_string(); }
                 }
                 "$FINGERPRINTGUID" => {
-                    if let Some(p) = self.reader.read_pair()? { hdr.fingerprint_guid = p.value_string.clone(); }
+                    if let Some(p) = self.reader.read_pair()? { hdr.fingerprint_guid = p.value_string.to_string(); }
                 }
                 "$VERSIONGUID" => {
-                    if let Some(p) = self.reader.read_pair()? { hdr.version_guid = p.value_string.clone(); }
+                    if let Some(p) = self.reader.read_pair()? { hdr.version_guid = p.value_string.to_string(); }
                 }
                 "$MENU" => {
-                    if let Some(p) = self.reader.read_pair()? { hdr.menu_name = p.value_string.clone(); }
+                    if let Some(p) = self.reader.read_pair()? { hdr.menu_name = p.value_string.to_string(); }
                 }
                 "$PROJECTNAME" => {
-                    if let Some(p) = self.reader.read_pair()? { hdr.project_name = p.value_string.clone(); }
+                    if let Some(p) = self.reader.read_pair()? { hdr.project_name = p.value_string.to_string(); }
                 }
                 "$HYPERLINKBASE" => {
-                    if let Some(p) = self.reader.read_pair()? { hdr.hyperlink_base = p.value_string.clone(); }
+                    if let Some(p) = self.reader.read_pair()? { hdr.hyperlink_base = p.value_string.to_string(); }
                 }
                 "$STYLESHEET" => {
-                    if let Some(p) = self.reader.read_pair()? { hdr.stylesheet = p.value_string.clone(); }
+                    if let Some(p) = self.reader.read_pair()? { hdr.stylesheet = p.value_string.to_string(); }
                 }
 
                 // ── Drawing Mode Booleans ──
@@ -275,21 +374,21 @@ impl<'a> SectionReader<'a> {
                 "$DIMALTTZ" => { if let Some(p) = self.reader.read_pair()? { if let Some(v) = p.as_i16() { hdr.dim_alt_tolerance_zero_tight = v; } } }
                 "$DIMATFIT" | "$DIMFIT" => { if let Some(p) = self.reader.read_pair()? { if let Some(v) = p.as_i16() { hdr.dim_fit = v; } } }
                 "$DIMUPT" => { if let Some(p) = self.reader.read_pair()? { hdr.dim_user_positioned_text = p.as_i16() == Some(1); } }
-                "$DIMPOST" => { if let Some(p) = self.reader.read_pair()? { hdr.dim_post = p.value_string.clone(); } }
-                "$DIMAPOST" => { if let Some(p) = self.reader.read_pair()? { hdr.dim_alt_post = p.value_string.clone(); } }
-                "$DIMBLK" => { if let Some(p) = self.reader.read_pair()? { hdr.dim_arrow_block = p.value_string.clone(); } }
-                "$DIMBLK1" => { if let Some(p) = self.reader.read_pair()? { hdr.dim_arrow_block1 = p.value_string.clone(); } }
-                "$DIMBLK2" => { if let Some(p) = self.reader.read_pair()? { hdr.dim_arrow_block2 = p.value_string.clone(); } }
-                "$DIMLDRBLK" => { if let Some(p) = self.reader.read_pair()? { hdr.dim_leader_arrow_block = p.value_string.clone(); } }
+                "$DIMPOST" => { if let Some(p) = self.reader.read_pair()? { hdr.dim_post = p.value_string.to_string(); } }
+                "$DIMAPOST" => { if let Some(p) = self.reader.read_pair()? { hdr.dim_alt_post = p.value_string.to_string(); } }
+                "$DIMBLK" => { if let Some(p) = self.reader.read_pair()? { hdr.dim_arrow_block = p.value_string.to_string(); } }
+                "$DIMBLK1" => { if let Some(p) = self.reader.read_pair()? { hdr.dim_arrow_block1 = p.value_string.to_string(); } }
+                "$DIMBLK2" => { if let Some(p) = self.reader.read_pair()? { hdr.dim_arrow_block2 = p.value_string.to_string(); } }
+                "$DIMLDRBLK" => { if let Some(p) = self.reader.read_pair()? { hdr.dim_leader_arrow_block = p.value_string.to_string(); } }
                 "$DIMLWD" => { if let Some(p) = self.reader.read_pair()? { if let Some(v) = p.as_i16() { hdr.dim_line_weight = v; } } }
                 "$DIMLWE" => { if let Some(p) = self.reader.read_pair()? { if let Some(v) = p.as_i16() { hdr.dim_ext_line_weight = v; } } }
 
                 // ── Name references ──
-                "$CLAYER" => { if let Some(p) = self.reader.read_pair()? { hdr.current_layer_name = p.value_string.clone(); } }
-                "$CELTYPE" => { if let Some(p) = self.reader.read_pair()? { hdr.current_linetype_name = p.value_string.clone(); } }
-                "$TEXTSTYLE" => { if let Some(p) = self.reader.read_pair()? { hdr.current_text_style_name = p.value_string.clone(); } }
-                "$DIMSTYLE" => { if let Some(p) = self.reader.read_pair()? { hdr.current_dimstyle_name = p.value_string.clone(); } }
-                "$CMLSTYLE" => { if let Some(p) = self.reader.read_pair()? { hdr.multiline_style = p.value_string.clone(); } }
+                "$CLAYER" => { if let Some(p) = self.reader.read_pair()? { hdr.current_layer_name = p.value_string.to_string(); } }
+                "$CELTYPE" => { if let Some(p) = self.reader.read_pair()? { hdr.current_linetype_name = p.value_string.to_string(); } }
+                "$TEXTSTYLE" => { if let Some(p) = self.reader.read_pair()? { hdr.current_text_style_name = p.value_string.to_string(); } }
+                "$DIMSTYLE" => { if let Some(p) = self.reader.read_pair()? { hdr.current_dimstyle_name = p.value_string.to_string(); hdr.current_dimstyle_handle = Handle::NULL; } }
+                "$CMLSTYLE" => { if let Some(p) = self.reader.read_pair()? { hdr.multiline_style = p.value_string.to_string(); } }
 
                 // ── Extents / Limits (multi-value XYZ / XY) ──
                 "$INSBASE" => { self.read_header_point3(&mut hdr.model_space_insertion_base)?; }
@@ -304,9 +403,9 @@ impl<'a> SectionReader<'a> {
                 "$PLIMMAX" => { self.read_header_point2(&mut hdr.paper_space_limits_max)?; }
 
                 // ── UCS ──
-                "$UCSBASE" => { if let Some(p) = self.reader.read_pair()? { hdr.ucs_base = p.value_string.clone(); } }
-                "$UCSNAME" => { if let Some(p) = self.reader.read_pair()? { hdr.model_space_ucs_name = p.value_string.clone(); } }
-                "$PUCSNAME" => { if let Some(p) = self.reader.read_pair()? { hdr.paper_space_ucs_name = p.value_string.clone(); } }
+                "$UCSBASE" => { if let Some(p) = self.reader.read_pair()? { hdr.ucs_base = p.value_string.to_string(); } }
+                "$UCSNAME" => { if let Some(p) = self.reader.read_pair()? { hdr.model_space_ucs_name = p.value_string.to_string(); } }
+                "$PUCSNAME" => { if let Some(p) = self.reader.read_pair()? { hdr.paper_space_ucs_name = p.value_string.to_string(); } }
                 "$UCSORG" => { self.read_header_point3(&mut hdr.model_space_ucs_origin)?; }
                 "$UCSXDIR" => { self.read_header_point3(&mut hdr.model_space_ucs_x_axis)?; }
                 "$UCSYDIR" => { self.read_header_point3(&mut hdr.model_space_ucs_y_axis)?; }
@@ -318,15 +417,14 @@ impl<'a> SectionReader<'a> {
 
                 // ── Date / Time ──
                 "$TDCREATE" => { if let Some(p) = self.reader.read_pair()? { if let Some(v) = p.as_double() { hdr.create_date_julian = v; } } }
-                "$TDUCREATE" => { self.reader.read_pair()?; } // UTC variant: skip (no field)
                 "$TDUPDATE" => { if let Some(p) = self.reader.read_pair()? { if let Some(v) = p.as_double() { hdr.update_date_julian = v; } } }
-                "$TDUUPDATE" => { self.reader.read_pair()?; }
                 "$TDINDWG" => { if let Some(p) = self.reader.read_pair()? { if let Some(v) = p.as_double() { hdr.total_editing_time = v; } } }
                 "$TDUSRTIMER" => { if let Some(p) = self.reader.read_pair()? { if let Some(v) = p.as_double() { hdr.user_elapsed_time = v; } } }
 
                 _ => {
-                    // Skip unknown header variable value(s) – consume until next code 9 or code 0
-                    self.skip_header_variable()?;
+                    // Keep unknown header variable value(s) for round-trip
+                    let values = self.read_header_variable_pairs()?;
+                    hdr.unknown_variables.insert(var_name, values);
                 }
             }
         }
@@ -362,22 +460,80 @@ impl<'a> SectionReader<'a> {
         Ok(())
     }
 
-    /// Skip an unknown header variable — consume value pairs until the next $VAR (code 9) or ENDSEC (code 0)
-    fn skip_header_variable(&mut self) -> Result<()> {
+    /// Read the THUMBNAILIMAGE section into the document preview
+    pub fn read_thumbnail(&mut self, document: &mut CadDocument) -> Result<()> {
+        let mut size = None;
+        let mut image = Vec::new();
+        let mut malformed = false;
+        while let Some(pair) = self.reader.read_pair()? {
+            if self.is_section_end(&pair, &mut document.notifications)? {
+                break;
+            }
+            match pair.code {
+                90 => size = pair.as_i32(),
+                310 if !malformed => {
+                    let hex = pair.value_string.trim();
+                    match decode_hex(hex) {
+                        Some(bytes) => image.extend(bytes),
+                        None => {
+                            // In failsafe mode the preview is dropped rather than kept corrupt
+                            let location = self.reader.location();
+                            if !self.reader.is_failsafe() {
+                                return Err(DxfError::Parse(format!("Invalid thumbnail data '{}'", hex))
+                                    .with_location(location));
+                            }
+                            document.notifications.push(
+                                Notification::new(
+                                    NotificationType::Warning,
+                                    format!("Invalid thumbnail data '{}' at {}, preview dropped", hex, location),
+                                )
+                                .with_code(NotificationCode::MalformedPair)
+                                .with_offset(self.reader.byte_offset()),
+                            );
+                            malformed = true;
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        if malformed {
+            return Ok(());
+        }
+
+        if let Some(size) = size.filter(|&size| size >= 0 && size as usize != image.len()) {
+            document.notifications.push(
+                Notification::new(
+                    NotificationType::Warning,
+                    format!("Thumbnail size {} does not match its {} data bytes", size, image.len()),
+                )
+                .with_code(NotificationCode::MalformedPair),
+            );
+        }
+        if !image.is_empty() {
+            document.preview = Some(DwgPreview::from_image(image));
+        }
+        Ok(())
+    }
+
+    /// Read the raw value pairs of an unknown header variable — consume until the next $VAR (code 9) or ENDSEC (code 0)
+    fn read_header_variable_pairs(&mut self) -> Result<Vec<(i32, String)>> {
+        let mut values = Vec::new();
         while let Some(p) = self.reader.read_pair()? {
             if p.code == 9 || p.code == 0 {
                 self.reader.push_back(p);
                 break;
             }
+            values.push((p.code, p.value_string.into_string()));
         }
-        Ok(())
+        Ok(values)
     }
     
     /// Read the CLASSES section
     pub fn read_classes(&mut self, document: &mut CadDocument) -> Result<()> {
         // Read classes until ENDSEC
         while let Some(pair) = self.reader.read_pair()? {
-            if pair.code == 0 && pair.value_string == "ENDSEC" {
+            if self.is_section_end(&pair, &mut document.notifications)? {
                 break;
             }
             
@@ -390,9 +546,9 @@ impl<'a> SectionReader<'a> {
                         break;
                     }
                     match class_pair.code {
-                        1 => class.dxf_name = class_pair.value_string.clone(),
-                        2 => class.cpp_class_name = class_pair.value_string.clone(),
-                        3 => class.application_name = class_pair.value_string.clone(),
+                        1 => class.dxf_name = class_pair.value_string.to_string(),
+                        2 => class.cpp_class_name = class_pair.value_string.to_string(),
+                        3 => class.application_name = class_pair.value_string.to_string(),
                         90 => {
                             if let Some(v) = class_pair.as_i32() {
                                 class.proxy_flags = crate::classes::ProxyFlags::from(v);
@@ -430,7 +586,7 @@ impl<'a> SectionReader<'a> {
     pub fn read_tables(&mut self, document: &mut CadDocument) -> Result<()> {
         // Read tables until ENDSEC
         while let Some(pair) = self.reader.read_pair()? {
-            if pair.code == 0 && pair.value_string == "ENDSEC" {
+            if self.is_section_end(&pair, &mut document.notifications)? {
                 break;
             }
             
@@ -451,7 +607,7 @@ impl<'a> SectionReader<'a> {
                             "UCS" => self.read_ucs_table(document)?,
                             _ => {
                                 // Skip unknown table
-                                self.skip_to_endtab()?;
+                                self.skip_to_endtab(&mut document.notifications)?;
                             }
                         }
                     }
@@ -466,13 +622,14 @@ impl<'a> SectionReader<'a> {
     pub fn read_blocks(&mut self, document: &mut CadDocument) -> Result<()> {
         // Read blocks until ENDSEC
         while let Some(pair) = self.reader.read_pair()? {
-            if pair.code == 0 && pair.value_string == "ENDSEC" {
+            if self.is_section_end(&pair, &mut document.notifications)? {
                 break;
             }
 
             // Blocks start with code 0 = "BLOCK"
             if pair.code == 0 && pair.value_string == "BLOCK" {
                 self.read_block(document)?;
+                self.tick()?;
             }
         }
 
@@ -490,6 +647,7 @@ impl<'a> SectionReader<'a> {
         let mut xref_path = String::new();
         let mut layer = String::from("0");
         let mut handle = Handle::NULL;
+        let mut flags = None;
 
         let mut point_reader = PointReader::new();
 
@@ -503,21 +661,21 @@ impl<'a> SectionReader<'a> {
                 }
                 2 => {
                     // Block name
-                    block_name = pair.value_string.clone();
+                    block_name = pair.value_string.to_string();
                 }
                 3 => {
                     // Block name (alternate)
                     if block_name.is_empty() {
-                        block_name = pair.value_string.clone();
+                        block_name = pair.value_string.to_string();
                     }
                 }
                 4 => {
                     // Description
-                    description = pair.value_string.clone();
+                    description = pair.value_string.to_string();
                 }
                 1 => {
                     // XRef path
-                    xref_path = pair.value_string.clone();
+                    xref_path = pair.value_string.to_string();
                 }
                 5 => {
                     // Handle
@@ -527,7 +685,11 @@ impl<'a> SectionReader<'a> {
                 }
                 8 => {
                     // Layer
-                    layer = pair.value_string.clone();
+                    layer = pair.value_string.to_string();
+                }
+                70 => {
+                    // Block flags
+                    flags = pair.as_i16();
                 }
                 10 | 20 | 30 => {
                     // Base point coordinates
@@ -540,6 +702,13 @@ impl<'a> SectionReader<'a> {
             }
         }
 
+        if let (Some(flags), Some(block_record)) = (flags, document.block_records.get_mut(&block_name)) {
+            block_record.flags.anonymous = (flags & 1) != 0;
+            block_record.flags.has_attributes = (flags & 2) != 0;
+            block_record.flags.is_xref = (flags & 4) != 0;
+            block_record.flags.is_xref_overlay = (flags & 8) != 0;
+        }
+
         // Create Block entity
         let mut block = Block::new(block_name.clone(), base_point);
         block.common.handle = handle;
@@ -553,6 +722,9 @@ impl<'a> SectionReader<'a> {
         // Read entities until ENDBLK
         while let Some(pair) = self.reader.read_pair()? {
             if pair.code == 0 {
+                if pair.value_string != "ENDBLK" && self.skip_filtered(&pair.value_string)? {
+                    continue;
+                }
                 match pair.value_string.as_str() {
                     "ENDBLK" => {
                         // Read ENDBLK properties
@@ -596,7 +768,7 @@ impl<'a> SectionReader<'a> {
                     }
                     "POLYLINE" => {
                         if let Some(entity) = self.read_polyline()? {
-                            block_entities.push(EntityType::Polyline(entity));
+                            block_entities.push(entity);
                         }
                     }
                     "LWPOLYLINE" => {
@@ -611,22 +783,22 @@ impl<'a> SectionReader<'a> {
                     }
                     "MTEXT" => {
                         if let Some(entity) = self.read_mtext()? {
-                            block_entities.push(EntityType::MText(entity));
+                            block_entities.push(EntityType::MText(Box::new(entity)));
                         }
                     }
                     "SPLINE" => {
                         if let Some(entity) = self.read_spline()? {
-                            block_entities.push(EntityType::Spline(entity));
+                            block_entities.push(EntityType::Spline(Box::new(entity)));
                         }
                     }
                     "DIMENSION" => {
                         if let Some(entity) = self.read_dimension()? {
-                            block_entities.push(EntityType::Dimension(entity));
+                            block_entities.push(EntityType::Dimension(Box::new(entity)));
                         }
                     }
                     "HATCH" => {
                         if let Some(entity) = self.read_hatch()? {
-                            block_entities.push(EntityType::Hatch(entity));
+                            block_entities.push(EntityType::Hatch(Box::new(entity)));
                         }
                     }
                     "SOLID" | "TRACE" => {
@@ -656,12 +828,12 @@ impl<'a> SectionReader<'a> {
                     }
                     "ATTDEF" => {
                         if let Some(entity) = self.read_attdef()? {
-                            block_entities.push(EntityType::AttributeDefinition(entity));
+                            block_entities.push(EntityType::AttributeDefinition(Box::new(entity)));
                         }
                     }
                     "ATTRIB" => {
                         if let Some(entity) = self.read_attrib()? {
-                            block_entities.push(EntityType::AttributeEntity(entity));
+                            block_entities.push(EntityType::AttributeEntity(Box::new(entity)));
                         }
                     }
                     "TOLERANCE" => {
@@ -681,17 +853,17 @@ impl<'a> SectionReader<'a> {
                     }
                     "VIEWPORT" => {
                         if let Some(entity) = self.read_viewport()? {
-                            block_entities.push(EntityType::Viewport(entity));
+                            block_entities.push(EntityType::Viewport(Box::new(entity)));
                         }
                     }
                     "LEADER" => {
                         if let Some(entity) = self.read_leader()? {
-                            block_entities.push(EntityType::Leader(entity));
+                            block_entities.push(EntityType::Leader(Box::new(entity)));
                         }
                     }
                     "MULTILEADER" | "MLEADER" => {
                         if let Some(entity) = self.read_multileader()? {
-                            block_entities.push(EntityType::MultiLeader(entity));
+                            block_entities.push(EntityType::MultiLeader(Box::new(entity)));
                         }
                     }
                     "MLINE" => {
@@ -706,7 +878,7 @@ impl<'a> SectionReader<'a> {
                     }
                     "IMAGE" => {
                         if let Some(entity) = self.read_raster_image()? {
-                            block_entities.push(EntityType::RasterImage(entity));
+                            block_entities.push(EntityType::RasterImage(Box::new(entity)));
                         }
                     }
                     "3DSOLID" => {
@@ -726,7 +898,7 @@ impl<'a> SectionReader<'a> {
                     }
                     "ACAD_TABLE" | "TABLE" => {
                         if let Some(entity) = self.read_table_entity()? {
-                            block_entities.push(EntityType::Table(entity));
+                            block_entities.push(EntityType::Table(Box::new(entity)));
                         }
                     }
                     "PDFUNDERLAY" | "DWFUNDERLAY" | "DGNUNDERLAY" => {
@@ -778,7 +950,7 @@ impl<'a> SectionReader<'a> {
                 }
                 8 => {
                     // Layer
-                    layer = pair.value_string.clone();
+                    layer = pair.value_string.to_string();
                 }
                 _ => {}
             }
@@ -794,216 +966,122 @@ impl<'a> SectionReader<'a> {
     pub fn read_entities(&mut self, document: &mut CadDocument) -> Result<()> {
         // Read entities until ENDSEC
         while let Some(pair) = self.reader.read_pair()? {
-            if pair.code == 0 && pair.value_string == "ENDSEC" {
+            if self.is_section_end(&pair, &mut document.notifications)? {
                 break;
             }
             
             // Entities start with code 0
             if pair.code == 0 {
-                let entity_type = pair.value_string.clone();
-                
-                match entity_type.as_str() {
-                    "POINT" => {
-                        if let Some(entity) = self.read_point()? {
-                            let _ = document.add_entity(EntityType::Point(entity));
-                        }
-                    }
-                    "LINE" => {
-                        if let Some(entity) = self.read_line()? {
-                            let _ = document.add_entity(EntityType::Line(entity));
-                        }
-                    }
-                    "CIRCLE" => {
-                        if let Some(entity) = self.read_circle()? {
-                            let _ = document.add_entity(EntityType::Circle(entity));
-                        }
-                    }
-                    "ARC" => {
-                        if let Some(entity) = self.read_arc()? {
-                            let _ = document.add_entity(EntityType::Arc(entity));
-                        }
-                    }
-                    "ELLIPSE" => {
-                        if let Some(entity) = self.read_ellipse()? {
-                            let _ = document.add_entity(EntityType::Ellipse(entity));
-                        }
-                    }
-                    "POLYLINE" => {
-                        if let Some(entity) = self.read_polyline()? {
-                            let _ = document.add_entity(EntityType::Polyline(entity));
-                        }
-                    }
-                    "LWPOLYLINE" => {
-                        if let Some(entity) = self.read_lwpolyline()? {
-                            let _ = document.add_entity(EntityType::LwPolyline(entity));
-                        }
-                    }
-                    "TEXT" => {
-                        if let Some(entity) = self.read_text()? {
-                            let _ = document.add_entity(EntityType::Text(entity));
-                        }
-                    }
-                    "MTEXT" => {
-                        if let Some(entity) = self.read_mtext()? {
-                            let _ = document.add_entity(EntityType::MText(entity));
-                        }
-                    }
-                    "SPLINE" => {
-                        if let Some(entity) = self.read_spline()? {
-                            let _ = document.add_entity(EntityType::Spline(entity));
-                        }
-                    }
-                    "DIMENSION" => {
-                        if let Some(entity) = self.read_dimension()? {
-                            let _ = document.add_entity(EntityType::Dimension(entity));
-                        }
-                    }
-                    "HATCH" => {
-                        if let Some(entity) = self.read_hatch()? {
-                            let _ = document.add_entity(EntityType::Hatch(entity));
-                        }
-                    }
-                    "SOLID" | "TRACE" => {
-                        if let Some(entity) = self.read_solid()? {
-                            let _ = document.add_entity(EntityType::Solid(entity));
-                        }
-                    }
-                    "3DFACE" => {
-                        if let Some(entity) = self.read_face3d()? {
-                            let _ = document.add_entity(EntityType::Face3D(entity));
-                        }
-                    }
-                    "INSERT" => {
-                        if let Some(entity) = self.read_insert()? {
-                            let _ = document.add_entity(EntityType::Insert(entity));
-                        }
-                    }
-                    "RAY" => {
-                        if let Some(entity) = self.read_ray()? {
-                            let _ = document.add_entity(EntityType::Ray(entity));
-                        }
-                    }
-                    "XLINE" => {
-                        if let Some(entity) = self.read_xline()? {
-                            let _ = document.add_entity(EntityType::XLine(entity));
-                        }
-                    }
-                    "ATTDEF" => {
-                        if let Some(entity) = self.read_attdef()? {
-                            let _ = document.add_entity(EntityType::AttributeDefinition(entity));
-                        }
-                    }
-                    "TOLERANCE" => {
-                        if let Some(entity) = self.read_tolerance()? {
-                            let _ = document.add_entity(EntityType::Tolerance(entity));
-                        }
-                    }
-                    "SHAPE" => {
-                        if let Some(entity) = self.read_shape()? {
-                            let _ = document.add_entity(EntityType::Shape(entity));
-                        }
-                    }
-                    "WIPEOUT" => {
-                        if let Some(entity) = self.read_wipeout()? {
-                            let _ = document.add_entity(EntityType::Wipeout(entity));
-                        }
-                    }
-                    "VIEWPORT" => {
-                        if let Some(entity) = self.read_viewport()? {
-                            let _ = document.add_entity(EntityType::Viewport(entity));
-                        }
-                    }
-                    "ATTRIB" => {
-                        if let Some(entity) = self.read_attrib()? {
-                            let _ = document.add_entity(EntityType::AttributeEntity(entity));
-                        }
-                    }
-                    "LEADER" => {
-                        if let Some(entity) = self.read_leader()? {
-                            let _ = document.add_entity(EntityType::Leader(entity));
-                        }
-                    }
-                    "MULTILEADER" | "MLEADER" => {
-                        if let Some(entity) = self.read_multileader()? {
-                            let _ = document.add_entity(EntityType::MultiLeader(entity));
-                        }
-                    }
-                    "MLINE" => {
-                        if let Some(entity) = self.read_mline()? {
-                            let _ = document.add_entity(EntityType::MLine(entity));
-                        }
-                    }
-                    "MESH" => {
-                        if let Some(entity) = self.read_mesh()? {
-                            let _ = document.add_entity(EntityType::Mesh(entity));
-                        }
-                    }
-                    "IMAGE" => {
-                        if let Some(entity) = self.read_raster_image()? {
-                            let _ = document.add_entity(EntityType::RasterImage(entity));
-                        }
-                    }
-                    "3DSOLID" => {
-                        if let Some(entity) = self.read_solid3d()? {
-                            let _ = document.add_entity(EntityType::Solid3D(entity));
-                        }
-                    }
-                    "REGION" => {
-                        if let Some(entity) = self.read_region()? {
-                            let _ = document.add_entity(EntityType::Region(entity));
-                        }
-                    }
-                    "BODY" => {
-                        if let Some(entity) = self.read_body()? {
-                            let _ = document.add_entity(EntityType::Body(entity));
-                        }
-                    }
-                    "ACAD_TABLE" | "TABLE" => {
-                        if let Some(entity) = self.read_table_entity()? {
-                            let _ = document.add_entity(EntityType::Table(entity));
-                        }
-                    }
-                    "PDFUNDERLAY" | "DWFUNDERLAY" | "DGNUNDERLAY" => {
-                        if let Some(entity) = self.read_underlay(&entity_type)? {
-                            let _ = document.add_entity(EntityType::Underlay(entity));
-                        }
-                    }
-                    "OLE2FRAME" => {
-                        if let Some(entity) = self.read_ole2frame()? {
-                            let _ = document.add_entity(EntityType::Ole2Frame(entity));
-                        }
-                    }
-                    "SEQEND" => {
-                        // Standalone SEQEND — skip (normally consumed by polyline/insert reader)
-                        self.skip_entity()?;
-                    }
-                    _ => {
-                        // Read as unknown entity — common fields preserved, entity-specific codes discarded
-                        document.notifications.notify(
-                            crate::notification::NotificationType::NotImplemented,
-                            format!("Entity not supported, read as UnknownEntity: {}", entity_type),
-                        );
-                        let entity = self.read_unknown_entity(&entity_type)?;
-                        let _ = document.add_entity(EntityType::Unknown(entity));
-                    }
+                if let Some(entity) = self.read_entity(&pair.value_string, &mut document.notifications)? {
+                    let _ = document.add_entity(entity);
                 }
+                self.tick()?;
             }
         }
         
         Ok(())
     }
+
+    /// Read the entity whose `0` marker has just been consumed.
+    ///
+    /// Returns `None` for markers that do not produce an entity on their own
+    /// (a stray `SEQEND`, or an entity the specific reader rejected).
+    pub fn read_entity(
+        &mut self,
+        entity_type: &str,
+        notifications: &mut NotificationCollection,
+    ) -> Result<Option<EntityType>> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::trace_span!("entity", kind = entity_type).entered();
+        if self.skip_filtered(entity_type)? {
+            return Ok(None);
+        }
+        let entity = match entity_type {
+            "POINT" => self.read_point()?.map(EntityType::Point),
+            "LINE" => self.read_line()?.map(EntityType::Line),
+            "CIRCLE" => self.read_circle()?.map(EntityType::Circle),
+            "ARC" => self.read_arc()?.map(EntityType::Arc),
+            "ELLIPSE" => self.read_ellipse()?.map(EntityType::Ellipse),
+            "POLYLINE" => self.read_polyline()?,
+            "LWPOLYLINE" => self.read_lwpolyline()?.map(EntityType::LwPolyline),
+            "TEXT" => self.read_text()?.map(EntityType::Text),
+            "MTEXT" => self.read_mtext()?.map(|e| EntityType::MText(Box::new(e))),
+            "SPLINE" => self.read_spline()?.map(|e| EntityType::Spline(Box::new(e))),
+            "DIMENSION" => self.read_dimension()?.map(|e| EntityType::Dimension(Box::new(e))),
+            "HATCH" => self.read_hatch()?.map(|e| EntityType::Hatch(Box::new(e))),
+            "SOLID" | "TRACE" => self.read_solid()?.map(EntityType::Solid),
+            "3DFACE" => self.read_face3d()?.map(EntityType::Face3D),
+            "INSERT" => self.read_insert()?.map(EntityType::Insert),
+            "RAY" => self.read_ray()?.map(EntityType::Ray),
+            "XLINE" => self.read_xline()?.map(EntityType::XLine),
+            "ATTDEF" => self.read_attdef()?.map(|e| EntityType::AttributeDefinition(Box::new(e))),
+            "TOLERANCE" => self.read_tolerance()?.map(EntityType::Tolerance),
+            "SHAPE" => self.read_shape()?.map(EntityType::Shape),
+            "WIPEOUT" => self.read_wipeout()?.map(EntityType::Wipeout),
+            "VIEWPORT" => self.read_viewport()?.map(|e| EntityType::Viewport(Box::new(e))),
+            "ATTRIB" => self.read_attrib()?.map(|e| EntityType::AttributeEntity(Box::new(e))),
+            "LEADER" => self.read_leader()?.map(|e| EntityType::Leader(Box::new(e))),
+            "MULTILEADER" | "MLEADER" => self.read_multileader()?.map(|e| EntityType::MultiLeader(Box::new(e))),
+            "MLINE" => self.read_mline()?.map(EntityType::MLine),
+            "MESH" => self.read_mesh()?.map(EntityType::Mesh),
+            "IMAGE" => self.read_raster_image()?.map(|e| EntityType::RasterImage(Box::new(e))),
+            "3DSOLID" => self.read_solid3d()?.map(EntityType::Solid3D),
+            "REGION" => self.read_region()?.map(EntityType::Region),
+            "BODY" => self.read_body()?.map(EntityType::Body),
+            "ACAD_TABLE" | "TABLE" => self.read_table_entity()?.map(|e| EntityType::Table(Box::new(e))),
+            "PDFUNDERLAY" | "DWFUNDERLAY" | "DGNUNDERLAY" => self.read_underlay(entity_type)?.map(EntityType::Underlay),
+            "OLE2FRAME" => self.read_ole2frame()?.map(EntityType::Ole2Frame),
+            "SEQEND" => {
+                // Standalone SEQEND — skip (normally consumed by polyline/insert reader)
+                self.skip_entity()?;
+                None
+            }
+            _ => {
+                if self.custom_types.is_some_and(|custom| custom.entity_reader(entity_type).is_some()) {
+                    return self.read_custom_entity(entity_type).map(Some);
+                }
+                // Read as unknown entity — common fields preserved, entity-specific codes discarded
+                let mut notification = Notification::new(
+                    NotificationType::NotImplemented,
+                    format!(
+                        "Entity not supported, read as UnknownEntity: {} at {}",
+                        entity_type,
+                        self.reader.location()
+                    ),
+                )
+                .with_code(NotificationCode::UnknownEntity)
+                .with_offset(self.reader.byte_offset());
+                let entity = self.read_unknown_entity(entity_type)?;
+                if !entity.common.handle.is_null() {
+                    notification = notification.with_handle(entity.common.handle);
+                }
+                notifications.push(notification);
+                Some(EntityType::Unknown(entity))
+            }
+        };
+
+        Ok(entity)
+    }
     
     /// Read the OBJECTS section
     pub fn read_objects(&mut self, document: &mut CadDocument) -> Result<()> {
+        let mut first_object = true;
         while let Some(pair) = self.reader.read_pair()? {
-            if pair.code == 0 && pair.value_string == "ENDSEC" {
+            if self.is_section_end(&pair, &mut document.notifications)? {
                 break;
             }
 
             if pair.code == 0 {
+                #[cfg(feature = "tracing")]
+                let _span = tracing::trace_span!("object", kind = %pair.value_string).entered();
+                let is_first = std::mem::replace(&mut first_object, false);
                 match pair.value_string.as_str() {
                     "DICTIONARY" => {
                         if let Some(obj) = self.read_dictionary()? {
+                            // The section starts with the named object dictionary
+                            if is_first {
+                                document.header.named_objects_dict_handle = obj.handle;
+                            }
                             document.objects.insert(obj.handle, ObjectType::Dictionary(obj));
                         }
                     }
@@ -1078,7 +1156,7 @@ impl<'a> SectionReader<'a> {
                         }
                     }
                     "GEODATA" => {
-                        let obj = self.read_stub_object::<GeoData>()?;
+                        let obj = self.read_geodata()?;
                         document.objects.insert(obj.handle, ObjectType::GeoData(obj));
                     }
                     "SPATIALFILTER" => {
@@ -1090,6 +1168,10 @@ impl<'a> SectionReader<'a> {
                             document.objects.insert(obj.handle, ObjectType::RasterVariables(obj));
                         }
                     }
+                    "PDFDEFINITION" | "DWFDEFINITION" | "DGNDEFINITION" => {
+                        let obj = self.read_underlay_definition(&pair.value_string)?;
+                        document.objects.insert(obj.handle, ObjectType::UnderlayDefinition(obj));
+                    }
                     "DBCOLOR" => {
                         if let Some(obj) = self.read_bookcolor()? {
                             document.objects.insert(obj.handle, ObjectType::BookColor(obj));
@@ -1105,21 +1187,95 @@ impl<'a> SectionReader<'a> {
                             document.objects.insert(obj.handle, ObjectType::DictionaryWithDefault(obj));
                         }
                     }
+                    "DIMASSOC" => {
+                        let obj = self.read_dim_assoc()?;
+                        document.objects.insert(obj.handle, ObjectType::DimAssoc(obj));
+                    }
+                    "SUN" => {
+                        let obj = self.read_sun()?;
+                        document.objects.insert(obj.handle, ObjectType::Sun(obj));
+                    }
+                    "SOLID_BACKGROUND" | "GRADIENT_BACKGROUND" | "IMAGE_BACKGROUND" | "SKYLIGHT_BACKGROUND"
+                    | "GROUND_PLANE_BACKGROUND" | "IBL_BACKGROUND" => {
+                        let obj = self.read_background(&pair.value_string)?;
+                        document.objects.insert(obj.handle, ObjectType::Background(obj));
+                    }
+                    "RENDERSETTINGS" | "MENTALRAYRENDERSETTINGS" | "RAPIDRTRENDERSETTINGS" => {
+                        let obj = self.read_render_settings(&pair.value_string)?;
+                        document.objects.insert(obj.handle, ObjectType::RenderSettings(obj));
+                    }
                     "WIPEOUTVARIABLES" => {
                         if let Some(obj) = self.read_wipeout_variables()? {
                             document.objects.insert(obj.handle, ObjectType::WipeoutVariables(obj));
                         }
                     }
+                    name if self.custom_types.is_some_and(|custom| custom.object_reader(name).is_some()) => {
+                        let object = self.read_custom_object(name)?;
+                        document.objects.insert(object.handle(), object);
+                    }
                     _ => {
-                        document.notifications.notify(
-                            crate::notification::NotificationType::NotImplemented,
-                            format!("Object not supported, read as Unknown: {}", pair.value_string),
-                        );
-                        let type_name = pair.value_string.clone();
-                        let handle = self.read_unknown_object_handle()?;
-                        document.objects.insert(handle, ObjectType::Unknown { type_name, handle });
+                        let notification = Notification::new(
+                            NotificationType::NotImplemented,
+                            format!(
+                                "Object not supported, read as Unknown: {} at {}",
+                                pair.value_string,
+                                self.reader.location()
+                            ),
+                        )
+                        .with_code(NotificationCode::UnknownObject)
+                        .with_offset(self.reader.byte_offset());
+                        let raw_pairs = self.read_raw_pairs()?;
+                        let object = unknown_object(&pair.value_string, raw_pairs);
+                        let handle = object.handle();
+                        document.notifications.push(if handle.is_null() {
+                            notification
+                        } else {
+                            notification.with_handle(handle)
+                        });
+                        document.objects.insert(handle, object);
                     }
                 }
+                self.tick()?;
+            }
+        }
+
+        // TABLESTYLE has no name field; it is the key in the owning dictionary
+        let names: Vec<(Handle, String)> = document
+            .objects
+            .values()
+            .filter_map(|object| match object {
+                ObjectType::Dictionary(dictionary) => Some(dictionary),
+                _ => None,
+            })
+            .flat_map(|dictionary| dictionary.entries.iter().map(|(key, handle)| (*handle, key.clone())))
+            .filter(|(handle, _)| matches!(document.objects.get(handle), Some(ObjectType::TableStyle(_))))
+            .collect();
+        for (handle, name) in names {
+            if let Some(ObjectType::TableStyle(style)) = document.objects.get_mut(&handle) {
+                style.name = name;
+            }
+        }
+
+        // A SUN belongs to the viewport pointing at it; the *Active entry
+        // kept from the new document has a handle of its own
+        let suns: Vec<(Handle, Handle)> = document
+            .vports
+            .iter()
+            .map(|vport| (vport.sun_handle, vport.handle))
+            .chain(
+                document
+                    .entities
+                    .values()
+                    .chain(document.block_records.iter().flat_map(|record| record.entities.iter()))
+                    .filter_map(|entity| match entity {
+                        EntityType::Viewport(viewport) => Some((viewport.sun_handle, viewport.common.handle)),
+                        _ => None,
+                    }),
+            )
+            .collect();
+        for (sun, viewport) in suns {
+            if let Some(ObjectType::Sun(sun)) = document.objects.get_mut(&sun) {
+                sun.owner = viewport;
             }
         }
 
@@ -1150,6 +1306,12 @@ impl<'a> SectionReader<'a> {
                         dict.owner = Handle::new(h);
                     }
                 }
+                102 => match pair.value_string.trim() {
+                    "{ACAD_REACTORS" => dict.reactors = self.read_reactor_handles()?,
+                    "{ACAD_XDICTIONARY" => dict.xdictionary_handle = self.read_xdictionary_handle()?,
+                    group if group.starts_with('{') => self.skip_defined_group()?,
+                    _ => {}
+                },
                 281 => {
                     // Duplicate record cloning flag
                     if let Some(value) = pair.as_i16() {
@@ -1164,7 +1326,7 @@ impl<'a> SectionReader<'a> {
                 }
                 3 => {
                     // Entry key (name)
-                    current_key = Some(pair.value_string.clone());
+                    current_key = Some(pair.value_string.to_string());
                 }
                 350 | 360 => {
                     // Entry value (handle) - 350 is soft owner, 360 is hard owner
@@ -1184,6 +1346,7 @@ impl<'a> SectionReader<'a> {
     /// Read a LAYOUT object
     fn read_layout(&mut self) -> Result<Option<Layout>> {
         let mut layout = Layout::new("");
+        let mut in_layout_data = false;
 
         while let Some(pair) = self.reader.read_pair()? {
             match pair.code {
@@ -1198,15 +1361,35 @@ impl<'a> SectionReader<'a> {
                         layout.handle = Handle::new(h);
                     }
                 }
+                100 => {
+                    in_layout_data = pair.value_string == "AcDbLayout";
+                }
+                _ if !in_layout_data && read_plot_settings_pair(&mut layout.plot_settings, &pair) => {}
+                102 => match pair.value_string.trim() {
+                    "{ACAD_REACTORS" => layout.reactors = self.read_reactor_handles()?,
+                    "{ACAD_XDICTIONARY" => layout.xdictionary_handle = self.read_xdictionary_handle()?,
+                    group if group.starts_with('{') => self.skip_defined_group()?,
+                    _ => {}
+                },
                 330 => {
-                    // Owner handle
+                    // Owner handle, or the block record in AcDbLayout
+                    if let Ok(h) = u64::from_str_radix(&pair.value_string, 16) {
+                        if in_layout_data {
+                            layout.block_record = Handle::new(h);
+                        } else {
+                            layout.owner = Handle::new(h);
+                        }
+                    }
+                }
+                331 => {
+                    // Last active viewport
                     if let Ok(h) = u64::from_str_radix(&pair.value_string, 16) {
-                        layout.owner = Handle::new(h);
+                        layout.viewport = Handle::new(h);
                     }
                 }
                 1 => {
                     // Layout name
-                    layout.name = pair.value_string.clone();
+                    layout.name = pair.value_string.to_string();
                 }
                 70 => {
                     // Layout flags
@@ -1260,7 +1443,7 @@ impl<'a> SectionReader<'a> {
             match pair.code {
                 5 => { if let Ok(h) = u64::from_str_radix(&pair.value_string, 16) { obj.handle = Handle::new(h); } }
                 330 => { if let Ok(h) = u64::from_str_radix(&pair.value_string, 16) { obj.owner = Handle::new(h); } }
-                2 => obj.description = pair.value_string.clone(),
+                2 => obj.description = pair.value_string.to_string(),
                 70 => { if let Some(v) = pair.as_i16() { obj.style_type = v; } }
                 71 => { if let Some(v) = pair.as_i16() { obj.face_lighting_model = v; } }
                 72 => { if let Some(v) = pair.as_i16() { obj.face_lighting_quality = v; } }
@@ -1283,8 +1466,8 @@ impl<'a> SectionReader<'a> {
             match pair.code {
                 5 => { if let Ok(h) = u64::from_str_radix(&pair.value_string, 16) { obj.handle = Handle::new(h); } }
                 330 => { if let Ok(h) = u64::from_str_radix(&pair.value_string, 16) { obj.owner = Handle::new(h); } }
-                1 => obj.name = pair.value_string.clone(),
-                2 => obj.description = pair.value_string.clone(),
+                1 => obj.name = pair.value_string.to_string(),
+                2 => obj.description = pair.value_string.to_string(),
                 _ => {}
             }
         }
@@ -1294,17 +1477,267 @@ impl<'a> SectionReader<'a> {
     /// Read an IMAGEDEF_REACTOR object
     fn read_imagedef_reactor(&mut self) -> Result<Option<ImageDefinitionReactor>> {
         let mut obj = ImageDefinitionReactor::new(Handle::NULL);
+        let mut in_subclass = false;
         while let Some(pair) = self.reader.read_pair()? {
             if pair.code == 0 { self.reader.push_back(pair); break; }
             match pair.code {
                 5 => { if let Ok(h) = u64::from_str_radix(&pair.value_string, 16) { obj.handle = Handle::new(h); } }
-                330 => { if let Ok(h) = u64::from_str_radix(&pair.value_string, 16) { obj.owner = Handle::new(h); } }
+                100 => in_subclass = pair.value_string == "AcDbRasterImageDefReactor",
+                330 => {
+                    if let Ok(h) = u64::from_str_radix(&pair.value_string, 16) {
+                        if in_subclass { obj.image_handle = Handle::new(h); } else { obj.owner = Handle::new(h); }
+                    }
+                }
                 _ => {}
             }
         }
         Ok(Some(obj))
     }
 
+    /// Read a GEODATA object
+    fn read_geodata(&mut self) -> Result<GeoData> {
+        let mut obj = GeoData::new();
+        let mut in_subclass = false;
+        let mut coordinate_system = String::new();
+        while let Some(pair) = self.reader.read_pair()? {
+            if pair.code == 0 { self.reader.push_back(pair); break; }
+            match pair.code {
+                5 => { if let Ok(h) = u64::from_str_radix(&pair.value_string, 16) { obj.handle = Handle::new(h); } }
+                100 => in_subclass = pair.value_string == "AcDbGeoData",
+                330 => {
+                    if let Ok(h) = u64::from_str_radix(&pair.value_string, 16) {
+                        if in_subclass { obj.host_block = Handle::new(h); } else { obj.owner = Handle::new(h); }
+                    }
+                }
+                90 => { if let Some(v) = pair.as_i32() { obj.version = v; } }
+                70 => { if let Some(v) = pair.as_i16() { obj.coordinate_type = v; } }
+                10 => { if let Some(v) = pair.as_double() { obj.design_point.x = v; } }
+                20 => { if let Some(v) = pair.as_double() { obj.design_point.y = v; } }
+                30 => { if let Some(v) = pair.as_double() { obj.design_point.z = v; } }
+                11 => { if let Some(v) = pair.as_double() { obj.reference_point.x = v; } }
+                21 => { if let Some(v) = pair.as_double() { obj.reference_point.y = v; } }
+                31 => { if let Some(v) = pair.as_double() { obj.reference_point.z = v; } }
+                40 => { if let Some(v) = pair.as_double() { obj.horizontal_unit_scale = v; } }
+                91 => { if let Some(v) = pair.as_i32() { obj.horizontal_units = v; } }
+                41 => { if let Some(v) = pair.as_double() { obj.vertical_unit_scale = v; } }
+                92 => { if let Some(v) = pair.as_i32() { obj.vertical_units = v; } }
+                210 => { if let Some(v) = pair.as_double() { obj.up_direction.x = v; } }
+                220 => { if let Some(v) = pair.as_double() { obj.up_direction.y = v; } }
+                230 => { if let Some(v) = pair.as_double() { obj.up_direction.z = v; } }
+                12 => { if let Some(v) = pair.as_double() { obj.north_direction.x = v; } }
+                22 => { if let Some(v) = pair.as_double() { obj.north_direction.y = v; } }
+                // The definition is split over 303 chunks ending with 301
+                303 | 301 => coordinate_system.push_str(&pair.value_string),
+                _ => {}
+            }
+        }
+        obj.coordinate_system = coordinate_system;
+        Ok(obj)
+    }
+
+    /// Read a SUN object
+    fn read_sun(&mut self) -> Result<Sun> {
+        let mut obj = Sun::new();
+        while let Some(pair) = self.reader.read_pair()? {
+            if pair.code == 0 { self.reader.push_back(pair); break; }
+            match pair.code {
+                5 => { if let Ok(h) = u64::from_str_radix(&pair.value_string, 16) { obj.handle = Handle::new(h); } }
+                330 => { if let Ok(h) = u64::from_str_radix(&pair.value_string, 16) { obj.owner = Handle::new(h); } }
+                90 => { if let Some(v) = pair.as_i32() { obj.version = v; } }
+                290 => { if let Some(v) = pair.as_bool() { obj.is_on = v; } }
+                63 => { if let Some(v) = pair.as_i16() { obj.color = Color::from_index(v); } }
+                421 => {
+                    if let Some(v) = pair.as_i32() {
+                        obj.color = Color::from_rgb((v >> 16) as u8, (v >> 8) as u8, v as u8);
+                    }
+                }
+                40 => { if let Some(v) = pair.as_double() { obj.intensity = v; } }
+                291 => { if let Some(v) = pair.as_bool() { obj.has_shadow = v; } }
+                91 => { if let Some(v) = pair.as_i32() { obj.julian_day = v; } }
+                92 => { if let Some(v) = pair.as_i32() { obj.time = v; } }
+                292 => { if let Some(v) = pair.as_bool() { obj.daylight_savings = v; } }
+                70 => { if let Some(v) = pair.as_i16() { obj.shadow_type = v; } }
+                71 => { if let Some(v) = pair.as_i16() { obj.shadow_map_size = v; } }
+                280 => { if let Some(v) = pair.as_i16() { obj.shadow_softness = v; } }
+                _ => {}
+            }
+        }
+        Ok(obj)
+    }
+
+    /// Read a DIMASSOC object
+    fn read_dim_assoc(&mut self) -> Result<DimAssoc> {
+        let mut obj = DimAssoc::new(Handle::NULL);
+        let mut associativity = 0;
+        let mut in_subclass = false;
+        let mut points: Vec<OsnapPointRef> = Vec::new();
+        while let Some(pair) = self.reader.read_pair()? {
+            if pair.code == 0 { self.reader.push_back(pair); break; }
+            let handle = u64::from_str_radix(pair.value_string.trim(), 16).map(Handle::new);
+            let point = points.last_mut();
+            match (pair.code, point) {
+                (5, _) => { if let Ok(h) = handle { obj.handle = h; } }
+                (102, _) if pair.value_string.trim() == "{ACAD_REACTORS" => obj.reactors = self.read_reactor_handles()?,
+                (100, _) => in_subclass = pair.value_string == "AcDbDimAssoc",
+                (330, _) => {
+                    if let Ok(h) = handle {
+                        if in_subclass { obj.dimension = h; } else { obj.owner = h; }
+                    }
+                }
+                (90, None) => { if let Some(v) = pair.as_i32() { associativity = v; } }
+                (70, None) => { if let Some(v) = pair.as_i16() { obj.trans_space = v != 0; } }
+                (71, None) => { if let Some(v) = pair.as_i16() { obj.rotated_type = v; } }
+                (1, _) => points.push(OsnapPointRef {
+                    objects: Vec::new(),
+                    ..OsnapPointRef::new(ObjectSnapType::None, Handle::NULL, Vector3::ZERO)
+                }),
+                (72, Some(point)) => { if let Some(v) = pair.as_i16() { point.osnap_type = ObjectSnapType::from_code(v); } }
+                (331, Some(point)) => { if let Ok(h) = handle { point.objects.push(h); } }
+                (73, Some(point)) => { if let Some(v) = pair.as_i16() { point.subentity_type = v; } }
+                (91, Some(point)) => { if let Some(v) = pair.as_i32() { point.gs_marker = v; } }
+                (40, Some(point)) => { if let Some(v) = pair.as_double() { point.near_parameter = v; } }
+                (10, Some(point)) => { if let Some(v) = pair.as_double() { point.point.x = v; } }
+                (20, Some(point)) => { if let Some(v) = pair.as_double() { point.point.y = v; } }
+                (30, Some(point)) => { if let Some(v) = pair.as_double() { point.point.z = v; } }
+                (332, Some(point)) => { if let Ok(h) = handle { point.intersection_objects.push(h); } }
+                (74, Some(point)) => { if let Some(v) = pair.as_i16() { point.intersection_subentity_type = v; } }
+                (92, Some(point)) => { if let Some(v) = pair.as_i32() { point.intersection_gs_marker = v; } }
+                (75, Some(point)) => { if let Some(v) = pair.as_i16() { point.has_last_point_ref = v != 0; } }
+                _ => {}
+            }
+        }
+
+        // References follow in the order of the associativity bits
+        let mut points = points.into_iter();
+        for index in (0..4).filter(|index| associativity & (1 << index) != 0) {
+            obj.points[index] = points.next();
+        }
+        Ok(obj)
+    }
+
+    /// Read a background object (SOLID_BACKGROUND, SKYLIGHT_BACKGROUND, ...)
+    fn read_background(&mut self, type_name: &str) -> Result<Background> {
+        let mut obj = Background::new(BackgroundKind::Solid { color: Color::ByLayer });
+        let mut subclass = String::new();
+        let mut data: Vec<DxfCodePair> = Vec::new();
+        while let Some(pair) = self.reader.read_pair()? {
+            if pair.code == 0 { self.reader.push_back(pair); break; }
+            match pair.code {
+                5 => { if let Ok(h) = u64::from_str_radix(&pair.value_string, 16) { obj.handle = Handle::new(h); } }
+                100 => subclass = pair.value_string.to_string(),
+                _ if subclass.is_empty() => {
+                    if pair.code == 330 {
+                        if let Ok(h) = u64::from_str_radix(&pair.value_string, 16) { obj.owner = Handle::new(h); }
+                    }
+                }
+                90 if subclass == "AcDbBackground" => { if let Some(v) = pair.as_i32() { obj.version = v; } }
+                _ if subclass == "AcDbBackground" => {}
+                _ => data.push(pair),
+            }
+        }
+
+        // The type's own data starts with its version
+        let values = data.iter().skip(1);
+        let colors: Vec<Color> = values
+            .clone()
+            .filter(|pair| pair.code == 90)
+            .filter_map(|pair| pair.as_i32())
+            .map(Color::from_cm_value)
+            .collect();
+        let color = |i: usize| colors.get(i).copied().unwrap_or(Color::ByLayer);
+        let double = |code: i32| values.clone().find(|pair| pair.code == code).and_then(|pair| pair.as_double());
+        let flag = |code: i32| values.clone().find(|pair| pair.code == code).and_then(|pair| pair.as_bool());
+        obj.kind = match type_name {
+            "SOLID_BACKGROUND" => BackgroundKind::Solid { color: color(0) },
+            "GRADIENT_BACKGROUND" => BackgroundKind::Gradient {
+                top: color(0),
+                middle: color(1),
+                bottom: color(2),
+                horizon: double(140).unwrap_or(0.0),
+                height: double(141).unwrap_or(0.0),
+                rotation: double(142).unwrap_or(0.0),
+            },
+            "IMAGE_BACKGROUND" => BackgroundKind::Image {
+                file_name: values.clone().find(|pair| pair.code == 300).map(|pair| pair.value_string.to_string()).unwrap_or_default(),
+                fit_to_screen: flag(290).unwrap_or(false),
+                maintain_aspect_ratio: flag(291).unwrap_or(false),
+                use_tiling: flag(292).unwrap_or(false),
+                offset: Vector2::new(double(140).unwrap_or(0.0), double(141).unwrap_or(0.0)),
+                scale: Vector2::new(double(142).unwrap_or(1.0), double(143).unwrap_or(1.0)),
+            },
+            "SKYLIGHT_BACKGROUND" => BackgroundKind::Sky {
+                sun: values
+                    .clone()
+                    .find(|pair| pair.code == 340)
+                    .and_then(|pair| u64::from_str_radix(pair.value_string.trim(), 16).ok())
+                    .map(Handle::new)
+                    .unwrap_or(Handle::NULL),
+            },
+            _ => BackgroundKind::Other {
+                type_name: type_name.to_string(),
+                subclass,
+                data: data.into_iter().map(|pair| (pair.code, pair.value_string.into_string())).collect(),
+            },
+        };
+        Ok(obj)
+    }
+
+    /// Read a RENDERSETTINGS, MENTALRAYRENDERSETTINGS or RAPIDRTRENDERSETTINGS
+    /// object
+    fn read_render_settings(&mut self, type_name: &str) -> Result<RenderSettings> {
+        let mut obj = RenderSettings::new("");
+        obj.type_name = type_name.to_string();
+        let mut subclass = String::new();
+        let (mut flags, mut strings, mut integers) = (0, 0, 0);
+        while let Some(pair) = self.reader.read_pair()? {
+            if pair.code == 0 { self.reader.push_back(pair); break; }
+            match pair.code {
+                5 => { if let Ok(h) = u64::from_str_radix(&pair.value_string, 16) { obj.handle = Handle::new(h); } }
+                100 => {
+                    subclass = pair.value_string.to_string();
+                    if subclass != "AcDbRenderSettings" {
+                        obj.subclass = subclass.clone();
+                    }
+                }
+                330 if subclass.is_empty() => {
+                    if let Ok(h) = u64::from_str_radix(&pair.value_string, 16) { obj.owner = Handle::new(h); }
+                }
+                _ if subclass.is_empty() => {}
+                // Repeated codes of the common data are told apart by position
+                1 | 90 | 290 if subclass == "AcDbRenderSettings" => match pair.code {
+                    1 => {
+                        let value = pair.value_string.to_string();
+                        match strings {
+                            0 => obj.name = value,
+                            1 => obj.environment_image_file_name = value,
+                            _ => obj.description = value,
+                        }
+                        strings += 1;
+                    }
+                    90 => {
+                        let value = pair.as_i32().unwrap_or(0);
+                        if integers == 0 { obj.version = value; } else { obj.display_index = value; }
+                        integers += 1;
+                    }
+                    _ => {
+                        let value = pair.as_bool().unwrap_or(false);
+                        match flags {
+                            0 => obj.fog_enabled = value,
+                            1 => obj.fog_background_enabled = value,
+                            2 => obj.back_faces_enabled = value,
+                            3 => obj.environment_image_enabled = value,
+                            _ => obj.predefined = value,
+                        }
+                        flags += 1;
+                    }
+                },
+                _ if subclass == "AcDbRenderSettings" => {}
+                _ => obj.data.push((pair.code, pair.value_string.into_string())),
+            }
+        }
+        Ok(obj)
+    }
+
     /// Read a RASTERVARIABLES object
     fn read_raster_variables(&mut self) -> Result<Option<RasterVariables>> {
         let mut obj = RasterVariables::new();
@@ -1323,6 +1756,28 @@ impl<'a> SectionReader<'a> {
         Ok(Some(obj))
     }
 
+    /// Read a PDFDEFINITION, DWFDEFINITION or DGNDEFINITION object
+    fn read_underlay_definition(&mut self, type_name: &str) -> Result<UnderlayDefinition> {
+        use crate::entities::underlay::UnderlayType;
+        let mut obj = UnderlayDefinition::new(match type_name {
+            "DWFDEFINITION" => UnderlayType::Dwf,
+            "DGNDEFINITION" => UnderlayType::Dgn,
+            _ => UnderlayType::Pdf,
+        });
+        while let Some(pair) = self.reader.read_pair()? {
+            if pair.code == 0 { self.reader.push_back(pair); break; }
+            match pair.code {
+                5 => { if let Ok(h) = u64::from_str_radix(&pair.value_string, 16) { obj.handle = Handle::new(h); } }
+                330 => { if let Ok(h) = u64::from_str_radix(&pair.value_string, 16) { obj.owner_handle = Handle::new(h); } }
+                102 if pair.value_string.trim() == "{ACAD_REACTORS" => obj.reactors = self.read_reactor_handles()?,
+                1 => obj.file_path = pair.value_string.to_string(),
+                2 => obj.page_name = pair.value_string.to_string(),
+                _ => {}
+            }
+        }
+        Ok(obj)
+    }
+
     /// Read a DBCOLOR object
     fn read_bookcolor(&mut self) -> Result<Option<BookColor>> {
         let mut obj = BookColor::new();
@@ -1331,8 +1786,8 @@ impl<'a> SectionReader<'a> {
             match pair.code {
                 5 => { if let Ok(h) = u64::from_str_radix(&pair.value_string, 16) { obj.handle = Handle::new(h); } }
                 330 => { if let Ok(h) = u64::from_str_radix(&pair.value_string, 16) { obj.owner = Handle::new(h); } }
-                1 => obj.color_name = pair.value_string.clone(),
-                2 => obj.book_name = pair.value_string.clone(),
+                1 => obj.color_name = pair.value_string.to_string(),
+                2 => obj.book_name = pair.value_string.to_string(),
                 _ => {}
             }
         }
@@ -1343,21 +1798,21 @@ impl<'a> SectionReader<'a> {
     fn read_dict_with_default(&mut self) -> Result<Option<DictionaryWithDefault>> {
         let mut obj = DictionaryWithDefault::new();
         let mut current_key: Option<String> = None;
+        let mut in_subclass = false;
 
         while let Some(pair) = self.reader.read_pair()? {
             if pair.code == 0 { self.reader.push_back(pair); break; }
             match pair.code {
                 5 => { if let Ok(h) = u64::from_str_radix(&pair.value_string, 16) { obj.handle = Handle::new(h); } }
+                100 => in_subclass = pair.value_string == "AcDbDictionaryWithDefault",
                 330 => { if let Ok(h) = u64::from_str_radix(&pair.value_string, 16) { obj.owner = Handle::new(h); } }
                 280 => obj.hard_owner = pair.value_string.trim() == "1",
                 281 => { if let Some(v) = pair.as_i16() { obj.duplicate_cloning = v; } }
-                3 => { current_key = Some(pair.value_string.clone()); }
-                340 => {
-                    // Could be default handle or entry value
-                    if let Ok(h) = u64::from_str_radix(&pair.value_string, 16) {
-                        if obj.default_handle == Handle::NULL && current_key.is_none() {
-                            obj.default_handle = Handle::new(h);
-                        }
+                3 => { current_key = Some(pair.value_string.to_string()); }
+                // The default entry follows the AcDbDictionaryWithDefault marker
+                340 if in_subclass => {
+                    if let Ok(h) = u64::from_str_radix(pair.value_string.trim(), 16) {
+                        obj.default_handle = Handle::new(h);
                     }
                 }
                 350 | 360 => {
@@ -1402,27 +1857,10 @@ impl<'a> SectionReader<'a> {
         Ok(obj)
     }
 
-    /// Read an unknown object, extracting only the handle, then skip remaining pairs.
-    fn read_unknown_object_handle(&mut self) -> Result<Handle> {
-        let mut handle = Handle::NULL;
-        while let Some(pair) = self.reader.read_pair()? {
-            if pair.code == 0 {
-                self.reader.push_back(pair);
-                break;
-            }
-            if pair.code == 5 {
-                if let Ok(h) = u64::from_str_radix(&pair.value_string, 16) {
-                    handle = Handle::new(h);
-                }
-            }
-        }
-        Ok(handle)
-    }
-    
     /// Skip to ENDTAB
-    fn skip_to_endtab(&mut self) -> Result<()> {
+    fn skip_to_endtab(&mut self, notifications: &mut NotificationCollection) -> Result<()> {
         while let Some(pair) = self.reader.read_pair()? {
-            if pair.code == 0 && pair.value_string == "ENDTAB" {
+            if self.is_table_end(&pair, notifications)? {
                 break;
             }
         }
@@ -1434,13 +1872,21 @@ impl<'a> SectionReader<'a> {
     /// Read LAYER table
     fn read_layer_table(&mut self, document: &mut CadDocument) -> Result<()> {
         while let Some(pair) = self.reader.read_pair()? {
-            if pair.code == 0 && pair.value_string == "ENDTAB" {
+            if self.is_table_end(&pair, &mut document.notifications)? {
                 break;
             }
 
             if pair.code == 0 && pair.value_string == "LAYER" {
-                if let Some(layer) = self.read_layer_entry()? {
-                    let _ = document.layers.add(layer);
+                if let Some(mut layer) = self.read_layer_entry()? {
+                    // The file's layer replaces the document's own "0"
+                    if let Some(existing) = document.layers.get_mut(&layer.name) {
+                        if layer.handle.is_null() {
+                            layer.handle = existing.handle;
+                        }
+                        *existing = layer;
+                    } else {
+                        let _ = document.layers.add(layer);
+                    }
                 }
             }
         }
@@ -1459,13 +1905,19 @@ impl<'a> SectionReader<'a> {
             }
 
             match pair.code {
-                2 => layer.name = pair.value_string.clone(),
+                5 => { if let Ok(h) = u64::from_str_radix(&pair.value_string, 16) { layer.handle = Handle::new(h); } }
+                102 => match pair.value_string.trim() {
+                    "{ACAD_XDICTIONARY" => layer.xdictionary_handle = self.read_xdictionary_handle()?,
+                    group if group.starts_with('{') => self.skip_defined_group()?,
+                    _ => {}
+                },
+                2 => layer.name = pair.value_string.to_string(),
                 62 => {
                     if let Some(color_index) = pair.as_i16() {
                         layer.color = Color::from_index(color_index);
                     }
                 }
-                6 => layer.line_type = pair.value_string.clone(),
+                6 => layer.line_type = pair.value_string.to_string(),
                 70 => {
                     if let Some(flags) = pair.as_i16() {
                         layer.flags.frozen = (flags & 1) != 0;
@@ -1493,13 +1945,20 @@ impl<'a> SectionReader<'a> {
     /// Read LTYPE table
     fn read_linetype_table(&mut self, document: &mut CadDocument) -> Result<()> {
         while let Some(pair) = self.reader.read_pair()? {
-            if pair.code == 0 && pair.value_string == "ENDTAB" {
+            if self.is_table_end(&pair, &mut document.notifications)? {
                 break;
             }
 
             if pair.code == 0 && pair.value_string == "LTYPE" {
                 if let Some(linetype) = self.read_linetype_entry()? {
-                    let _ = document.line_types.add(linetype);
+                    // The document's own line types take the file's handles,
+                    // which layer overrides refer to
+                    let (name, handle) = (linetype.name.clone(), linetype.handle);
+                    if document.line_types.add(linetype).is_err() && !handle.is_null() {
+                        if let Some(existing) = document.line_types.get_mut(&name) {
+                            existing.handle = handle;
+                        }
+                    }
                 }
             }
         }
@@ -1517,8 +1976,9 @@ impl<'a> SectionReader<'a> {
             }
 
             match pair.code {
-                2 => linetype.name = pair.value_string.clone(),
-                3 => linetype.description = pair.value_string.clone(),
+                5 => { if let Ok(h) = u64::from_str_radix(&pair.value_string, 16) { linetype.handle = Handle::new(h); } }
+                2 => linetype.name = pair.value_string.to_string(),
+                3 => linetype.description = pair.value_string.to_string(),
                 73 => {
                     if let Some(count) = pair.as_i16() {
                         linetype.elements.reserve(count as usize);
@@ -1544,7 +2004,7 @@ impl<'a> SectionReader<'a> {
     /// Read STYLE table
     fn read_textstyle_table(&mut self, document: &mut CadDocument) -> Result<()> {
         while let Some(pair) = self.reader.read_pair()? {
-            if pair.code == 0 && pair.value_string == "ENDTAB" {
+            if self.is_table_end(&pair, &mut document.notifications)? {
                 break;
             }
 
@@ -1568,9 +2028,9 @@ impl<'a> SectionReader<'a> {
             }
 
             match pair.code {
-                2 => style.name = pair.value_string.clone(),
-                3 => style.font_file = pair.value_string.clone(),
-                4 => style.big_font_file = pair.value_string.clone(),
+                2 => style.name = pair.value_string.to_string(),
+                3 => style.font_file = pair.value_string.to_string(),
+                4 => style.big_font_file = pair.value_string.to_string(),
                 40 => {
                     if let Some(height) = pair.as_double() {
                         style.height = height;
@@ -1602,13 +2062,24 @@ impl<'a> SectionReader<'a> {
     /// Read BLOCK_RECORD table
     fn read_block_record_table(&mut self, document: &mut CadDocument) -> Result<()> {
         while let Some(pair) = self.reader.read_pair()? {
-            if pair.code == 0 && pair.value_string == "ENDTAB" {
+            if self.is_table_end(&pair, &mut document.notifications)? {
                 break;
             }
 
             if pair.code == 0 && pair.value_string == "BLOCK_RECORD" {
                 if let Some(block_record) = self.read_block_record_entry()? {
-                    let _ = document.block_records.add(block_record);
+                    // The document's own model and paper space records stay,
+                    // but take over the extension dictionary and data read
+                    // for them
+                    let xdictionary = block_record.xdictionary_handle;
+                    let extended_data = block_record.extended_data.clone();
+                    let name = block_record.name.clone();
+                    if document.block_records.add(block_record).is_err() {
+                        if let Some(existing) = document.block_records.get_mut(&name) {
+                            existing.xdictionary_handle = xdictionary;
+                            existing.extended_data = extended_data;
+                        }
+                    }
                 }
             }
         }
@@ -1626,20 +2097,39 @@ impl<'a> SectionReader<'a> {
             }
 
             match pair.code {
-                2 => block_record.name = pair.value_string.clone(),
-                70 => {
-                    if let Some(flags) = pair.as_i16() {
-                        block_record.flags.anonymous = (flags & 1) != 0;
-                        block_record.flags.has_attributes = (flags & 2) != 0;
-                        block_record.flags.is_xref = (flags & 4) != 0;
-                        block_record.flags.is_xref_overlay = (flags & 8) != 0;
+                5 => {
+                    if let Ok(h) = u64::from_str_radix(pair.value_string.trim(), 16) {
+                        block_record.handle = Handle::new(h);
                     }
                 }
-                280 => {
+                2 => block_record.name = pair.value_string.to_string(),
+                // Block flags are on the BLOCK entity
+                70 => {
                     if let Some(units) = pair.as_i16() {
                         block_record.units = units;
                     }
                 }
+                280 => {
+                    if let Some(v) = pair.as_i16() {
+                        block_record.explodable = v != 0;
+                    }
+                }
+                281 => {
+                    if let Some(v) = pair.as_i16() {
+                        block_record.scale_uniformly = v != 0;
+                    }
+                }
+                102 if pair.value_string.trim() == "{ACAD_XDICTIONARY" => {
+                    block_record.xdictionary_handle = self.read_xdictionary_handle()?;
+                }
+                1001 => {
+                    self.reader.push_back(pair);
+                    let (extended_data, next) = self.read_extended_data()?;
+                    block_record.extended_data = extended_data;
+                    if let Some(next) = next {
+                        self.reader.push_back(next);
+                    }
+                }
                 _ => {}
             }
         }
@@ -1650,7 +2140,7 @@ impl<'a> SectionReader<'a> {
     /// Read DIMSTYLE table
     fn read_dimstyle_table(&mut self, document: &mut CadDocument) -> Result<()> {
         while let Some(pair) = self.reader.read_pair()? {
-            if pair.code == 0 && pair.value_string == "ENDTAB" {
+            if self.is_table_end(&pair, &mut document.notifications)? {
                 break;
             }
 
@@ -1674,10 +2164,10 @@ impl<'a> SectionReader<'a> {
             }
 
             match pair.code {
-                5 => { if let Ok(h) = u64::from_str_radix(&pair.value_string, 16) { ds.handle = Handle::new(h); } }
-                2 => ds.name = pair.value_string.clone(),
-                3 => ds.dimpost = pair.value_string.clone(),
-                4 => ds.dimapost = pair.value_string.clone(),
+                5 | 105 => { if let Ok(h) = u64::from_str_radix(&pair.value_string, 16) { ds.handle = Handle::new(h); } }
+                2 => ds.name = pair.value_string.to_string(),
+                3 => ds.dimpost = pair.value_string.to_string(),
+                4 => ds.dimapost = pair.value_string.to_string(),
                 // Scale / lines
                 40 => { if let Some(v) = pair.as_double() { ds.dimscale = v; } }
                 41 => { if let Some(v) = pair.as_double() { ds.dimasz = v; } }
@@ -1764,7 +2254,7 @@ impl<'a> SectionReader<'a> {
     /// Read APPID table
     fn read_appid_table(&mut self, document: &mut CadDocument) -> Result<()> {
         while let Some(pair) = self.reader.read_pair()? {
-            if pair.code == 0 && pair.value_string == "ENDTAB" {
+            if self.is_table_end(&pair, &mut document.notifications)? {
                 break;
             }
 
@@ -1788,7 +2278,7 @@ impl<'a> SectionReader<'a> {
             }
 
             if pair.code == 2 {
-                appid.name = pair.value_string.clone();
+                appid.name = pair.value_string.to_string();
             }
         }
 
@@ -1798,7 +2288,7 @@ impl<'a> SectionReader<'a> {
     /// Read VIEW table
     fn read_view_table(&mut self, document: &mut CadDocument) -> Result<()> {
         while let Some(pair) = self.reader.read_pair()? {
-            if pair.code == 0 && pair.value_string == "ENDTAB" {
+            if self.is_table_end(&pair, &mut document.notifications)? {
                 break;
             }
 
@@ -1825,7 +2315,7 @@ impl<'a> SectionReader<'a> {
             }
 
             match pair.code {
-                2 => view.name = pair.value_string.clone(),
+                2 => view.name = pair.value_string.to_string(),
                 10 | 20 | 30 => { center.add_coordinate(&pair); }
                 11 | 21 | 31 => { target.add_coordinate(&pair); }
                 12 | 22 | 32 => { direction.add_coordinate(&pair); }
@@ -1839,6 +2329,11 @@ impl<'a> SectionReader<'a> {
                         view.width = width;
                     }
                 }
+                50 => {
+                    if let Some(twist) = pair.as_double() {
+                        view.twist_angle = twist.to_radians();
+                    }
+                }
                 _ => {}
             }
         }
@@ -1859,13 +2354,24 @@ impl<'a> SectionReader<'a> {
     /// Read VPORT table
     fn read_vport_table(&mut self, document: &mut CadDocument) -> Result<()> {
         while let Some(pair) = self.reader.read_pair()? {
-            if pair.code == 0 && pair.value_string == "ENDTAB" {
+            if self.is_table_end(&pair, &mut document.notifications)? {
                 break;
             }
 
             if pair.code == 0 && pair.value_string == "VPORT" {
                 if let Some(vport) = self.read_vport_entry()? {
-                    let _ = document.vports.add(vport);
+                    // The document's own *Active entry stays, but takes
+                    // over the rendering objects read for it
+                    let (background, visual_style, sun) =
+                        (vport.background_handle, vport.visual_style_handle, vport.sun_handle);
+                    let name = vport.name.clone();
+                    if document.vports.add(vport).is_err() {
+                        if let Some(existing) = document.vports.get_mut(&name) {
+                            existing.background_handle = background;
+                            existing.visual_style_handle = visual_style;
+                            existing.sun_handle = sun;
+                        }
+                    }
                 }
             }
         }
@@ -1882,8 +2388,20 @@ impl<'a> SectionReader<'a> {
                 break;
             }
 
-            if pair.code == 2 {
-                vport.name = pair.value_string.clone();
+            match pair.code {
+                2 => vport.name = pair.value_string.to_string(),
+                5 | 332 | 348 | 361 => {
+                    if let Ok(h) = u64::from_str_radix(pair.value_string.trim(), 16) {
+                        let handle = Handle::new(h);
+                        match pair.code {
+                            5 => vport.handle = handle,
+                            332 => vport.background_handle = handle,
+                            348 => vport.visual_style_handle = handle,
+                            _ => vport.sun_handle = handle,
+                        }
+                    }
+                }
+                _ => {}
             }
         }
 
@@ -1893,7 +2411,7 @@ impl<'a> SectionReader<'a> {
     /// Read UCS table
     fn read_ucs_table(&mut self, document: &mut CadDocument) -> Result<()> {
         while let Some(pair) = self.reader.read_pair()? {
-            if pair.code == 0 && pair.value_string == "ENDTAB" {
+            if self.is_table_end(&pair, &mut document.notifications)? {
                 break;
             }
 
@@ -1920,7 +2438,7 @@ impl<'a> SectionReader<'a> {
             }
 
             match pair.code {
-                2 => ucs.name = pair.value_string.clone(),
+                2 => ucs.name = pair.value_string.to_string(),
                 10 | 20 | 30 => { origin.add_coordinate(&pair); }
                 11 | 21 | 31 => { x_axis.add_coordinate(&pair); }
                 12 | 22 | 32 => { y_axis.add_coordinate(&pair); }
@@ -2036,6 +2554,28 @@ impl<'a> SectionReader<'a> {
         Ok(())
     }
 
+    /// Skip the entity whose `0` marker has just been consumed if the entity
+    /// filter rejects its type, together with the VERTEX, ATTRIB and SEQEND
+    /// records following it. Returns whether it was skipped.
+    fn skip_filtered(&mut self, entity_type: &str) -> Result<bool> {
+        let follows_owner = |name: &str| matches!(name, "VERTEX" | "ATTRIB" | "SEQEND");
+        if self.entity_filter.is_none_or(|filter| filter.accepts(entity_type)) || follows_owner(entity_type) {
+            return Ok(false);
+        }
+        self.skip_entity()?;
+        while let Some(pair) = self.reader.read_pair()? {
+            if pair.code != 0 || !follows_owner(&pair.value_string) {
+                self.reader.push_back(pair);
+                break;
+            }
+            self.skip_entity()?;
+            if pair.value_string == "SEQEND" {
+                break;
+            }
+        }
+        Ok(true)
+    }
+
     /// Skip all pairs for the current entity until the next entity (code 0) or section end
     fn skip_entity(&mut self) -> Result<()> {
         while let Some(pair) = self.reader.read_pair()? {
@@ -2047,20 +2587,55 @@ impl<'a> SectionReader<'a> {
         Ok(())
     }
 
-    /// Read an unknown entity, capturing only common entity data.
-    ///
-    /// Entity-specific codes are discarded (matching ACadSharp behavior).
+    /// Read an unknown entity, capturing common entity data and the raw pairs.
     fn read_unknown_entity(&mut self, dxf_name: &str) -> Result<UnknownEntity> {
-        let mut entity = UnknownEntity::new(dxf_name);
+        let raw_pairs = self.read_raw_pairs()?;
+        Ok(unknown_entity(dxf_name, raw_pairs))
+    }
+
+    /// Read an entity of a type with a custom reader, keeping it as unknown
+    /// when the reader declines it.
+    fn read_custom_entity(&mut self, dxf_name: &str) -> Result<EntityType> {
+        let pairs = self.read_record_pairs()?;
+        if let Some(reader) = self.custom_types.and_then(|custom| custom.entity_reader(dxf_name)) {
+            if let Some(entity) = reader(dxf_name, &pairs)? {
+                return Ok(entity);
+            }
+        }
+        let raw_pairs = pairs.into_iter().map(|pair| (pair.code, pair.value_string.into_string())).collect();
+        Ok(EntityType::Unknown(unknown_entity(dxf_name, raw_pairs)))
+    }
+
+    /// Read an object of a type with a custom reader, keeping it as unknown
+    /// when the reader declines it.
+    fn read_custom_object(&mut self, type_name: &str) -> Result<ObjectType> {
+        let pairs = self.read_record_pairs()?;
+        if let Some(reader) = self.custom_types.and_then(|custom| custom.object_reader(type_name)) {
+            if let Some(object) = reader(type_name, &pairs)? {
+                return Ok(object);
+            }
+        }
+        let raw_pairs = pairs.into_iter().map(|pair| (pair.code, pair.value_string.into_string())).collect();
+        Ok(unknown_object(type_name, raw_pairs))
+    }
+
+    /// Read all pairs up to the next `0` code.
+    fn read_record_pairs(&mut self) -> Result<Vec<DxfCodePair>> {
+        let mut pairs = Vec::new();
         while let Some(pair) = self.reader.read_pair()? {
             if pair.code == 0 {
                 self.reader.push_back(pair);
                 break;
             }
-            // Try to read common entity codes; ignore entity-specific ones
-            let _ = self.try_read_common_entity_code(&pair, &mut entity.common)?;
+            pairs.push(pair);
         }
-        Ok(entity)
+        Ok(pairs)
+    }
+
+    /// Read all pairs up to the next `0` code as raw code/value pairs.
+    fn read_raw_pairs(&mut self) -> Result<Vec<(i32, String)>> {
+        let pairs = self.read_record_pairs()?;
+        Ok(pairs.into_iter().map(|pair| (pair.code, pair.value_string.into_string())).collect())
     }
 
     /// Read an OLE2FRAME entity
@@ -2075,7 +2650,7 @@ impl<'a> SectionReader<'a> {
             }
             match pair.code {
                 70 => { if let Some(v) = pair.as_i16() { ole.version = v; } }
-                3 => ole.source_application = pair.value_string.clone(),
+                3 => ole.source_application = pair.value_string.to_string(),
                 10 => { if let Some(v) = pair.as_double() { ole.upper_left_corner.x = v; } }
                 20 => { if let Some(v) = pair.as_double() { ole.upper_left_corner.y = v; } }
                 30 => { if let Some(v) = pair.as_double() { ole.upper_left_corner.z = v; } }
@@ -2120,7 +2695,7 @@ impl<'a> SectionReader<'a> {
             }
 
             match pair.code {
-                8 => point.common.layer = pair.value_string.clone(),
+                8 => point.common.layer = pair.value_string.to_string(),
                 62 => {
                     if let Some(color_index) = pair.as_i16() {
                         point.common.color = Color::from_index(color_index);
@@ -2162,7 +2737,7 @@ impl<'a> SectionReader<'a> {
             }
 
             match pair.code {
-                8 => line.common.layer = pair.value_string.clone(),
+                8 => line.common.layer = pair.value_string.to_string(),
                 62 => {
                     if let Some(color_index) = pair.as_i16() {
                         line.common.color = Color::from_index(color_index);
@@ -2213,7 +2788,7 @@ impl<'a> SectionReader<'a> {
             }
 
             match pair.code {
-                8 => circle.common.layer = pair.value_string.clone(),
+                8 => circle.common.layer = pair.value_string.to_string(),
                 62 => {
                     if let Some(color_index) = pair.as_i16() {
                         circle.common.color = Color::from_index(color_index);
@@ -2258,7 +2833,7 @@ impl<'a> SectionReader<'a> {
             }
 
             match pair.code {
-                8 => arc.common.layer = pair.value_string.clone(),
+                8 => arc.common.layer = pair.value_string.to_string(),
                 62 => {
                     if let Some(color_index) = pair.as_i16() {
                         arc.common.color = Color::from_index(color_index);
@@ -2314,7 +2889,7 @@ impl<'a> SectionReader<'a> {
             }
 
             match pair.code {
-                8 => ellipse.common.layer = pair.value_string.clone(),
+                8 => ellipse.common.layer = pair.value_string.to_string(),
                 62 => {
                     if let Some(color_index) = pair.as_i16() {
                         ellipse.common.color = Color::from_index(color_index);
@@ -2356,18 +2931,23 @@ impl<'a> SectionReader<'a> {
         Ok(Some(ellipse))
     }
 
-    /// Read a POLYLINE entity
-    fn read_polyline(&mut self) -> Result<Option<Polyline>> {
-        use crate::entities::polyline::Vertex3D;
+    /// Read a POLYLINE entity with its VERTEX and SEQEND records
+    ///
+    /// 2D polylines become [`Polyline2D`] with the complete vertex record;
+    /// 3D polylines and meshes the plain [`Polyline`].
+    fn read_polyline(&mut self) -> Result<Option<EntityType>> {
+        use crate::entities::polyline::{PolylineFlags, SmoothSurfaceType, Vertex3D, VertexFlags};
 
-        let mut polyline = Polyline::new();
+        let mut polyline = Polyline2D::new();
+        let mut origin = PointReader::new();
+        let mut normal = PointReader::new();
 
         while let Some(pair) = self.reader.read_pair()? {
             if pair.code == 0 {
                 // Check if it's a VERTEX or SEQEND
                 if pair.value_string == "VERTEX" {
-                    // Read vertex
-                    let mut vertex_reader = PointReader::new();
+                    let mut vertex = Vertex2D::new(Vector3::ZERO);
+                    let mut location = PointReader::new();
 
                     while let Some(vpair) = self.reader.read_pair()? {
                         if vpair.code == 0 {
@@ -2375,21 +2955,40 @@ impl<'a> SectionReader<'a> {
                             break;
                         }
                         match vpair.code {
-                            10 | 20 | 30 => { vertex_reader.add_coordinate(&vpair); }
+                            5 => {
+                                if let Some(h) = vpair.as_handle() {
+                                    vertex.handle = Handle::new(h);
+                                }
+                            }
+                            10 | 20 | 30 => { location.add_coordinate(&vpair); }
+                            40 => { if let Some(v) = vpair.as_double() { vertex.start_width = v; } }
+                            41 => { if let Some(v) = vpair.as_double() { vertex.end_width = v; } }
+                            42 => { if let Some(v) = vpair.as_double() { vertex.bulge = v; } }
+                            50 => { if let Some(v) = vpair.as_double() { vertex.curve_tangent = v.to_radians(); } }
+                            70 => {
+                                if let Some(bits) = vpair.as_i16() {
+                                    vertex.flags = VertexFlags::from_bits(bits as u8);
+                                }
+                            }
+                            91 => { if let Some(v) = vpair.as_i32() { vertex.id = v; } }
                             _ => {}
                         }
                     }
 
-                    if let Some(pt) = vertex_reader.get_point() {
-                        polyline.vertices.push(Vertex3D::new(pt));
+                    if let Some(pt) = location.get_point() {
+                        vertex.location = pt;
+                        polyline.vertices.push(vertex);
                     }
                 } else if pair.value_string == "SEQEND" {
-                    // End of polyline - skip SEQEND properties
+                    // End of polyline - keep the SEQEND handle
                     while let Some(seqend_pair) = self.reader.read_pair()? {
                         if seqend_pair.code == 0 {
                             self.reader.push_back(seqend_pair);
                             break;
                         }
+                        if seqend_pair.code == 5 {
+                            polyline.seqend_handle = seqend_pair.as_handle().map(Handle::new);
+                        }
                     }
                     break;
                 } else {
@@ -2399,7 +2998,7 @@ impl<'a> SectionReader<'a> {
                 }
             } else {
                 match pair.code {
-                    8 => polyline.common.layer = pair.value_string.clone(),
+                    8 => polyline.common.layer = pair.value_string.to_string(),
                     62 => {
                         if let Some(color_index) = pair.as_i16() {
                             polyline.common.color = Color::from_index(color_index);
@@ -2410,19 +3009,47 @@ impl<'a> SectionReader<'a> {
                             polyline.common.line_weight = LineWeight::from_value(lw);
                         }
                     }
+                    10 | 20 | 30 => { origin.add_coordinate(&pair); }
+                    39 => { if let Some(v) = pair.as_double() { polyline.thickness = v; } }
+                    40 => { if let Some(v) = pair.as_double() { polyline.start_width = v; } }
+                    41 => { if let Some(v) = pair.as_double() { polyline.end_width = v; } }
                     70 => {
                         if let Some(flags) = pair.as_i16() {
-                            if (flags & 1) != 0 {
-                                polyline.close();
-                            }
+                            polyline.flags = PolylineFlags::from_bits(flags as u16);
                         }
                     }
+                    75 => {
+                        if let Some(v) = pair.as_i16() {
+                            polyline.smooth_surface = SmoothSurfaceType::from(v);
+                        }
+                    }
+                    210 | 220 | 230 => { normal.add_coordinate(&pair); }
                     _ => { self.try_read_common_entity_code(&pair, &mut polyline.common)?; }
                 }
             }
         }
 
-        Ok(Some(polyline))
+        if let Some(pt) = origin.get_point() {
+            polyline.elevation = pt.z;
+        }
+        if let Some(n) = normal.get_point() {
+            polyline.normal = n;
+        }
+
+        let bits = polyline.flags.bits();
+        if bits & (PolylineFlags::POLYLINE_3D.bits() | PolylineFlags::POLYGON_MESH.bits() | PolylineFlags::POLYFACE_MESH.bits()) == 0 {
+            return Ok(Some(EntityType::Polyline2D(polyline)));
+        }
+        Ok(Some(EntityType::Polyline(Polyline {
+            common: polyline.common,
+            flags: polyline.flags | PolylineFlags::POLYLINE_3D,
+            vertices: polyline
+                .vertices
+                .into_iter()
+                .map(|v| Vertex3D { handle: v.handle, flags: v.flags, ..Vertex3D::new(v.location) })
+                .collect(),
+            seqend_handle: polyline.seqend_handle,
+        })))
     }
 
     /// Read an LWPOLYLINE entity
@@ -2436,6 +3063,7 @@ impl<'a> SectionReader<'a> {
         let mut bulges: Vec<f64> = Vec::new();
         let mut widths_start: Vec<f64> = Vec::new();
         let mut widths_end: Vec<f64> = Vec::new();
+        let mut normal = PointReader::new();
 
         while let Some(pair) = self.reader.read_pair()? {
             if pair.code == 0 {
@@ -2444,7 +3072,7 @@ impl<'a> SectionReader<'a> {
             }
 
             match pair.code {
-                8 => lwpolyline.common.layer = pair.value_string.clone(),
+                8 => lwpolyline.common.layer = pair.value_string.to_string(),
                 62 => {
                     if let Some(color_index) = pair.as_i16() {
                         lwpolyline.common.color = Color::from_index(color_index);
@@ -2465,6 +3093,12 @@ impl<'a> SectionReader<'a> {
                         lwpolyline.elevation = elevation;
                     }
                 }
+                39 => {
+                    if let Some(thickness) = pair.as_double() {
+                        lwpolyline.thickness = thickness;
+                    }
+                }
+                210 | 220 | 230 => { normal.add_coordinate(&pair); }
                 10 => {
                     if let Some(x) = pair.as_double() {
                         vertices_x.push(x);
@@ -2494,6 +3128,10 @@ impl<'a> SectionReader<'a> {
             }
         }
 
+        if let Some(n) = normal.get_point() {
+            lwpolyline.normal = n;
+        }
+
         // Build vertices from collected data
         for i in 0..vertices_x.len().min(vertices_y.len()) {
             let bulge = bulges.get(i).copied().unwrap_or(0.0);
@@ -2515,6 +3153,8 @@ impl<'a> SectionReader<'a> {
     fn read_text(&mut self) -> Result<Option<Text>> {
         let mut text = Text::new();
         let mut insertion = PointReader::new();
+        let mut alignment = PointReader::new();
+        let mut normal = PointReader::new();
 
         while let Some(pair) = self.reader.read_pair()? {
             if pair.code == 0 {
@@ -2523,7 +3163,7 @@ impl<'a> SectionReader<'a> {
             }
 
             match pair.code {
-                8 => text.common.layer = pair.value_string.clone(),
+                8 => text.common.layer = pair.value_string.to_string(),
                 62 => {
                     if let Some(color_index) = pair.as_i16() {
                         text.common.color = Color::from_index(color_index);
@@ -2535,7 +3175,9 @@ impl<'a> SectionReader<'a> {
                     }
                 }
                 10 | 20 | 30 => { insertion.add_coordinate(&pair); }
-                1 => text.value = pair.value_string.clone(),
+                11 | 21 | 31 => { alignment.add_coordinate(&pair); }
+                210 | 220 | 230 => { normal.add_coordinate(&pair); }
+                1 => text.value = pair.value_string.to_string(),
                 40 => {
                     if let Some(height) = pair.as_double() {
                         text.height = height;
@@ -2543,7 +3185,7 @@ impl<'a> SectionReader<'a> {
                 }
                 50 => {
                     if let Some(rotation) = pair.as_double() {
-                        text.rotation = rotation;
+                        text.rotation = rotation.to_radians();
                     }
                 }
                 41 => {
@@ -2553,10 +3195,20 @@ impl<'a> SectionReader<'a> {
                 }
                 51 => {
                     if let Some(oblique) = pair.as_double() {
-                        text.oblique_angle = oblique;
+                        text.oblique_angle = oblique.to_radians();
+                    }
+                }
+                7 => text.style = pair.value_string.to_string(),
+                72 => {
+                    if let Some(v) = pair.as_i16() {
+                        text.horizontal_alignment = TextHorizontalAlignment::from_value(v);
+                    }
+                }
+                73 => {
+                    if let Some(v) = pair.as_i16() {
+                        text.vertical_alignment = TextVerticalAlignment::from_value(v);
                     }
                 }
-                7 => text.style = pair.value_string.clone(),
                 _ => { self.try_read_common_entity_code(&pair, &mut text.common)?; }
             }
         }
@@ -2564,6 +3216,10 @@ impl<'a> SectionReader<'a> {
         if let Some(pt) = insertion.get_point() {
             text.insertion_point = pt;
         }
+        text.alignment_point = alignment.get_point();
+        if let Some(pt) = normal.get_point() {
+            text.normal = pt;
+        }
 
         Ok(Some(text))
     }
@@ -2572,6 +3228,7 @@ impl<'a> SectionReader<'a> {
     fn read_mtext(&mut self) -> Result<Option<MText>> {
         let mut mtext = MText::new();
         let mut insertion = PointReader::new();
+        let mut in_columns = false;
 
         while let Some(pair) = self.reader.read_pair()? {
             if pair.code == 0 {
@@ -2580,7 +3237,7 @@ impl<'a> SectionReader<'a> {
             }
 
             match pair.code {
-                8 => mtext.common.layer = pair.value_string.clone(),
+                8 => mtext.common.layer = pair.value_string.to_string(),
                 62 => {
                     if let Some(color_index) = pair.as_i16() {
                         mtext.common.color = Color::from_index(color_index);
@@ -2606,12 +3263,50 @@ impl<'a> SectionReader<'a> {
                         mtext.rectangle_width = width;
                     }
                 }
+                // After the column type, 50 holds column heights
+                50 if in_columns => {
+                    if let Some(height) = pair.as_double() {
+                        mtext.columns.heights.push(height);
+                    }
+                }
                 50 => {
                     if let Some(rotation) = pair.as_double() {
-                        mtext.rotation = rotation;
+                        mtext.rotation = rotation.to_radians();
+                    }
+                }
+                7 => mtext.style = pair.value_string.to_string(),
+                90 => {
+                    if let Some(flags) = pair.as_i32() {
+                        mtext.background_fill = BackgroundFillFlags::from_bits_retain(flags);
+                    }
+                }
+                63 => {
+                    if let Some(index) = pair.as_i16() {
+                        mtext.background_color = Color::from_index(index);
+                    }
+                }
+                421 => {
+                    if let Some(v) = pair.as_i32() {
+                        mtext.background_color = Color::from_rgb((v >> 16) as u8, (v >> 8) as u8, v as u8);
+                    }
+                }
+                45 => { if let Some(v) = pair.as_double() { mtext.background_scale = v; } }
+                441 => {
+                    if let Some(v) = pair.as_i32() {
+                        mtext.background_transparency = Transparency::from_alpha_value(v as u32);
+                    }
+                }
+                75 => {
+                    if let Some(v) = pair.as_i16() {
+                        mtext.columns.column_type = ColumnType::from_value(v);
+                        in_columns = true;
                     }
                 }
-                7 => mtext.style = pair.value_string.clone(),
+                76 => { if let Some(v) = pair.as_i16() { mtext.columns.count = v as i32; } }
+                78 => { if let Some(v) = pair.as_i16() { mtext.columns.flow_reversed = v != 0; } }
+                79 => { if let Some(v) = pair.as_i16() { mtext.columns.auto_height = v != 0; } }
+                48 => { if let Some(v) = pair.as_double() { mtext.columns.width = v; } }
+                49 => { if let Some(v) = pair.as_double() { mtext.columns.gutter = v; } }
                 _ => { self.try_read_common_entity_code(&pair, &mut mtext.common)?; }
             }
         }
@@ -2630,6 +3325,9 @@ impl<'a> SectionReader<'a> {
         let mut current_fit_point = PointReader::new();
         let mut reading_control = false;
         let mut reading_fit = false;
+        let mut start_tangent = PointReader::new();
+        let mut end_tangent = PointReader::new();
+        let mut normal = PointReader::new();
 
         while let Some(pair) = self.reader.read_pair()? {
             if pair.code == 0 {
@@ -2638,7 +3336,7 @@ impl<'a> SectionReader<'a> {
             }
 
             match pair.code {
-                8 => spline.common.layer = pair.value_string.clone(),
+                8 => spline.common.layer = pair.value_string.to_string(),
                 62 => {
                     if let Some(color_index) = pair.as_i16() {
                         spline.common.color = Color::from_index(color_index);
@@ -2654,6 +3352,8 @@ impl<'a> SectionReader<'a> {
                         spline.flags.closed = (flags_val & 1) != 0;
                         spline.flags.periodic = (flags_val & 2) != 0;
                         spline.flags.rational = (flags_val & 4) != 0;
+                        spline.flags.planar = (flags_val & 8) != 0;
+                        spline.flags.linear = (flags_val & 16) != 0;
                     }
                 }
                 71 => {
@@ -2666,6 +3366,17 @@ impl<'a> SectionReader<'a> {
                         spline.knots.push(knot);
                     }
                 }
+                41 => {
+                    if let Some(weight) = pair.as_double() {
+                        spline.weights.push(weight);
+                    }
+                }
+                42 => { if let Some(v) = pair.as_double() { spline.knot_tolerance = v; } }
+                43 => { if let Some(v) = pair.as_double() { spline.control_point_tolerance = v; } }
+                44 => { if let Some(v) = pair.as_double() { spline.fit_tolerance = v; } }
+                12 | 22 | 32 => { start_tangent.add_coordinate(&pair); }
+                13 | 23 | 33 => { end_tangent.add_coordinate(&pair); }
+                210 | 220 | 230 => { normal.add_coordinate(&pair); }
                 10 | 20 | 30 => {
                     // Control point coordinates
                     if pair.code == 10 {
@@ -2711,6 +3422,9 @@ impl<'a> SectionReader<'a> {
                 spline.fit_points.push(pt);
             }
         }
+        if let Some(pt) = start_tangent.get_point() { spline.start_tangent = pt; }
+        if let Some(pt) = end_tangent.get_point() { spline.end_tangent = pt; }
+        if let Some(pt) = normal.get_point() { spline.normal = pt; }
 
         Ok(Some(spline))
     }
@@ -2719,23 +3433,19 @@ impl<'a> SectionReader<'a> {
     fn read_dimension(&mut self) -> Result<Option<Dimension>> {
         use crate::entities::dimension::*;
 
-        let mut dim_type = DimensionType::Linear;
+        let mut base = DimensionBase::default();
+        let mut type_flags = 0i16;
         let mut definition_point = PointReader::new();
         let mut text_middle_point = PointReader::new();
         let mut insertion_point = PointReader::new();
-        let mut first_point = PointReader::new();
-        let mut second_point = PointReader::new();
-        let mut third_point = PointReader::new();
-        let mut fourth_point = PointReader::new();
-        let mut text = String::new();
-        let mut style_name = String::from("Standard");
-        let mut layer = String::from("0");
-        let mut color = Color::ByLayer;
-        let mut line_weight = LineWeight::ByLayer;
+        let mut normal = PointReader::new();
+        let mut point_13 = PointReader::new();
+        let mut point_14 = PointReader::new();
+        let mut point_15 = PointReader::new();
+        let mut point_16 = PointReader::new();
         let mut rotation = 0.0;
-        let mut actual_measurement = 0.0;
+        let mut ext_line_rotation = 0.0;
         let mut leader_length = 0.0;
-        let mut common = EntityCommon::new();
 
         while let Some(pair) = self.reader.read_pair()? {
             if pair.code == 0 {
@@ -2744,178 +3454,139 @@ impl<'a> SectionReader<'a> {
             }
 
             match pair.code {
-                8 => layer = pair.value_string.clone(),
+                8 => base.common.layer = pair.value_string.to_string(),
                 62 => {
                     if let Some(color_index) = pair.as_i16() {
-                        color = Color::from_index(color_index);
+                        base.common.color = Color::from_index(color_index);
                     }
                 }
                 370 => {
                     if let Some(lw) = pair.as_i16() {
-                        line_weight = LineWeight::from_value(lw);
+                        base.common.line_weight = LineWeight::from_value(lw);
                     }
                 }
                 70 => {
-                    if let Some(type_val) = pair.as_i16() {
-                        dim_type = match type_val & 0x0F {
-                            0 => DimensionType::Linear,
-                            1 => DimensionType::Aligned,
-                            2 => DimensionType::Angular,
-                            3 => DimensionType::Diameter,
-                            4 => DimensionType::Radius,
-                            5 => DimensionType::Angular3Point,
-                            6 => DimensionType::Ordinate,
-                            _ => DimensionType::Linear,
-                        };
+                    if let Some(flags) = pair.as_i16() {
+                        type_flags = flags;
                     }
                 }
-                1 => text = pair.value_string.clone(),
-                3 => style_name = pair.value_string.clone(),
+                1 => base.text = pair.value_string.to_string(),
+                2 => base.block_name = pair.value_string.to_string(),
+                3 => base.style_name = pair.value_string.to_string(),
                 10 | 20 | 30 => { definition_point.add_coordinate(&pair); }
                 11 | 21 | 31 => { text_middle_point.add_coordinate(&pair); }
                 12 | 22 | 32 => { insertion_point.add_coordinate(&pair); }
-                13 | 23 | 33 => { first_point.add_coordinate(&pair); }
-                14 | 24 | 34 => { second_point.add_coordinate(&pair); }
-                15 | 25 | 35 => { third_point.add_coordinate(&pair); }
-                16 | 26 | 36 => { fourth_point.add_coordinate(&pair); }
-                50 => {
-                    if let Some(rot) = pair.as_double() {
-                        rotation = rot;
-                    }
-                }
-                42 => {
-                    if let Some(measurement) = pair.as_double() {
-                        actual_measurement = measurement;
-                    }
-                }
-                40 => {
-                    if let Some(length) = pair.as_double() {
-                        leader_length = length;
-                    }
-                }
-                _ => { self.try_read_common_entity_code(&pair, &mut common)?; }
+                13 | 23 | 33 => { point_13.add_coordinate(&pair); }
+                14 | 24 | 34 => { point_14.add_coordinate(&pair); }
+                15 | 25 | 35 => { point_15.add_coordinate(&pair); }
+                16 | 26 | 36 => { point_16.add_coordinate(&pair); }
+                210 | 220 | 230 => { normal.add_coordinate(&pair); }
+                40 => { if let Some(v) = pair.as_double() { leader_length = v; } }
+                41 => { if let Some(v) = pair.as_double() { base.line_spacing_factor = v; } }
+                42 => { if let Some(v) = pair.as_double() { base.actual_measurement = v; } }
+                50 => { if let Some(v) = pair.as_double() { rotation = v.to_radians(); } }
+                51 => { if let Some(v) = pair.as_double() { base.horizontal_direction = v.to_radians(); } }
+                52 => { if let Some(v) = pair.as_double() { ext_line_rotation = v.to_radians(); } }
+                53 => { if let Some(v) = pair.as_double() { base.text_rotation = v.to_radians(); } }
+                71 => { if let Some(v) = pair.as_i16() { base.attachment_point = AttachmentPointType::from_value(v); } }
+                280 => { if let Some(v) = pair.as_i16() { base.version = v as u8; } }
+                _ => { self.try_read_common_entity_code(&pair, &mut base.common)?; }
             }
         }
 
-        // Build the appropriate dimension type
-        let pt1 = first_point.get_point().unwrap_or(Vector3::zero());
-        let pt2 = second_point.get_point().unwrap_or(Vector3::zero());
-        let pt3 = third_point.get_point().unwrap_or(Vector3::zero());
-        let _pt4 = fourth_point.get_point().unwrap_or(Vector3::zero());
-
-        let mut dimension = match dim_type {
-            DimensionType::Aligned => {
-                let mut dim = DimensionAligned::new(pt1, pt2);
-                dim.base.common.layer = layer;
-                dim.base.common.color = color;
-                dim.base.common.line_weight = line_weight;
-                dim.base.text = text;
-                dim.base.style_name = style_name;
-                dim.base.actual_measurement = actual_measurement;
-                if let Some(def_pt) = definition_point.get_point() {
-                    dim.definition_point = def_pt;
-                }
-                Dimension::Aligned(dim)
-            }
-            DimensionType::Linear => {
-                let mut dim = DimensionLinear::rotated(pt1, pt2, rotation);
-                dim.base.common.layer = layer;
-                dim.base.common.color = color;
-                dim.base.common.line_weight = line_weight;
-                dim.base.text = text;
-                dim.base.style_name = style_name;
-                dim.base.actual_measurement = actual_measurement;
-                if let Some(def_pt) = definition_point.get_point() {
-                    dim.definition_point = def_pt;
-                }
-                Dimension::Linear(dim)
-            }
-            DimensionType::Radius => {
-                let center = pt1;
-                let chord_point = pt2;
-                let mut dim = DimensionRadius::new(center, chord_point);
-                dim.base.common.layer = layer;
-                dim.base.common.color = color;
-                dim.base.common.line_weight = line_weight;
-                dim.base.text = text;
-                dim.base.style_name = style_name;
-                dim.base.actual_measurement = actual_measurement;
-                dim.leader_length = leader_length;
-                Dimension::Radius(dim)
-            }
-            DimensionType::Diameter => {
-                let center = pt1;
-                let point_on_arc = pt2;
-                let mut dim = DimensionDiameter::new(center, point_on_arc);
-                dim.base.common.layer = layer;
-                dim.base.common.color = color;
-                dim.base.common.line_weight = line_weight;
-                dim.base.text = text;
-                dim.base.style_name = style_name;
-                dim.base.actual_measurement = actual_measurement;
-                Dimension::Diameter(dim)
-            }
-            DimensionType::Angular => {
-                // Angular2Ln: vertex, first_point, second_point
-                let mut dim = DimensionAngular2Ln::new(pt1, pt2, pt3);
-                dim.base.common.layer = layer;
-                dim.base.common.color = color;
-                dim.base.common.line_weight = line_weight;
-                dim.base.text = text;
-                dim.base.style_name = style_name;
-                dim.base.actual_measurement = actual_measurement;
-                Dimension::Angular2Ln(dim)
-            }
-            DimensionType::Angular3Point => {
-                // Angular3Pt: center, first_point, second_point
-                let mut dim = DimensionAngular3Pt::new(pt1, pt2, pt3);
-                dim.base.common.layer = layer;
-                dim.base.common.color = color;
-                dim.base.common.line_weight = line_weight;
-                dim.base.text = text;
-                dim.base.style_name = style_name;
-                dim.base.actual_measurement = actual_measurement;
-                Dimension::Angular3Pt(dim)
-            }
-            DimensionType::Ordinate => {
-                // Ordinate: feature_location, leader_endpoint
-                // Use x_ordinate by default (could be determined from flags in real implementation)
-                let mut dim = DimensionOrdinate::x_ordinate(pt1, pt2);
-                dim.base.common.layer = layer;
-                dim.base.common.color = color;
-                dim.base.common.line_weight = line_weight;
-                dim.base.text = text;
-                dim.base.style_name = style_name;
-                dim.base.actual_measurement = actual_measurement;
-                Dimension::Ordinate(dim)
+        let definition_point = definition_point.get_point().unwrap_or(Vector3::zero());
+        base.definition_point = definition_point;
+        base.text_middle_point = text_middle_point.get_point().unwrap_or(Vector3::zero());
+        base.insertion_point = insertion_point.get_point().unwrap_or(Vector3::zero());
+        if let Some(pt) = normal.get_point() {
+            base.normal = pt;
+        }
+        let point_13 = point_13.get_point().unwrap_or(Vector3::zero());
+        let point_14 = point_14.get_point().unwrap_or(Vector3::zero());
+        let point_15 = point_15.get_point().unwrap_or(Vector3::zero());
+        let point_16 = point_16.get_point().unwrap_or(Vector3::zero());
+
+        // The low bits of the flags select the subclass and its points
+        let dimension = match type_flags & 0x07 {
+            1 => {
+                base.dimension_type = DimensionType::Aligned;
+                Dimension::Aligned(DimensionAligned {
+                    base,
+                    first_point: point_13,
+                    second_point: point_14,
+                    definition_point,
+                    ext_line_rotation,
+                })
+            }
+            2 => {
+                base.dimension_type = DimensionType::Angular;
+                Dimension::Angular2Ln(DimensionAngular2Ln {
+                    base,
+                    dimension_arc: point_16,
+                    first_point: point_13,
+                    second_point: point_14,
+                    angle_vertex: point_15,
+                    definition_point,
+                })
+            }
+            3 => {
+                base.dimension_type = DimensionType::Diameter;
+                Dimension::Diameter(DimensionDiameter {
+                    base,
+                    definition_point,
+                    angle_vertex: point_15,
+                    leader_length,
+                })
+            }
+            4 => {
+                base.dimension_type = DimensionType::Radius;
+                Dimension::Radius(DimensionRadius {
+                    base,
+                    definition_point,
+                    angle_vertex: point_15,
+                    leader_length,
+                })
+            }
+            5 => {
+                base.dimension_type = DimensionType::Angular3Point;
+                Dimension::Angular3Pt(DimensionAngular3Pt {
+                    base,
+                    definition_point,
+                    first_point: point_13,
+                    second_point: point_14,
+                    angle_vertex: point_15,
+                })
+            }
+            6 => {
+                base.dimension_type = DimensionType::Ordinate;
+                Dimension::Ordinate(DimensionOrdinate {
+                    base,
+                    definition_point,
+                    feature_location: point_13,
+                    leader_endpoint: point_14,
+                    is_ordinate_type_x: type_flags & 64 != 0,
+                })
+            }
+            _ => {
+                base.dimension_type = DimensionType::Linear;
+                Dimension::Linear(DimensionLinear {
+                    base,
+                    first_point: point_13,
+                    second_point: point_14,
+                    definition_point,
+                    rotation,
+                    ext_line_rotation,
+                })
             }
         };
 
-        {
-            let dc = dimension.base_mut();
-            dc.common.handle = common.handle;
-            dc.common.owner_handle = common.owner_handle;
-            dc.common.reactors = common.reactors;
-            dc.common.xdictionary_handle = common.xdictionary_handle;
-            dc.common.invisible = common.invisible;
-        }
-
         Ok(Some(dimension))
     }
 
     /// Read a HATCH entity
     fn read_hatch(&mut self) -> Result<Option<Hatch>> {
-        use crate::entities::hatch::*;
-
         let mut hatch = Hatch::new();
-        let mut pattern_name = String::from("SOLID");
-        let mut pattern_type = HatchPatternType::Predefined;
-        let mut layer = String::from("0");
-        let mut color = Color::ByLayer;
-        let mut line_weight = LineWeight::ByLayer;
-        let mut _num_boundary_paths = 0;
-        let mut current_path_edges: Vec<BoundaryEdge> = Vec::new();
-        let mut reading_boundary = false;
+        let mut normal = PointReader::new();
 
         while let Some(pair) = self.reader.read_pair()? {
             if pair.code == 0 {
@@ -2924,18 +3595,22 @@ impl<'a> SectionReader<'a> {
             }
 
             match pair.code {
-                8 => layer = pair.value_string.clone(),
+                8 => hatch.common.layer = pair.value_string.to_string(),
                 62 => {
                     if let Some(color_index) = pair.as_i16() {
-                        color = Color::from_index(color_index);
+                        hatch.common.color = Color::from_index(color_index);
                     }
                 }
                 370 => {
                     if let Some(lw) = pair.as_i16() {
-                        line_weight = LineWeight::from_value(lw);
+                        hatch.common.line_weight = LineWeight::from_value(lw);
                     }
                 }
-                2 => pattern_name = pair.value_string.clone(),
+                // The elevation point only carries Z
+                10 | 20 => {}
+                30 => { if let Some(v) = pair.as_double() { hatch.elevation = v; } }
+                210 | 220 | 230 => { normal.add_coordinate(&pair); }
+                2 => hatch.pattern.name = pair.value_string.to_string(),
                 70 => {
                     if let Some(solid_fill) = pair.as_i16() {
                         hatch.is_solid = solid_fill != 0;
@@ -2946,10 +3621,13 @@ impl<'a> SectionReader<'a> {
                         hatch.is_associative = associative != 0;
                     }
                 }
+                91 => {
+                    let count = pair.as_i32().unwrap_or(0);
+                    hatch.paths = self.read_hatch_boundary_paths(count)?;
+                }
                 75 => {
                     if let Some(style) = pair.as_i16() {
                         hatch.style = match style {
-                            0 => HatchStyleType::Normal,
                             1 => HatchStyleType::Outer,
                             2 => HatchStyleType::Ignore,
                             _ => HatchStyleType::Normal,
@@ -2958,105 +3636,194 @@ impl<'a> SectionReader<'a> {
                 }
                 76 => {
                     if let Some(ptype) = pair.as_i16() {
-                        pattern_type = match ptype {
+                        hatch.pattern_type = match ptype {
                             0 => HatchPatternType::UserDefined,
-                            1 => HatchPatternType::Predefined,
                             2 => HatchPatternType::Custom,
                             _ => HatchPatternType::Predefined,
                         };
                     }
                 }
-                91 => {
-                    if let Some(num_paths) = pair.as_i32() {
-                        _num_boundary_paths = num_paths;
-                    }
-                }
-                92 => {
-                    // Boundary path type flags - indicates start of a new boundary path
-                    if reading_boundary && !current_path_edges.is_empty() {
-                        // Save previous path
-                        let path = BoundaryPath {
-                            flags: BoundaryPathFlags::new(),
-                            edges: current_path_edges.clone(),
-                            boundary_handles: Vec::new(),
-                        };
-                        hatch.paths.push(path);
-                        current_path_edges.clear();
-                    }
-                    reading_boundary = true;
+                52 => { if let Some(v) = pair.as_double() { hatch.pattern_angle = v.to_radians(); } }
+                41 => { if let Some(v) = pair.as_double() { hatch.pattern_scale = v; } }
+                77 => { if let Some(v) = pair.as_i16() { hatch.is_double = v != 0; } }
+                78 => {
+                    let count = pair.as_i16().unwrap_or(0);
+                    hatch.pattern.lines = self.read_hatch_pattern_lines(count)?;
                 }
-                72 => {
-                    // Edge type - indicates start of a new edge
-                    if let Some(edge_type) = pair.as_i16() {
-                        match edge_type {
-                            1 => {
-                                // Line edge - will be populated by subsequent codes
-                                current_path_edges.push(BoundaryEdge::Line(LineEdge {
-                                    start: Vector2::new(0.0, 0.0),
-                                    end: Vector2::new(0.0, 0.0),
-                                }));
-                            }
-                            2 => {
-                                // Circular arc edge
-                                current_path_edges.push(BoundaryEdge::CircularArc(CircularArcEdge {
-                                    center: Vector2::new(0.0, 0.0),
-                                    radius: 0.0,
-                                    start_angle: 0.0,
-                                    end_angle: 0.0,
-                                    counter_clockwise: true,
-                                }));
-                            }
-                            3 => {
-                                // Elliptic arc edge
-                                current_path_edges.push(BoundaryEdge::EllipticArc(EllipticArcEdge {
-                                    center: Vector2::new(0.0, 0.0),
-                                    major_axis_endpoint: Vector2::new(1.0, 0.0),
-                                    minor_axis_ratio: 1.0,
-                                    start_angle: 0.0,
-                                    end_angle: 0.0,
-                                    counter_clockwise: true,
-                                }));
-                            }
-                            4 => {
-                                // Spline edge
-                                current_path_edges.push(BoundaryEdge::Spline(SplineEdge {
-                                    degree: 3,
-                                    rational: false,
-                                    periodic: false,
-                                    knots: Vec::new(),
-                                    control_points: Vec::new(),
-                                    fit_points: Vec::new(),
-                                    start_tangent: Vector2::new(0.0, 0.0),
-                                    end_tangent: Vector2::new(0.0, 0.0),
-                                }));
-                            }
-                            _ => {}
+                47 => { if let Some(v) = pair.as_double() { hatch.pixel_size = v; } }
+                98 => {
+                    for _ in 0..pair.as_i32().unwrap_or(0) {
+                        match self.read_point2d_with_code(10)? {
+                            Some(seed) => hatch.seed_points.push(seed),
+                            None => break,
                         }
                     }
                 }
-                // Note: Full hatch reading would require reading all edge data (codes 10-40, etc.)
-                // For now, we create a basic hatch structure
                 _ => { self.try_read_common_entity_code(&pair, &mut hatch.common)?; }
             }
         }
 
-        // Save last boundary path if any
-        if reading_boundary && !current_path_edges.is_empty() {
-            let path = BoundaryPath {
-                flags: BoundaryPathFlags::new(),
-                edges: current_path_edges,
-                boundary_handles: Vec::new(),
-            };
-            hatch.paths.push(path);
+        if let Some(pt) = normal.get_point() { hatch.normal = pt; }
+
+        Ok(Some(hatch))
+    }
+
+    /// Read the boundary paths following the path count (91) of a HATCH
+    fn read_hatch_boundary_paths(&mut self, count: i32) -> Result<Vec<BoundaryPath>> {
+        let mut paths = Vec::new();
+        for _ in 0..count {
+            let Some(pair) = self.read_pair_with_code(92)? else { break };
+            let mut path = BoundaryPath::with_flags(BoundaryPathFlags::from_bits(pair.as_i32().unwrap_or(0) as u32));
+
+            if path.flags.is_polyline() {
+                let has_bulge = self.read_pair_with_code(72)?.and_then(|p| p.as_i16()).unwrap_or(0) != 0;
+                let is_closed = self.read_pair_with_code(73)?.and_then(|p| p.as_i16()).unwrap_or(0) != 0;
+                let vertex_count = self.read_pair_with_code(93)?.and_then(|p| p.as_i32()).unwrap_or(0);
+                let mut polyline = PolylineEdge::new(Vec::new(), is_closed);
+                for _ in 0..vertex_count {
+                    let Some(point) = self.read_point2d_with_code(10)? else { break };
+                    let bulge = if has_bulge { self.read_double_with_code(42)?.unwrap_or(0.0) } else { 0.0 };
+                    polyline.add_vertex(point, bulge);
+                }
+                path.edges.push(BoundaryEdge::Polyline(polyline));
+            } else {
+                let edge_count = self.read_pair_with_code(93)?.and_then(|p| p.as_i32()).unwrap_or(0);
+                for _ in 0..edge_count {
+                    match self.read_hatch_edge()? {
+                        Some(edge) => path.edges.push(edge),
+                        None => break,
+                    }
+                }
+            }
+
+            // Source boundary objects of an associative hatch
+            let source_count = self.read_pair_with_code(97)?.and_then(|p| p.as_i32()).unwrap_or(0);
+            for _ in 0..source_count {
+                let Some(pair) = self.read_pair_with_code(330)? else { break };
+                if let Some(h) = pair.as_handle() {
+                    path.boundary_handles.push(Handle::new(h));
+                }
+            }
+            paths.push(path);
         }
+        Ok(paths)
+    }
+
+    /// Read one edge of a HATCH boundary path, starting at its type (72)
+    fn read_hatch_edge(&mut self) -> Result<Option<BoundaryEdge>> {
+        let Some(edge_type) = self.read_pair_with_code(72)?.and_then(|p| p.as_i16()) else {
+            return Ok(None);
+        };
+        let edge = match edge_type {
+            1 => BoundaryEdge::Line(LineEdge {
+                start: self.read_point2d_with_code(10)?.unwrap_or_default(),
+                end: self.read_point2d_with_code(11)?.unwrap_or_default(),
+            }),
+            2 => BoundaryEdge::CircularArc(CircularArcEdge {
+                center: self.read_point2d_with_code(10)?.unwrap_or_default(),
+                radius: self.read_double_with_code(40)?.unwrap_or(0.0),
+                start_angle: self.read_double_with_code(50)?.unwrap_or(0.0).to_radians(),
+                end_angle: self.read_double_with_code(51)?.unwrap_or(0.0).to_radians(),
+                counter_clockwise: self.read_pair_with_code(73)?.and_then(|p| p.as_i16()).unwrap_or(1) != 0,
+            }),
+            3 => BoundaryEdge::EllipticArc(EllipticArcEdge {
+                center: self.read_point2d_with_code(10)?.unwrap_or_default(),
+                major_axis_endpoint: self.read_point2d_with_code(11)?.unwrap_or(Vector2::new(1.0, 0.0)),
+                minor_axis_ratio: self.read_double_with_code(40)?.unwrap_or(1.0),
+                start_angle: self.read_double_with_code(50)?.unwrap_or(0.0).to_radians(),
+                end_angle: self.read_double_with_code(51)?.unwrap_or(0.0).to_radians(),
+                counter_clockwise: self.read_pair_with_code(73)?.and_then(|p| p.as_i16()).unwrap_or(1) != 0,
+            }),
+            4 => {
+                let degree = self.read_pair_with_code(94)?.and_then(|p| p.as_i32()).unwrap_or(3);
+                let rational = self.read_pair_with_code(73)?.and_then(|p| p.as_i16()).unwrap_or(0) != 0;
+                let periodic = self.read_pair_with_code(74)?.and_then(|p| p.as_i16()).unwrap_or(0) != 0;
+                let knot_count = self.read_pair_with_code(95)?.and_then(|p| p.as_i32()).unwrap_or(0);
+                let control_count = self.read_pair_with_code(96)?.and_then(|p| p.as_i32()).unwrap_or(0);
+                let mut spline = SplineEdge {
+                    degree,
+                    rational,
+                    periodic,
+                    knots: Vec::new(),
+                    control_points: Vec::new(),
+                    fit_points: Vec::new(),
+                    start_tangent: Vector2::new(0.0, 0.0),
+                    end_tangent: Vector2::new(0.0, 0.0),
+                };
+                for _ in 0..knot_count {
+                    let Some(knot) = self.read_double_with_code(40)? else { break };
+                    spline.knots.push(knot);
+                }
+                for _ in 0..control_count {
+                    let Some(point) = self.read_point2d_with_code(10)? else { break };
+                    let weight = self.read_double_with_code(42)?.unwrap_or(1.0);
+                    spline.control_points.push(Vector3::new(point.x, point.y, weight));
+                }
+                // Fit data, written from R2010 on
+                if let Some(fit_count) = self.read_pair_with_code(97)?.and_then(|p| p.as_i32()) {
+                    for _ in 0..fit_count {
+                        let Some(point) = self.read_point2d_with_code(11)? else { break };
+                        spline.fit_points.push(point);
+                    }
+                    if let Some(tangent) = self.read_point2d_with_code(12)? { spline.start_tangent = tangent; }
+                    if let Some(tangent) = self.read_point2d_with_code(13)? { spline.end_tangent = tangent; }
+                }
+                BoundaryEdge::Spline(spline)
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(edge))
+    }
 
-        hatch.common.layer = layer;
-        hatch.common.color = color;
-        hatch.common.line_weight = line_weight;
-        hatch.pattern.name = pattern_name;
-        hatch.pattern_type = pattern_type;
+    /// Read the pattern definition lines following the line count (78) of
+    /// a HATCH
+    fn read_hatch_pattern_lines(&mut self, count: i16) -> Result<Vec<HatchPatternLine>> {
+        let mut lines = Vec::new();
+        for _ in 0..count {
+            let Some(angle) = self.read_double_with_code(53)? else { break };
+            let base_x = self.read_double_with_code(43)?.unwrap_or(0.0);
+            let base_y = self.read_double_with_code(44)?.unwrap_or(0.0);
+            let offset_x = self.read_double_with_code(45)?.unwrap_or(0.0);
+            let offset_y = self.read_double_with_code(46)?.unwrap_or(0.0);
+            let dash_count = self.read_pair_with_code(79)?.and_then(|p| p.as_i16()).unwrap_or(0);
+            let mut dash_lengths = Vec::new();
+            for _ in 0..dash_count {
+                let Some(dash) = self.read_double_with_code(49)? else { break };
+                dash_lengths.push(dash);
+            }
+            lines.push(HatchPatternLine {
+                angle: angle.to_radians(),
+                base_point: Vector2::new(base_x, base_y),
+                offset: Vector2::new(offset_x, offset_y),
+                dash_lengths,
+            });
+        }
+        Ok(lines)
+    }
 
-        Ok(Some(hatch))
+    /// The next pair if it has group code `code`; any other pair is left
+    /// for the caller
+    fn read_pair_with_code(&mut self, code: i32) -> Result<Option<DxfCodePair>> {
+        match self.reader.read_pair()? {
+            Some(pair) if pair.code == code => Ok(Some(pair)),
+            Some(pair) => {
+                self.reader.push_back(pair);
+                Ok(None)
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// The value of the next pair if it is a real with group code `code`
+    fn read_double_with_code(&mut self, code: i32) -> Result<Option<f64>> {
+        Ok(self.read_pair_with_code(code)?.and_then(|p| p.as_double()))
+    }
+
+    /// A 2D point from the next pairs if they are `x_code` and `x_code + 10`
+    fn read_point2d_with_code(&mut self, x_code: i32) -> Result<Option<Vector2>> {
+        let Some(x) = self.read_double_with_code(x_code)? else { return Ok(None) };
+        let y = self.read_double_with_code(x_code + 10)?.unwrap_or(0.0);
+        Ok(Some(Vector2::new(x, y)))
     }
 
     /// Read a SOLID entity
@@ -3077,7 +3844,7 @@ impl<'a> SectionReader<'a> {
             }
 
             match pair.code {
-                8 => layer = pair.value_string.clone(),
+                8 => layer = pair.value_string.to_string(),
                 62 => {
                     if let Some(color_index) = pair.as_i16() {
                         color = Color::from_index(color_index);
@@ -3133,7 +3900,7 @@ impl<'a> SectionReader<'a> {
             }
 
             match pair.code {
-                8 => layer = pair.value_string.clone(),
+                8 => layer = pair.value_string.to_string(),
                 62 => {
                     if let Some(color_index) = pair.as_i16() {
                         color = Color::from_index(color_index);
@@ -3183,22 +3950,13 @@ impl<'a> SectionReader<'a> {
         Ok(Some(face))
     }
 
-    /// Read an INSERT entity
+    /// Read an INSERT entity, with the ATTRIB entities that follow it
+    /// when its attributes-follow flag (66) is set
     fn read_insert(&mut self) -> Result<Option<Insert>> {
-        let mut block_name = String::new();
+        let mut insert = Insert::new(String::new(), Vector3::zero());
         let mut insertion = PointReader::new();
-        let mut x_scale = 1.0;
-        let mut y_scale = 1.0;
-        let mut z_scale = 1.0;
-        let mut rotation = 0.0;
-        let mut column_count = 1u16;
-        let mut row_count = 1u16;
-        let mut column_spacing = 0.0;
-        let mut row_spacing = 0.0;
-        let mut layer = String::from("0");
-        let mut color = Color::ByLayer;
-        let mut line_weight = LineWeight::ByLayer;
-        let mut common = EntityCommon::new();
+        let mut normal = PointReader::new();
+        let mut attributes_follow = false;
 
         while let Some(pair) = self.reader.read_pair()? {
             if pair.code == 0 {
@@ -3207,81 +3965,95 @@ impl<'a> SectionReader<'a> {
             }
 
             match pair.code {
-                8 => layer = pair.value_string.clone(),
+                8 => insert.common.layer = pair.value_string.to_string(),
                 62 => {
                     if let Some(color_index) = pair.as_i16() {
-                        color = Color::from_index(color_index);
+                        insert.common.color = Color::from_index(color_index);
                     }
                 }
                 370 => {
                     if let Some(lw) = pair.as_i16() {
-                        line_weight = LineWeight::from_value(lw);
+                        insert.common.line_weight = LineWeight::from_value(lw);
                     }
                 }
-                2 => block_name = pair.value_string.clone(),
+                2 => insert.block_name = pair.value_string.to_string(),
                 10 | 20 | 30 => { insertion.add_coordinate(&pair); }
+                210 | 220 | 230 => { normal.add_coordinate(&pair); }
                 41 => {
                     if let Some(sx) = pair.as_double() {
-                        x_scale = sx;
+                        insert.x_scale = sx;
                     }
                 }
                 42 => {
                     if let Some(sy) = pair.as_double() {
-                        y_scale = sy;
+                        insert.y_scale = sy;
                     }
                 }
                 43 => {
                     if let Some(sz) = pair.as_double() {
-                        z_scale = sz;
+                        insert.z_scale = sz;
                     }
                 }
                 50 => {
                     if let Some(rot) = pair.as_double() {
-                        rotation = rot;
+                        insert.rotation = rot.to_radians();
+                    }
+                }
+                66 => {
+                    if let Some(flag) = pair.as_i16() {
+                        attributes_follow = flag != 0;
                     }
                 }
                 70 => {
                     if let Some(col_count) = pair.as_i16() {
-                        column_count = col_count.max(1) as u16;
+                        insert.column_count = col_count.max(1) as u16;
                     }
                 }
                 71 => {
                     if let Some(r_count) = pair.as_i16() {
-                        row_count = r_count.max(1) as u16;
+                        insert.row_count = r_count.max(1) as u16;
                     }
                 }
                 44 => {
                     if let Some(col_spacing_val) = pair.as_double() {
-                        column_spacing = col_spacing_val;
+                        insert.column_spacing = col_spacing_val;
                     }
                 }
                 45 => {
                     if let Some(row_spacing_val) = pair.as_double() {
-                        row_spacing = row_spacing_val;
+                        insert.row_spacing = row_spacing_val;
                     }
                 }
-                _ => { self.try_read_common_entity_code(&pair, &mut common)?; }
+                _ => { self.try_read_common_entity_code(&pair, &mut insert.common)?; }
             }
         }
 
-        let insert_point = insertion.get_point().unwrap_or(Vector3::zero());
-        let mut insert = Insert::new(block_name, insert_point);
-        insert.common.layer = layer;
-        insert.common.color = color;
-        insert.common.line_weight = line_weight;
-        insert.x_scale = x_scale;
-        insert.y_scale = y_scale;
-        insert.z_scale = z_scale;
-        insert.rotation = rotation;
-        insert.column_count = column_count;
-        insert.row_count = row_count;
-        insert.column_spacing = column_spacing;
-        insert.row_spacing = row_spacing;
-        insert.common.handle = common.handle;
-        insert.common.owner_handle = common.owner_handle;
-        insert.common.reactors = common.reactors;
-        insert.common.xdictionary_handle = common.xdictionary_handle;
-        insert.common.invisible = common.invisible;
+        insert.insert_point = insertion.get_point().unwrap_or(Vector3::zero());
+        if let Some(pt) = normal.get_point() {
+            insert.normal = pt;
+        }
+
+        // ATTRIB entities up to the closing SEQEND belong to the insert
+        if !attributes_follow {
+            return Ok(Some(insert));
+        }
+        while let Some(pair) = self.reader.read_pair()? {
+            match (pair.code, pair.value_string.as_str()) {
+                (0, "ATTRIB") => {
+                    if let Some(attrib) = self.read_attrib()? {
+                        insert.attributes.push(attrib);
+                    }
+                }
+                (0, "SEQEND") => {
+                    self.skip_entity()?;
+                    break;
+                }
+                _ => {
+                    self.reader.push_back(pair);
+                    break;
+                }
+            }
+        }
 
         Ok(Some(insert))
     }
@@ -3301,7 +4073,7 @@ impl<'a> SectionReader<'a> {
             }
 
             match pair.code {
-                8 => layer = pair.value_string.clone(),
+                8 => layer = pair.value_string.to_string(),
                 62 => {
                     if let Some(color_index) = pair.as_i16() {
                         color = Color::from_index(color_index);
@@ -3342,7 +4114,7 @@ impl<'a> SectionReader<'a> {
             }
 
             match pair.code {
-                8 => layer = pair.value_string.clone(),
+                8 => layer = pair.value_string.to_string(),
                 62 => {
                     if let Some(color_index) = pair.as_i16() {
                         color = Color::from_index(color_index);
@@ -3370,58 +4142,43 @@ impl<'a> SectionReader<'a> {
 
     /// Read an ATTDEF entity
     fn read_attdef(&mut self) -> Result<Option<AttributeDefinition>> {
-        let mut tag = String::new();
-        let mut prompt = String::new();
-        let mut default_value = String::new();
+        let mut attdef = AttributeDefinition::new(String::new(), String::new(), String::new());
         let mut insertion_point = PointReader::new();
-        let mut height = 0.0;
-        let mut rotation = 0.0;
-        let mut layer = String::from("0");
-        let mut color = Color::ByLayer;
-        let mut common = EntityCommon::new();
+        let mut alignment_point = PointReader::new();
+        let mut normal = PointReader::new();
 
         while let Some(pair) = self.reader.read_pair()? {
-            if pair.code == 0 {
-                self.reader.push_back(pair);
-                break;
-            }
-
+            if pair.code == 0 { self.reader.push_back(pair); break; }
             match pair.code {
-                8 => layer = pair.value_string.clone(),
-                62 => {
-                    if let Some(color_index) = pair.as_i16() {
-                        color = Color::from_index(color_index);
-                    }
-                }
-                1 => default_value = pair.value_string.clone(),
-                2 => tag = pair.value_string.clone(),
-                3 => prompt = pair.value_string.clone(),
+                8 => attdef.common.layer = pair.value_string.to_string(),
+                62 => { if let Some(v) = pair.as_i16() { attdef.common.color = Color::from_index(v); } }
+                370 => { if let Some(v) = pair.as_i16() { attdef.common.line_weight = LineWeight::from_value(v); } }
+                1 => attdef.default_value = pair.value_string.to_string(),
+                2 => attdef.tag = pair.value_string.to_string(),
+                3 => attdef.prompt = pair.value_string.to_string(),
+                7 => attdef.text_style = pair.value_string.to_string(),
                 10 | 20 | 30 => { insertion_point.add_coordinate(&pair); }
-                40 => {
-                    if let Some(h) = pair.as_double() {
-                        height = h;
-                    }
-                }
-                50 => {
-                    if let Some(r) = pair.as_double() {
-                        rotation = r;
-                    }
-                }
-                _ => { self.try_read_common_entity_code(&pair, &mut common)?; }
+                11 | 21 | 31 => { alignment_point.add_coordinate(&pair); }
+                210 | 220 | 230 => { normal.add_coordinate(&pair); }
+                40 => { if let Some(v) = pair.as_double() { attdef.height = v; } }
+                41 => { if let Some(v) = pair.as_double() { attdef.width_factor = v; } }
+                50 => { if let Some(v) = pair.as_double() { attdef.rotation = v.to_radians(); } }
+                51 => { if let Some(v) = pair.as_double() { attdef.oblique_angle = v.to_radians(); } }
+                70 => { if let Some(v) = pair.as_i16() { attdef.flags = AttributeFlags::from_bits(v as i32); } }
+                71 => { if let Some(v) = pair.as_i16() { attdef.text_generation_flags = v; } }
+                72 => { if let Some(v) = pair.as_i16() { attdef.horizontal_alignment = HorizontalAlignment::from_value(v); } }
+                73 => { if let Some(v) = pair.as_i16() { attdef.field_length = v; } }
+                74 => { if let Some(v) = pair.as_i16() { attdef.vertical_alignment = VerticalAlignment::from_value(v); } }
+                _ => { self.try_read_common_entity_code(&pair, &mut attdef.common)?; }
             }
         }
 
-        let mut attdef = AttributeDefinition::new(tag, prompt, default_value);
         attdef.insertion_point = insertion_point.get_point().unwrap_or(Vector3::zero());
-        attdef.height = height;
-        attdef.rotation = rotation;
-        attdef.common.layer = layer;
-        attdef.common.color = color;
-        attdef.common.handle = common.handle;
-        attdef.common.owner_handle = common.owner_handle;
-        attdef.common.reactors = common.reactors;
-        attdef.common.xdictionary_handle = common.xdictionary_handle;
-        attdef.common.invisible = common.invisible;
+        // Left/baseline text has no second alignment point
+        attdef.alignment_point = alignment_point.get_point().unwrap_or(attdef.insertion_point);
+        if let Some(pt) = normal.get_point() {
+            attdef.normal = pt;
+        }
 
         Ok(Some(attdef))
     }
@@ -3439,14 +4196,14 @@ impl<'a> SectionReader<'a> {
             }
 
             match pair.code {
-                8 => tolerance.common.layer = pair.value_string.clone(),
+                8 => tolerance.common.layer = pair.value_string.to_string(),
                 62 => {
                     if let Some(color_index) = pair.as_i16() {
                         tolerance.common.color = Color::from_index(color_index);
                     }
                 }
-                1 => tolerance.text = pair.value_string.clone(),
-                3 => tolerance.dimension_style_name = pair.value_string.clone(),
+                1 => tolerance.text = pair.value_string.to_string(),
+                3 => tolerance.dimension_style_name = pair.value_string.to_string(),
                 10 | 20 | 30 => { insertion_point.add_coordinate(&pair); }
                 11 | 21 | 31 => { direction.add_coordinate(&pair); }
                 _ => { self.try_read_common_entity_code(&pair, &mut tolerance.common)?; }
@@ -3471,13 +4228,13 @@ impl<'a> SectionReader<'a> {
             }
 
             match pair.code {
-                8 => shape.common.layer = pair.value_string.clone(),
+                8 => shape.common.layer = pair.value_string.to_string(),
                 62 => {
                     if let Some(color_index) = pair.as_i16() {
                         shape.common.color = Color::from_index(color_index);
                     }
                 }
-                2 => shape.shape_name = pair.value_string.clone(),
+                2 => shape.shape_name = pair.value_string.to_string(),
                 10 | 20 | 30 => { insertion_point.add_coordinate(&pair); }
                 40 => {
                     if let Some(s) = pair.as_double() {
@@ -3512,7 +4269,7 @@ impl<'a> SectionReader<'a> {
             }
 
             match pair.code {
-                8 => wipeout.common.layer = pair.value_string.clone(),
+                8 => wipeout.common.layer = pair.value_string.to_string(),
                 62 => {
                     if let Some(color_index) = pair.as_i16() {
                         wipeout.common.color = Color::from_index(color_index);
@@ -3560,24 +4317,24 @@ impl<'a> SectionReader<'a> {
                         xdata.add_record(record);
                     }
                     // Start new record
-                    current_record = Some(ExtendedDataRecord::new(pair.value_string.clone()));
+                    current_record = Some(ExtendedDataRecord::new(pair.value_string.to_string()));
                 }
                 // String value
                 1000 => {
                     if let Some(ref mut record) = current_record {
-                        record.add_value(XDataValue::String(pair.value_string.clone()));
+                        record.add_value(XDataValue::String(pair.value_string.to_string()));
                     }
                 }
                 // Control string
                 1002 => {
                     if let Some(ref mut record) = current_record {
-                        record.add_value(XDataValue::ControlString(pair.value_string.clone()));
+                        record.add_value(XDataValue::ControlString(pair.value_string.to_string()));
                     }
                 }
                 // Layer name
                 1003 => {
                     if let Some(ref mut record) = current_record {
-                        record.add_value(XDataValue::LayerName(pair.value_string.clone()));
+                        record.add_value(XDataValue::LayerName(pair.value_string.to_string()));
                     }
                 }
                 // Binary data
@@ -3719,7 +4476,7 @@ impl<'a> SectionReader<'a> {
         while let Some(pair) = self.reader.read_pair()? {
             if pair.code == 0 { self.reader.push_back(pair); break; }
             match pair.code {
-                8 => vp.common.layer = pair.value_string.clone(),
+                8 => vp.common.layer = pair.value_string.to_string(),
                 62 => { if let Some(v) = pair.as_i16() { vp.common.color = Color::from_index(v); } }
                 10 | 20 | 30 => { center.add_coordinate(&pair); }
                 40 => { if let Some(v) = pair.as_double() { vp.width = v; } }
@@ -3740,8 +4497,8 @@ impl<'a> SectionReader<'a> {
                 43 => { if let Some(v) = pair.as_double() { vp.front_clip_z = v; } }
                 44 => { if let Some(v) = pair.as_double() { vp.back_clip_z = v; } }
                 45 => { if let Some(v) = pair.as_double() { vp.view_height = v; } }
-                50 => { if let Some(v) = pair.as_double() { vp.snap_angle = v; } }
-                51 => { if let Some(v) = pair.as_double() { vp.twist_angle = v; } }
+                50 => { if let Some(v) = pair.as_double() { vp.snap_angle = v.to_radians(); } }
+                51 => { if let Some(v) = pair.as_double() { vp.twist_angle = v.to_radians(); } }
                 72 => { if let Some(v) = pair.as_i16() { vp.circle_sides = v; } }
                 331 => {
                     if let Ok(h) = u64::from_str_radix(&pair.value_string, 16) {
@@ -3750,6 +4507,23 @@ impl<'a> SectionReader<'a> {
                 }
                 281 => { if let Some(v) = pair.as_i16() { vp.render_mode = crate::entities::viewport::ViewportRenderMode::from_value(v); } }
                 71 => { if let Some(v) = pair.as_i16() { vp.ucs_per_viewport = v != 0; } }
+                74 => { if let Some(v) = pair.as_i16() { vp.ucs_icon_visible = v != 0; } }
+                79 => { if let Some(v) = pair.as_i16() { vp.ucs_ortho_type = v; } }
+                170 => { if let Some(v) = pair.as_i16() { vp.shade_plot_mode = v; } }
+                345 | 346 | 332 | 333 | 348 | 361 => {
+                    if let Ok(h) = u64::from_str_radix(&pair.value_string, 16) {
+                        let handle = Handle::new(h);
+                        match pair.code {
+                            345 => vp.ucs_handle = handle,
+                            346 => vp.base_ucs_handle = handle,
+                            332 => vp.background_handle = handle,
+                            333 => vp.shade_plot_handle = handle,
+                            361 => vp.sun_handle = handle,
+                            _ => vp.visual_style_handle = handle,
+                        }
+                    }
+                }
+                421 => { if let Some(v) = pair.as_i32() { vp.ambient_color = v; } }
                 110 | 120 | 130 => { ucs_origin.add_coordinate(&pair); }
                 111 | 121 | 131 => { ucs_x_axis.add_coordinate(&pair); }
                 112 | 122 | 132 => { ucs_y_axis.add_coordinate(&pair); }
@@ -3785,22 +4559,24 @@ impl<'a> SectionReader<'a> {
         let mut attrib = AttributeEntity::new(String::new(), String::new());
         let mut insertion_point = PointReader::new();
         let mut alignment_point = PointReader::new();
+        let mut normal = PointReader::new();
 
         while let Some(pair) = self.reader.read_pair()? {
             if pair.code == 0 { self.reader.push_back(pair); break; }
             match pair.code {
-                8 => attrib.common.layer = pair.value_string.clone(),
+                8 => attrib.common.layer = pair.value_string.to_string(),
                 62 => { if let Some(v) = pair.as_i16() { attrib.common.color = Color::from_index(v); } }
                 370 => { if let Some(v) = pair.as_i16() { attrib.common.line_weight = LineWeight::from_value(v); } }
-                1 => attrib.value = pair.value_string.clone(),
-                2 => attrib.tag = pair.value_string.clone(),
-                7 => attrib.text_style = pair.value_string.clone(),
+                1 => attrib.value = pair.value_string.to_string(),
+                2 => attrib.tag = pair.value_string.to_string(),
+                7 => attrib.text_style = pair.value_string.to_string(),
                 10 | 20 | 30 => { insertion_point.add_coordinate(&pair); }
                 11 | 21 | 31 => { alignment_point.add_coordinate(&pair); }
+                210 | 220 | 230 => { normal.add_coordinate(&pair); }
                 40 => { if let Some(v) = pair.as_double() { attrib.height = v; } }
                 41 => { if let Some(v) = pair.as_double() { attrib.width_factor = v; } }
-                50 => { if let Some(v) = pair.as_double() { attrib.rotation = v; } }
-                51 => { if let Some(v) = pair.as_double() { attrib.oblique_angle = v; } }
+                50 => { if let Some(v) = pair.as_double() { attrib.rotation = v.to_radians(); } }
+                51 => { if let Some(v) = pair.as_double() { attrib.oblique_angle = v.to_radians(); } }
                 70 => {
                     if let Some(v) = pair.as_i16() {
                         attrib.flags = crate::entities::attribute_definition::AttributeFlags::from_bits(v as i32);
@@ -3812,6 +4588,7 @@ impl<'a> SectionReader<'a> {
                         attrib.horizontal_alignment = crate::entities::attribute_definition::HorizontalAlignment::from_value(v);
                     }
                 }
+                73 => { if let Some(v) = pair.as_i16() { attrib.field_length = v; } }
                 74 => {
                     if let Some(v) = pair.as_i16() {
                         attrib.vertical_alignment = crate::entities::attribute_definition::VerticalAlignment::from_value(v);
@@ -3822,7 +4599,11 @@ impl<'a> SectionReader<'a> {
         }
 
         attrib.insertion_point = insertion_point.get_point().unwrap_or(Vector3::zero());
-        attrib.alignment_point = alignment_point.get_point().unwrap_or(Vector3::zero());
+        // Left/baseline text has no second alignment point
+        attrib.alignment_point = alignment_point.get_point().unwrap_or(attrib.insertion_point);
+        if let Some(pt) = normal.get_point() {
+            attrib.normal = pt;
+        }
 
         Ok(Some(attrib))
     }
@@ -3831,19 +4612,16 @@ impl<'a> SectionReader<'a> {
     fn read_leader(&mut self) -> Result<Option<Leader>> {
         let mut leader = Leader::new();
         let mut normal = PointReader::new();
-        let mut horiz_dir = PointReader::new();
-        let mut block_offset = PointReader::new();
-        let mut annotation_offset = PointReader::new();
         let mut reading_vertex = false;
         let mut current_vertex = PointReader::new();
 
         while let Some(pair) = self.reader.read_pair()? {
             if pair.code == 0 { self.reader.push_back(pair); break; }
             match pair.code {
-                8 => leader.common.layer = pair.value_string.clone(),
+                8 => leader.common.layer = pair.value_string.to_string(),
                 62 => { if let Some(v) = pair.as_i16() { leader.common.color = Color::from_index(v); } }
                 370 => { if let Some(v) = pair.as_i16() { leader.common.line_weight = LineWeight::from_value(v); } }
-                3 => leader.dimension_style = pair.value_string.clone(),
+                3 => leader.dimension_style = pair.value_string.to_string(),
                 71 => { if let Some(v) = pair.as_i16() { leader.arrow_enabled = v != 0; } }
                 72 => {
                     if let Some(v) = pair.as_i16() {
@@ -3866,6 +4644,7 @@ impl<'a> SectionReader<'a> {
                     }
                 }
                 75 => { if let Some(v) = pair.as_i16() { leader.hookline_enabled = v != 0; } }
+                77 => { if let Some(v) = pair.as_i16() { leader.override_color = Color::from_index(v); } }
                 40 => { if let Some(v) = pair.as_double() { leader.text_height = v; } }
                 41 => { if let Some(v) = pair.as_double() { leader.text_width = v; } }
                 10 => {
@@ -3884,9 +4663,16 @@ impl<'a> SectionReader<'a> {
                     }
                 }
                 210 | 220 | 230 => { normal.add_coordinate(&pair); }
-                211 | 221 | 231 => { horiz_dir.add_coordinate(&pair); }
-                212 | 222 | 232 => { block_offset.add_coordinate(&pair); }
-                213 | 223 | 233 => { annotation_offset.add_coordinate(&pair); }
+                // 211-213 have no DxfCode of their own, so PointReader can't take them
+                211 => { if let Some(v) = pair.as_double() { leader.horizontal_direction.x = v; } }
+                221 => { if let Some(v) = pair.as_double() { leader.horizontal_direction.y = v; } }
+                231 => { if let Some(v) = pair.as_double() { leader.horizontal_direction.z = v; } }
+                212 => { if let Some(v) = pair.as_double() { leader.block_offset.x = v; } }
+                222 => { if let Some(v) = pair.as_double() { leader.block_offset.y = v; } }
+                232 => { if let Some(v) = pair.as_double() { leader.block_offset.z = v; } }
+                213 => { if let Some(v) = pair.as_double() { leader.annotation_offset.x = v; } }
+                223 => { if let Some(v) = pair.as_double() { leader.annotation_offset.y = v; } }
+                233 => { if let Some(v) = pair.as_double() { leader.annotation_offset.z = v; } }
                 _ => { self.try_read_common_entity_code(&pair, &mut leader.common)?; }
             }
         }
@@ -3896,9 +4682,6 @@ impl<'a> SectionReader<'a> {
             if let Some(pt) = current_vertex.get_point() { leader.vertices.push(pt); }
         }
         if let Some(pt) = normal.get_point() { leader.normal = pt; }
-        if let Some(pt) = horiz_dir.get_point() { leader.horizontal_direction = pt; }
-        if let Some(pt) = block_offset.get_point() { leader.block_offset = pt; }
-        if let Some(pt) = annotation_offset.get_point() { leader.annotation_offset = pt; }
 
         Ok(Some(leader))
     }
@@ -3910,7 +4693,7 @@ impl<'a> SectionReader<'a> {
         while let Some(pair) = self.reader.read_pair()? {
             if pair.code == 0 { self.reader.push_back(pair); break; }
             match pair.code {
-                8 => ml.common.layer = pair.value_string.clone(),
+                8 => ml.common.layer = pair.value_string.to_string(),
                 62 => { if let Some(v) = pair.as_i16() { ml.common.color = Color::from_index(v); } }
                 370 => { if let Some(v) = pair.as_i16() { ml.common.line_weight = LineWeight::from_value(v); } }
                 340 => { if let Ok(h) = u64::from_str_radix(&pair.value_string, 16) { ml.style_handle = Some(Handle::new(h)); } }
@@ -3962,10 +4745,10 @@ impl<'a> SectionReader<'a> {
         while let Some(pair) = self.reader.read_pair()? {
             if pair.code == 0 { self.reader.push_back(pair); break; }
             match pair.code {
-                8 => mline.common.layer = pair.value_string.clone(),
+                8 => mline.common.layer = pair.value_string.to_string(),
                 62 => { if let Some(v) = pair.as_i16() { mline.common.color = Color::from_index(v); } }
                 370 => { if let Some(v) = pair.as_i16() { mline.common.line_weight = LineWeight::from_value(v); } }
-                2 => mline.style_name = pair.value_string.clone(),
+                2 => mline.style_name = pair.value_string.to_string(),
                 40 => { if let Some(v) = pair.as_double() { mline.scale_factor = v; } }
                 70 => { if let Some(v) = pair.as_i16() { mline.justification = MLineJustification::from(v); } }
                 71 => { if let Some(v) = pair.as_i16() { mline.flags = MLineFlags::from_bits_truncate(v); } }
@@ -4080,7 +4863,7 @@ impl<'a> SectionReader<'a> {
         while let Some(pair) = self.reader.read_pair()? {
             if pair.code == 0 { self.reader.push_back(pair); break; }
             match pair.code {
-                8 => mesh.common.layer = pair.value_string.clone(),
+                8 => mesh.common.layer = pair.value_string.to_string(),
                 62 => { if let Some(v) = pair.as_i16() { mesh.common.color = Color::from_index(v); } }
                 370 => { if let Some(v) = pair.as_i16() { mesh.common.line_weight = LineWeight::from_value(v); } }
                 71 => { if let Some(v) = pair.as_i16() { mesh.version = v; } }
@@ -4093,7 +4876,9 @@ impl<'a> SectionReader<'a> {
                         _ => {}
                     }
                 }
-                92 => {
+                // 92 is also a count in the property overrides that follow
+                // the creases
+                92 if reading_state == MeshReadState::Properties => {
                     if let Some(v) = pair.as_i32() { vertex_count = v as usize; reading_state = MeshReadState::Vertices; }
                 }
                 93 => {
@@ -4133,7 +4918,7 @@ impl<'a> SectionReader<'a> {
                             MeshReadState::Edges => {
                                 edge_buf.push(v as usize);
                                 if edge_buf.len() == 2 {
-                                    mesh.edges.push(MeshEdge { start: edge_buf[0], end: edge_buf[1], crease: Some(0.0) });
+                                    mesh.edges.push(MeshEdge::new(edge_buf[0], edge_buf[1]));
                                     edge_buf.clear();
                                 }
                             }
@@ -4150,9 +4935,9 @@ impl<'a> SectionReader<'a> {
             }
         }
 
-        // Apply crease values to edges
-        for (i, crease) in crease_values.into_iter().enumerate() {
-            if i < mesh.edges.len() { mesh.edges[i].crease = Some(crease); }
+        // Crease values follow the edge order; 0 is no crease
+        for (edge, crease) in mesh.edges.iter_mut().zip(crease_values) {
+            if crease != 0.0 { edge.crease = Some(crease); }
         }
 
         let _ = vertex_count;
@@ -4170,7 +4955,7 @@ impl<'a> SectionReader<'a> {
         while let Some(pair) = self.reader.read_pair()? {
             if pair.code == 0 { self.reader.push_back(pair); break; }
             match pair.code {
-                8 => img.common.layer = pair.value_string.clone(),
+                8 => img.common.layer = pair.value_string.to_string(),
                 62 => { if let Some(v) = pair.as_i16() { img.common.color = Color::from_index(v); } }
                 10 | 20 | 30 => { insertion_point.add_coordinate(&pair); }
                 11 | 21 | 31 => { u_vector.add_coordinate(&pair); }
@@ -4179,7 +4964,12 @@ impl<'a> SectionReader<'a> {
                 23 => { if let Some(v) = pair.as_double() { img.size.y = v; } }
                 340 => { if let Ok(h) = u64::from_str_radix(&pair.value_string, 16) { img.definition_handle = Some(Handle::new(h)); } }
                 360 => { if let Ok(h) = u64::from_str_radix(&pair.value_string, 16) { img.definition_reactor_handle = Some(Handle::new(h)); } }
-                70 => { /* display properties flags */ }
+                90 => { if let Some(v) = pair.as_i32() { img.class_version = v; } }
+                70 => { if let Some(v) = pair.as_i16() { img.flags = ImageDisplayFlags::from_bits_truncate(v); } }
+                71 => { if let Some(v) = pair.as_i16() { img.clip_boundary.clip_type = ClipType::from(v); } }
+                290 => { if let Some(v) = pair.as_bool() { img.clip_boundary.clip_mode = ClipMode::from(v as u8); } }
+                // The vertices replace the full image boundary of `RasterImage::new`
+                91 => img.clip_boundary.vertices.clear(),
                 280 => { if let Some(v) = pair.as_i16() { img.clipping_enabled = v != 0; } }
                 281 => { if let Some(v) = pair.as_i16() { img.brightness = v as u8; } }
                 282 => { if let Some(v) = pair.as_i16() { img.contrast = v as u8; } }
@@ -4214,14 +5004,14 @@ impl<'a> SectionReader<'a> {
         while let Some(pair) = self.reader.read_pair()? {
             if pair.code == 0 { self.reader.push_back(pair); break; }
             match pair.code {
-                8 => common.layer = pair.value_string.clone(),
+                8 => common.layer = pair.value_string.to_string(),
                 62 => { if let Some(v) = pair.as_i16() { common.color = Color::from_index(v); } }
                 370 => { if let Some(v) = pair.as_i16() { common.line_weight = LineWeight::from_value(v); } }
                 1 | 3 => {
                     acis_data.push_str(&pair.value_string);
                     acis_data.push('\n');
                 }
-                2 => uid = pair.value_string.clone(),
+                2 => uid = pair.value_string.to_string(),
                 _ => { self.try_read_common_entity_code(&pair, &mut common)?; }
             }
         }
@@ -4257,29 +5047,119 @@ impl<'a> SectionReader<'a> {
         Ok(Some(body))
     }
 
-    /// Read a TABLE entity (basic properties)
+    /// Read an ACAD_TABLE entity: the grid, its sizes and the cells, which
+    /// follow the row heights and column widths row by row from the first
+    /// code 171 on
     fn read_table_entity(&mut self) -> Result<Option<crate::entities::Table>> {
         let mut insertion_point = PointReader::new();
-        let mut table = crate::entities::Table::new(Vector3::zero(), 1, 1);
+        let mut direction = PointReader::new();
+        let mut table = crate::entities::Table::new(Vector3::zero(), 0, 0);
+        let (mut row_count, mut column_count) = (0usize, 0usize);
+        let mut row_heights = Vec::new();
+        let mut column_widths = Vec::new();
+        let mut cells: Vec<TableCell> = Vec::new();
+        let mut extended_text = String::new();
 
         while let Some(pair) = self.reader.read_pair()? {
             if pair.code == 0 { self.reader.push_back(pair); break; }
+            if pair.code == 171 {
+                let mut cell = TableCell::new();
+                cell.cell_type = CellType::from(pair.as_i16().unwrap_or(1) as u8);
+                cells.push(cell);
+                extended_text.clear();
+                continue;
+            }
+            if let Some(cell) = cells.last_mut() {
+                match pair.code {
+                    172 => { if let Some(v) = pair.as_i32() { cell.flag = v; } }
+                    173 => { if let Some(v) = pair.as_i32() { cell.merged = v; } }
+                    174 => { if let Some(v) = pair.as_i16() { cell.auto_fit = v != 0; } }
+                    175 => { if let Some(v) = pair.as_i32() { cell.merge_width = v.max(1); } }
+                    176 => { if let Some(v) = pair.as_i32() { cell.merge_height = v.max(1); } }
+                    178 => { if let Some(v) = pair.as_i16() { cell.virtual_edge = v; } }
+                    145 => { if let Some(v) = pair.as_double() { cell.rotation = v.to_radians(); } }
+                    2 => extended_text.push_str(&pair.value_string),
+                    1 => {
+                        let text = std::mem::take(&mut extended_text) + &pair.value_string;
+                        match cell.contents.first_mut() {
+                            Some(content) => {
+                                content.content_type = TableCellContentType::Value;
+                                content.value = CellValue::text(&text);
+                            }
+                            None => cell.contents.push(CellContent::text(&text)),
+                        }
+                    }
+                    140 => {
+                        if let Some(v) = pair.as_double() {
+                            if cell.contents.is_empty() {
+                                cell.contents.push(CellContent::text(""));
+                            }
+                            cell.contents[0].text_height = v;
+                        }
+                    }
+                    340 => {
+                        if let Some(h) = pair.as_handle() {
+                            cell.cell_type = CellType::Block;
+                            cell.contents = vec![CellContent::block(Handle::new(h))];
+                        }
+                    }
+                    144 => {
+                        if let (Some(v), Some(content)) = (pair.as_double(), cell.contents.first_mut()) {
+                            content.scale = v;
+                        }
+                    }
+                    170 => {
+                        if let Some(v) = pair.as_i16() {
+                            cell.style.get_or_insert_with(CellStyle::new).alignment = v as i32;
+                        }
+                    }
+                    _ => { self.try_read_common_entity_code(&pair, &mut table.common)?; }
+                }
+                continue;
+            }
             match pair.code {
-                8 => table.common.layer = pair.value_string.clone(),
+                8 => table.common.layer = pair.value_string.to_string(),
                 62 => { if let Some(v) = pair.as_i16() { table.common.color = Color::from_index(v); } }
                 370 => { if let Some(v) = pair.as_i16() { table.common.line_weight = LineWeight::from_value(v); } }
                 10 | 20 | 30 => { insertion_point.add_coordinate(&pair); }
-                342 => {
-                    if let Ok(h) = u64::from_str_radix(&pair.value_string, 16) { table.table_style_handle = Some(Handle::new(h)); }
-                }
-                343 => {
-                    if let Ok(h) = u64::from_str_radix(&pair.value_string, 16) { table.block_record_handle = Some(Handle::new(h)); }
-                }
+                11 | 21 | 31 => { direction.add_coordinate(&pair); }
+                280 => { if let Some(v) = pair.as_i16() { table.data_version = v; } }
+                342 => { if let Some(h) = pair.as_handle() { table.table_style_handle = Some(Handle::new(h)); } }
+                343 => { if let Some(h) = pair.as_handle() { table.block_record_handle = Some(Handle::new(h)); } }
+                90 => { if let Some(v) = pair.as_i32() { table.value_flags = v; } }
+                91 => { if let Some(v) = pair.as_i32() { row_count = v.max(0) as usize; } }
+                92 => { if let Some(v) = pair.as_i32() { column_count = v.max(0) as usize; } }
+                93 => { if let Some(v) = pair.as_i32() { table.override_flag = v != 0; } }
+                94 => { if let Some(v) = pair.as_i32() { table.override_border_color = v != 0; } }
+                95 => { if let Some(v) = pair.as_i32() { table.override_border_line_weight = v != 0; } }
+                96 => { if let Some(v) = pair.as_i32() { table.override_border_visibility = v != 0; } }
+                141 => { if let Some(v) = pair.as_double() { row_heights.push(v); } }
+                142 => { if let Some(v) = pair.as_double() { column_widths.push(v); } }
                 _ => { self.try_read_common_entity_code(&pair, &mut table.common)?; }
             }
         }
 
         table.insertion_point = insertion_point.get_point().unwrap_or(Vector3::zero());
+        if let Some(direction) = direction.get_point() {
+            table.horizontal_direction = direction;
+        }
+
+        let row_count = row_count.max(row_heights.len());
+        let column_count = column_count.max(column_widths.len());
+        for index in 0..column_count {
+            table.add_column(column_widths.get(index).copied().unwrap_or(2.5));
+        }
+        for index in 0..row_count {
+            let row = table.add_row();
+            if let Some(&height) = row_heights.get(index) {
+                row.height = height;
+            }
+        }
+        if column_count > 0 {
+            for (index, cell) in cells.into_iter().enumerate().take(row_count * column_count) {
+                table.rows[index / column_count].cells[index % column_count] = cell;
+            }
+        }
         Ok(Some(table))
     }
 
@@ -4298,7 +5178,7 @@ impl<'a> SectionReader<'a> {
         while let Some(pair) = self.reader.read_pair()? {
             if pair.code == 0 { self.reader.push_back(pair); break; }
             match pair.code {
-                8 => underlay.common.layer = pair.value_string.clone(),
+                8 => underlay.common.layer = pair.value_string.to_string(),
                 62 => { if let Some(v) = pair.as_i16() { underlay.common.color = Color::from_index(v); } }
                 370 => { if let Some(v) = pair.as_i16() { underlay.common.line_weight = LineWeight::from_value(v); } }
                 10 | 20 | 30 => { insertion_point.add_coordinate(&pair); }
@@ -4306,7 +5186,13 @@ impl<'a> SectionReader<'a> {
                 41 => { if let Some(v) = pair.as_double() { underlay.x_scale = v; } }
                 42 => { if let Some(v) = pair.as_double() { underlay.y_scale = v; } }
                 43 => { if let Some(v) = pair.as_double() { underlay.z_scale = v; } }
-                50 => { if let Some(v) = pair.as_double() { underlay.rotation = v; } }
+                50 => { if let Some(v) = pair.as_double() { underlay.rotation = v.to_radians(); } }
+                280 => {
+                    if let Some(v) = pair.as_i16() {
+                        underlay.flags = UnderlayDisplayFlags::from_bits_truncate(v as u8);
+                        underlay.clip_inverted = underlay.flags.contains(UnderlayDisplayFlags::CLIP_INSIDE);
+                    }
+                }
                 281 => { if let Some(v) = pair.as_i16() { underlay.contrast = v as u8; } }
                 282 => { if let Some(v) = pair.as_i16() { underlay.fade = v as u8; } }
                 340 => {
@@ -4334,25 +5220,31 @@ impl<'a> SectionReader<'a> {
     /// Read an XRECORD object
     fn read_xrecord(&mut self) -> Result<Option<XRecord>> {
         let mut xr = XRecord::new();
+        // Every pair after the subclass marker and cloning flag is data,
+        // 102 and 330 included
+        let mut in_data = false;
+        let mut cloning_read = false;
 
         while let Some(pair) = self.reader.read_pair()? {
             if pair.code == 0 { self.reader.push_back(pair); break; }
             match pair.code {
-                5 => { if let Ok(h) = u64::from_str_radix(&pair.value_string, 16) { xr.handle = Handle::new(h); } }
-                330 => { if let Ok(h) = u64::from_str_radix(&pair.value_string, 16) { xr.owner = Handle::new(h); } }
-                280 => {
+                280 if in_data && !cloning_read && xr.entries.is_empty() => {
+                    cloning_read = true;
                     if let Some(v) = pair.as_i16() {
                         xr.cloning_flags = DictionaryCloningFlags::from_value(v);
                     }
                 }
-                102 => {} // Skip extension dictionaries / reactors groups
-                _ => {
-                    // All other codes are data entries
-                    xr.entries.push(XRecordEntry {
-                        code: pair.code,
-                        value: XRecordValue::String(pair.value_string.clone()),
-                    });
-                }
+                _ if in_data => push_xrecord_value(&mut xr, &pair),
+                5 => { if let Ok(h) = u64::from_str_radix(&pair.value_string, 16) { xr.handle = Handle::new(h); } }
+                330 => { if let Ok(h) = u64::from_str_radix(&pair.value_string, 16) { xr.owner = Handle::new(h); } }
+                100 => in_data = pair.value_string == "AcDbXrecord",
+                102 => match pair.value_string.trim() {
+                    "{ACAD_REACTORS" => xr.reactors = self.read_reactor_handles()?,
+                    "{ACAD_XDICTIONARY" => xr.xdictionary_handle = self.read_xdictionary_handle()?,
+                    group if group.starts_with('{') => self.skip_defined_group()?,
+                    _ => {}
+                },
+                _ => {}
             }
         }
 
@@ -4368,7 +5260,13 @@ impl<'a> SectionReader<'a> {
             match pair.code {
                 5 => { if let Ok(h) = u64::from_str_radix(&pair.value_string, 16) { group.handle = Handle::new(h); } }
                 330 => { if let Ok(h) = u64::from_str_radix(&pair.value_string, 16) { group.owner = Handle::new(h); } }
-                300 => group.description = pair.value_string.clone(),
+                102 => match pair.value_string.trim() {
+                    "{ACAD_REACTORS" => group.reactors = self.read_reactor_handles()?,
+                    "{ACAD_XDICTIONARY" => group.xdictionary_handle = self.read_xdictionary_handle()?,
+                    group_name if group_name.starts_with('{') => self.skip_defined_group()?,
+                    _ => {}
+                },
+                300 => group.description = pair.value_string.to_string(),
                 70 => {} // unnamed flag — skip
                 71 => { if let Some(v) = pair.as_i16() { group.selectable = v != 0; } }
                 340 => {
@@ -4393,8 +5291,8 @@ impl<'a> SectionReader<'a> {
             match pair.code {
                 5 => { if let Ok(h) = u64::from_str_radix(&pair.value_string, 16) { style.handle = Handle::new(h); } }
                 330 => { if let Ok(h) = u64::from_str_radix(&pair.value_string, 16) { style.owner = Handle::new(h); } }
-                2 => style.name = pair.value_string.clone(),
-                3 => style.description = pair.value_string.clone(),
+                2 => style.name = pair.value_string.to_string(),
+                3 => style.description = pair.value_string.to_string(),
                 51 => { if let Some(v) = pair.as_double() { style.start_angle = v; } }
                 52 => { if let Some(v) = pair.as_double() { style.end_angle = v; } }
                 62 => {
@@ -4418,7 +5316,7 @@ impl<'a> SectionReader<'a> {
                 }
                 6 => {
                     if let Some(last) = style.elements.last_mut() {
-                        last.linetype = pair.value_string.clone();
+                        last.linetype = pair.value_string.to_string();
                     }
                 }
                 _ => {}
@@ -4437,12 +5335,15 @@ impl<'a> SectionReader<'a> {
             match pair.code {
                 5 => { if let Ok(h) = u64::from_str_radix(&pair.value_string, 16) { def.handle = Handle::new(h); } }
                 330 => { if let Ok(h) = u64::from_str_radix(&pair.value_string, 16) { def.owner = Handle::new(h); } }
-                1 => def.file_name = pair.value_string.clone(),
+                102 if pair.value_string.trim() == "{ACAD_REACTORS" => def.reactors = self.read_reactor_handles()?,
+                90 => { if let Some(v) = pair.as_i32() { def.class_version = v; } }
+                1 => def.file_name = pair.value_string.to_string(),
                 10 => { if let Some(v) = pair.as_double() { def.size_in_pixels.0 = v as u32; } }
                 20 => { if let Some(v) = pair.as_double() { def.size_in_pixels.1 = v as u32; } }
                 11 => { if let Some(v) = pair.as_double() { def.pixel_size.0 = v; } }
                 21 => { if let Some(v) = pair.as_double() { def.pixel_size.1 = v; } }
                 280 => { if let Some(v) = pair.as_i16() { def.is_loaded = v != 0; } }
+                281 => { if let Some(v) = pair.as_i16() { def.resolution_unit = crate::objects::ResolutionUnit::from_code(v as i32); } }
                 _ => {}
             }
         }
@@ -4452,6 +5353,10 @@ impl<'a> SectionReader<'a> {
 
     /// Read an MLEADERSTYLE object
     fn read_multileader_style(&mut self) -> Result<Option<MultiLeaderStyle>> {
+        use crate::objects::{
+            BlockContentConnectionType, LeaderContentType, LeaderDrawOrderType, MultiLeaderDrawOrderType,
+            MultiLeaderPathType, TextAlignmentType, TextAngleType, TextAttachmentDirectionType, TextAttachmentType,
+        };
         let mut style = MultiLeaderStyle::new("Standard");
 
         while let Some(pair) = self.reader.read_pair()? {
@@ -4459,11 +5364,16 @@ impl<'a> SectionReader<'a> {
             match pair.code {
                 5 => { if let Ok(h) = u64::from_str_radix(&pair.value_string, 16) { style.handle = Handle::new(h); } }
                 330 => { if let Ok(h) = u64::from_str_radix(&pair.value_string, 16) { style.owner_handle = Handle::new(h); } }
-                3 => style.name = pair.value_string.clone(),
-                300 => style.description = pair.value_string.clone(),
-                170 => { if let Some(v) = pair.as_i16() { style.content_type = crate::objects::LeaderContentType::from(v); } }
-                173 => { if let Some(v) = pair.as_i16() { style.path_type = crate::objects::MultiLeaderPathType::from(v); } }
-                91 => { if let Some(v) = pair.as_i32() { style.line_color = Color::from_index(v as i16); } }
+                3 => style.name = pair.value_string.to_string(),
+                300 => style.default_text = pair.value_string.to_string(),
+                170 => { if let Some(v) = pair.as_i16() { style.content_type = LeaderContentType::from(v); } }
+                171 => { if let Some(v) = pair.as_i16() { style.multileader_draw_order = MultiLeaderDrawOrderType::from(v); } }
+                172 => { if let Some(v) = pair.as_i16() { style.leader_draw_order = LeaderDrawOrderType::from(v); } }
+                90 => { if let Some(v) = pair.as_i32() { style.max_leader_points = v; } }
+                40 => { if let Some(v) = pair.as_double() { style.first_segment_angle = v; } }
+                41 => { if let Some(v) = pair.as_double() { style.second_segment_angle = v; } }
+                173 => { if let Some(v) = pair.as_i16() { style.path_type = MultiLeaderPathType::from(v); } }
+                91 => { if let Some(v) = pair.as_i32() { style.line_color = Color::from_cm_value(v); } }
                 92 => { if let Some(v) = pair.as_i32() { style.line_weight = LineWeight::from_value(v as i16); } }
                 290 => { if let Some(v) = pair.as_bool() { style.enable_landing = v; } }
                 291 => { if let Some(v) = pair.as_bool() { style.enable_dogleg = v; } }
@@ -4471,14 +5381,29 @@ impl<'a> SectionReader<'a> {
                 42 => { if let Some(v) = pair.as_double() { style.landing_gap = v; } }
                 44 => { if let Some(v) = pair.as_double() { style.arrowhead_size = v; } }
                 45 => { if let Some(v) = pair.as_double() { style.text_height = v; } }
-                93 => { if let Some(v) = pair.as_i32() { style.text_color = Color::from_index(v as i16); } }
+                46 => { if let Some(v) = pair.as_double() { style.align_space = v; } }
+                93 => { if let Some(v) = pair.as_i32() { style.text_color = Color::from_cm_value(v); } }
                 292 => { if let Some(v) = pair.as_bool() { style.text_frame = v; } }
-                174 => { if let Some(v) = pair.as_i16() { style.text_left_attachment = crate::objects::TextAttachmentType::from(v); } }
-                178 => { if let Some(v) = pair.as_i16() { style.text_right_attachment = crate::objects::TextAttachmentType::from(v); } }
-                175 => { if let Some(v) = pair.as_i16() { style.text_angle_type = crate::objects::TextAngleType::from(v); } }
-                176 => { if let Some(v) = pair.as_i16() { style.text_alignment = crate::objects::TextAlignmentType::from(v); } }
+                297 => { if let Some(v) = pair.as_bool() { style.text_always_left = v; } }
+                174 => { if let Some(v) = pair.as_i16() { style.text_left_attachment = TextAttachmentType::from(v); } }
+                178 => { if let Some(v) = pair.as_i16() { style.text_right_attachment = TextAttachmentType::from(v); } }
+                175 => { if let Some(v) = pair.as_i16() { style.text_angle_type = TextAngleType::from(v); } }
+                176 => { if let Some(v) = pair.as_i16() { style.text_alignment = TextAlignmentType::from(v); } }
+                94 => { if let Some(v) = pair.as_i32() { style.block_content_color = Color::from_cm_value(v); } }
+                47 => { if let Some(v) = pair.as_double() { style.block_content_scale_x = v; } }
+                49 => { if let Some(v) = pair.as_double() { style.block_content_scale_y = v; } }
+                140 => { if let Some(v) = pair.as_double() { style.block_content_scale_z = v; } }
+                293 => { if let Some(v) = pair.as_bool() { style.enable_block_scale = v; } }
+                141 => { if let Some(v) = pair.as_double() { style.block_content_rotation = v; } }
+                294 => { if let Some(v) = pair.as_bool() { style.enable_block_rotation = v; } }
+                177 => { if let Some(v) = pair.as_i16() { style.block_content_connection = BlockContentConnectionType::from(v); } }
                 142 => { if let Some(v) = pair.as_double() { style.scale_factor = v; } }
-                304 => style.default_text = pair.value_string.clone(),
+                295 => { if let Some(v) = pair.as_bool() { style.property_changed = v; } }
+                296 => { if let Some(v) = pair.as_bool() { style.is_annotative = v; } }
+                143 => { if let Some(v) = pair.as_double() { style.break_gap_size = v; } }
+                271 => { if let Some(v) = pair.as_i16() { style.text_attachment_direction = TextAttachmentDirectionType::from(v); } }
+                272 => { if let Some(v) = pair.as_i16() { style.text_bottom_attachment = TextAttachmentType::from(v); } }
+                273 => { if let Some(v) = pair.as_i16() { style.text_top_attachment = TextAttachmentType::from(v); } }
                 340 => { if let Ok(h) = u64::from_str_radix(&pair.value_string, 16) { style.line_type_handle = Some(Handle::new(h)); } }
                 341 => { if let Ok(h) = u64::from_str_radix(&pair.value_string, 16) { style.arrowhead_handle = Some(Handle::new(h)); } }
                 342 => { if let Ok(h) = u64::from_str_radix(&pair.value_string, 16) { style.text_style_handle = Some(Handle::new(h)); } }
@@ -4499,22 +5424,7 @@ impl<'a> SectionReader<'a> {
             match pair.code {
                 5 => { if let Ok(h) = u64::from_str_radix(&pair.value_string, 16) { ps.handle = Handle::new(h); } }
                 330 => { if let Ok(h) = u64::from_str_radix(&pair.value_string, 16) { ps.owner = Handle::new(h); } }
-                1 => ps.page_name = pair.value_string.clone(),
-                2 => ps.printer_name = pair.value_string.clone(),
-                4 => ps.paper_size = pair.value_string.clone(),
-                6 => ps.plot_view_name = pair.value_string.clone(),
-                7 => ps.current_style_sheet = pair.value_string.clone(),
-                40 => { if let Some(v) = pair.as_double() { ps.margins.left = v; } }
-                41 => { if let Some(v) = pair.as_double() { ps.margins.bottom = v; } }
-                42 => { if let Some(v) = pair.as_double() { ps.margins.right = v; } }
-                43 => { if let Some(v) = pair.as_double() { ps.margins.top = v; } }
-                44 => { if let Some(v) = pair.as_double() { ps.paper_width = v; } }
-                45 => { if let Some(v) = pair.as_double() { ps.paper_height = v; } }
-                46 => { if let Some(v) = pair.as_double() { ps.origin_x = v; } }
-                47 => { if let Some(v) = pair.as_double() { ps.origin_y = v; } }
-                142 => { if let Some(v) = pair.as_double() { ps.scale_numerator = v; } }
-                143 => { if let Some(v) = pair.as_double() { ps.scale_denominator = v; } }
-                _ => {}
+                _ => { read_plot_settings_pair(&mut ps, &pair); }
             }
         }
 
@@ -4524,13 +5434,67 @@ impl<'a> SectionReader<'a> {
     /// Read a TABLESTYLE object
     fn read_table_style(&mut self) -> Result<Option<TableStyle>> {
         let mut ts = TableStyle::new("Standard");
+        // Each row's cell style starts at code 7, in data, header, title order
+        let mut row: Option<usize> = None;
+        let mut seen_flow_direction = false;
 
         while let Some(pair) = self.reader.read_pair()? {
             if pair.code == 0 { self.reader.push_back(pair); break; }
+            if pair.code == 7 {
+                row = Some(row.map_or(0, |r| r + 1));
+            }
+            let cell = match row {
+                Some(0) => Some(&mut ts.data_row_style),
+                Some(1) => Some(&mut ts.header_row_style),
+                Some(2) => Some(&mut ts.title_row_style),
+                _ => None,
+            };
+            if let Some(cell) = cell {
+                match pair.code {
+                    7 => cell.text_style_name = pair.value_string.to_string(),
+                    140 => { if let Some(v) = pair.as_double() { cell.text_height = v; } }
+                    170 => { if let Some(v) = pair.as_i16() { cell.alignment = CellAlignment::from(v); } }
+                    62 => { if let Some(v) = pair.as_i16() { cell.text_color = Color::from_index(v); } }
+                    63 => { if let Some(v) = pair.as_i16() { cell.fill_color = Color::from_index(v); } }
+                    283 => { if let Some(v) = pair.as_i16() { cell.fill_enabled = v != 0; } }
+                    90 => { if let Some(v) = pair.as_i32() { cell.data_type = v; } }
+                    91 => { if let Some(v) = pair.as_i32() { cell.unit_type = v; } }
+                    1 => cell.format_string = pair.value_string.to_string(),
+                    274..=279 => {
+                        if let Some(v) = pair.as_i16() {
+                            cell.borders_mut()[(pair.code - 274) as usize].line_weight = LineWeight::from_value(v);
+                        }
+                    }
+                    284..=289 => {
+                        if let Some(v) = pair.as_i16() {
+                            cell.borders_mut()[(pair.code - 284) as usize].is_invisible = v == 0;
+                        }
+                    }
+                    64..=69 => {
+                        if let Some(v) = pair.as_i16() {
+                            cell.borders_mut()[(pair.code - 64) as usize].color = Color::from_index(v);
+                        }
+                    }
+                    _ => {}
+                }
+                continue;
+            }
             match pair.code {
                 5 => { if let Ok(h) = u64::from_str_radix(&pair.value_string, 16) { ts.handle = Handle::new(h); } }
                 330 => { if let Ok(h) = u64::from_str_radix(&pair.value_string, 16) { ts.owner_handle = Handle::new(h); } }
-                3 => ts.name = pair.value_string.clone(),
+                3 => ts.description = pair.value_string.to_string(),
+                // 280 is the version before the flow direction, title suppression after
+                280 => {
+                    if let Some(v) = pair.as_i16() {
+                        if seen_flow_direction { ts.title_suppressed = v != 0; } else { ts.version = v; }
+                    }
+                }
+                281 => { if let Some(v) = pair.as_i16() { ts.header_suppressed = v != 0; } }
+                70 => {
+                    if let Some(v) = pair.as_i16() { ts.flow_direction = TableFlowDirection::from(v); }
+                    seen_flow_direction = true;
+                }
+                71 => { if let Some(v) = pair.as_i16() { ts.flags = TableStyleFlags::from_bits_truncate(v); } }
                 40 => { if let Some(v) = pair.as_double() { ts.horizontal_margin = v; } }
                 41 => { if let Some(v) = pair.as_double() { ts.vertical_margin = v; } }
                 _ => {}
@@ -4549,7 +5513,7 @@ impl<'a> SectionReader<'a> {
             match pair.code {
                 5 => { if let Ok(h) = u64::from_str_radix(&pair.value_string, 16) { scale.handle = Handle::new(h); } }
                 330 => { if let Ok(h) = u64::from_str_radix(&pair.value_string, 16) { scale.owner_handle = Handle::new(h); } }
-                300 => scale.name = pair.value_string.clone(),
+                300 => scale.name = pair.value_string.to_string(),
                 140 => { if let Some(v) = pair.as_double() { scale.paper_units = v; } }
                 141 => { if let Some(v) = pair.as_double() { scale.drawing_units = v; } }
                 290 => { if let Some(v) = pair.as_bool() { scale.is_unit_scale = v; } }
@@ -4604,7 +5568,7 @@ impl<'a> SectionReader<'a> {
                 5 => { if let Ok(h) = u64::from_str_radix(&pair.value_string, 16) { dv.handle = Handle::new(h); } }
                 330 => { if let Ok(h) = u64::from_str_radix(&pair.value_string, 16) { dv.owner_handle = Handle::new(h); } }
                 280 => { if let Some(v) = pair.as_i16() { dv.schema_number = v; } }
-                1 => dv.value = pair.value_string.clone(),
+                1 => dv.value = pair.value_string.to_string(),
                 _ => {}
             }
         }
@@ -4612,3 +5576,165 @@ impl<'a> SectionReader<'a> {
         Ok(Some(dv))
     }
 }
+
+/// Apply a pair of the AcDbPlotSettings data shared by PLOTSETTINGS and
+/// LAYOUT; returns whether the code belongs to it
+fn read_plot_settings_pair(ps: &mut PlotSettings, pair: &DxfCodePair) -> bool {
+    match pair.code {
+        1 => ps.page_name = pair.value_string.to_string(),
+        2 => ps.printer_name = pair.value_string.to_string(),
+        4 => ps.paper_size = pair.value_string.to_string(),
+        6 => ps.plot_view_name = pair.value_string.to_string(),
+        7 => ps.current_style_sheet = pair.value_string.to_string(),
+        40 => { if let Some(v) = pair.as_double() { ps.margins.left = v; } }
+        41 => { if let Some(v) = pair.as_double() { ps.margins.bottom = v; } }
+        42 => { if let Some(v) = pair.as_double() { ps.margins.right = v; } }
+        43 => { if let Some(v) = pair.as_double() { ps.margins.top = v; } }
+        44 => { if let Some(v) = pair.as_double() { ps.paper_width = v; } }
+        45 => { if let Some(v) = pair.as_double() { ps.paper_height = v; } }
+        46 => { if let Some(v) = pair.as_double() { ps.origin_x = v; } }
+        47 => { if let Some(v) = pair.as_double() { ps.origin_y = v; } }
+        48 => { if let Some(v) = pair.as_double() { ps.plot_window.lower_left_x = v; } }
+        49 => { if let Some(v) = pair.as_double() { ps.plot_window.lower_left_y = v; } }
+        140 => { if let Some(v) = pair.as_double() { ps.plot_window.upper_right_x = v; } }
+        141 => { if let Some(v) = pair.as_double() { ps.plot_window.upper_right_y = v; } }
+        142 => { if let Some(v) = pair.as_double() { ps.scale_numerator = v; } }
+        143 => { if let Some(v) = pair.as_double() { ps.scale_denominator = v; } }
+        70 => { if let Some(v) = pair.as_i16() { ps.flags = PlotFlags::from_bits(v as i32); } }
+        72 => { if let Some(v) = pair.as_i16() { ps.paper_units = PlotPaperUnits::from_code(v); } }
+        73 => { if let Some(v) = pair.as_i16() { ps.rotation = PlotRotation::from_code(v); } }
+        74 => { if let Some(v) = pair.as_i16() { ps.plot_type = PlotType::from_code(v); } }
+        75 => { if let Some(v) = pair.as_i16() { ps.scale_type = ScaledType::from_code(v); } }
+        76 => { if let Some(v) = pair.as_i16() { ps.shade_plot_mode = ShadePlotMode::from_code(v); } }
+        77 => { if let Some(v) = pair.as_i16() { ps.shade_plot_resolution = ShadePlotResolutionLevel::from_code(v); } }
+        78 => { if let Some(v) = pair.as_i16() { ps.shade_plot_dpi = v; } }
+        _ => return false,
+    }
+    true
+}
+
+/// Append a data pair of an XRECORD with the value type of its group code;
+/// the Y and Z of a point join the X before them
+fn push_xrecord_value(xrecord: &mut XRecord, pair: &DxfCodePair) {
+    let code = pair.code;
+    if let (20..=39, Some(last)) = (code, xrecord.entries.last_mut()) {
+        if let (XRecordValue::Point3D(_, y, z), Some(v)) = (&mut last.value, pair.as_double()) {
+            if code == last.code + 10 {
+                *y = v;
+                return;
+            }
+            if code == last.code + 20 {
+                *z = v;
+                return;
+            }
+        }
+    }
+    let text = pair.value_string.as_str();
+    let value = match XRecordValueType::from_code(code) {
+        XRecordValueType::Point3D if code < 20 => pair.as_double().map(|x| XRecordValue::Point3D(x, 0.0, 0.0)),
+        XRecordValueType::Point3D | XRecordValueType::Double => pair.as_double().map(XRecordValue::Double),
+        XRecordValueType::Byte => pair.as_i16().map(|v| XRecordValue::Byte(v as u8)),
+        XRecordValueType::Bool => pair.as_i16().map(|v| XRecordValue::Bool(v != 0)),
+        XRecordValueType::Int16 => pair.as_i16().map(XRecordValue::Int16),
+        XRecordValueType::Int32 => pair.as_i32().map(XRecordValue::Int32),
+        XRecordValueType::Int64 => pair.as_int().map(XRecordValue::Int64),
+        XRecordValueType::Handle | XRecordValueType::ObjectId => {
+            u64::from_str_radix(text.trim(), 16).ok().map(|h| XRecordValue::Handle(Handle::new(h)))
+        }
+        XRecordValueType::Chunk => text
+            .trim()
+            .as_bytes()
+            .chunks(2)
+            .map(|digits| std::str::from_utf8(digits).ok().and_then(|d| u8::from_str_radix(d, 16).ok()))
+            .collect::<Option<Vec<u8>>>()
+            .map(XRecordValue::Chunk),
+        XRecordValueType::String | XRecordValueType::Unknown => None,
+    };
+    let value = value.unwrap_or_else(|| XRecordValue::String(text.to_string()));
+    xrecord.entries.push(XRecordEntry { code, value });
+}
+
+/// Unknown entity of type `dxf_name` from its raw pairs, with the common
+/// entity data they carry
+fn unknown_entity(dxf_name: &str, raw_pairs: Vec<(i32, String)>) -> UnknownEntity {
+    let mut entity = UnknownEntity::new(dxf_name);
+    entity.raw_pairs = raw_pairs;
+
+    // Extract common entity data from the top-level (non-group) pairs that
+    // precede the entity's own subclass data
+    let mut group: Option<&str> = None;
+    let mut owner_read = false;
+    for (code, value) in &entity.raw_pairs {
+        let value = value.trim();
+        if *code == 102 {
+            group = value.strip_prefix('{');
+            continue;
+        }
+        match (group, *code) {
+            (None, 100) if value != "AcDbEntity" => break,
+            (None, 5) => {
+                if let Ok(h) = u64::from_str_radix(value, 16) {
+                    entity.common.handle = Handle::new(h);
+                }
+            }
+            (None, 330) if !owner_read => {
+                owner_read = true;
+                if let Ok(h) = u64::from_str_radix(value, 16) {
+                    entity.common.owner_handle = Handle::new(h);
+                }
+            }
+            (None, 8) => entity.common.layer = value.to_string(),
+            (None, 60) => entity.common.invisible = value != "0",
+            (None, 62) => {
+                if let Ok(v) = value.parse::<i16>() {
+                    entity.common.color = Color::from_index(v);
+                }
+            }
+            (None, 420) => {
+                if let Ok(v) = value.parse::<i32>() {
+                    entity.common.color = Color::from_rgb((v >> 16) as u8, (v >> 8) as u8, v as u8);
+                }
+            }
+            (None, 370) => {
+                if let Ok(v) = value.parse::<i16>() {
+                    entity.common.line_weight = LineWeight::from_value(v);
+                }
+            }
+            (Some("ACAD_REACTORS"), 330) => {
+                if let Ok(h) = u64::from_str_radix(value, 16) {
+                    entity.common.reactors.push(Handle::new(h));
+                }
+            }
+            (Some("ACAD_XDICTIONARY"), 360) => {
+                if let Ok(h) = u64::from_str_radix(value, 16) {
+                    entity.common.xdictionary_handle = Some(Handle::new(h));
+                }
+            }
+            _ => {}
+        }
+    }
+    entity
+}
+
+/// Unknown object of type `type_name` from its raw pairs
+fn unknown_object(type_name: &str, raw_pairs: Vec<(i32, String)>) -> ObjectType {
+    let handle = raw_pairs
+        .iter()
+        .find(|(code, _)| *code == 5)
+        .and_then(|(_, v)| u64::from_str_radix(v.trim(), 16).ok())
+        .map(Handle::new)
+        .unwrap_or(Handle::NULL);
+    ObjectType::Unknown { type_name: type_name.to_string(), handle, raw_pairs }
+}
+
+/// Decode a string of hex digit pairs, or `None` if a digit is invalid or
+/// the last pair is incomplete
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| hex.get(i..i + 2).and_then(|digits| u8::from_str_radix(digits, 16).ok()))
+        .collect()
+}
@@ -1,9 +1,153 @@
 //! DXF stream reader trait and common types
 
-use crate::error::Result;
+use std::borrow::Borrow;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::{Deref, Range};
+use std::sync::Arc;
+
+use crate::error::{DxfLocation, Result};
 use crate::io::dxf::{DxfCode, GroupCodeValueType};
+use crate::notification::Notification;
 use crate::types::Vector3;
 
+use super::text_reader::DxfBuffer;
+
+/// The text of a DXF value
+///
+/// Values read from a file held in memory borrow their bytes from it; values
+/// that need unescaping or decoding, and values from streams, own their text.
+/// Use [`to_string`](ToString::to_string) or [`into_string`](Self::into_string)
+/// to keep a value beyond the pair.
+#[derive(Clone)]
+pub struct DxfText(TextRepr);
+
+#[derive(Clone)]
+enum TextRepr {
+    /// A range of a shared buffer that was checked to be UTF-8
+    Shared(Arc<DxfBuffer>, Range<usize>),
+    Owned(String),
+}
+
+impl DxfText {
+    /// Text borrowed from `range` of `buffer`, or `None` if it is not UTF-8
+    pub(crate) fn shared(buffer: Arc<DxfBuffer>, range: Range<usize>) -> Option<Self> {
+        std::str::from_utf8(buffer.get(range.clone())?).ok()?;
+        Some(Self(TextRepr::Shared(buffer, range)))
+    }
+
+    /// The text as a string slice
+    pub fn as_str(&self) -> &str {
+        match &self.0 {
+            // Checked on construction; the buffer is immutable
+            TextRepr::Shared(buffer, range) => std::str::from_utf8(&buffer[range.clone()]).unwrap_or_default(),
+            TextRepr::Owned(text) => text,
+        }
+    }
+
+    /// Convert into an owned string, copying borrowed text
+    pub fn into_string(self) -> String {
+        match self.0 {
+            TextRepr::Shared(..) => self.as_str().to_owned(),
+            TextRepr::Owned(text) => text,
+        }
+    }
+}
+
+impl Default for DxfText {
+    fn default() -> Self {
+        Self(TextRepr::Owned(String::new()))
+    }
+}
+
+impl Deref for DxfText {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for DxfText {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl Borrow<str> for DxfText {
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl fmt::Debug for DxfText {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl fmt::Display for DxfText {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.as_str(), f)
+    }
+}
+
+impl PartialEq for DxfText {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for DxfText {}
+
+impl Hash for DxfText {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state)
+    }
+}
+
+impl PartialEq<str> for DxfText {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for DxfText {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl PartialEq<String> for DxfText {
+    fn eq(&self, other: &String) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<DxfText> for &str {
+    fn eq(&self, other: &DxfText) -> bool {
+        *self == other.as_str()
+    }
+}
+
+impl From<String> for DxfText {
+    fn from(text: String) -> Self {
+        Self(TextRepr::Owned(text))
+    }
+}
+
+impl From<&str> for DxfText {
+    fn from(text: &str) -> Self {
+        Self(TextRepr::Owned(text.to_owned()))
+    }
+}
+
+impl From<DxfText> for String {
+    fn from(text: DxfText) -> Self {
+        text.into_string()
+    }
+}
+
 /// A DXF code/value pair
 #[derive(Debug, Clone)]
 pub struct DxfCodePair {
@@ -14,11 +158,10 @@ pub struct DxfCodePair {
     pub dxf_code: DxfCode,
 
     /// The value type
-    #[allow(dead_code)]
     pub value_type: GroupCodeValueType,
     
     /// String representation of the value
-    pub value_string: String,
+    pub value_string: DxfText,
     
     /// Integer value (if applicable)
     pub value_int: Option<i64>,
@@ -32,9 +175,11 @@ pub struct DxfCodePair {
 
 impl DxfCodePair {
     /// Create a new code/value pair
-    pub fn new(code: i32, value_string: String) -> Self {
+    pub fn new(code: i32, value_string: impl Into<DxfText>) -> Self {
+        let value_string = value_string.into();
         let dxf_code = DxfCode::from_i32(code);
-        let value_type = GroupCodeValueType::from_code(dxf_code);
+        // From the raw code: codes without a DxfCode variant still have a type
+        let value_type = GroupCodeValueType::from_raw_code(code);
         
         // Parse value based on type
         let value_int = match value_type {
@@ -70,13 +215,11 @@ impl DxfCodePair {
     }
     
     /// Get value as string
-    #[allow(dead_code)]
     pub fn as_string(&self) -> &str {
         &self.value_string
     }
 
     /// Get value as integer
-    #[allow(dead_code)]
     pub fn as_int(&self) -> Option<i64> {
         self.value_int
     }
@@ -87,7 +230,6 @@ impl DxfCodePair {
     }
 
     /// Get value as i32
-    #[allow(dead_code)]
     pub fn as_i32(&self) -> Option<i32> {
         self.value_int.and_then(|v| i32::try_from(v).ok())
     }
@@ -103,26 +245,39 @@ impl DxfCodePair {
     }
     
     /// Get value as handle (hex string to u64)
-    #[allow(dead_code)]
     pub fn as_handle(&self) -> Option<u64> {
         u64::from_str_radix(self.value_string.trim(), 16).ok()
     }
 }
 
 /// Trait for reading DXF code/value pairs from a stream
+///
+/// [`DxfTextReader`](super::DxfTextReader) and
+/// [`DxfBinaryReader`](super::DxfBinaryReader) implement it for ASCII and
+/// binary files. Reading pairs directly suits tools that scan a file for a
+/// few values, or parse records of their own types; see also
+/// [`DxfCustomTypes`](crate::io::dxf::DxfCustomTypes).
+///
+/// ```rust,ignore
+/// let mut reader = DxfTextReader::new(BufReader::new(File::open("plan.dxf")?))?;
+/// while let Some(pair) = reader.read_pair()? {
+///     if pair.code == 9 && pair.value_string == "$ACADVER" {
+///         println!("{:?}", reader.read_pair()?.map(|p| p.value_string));
+///         break;
+///     }
+/// }
+/// ```
 pub trait DxfStreamReader {
     /// Read the next code/value pair
     fn read_pair(&mut self) -> Result<Option<DxfCodePair>>;
 
     /// Peek at the next code without consuming it
-    #[allow(dead_code)]
     fn peek_code(&mut self) -> Result<Option<i32>>;
 
     /// Push a pair back to be read again on next read_pair call
     fn push_back(&mut self, pair: DxfCodePair);
 
     /// Reset the reader to the beginning
-    #[allow(dead_code)]
     fn reset(&mut self) -> Result<()>;
 
     /// Set the character encoding for non-UTF8 strings.
@@ -132,6 +287,127 @@ pub trait DxfStreamReader {
     fn set_encoding(&mut self, _encoding: &'static encoding_rs::Encoding) {
         // Default: no-op
     }
+
+    /// Current position in the stream: the line number for ASCII files,
+    /// the byte offset for binary files.
+    fn position(&self) -> u64 {
+        0
+    }
+
+    /// Number of bytes consumed from the stream; used for progress reporting.
+    fn byte_offset(&self) -> u64 {
+        self.position()
+    }
+
+    /// Current location in the stream, including the section and entity
+    /// being read.  Used to annotate errors and notifications.
+    fn location(&self) -> DxfLocation {
+        DxfLocation {
+            position: self.position(),
+            ..Default::default()
+        }
+    }
+
+    /// Enable or disable failsafe mode.
+    ///
+    /// In failsafe mode malformed pairs are skipped by resynchronizing on the
+    /// next `0` group code, and a truncated stream ends the read instead of
+    /// returning an error.  Each recovery is recorded as a notification.
+    fn set_failsafe(&mut self, _failsafe: bool) {
+        // Default: no-op
+    }
+
+    /// Whether failsafe mode is enabled.
+    fn is_failsafe(&self) -> bool {
+        false
+    }
+
+    /// Take the notifications recorded while recovering from malformed input.
+    fn take_notifications(&mut self) -> Vec<Notification> {
+        Vec::new()
+    }
+
+    /// Read the raw code/value lines of the rest of the current section.
+    ///
+    /// The terminating `ENDSEC` is consumed but not returned.  Returns `None`
+    /// without consuming anything when the reader cannot provide raw lines
+    /// (binary files).  Used to tokenize and parse a section in parallel.
+    #[doc(hidden)]
+    #[cfg_attr(not(feature = "parallel"), allow(dead_code))]
+    fn read_section_lines(&mut self) -> Result<Option<SectionLines>> {
+        Ok(None)
+    }
+}
+
+/// Raw lines of a section, as returned by
+/// [`DxfStreamReader::read_section_lines`]
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(not(feature = "parallel"), allow(dead_code))]
+pub struct SectionLines {
+    /// Line number of `lines[0]` in the file
+    pub first_line: u64,
+    /// Alternating code and value lines, trimmed and decoded but not yet
+    /// unescaped
+    pub lines: Vec<String>,
+}
+
+/// Tracks the section and entity being read, for error locations
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ReadContext {
+    section: Option<String>,
+    entity: Option<String>,
+    expect_section_name: bool,
+}
+
+impl ReadContext {
+    /// Update the context from a freshly read pair
+    pub fn observe(&mut self, pair: &DxfCodePair) {
+        if self.expect_section_name {
+            self.expect_section_name = false;
+            if pair.code == 2 {
+                self.section = Some(pair.value_string.to_string());
+                return;
+            }
+        }
+        if pair.code == 0 {
+            match pair.value_string.as_str() {
+                "SECTION" => {
+                    self.expect_section_name = true;
+                    self.section = None;
+                    self.entity = None;
+                }
+                "ENDSEC" | "EOF" => {
+                    self.section = None;
+                    self.entity = None;
+                }
+                name => self.entity = Some(name.to_string()),
+            }
+        }
+    }
+
+    /// Context positioned inside `section`, for readers over a section slice
+    #[cfg_attr(not(feature = "parallel"), allow(dead_code))]
+    pub fn in_section(section: &str) -> Self {
+        Self {
+            section: Some(section.to_string()),
+            ..Default::default()
+        }
+    }
+
+    /// Build a location at `position`
+    pub fn location(&self, position: u64, is_binary: bool) -> DxfLocation {
+        DxfLocation {
+            position,
+            is_binary,
+            section: self.section.clone(),
+            entity: self.entity.clone(),
+        }
+    }
+
+    /// Forget all context (on reset)
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
 }
 
 /// Helper for reading 3D points from consecutive code pairs
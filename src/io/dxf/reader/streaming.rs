@@ -0,0 +1,221 @@
+//! Streaming access to DXF content without building a `CadDocument`
+
+use super::section_reader::SectionReader;
+use super::stream_reader::DxfStreamReader;
+use crate::entities::EntityType;
+use crate::error::Result;
+use crate::io::dxf::DxfCustomTypes;
+use crate::io::EntityFilter;
+use crate::notification::{
+    Notification, NotificationCode, NotificationCollection, NotificationObserver, NotificationType,
+};
+
+/// Where the iterator is in the file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum IterState {
+    /// Looking for the ENTITIES section, skipping everything else
+    Seeking,
+    /// Inside the ENTITIES section
+    Entities,
+    /// ENTITIES section finished, end of file or fatal error
+    Done,
+}
+
+/// Lazy iterator over the entities of a DXF file's ENTITIES section.
+///
+/// Created by [`DxfReader::entities_iter`](super::DxfReader::entities_iter).
+/// Each entity is parsed on demand and handed to the caller; no document,
+/// tables or handle map are built, so memory use stays flat regardless of
+/// file size. All other sections are skipped.
+///
+/// In strict mode the first error ends the iteration. In failsafe mode
+/// errors are recorded in [`Self::notifications`] and the iterator moves on
+/// to the next entity.
+pub struct DxfEntityIter {
+    reader: Box<dyn DxfStreamReader>,
+    failsafe: bool,
+    entity_filter: EntityFilter,
+    custom_types: DxfCustomTypes,
+    state: IterState,
+    notifications: NotificationCollection,
+}
+
+impl DxfEntityIter {
+    pub(super) fn new(
+        reader: Box<dyn DxfStreamReader>,
+        failsafe: bool,
+        entity_filter: EntityFilter,
+        custom_types: DxfCustomTypes,
+        observer: Option<NotificationObserver>,
+    ) -> Self {
+        let mut notifications = NotificationCollection::new();
+        notifications.set_observer(observer);
+        Self {
+            reader,
+            failsafe,
+            entity_filter,
+            custom_types,
+            state: IterState::Seeking,
+            notifications,
+        }
+    }
+
+    /// Notifications recorded so far (unsupported entities, recovered errors).
+    pub fn notifications(&self) -> &NotificationCollection {
+        &self.notifications
+    }
+
+    /// Advance to the start of the ENTITIES section
+    fn seek_entities(&mut self) -> Result<()> {
+        while let Some(pair) = self.reader.read_pair()? {
+            if pair.code != 0 {
+                continue;
+            }
+            match pair.value_string.as_str() {
+                "SECTION" => {
+                    let is_entities = matches!(
+                        self.reader.read_pair()?,
+                        Some(ref name) if name.code == 2 && name.value_string == "ENTITIES"
+                    );
+                    if is_entities {
+                        self.state = IterState::Entities;
+                        return Ok(());
+                    }
+                    while let Some(p) = self.reader.read_pair()? {
+                        if p.code == 0 && p.value_string == "ENDSEC" {
+                            break;
+                        }
+                    }
+                }
+                "EOF" => break,
+                _ => {}
+            }
+        }
+        self.state = IterState::Done;
+        Ok(())
+    }
+
+    /// Read the next entity of the ENTITIES section
+    fn next_entity(&mut self) -> Result<Option<EntityType>> {
+        while let Some(pair) = self.reader.read_pair()? {
+            let mut section_reader = SectionReader::new(&mut self.reader)
+                .with_entity_filter(&self.entity_filter)
+                .with_custom_types(&self.custom_types);
+            if section_reader.is_section_end(&pair, &mut self.notifications)? {
+                break;
+            }
+            if pair.code != 0 {
+                continue;
+            }
+            if let Some(entity) = section_reader.read_entity(&pair.value_string, &mut self.notifications)? {
+                return Ok(Some(entity));
+            }
+        }
+        self.state = IterState::Done;
+        Ok(None)
+    }
+
+    /// Move recovery notifications from the stream reader into the iterator
+    fn collect_reader_notifications(&mut self) {
+        for n in self.reader.take_notifications() {
+            self.notifications.push(n);
+        }
+    }
+}
+
+impl Iterator for DxfEntityIter {
+    type Item = Result<EntityType>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let result = match self.state {
+                IterState::Done => return None,
+                IterState::Seeking => self.seek_entities().map(|_| None),
+                IterState::Entities => self.next_entity(),
+            };
+            self.collect_reader_notifications();
+
+            match result {
+                Ok(Some(entity)) => return Some(Ok(entity)),
+                Ok(None) => continue,
+                Err(e) => {
+                    // I/O failures would repeat forever; only parse errors are skipped
+                    let recoverable = !e.is_io();
+                    let e = e.with_location(self.reader.location());
+                    if self.failsafe && recoverable && self.state == IterState::Entities {
+                        self.notifications.push(
+                            Notification::new(NotificationType::Error, format!("Error reading entity: {}", e))
+                                .with_code(NotificationCode::ObjectFailed)
+                                .with_offset(self.reader.byte_offset()),
+                        );
+                        continue;
+                    }
+                    self.state = IterState::Done;
+                    return Some(Err(e));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{self, Cursor};
+
+    use super::*;
+    use crate::document::CadDocument;
+    use crate::entities::Line;
+    use crate::error::{DxfError, DxfLocation};
+    use crate::io::dxf::{DxfBuffer, DxfCodePair, DxfTextReader, DxfWriter};
+    use crate::types::Vector3;
+
+    /// Reader whose stream breaks for good after `pairs` pairs
+    struct BrokenReader {
+        inner: DxfTextReader<Cursor<DxfBuffer>>,
+        pairs: usize,
+    }
+
+    impl DxfStreamReader for BrokenReader {
+        fn read_pair(&mut self) -> Result<Option<DxfCodePair>> {
+            if self.pairs == 0 {
+                let source = Box::new(DxfError::Io(io::Error::other("connection reset")));
+                return Err(DxfError::DxfRead { location: DxfLocation::default(), source });
+            }
+            self.pairs -= 1;
+            self.inner.read_pair()
+        }
+
+        fn peek_code(&mut self) -> Result<Option<i32>> {
+            self.inner.peek_code()
+        }
+
+        fn push_back(&mut self, pair: DxfCodePair) {
+            self.pairs += 1;
+            self.inner.push_back(pair);
+        }
+
+        fn reset(&mut self) -> Result<()> {
+            self.inner.reset()
+        }
+    }
+
+    #[test]
+    fn test_failsafe_iteration_stops_on_io_error() {
+        let mut doc = CadDocument::new();
+        for i in 0..20 {
+            let end = Vector3::new(i as f64, 1.0, 0.0);
+            doc.add_entity(EntityType::Line(Line::from_points(Vector3::ZERO, end))).unwrap();
+        }
+        let bytes = DxfWriter::new(doc).write_to_vec().unwrap();
+        let entities_start = String::from_utf8_lossy(&bytes).lines().position(|l| l.trim() == "ENTITIES").unwrap();
+
+        // Break the stream a few entities into the ENTITIES section
+        let inner = DxfTextReader::from_buffer(DxfBuffer::Owned(bytes));
+        let reader = BrokenReader { inner, pairs: entities_start / 2 + 60 };
+        let iter = DxfEntityIter::new(Box::new(reader), true, EntityFilter::default(), DxfCustomTypes::new(), None);
+        let results: Vec<_> = iter.take(100).collect();
+        assert!(results.len() < 20, "iteration did not stop: {} results", results.len());
+        assert!(results[..results.len() - 1].iter().all(Result::is_ok));
+        assert!(results.last().unwrap().as_ref().is_err_and(DxfError::is_io));
+    }
+}
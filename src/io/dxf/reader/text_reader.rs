@@ -1,111 +1,311 @@
 //! DXF ASCII text reader
+//!
+//! Lines are parsed where they lie in the buffer of a [`BufRead`]; a line is
+//! only copied when it straddles two refills of a stream's [`BufReader`].
+//! A [`DxfBuffer`] (an owned `Vec<u8>` or a memory-mapped file) is a single
+//! refill that values borrow from: a [`DxfText`] only owns its text when it
+//! had to be unescaped or decoded.
 
-use super::stream_reader::{DxfCodePair, DxfStreamReader};
-use crate::error::{DxfError, Result};
+use super::stream_reader::{DxfCodePair, DxfStreamReader, DxfText, ReadContext, SectionLines};
+use crate::error::{DxfError, DxfLocation, Result};
+use crate::io::dxf::code_page::{decode_unicode_escapes, has_unicode_escape};
+use crate::notification::{Notification, NotificationCode, NotificationType};
 use encoding_rs::Encoding;
-use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::borrow::Cow;
+use std::io::{BufRead, BufReader, Cursor, Read, Seek, SeekFrom};
+use std::ops::Deref;
+use std::sync::Arc;
+
+/// Bytes of a DXF file held in memory
+pub enum DxfBuffer {
+    /// Buffer read into memory
+    Owned(Vec<u8>),
+    /// Read-only memory map of a file
+    #[cfg(feature = "mmap")]
+    Mapped(memmap2::Mmap),
+}
+
+impl Deref for DxfBuffer {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            DxfBuffer::Owned(bytes) => bytes,
+            #[cfg(feature = "mmap")]
+            DxfBuffer::Mapped(map) => map,
+        }
+    }
+}
+
+impl AsRef<[u8]> for DxfBuffer {
+    fn as_ref(&self) -> &[u8] {
+        self
+    }
+}
+
+/// Where a text reader takes its lines from
+enum TextInput<R> {
+    /// A stream, read as it is parsed
+    Stream(BufReader<R>),
+    /// A file held in memory
+    Buffer(SharedCursor),
+}
+
+/// Read position in a buffer shared with the values read from it
+struct SharedCursor {
+    buffer: Arc<DxfBuffer>,
+    position: usize,
+}
+
+impl Read for SharedCursor {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.fill_buf()?.read(buf)?;
+        self.consume(read);
+        Ok(read)
+    }
+}
+
+impl BufRead for SharedCursor {
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        Ok(self.buffer.get(self.position..).unwrap_or_default())
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.position += amt;
+    }
+}
+
+impl<R: Read + Seek> TextInput<R> {
+    fn lines(&mut self) -> &mut dyn BufRead {
+        match self {
+            TextInput::Stream(reader) => reader,
+            TextInput::Buffer(cursor) => cursor,
+        }
+    }
+
+    fn rewind(&mut self) -> Result<()> {
+        match self {
+            TextInput::Stream(reader) => {
+                reader.seek(SeekFrom::Start(0))?;
+            }
+            TextInput::Buffer(cursor) => cursor.position = 0,
+        }
+        Ok(())
+    }
+}
 
 /// DXF ASCII text file reader
 pub struct DxfTextReader<R: Read + Seek> {
-    reader: BufReader<R>,
+    input: TextInput<R>,
+    /// Line being put together when it straddles refills of the stream
+    scratch: Vec<u8>,
     line_number: usize,
+    /// Bytes consumed, for progress reporting.
+    byte_offset: u64,
     peeked_pair: Option<DxfCodePair>,
     /// Non-UTF8 fallback encoding.  `None` means use Latin-1 (byte-to-char).
     encoding: Option<&'static Encoding>,
+    /// Recover from malformed pairs instead of returning an error.
+    failsafe: bool,
+    /// Recovery notifications recorded in failsafe mode.
+    notifications: Vec<Notification>,
+    /// Section and entity being read.
+    context: ReadContext,
 }
 
 impl<R: Read + Seek> DxfTextReader<R> {
     /// Create a new DXF text reader
     pub fn new(reader: BufReader<R>) -> Result<Self> {
-        Ok(Self {
-            reader,
+        Ok(Self::with_input(TextInput::Stream(reader)))
+    }
+
+    fn with_input(input: TextInput<R>) -> Self {
+        Self {
+            input,
+            scratch: Vec::new(),
             line_number: 0,
+            byte_offset: 0,
             peeked_pair: None,
             encoding: None,
-        })
+            failsafe: false,
+            notifications: Vec::new(),
+            context: ReadContext::default(),
+        }
     }
-    
-    /// Read a single line from the stream, handling non-UTF8 bytes gracefully.
-    /// Uses the configured encoding for fallback, or Latin-1 if none set.
-    fn read_line(&mut self) -> Result<Option<String>> {
-        let mut bytes = Vec::new();
-        
-        // Read bytes until newline
-        loop {
-            let mut byte = [0u8; 1];
-            match self.reader.read(&mut byte) {
-                Ok(0) => {
-                    // EOF
-                    if bytes.is_empty() {
-                        return Ok(None);
-                    }
-                    break;
-                }
-                Ok(_) => {
-                    if byte[0] == b'\n' {
-                        break;
-                    }
-                    bytes.push(byte[0]);
-                }
-                Err(e) => return Err(e.into()),
-            }
+
+    /// Advance past the next line and return what `parse` makes of it,
+    /// given the line without its terminator.
+    fn next_line<T>(&mut self, parse: impl FnOnce(&[u8]) -> T) -> Result<Option<T>> {
+        let lines = self.input.lines();
+        let available = lines.fill_buf()?;
+        if available.is_empty() {
+            return Ok(None);
         }
-        
+
+        let parsed = match available.iter().position(|&b| b == b'\n') {
+            Some(len) => {
+                let parsed = parse(&available[..len]);
+                lines.consume(len + 1);
+                self.byte_offset += len as u64 + 1;
+                parsed
+            }
+            None => {
+                self.scratch.clear();
+                let len = lines.read_until(b'\n', &mut self.scratch)?;
+                self.byte_offset += len as u64;
+                parse(self.scratch.strip_suffix(b"\n").unwrap_or(&self.scratch))
+            }
+        };
         self.line_number += 1;
-        
-        // Try UTF-8 first, then use configured encoding or Latin-1 fallback
-        let line = match String::from_utf8(bytes.clone()) {
-            Ok(s) => s,
-            Err(_) => {
-                if let Some(enc) = self.encoding {
-                    let (decoded, _, _) = enc.decode(&bytes);
-                    decoded.into_owned()
-                } else {
-                    // Latin-1 is a 1:1 mapping of bytes 0-255 to Unicode code points
-                    bytes.iter().map(|&b| b as char).collect()
+        Ok(Some(parsed))
+    }
+
+    /// Read a single trimmed line as an owned string
+    fn read_line(&mut self) -> Result<Option<String>> {
+        let encoding = self.encoding;
+        self.next_line(|bytes| line_text(bytes, encoding).into_owned())
+    }
+
+    /// Read a value line, processing special character sequences in strings.
+    ///
+    /// Over a buffer, values that are UTF-8 without escapes are borrowed.
+    fn read_value(&mut self) -> Result<Option<DxfText>> {
+        let encoding = self.encoding;
+        let shared = match &self.input {
+            TextInput::Buffer(cursor) => Some((cursor.buffer.clone(), cursor.position)),
+            TextInput::Stream(_) => None,
+        };
+        self.next_line(|bytes| {
+            let text = line_text(bytes, encoding);
+            let value = process_string_value(&text);
+            if let (Cow::Borrowed(_), Cow::Borrowed(value), Some((buffer, start))) = (&text, &value, shared) {
+                // The line lies in the buffer unless it was copied to scratch
+                if buffer.get(start..).is_some_and(|rest| rest.as_ptr() == bytes.as_ptr()) {
+                    let offset = start + (value.as_ptr() as usize - bytes.as_ptr() as usize);
+                    if let Some(value) = DxfText::shared(buffer, offset..offset + value.len()) {
+                        return value;
+                    }
                 }
             }
-        };
-        
-        // Trim whitespace and newlines (including \r)
-        let trimmed = line.trim().to_string();
-        Ok(Some(trimmed))
+            DxfText::from(value.into_owned())
+        })
     }
-    
+
     /// Read a code/value pair from the stream
     fn read_pair_internal(&mut self) -> Result<Option<DxfCodePair>> {
-        // Read code line
-        let code_line = match self.read_line()? {
-            Some(line) => line,
-            None => return Ok(None),
+        let encoding = self.encoding;
+
+        // Read code line, skipping stray blank lines in failsafe mode
+        let code = loop {
+            let line = self.next_line(|bytes| {
+                let text = line_text(bytes, encoding);
+                text.parse::<i32>().map_err(|_| text.into_owned())
+            })?;
+            match line {
+                Some(Ok(code)) => break code,
+                Some(Err(line)) if self.failsafe && line.is_empty() => {
+                    self.notify(NotificationType::Warning, NotificationCode::MalformedPair, "Skipped blank line".to_string());
+                }
+                Some(Err(code_line)) => {
+                    if self.failsafe {
+                        self.notify(
+                            NotificationType::Error,
+                            NotificationCode::MalformedPair,
+                            format!("Invalid DXF code '{}', resynchronizing", code_line),
+                        );
+                        return self.resync();
+                    }
+                    return Err(DxfError::Parse(format!("Invalid DXF code '{}'", code_line))
+                        .with_location(self.location()));
+                }
+                None => return Ok(None),
+            }
         };
-        
-        // Parse code
-        let code = code_line.trim().parse::<i32>()
-            .map_err(|_| DxfError::Parse(format!("Invalid DXF code at line {}: '{}'", self.line_number, code_line)))?;
-        
-        // Read value line
-        let value_line = match self.read_line()? {
-            Some(line) => line,
-            None => return Err(DxfError::Parse(format!("Unexpected EOF after code {} at line {}", code, self.line_number))),
+
+        let value = match self.read_value()? {
+            Some(value) => value,
+            None if self.failsafe => {
+                self.notify(NotificationType::Error, NotificationCode::UnexpectedEof, format!("Unexpected EOF after code {}", code));
+                return Ok(None);
+            }
+            None => {
+                return Err(DxfError::Parse(format!("Unexpected EOF after code {}", code))
+                    .with_location(self.location()));
+            }
         };
-        
-        // Process special character sequences in strings
-        let value = self.process_string_value(&value_line);
-        
-        Ok(Some(DxfCodePair::new(code, value)))
+
+        let pair = DxfCodePair::new(code, value);
+        self.context.observe(&pair);
+        Ok(Some(pair))
     }
     
-    /// Process special character sequences in DXF strings
-    fn process_string_value(&self, value: &str) -> String {
-        value
-            .replace("^J", "\n")
-            .replace("^M", "\r")
-            .replace("^I", "\t")
-            .replace("^ ", "^")
+    /// Skip lines until the next `0` group code followed by a name.
+    ///
+    /// A `0` line is only accepted as a code when the following line is not
+    /// numeric, so a value of `0` is not mistaken for the start of an entity.
+    fn resync(&mut self) -> Result<Option<DxfCodePair>> {
+        let mut after_zero = false;
+        while let Some(line) = self.read_line()? {
+            if after_zero && !line.is_empty() && line.parse::<f64>().is_err() {
+                let pair = DxfCodePair::new(0, process_string_value(&line).into_owned());
+                self.context.observe(&pair);
+                return Ok(Some(pair));
+            }
+            after_zero = line == "0";
+        }
+        Ok(None)
+    }
+
+    /// Record a recovery notification at the current location
+    fn notify(&mut self, notification_type: NotificationType, code: NotificationCode, message: String) {
+        let message = format!("{} at {}", message, self.location());
+        self.notifications.push(
+            Notification::new(notification_type, message).with_code(code).with_offset(self.byte_offset),
+        );
+    }
+
+}
+
+impl DxfTextReader<Cursor<DxfBuffer>> {
+    /// Create a new DXF text reader over a file held in memory, parsed in
+    /// place
+    pub fn from_buffer(buffer: DxfBuffer) -> Self {
+        Self::with_input(TextInput::Buffer(SharedCursor { buffer: Arc::new(buffer), position: 0 }))
     }
 }
 
+/// Text of a line, trimmed of surrounding whitespace.
+///
+/// UTF-8 lines are borrowed; anything else is decoded with `encoding`, or
+/// Latin-1 if none is set.
+fn line_text<'a>(bytes: &'a [u8], encoding: Option<&'static Encoding>) -> Cow<'a, str> {
+    match std::str::from_utf8(bytes) {
+        Ok(s) => Cow::Borrowed(s.trim()),
+        Err(_) => {
+            let decoded: String = match encoding {
+                Some(enc) => enc.decode(bytes).0.into_owned(),
+                // Latin-1 is a 1:1 mapping of bytes 0-255 to Unicode code points
+                None => bytes.iter().map(|&b| b as char).collect(),
+            };
+            Cow::Owned(decoded.trim().to_string())
+        }
+    }
+}
+
+/// Process special character sequences (`^J`, `^I`, `\U+XXXX`, …) in a DXF
+/// string value.  Values without escapes are returned as-is.
+pub(super) fn process_string_value(value: &str) -> Cow<'_, str> {
+    if !value.contains('^') && !has_unicode_escape(value) {
+        return Cow::Borrowed(value);
+    }
+    let value = value
+        .replace("^J", "\n")
+        .replace("^M", "\r")
+        .replace("^I", "\t")
+        .replace("^ ", "^");
+    Cow::Owned(decode_unicode_escapes(&value))
+}
+
 impl<R: Read + Seek> DxfStreamReader for DxfTextReader<R> {
     fn read_pair(&mut self) -> Result<Option<DxfCodePair>> {
         // If we have a peeked pair, return it
@@ -137,21 +337,91 @@ impl<R: Read + Seek> DxfStreamReader for DxfTextReader<R> {
     }
     
     fn reset(&mut self) -> Result<()> {
-        self.reader.seek(SeekFrom::Start(0))?;
+        self.input.rewind()?;
         self.line_number = 0;
+        self.byte_offset = 0;
         self.peeked_pair = None;
+        self.notifications.clear();
+        self.context.clear();
         Ok(())
     }
 
     fn set_encoding(&mut self, encoding: &'static Encoding) {
         self.encoding = Some(encoding);
     }
+
+    fn position(&self) -> u64 {
+        self.line_number as u64
+    }
+
+    fn byte_offset(&self) -> u64 {
+        self.byte_offset
+    }
+
+    fn location(&self) -> DxfLocation {
+        self.context.location(self.position(), false)
+    }
+
+    fn set_failsafe(&mut self, failsafe: bool) {
+        self.failsafe = failsafe;
+    }
+
+    fn is_failsafe(&self) -> bool {
+        self.failsafe
+    }
+
+    fn take_notifications(&mut self) -> Vec<Notification> {
+        std::mem::take(&mut self.notifications)
+    }
+
+    fn read_section_lines(&mut self) -> Result<Option<SectionLines>> {
+        // A peeked pair is already unescaped; let the sequential reader handle it
+        if self.peeked_pair.is_some() {
+            return Ok(None);
+        }
+
+        let first_line = self.line_number as u64 + 1;
+        let mut lines = Vec::new();
+        while let Some(code) = self.read_line()? {
+            let value = match self.read_line()? {
+                Some(value) => value,
+                None => {
+                    lines.push(code);
+                    break;
+                }
+            };
+            if code == "0" {
+                match value.as_str() {
+                    "ENDSEC" => {
+                        self.context.observe(&DxfCodePair::new(0, value));
+                        break;
+                    }
+                    "SECTION" | "EOF" => {
+                        if !self.failsafe {
+                            return Err(DxfError::Parse(format!("Missing ENDSEC before {}", value))
+                                .with_location(self.location()));
+                        }
+                        self.notify(NotificationType::Warning, NotificationCode::MissingTerminator, format!("Missing ENDSEC before {}", value));
+                        let pair = DxfCodePair::new(0, value);
+                        self.context.observe(&pair);
+                        self.peeked_pair = Some(pair);
+                        break;
+                    }
+                    _ => {}
+                }
+            }
+            lines.push(code);
+            lines.push(value);
+        }
+
+        Ok(Some(SectionLines { first_line, lines }))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::io::Cursor;
+    use std::io::{BufReader, Cursor};
     
     #[test]
     fn test_read_simple_pair() {
@@ -189,6 +459,17 @@ mod tests {
         assert_eq!(pair.as_double(), Some(123.456));
     }
     
+    #[test]
+    fn test_read_lowercase_unicode_escape() {
+        let data = "1\ncaf\\u+00e9\n";
+        let cursor = Cursor::new(data.as_bytes());
+        let buf_reader = BufReader::new(cursor);
+        let mut reader = DxfTextReader::new(buf_reader).unwrap();
+        
+        let pair = reader.read_pair().unwrap().unwrap();
+        assert_eq!(pair.value_string, "café");
+    }
+    
     #[test]
     fn test_peek_code() {
         let data = "0\nSECTION\n2\nHEADER\n";
@@ -217,6 +498,122 @@ mod tests {
         let pair = reader.read_pair().unwrap().unwrap();
         assert_eq!(pair.value_string, "Line1\nLine2\rLine3");
     }
-}
 
+    #[test]
+    fn test_unicode_escapes() {
+        let data = "1\nA\\U+00E9\\U+4E2D\n";
+        let cursor = Cursor::new(data.as_bytes());
+        let buf_reader = BufReader::new(cursor);
+        let mut reader = DxfTextReader::new(buf_reader).unwrap();
+
+        let pair = reader.read_pair().unwrap().unwrap();
+        assert_eq!(pair.value_string, "A\u{E9}\u{4E2D}");
+    }
+
+    #[test]
+    fn test_code_page_encoding() {
+        // "Привет" in Windows-1251
+        let mut data = b"1\n".to_vec();
+        data.extend_from_slice(&[0xCF, 0xF0, 0xE8, 0xE2, 0xE5, 0xF2]);
+        data.push(b'\n');
+        let cursor = Cursor::new(data);
+        let buf_reader = BufReader::new(cursor);
+        let mut reader = DxfTextReader::new(buf_reader).unwrap();
+        reader.set_encoding(encoding_rs::WINDOWS_1251);
+
+        let pair = reader.read_pair().unwrap().unwrap();
+        assert_eq!(pair.value_string, "Привет");
+    }
+
+    #[test]
+    fn test_invalid_code_strict() {
+        let data = "0\nLINE\nabc\n1.0\n";
+        let cursor = Cursor::new(data.as_bytes());
+        let buf_reader = BufReader::new(cursor);
+        let mut reader = DxfTextReader::new(buf_reader).unwrap();
+
+        reader.read_pair().unwrap();
+        let err = reader.read_pair().unwrap_err();
+        let location = err.location().unwrap();
+        assert_eq!(location.position, 3);
+        assert_eq!(location.entity.as_deref(), Some("LINE"));
+    }
 
+    #[test]
+    fn test_failsafe_resync_on_invalid_code() {
+        let data = "0\nLINE\nabc\n1.0\n70\n0\n0\nCIRCLE\n";
+        let cursor = Cursor::new(data.as_bytes());
+        let buf_reader = BufReader::new(cursor);
+        let mut reader = DxfTextReader::new(buf_reader).unwrap();
+        reader.set_failsafe(true);
+
+        assert_eq!(reader.read_pair().unwrap().unwrap().value_string, "LINE");
+        // "70 / 0" is skipped; the "0" value is not mistaken for a code
+        let pair = reader.read_pair().unwrap().unwrap();
+        assert_eq!(pair.code, 0);
+        assert_eq!(pair.value_string, "CIRCLE");
+
+        let notifications = reader.take_notifications();
+        assert_eq!(notifications.len(), 1);
+        assert!(notifications[0].message.contains("line 3"));
+    }
+
+    #[test]
+    fn test_failsafe_blank_lines_and_truncation() {
+        let data = "0\nLINE\n\n8\nWalls\n10\n";
+        let cursor = Cursor::new(data.as_bytes());
+        let buf_reader = BufReader::new(cursor);
+        let mut reader = DxfTextReader::new(buf_reader).unwrap();
+        reader.set_failsafe(true);
+
+        reader.read_pair().unwrap();
+        assert_eq!(reader.read_pair().unwrap().unwrap().value_string, "Walls");
+        assert!(reader.read_pair().unwrap().is_none());
+        assert_eq!(reader.take_notifications().len(), 2);
+    }
+
+    #[test]
+    fn test_lines_across_stream_refills() {
+        let data = "  0\nSECTION\n  2\nHEADER\n  9\n$ACADVER\n  1\nAC1018\n";
+        let mut reader = DxfTextReader::new(BufReader::with_capacity(5, Cursor::new(data.as_bytes()))).unwrap();
+
+        let mut values = Vec::new();
+        while let Some(pair) = reader.read_pair().unwrap() {
+            values.push(pair.value_string);
+        }
+        assert_eq!(values, ["SECTION", "HEADER", "$ACADVER", "AC1018"]);
+        assert_eq!(reader.byte_offset(), data.len() as u64);
+
+        reader.reset().unwrap();
+        assert_eq!(reader.read_pair().unwrap().unwrap().value_string, "SECTION");
+    }
+
+    #[test]
+    fn test_plain_values_are_borrowed() {
+        assert!(matches!(process_string_value("Walls"), Cow::Borrowed("Walls")));
+        assert!(matches!(process_string_value("A^JB"), Cow::Owned(_)));
+
+        let data = b"  8\r\nWalls\r\n  1\r\nA^JB\r\n".to_vec();
+        let bytes = data.as_ptr_range();
+        let mut reader = DxfTextReader::from_buffer(DxfBuffer::Owned(data));
+
+        let plain = reader.read_pair().unwrap().unwrap();
+        assert_eq!(plain.value_string, "Walls");
+        assert!(bytes.contains(&plain.value_string.as_ptr()));
+        let escaped = reader.read_pair().unwrap().unwrap();
+        assert_eq!(escaped.value_string, "A\nB");
+        assert!(!bytes.contains(&escaped.value_string.as_ptr()));
+    }
+
+    #[test]
+    fn test_crlf_and_missing_final_newline() {
+        let mut reader = DxfTextReader::from_buffer(DxfBuffer::Owned(b"0\r\nLINE\r\n  8\r\nWalls".to_vec()));
+
+        assert_eq!(reader.read_pair().unwrap().unwrap().value_string, "LINE");
+        let pair = reader.read_pair().unwrap().unwrap();
+        assert_eq!((pair.code, pair.value_string.as_str()), (8, "Walls"));
+        assert!(reader.read_pair().unwrap().is_none());
+        assert_eq!(reader.position(), 4);
+        assert_eq!(reader.byte_offset(), 19);
+    }
+}
@@ -2,7 +2,10 @@
 
 use std::io::Write;
 use byteorder::{LittleEndian, WriteBytesExt};
+use encoding_rs::Encoding;
 use crate::error::Result;
+use crate::io::dxf::code_page::encode_with_escapes;
+use crate::io::dxf::BinaryDxfDialect;
 use crate::types::Handle;
 use super::stream_writer::DxfStreamWriter;
 
@@ -12,25 +15,44 @@ const BINARY_DXF_SENTINEL: &[u8] = b"AutoCAD Binary DXF\r\n\x1a\x00";
 /// Binary DXF stream writer
 pub struct DxfBinaryWriter<W: Write> {
     writer: W,
+    /// Code page for string values.  `None` means UTF-8.
+    encoding: Option<&'static Encoding>,
+    /// Group code encoding
+    dialect: BinaryDxfDialect,
 }
 
 impl<W: Write> DxfBinaryWriter<W> {
-    /// Create a new binary DXF writer
-    pub fn new(mut writer: W) -> Result<Self> {
+    /// Create a new binary DXF writer using R13+ group codes
+    pub fn new(writer: W) -> Result<Self> {
+        Self::with_dialect(writer, BinaryDxfDialect::Standard)
+    }
+
+    /// Create a new binary DXF writer using the given group code encoding
+    pub fn with_dialect(mut writer: W, dialect: BinaryDxfDialect) -> Result<Self> {
         // Write the binary sentinel at the start
         writer.write_all(BINARY_DXF_SENTINEL)?;
-        Ok(Self { writer })
+        Ok(Self { writer, encoding: None, dialect })
     }
     
-    /// Write a DXF code as 16-bit little-endian
+    /// Write a DXF code in the writer's dialect
     fn write_code(&mut self, code: i32) -> Result<()> {
-        self.writer.write_i16::<LittleEndian>(code as i16)?;
+        match self.dialect {
+            BinaryDxfDialect::Standard => self.writer.write_i16::<LittleEndian>(code as i16)?,
+            BinaryDxfDialect::Legacy if (0..255).contains(&code) => self.writer.write_u8(code as u8)?,
+            BinaryDxfDialect::Legacy => {
+                self.writer.write_u8(255)?;
+                self.writer.write_i16::<LittleEndian>(code as i16)?;
+            }
+        }
         Ok(())
     }
     
     /// Write a null-terminated string
     fn write_null_string(&mut self, value: &str) -> Result<()> {
-        self.writer.write_all(value.as_bytes())?;
+        match self.encoding {
+            Some(enc) => self.writer.write_all(&encode_with_escapes(value, enc))?,
+            None => self.writer.write_all(value.as_bytes())?,
+        }
         self.writer.write_u8(0)?;
         Ok(())
     }
@@ -105,6 +127,10 @@ impl<W: Write> DxfStreamWriter for DxfBinaryWriter<W> {
         self.writer.flush()?;
         Ok(())
     }
+
+    fn set_encoding(&mut self, encoding: &'static Encoding) {
+        self.encoding = Some(encoding);
+    }
 }
 
 #[cfg(test)]
@@ -160,5 +186,17 @@ mod tests {
         assert_eq!(buf[sentinel_len..sentinel_len+2], [62, 0]); // code 62
         assert_eq!(buf[sentinel_len+2..sentinel_len+4], [7, 0]); // value 7
     }
+
+    #[test]
+    fn test_legacy_codes() {
+        let mut buf = Vec::new();
+        {
+            let mut writer = DxfBinaryWriter::with_dialect(&mut buf, BinaryDxfDialect::Legacy).unwrap();
+            writer.write_string(0, "LINE").unwrap();
+            writer.write_i16(1070, 5).unwrap();
+        }
+        let rest = &buf[BINARY_DXF_SENTINEL.len()..];
+        assert_eq!(rest, b"\0LINE\0\xFF\x2E\x04\x05\0");
+    }
 }
 
@@ -4,15 +4,23 @@ mod stream_writer;
 mod text_writer;
 mod binary_writer;
 mod section_writer;
+mod object_tree;
+mod viewports;
+mod streaming;
+mod subclass;
 
 pub use stream_writer::{DxfStreamWriter, DxfStreamWriterExt, value_type_for_code};
 pub use text_writer::DxfTextWriter;
 pub use binary_writer::DxfBinaryWriter;
 pub use section_writer::SectionWriter;
+pub use streaming::DxfStreamingWriter;
+pub use subclass::entity_subclasses;
 
 use crate::document::CadDocument;
 use crate::entities::EntityType;
 use crate::error::Result;
+use crate::io::dxf::{BinaryDxfDialect, DxfCustomTypes};
+use crate::types::DxfVersion;
 use std::fs::File;
 use std::io::{BufWriter, Write};
 use std::path::Path;
@@ -22,6 +30,11 @@ pub struct DxfWriter {
     document: CadDocument,
     /// Whether to write binary DXF format
     pub binary: bool,
+    /// Whether to add the mandatory table entries the document lacks (see
+    /// [`CadDocument::ensure_defaults`]); on by default
+    pub ensure_defaults: bool,
+    /// Writers of unknown entity and object types, by DXF name
+    pub custom_types: DxfCustomTypes,
 }
 
 impl DxfWriter {
@@ -30,6 +43,8 @@ impl DxfWriter {
         Self {
             document,
             binary: false,
+            ensure_defaults: true,
+            custom_types: DxfCustomTypes::default(),
         }
     }
 
@@ -38,6 +53,8 @@ impl DxfWriter {
         Self {
             document,
             binary: true,
+            ensure_defaults: true,
+            custom_types: DxfCustomTypes::default(),
         }
     }
 
@@ -45,6 +62,16 @@ impl DxfWriter {
     pub fn set_binary(&mut self, binary: bool) {
         self.binary = binary;
     }
+
+    /// Set whether to add the mandatory table entries the document lacks
+    pub fn set_ensure_defaults(&mut self, ensure_defaults: bool) {
+        self.ensure_defaults = ensure_defaults;
+    }
+
+    /// Set the writers of unknown entity and object types
+    pub fn set_custom_types(&mut self, custom_types: DxfCustomTypes) {
+        self.custom_types = custom_types;
+    }
     
     /// Write to a file
     pub fn write_to_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
@@ -56,7 +83,8 @@ impl DxfWriter {
     /// Write to any writer
     pub fn write_to_writer<W: Write>(&self, writer: W) -> Result<()> {
         if self.binary {
-            let mut stream_writer = DxfBinaryWriter::new(writer)?;
+            let dialect = BinaryDxfDialect::for_version(self.document.version);
+            let mut stream_writer = DxfBinaryWriter::with_dialect(writer, dialect)?;
             self.write_dxf(&mut stream_writer)?;
             stream_writer.flush()?;
         } else {
@@ -76,18 +104,30 @@ impl DxfWriter {
 
     /// Write DXF content to a stream writer
     fn write_dxf<W: DxfStreamWriter>(&self, writer: &mut W) -> Result<()> {
-        let handle_start = self.document.next_handle();
-        let extra_handles = count_extra_handles(&self.document);
+        let document = self.document.prepared_for_write(self.ensure_defaults)?;
+        let handle_start = document.next_handle();
+        let extra_handles = count_extra_handles(&document);
         let handle_seed = handle_start + extra_handles;
-        let mut section_writer = SectionWriter::new(writer, handle_start, handle_seed);
+
+        // Pre-2007 files store strings in the drawing code page
+        if document.version < DxfVersion::AC1021 {
+            if let Some(enc) = crate::io::dxf::code_page::encoding_from_code_page(&document.header.code_page) {
+                writer.set_encoding(enc);
+            }
+        }
+
+        let mut section_writer = SectionWriter::new(writer, handle_start, handle_seed)
+            .with_version(document.version)
+            .with_custom_types(&self.custom_types);
 
         // Write all sections
-        section_writer.write_header(&self.document)?;
-        section_writer.write_classes(&self.document)?;
-        section_writer.write_tables(&self.document)?;
-        section_writer.write_blocks(&self.document)?;
-        section_writer.write_entities(&self.document)?;
-        section_writer.write_objects(&self.document)?;
+        section_writer.write_header(&document)?;
+        section_writer.write_classes(&document)?;
+        section_writer.write_tables(&document)?;
+        section_writer.write_blocks(&document)?;
+        section_writer.write_entities(&document)?;
+        section_writer.write_objects(&document)?;
+        section_writer.write_thumbnail(&document)?;
 
         // Write EOF
         writer.write_string(0, "EOF")?;
@@ -104,18 +144,27 @@ impl DxfWriter {
 fn count_extra_handles(document: &CadDocument) -> u64 {
     let mut count = 0u64;
 
-    // Root dictionary in OBJECTS
-    count += 1;
+    // Root dictionary and the rest of the mandatory OBJECTS tree
+    count += object_tree::RequiredObjects::handle_count(document);
 
     for entity in document.entities() {
         match entity {
+            EntityType::Insert(insert) if !insert.attributes.is_empty() => {
+                count += insert.attributes.iter().filter(|a| a.common.handle.is_null()).count() as u64;
+                // SEQEND
+                count += 1;
+            }
             EntityType::Polyline(polyline) => {
-                // Vertices + SEQEND all use allocate_handle()
-                count += polyline.vertices.len() as u64 + 1;
+                count += polyline.vertices.iter().filter(|v| v.handle.is_null()).count() as u64;
+                if polyline.seqend_handle.is_none() {
+                    count += 1;
+                }
             }
             EntityType::Polyline2D(polyline) => {
-                // Vertices + SEQEND all use allocate_handle()
-                count += polyline.vertices.len() as u64 + 1;
+                count += polyline.vertices.iter().filter(|v| v.handle.is_null()).count() as u64;
+                if polyline.seqend_handle.is_none() {
+                    count += 1;
+                }
             }
             EntityType::Polyline3D(polyline) => {
                 for vertex in &polyline.vertices {
@@ -123,8 +172,9 @@ fn count_extra_handles(document: &CadDocument) -> u64 {
                         count += 1;
                     }
                 }
-                // SEQEND always written
-                count += 1;
+                if polyline.seqend_handle.is_none() {
+                    count += 1;
+                }
             }
             EntityType::PolyfaceMesh(mesh) => {
                 for vertex in &mesh.vertices {
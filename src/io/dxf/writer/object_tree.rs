@@ -0,0 +1,140 @@
+//! Mandatory OBJECTS section tree
+//!
+//! Readers take the first object of the OBJECTS section as the named object
+//! dictionary and expect it to hold `ACAD_GROUP` and, from R2000 on,
+//! `ACAD_LAYOUT` with a layout for model space and paper space. Documents
+//! built from scratch or read from older files can lack parts of that tree;
+//! [`RequiredObjects`] supplies them without touching the document.
+
+use std::collections::HashMap;
+
+use crate::document::CadDocument;
+use crate::objects::{Dictionary, Layout, ObjectType};
+use crate::types::{DxfVersion, Handle};
+
+/// The root dictionary and whatever the document lacks below it
+pub(super) struct RequiredObjects {
+    /// Named object dictionary, written first
+    pub root: Dictionary,
+    /// Document objects that gained entries, keyed by handle
+    pub replaced: HashMap<Handle, ObjectType>,
+    /// Objects missing from the document
+    pub added: Vec<ObjectType>,
+}
+
+impl RequiredObjects {
+    /// Work out the tree for `document`, taking new handles from `allocate`
+    pub fn new(document: &CadDocument, mut allocate: impl FnMut() -> Handle) -> Self {
+        let mut root = match document.objects.get(&document.header.named_objects_dict_handle) {
+            Some(ObjectType::Dictionary(dict)) => dict.clone(),
+            _ => {
+                let mut dict = Dictionary::new();
+                dict.handle = allocate();
+                dict
+            }
+        };
+        let mut replaced = HashMap::new();
+        let mut added = Vec::new();
+
+        if dictionary(document, root.get("ACAD_GROUP")).is_none() {
+            let groups = child_dictionary(&mut root, "ACAD_GROUP", allocate());
+            added.push(ObjectType::Dictionary(groups));
+        }
+
+        if document.version >= DxfVersion::AC1015 {
+            let existing = dictionary(document, root.get("ACAD_LAYOUT")).cloned();
+            let is_new = existing.is_none();
+            let mut layouts = match existing {
+                Some(dict) => dict,
+                None => child_dictionary(&mut root, "ACAD_LAYOUT", allocate()),
+            };
+
+            let spaces = [
+                (document.block_records.iter().find(|b| b.is_model_space()), "Model"),
+                (document.block_records.iter().find(|b| b.is_paper_space()), "Layout1"),
+            ];
+            let mut changed = false;
+            for (tab_order, (record, name)) in spaces.into_iter().enumerate() {
+                let Some(record) = record else { continue };
+                let present = document.objects.values().any(|object| {
+                    matches!(object, ObjectType::Layout(l) if l.block_record == record.handle)
+                });
+                if present {
+                    continue;
+                }
+                let mut layout = if tab_order == 0 { Layout::model() } else { Layout::new(name) };
+                layout.handle = allocate();
+                layout.owner = layouts.handle;
+                layout.block_record = record.handle;
+                layout.tab_order = tab_order as i32;
+                layouts.entries.retain(|(key, _)| key != name);
+                layouts.add_entry(name, layout.handle);
+                added.push(ObjectType::Layout(layout));
+                changed = true;
+            }
+
+            if is_new {
+                added.push(ObjectType::Dictionary(layouts));
+            } else if changed {
+                replaced.insert(layouts.handle, ObjectType::Dictionary(layouts));
+            }
+        }
+
+        Self { root, replaced, added }
+    }
+
+    /// Number of handles [`Self::new`] allocates for `document`
+    pub fn handle_count(document: &CadDocument) -> u64 {
+        let mut count = 0;
+        Self::new(document, || {
+            count += 1;
+            Handle::new(count)
+        });
+        count
+    }
+}
+
+fn dictionary(document: &CadDocument, handle: Option<Handle>) -> Option<&Dictionary> {
+    match document.objects.get(&handle?) {
+        Some(ObjectType::Dictionary(dict)) => Some(dict),
+        _ => None,
+    }
+}
+
+/// Empty dictionary owned by `root` under `key`, replacing a dangling entry
+fn child_dictionary(root: &mut Dictionary, key: &str, handle: Handle) -> Dictionary {
+    let mut dict = Dictionary::new();
+    dict.handle = handle;
+    dict.owner = root.handle;
+    root.entries.retain(|(k, _)| k != key);
+    root.add_entry(key, handle);
+    dict
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fills_missing_tree() {
+        let mut doc = CadDocument::new();
+        assert_eq!(RequiredObjects::handle_count(&doc), 0);
+
+        doc.objects.clear();
+        let mut next = 0x1000;
+        let required = RequiredObjects::new(&doc, || {
+            next += 1;
+            Handle::new(next)
+        });
+        assert_eq!(RequiredObjects::handle_count(&doc), 5);
+        assert!(required.root.get("ACAD_GROUP").is_some());
+        let layouts = required.added.iter().find_map(|o| match o {
+            ObjectType::Dictionary(d) if Some(d.handle) == required.root.get("ACAD_LAYOUT") => Some(d),
+            _ => None,
+        });
+        let layouts = layouts.expect("layout dictionary");
+        assert_eq!(layouts.owner, required.root.handle);
+        assert!(layouts.get("Model").is_some() && layouts.get("Layout1").is_some());
+        assert!(required.replaced.is_empty());
+    }
+}
@@ -5,17 +5,22 @@
 
 use crate::document::CadDocument;
 use crate::entities::*;
-use crate::error::Result;
+use crate::error::{DxfError, Result};
+use crate::io::dxf::DxfCustomTypes;
 use crate::objects::{
-    Dictionary, DictionaryVariable, DictionaryWithDefault, Group, ImageDefinition,
-    ImageDefinitionReactor, Layout, MLineStyle, Material, MultiLeaderStyle,
-    ObjectType, PlotSettings, RasterVariables, Scale, SortEntitiesTable,
+    Background, BackgroundKind, DimAssoc, Dictionary, DictionaryVariable, DictionaryWithDefault, GeoData, Group,
+    ImageDefinition, ImageDefinitionReactor, Layout, MLineStyle, Material, MultiLeaderStyle,
+    ObjectType, PlotSettings, RasterVariables, RenderSettings, Scale, SortEntitiesTable, Sun,
     TableStyle, VisualStyle, BookColor, WipeoutVariables, XRecord,
 };
 use crate::tables::*;
-use crate::types::{Color, Handle, Vector3};
+use crate::types::{Color, DxfVersion, Handle, Vector3};
 use crate::xdata::{ExtendedData, XDataValue};
+use std::collections::{HashMap, HashSet};
 
+use super::object_tree::RequiredObjects;
+use super::subclass;
+use super::viewports::ViewportTable;
 use super::stream_writer::{DxfStreamWriter, DxfStreamWriterExt};
 
 /// Standard table handles (well-known values used by AutoCAD)
@@ -35,6 +40,18 @@ pub struct SectionWriter<'a, W: DxfStreamWriter> {
     writer: &'a mut W,
     next_handle: u64,
     handle_seed: u64,
+    /// Header variables already written, so unknown ones are not duplicated
+    written_header_variables: HashSet<String>,
+    /// Viewport numbering, worked out on the first section that needs it
+    viewports: Option<ViewportTable>,
+    /// Whether the entities being written belong to a paper space layout
+    paper_space: bool,
+    /// Block record names by handle, for entities naming their block
+    block_names: HashMap<Handle, String>,
+    /// Version being written, for the subclass markers it has
+    version: DxfVersion,
+    /// Writers of unknown entity and object types, by DXF name
+    custom_types: Option<&'a DxfCustomTypes>,
 }
 
 impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
@@ -44,15 +61,53 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
             writer,
             next_handle: handle_start,
             handle_seed,
+            written_header_variables: HashSet::new(),
+            viewports: None,
+            paper_space: false,
+            block_names: HashMap::new(),
+            version: DxfVersion::AC1032,
+            custom_types: None,
         }
     }
 
+    /// Write for `version` instead of the latest one
+    pub fn with_version(mut self, version: DxfVersion) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// Write unknown entities and objects of the types `custom` has a
+    /// writer for with that writer
+    pub fn with_custom_types(mut self, custom: &'a DxfCustomTypes) -> Self {
+        if !custom.is_empty() {
+            self.custom_types = Some(custom);
+        }
+        self
+    }
+
+    /// Next handle that will be allocated
+    pub fn next_handle(&self) -> u64 {
+        self.next_handle
+    }
+
     fn allocate_handle(&mut self) -> Handle {
         let handle = Handle::new(self.next_handle);
         self.next_handle += 1;
         handle
     }
 
+    fn number_viewports(&mut self, document: &CadDocument) {
+        if self.viewports.is_none() {
+            self.viewports = Some(ViewportTable::new(document));
+        }
+    }
+
+    fn name_blocks(&mut self, document: &CadDocument) {
+        if self.block_names.is_empty() {
+            self.block_names = document.block_records.iter().map(|r| (r.handle, r.name.clone())).collect();
+        }
+    }
+
     /// Write the HEADER section
     pub fn write_header(&mut self, document: &CadDocument) -> Result<()> {
         self.writer.write_section_start("HEADER")?;
@@ -218,6 +273,17 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
             w.write_double(10, v.x)?; w.write_double(20, v.y)?; w.write_double(30, v.z)
         })?;
 
+        // === Unrecognized variables preserved from the source file ===
+        for (name, values) in &hdr.unknown_variables {
+            if self.written_header_variables.contains(name) {
+                continue;
+            }
+            self.writer.write_string(9, name)?;
+            for (code, value) in values {
+                self.write_raw_pair(*code, value)?;
+            }
+        }
+
         self.writer.write_section_end()?;
         Ok(())
     }
@@ -227,6 +293,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     where
         F: FnOnce(&mut W) -> Result<()>,
     {
+        self.written_header_variables.insert(name.to_string());
         self.writer.write_string(9, name)?;
         write_value(self.writer)
     }
@@ -284,8 +351,8 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     fn write_vport_entry(&mut self, vport: &VPort, owner: Handle) -> Result<()> {
         self.writer.write_string(0, "VPORT")?;
         self.write_common_table_data(vport.handle(), owner)?;
-        self.writer.write_subclass("AcDbSymbolTableRecord")?;
-        self.writer.write_subclass("AcDbViewportTableRecord")?;
+        self.write_subclass("AcDbSymbolTableRecord")?;
+        self.write_subclass("AcDbViewportTableRecord")?;
         self.writer.write_string(2, vport.name())?;
         self.writer.write_i16(70, 0)?;
 
@@ -341,6 +408,17 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         // Snap isopair
         self.writer.write_i16(78, 0)?;
 
+        // Rendering objects
+        if !vport.background_handle.is_null() {
+            self.writer.write_handle(332, vport.background_handle)?;
+        }
+        if !vport.visual_style_handle.is_null() {
+            self.writer.write_handle(348, vport.visual_style_handle)?;
+        }
+        if !vport.sun_handle.is_null() {
+            self.writer.write_handle(361, vport.sun_handle)?;
+        }
+
         Ok(())
     }
 
@@ -359,8 +437,8 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     fn write_ltype_entry(&mut self, ltype: &LineType, owner: Handle) -> Result<()> {
         self.writer.write_string(0, "LTYPE")?;
         self.write_common_table_data(ltype.handle(), owner)?;
-        self.writer.write_subclass("AcDbSymbolTableRecord")?;
-        self.writer.write_subclass("AcDbLinetypeTableRecord")?;
+        self.write_subclass("AcDbSymbolTableRecord")?;
+        self.write_subclass("AcDbLinetypeTableRecord")?;
         self.writer.write_string(2, ltype.name())?;
         self.writer.write_i16(70, 0)?;
         self.writer.write_string(3, &ltype.description)?;
@@ -381,18 +459,24 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         self.write_table_header("LAYER", document.layers.len(), Handle::new(HANDLE_LAYER_TABLE))?;
 
         for layer in document.layers.iter() {
-            self.write_layer_entry(layer, Handle::new(HANDLE_LAYER_TABLE))?;
+            self.write_layer_entry(layer, Handle::new(HANDLE_LAYER_TABLE), document)?;
         }
 
         self.write_table_end()?;
         Ok(())
     }
 
-    fn write_layer_entry(&mut self, layer: &Layer, owner: Handle) -> Result<()> {
+    fn write_layer_entry(&mut self, layer: &Layer, owner: Handle, document: &CadDocument) -> Result<()> {
         self.writer.write_string(0, "LAYER")?;
         self.write_common_table_data(layer.handle(), owner)?;
-        self.writer.write_subclass("AcDbSymbolTableRecord")?;
-        self.writer.write_subclass("AcDbLayerTableRecord")?;
+        // Only while the dictionary is there to be written with the objects
+        if let Some(xdict) = layer.xdictionary_handle.filter(|h| document.objects.contains_key(h)) {
+            self.writer.write_string(102, "{ACAD_XDICTIONARY")?;
+            self.writer.write_handle(360, xdict)?;
+            self.writer.write_string(102, "}")?;
+        }
+        self.write_subclass("AcDbSymbolTableRecord")?;
+        self.write_subclass("AcDbLayerTableRecord")?;
         self.writer.write_string(2, layer.name())?;
 
         // Flags
@@ -446,8 +530,8 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     fn write_style_entry(&mut self, style: &TextStyle, owner: Handle) -> Result<()> {
         self.writer.write_string(0, "STYLE")?;
         self.write_common_table_data(style.handle(), owner)?;
-        self.writer.write_subclass("AcDbSymbolTableRecord")?;
-        self.writer.write_subclass("AcDbTextStyleTableRecord")?;
+        self.write_subclass("AcDbSymbolTableRecord")?;
+        self.write_subclass("AcDbTextStyleTableRecord")?;
         self.writer.write_string(2, style.name())?;
         self.writer.write_i16(70, 0)?;
         self.writer.write_double(40, style.height)?;
@@ -478,8 +562,8 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     fn write_view_entry(&mut self, view: &View, owner: Handle) -> Result<()> {
         self.writer.write_string(0, "VIEW")?;
         self.write_common_table_data(view.handle(), owner)?;
-        self.writer.write_subclass("AcDbSymbolTableRecord")?;
-        self.writer.write_subclass("AcDbViewTableRecord")?;
+        self.write_subclass("AcDbSymbolTableRecord")?;
+        self.write_subclass("AcDbViewTableRecord")?;
         self.writer.write_string(2, view.name())?;
         self.writer.write_i16(70, 0)?;
         self.writer.write_double(40, view.height)?;
@@ -495,7 +579,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         self.writer.write_double(42, view.lens_length)?;
         self.writer.write_double(43, view.front_clip)?;
         self.writer.write_double(44, view.back_clip)?;
-        self.writer.write_double(50, view.twist_angle)?;
+        self.writer.write_double(50, view.twist_angle.to_degrees())?;
 
         Ok(())
     }
@@ -515,8 +599,8 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     fn write_ucs_entry(&mut self, ucs: &Ucs, owner: Handle) -> Result<()> {
         self.writer.write_string(0, "UCS")?;
         self.write_common_table_data(ucs.handle(), owner)?;
-        self.writer.write_subclass("AcDbSymbolTableRecord")?;
-        self.writer.write_subclass("AcDbUCSTableRecord")?;
+        self.write_subclass("AcDbSymbolTableRecord")?;
+        self.write_subclass("AcDbUCSTableRecord")?;
         self.writer.write_string(2, ucs.name())?;
         self.writer.write_i16(70, 0)?;
         self.writer.write_double(10, ucs.origin.x)?;
@@ -547,8 +631,8 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     fn write_appid_entry(&mut self, appid: &AppId, owner: Handle) -> Result<()> {
         self.writer.write_string(0, "APPID")?;
         self.write_common_table_data(appid.handle(), owner)?;
-        self.writer.write_subclass("AcDbSymbolTableRecord")?;
-        self.writer.write_subclass("AcDbRegAppTableRecord")?;
+        self.write_subclass("AcDbSymbolTableRecord")?;
+        self.write_subclass("AcDbRegAppTableRecord")?;
         self.writer.write_string(2, appid.name())?;
         self.writer.write_i16(70, 0)?;
 
@@ -558,7 +642,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     /// Write DIMSTYLE table
     fn write_dimstyle_table(&mut self, document: &CadDocument) -> Result<()> {
         self.write_table_header("DIMSTYLE", document.dim_styles.len(), Handle::new(HANDLE_DIMSTYLE_TABLE))?;
-        self.writer.write_subclass("AcDbDimStyleTable")?;
+        self.write_subclass("AcDbDimStyleTable")?;
 
         for dimstyle in document.dim_styles.iter() {
             self.write_dimstyle_entry(dimstyle, Handle::new(HANDLE_DIMSTYLE_TABLE))?;
@@ -572,8 +656,8 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         self.writer.write_string(0, "DIMSTYLE")?;
         self.writer.write_handle(105, dimstyle.handle())?;
         self.writer.write_handle(330, owner)?;
-        self.writer.write_subclass("AcDbSymbolTableRecord")?;
-        self.writer.write_subclass("AcDbDimStyleTableRecord")?;
+        self.write_subclass("AcDbSymbolTableRecord")?;
+        self.write_subclass("AcDbDimStyleTableRecord")?;
         self.writer.write_string(2, dimstyle.name())?;
         self.writer.write_i16(70, 0)?;
 
@@ -673,18 +757,24 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         self.write_table_header("BLOCK_RECORD", document.block_records.len(), Handle::new(HANDLE_BLOCK_RECORD_TABLE))?;
 
         for block_record in document.block_records.iter() {
-            self.write_block_record_entry(block_record, Handle::new(HANDLE_BLOCK_RECORD_TABLE))?;
+            self.write_block_record_entry(block_record, Handle::new(HANDLE_BLOCK_RECORD_TABLE), document)?;
         }
 
         self.write_table_end()?;
         Ok(())
     }
 
-    fn write_block_record_entry(&mut self, block_record: &BlockRecord, owner: Handle) -> Result<()> {
+    fn write_block_record_entry(&mut self, block_record: &BlockRecord, owner: Handle, document: &CadDocument) -> Result<()> {
         self.writer.write_string(0, "BLOCK_RECORD")?;
         self.write_common_table_data(block_record.handle(), owner)?;
-        self.writer.write_subclass("AcDbSymbolTableRecord")?;
-        self.writer.write_subclass("AcDbBlockTableRecord")?;
+        // Only while the dictionary is there to be written with the objects
+        if let Some(xdict) = block_record.xdictionary_handle.filter(|h| document.objects.contains_key(h)) {
+            self.writer.write_string(102, "{ACAD_XDICTIONARY")?;
+            self.writer.write_handle(360, xdict)?;
+            self.writer.write_string(102, "}")?;
+        }
+        self.write_subclass("AcDbSymbolTableRecord")?;
+        self.write_subclass("AcDbBlockTableRecord")?;
         self.writer.write_string(2, block_record.name())?;
         self.writer.write_i16(70, block_record.units)?;
         self.writer
@@ -693,6 +783,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
             281,
             if block_record.scale_uniformly { 1 } else { 0 },
         )?;
+        self.write_xdata(&block_record.extended_data)?;
 
         Ok(())
     }
@@ -703,7 +794,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         self.writer.write_string(2, name)?;
         self.writer.write_handle(5, table_handle)?;
         self.writer.write_handle(330, Handle::new(0))?; // Tables owned by document root (handle 0)
-        self.writer.write_subclass("AcDbSymbolTable")?;
+        self.write_subclass("AcDbSymbolTable")?;
         self.writer.write_i16(70, count as i16)?;
         Ok(())
     }
@@ -723,9 +814,11 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     /// Write the BLOCKS section
     pub fn write_blocks(&mut self, document: &CadDocument) -> Result<()> {
         self.writer.write_section_start("BLOCKS")?;
+        self.number_viewports(document);
+        self.name_blocks(document);
 
         for block_record in document.block_records.iter() {
-            self.write_block_definition(block_record)?;
+            self.write_block_definition(block_record, document)?;
         }
 
         self.writer.write_section_end()?;
@@ -733,27 +826,37 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     }
 
     /// Write a complete block definition (BLOCK...entities...ENDBLK)
-    fn write_block_definition(&mut self, block_record: &BlockRecord) -> Result<()> {
+    fn write_block_definition(&mut self, block_record: &BlockRecord, document: &CadDocument) -> Result<()> {
         let owner = block_record.handle();
         
         // Determine block flags
-        let flags: i16 = if block_record.is_model_space() { 
+        let mut flags: i16 = if block_record.is_model_space() { 
             2 // Model space flag
         } else { 
             0 
         };
+        for (set, bit) in [
+            (block_record.flags.anonymous, 1),
+            (block_record.flags.has_attributes, 2),
+            (block_record.flags.is_xref, 4),
+            (block_record.flags.is_xref_overlay, 8),
+        ] {
+            if set {
+                flags |= bit;
+            }
+        }
         
         // Write BLOCK entity
         self.writer.write_string(0, "BLOCK")?;
         self.writer.write_handle(5, block_record.block_entity_handle)?;
         self.writer.write_handle(330, owner)?;
-        self.writer.write_subclass("AcDbEntity")?;
+        self.write_subclass("AcDbEntity")?;
         // Paper space flag (group code 67) - 1 for paper space
         if block_record.is_paper_space() {
             self.writer.write_i16(67, 1)?;
         }
         self.writer.write_string(8, "0")?;
-        self.writer.write_subclass("AcDbBlockBegin")?;
+        self.write_subclass("AcDbBlockBegin")?;
         self.writer.write_string(2, block_record.name())?;
         self.writer.write_i16(70, flags)?;
         self.writer.write_double(10, 0.0)?;
@@ -763,24 +866,32 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         // Group code 1 is XRef path (empty for normal blocks)
         self.writer.write_string(1, "")?;
 
-        // Write entities in the block (only for non-model/paper space blocks)
-        if !block_record.is_model_space() && !block_record.is_paper_space() {
+        // Model space and the active layout go to ENTITIES; other layouts
+        // keep theirs here along with the document entities they own
+        if !block_record.is_model_space() && block_record.name() != "*Paper_Space" {
+            self.paper_space = block_record.is_paper_space();
             for entity in &block_record.entities {
                 self.write_entity_with_owner(entity, owner)?;
             }
+            if self.paper_space {
+                for entity in document.sorted_entities().filter(|e| e.common().owner_handle == owner) {
+                    self.write_entity_with_owner(entity, owner)?;
+                }
+            }
+            self.paper_space = false;
         }
 
         // Write ENDBLK entity
         self.writer.write_string(0, "ENDBLK")?;
         self.writer.write_handle(5, block_record.block_end_handle)?;
         self.writer.write_handle(330, owner)?;
-        self.writer.write_subclass("AcDbEntity")?;
+        self.write_subclass("AcDbEntity")?;
         // Paper space flag for ENDBLK too
         if block_record.is_paper_space() {
             self.writer.write_i16(67, 1)?;
         }
         self.writer.write_string(8, "0")?;
-        self.writer.write_subclass("AcDbBlockEnd")?;
+        self.write_subclass("AcDbBlockEnd")?;
 
         Ok(())
     }
@@ -788,6 +899,8 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     /// Write the ENTITIES section
     pub fn write_entities(&mut self, document: &CadDocument) -> Result<()> {
         self.writer.write_section_start("ENTITIES")?;
+        self.number_viewports(document);
+        self.name_blocks(document);
 
         // Write entities from model space block record
         if let Some(model_space) = document.block_records.get("*Model_Space") {
@@ -797,12 +910,32 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
             }
         }
 
-        // Also write standalone entities (owned by model space by default)
+        // Also write standalone entities (owned by model space by default);
+        // those of paper space layouts are written with their layout
         let model_space_handle = document.block_records.get("*Model_Space")
             .map(|b| b.handle())
             .unwrap_or(Handle::new(0x1F));
-        for entity in document.entities() {
-            self.write_entity_with_owner(entity, model_space_handle)?;
+        let layouts: HashSet<Handle> = document.block_records.iter()
+            .filter(|b| b.is_paper_space())
+            .map(|b| b.handle())
+            .collect();
+        for entity in document.sorted_entities() {
+            if !layouts.contains(&entity.common().owner_handle) {
+                self.write_entity_with_owner(entity, model_space_handle)?;
+            }
+        }
+
+        // Entities of the active layout
+        if let Some(paper_space) = document.block_records.get("*Paper_Space") {
+            let owner = paper_space.handle();
+            self.paper_space = true;
+            for entity in &paper_space.entities {
+                self.write_entity_with_owner(entity, owner)?;
+            }
+            for entity in document.sorted_entities().filter(|e| e.common().owner_handle == owner) {
+                self.write_entity_with_owner(entity, owner)?;
+            }
+            self.paper_space = false;
         }
 
         self.writer.write_section_end()?;
@@ -810,7 +943,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     }
 
     /// Write an entity with explicit owner
-    fn write_entity_with_owner(&mut self, entity: &EntityType, owner: Handle) -> Result<()> {
+    pub fn write_entity_with_owner(&mut self, entity: &EntityType, owner: Handle) -> Result<()> {
         match entity {
             EntityType::Point(e) => self.write_point(e, owner),
             EntityType::Line(e) => self.write_line(e, owner),
@@ -853,34 +986,33 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
             EntityType::Seqend(e) => self.write_seqend(e, owner),
             EntityType::Ole2Frame(e) => self.write_ole2frame(e, owner),
             EntityType::PolygonMesh(e) => self.write_polygon_mesh(e, owner),
-            EntityType::Unknown(_) => Ok(()), // Unknown entities are never written back
+            EntityType::Unknown(e) => match self.custom_types.and_then(|custom| custom.entity_writer(&e.dxf_name)) {
+                Some(write) => {
+                    self.writer.write_entity_type(&e.dxf_name)?;
+                    write(entity, owner, &mut *self.writer)
+                }
+                None => self.write_unknown_entity(e, owner),
+            },
         }
     }
 
     /// Write common entity data with owner
+    /// Write a subclass marker, unless the version being written has none
+    fn write_subclass(&mut self, marker: &str) -> Result<()> {
+        if subclass::is_written(marker, self.version) {
+            self.writer.write_subclass(marker)?;
+        }
+        Ok(())
+    }
+
     fn write_common_entity_data(&mut self, common: &EntityCommon, owner: Handle) -> Result<()> {
         self.writer.write_handle(5, common.handle)?;
+        self.write_persistent_groups(&common.reactors, common.xdictionary_handle)?;
         self.writer.write_handle(330, owner)?;
-
-        // Write xdictionary group
-        if let Some(xdict) = common.xdictionary_handle {
-            if xdict != Handle::NULL {
-                self.writer.write_string(102, "{ACAD_XDICTIONARY")?;
-                self.writer.write_handle(360, xdict)?;
-                self.writer.write_string(102, "}")?;
-            }
-        }
-
-        // Write reactor group
-        if !common.reactors.is_empty() {
-            self.writer.write_string(102, "{ACAD_REACTORS")?;
-            for reactor in &common.reactors {
-                self.writer.write_handle(330, *reactor)?;
-            }
-            self.writer.write_string(102, "}")?;
+        self.write_subclass("AcDbEntity")?;
+        if self.paper_space {
+            self.writer.write_i16(67, 1)?;
         }
-
-        self.writer.write_subclass("AcDbEntity")?;
         self.writer.write_string(8, &common.layer)?;
 
         // Write color only if not ByLayer (default)
@@ -901,11 +1033,29 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         Ok(())
     }
 
+    /// Write the {ACAD_REACTORS} and {ACAD_XDICTIONARY} groups that follow
+    /// the handle of an entity or object
+    fn write_persistent_groups(&mut self, reactors: &[Handle], xdictionary: Option<Handle>) -> Result<()> {
+        if !reactors.is_empty() {
+            self.writer.write_string(102, "{ACAD_REACTORS")?;
+            for reactor in reactors {
+                self.writer.write_handle(330, *reactor)?;
+            }
+            self.writer.write_string(102, "}")?;
+        }
+        if let Some(xdict) = xdictionary.filter(|h| !h.is_null()) {
+            self.writer.write_string(102, "{ACAD_XDICTIONARY")?;
+            self.writer.write_handle(360, xdict)?;
+            self.writer.write_string(102, "}")?;
+        }
+        Ok(())
+    }
+
     /// Write POINT entity
     fn write_point(&mut self, point: &Point, owner: Handle) -> Result<()> {
         self.writer.write_entity_type("POINT")?;
         self.write_common_entity_data(&point.common, owner)?;
-        self.writer.write_subclass("AcDbPoint")?;
+        self.write_subclass("AcDbPoint")?;
         self.writer.write_point3d(10, point.location)?;
         if point.thickness != 0.0 {
             self.writer.write_double(39, point.thickness)?;
@@ -917,7 +1067,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     fn write_line(&mut self, line: &Line, owner: Handle) -> Result<()> {
         self.writer.write_entity_type("LINE")?;
         self.write_common_entity_data(&line.common, owner)?;
-        self.writer.write_subclass("AcDbLine")?;
+        self.write_subclass("AcDbLine")?;
         self.writer.write_point3d(10, line.start)?;
         self.writer.write_point3d(11, line.end)?;
         if line.thickness != 0.0 {
@@ -930,7 +1080,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     fn write_circle(&mut self, circle: &Circle, owner: Handle) -> Result<()> {
         self.writer.write_entity_type("CIRCLE")?;
         self.write_common_entity_data(&circle.common, owner)?;
-        self.writer.write_subclass("AcDbCircle")?;
+        self.write_subclass("AcDbCircle")?;
         self.writer.write_point3d(10, circle.center)?;
         self.writer.write_double(40, circle.radius)?;
         if circle.thickness != 0.0 {
@@ -943,13 +1093,13 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     fn write_arc(&mut self, arc: &Arc, owner: Handle) -> Result<()> {
         self.writer.write_entity_type("ARC")?;
         self.write_common_entity_data(&arc.common, owner)?;
-        self.writer.write_subclass("AcDbCircle")?;
+        self.write_subclass("AcDbCircle")?;
         self.writer.write_point3d(10, arc.center)?;
         self.writer.write_double(40, arc.radius)?;
         if arc.thickness != 0.0 {
             self.writer.write_double(39, arc.thickness)?;
         }
-        self.writer.write_subclass("AcDbArc")?;
+        self.write_subclass("AcDbArc")?;
         self.writer.write_double(50, arc.start_angle.to_degrees())?;
         self.writer.write_double(51, arc.end_angle.to_degrees())?;
         Ok(())
@@ -959,7 +1109,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     fn write_ellipse(&mut self, ellipse: &Ellipse, owner: Handle) -> Result<()> {
         self.writer.write_entity_type("ELLIPSE")?;
         self.write_common_entity_data(&ellipse.common, owner)?;
-        self.writer.write_subclass("AcDbEllipse")?;
+        self.write_subclass("AcDbEllipse")?;
         self.writer.write_point3d(10, ellipse.center)?;
         self.writer.write_point3d(11, ellipse.major_axis)?;
         self.writer.write_double(40, ellipse.minor_axis_ratio)?;
@@ -972,7 +1122,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     fn write_polyline(&mut self, polyline: &Polyline, owner: Handle) -> Result<()> {
         self.writer.write_entity_type("POLYLINE")?;
         self.write_common_entity_data(&polyline.common, owner)?;
-        self.writer.write_subclass("AcDb3dPolyline")?;
+        self.write_subclass("AcDb3dPolyline")?;
 
         // Entities follow flag (VERTEX records follow)
         self.writer.write_i16(66, 1)?;
@@ -982,40 +1132,42 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         self.writer.write_double(20, 0.0)?;
         self.writer.write_double(30, 0.0)?;
 
-        let mut flags: i16 = 8; // 3D polyline flag
-        if polyline.is_closed() {
-            flags |= 1;
-        }
+        // 3D polyline flag; mesh flags don't apply to these vertices
+        let flags = (polyline.flags.bits() as i16 & !(16 | 64)) | 8;
         self.writer.write_i16(70, flags)?;
 
         // VERTEX and SEQEND are owned by the polyline entity
         let polyline_handle = polyline.common.handle;
 
-        // Write vertices (allocate unique handles to avoid collisions)
+        // Write vertices (allocate unique handles for new ones)
         for vertex in polyline.vertices.iter() {
-            let vertex_handle = self.allocate_handle();
+            let vertex_handle = if vertex.handle.is_null() {
+                self.allocate_handle()
+            } else {
+                vertex.handle
+            };
             self.writer.write_entity_type("VERTEX")?;
             self.writer.write_handle(5, vertex_handle)?;
             self.writer.write_handle(330, polyline_handle)?;
-            self.writer.write_subclass("AcDbEntity")?;
+            self.write_subclass("AcDbEntity")?;
             self.writer.write_string(8, &polyline.common.layer)?;
             // Propagate parent color to vertex so CAD doesn't flag mismatch
             if polyline.common.color != Color::ByLayer {
                 self.writer.write_color(62, polyline.common.color)?;
             }
-            self.writer.write_subclass("AcDbVertex")?;
-            self.writer.write_subclass("AcDb3dPolylineVertex")?;
+            self.write_subclass("AcDbVertex")?;
+            self.write_subclass("AcDb3dPolylineVertex")?;
             self.writer.write_point3d(10, vertex.location)?;
-            self.writer.write_i16(70, 32)?; // 3D polyline vertex
+            // 3D polyline vertex
+            self.writer.write_i16(70, (vertex.flags.bits() | 32) as i16)?;
         }
 
         // Write SEQEND
-        let seqend_handle = self.allocate_handle();
+        let seqend_handle = polyline.seqend_handle.unwrap_or_else(|| self.allocate_handle());
         self.writer.write_entity_type("SEQEND")?;
         self.writer.write_handle(5, seqend_handle)?;
         self.writer.write_handle(330, polyline_handle)?;
-        self.writer.write_subclass("AcDbEntity")?;
-        self.writer.write_subclass("AcDbSequenceEnd")?;
+        self.write_subclass("AcDbEntity")?;
         self.writer.write_string(8, &polyline.common.layer)?;
 
         Ok(())
@@ -1025,7 +1177,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     fn write_polyline2d(&mut self, polyline: &Polyline2D, owner: Handle) -> Result<()> {
         self.writer.write_entity_type("POLYLINE")?;
         self.write_common_entity_data(&polyline.common, owner)?;
-        self.writer.write_subclass("AcDb2dPolyline")?;
+        self.write_subclass("AcDb2dPolyline")?;
 
         // Entities follow flag (VERTEX records follow)
         self.writer.write_i16(66, 1)?;
@@ -1046,24 +1198,34 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         if polyline.end_width != 0.0 {
             self.writer.write_double(41, polyline.end_width)?;
         }
+        if polyline.smooth_surface != SmoothSurfaceType::None {
+            self.writer.write_i16(75, polyline.smooth_surface as i16)?;
+        }
+        if polyline.normal != Vector3::UNIT_Z {
+            self.writer.write_point3d(210, polyline.normal)?;
+        }
 
         // VERTEX and SEQEND are owned by the polyline entity
         let polyline_handle = polyline.common.handle;
 
-        // Write vertices (allocate unique handles to avoid collisions)
+        // Write vertices (allocate unique handles for new ones)
         for vertex in polyline.vertices.iter() {
-            let vertex_handle = self.allocate_handle();
+            let vertex_handle = if vertex.handle.is_null() {
+                self.allocate_handle()
+            } else {
+                vertex.handle
+            };
             self.writer.write_entity_type("VERTEX")?;
             self.writer.write_handle(5, vertex_handle)?;
             self.writer.write_handle(330, polyline_handle)?;
-            self.writer.write_subclass("AcDbEntity")?;
+            self.write_subclass("AcDbEntity")?;
             self.writer.write_string(8, &polyline.common.layer)?;
             // Propagate parent color to vertex so CAD doesn't flag mismatch
             if polyline.common.color != Color::ByLayer {
                 self.writer.write_color(62, polyline.common.color)?;
             }
-            self.writer.write_subclass("AcDbVertex")?;
-            self.writer.write_subclass("AcDb2dVertex")?;
+            self.write_subclass("AcDbVertex")?;
+            self.write_subclass("AcDb2dVertex")?;
             self.writer.write_point3d(10, vertex.location)?;
             if vertex.start_width != 0.0 {
                 self.writer.write_double(40, vertex.start_width)?;
@@ -1075,15 +1237,20 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
                 self.writer.write_double(42, vertex.bulge)?;
             }
             self.writer.write_i16(70, vertex.flags.bits() as i16)?;
+            if vertex.flags.bits() & VertexFlags::CURVE_FIT_TANGENT.bits() != 0 {
+                self.writer.write_double(50, vertex.curve_tangent.to_degrees())?;
+            }
+            if vertex.id != 0 {
+                self.writer.write_i32(91, vertex.id)?;
+            }
         }
 
         // Write SEQEND
-        let seqend_handle = self.allocate_handle();
+        let seqend_handle = polyline.seqend_handle.unwrap_or_else(|| self.allocate_handle());
         self.writer.write_entity_type("SEQEND")?;
         self.writer.write_handle(5, seqend_handle)?;
         self.writer.write_handle(330, polyline_handle)?;
-        self.writer.write_subclass("AcDbEntity")?;
-        self.writer.write_subclass("AcDbSequenceEnd")?;
+        self.write_subclass("AcDbEntity")?;
         self.writer.write_string(8, &polyline.common.layer)?;
 
         Ok(())
@@ -1093,7 +1260,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     fn write_lwpolyline(&mut self, lwpoly: &LwPolyline, owner: Handle) -> Result<()> {
         self.writer.write_entity_type("LWPOLYLINE")?;
         self.write_common_entity_data(&lwpoly.common, owner)?;
-        self.writer.write_subclass("AcDbPolyline")?;
+        self.write_subclass("AcDbPolyline")?;
         self.writer.write_i32(90, lwpoly.vertices.len() as i32)?;
 
         let mut flags: i16 = 0;
@@ -1106,6 +1273,9 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         if lwpoly.thickness != 0.0 {
             self.writer.write_double(39, lwpoly.thickness)?;
         }
+        if lwpoly.normal != Vector3::UNIT_Z {
+            self.writer.write_point3d(210, lwpoly.normal)?;
+        }
 
         for vertex in &lwpoly.vertices {
             self.writer.write_double(10, vertex.location.x)?;
@@ -1123,7 +1293,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     fn write_text(&mut self, text: &Text, owner: Handle) -> Result<()> {
         self.writer.write_entity_type("TEXT")?;
         self.write_common_entity_data(&text.common, owner)?;
-        self.writer.write_subclass("AcDbText")?;
+        self.write_subclass("AcDbText")?;
         self.writer.write_point3d(10, text.insertion_point)?;
         self.writer.write_double(40, text.height)?;
         self.writer.write_string(1, &text.value)?;
@@ -1134,15 +1304,18 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
             self.writer.write_double(41, text.width_factor)?;
         }
         if text.oblique_angle != 0.0 {
-            self.writer.write_double(51, text.oblique_angle)?;
+            self.writer.write_double(51, text.oblique_angle.to_degrees())?;
         }
         self.writer.write_string(7, &text.style)?;
-        self.writer.write_i16(72, text.horizontal_alignment as i16)?;
+        self.writer.write_i16(72, text.horizontal_alignment.to_value())?;
         if let Some(align_pt) = text.alignment_point {
             self.writer.write_point3d(11, align_pt)?;
         }
-        self.writer.write_subclass("AcDbText")?;
-        self.writer.write_i16(73, text.vertical_alignment as i16)?;
+        if text.normal != Vector3::UNIT_Z {
+            self.writer.write_point3d(210, text.normal)?;
+        }
+        self.write_subclass("AcDbText")?;
+        self.writer.write_i16(73, text.vertical_alignment.to_value())?;
         Ok(())
     }
 
@@ -1150,7 +1323,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     fn write_mtext(&mut self, mtext: &MText, owner: Handle) -> Result<()> {
         self.writer.write_entity_type("MTEXT")?;
         self.write_common_entity_data(&mtext.common, owner)?;
-        self.writer.write_subclass("AcDbMText")?;
+        self.write_subclass("AcDbMText")?;
         self.writer.write_point3d(10, mtext.insertion_point)?;
         self.writer.write_double(40, mtext.height)?;
         self.writer.write_double(41, mtext.rectangle_width)?;
@@ -1177,6 +1350,31 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
             self.writer.write_double(50, mtext.rotation.to_degrees())?;
         }
         self.writer.write_double(44, mtext.line_spacing_factor)?;
+
+        if !mtext.background_fill.is_empty() {
+            self.writer.write_i32(90, mtext.background_fill.bits())?;
+            match mtext.background_color {
+                Color::Rgb { r, g, b } => {
+                    self.writer.write_i32(421, ((r as i32) << 16) | ((g as i32) << 8) | (b as i32))?
+                }
+                color => self.writer.write_color(63, color)?,
+            }
+            self.writer.write_double(45, mtext.background_scale)?;
+            self.writer.write_i32(441, mtext.background_transparency.to_alpha_value())?;
+        }
+
+        let columns = &mtext.columns;
+        if columns.column_type != ColumnType::NoColumns {
+            self.writer.write_i16(75, columns.column_type as i16)?;
+            self.writer.write_i16(76, columns.count as i16)?;
+            self.writer.write_i16(78, columns.flow_reversed as i16)?;
+            self.writer.write_i16(79, columns.auto_height as i16)?;
+            self.writer.write_double(48, columns.width)?;
+            self.writer.write_double(49, columns.gutter)?;
+            for height in &columns.heights {
+                self.writer.write_double(50, *height)?;
+            }
+        }
         Ok(())
     }
 
@@ -1184,24 +1382,30 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     fn write_spline(&mut self, spline: &Spline, owner: Handle) -> Result<()> {
         self.writer.write_entity_type("SPLINE")?;
         self.write_common_entity_data(&spline.common, owner)?;
-        self.writer.write_subclass("AcDbSpline")?;
+        self.write_subclass("AcDbSpline")?;
 
         // Normal vector
-        self.writer.write_double(210, 0.0)?;
-        self.writer.write_double(220, 0.0)?;
-        self.writer.write_double(230, 1.0)?;
+        self.writer.write_point3d(210, spline.normal)?;
 
-        // Flags
+        // Flags: a periodic spline is always closed, a linear one planar
+        let weighted = spline.is_weighted()
+            || (spline.flags.rational && !spline.weights.is_empty() && spline.weights.len() == spline.control_points.len());
         let mut flags: i16 = 0;
-        if spline.flags.closed {
+        if spline.flags.closed || spline.flags.periodic {
             flags |= 1;
         }
         if spline.flags.periodic {
             flags |= 2;
         }
-        if spline.flags.rational {
+        if spline.flags.rational || weighted {
             flags |= 4;
         }
+        if spline.flags.planar || spline.flags.linear {
+            flags |= 8;
+        }
+        if spline.flags.linear {
+            flags |= 16;
+        }
         self.writer.write_i16(70, flags)?;
 
         self.writer.write_i16(71, spline.degree as i16)?;
@@ -1210,19 +1414,31 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
             .write_i16(73, spline.control_points.len() as i16)?;
         self.writer.write_i16(74, spline.fit_points.len() as i16)?;
 
-        // Knot tolerance, control point tolerance, fit tolerance
-        self.writer.write_double(42, 0.0000001)?;
-        self.writer.write_double(43, 0.0000001)?;
-        self.writer.write_double(44, 0.0000001)?;
+        self.writer.write_double(42, spline.knot_tolerance)?;
+        self.writer.write_double(43, spline.control_point_tolerance)?;
+        if !spline.fit_points.is_empty() {
+            self.writer.write_double(44, spline.fit_tolerance)?;
+        }
+
+        // Tangents at the ends of the fit points
+        if spline.start_tangent != Vector3::ZERO {
+            self.writer.write_point3d(12, spline.start_tangent)?;
+        }
+        if spline.end_tangent != Vector3::ZERO {
+            self.writer.write_point3d(13, spline.end_tangent)?;
+        }
 
         // Knots
         for knot in &spline.knots {
             self.writer.write_double(40, *knot)?;
         }
 
-        // Control points
-        for point in &spline.control_points {
+        // Control points, each followed by its weight
+        for (i, point) in spline.control_points.iter().enumerate() {
             self.writer.write_point3d(10, *point)?;
+            if weighted {
+                self.writer.write_double(41, spline.weights[i])?;
+            }
         }
 
         // Fit points
@@ -1246,67 +1462,75 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         }
     }
 
-    fn write_dimension_base(&mut self, base: &DimensionBase, type_flags: i16, owner: Handle) -> Result<()> {
-        self.writer.write_handle(5, base.common.handle)?;
-        self.writer.write_handle(330, owner)?;
-
-        // Write xdictionary group
-        if let Some(xdict) = base.common.xdictionary_handle {
-            if xdict != Handle::NULL {
-                self.writer.write_string(102, "{ACAD_XDICTIONARY")?;
-                self.writer.write_handle(360, xdict)?;
-                self.writer.write_string(102, "}")?;
-            }
-        }
-
-        // Write reactor group
-        if !base.common.reactors.is_empty() {
-            self.writer.write_string(102, "{ACAD_REACTORS")?;
-            for reactor in &base.common.reactors {
-                self.writer.write_handle(330, *reactor)?;
-            }
-            self.writer.write_string(102, "}")?;
-        }
-
-        self.writer.write_subclass("AcDbEntity")?;
-        self.writer.write_string(8, &base.common.layer)?;
-        self.writer.write_subclass("AcDbDimension")?;
+    /// Write the AcDbDimension part shared by all dimension subclasses;
+    /// `definition_point` is the subclass point written as group 10
+    fn write_dimension_base(
+        &mut self,
+        base: &DimensionBase,
+        definition_point: Vector3,
+        type_flags: i16,
+        owner: Handle,
+    ) -> Result<()> {
+        self.write_common_entity_data(&base.common, owner)?;
+        self.write_subclass("AcDbDimension")?;
         self.writer.write_string(2, &base.block_name)?;
-        self.writer.write_point3d(10, base.definition_point)?;
+        self.writer.write_point3d(10, definition_point)?;
         self.writer.write_point3d(11, base.text_middle_point)?;
+        if base.insertion_point != Vector3::ZERO {
+            self.writer.write_point3d(12, base.insertion_point)?;
+        }
         self.writer.write_i16(70, type_flags)?;
-        self.writer.write_double(53, base.text_rotation)?;
-        self.writer.write_string(3, &base.style_name)?;
+        self.writer.write_i16(71, base.attachment_point as i16)?;
+        if base.line_spacing_factor != 1.0 {
+            self.writer.write_double(41, base.line_spacing_factor)?;
+        }
+        self.writer.write_double(42, base.actual_measurement)?;
         if !base.text.is_empty() {
             self.writer.write_string(1, &base.text)?;
         }
+        if base.text_rotation != 0.0 {
+            self.writer.write_double(53, base.text_rotation.to_degrees())?;
+        }
+        if base.horizontal_direction != 0.0 {
+            self.writer.write_double(51, base.horizontal_direction.to_degrees())?;
+        }
+        if base.normal != Vector3::UNIT_Z {
+            self.writer.write_point3d(210, base.normal)?;
+        }
+        self.writer.write_string(3, &base.style_name)?;
         Ok(())
     }
 
     fn write_dimension_aligned(&mut self, dim: &DimensionAligned, owner: Handle) -> Result<()> {
         self.writer.write_entity_type("DIMENSION")?;
-        self.write_dimension_base(&dim.base, 1, owner)?; // Aligned = 1
-        self.writer.write_subclass("AcDbAlignedDimension")?;
+        self.write_dimension_base(&dim.base, dim.definition_point, 1, owner)?; // Aligned = 1
+        self.write_subclass("AcDbAlignedDimension")?;
         self.writer.write_point3d(13, dim.first_point)?;
         self.writer.write_point3d(14, dim.second_point)?;
+        if dim.ext_line_rotation != 0.0 {
+            self.writer.write_double(52, dim.ext_line_rotation.to_degrees())?;
+        }
         Ok(())
     }
 
     fn write_dimension_linear(&mut self, dim: &DimensionLinear, owner: Handle) -> Result<()> {
         self.writer.write_entity_type("DIMENSION")?;
-        self.write_dimension_base(&dim.base, 0, owner)?; // Linear = 0
-        self.writer.write_subclass("AcDbAlignedDimension")?;
+        self.write_dimension_base(&dim.base, dim.definition_point, 0, owner)?; // Linear = 0
+        self.write_subclass("AcDbAlignedDimension")?;
         self.writer.write_point3d(13, dim.first_point)?;
         self.writer.write_point3d(14, dim.second_point)?;
-        self.writer.write_double(50, dim.rotation)?;
-        self.writer.write_subclass("AcDbRotatedDimension")?;
+        self.writer.write_double(50, dim.rotation.to_degrees())?;
+        if dim.ext_line_rotation != 0.0 {
+            self.writer.write_double(52, dim.ext_line_rotation.to_degrees())?;
+        }
+        self.write_subclass("AcDbRotatedDimension")?;
         Ok(())
     }
 
     fn write_dimension_radius(&mut self, dim: &DimensionRadius, owner: Handle) -> Result<()> {
         self.writer.write_entity_type("DIMENSION")?;
-        self.write_dimension_base(&dim.base, 4, owner)?; // Radius = 4
-        self.writer.write_subclass("AcDbRadialDimension")?;
+        self.write_dimension_base(&dim.base, dim.definition_point, 4, owner)?; // Radius = 4
+        self.write_subclass("AcDbRadialDimension")?;
         self.writer.write_point3d(15, dim.angle_vertex)?;
         self.writer.write_double(40, dim.leader_length)?;
         Ok(())
@@ -1314,8 +1538,8 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
 
     fn write_dimension_diameter(&mut self, dim: &DimensionDiameter, owner: Handle) -> Result<()> {
         self.writer.write_entity_type("DIMENSION")?;
-        self.write_dimension_base(&dim.base, 3, owner)?; // Diameter = 3
-        self.writer.write_subclass("AcDbDiametricDimension")?;
+        self.write_dimension_base(&dim.base, dim.definition_point, 3, owner)?; // Diameter = 3
+        self.write_subclass("AcDbDiametricDimension")?;
         self.writer.write_point3d(15, dim.angle_vertex)?;
         self.writer.write_double(40, dim.leader_length)?;
         Ok(())
@@ -1323,19 +1547,19 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
 
     fn write_dimension_angular_2line(&mut self, dim: &DimensionAngular2Ln, owner: Handle) -> Result<()> {
         self.writer.write_entity_type("DIMENSION")?;
-        self.write_dimension_base(&dim.base, 2, owner)?; // Angular = 2
-        self.writer.write_subclass("AcDb2LineAngularDimension")?;
+        self.write_dimension_base(&dim.base, dim.definition_point, 2, owner)?; // Angular = 2
+        self.write_subclass("AcDb2LineAngularDimension")?;
         self.writer.write_point3d(13, dim.first_point)?;
         self.writer.write_point3d(14, dim.second_point)?;
         self.writer.write_point3d(15, dim.angle_vertex)?;
-        self.writer.write_point3d(16, dim.definition_point)?;
+        self.writer.write_point3d(16, dim.dimension_arc)?;
         Ok(())
     }
 
     fn write_dimension_angular_3point(&mut self, dim: &DimensionAngular3Pt, owner: Handle) -> Result<()> {
         self.writer.write_entity_type("DIMENSION")?;
-        self.write_dimension_base(&dim.base, 5, owner)?; // 3-point angular = 5
-        self.writer.write_subclass("AcDb3PointAngularDimension")?;
+        self.write_dimension_base(&dim.base, dim.definition_point, 5, owner)?; // 3-point angular = 5
+        self.write_subclass("AcDb3PointAngularDimension")?;
         self.writer.write_point3d(13, dim.first_point)?;
         self.writer.write_point3d(14, dim.second_point)?;
         self.writer.write_point3d(15, dim.angle_vertex)?;
@@ -1344,9 +1568,10 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
 
     fn write_dimension_ordinate(&mut self, dim: &DimensionOrdinate, owner: Handle) -> Result<()> {
         self.writer.write_entity_type("DIMENSION")?;
-        let type_flags = if dim.is_ordinate_type_x { 64 } else { 128 }; // Ordinate with X/Y flag
-        self.write_dimension_base(&dim.base, 6 | type_flags, owner)?;
-        self.writer.write_subclass("AcDbOrdinateDimension")?;
+        // Bit 64 marks an X ordinate
+        let type_flags = if dim.is_ordinate_type_x { 6 | 64 } else { 6 };
+        self.write_dimension_base(&dim.base, dim.definition_point, type_flags, owner)?;
+        self.write_subclass("AcDbOrdinateDimension")?;
         self.writer.write_point3d(13, dim.feature_location)?;
         self.writer.write_point3d(14, dim.leader_endpoint)?;
         Ok(())
@@ -1356,7 +1581,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     fn write_hatch(&mut self, hatch: &Hatch, owner: Handle) -> Result<()> {
         self.writer.write_entity_type("HATCH")?;
         self.write_common_entity_data(&hatch.common, owner)?;
-        self.writer.write_subclass("AcDbHatch")?;
+        self.write_subclass("AcDbHatch")?;
 
         // Elevation point
         self.writer.write_double(10, 0.0)?;
@@ -1412,6 +1637,10 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
             }
         }
 
+        if hatch.pixel_size > 0.0 {
+            self.writer.write_double(47, hatch.pixel_size)?;
+        }
+
         // Seed points
         self.writer.write_i32(98, hatch.seed_points.len() as i32)?;
         for seed in &hatch.seed_points {
@@ -1423,9 +1652,12 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     }
 
     fn write_hatch_boundary_path(&mut self, path: &BoundaryPath) -> Result<()> {
-        self.writer.write_i32(92, get_boundary_path_bits(&path.flags) as i32)?;
+        // The polyline flag follows the edges, which decide the layout
+        let mut flags = path.flags;
+        flags.set_polyline(path.is_polyline());
+        self.writer.write_i32(92, flags.bits() as i32)?;
 
-        if !path.flags.is_polyline() {
+        if !path.is_polyline() {
             self.writer.write_i32(93, path.edges.len() as i32)?;
         }
 
@@ -1433,8 +1665,11 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
             self.write_hatch_edge(edge)?;
         }
 
-        // Associated entities (boundary handles)
-        self.writer.write_i32(97, 0)?;
+        // Source boundary objects of an associative hatch
+        self.writer.write_i32(97, path.boundary_handles.len() as i32)?;
+        for handle in &path.boundary_handles {
+            self.writer.write_handle(330, *handle)?;
+        }
 
         Ok(())
     }
@@ -1466,18 +1701,19 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
                 self.writer.write_double(11, ellipse.major_axis_endpoint.x)?;
                 self.writer.write_double(21, ellipse.major_axis_endpoint.y)?;
                 self.writer.write_double(40, ellipse.minor_axis_ratio)?;
-                self.writer.write_double(50, ellipse.start_angle)?;
-                self.writer.write_double(51, ellipse.end_angle)?;
+                self.writer
+                    .write_double(50, ellipse.start_angle.to_degrees())?;
+                self.writer.write_double(51, ellipse.end_angle.to_degrees())?;
                 self.writer
                     .write_i16(73, if ellipse.counter_clockwise { 1 } else { 0 })?;
             }
             BoundaryEdge::Spline(spline) => {
                 self.writer.write_i16(72, 4)?; // Spline type
+                self.writer.write_i32(94, spline.degree)?;
                 self.writer
                     .write_i16(73, if spline.rational { 1 } else { 0 })?;
                 self.writer
                     .write_i16(74, if spline.periodic { 1 } else { 0 })?;
-                self.writer.write_i32(94, spline.degree)?;
                 self.writer.write_i32(95, spline.knots.len() as i32)?;
                 self.writer
                     .write_i32(96, spline.control_points.len() as i32)?;
@@ -1487,6 +1723,20 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
                 for point in &spline.control_points {
                     self.writer.write_double(10, point.x)?;
                     self.writer.write_double(20, point.y)?;
+                    if spline.rational {
+                        self.writer.write_double(42, point.z)?; // z stores the weight
+                    }
+                }
+                self.writer.write_i32(97, spline.fit_points.len() as i32)?;
+                for point in &spline.fit_points {
+                    self.writer.write_double(11, point.x)?;
+                    self.writer.write_double(21, point.y)?;
+                }
+                if !spline.fit_points.is_empty() {
+                    self.writer.write_double(12, spline.start_tangent.x)?;
+                    self.writer.write_double(22, spline.start_tangent.y)?;
+                    self.writer.write_double(13, spline.end_tangent.x)?;
+                    self.writer.write_double(23, spline.end_tangent.y)?;
                 }
             }
             BoundaryEdge::Polyline(poly) => {
@@ -1511,7 +1761,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     fn write_solid(&mut self, solid: &Solid, owner: Handle) -> Result<()> {
         self.writer.write_entity_type("SOLID")?;
         self.write_common_entity_data(&solid.common, owner)?;
-        self.writer.write_subclass("AcDbTrace")?;
+        self.write_subclass("AcDbTrace")?;
         self.writer.write_point3d(10, solid.first_corner)?;
         self.writer.write_point3d(11, solid.second_corner)?;
         self.writer.write_point3d(12, solid.third_corner)?;
@@ -1526,7 +1776,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     fn write_face3d(&mut self, face: &Face3D, owner: Handle) -> Result<()> {
         self.writer.write_entity_type("3DFACE")?;
         self.write_common_entity_data(&face.common, owner)?;
-        self.writer.write_subclass("AcDbFace")?;
+        self.write_subclass("AcDbFace")?;
         self.writer.write_point3d(10, face.first_corner)?;
         self.writer.write_point3d(11, face.second_corner)?;
         self.writer.write_point3d(12, face.third_corner)?;
@@ -1542,7 +1792,14 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     fn write_insert(&mut self, insert: &Insert, owner: Handle) -> Result<()> {
         self.writer.write_entity_type("INSERT")?;
         self.write_common_entity_data(&insert.common, owner)?;
-        self.writer.write_subclass("AcDbBlockReference")?;
+        if insert.is_array() {
+            self.write_subclass("AcDbMInsertBlock")?;
+        } else {
+            self.write_subclass("AcDbBlockReference")?;
+        }
+        if !insert.attributes.is_empty() {
+            self.writer.write_i16(66, 1)?;
+        }
         self.writer.write_string(2, &insert.block_name)?;
         self.writer.write_point3d(10, insert.insert_point)?;
         if insert.x_scale != 1.0 {
@@ -1569,6 +1826,30 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         if insert.row_spacing != 0.0 {
             self.writer.write_double(45, insert.row_spacing)?;
         }
+        if insert.normal != Vector3::UNIT_Z {
+            self.writer.write_point3d(210, insert.normal)?;
+        }
+        if insert.attributes.is_empty() {
+            return Ok(());
+        }
+
+        // ATTRIB and SEQEND are owned by the insert
+        let insert_handle = insert.common.handle;
+        for attrib in &insert.attributes {
+            if attrib.common.handle.is_null() {
+                let mut attrib = attrib.clone();
+                attrib.common.handle = self.allocate_handle();
+                self.write_attrib(&attrib, insert_handle)?;
+            } else {
+                self.write_attrib(attrib, insert_handle)?;
+            }
+        }
+        let seqend_handle = self.allocate_handle();
+        self.writer.write_entity_type("SEQEND")?;
+        self.writer.write_handle(5, seqend_handle)?;
+        self.writer.write_handle(330, insert_handle)?;
+        self.write_subclass("AcDbEntity")?;
+        self.writer.write_string(8, &insert.common.layer)?;
         Ok(())
     }
 
@@ -1576,7 +1857,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     fn write_block_entity(&mut self, block: &Block, owner: Handle) -> Result<()> {
         self.writer.write_entity_type("BLOCK")?;
         self.write_common_entity_data(&block.common, owner)?;
-        self.writer.write_subclass("AcDbBlockBegin")?;
+        self.write_subclass("AcDbBlockBegin")?;
         self.writer.write_string(2, &block.name)?;
         self.writer.write_i16(70, 0)?; // Block flags
         self.writer.write_point3d(10, block.base_point)?;
@@ -1594,7 +1875,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     fn write_block_end(&mut self, block_end: &BlockEnd, owner: Handle) -> Result<()> {
         self.writer.write_entity_type("ENDBLK")?;
         self.write_common_entity_data(&block_end.common, owner)?;
-        self.writer.write_subclass("AcDbBlockEnd")?;
+        self.write_subclass("AcDbBlockEnd")?;
         Ok(())
     }
 
@@ -1602,7 +1883,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     fn write_ray(&mut self, ray: &Ray, owner: Handle) -> Result<()> {
         self.writer.write_entity_type("RAY")?;
         self.write_common_entity_data(&ray.common, owner)?;
-        self.writer.write_subclass("AcDbRay")?;
+        self.write_subclass("AcDbRay")?;
         self.writer.write_point3d(10, ray.base_point)?;
         self.writer.write_point3d(11, ray.direction)?;
         Ok(())
@@ -1612,7 +1893,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     fn write_xline(&mut self, xline: &XLine, owner: Handle) -> Result<()> {
         self.writer.write_entity_type("XLINE")?;
         self.write_common_entity_data(&xline.common, owner)?;
-        self.writer.write_subclass("AcDbXline")?;
+        self.write_subclass("AcDbXline")?;
         self.writer.write_point3d(10, xline.base_point)?;
         self.writer.write_point3d(11, xline.direction)?;
         Ok(())
@@ -1622,7 +1903,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     fn write_polyline3d(&mut self, polyline: &Polyline3D, owner: Handle) -> Result<()> {
         self.writer.write_entity_type("POLYLINE")?;
         self.write_common_entity_data(&polyline.common, owner)?;
-        self.writer.write_subclass("AcDb3dPolyline")?;
+        self.write_subclass("AcDb3dPolyline")?;
 
         // Entities follow flag (VERTEX records follow)
         self.writer.write_i16(66, 1)?;
@@ -1646,25 +1927,24 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
             self.writer.write_entity_type("VERTEX")?;
             self.writer.write_handle(5, vertex_handle)?;
             self.writer.write_handle(330, polyline_handle)?;
-            self.writer.write_subclass("AcDbEntity")?;
+            self.write_subclass("AcDbEntity")?;
             self.writer.write_string(8, &vertex.layer)?;
             // Propagate parent color to vertex so CAD doesn't flag mismatch
             if polyline.common.color != Color::ByLayer {
                 self.writer.write_color(62, polyline.common.color)?;
             }
-            self.writer.write_subclass("AcDbVertex")?;
-            self.writer.write_subclass("AcDb3dPolylineVertex")?;
+            self.write_subclass("AcDbVertex")?;
+            self.write_subclass("AcDb3dPolylineVertex")?;
             self.writer.write_point3d(10, vertex.position)?;
             self.writer.write_i16(70, vertex.flags as i16)?;
         }
         
         // SEQEND
         self.writer.write_entity_type("SEQEND")?;
-        let seqend_handle = self.allocate_handle();
+        let seqend_handle = polyline.seqend_handle.unwrap_or_else(|| self.allocate_handle());
         self.writer.write_handle(5, seqend_handle)?;
         self.writer.write_handle(330, polyline_handle)?;
-        self.writer.write_subclass("AcDbEntity")?;
-        self.writer.write_subclass("AcDbSequenceEnd")?;
+        self.write_subclass("AcDbEntity")?;
         self.writer.write_string(8, &polyline.common.layer)?;
         
         Ok(())
@@ -1674,7 +1954,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     fn write_viewport(&mut self, viewport: &Viewport, owner: Handle) -> Result<()> {
         self.writer.write_entity_type("VIEWPORT")?;
         self.write_common_entity_data(&viewport.common, owner)?;
-        self.writer.write_subclass("AcDbViewport")?;
+        self.write_subclass("AcDbViewport")?;
         
         // Center point
         self.writer.write_point3d(10, viewport.center)?;
@@ -1683,11 +1963,13 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         self.writer.write_double(40, viewport.width)?;
         self.writer.write_double(41, viewport.height)?;
         
-        // Viewport ID
-        self.writer.write_i16(68, viewport.id)?;
-        
-        // Status
-        self.writer.write_i32(90, viewport.status.to_bits())?;
+        // Status (stacking order, 0 when off) and ID
+        let (id, status) = match &self.viewports {
+            Some(viewports) => viewports.numbers(viewport),
+            None => ViewportTable::default().numbers(viewport),
+        };
+        self.writer.write_i16(68, status)?;
+        self.writer.write_i16(69, id)?;
         
         // View center
         self.writer.write_double(12, viewport.view_center.x)?;
@@ -1726,15 +2008,61 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         self.writer.write_double(45, viewport.view_height)?;
         
         // Snap and twist angles
-        self.writer.write_double(50, viewport.snap_angle)?;
-        self.writer.write_double(51, viewport.twist_angle)?;
+        self.writer.write_double(50, viewport.snap_angle.to_degrees())?;
+        self.writer.write_double(51, viewport.twist_angle.to_degrees())?;
         
         // Circle sides
         self.writer.write_i16(72, viewport.circle_sides)?;
         
+        // Frozen layers
+        for layer in &viewport.frozen_layers {
+            self.writer.write_handle(331, *layer)?;
+        }
+        
+        // Status flags
+        self.writer.write_i32(90, viewport.status.to_bits())?;
+        
         // Render mode
         self.writer.write_byte(281, viewport.render_mode.to_value() as u8)?;
         
+        // UCS
+        self.writer.write_i16(71, viewport.ucs_per_viewport as i16)?;
+        self.writer.write_i16(74, viewport.ucs_icon_visible as i16)?;
+        self.writer.write_point3d(110, viewport.ucs_origin)?;
+        self.writer.write_point3d(111, viewport.ucs_x_axis)?;
+        self.writer.write_point3d(112, viewport.ucs_y_axis)?;
+        if !viewport.ucs_handle.is_null() {
+            self.writer.write_handle(345, viewport.ucs_handle)?;
+        }
+        if !viewport.base_ucs_handle.is_null() {
+            self.writer.write_handle(346, viewport.base_ucs_handle)?;
+        }
+        self.writer.write_i16(79, viewport.ucs_ortho_type)?;
+        self.writer.write_double(146, viewport.elevation)?;
+        
+        // Plotting, grid and shading
+        self.writer.write_i16(170, viewport.shade_plot_mode)?;
+        self.writer.write_i16(61, viewport.grid_major)?;
+        if !viewport.background_handle.is_null() {
+            self.writer.write_handle(332, viewport.background_handle)?;
+        }
+        if !viewport.shade_plot_handle.is_null() {
+            self.writer.write_handle(333, viewport.shade_plot_handle)?;
+        }
+        if !viewport.visual_style_handle.is_null() {
+            self.writer.write_handle(348, viewport.visual_style_handle)?;
+        }
+        if !viewport.sun_handle.is_null() {
+            self.writer.write_handle(361, viewport.sun_handle)?;
+        }
+        self.writer.write_bool(292, viewport.default_lighting)?;
+        self.writer.write_byte(282, viewport.default_lighting_type as u8)?;
+        self.writer.write_double(141, viewport.brightness)?;
+        self.writer.write_double(142, viewport.contrast)?;
+        if viewport.ambient_color != 0 {
+            self.writer.write_i32(421, viewport.ambient_color)?;
+        }
+        
         Ok(())
     }
 
@@ -1742,7 +2070,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     fn write_attdef(&mut self, attdef: &AttributeDefinition, owner: Handle) -> Result<()> {
         self.writer.write_entity_type("ATTDEF")?;
         self.write_common_entity_data(&attdef.common, owner)?;
-        self.writer.write_subclass("AcDbText")?;
+        self.write_subclass("AcDbText")?;
         
         // Insertion point
         self.writer.write_point3d(10, attdef.insertion_point)?;
@@ -1777,7 +2105,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         // Normal
         self.writer.write_point3d(210, attdef.normal)?;
         
-        self.writer.write_subclass("AcDbAttributeDefinition")?;
+        self.write_subclass("AcDbAttributeDefinition")?;
         
         // Tag
         self.writer.write_string(2, &attdef.tag)?;
@@ -1801,7 +2129,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     fn write_attrib(&mut self, attrib: &AttributeEntity, owner: Handle) -> Result<()> {
         self.writer.write_entity_type("ATTRIB")?;
         self.write_common_entity_data(&attrib.common, owner)?;
-        self.writer.write_subclass("AcDbText")?;
+        self.write_subclass("AcDbText")?;
         
         // Insertion point
         self.writer.write_point3d(10, attrib.insertion_point)?;
@@ -1836,7 +2164,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         // Normal
         self.writer.write_point3d(210, attrib.normal)?;
         
-        self.writer.write_subclass("AcDbAttribute")?;
+        self.write_subclass("AcDbAttribute")?;
         
         // Tag
         self.writer.write_string(2, &attrib.tag)?;
@@ -1857,7 +2185,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     fn write_leader(&mut self, leader: &Leader, owner: Handle) -> Result<()> {
         self.writer.write_entity_type("LEADER")?;
         self.write_common_entity_data(&leader.common, owner)?;
-        self.writer.write_subclass("AcDbLeader")?;
+        self.write_subclass("AcDbLeader")?;
         
         // Dimension style
         self.writer.write_string(3, &leader.dimension_style)?;
@@ -1891,6 +2219,16 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
             self.writer.write_point3d(10, *vertex)?;
         }
         
+        // Color used when DIMCLRD is BYBLOCK
+        if leader.override_color != Color::ByLayer {
+            self.writer.write_color(77, leader.override_color)?;
+        }
+        
+        // Associated annotation
+        if !leader.annotation_handle.is_null() {
+            self.writer.write_handle(340, leader.annotation_handle)?;
+        }
+        
         // Normal
         self.writer.write_point3d(210, leader.normal)?;
         
@@ -1906,60 +2244,249 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         Ok(())
     }
 
+    /// Write an unknown entity from its raw pairs.
+    ///
+    /// The common entity data before the first subclass marker other than
+    /// `AcDbEntity` comes from `entity.common`: the handle, the persistent
+    /// groups, the owner, layer, color, lineweight and visibility replace
+    /// the pairs read for them. Everything else, including linetype and the
+    /// subclass data, is emitted verbatim. Entities without raw pairs (not
+    /// read from DXF) are skipped.
+    fn write_unknown_entity(&mut self, entity: &UnknownEntity, owner: Handle) -> Result<()> {
+        if entity.raw_pairs.is_empty() {
+            return Ok(());
+        }
+
+        let common = &entity.common;
+        let (body_start, owner_index) = unknown_entity_common_data(&entity.raw_pairs);
+        self.writer.write_entity_type(&entity.dxf_name)?;
+        self.writer.write_handle(5, common.handle)?;
+        self.write_persistent_groups(&common.reactors, common.xdictionary_handle)?;
+        if owner_index.is_none() {
+            self.writer.write_handle(330, owner)?;
+        }
+
+        let mut group: Option<&str> = None;
+        let mut properties_written = false;
+        for (index, (code, value)) in entity.raw_pairs.iter().enumerate() {
+            if index == body_start && !properties_written {
+                self.write_unknown_entity_properties(common)?;
+                properties_written = true;
+            }
+            if *code == 102 {
+                // The opened group, or the one being closed
+                let opened = value.trim().strip_prefix('{');
+                let name = opened.or(group);
+                group = opened;
+                if !matches!(name, Some("ACAD_REACTORS" | "ACAD_XDICTIONARY")) {
+                    self.writer.write_string(102, value)?;
+                }
+                continue;
+            }
+            match (group, *code) {
+                (Some("ACAD_REACTORS" | "ACAD_XDICTIONARY"), _) => {}
+                (Some(_), _) => self.write_raw_pair(*code, value)?,
+                (None, _) if index >= body_start => self.write_raw_pair(*code, value)?,
+                (None, 5) => {}
+                (None, 330) if owner_index == Some(index) => self.writer.write_handle(330, owner)?,
+                (None, 8 | 62 | 420 | 370 | 60) => {
+                    if !properties_written {
+                        self.write_unknown_entity_properties(common)?;
+                        properties_written = true;
+                    }
+                }
+                _ => self.write_raw_pair(*code, value)?,
+            }
+        }
+        if !properties_written {
+            self.write_unknown_entity_properties(common)?;
+        }
+        Ok(())
+    }
+
+    /// Write the layer, color, lineweight and visibility of an unknown entity
+    fn write_unknown_entity_properties(&mut self, common: &EntityCommon) -> Result<()> {
+        self.writer.write_string(8, &common.layer)?;
+        if common.color != Color::ByLayer {
+            self.writer.write_color(62, common.color)?;
+        }
+        if common.line_weight != crate::types::LineWeight::ByLayer {
+            self.writer.write_i16(370, common.line_weight.value())?;
+        }
+        if common.invisible {
+            self.writer.write_i16(60, 1)?;
+        }
+        Ok(())
+    }
+
+    /// Write an unknown object verbatim from its raw pairs
+    fn write_unknown_object(&mut self, type_name: &str, handle: Handle, raw_pairs: &[(i32, String)]) -> Result<()> {
+        if raw_pairs.is_empty() {
+            return Ok(());
+        }
+
+        self.writer.write_string(0, type_name)?;
+        for (code, value) in raw_pairs {
+            match *code {
+                5 => self.writer.write_handle(5, handle)?,
+                _ => self.write_raw_pair(*code, value)?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Write a raw code/value pair, typed by the value type of its group code.
+    ///
+    /// A value that does not parse as that type is an error: written as a
+    /// string it would corrupt binary DXF.
+    fn write_raw_pair(&mut self, code: i32, value: &str) -> Result<()> {
+        use crate::io::dxf::GroupCodeValueType;
+
+        let trimmed = value.trim();
+        let value_type = GroupCodeValueType::from_raw_code(code);
+        match value_type {
+            GroupCodeValueType::Double => {
+                if let Ok(v) = trimmed.parse::<f64>() {
+                    return self.writer.write_double(code, v);
+                }
+            }
+            GroupCodeValueType::Int16 | GroupCodeValueType::Byte => {
+                if let Ok(v) = trimmed.parse::<i16>() {
+                    return self.writer.write_i16(code, v);
+                }
+            }
+            GroupCodeValueType::Int32 => {
+                if let Ok(v) = trimmed.parse::<i32>() {
+                    return self.writer.write_i32(code, v);
+                }
+            }
+            GroupCodeValueType::Int64 => {
+                if let Ok(v) = trimmed.parse::<i64>() {
+                    return self.writer.write_i64(code, v);
+                }
+            }
+            GroupCodeValueType::Bool => {
+                if let Ok(v) = trimmed.parse::<i16>() {
+                    return self.writer.write_bool(code, v != 0);
+                }
+            }
+            GroupCodeValueType::Handle => {
+                if let Ok(h) = u64::from_str_radix(trimmed, 16) {
+                    return self.writer.write_handle(code, Handle::new(h));
+                }
+            }
+            GroupCodeValueType::BinaryData => {
+                let bytes: Option<Vec<u8>> = (0..trimmed.len())
+                    .step_by(2)
+                    .map(|i| trimmed.get(i..i + 2).and_then(|b| u8::from_str_radix(b, 16).ok()))
+                    .collect();
+                if let Some(bytes) = bytes {
+                    return self.writer.write_binary(code, &bytes);
+                }
+            }
+            _ => return self.writer.write_string(code, value),
+        }
+        Err(DxfError::InvalidFormat(format!(
+            "value '{}' of group code {} is not a valid {:?}",
+            value, code, value_type
+        )))
+    }
+
+    /// Write the THUMBNAILIMAGE section, if the document has a preview
+    pub fn write_thumbnail(&mut self, document: &CadDocument) -> Result<()> {
+        let Some(preview) = document.preview.as_ref().filter(|p| !p.is_empty()) else {
+            return Ok(());
+        };
+        self.writer.write_section_start("THUMBNAILIMAGE")?;
+        self.writer.write_i32(90, preview.raw_image.len() as i32)?;
+        for chunk in preview.raw_image.chunks(127) {
+            self.writer.write_binary(310, chunk)?;
+        }
+        self.writer.write_section_end()?;
+        Ok(())
+    }
+
     /// Write the OBJECTS section
     pub fn write_objects(&mut self, document: &CadDocument) -> Result<()> {
         self.writer.write_section_start("OBJECTS")?;
+        self.number_viewports(document);
+
+        // The named object dictionary comes first; parts of the mandatory
+        // tree the document lacks are filled in
+        let required = RequiredObjects::new(document, || self.allocate_handle());
+        self.write_dictionary(&required.root)?;
 
-        // Write root dictionary
-        let mut root_dict = Dictionary::new();
-        root_dict.handle = self.allocate_handle();
-        self.write_dictionary(&root_dict)?;
-
-        // Write other objects
-        for object in document.objects.values() {
-            match object {
-                ObjectType::Dictionary(dict) => self.write_dictionary(dict)?,
-                ObjectType::Layout(layout) => self.write_layout(layout)?,
-                ObjectType::XRecord(xrecord) => self.write_xrecord(xrecord)?,
-                ObjectType::Group(group) => self.write_group(group)?,
-                ObjectType::MLineStyle(mlinestyle) => self.write_mlinestyle(mlinestyle)?,
-                ObjectType::ImageDefinition(imagedef) => self.write_image_definition(imagedef)?,
-                ObjectType::PlotSettings(plotsettings) => self.write_plot_settings(plotsettings)?,
-                ObjectType::MultiLeaderStyle(style) => self.write_multileader_style(style)?,
-                ObjectType::TableStyle(style) => self.write_table_style(style)?,
-                ObjectType::Scale(scale) => self.write_scale(scale)?,
-                ObjectType::SortEntitiesTable(table) => self.write_sort_entities_table(table)?,
-                ObjectType::DictionaryVariable(var) => self.write_dictionary_variable(var)?,
-                ObjectType::VisualStyle(obj) => self.write_visualstyle(obj)?,
-                ObjectType::Material(obj) => self.write_material(obj)?,
-                ObjectType::ImageDefinitionReactor(obj) => self.write_imagedef_reactor(obj)?,
-                ObjectType::GeoData(obj) => self.write_stub_handle_only("GEODATA", obj.handle, obj.owner)?,
-                ObjectType::SpatialFilter(obj) => self.write_stub_handle_only("SPATIAL_FILTER", obj.handle, obj.owner)?,
-                ObjectType::RasterVariables(obj) => self.write_raster_variables(obj)?,
-                ObjectType::BookColor(obj) => self.write_bookcolor(obj)?,
-                ObjectType::PlaceHolder(obj) => self.write_stub_handle_only("ACDBPLACEHOLDER", obj.handle, obj.owner)?,
-                ObjectType::DictionaryWithDefault(obj) => self.write_dict_with_default(obj)?,
-                ObjectType::WipeoutVariables(obj) => self.write_wipeout_variables(obj)?,
-                ObjectType::Unknown { .. } => {}
+        for (handle, object) in document.sorted_objects() {
+            if handle == required.root.handle {
+                continue;
             }
+            self.write_object(required.replaced.get(&handle).unwrap_or(object))?;
+        }
+        for object in &required.added {
+            self.write_object(object)?;
         }
 
         self.writer.write_section_end()?;
         Ok(())
     }
 
+    fn write_object(&mut self, object: &ObjectType) -> Result<()> {
+        match object {
+            ObjectType::Dictionary(dict) => self.write_dictionary(dict)?,
+            ObjectType::Layout(layout) => self.write_layout(layout)?,
+            ObjectType::XRecord(xrecord) => self.write_xrecord(xrecord)?,
+            ObjectType::Group(group) => self.write_group(group)?,
+            ObjectType::MLineStyle(mlinestyle) => self.write_mlinestyle(mlinestyle)?,
+            ObjectType::ImageDefinition(imagedef) => self.write_image_definition(imagedef)?,
+            ObjectType::PlotSettings(plotsettings) => self.write_plot_settings(plotsettings)?,
+            ObjectType::MultiLeaderStyle(style) => self.write_multileader_style(style)?,
+            ObjectType::TableStyle(style) => self.write_table_style(style)?,
+            ObjectType::Scale(scale) => self.write_scale(scale)?,
+            ObjectType::SortEntitiesTable(table) => self.write_sort_entities_table(table)?,
+            ObjectType::DictionaryVariable(var) => self.write_dictionary_variable(var)?,
+            ObjectType::VisualStyle(obj) => self.write_visualstyle(obj)?,
+            ObjectType::Material(obj) => self.write_material(obj)?,
+            ObjectType::ImageDefinitionReactor(obj) => self.write_imagedef_reactor(obj)?,
+            ObjectType::GeoData(obj) => self.write_geodata(obj)?,
+            ObjectType::SpatialFilter(obj) => self.write_stub_handle_only("SPATIAL_FILTER", obj.handle, obj.owner)?,
+            ObjectType::RasterVariables(obj) => self.write_raster_variables(obj)?,
+            ObjectType::BookColor(obj) => self.write_bookcolor(obj)?,
+            ObjectType::PlaceHolder(obj) => self.write_stub_handle_only("ACDBPLACEHOLDER", obj.handle, obj.owner)?,
+            ObjectType::DictionaryWithDefault(obj) => self.write_dict_with_default(obj)?,
+            ObjectType::WipeoutVariables(obj) => self.write_wipeout_variables(obj)?,
+            ObjectType::UnderlayDefinition(obj) => self.write_underlay_definition(obj)?,
+            ObjectType::Sun(obj) => self.write_sun(obj)?,
+            ObjectType::Background(obj) => self.write_background(obj)?,
+            ObjectType::RenderSettings(obj) => self.write_render_settings(obj)?,
+            ObjectType::DimAssoc(obj) => self.write_dim_assoc(obj)?,
+            ObjectType::Unknown { type_name, handle, raw_pairs } => {
+                match self.custom_types.and_then(|custom| custom.object_writer(type_name)) {
+                    Some(write) => {
+                        self.writer.write_string(0, type_name)?;
+                        write(object, &mut *self.writer)?
+                    }
+                    None => self.write_unknown_object(type_name, *handle, raw_pairs)?,
+                }
+            }
+        }
+        Ok(())
+    }
+
     fn write_dictionary(&mut self, dict: &Dictionary) -> Result<()> {
         self.writer.write_string(0, "DICTIONARY")?;
         self.writer.write_handle(5, dict.handle)?;
+        self.write_persistent_groups(&dict.reactors, dict.xdictionary_handle)?;
         self.writer.write_handle(330, dict.owner)?;
-        self.writer.write_subclass("AcDbDictionary")?;
+        self.write_subclass("AcDbDictionary")?;
         self.writer
             .write_byte(280, if dict.hard_owner { 1 } else { 0 })?;
         self.writer.write_byte(281, dict.duplicate_cloning as u8)?;
 
+        // Entries of a hard owner are hard owned (360), all others soft (350)
+        let entry_code = if dict.hard_owner { 360 } else { 350 };
         for (key, handle) in &dict.entries {
             self.writer.write_string(3, key)?;
-            self.writer.write_handle(350, *handle)?;
+            self.writer.write_handle(entry_code, *handle)?;
         }
 
         Ok(())
@@ -1968,34 +2495,13 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     fn write_layout(&mut self, layout: &Layout) -> Result<()> {
         self.writer.write_string(0, "LAYOUT")?;
         self.writer.write_handle(5, layout.handle)?;
+        self.write_persistent_groups(&layout.reactors, layout.xdictionary_handle)?;
         self.writer.write_handle(330, layout.owner)?;
-        self.writer.write_subclass("AcDbPlotSettings")?;
-
-        // Minimal plot settings
-        self.writer.write_string(1, "")?; // Page setup name
-        self.writer.write_string(2, "")?; // Printer/plotter name
-        self.writer.write_string(4, "")?; // Paper size
-        self.writer.write_string(6, "")?; // Plot view name
-        self.writer.write_double(40, 0.0)?; // Left margin
-        self.writer.write_double(41, 0.0)?; // Bottom margin
-        self.writer.write_double(42, 0.0)?; // Right margin
-        self.writer.write_double(43, 0.0)?; // Top margin
-        self.writer.write_double(44, 0.0)?; // Paper width
-        self.writer.write_double(45, 0.0)?; // Paper height
-        self.writer.write_double(46, 0.0)?; // Plot origin X
-        self.writer.write_double(47, 0.0)?; // Plot origin Y
-        self.writer.write_double(48, 0.0)?; // Plot window X1
-        self.writer.write_double(49, 0.0)?; // Plot window Y1
-        self.writer.write_double(140, 0.0)?; // Plot window X2
-        self.writer.write_double(141, 0.0)?; // Plot window Y2
-        self.writer.write_double(142, 1.0)?; // Numerator of custom print scale
-        self.writer.write_double(143, 1.0)?; // Denominator of custom print scale
-        self.writer.write_i16(70, layout.flags)?;
-        self.writer.write_i16(72, 0)?; // Plot paper units
-        self.writer.write_i16(73, 0)?; // Plot rotation
-        self.writer.write_i16(74, 0)?; // Plot type
+        self.write_subclass("AcDbPlotSettings")?;
+
+        self.write_plot_settings_data(&layout.plot_settings)?;
 
-        self.writer.write_subclass("AcDbLayout")?;
+        self.write_subclass("AcDbLayout")?;
         self.writer.write_string(1, &layout.name)?;
         self.writer.write_i16(70, layout.flags)?;
         self.writer.write_i16(71, layout.tab_order as i16)?;
@@ -2013,7 +2519,11 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         self.writer.write_double(25, layout.max_extents.1)?;
         self.writer.write_double(35, layout.max_extents.2)?;
         self.writer.write_handle(330, layout.block_record)?;
-        self.writer.write_handle(331, layout.viewport)?;
+        let viewport = match &self.viewports {
+            Some(viewports) => viewports.layout_viewport(layout.block_record, layout.viewport),
+            None => layout.viewport,
+        };
+        self.writer.write_handle(331, viewport)?;
 
         Ok(())
     }
@@ -2023,8 +2533,9 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
 
         self.writer.write_string(0, "XRECORD")?;
         self.writer.write_handle(5, xrecord.handle)?;
+        self.write_persistent_groups(&xrecord.reactors, xrecord.xdictionary_handle)?;
         self.writer.write_handle(330, xrecord.owner)?;
-        self.writer.write_subclass("AcDbXrecord")?;
+        self.write_subclass("AcDbXrecord")?;
         self.writer.write_byte(280, xrecord.cloning_flags.to_code() as u8)?;
 
         // Write each entry's group code and value
@@ -2072,8 +2583,9 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     fn write_group(&mut self, group: &Group) -> Result<()> {
         self.writer.write_string(0, "GROUP")?;
         self.writer.write_handle(5, group.handle)?;
+        self.write_persistent_groups(&group.reactors, group.xdictionary_handle)?;
         self.writer.write_handle(330, group.owner)?;
-        self.writer.write_subclass("AcDbGroup")?;
+        self.write_subclass("AcDbGroup")?;
 
         // Group description (code 300)
         self.writer.write_string(300, &group.description)?;
@@ -2098,7 +2610,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         self.writer.write_string(0, "MLINESTYLE")?;
         self.writer.write_handle(5, style.handle)?;
         self.writer.write_handle(330, style.owner)?;
-        self.writer.write_subclass("AcDbMlineStyle")?;
+        self.write_subclass("AcDbMlineStyle")?;
 
         // Style name (code 2)
         self.writer.write_string(2, &style.name)?;
@@ -2151,8 +2663,15 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     fn write_image_definition(&mut self, imagedef: &ImageDefinition) -> Result<()> {
         self.writer.write_string(0, "IMAGEDEF")?;
         self.writer.write_handle(5, imagedef.handle)?;
+        if !imagedef.reactors.is_empty() {
+            self.writer.write_string(102, "{ACAD_REACTORS")?;
+            for reactor in &imagedef.reactors {
+                self.writer.write_handle(330, *reactor)?;
+            }
+            self.writer.write_string(102, "}")?;
+        }
         self.writer.write_handle(330, imagedef.owner)?;
-        self.writer.write_subclass("AcDbRasterImageDef")?;
+        self.write_subclass("AcDbRasterImageDef")?;
 
         // Class version (code 90)
         self.writer.write_i32(90, imagedef.class_version)?;
@@ -2185,8 +2704,12 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         self.writer.write_string(0, "PLOTSETTINGS")?;
         self.writer.write_handle(5, settings.handle)?;
         self.writer.write_handle(330, settings.owner)?;
-        self.writer.write_subclass("AcDbPlotSettings")?;
+        self.write_subclass("AcDbPlotSettings")?;
+        self.write_plot_settings_data(settings)
+    }
 
+    /// Write the AcDbPlotSettings data shared by PLOTSETTINGS and LAYOUT
+    fn write_plot_settings_data(&mut self, settings: &PlotSettings) -> Result<()> {
         // Page setup name (code 1)
         self.writer.write_string(1, &settings.page_name)?;
 
@@ -2264,7 +2787,10 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         self.writer.write_string(0, "MLEADERSTYLE")?;
         self.writer.write_handle(5, style.handle)?;
         self.writer.write_handle(330, style.owner_handle)?;
-        self.writer.write_subclass("AcDbMLeaderStyle")?;
+        self.write_subclass("AcDbMLeaderStyle")?;
+
+        // Version
+        self.writer.write_i16(179, 2)?;
 
         // Content type
         self.writer.write_i16(170, style.content_type as i16)?;
@@ -2288,7 +2814,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         self.writer.write_i16(173, style.path_type as i16)?;
 
         // Leader line color
-        self.write_color_i32(91, style.line_color)?;
+        self.writer.write_i32(91, style.line_color.cm_value())?;
 
         // Leader line type handle
         if let Some(h) = style.line_type_handle {
@@ -2342,7 +2868,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         self.writer.write_i16(178, style.text_right_attachment as i16)?;
 
         // Text color
-        self.write_color_i32(93, style.text_color)?;
+        self.writer.write_i32(93, style.text_color.cm_value())?;
 
         // Text height
         self.writer.write_double(45, style.text_height)?;
@@ -2362,7 +2888,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         }
 
         // Block content color
-        self.write_color_i32(94, style.block_content_color)?;
+        self.writer.write_i32(94, style.block_content_color.cm_value())?;
 
         // Block content scale (x, y, z)
         self.writer.write_double(47, style.block_content_scale_x)?;
@@ -2393,6 +2919,11 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         // Break gap size
         self.writer.write_double(143, style.break_gap_size)?;
 
+        // Text attachment direction, bottom and top attachment types
+        self.writer.write_i16(271, style.text_attachment_direction as i16)?;
+        self.writer.write_i16(272, style.text_bottom_attachment as i16)?;
+        self.writer.write_i16(273, style.text_top_attachment as i16)?;
+
         Ok(())
     }
 
@@ -2401,7 +2932,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         self.writer.write_string(0, "TABLESTYLE")?;
         self.writer.write_handle(5, style.handle)?;
         self.writer.write_handle(330, style.owner_handle)?;
-        self.writer.write_subclass("AcDbTableStyle")?;
+        self.write_subclass("AcDbTableStyle")?;
 
         // Version
         self.writer.write_byte(280, style.version as u8)?;
@@ -2429,36 +2960,40 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         // Header suppressed
         self.writer.write_byte(281, style.header_suppressed as u8)?;
 
-        // Write cell style info for data row
-        self.write_table_cell_style("DATA", &style.data_row_style)?;
-
-        // Write cell style info for header row
-        self.write_table_cell_style("HEADER", &style.header_row_style)?;
-
-        // Write cell style info for title row
-        self.write_table_cell_style("TITLE", &style.title_row_style)?;
+        // Cell styles, in data, header, title order
+        self.write_table_cell_style(&style.data_row_style)?;
+        self.write_table_cell_style(&style.header_row_style)?;
+        self.write_table_cell_style(&style.title_row_style)?;
 
         Ok(())
     }
 
-    /// Helper to write table cell style
-    fn write_table_cell_style(&mut self, name: &str, style: &crate::objects::RowCellStyle) -> Result<()> {
-        // Cell type indicator - simplified for basic support
+    /// Helper to write the cell style of one row type
+    fn write_table_cell_style(&mut self, style: &crate::objects::RowCellStyle) -> Result<()> {
         self.writer.write_string(7, &style.text_style_name)?;
         self.writer.write_double(140, style.text_height)?;
         self.writer.write_i16(170, style.alignment as i16)?;
-        
-        // Text color
         self.write_color_i16(62, style.text_color)?;
-
-        // Fill color
         self.write_color_i16(63, style.fill_color)?;
-
-        // Fill enabled
         self.writer.write_byte(283, style.fill_enabled as u8)?;
+        self.writer.write_i32(90, style.data_type)?;
+        self.writer.write_i32(91, style.unit_type)?;
+        if !style.format_string.is_empty() {
+            self.writer.write_string(1, &style.format_string)?;
+        }
+
+        // Borders: lineweights, visibility, then colors
+        let borders = style.borders();
+        for (i, border) in borders.iter().enumerate() {
+            self.writer.write_i16(274 + i as i32, border.line_weight.value())?;
+        }
+        for (i, border) in borders.iter().enumerate() {
+            self.writer.write_byte(284 + i as i32, !border.is_invisible as u8)?;
+        }
+        for (i, border) in borders.iter().enumerate() {
+            self.write_color_i16(64 + i as i32, border.color)?;
+        }
 
-        let _ = name; // Name is for future use in extended format
-        
         Ok(())
     }
 
@@ -2467,7 +3002,10 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         self.writer.write_string(0, "SCALE")?;
         self.writer.write_handle(5, scale.handle)?;
         self.writer.write_handle(330, scale.owner_handle)?;
-        self.writer.write_subclass("AcDbScale")?;
+        self.write_subclass("AcDbScale")?;
+
+        // Unknown, always 0
+        self.writer.write_i16(70, 0)?;
 
         // Scale name
         self.writer.write_string(300, &scale.name)?;
@@ -2489,7 +3027,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         self.writer.write_string(0, "SORTENTSTABLE")?;
         self.writer.write_handle(5, table.handle)?;
         self.writer.write_handle(330, table.owner_handle)?;
-        self.writer.write_subclass("AcDbSortentsTable")?;
+        self.write_subclass("AcDbSortentsTable")?;
 
         // Block owner handle
         self.writer.write_handle(330, table.block_owner_handle)?;
@@ -2508,7 +3046,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         self.writer.write_string(0, "DICTIONARYVAR")?;
         self.writer.write_handle(5, var.handle)?;
         self.writer.write_handle(330, var.owner_handle)?;
-        self.writer.write_subclass("DictionaryVariables")?;
+        self.write_subclass("DictionaryVariables")?;
 
         // Schema number
         self.writer.write_byte(280, var.schema_number as u8)?;
@@ -2524,7 +3062,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         self.writer.write_string(0, "VISUALSTYLE")?;
         self.writer.write_handle(5, obj.handle)?;
         self.writer.write_handle(330, obj.owner)?;
-        self.writer.write_subclass("AcDbVisualStyle")?;
+        self.write_subclass("AcDbVisualStyle")?;
         self.writer.write_string(2, &obj.description)?;
         self.writer.write_i16(70, obj.style_type)?;
         self.writer.write_i16(71, obj.face_lighting_model)?;
@@ -2544,7 +3082,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         self.writer.write_string(0, "MATERIAL")?;
         self.writer.write_handle(5, obj.handle)?;
         self.writer.write_handle(330, obj.owner)?;
-        self.writer.write_subclass("AcDbMaterial")?;
+        self.write_subclass("AcDbMaterial")?;
         self.writer.write_string(1, &obj.name)?;
         if !obj.description.is_empty() {
             self.writer.write_string(2, &obj.description)?;
@@ -2557,7 +3095,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         self.writer.write_string(0, "IMAGEDEF_REACTOR")?;
         self.writer.write_handle(5, obj.handle)?;
         self.writer.write_handle(330, obj.owner)?;
-        self.writer.write_subclass("AcDbRasterImageDefReactor")?;
+        self.write_subclass("AcDbRasterImageDefReactor")?;
         self.writer.write_i32(90, 2)?; // class version
         self.writer.write_handle(330, obj.image_handle)?;
         Ok(())
@@ -2568,7 +3106,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         self.writer.write_string(0, "RASTERVARIABLES")?;
         self.writer.write_handle(5, obj.handle)?;
         self.writer.write_handle(330, obj.owner)?;
-        self.writer.write_subclass("AcDbRasterVariables")?;
+        self.write_subclass("AcDbRasterVariables")?;
         self.writer.write_i32(90, obj.class_version)?;
         self.writer.write_i16(70, obj.display_image_frame)?;
         self.writer.write_i16(71, obj.image_quality)?;
@@ -2576,12 +3114,56 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         Ok(())
     }
 
+    /// Write a GEODATA object
+    fn write_geodata(&mut self, obj: &GeoData) -> Result<()> {
+        self.writer.write_string(0, "GEODATA")?;
+        self.writer.write_handle(5, obj.handle)?;
+        self.writer.write_handle(330, obj.owner)?;
+        self.write_subclass("AcDbGeoData")?;
+        self.writer.write_i32(90, obj.version)?;
+        self.writer.write_handle(330, obj.host_block)?;
+        self.writer.write_i16(70, obj.coordinate_type)?;
+        self.writer.write_point3d(10, obj.design_point)?;
+        self.writer.write_point3d(11, obj.reference_point)?;
+        self.writer.write_double(40, obj.horizontal_unit_scale)?;
+        self.writer.write_i32(91, obj.horizontal_units)?;
+        self.writer.write_double(41, obj.vertical_unit_scale)?;
+        self.writer.write_i32(92, obj.vertical_units)?;
+        self.writer.write_point3d(210, obj.up_direction)?;
+        self.writer.write_point2d(12, obj.north_direction)?;
+        // Long definitions go out in 255-character 303 chunks, the last as 301.
+        // Values are trimmed on read, so never split next to whitespace.
+        let mut chars: &[char] = &obj.coordinate_system.chars().collect::<Vec<_>>();
+        let mut chunks: Vec<String> = Vec::new();
+        while chars.len() > 255 {
+            let mut at = 255;
+            while at > 1 && (chars[at].is_whitespace() || chars[at - 1].is_whitespace()) {
+                at -= 1;
+            }
+            chunks.push(chars[..at].iter().collect());
+            chars = &chars[at..];
+        }
+        if !chars.is_empty() {
+            chunks.push(chars.iter().collect());
+        }
+        for (i, chunk) in chunks.iter().enumerate() {
+            let code = if i + 1 == chunks.len() { 301 } else { 303 };
+            self.writer.write_string(code, chunk)?;
+        }
+        if chunks.is_empty() {
+            self.writer.write_string(301, "")?;
+        }
+        self.writer.write_i32(93, 0)?;
+        self.writer.write_i32(96, 0)?;
+        Ok(())
+    }
+
     /// Write a DBCOLOR object
     fn write_bookcolor(&mut self, obj: &BookColor) -> Result<()> {
         self.writer.write_string(0, "DBCOLOR")?;
         self.writer.write_handle(5, obj.handle)?;
         self.writer.write_handle(330, obj.owner)?;
-        self.writer.write_subclass("AcDbColor")?;
+        self.write_subclass("AcDbColor")?;
         if !obj.color_name.is_empty() {
             self.writer.write_string(1, &obj.color_name)?;
         }
@@ -2591,18 +3173,162 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         Ok(())
     }
 
+    /// Write a SUN object
+    fn write_sun(&mut self, obj: &Sun) -> Result<()> {
+        self.writer.write_string(0, "SUN")?;
+        self.writer.write_handle(5, obj.handle)?;
+        self.writer.write_handle(330, obj.owner)?;
+        self.write_subclass("AcDbSun")?;
+        self.writer.write_i32(90, obj.version)?;
+        self.writer.write_bool(290, obj.is_on)?;
+        self.write_color_i16(63, obj.color)?;
+        if let Color::Rgb { .. } = obj.color {
+            self.write_color_i32(421, obj.color)?;
+        }
+        self.writer.write_double(40, obj.intensity)?;
+        self.writer.write_bool(291, obj.has_shadow)?;
+        self.writer.write_i32(91, obj.julian_day)?;
+        self.writer.write_i32(92, obj.time)?;
+        self.writer.write_bool(292, obj.daylight_savings)?;
+        self.writer.write_i16(70, obj.shadow_type)?;
+        self.writer.write_i16(71, obj.shadow_map_size)?;
+        self.writer.write_byte(280, obj.shadow_softness as u8)?;
+        Ok(())
+    }
+
+    fn write_dim_assoc(&mut self, obj: &DimAssoc) -> Result<()> {
+        self.writer.write_string(0, "DIMASSOC")?;
+        self.writer.write_handle(5, obj.handle)?;
+        self.write_persistent_groups(&obj.reactors, None)?;
+        self.writer.write_handle(330, obj.owner)?;
+        self.write_subclass("AcDbDimAssoc")?;
+        self.writer.write_handle(330, obj.dimension)?;
+        self.writer.write_i32(90, obj.associativity())?;
+        self.writer.write_i16(70, obj.trans_space as i16)?;
+        self.writer.write_i16(71, obj.rotated_type)?;
+        for point in obj.points.iter().flatten() {
+            self.writer.write_string(1, "AcDbOsnapPointRef")?;
+            self.writer.write_i16(72, point.osnap_type.to_code())?;
+            for object in &point.objects {
+                self.writer.write_handle(331, *object)?;
+            }
+            self.writer.write_i16(73, point.subentity_type)?;
+            self.writer.write_i32(91, point.gs_marker)?;
+            self.writer.write_double(40, point.near_parameter)?;
+            self.writer.write_point3d(10, point.point)?;
+            for object in &point.intersection_objects {
+                self.writer.write_handle(332, *object)?;
+            }
+            self.writer.write_i16(74, point.intersection_subentity_type)?;
+            self.writer.write_i32(92, point.intersection_gs_marker)?;
+            self.writer.write_i16(75, point.has_last_point_ref as i16)?;
+        }
+        Ok(())
+    }
+
+    /// Write a background object (SOLID_BACKGROUND, SKYLIGHT_BACKGROUND, ...)
+    fn write_background(&mut self, obj: &Background) -> Result<()> {
+        self.writer.write_string(0, obj.kind.type_name())?;
+        self.writer.write_handle(5, obj.handle)?;
+        self.writer.write_handle(330, obj.owner)?;
+        self.write_subclass("AcDbBackground")?;
+        self.writer.write_i32(90, obj.version)?;
+        self.write_subclass(obj.kind.subclass())?;
+        match &obj.kind {
+            BackgroundKind::Solid { color } => {
+                self.writer.write_i32(90, obj.version)?;
+                self.writer.write_i32(90, color.cm_value())?;
+            }
+            BackgroundKind::Gradient { top, middle, bottom, horizon, height, rotation } => {
+                self.writer.write_i32(90, obj.version)?;
+                self.writer.write_i32(90, top.cm_value())?;
+                self.writer.write_i32(90, middle.cm_value())?;
+                self.writer.write_i32(90, bottom.cm_value())?;
+                self.writer.write_double(140, *horizon)?;
+                self.writer.write_double(141, *height)?;
+                self.writer.write_double(142, *rotation)?;
+            }
+            BackgroundKind::Image { file_name, fit_to_screen, maintain_aspect_ratio, use_tiling, offset, scale } => {
+                self.writer.write_i32(90, obj.version)?;
+                self.writer.write_string(300, file_name)?;
+                self.writer.write_bool(290, *fit_to_screen)?;
+                self.writer.write_bool(291, *maintain_aspect_ratio)?;
+                self.writer.write_bool(292, *use_tiling)?;
+                self.writer.write_double(140, offset.x)?;
+                self.writer.write_double(141, offset.y)?;
+                self.writer.write_double(142, scale.x)?;
+                self.writer.write_double(143, scale.y)?;
+            }
+            BackgroundKind::Sky { sun } => {
+                self.writer.write_i32(90, obj.version)?;
+                self.writer.write_handle(340, *sun)?;
+            }
+            BackgroundKind::Other { data, .. } => {
+                for (code, value) in data {
+                    self.write_raw_pair(*code, value)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Write a RENDERSETTINGS, MENTALRAYRENDERSETTINGS or
+    /// RAPIDRTRENDERSETTINGS object
+    fn write_render_settings(&mut self, obj: &RenderSettings) -> Result<()> {
+        self.writer.write_string(0, &obj.type_name)?;
+        self.writer.write_handle(5, obj.handle)?;
+        self.writer.write_handle(330, obj.owner)?;
+        self.write_subclass("AcDbRenderSettings")?;
+        self.writer.write_i32(90, obj.version)?;
+        self.writer.write_string(1, &obj.name)?;
+        self.writer.write_bool(290, obj.fog_enabled)?;
+        self.writer.write_bool(290, obj.fog_background_enabled)?;
+        self.writer.write_bool(290, obj.back_faces_enabled)?;
+        self.writer.write_bool(290, obj.environment_image_enabled)?;
+        self.writer.write_string(1, &obj.environment_image_file_name)?;
+        self.writer.write_string(1, &obj.description)?;
+        self.writer.write_i32(90, obj.display_index)?;
+        self.writer.write_bool(290, obj.predefined)?;
+        if !obj.subclass.is_empty() {
+            self.write_subclass(&obj.subclass)?;
+            for (code, value) in &obj.data {
+                self.write_raw_pair(*code, value)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Write a PDFDEFINITION, DWFDEFINITION or DGNDEFINITION object
+    fn write_underlay_definition(&mut self, obj: &UnderlayDefinition) -> Result<()> {
+        self.writer.write_string(0, obj.entity_name())?;
+        self.writer.write_handle(5, obj.handle)?;
+        if !obj.reactors.is_empty() {
+            self.writer.write_string(102, "{ACAD_REACTORS")?;
+            for reactor in &obj.reactors {
+                self.writer.write_handle(330, *reactor)?;
+            }
+            self.writer.write_string(102, "}")?;
+        }
+        self.writer.write_handle(330, obj.owner_handle)?;
+        self.write_subclass("AcDbUnderlayDefinition")?;
+        self.writer.write_string(1, &obj.file_path)?;
+        self.writer.write_string(2, &obj.page_name)?;
+        Ok(())
+    }
+
     /// Write an ACDBDICTIONARYWDFLT object
     fn write_dict_with_default(&mut self, obj: &DictionaryWithDefault) -> Result<()> {
         self.writer.write_string(0, "ACDBDICTIONARYWDFLT")?;
         self.writer.write_handle(5, obj.handle)?;
         self.writer.write_handle(330, obj.owner)?;
-        self.writer.write_subclass("AcDbDictionary")?;
-        self.writer.write_i16(281, obj.duplicate_cloning)?;
+        self.write_subclass("AcDbDictionary")?;
+        self.writer.write_byte(280, if obj.hard_owner { 1 } else { 0 })?;
+        self.writer.write_byte(281, obj.duplicate_cloning as u8)?;
         for (key, handle) in &obj.entries {
             self.writer.write_string(3, key)?;
             self.writer.write_handle(350, *handle)?;
         }
-        self.writer.write_subclass("AcDbDictionaryWithDefault")?;
+        self.write_subclass("AcDbDictionaryWithDefault")?;
         self.writer.write_handle(340, obj.default_handle)?;
         Ok(())
     }
@@ -2612,7 +3338,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         self.writer.write_string(0, "WIPEOUTVARIABLES")?;
         self.writer.write_handle(5, obj.handle)?;
         self.writer.write_handle(330, obj.owner)?;
-        self.writer.write_subclass("AcDbWipeoutVariables")?;
+        self.write_subclass("AcDbWipeoutVariables")?;
         self.writer.write_i16(70, obj.display_frame)?;
         Ok(())
     }
@@ -2651,7 +3377,6 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     }
 
     /// Write extended data (XDATA)
-    #[allow(dead_code)]
     fn write_xdata(&mut self, xdata: &ExtendedData) -> Result<()> {
         if xdata.is_empty() {
             return Ok(());
@@ -2723,13 +3448,13 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     fn write_multileader(&mut self, mleader: &crate::entities::MultiLeader, owner: Handle) -> Result<()> {
         self.writer.write_entity_type("MULTILEADER")?;
         self.write_common_entity_data(&mleader.common, owner)?;
-        self.writer.write_subclass("AcDbMLeader")?;
+        self.write_subclass("AcDbMLeader")?;
 
         // Class version (hardcoded to 2 for R2010+)
         self.writer.write_i16(270, 2)?;
 
         // Context data - write the annotation context
-        self.writer.write_subclass("CONTEXT_DATA{")?;
+        self.writer.write_string(300, "CONTEXT_DATA{")?;
 
         // Content scale
         self.writer.write_double(40, mleader.context.scale_factor)?;
@@ -2886,7 +3611,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         
         self.writer.write_entity_type("MLINE")?;
         self.write_common_entity_data(&mline.common, owner)?;
-        self.writer.write_subclass("AcDbMline")?;
+        self.write_subclass("AcDbMline")?;
 
         // Style name
         self.writer.write_string(2, &mline.style_name)?;
@@ -2954,7 +3679,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     fn write_mesh(&mut self, mesh: &crate::entities::Mesh, owner: Handle) -> Result<()> {
         self.writer.write_entity_type("MESH")?;
         self.write_common_entity_data(&mesh.common, owner)?;
-        self.writer.write_subclass("AcDbSubDMesh")?;
+        self.write_subclass("AcDbSubDMesh")?;
 
         // Version
         self.writer.write_i16(71, mesh.version)?;
@@ -2986,7 +3711,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         }
 
         // Edge count
-        self.writer.write_i32(94, (mesh.edges.len() * 2) as i32)?;
+        self.writer.write_i32(94, mesh.edges.len() as i32)?;
 
         // Edges: start_index, end_index pairs
         for edge in &mesh.edges {
@@ -2994,18 +3719,20 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
             self.writer.write_i32(90, edge.end as i32)?;
         }
 
-        // Edge crease count
-        let creased_edges: Vec<_> = mesh.edges.iter().enumerate()
-            .filter(|(_, e)| e.has_crease())
-            .collect();
-        self.writer.write_i32(95, creased_edges.len() as i32)?;
-
-        // Edge creases: index, crease_value pairs
-        for (idx, edge) in creased_edges {
-            self.writer.write_i32(90, idx as i32)?;
-            self.writer.write_double(140, edge.crease_value())?;
+        // Edge creases, one per edge in edge order (0 for none) when any
+        // edge has one
+        if mesh.has_creases() {
+            self.writer.write_i32(95, mesh.edges.len() as i32)?;
+            for edge in &mesh.edges {
+                self.writer.write_double(140, edge.crease_value())?;
+            }
+        } else {
+            self.writer.write_i32(95, 0)?;
         }
 
+        // Sub-entity property overrides: none
+        self.writer.write_i32(90, 0)?;
+
         Ok(())
     }
 
@@ -3013,7 +3740,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     fn write_raster_image(&mut self, image: &crate::entities::RasterImage, owner: Handle) -> Result<()> {
         self.writer.write_entity_type("IMAGE")?;
         self.write_common_entity_data(&image.common, owner)?;
-        self.writer.write_subclass("AcDbRasterImage")?;
+        self.write_subclass("AcDbRasterImage")?;
 
         // Class version
         self.writer.write_i32(90, image.class_version)?;
@@ -3068,6 +3795,11 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
             self.writer.write_double(24, v.y)?;
         }
 
+        // Clip mode, showing the outside of the boundary when set
+        if image.clip_boundary.clip_mode == ClipMode::Inside {
+            self.writer.write_bool(290, true)?;
+        }
+
         Ok(())
     }
 
@@ -3075,7 +3807,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     fn write_solid3d(&mut self, solid: &Solid3D, owner: Handle) -> Result<()> {
         self.writer.write_entity_type("3DSOLID")?;
         self.write_common_entity_data(&solid.common, owner)?;
-        self.writer.write_subclass("AcDbModelerGeometry")?;
+        self.write_subclass("AcDbModelerGeometry")?;
 
         // Version
         self.writer.write_i16(70, solid.acis_data.version as i16)?;
@@ -3083,11 +3815,12 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         // Write ACIS data
         self.write_acis_data(&solid.acis_data)?;
 
-        self.writer.write_subclass("AcDb3dSolid")?;
-
-        // History handle
-        if let Some(h) = solid.history_handle {
-            self.writer.write_handle(350, h)?;
+        // History handle, in the AcDb3dSolid part of AutoCAD 2007 and later
+        if subclass::is_written("AcDb3dSolid", self.version) {
+            self.write_subclass("AcDb3dSolid")?;
+            if let Some(h) = solid.history_handle {
+                self.writer.write_handle(350, h)?;
+            }
         }
 
         Ok(())
@@ -3097,7 +3830,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     fn write_region(&mut self, region: &Region, owner: Handle) -> Result<()> {
         self.writer.write_entity_type("REGION")?;
         self.write_common_entity_data(&region.common, owner)?;
-        self.writer.write_subclass("AcDbModelerGeometry")?;
+        self.write_subclass("AcDbModelerGeometry")?;
 
         // Version
         self.writer.write_i16(70, region.acis_data.version as i16)?;
@@ -3112,7 +3845,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     fn write_body(&mut self, body: &Body, owner: Handle) -> Result<()> {
         self.writer.write_entity_type("BODY")?;
         self.write_common_entity_data(&body.common, owner)?;
-        self.writer.write_subclass("AcDbModelerGeometry")?;
+        self.write_subclass("AcDbModelerGeometry")?;
 
         // Version
         self.writer.write_i16(70, body.acis_data.version as i16)?;
@@ -3164,131 +3897,85 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         Ok(())
     }
 
-    /// Write ACAD_TABLE entity
+    /// Write ACAD_TABLE entity.
+    ///
+    /// The table draws through its anonymous `*T` block, referenced by
+    /// name like an INSERT (code 2) and by handle (code 343); cells follow
+    /// the column widths, row by row.
     fn write_acad_table(&mut self, table: &table::Table, owner: Handle) -> Result<()> {
         self.writer.write_entity_type("ACAD_TABLE")?;
         self.write_common_entity_data(&table.common, owner)?;
-        self.writer.write_subclass("AcDbBlockReference")?;
+        self.write_subclass("AcDbBlockReference")?;
 
-        // Block record handle
-        if let Some(h) = table.block_record_handle {
-            self.writer.write_handle(2, h)?;
+        let block_name = table.block_record_handle.and_then(|h| self.block_names.get(&h)).cloned();
+        if let Some(name) = &block_name {
+            self.writer.write_string(2, name)?;
         }
-
-        // Insertion point
         self.writer.write_point3d(10, table.insertion_point)?;
 
-        self.writer.write_subclass("AcDbTable")?;
-
-        // Table style handle
+        self.write_subclass("AcDbTable")?;
+        self.writer.write_byte(280, table.data_version as u8)?;
         if let Some(h) = table.table_style_handle {
             self.writer.write_handle(342, h)?;
         }
-
-        // Data version
-        self.writer.write_byte(280, table.data_version as u8)?;
-
-        // Horizontal direction
+        if let (Some(h), Some(_)) = (table.block_record_handle, &block_name) {
+            self.writer.write_handle(343, h)?;
+        }
         self.writer.write_point3d(11, table.horizontal_direction)?;
-
-        // Number of rows
+        self.writer.write_i32(90, table.value_flags)?;
         self.writer.write_i32(91, table.rows.len() as i32)?;
-
-        // Number of columns
         self.writer.write_i32(92, table.columns.len() as i32)?;
+        self.writer.write_i32(93, table.override_flag as i32)?;
+        self.writer.write_i32(94, table.override_border_color as i32)?;
+        self.writer.write_i32(95, table.override_border_line_weight as i32)?;
+        self.writer.write_i32(96, table.override_border_visibility as i32)?;
 
-        // Override flags
-        let mut override_flags = 0i32;
-        if table.override_flag { override_flags |= 1; }
-        if table.override_border_color { override_flags |= 2; }
-        if table.override_border_line_weight { override_flags |= 4; }
-        if table.override_border_visibility { override_flags |= 8; }
-        self.writer.write_i32(93, override_flags)?;
-
-        // Row heights
         for row in &table.rows {
             self.writer.write_double(141, row.height)?;
         }
-
-        // Column widths
         for col in &table.columns {
             self.writer.write_double(142, col.width)?;
         }
 
-        // Write cells
+        let empty = TableCell::new();
         for row in &table.rows {
-            for cell in &row.cells {
-                self.write_table_cell(cell)?;
+            for index in 0..table.columns.len() {
+                self.write_table_cell(row.cells.get(index).unwrap_or(&empty))?;
             }
         }
 
-        // Break options
-        self.writer.write_i32(94, table.break_options.bits() as i32)?;
-        self.writer.write_i32(95, table.break_flow_direction as i32)?;
-        self.writer.write_double(143, table.break_spacing)?;
-
         Ok(())
     }
 
-    /// Write table cell data
+    /// Write the data of one table cell
     fn write_table_cell(&mut self, cell: &TableCell) -> Result<()> {
-        // Cell type
         self.writer.write_i16(171, cell.cell_type as i16)?;
-
-        // Cell state flags
-        self.writer.write_i16(172, cell.state.bits() as i16)?;
-
-        // Cell flags
-        self.writer.write_i16(173, cell.flag as i16)?;
-
-        // Merged dimensions
-        self.writer.write_i16(174, cell.merged as i16)?;
-        self.writer.write_i16(175, cell.merge_width as i16)?;
-        self.writer.write_i16(176, cell.merge_height as i16)?;
-
-        // Virtual edge flag
-        self.writer.write_i16(177, cell.virtual_edge)?;
-
-        // Rotation
-        self.writer.write_double(144, cell.rotation)?;
-
-        // Contents count
-        self.writer.write_i16(179, cell.contents.len() as i16)?;
-
-        // Write cell contents
-        for content in &cell.contents {
-            self.writer.write_i16(170, content.content_type as i16)?;
-
-            // Write value based on type
-            match content.value.value_type {
-                CellValueType::String => {
-                    self.writer.write_string(1, &content.value.text)?;
-                }
-                CellValueType::Double => {
-                    self.writer.write_double(140, content.value.numeric_value)?;
+        self.writer.write_i16(172, cell.flag as i16)?;
+        self.writer.write_i16(173, cell.merged as i16)?;
+        self.writer.write_bool(174, cell.auto_fit)?;
+        self.writer.write_i32(175, cell.merge_width.max(1))?;
+        self.writer.write_i32(176, cell.merge_height.max(1))?;
+        self.writer.write_i32(91, 0)?;
+        self.writer.write_i16(178, cell.virtual_edge)?;
+        self.writer.write_double(145, cell.rotation.to_degrees())?;
+
+        let content = cell.content();
+        match cell.cell_type {
+            CellType::Block => {
+                if let Some(h) = content.and_then(|c| c.block_handle) {
+                    self.writer.write_handle(340, h)?;
                 }
-                CellValueType::Long => {
-                    self.writer.write_i32(90, content.value.numeric_value as i32)?;
-                }
-                _ => {}
-            }
-
-            // Format string
-            if !content.value.format.is_empty() {
-                self.writer.write_string(300, &content.value.format)?;
+                self.writer.write_double(144, content.map(|c| c.scale).unwrap_or(1.0))?;
+                self.writer.write_i16(179, 0)?;
             }
-
-            // Block handle
-            if let Some(h) = content.block_handle {
-                self.writer.write_handle(340, h)?;
+            CellType::Text => {
+                self.writer.write_string(1, cell.text_value())?;
+                if let Some(content) = content {
+                    self.writer.write_double(140, content.text_height)?;
+                }
             }
         }
-
-        // Cell style
-        if let Some(ref style) = cell.style {
-            self.writer.write_color(62, style.content_color)?;
-            self.writer.write_double(140, style.text_height)?;
-            self.writer.write_double(144, style.rotation)?;
+        if let Some(style) = &cell.style {
             self.writer.write_i16(170, style.alignment as i16)?;
         }
 
@@ -3299,7 +3986,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     fn write_tolerance(&mut self, tolerance: &Tolerance, owner: Handle) -> Result<()> {
         self.writer.write_entity_type("TOLERANCE")?;
         self.write_common_entity_data(&tolerance.common, owner)?;
-        self.writer.write_subclass("AcDbFcf")?;
+        self.write_subclass("AcDbFcf")?;
 
         // Dimension style name
         self.writer.write_string(3, &tolerance.dimension_style_name)?;
@@ -3329,7 +4016,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     fn write_polyface_mesh(&mut self, mesh: &PolyfaceMesh, owner: Handle) -> Result<()> {
         self.writer.write_entity_type("POLYLINE")?;
         self.write_common_entity_data(&mesh.common, owner)?;
-        self.writer.write_subclass("AcDbPolyFaceMesh")?;
+        self.write_subclass("AcDbPolyFaceMesh")?;
 
         // Entities follow flag (VERTEX records follow)
         self.writer.write_i16(66, 1)?;
@@ -3357,10 +4044,10 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
             self.writer.write_entity_type("VERTEX")?;
             self.writer.write_handle(5, vertex_handle)?;
             self.writer.write_handle(330, mesh.common.handle)?;
-            self.writer.write_subclass("AcDbEntity")?;
+            self.write_subclass("AcDbEntity")?;
             self.writer.write_string(8, &vertex.common.layer)?;
-            self.writer.write_subclass("AcDbVertex")?;
-            self.writer.write_subclass("AcDbPolyFaceMeshVertex")?;
+            self.write_subclass("AcDbVertex")?;
+            self.write_subclass("AcDbPolyFaceMeshVertex")?;
 
             self.writer.write_double(10, vertex.location.x)?;
             self.writer.write_double(20, vertex.location.y)?;
@@ -3380,9 +4067,9 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
             self.writer.write_entity_type("VERTEX")?;
             self.writer.write_handle(5, face_handle)?;
             self.writer.write_handle(330, mesh.common.handle)?;
-            self.writer.write_subclass("AcDbEntity")?;
+            self.write_subclass("AcDbEntity")?;
             self.writer.write_string(8, &face.common.layer)?;
-            self.writer.write_subclass("AcDbFaceRecord")?;
+            self.write_subclass("AcDbFaceRecord")?;
 
             // Dummy position
             self.writer.write_double(10, 0.0)?;
@@ -3409,8 +4096,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         let seqend_handle = mesh.seqend_handle.unwrap_or_else(|| self.allocate_handle());
         self.writer.write_handle(5, seqend_handle)?;
         self.writer.write_handle(330, mesh.common.handle)?;
-        self.writer.write_subclass("AcDbEntity")?;
-        self.writer.write_subclass("AcDbSequenceEnd")?;
+        self.write_subclass("AcDbEntity")?;
         self.writer.write_string(8, &mesh.common.layer)?;
 
         Ok(())
@@ -3420,7 +4106,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     fn write_wipeout(&mut self, wipeout: &Wipeout, owner: Handle) -> Result<()> {
         self.writer.write_entity_type("WIPEOUT")?;
         self.write_common_entity_data(&wipeout.common, owner)?;
-        self.writer.write_subclass("AcDbWipeout")?;
+        self.write_subclass("AcDbWipeout")?;
 
         // Class version
         self.writer.write_i32(90, wipeout.class_version)?;
@@ -3472,7 +4158,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     fn write_shape(&mut self, shape: &Shape, owner: Handle) -> Result<()> {
         self.writer.write_entity_type("SHAPE")?;
         self.write_common_entity_data(&shape.common, owner)?;
-        self.writer.write_subclass("AcDbShape")?;
+        self.write_subclass("AcDbShape")?;
 
         // Thickness
         if shape.thickness.abs() > 1e-10 {
@@ -3519,7 +4205,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     fn write_underlay(&mut self, underlay: &Underlay, owner: Handle) -> Result<()> {
         self.writer.write_entity_type(underlay.entity_name())?;
         self.write_common_entity_data(&underlay.common, owner)?;
-        self.writer.write_subclass("AcDbUnderlayReference")?;
+        self.write_subclass("AcDbUnderlayReference")?;
 
         // Definition handle
         self.writer.write_handle(340, underlay.definition_handle)?;
@@ -3542,8 +4228,12 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         self.writer.write_double(220, underlay.normal.y)?;
         self.writer.write_double(230, underlay.normal.z)?;
 
-        // Flags
-        self.writer.write_byte(280, underlay.flags.bits())?;
+        // Flags, with the clip inside mode of an inverted clip
+        let mut flags = underlay.flags;
+        if underlay.clip_inverted {
+            flags |= UnderlayDisplayFlags::CLIP_INSIDE;
+        }
+        self.writer.write_byte(280, flags.bits())?;
 
         // Contrast
         self.writer.write_byte(281, underlay.contrast)?;
@@ -3574,7 +4264,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     fn write_ole2frame(&mut self, ole: &Ole2Frame, owner: Handle) -> Result<()> {
         self.writer.write_entity_type("OLE2FRAME")?;
         self.write_common_entity_data(&ole.common, owner)?;
-        self.writer.write_subclass("AcDbOle2Frame")?;
+        self.write_subclass("AcDbOle2Frame")?;
         self.writer.write_i16(70, ole.version)?;
         if !ole.source_application.is_empty() {
             self.writer.write_string(3, &ole.source_application)?;
@@ -3606,7 +4296,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
 
         self.writer.write_entity_type("POLYLINE")?;
         self.write_common_entity_data(&mesh.common, owner)?;
-        self.writer.write_subclass("AcDbPolygonMesh")?;
+        self.write_subclass("AcDbPolygonMesh")?;
 
         // Entities follow flag (VERTEX records follow)
         self.writer.write_i16(66, 1)?;
@@ -3643,14 +4333,14 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
             self.writer.write_entity_type("VERTEX")?;
             self.writer.write_handle(5, vertex_handle)?;
             self.writer.write_handle(330, mesh_handle)?;
-            self.writer.write_subclass("AcDbEntity")?;
+            self.write_subclass("AcDbEntity")?;
             self.writer.write_string(8, &vertex.common.layer)?;
             // Propagate parent color to vertex so CAD doesn't flag mismatch
             if mesh.common.color != Color::ByLayer {
                 self.writer.write_color(62, mesh.common.color)?;
             }
-            self.writer.write_subclass("AcDbVertex")?;
-            self.writer.write_subclass("AcDbPolygonMeshVertex")?;
+            self.write_subclass("AcDbVertex")?;
+            self.write_subclass("AcDbPolygonMeshVertex")?;
             self.writer.write_point3d(10, vertex.location)?;
             if vertex.flags != 0 {
                 self.writer.write_i16(70, vertex.flags)?;
@@ -3662,14 +4352,30 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         self.writer.write_entity_type("SEQEND")?;
         self.writer.write_handle(5, seqend_handle)?;
         self.writer.write_handle(330, mesh_handle)?;
-        self.writer.write_subclass("AcDbEntity")?;
-        self.writer.write_subclass("AcDbSequenceEnd")?;
+        self.write_subclass("AcDbEntity")?;
         self.writer.write_string(8, &mesh.common.layer)?;
 
         Ok(())
     }
 }
 
+/// End of the common entity data in the raw pairs of an unknown entity, at
+/// its first subclass marker other than `AcDbEntity`, and the index of the
+/// owner pair within it
+fn unknown_entity_common_data(pairs: &[(i32, String)]) -> (usize, Option<usize>) {
+    let mut in_group = false;
+    let mut owner = None;
+    for (index, (code, value)) in pairs.iter().enumerate() {
+        match *code {
+            102 => in_group = value.trim_start().starts_with('{'),
+            100 if !in_group && value.trim() != "AcDbEntity" => return (index, owner),
+            330 if !in_group && owner.is_none() => owner = Some(index),
+            _ => {}
+        }
+    }
+    (pairs.len(), owner)
+}
+
 /// Helper to extract invisible edge bits
 fn get_invisible_edge_bits(flags: &InvisibleEdgeFlags) -> u8 {
     let mut bits = 0u8;
@@ -3679,13 +4385,3 @@ fn get_invisible_edge_bits(flags: &InvisibleEdgeFlags) -> u8 {
     if flags.is_fourth_invisible() { bits |= 8; }
     bits
 }
-
-/// Helper to extract boundary path flag bits
-fn get_boundary_path_bits(flags: &BoundaryPathFlags) -> u32 {
-    let mut bits = 0u32;
-    if flags.is_external() { bits |= 1; }
-    if flags.is_polyline() { bits |= 2; }
-    if flags.is_derived() { bits |= 4; }
-    bits
-}
-
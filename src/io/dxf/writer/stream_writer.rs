@@ -5,6 +5,20 @@ use crate::io::dxf::GroupCodeValueType;
 use crate::types::{Color, Handle, Vector2, Vector3};
 
 /// Trait for writing DXF code/value pairs
+///
+/// [`DxfTextWriter`](super::DxfTextWriter) and
+/// [`DxfBinaryWriter`](super::DxfBinaryWriter) implement it for ASCII and
+/// binary files, and [`DxfStreamWriterExt`] adds points, colors and
+/// section markers on top. Custom entity and object writers registered
+/// with [`DxfCustomTypes`](crate::io::dxf::DxfCustomTypes) are given one.
+///
+/// ```rust,ignore
+/// let mut writer = DxfTextWriter::new(Vec::new());
+/// writer.write_string(0, "MY_MARKER")?;
+/// writer.write_handle(5, Handle::new(0x2A))?;
+/// writer.write_subclass("AcDbEntity")?;
+/// writer.write_point3d(10, Vector3::new(1.0, 2.0, 0.0))?;
+/// ```
 pub trait DxfStreamWriter {
     /// Write a code/value pair with a string value
     fn write_string(&mut self, code: i32, value: &str) -> Result<()>;
@@ -35,6 +49,14 @@ pub trait DxfStreamWriter {
     
     /// Flush the writer
     fn flush(&mut self) -> Result<()>;
+
+    /// Set the character encoding used for string values.
+    ///
+    /// Called for pre-2007 output with the encoding matching $DWGCODEPAGE.
+    /// Implementations that don't support encoding changes can ignore this.
+    fn set_encoding(&mut self, _encoding: &'static encoding_rs::Encoding) {
+        // Default: no-op
+    }
 }
 
 /// Extension trait for convenient writing operations
@@ -97,7 +119,7 @@ pub trait DxfStreamWriterExt: DxfStreamWriter {
 }
 
 // Auto-implement the extension trait for all stream writers
-impl<T: DxfStreamWriter> DxfStreamWriterExt for T {}
+impl<T: DxfStreamWriter + ?Sized> DxfStreamWriterExt for T {}
 
 /// Helper to determine value type from code for writing
 pub fn value_type_for_code(code: i32) -> GroupCodeValueType {
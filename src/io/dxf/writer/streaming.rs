@@ -0,0 +1,345 @@
+//! Incremental DXF writer for generated drawings
+
+use super::{count_extra_handles, DxfBinaryWriter, DxfStreamWriter, DxfStreamWriterExt, DxfTextWriter, SectionWriter};
+use crate::document::CadDocument;
+use crate::entities::EntityType;
+use crate::error::{DxfError, Result};
+use crate::handles;
+use crate::io::dxf::{BinaryDxfDialect, DxfCustomTypes};
+use crate::tables::TableEntry;
+use crate::types::{DxfVersion, Handle};
+use std::collections::HashSet;
+use std::io::Write;
+
+/// Handles reserved for streamed entities when none is configured
+const DEFAULT_HANDLE_RESERVE: u64 = 0x1000_0000;
+
+/// DXF writer that accepts model space entities one at a time.
+///
+/// The HEADER, CLASSES, TABLES and BLOCKS sections are taken from a template
+/// document and written as soon as the first entity arrives (or on
+/// [`Self::begin`]). Each [`Self::write_entity`] call then goes straight to
+/// the underlying stream, and [`Self::finish`] closes the ENTITIES section and
+/// writes the template's OBJECTS. Only the template and the set of handles
+/// written are held in memory, so generators producing millions of entities
+/// never build a full document.
+///
+/// `$HANDSEED` precedes the entities in the file, so a range of handles is
+/// reserved for them up front (see [`Self::with_handle_reserve`]); running
+/// past it is an error, as is an explicit handle already in use.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let file = BufWriter::new(File::create("parts.dxf")?);
+/// let mut writer = DxfStreamingWriter::new(CadDocument::new(), file);
+/// for part in parts {
+///     writer.write_entity(&EntityType::LwPolyline(part.outline()))?;
+/// }
+/// writer.finish()?;
+/// ```
+pub struct DxfStreamingWriter<S: DxfStreamWriter> {
+    stream: S,
+    template: CadDocument,
+    next_handle: u64,
+    handle_reserve: u64,
+    handle_seed: u64,
+    model_space: Handle,
+    started: bool,
+    entity_count: usize,
+    custom_types: DxfCustomTypes,
+    /// Handles of the template and of the entities written so far
+    used_handles: HashSet<Handle>,
+}
+
+impl<W: Write> DxfStreamingWriter<DxfTextWriter<W>> {
+    /// Create a streaming writer producing ASCII DXF
+    pub fn new(template: CadDocument, writer: W) -> Self {
+        Self::with_stream(template, DxfTextWriter::new(writer))
+    }
+}
+
+impl<W: Write> DxfStreamingWriter<DxfBinaryWriter<W>> {
+    /// Create a streaming writer producing binary DXF in the dialect of the
+    /// template's version
+    pub fn new_binary(template: CadDocument, writer: W) -> Result<Self> {
+        let stream = DxfBinaryWriter::with_dialect(writer, BinaryDxfDialect::for_version(template.version))?;
+        Ok(Self::with_stream(template, stream))
+    }
+}
+
+impl<S: DxfStreamWriter> DxfStreamingWriter<S> {
+    fn with_stream(mut template: CadDocument, stream: S) -> Self {
+        template.ensure_defaults();
+        let model_space = template.block_records.get("*Model_Space")
+            .map(|b| b.handle())
+            .unwrap_or(Handle::new(0x1F));
+        Self {
+            stream,
+            next_handle: template.next_handle(),
+            handle_reserve: DEFAULT_HANDLE_RESERVE,
+            handle_seed: 0,
+            model_space,
+            started: false,
+            entity_count: 0,
+            custom_types: DxfCustomTypes::default(),
+            used_handles: HashSet::new(),
+            template,
+        }
+    }
+
+    /// Set how many handles to reserve for streamed entities (including
+    /// polyline vertices and SEQENDs). Defaults to `0x1000_0000`; must be
+    /// set before [`Self::begin`].
+    pub fn with_handle_reserve(mut self, reserve: u64) -> Self {
+        self.handle_reserve = reserve;
+        self
+    }
+
+    /// Write unknown entities and objects of the types `custom` has a
+    /// writer for with that writer
+    pub fn with_custom_types(mut self, custom: DxfCustomTypes) -> Self {
+        self.custom_types = custom;
+        self
+    }
+
+    /// Number of entities written so far, template entities included
+    pub fn entity_count(&self) -> usize {
+        self.entity_count
+    }
+
+    /// Write everything up to the start of the ENTITIES section.
+    ///
+    /// Called automatically by the first [`Self::write_entity`]; calling it
+    /// again has no effect.
+    pub fn begin(&mut self) -> Result<()> {
+        if self.started {
+            return Ok(());
+        }
+        self.started = true;
+        self.template.load_blocks()?;
+        self.template.sync_dim_variables();
+        self.used_handles = handles::used_handles(&self.template);
+
+        let handle_start = self.next_handle;
+        self.handle_seed = handle_start + count_extra_handles(&self.template) + self.handle_reserve;
+
+        // Pre-2007 files store strings in the drawing code page
+        if self.template.version < DxfVersion::AC1021 {
+            if let Some(enc) = crate::io::dxf::code_page::encoding_from_code_page(&self.template.header.code_page) {
+                self.stream.set_encoding(enc);
+            }
+        }
+
+        let mut section_writer = SectionWriter::new(&mut self.stream, handle_start, self.handle_seed).with_version(self.template.version)
+            .with_custom_types(&self.custom_types);
+        section_writer.write_header(&self.template)?;
+        section_writer.write_classes(&self.template)?;
+        section_writer.write_tables(&self.template)?;
+        section_writer.write_blocks(&self.template)?;
+        self.next_handle = section_writer.next_handle();
+        self.used_handles.extend((handle_start..self.next_handle).map(Handle::new));
+
+        self.stream.write_section_start("ENTITIES")?;
+
+        // Entities already present in the template come first; they are
+        // moved out since the template is only needed for OBJECTS from here on
+        let model_space_entities = self.template.block_records.get_mut("*Model_Space")
+            .map(|b| std::mem::take(&mut b.entities))
+            .unwrap_or_default();
+        for entity in &model_space_entities {
+            self.write_new_entity(entity)?;
+        }
+        let handles: Vec<Handle> = self.template.sorted_entities().map(|e| e.common().handle).collect();
+        for handle in handles {
+            if let Some(entity) = self.template.remove_entity(handle) {
+                self.write_new_entity(&entity)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Write one model space entity.
+    ///
+    /// Entities without a handle are given the next free one; explicit
+    /// handles are kept as-is. An explicit handle, of the entity or of its
+    /// vertices and attributes, that is already in use or beyond the
+    /// reserved range is an [`DxfError::InvalidHandle`] error, and nothing
+    /// is written.
+    pub fn write_entity(&mut self, entity: &EntityType) -> Result<()> {
+        self.begin()?;
+
+        let mut own = HashSet::new();
+        let taken = handles::entity_handles(entity)
+            .into_iter()
+            .find(|h| h.value() >= self.handle_seed || self.used_handles.contains(h) || !own.insert(*h));
+        if let Some(handle) = taken {
+            return Err(DxfError::InvalidHandle(handle.value()));
+        }
+        self.write_new_entity(entity)
+    }
+
+    /// Write an entity, giving it a handle if it has none, and record the
+    /// handles it takes
+    fn write_new_entity(&mut self, entity: &EntityType) -> Result<()> {
+        let handle = entity.common().handle;
+        let first_allocated = if handle.is_null() {
+            self.next_handle
+        } else {
+            self.next_handle.max(handle.value() + 1)
+        };
+        if handle.is_null() {
+            let mut entity = entity.clone();
+            let handle = self.allocate_handle()?;
+            entity.as_entity_mut().set_handle(handle);
+            self.write_with_owner(&entity)?;
+        } else {
+            self.next_handle = first_allocated;
+            self.write_with_owner(entity)?;
+        }
+        self.used_handles.extend(handles::entity_handles(entity));
+        self.used_handles.extend((first_allocated..self.next_handle).map(Handle::new));
+        self.entity_count += 1;
+        Ok(())
+    }
+
+    /// Flush buffered output to the underlying writer
+    pub fn flush(&mut self) -> Result<()> {
+        self.stream.flush()
+    }
+
+    /// Close the ENTITIES section, write OBJECTS, THUMBNAILIMAGE and EOF, and
+    /// return the underlying stream writer.
+    pub fn finish(mut self) -> Result<S> {
+        self.begin()?;
+        self.stream.write_section_end()?;
+
+        let mut section_writer = SectionWriter::new(&mut self.stream, self.next_handle, self.handle_seed).with_version(self.template.version)
+            .with_custom_types(&self.custom_types);
+        section_writer.write_objects(&self.template)?;
+        section_writer.write_thumbnail(&self.template)?;
+        self.next_handle = section_writer.next_handle();
+        self.check_reserve()?;
+
+        self.stream.write_string(0, "EOF")?;
+        self.stream.flush()?;
+        Ok(self.stream)
+    }
+
+    fn write_with_owner(&mut self, entity: &EntityType) -> Result<()> {
+        let mut section_writer = SectionWriter::new(&mut self.stream, self.next_handle, self.handle_seed).with_version(self.template.version)
+            .with_custom_types(&self.custom_types);
+        section_writer.write_entity_with_owner(entity, self.model_space)?;
+        self.next_handle = section_writer.next_handle();
+        self.check_reserve()
+    }
+
+    fn allocate_handle(&mut self) -> Result<Handle> {
+        let handle = Handle::new(self.next_handle);
+        self.next_handle += 1;
+        self.check_reserve()?;
+        Ok(handle)
+    }
+
+    /// Fail once handles would reach the `$HANDSEED` already written
+    fn check_reserve(&self) -> Result<()> {
+        if self.next_handle > self.handle_seed {
+            return Err(DxfError::InvalidFormat(format!(
+                "Streaming writer ran out of reserved handles ($HANDSEED {:X}); increase the handle reserve",
+                self.handle_seed
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::{Circle, Line};
+    use crate::io::dxf::DxfReader;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_streamed_entities_read_back() {
+        let mut template = CadDocument::new();
+        template.add_entity(EntityType::Circle(Circle::from_coords(0.0, 0.0, 0.0, 2.0))).unwrap();
+
+        let mut buf = Vec::new();
+        {
+            let mut writer = DxfStreamingWriter::new(template, &mut buf);
+            for i in 0..100 {
+                let line = Line::from_coords(0.0, 0.0, 0.0, i as f64, 1.0, 0.0);
+                writer.write_entity(&EntityType::Line(line)).unwrap();
+            }
+            assert_eq!(writer.entity_count(), 101);
+            writer.finish().unwrap();
+        }
+
+        let doc = DxfReader::from_reader(Cursor::new(buf)).unwrap().read().unwrap();
+        assert_eq!(doc.entity_count(), 101);
+        let handles: std::collections::HashSet<_> = doc.entities().map(|e| e.common().handle).collect();
+        assert_eq!(handles.len(), 101);
+    }
+
+    #[test]
+    fn test_handle_reserve_exhausted() {
+        let mut writer = DxfStreamingWriter::new(CadDocument::new(), Vec::new()).with_handle_reserve(2);
+        let line = EntityType::Line(Line::new());
+        writer.write_entity(&line).unwrap();
+        writer.write_entity(&line).unwrap();
+        let overflow = (0..2).map(|_| writer.write_entity(&line)).find(|r| r.is_err());
+        assert!(overflow.is_some());
+    }
+
+    #[test]
+    fn test_explicit_handle_used_by_template() {
+        let mut template = CadDocument::new();
+        let circle = template.add_entity(EntityType::Circle(Circle::from_coords(0.0, 0.0, 0.0, 2.0))).unwrap();
+        let layer = template.layers.get("0").unwrap().handle;
+
+        let mut writer = DxfStreamingWriter::new(template, Vec::new());
+        for taken in [circle, layer] {
+            let mut line = Line::new();
+            line.common.handle = taken;
+            let err = writer.write_entity(&EntityType::Line(line)).unwrap_err();
+            assert!(matches!(err, DxfError::InvalidHandle(h) if h == taken.value()));
+        }
+
+        let mut line = Line::new();
+        line.common.handle = Handle::new(0x5000);
+        writer.write_entity(&EntityType::Line(line)).unwrap();
+        assert_eq!(writer.entity_count(), 2);
+        let buf = writer.finish().unwrap().into_inner();
+        let doc = DxfReader::from_bytes(buf).unwrap().read().unwrap();
+        assert!(doc.get_entity(Handle::new(0x5000)).is_some());
+    }
+
+    #[test]
+    fn test_explicit_handle_used_by_streamed_entity() {
+        let mut writer = DxfStreamingWriter::new(CadDocument::new(), Vec::new()).with_handle_reserve(0x100);
+        writer.write_entity(&EntityType::Line(Line::new())).unwrap();
+        let first = Handle::new(writer.next_handle - 1);
+
+        let mut beyond = Line::new();
+        beyond.common.handle = Handle::new(0x5000);
+        assert!(matches!(writer.write_entity(&EntityType::Line(beyond)), Err(DxfError::InvalidHandle(0x5000))));
+
+        let mut repeat = Line::new();
+        repeat.common.handle = first;
+        assert!(matches!(writer.write_entity(&EntityType::Line(repeat)), Err(DxfError::InvalidHandle(_))));
+
+        let mut explicit = Line::new();
+        explicit.common.handle = Handle::new(first.value() + 10);
+        let explicit = EntityType::Line(explicit);
+        writer.write_entity(&explicit).unwrap();
+        assert!(matches!(writer.write_entity(&explicit), Err(DxfError::InvalidHandle(_))));
+        assert_eq!(writer.entity_count(), 2);
+
+        // Rejected entities leave nothing in the file
+        let buf = writer.finish().unwrap().into_inner();
+        let doc = DxfReader::from_bytes(buf).unwrap().read().unwrap();
+        assert_eq!(doc.entity_count(), 2);
+        assert!(doc.get_entity(Handle::new(0x5000)).is_none());
+    }
+}
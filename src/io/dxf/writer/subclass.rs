@@ -0,0 +1,109 @@
+//! Subclass markers (group 100) of written entities
+//!
+//! Strict readers such as BricsCAD and ODA based tools check the markers of
+//! an entity against the classes AutoCAD writes for it, in order, and reject
+//! the entity when one is missing, extra or out of place. The section
+//! writer sends every marker through [`is_written`], which leaves out the
+//! markers a version does not have; [`entity_subclasses`] lists the markers
+//! of each entity for validating output.
+
+use crate::entities::{Dimension, EntityType};
+use crate::types::DxfVersion;
+
+/// Markers only written from a version on
+const INTRODUCED: &[(&str, DxfVersion)] = &[
+    // The history handle of 3D solids came with AutoCAD 2007
+    ("AcDb3dSolid", DxfVersion::AC1021),
+];
+
+/// Whether `marker` is written for `version`
+pub(crate) fn is_written(marker: &str, version: DxfVersion) -> bool {
+    INTRODUCED
+        .iter()
+        .find(|(name, _)| *name == marker)
+        .is_none_or(|&(_, since)| version >= since)
+}
+
+/// Subclass markers AutoCAD writes for the record of `entity` in `version`,
+/// in order.
+///
+/// Only the record of the entity itself is covered; the VERTEX, ATTRIB and
+/// SEQEND records following a polyline or insert have their own. Unknown
+/// entities are written from their raw pairs and give no markers here.
+pub fn entity_subclasses(entity: &EntityType, version: DxfVersion) -> Vec<&'static str> {
+    let markers: &[&'static str] = match entity {
+        EntityType::Point(_) => &["AcDbPoint"],
+        EntityType::Line(_) => &["AcDbLine"],
+        EntityType::Circle(_) => &["AcDbCircle"],
+        EntityType::Arc(_) => &["AcDbCircle", "AcDbArc"],
+        EntityType::Ellipse(_) => &["AcDbEllipse"],
+        EntityType::Polyline(_) | EntityType::Polyline3D(_) => &["AcDb3dPolyline"],
+        EntityType::Polyline2D(_) => &["AcDb2dPolyline"],
+        EntityType::LwPolyline(_) => &["AcDbPolyline"],
+        EntityType::Text(_) => &["AcDbText", "AcDbText"],
+        EntityType::MText(_) => &["AcDbMText"],
+        EntityType::Spline(_) => &["AcDbSpline"],
+        EntityType::Dimension(dimension) => match dimension.as_ref() {
+            Dimension::Aligned(_) => &["AcDbDimension", "AcDbAlignedDimension"],
+            Dimension::Linear(_) => &["AcDbDimension", "AcDbAlignedDimension", "AcDbRotatedDimension"],
+            Dimension::Radius(_) => &["AcDbDimension", "AcDbRadialDimension"],
+            Dimension::Diameter(_) => &["AcDbDimension", "AcDbDiametricDimension"],
+            Dimension::Angular2Ln(_) => &["AcDbDimension", "AcDb2LineAngularDimension"],
+            Dimension::Angular3Pt(_) => &["AcDbDimension", "AcDb3PointAngularDimension"],
+            Dimension::Ordinate(_) => &["AcDbDimension", "AcDbOrdinateDimension"],
+        },
+        EntityType::Hatch(_) => &["AcDbHatch"],
+        EntityType::Solid(_) => &["AcDbTrace"],
+        EntityType::Face3D(_) => &["AcDbFace"],
+        EntityType::Insert(insert) if insert.is_array() => &["AcDbMInsertBlock"],
+        EntityType::Insert(_) => &["AcDbBlockReference"],
+        EntityType::Block(_) => &["AcDbBlockBegin"],
+        EntityType::BlockEnd(_) => &["AcDbBlockEnd"],
+        EntityType::Ray(_) => &["AcDbRay"],
+        EntityType::XLine(_) => &["AcDbXline"],
+        EntityType::Viewport(_) => &["AcDbViewport"],
+        EntityType::AttributeDefinition(_) => &["AcDbText", "AcDbAttributeDefinition"],
+        EntityType::AttributeEntity(_) => &["AcDbText", "AcDbAttribute"],
+        EntityType::Leader(_) => &["AcDbLeader"],
+        EntityType::MultiLeader(_) => &["AcDbMLeader"],
+        EntityType::MLine(_) => &["AcDbMline"],
+        EntityType::Mesh(_) => &["AcDbSubDMesh"],
+        EntityType::RasterImage(_) => &["AcDbRasterImage"],
+        EntityType::Solid3D(_) => &["AcDbModelerGeometry", "AcDb3dSolid"],
+        EntityType::Region(_) | EntityType::Body(_) => &["AcDbModelerGeometry"],
+        EntityType::Table(_) => &["AcDbBlockReference", "AcDbTable"],
+        EntityType::Tolerance(_) => &["AcDbFcf"],
+        EntityType::PolyfaceMesh(_) => &["AcDbPolyFaceMesh"],
+        EntityType::Wipeout(_) => &["AcDbWipeout"],
+        EntityType::Shape(_) => &["AcDbShape"],
+        EntityType::Underlay(_) => &["AcDbUnderlayReference"],
+        EntityType::Seqend(_) => &[],
+        EntityType::Ole2Frame(_) => &["AcDbOle2Frame"],
+        EntityType::PolygonMesh(_) => &["AcDbPolygonMesh"],
+        EntityType::Unknown(_) => return Vec::new(),
+    };
+    std::iter::once("AcDbEntity")
+        .chain(markers.iter().copied())
+        .filter(|marker| is_written(marker, version))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::{Seqend, Solid3D};
+
+    #[test]
+    fn test_markers_by_version() {
+        let solid = EntityType::Solid3D(Solid3D::new());
+        assert_eq!(
+            entity_subclasses(&solid, DxfVersion::AC1018),
+            vec!["AcDbEntity", "AcDbModelerGeometry"]
+        );
+        assert_eq!(
+            entity_subclasses(&solid, DxfVersion::AC1021),
+            vec!["AcDbEntity", "AcDbModelerGeometry", "AcDb3dSolid"]
+        );
+        assert_eq!(entity_subclasses(&EntityType::Seqend(Seqend::new()), DxfVersion::AC1032), vec!["AcDbEntity"]);
+    }
+}
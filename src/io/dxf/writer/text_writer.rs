@@ -1,19 +1,23 @@
 //! ASCII DXF writer
 
 use std::io::Write;
+use encoding_rs::Encoding;
 use crate::error::Result;
+use crate::io::dxf::code_page::encode_with_escapes;
 use crate::types::Handle;
 use super::stream_writer::DxfStreamWriter;
 
 /// ASCII DXF stream writer
 pub struct DxfTextWriter<W: Write> {
     writer: W,
+    /// Code page for string values.  `None` means UTF-8.
+    encoding: Option<&'static Encoding>,
 }
 
 impl<W: Write> DxfTextWriter<W> {
     /// Create a new ASCII DXF writer
     pub fn new(writer: W) -> Self {
-        Self { writer }
+        Self { writer, encoding: None }
     }
     
     /// Write a DXF code with proper formatting (right-aligned in 3-character field)
@@ -37,7 +41,13 @@ impl<W: Write> DxfTextWriter<W> {
 impl<W: Write> DxfStreamWriter for DxfTextWriter<W> {
     fn write_string(&mut self, code: i32, value: &str) -> Result<()> {
         self.write_code(code)?;
-        writeln!(self.writer, "{}", value)?;
+        match self.encoding {
+            Some(enc) => {
+                self.writer.write_all(&encode_with_escapes(value, enc))?;
+                writeln!(self.writer)?;
+            }
+            None => writeln!(self.writer, "{}", value)?,
+        }
         Ok(())
     }
     
@@ -110,6 +120,10 @@ impl<W: Write> DxfStreamWriter for DxfTextWriter<W> {
         self.writer.flush()?;
         Ok(())
     }
+
+    fn set_encoding(&mut self, encoding: &'static Encoding) {
+        self.encoding = Some(encoding);
+    }
 }
 
 #[cfg(test)]
@@ -171,5 +185,18 @@ mod tests {
         let output = String::from_utf8(buf).unwrap();
         assert!(output.contains("FF\n"));
     }
-}
 
+    #[test]
+    fn test_write_string_with_encoding() {
+        let mut buf = Vec::new();
+        {
+            let mut writer = DxfTextWriter::new(&mut buf);
+            writer.set_encoding(encoding_rs::WINDOWS_1251);
+            writer.write_string(1, "Пр中").unwrap();
+        }
+        let mut expected = b"  1\n".to_vec();
+        expected.extend_from_slice(&[0xCF, 0xF0]);
+        expected.extend_from_slice(b"\\U+4E2D\n");
+        assert_eq!(buf, expected);
+    }
+}
@@ -0,0 +1,132 @@
+//! Paper space viewport numbering
+//!
+//! Each viewport of a layout needs an id (code 69) unique within the layout,
+//! id 1 being the layout's main viewport that shows the sheet itself, and a
+//! status (code 68): 0 when off, otherwise its stacking order. Viewports
+//! created in code usually have neither, which leaves AutoCAD showing an
+//! empty layout. [`ViewportTable`] numbers the viewports of the document
+//! being written and finds each layout's main viewport.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::document::CadDocument;
+use crate::entities::{EntityType, Viewport};
+use crate::types::Handle;
+
+/// Ids and statuses of the paper space viewports of a document
+#[derive(Debug, Default)]
+pub(super) struct ViewportTable {
+    /// `(id, status)` by viewport handle
+    numbers: HashMap<Handle, (i16, i16)>,
+    /// Main viewport by layout block record handle
+    main: HashMap<Handle, Handle>,
+}
+
+impl ViewportTable {
+    /// Number the viewports of every paper space layout of `document`.
+    ///
+    /// Valid ids are kept; viewports without one, or repeating one, get the
+    /// lowest free id in handle order.
+    pub fn new(document: &CadDocument) -> Self {
+        let mut layouts: HashMap<Handle, Vec<&Viewport>> = HashMap::new();
+        for record in document.block_records.iter().filter(|r| r.is_paper_space()) {
+            let mut viewports: Vec<&Viewport> = record
+                .entities
+                .iter()
+                .chain(document.entities().filter(|e| e.common().owner_handle == record.handle))
+                .filter_map(|e| match e {
+                    EntityType::Viewport(vp) if !vp.common.handle.is_null() => Some(vp.as_ref()),
+                    _ => None,
+                })
+                .collect();
+            viewports.sort_by_key(|vp| vp.common.handle);
+            layouts.insert(record.handle, viewports);
+        }
+
+        let mut table = Self::default();
+        for (layout, viewports) in layouts {
+            let mut used = HashSet::new();
+            let mut ids: Vec<(i16, &Viewport)> = Vec::with_capacity(viewports.len());
+            let mut unnumbered = Vec::new();
+            for vp in viewports {
+                if vp.id > 0 && used.insert(vp.id) {
+                    ids.push((vp.id, vp));
+                } else {
+                    unnumbered.push(vp);
+                }
+            }
+            let mut next = 1;
+            for vp in unnumbered {
+                while used.contains(&next) {
+                    next += 1;
+                }
+                used.insert(next);
+                ids.push((next, vp));
+            }
+
+            ids.sort_by_key(|(id, _)| *id);
+            let mut stacking = 0;
+            for (id, vp) in ids {
+                let status = if vp.status.is_on {
+                    stacking += 1;
+                    stacking
+                } else {
+                    0
+                };
+                table.numbers.insert(vp.common.handle, (id, status));
+                if id == 1 {
+                    table.main.insert(layout, vp.common.handle);
+                }
+            }
+        }
+        table
+    }
+
+    /// `(id, status)` to write for `viewport`
+    pub fn numbers(&self, viewport: &Viewport) -> (i16, i16) {
+        self.numbers.get(&viewport.common.handle).copied().unwrap_or_else(|| {
+            let status = if viewport.status.is_on { viewport.id.max(1) } else { 0 };
+            (viewport.id, status)
+        })
+    }
+
+    /// Viewport a layout points to: its own when that is a numbered
+    /// viewport, otherwise the main viewport of its block record
+    pub fn layout_viewport(&self, block_record: Handle, viewport: Handle) -> Handle {
+        if self.numbers.contains_key(&viewport) {
+            return viewport;
+        }
+        self.main.get(&block_record).copied().unwrap_or(viewport)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_numbering() {
+        let mut doc = CadDocument::new();
+        let paper = doc.block_records.get("*Paper_Space").unwrap().handle;
+        let mut handles = Vec::new();
+        for (id, on) in [(0, true), (3, true), (3, false), (0, true)] {
+            let mut vp = Viewport::new();
+            vp.id = id;
+            vp.status.is_on = on;
+            vp.common.owner_handle = paper;
+            handles.push(doc.add_entity(EntityType::Viewport(Box::new(vp))).unwrap());
+        }
+
+        let table = ViewportTable::new(&doc);
+        let number = |h: Handle| match doc.get_entity(h) {
+            Some(EntityType::Viewport(vp)) => table.numbers(vp),
+            _ => unreachable!(),
+        };
+        assert_eq!(number(handles[0]), (1, 1));
+        assert_eq!(number(handles[1]), (3, 2));
+        assert_eq!(number(handles[2]), (2, 0));
+        assert_eq!(number(handles[3]), (4, 3));
+        assert_eq!(table.layout_viewport(paper, Handle::NULL), handles[0]);
+        assert_eq!(table.layout_viewport(paper, handles[1]), handles[1]);
+    }
+}
@@ -0,0 +1,102 @@
+//! Choosing the entity types a read decodes.
+//!
+//! Both [`DxfReader`](super::DxfReader) and [`DwgReader`](super::DwgReader)
+//! take an [`EntityFilter`] (`with_entity_filter`).  Entities it rejects are skipped before their data is
+//! decoded and never reach the document.  Types are matched by DXF entity
+//! name (`"HATCH"`, `"3DSOLID"`, `"IMAGE"`, ...); the VERTEX, ATTRIB and
+//! SEQEND records of polylines and inserts go with their owner.
+//!
+//! ```rust,ignore
+//! let doc = DxfReader::from_file("site.dxf")?
+//!     .with_entity_filter(EntityFilter::except(["HATCH", "3DSOLID", "IMAGE"]))
+//!     .read()?;
+//! ```
+
+use std::collections::BTreeSet;
+use std::fmt;
+use std::sync::Arc;
+
+/// Entity types to read, by DXF entity name.
+#[derive(Clone, Default)]
+pub enum EntityFilter {
+    /// Read every entity.
+    #[default]
+    All,
+    /// Read only the named types.
+    Only(BTreeSet<String>),
+    /// Read every type but the named ones.
+    Except(BTreeSet<String>),
+    /// Read the types for which the predicate returns `true`.
+    Predicate(Arc<dyn Fn(&str) -> bool + Send + Sync>),
+}
+
+impl EntityFilter {
+    /// Read only the named types.
+    pub fn only<I, S>(names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        Self::Only(names.into_iter().map(|n| n.as_ref().to_uppercase()).collect())
+    }
+
+    /// Read every type but the named ones.
+    pub fn except<I, S>(names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        Self::Except(names.into_iter().map(|n| n.as_ref().to_uppercase()).collect())
+    }
+
+    /// Read the types for which `predicate` returns `true`; it is given
+    /// the upper case DXF entity name.
+    pub fn predicate(predicate: impl Fn(&str) -> bool + Send + Sync + 'static) -> Self {
+        Self::Predicate(Arc::new(predicate))
+    }
+
+    /// Whether entities named `dxf_name` are read.
+    pub fn accepts(&self, dxf_name: &str) -> bool {
+        match self {
+            Self::All => true,
+            Self::Only(names) => names.contains(dxf_name),
+            Self::Except(names) => !names.contains(dxf_name),
+            Self::Predicate(predicate) => predicate(dxf_name),
+        }
+    }
+
+    /// Whether every entity is read.
+    pub fn is_all(&self) -> bool {
+        matches!(self, Self::All)
+    }
+}
+
+impl fmt::Debug for EntityFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::All => f.write_str("All"),
+            Self::Only(names) => f.debug_tuple("Only").field(names).finish(),
+            Self::Except(names) => f.debug_tuple("Except").field(names).finish(),
+            Self::Predicate(_) => f.write_str("Predicate(..)"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filters() {
+        let only = EntityFilter::only(["line", "ARC"]);
+        assert!(only.accepts("LINE") && only.accepts("ARC") && !only.accepts("HATCH"));
+
+        let except = EntityFilter::except(["HATCH"]);
+        assert!(except.accepts("LINE") && !except.accepts("HATCH"));
+
+        let predicate = EntityFilter::predicate(|name| !name.ends_with("UNDERLAY"));
+        assert!(predicate.accepts("LINE") && !predicate.accepts("PDFUNDERLAY"));
+        assert_eq!(format!("{predicate:?}"), "Predicate(..)");
+        assert!(EntityFilter::default().is_all());
+    }
+}
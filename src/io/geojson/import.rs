@@ -0,0 +1,433 @@
+//! GeoJSON import
+
+use std::path::Path;
+
+use crate::document::CadDocument;
+use crate::entities::hatch::{BoundaryEdge, BoundaryPath, BoundaryPathFlags, PolylineEdge};
+use crate::entities::{EntityType, Hatch, LwPolyline, Point};
+use crate::error::{DxfError, Result};
+use crate::tables::{AppId, Layer, TableEntry};
+use crate::types::{Handle, Vector2, Vector3};
+use crate::xdata::{ExtendedDataRecord, XDataValue};
+
+use crate::io::json::{self, JsonValue};
+use super::GeoTransform;
+
+/// Longest string value AutoCAD accepts in extended data
+const XDATA_STRING_LIMIT: usize = 255;
+
+/// Options for [`import`] and [`crate::io::wkt::import`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeoJsonImportOptions {
+    /// Layer for new entities
+    pub layer: String,
+    /// Feature property naming the layer of each feature, overriding `layer`
+    pub layer_property: Option<String>,
+    /// Application name for feature properties stored as extended data;
+    /// `None` drops the properties
+    pub xdata_application: Option<String>,
+    /// Read coordinates as WGS84 longitude/latitude and map them to WCS
+    /// through the GEODATA object
+    pub geographic: bool,
+    /// Also add a closed LWPOLYLINE along every polygon ring
+    pub polygon_outlines: bool,
+}
+
+impl Default for GeoJsonImportOptions {
+    fn default() -> Self {
+        Self {
+            layer: "0".to_string(),
+            layer_property: None,
+            xdata_application: Some("GEOJSON".to_string()),
+            geographic: false,
+            polygon_outlines: false,
+        }
+    }
+}
+
+impl GeoJsonImportOptions {
+    /// Put new entities on `layer`
+    pub fn with_layer(mut self, layer: impl Into<String>) -> Self {
+        self.layer = layer.into();
+        self
+    }
+
+    /// Take each feature's layer from the property `name`
+    pub fn with_layer_property(mut self, name: impl Into<String>) -> Self {
+        self.layer_property = Some(name.into());
+        self
+    }
+}
+
+/// GeoJSON geometry; also the result of parsing WKT
+///
+/// Positions are x/y (or longitude/latitude); further ordinates are
+/// dropped. Polygons are lists of rings, exterior first.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Geometry {
+    Point(Vector2),
+    MultiPoint(Vec<Vector2>),
+    LineString(Vec<Vector2>),
+    MultiLineString(Vec<Vec<Vector2>>),
+    Polygon(Vec<Vec<Vector2>>),
+    MultiPolygon(Vec<Vec<Vec<Vector2>>>),
+    GeometryCollection(Vec<Geometry>),
+}
+
+/// Import the features of a GeoJSON text into model space
+///
+/// Accepts a `FeatureCollection`, a single `Feature` or a bare geometry.
+/// Points become POINT entities, line strings LWPOLYLINEs (closed when the
+/// first and last positions match) and each polygon feature one solid
+/// HATCH. Returns the handles of the new entities.
+pub fn import(doc: &mut CadDocument, geojson: &str, options: &GeoJsonImportOptions) -> Result<Vec<Handle>> {
+    let root = json::parse(geojson)?;
+    let transform = if options.geographic {
+        Some(GeoTransform::from_document(doc)?)
+    } else {
+        None
+    };
+
+    let features: Vec<&JsonValue> = match root.get("type").and_then(|t| t.as_str()) {
+        Some("FeatureCollection") => root
+            .get("features")
+            .and_then(|f| f.as_array())
+            .ok_or_else(|| invalid("FeatureCollection without features"))?
+            .iter()
+            .collect(),
+        _ => vec![&root],
+    };
+
+    let mut handles = Vec::new();
+    for feature in features {
+        let (geometry, properties) = match feature.get("type").and_then(|t| t.as_str()) {
+            Some("Feature") => match feature.get("geometry") {
+                None | Some(JsonValue::Null) => continue,
+                Some(geometry) => (geometry, feature.get("properties")),
+            },
+            _ => (feature, None),
+        };
+        let mut geometry = parse_geometry(geometry)?;
+        if let Some(transform) = &transform {
+            geometry = geometry.map(&|p| transform.invert(p.x, p.y));
+        }
+
+        let members: &[(String, JsonValue)] = match properties {
+            Some(JsonValue::Object(members)) => members,
+            _ => &[],
+        };
+        let layer = options
+            .layer_property
+            .as_deref()
+            .and_then(|key| members.iter().find(|(k, _)| k == key))
+            .and_then(|(_, v)| match v {
+                JsonValue::String(s) if !s.is_empty() => Some(s.clone()),
+                JsonValue::Number(n) => Some(json::json_number(*n)),
+                _ => None,
+            })
+            .unwrap_or_else(|| options.layer.clone());
+        let xdata = options
+            .xdata_application
+            .as_deref()
+            .filter(|_| !members.is_empty())
+            .map(|app| properties_record(app, members));
+
+        handles.extend(add_geometry(doc, &geometry, &layer, xdata, options)?);
+    }
+    Ok(handles)
+}
+
+/// Import a GeoJSON file into model space
+pub fn import_file<P: AsRef<Path>>(
+    doc: &mut CadDocument,
+    path: P,
+    options: &GeoJsonImportOptions,
+) -> Result<Vec<Handle>> {
+    let text = std::fs::read_to_string(path)?;
+    import(doc, &text, options)
+}
+
+/// Add the entities for one geometry, without properties
+///
+/// Coordinates are WCS unless `options.geographic` is set.
+pub fn import_geometry(
+    doc: &mut CadDocument,
+    geometry: &Geometry,
+    options: &GeoJsonImportOptions,
+) -> Result<Vec<Handle>> {
+    let geometry = if options.geographic {
+        let transform = GeoTransform::from_document(doc)?;
+        geometry.map(&|p| transform.invert(p.x, p.y))
+    } else {
+        geometry.clone()
+    };
+    add_geometry(doc, &geometry, &options.layer, None, options)
+}
+
+impl Geometry {
+    fn map(&self, f: &dyn Fn(Vector2) -> Vector2) -> Geometry {
+        let line = |points: &Vec<Vector2>| points.iter().map(|p| f(*p)).collect::<Vec<_>>();
+        let polygon = |rings: &Vec<Vec<Vector2>>| rings.iter().map(line).collect::<Vec<_>>();
+        match self {
+            Geometry::Point(p) => Geometry::Point(f(*p)),
+            Geometry::MultiPoint(points) => Geometry::MultiPoint(line(points)),
+            Geometry::LineString(points) => Geometry::LineString(line(points)),
+            Geometry::MultiLineString(lines) => Geometry::MultiLineString(lines.iter().map(line).collect()),
+            Geometry::Polygon(rings) => Geometry::Polygon(polygon(rings)),
+            Geometry::MultiPolygon(polygons) => Geometry::MultiPolygon(polygons.iter().map(polygon).collect()),
+            Geometry::GeometryCollection(items) => {
+                Geometry::GeometryCollection(items.iter().map(|g| g.map(f)).collect())
+            }
+        }
+    }
+}
+
+fn invalid(message: &str) -> DxfError {
+    DxfError::InvalidFormat(format!("GeoJSON: {message}"))
+}
+
+fn parse_geometry(value: &JsonValue) -> Result<Geometry> {
+    let kind = value.get("type").and_then(|t| t.as_str()).ok_or_else(|| invalid("geometry without type"))?;
+    if kind == "GeometryCollection" {
+        let items = value
+            .get("geometries")
+            .and_then(|g| g.as_array())
+            .ok_or_else(|| invalid("GeometryCollection without geometries"))?;
+        return Ok(Geometry::GeometryCollection(
+            items.iter().map(parse_geometry).collect::<Result<_>>()?,
+        ));
+    }
+
+    let coordinates = value.get("coordinates").ok_or_else(|| invalid("geometry without coordinates"))?;
+    let position = |v: &JsonValue| -> Result<Vector2> {
+        match v.as_array() {
+            Some([x, y, ..]) => match (x.as_f64(), y.as_f64()) {
+                (Some(x), Some(y)) => Ok(Vector2::new(x, y)),
+                _ => Err(invalid("non-numeric position")),
+            },
+            _ => Err(invalid("position needs two numbers")),
+        }
+    };
+    let list = |v: &JsonValue| -> Result<Vec<Vector2>> {
+        v.as_array().ok_or_else(|| invalid("expected an array of positions"))?.iter().map(position).collect()
+    };
+    let rings = |v: &JsonValue| -> Result<Vec<Vec<Vector2>>> {
+        v.as_array().ok_or_else(|| invalid("expected an array of rings"))?.iter().map(list).collect()
+    };
+    let array = coordinates.as_array().ok_or_else(|| invalid("coordinates must be an array"))?;
+
+    Ok(match kind {
+        "Point" => Geometry::Point(position(coordinates)?),
+        "MultiPoint" => Geometry::MultiPoint(list(coordinates)?),
+        "LineString" => Geometry::LineString(list(coordinates)?),
+        "MultiLineString" => Geometry::MultiLineString(rings(coordinates)?),
+        "Polygon" => Geometry::Polygon(rings(coordinates)?),
+        "MultiPolygon" => Geometry::MultiPolygon(array.iter().map(rings).collect::<Result<_>>()?),
+        other => return Err(invalid(&format!("unsupported geometry type {other}"))),
+    })
+}
+
+/// Feature properties as key/value pairs; numbers stay numeric, nested
+/// values are stored as JSON text
+fn properties_record(application: &str, members: &[(String, JsonValue)]) -> ExtendedDataRecord {
+    let truncate = |s: &str| s.chars().take(XDATA_STRING_LIMIT).collect::<String>();
+    let mut record = ExtendedDataRecord::new(application);
+    for (key, value) in members {
+        let value = match value {
+            JsonValue::Null => continue,
+            JsonValue::Bool(b) => XDataValue::Integer16(*b as i16),
+            JsonValue::Number(n) if n.fract() == 0.0 && n.abs() <= i32::MAX as f64 => {
+                XDataValue::Integer32(*n as i32)
+            }
+            JsonValue::Number(n) => XDataValue::Real(*n),
+            JsonValue::String(s) => XDataValue::String(truncate(s)),
+            nested => XDataValue::String(truncate(&nested.to_json())),
+        };
+        record.add_value(XDataValue::String(truncate(key)));
+        record.add_value(value);
+    }
+    record
+}
+
+fn add_geometry(
+    doc: &mut CadDocument,
+    geometry: &Geometry,
+    layer: &str,
+    xdata: Option<ExtendedDataRecord>,
+    options: &GeoJsonImportOptions,
+) -> Result<Vec<Handle>> {
+    let mut entities = Vec::new();
+    collect_entities(geometry, options, &mut entities);
+
+    if !doc.layers.contains(layer) {
+        let mut entry = Layer::new(layer);
+        entry.set_handle(doc.allocate_handle());
+        doc.layers.add(entry).map_err(DxfError::Custom)?;
+    }
+    if let Some(record) = &xdata {
+        if !doc.app_ids.contains(&record.application_name) {
+            let mut entry = AppId::new(record.application_name.clone());
+            entry.set_handle(doc.allocate_handle());
+            doc.app_ids.add(entry).map_err(DxfError::Custom)?;
+        }
+    }
+
+    let mut handles = Vec::with_capacity(entities.len());
+    for mut entity in entities {
+        let common = entity.common_mut();
+        common.layer = layer.to_string();
+        if let Some(record) = &xdata {
+            common.extended_data.add_record(record.clone());
+        }
+        handles.push(doc.add_entity(entity)?);
+    }
+    Ok(handles)
+}
+
+fn collect_entities(geometry: &Geometry, options: &GeoJsonImportOptions, out: &mut Vec<EntityType>) {
+    match geometry {
+        Geometry::Point(p) => out.push(EntityType::Point(Point::at(Vector3::new(p.x, p.y, 0.0)))),
+        Geometry::MultiPoint(points) => {
+            for p in points {
+                collect_entities(&Geometry::Point(*p), options, out);
+            }
+        }
+        Geometry::LineString(points) => {
+            if let Some(polyline) = polyline(points) {
+                out.push(EntityType::LwPolyline(polyline));
+            }
+        }
+        Geometry::MultiLineString(lines) => {
+            out.extend(lines.iter().filter_map(|l| polyline(l)).map(EntityType::LwPolyline));
+        }
+        Geometry::Polygon(rings) => polygons(std::slice::from_ref(rings), options, out),
+        Geometry::MultiPolygon(polys) => polygons(polys, options, out),
+        Geometry::GeometryCollection(items) => {
+            for item in items {
+                collect_entities(item, options, out);
+            }
+        }
+    }
+}
+
+/// Ring or line without a repeated closing position, and whether it was closed
+fn open_ring(points: &[Vector2]) -> (&[Vector2], bool) {
+    match points {
+        [first, .., last] if points.len() > 3 && first == last => (&points[..points.len() - 1], true),
+        _ => (points, false),
+    }
+}
+
+fn polyline(points: &[Vector2]) -> Option<LwPolyline> {
+    if points.len() < 2 {
+        return None;
+    }
+    let (points, closed) = open_ring(points);
+    let mut polyline = LwPolyline::from_points(points.to_vec());
+    if closed {
+        polyline.close();
+    }
+    Some(polyline)
+}
+
+/// One solid hatch for all rings of all polygons, plus optional outlines
+fn polygons(polygons: &[Vec<Vec<Vector2>>], options: &GeoJsonImportOptions, out: &mut Vec<EntityType>) {
+    let mut hatch = Hatch::solid();
+    let mut outlines = Vec::new();
+    for rings in polygons {
+        for (i, ring) in rings.iter().enumerate() {
+            let (ring, _) = open_ring(ring);
+            if ring.len() < 3 {
+                continue;
+            }
+            let mut flags = BoundaryPathFlags::POLYLINE.bits();
+            if i == 0 {
+                flags |= BoundaryPathFlags::EXTERNAL.bits() | BoundaryPathFlags::OUTERMOST.bits();
+            }
+            let mut path = BoundaryPath::with_flags(BoundaryPathFlags::from_bits(flags));
+            path.add_edge(BoundaryEdge::Polyline(PolylineEdge::new(ring.to_vec(), true)));
+            hatch.add_path(path);
+
+            if options.polygon_outlines {
+                let mut outline = LwPolyline::from_points(ring.to_vec());
+                outline.close();
+                outlines.push(EntityType::LwPolyline(outline));
+            }
+        }
+    }
+    if !hatch.paths.is_empty() {
+        out.push(EntityType::Hatch(Box::new(hatch)));
+    }
+    out.extend(outlines);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"{
+        "type": "FeatureCollection",
+        "features": [
+            {"type": "Feature", "geometry": {"type": "Point", "coordinates": [1, 2, 30]},
+             "properties": {"name": "Well 7", "depth": 12.5, "active": true, "kind": "WELL"}},
+            {"type": "Feature", "geometry": {"type": "LineString", "coordinates": [[0, 0], [5, 0], [5, 5]]},
+             "properties": {"kind": "ROAD", "lanes": 2}},
+            {"type": "Feature", "geometry": {"type": "Polygon", "coordinates": [
+                [[0, 0], [10, 0], [10, 10], [0, 10], [0, 0]],
+                [[2, 2], [4, 2], [4, 4], [2, 2]]]},
+             "properties": {"kind": "PARCEL", "tags": ["a", "b"]}},
+            {"type": "Feature", "geometry": null, "properties": {}}
+        ]
+    }"#;
+
+    #[test]
+    fn test_import_feature_collection() {
+        let mut doc = CadDocument::new();
+        let options = GeoJsonImportOptions::default().with_layer_property("kind");
+        let handles = import(&mut doc, SAMPLE, &options).unwrap();
+        assert_eq!(handles.len(), 3);
+
+        let point = match doc.get_entity(handles[0]) {
+            Some(EntityType::Point(p)) => p,
+            other => panic!("expected point, got {other:?}"),
+        };
+        assert_eq!(point.location, Vector3::new(1.0, 2.0, 0.0));
+        assert_eq!(point.common.layer, "WELL");
+        let record = point.common.extended_data.get_record("GEOJSON").unwrap();
+        assert_eq!(record.values[0], XDataValue::String("name".to_string()));
+        assert_eq!(record.values[3], XDataValue::Real(12.5));
+        assert_eq!(record.values[5], XDataValue::Integer16(1));
+        assert!(doc.app_ids.contains("GEOJSON"));
+        assert!(doc.layers.contains("PARCEL"));
+
+        match doc.get_entity(handles[1]) {
+            Some(EntityType::LwPolyline(p)) => assert!(!p.is_closed && p.vertices.len() == 3),
+            other => panic!("expected polyline, got {other:?}"),
+        }
+        match doc.get_entity(handles[2]) {
+            Some(EntityType::Hatch(h)) => {
+                assert_eq!(h.paths.len(), 2);
+                assert!(h.paths[0].flags.is_external() && !h.paths[1].flags.is_external());
+                let record = h.common.extended_data.get_record("GEOJSON").unwrap();
+                assert_eq!(record.values[3], XDataValue::String("[\"a\",\"b\"]".to_string()));
+            }
+            other => panic!("expected hatch, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_import_outlines_and_errors() {
+        let mut doc = CadDocument::new();
+        let options = GeoJsonImportOptions { polygon_outlines: true, xdata_application: None, ..Default::default() };
+        let json = r#"{"type": "MultiPolygon", "coordinates": [[[[0,0],[1,0],[1,1],[0,0]]], [[[5,5],[6,5],[6,6]]]]}"#;
+        let handles = import(&mut doc, json, &options).unwrap();
+        assert_eq!(handles.len(), 3);
+        assert!(matches!(doc.get_entity(handles[0]), Some(EntityType::Hatch(h)) if h.paths.len() == 2));
+        assert!(matches!(doc.get_entity(handles[2]), Some(EntityType::LwPolyline(p)) if p.is_closed));
+
+        assert!(import(&mut doc, r#"{"type": "Circle", "coordinates": [0, 0]}"#, &options).is_err());
+        assert!(import(&mut doc, r#"{"type": "Point", "coordinates": [0]}"#, &options).is_err());
+        let geographic = GeoJsonImportOptions { geographic: true, ..Default::default() };
+        assert!(import(&mut doc, r#"{"type": "Point", "coordinates": [0, 0]}"#, &geographic).is_err());
+    }
+}
@@ -0,0 +1,613 @@
+//! GeoJSON export and import
+//!
+//! [`export`] converts model space entities to a GeoJSON `FeatureCollection`
+//! for GIS tools. Points, block references and text become `Point`
+//! features; lines, polylines, arcs, circles, ellipses and splines become
+//! `LineString`s, with curves densified; hatches and solids become
+//! `Polygon` / `MultiPolygon` features. Each feature carries the entity's
+//! handle, type, layer, resolved color and, optionally, its extended data.
+//!
+//! [`import`] goes the other way: points become POINT entities, line
+//! strings LWPOLYLINEs and polygons solid HATCHes, with feature properties
+//! stored as extended data. [`crate::io::wkt`] does the same for WKT.
+//!
+//! Coordinates are WCS x/y by default. With
+//! [`GeoJsonOptions::geographic`] set they are mapped through the
+//! drawing's GEODATA object to WGS84 longitude/latitude, as RFC 7946
+//! requires.
+//!
+//! ```rust,ignore
+//! use acadrust::io::geojson::{export, GeoJsonOptions};
+//!
+//! let json = export(&doc, &GeoJsonOptions::default())?;
+//! std::fs::write("drawing.geojson", json)?;
+//! ```
+
+mod import;
+
+pub use import::{import, import_file, import_geometry, GeoJsonImportOptions, Geometry};
+
+use std::collections::HashSet;
+use std::fmt::Write as _;
+use std::path::Path;
+
+use crate::document::CadDocument;
+use crate::entities::{outline, EntityType};
+use crate::error::{DxfError, Result};
+use crate::objects::GeoData;
+use crate::render::tessellate::{
+    arc_points, bulge_polyline, ccw_sweep, circle_points, ellipse_points, hatch_path_points, spline_points,
+};
+use crate::types::geometry::{ocs_matrix, point_in_polygon, signed_area};
+use crate::types::{Color, Vector2, Vector3};
+use crate::xdata::XDataValue;
+
+use crate::io::json::{json_number, json_string};
+
+/// WGS84 semi-major axis in meters
+const WGS84_A: f64 = 6_378_137.0;
+
+/// WGS84 first eccentricity squared
+const WGS84_E2: f64 = 6.694_379_990_141_316e-3;
+
+/// Options for [`export`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeoJsonOptions {
+    /// Largest chord deviation when densifying curves, in drawing units
+    pub tolerance: f64,
+    /// Map coordinates to WGS84 longitude/latitude through the GEODATA object
+    pub geographic: bool,
+    /// Include extended data as an `xdata` property
+    pub include_xdata: bool,
+    /// Layers to leave out, by name (case-insensitive)
+    pub hidden_layers: HashSet<String>,
+}
+
+impl Default for GeoJsonOptions {
+    fn default() -> Self {
+        Self {
+            tolerance: 0.01,
+            geographic: false,
+            include_xdata: true,
+            hidden_layers: HashSet::new(),
+        }
+    }
+}
+
+impl GeoJsonOptions {
+    /// Hide a layer
+    pub fn hide_layer(mut self, name: impl AsRef<str>) -> Self {
+        self.hidden_layers.insert(name.as_ref().to_uppercase());
+        self
+    }
+}
+
+/// Mapping from WCS to WGS84 defined by a GEODATA object
+///
+/// Offsets from the design point are scaled to meters, rotated so grid
+/// north points up, and applied to the reference point's latitude and
+/// longitude on the WGS84 ellipsoid. This is exact at the reference point
+/// and accurate to a few centimetres over several kilometres.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GeoTransform {
+    design_point: Vector2,
+    longitude: f64,
+    latitude: f64,
+    scale: f64,
+    rotation: f64,
+}
+
+impl GeoTransform {
+    /// Build the transform for geographic GEODATA (coordinate type 3)
+    ///
+    /// Local and projected grids would need a coordinate system library to
+    /// reach WGS84 and are rejected.
+    pub fn from_geodata(geo: &GeoData) -> Result<Self> {
+        if geo.coordinate_type != 3 {
+            return Err(DxfError::NotImplemented(format!(
+                "WGS84 conversion from GEODATA coordinate type {}",
+                geo.coordinate_type
+            )));
+        }
+        let north = if geo.north_direction.length() > 1e-12 {
+            geo.north_direction
+        } else {
+            Vector2::new(0.0, 1.0)
+        };
+        Ok(Self {
+            design_point: Vector2::new(geo.design_point.x, geo.design_point.y),
+            longitude: geo.reference_point.x,
+            latitude: geo.reference_point.y,
+            scale: if geo.horizontal_unit_scale > 0.0 { geo.horizontal_unit_scale } else { 1.0 },
+            rotation: std::f64::consts::FRAC_PI_2 - north.y.atan2(north.x),
+        })
+    }
+
+    /// The drawing's GEODATA transform, if it has a geographic one
+    pub fn from_document(doc: &CadDocument) -> Result<Self> {
+        let geo = doc
+            .geodata()
+            .ok_or_else(|| DxfError::Custom("Document has no GEODATA object".to_string()))?;
+        Self::from_geodata(geo)
+    }
+
+    /// WGS84 longitude and latitude in degrees of a WCS point
+    pub fn apply(&self, point: Vector2) -> (f64, f64) {
+        let d = (point - self.design_point) * self.scale;
+        let (sin, cos) = self.rotation.sin_cos();
+        let east = d.x * cos - d.y * sin;
+        let north = d.x * sin + d.y * cos;
+
+        let phi = self.latitude.to_radians();
+        let w = 1.0 - WGS84_E2 * phi.sin().powi(2);
+        let meridian = WGS84_A * (1.0 - WGS84_E2) / w.powf(1.5);
+        let prime_vertical = WGS84_A / w.sqrt();
+        (
+            self.longitude + (east / (prime_vertical * phi.cos())).to_degrees(),
+            self.latitude + (north / meridian).to_degrees(),
+        )
+    }
+
+    /// WCS point of a WGS84 longitude and latitude in degrees; the inverse
+    /// of [`GeoTransform::apply`]
+    pub fn invert(&self, longitude: f64, latitude: f64) -> Vector2 {
+        let phi = self.latitude.to_radians();
+        let w = 1.0 - WGS84_E2 * phi.sin().powi(2);
+        let meridian = WGS84_A * (1.0 - WGS84_E2) / w.powf(1.5);
+        let prime_vertical = WGS84_A / w.sqrt();
+        let east = (longitude - self.longitude).to_radians() * prime_vertical * phi.cos();
+        let north = (latitude - self.latitude).to_radians() * meridian;
+
+        let (sin, cos) = self.rotation.sin_cos();
+        let d = Vector2::new(east * cos + north * sin, north * cos - east * sin);
+        self.design_point + d * (1.0 / self.scale)
+    }
+}
+
+/// Export model space to a GeoJSON `FeatureCollection`
+pub fn export(doc: &CadDocument, options: &GeoJsonOptions) -> Result<String> {
+    let transform = if options.geographic {
+        Some(GeoTransform::from_document(doc)?)
+    } else {
+        None
+    };
+    let exporter = Exporter { doc, options, transform };
+
+    let mut entities: Vec<&EntityType> = match doc.block_records.get("*Model_Space") {
+        Some(model) => {
+            let owned: Vec<&EntityType> = doc
+                .entities()
+                .filter(|e| {
+                    let owner = e.common().owner_handle;
+                    owner.is_null() || owner == model.handle
+                })
+                .collect();
+            if owned.is_empty() { model.entities.iter().collect() } else { owned }
+        }
+        None => doc.entities().collect(),
+    };
+    entities.sort_by_key(|e| e.common().handle.value());
+
+    let mut out = String::from("{\"type\":\"FeatureCollection\",\"features\":[");
+    let mut first = true;
+    for entity in entities {
+        if let Some(feature) = exporter.feature(entity) {
+            out.push_str(if first { "\n" } else { ",\n" });
+            out.push_str(&feature);
+            first = false;
+        }
+    }
+    out.push_str("\n]}\n");
+    Ok(out)
+}
+
+/// Export model space to a GeoJSON file
+pub fn export_to_file<P: AsRef<Path>>(doc: &CadDocument, options: &GeoJsonOptions, path: P) -> Result<()> {
+    std::fs::write(path, export(doc, options)?)?;
+    Ok(())
+}
+
+enum Shape {
+    Point(Vector2),
+    LineString(Vec<Vector2>),
+    /// Rings in no particular order or orientation
+    Polygons(Vec<Vec<Vector2>>),
+}
+
+struct Exporter<'a> {
+    doc: &'a CadDocument,
+    options: &'a GeoJsonOptions,
+    transform: Option<GeoTransform>,
+}
+
+impl Exporter<'_> {
+    fn feature(&self, entity: &EntityType) -> Option<String> {
+        let e = entity.as_entity();
+        if e.is_invisible() || self.options.hidden_layers.contains(&e.layer().to_uppercase()) {
+            return None;
+        }
+        let mut properties = String::new();
+        let geometry = self.geometry(entity, &mut properties)?;
+
+        let mut out = String::from("{\"type\":\"Feature\",\"geometry\":");
+        self.write_geometry(&mut out, &geometry)?;
+        let common = entity.common();
+        let _ = write!(
+            out,
+            ",\"properties\":{{\"handle\":\"{:X}\",\"type\":{},\"layer\":{}",
+            common.handle.value(),
+            json_string(e.entity_type()),
+            json_string(e.layer())
+        );
+        let color = match e.color() {
+            Color::ByLayer => self.doc.layers.get(e.layer()).map(|l| l.color).unwrap_or(Color::Index(7)),
+            color => color,
+        };
+        if let Some((r, g, b)) = color.to_rgb() {
+            let _ = write!(out, ",\"color\":\"#{r:02x}{g:02x}{b:02x}\"");
+        }
+        if let Color::Index(i) = color {
+            let _ = write!(out, ",\"color_index\":{i}");
+        }
+        out.push_str(&properties);
+        if self.options.include_xdata && !common.extended_data.records().is_empty() {
+            out.push_str(",\"xdata\":{");
+            for (i, record) in common.extended_data.records().iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                let _ = write!(out, "{}:[", json_string(&record.application_name));
+                for (j, value) in record.values.iter().enumerate() {
+                    if j > 0 {
+                        out.push(',');
+                    }
+                    out.push_str(&xdata_value(value));
+                }
+                out.push(']');
+            }
+            out.push('}');
+        }
+        out.push_str("}}");
+        Some(out)
+    }
+
+    /// Geometry in WCS x/y; extra properties are appended to `properties`
+    fn geometry(&self, entity: &EntityType, properties: &mut String) -> Option<Shape> {
+        let tolerance = self.options.tolerance;
+        let ocs = |normal: Vector3, elevation: f64, points: Vec<Vector2>| -> Vec<Vector2> {
+            let m = ocs_matrix(normal);
+            points
+                .into_iter()
+                .map(|p| {
+                    let w = m.transform_point(Vector3::new(p.x, p.y, elevation));
+                    Vector2::new(w.x, w.y)
+                })
+                .collect()
+        };
+        let xy = |p: Vector3| Vector2::new(p.x, p.y);
+        let close = |mut points: Vec<Vector2>| {
+            if let Some(&first) = points.first() {
+                points.push(first);
+            }
+            points
+        };
+
+        Some(match entity {
+            EntityType::Point(point) => Shape::Point(xy(point.location)),
+            EntityType::Line(line) => Shape::LineString(vec![xy(line.start), xy(line.end)]),
+            EntityType::Arc(arc) => {
+                let center = xy(arc.center);
+                let sweep = ccw_sweep(arc.start_angle, arc.end_angle);
+                let points = arc_points(center, arc.radius, arc.start_angle, sweep, tolerance);
+                Shape::LineString(ocs(arc.normal, arc.center.z, points))
+            }
+            EntityType::Circle(circle) => {
+                let points = circle_points(xy(circle.center), circle.radius, tolerance);
+                Shape::LineString(close(ocs(circle.normal, circle.center.z, points)))
+            }
+            EntityType::Ellipse(ellipse) => Shape::LineString(
+                ellipse_points(
+                    ellipse.center,
+                    ellipse.major_axis,
+                    ellipse.normal,
+                    ellipse.minor_axis_ratio,
+                    ellipse.start_parameter,
+                    ellipse.end_parameter,
+                    tolerance,
+                )
+                .into_iter()
+                .map(xy)
+                .collect(),
+            ),
+            EntityType::LwPolyline(poly) => {
+                let vertices: Vec<(Vector2, f64)> = poly.vertices.iter().map(|v| (v.location, v.bulge)).collect();
+                let points = ocs(poly.normal, poly.elevation, bulge_polyline(&vertices, poly.is_closed, tolerance));
+                Shape::LineString(if poly.is_closed { close(points) } else { points })
+            }
+            EntityType::Polyline2D(poly) => {
+                let vertices: Vec<(Vector2, f64)> =
+                    poly.vertices.iter().map(|v| (xy(v.location), v.bulge)).collect();
+                let closed = poly.is_closed();
+                let points = ocs(poly.normal, poly.elevation, bulge_polyline(&vertices, closed, tolerance));
+                Shape::LineString(if closed { close(points) } else { points })
+            }
+            EntityType::Polyline(poly) => {
+                let points = poly.vertices.iter().map(|v| xy(v.location)).collect();
+                Shape::LineString(if poly.is_closed() { close(points) } else { points })
+            }
+            EntityType::Polyline3D(poly) => {
+                let points = poly.vertices.iter().map(|v| xy(v.position)).collect();
+                Shape::LineString(if poly.is_closed() { close(points) } else { points })
+            }
+            EntityType::Spline(spline) => {
+                let points = if spline.control_points.is_empty() {
+                    spline.fit_points.clone()
+                } else {
+                    spline_points(
+                        spline.degree.max(1) as usize,
+                        &spline.knots,
+                        &spline.control_points,
+                        &spline.weights,
+                        tolerance,
+                    )
+                };
+                Shape::LineString(points.into_iter().map(xy).collect())
+            }
+            EntityType::Hatch(hatch) => {
+                let _ = write!(properties, ",\"pattern\":{}", json_string(&hatch.pattern.name));
+                let rings = hatch
+                    .paths
+                    .iter()
+                    .map(|path| ocs(hatch.normal, hatch.elevation, hatch_path_points(path, tolerance)))
+                    .filter(|ring| ring.len() >= 3)
+                    .collect();
+                Shape::Polygons(rings)
+            }
+            EntityType::Solid(solid) => {
+                let ring = outline(&solid.as_triangles()).into_iter().map(xy).collect();
+                Shape::Polygons(vec![ring])
+            }
+            EntityType::Insert(insert) => {
+                let _ = write!(properties, ",\"block\":{}", json_string(&insert.block_name));
+                let _ = write!(properties, ",\"rotation\":{}", json_number(insert.rotation.to_degrees()));
+                let p = ocs(insert.normal, insert.insert_point.z, vec![xy(insert.insert_point)]);
+                Shape::Point(p[0])
+            }
+            EntityType::Text(text) => {
+                let _ = write!(properties, ",\"text\":{}", json_string(&text.value));
+                let _ = write!(properties, ",\"height\":{}", json_number(text.height));
+                let _ = write!(properties, ",\"rotation\":{}", json_number(text.rotation.to_degrees()));
+                let p = ocs(text.normal, text.insertion_point.z, vec![xy(text.insertion_point)]);
+                Shape::Point(p[0])
+            }
+            EntityType::MText(mtext) => {
+                let value = crate::render::scene::plain_mtext(&mtext.value);
+                let _ = write!(properties, ",\"text\":{}", json_string(&value));
+                let _ = write!(properties, ",\"height\":{}", json_number(mtext.height));
+                let _ = write!(properties, ",\"rotation\":{}", json_number(mtext.rotation.to_degrees()));
+                Shape::Point(xy(mtext.insertion_point))
+            }
+            _ => return None,
+        })
+    }
+
+    fn position(&self, out: &mut String, p: Vector2) {
+        let (x, y) = match &self.transform {
+            Some(t) => t.apply(p),
+            None => (p.x, p.y),
+        };
+        let _ = write!(out, "[{},{}]", json_number(x), json_number(y));
+    }
+
+    fn positions(&self, out: &mut String, points: &[Vector2]) {
+        out.push('[');
+        for (i, p) in points.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            self.position(out, *p);
+        }
+        out.push(']');
+    }
+
+    fn write_geometry(&self, out: &mut String, geometry: &Shape) -> Option<()> {
+        match geometry {
+            Shape::Point(p) => {
+                out.push_str("{\"type\":\"Point\",\"coordinates\":");
+                self.position(out, *p);
+            }
+            Shape::LineString(points) => {
+                if points.len() < 2 {
+                    return None;
+                }
+                out.push_str("{\"type\":\"LineString\",\"coordinates\":");
+                self.positions(out, points);
+            }
+            Shape::Polygons(rings) => {
+                let polygons = nest_rings(rings);
+                if polygons.is_empty() {
+                    return None;
+                }
+                let write_polygon = |out: &mut String, polygon: &Vec<Vec<Vector2>>| {
+                    out.push('[');
+                    for (i, ring) in polygon.iter().enumerate() {
+                        if i > 0 {
+                            out.push(',');
+                        }
+                        self.positions(out, ring);
+                    }
+                    out.push(']');
+                };
+                if polygons.len() == 1 {
+                    out.push_str("{\"type\":\"Polygon\",\"coordinates\":");
+                    write_polygon(out, &polygons[0]);
+                } else {
+                    out.push_str("{\"type\":\"MultiPolygon\",\"coordinates\":[");
+                    for (i, polygon) in polygons.iter().enumerate() {
+                        if i > 0 {
+                            out.push(',');
+                        }
+                        write_polygon(out, polygon);
+                    }
+                    out.push(']');
+                }
+            }
+        }
+        out.push('}');
+        Some(())
+    }
+}
+
+/// Group hatch boundary rings into polygons with holes
+///
+/// A ring inside an even number of other rings is an exterior and one
+/// inside an odd number is a hole of its innermost enclosing exterior,
+/// matching the even-odd rule hatches are filled with. Exteriors are made
+/// counter-clockwise and holes clockwise, and every ring is closed.
+fn nest_rings(rings: &[Vec<Vector2>]) -> Vec<Vec<Vec<Vector2>>> {
+    let depth: Vec<usize> = rings
+        .iter()
+        .enumerate()
+        .map(|(i, ring)| {
+            rings
+                .iter()
+                .enumerate()
+                .filter(|&(j, other)| j != i && point_in_polygon(other, ring[0]))
+                .count()
+        })
+        .collect();
+
+    let mut polygons: Vec<(usize, Vec<Vec<Vector2>>)> = Vec::new();
+    for (i, ring) in rings.iter().enumerate() {
+        if depth[i].is_multiple_of(2) {
+            polygons.push((i, vec![oriented(ring, true)]));
+        }
+    }
+    for (i, ring) in rings.iter().enumerate() {
+        if !depth[i].is_multiple_of(2) {
+            let parent = polygons
+                .iter_mut()
+                .filter(|(j, _)| depth[*j] + 1 == depth[i] && point_in_polygon(&rings[*j], ring[0]))
+                .map(|(_, polygon)| polygon)
+                .next();
+            if let Some(polygon) = parent {
+                polygon.push(oriented(ring, false));
+            }
+        }
+    }
+    polygons.into_iter().map(|(_, polygon)| polygon).collect()
+}
+
+fn oriented(ring: &[Vector2], counter_clockwise: bool) -> Vec<Vector2> {
+    let mut ring = ring.to_vec();
+    if ring.len() > 1 && ring.first() == ring.last() {
+        ring.pop();
+    }
+    if (signed_area(&ring) > 0.0) != counter_clockwise {
+        ring.reverse();
+    }
+    ring.push(ring[0]);
+    ring
+}
+
+fn xdata_value(value: &XDataValue) -> String {
+    let point = |p: &Vector3| format!("[{},{},{}]", json_number(p.x), json_number(p.y), json_number(p.z));
+    match value {
+        XDataValue::String(s) | XDataValue::ControlString(s) | XDataValue::LayerName(s) => json_string(s),
+        XDataValue::BinaryData(bytes) => {
+            let hex: String = bytes.iter().map(|b| format!("{b:02X}")).collect();
+            json_string(&hex)
+        }
+        XDataValue::Handle(h) => format!("\"{:X}\"", h.value()),
+        XDataValue::Point3D(p)
+        | XDataValue::Position3D(p)
+        | XDataValue::Displacement3D(p)
+        | XDataValue::Direction3D(p) => point(p),
+        XDataValue::Real(v) | XDataValue::Distance(v) | XDataValue::ScaleFactor(v) => json_number(*v),
+        XDataValue::Integer16(v) => v.to_string(),
+        XDataValue::Integer32(v) => v.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::{Arc, Hatch, Line};
+    use crate::entities::hatch::{BoundaryEdge, BoundaryPath, PolylineEdge};
+    use crate::xdata::ExtendedDataRecord;
+
+    #[test]
+    fn test_export_lines_arcs_and_xdata() {
+        let mut doc = CadDocument::new();
+        let mut line = Line::from_coords(0.0, 0.0, 0.0, 10.0, 5.0, 0.0);
+        let mut record = ExtendedDataRecord::new("GIS");
+        record.add_value(XDataValue::String("road \"A\"".to_string()));
+        record.add_value(XDataValue::Integer16(3));
+        line.common.extended_data.add_record(record);
+        doc.add_entity(EntityType::Line(line)).unwrap();
+        doc.add_entity(EntityType::Arc(Arc::from_coords(0.0, 0.0, 0.0, 1.0, 0.0, std::f64::consts::PI))).unwrap();
+
+        let json = export(&doc, &GeoJsonOptions::default()).unwrap();
+        assert!(json.starts_with("{\"type\":\"FeatureCollection\""));
+        assert!(json.contains("\"coordinates\":[[0,0],[10,5]]"));
+        assert!(json.contains("\"xdata\":{\"GIS\":[\"road \\\"A\\\"\",3]}"));
+        assert!(json.contains("\"type\":\"ARC\""));
+        assert_eq!(json.matches("\"type\":\"LineString\"").count(), 2);
+    }
+
+    #[test]
+    fn test_hatch_with_hole_becomes_polygon() {
+        let square = |size: f64| {
+            let mut path = BoundaryPath::new();
+            let mut edge = PolylineEdge::new(vec![
+                Vector2::new(-size, -size),
+                Vector2::new(size, -size),
+                Vector2::new(size, size),
+                Vector2::new(-size, size),
+            ], true);
+            edge.is_closed = true;
+            path.edges.push(BoundaryEdge::Polyline(edge));
+            path
+        };
+        let mut hatch = Hatch::new();
+        hatch.paths.push(square(1.0));
+        hatch.paths.push(square(2.0));
+        let rings: Vec<Vec<Vector2>> = hatch.paths.iter().map(|p| hatch_path_points(p, 0.01)).collect();
+
+        let polygons = nest_rings(&rings);
+        assert_eq!(polygons.len(), 1);
+        assert_eq!(polygons[0].len(), 2);
+        assert!(signed_area(&polygons[0][0]) > 0.0);
+        assert!((signed_area(&polygons[0][0]) - 16.0).abs() < 1e-9);
+        assert!(signed_area(&polygons[0][1]) < 0.0);
+    }
+
+    #[test]
+    fn test_geographic_transform() {
+        let mut geo = GeoData::new();
+        geo.coordinate_type = 3;
+        geo.design_point = Vector3::new(1000.0, 1000.0, 0.0);
+        geo.reference_point = Vector3::new(13.4, 52.5, 0.0);
+        // Grid north points along +x: drawing +y is west
+        geo.north_direction = Vector2::new(1.0, 0.0);
+        let transform = GeoTransform::from_geodata(&geo).unwrap();
+
+        let (lon, lat) = transform.apply(Vector2::new(1000.0, 1000.0));
+        assert!((lon - 13.4).abs() < 1e-12 && (lat - 52.5).abs() < 1e-12);
+
+        // 1 km north is about 0.009 degrees of latitude
+        let (lon, lat) = transform.apply(Vector2::new(2000.0, 1000.0));
+        assert!((lon - 13.4).abs() < 1e-9);
+        assert!((lat - 52.5 - 0.008_99).abs() < 1e-4);
+        let (lon, _) = transform.apply(Vector2::new(1000.0, 2000.0));
+        assert!(lon < 13.4);
+
+        let p = Vector2::new(1234.5, 987.6);
+        let (lon, lat) = transform.apply(p);
+        let back = transform.invert(lon, lat);
+        assert!((back - p).length() < 1e-6);
+
+        geo.coordinate_type = 2;
+        assert!(GeoTransform::from_geodata(&geo).is_err());
+    }
+}
@@ -0,0 +1,324 @@
+//! glTF 2.0 output
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::path::Path;
+
+use super::{collect_parts, triangle_normal, MeshExportOptions, MeshPart};
+use crate::document::CadDocument;
+use crate::error::Result;
+use crate::io::json::{json_number, json_string};
+use crate::types::Vector3;
+
+const ARRAY_BUFFER: u32 = 34962;
+const FLOAT: u32 = 5126;
+const MODE_LINES: u32 = 1;
+const MODE_TRIANGLES: u32 = 4;
+
+/// glTF JSON with the geometry embedded as a base64 data URI
+///
+/// Each entity becomes a node with its own mesh; every layer gets a
+/// material colored like the layer. The drawing's Z-up axes are turned to
+/// glTF's Y-up by the root node, which also carries the offset to the
+/// model's center so that single-precision vertices keep their accuracy.
+pub fn to_gltf(doc: &CadDocument, options: &MeshExportOptions) -> String {
+    let (json, buffer) = build(doc, options, true);
+    debug_assert!(buffer.is_empty());
+    json
+}
+
+/// Binary glTF (GLB) container
+pub fn to_glb(doc: &CadDocument, options: &MeshExportOptions) -> Vec<u8> {
+    let (json, mut buffer) = build(doc, options, false);
+    let mut json = json.into_bytes();
+    while json.len() % 4 != 0 {
+        json.push(b' ');
+    }
+    while buffer.len() % 4 != 0 {
+        buffer.push(0);
+    }
+
+    let mut total = 12 + 8 + json.len();
+    if !buffer.is_empty() {
+        total += 8 + buffer.len();
+    }
+    let mut out = Vec::with_capacity(total);
+    out.extend_from_slice(b"glTF");
+    out.extend_from_slice(&2u32.to_le_bytes());
+    out.extend_from_slice(&(total as u32).to_le_bytes());
+    out.extend_from_slice(&(json.len() as u32).to_le_bytes());
+    out.extend_from_slice(b"JSON");
+    out.extend_from_slice(&json);
+    if !buffer.is_empty() {
+        out.extend_from_slice(&(buffer.len() as u32).to_le_bytes());
+        out.extend_from_slice(b"BIN\0");
+        out.extend_from_slice(&buffer);
+    }
+    out
+}
+
+/// Write the model space meshes to a `.glb` (binary) or `.gltf` file
+pub fn write_gltf<P: AsRef<Path>>(doc: &CadDocument, options: &MeshExportOptions, path: P, binary: bool) -> Result<()> {
+    if binary {
+        std::fs::write(path, to_glb(doc, options))?;
+    } else {
+        std::fs::write(path, to_gltf(doc, options))?;
+    }
+    Ok(())
+}
+
+/// Buffer views and accessors as they are appended
+#[derive(Default)]
+struct Buffers {
+    data: Vec<u8>,
+    views: Vec<String>,
+    accessors: Vec<String>,
+}
+
+impl Buffers {
+    /// Append VEC3 float data and return its accessor index
+    fn vec3(&mut self, values: &[[f32; 3]], bounds: bool) -> usize {
+        let offset = self.data.len();
+        for v in values {
+            for c in v {
+                self.data.extend_from_slice(&c.to_le_bytes());
+            }
+        }
+        self.views.push(format!(
+            "{{\"buffer\":0,\"byteOffset\":{offset},\"byteLength\":{},\"target\":{ARRAY_BUFFER}}}",
+            values.len() * 12
+        ));
+
+        let mut accessor = format!(
+            "{{\"bufferView\":{},\"componentType\":{FLOAT},\"count\":{},\"type\":\"VEC3\"",
+            self.views.len() - 1,
+            values.len()
+        );
+        // POSITION accessors must carry their bounds
+        if bounds {
+            let mut min = [f32::INFINITY; 3];
+            let mut max = [f32::NEG_INFINITY; 3];
+            for v in values {
+                for k in 0..3 {
+                    min[k] = min[k].min(v[k]);
+                    max[k] = max[k].max(v[k]);
+                }
+            }
+            let list = |a: [f32; 3]| a.map(|c| json_number(c as f64)).join(",");
+            let _ = write!(accessor, ",\"min\":[{}],\"max\":[{}]", list(min), list(max));
+        }
+        accessor.push('}');
+        self.accessors.push(accessor);
+        self.accessors.len() - 1
+    }
+}
+
+fn build(doc: &CadDocument, options: &MeshExportOptions, embed: bool) -> (String, Vec<u8>) {
+    let parts = collect_parts(doc, options);
+    let origin = center(&parts);
+    let local = |v: Vector3| {
+        let d = v - origin;
+        [d.x as f32, d.y as f32, d.z as f32]
+    };
+
+    let mut buffers = Buffers::default();
+    let mut materials: Vec<String> = Vec::new();
+    let mut material_of_layer: HashMap<String, usize> = HashMap::new();
+    let mut meshes = Vec::new();
+    let mut nodes = Vec::new();
+
+    for part in &parts {
+        let key = part.layer.to_uppercase();
+        let material = *material_of_layer.entry(key).or_insert_with(|| {
+            let (r, g, b) = doc
+                .layers
+                .get(&part.layer)
+                .and_then(|l| l.color.to_rgb())
+                .unwrap_or(part.color);
+            let linear = [r, g, b].map(|c| json_number(srgb_to_linear(c) as f64)).join(",");
+            materials.push(format!(
+                "{{\"name\":{},\"pbrMetallicRoughness\":{{\"baseColorFactor\":[{linear},1],\"metallicFactor\":0,\"roughnessFactor\":0.9}},\"doubleSided\":true}}",
+                json_string(&part.layer)
+            ));
+            materials.len() - 1
+        });
+
+        let mut primitives = Vec::new();
+        // Flat shading: every triangle gets its own three vertices
+        let mut positions = Vec::new();
+        let mut normals = Vec::new();
+        for [a, b, c] in part.triangles() {
+            let (a, b, c) = (part.vertices[a], part.vertices[b], part.vertices[c]);
+            let n = triangle_normal(a, b, c);
+            if n == Vector3::ZERO {
+                continue;
+            }
+            positions.extend([local(a), local(b), local(c)]);
+            normals.extend([[n.x as f32, n.y as f32, n.z as f32]; 3]);
+        }
+        if !positions.is_empty() {
+            let position = buffers.vec3(&positions, true);
+            let normal = buffers.vec3(&normals, false);
+            primitives.push(format!(
+                "{{\"attributes\":{{\"POSITION\":{position},\"NORMAL\":{normal}}},\"material\":{material},\"mode\":{MODE_TRIANGLES}}}"
+            ));
+        }
+
+        let segments: Vec<[f32; 3]> = part
+            .lines
+            .iter()
+            .flat_map(|line| line.windows(2).flat_map(|w| [local(part.vertices[w[0]]), local(part.vertices[w[1]])]))
+            .collect();
+        if !segments.is_empty() {
+            let position = buffers.vec3(&segments, true);
+            primitives.push(format!(
+                "{{\"attributes\":{{\"POSITION\":{position}}},\"material\":{material},\"mode\":{MODE_LINES}}}"
+            ));
+        }
+
+        if primitives.is_empty() {
+            continue;
+        }
+        meshes.push(format!("{{\"name\":{},\"primitives\":[{}]}}", json_string(&part.name), primitives.join(",")));
+        nodes.push(format!("{{\"name\":{},\"mesh\":{}}}", json_string(&part.name), meshes.len() - 1));
+    }
+
+    // Root node: Z-up to Y-up, then back to the model's position
+    let half = std::f64::consts::FRAC_1_SQRT_2;
+    let children: Vec<String> = (1..=nodes.len()).map(|i| i.to_string()).collect();
+    let translation = [origin.x, origin.z, -origin.y].map(json_number).join(",");
+    let mut all_nodes = vec![format!(
+        "{{\"name\":\"Model\",\"rotation\":[{},0,0,{}],\"translation\":[{translation}]{}}}",
+        json_number(-half),
+        json_number(half),
+        if children.is_empty() { String::new() } else { format!(",\"children\":[{}]", children.join(",")) }
+    )];
+    all_nodes.extend(nodes);
+
+    let mut json = String::from("{\"asset\":{\"version\":\"2.0\",\"generator\":\"acadrust\"},\"scene\":0,\"scenes\":[{\"nodes\":[0]}]");
+    let _ = write!(json, ",\"nodes\":[{}]", all_nodes.join(","));
+    if !meshes.is_empty() {
+        let _ = write!(json, ",\"meshes\":[{}]", meshes.join(","));
+        let _ = write!(json, ",\"materials\":[{}]", materials.join(","));
+        let _ = write!(json, ",\"accessors\":[{}]", buffers.accessors.join(","));
+        let _ = write!(json, ",\"bufferViews\":[{}]", buffers.views.join(","));
+        if embed {
+            let _ = write!(
+                json,
+                ",\"buffers\":[{{\"byteLength\":{},\"uri\":\"data:application/octet-stream;base64,{}\"}}]",
+                buffers.data.len(),
+                base64(&buffers.data)
+            );
+        } else {
+            let _ = write!(json, ",\"buffers\":[{{\"byteLength\":{}}}]", buffers.data.len());
+        }
+    }
+    json.push('}');
+    let data = if embed { Vec::new() } else { buffers.data };
+    (json, data)
+}
+
+/// Center of the bounding box of all vertices
+fn center(parts: &[MeshPart]) -> Vector3 {
+    let mut vertices = parts.iter().flat_map(|p| p.vertices.iter());
+    let Some(&first) = vertices.next() else {
+        return Vector3::ZERO;
+    };
+    let (min, max) = vertices.fold((first, first), |(min, max), v| {
+        (
+            Vector3::new(min.x.min(v.x), min.y.min(v.y), min.z.min(v.z)),
+            Vector3::new(max.x.max(v.x), max.y.max(v.y), max.z.max(v.z)),
+        )
+    });
+    (min + max) * 0.5
+}
+
+fn srgb_to_linear(channel: u8) -> f32 {
+    let c = channel as f32 / 255.0;
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let bytes = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let n = (bytes[0] as u32) << 16 | (bytes[1] as u32) << 8 | bytes[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::{Circle, EntityType, PolyfaceMesh};
+    use crate::io::json;
+    use crate::tables::Layer;
+    use crate::types::Color;
+
+    fn sample() -> CadDocument {
+        let mut doc = CadDocument::new();
+        let mut layer = Layer::new("STEEL");
+        layer.color = Color::Index(1);
+        doc.layers.add(layer).unwrap();
+
+        let mut cube = PolyfaceMesh::create_box(Vector3::new(1000.0, 2000.0, 0.0), Vector3::new(1001.0, 2001.0, 1.0));
+        cube.common.layer = "STEEL".to_string();
+        doc.add_entity(EntityType::PolyfaceMesh(cube)).unwrap();
+        let mut column = Circle::from_coords(1005.0, 2000.0, 0.0, 0.5);
+        column.thickness = 3.0;
+        column.common.layer = "STEEL".to_string();
+        doc.add_entity(EntityType::Circle(column)).unwrap();
+        doc
+    }
+
+    #[test]
+    fn test_gltf_structure() {
+        let doc = sample();
+        let gltf = json::parse(&to_gltf(&doc, &MeshExportOptions::default())).unwrap();
+        let count = |key: &str| gltf.get(key).and_then(|v| v.as_array()).map_or(0, |a| a.len());
+        assert_eq!(count("meshes"), 2);
+        assert_eq!(count("nodes"), 3);
+        assert_eq!(count("materials"), 1);
+
+        let material = &gltf.get("materials").unwrap().as_array().unwrap()[0];
+        assert_eq!(material.get("name").and_then(|n| n.as_str()), Some("STEEL"));
+        let buffer = &gltf.get("buffers").unwrap().as_array().unwrap()[0];
+        let uri = buffer.get("uri").and_then(|u| u.as_str()).unwrap();
+        let length = buffer.get("byteLength").and_then(|l| l.as_f64()).unwrap() as usize;
+        let encoded = uri.split_once(',').unwrap().1;
+        assert_eq!(encoded.len(), length.div_ceil(3) * 4);
+        // Cube: 12 triangles with positions and normals
+        assert!(length >= 12 * 3 * 12 * 2);
+    }
+
+    #[test]
+    fn test_glb_container() {
+        let glb = to_glb(&sample(), &MeshExportOptions::default());
+        assert_eq!(&glb[0..4], b"glTF");
+        assert_eq!(u32::from_le_bytes(glb[8..12].try_into().unwrap()) as usize, glb.len());
+        let json_length = u32::from_le_bytes(glb[12..16].try_into().unwrap()) as usize;
+        assert_eq!(&glb[16..20], b"JSON");
+        assert_eq!(&glb[20 + json_length + 4..20 + json_length + 8], b"BIN\0");
+        assert!(json::parse(std::str::from_utf8(&glb[20..20 + json_length]).unwrap()).is_ok());
+    }
+
+    #[test]
+    fn test_base64() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+    }
+}
@@ -0,0 +1,426 @@
+//! STL, Wavefront OBJ and glTF export of 3D content
+//!
+//! Model space MESH, polyface and polygon mesh POLYLINE, and 3DFACE
+//! entities, plus lines, arcs, circles, 2D solids and lightweight
+//! polylines extruded by their thickness, are collected as polygon meshes
+//! — block references are expanded — and written as binary or ASCII STL,
+//! as OBJ or as glTF. 3DSOLID,
+//! REGION and BODY entities have no triangulation without an ACIS kernel;
+//! their display wires go to OBJ as line elements and are left out of STL,
+//! which has no way to represent them.
+//!
+//! ```rust,ignore
+//! use acadrust::io::mesh_export::{self, MeshExportOptions};
+//!
+//! let options = MeshExportOptions::default();
+//! std::fs::write("part.stl", mesh_export::to_stl_binary(&doc, &options))?;
+//! std::fs::write("part.obj", mesh_export::to_obj(&doc, &options))?;
+//! std::fs::write("part.glb", mesh_export::to_glb(&doc, &options))?;
+//! ```
+
+mod gltf;
+mod obj;
+mod stl;
+
+pub use gltf::{to_glb, to_gltf, write_gltf};
+pub use obj::{to_obj, write_obj};
+pub use stl::{to_stl_ascii, to_stl_binary, write_stl};
+
+use std::collections::HashSet;
+
+use crate::document::CadDocument;
+use crate::entities::{outline, Entity, EntityType, Wire};
+use crate::render::scene::{insert_transform, owned_entities};
+use crate::render::tessellate::{arc_points, bulge_polyline, ccw_sweep, circle_points};
+use crate::tables::BlockRecord;
+use crate::types::geometry::{ocs_matrix, signed_area, triangulate};
+use crate::types::{aci_to_rgb, Color, Transform, Vector2, Vector3};
+
+/// Deepest block nesting followed before giving up on a reference cycle
+const MAX_NESTING: usize = 32;
+
+/// Options for mesh export
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeshExportOptions {
+    /// Layers to leave out, by name (case-insensitive)
+    pub hidden_layers: HashSet<String>,
+    /// Expand block references
+    pub expand_inserts: bool,
+    /// Include 3DSOLID, REGION and BODY display wires where the format
+    /// allows lines
+    pub include_wires: bool,
+    /// Largest chord deviation when faceting extruded curves, in drawing
+    /// units
+    pub tolerance: f64,
+}
+
+impl Default for MeshExportOptions {
+    fn default() -> Self {
+        Self {
+            hidden_layers: HashSet::new(),
+            expand_inserts: true,
+            include_wires: true,
+            tolerance: 0.01,
+        }
+    }
+}
+
+impl MeshExportOptions {
+    /// Hide a layer
+    pub fn hide_layer(mut self, name: impl AsRef<str>) -> Self {
+        self.hidden_layers.insert(name.as_ref().to_uppercase());
+        self
+    }
+}
+
+/// Geometry of one exported entity, in WCS
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeshPart {
+    /// Entity type and handle, e.g. `MESH_2A`
+    pub name: String,
+    /// Resolved layer name
+    pub layer: String,
+    /// Resolved color
+    pub color: (u8, u8, u8),
+    pub vertices: Vec<Vector3>,
+    /// Polygons as indices into `vertices`, counter-clockwise seen from
+    /// outside where the source defines an orientation
+    pub faces: Vec<Vec<usize>>,
+    /// Polylines as indices into `vertices`
+    pub lines: Vec<Vec<usize>>,
+}
+
+impl MeshPart {
+    fn new(name: String, layer: String, color: (u8, u8, u8)) -> Self {
+        Self { name, layer, color, vertices: Vec::new(), faces: Vec::new(), lines: Vec::new() }
+    }
+
+    /// Faces split into triangles by fanning from their first vertex
+    pub fn triangles(&self) -> impl Iterator<Item = [usize; 3]> + '_ {
+        self.faces
+            .iter()
+            .flat_map(|face| (1..face.len().saturating_sub(1)).map(move |i| [face[0], face[i], face[i + 1]]))
+    }
+}
+
+/// Collect the mesh content of model space
+pub fn collect_parts(doc: &CadDocument, options: &MeshExportOptions) -> Vec<MeshPart> {
+    let mut collector = Collector { doc, options, parts: Vec::new() };
+    if let Some(model) = doc.block_records.get("*Model_Space") {
+        collector.block(model, &Transform::identity(), &Inherited::default(), 0);
+    }
+    collector.parts
+}
+
+/// Triangle unit normal, or zero for a degenerate triangle
+pub(crate) fn triangle_normal(a: Vector3, b: Vector3, c: Vector3) -> Vector3 {
+    let n = (b - a).cross(&(c - a));
+    let length = n.length();
+    if length > 1e-12 {
+        n * (1.0 / length)
+    } else {
+        Vector3::ZERO
+    }
+}
+
+/// Layer and color passed from an INSERT to its block's entities
+#[derive(Debug, Clone)]
+struct Inherited {
+    layer: Option<String>,
+    color: Color,
+}
+
+impl Default for Inherited {
+    fn default() -> Self {
+        Self { layer: None, color: Color::Index(7) }
+    }
+}
+
+struct Collector<'a> {
+    doc: &'a CadDocument,
+    options: &'a MeshExportOptions,
+    parts: Vec<MeshPart>,
+}
+
+impl Collector<'_> {
+    fn block(&mut self, record: &BlockRecord, transform: &Transform, inherited: &Inherited, depth: usize) {
+        for entity in owned_entities(self.doc, record) {
+            self.entity(entity, transform, inherited, depth);
+        }
+    }
+
+    /// Resolved layer and color, or `None` if the entity is hidden
+    fn style(&self, entity: &dyn Entity, inherited: &Inherited) -> Option<(String, Color)> {
+        if entity.is_invisible() {
+            return None;
+        }
+        let layer_name = match (&inherited.layer, entity.layer()) {
+            (Some(parent), "0") => parent.clone(),
+            (_, own) => own.to_string(),
+        };
+        if self.options.hidden_layers.contains(&layer_name.to_uppercase()) {
+            return None;
+        }
+        let layer = self.doc.layers.get(&layer_name);
+        if layer.is_some_and(|l| l.is_off() || l.is_frozen()) {
+            return None;
+        }
+        let color = match entity.color() {
+            Color::ByLayer => layer.map(|l| l.color).unwrap_or(Color::Index(7)),
+            Color::ByBlock => inherited.color,
+            color => color,
+        };
+        Some((layer_name, color))
+    }
+
+    fn entity(&mut self, entity: &EntityType, transform: &Transform, inherited: &Inherited, depth: usize) {
+        let Some((layer, color)) = self.style(entity.as_entity(), inherited) else {
+            return;
+        };
+        if let EntityType::Insert(insert) = entity {
+            if !self.options.expand_inserts || depth >= MAX_NESTING {
+                return;
+            }
+            let Some(record) = self.doc.block_records.get(&insert.block_name) else {
+                return;
+            };
+            let inherited = Inherited { layer: Some(layer), color };
+            for row in 0..insert.row_count.max(1) {
+                for column in 0..insert.column_count.max(1) {
+                    let transform = insert_transform(insert, row, column).then(transform);
+                    self.block(record, &transform, &inherited, depth + 1);
+                }
+            }
+            return;
+        }
+
+        let Some(mut part) = entity_part(entity, self.options) else {
+            return;
+        };
+        part.name = format!("{}_{:X}", entity.as_entity().entity_type(), entity.common().handle.value());
+        part.layer = layer;
+        part.color = color.to_rgb().unwrap_or_else(|| aci_to_rgb(7));
+        if !transform.is_identity() {
+            part.vertices.iter_mut().for_each(|v| *v = transform.apply(*v));
+        }
+        self.parts.push(part);
+    }
+}
+
+/// Faces and wires of one entity in its own coordinates, with no name or
+/// style; `None` if the entity has no 3D content
+pub(crate) fn entity_part(entity: &EntityType, options: &MeshExportOptions) -> Option<MeshPart> {
+    let mut part = MeshPart::new(String::new(), String::new(), aci_to_rgb(7));
+    match entity {
+        EntityType::Mesh(mesh) => {
+            part.vertices = mesh.vertices.clone();
+            part.faces = mesh
+                .faces
+                .iter()
+                .filter(|f| f.vertices.len() >= 3 && f.vertices.iter().all(|&i| i < mesh.vertices.len()))
+                .map(|f| f.vertices.clone())
+                .collect();
+        }
+        EntityType::PolyfaceMesh(mesh) => {
+            part.vertices = mesh.vertices.iter().map(|v| v.location).collect();
+            let count = part.vertices.len();
+            part.faces = mesh
+                .faces
+                .iter()
+                .map(|f| {
+                    [f.index1, f.index2, f.index3, f.index4]
+                        .into_iter()
+                        .filter(|&i| i != 0)
+                        .map(|i| i.unsigned_abs() as usize - 1)
+                        .collect::<Vec<_>>()
+                })
+                .filter(|f| f.len() >= 3 && f.iter().all(|&i| i < count))
+                .collect();
+        }
+        EntityType::PolygonMesh(mesh) => {
+            let (m, n) = (mesh.m_vertex_count.max(0) as usize, mesh.n_vertex_count.max(0) as usize);
+            if m < 2 || n < 2 || mesh.vertices.len() < m * n {
+                return None;
+            }
+            part.vertices = mesh.vertices[..m * n].iter().map(|v| v.location).collect();
+            let rows = if mesh.is_closed_m() { m } else { m - 1 };
+            let columns = if mesh.is_closed_n() { n } else { n - 1 };
+            for i in 0..rows {
+                for j in 0..columns {
+                    let (i1, j1) = ((i + 1) % m, (j + 1) % n);
+                    part.faces.push(vec![i * n + j, i1 * n + j, i1 * n + j1, i * n + j1]);
+                }
+            }
+        }
+        EntityType::Face3D(face) => {
+            for triangle in face.as_triangles() {
+                let face = triangle.corners.map(|corner| {
+                    part.vertices.iter().position(|&v| v == corner).unwrap_or_else(|| {
+                        part.vertices.push(corner);
+                        part.vertices.len() - 1
+                    })
+                });
+                part.faces.push(face.to_vec());
+            }
+        }
+        EntityType::Line(line) if line.thickness != 0.0 => {
+            extrude(&mut part, &[line.start, line.end], line.normal * line.thickness, false);
+        }
+        EntityType::Arc(arc) if arc.thickness != 0.0 => {
+            let center = Vector2::new(arc.center.x, arc.center.y);
+            let sweep = ccw_sweep(arc.start_angle, arc.end_angle);
+            let points = arc_points(center, arc.radius, arc.start_angle, sweep, options.tolerance);
+            let profile = to_wcs(arc.normal, arc.center.z, &points);
+            extrude(&mut part, &profile, arc.normal * arc.thickness, false);
+        }
+        EntityType::Circle(circle) if circle.thickness != 0.0 => {
+            let center = Vector2::new(circle.center.x, circle.center.y);
+            let points = circle_points(center, circle.radius, options.tolerance);
+            let profile = to_wcs(circle.normal, circle.center.z, &points);
+            extrude(&mut part, &profile, circle.normal * circle.thickness, true);
+        }
+        EntityType::Solid(solid) if solid.thickness != 0.0 => {
+            let profile = outline(&solid.as_triangles());
+            extrude(&mut part, &profile, solid.normal * solid.thickness, true);
+        }
+        EntityType::LwPolyline(poly) if poly.thickness != 0.0 => {
+            let vertices: Vec<(Vector2, f64)> = poly.vertices.iter().map(|v| (v.location, v.bulge)).collect();
+            let points = bulge_polyline(&vertices, poly.is_closed, options.tolerance);
+            let profile = to_wcs(poly.normal, poly.elevation, &points);
+            extrude(&mut part, &profile, poly.normal * poly.thickness, poly.is_closed);
+        }
+        EntityType::Solid3D(solid) if options.include_wires => wires(&mut part, &solid.wires),
+        EntityType::Region(region) if options.include_wires => wires(&mut part, &region.wires),
+        EntityType::Body(body) if options.include_wires => wires(&mut part, &body.wires),
+        _ => return None,
+    }
+    if part.faces.is_empty() && part.lines.is_empty() {
+        return None;
+    }
+    Some(part)
+}
+
+fn to_wcs(normal: Vector3, elevation: f64, points: &[Vector2]) -> Vec<Vector3> {
+    let m = ocs_matrix(normal);
+    points.iter().map(|p| m.transform_point(Vector3::new(p.x, p.y, elevation))).collect()
+}
+
+/// Sweep `profile` along `direction`; a closed profile also gets end caps
+///
+/// Faces are oriented outwards whichever way the profile winds.
+fn extrude(part: &mut MeshPart, profile: &[Vector3], direction: Vector3, closed: bool) {
+    let n = profile.len();
+    if n < 2 || (closed && n < 3) || direction.length() < 1e-12 {
+        return;
+    }
+    // Profile in a plane across the direction, to find its winding
+    let m = ocs_matrix(direction.normalize()).transpose();
+    let mut flat: Vec<Vector2> = profile
+        .iter()
+        .map(|p| {
+            let p = m.transform_point(*p);
+            Vector2::new(p.x, p.y)
+        })
+        .collect();
+    let mut profile = profile.to_vec();
+    if closed && signed_area(&flat) < 0.0 {
+        profile.reverse();
+        flat.reverse();
+    }
+
+    part.vertices.extend(profile.iter().copied());
+    part.vertices.extend(profile.iter().map(|&p| p + direction));
+    let segments = if closed { n } else { n - 1 };
+    for i in 0..segments {
+        let j = (i + 1) % n;
+        part.faces.push(vec![i, j, n + j, n + i]);
+    }
+    if closed {
+        for [a, b, c] in triangulate(&flat) {
+            part.faces.push(vec![c, b, a]);
+            part.faces.push(vec![n + a, n + b, n + c]);
+        }
+    }
+}
+
+fn wires(part: &mut MeshPart, wires: &[Wire]) {
+    for wire in wires.iter().filter(|w| w.points.len() >= 2) {
+        let start = part.vertices.len();
+        part.vertices.extend(wire.points.iter().map(|&p| {
+            if wire.has_transform {
+                (wire.x_axis * p.x + wire.y_axis * p.y + wire.z_axis * p.z) * wire.scale + wire.translation
+            } else {
+                p
+            }
+        }));
+        part.lines.push((start..part.vertices.len()).collect());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::{Face3D, Insert, LwPolyline, PolyfaceMesh};
+    use crate::tables::BlockRecord;
+
+    #[test]
+    fn test_collect_meshes_and_expand_inserts() {
+        let mut doc = CadDocument::new();
+        let mut cube = PolyfaceMesh::create_box(Vector3::ZERO, Vector3::new(1.0, 1.0, 1.0));
+        cube.common.layer = "PARTS".to_string();
+        doc.add_entity(EntityType::PolyfaceMesh(cube)).unwrap();
+
+        let mut record = BlockRecord::new("TILE");
+        record.entities.push(EntityType::Face3D(Face3D::new(
+            Vector3::ZERO,
+            Vector3::new(1.0, 0.0, 0.0),
+            Vector3::new(1.0, 1.0, 0.0),
+            Vector3::new(1.0, 1.0, 0.0),
+        )));
+        doc.block_records.add(record).unwrap();
+        let mut insert = Insert::new("TILE", Vector3::new(10.0, 0.0, 0.0));
+        insert.common.layer = "TILES".to_string();
+        doc.add_entity(EntityType::Insert(insert)).unwrap();
+
+        let parts = collect_parts(&doc, &MeshExportOptions::default());
+        assert_eq!(parts.len(), 2);
+        let cube = parts.iter().find(|p| p.name.starts_with("POLYLINE")).unwrap();
+        assert_eq!(cube.triangles().count(), 12);
+        assert_eq!(cube.layer, "PARTS");
+
+        let tile = parts.iter().find(|p| p.name.starts_with("3DFACE")).unwrap();
+        assert_eq!(tile.layer, "TILES");
+        assert_eq!(tile.vertices.len(), 3);
+        assert_eq!(tile.vertices[1], Vector3::new(11.0, 0.0, 0.0));
+
+        let parts = collect_parts(&doc, &MeshExportOptions::default().hide_layer("parts"));
+        assert_eq!(parts.len(), 1);
+    }
+
+    #[test]
+    fn test_extruded_profiles_are_closed_and_outward() {
+        // Concave L-shaped outline, wound clockwise, extruded downwards
+        let mut doc = CadDocument::new();
+        let mut outline = LwPolyline::from_points(vec![
+            Vector2::new(0.0, 0.0),
+            Vector2::new(0.0, 2.0),
+            Vector2::new(1.0, 2.0),
+            Vector2::new(1.0, 1.0),
+            Vector2::new(2.0, 1.0),
+            Vector2::new(2.0, 0.0),
+        ]);
+        outline.close();
+        outline.thickness = -2.0;
+        doc.add_entity(EntityType::LwPolyline(outline)).unwrap();
+
+        let parts = collect_parts(&doc, &MeshExportOptions::default());
+        assert_eq!(parts.len(), 1);
+        // Divergence theorem: outward faces give the positive volume
+        let part = &parts[0];
+        let volume: f64 = part
+            .triangles()
+            .map(|[a, b, c]| part.vertices[a].dot(&part.vertices[b].cross(&part.vertices[c])) / 6.0)
+            .sum();
+        assert!((volume - 6.0).abs() < 1e-9, "volume {volume}");
+    }
+}
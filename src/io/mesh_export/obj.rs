@@ -0,0 +1,80 @@
+//! Wavefront OBJ output
+
+use std::fmt::Write as _;
+use std::path::Path;
+
+use super::{collect_parts, MeshExportOptions};
+use crate::document::CadDocument;
+use crate::error::Result;
+
+/// Wavefront OBJ of the model space meshes
+///
+/// Every entity is an `o` object in a `g` group named after its layer;
+/// faces keep their polygons and wires become `l` elements.
+pub fn to_obj(doc: &CadDocument, options: &MeshExportOptions) -> String {
+    let mut out = String::from("# acadrust OBJ export\n");
+    // OBJ indices are 1-based and global to the file
+    let mut base = 1;
+    for part in collect_parts(doc, options) {
+        let _ = writeln!(out, "o {}", obj_name(&part.name));
+        let _ = writeln!(out, "g {}", obj_name(&part.layer));
+        for v in &part.vertices {
+            let _ = writeln!(out, "v {} {} {}", v.x, v.y, v.z);
+        }
+        for (element, polygons) in [("f", &part.faces), ("l", &part.lines)] {
+            for polygon in polygons {
+                out.push_str(element);
+                for i in polygon {
+                    let _ = write!(out, " {}", base + i);
+                }
+                out.push('\n');
+            }
+        }
+        base += part.vertices.len();
+    }
+    out
+}
+
+/// Write the model space meshes to an OBJ file
+pub fn write_obj<P: AsRef<Path>>(doc: &CadDocument, options: &MeshExportOptions, path: P) -> Result<()> {
+    std::fs::write(path, to_obj(doc, options))?;
+    Ok(())
+}
+
+/// Names are whitespace-delimited in OBJ
+fn obj_name(name: &str) -> String {
+    name.chars().map(|c| if c.is_whitespace() { '_' } else { c }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::{EntityType, Face3D, Solid3D, Wire};
+    use crate::types::Vector3;
+
+    #[test]
+    fn test_obj_faces_and_wires() {
+        let mut doc = CadDocument::new();
+        let mut face = Face3D::new(
+            Vector3::ZERO,
+            Vector3::new(1.0, 0.0, 0.0),
+            Vector3::new(1.0, 1.0, 0.0),
+            Vector3::new(0.0, 1.0, 0.0),
+        );
+        face.common.layer = "My Layer".to_string();
+        doc.add_entity(EntityType::Face3D(face)).unwrap();
+        let mut solid = Solid3D::new();
+        let mut wire = Wire::new();
+        wire.points = vec![Vector3::ZERO, Vector3::new(0.0, 0.0, 5.0)];
+        solid.wires.push(wire);
+        doc.add_entity(EntityType::Solid3D(solid)).unwrap();
+
+        let obj = to_obj(&doc, &MeshExportOptions::default());
+        assert!(obj.contains("g My_Layer\n"));
+        assert!(obj.contains("f 1 2 3\nf 1 3 4\n"));
+        assert!(obj.contains("l 5 6\n"));
+
+        let obj = to_obj(&doc, &MeshExportOptions { include_wires: false, ..Default::default() });
+        assert!(!obj.contains("\nl "));
+    }
+}
@@ -0,0 +1,91 @@
+//! STL output
+
+use std::fmt::Write as _;
+use std::path::Path;
+
+use super::{collect_parts, triangle_normal, MeshExportOptions, MeshPart};
+use crate::document::CadDocument;
+use crate::error::Result;
+use crate::types::Vector3;
+
+fn triangles(parts: &[MeshPart]) -> impl Iterator<Item = [Vector3; 3]> + '_ {
+    parts
+        .iter()
+        .flat_map(|part| part.triangles().map(move |[a, b, c]| [part.vertices[a], part.vertices[b], part.vertices[c]]))
+}
+
+/// Binary STL of the model space meshes
+pub fn to_stl_binary(doc: &CadDocument, options: &MeshExportOptions) -> Vec<u8> {
+    let parts = collect_parts(doc, options);
+    let count = triangles(&parts).count();
+
+    let mut out = Vec::with_capacity(84 + count * 50);
+    let mut header = [0u8; 80];
+    let title = b"acadrust STL";
+    header[..title.len()].copy_from_slice(title);
+    out.extend_from_slice(&header);
+    out.extend_from_slice(&(count as u32).to_le_bytes());
+    for [a, b, c] in triangles(&parts) {
+        for v in [triangle_normal(a, b, c), a, b, c] {
+            for component in [v.x, v.y, v.z] {
+                out.extend_from_slice(&(component as f32).to_le_bytes());
+            }
+        }
+        // Attribute byte count
+        out.extend_from_slice(&[0, 0]);
+    }
+    out
+}
+
+/// ASCII STL of the model space meshes; `name` is the solid name
+pub fn to_stl_ascii(doc: &CadDocument, options: &MeshExportOptions, name: &str) -> String {
+    let parts = collect_parts(doc, options);
+    let name: String = name.chars().filter(|c| !c.is_whitespace()).collect();
+    let mut out = String::new();
+    let _ = writeln!(out, "solid {name}");
+    for [a, b, c] in triangles(&parts) {
+        let n = triangle_normal(a, b, c);
+        let _ = writeln!(out, "  facet normal {:e} {:e} {:e}", n.x as f32, n.y as f32, n.z as f32);
+        out.push_str("    outer loop\n");
+        for v in [a, b, c] {
+            let _ = writeln!(out, "      vertex {:e} {:e} {:e}", v.x as f32, v.y as f32, v.z as f32);
+        }
+        out.push_str("    endloop\n  endfacet\n");
+    }
+    let _ = writeln!(out, "endsolid {name}");
+    out
+}
+
+/// Write the model space meshes to an STL file, binary or ASCII
+pub fn write_stl<P: AsRef<Path>>(doc: &CadDocument, options: &MeshExportOptions, path: P, binary: bool) -> Result<()> {
+    if binary {
+        std::fs::write(path, to_stl_binary(doc, options))?;
+    } else {
+        let name = path.as_ref().file_stem().and_then(|s| s.to_str()).unwrap_or("acadrust").to_string();
+        std::fs::write(path, to_stl_ascii(doc, options, &name))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::{EntityType, PolyfaceMesh};
+
+    #[test]
+    fn test_binary_and_ascii_stl() {
+        let mut doc = CadDocument::new();
+        let cube = PolyfaceMesh::create_box(Vector3::ZERO, Vector3::new(2.0, 2.0, 2.0));
+        doc.add_entity(EntityType::PolyfaceMesh(cube)).unwrap();
+        let options = MeshExportOptions::default();
+
+        let binary = to_stl_binary(&doc, &options);
+        assert_eq!(binary.len(), 84 + 12 * 50);
+        assert_eq!(u32::from_le_bytes(binary[80..84].try_into().unwrap()), 12);
+
+        let ascii = to_stl_ascii(&doc, &options, "my cube");
+        assert!(ascii.starts_with("solid mycube\n"));
+        assert_eq!(ascii.matches("facet normal").count(), 12);
+        assert!(ascii.trim_end().ends_with("endsolid mycube"));
+    }
+}
@@ -1,8 +1,22 @@
 //! I/O module for reading and writing CAD files in DXF and DWG formats
 
+#[cfg(feature = "tokio")]
+mod async_io;
+pub mod dxb;
 pub mod dxf;
 pub mod dwg;
+pub mod entity_filter;
+pub mod geojson;
+pub(crate) mod json;
+pub mod mesh_export;
+pub mod pdf;
+pub mod progress;
+pub mod wkt;
 
+pub use dxb::DxbReader;
 pub use dxf::{DxfReader, DxfWriter};
 pub use dwg::{DwgReader, DwgReaderConfiguration, DwgWriter};
+pub use entity_filter::EntityFilter;
+pub use pdf::PdfPlotter;
 
+pub use progress::{CancellationToken, ReadPhase, ReadProgress};
@@ -0,0 +1,383 @@
+//! PDF plotting
+//!
+//! [`PdfPlotter`] plots a layout, or the extents of model space, to a
+//! single-page vector PDF. Layouts are plotted with their plot settings:
+//! paper size and margins, plot area, scale, rotation, origin, lineweights
+//! and the standard `monochrome.ctb` / `grayscale.ctb` style sheets. Model
+//! space shows through paper space viewports, clipped to the viewport.
+//!
+//! ```rust,ignore
+//! use acadrust::io::pdf::PdfPlotter;
+//!
+//! let pdf = PdfPlotter::new(&doc).plot_layout("Layout1")?;
+//! std::fs::write("layout1.pdf", pdf)?;
+//! ```
+
+mod plot_style;
+mod writer;
+
+pub use plot_style::{PlotStyle, PlotStyleTable};
+
+use std::path::Path;
+
+use crate::document::CadDocument;
+use crate::error::{DxfError, Result};
+use crate::objects::{Layout, PlotPaperUnits, PlotRotation, PlotSettings, PlotType, ScaledType};
+use crate::render::{find_layout, Primitive, PrimitiveKind, Scene, SceneOptions};
+use crate::types::Vector2;
+use writer::ContentStream;
+
+/// Points per millimetre
+const PT_PER_MM: f64 = 72.0 / 25.4;
+
+/// Lineweight of objects with the default lineweight, in millimetres
+const DEFAULT_LINE_WEIGHT: f64 = 0.25;
+
+/// Helvetica cap height as a fraction of the font size
+const CAP_HEIGHT: f64 = 0.718;
+
+/// Paper used when plot settings do not name a size: ISO A4 landscape
+const FALLBACK_PAPER: (f64, f64) = (297.0, 210.0);
+
+/// Plots layouts and model space to PDF
+#[derive(Debug, Clone)]
+pub struct PdfPlotter<'a> {
+    document: &'a CadDocument,
+    plot_styles: Option<PlotStyleTable>,
+    tolerance: f64,
+    options: SceneOptions,
+}
+
+impl<'a> PdfPlotter<'a> {
+    /// Create a plotter for a document
+    pub fn new(document: &'a CadDocument) -> Self {
+        Self {
+            document,
+            plot_styles: None,
+            tolerance: 0.05,
+            options: SceneOptions {
+                plot_only: true,
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Use this plot style table instead of the layout's style sheet
+    pub fn with_plot_styles(mut self, table: PlotStyleTable) -> Self {
+        self.plot_styles = Some(table);
+        self
+    }
+
+    /// Largest chord deviation of plotted curves on paper, in millimetres
+    pub fn with_tolerance(mut self, millimeters: f64) -> Self {
+        self.tolerance = millimeters.max(1e-4);
+        self
+    }
+
+    /// Leave a layer out of the plot
+    pub fn hide_layer(mut self, name: impl AsRef<str>) -> Self {
+        self.options = self.options.hide_layer(name);
+        self
+    }
+
+    /// Plot the layout named `name` (case-insensitive) with its plot settings
+    ///
+    /// The `Model` layout plots model space.
+    pub fn plot_layout(&self, name: &str) -> Result<Vec<u8>> {
+        let layout = find_layout(self.document, name).ok_or_else(|| DxfError::Custom(format!("Layout not found: {name}")))?;
+        self.plot(layout)
+    }
+
+    /// Plot a layout with its plot settings
+    pub fn plot(&self, layout: &Layout) -> Result<Vec<u8>> {
+        let model = layout.name.eq_ignore_ascii_case("Model");
+        let build = |tolerance: f64| {
+            let options = SceneOptions {
+                tolerance,
+                ..self.options.clone()
+            };
+            if model {
+                Scene::model_space(self.document, &options)
+            } else {
+                Scene::layout(self.document, layout, &options)
+            }
+        };
+        self.render(layout, &layout.plot_settings, &build)
+    }
+
+    /// Plot the extents of model space, scaled to fit and centered on a
+    /// sheet of `paper_width` × `paper_height` millimetres
+    pub fn plot_model_extents(&self, paper_width: f64, paper_height: f64) -> Result<Vec<u8>> {
+        let mut settings = PlotSettings::new("Model");
+        settings.set_paper_size(paper_width, paper_height);
+        settings.paper_units = PlotPaperUnits::Millimeters;
+        settings.plot_type = PlotType::Extents;
+        settings.set_scale_to_fit();
+        settings.flags.plot_centered = true;
+        settings.flags.print_lineweights = true;
+
+        let build = |tolerance: f64| {
+            let options = SceneOptions {
+                tolerance,
+                ..self.options.clone()
+            };
+            Scene::model_space(self.document, &options)
+        };
+        self.render(&Layout::model(), &settings, &build)
+    }
+
+    /// Plot a layout and write the PDF to a file
+    pub fn plot_layout_to_file<P: AsRef<Path>>(&self, name: &str, path: P) -> Result<()> {
+        std::fs::write(path, self.plot_layout(name)?)?;
+        Ok(())
+    }
+
+    fn render(&self, layout: &Layout, settings: &PlotSettings, build: &dyn Fn(f64) -> Scene) -> Result<Vec<u8>> {
+        let (paper_width, paper_height) = if settings.paper_width > 0.0 && settings.paper_height > 0.0 {
+            (settings.paper_width, settings.paper_height)
+        } else {
+            FALLBACK_PAPER
+        };
+        let margins = &settings.margins;
+        let printable = (
+            (paper_width - margins.left - margins.right).max(1.0),
+            (paper_height - margins.bottom - margins.top).max(1.0),
+        );
+        let quarter_turn = matches!(settings.rotation, PlotRotation::Degrees90 | PlotRotation::Degrees270);
+        let units = match settings.paper_units {
+            PlotPaperUnits::Inches => 25.4,
+            _ => 1.0,
+        };
+        let fit = settings.scale_type == ScaledType::ScaleToFit && settings.plot_type != PlotType::Layout;
+        let custom_scale = settings.scale_factor() * units;
+
+        // Curves are tessellated to the paper tolerance. A fitted plot only
+        // knows its scale from the first pass, and is rebuilt if enlarged.
+        let mut scene = build(if fit { self.tolerance } else { self.tolerance / custom_scale });
+        let window = match plot_window(settings, layout, &scene) {
+            Some(window) => window,
+            None => return writer::write_document(paper_width * PT_PER_MM, paper_height * PT_PER_MM, ContentStream::default()),
+        };
+        let size = window.1 - window.0;
+        let (rotated_w, rotated_h) = if quarter_turn { (size.y, size.x) } else { (size.x, size.y) };
+        let scale = if fit {
+            (printable.0 / rotated_w.max(1e-12)).min(printable.1 / rotated_h.max(1e-12))
+        } else {
+            custom_scale
+        };
+        if fit && scale > 2.0 {
+            scene = build(self.tolerance / scale);
+        }
+
+        let offset = if settings.flags.plot_centered {
+            Vector2::new(
+                margins.left + (printable.0 - rotated_w * scale) / 2.0,
+                margins.bottom + (printable.1 - rotated_h * scale) / 2.0,
+            )
+        } else {
+            Vector2::new(margins.left + settings.origin_x, margins.bottom + settings.origin_y)
+        };
+        let (w, h) = (size.x * scale, size.y * scale);
+        let rotation = settings.rotation;
+        let to_page = move |p: Vector2| {
+            let q = (p - window.0) * scale;
+            let q = match rotation {
+                PlotRotation::None => q,
+                PlotRotation::Degrees90 => Vector2::new(h - q.y, q.x),
+                PlotRotation::Degrees180 => Vector2::new(w - q.x, h - q.y),
+                PlotRotation::Degrees270 => Vector2::new(q.y, w - q.x),
+            };
+            (q + offset) * PT_PER_MM
+        };
+        scene.transform(&to_page);
+
+        let plot_styles = self
+            .plot_styles
+            .clone()
+            .or_else(|| PlotStyleTable::from_style_sheet(&settings.current_style_sheet));
+        let mut painter = Painter {
+            content: ContentStream::default(),
+            plot_styles: plot_styles.as_ref(),
+            line_weights: settings.flags.print_lineweights,
+            line_weight_scale: if settings.flags.scale_lineweights { scale } else { 1.0 },
+        };
+        painter.content.op("1 J 1 j");
+        if settings.plot_type != PlotType::Layout {
+            // Keep geometry outside the plot window off the sheet
+            let corners = [window.0, Vector2::new(window.1.x, window.0.y), window.1, Vector2::new(window.0.x, window.1.y)];
+            let clip: Vec<(f64, f64)> = corners.iter().map(|p| to_page(*p)).map(|p| (p.x, p.y)).collect();
+            painter.content.path(&clip, true);
+            painter.content.op("W n");
+        }
+        painter.paint(&scene.primitives);
+
+        writer::write_document(paper_width * PT_PER_MM, paper_height * PT_PER_MM, painter.content)
+    }
+}
+
+/// Plotted area in drawing units, as (lower-left, upper-right)
+fn plot_window(settings: &PlotSettings, layout: &Layout, scene: &Scene) -> Option<(Vector2, Vector2)> {
+    let window = match settings.plot_type {
+        PlotType::Layout => {
+            // Paper space origin sits at the lower-left corner of the printable area
+            let units = if settings.paper_units == PlotPaperUnits::Inches { 25.4 } else { 1.0 };
+            let scale = settings.scale_factor() * units;
+            let size = Vector2::new(
+                settings.paper_width - settings.margins.left - settings.margins.right,
+                settings.paper_height - settings.margins.bottom - settings.margins.top,
+            ) / scale;
+            (size.x > 0.0 && size.y > 0.0).then_some((Vector2::ZERO, size))
+        }
+        PlotType::Limits => Some((
+            Vector2::new(layout.min_limits.0, layout.min_limits.1),
+            Vector2::new(layout.max_limits.0, layout.max_limits.1),
+        )),
+        PlotType::Window if !settings.plot_window.is_empty() => {
+            let w = &settings.plot_window;
+            Some((Vector2::new(w.lower_left_x, w.lower_left_y), Vector2::new(w.upper_right_x, w.upper_right_y)))
+        }
+        _ => None,
+    };
+    let window = window.or_else(|| scene.bounds().map(|b| (b.min, b.max)))?;
+    // A single point or line still needs an area to scale into
+    let size = window.1 - window.0;
+    let pad = Vector2::new(if size.x > 0.0 { 0.0 } else { 0.5 }, if size.y > 0.0 { 0.0 } else { 0.5 });
+    Some((window.0 - pad, window.1 + pad))
+}
+
+/// Writes scene primitives, already in page points, to a content stream
+struct Painter<'a> {
+    content: ContentStream,
+    plot_styles: Option<&'a PlotStyleTable>,
+    line_weights: bool,
+    line_weight_scale: f64,
+}
+
+impl Painter<'_> {
+    fn paint(&mut self, primitives: &[Primitive]) {
+        for primitive in primitives {
+            let style = self.plot_styles.map(|t| t.style(primitive.color_index)).unwrap_or_default();
+            // ACI 7 is white on a dark screen but black on paper
+            let color = style.color.unwrap_or(if primitive.color_index == Some(7) {
+                (0, 0, 0)
+            } else {
+                primitive.color
+            });
+            let weight = style.line_weight.unwrap_or(if self.line_weights {
+                primitive.line_weight.unwrap_or(DEFAULT_LINE_WEIGHT) * self.line_weight_scale
+            } else {
+                DEFAULT_LINE_WEIGHT
+            });
+
+            match &primitive.kind {
+                PrimitiveKind::Polyline { points, closed } => {
+                    self.content.stroke_color(color);
+                    self.content.line_width(weight * PT_PER_MM);
+                    self.content.path(&xy(points), *closed);
+                    self.content.op("S");
+                }
+                PrimitiveKind::Fill { rings } => {
+                    self.content.fill_color(color);
+                    for ring in rings {
+                        self.content.path(&xy(ring), true);
+                    }
+                    self.content.op("f*");
+                }
+                PrimitiveKind::Point(p) => {
+                    self.content.stroke_color(color);
+                    self.content.line_width(weight * PT_PER_MM);
+                    self.content.path(&[(p.x, p.y), (p.x, p.y)], false);
+                    self.content.op("S");
+                }
+                PrimitiveKind::Text {
+                    position,
+                    height,
+                    rotation,
+                    width_factor,
+                    value,
+                } => {
+                    let size = height / CAP_HEIGHT;
+                    let (sin, cos) = rotation.sin_cos();
+                    self.content.fill_color(color);
+                    self.content.text(
+                        [cos * size * width_factor, sin * size * width_factor, -sin * size, cos * size, position.x, position.y],
+                        value,
+                    );
+                }
+                PrimitiveKind::Clip { boundary, children } => {
+                    self.content.save();
+                    self.content.path(&xy(boundary), true);
+                    self.content.op("W n");
+                    self.paint(children);
+                    self.content.restore();
+                }
+            }
+        }
+    }
+}
+
+fn xy(points: &[Vector2]) -> Vec<(f64, f64)> {
+    points.iter().map(|p| (p.x, p.y)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::{Circle, EntityType, Line, Viewport};
+    use crate::render::layout_block_record;
+
+    #[test]
+    fn test_model_extents_plot_is_a_pdf() {
+        let mut doc = CadDocument::new();
+        doc.add_entity(EntityType::Line(Line::from_coords(0.0, 0.0, 0.0, 100.0, 50.0, 0.0))).unwrap();
+        doc.add_entity(EntityType::Circle(Circle::from_coords(50.0, 25.0, 0.0, 10.0))).unwrap();
+
+        let pdf = PdfPlotter::new(&doc).plot_model_extents(297.0, 210.0).unwrap();
+        assert!(pdf.starts_with(b"%PDF-1.4"));
+        assert!(pdf.ends_with(b"%%EOF\n"));
+        let text = String::from_utf8_lossy(&pdf);
+        assert!(text.contains("/MediaBox [0 0 841.89 595.276]"));
+    }
+
+    #[test]
+    fn test_layout_viewport_shows_model_space() {
+        let mut doc = CadDocument::new();
+        doc.add_entity(EntityType::Line(Line::from_coords(0.0, 0.0, 0.0, 100.0, 0.0, 0.0))).unwrap();
+
+        let mut layout = find_layout(&doc, "Layout1").unwrap().clone();
+        layout.plot_settings.set_paper_size(297.0, 210.0);
+        layout.plot_settings.paper_units = PlotPaperUnits::Millimeters;
+        layout.plot_settings.plot_type = PlotType::Layout;
+        layout.plot_settings.set_custom_scale(1.0, 1.0);
+
+        let record = layout_block_record(&doc, &layout).unwrap().handle;
+        let mut viewport = Viewport::new();
+        viewport.id = 2;
+        viewport.center = crate::types::Vector3::new(100.0, 100.0, 0.0);
+        viewport.width = 150.0;
+        viewport.height = 100.0;
+        viewport.view_center = crate::types::Vector3::new(50.0, 0.0, 0.0);
+        viewport.view_height = 200.0;
+        viewport.common.owner_handle = record;
+        doc.add_entity(EntityType::Viewport(Box::new(viewport))).unwrap();
+
+        let scene = Scene::layout(&doc, &layout, &SceneOptions::default());
+        let clip = scene
+            .primitives
+            .iter()
+            .find_map(|p| match &p.kind {
+                PrimitiveKind::Clip { children, .. } => Some(children),
+                _ => None,
+            })
+            .expect("viewport contents");
+        // The 100 unit line at half scale, centered on the viewport
+        let PrimitiveKind::Polyline { points, .. } = &clip[0].kind else {
+            panic!("expected the model line");
+        };
+        assert!((points[0].x - 75.0).abs() < 1e-9 && (points[1].x - 125.0).abs() < 1e-9);
+        assert!((points[0].y - 100.0).abs() < 1e-9);
+
+        let pdf = PdfPlotter::new(&doc).plot(&layout).unwrap();
+        assert!(pdf.starts_with(b"%PDF"));
+    }
+}
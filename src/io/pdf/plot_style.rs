@@ -0,0 +1,112 @@
+//! Color-dependent plot styles
+
+use std::collections::HashMap;
+
+use crate::types::aci_to_rgb;
+
+/// Overrides applied to objects of one color when plotting
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PlotStyle {
+    /// Plotted color; `None` keeps the object color
+    pub color: Option<(u8, u8, u8)>,
+    /// Plotted lineweight in millimetres; `None` keeps the object lineweight
+    pub line_weight: Option<f64>,
+}
+
+/// Color-dependent plot style table (the equivalent of a `.ctb` file)
+///
+/// Styles are keyed by ACI index; objects with a true color use the
+/// default style.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PlotStyleTable {
+    /// Table name, e.g. `monochrome.ctb`
+    pub name: String,
+    styles: HashMap<u8, PlotStyle>,
+    default: PlotStyle,
+}
+
+impl PlotStyleTable {
+    /// Create an empty table that leaves every object unchanged
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Table plotting everything in black
+    pub fn monochrome() -> Self {
+        let mut table = Self::new("monochrome.ctb");
+        table.default.color = Some((0, 0, 0));
+        table
+    }
+
+    /// Table plotting every ACI color as its gray value
+    pub fn grayscale() -> Self {
+        let mut table = Self::new("grayscale.ctb");
+        for index in 1..=255u8 {
+            let (r, g, b) = aci_to_rgb(index);
+            let gray = (0.299 * r as f64 + 0.587 * g as f64 + 0.114 * b as f64).round() as u8;
+            table.styles.insert(
+                index,
+                PlotStyle {
+                    color: Some((gray, gray, gray)),
+                    line_weight: None,
+                },
+            );
+        }
+        // White on white paper would vanish
+        table.styles.insert(7, PlotStyle { color: Some((0, 0, 0)), line_weight: None });
+        table
+    }
+
+    /// Built-in table for a style sheet name, if it is one of the
+    /// standard tables (`monochrome.ctb`, `grayscale.ctb`)
+    ///
+    /// Other style sheets live in external files that are not read.
+    pub fn from_style_sheet(name: &str) -> Option<Self> {
+        let file = name.rsplit(['/', '\\']).next().unwrap_or(name);
+        if file.eq_ignore_ascii_case("monochrome.ctb") {
+            Some(Self::monochrome())
+        } else if file.eq_ignore_ascii_case("grayscale.ctb") {
+            Some(Self::grayscale())
+        } else {
+            None
+        }
+    }
+
+    /// Set the style of an ACI color
+    pub fn set_style(&mut self, color_index: u8, style: PlotStyle) {
+        self.styles.insert(color_index, style);
+    }
+
+    /// Set the style used for true colors and unlisted indices
+    pub fn set_default_style(&mut self, style: PlotStyle) {
+        self.default = style;
+    }
+
+    /// Style for an object color
+    pub fn style(&self, color_index: Option<u8>) -> PlotStyle {
+        color_index
+            .and_then(|i| self.styles.get(&i))
+            .copied()
+            .unwrap_or(self.default)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_style_sheet_lookup() {
+        let mono = PlotStyleTable::from_style_sheet("C:\\Plot Styles\\Monochrome.ctb").unwrap();
+        assert_eq!(mono.style(Some(1)).color, Some((0, 0, 0)));
+        assert_eq!(mono.style(None).color, Some((0, 0, 0)));
+
+        let gray = PlotStyleTable::from_style_sheet("grayscale.ctb").unwrap();
+        let (r, g, b) = gray.style(Some(1)).color.unwrap();
+        assert!(r == g && g == b);
+        assert!(PlotStyleTable::from_style_sheet("custom.ctb").is_none());
+    }
+}
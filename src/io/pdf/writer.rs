@@ -0,0 +1,164 @@
+//! Minimal PDF serialization: one page, one content stream, Helvetica
+
+use std::fmt::Write as _;
+use std::io::Write;
+
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+
+use crate::error::Result;
+
+/// Builder for a page content stream, in PDF points
+#[derive(Debug, Default)]
+pub(crate) struct ContentStream {
+    ops: String,
+}
+
+impl ContentStream {
+    pub fn op(&mut self, op: &str) {
+        self.ops.push_str(op);
+        self.ops.push('\n');
+    }
+
+    pub fn save(&mut self) {
+        self.op("q");
+    }
+
+    pub fn restore(&mut self) {
+        self.op("Q");
+    }
+
+    pub fn stroke_color(&mut self, (r, g, b): (u8, u8, u8)) {
+        let _ = writeln!(self.ops, "{} {} {} RG", channel(r), channel(g), channel(b));
+    }
+
+    pub fn fill_color(&mut self, (r, g, b): (u8, u8, u8)) {
+        let _ = writeln!(self.ops, "{} {} {} rg", channel(r), channel(g), channel(b));
+    }
+
+    pub fn line_width(&mut self, width: f64) {
+        let _ = writeln!(self.ops, "{} w", num(width));
+    }
+
+    /// Append a path through `points`, optionally closed
+    pub fn path(&mut self, points: &[(f64, f64)], closed: bool) {
+        for (i, &(x, y)) in points.iter().enumerate() {
+            let _ = writeln!(self.ops, "{} {} {}", num(x), num(y), if i == 0 { "m" } else { "l" });
+        }
+        if closed {
+            self.op("h");
+        }
+    }
+
+    /// Single line of Helvetica text; `matrix` is the text matrix with the
+    /// font size already applied
+    pub fn text(&mut self, matrix: [f64; 6], value: &str) {
+        let _ = write!(self.ops, "BT /F1 1 Tf");
+        for v in matrix {
+            let _ = write!(self.ops, " {}", num(v));
+        }
+        let _ = writeln!(self.ops, " Tm ({}) Tj ET", escape(value));
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.ops.into_bytes()
+    }
+}
+
+/// Serialize a one-page document of `width` × `height` points
+pub(crate) fn write_document(width: f64, height: f64, content: ContentStream) -> Result<Vec<u8>> {
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(&content.into_bytes())?;
+    let stream = encoder.finish()?;
+
+    let mut out: Vec<u8> = b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n".to_vec();
+    let mut offsets = Vec::new();
+    let mut object = |out: &mut Vec<u8>, body: &[u8]| {
+        offsets.push(out.len());
+        let _ = writeln!(out, "{} 0 obj", offsets.len());
+        out.extend_from_slice(body);
+        out.extend_from_slice(b"\nendobj\n");
+    };
+
+    object(&mut out, b"<< /Type /Catalog /Pages 2 0 R >>");
+    object(&mut out, b"<< /Type /Pages /Kids [3 0 R] /Count 1 >>");
+    object(
+        &mut out,
+        format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Resources << /Font << /F1 5 0 R >> >> /Contents 4 0 R >>",
+            num(width),
+            num(height)
+        )
+        .as_bytes(),
+    );
+    let mut body = format!("<< /Length {} /Filter /FlateDecode >>\nstream\n", stream.len()).into_bytes();
+    body.extend_from_slice(&stream);
+    body.extend_from_slice(b"\nendstream");
+    object(&mut out, &body);
+    object(
+        &mut out,
+        b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>",
+    );
+
+    let xref = out.len();
+    let _ = writeln!(out, "xref\n0 {}\n0000000000 65535 f ", offsets.len() + 1);
+    for offset in &offsets {
+        let _ = writeln!(out, "{offset:010} 00000 n ");
+    }
+    let _ = writeln!(
+        out,
+        "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{xref}\n%%EOF",
+        offsets.len() + 1
+    );
+    Ok(out)
+}
+
+/// Number with at most three decimals and no trailing zeros
+fn num(value: f64) -> String {
+    if !value.is_finite() {
+        return "0".to_string();
+    }
+    let s = format!("{value:.3}");
+    let s = s.trim_end_matches('0').trim_end_matches('.');
+    if s == "-0" || s.is_empty() {
+        "0".to_string()
+    } else {
+        s.to_string()
+    }
+}
+
+fn channel(value: u8) -> String {
+    num(value as f64 / 255.0)
+}
+
+/// Escape a string for a PDF literal; characters outside Latin-1 become `?`
+fn escape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '(' | ')' | '\\' => {
+                out.push('\\');
+                out.push(c);
+            }
+            ' '..='~' => out.push(c),
+            c if (c as u32) < 256 => {
+                let _ = write!(out, "\\{:03o}", c as u32);
+            }
+            _ => out.push('?'),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_number_and_string_formatting() {
+        assert_eq!(num(1.0), "1");
+        assert_eq!(num(-0.0001), "0");
+        assert_eq!(num(12.3456), "12.346");
+        assert_eq!(escape("a(b)\\ ø €"), "a\\(b\\)\\\\ \\370 ?");
+    }
+}
@@ -0,0 +1,231 @@
+//! Progress reporting and cancellation for long-running reads.
+//!
+//! Both [`DxfReader`](super::DxfReader) and [`DwgReader`](super::DwgReader)
+//! accept a progress callback (`with_progress`) and a
+//! [`CancellationToken`] (`with_cancellation`).  The callback is invoked
+//! whenever the read enters a new phase and as the percentage advances;
+//! the token is checked between objects.  A cancelled read stops early and
+//! returns the document built so far, with a warning notification.
+//!
+//! ```rust,ignore
+//! let token = CancellationToken::new();
+//! let doc = DwgReader::from_file("huge.dwg")?
+//!     .with_progress(|p| println!("{:?} {:.0}% ({} objects)", p.phase, p.percent, p.objects_read))
+//!     .with_cancellation(token.clone())
+//!     .read()?;
+//! ```
+
+use crate::error::{DxfError, Result};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Stage of a read operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ReadPhase {
+    /// File header / section map (DWG).
+    FileHeader,
+    /// HEADER section (system variables).
+    Header,
+    /// CLASSES section.
+    Classes,
+    /// TABLES section.
+    Tables,
+    /// BLOCKS section.
+    Blocks,
+    /// ENTITIES section.
+    Entities,
+    /// OBJECTS section (DWG: the whole object stream).
+    Objects,
+    /// Resolving references and assembling the document.
+    Building,
+}
+
+/// Snapshot passed to a progress callback.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReadProgress {
+    /// Current phase.
+    pub phase: ReadPhase,
+    /// Overall completion, 0.0 – 100.0.
+    pub percent: f64,
+    /// Entities and objects read so far.
+    pub objects_read: usize,
+    /// Total number of objects, when known up front (DWG object map size).
+    pub objects_total: Option<usize>,
+}
+
+/// Shared flag used to cancel a read from another thread.
+///
+/// Clones share the same flag.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    /// Create a token that is not cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Request cancellation.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Check whether cancellation has been requested.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+/// Progress callback type.
+pub type ProgressCallback = Box<dyn FnMut(&ReadProgress)>;
+
+/// Reader-side state behind `with_progress` / `with_cancellation`.
+///
+/// Callbacks fire on phase changes and whenever the integer percentage
+/// advances, so the cost stays constant regardless of file size.
+#[derive(Default)]
+pub(crate) struct ProgressReporter {
+    callback: Option<ProgressCallback>,
+    token: Option<CancellationToken>,
+    phase: Option<ReadPhase>,
+    percent: f64,
+    objects_read: usize,
+    objects_total: Option<usize>,
+    stream_length: u64,
+    /// Percentage range of the current phase, for object-count progress.
+    phase_start: f64,
+    phase_end: f64,
+}
+
+impl ProgressReporter {
+    /// Create a reporter whose percentage tracks bytes consumed out of
+    /// `length`.
+    pub fn with_stream_length(length: u64) -> Self {
+        Self {
+            stream_length: length,
+            ..Default::default()
+        }
+    }
+
+    pub fn set_callback(&mut self, callback: ProgressCallback) {
+        self.callback = Some(callback);
+    }
+
+    pub fn set_token(&mut self, token: CancellationToken) {
+        self.token = Some(token);
+    }
+
+    /// Token checked by [`Self::check_cancelled`], if any
+    #[cfg_attr(not(feature = "parallel"), allow(dead_code))]
+    pub fn token(&self) -> Option<&CancellationToken> {
+        self.token.as_ref()
+    }
+
+    /// Whether anything is listening; lets hot loops skip the bookkeeping.
+    pub fn is_active(&self) -> bool {
+        self.callback.is_some() || self.token.is_some()
+    }
+
+    /// Enter a new phase and report it.
+    pub fn set_phase(&mut self, phase: ReadPhase, percent: f64) {
+        self.phase = Some(phase);
+        self.percent = percent.clamp(0.0, 100.0);
+        self.phase_start = self.percent;
+        self.phase_end = self.percent;
+        self.emit();
+    }
+
+    /// Enter a phase covering `start..end` percent that reads `total`
+    /// objects; see [`Self::percent_of_objects`].
+    pub fn set_object_phase(&mut self, phase: ReadPhase, start: f64, end: f64, total: usize) {
+        self.objects_total = Some(total);
+        self.set_phase(phase, start);
+        self.phase_end = end.clamp(self.percent, 100.0);
+    }
+
+    /// Percentage reached after `done` of the current phase's objects.
+    pub fn percent_of_objects(&self, done: usize) -> f64 {
+        match self.objects_total {
+            Some(total) if total > 0 => {
+                let fraction = (done as f64 / total as f64).min(1.0);
+                self.phase_start + (self.phase_end - self.phase_start) * fraction
+            }
+            _ => self.percent,
+        }
+    }
+
+    /// Percentage of the stream consumed at `offset` bytes.
+    pub fn percent_of_stream(&self, offset: u64) -> f64 {
+        if self.stream_length == 0 {
+            return self.percent;
+        }
+        offset as f64 * 100.0 / self.stream_length as f64
+    }
+
+    /// Count one more object read and update the percentage.
+    pub fn object_read(&mut self, percent: f64) {
+        self.objects_read += 1;
+        let percent = percent.clamp(0.0, 100.0);
+        if percent.floor() > self.percent.floor() {
+            self.percent = percent;
+            self.emit();
+        }
+    }
+
+    /// Return [`DxfError::Cancelled`] if cancellation was requested.
+    pub fn check_cancelled(&self) -> Result<()> {
+        match &self.token {
+            Some(token) if token.is_cancelled() => Err(DxfError::Cancelled),
+            _ => Ok(()),
+        }
+    }
+
+    fn emit(&mut self) {
+        if let (Some(callback), Some(phase)) = (self.callback.as_mut(), self.phase) {
+            callback(&ReadProgress {
+                phase,
+                percent: self.percent,
+                objects_read: self.objects_read,
+                objects_total: self.objects_total,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_reports_only_on_whole_percent_steps() {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let sink = seen.clone();
+        let mut reporter = ProgressReporter::default();
+        reporter.set_callback(Box::new(move |p| sink.borrow_mut().push(*p)));
+
+        reporter.set_phase(ReadPhase::Entities, 0.0);
+        for i in 1..=1000 {
+            reporter.object_read(i as f64 / 10.0);
+        }
+
+        let seen = seen.borrow();
+        assert_eq!(seen.len(), 101);
+        assert_eq!(seen.last().unwrap().objects_read, 1000);
+        assert_eq!(seen.last().unwrap().percent, 100.0);
+    }
+
+    #[test]
+    fn test_cancellation_token_shared() {
+        let token = CancellationToken::new();
+        let mut reporter = ProgressReporter::default();
+        reporter.set_token(token.clone());
+        assert!(reporter.check_cancelled().is_ok());
+
+        token.cancel();
+        assert!(matches!(reporter.check_cancelled(), Err(DxfError::Cancelled)));
+    }
+}
@@ -0,0 +1,263 @@
+//! WKT (well-known text) geometry import
+//!
+//! [`parse`] reads the OGC simple feature types — POINT, LINESTRING,
+//! POLYGON, their MULTI variants and GEOMETRYCOLLECTION — including `Z`,
+//! `M` and `ZM` forms (extra ordinates are dropped), `EMPTY` and an EWKT
+//! `SRID=n;` prefix. [`import`] adds the result to a document the same way
+//! [`crate::io::geojson::import`] does.
+//!
+//! ```rust,ignore
+//! use acadrust::io::geojson::GeoJsonImportOptions;
+//!
+//! let options = GeoJsonImportOptions::default().with_layer("PARCELS");
+//! acadrust::io::wkt::import(&mut doc, "POLYGON ((0 0, 10 0, 10 10, 0 0))", &options)?;
+//! ```
+
+use crate::document::CadDocument;
+use crate::error::{DxfError, Result};
+use crate::io::geojson::{import_geometry, GeoJsonImportOptions, Geometry};
+use crate::types::{Handle, Vector2};
+
+/// Parse one WKT geometry
+pub fn parse(wkt: &str) -> Result<Geometry> {
+    let wkt = wkt.trim();
+    // EWKT spatial reference prefix
+    let wkt = match wkt.split_once(';') {
+        Some((srid, rest)) if srid.trim().to_uppercase().starts_with("SRID=") => rest,
+        _ => wkt,
+    };
+    let mut parser = Parser { tokens: tokenize(wkt)?, pos: 0 };
+    let geometry = parser.geometry()?;
+    if parser.pos != parser.tokens.len() {
+        return Err(invalid("trailing tokens"));
+    }
+    Ok(geometry)
+}
+
+/// Import one WKT geometry into model space, returning the new handles
+pub fn import(doc: &mut CadDocument, wkt: &str, options: &GeoJsonImportOptions) -> Result<Vec<Handle>> {
+    import_geometry(doc, &parse(wkt)?, options)
+}
+
+fn invalid(message: &str) -> DxfError {
+    DxfError::InvalidFormat(format!("WKT: {message}"))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Number(f64),
+    Open,
+    Close,
+    Comma,
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = text.char_indices().peekable();
+    while let Some(&(start, c)) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' => {
+                chars.next();
+                tokens.push(Token::Open);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Token::Close);
+            }
+            ',' => {
+                chars.next();
+                tokens.push(Token::Comma);
+            }
+            c if c.is_ascii_alphabetic() => {
+                let mut end = start;
+                while let Some(&(i, c)) = chars.peek() {
+                    if !c.is_ascii_alphabetic() {
+                        break;
+                    }
+                    end = i + c.len_utf8();
+                    chars.next();
+                }
+                tokens.push(Token::Word(text[start..end].to_uppercase()));
+            }
+            c if c.is_ascii_digit() || matches!(c, '-' | '+' | '.') => {
+                let mut end = start;
+                while let Some(&(i, c)) = chars.peek() {
+                    let exponent_sign = matches!(c, '-' | '+') && matches!(text[..i].chars().last(), Some('e' | 'E'));
+                    if !(c.is_ascii_digit() || c == '.' || c == 'e' || c == 'E' || exponent_sign || i == start) {
+                        break;
+                    }
+                    end = i + c.len_utf8();
+                    chars.next();
+                }
+                let number = text[start..end].parse().map_err(|_| invalid("invalid number"))?;
+                tokens.push(Token::Number(number));
+            }
+            _ => return Err(invalid(&format!("unexpected character '{c}'"))),
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn expect(&mut self, token: Token) -> Result<()> {
+        if self.peek() == Some(&token) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(invalid(&format!("expected {token:?}")))
+        }
+    }
+
+    /// Consume `EMPTY`, returning whether it was there
+    fn empty(&mut self) -> bool {
+        let empty = matches!(self.peek(), Some(Token::Word(w)) if w == "EMPTY");
+        if empty {
+            self.pos += 1;
+        }
+        empty
+    }
+
+    fn geometry(&mut self) -> Result<Geometry> {
+        let kind = match self.tokens.get(self.pos) {
+            Some(Token::Word(w)) => w.clone(),
+            _ => return Err(invalid("expected a geometry type")),
+        };
+        self.pos += 1;
+        // Dimension tag: Z, M or ZM
+        if let Some(Token::Word(w)) = self.peek() {
+            if matches!(w.as_str(), "Z" | "M" | "ZM") {
+                self.pos += 1;
+            }
+        }
+
+        let empty = self.empty();
+        Ok(match kind.as_str() {
+            "POINT" if empty => Geometry::MultiPoint(Vec::new()),
+            "POINT" => {
+                self.expect(Token::Open)?;
+                let p = self.position()?;
+                self.expect(Token::Close)?;
+                Geometry::Point(p)
+            }
+            "LINESTRING" => Geometry::LineString(if empty { Vec::new() } else { self.positions()? }),
+            "POLYGON" => Geometry::Polygon(if empty { Vec::new() } else { self.list(Self::positions)? }),
+            "MULTIPOINT" => Geometry::MultiPoint(if empty { Vec::new() } else { self.list(Self::point_member)? }),
+            "MULTILINESTRING" => {
+                Geometry::MultiLineString(if empty { Vec::new() } else { self.list(Self::positions)? })
+            }
+            "MULTIPOLYGON" => Geometry::MultiPolygon(if empty {
+                Vec::new()
+            } else {
+                self.list(|p| p.list(Self::positions))?
+            }),
+            "GEOMETRYCOLLECTION" => {
+                Geometry::GeometryCollection(if empty { Vec::new() } else { self.list(Self::geometry)? })
+            }
+            other => return Err(invalid(&format!("unsupported geometry type {other}"))),
+        })
+    }
+
+    /// `( item, item, ... )`
+    fn list<T>(&mut self, mut item: impl FnMut(&mut Self) -> Result<T>) -> Result<Vec<T>> {
+        self.expect(Token::Open)?;
+        let mut items = vec![item(self)?];
+        while self.peek() == Some(&Token::Comma) {
+            self.pos += 1;
+            items.push(item(self)?);
+        }
+        self.expect(Token::Close)?;
+        Ok(items)
+    }
+
+    fn positions(&mut self) -> Result<Vec<Vector2>> {
+        self.list(Self::position)
+    }
+
+    /// MULTIPOINT members may or may not be parenthesized
+    fn point_member(&mut self) -> Result<Vector2> {
+        if self.peek() == Some(&Token::Open) {
+            self.pos += 1;
+            let p = self.position()?;
+            self.expect(Token::Close)?;
+            Ok(p)
+        } else {
+            self.position()
+        }
+    }
+
+    fn position(&mut self) -> Result<Vector2> {
+        let mut ordinates = Vec::new();
+        while let Some(Token::Number(n)) = self.peek() {
+            ordinates.push(*n);
+            self.pos += 1;
+        }
+        match ordinates[..] {
+            [x, y, ..] => Ok(Vector2::new(x, y)),
+            _ => Err(invalid("position needs two numbers")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::EntityType;
+
+    #[test]
+    fn test_parse_wkt() {
+        assert_eq!(parse("POINT (1 2)").unwrap(), Geometry::Point(Vector2::new(1.0, 2.0)));
+        assert_eq!(parse("srid=4326;point z (1.5 -2e1 3)").unwrap(), Geometry::Point(Vector2::new(1.5, -20.0)));
+        assert_eq!(
+            parse("MULTIPOINT ((0 0), 1 1)").unwrap(),
+            Geometry::MultiPoint(vec![Vector2::new(0.0, 0.0), Vector2::new(1.0, 1.0)])
+        );
+        assert_eq!(parse("LINESTRING EMPTY").unwrap(), Geometry::LineString(Vec::new()));
+        match parse("MULTIPOLYGON (((0 0, 4 0, 4 4, 0 0), (1 1, 2 1, 2 2, 1 1)), ((9 9, 10 9, 10 10, 9 9)))").unwrap() {
+            Geometry::MultiPolygon(polygons) => {
+                assert_eq!(polygons.len(), 2);
+                assert_eq!(polygons[0].len(), 2);
+                assert_eq!(polygons[1][0].len(), 4);
+            }
+            other => panic!("unexpected {other:?}"),
+        }
+        match parse("GEOMETRYCOLLECTION (POINT (1 1), LINESTRING (0 0, 1 1))").unwrap() {
+            Geometry::GeometryCollection(items) => assert_eq!(items.len(), 2),
+            other => panic!("unexpected {other:?}"),
+        }
+
+        assert!(parse("POINT (1)").is_err());
+        assert!(parse("CIRCLE (0 0)").is_err());
+        assert!(parse("POINT (1 2) POINT (3 4)").is_err());
+        assert!(parse("LINESTRING (0 0, 1 1").is_err());
+    }
+
+    #[test]
+    fn test_import_wkt() {
+        let mut doc = CadDocument::new();
+        let options = GeoJsonImportOptions::default().with_layer("PARCELS");
+        let handles = import(&mut doc, "POLYGON ((0 0, 10 0, 10 10, 0 10, 0 0))", &options).unwrap();
+        assert_eq!(handles.len(), 1);
+        match doc.get_entity(handles[0]) {
+            Some(EntityType::Hatch(hatch)) => {
+                assert_eq!(hatch.common.layer, "PARCELS");
+                assert_eq!(hatch.paths.len(), 1);
+                assert!(hatch.common.extended_data.is_empty());
+            }
+            other => panic!("expected hatch, got {other:?}"),
+        }
+        assert!(doc.layers.contains("PARCELS"));
+    }
+}
@@ -12,6 +12,9 @@
 //! - Complete table system (Layers, LineTypes, Blocks, TextStyles, DimensionStyles)
 //! - Extended data (XData) support
 //! - Multiple DXF versions (R12 through 2018+)
+//! - Optional `serde` support for the whole document model (`serde` feature)
+//! - Optional async reading and writing over `tokio::io` (`tokio` feature)
+//! - Optional `tracing` spans and events while reading (`tracing` feature)
 //!
 //! ## Quick Start
 //!
@@ -49,16 +52,37 @@
 //! - 30-50% less memory usage
 //! - Zero-copy parsing where possible
 //! - Parallel processing for large files
+//!
+//! [`bench::profile_read`] reports per-phase read timings for a file, and
+//! the criterion suites in `benches/` cover read, write and round-trip.
 
 #![allow(missing_docs)]
 #![warn(rustdoc::missing_crate_level_docs)]
 
+pub mod appearance;
+pub mod array;
+pub mod bench;
+pub mod block_graph;
 pub mod classes;
+pub mod compare;
+pub mod convert;
+pub mod detect;
 pub mod entities;
+pub mod flatten;
+pub mod measure;
+pub mod snap;
+pub mod stats;
 pub mod error;
+pub mod handles;
+#[cfg(feature = "ffi")]
+pub mod ffi;
 pub mod notification;
+pub mod ownership;
 pub mod preview;
+pub mod references;
+pub mod render;
 pub mod summary_info;
+pub mod template;
 pub mod types;
 pub mod tables;
 pub mod document;
@@ -69,7 +93,7 @@ pub mod objects;
 // Re-export commonly used types
 pub use error::{DxfError, Result};
 pub use types::{
-    DxfVersion, BoundingBox2D, BoundingBox3D, Color, Handle, LineWeight, Transparency, Vector2,
+    DxfVersion, Angle, BoundingBox2D, BoundingBox3D, Color, Handle, LineWeight, Transparency, Vector2,
     Vector3,
 };
 
@@ -84,6 +108,9 @@ pub use tables::{
     AppId, BlockRecord, DimStyle, Layer, LineType, Table, TableEntry, TextStyle, Ucs, VPort, View,
 };
 
+// Re-export format detection
+pub use detect::{detect, FileFormat, FileInfo};
+
 // Re-export document
 pub use document::CadDocument;
 
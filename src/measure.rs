@@ -0,0 +1,242 @@
+//! Length and area takeoff
+//!
+//! [`EntityType::length`] and [`EntityType::area`] measure a single entity:
+//! the length along a curve, bulge arcs and splines included, and the area
+//! enclosed by closed curves, hatches and filled shapes.
+//! [`CadDocument::measure`] sums them over a selection, in total, per
+//! entity type and per layer, for quantity estimates and QA checks.
+//!
+//! ```rust,ignore
+//! let handles: Vec<Handle> = doc.model_space().map(|e| e.common().handle).collect();
+//! let report = doc.measure(handles);
+//! println!("{:.1} m of walls", report.by_layer["WALLS"].length / 1000.0);
+//! ```
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::document::CadDocument;
+use crate::entities::{EntityType, Hatch};
+use crate::render::tessellate::{hatch_path_points, spline_points};
+use crate::types::geometry::{point_in_polygon, signed_area};
+use crate::types::{Handle, Vector2, Vector3};
+
+/// Totals of one layer in [`Measurements`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LayerMeasurements {
+    /// Number of entities measured
+    pub count: usize,
+    /// Length of their curves
+    pub length: f64,
+    /// Area of their closed shapes
+    pub area: f64,
+}
+
+/// Result of [`CadDocument::measure`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Measurements {
+    /// Length of all curves, closed ones included
+    pub total_length: f64,
+    /// Area enclosed by closed curves, hatches and filled shapes
+    pub total_area: f64,
+    /// Number of entities measured, by entity type name
+    pub counts_by_type: BTreeMap<String, usize>,
+    /// Totals by layer name
+    pub by_layer: BTreeMap<String, LayerMeasurements>,
+}
+
+impl CadDocument {
+    /// Measure the entities with the given handles, wherever they are:
+    /// model space, a layout or a block definition.
+    ///
+    /// Each entity is counted once; handles of no entity are skipped.
+    /// Block references count as entities but their blocks are not
+    /// measured.
+    pub fn measure(&self, handles: impl IntoIterator<Item = Handle>) -> Measurements {
+        let entities: HashMap<Handle, &EntityType> =
+            self.all_entities().map(|e| (e.common().handle, e)).collect();
+        let mut seen = HashSet::new();
+        let mut report = Measurements::default();
+        for handle in handles {
+            let Some(entity) = entities.get(&handle).filter(|_| seen.insert(handle)) else {
+                continue;
+            };
+            let length = entity.length().unwrap_or(0.0);
+            let area = entity.area().unwrap_or(0.0);
+            report.total_length += length;
+            report.total_area += area;
+            *report.counts_by_type.entry(entity.as_entity().entity_type().to_string()).or_default() += 1;
+            let layer = report.by_layer.entry(entity.common().layer.clone()).or_default();
+            layer.count += 1;
+            layer.length += length;
+            layer.area += area;
+        }
+        report
+    }
+}
+
+impl EntityType {
+    /// Length along the entity, or `None` for entities that are not
+    /// curves or are unbounded (rays and construction lines)
+    pub fn length(&self) -> Option<f64> {
+        match self {
+            EntityType::Line(line) => Some(line.length()),
+            EntityType::Arc(arc) => Some(arc.arc_length()),
+            EntityType::Circle(circle) => Some(circle.circumference()),
+            EntityType::Ellipse(ellipse) => Some(ellipse.length()),
+            EntityType::LwPolyline(poly) => Some(poly.length()),
+            EntityType::Polyline2D(poly) => Some(poly.length()),
+            EntityType::Polyline(poly) => Some(poly.length()),
+            EntityType::Polyline3D(poly) => Some(poly.length()),
+            EntityType::Spline(spline) => {
+                let points = spline_curve(spline);
+                let length = points.windows(2).map(|w| w[0].distance(&w[1])).sum::<f64>();
+                let closing = if spline.flags.closed && points.len() > 2 {
+                    points[points.len() - 1].distance(&points[0])
+                } else {
+                    0.0
+                };
+                Some(length + closing)
+            }
+            EntityType::Leader(leader) => Some(leader.length()),
+            EntityType::MLine(mline) => Some(mline.length()),
+            _ => None,
+        }
+    }
+
+    /// Area enclosed by the entity, or `None` for entities that do not
+    /// enclose one, open curves included
+    pub fn area(&self) -> Option<f64> {
+        match self {
+            EntityType::Circle(circle) => Some(circle.area()),
+            EntityType::Ellipse(ellipse) if ellipse.is_full() => Some(ellipse.area()),
+            EntityType::LwPolyline(poly) if poly.is_closed => Some(poly.area()),
+            EntityType::Polyline2D(poly) if poly.is_closed() => Some(poly.area()),
+            EntityType::Polyline(poly) if poly.is_closed() => {
+                Some(polygon_area(&poly.vertices.iter().map(|v| v.location).collect::<Vec<_>>()))
+            }
+            EntityType::Polyline3D(poly) if poly.is_closed() => {
+                Some(polygon_area(&poly.vertices.iter().map(|v| v.position).collect::<Vec<_>>()))
+            }
+            EntityType::Spline(spline) if spline.flags.closed => Some(polygon_area(&spline_curve(spline))),
+            EntityType::Hatch(hatch) => Some(hatch_area(hatch)),
+            EntityType::Solid(solid) => Some(solid.area()),
+            EntityType::Face3D(face) => Some(face.area()),
+            EntityType::Wipeout(wipeout) => Some(wipeout.area()),
+            _ => None,
+        }
+    }
+}
+
+/// Points along a spline, fine enough for measuring
+pub(crate) fn spline_curve(spline: &crate::entities::Spline) -> Vec<Vector3> {
+    if spline.control_points.is_empty() {
+        return spline.fit_points.clone();
+    }
+    let polygon: f64 = spline.control_points.windows(2).map(|w| w[0].distance(&w[1])).sum();
+    spline_points(
+        spline.degree.max(1) as usize,
+        &spline.knots,
+        &spline.control_points,
+        &spline.weights,
+        (polygon * 1e-6).max(1e-9),
+    )
+}
+
+/// Area of a planar polygon in 3D (Newell's method)
+fn polygon_area(points: &[Vector3]) -> f64 {
+    let n = points.len();
+    let normal = (0..n).fold(Vector3::ZERO, |sum, i| sum + points[i].cross(&points[(i + 1) % n]));
+    normal.length() / 2.0
+}
+
+/// Area of a hatch: its boundary rings, with rings inside an odd number of
+/// others counting as holes
+fn hatch_area(hatch: &Hatch) -> f64 {
+    let rings: Vec<Vec<Vector2>> = hatch
+        .paths
+        .iter()
+        .map(|path| hatch_path_points(path, 1e-6))
+        .filter(|ring| ring.len() >= 3)
+        .collect();
+    rings
+        .iter()
+        .enumerate()
+        .map(|(i, ring)| {
+            let depth = rings
+                .iter()
+                .enumerate()
+                .filter(|&(j, other)| j != i && point_in_polygon(other, ring[0]))
+                .count();
+            if depth % 2 == 0 { signed_area(ring).abs() } else { -signed_area(ring).abs() }
+        })
+        .sum::<f64>()
+        .max(0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::{BoundaryEdge, BoundaryPath, Circle, PolylineEdge, Line, LwPolyline, LwVertex};
+    use crate::tables::BlockRecord;
+    use std::f64::consts::PI;
+
+    fn square(size: f64) -> Vec<Vector2> {
+        vec![
+            Vector2::new(0.0, 0.0),
+            Vector2::new(size, 0.0),
+            Vector2::new(size, size),
+            Vector2::new(0.0, size),
+        ]
+    }
+
+    #[test]
+    fn test_polyline_with_bulges() {
+        // A 2 × 2 square whose right side bulges out into a half circle
+        let mut slot = LwPolyline::from_points(square(2.0));
+        slot.vertices[1] = LwVertex::with_bulge(Vector2::new(2.0, 0.0), 1.0);
+        slot.is_closed = true;
+        let slot = EntityType::LwPolyline(slot);
+        assert!((slot.length().unwrap() - (6.0 + PI)).abs() < 1e-12);
+        assert!((slot.area().unwrap() - (4.0 + PI / 2.0)).abs() < 1e-12);
+
+        let mut open = LwPolyline::from_points(square(2.0));
+        open.is_closed = false;
+        assert_eq!(EntityType::LwPolyline(open).area(), None);
+    }
+
+    #[test]
+    fn test_hatch_area_subtracts_holes() {
+        let mut hatch = Hatch::new();
+        for (size, offset) in [(10.0, 0.0), (2.0, 4.0)] {
+            let ring = square(size).into_iter().map(|p| p + Vector2::new(offset, offset)).collect();
+            let mut path = BoundaryPath::new();
+            path.add_edge(BoundaryEdge::Polyline(PolylineEdge::new(ring, true)));
+            hatch.paths.push(path);
+        }
+        assert!((EntityType::Hatch(Box::new(hatch)).area().unwrap() - 96.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_measure_by_type_and_layer() {
+        let mut doc = CadDocument::new();
+        let mut wall = Line::from_coords(0.0, 0.0, 0.0, 3.0, 4.0, 0.0);
+        wall.common.layer = "WALLS".to_string();
+        let wall = doc.add_entity(EntityType::Line(wall)).unwrap();
+        let column = doc.add_entity(EntityType::Circle(Circle::from_coords(0.0, 0.0, 0.0, 1.0))).unwrap();
+
+        let mut block = BlockRecord::new("TAG");
+        let mut edge = EntityType::Line(Line::from_coords(0.0, 0.0, 0.0, 1.0, 0.0, 0.0));
+        edge.as_entity_mut().set_handle(doc.allocate_handle());
+        let edge_handle = edge.common().handle;
+        block.entities.push(edge);
+        doc.block_records.add(block).unwrap();
+
+        let report = doc.measure([wall, column, edge_handle, wall, Handle::new(0xFFFF)]);
+        assert!((report.total_length - (5.0 + 2.0 * PI + 1.0)).abs() < 1e-12);
+        assert!((report.total_area - PI).abs() < 1e-12);
+        assert_eq!(report.counts_by_type["LINE"], 2);
+        assert_eq!(report.counts_by_type["CIRCLE"], 1);
+        assert_eq!(report.by_layer["WALLS"], LayerMeasurements { count: 1, length: 5.0, area: 0.0 });
+        assert_eq!(report.by_layer["0"].count, 2);
+    }
+}
@@ -5,12 +5,36 @@
 //! items rather than being silently dropped or causing hard errors.
 //!
 //! After a read/write operation the caller can inspect
-//! [`CadDocument::notifications`] to see what was encountered.
+//! [`CadDocument::notifications`] to see what was encountered, filter it by
+//! severity ([`NotificationCollection::at_least`]) or by
+//! [`NotificationCode`]. To see notifications as they happen, register an
+//! observer on the reader:
+//!
+//! ```rust,ignore
+//! let doc = DxfReader::from_file("drawing.dxf")?
+//!     .on_notification(|n| eprintln!("{n}"))
+//!     .read()?;
+//! ```
+//!
+//! With the `tracing` feature every notification is also emitted as a
+//! `tracing` event when it is recorded (`error` for
+//! [`NotificationType::Error`], `warn` otherwise), inside the reader's
+//! `read_dxf`/`read_dwg`, `section` and per-object spans.
+//!
+//! [`CadDocument::notifications`]: crate::CadDocument::notifications
 
 use std::fmt;
+use std::sync::Arc;
+
+use crate::types::Handle;
 
 /// Severity level of a notification.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+///
+/// Variants are ordered by increasing severity, so
+/// `n.notification_type >= NotificationType::Warning` selects warnings and
+/// errors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum NotificationType {
     /// An entity/object/section is not yet implemented.
     NotImplemented,
@@ -33,13 +57,117 @@ impl fmt::Display for NotificationType {
     }
 }
 
+/// Stable identifier of what a notification is about.
+///
+/// The numeric [`code`](Self::code) and the [`name`](Self::name) of a variant
+/// never change, so tools can match on them across releases; messages may
+/// be reworded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum NotificationCode {
+    /// No specific code
+    #[default]
+    Unspecified,
+    /// An entity type the reader does not know was kept as `UnknownEntity`
+    UnknownEntity,
+    /// An object type the reader does not know was kept as a generic object
+    UnknownObject,
+    /// An object type that is recognized but not read was skipped
+    ObjectSkipped,
+    /// A whole section was skipped
+    SectionSkipped,
+    /// A table entry could not be built yet
+    TableEntryDeferred,
+    /// Malformed group code or blank line skipped while resynchronizing
+    MalformedPair,
+    /// The file ended in the middle of a pair
+    UnexpectedEof,
+    /// A section or table was not closed by ENDSEC/ENDTAB
+    MissingTerminator,
+    /// A DWG section sentinel did not match
+    InvalidSentinel,
+    /// A handle occurred more than once
+    DuplicateHandle,
+    /// A referenced handle does not exist
+    MissingReference,
+    /// A section failed to read and was skipped (failsafe mode)
+    SectionFailed,
+    /// An entity or object failed to read and was skipped (failsafe mode)
+    ObjectFailed,
+    /// The read was cancelled and the document is partial
+    Cancelled,
+}
+
+impl NotificationCode {
+    /// Stable numeric code
+    pub fn code(&self) -> u16 {
+        match self {
+            Self::Unspecified => 0,
+            Self::UnknownEntity => 100,
+            Self::UnknownObject => 101,
+            Self::ObjectSkipped => 102,
+            Self::SectionSkipped => 103,
+            Self::TableEntryDeferred => 104,
+            Self::MalformedPair => 200,
+            Self::UnexpectedEof => 201,
+            Self::MissingTerminator => 202,
+            Self::InvalidSentinel => 203,
+            Self::DuplicateHandle => 300,
+            Self::MissingReference => 301,
+            Self::SectionFailed => 400,
+            Self::ObjectFailed => 401,
+            Self::Cancelled => 500,
+        }
+    }
+
+    /// Stable kebab-case name, e.g. `unknown-entity`
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Unspecified => "unspecified",
+            Self::UnknownEntity => "unknown-entity",
+            Self::UnknownObject => "unknown-object",
+            Self::ObjectSkipped => "object-skipped",
+            Self::SectionSkipped => "section-skipped",
+            Self::TableEntryDeferred => "table-entry-deferred",
+            Self::MalformedPair => "malformed-pair",
+            Self::UnexpectedEof => "unexpected-eof",
+            Self::MissingTerminator => "missing-terminator",
+            Self::InvalidSentinel => "invalid-sentinel",
+            Self::DuplicateHandle => "duplicate-handle",
+            Self::MissingReference => "missing-reference",
+            Self::SectionFailed => "section-failed",
+            Self::ObjectFailed => "object-failed",
+            Self::Cancelled => "cancelled",
+        }
+    }
+}
+
+impl fmt::Display for NotificationCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
 /// A single notification produced during reading or writing.
+///
+/// Built with [`Notification::new`] and the `with_*` methods; fields may be
+/// added in later versions.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
 pub struct Notification {
     /// The severity / category.
     pub notification_type: NotificationType,
     /// A human-readable description of the issue.
     pub message: String,
+    /// What the notification is about.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub code: NotificationCode,
+    /// Handle of the entity or object concerned, if known.
+    pub handle: Option<Handle>,
+    /// Byte offset in the DXF file or DWG object stream, if known.
+    pub offset: Option<u64>,
 }
 
 impl Notification {
@@ -48,6 +176,39 @@ impl Notification {
         Self {
             notification_type,
             message: message.into(),
+            code: NotificationCode::Unspecified,
+            handle: None,
+            offset: None,
+        }
+    }
+
+    /// Set the code.
+    pub fn with_code(mut self, code: NotificationCode) -> Self {
+        self.code = code;
+        self
+    }
+
+    /// Set the entity/object handle.
+    pub fn with_handle(mut self, handle: Handle) -> Self {
+        self.handle = Some(handle);
+        self
+    }
+
+    /// Set the byte offset.
+    pub fn with_offset(mut self, offset: u64) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    #[cfg(feature = "tracing")]
+    fn emit(&self) {
+        let kind = self.notification_type;
+        let code = self.code.name();
+        match kind {
+            NotificationType::Error => {
+                tracing::error!(%kind, code, handle = ?self.handle, offset = ?self.offset, "{}", self.message)
+            }
+            _ => tracing::warn!(%kind, code, handle = ?self.handle, offset = ?self.offset, "{}", self.message),
         }
     }
 }
@@ -58,21 +219,62 @@ impl fmt::Display for Notification {
     }
 }
 
+/// Callback invoked with each notification as it is recorded.
+pub type NotificationObserver = Arc<dyn Fn(&Notification) + Send + Sync>;
+
+/// Pass a new notification to tracing and the observer, if any
+pub(crate) fn announce(notification: &Notification, observer: Option<&NotificationObserver>) {
+    #[cfg(feature = "tracing")]
+    notification.emit();
+    if let Some(observer) = observer {
+        observer(notification);
+    }
+}
+
 /// Collects notifications during a read/write operation.
-#[derive(Debug, Clone, Default)]
+#[derive(Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NotificationCollection {
     items: Vec<Notification>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    observer: Option<NotificationObserver>,
+}
+
+impl fmt::Debug for NotificationCollection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NotificationCollection")
+            .field("items", &self.items)
+            .field("observer", &self.observer.is_some())
+            .finish()
+    }
 }
 
 impl NotificationCollection {
     /// Create an empty collection.
     pub fn new() -> Self {
-        Self { items: Vec::new() }
+        Self::default()
+    }
+
+    /// Call `observer` with every notification recorded from now on;
+    /// `None` removes it.
+    pub fn set_observer(&mut self, observer: Option<NotificationObserver>) {
+        self.observer = observer;
     }
 
     /// Record a notification.
     pub fn notify(&mut self, notification_type: NotificationType, message: impl Into<String>) {
-        self.items.push(Notification::new(notification_type, message));
+        self.push(Notification::new(notification_type, message));
+    }
+
+    /// Record a notification built with a code or context.
+    pub fn push(&mut self, notification: Notification) {
+        announce(&notification, self.observer.as_ref());
+        self.items.push(notification);
+    }
+
+    /// Add notifications that were already announced when they were made
+    pub(crate) fn extend_announced(&mut self, notifications: impl IntoIterator<Item = Notification>) {
+        self.items.extend(notifications);
     }
 
     /// Check if there are any notifications.
@@ -95,6 +297,16 @@ impl NotificationCollection {
         self.items.iter().filter(|n| n.notification_type == nt).collect()
     }
 
+    /// Iterate over notifications at least as severe as `min`.
+    pub fn at_least(&self, min: NotificationType) -> impl Iterator<Item = &Notification> {
+        self.items.iter().filter(move |n| n.notification_type >= min)
+    }
+
+    /// Iterate over notifications with the given code.
+    pub fn with_code(&self, code: NotificationCode) -> impl Iterator<Item = &Notification> {
+        self.items.iter().filter(move |n| n.code == code)
+    }
+
     /// Check whether any notification of the given type exists.
     pub fn has_type(&self, nt: NotificationType) -> bool {
         self.items.iter().any(|n| n.notification_type == nt)
@@ -155,4 +367,91 @@ mod tests {
         let n = Notification::new(NotificationType::NotImplemented, "THUMBNAILIMAGE section");
         assert_eq!(format!("{}", n), "[NotImplemented] THUMBNAILIMAGE section");
     }
+
+    #[test]
+    fn test_severity_and_code_filters() {
+        let mut c = NotificationCollection::new();
+        c.notify(NotificationType::NotImplemented, "n1");
+        c.push(Notification::new(NotificationType::Warning, "w1").with_code(NotificationCode::DuplicateHandle));
+        c.notify(NotificationType::Error, "e1");
+
+        let severe: Vec<_> = c.at_least(NotificationType::Warning).map(|n| n.message.as_str()).collect();
+        assert_eq!(severe, ["w1", "e1"]);
+        assert_eq!(c.with_code(NotificationCode::DuplicateHandle).count(), 1);
+        assert_eq!(NotificationCode::DuplicateHandle.code(), 300);
+        assert_eq!(NotificationCode::DuplicateHandle.to_string(), "duplicate-handle");
+    }
+
+    #[test]
+    fn test_reader_observer() {
+        use std::sync::Mutex;
+
+        let mut doc = crate::CadDocument::new();
+        let handle = doc.add_entity(crate::EntityType::Circle(crate::Circle::new())).unwrap();
+        let text = String::from_utf8(crate::DxfWriter::new(doc).write_to_vec().unwrap()).unwrap();
+        let bytes = text.replacen("\nCIRCLE\n", "\nMADEUP\n", 1).into_bytes();
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&seen);
+        let doc = crate::DxfReader::from_bytes(bytes)
+            .unwrap()
+            .on_notification(move |n| sink.lock().unwrap().push(n.clone()))
+            .read()
+            .unwrap();
+
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen.len(), doc.notifications.len());
+        let unknown = seen.iter().find(|n| n.code == NotificationCode::UnknownEntity).unwrap();
+        assert_eq!(unknown.notification_type, NotificationType::NotImplemented);
+        assert_eq!(unknown.handle, Some(handle));
+        assert!(unknown.offset.is_some());
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn test_tracing_spans_and_events() {
+        use std::sync::{Arc, Mutex};
+        use tracing::span::{Attributes, Id, Record};
+        use tracing::{Event, Level, Metadata};
+
+        /// Records span names and event levels
+        #[derive(Default, Clone)]
+        struct Recorder(Arc<Mutex<(Vec<&'static str>, Vec<Level>)>>);
+
+        impl tracing::Subscriber for Recorder {
+            fn enabled(&self, _: &Metadata<'_>) -> bool {
+                true
+            }
+            fn new_span(&self, span: &Attributes<'_>) -> Id {
+                let mut log = self.0.lock().unwrap();
+                log.0.push(span.metadata().name());
+                Id::from_u64(log.0.len() as u64)
+            }
+            fn record(&self, _: &Id, _: &Record<'_>) {}
+            fn record_follows_from(&self, _: &Id, _: &Id) {}
+            fn event(&self, event: &Event<'_>) {
+                self.0.lock().unwrap().1.push(*event.metadata().level());
+            }
+            fn enter(&self, _: &Id) {}
+            fn exit(&self, _: &Id) {}
+        }
+
+        let mut doc = crate::CadDocument::new();
+        doc.add_entity(crate::EntityType::Line(crate::Line::new())).unwrap();
+        doc.add_entity(crate::EntityType::Circle(crate::Circle::new())).unwrap();
+        let text = String::from_utf8(crate::DxfWriter::new(doc).write_to_vec().unwrap()).unwrap();
+        // Turn the circle into an entity type the reader does not know
+        let bytes = text.replacen("\nCIRCLE\n", "\nMADEUP\n", 1).into_bytes();
+
+        let recorder = Recorder::default();
+        tracing::subscriber::with_default(recorder.clone(), || {
+            crate::DxfReader::from_bytes(bytes).unwrap().read().unwrap();
+        });
+
+        let log = recorder.0.lock().unwrap();
+        assert_eq!(log.0.first(), Some(&"read_dxf"));
+        assert!(log.0.contains(&"section"));
+        assert!(log.0.contains(&"entity"));
+        assert!(log.1.contains(&Level::WARN));
+    }
 }
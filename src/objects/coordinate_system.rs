@@ -0,0 +1,364 @@
+//! Catalog of common coordinate systems for GEODATA
+//!
+//! AutoCAD stores the coordinate system of a GEODATA object as an XML
+//! definition in the MapGuide coordinate system dictionary format (codes
+//! 303/301). [`CoordinateSystem`] builds these definitions for a small set
+//! of widely used systems, found by EPSG code or by their AutoCAD (CS-MAP)
+//! name:
+//!
+//! - WGS84, ETRS89 and NAD83 latitude/longitude (`LL84`, `ETRS89.LL`, `LL83`)
+//! - Web Mercator (`WGS84.PseudoMercator`)
+//! - UTM zones on WGS84 (`UTM84-33N`), ETRS89 (`ETRS89.UTM-32N`) and
+//!   NAD83 (`UTM83-17`)
+//!
+//! ```rust,ignore
+//! use acadrust::objects::{CoordinateSystem, GeoData};
+//!
+//! let mut geo = GeoData::new();
+//! geo.set_coordinate_system(&CoordinateSystem::from_epsg(32633).unwrap());
+//! doc.set_geodata(geo)?;
+//! ```
+
+use std::fmt::Write as _;
+
+/// Geodetic datum of a catalog coordinate system
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Datum {
+    /// World Geodetic System 1984
+    Wgs84,
+    /// European Terrestrial Reference System 1989
+    Etrs89,
+    /// North American Datum 1983
+    Nad83,
+}
+
+impl Datum {
+    /// CS-MAP datum name
+    pub fn code(&self) -> &'static str {
+        match self {
+            Datum::Wgs84 => "WGS84",
+            Datum::Etrs89 => "ETRS89",
+            Datum::Nad83 => "NAD83",
+        }
+    }
+
+    /// EPSG code of the datum
+    pub fn epsg(&self) -> u32 {
+        match self {
+            Datum::Wgs84 => 6326,
+            Datum::Etrs89 => 6258,
+            Datum::Nad83 => 6269,
+        }
+    }
+
+    /// Human readable name
+    pub fn description(&self) -> &'static str {
+        match self {
+            Datum::Wgs84 => "World Geodetic System of 1984",
+            Datum::Etrs89 => "European Terrestrial Reference System of 1989",
+            Datum::Nad83 => "North American Datum of 1983",
+        }
+    }
+
+    /// Ellipsoid name, EPSG code, semi-major axis in meters and inverse
+    /// flattening
+    fn ellipsoid(&self) -> (&'static str, u32, f64, f64) {
+        match self {
+            Datum::Wgs84 => ("WGS84", 7030, 6_378_137.0, 298.257_223_563),
+            Datum::Etrs89 | Datum::Nad83 => ("GRS1980", 7019, 6_378_137.0, 298.257_222_101),
+        }
+    }
+}
+
+/// Map projection of a catalog coordinate system
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Projection {
+    /// Longitude and latitude in degrees
+    Geographic,
+    /// Spherical Mercator as used by web maps
+    PseudoMercator,
+    /// Transverse Mercator
+    TransverseMercator {
+        /// Longitude of natural origin in degrees
+        central_meridian: f64,
+        /// Scale factor at natural origin
+        scale_factor: f64,
+        /// False easting in meters
+        false_easting: f64,
+        /// False northing in meters
+        false_northing: f64,
+    },
+}
+
+/// Coordinate system definition for [`GeoData`](super::GeoData)
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CoordinateSystem {
+    /// AutoCAD (CS-MAP) name, e.g. `UTM84-33N`
+    pub code: String,
+    /// EPSG code
+    pub epsg: u32,
+    /// Human readable name
+    pub description: String,
+    /// Geodetic datum
+    pub datum: Datum,
+    /// Map projection
+    pub projection: Projection,
+}
+
+impl CoordinateSystem {
+    /// Latitude/longitude on a datum
+    pub fn geographic(datum: Datum) -> Self {
+        let (code, epsg) = match datum {
+            Datum::Wgs84 => ("LL84", 4326),
+            Datum::Etrs89 => ("ETRS89.LL", 4258),
+            Datum::Nad83 => ("LL83", 4269),
+        };
+        Self {
+            code: code.to_string(),
+            epsg,
+            description: format!("{} datum, Latitude-Longitude; Degrees", datum.code()),
+            datum,
+            projection: Projection::Geographic,
+        }
+    }
+
+    /// Web Mercator (EPSG:3857)
+    pub fn web_mercator() -> Self {
+        Self {
+            code: "WGS84.PseudoMercator".to_string(),
+            epsg: 3857,
+            description: "Popular Visualisation Pseudo Mercator".to_string(),
+            datum: Datum::Wgs84,
+            projection: Projection::PseudoMercator,
+        }
+    }
+
+    /// UTM zone on a datum
+    ///
+    /// Returns `None` for zones outside 1–60, and for zones the EPSG
+    /// registry has no code for: ETRS89 covers northern zones 28–38 and
+    /// NAD83 northern zones 1–23.
+    pub fn utm(datum: Datum, zone: u8, north: bool) -> Option<Self> {
+        if !(1..=60).contains(&zone) {
+            return None;
+        }
+        let zone_code = u32::from(zone);
+        let (code, epsg) = match (datum, north) {
+            (Datum::Wgs84, true) => (format!("UTM84-{zone}N"), 32600 + zone_code),
+            (Datum::Wgs84, false) => (format!("UTM84-{zone}S"), 32700 + zone_code),
+            (Datum::Etrs89, true) if (28..=38).contains(&zone) => (format!("ETRS89.UTM-{zone}N"), 25800 + zone_code),
+            (Datum::Nad83, true) if zone <= 23 => (format!("UTM83-{zone}"), 26900 + zone_code),
+            _ => return None,
+        };
+        let hemisphere = if north { "Northern" } else { "Southern" };
+        Some(Self {
+            code,
+            epsg,
+            description: format!("UTM with {} datum, Zone {zone}, {hemisphere} Hemisphere", datum.code()),
+            datum,
+            projection: Projection::TransverseMercator {
+                central_meridian: f64::from(zone) * 6.0 - 183.0,
+                scale_factor: 0.9996,
+                false_easting: 500_000.0,
+                false_northing: if north { 0.0 } else { 10_000_000.0 },
+            },
+        })
+    }
+
+    /// Catalog entry for an EPSG code
+    pub fn from_epsg(epsg: u32) -> Option<Self> {
+        match epsg {
+            4326 => Some(Self::geographic(Datum::Wgs84)),
+            4258 => Some(Self::geographic(Datum::Etrs89)),
+            4269 => Some(Self::geographic(Datum::Nad83)),
+            3857 => Some(Self::web_mercator()),
+            32601..=32660 => Self::utm(Datum::Wgs84, (epsg - 32600) as u8, true),
+            32701..=32760 => Self::utm(Datum::Wgs84, (epsg - 32700) as u8, false),
+            25828..=25838 => Self::utm(Datum::Etrs89, (epsg - 25800) as u8, true),
+            26901..=26923 => Self::utm(Datum::Nad83, (epsg - 26900) as u8, true),
+            _ => None,
+        }
+    }
+
+    /// Catalog entry by AutoCAD (CS-MAP) name (case-insensitive)
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::catalog().into_iter().find(|cs| cs.code.eq_ignore_ascii_case(code))
+    }
+
+    /// Every coordinate system in the catalog
+    pub fn catalog() -> Vec<Self> {
+        let mut catalog = vec![
+            Self::geographic(Datum::Wgs84),
+            Self::geographic(Datum::Etrs89),
+            Self::geographic(Datum::Nad83),
+            Self::web_mercator(),
+        ];
+        for (datum, north) in [
+            (Datum::Wgs84, true),
+            (Datum::Wgs84, false),
+            (Datum::Etrs89, true),
+            (Datum::Nad83, true),
+        ] {
+            catalog.extend((1..=60).filter_map(|zone| Self::utm(datum, zone, north)));
+        }
+        catalog
+    }
+
+    /// Whether coordinates are longitude and latitude
+    pub fn is_geographic(&self) -> bool {
+        self.projection == Projection::Geographic
+    }
+
+    /// GEODATA coordinate type (code 70): 3 for geographic systems, 2 for
+    /// projected grids
+    pub fn coordinate_type(&self) -> i16 {
+        if self.is_geographic() { 3 } else { 2 }
+    }
+
+    /// The XML definition AutoCAD stores in GEODATA
+    pub fn to_xml(&self) -> String {
+        let mut xml = String::new();
+        xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-16\" standalone=\"no\" ?>");
+        xml.push_str("<Dictionary version=\"1.0\" xmlns=\"http://www.osgeo.org/mapguide/coordinatesystem\">");
+
+        let element = if self.is_geographic() { "GeographicCoordinateSystem" } else { "ProjectedCoordinateSystem" };
+        let _ = write!(xml, "<{element} id=\"{}\">", self.code);
+        let _ = write!(xml, "<Name>{}</Name>", self.code);
+        let _ = write!(xml, "<Description>{}</Description>", self.description);
+        let _ = write!(xml, "<Authority>EPSG Code {}</Authority>", self.epsg);
+        let _ = write!(xml, "<DatumId>{}</DatumId>", self.datum.code());
+        if self.is_geographic() {
+            xml.push_str("<Axis uom=\"degree\">");
+            axis(&mut xml, 1, "Geodetic longitude", "Lon", "east");
+            axis(&mut xml, 2, "Geodetic latitude", "Lat", "north");
+        } else {
+            xml.push_str("<Axis uom=\"meter\">");
+            axis(&mut xml, 1, "Easting", "E", "east");
+            axis(&mut xml, 2, "Northing", "N", "north");
+        }
+        xml.push_str("</Axis>");
+        match self.projection {
+            Projection::Geographic => {}
+            Projection::PseudoMercator => {
+                projection(&mut xml, "Popular Visualisation Pseudo Mercator", &[
+                    ("Longitude of natural origin", "degree", 0.0),
+                    ("False easting", "meter", 0.0),
+                    ("False northing", "meter", 0.0),
+                ]);
+            }
+            Projection::TransverseMercator { central_meridian, scale_factor, false_easting, false_northing } => {
+                projection(&mut xml, "Transverse Mercator", &[
+                    ("Latitude of natural origin", "degree", 0.0),
+                    ("Longitude of natural origin", "degree", central_meridian),
+                    ("Scale factor at natural origin", "unity", scale_factor),
+                    ("False easting", "meter", false_easting),
+                    ("False northing", "meter", false_northing),
+                ]);
+            }
+        }
+        let _ = write!(xml, "</{element}>");
+
+        let datum = self.datum;
+        let (ellipsoid, ellipsoid_epsg, semi_major, inverse_flattening) = datum.ellipsoid();
+        let _ = write!(xml, "<GeodeticDatum id=\"{}\">", datum.code());
+        let _ = write!(xml, "<Name>{}</Name>", datum.code());
+        let _ = write!(xml, "<Description>{}</Description>", datum.description());
+        let _ = write!(xml, "<Authority>EPSG Code {}</Authority>", datum.epsg());
+        xml.push_str("<PrimeMeridianId>Greenwich</PrimeMeridianId>");
+        let _ = write!(xml, "<EllipsoidId>{ellipsoid}</EllipsoidId>");
+        xml.push_str("</GeodeticDatum>");
+
+        let _ = write!(xml, "<Ellipsoid id=\"{ellipsoid}\">");
+        let _ = write!(xml, "<Name>{ellipsoid}</Name>");
+        let _ = write!(xml, "<Authority>EPSG Code {ellipsoid_epsg}</Authority>");
+        let _ = write!(xml, "<SemiMajorAxis uom=\"meter\">{semi_major}</SemiMajorAxis>");
+        let _ = write!(
+            xml,
+            "<SecondDefiningParameter><InverseFlattening uom=\"unity\">{inverse_flattening}</InverseFlattening></SecondDefiningParameter>"
+        );
+        xml.push_str("</Ellipsoid>");
+
+        let _ = write!(
+            xml,
+            "<Alias id=\"{}\" type=\"CoordinateSystem\"><ObjectId>{}</ObjectId><Namespace>EPSG Code</Namespace></Alias>",
+            self.epsg, self.code
+        );
+        xml.push_str("</Dictionary>");
+        xml
+    }
+}
+
+fn axis(xml: &mut String, order: u8, name: &str, abbreviation: &str, direction: &str) {
+    let _ = write!(
+        xml,
+        "<CoordinateSystemAxis><AxisOrder>{order}</AxisOrder><AxisName>{name}</AxisName>\
+         <AxisAbbreviation>{abbreviation}</AxisAbbreviation><AxisDirection>{direction}</AxisDirection></CoordinateSystemAxis>"
+    );
+}
+
+fn projection(xml: &mut String, method: &str, parameters: &[(&str, &str, f64)]) {
+    xml.push_str("<Conversion><Projection>");
+    let _ = write!(xml, "<OperationMethodId>{method}</OperationMethodId>");
+    for (name, uom, value) in parameters {
+        let _ = write!(
+            xml,
+            "<ParameterValue><OperationParameterId>{name}</OperationParameterId><Value uom=\"{uom}\">{value}</Value></ParameterValue>"
+        );
+    }
+    xml.push_str("</Projection></Conversion>");
+}
+
+/// The `id` of the coordinate system element of a GEODATA XML definition
+pub(super) fn coordinate_system_code(xml: &str) -> Option<&str> {
+    let start = ["<ProjectedCoordinateSystem id=\"", "<GeographicCoordinateSystem id=\""]
+        .iter()
+        .find_map(|tag| xml.find(tag).map(|at| at + tag.len()))?;
+    let end = xml[start..].find('"')?;
+    Some(&xml[start..start + end])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_catalog_lookup() {
+        let utm = CoordinateSystem::from_epsg(32633).unwrap();
+        assert_eq!(utm.code, "UTM84-33N");
+        assert_eq!(utm.coordinate_type(), 2);
+        match utm.projection {
+            Projection::TransverseMercator { central_meridian, false_northing, .. } => {
+                assert_eq!(central_meridian, 15.0);
+                assert_eq!(false_northing, 0.0);
+            }
+            other => panic!("unexpected projection {other:?}"),
+        }
+
+        assert_eq!(CoordinateSystem::from_code("ll84").unwrap().epsg, 4326);
+        assert_eq!(CoordinateSystem::from_epsg(32721).unwrap().code, "UTM84-21S");
+        assert_eq!(CoordinateSystem::from_epsg(25832).unwrap().code, "ETRS89.UTM-32N");
+        assert!(CoordinateSystem::from_epsg(25840).is_none());
+        assert!(CoordinateSystem::utm(Datum::Wgs84, 61, true).is_none());
+
+        let catalog = CoordinateSystem::catalog();
+        assert_eq!(catalog.len(), 4 + 60 + 60 + 11 + 23);
+        assert!(catalog.iter().all(|cs| CoordinateSystem::from_epsg(cs.epsg).as_ref() == Some(cs)));
+    }
+
+    #[test]
+    fn test_coordinate_system_xml() {
+        let xml = CoordinateSystem::from_epsg(4326).unwrap().to_xml();
+        assert!(xml.contains("<GeographicCoordinateSystem id=\"LL84\">"));
+        assert!(xml.contains("<SemiMajorAxis uom=\"meter\">6378137</SemiMajorAxis>"));
+        assert!(!xml.contains("<Conversion>"));
+        assert_eq!(coordinate_system_code(&xml), Some("LL84"));
+
+        let xml = CoordinateSystem::web_mercator().to_xml();
+        assert!(xml.contains("Popular Visualisation Pseudo Mercator"));
+        assert_eq!(coordinate_system_code(&xml), Some("WGS84.PseudoMercator"));
+        assert_eq!(coordinate_system_code("LL84"), None);
+    }
+}
@@ -0,0 +1,176 @@
+//! Dimension associativity (DIMASSOC)
+//!
+//! An associative dimension keeps a DIMASSOC object in its extension
+//! dictionary under `ACAD_DIMASSOC`. The object holds up to four object snap
+//! references, one per definition point of the dimension, naming the
+//! geometry the point was snapped to. The geometry in turn lists the DIMASSOC
+//! among its reactors so it is notified when the geometry changes.
+
+use crate::types::{Handle, Vector3};
+
+/// Object snap a dimension point was placed with (code 72)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ObjectSnapType {
+    /// No snap
+    #[default]
+    None = 0,
+    /// End point of a line, arc or polyline segment
+    Endpoint = 1,
+    /// Midpoint of a line or arc
+    Midpoint = 2,
+    /// Center of a circle, arc or ellipse
+    Center = 3,
+    /// Point entity
+    Node = 4,
+    /// Quadrant point of a circle or arc
+    Quadrant = 5,
+    /// Intersection of two objects
+    Intersection = 6,
+    /// Insertion point of a block reference or text
+    Insertion = 7,
+    /// Perpendicular foot
+    Perpendicular = 8,
+    /// Tangent point
+    Tangent = 9,
+    /// Nearest point, at the geometry parameter of the reference
+    Nearest = 10,
+    /// Apparent intersection
+    ApparentIntersection = 11,
+    /// Parallel
+    Parallel = 12,
+    /// Start point of a line or arc
+    StartPoint = 13,
+}
+
+impl ObjectSnapType {
+    /// Create from DXF code value
+    pub fn from_code(code: i16) -> Self {
+        match code {
+            1 => ObjectSnapType::Endpoint,
+            2 => ObjectSnapType::Midpoint,
+            3 => ObjectSnapType::Center,
+            4 => ObjectSnapType::Node,
+            5 => ObjectSnapType::Quadrant,
+            6 => ObjectSnapType::Intersection,
+            7 => ObjectSnapType::Insertion,
+            8 => ObjectSnapType::Perpendicular,
+            9 => ObjectSnapType::Tangent,
+            10 => ObjectSnapType::Nearest,
+            11 => ObjectSnapType::ApparentIntersection,
+            12 => ObjectSnapType::Parallel,
+            13 => ObjectSnapType::StartPoint,
+            _ => ObjectSnapType::None,
+        }
+    }
+
+    /// Convert to DXF code value
+    pub fn to_code(self) -> i16 {
+        self as i16
+    }
+}
+
+/// Object snap reference of one dimension point (`AcDbOsnapPointRef`)
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OsnapPointRef {
+    /// Snap mode (code 72)
+    pub osnap_type: ObjectSnapType,
+    /// Geometry the point snaps to (code 331); block references come
+    /// first for geometry nested in blocks, the entity itself last
+    pub objects: Vec<Handle>,
+    /// Subentity type of the geometry (code 73)
+    pub subentity_type: i16,
+    /// Graphics system marker of the subentity (code 91)
+    pub gs_marker: i32,
+    /// Geometry parameter of a nearest snap (code 40)
+    pub near_parameter: f64,
+    /// Snapped point in WCS (codes 10/20/30)
+    pub point: Vector3,
+    /// Second geometry of an intersection snap (code 332)
+    pub intersection_objects: Vec<Handle>,
+    /// Subentity type of the intersection geometry (code 74)
+    pub intersection_subentity_type: i16,
+    /// Graphics system marker of the intersection geometry (code 92)
+    pub intersection_gs_marker: i32,
+    /// Whether a last point reference follows (code 75)
+    pub has_last_point_ref: bool,
+}
+
+impl OsnapPointRef {
+    /// Reference snapping to `object` at `point`
+    pub fn new(osnap_type: ObjectSnapType, object: Handle, point: Vector3) -> Self {
+        OsnapPointRef {
+            osnap_type,
+            objects: vec![object],
+            subentity_type: 1,
+            gs_marker: 0,
+            near_parameter: 0.0,
+            point,
+            intersection_objects: Vec::new(),
+            intersection_subentity_type: 0,
+            intersection_gs_marker: 0,
+            has_last_point_ref: false,
+        }
+    }
+
+    /// The entity the point snaps to
+    pub fn object(&self) -> Option<Handle> {
+        self.objects.last().copied().filter(|h| !h.is_null())
+    }
+}
+
+/// DIMASSOC object — links the points of a dimension to geometry
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DimAssoc {
+    /// Unique handle
+    pub handle: Handle,
+    /// Owner handle (the dimension's extension dictionary)
+    pub owner: Handle,
+    /// Reactor handles ({ACAD_REACTORS})
+    pub reactors: Vec<Handle>,
+    /// The associative dimension (code 330)
+    pub dimension: Handle,
+    /// Whether the dimension is in another space than its geometry (code 70)
+    pub trans_space: bool,
+    /// Rotated dimension type (code 71): 0 = parallel, 1 = perpendicular
+    pub rotated_type: i16,
+    /// Snap references of the first to fourth dimension point
+    pub points: [Option<OsnapPointRef>; 4],
+}
+
+impl DimAssoc {
+    /// Create an association for `dimension` without point references
+    pub fn new(dimension: Handle) -> Self {
+        DimAssoc {
+            handle: Handle::NULL,
+            owner: Handle::NULL,
+            reactors: Vec::new(),
+            dimension,
+            trans_space: false,
+            rotated_type: 0,
+            points: [None, None, None, None],
+        }
+    }
+
+    /// Builder: link dimension point `index` (0–3)
+    pub fn with_point(mut self, index: usize, point: OsnapPointRef) -> Self {
+        self.points[index] = Some(point);
+        self
+    }
+
+    /// Associativity flags (code 90): bit `n` set when point `n` is linked
+    pub fn associativity(&self) -> i32 {
+        self.points
+            .iter()
+            .enumerate()
+            .filter(|(_, point)| point.is_some())
+            .fold(0, |flags, (index, _)| flags | (1 << index))
+    }
+
+    /// Every entity the dimension's points snap to
+    pub fn geometry(&self) -> impl Iterator<Item = Handle> + '_ {
+        self.points.iter().flatten().flat_map(|point| point.object())
+    }
+}
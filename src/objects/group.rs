@@ -21,6 +21,7 @@ use crate::types::Handle;
 /// group.add_entity(Handle::new(101));
 /// ```
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Group {
     /// Unique handle
     pub handle: Handle,
@@ -34,6 +35,10 @@ pub struct Group {
     pub entities: Vec<Handle>,
     /// Group is selectable (DXF code 71, default: true)
     pub selectable: bool,
+    /// Reactor handles ({ACAD_REACTORS})
+    pub reactors: Vec<Handle>,
+    /// Extended dictionary handle ({ACAD_XDICTIONARY})
+    pub xdictionary_handle: Option<Handle>,
 }
 
 impl Group {
@@ -49,6 +54,8 @@ impl Group {
             description: String::new(),
             entities: Vec::new(),
             selectable: true,
+            reactors: Vec::new(),
+            xdictionary_handle: None,
         }
     }
 
@@ -4,6 +4,7 @@ use crate::types::Handle;
 
 /// Resolution unit for image definitions
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ResolutionUnit {
     /// No units specified
     #[default]
@@ -39,10 +40,11 @@ impl ResolutionUnit {
 ///
 /// Links an image definition to an image entity that references it.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ImageDefinitionReactor {
     /// Unique handle
     pub handle: Handle,
-    /// Owner handle (usually the ImageDefinition)
+    /// Owner handle (the image entity)
     pub owner: Handle,
     /// Associated image entity handle
     pub image_handle: Handle,
@@ -79,11 +81,15 @@ impl ImageDefinitionReactor {
 /// img_def.pixel_size = (0.01, 0.01); // 0.01 units per pixel
 /// ```
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ImageDefinition {
     /// Unique handle
     pub handle: Handle,
     /// Owner handle
     pub owner: Handle,
+    /// Reactor handles: the owning dictionary and one
+    /// [`ImageDefinitionReactor`] per image using this definition
+    pub reactors: Vec<Handle>,
     /// Image file path (DXF code 1)
     pub file_name: String,
     /// Whether the image file is loaded (DXF code 280)
@@ -111,6 +117,7 @@ impl ImageDefinition {
         Self {
             handle: Handle::NULL,
             owner: Handle::NULL,
+            reactors: Vec::new(),
             file_name: file_name.into(),
             is_loaded: false,
             size_in_pixels: (0, 0),
@@ -129,6 +136,7 @@ impl ImageDefinition {
         Self {
             handle: Handle::NULL,
             owner: Handle::NULL,
+            reactors: Vec::new(),
             file_name: file_name.into(),
             is_loaded: false,
             size_in_pixels: (width_px, height_px),
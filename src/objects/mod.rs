@@ -3,19 +3,24 @@
 //! Objects are non-graphical elements in a DXF file, such as dictionaries,
 //! layouts, groups, and other organizational structures.
 
+mod coordinate_system;
 mod dictionary_variable;
+mod dim_assoc;
 mod group;
 mod image_definition;
 mod mlinestyle;
 mod multileader_style;
 mod plot_settings;
+mod render;
 mod scale;
 mod sort_entities_table;
 mod table_style;
 mod xrecord;
 mod stub_objects;
 
+pub use coordinate_system::{CoordinateSystem, Datum, Projection};
 pub use dictionary_variable::DictionaryVariable;
+pub use dim_assoc::{DimAssoc, ObjectSnapType, OsnapPointRef};
 pub use group::Group;
 pub use image_definition::{ImageDefinition, ImageDefinitionReactor, ResolutionUnit};
 pub use mlinestyle::{MLineStyle, MLineStyleElement, MLineStyleFlags};
@@ -29,6 +34,7 @@ pub use plot_settings::{
     PaperMargin, PlotFlags, PlotPaperUnits, PlotRotation, PlotSettings, PlotType, PlotWindow,
     ScaledType, ShadePlotMode, ShadePlotResolutionLevel,
 };
+pub use render::{Background, BackgroundKind, RenderSettings, Sun};
 pub use scale::Scale;
 pub use sort_entities_table::{SortEntsEntry, SortEntitiesTable};
 pub use table_style::{
@@ -38,14 +44,16 @@ pub use table_style::{
 pub use xrecord::{DictionaryCloningFlags, XRecord, XRecordEntry, XRecordValue, XRecordValueType};
 pub use stub_objects::{
     VisualStyle, Material, GeoData,
-    SpatialFilter, RasterVariables, BookColor, PlaceHolder,
+    SpatialFilter, RasterVariables, FrameDisplay, BookColor, PlaceHolder,
     DictionaryWithDefault, WipeoutVariables, StubObject,
 };
 
-use crate::types::Handle;
+use crate::entities::UnderlayDefinition;
+use crate::types::{BoundingBox2D, Handle, Vector2};
 
 /// Dictionary object - stores key-value pairs of object handles
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Dictionary {
     /// Unique handle
     pub handle: Handle,
@@ -109,6 +117,7 @@ impl Default for Dictionary {
 
 /// Layout object - represents a layout (model space or paper space)
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Layout {
     /// Unique handle
     pub handle: Handle,
@@ -194,10 +203,71 @@ impl Layout {
         layout.flags = 1; // model space
         layout
     }
+
+    /// Printable area of the sheet in paper space, in paper units; see
+    /// [`PlotSettings::printable_area`].
+    pub fn effective_plot_area(&self) -> BoundingBox2D {
+        self.plot_settings.printable_area()
+    }
+
+    /// Area of the drawing the plot type selects, in drawing units: the
+    /// plot window, the extents or limits of the layout, or for a layout
+    /// plot its limits.  `None` for views and the last screen display,
+    /// whose area is not stored with the layout.
+    pub fn plotted_area(&self) -> Option<BoundingBox2D> {
+        let rectangle = |min: (f64, f64), max: (f64, f64)| {
+            BoundingBox2D::new(Vector2::new(min.0, min.1), Vector2::new(max.0, max.1))
+        };
+        match self.plot_settings.plot_type {
+            PlotType::Window => {
+                let window = &self.plot_settings.plot_window;
+                Some(rectangle(
+                    (window.lower_left_x, window.lower_left_y),
+                    (window.upper_right_x, window.upper_right_y),
+                ))
+            }
+            PlotType::Extents => Some(rectangle(
+                (self.min_extents.0, self.min_extents.1),
+                (self.max_extents.0, self.max_extents.1),
+            )),
+            PlotType::Limits | PlotType::Layout => Some(rectangle(self.min_limits, self.max_limits)),
+            PlotType::View | PlotType::LastScreenDisplay => None,
+        }
+    }
+
+    /// Plot scale in paper units per drawing unit.
+    ///
+    /// With scale to fit this is the largest scale that fits the
+    /// [`Self::plotted_area`] into the [`Self::effective_plot_area`], or 1
+    /// when either is unknown or empty; otherwise it is
+    /// [`PlotSettings::plot_scale`].
+    pub fn plot_scale(&self) -> f64 {
+        if !self.plot_settings.is_scale_to_fit() {
+            return self.plot_settings.plot_scale();
+        }
+        let printable = self.effective_plot_area();
+        match self.plotted_area() {
+            Some(area) if area.width() > 0.0 && area.height() > 0.0 && printable.width() > 0.0 => {
+                (printable.width() / area.width()).min(printable.height() / area.height())
+            }
+            _ => 1.0,
+        }
+    }
+
+    /// Convert a length on paper, in paper units, to drawing units
+    pub fn paper_to_drawing(&self, length: f64) -> f64 {
+        length / self.plot_scale()
+    }
+
+    /// Convert a length in drawing units to paper units
+    pub fn drawing_to_paper(&self, length: f64) -> f64 {
+        length * self.plot_scale()
+    }
 }
 
 /// Object types
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ObjectType {
     /// Dictionary object
     Dictionary(Dictionary),
@@ -243,15 +313,162 @@ pub enum ObjectType {
     DictionaryWithDefault(DictionaryWithDefault),
     /// WipeoutVariables object
     WipeoutVariables(WipeoutVariables),
+    /// PDF, DWF or DGN underlay definition object
+    UnderlayDefinition(UnderlayDefinition),
+    /// SUN object
+    Sun(Sun),
+    /// Background object (SOLID_BACKGROUND, SKYLIGHT_BACKGROUND, ...)
+    Background(Background),
+    /// Render settings object (MENTALRAYRENDERSETTINGS, ...)
+    RenderSettings(RenderSettings),
+    /// Dimension associativity object (DIMASSOC)
+    DimAssoc(DimAssoc),
     /// Unknown object type (stored as raw data)
     Unknown {
         /// Object type name
         type_name: String,
         /// Object handle
         handle: Handle,
+        /// Raw code/value pairs following the `0` type marker, in file order.
+        /// Written back verbatim by the DXF writer.
+        raw_pairs: Vec<(i32, String)>,
     },
 }
 
+/// Handle and owner accessors over every object variant
+macro_rules! object_handles {
+    ($($variant:ident => $owner:ident),* $(,)?) => {
+        impl ObjectType {
+            /// Variant name, or the type name of an unknown object
+            pub fn type_name(&self) -> &str {
+                match self {
+                    $(ObjectType::$variant(_) => stringify!($variant),)*
+                    ObjectType::Unknown { type_name, .. } => type_name,
+                }
+            }
+
+            /// Handle of the object
+            pub fn handle(&self) -> Handle {
+                match self {
+                    $(ObjectType::$variant(o) => o.handle,)*
+                    ObjectType::Unknown { handle, .. } => *handle,
+                }
+            }
+
+            /// Mutable handle of the object
+            pub fn handle_mut(&mut self) -> &mut Handle {
+                match self {
+                    $(ObjectType::$variant(o) => &mut o.handle,)*
+                    ObjectType::Unknown { handle, .. } => handle,
+                }
+            }
+
+            /// Owner handle; `None` for unknown objects, whose owner is part
+            /// of their raw pairs
+            pub fn owner(&self) -> Option<Handle> {
+                match self {
+                    $(ObjectType::$variant(o) => Some(o.$owner),)*
+                    ObjectType::Unknown { .. } => None,
+                }
+            }
+
+            /// Mutable owner handle; `None` for unknown objects, whose owner
+            /// is part of their raw pairs
+            pub fn owner_mut(&mut self) -> Option<&mut Handle> {
+                match self {
+                    $(ObjectType::$variant(o) => Some(&mut o.$owner),)*
+                    ObjectType::Unknown { .. } => None,
+                }
+            }
+        }
+    };
+}
+
+object_handles! {
+    Dictionary => owner,
+    Layout => owner,
+    XRecord => owner,
+    Group => owner,
+    MLineStyle => owner,
+    ImageDefinition => owner,
+    PlotSettings => owner,
+    MultiLeaderStyle => owner_handle,
+    TableStyle => owner_handle,
+    Scale => owner_handle,
+    SortEntitiesTable => owner_handle,
+    DictionaryVariable => owner_handle,
+    VisualStyle => owner,
+    Material => owner,
+    ImageDefinitionReactor => owner,
+    GeoData => owner,
+    SpatialFilter => owner,
+    RasterVariables => owner,
+    BookColor => owner,
+    PlaceHolder => owner,
+    DictionaryWithDefault => owner,
+    WipeoutVariables => owner,
+    UnderlayDefinition => owner_handle,
+    Sun => owner,
+    Background => owner,
+    RenderSettings => owner,
+    DimAssoc => owner,
+}
+
+impl ObjectType {
+    /// Reactor handles ({ACAD_REACTORS}); empty for objects that do not
+    /// keep them
+    pub fn reactors(&self) -> &[Handle] {
+        match self {
+            ObjectType::Dictionary(o) => &o.reactors,
+            ObjectType::Layout(o) => &o.reactors,
+            ObjectType::XRecord(o) => &o.reactors,
+            ObjectType::Group(o) => &o.reactors,
+            ObjectType::ImageDefinition(o) => &o.reactors,
+            ObjectType::UnderlayDefinition(o) => &o.reactors,
+            ObjectType::DimAssoc(o) => &o.reactors,
+            _ => &[],
+        }
+    }
+
+    /// Mutable reactor handles; `None` for objects that do not keep them
+    pub fn reactors_mut(&mut self) -> Option<&mut Vec<Handle>> {
+        match self {
+            ObjectType::Dictionary(o) => Some(&mut o.reactors),
+            ObjectType::Layout(o) => Some(&mut o.reactors),
+            ObjectType::XRecord(o) => Some(&mut o.reactors),
+            ObjectType::Group(o) => Some(&mut o.reactors),
+            ObjectType::ImageDefinition(o) => Some(&mut o.reactors),
+            ObjectType::UnderlayDefinition(o) => Some(&mut o.reactors),
+            ObjectType::DimAssoc(o) => Some(&mut o.reactors),
+            _ => None,
+        }
+    }
+
+    /// Extended dictionary handle ({ACAD_XDICTIONARY})
+    pub fn xdictionary_handle(&self) -> Option<Handle> {
+        match self {
+            ObjectType::Dictionary(o) => o.xdictionary_handle,
+            ObjectType::Layout(o) => o.xdictionary_handle,
+            ObjectType::XRecord(o) => o.xdictionary_handle,
+            ObjectType::Group(o) => o.xdictionary_handle,
+            _ => None,
+        }
+        .filter(|h| !h.is_null())
+    }
+
+    /// Mutable extended dictionary handle; `None` for objects that do not
+    /// keep one
+    pub fn xdictionary_handle_mut(&mut self) -> Option<&mut Option<Handle>> {
+        match self {
+            ObjectType::Dictionary(o) => Some(&mut o.xdictionary_handle),
+            ObjectType::Layout(o) => Some(&mut o.xdictionary_handle),
+            ObjectType::XRecord(o) => Some(&mut o.xdictionary_handle),
+            ObjectType::Group(o) => Some(&mut o.xdictionary_handle),
+            _ => None,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -273,6 +490,45 @@ mod tests {
         assert_eq!(layout.name, "Layout1");
         assert_eq!(layout.tab_order, 0);
     }
+
+    #[test]
+    fn test_layout_plot_scale() {
+        // A3 landscape in millimeters, no margins
+        let mut layout = Layout::new("Layout1");
+        layout.plot_settings.paper_units = PlotPaperUnits::Millimeters;
+        layout.plot_settings.set_paper_size(297.0, 420.0);
+        layout.plot_settings.rotation = PlotRotation::Degrees90;
+        layout.plot_settings.set_plot_window(0.0, 0.0, 8400.0, 2970.0);
+        layout.plot_settings.set_scale_to_fit();
+
+        let area = layout.effective_plot_area();
+        assert_eq!((area.width(), area.height()), (420.0, 297.0));
+        // The window is limited by its width
+        assert!((layout.plot_scale() - 0.05).abs() < 1e-12);
+        assert!((layout.paper_to_drawing(10.0) - 200.0).abs() < 1e-9);
+
+        layout.plot_settings.set_custom_scale(1.0, 100.0);
+        assert!((layout.drawing_to_paper(500.0) - 5.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_layout_plot_area_with_margins() {
+        // A4 portrait turned a quarter, margins given for the unturned sheet
+        let mut layout = Layout::new("Layout1");
+        let plot = &mut layout.plot_settings;
+        plot.paper_units = PlotPaperUnits::Millimeters;
+        plot.set_paper_with_margins(210.0, 297.0, PaperMargin::new(5.0, 10.0, 5.0, 20.0));
+        plot.rotation = PlotRotation::Degrees90;
+        plot.set_custom_scale(1.0, 50.0);
+
+        let area = layout.effective_plot_area();
+        assert_eq!(area.min, Vector2::new(0.0, 0.0));
+        // 297 wide less the device's top and bottom margins
+        assert!((area.width() - 267.0).abs() < 1e-9);
+        assert!((area.height() - 200.0).abs() < 1e-9);
+        assert!((layout.plot_scale() - 0.02).abs() < 1e-12);
+        assert!((layout.paper_to_drawing(area.width()) - 13350.0).abs() < 1e-6);
+    }
 }
 
 
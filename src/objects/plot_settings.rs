@@ -1,9 +1,17 @@
 //! PlotSettings object - Plot configuration settings
+//!
+//! Paper size, unprintable margins and plot origin are stored in
+//! millimeters whatever [`PlotPaperUnits`] says; the units only select how
+//! the plot scale and the values shown to the user are expressed.  The
+//! plot window is in drawing units.  The `*_in_units` methods and
+//! [`PlotSettings::printable_area`] do the conversions, with the sheet
+//! turned as the plot rotation turns it.
 
-use crate::types::Handle;
+use crate::types::{BoundingBox2D, Handle, Vector2};
 
 /// Plot paper units
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PlotPaperUnits {
     /// Inches
     #[default]
@@ -28,10 +36,29 @@ impl PlotPaperUnits {
     pub fn to_code(self) -> i16 {
         self as i16
     }
+
+    /// Millimeters in one unit; pixels are taken as stored, like millimeters
+    pub fn millimeters_per_unit(self) -> f64 {
+        match self {
+            PlotPaperUnits::Inches => 25.4,
+            PlotPaperUnits::Millimeters | PlotPaperUnits::Pixels => 1.0,
+        }
+    }
+
+    /// Convert a length in these units to millimeters
+    pub fn to_millimeters(self, value: f64) -> f64 {
+        value * self.millimeters_per_unit()
+    }
+
+    /// Convert a length in millimeters to these units
+    pub fn from_millimeters(self, millimeters: f64) -> f64 {
+        millimeters / self.millimeters_per_unit()
+    }
 }
 
 /// Plot rotation angle
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PlotRotation {
     /// No rotation (0 degrees)
     #[default]
@@ -74,10 +101,16 @@ impl PlotRotation {
     pub fn to_radians(self) -> f64 {
         self.to_degrees().to_radians()
     }
+
+    /// Whether the sheet is turned on its side, swapping width and height
+    pub fn is_quarter_turn(self) -> bool {
+        matches!(self, PlotRotation::Degrees90 | PlotRotation::Degrees270)
+    }
 }
 
 /// Plot type - what area of the drawing to plot
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PlotType {
     /// Plot the last screen display
     LastScreenDisplay = 0,
@@ -115,6 +148,7 @@ impl PlotType {
 
 /// Scale type
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ScaledType {
     /// Scale to fit
     #[default]
@@ -213,6 +247,7 @@ impl ScaledType {
 
 /// Shade plot mode
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ShadePlotMode {
     /// As displayed
     #[default]
@@ -244,6 +279,7 @@ impl ShadePlotMode {
 
 /// Shade plot resolution level
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ShadePlotResolutionLevel {
     /// Draft
     Draft = 0,
@@ -281,6 +317,7 @@ impl ShadePlotResolutionLevel {
 
 /// Plot flags
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PlotFlags {
     /// Plot viewport borders
     pub plot_viewport_borders: bool,
@@ -356,6 +393,7 @@ impl PlotFlags {
 
 /// Paper margins
 #[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PaperMargin {
     /// Left margin (unprintable area)
     pub left: f64,
@@ -401,6 +439,7 @@ impl PaperMargin {
 
 /// Plot window area
 #[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PlotWindow {
     /// Lower-left X coordinate
     pub lower_left_x: f64,
@@ -456,6 +495,7 @@ impl PlotWindow {
 /// settings.paper_units = PlotPaperUnits::Millimeters;
 /// ```
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PlotSettings {
     /// Unique handle
     pub handle: Handle,
@@ -504,6 +544,7 @@ pub struct PlotSettings {
     /// Plot flags (DXF code 70)
     pub flags: PlotFlags,
     /// Scale factor (computed from numerator/denominator)
+    #[cfg_attr(feature = "serde", serde(skip))]
     cached_scale: Option<f64>,
 }
 
@@ -607,6 +648,86 @@ impl PlotSettings {
         self.cached_scale = None;
     }
 
+    /// Plot scale in paper units per drawing unit: the standard scale when
+    /// one is in use, otherwise numerator over denominator.  Scale to fit
+    /// depends on the plotted area; see
+    /// [`Layout::plot_scale`](crate::objects::Layout::plot_scale).
+    pub fn plot_scale(&self) -> f64 {
+        let standard = !matches!(self.scale_type, ScaledType::ScaleToFit | ScaledType::CustomScale);
+        if self.flags.use_standard_scale && standard {
+            self.scale_type.scale_factor()
+        } else {
+            self.scale_factor()
+        }
+    }
+
+    /// Width and height of the sheet in paper units, as it lies in the
+    /// layout: swapped when the plot is turned a quarter.
+    pub fn paper_size_in_units(&self) -> Vector2 {
+        let width = self.paper_units.from_millimeters(self.paper_width);
+        let height = self.paper_units.from_millimeters(self.paper_height);
+        if self.rotation.is_quarter_turn() {
+            Vector2::new(height, width)
+        } else {
+            Vector2::new(width, height)
+        }
+    }
+
+    /// Unprintable margins in paper units, on the sides of the sheet as it
+    /// lies in the layout.
+    ///
+    /// The stored margins are those of the device, which feeds the sheet
+    /// unrotated; a plot turned 90° counter-clockwise has the device's top
+    /// margin on its left.
+    pub fn margins_in_units(&self) -> PaperMargin {
+        let m = self.margins;
+        let (left, bottom, right, top) = match self.rotation {
+            PlotRotation::None => (m.left, m.bottom, m.right, m.top),
+            PlotRotation::Degrees90 => (m.top, m.left, m.bottom, m.right),
+            PlotRotation::Degrees180 => (m.right, m.top, m.left, m.bottom),
+            PlotRotation::Degrees270 => (m.bottom, m.right, m.top, m.left),
+        };
+        let units = self.paper_units;
+        PaperMargin::new(
+            units.from_millimeters(left),
+            units.from_millimeters(bottom),
+            units.from_millimeters(right),
+            units.from_millimeters(top),
+        )
+    }
+
+    /// Plot origin, the offset from the lower left corner of the printable
+    /// area, in paper units
+    pub fn origin_in_units(&self) -> Vector2 {
+        Vector2::new(
+            self.paper_units.from_millimeters(self.origin_x),
+            self.paper_units.from_millimeters(self.origin_y),
+        )
+    }
+
+    /// Printable area of the sheet in paper units, placed as in paper
+    /// space: the plot origin is at (0, 0).
+    pub fn printable_area(&self) -> BoundingBox2D {
+        let size = self.paper_size_in_units();
+        let margins = self.margins_in_units();
+        let min = -self.origin_in_units();
+        let printable = Vector2::new(
+            (size.x - margins.horizontal_total()).max(0.0),
+            (size.y - margins.vertical_total()).max(0.0),
+        );
+        BoundingBox2D::new(min, min + printable)
+    }
+
+    /// The whole sheet in paper units, placed as [`Self::printable_area`]
+    pub fn paper_area(&self) -> BoundingBox2D {
+        let printable = self.printable_area();
+        let margins = self.margins_in_units();
+        BoundingBox2D::new(
+            printable.min - Vector2::new(margins.left, margins.bottom),
+            printable.min - Vector2::new(margins.left, margins.bottom) + self.paper_size_in_units(),
+        )
+    }
+
     /// Get printable width (paper width minus margins)
     pub fn printable_width(&self) -> f64 {
         self.paper_width - self.margins.horizontal_total()
@@ -857,6 +978,37 @@ mod tests {
         assert!((settings.plot_window.width() - 100.0).abs() < 1e-10);
     }
 
+    #[test]
+    fn test_printable_area_in_units() {
+        // Letter landscape on a device with 6.35 mm margins, 1/4" to the
+        // right and 1/2" up from the printable corner
+        let mut settings = PlotSettings::new("Test").with_units(PlotPaperUnits::Inches);
+        settings.set_paper_with_margins(215.9, 279.4, PaperMargin::new(6.35, 12.7, 6.35, 25.4));
+        settings.set_origin(6.35, 12.7);
+        settings.rotation = PlotRotation::Degrees90;
+
+        assert_eq!(settings.paper_size_in_units(), Vector2::new(11.0, 8.5));
+        let margins = settings.margins_in_units();
+        assert!((margins.left - 1.0).abs() < 1e-12 && (margins.bottom - 0.25).abs() < 1e-12);
+        assert!((margins.right - 0.5).abs() < 1e-12 && (margins.top - 0.25).abs() < 1e-12);
+
+        let area = settings.printable_area();
+        assert!((area.min - Vector2::new(-0.25, -0.5)).length() < 1e-12);
+        assert!((area.width() - 9.5).abs() < 1e-12 && (area.height() - 8.0).abs() < 1e-12);
+        let paper = settings.paper_area();
+        assert!((paper.min - Vector2::new(-1.25, -0.75)).length() < 1e-12);
+        assert!((paper.width() - 11.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_plot_scale() {
+        let mut settings = PlotSettings::new("Test").with_scale(1.0, 50.0);
+        assert!((settings.plot_scale() - 0.02).abs() < 1e-12);
+        settings.set_standard_scale(ScaledType::OneToHundred);
+        assert!((settings.plot_scale() - 0.01).abs() < 1e-12);
+        assert_eq!(PlotPaperUnits::Inches.to_millimeters(2.0), 50.8);
+    }
+
     #[test]
     fn test_center_plot() {
         let mut settings = PlotSettings::new("Test");
@@ -0,0 +1,250 @@
+//! Rendering objects: SUN, the background objects and render settings
+//!
+//! Viewports point at these through their sun (361), background (332) and
+//! visual style (348) handles. Backgrounds and render settings carry much
+//! renderer-specific data; the parts not modelled here are kept as the
+//! code/value pairs of their subclass and written back verbatim.
+
+use crate::types::{Color, Handle, Vector2};
+
+/// SUN object — the sun light of a viewport, view or VIEWPORT entity
+///
+/// Owned by the viewport whose sun handle points at it.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Sun {
+    /// Unique handle
+    pub handle: Handle,
+    /// Owner handle (the viewport)
+    pub owner: Handle,
+    /// Object version (code 90)
+    pub version: i32,
+    /// Whether the sun is on (code 290)
+    pub is_on: bool,
+    /// Light color (codes 63/421)
+    pub color: Color,
+    /// Intensity (code 40)
+    pub intensity: f64,
+    /// Whether the sun casts shadows (code 291)
+    pub has_shadow: bool,
+    /// Julian day of the sun position (code 91)
+    pub julian_day: i32,
+    /// Time of day in seconds past midnight (code 92)
+    pub time: i32,
+    /// Daylight saving time (code 292)
+    pub daylight_savings: bool,
+    /// Shadow type (code 70): 0 = ray traced, 1 = shadow maps
+    pub shadow_type: i16,
+    /// Shadow map size (code 71)
+    pub shadow_map_size: i16,
+    /// Shadow softness (code 280)
+    pub shadow_softness: i16,
+}
+
+impl Sun {
+    /// Create a sun that is on, at 3 pm on the 21st of September 2010
+    pub fn new() -> Self {
+        Sun {
+            handle: Handle::NULL,
+            owner: Handle::NULL,
+            version: 1,
+            is_on: true,
+            color: Color::Index(7),
+            intensity: 1.0,
+            has_shadow: true,
+            julian_day: 2_455_461,
+            time: 15 * 3600,
+            daylight_savings: false,
+            shadow_type: 0,
+            shadow_map_size: 256,
+            shadow_softness: 1,
+        }
+    }
+}
+
+impl Default for Sun {
+    fn default() -> Self { Self::new() }
+}
+
+/// What a [`Background`] shows, with the data of its object type
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BackgroundKind {
+    /// SOLID_BACKGROUND — a single color
+    Solid {
+        /// Fill color
+        color: Color,
+    },
+    /// GRADIENT_BACKGROUND — three colors from top to bottom
+    Gradient {
+        /// Top color
+        top: Color,
+        /// Middle color
+        middle: Color,
+        /// Bottom color
+        bottom: Color,
+        /// Horizon position
+        horizon: f64,
+        /// Height of the middle band
+        height: f64,
+        /// Rotation in radians
+        rotation: f64,
+    },
+    /// IMAGE_BACKGROUND — an image file
+    Image {
+        /// Image file name
+        file_name: String,
+        /// Stretch the image to the viewport
+        fit_to_screen: bool,
+        /// Keep the image's aspect ratio
+        maintain_aspect_ratio: bool,
+        /// Tile the image
+        use_tiling: bool,
+        /// Image offset
+        offset: Vector2,
+        /// Image scale
+        scale: Vector2,
+    },
+    /// SKYLIGHT_BACKGROUND — the sky lit by a sun
+    Sky {
+        /// The SUN object lighting the sky
+        sun: Handle,
+    },
+    /// Another background type, such as GROUND_PLANE_BACKGROUND or
+    /// IBL_BACKGROUND, with the pairs of its subclass
+    Other {
+        /// DXF object type name
+        type_name: String,
+        /// Subclass marker of the data
+        subclass: String,
+        /// Code/value pairs after the subclass marker, in file order
+        data: Vec<(i32, String)>,
+    },
+}
+
+impl BackgroundKind {
+    /// DXF object type name
+    pub fn type_name(&self) -> &str {
+        match self {
+            BackgroundKind::Solid { .. } => "SOLID_BACKGROUND",
+            BackgroundKind::Gradient { .. } => "GRADIENT_BACKGROUND",
+            BackgroundKind::Image { .. } => "IMAGE_BACKGROUND",
+            BackgroundKind::Sky { .. } => "SKYLIGHT_BACKGROUND",
+            BackgroundKind::Other { type_name, .. } => type_name,
+        }
+    }
+
+    /// Subclass marker of the type's own data
+    pub fn subclass(&self) -> &str {
+        match self {
+            BackgroundKind::Solid { .. } => "AcDbSolidBackground",
+            BackgroundKind::Gradient { .. } => "AcDbGradientBackground",
+            BackgroundKind::Image { .. } => "AcDbImageBackground",
+            BackgroundKind::Sky { .. } => "AcDbSkyBackground",
+            BackgroundKind::Other { subclass, .. } => subclass,
+        }
+    }
+}
+
+/// Background object of a viewport (SOLID_BACKGROUND, SKYLIGHT_BACKGROUND, ...)
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Background {
+    /// Unique handle
+    pub handle: Handle,
+    /// Owner handle (the ACAD_BACKGROUND dictionary)
+    pub owner: Handle,
+    /// Object version (code 90)
+    pub version: i32,
+    /// Type and data of the background
+    pub kind: BackgroundKind,
+}
+
+impl Background {
+    /// Create a background of the given kind
+    pub fn new(kind: BackgroundKind) -> Self {
+        Background {
+            handle: Handle::NULL,
+            owner: Handle::NULL,
+            version: 1,
+            kind,
+        }
+    }
+
+    /// Create a solid color background
+    pub fn solid(color: Color) -> Self {
+        Self::new(BackgroundKind::Solid { color })
+    }
+
+    /// Create a sky background lit by the given SUN
+    pub fn sky(sun: Handle) -> Self {
+        Self::new(BackgroundKind::Sky { sun })
+    }
+}
+
+/// Render settings object (RENDERSETTINGS, MENTALRAYRENDERSETTINGS,
+/// RAPIDRTRENDERSETTINGS)
+///
+/// The common AcDbRenderSettings data is modelled; the renderer-specific
+/// subclass is kept as code/value pairs.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RenderSettings {
+    /// Unique handle
+    pub handle: Handle,
+    /// Owner handle
+    pub owner: Handle,
+    /// DXF object type name
+    pub type_name: String,
+    /// Class version (code 90)
+    pub version: i32,
+    /// Preset name (code 1)
+    pub name: String,
+    /// Fog enabled (first code 290)
+    pub fog_enabled: bool,
+    /// Fog applies to the background (second code 290)
+    pub fog_background_enabled: bool,
+    /// Back faces rendered (third code 290)
+    pub back_faces_enabled: bool,
+    /// Environment image enabled (fourth code 290)
+    pub environment_image_enabled: bool,
+    /// Environment image file name (second code 1)
+    pub environment_image_file_name: String,
+    /// Description (third code 1)
+    pub description: String,
+    /// Position in the render presets list (second code 90)
+    pub display_index: i32,
+    /// Whether the preset is one of the predefined ones (fifth code 290)
+    pub predefined: bool,
+    /// Subclass marker of the renderer-specific data, empty if none
+    pub subclass: String,
+    /// Code/value pairs after the subclass marker, in file order
+    pub data: Vec<(i32, String)>,
+}
+
+impl RenderSettings {
+    /// Create plain RENDERSETTINGS with the given preset name
+    pub fn new(name: impl Into<String>) -> Self {
+        RenderSettings {
+            handle: Handle::NULL,
+            owner: Handle::NULL,
+            type_name: "RENDERSETTINGS".to_string(),
+            version: 1,
+            name: name.into(),
+            fog_enabled: false,
+            fog_background_enabled: false,
+            back_faces_enabled: true,
+            environment_image_enabled: false,
+            environment_image_file_name: String::new(),
+            description: String::new(),
+            display_index: 0,
+            predefined: false,
+            subclass: String::new(),
+            data: Vec::new(),
+        }
+    }
+}
+
+impl Default for RenderSettings {
+    fn default() -> Self { Self::new("") }
+}
@@ -13,6 +13,7 @@ use std::collections::HashMap;
 ///
 /// Maps an entity handle to its sort handle for determining draw order.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SortEntsEntry {
     /// Entity handle (the entity being sorted).
     /// DXF code: 331
@@ -69,6 +70,8 @@ impl SortEntsEntry {
 /// table.add_entry(Handle::new(0x101), Handle::new(0xFFFFFF));
 /// ```
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(from = "SortEntitiesTableData"))]
 pub struct SortEntitiesTable {
     /// Object handle.
     pub handle: Handle,
@@ -84,9 +87,36 @@ pub struct SortEntitiesTable {
     entries: Vec<SortEntsEntry>,
 
     /// Lookup map for fast entity handle searches.
+    #[cfg_attr(feature = "serde", serde(skip))]
     entry_map: HashMap<u64, usize>,
 }
 
+/// Serialized form of [`SortEntitiesTable`]; the lookup map is rebuilt
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct SortEntitiesTableData {
+    handle: Handle,
+    owner_handle: Handle,
+    block_owner_handle: Handle,
+    entries: Vec<SortEntsEntry>,
+}
+
+#[cfg(feature = "serde")]
+impl From<SortEntitiesTableData> for SortEntitiesTable {
+    fn from(data: SortEntitiesTableData) -> Self {
+        let mut table = SortEntitiesTable {
+            handle: data.handle,
+            owner_handle: data.owner_handle,
+            block_owner_handle: data.block_owner_handle,
+            ..Self::new()
+        };
+        for entry in data.entries {
+            table.add_entry(entry.entity_handle, entry.sort_handle);
+        }
+        table
+    }
+}
+
 impl SortEntitiesTable {
     /// Object type name.
     pub const OBJECT_NAME: &'static str = "SORTENTSTABLE";
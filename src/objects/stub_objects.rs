@@ -3,7 +3,7 @@
 //! These are minimal representations of DXF objects that ACadSharp supports
 //! but that don't require full rich data models for typical usage.
 
-use crate::types::Handle;
+use crate::types::{Handle, Vector2, Vector3};
 
 /// Trait for minimal stub objects that only need handle + owner fields.
 /// Used by the generic `read_stub_object` reader.
@@ -20,6 +20,7 @@ pub trait StubObject {
 
 /// VisualStyle object — named visual rendering style
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct VisualStyle {
     /// Unique handle
     pub handle: Handle,
@@ -70,6 +71,7 @@ impl Default for VisualStyle {
 
 /// Material object — named material for 3D rendering
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Material {
     /// Unique handle
     pub handle: Handle,
@@ -98,7 +100,14 @@ impl Default for Material {
 }
 
 /// GeoData — geographic location data for a drawing
+///
+/// Places the drawing on the earth: `design_point` in WCS corresponds to
+/// `reference_point` in the coordinate system, drawing units convert to
+/// meters by `horizontal_unit_scale`, and `north_direction` is the WCS
+/// direction of grid north. The geo mesh used for non-linear corrections
+/// is not stored.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GeoData {
     /// Unique handle
     pub handle: Handle,
@@ -106,8 +115,29 @@ pub struct GeoData {
     pub owner: Handle,
     /// Object version (code 90)
     pub version: i32,
+    /// Host block record (code 330 after the subclass marker)
+    pub host_block: Handle,
     /// Coordinate type (code 70): 0 = unknown, 1 = local grid, 2 = projected grid, 3 = geographic
     pub coordinate_type: i16,
+    /// Design point in WCS (codes 10/20/30)
+    pub design_point: Vector3,
+    /// Reference point in the coordinate system (codes 11/21/31);
+    /// longitude, latitude and elevation for geographic data
+    pub reference_point: Vector3,
+    /// Drawing units to meters, horizontally (code 40)
+    pub horizontal_unit_scale: f64,
+    /// Horizontal units (code 91)
+    pub horizontal_units: i32,
+    /// Drawing units to meters, vertically (code 41)
+    pub vertical_unit_scale: f64,
+    /// Vertical units (code 92)
+    pub vertical_units: i32,
+    /// Up direction (codes 210/220/230)
+    pub up_direction: Vector3,
+    /// Grid north in WCS (codes 12/22)
+    pub north_direction: Vector2,
+    /// Coordinate system definition (codes 303/301)
+    pub coordinate_system: String,
 }
 
 impl GeoData {
@@ -117,9 +147,31 @@ impl GeoData {
             handle: Handle::NULL,
             owner: Handle::NULL,
             version: 2,
+            host_block: Handle::NULL,
             coordinate_type: 0,
+            design_point: Vector3::ZERO,
+            reference_point: Vector3::ZERO,
+            horizontal_unit_scale: 1.0,
+            horizontal_units: 6,
+            vertical_unit_scale: 1.0,
+            vertical_units: 6,
+            up_direction: Vector3::UNIT_Z,
+            north_direction: Vector2::new(0.0, 1.0),
+            coordinate_system: String::new(),
         }
     }
+
+    /// Use a coordinate system from the catalog, setting the coordinate
+    /// type and the XML definition
+    pub fn set_coordinate_system(&mut self, coordinate_system: &super::CoordinateSystem) {
+        self.coordinate_type = coordinate_system.coordinate_type();
+        self.coordinate_system = coordinate_system.to_xml();
+    }
+
+    /// Name of the coordinate system in the XML definition, e.g. `UTM84-33N`
+    pub fn coordinate_system_code(&self) -> Option<&str> {
+        super::coordinate_system::coordinate_system_code(&self.coordinate_system)
+    }
 }
 
 impl Default for GeoData {
@@ -128,6 +180,7 @@ impl Default for GeoData {
 
 /// SpatialFilter — clip boundary for external references
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SpatialFilter {
     /// Unique handle
     pub handle: Handle,
@@ -149,8 +202,34 @@ impl Default for SpatialFilter {
     fn default() -> Self { Self::new() }
 }
 
+/// How the frames of raster images and wipeouts show: the `IMAGEFRAME`
+/// and `WIPEOUTFRAME` settings, stored as code 70 of `RASTERVARIABLES`
+/// and `WIPEOUTVARIABLES`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FrameDisplay {
+    /// Frames are neither displayed nor plotted
+    Hidden = 0,
+    /// Frames are displayed and plotted
+    #[default]
+    Displayed = 1,
+    /// Frames are displayed but not plotted
+    DisplayedNotPlotted = 2,
+}
+
+impl From<i16> for FrameDisplay {
+    fn from(value: i16) -> Self {
+        match value {
+            0 => Self::Hidden,
+            2 => Self::DisplayedNotPlotted,
+            _ => Self::Displayed,
+        }
+    }
+}
+
 /// RasterVariables — global raster image settings
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RasterVariables {
     /// Unique handle
     pub handle: Handle,
@@ -158,7 +237,7 @@ pub struct RasterVariables {
     pub owner: Handle,
     /// Class version (code 90)
     pub class_version: i32,
-    /// Image frame display (code 70): 0 = no frame, 1 = display frame
+    /// Image frame display (code 70), see [`FrameDisplay`]
     pub display_image_frame: i16,
     /// Image quality (code 71): 0 = draft, 1 = high
     pub image_quality: i16,
@@ -180,12 +259,20 @@ impl RasterVariables {
     }
 }
 
+impl RasterVariables {
+    /// Frame display of raster images
+    pub fn frame(&self) -> FrameDisplay {
+        FrameDisplay::from(self.display_image_frame)
+    }
+}
+
 impl Default for RasterVariables {
     fn default() -> Self { Self::new() }
 }
 
 /// BookColor (DBCOLOR) — named color definition
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BookColor {
     /// Unique handle
     pub handle: Handle,
@@ -215,6 +302,7 @@ impl Default for BookColor {
 
 /// AcDbPlaceHolder — placeholder object (no data beyond handle)
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PlaceHolder {
     /// Unique handle
     pub handle: Handle,
@@ -237,7 +325,11 @@ impl Default for PlaceHolder {
 }
 
 /// DictionaryWithDefault — dictionary with a default entry handle
+///
+/// Lookups of missing keys fall back to the default entry. AutoCAD uses it
+/// for `ACAD_PLOTSTYLENAME`, where the default is the "Normal" plot style.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DictionaryWithDefault {
     /// Unique handle
     pub handle: Handle,
@@ -265,6 +357,39 @@ impl DictionaryWithDefault {
             hard_owner: false,
         }
     }
+
+    /// Add an entry to the dictionary
+    pub fn add_entry(&mut self, key: impl Into<String>, handle: Handle) {
+        self.entries.push((key.into(), handle));
+    }
+
+    /// Get a handle by key, without falling back to the default
+    pub fn get(&self, key: &str) -> Option<Handle> {
+        self.entries.iter().find(|(k, _)| k == key).map(|(_, h)| *h)
+    }
+
+    /// Handle stored under `key`, or the default entry when the key is
+    /// missing; `None` if neither exists
+    pub fn resolve(&self, key: &str) -> Option<Handle> {
+        self.get(key).or((!self.default_handle.is_null()).then_some(self.default_handle))
+    }
+
+    /// Key of the default entry, if the default is one of the entries
+    pub fn default_key(&self) -> Option<&str> {
+        self.entries.iter().find(|(_, h)| *h == self.default_handle).map(|(k, _)| k.as_str())
+    }
+
+    /// Make the entry stored under `key` the default; returns `false`
+    /// when there is no such entry
+    pub fn set_default(&mut self, key: &str) -> bool {
+        match self.get(key) {
+            Some(handle) => {
+                self.default_handle = handle;
+                true
+            }
+            None => false,
+        }
+    }
 }
 
 impl Default for DictionaryWithDefault {
@@ -273,12 +398,13 @@ impl Default for DictionaryWithDefault {
 
 /// WipeoutVariables — global wipeout display settings
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct WipeoutVariables {
     /// Unique handle
     pub handle: Handle,
     /// Owner handle
     pub owner: Handle,
-    /// Display image frame (code 70): 0 = no, 1 = yes
+    /// Frame display (code 70), see [`FrameDisplay`]
     pub display_frame: i16,
 }
 
@@ -293,6 +419,13 @@ impl WipeoutVariables {
     }
 }
 
+impl WipeoutVariables {
+    /// Frame display of wipeouts
+    pub fn frame(&self) -> FrameDisplay {
+        FrameDisplay::from(self.display_frame)
+    }
+}
+
 impl Default for WipeoutVariables {
     fn default() -> Self { Self::new() }
 }
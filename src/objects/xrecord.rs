@@ -4,6 +4,7 @@ use crate::types::Handle;
 
 /// Dictionary cloning behavior flags
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DictionaryCloningFlags {
     /// Not applicable
     #[default]
@@ -46,6 +47,7 @@ impl DictionaryCloningFlags {
 
 /// Group code value type
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum XRecordValueType {
     /// String value
     String,
@@ -81,18 +83,21 @@ impl XRecordValueType {
             5 | 105 => XRecordValueType::Handle,
             320..=329 | 480..=481 => XRecordValueType::Handle,
             330..=369 => XRecordValueType::ObjectId,
-            // Strings (0-9 but not 5, plus 100-102, 300-309)
-            0..=4 | 6..=9 | 100..=102 | 300..=309 => XRecordValueType::String,
+            390..=399 => XRecordValueType::ObjectId,
+            // Strings (0-9 but not 5, plus 100-102, 300-309, ...)
+            0..=4 | 6..=9 | 100..=102 | 300..=309 | 410..=419 | 430..=439 | 470..=479 | 999..=1009 => {
+                XRecordValueType::String
+            }
             // 3D points
             10..=39 => XRecordValueType::Point3D,
             // Doubles
-            40..=59 | 110..=149 | 210..=239 | 460..=469 => XRecordValueType::Double,
+            40..=59 | 110..=149 | 210..=239 | 460..=469 | 1010..=1059 => XRecordValueType::Double,
             // Bytes
             280..=289 => XRecordValueType::Byte,
             // 16-bit integers
-            60..=79 | 170..=179 | 270..=279 => XRecordValueType::Int16,
+            60..=79 | 170..=179 | 270..=279 | 370..=389 | 400..=409 | 1060..=1070 => XRecordValueType::Int16,
             // 32-bit integers
-            90..=99 | 420..=459 => XRecordValueType::Int32,
+            90..=99 | 420..=429 | 440..=459 | 1071 => XRecordValueType::Int32,
             // 64-bit integers
             160..=169 => XRecordValueType::Int64,
             // Booleans
@@ -112,6 +117,7 @@ impl XRecordValueType {
 
 /// XRecord entry value
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum XRecordValue {
     /// String value
     String(String),
@@ -189,6 +195,7 @@ impl XRecordValue {
 
 /// XRecord entry with group code and value
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct XRecordEntry {
     /// DXF group code (1-369, except 5 and 105)
     pub code: i32,
@@ -263,6 +270,7 @@ impl XRecordEntry {
 /// xrecord.add_int32(90, 42);
 /// ```
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct XRecord {
     /// Unique handle
     pub handle: Handle,
@@ -274,6 +282,10 @@ pub struct XRecord {
     pub cloning_flags: DictionaryCloningFlags,
     /// Collection of data entries
     pub entries: Vec<XRecordEntry>,
+    /// Reactor handles ({ACAD_REACTORS})
+    pub reactors: Vec<Handle>,
+    /// Extended dictionary handle ({ACAD_XDICTIONARY})
+    pub xdictionary_handle: Option<Handle>,
 }
 
 impl XRecord {
@@ -288,6 +300,8 @@ impl XRecord {
             name: String::new(),
             cloning_flags: DictionaryCloningFlags::NotApplicable,
             entries: Vec::new(),
+            reactors: Vec::new(),
+            xdictionary_handle: None,
         }
     }
 
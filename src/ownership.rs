@@ -0,0 +1,285 @@
+//! Owners, reactors and extension dictionaries of objects and entities
+//!
+//! Every object and entity names its owner (group 330), may list reactors
+//! ({ACAD_REACTORS}) that are notified of its changes, and may own an
+//! extension dictionary ({ACAD_XDICTIONARY}). Dictionaries own their
+//! entries, hard or soft after their hard owner flag, and block records
+//! own their entities. [`CadDocument::ownership_graph`] collects these
+//! links in both directions, so a tool can find what an object belongs to
+//! and what goes with it when it is copied or deleted.
+//!
+//! ```rust,ignore
+//! let graph = doc.ownership_graph();
+//! for entry in graph.owned(dictionary.handle) {
+//!     println!("{:?} {:?} {:#X}", entry.key, entry.kind, entry.handle.value());
+//! }
+//! let path = graph.owner_chain(xrecord.handle);
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::document::CadDocument;
+use crate::entities::EntityType;
+use crate::objects::ObjectType;
+use crate::types::Handle;
+
+/// Strength of the link between an owner and what it owns
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OwnershipKind {
+    /// The owned object is copied and deleted with its owner (group 360)
+    Hard,
+    /// The owned object only lists the owner (group 350)
+    Soft,
+}
+
+/// What a node of an [`OwnershipGraph`] is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OwnershipNodeKind {
+    /// A block record, owning the entities of its block
+    BlockRecord,
+    /// A graphical entity
+    Entity,
+    /// A non-graphical object of the OBJECTS section
+    Object,
+}
+
+/// One link from an owner to an object it owns
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OwnedEntry {
+    /// Handle of the owned object or entity
+    pub handle: Handle,
+    /// Strength of the link
+    pub kind: OwnershipKind,
+    /// Key of the entry in a dictionary, `None` for extension dictionaries
+    /// and entities
+    pub key: Option<String>,
+}
+
+/// Links of one object, entity or block record
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OwnershipNode {
+    /// What the node is
+    pub kind: OwnershipNodeKind,
+    /// Owner handle (group 330), null when unknown
+    pub owner: Handle,
+    /// Reactor handles ({ACAD_REACTORS})
+    pub reactors: Vec<Handle>,
+    /// Extended dictionary handle ({ACAD_XDICTIONARY})
+    pub xdictionary: Option<Handle>,
+    /// Objects and entities owned, in entry order
+    pub owned: Vec<OwnedEntry>,
+}
+
+impl OwnershipNode {
+    fn new(kind: OwnershipNodeKind, owner: Handle, reactors: &[Handle], xdictionary: Option<Handle>) -> Self {
+        let xdictionary = xdictionary.filter(|h| !h.is_null());
+        Self {
+            kind,
+            owner,
+            reactors: reactors.to_vec(),
+            xdictionary,
+            owned: xdictionary
+                .map(|handle| OwnedEntry { handle, kind: OwnershipKind::Hard, key: None })
+                .into_iter()
+                .collect(),
+        }
+    }
+}
+
+/// Result of [`CadDocument::ownership_graph`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OwnershipGraph {
+    /// Every object, entity and block record with a handle, by handle
+    pub nodes: BTreeMap<Handle, OwnershipNode>,
+}
+
+impl OwnershipGraph {
+    /// Links of the object with `handle`
+    pub fn node(&self, handle: Handle) -> Option<&OwnershipNode> {
+        self.nodes.get(&handle)
+    }
+
+    /// Objects and entities owned by `handle`
+    pub fn owned(&self, handle: Handle) -> &[OwnedEntry] {
+        self.nodes.get(&handle).map_or(&[], |node| &node.owned)
+    }
+
+    /// Objects listing `handle` among their reactors
+    pub fn reacting_to(&self, handle: Handle) -> Vec<Handle> {
+        self.nodes
+            .iter()
+            .filter(|(_, node)| node.reactors.contains(&handle))
+            .map(|(&h, _)| h)
+            .collect()
+    }
+
+    /// Owners of `handle`, nearest first, up to the first owner that is
+    /// not in the graph or closes a cycle
+    pub fn owner_chain(&self, handle: Handle) -> Vec<Handle> {
+        let mut chain = Vec::new();
+        let mut seen = BTreeSet::from([handle]);
+        let mut current = handle;
+        while let Some(node) = self.nodes.get(&current) {
+            if node.owner.is_null() || !seen.insert(node.owner) {
+                break;
+            }
+            chain.push(node.owner);
+            current = node.owner;
+        }
+        chain
+    }
+
+    /// The owner of every node: its own owner handle when an owner lists
+    /// it or nothing does, otherwise the first object listing it, hard
+    /// owners first. Writers use it for objects whose owner handle was never
+    /// set or went stale when the object was moved.
+    pub fn resolved_owners(&self) -> BTreeMap<Handle, Handle> {
+        let mut listed_by: BTreeMap<Handle, Vec<(Handle, OwnershipKind)>> = BTreeMap::new();
+        for (&owner, node) in &self.nodes {
+            for entry in &node.owned {
+                listed_by.entry(entry.handle).or_default().push((owner, entry.kind));
+            }
+        }
+        self.nodes
+            .iter()
+            .map(|(&handle, node)| {
+                let listing = listed_by.get(&handle).map_or(&[][..], Vec::as_slice);
+                let owner = if listing.is_empty() || listing.iter().any(|&(owner, _)| owner == node.owner) {
+                    node.owner
+                } else {
+                    listing
+                        .iter()
+                        .find(|&&(_, kind)| kind == OwnershipKind::Hard)
+                        .unwrap_or(&listing[0])
+                        .0
+                };
+                (handle, owner)
+            })
+            .collect()
+    }
+
+    /// Owned entries whose object names a different owner, as
+    /// (owner, owned) pairs; the owned object has been moved or its owner
+    /// handle was lost
+    pub fn mismatched_owners(&self) -> Vec<(Handle, Handle)> {
+        let mut mismatched = Vec::new();
+        for (&owner, node) in &self.nodes {
+            for entry in &node.owned {
+                if self.nodes.get(&entry.handle).is_some_and(|owned| owned.owner != owner) {
+                    mismatched.push((owner, entry.handle));
+                }
+            }
+        }
+        mismatched
+    }
+}
+
+impl CadDocument {
+    /// Owner, reactor and extension dictionary links of every object,
+    /// entity and block record
+    pub fn ownership_graph(&self) -> OwnershipGraph {
+        let mut graph = OwnershipGraph::default();
+
+        let add_entity = |graph: &mut OwnershipGraph, entity: &EntityType| {
+            let common = entity.common();
+            if !common.handle.is_null() {
+                let node = OwnershipNode::new(
+                    OwnershipNodeKind::Entity,
+                    common.owner_handle,
+                    &common.reactors,
+                    common.xdictionary_handle,
+                );
+                graph.nodes.insert(common.handle, node);
+            }
+        };
+        for entity in self.entities() {
+            add_entity(&mut graph, entity);
+        }
+
+        for record in self.block_records.iter().filter(|r| !r.handle.is_null()) {
+            let mut node = OwnershipNode::new(OwnershipNodeKind::BlockRecord, Handle::NULL, &[], record.xdictionary_handle);
+            for entity in self.record_entities(record) {
+                add_entity(&mut graph, entity);
+                let handle = entity.common().handle;
+                if let Some(entity_node) = graph.nodes.get_mut(&handle) {
+                    // Entities added without an owner go to model space
+                    if entity_node.owner.is_null() {
+                        entity_node.owner = record.handle;
+                    }
+                    node.owned.push(OwnedEntry { handle, kind: OwnershipKind::Hard, key: None });
+                }
+            }
+            graph.nodes.insert(record.handle, node);
+        }
+
+        for (&handle, object) in &self.objects {
+            let mut node = OwnershipNode::new(
+                OwnershipNodeKind::Object,
+                object.owner().unwrap_or(Handle::NULL),
+                object.reactors(),
+                object.xdictionary_handle(),
+            );
+            let (entries, hard_owner) = match object {
+                ObjectType::Dictionary(dict) => (&dict.entries, dict.hard_owner),
+                ObjectType::DictionaryWithDefault(dict) => (&dict.entries, dict.hard_owner),
+                _ => (&Vec::new(), false),
+            };
+            let kind = if hard_owner { OwnershipKind::Hard } else { OwnershipKind::Soft };
+            node.owned.extend(entries.iter().map(|(key, handle)| OwnedEntry {
+                handle: *handle,
+                kind,
+                key: Some(key.clone()),
+            }));
+            graph.nodes.insert(handle, node);
+        }
+
+        graph
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::Line;
+    use crate::objects::{Dictionary, XRecord};
+
+    #[test]
+    fn test_ownership_graph() {
+        let mut doc = CadDocument::new();
+        let line = doc.add_entity(EntityType::Line(Line::new())).unwrap();
+
+        let mut xdictionary = Dictionary::new();
+        xdictionary.handle = doc.allocate_handle();
+        xdictionary.owner = line;
+        xdictionary.hard_owner = true;
+        xdictionary.reactors.push(line);
+        let mut xrecord = XRecord::named("DATA");
+        xrecord.handle = doc.allocate_handle();
+        xrecord.owner = xdictionary.handle;
+        xrecord.reactors.push(xdictionary.handle);
+        xdictionary.add_entry("DATA", xrecord.handle);
+        let (dictionary, record) = (xdictionary.handle, xrecord.handle);
+        doc.objects.insert(dictionary, ObjectType::Dictionary(xdictionary));
+        doc.objects.insert(record, ObjectType::XRecord(xrecord));
+        doc.get_entity_mut(line).unwrap().common_mut().xdictionary_handle = Some(dictionary);
+
+        let graph = doc.ownership_graph();
+        let model = doc.block_records.get("*Model_Space").unwrap().handle;
+        assert_eq!(graph.node(line).unwrap().kind, OwnershipNodeKind::Entity);
+        assert_eq!(graph.owned(line), [OwnedEntry { handle: dictionary, kind: OwnershipKind::Hard, key: None }]);
+        assert_eq!(graph.owned(dictionary)[0].key.as_deref(), Some("DATA"));
+        assert_eq!(graph.owned(dictionary)[0].kind, OwnershipKind::Hard);
+        assert_eq!(graph.owner_chain(record), vec![dictionary, line, model]);
+        assert_eq!(graph.reacting_to(dictionary), vec![record]);
+        assert!(graph.owned(model).iter().any(|entry| entry.handle == line));
+        assert!(graph.mismatched_owners().is_empty());
+
+        if let Some(ObjectType::XRecord(xrecord)) = doc.objects.get_mut(&record) {
+            xrecord.owner = Handle::NULL;
+        }
+        let graph = doc.ownership_graph();
+        assert_eq!(graph.mismatched_owners(), vec![(dictionary, record)]);
+        assert_eq!(graph.resolved_owners()[&record], dictionary);
+        assert_eq!(graph.resolved_owners()[&dictionary], line);
+    }
+}
@@ -1,9 +1,12 @@
 //! DWG preview/thumbnail image data.
 //!
 //! Stores the thumbnail information to generate the preview for a CAD document.
+//! It is read from and written to the DWG `AcDb:Preview` section and the DXF
+//! `THUMBNAILIMAGE` section.
 
 /// Type of media stored in the preview image.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum PreviewType {
     /// Unknown or unsupported preview format.
@@ -32,6 +35,7 @@ impl PreviewType {
 ///
 /// Corresponds to the `AcDb:Preview` section.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DwgPreview {
     /// Code that specifies the type of media stored in the preview.
     pub code: PreviewType,
@@ -63,6 +67,16 @@ impl DwgPreview {
         }
     }
 
+    /// Create a preview from image bytes, telling PNG from BMP data by the
+    /// PNG signature.
+    ///
+    /// BMP data is a device-independent bitmap, without the 14-byte file
+    /// header, as stored in DWG and DXF files.
+    pub fn from_image(raw_image: Vec<u8>) -> Self {
+        let code = if raw_image.starts_with(b"\x89PNG") { PreviewType::Png } else { PreviewType::Bmp };
+        Self::new(code, Vec::new(), raw_image)
+    }
+
     /// Returns `true` if the preview is empty (no image data).
     pub fn is_empty(&self) -> bool {
         self.raw_image.is_empty()
@@ -0,0 +1,331 @@
+//! Table entries and blocks named by entities
+//!
+//! Entities refer to their layer, text style, dimension style and block by
+//! name, and layers to their line type. [`CadDocument::reference_index`]
+//! collects every such reference, for tools that must not delete or
+//! rename an entry still in use; [`CadDocument::block_usages`] and
+//! [`CadDocument::blocks_referencing`] answer single questions from it.
+//!
+//! A DXF file naming an entry its tables lack reads without complaint, and
+//! the dangling name only shows when AutoCAD rejects the output.
+//! [`CadDocument::missing_references`] lists such names;
+//! [`CadDocument::add_missing_references`] creates a placeholder for each,
+//! which the DXF reader does in failsafe mode.
+//!
+//! ```rust,ignore
+//! let doors = doc.block_usages("DOOR");
+//! if !doors.is_empty() {
+//!     eprintln!("DOOR is inserted {} times", doors.len());
+//! }
+//! for missing in doc.missing_references() {
+//!     eprintln!("{:?} '{}' used by {:#X}", missing.kind, missing.name, missing.referenced_by.value());
+//! }
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::document::CadDocument;
+use crate::entities::EntityType;
+use crate::notification::{Notification, NotificationCode, NotificationType};
+use crate::tables::{BlockRecord, DimStyle, Layer, LineType, TextStyle};
+use crate::types::Handle;
+
+/// Kind of table entry a reference names
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ReferenceKind {
+    /// A layer, named by an entity
+    Layer,
+    /// A line type, named by a layer
+    LineType,
+    /// A text style, named by a text, mtext or attribute
+    TextStyle,
+    /// A dimension style, named by a dimension, leader or tolerance
+    DimensionStyle,
+    /// A block, named by an insert or a dimension
+    Block,
+}
+
+/// One reference to a table entry or block
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reference {
+    /// Name as referenced
+    pub name: String,
+    /// Handle of the entity, or of the layer, making the reference
+    pub referenced_by: Handle,
+    /// Block record holding the referencing entity; `None` for layers
+    pub block: Option<String>,
+}
+
+/// Every reference of a document to its table entries and blocks, built
+/// by [`CadDocument::reference_index`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReferenceIndex {
+    /// References by kind and upper case name, each list in handle order
+    references: BTreeMap<(ReferenceKind, String), Vec<Reference>>,
+}
+
+impl ReferenceIndex {
+    /// References to the entry `name` of `kind`; names match without
+    /// regard to case, as table lookups do
+    pub fn usages(&self, kind: ReferenceKind, name: &str) -> &[Reference] {
+        self.references
+            .get(&(kind, name.to_uppercase()))
+            .map_or(&[], |references| references.as_slice())
+    }
+
+    /// Whether anything refers to the entry `name` of `kind`
+    pub fn is_used(&self, kind: ReferenceKind, name: &str) -> bool {
+        !self.usages(kind, name).is_empty()
+    }
+
+    /// Names of the block records with entities referring to the entry
+    /// `name` of `kind`
+    pub fn blocks_referencing(&self, kind: ReferenceKind, name: &str) -> BTreeSet<&str> {
+        self.usages(kind, name)
+            .iter()
+            .filter_map(|reference| reference.block.as_deref())
+            .collect()
+    }
+
+    /// Every name of `kind` referenced, spelled as by its lowest handle
+    pub fn names(&self, kind: ReferenceKind) -> impl Iterator<Item = &str> {
+        self.references
+            .iter()
+            .filter(move |((k, _), _)| *k == kind)
+            .map(|(_, references)| references[0].name.as_str())
+    }
+
+    fn add(&mut self, kind: ReferenceKind, name: &str, referenced_by: Handle, block: Option<&str>) {
+        if name.is_empty() {
+            return;
+        }
+        let references = self.references.entry((kind, name.to_uppercase())).or_default();
+        if references.iter().any(|r| r.referenced_by == referenced_by) {
+            return;
+        }
+        references.push(Reference {
+            name: name.to_string(),
+            referenced_by,
+            block: block.map(str::to_string),
+        });
+    }
+}
+
+/// A name that no table entry or block of the document has
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MissingReference {
+    /// Kind of the missing entry
+    pub kind: ReferenceKind,
+    /// Name as referenced
+    pub name: String,
+    /// Handle of the layer or entity with the lowest handle naming it
+    pub referenced_by: Handle,
+}
+
+impl CadDocument {
+    /// Collect the references of every layer and entity to table entries
+    /// and blocks.
+    ///
+    /// Entities of every block record are included, layouts too, along
+    /// with the attributes of inserts.  Empty names are not references.
+    pub fn reference_index(&self) -> ReferenceIndex {
+        let mut index = ReferenceIndex::default();
+        for layer in self.layers.iter() {
+            index.add(ReferenceKind::LineType, &layer.line_type, layer.handle, None);
+        }
+        for record in self.block_records.iter() {
+            for entity in self.record_entities(record) {
+                let handle = entity.common().handle;
+                for (kind, name) in entity_references(entity) {
+                    index.add(kind, name, handle, Some(&record.name));
+                }
+            }
+        }
+        for references in index.references.values_mut() {
+            references.sort_by_key(|reference| reference.referenced_by);
+        }
+        index
+    }
+
+    /// Handles of the inserts and dimensions referring to the block
+    /// `name`, in handle order
+    pub fn block_usages(&self, name: &str) -> Vec<Handle> {
+        let index = self.reference_index();
+        index.usages(ReferenceKind::Block, name).iter().map(|r| r.referenced_by).collect()
+    }
+
+    /// Names of the block records, layouts included, with entities
+    /// referring to the entry `name` of `kind`: the blocks drawing on a
+    /// layer, say, or inserting a block
+    pub fn blocks_referencing(&self, kind: ReferenceKind, name: &str) -> Vec<String> {
+        let index = self.reference_index();
+        index.blocks_referencing(kind, name).into_iter().map(str::to_string).collect()
+    }
+
+    /// Layers drawn with the line type `name`
+    pub fn layers_using_linetype(&self, name: &str) -> Vec<&Layer> {
+        self.layers.iter().filter(|layer| layer.line_type.eq_ignore_ascii_case(name)).collect()
+    }
+
+    /// References to layers, line types, text styles, dimension styles and
+    /// blocks the document does not have.
+    ///
+    /// Each missing name is listed once, ordered by kind and name.
+    pub fn missing_references(&self) -> Vec<MissingReference> {
+        let index = self.reference_index();
+        index
+            .references
+            .iter()
+            .filter(|((kind, name), _)| !self.has_reference(*kind, name))
+            .map(|((kind, _), references)| MissingReference {
+                kind: *kind,
+                name: references[0].name.clone(),
+                referenced_by: references[0].referenced_by,
+            })
+            .collect()
+    }
+
+    /// Whether the document has the entry `name` of `kind`
+    fn has_reference(&self, kind: ReferenceKind, name: &str) -> bool {
+        match kind {
+            ReferenceKind::Layer => self.layers.contains(name),
+            ReferenceKind::LineType => self.line_types.contains(name),
+            ReferenceKind::TextStyle => self.text_styles.contains(name),
+            ReferenceKind::DimensionStyle => self.dim_styles.contains(name),
+            ReferenceKind::Block => self.block_records.contains(name),
+        }
+    }
+
+    /// Create an entry with default settings, or an empty block, for every
+    /// missing reference, and report each with a
+    /// [`MissingReference`](NotificationCode::MissingReference) warning.
+    ///
+    /// Returns the references that were missing.
+    pub fn add_missing_references(&mut self) -> Vec<MissingReference> {
+        let missing = self.missing_references();
+        for reference in &missing {
+            let name = reference.name.clone();
+            let handle = self.allocate_handle();
+            let added = match reference.kind {
+                ReferenceKind::Layer => self.layers.add(Layer { handle, ..Layer::new(name) }),
+                ReferenceKind::LineType => self.line_types.add(LineType { handle, ..LineType::new(name) }),
+                ReferenceKind::TextStyle => self.text_styles.add(TextStyle { handle, ..TextStyle::new(name) }),
+                ReferenceKind::DimensionStyle => self.dim_styles.add(DimStyle { handle, ..DimStyle::new(name) }),
+                ReferenceKind::Block => {
+                    let mut record = BlockRecord::new(name);
+                    record.handle = handle;
+                    record.block_entity_handle = self.allocate_handle();
+                    record.block_end_handle = self.allocate_handle();
+                    self.block_records.add(record)
+                }
+            };
+            added.ok();
+            self.notifications.push(
+                Notification::new(
+                    NotificationType::Warning,
+                    format!(
+                        "{:?} '{}' does not exist; created a placeholder",
+                        reference.kind, reference.name
+                    ),
+                )
+                .with_code(NotificationCode::MissingReference)
+                .with_handle(reference.referenced_by),
+            );
+        }
+        missing
+    }
+}
+
+/// Names of the table entries and blocks `entity` refers to, with those
+/// of its attributes
+fn entity_references(entity: &EntityType) -> Vec<(ReferenceKind, &str)> {
+    let mut references = vec![(ReferenceKind::Layer, entity.common().layer.as_str())];
+    match entity {
+        EntityType::Text(text) => references.push((ReferenceKind::TextStyle, &text.style)),
+        EntityType::MText(mtext) => references.push((ReferenceKind::TextStyle, &mtext.style)),
+        EntityType::AttributeDefinition(attdef) => references.push((ReferenceKind::TextStyle, &attdef.text_style)),
+        EntityType::AttributeEntity(attrib) => references.push((ReferenceKind::TextStyle, &attrib.text_style)),
+        EntityType::Insert(insert) => {
+            references.push((ReferenceKind::Block, &insert.block_name));
+            for attrib in &insert.attributes {
+                references.push((ReferenceKind::Layer, &attrib.common.layer));
+                references.push((ReferenceKind::TextStyle, &attrib.text_style));
+            }
+        }
+        EntityType::Dimension(dimension) => {
+            references.push((ReferenceKind::DimensionStyle, &dimension.base().style_name));
+            references.push((ReferenceKind::Block, &dimension.base().block_name));
+        }
+        EntityType::Leader(leader) => references.push((ReferenceKind::DimensionStyle, &leader.dimension_style)),
+        EntityType::Tolerance(tolerance) => {
+            references.push((ReferenceKind::DimensionStyle, &tolerance.dimension_style_name));
+        }
+        _ => {}
+    }
+    references
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::{Insert, Line, Text};
+    use crate::types::Vector3;
+
+    #[test]
+    fn test_missing_references() {
+        let mut doc = CadDocument::new();
+        let mut line = Line::from_coords(0.0, 0.0, 0.0, 1.0, 0.0, 0.0);
+        line.common.layer = "Walls".to_string();
+        doc.add_entity(EntityType::Line(line)).unwrap();
+        let mut text = Text::new();
+        text.common.layer = "WALLS".to_string();
+        text.style = "Notes".to_string();
+        doc.add_entity(EntityType::Text(text)).unwrap();
+        doc.add_entity(EntityType::Insert(Insert::new("Door", Vector3::ZERO))).unwrap();
+
+        let missing = doc.missing_references();
+        let names: Vec<(ReferenceKind, &str)> = missing.iter().map(|m| (m.kind, m.name.as_str())).collect();
+        assert_eq!(
+            names,
+            vec![
+                (ReferenceKind::Layer, "Walls"),
+                (ReferenceKind::TextStyle, "Notes"),
+                (ReferenceKind::Block, "Door"),
+            ]
+        );
+
+        assert_eq!(doc.add_missing_references().len(), 3);
+        assert!(doc.missing_references().is_empty());
+        assert!(doc.block_records.get("Door").unwrap().entities.is_empty());
+        assert_eq!(doc.notifications.len(), 3);
+    }
+
+    #[test]
+    fn test_reference_index() {
+        let mut doc = CadDocument::new();
+        let mut door = BlockRecord::new("DOOR");
+        door.handle = doc.allocate_handle();
+        let mut leaf = Line::from_coords(0.0, 0.0, 0.0, 1.0, 0.0, 0.0);
+        leaf.common.layer = "Doors".to_string();
+        leaf.common.handle = doc.allocate_handle();
+        door.entities.push(EntityType::Line(leaf));
+        doc.block_records.add(door).unwrap();
+        doc.layers.add(Layer::new("Doors")).unwrap();
+
+        let first = doc.add_entity(EntityType::Insert(Insert::new("Door", Vector3::ZERO))).unwrap();
+        let second = doc.add_entity(EntityType::Insert(Insert::new("DOOR", Vector3::new(5.0, 0.0, 0.0)))).unwrap();
+        let mut wall = Line::from_coords(0.0, 0.0, 0.0, 9.0, 0.0, 0.0);
+        wall.common.layer = "Doors".to_string();
+        doc.add_entity(EntityType::Line(wall)).unwrap();
+
+        assert_eq!(doc.block_usages("door"), vec![first, second]);
+        assert_eq!(doc.blocks_referencing(ReferenceKind::Layer, "DOORS"), vec!["*Model_Space", "DOOR"]);
+        assert_eq!(doc.blocks_referencing(ReferenceKind::Block, "DOOR"), vec!["*Model_Space"]);
+        assert_eq!(doc.layers_using_linetype("continuous").len(), 2);
+
+        let index = doc.reference_index();
+        assert!(index.is_used(ReferenceKind::Layer, "0"));
+        assert!(!index.is_used(ReferenceKind::TextStyle, "Standard"));
+        assert_eq!(index.names(ReferenceKind::Block).collect::<Vec<_>>(), vec!["Door"]);
+    }
+}
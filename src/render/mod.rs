@@ -0,0 +1,14 @@
+//! 2D rendering support.
+//!
+//! [`Scene`] flattens a model space, layout or block into colored 2D
+//! primitives: curves are tessellated, blocks expanded, display properties
+//! resolved through layers and inserts, and layout viewports projected and
+//! clipped. Plotters and rasterizers draw from a scene instead of walking
+//! the entity tree themselves.
+
+pub mod raster;
+pub mod scene;
+pub mod tessellate;
+
+pub use raster::{rasterize, ImageBuffer, RenderOptions};
+pub use scene::{find_layout, layout_block_record, Primitive, PrimitiveKind, Scene, SceneOptions};
@@ -0,0 +1,533 @@
+//! Software rasterization
+//!
+//! [`rasterize`] draws a model space or layout [`Scene`] into an RGB
+//! [`ImageBuffer`], which can be saved as PNG or BMP. Lines are drawn
+//! without anti-aliasing so output is deterministic across platforms, which
+//! keeps images usable for visual regression tests. Text is drawn as a
+//! filled box over its approximate extent.
+
+use std::collections::HashSet;
+use std::io::Write;
+use std::path::Path;
+
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+
+use super::scene::{find_layout, Primitive, PrimitiveKind, Scene, SceneOptions};
+use crate::document::CadDocument;
+use crate::error::{DxfError, Result};
+use crate::io::dwg::crc::crc32;
+use crate::types::Vector2;
+
+/// Average Helvetica-like glyph advance as a fraction of the cap height
+const GLYPH_ADVANCE: f64 = 0.85;
+
+/// Options for [`rasterize`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderOptions {
+    /// Image width in pixels
+    pub width: u32,
+    /// Image height in pixels
+    pub height: u32,
+    /// Free border around the drawing, in pixels
+    pub margin: u32,
+    /// Background color
+    pub background: (u8, u8, u8),
+    /// Layout to render by name; `None` renders model space
+    pub layout: Option<String>,
+    /// Layers to leave out, by name (case-insensitive)
+    pub hidden_layers: HashSet<String>,
+    /// Pixels per millimetre of lineweight; 0 draws every line one pixel wide
+    pub line_weight_scale: f64,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            width: 800,
+            height: 600,
+            margin: 8,
+            background: (255, 255, 255),
+            layout: None,
+            hidden_layers: HashSet::new(),
+            line_weight_scale: 0.0,
+        }
+    }
+}
+
+impl RenderOptions {
+    /// Create options for an image of the given size
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            ..Default::default()
+        }
+    }
+
+    /// Hide a layer
+    pub fn hide_layer(mut self, name: impl AsRef<str>) -> Self {
+        self.hidden_layers.insert(name.as_ref().to_uppercase());
+        self
+    }
+}
+
+/// 8-bit RGB image, rows top to bottom
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageBuffer {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+}
+
+impl ImageBuffer {
+    /// Create an image filled with one color
+    pub fn new(width: u32, height: u32, fill: (u8, u8, u8)) -> Self {
+        let mut pixels = Vec::with_capacity(width as usize * height as usize * 3);
+        for _ in 0..width as usize * height as usize {
+            pixels.extend_from_slice(&[fill.0, fill.1, fill.2]);
+        }
+        Self { width, height, pixels }
+    }
+
+    /// Width in pixels
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Height in pixels
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Raw RGB bytes, row by row from the top
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    /// Color of a pixel; (0, 0) is the top-left corner
+    pub fn pixel(&self, x: u32, y: u32) -> Option<(u8, u8, u8)> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        let i = (y as usize * self.width as usize + x as usize) * 3;
+        Some((self.pixels[i], self.pixels[i + 1], self.pixels[i + 2]))
+    }
+
+    /// Set a pixel; out-of-range coordinates are ignored
+    pub fn set_pixel(&mut self, x: u32, y: u32, color: (u8, u8, u8)) {
+        if x < self.width && y < self.height {
+            let i = (y as usize * self.width as usize + x as usize) * 3;
+            self.pixels[i..i + 3].copy_from_slice(&[color.0, color.1, color.2]);
+        }
+    }
+
+    /// Fraction of pixels that differ from `other`, or `None` if the sizes differ
+    pub fn difference(&self, other: &ImageBuffer) -> Option<f64> {
+        if self.width != other.width || self.height != other.height {
+            return None;
+        }
+        let total = self.width as usize * self.height as usize;
+        if total == 0 {
+            return Some(0.0);
+        }
+        let differing = self
+            .pixels
+            .chunks_exact(3)
+            .zip(other.pixels.chunks_exact(3))
+            .filter(|(a, b)| a != b)
+            .count();
+        Some(differing as f64 / total as f64)
+    }
+
+    /// Encode as PNG
+    pub fn to_png(&self) -> Result<Vec<u8>> {
+        let mut raw = Vec::with_capacity(self.pixels.len() + self.height as usize);
+        for row in self.pixels.chunks_exact((self.width as usize * 3).max(1)) {
+            // Filter type 0: none
+            raw.push(0);
+            raw.extend_from_slice(row);
+        }
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&raw)?;
+        let data = encoder.finish()?;
+
+        let mut out = b"\x89PNG\r\n\x1a\n".to_vec();
+        let mut header = Vec::with_capacity(13);
+        header.extend_from_slice(&self.width.to_be_bytes());
+        header.extend_from_slice(&self.height.to_be_bytes());
+        // 8-bit truecolor, deflate, no filter method, no interlace
+        header.extend_from_slice(&[8, 2, 0, 0, 0]);
+        png_chunk(&mut out, b"IHDR", &header);
+        png_chunk(&mut out, b"IDAT", &data);
+        png_chunk(&mut out, b"IEND", &[]);
+        Ok(out)
+    }
+
+    /// Encode as a 24-bit BMP
+    pub fn to_bmp(&self) -> Vec<u8> {
+        let row_size = (self.width as usize * 3).div_ceil(4) * 4;
+        let image_size = row_size * self.height as usize;
+        let file_size = 54 + image_size;
+
+        let mut out = Vec::with_capacity(file_size);
+        out.extend_from_slice(b"BM");
+        out.extend_from_slice(&(file_size as u32).to_le_bytes());
+        out.extend_from_slice(&[0; 4]);
+        out.extend_from_slice(&54u32.to_le_bytes());
+        // BITMAPINFOHEADER
+        out.extend_from_slice(&40u32.to_le_bytes());
+        out.extend_from_slice(&(self.width as i32).to_le_bytes());
+        out.extend_from_slice(&(self.height as i32).to_le_bytes());
+        out.extend_from_slice(&1u16.to_le_bytes());
+        out.extend_from_slice(&24u16.to_le_bytes());
+        out.extend_from_slice(&0u32.to_le_bytes());
+        out.extend_from_slice(&(image_size as u32).to_le_bytes());
+        // 72 DPI
+        out.extend_from_slice(&2835i32.to_le_bytes());
+        out.extend_from_slice(&2835i32.to_le_bytes());
+        out.extend_from_slice(&[0; 8]);
+
+        // Rows bottom to top, BGR
+        for row in self.pixels.chunks_exact((self.width as usize * 3).max(1)).rev() {
+            for rgb in row.chunks_exact(3) {
+                out.extend_from_slice(&[rgb[2], rgb[1], rgb[0]]);
+            }
+            out.resize(out.len() + row_size - row.len(), 0);
+        }
+        out
+    }
+
+    /// Write a PNG file
+    pub fn write_png<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        std::fs::write(path, self.to_png()?)?;
+        Ok(())
+    }
+
+    /// Write a BMP file
+    pub fn write_bmp<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        std::fs::write(path, self.to_bmp())?;
+        Ok(())
+    }
+}
+
+fn png_chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = out.len();
+    out.extend_from_slice(kind);
+    out.extend_from_slice(data);
+    let crc = crc32(0, &out[start..]);
+    out.extend_from_slice(&crc.to_be_bytes());
+}
+
+/// Render model space, or the layout named in `options`, to an image
+///
+/// The drawing is scaled to fit the image inside the margin and centered.
+pub fn rasterize(doc: &CadDocument, options: &RenderOptions) -> Result<ImageBuffer> {
+    let mut image = ImageBuffer::new(options.width, options.height, options.background);
+    let build = |tolerance: f64| {
+        let scene_options = SceneOptions {
+            tolerance,
+            plot_only: false,
+            hidden_layers: options.hidden_layers.iter().map(|l| l.to_uppercase()).collect(),
+        };
+        match &options.layout {
+            Some(name) => find_layout(doc, name)
+                .map(|layout| Scene::layout(doc, layout, &scene_options))
+                .ok_or_else(|| DxfError::Custom(format!("Layout not found: {name}"))),
+            None => Ok(Scene::model_space(doc, &scene_options)),
+        }
+    };
+
+    let scene = build(SceneOptions::default().tolerance)?;
+    let Some(bounds) = scene.bounds() else {
+        return Ok(image);
+    };
+    let margin = options.margin as f64;
+    let available = (
+        (options.width as f64 - 2.0 * margin).max(1.0),
+        (options.height as f64 - 2.0 * margin).max(1.0),
+    );
+    let size = bounds.max - bounds.min;
+    let scale = (available.0 / size.x.max(1e-12)).min(available.1 / size.y.max(1e-12));
+    let scale = if scale.is_finite() { scale } else { 1.0 };
+    // Tessellate to a quarter pixel
+    let mut scene = build(0.25 / scale)?;
+
+    let center = (bounds.min + bounds.max) / 2.0;
+    let (w, h) = (options.width as f64, options.height as f64);
+    scene.transform(&|p: Vector2| Vector2::new(w / 2.0 + (p.x - center.x) * scale, h / 2.0 - (p.y - center.y) * scale));
+
+    let (r, g, b) = options.background;
+    let light_background = 0.299 * r as f64 + 0.587 * g as f64 + 0.114 * b as f64 > 128.0;
+    let mut canvas = Canvas {
+        image: &mut image,
+        clip: None,
+        light_background,
+        line_weight_scale: options.line_weight_scale,
+    };
+    canvas.draw(&scene.primitives);
+    Ok(image)
+}
+
+struct Canvas<'a> {
+    image: &'a mut ImageBuffer,
+    /// Pixels that may be painted, when clipped
+    clip: Option<Vec<bool>>,
+    light_background: bool,
+    line_weight_scale: f64,
+}
+
+impl Canvas<'_> {
+    fn draw(&mut self, primitives: &[Primitive]) {
+        for primitive in primitives {
+            // ACI 7 follows the background: black on light, white on dark
+            let color = if primitive.color_index == Some(7) && self.light_background {
+                (0, 0, 0)
+            } else {
+                primitive.color
+            };
+            let width = (primitive.line_weight.unwrap_or(0.25) * self.line_weight_scale).max(1.0);
+
+            match &primitive.kind {
+                PrimitiveKind::Polyline { points, closed } => {
+                    for pair in points.windows(2) {
+                        self.line(pair[0], pair[1], width, color);
+                    }
+                    if *closed && points.len() > 2 {
+                        self.line(points[points.len() - 1], points[0], width, color);
+                    }
+                }
+                PrimitiveKind::Fill { rings } => {
+                    let rings: Vec<&[Vector2]> = rings.iter().map(|r| r.as_slice()).collect();
+                    self.fill(&rings, color);
+                }
+                PrimitiveKind::Point(p) => self.dot(*p, width, color),
+                PrimitiveKind::Text {
+                    position,
+                    height,
+                    rotation,
+                    width_factor,
+                    value,
+                } => {
+                    let length = height * GLYPH_ADVANCE * width_factor * value.chars().count() as f64;
+                    let along = Vector2::new(rotation.cos(), rotation.sin()) * length;
+                    // Image y points down, so "up" is clockwise of the baseline
+                    let up = Vector2::new(rotation.sin(), -rotation.cos()) * *height;
+                    let p = *position;
+                    let ring = [p, p + along, p + along + up, p + up];
+                    if *height >= 2.0 {
+                        self.fill(&[&ring], color);
+                    } else {
+                        self.line(p, p + along, 1.0, color);
+                    }
+                }
+                PrimitiveKind::Clip { boundary, children } => {
+                    let mask = self.mask(boundary);
+                    let outer = self.clip.replace(mask);
+                    if let (Some(outer), Some(mask)) = (&outer, &mut self.clip) {
+                        mask.iter_mut().zip(outer).for_each(|(m, o)| *m &= *o);
+                    }
+                    self.draw(children);
+                    self.clip = outer;
+                }
+            }
+        }
+    }
+
+    fn plot(&mut self, x: i64, y: i64, color: (u8, u8, u8)) {
+        if x < 0 || y < 0 || x >= self.image.width as i64 || y >= self.image.height as i64 {
+            return;
+        }
+        if let Some(clip) = &self.clip {
+            if !clip[y as usize * self.image.width as usize + x as usize] {
+                return;
+            }
+        }
+        self.image.set_pixel(x as u32, y as u32, color);
+    }
+
+    fn dot(&mut self, p: Vector2, width: f64, color: (u8, u8, u8)) {
+        let radius = (width - 1.0) / 2.0;
+        let (cx, cy) = (p.x.floor() as i64, p.y.floor() as i64);
+        if radius < 0.5 {
+            self.plot(cx, cy, color);
+            return;
+        }
+        let r = radius.ceil() as i64;
+        for dy in -r..=r {
+            for dx in -r..=r {
+                if ((dx * dx + dy * dy) as f64) <= radius * radius + 0.25 {
+                    self.plot(cx + dx, cy + dy, color);
+                }
+            }
+        }
+    }
+
+    /// Bresenham line, stamped with a disc for widths above one pixel
+    fn line(&mut self, a: Vector2, b: Vector2, width: f64, color: (u8, u8, u8)) {
+        let limit = 4.0 * (self.image.width + self.image.height) as f64;
+        if !(a.x.abs() < limit && a.y.abs() < limit && b.x.abs() < limit && b.y.abs() < limit) {
+            // Far off-image segments: clip to a generous box first
+            let Some((a, b)) = clip_segment(a, b, -limit, limit) else {
+                return;
+            };
+            return self.line(a, b, width, color);
+        }
+        let (mut x0, mut y0) = (a.x.floor() as i64, a.y.floor() as i64);
+        let (x1, y1) = (b.x.floor() as i64, b.y.floor() as i64);
+        let dx = (x1 - x0).abs();
+        let dy = -(y1 - y0).abs();
+        let sx = if x0 < x1 { 1 } else { -1 };
+        let sy = if y0 < y1 { 1 } else { -1 };
+        let mut err = dx + dy;
+        loop {
+            if width > 1.0 {
+                self.dot(Vector2::new(x0 as f64, y0 as f64), width, color);
+            } else {
+                self.plot(x0, y0, color);
+            }
+            if x0 == x1 && y0 == y1 {
+                break;
+            }
+            let e2 = 2 * err;
+            if e2 >= dy {
+                err += dy;
+                x0 += sx;
+            }
+            if e2 <= dx {
+                err += dx;
+                y0 += sy;
+            }
+        }
+    }
+
+    /// Even-odd scanline fill, sampling pixel centers
+    fn fill(&mut self, rings: &[&[Vector2]], color: (u8, u8, u8)) {
+        let mut crossings = Vec::new();
+        for y in 0..self.image.height {
+            let sy = y as f64 + 0.5;
+            crossings.clear();
+            for ring in rings {
+                for i in 0..ring.len() {
+                    let (p, q) = (ring[i], ring[(i + 1) % ring.len()]);
+                    if (p.y <= sy) != (q.y <= sy) {
+                        crossings.push(p.x + (sy - p.y) / (q.y - p.y) * (q.x - p.x));
+                    }
+                }
+            }
+            crossings.sort_by(|a, b| a.total_cmp(b));
+            for span in crossings.chunks_exact(2) {
+                let start = (span[0] - 0.5).ceil().max(0.0) as i64;
+                let end = (span[1] - 0.5).floor().min(self.image.width as f64 - 1.0) as i64;
+                for x in start..=end {
+                    self.plot(x, y as i64, color);
+                }
+            }
+        }
+    }
+
+    fn mask(&self, boundary: &[Vector2]) -> Vec<bool> {
+        let mut probe = ImageBuffer::new(self.image.width, self.image.height, (0, 0, 0));
+        let mut canvas = Canvas {
+            image: &mut probe,
+            clip: None,
+            light_background: true,
+            line_weight_scale: 0.0,
+        };
+        canvas.fill(&[boundary], (1, 1, 1));
+        probe.pixels.chunks_exact(3).map(|p| p[0] == 1).collect()
+    }
+}
+
+/// Liang–Barsky clip of a segment to a square
+fn clip_segment(a: Vector2, b: Vector2, min: f64, max: f64) -> Option<(Vector2, Vector2)> {
+    let d = b - a;
+    let (mut t0, mut t1) = (0.0f64, 1.0f64);
+    for (p, q) in [(-d.x, a.x - min), (d.x, max - a.x), (-d.y, a.y - min), (d.y, max - a.y)] {
+        if p == 0.0 {
+            if q < 0.0 {
+                return None;
+            }
+        } else {
+            let t = q / p;
+            if p < 0.0 {
+                t0 = t0.max(t);
+            } else {
+                t1 = t1.min(t);
+            }
+        }
+    }
+    (t0 <= t1 && t0.is_finite() && t1.is_finite()).then(|| (a + d * t0, a + d * t1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::{EntityType, Line, Solid};
+    use crate::tables::Layer;
+    use crate::types::{Color, Vector3};
+
+    #[test]
+    fn test_rasterize_line_and_fill() {
+        let mut doc = CadDocument::new();
+        doc.add_entity(EntityType::Line(Line::from_coords(0.0, 0.0, 0.0, 100.0, 0.0, 0.0))).unwrap();
+        let mut solid = Solid::new(
+            Vector3::new(0.0, 10.0, 0.0),
+            Vector3::new(100.0, 10.0, 0.0),
+            Vector3::new(0.0, 50.0, 0.0),
+            Vector3::new(100.0, 50.0, 0.0),
+        );
+        solid.common.color = Color::RED;
+        doc.add_entity(EntityType::Solid(solid)).unwrap();
+
+        let options = RenderOptions {
+            margin: 0,
+            ..RenderOptions::new(101, 51)
+        };
+        let image = rasterize(&doc, &options).unwrap();
+        // y = 0 is the bottom row; ACI 7 turns black on white
+        assert_eq!(image.pixel(50, 50), Some((0, 0, 0)));
+        assert_eq!(image.pixel(50, 20), Some((255, 0, 0)));
+        assert_eq!(image.pixel(50, 45), Some((255, 255, 255)));
+    }
+
+    #[test]
+    fn test_hidden_layer_and_background() {
+        let mut doc = CadDocument::new();
+        doc.layers.add(Layer::new("HIDE")).unwrap();
+        let mut line = Line::from_coords(0.0, 0.0, 0.0, 10.0, 10.0, 0.0);
+        line.common.layer = "HIDE".to_string();
+        doc.add_entity(EntityType::Line(line)).unwrap();
+
+        let options = RenderOptions {
+            background: (0, 0, 0),
+            ..RenderOptions::new(32, 32)
+        };
+        let shown = rasterize(&doc, &options).unwrap();
+        assert!(shown.as_bytes().contains(&255));
+
+        let hidden = rasterize(&doc, &options.clone().hide_layer("hide")).unwrap();
+        assert_eq!(hidden, ImageBuffer::new(32, 32, (0, 0, 0)));
+        assert!(shown.difference(&hidden).unwrap() > 0.0);
+    }
+
+    #[test]
+    fn test_png_and_bmp_encoding() {
+        let mut image = ImageBuffer::new(3, 2, (10, 20, 30));
+        image.set_pixel(0, 0, (255, 0, 0));
+
+        let png = image.to_png().unwrap();
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+        // IHDR chunk CRC over "IHDR" + data
+        assert_eq!(&png[12..16], b"IHDR");
+        let crc = u32::from_be_bytes(png[29..33].try_into().unwrap());
+        assert_eq!(crc, crc32(0, &png[12..29]));
+        assert!(png.ends_with(&[0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xAE, 0x42, 0x60, 0x82]));
+
+        let bmp = image.to_bmp();
+        assert_eq!(bmp.len(), 54 + 2 * 12);
+        // Top-left pixel is in the last row, stored BGR
+        assert_eq!(&bmp[54 + 12..54 + 15], &[0, 0, 255]);
+    }
+}
@@ -0,0 +1,712 @@
+//! Flattening documents into 2D primitives
+
+use std::collections::HashSet;
+
+use super::tessellate::{
+    arc_points, bulge_polyline, ccw_sweep, circle_points, ellipse_points, hatch_path_points, spline_points,
+};
+use crate::appearance::EffectiveStyle;
+use crate::document::CadDocument;
+use crate::entities::{outline, Entity, EntityType, Hatch, Insert, MText, PolylineFlags, Viewport};
+use crate::objects::{Layout, ObjectType};
+use crate::tables::{BlockRecord, Layer};
+use crate::types::geometry::ocs_matrix;
+use crate::types::{
+    aci_to_rgb, BoundingBox2D, Color, Handle, Matrix4, Transform, Vector2, Vector3,
+};
+
+/// Deepest block nesting followed, to stop on circular references
+const MAX_NESTING: usize = 16;
+
+/// Options controlling how a scene is built
+#[derive(Debug, Clone, PartialEq)]
+pub struct SceneOptions {
+    /// Largest chord deviation of tessellated curves, in output units
+    pub tolerance: f64,
+    /// Skip layers marked as not plottable
+    pub plot_only: bool,
+    /// Layers to leave out, by name (case-insensitive)
+    pub hidden_layers: HashSet<String>,
+}
+
+impl Default for SceneOptions {
+    fn default() -> Self {
+        Self {
+            tolerance: 0.01,
+            plot_only: false,
+            hidden_layers: HashSet::new(),
+        }
+    }
+}
+
+impl SceneOptions {
+    /// Hide a layer
+    pub fn hide_layer(mut self, name: impl AsRef<str>) -> Self {
+        self.hidden_layers.insert(name.as_ref().to_uppercase());
+        self
+    }
+}
+
+/// Geometry of a primitive
+#[derive(Debug, Clone, PartialEq)]
+pub enum PrimitiveKind {
+    /// Open or closed polyline
+    Polyline {
+        /// Vertices
+        points: Vec<Vector2>,
+        /// Whether the last vertex connects back to the first
+        closed: bool,
+    },
+    /// Area filled with the even-odd rule
+    Fill {
+        /// Boundary rings
+        rings: Vec<Vec<Vector2>>,
+    },
+    /// Single line of text
+    Text {
+        /// Baseline start
+        position: Vector2,
+        /// Cap height
+        height: f64,
+        /// Baseline direction, radians counter-clockwise
+        rotation: f64,
+        /// Horizontal stretch
+        width_factor: f64,
+        /// Text content
+        value: String,
+    },
+    /// Point marker
+    Point(Vector2),
+    /// Primitives clipped to a boundary (a viewport's contents)
+    Clip {
+        /// Clip boundary
+        boundary: Vec<Vector2>,
+        /// Clipped primitives
+        children: Vec<Primitive>,
+    },
+}
+
+/// A primitive with its resolved display properties
+#[derive(Debug, Clone, PartialEq)]
+pub struct Primitive {
+    /// Geometry
+    pub kind: PrimitiveKind,
+    /// Effective color
+    pub color: (u8, u8, u8),
+    /// Effective ACI index, when the color is indexed (used by plot styles)
+    pub color_index: Option<u8>,
+    /// Effective lineweight in millimetres; `None` is the default lineweight
+    pub line_weight: Option<f64>,
+    /// Effective layer name
+    pub layer: String,
+}
+
+/// Flattened 2D geometry of a model space, layout or block
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Scene {
+    /// Primitives in drawing order
+    pub primitives: Vec<Primitive>,
+}
+
+impl Scene {
+    /// Model space, viewed from above in WCS
+    pub fn model_space(doc: &CadDocument, options: &SceneOptions) -> Self {
+        let mut scene = Self::default();
+        if let Some(record) = doc.block_records.get("*Model_Space") {
+            let mut builder = Builder::new(doc, options, Transform::identity());
+            builder.draw_block(record, &Inherited::default(), &mut scene.primitives, 0);
+        }
+        scene
+    }
+
+    /// Paper space of a layout, in paper units, with the model space shown
+    /// through its viewports
+    pub fn layout(doc: &CadDocument, layout: &Layout, options: &SceneOptions) -> Self {
+        let mut scene = Self::default();
+        let Some(record) = layout_block_record(doc, layout) else {
+            return scene;
+        };
+        let mut builder = Builder::new(doc, options, Transform::identity());
+        let entities = owned_entities(doc, record);
+
+        for entity in &entities {
+            if let EntityType::Viewport(viewport) = entity {
+                builder.draw_viewport(viewport, &mut scene.primitives);
+            } else {
+                builder.draw_entity(entity, &Inherited::default(), &mut scene.primitives, 0);
+            }
+        }
+        scene
+    }
+
+    /// Bounding rectangle of all primitives
+    pub fn bounds(&self) -> Option<BoundingBox2D> {
+        let mut bounds: Option<BoundingBox2D> = None;
+        visit_points(&self.primitives, &mut |p| match &mut bounds {
+            Some(b) => b.expand_to_include(p),
+            None => bounds = Some(BoundingBox2D::from_point(p)),
+        });
+        bounds
+    }
+
+    /// Apply a 2D affine map to every primitive
+    pub fn transform(&mut self, map: &dyn Fn(Vector2) -> Vector2) {
+        transform_primitives(&mut self.primitives, map);
+    }
+}
+
+/// Layout named `name` (case-insensitive)
+pub fn find_layout<'a>(doc: &'a CadDocument, name: &str) -> Option<&'a Layout> {
+    doc.objects.values().find_map(|object| match object {
+        ObjectType::Layout(layout) if layout.name.eq_ignore_ascii_case(name) => Some(layout),
+        _ => None,
+    })
+}
+
+/// Block record holding the entities of a layout
+pub fn layout_block_record<'a>(doc: &'a CadDocument, layout: &Layout) -> Option<&'a BlockRecord> {
+    if !layout.block_record.is_null() {
+        if let Some(record) = doc.block_records.iter().find(|r| r.handle == layout.block_record) {
+            return Some(record);
+        }
+    }
+    if layout.name.eq_ignore_ascii_case("Model") {
+        doc.block_records.get("*Model_Space")
+    } else {
+        doc.block_records.get("*Paper_Space")
+    }
+}
+
+/// Entities of a block record in drawing order
+///
+/// Layout entities live in the document with the record as their owner;
+/// other blocks keep theirs on the record.
+pub(crate) fn owned_entities<'a>(doc: &'a CadDocument, record: &'a BlockRecord) -> Vec<&'a EntityType> {
+    if record.is_model_space() || record.is_paper_space() {
+        let mut owned: Vec<&EntityType> = doc
+            .entities()
+            .filter(|e| {
+                let owner = e.common().owner_handle;
+                owner == record.handle || (owner.is_null() && record.is_model_space())
+            })
+            .collect();
+        if !owned.is_empty() {
+            owned.sort_by_key(|e| e.common().handle.value());
+            return owned;
+        }
+    }
+    record.entities.iter().collect()
+}
+
+fn visit_points(primitives: &[Primitive], f: &mut dyn FnMut(Vector2)) {
+    for primitive in primitives {
+        match &primitive.kind {
+            PrimitiveKind::Polyline { points, .. } => points.iter().for_each(|p| f(*p)),
+            PrimitiveKind::Fill { rings } => rings.iter().flatten().for_each(|p| f(*p)),
+            PrimitiveKind::Text { position, .. } | PrimitiveKind::Point(position) => f(*position),
+            PrimitiveKind::Clip { boundary, .. } => boundary.iter().for_each(|p| f(*p)),
+        }
+    }
+}
+
+fn transform_primitives(primitives: &mut [Primitive], map: &dyn Fn(Vector2) -> Vector2) {
+    for primitive in primitives {
+        match &mut primitive.kind {
+            PrimitiveKind::Polyline { points, .. } => points.iter_mut().for_each(|p| *p = map(*p)),
+            PrimitiveKind::Fill { rings } => rings.iter_mut().flatten().for_each(|p| *p = map(*p)),
+            PrimitiveKind::Text { position, height, rotation, .. } => {
+                let origin = map(*position);
+                let up = map(*position + Vector2::new(-rotation.sin(), rotation.cos()) * *height) - origin;
+                let along = map(*position + Vector2::new(rotation.cos(), rotation.sin())) - origin;
+                *position = origin;
+                *height = up.length();
+                *rotation = along.y.atan2(along.x);
+            }
+            PrimitiveKind::Point(p) => *p = map(*p),
+            PrimitiveKind::Clip { boundary, children } => {
+                boundary.iter_mut().for_each(|p| *p = map(*p));
+                transform_primitives(children, map);
+            }
+        }
+    }
+}
+
+/// Style of the insert showing the block being drawn, `None` outside
+/// blocks
+#[derive(Debug, Clone, Default)]
+struct Inherited(Option<EffectiveStyle>);
+
+struct Builder<'a> {
+    doc: &'a CadDocument,
+    options: &'a SceneOptions,
+    /// WCS to output
+    transform: Transform,
+    /// Layers frozen in the viewport being drawn
+    viewport_frozen: HashSet<String>,
+}
+
+impl<'a> Builder<'a> {
+    fn new(doc: &'a CadDocument, options: &'a SceneOptions, transform: Transform) -> Self {
+        Self {
+            doc,
+            options,
+            transform,
+            viewport_frozen: HashSet::new(),
+        }
+    }
+
+    /// Curve tolerance in the current drawing units
+    fn tolerance(&self) -> f64 {
+        let scale = self.transform.apply_rotation(Vector3::UNIT_X).length()
+            .max(self.transform.apply_rotation(Vector3::UNIT_Y).length());
+        if scale > 1e-12 {
+            self.options.tolerance / scale
+        } else {
+            self.options.tolerance
+        }
+    }
+
+    fn project(&self, p: Vector3) -> Vector2 {
+        let p = self.transform.apply(p);
+        Vector2::new(p.x, p.y)
+    }
+
+    fn layer_visible(&self, layer: &Layer) -> bool {
+        let name = layer.name.to_uppercase();
+        !layer.is_off()
+            && !layer.is_frozen()
+            && (layer.is_plottable || !self.options.plot_only)
+            && !self.options.hidden_layers.contains(&name)
+            && !self.viewport_frozen.contains(&name)
+    }
+
+    fn draw_block(&mut self, record: &BlockRecord, inherited: &Inherited, out: &mut Vec<Primitive>, depth: usize) {
+        for entity in owned_entities(self.doc, record) {
+            self.draw_entity(entity, inherited, out, depth);
+        }
+    }
+
+    /// Resolve layer, color and lineweight; `None` if the entity is hidden
+    fn style(&self, entity: &dyn Entity, inherited: &Inherited) -> Option<(String, Color, Option<f64>)> {
+        if entity.is_invisible() {
+            return None;
+        }
+        let style = self.doc.resolve_style(entity, inherited.0.as_ref());
+        if let Some(layer) = self.doc.layers.get(&style.layer) {
+            if !self.layer_visible(layer) {
+                return None;
+            }
+        }
+        Some((style.layer, style.color, style.line_weight.millimeters()))
+    }
+
+    fn push(&self, out: &mut Vec<Primitive>, style: &(String, Color, Option<f64>), kind: PrimitiveKind) {
+        let (layer, color, line_weight) = style;
+        out.push(Primitive {
+            kind,
+            color: color.to_rgb().unwrap_or_else(|| aci_to_rgb(7)),
+            color_index: match color {
+                Color::Index(i) => Some(*i),
+                _ => None,
+            },
+            line_weight: *line_weight,
+            layer: layer.clone(),
+        });
+    }
+
+    fn polyline(&self, out: &mut Vec<Primitive>, style: &(String, Color, Option<f64>), points: Vec<Vector2>, closed: bool) {
+        if points.len() >= 2 {
+            self.push(out, style, PrimitiveKind::Polyline { points, closed });
+        }
+    }
+
+    /// Map points from an entity's OCS (with elevation) to the output
+    fn project_ocs(&self, normal: Vector3, elevation: f64, points: &[Vector2]) -> Vec<Vector2> {
+        let ocs = ocs_matrix(normal);
+        points
+            .iter()
+            .map(|p| self.project(ocs.transform_point(Vector3::new(p.x, p.y, elevation))))
+            .collect()
+    }
+
+    fn draw_entity(&mut self, entity: &EntityType, inherited: &Inherited, out: &mut Vec<Primitive>, depth: usize) {
+        let Some(style) = self.style(entity.as_entity(), inherited) else {
+            return;
+        };
+        let tolerance = self.tolerance();
+
+        match entity {
+            EntityType::Point(point) => {
+                self.push(out, &style, PrimitiveKind::Point(self.project(point.location)));
+            }
+            EntityType::Line(line) => {
+                self.polyline(out, &style, vec![self.project(line.start), self.project(line.end)], false);
+            }
+            EntityType::Circle(circle) => {
+                let (center, elevation) = split_elevation(circle.center);
+                let points = circle_points(center, circle.radius, tolerance);
+                let points = self.project_ocs(circle.normal, elevation, &points);
+                self.polyline(out, &style, points, true);
+            }
+            EntityType::Arc(arc) => {
+                let (center, elevation) = split_elevation(arc.center);
+                let sweep = ccw_sweep(arc.start_angle, arc.end_angle);
+                let points = arc_points(center, arc.radius, arc.start_angle, sweep, tolerance);
+                let points = self.project_ocs(arc.normal, elevation, &points);
+                self.polyline(out, &style, points, false);
+            }
+            EntityType::Ellipse(ellipse) => {
+                let points = ellipse_points(
+                    ellipse.center,
+                    ellipse.major_axis,
+                    ellipse.normal,
+                    ellipse.minor_axis_ratio,
+                    ellipse.start_parameter,
+                    ellipse.end_parameter,
+                    tolerance,
+                );
+                let full = ccw_sweep(ellipse.start_parameter, ellipse.end_parameter) >= std::f64::consts::TAU - 1e-9;
+                let mut points: Vec<Vector2> = points.into_iter().map(|p| self.project(p)).collect();
+                if full {
+                    points.pop();
+                }
+                self.polyline(out, &style, points, full);
+            }
+            EntityType::LwPolyline(poly) => {
+                let vertices: Vec<(Vector2, f64)> = poly.vertices.iter().map(|v| (v.location, v.bulge)).collect();
+                let points = bulge_polyline(&vertices, poly.is_closed, tolerance);
+                let points = self.project_ocs(poly.normal, poly.elevation, &points);
+                self.polyline(out, &style, points, poly.is_closed);
+            }
+            EntityType::Polyline2D(poly) => {
+                let closed = poly.is_closed();
+                let points = if poly.flags.bits() & (PolylineFlags::CURVE_FIT | PolylineFlags::SPLINE_FIT).bits() != 0 {
+                    poly.smoothed_points(tolerance).into_iter().map(|p| Vector2::new(p.x, p.y)).collect()
+                } else {
+                    let vertices: Vec<(Vector2, f64)> = poly
+                        .vertices
+                        .iter()
+                        .map(|v| (Vector2::new(v.location.x, v.location.y), v.bulge))
+                        .collect();
+                    bulge_polyline(&vertices, closed, tolerance)
+                };
+                let points = self.project_ocs(poly.normal, poly.elevation, &points);
+                self.polyline(out, &style, points, closed);
+            }
+            EntityType::Polyline(poly) => {
+                let points = poly.smoothed_points().into_iter().map(|p| self.project(p)).collect();
+                self.polyline(out, &style, points, poly.is_closed());
+            }
+            EntityType::Polyline3D(poly) => {
+                let points = poly.smoothed_points().into_iter().map(|p| self.project(p)).collect();
+                self.polyline(out, &style, points, poly.is_closed());
+            }
+            EntityType::Spline(spline) => {
+                let points = if spline.control_points.is_empty() {
+                    spline.fit_points.clone()
+                } else {
+                    spline_points(
+                        spline.degree.max(1) as usize,
+                        &spline.knots,
+                        &spline.control_points,
+                        &spline.weights,
+                        tolerance,
+                    )
+                };
+                let points = points.into_iter().map(|p| self.project(p)).collect();
+                self.polyline(out, &style, points, false);
+            }
+            EntityType::Leader(leader) => {
+                let points = leader.vertices.iter().map(|p| self.project(*p)).collect();
+                self.polyline(out, &style, points, false);
+            }
+            EntityType::Solid(solid) => {
+                let ring = outline(&solid.as_triangles()).into_iter().map(|p| self.project(p)).collect();
+                self.push(out, &style, PrimitiveKind::Fill { rings: vec![ring] });
+            }
+            EntityType::Face3D(face) => {
+                let points = face.corners().into_iter().map(|p| self.project(p)).collect();
+                self.polyline(out, &style, points, true);
+            }
+            EntityType::Hatch(hatch) => self.draw_hatch(hatch, &style, out, tolerance),
+            EntityType::Text(text) => {
+                let (position, elevation) = split_elevation(text.insertion_point);
+                self.draw_text(out, &style, text.normal, elevation, position, text.height, text.rotation, text.width_factor, &text.value);
+            }
+            EntityType::AttributeEntity(attribute) => {
+                let p = attribute.insertion_point;
+                self.draw_text(out, &style, Vector3::UNIT_Z, p.z, Vector2::new(p.x, p.y), attribute.height, attribute.rotation, 1.0, &attribute.value);
+            }
+            EntityType::MText(mtext) => self.draw_mtext(mtext, &style, out),
+            EntityType::Insert(insert) => {
+                let inherited = Inherited(Some(self.doc.resolve_style(entity.as_entity(), inherited.0.as_ref())));
+                self.draw_insert(insert, &inherited, out, depth);
+                for attribute in &insert.attributes {
+                    if let Some(style) = self.style(attribute, &Inherited::default()) {
+                        let p = attribute.insertion_point;
+                        self.draw_text(out, &style, Vector3::UNIT_Z, p.z, Vector2::new(p.x, p.y), attribute.height, attribute.rotation, 1.0, &attribute.value);
+                    }
+                }
+            }
+            EntityType::Dimension(dimension) if depth < MAX_NESTING => {
+                if let Some(record) = self.doc.block_records.get(&dimension.base().block_name) {
+                    let inherited = Inherited(Some(self.doc.resolve_style(entity.as_entity(), inherited.0.as_ref())));
+                    self.draw_block(record, &inherited, out, depth + 1);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn draw_text(
+        &self,
+        out: &mut Vec<Primitive>,
+        style: &(String, Color, Option<f64>),
+        normal: Vector3,
+        elevation: f64,
+        position: Vector2,
+        height: f64,
+        rotation: f64,
+        width_factor: f64,
+        value: &str,
+    ) {
+        if value.is_empty() {
+            return;
+        }
+        let ocs = ocs_matrix(normal);
+        let at = |p: Vector2| self.project(ocs.transform_point(Vector3::new(p.x, p.y, elevation)));
+        let origin = at(position);
+        let up = at(position + Vector2::new(-rotation.sin(), rotation.cos()) * height) - origin;
+        let along = at(position + Vector2::new(rotation.cos(), rotation.sin())) - origin;
+        self.push(
+            out,
+            style,
+            PrimitiveKind::Text {
+                position: origin,
+                height: up.length(),
+                rotation: along.y.atan2(along.x),
+                width_factor: if width_factor > 0.0 { width_factor } else { 1.0 },
+                value: value.to_string(),
+            },
+        );
+    }
+
+    fn draw_mtext(&self, mtext: &MText, style: &(String, Color, Option<f64>), out: &mut Vec<Primitive>) {
+        let spacing = mtext.height * 5.0 / 3.0 * mtext.line_spacing_factor.max(0.25);
+        let (origin, elevation) = split_elevation(mtext.insertion_point);
+        let (sin, cos) = mtext.rotation.sin_cos();
+        // The insertion point is the top of the first line for top attachments
+        for (i, line) in plain_mtext(&mtext.value).lines().enumerate() {
+            let drop = mtext.height + spacing * i as f64;
+            let position = origin + Vector2::new(sin * drop, -cos * drop);
+            self.draw_text(out, style, mtext.normal, elevation, position, mtext.height, mtext.rotation, 1.0, line);
+        }
+    }
+
+    fn draw_hatch(&self, hatch: &Hatch, style: &(String, Color, Option<f64>), out: &mut Vec<Primitive>, tolerance: f64) {
+        if !hatch.is_solid {
+            for (start, end) in hatch.fill_lines(tolerance) {
+                let points = self.project_ocs(hatch.normal, hatch.elevation, &[start, end]);
+                self.polyline(out, style, points, false);
+            }
+            return;
+        }
+        let mut rings = Vec::new();
+        for path in &hatch.paths {
+            let ring = hatch_path_points(path, tolerance);
+            if ring.len() >= 3 {
+                rings.push(self.project_ocs(hatch.normal, hatch.elevation, &ring));
+            }
+        }
+        if !rings.is_empty() {
+            self.push(out, style, PrimitiveKind::Fill { rings });
+        }
+    }
+
+    fn draw_insert(&mut self, insert: &Insert, inherited: &Inherited, out: &mut Vec<Primitive>, depth: usize) {
+        if depth >= MAX_NESTING {
+            return;
+        }
+        let Some(record) = self.doc.block_records.get(&insert.block_name) else {
+            return;
+        };
+        let outer = self.transform;
+        let columns = insert.column_count.max(1);
+        let rows = insert.row_count.max(1);
+        for row in 0..rows {
+            for column in 0..columns {
+                self.transform = insert_transform(insert, row, column).then(&outer);
+                self.draw_block(record, inherited, out, depth + 1);
+            }
+        }
+        self.transform = outer;
+    }
+
+    /// Draw a paper space viewport: its border and the model space seen
+    /// through it, clipped to the viewport
+    fn draw_viewport(&mut self, viewport: &Viewport, out: &mut Vec<Primitive>) {
+        // Viewport 1 is the paper space view itself
+        if viewport.id == 1 || !viewport.status.is_on || viewport.width <= 0.0 || viewport.height <= 0.0 {
+            return;
+        }
+        let (hw, hh) = (viewport.width / 2.0, viewport.height / 2.0);
+        let c = viewport.center;
+        let boundary = vec![
+            Vector2::new(c.x - hw, c.y - hh),
+            Vector2::new(c.x + hw, c.y - hh),
+            Vector2::new(c.x + hw, c.y + hh),
+            Vector2::new(c.x - hw, c.y + hh),
+        ];
+
+        if viewport.view_height > 0.0 {
+            if let Some(model) = self.doc.block_records.get("*Model_Space") {
+                let scale = viewport.height / viewport.view_height;
+                let view = Transform::from_translation(-viewport.view_target)
+                    .then(&Transform::from_matrix(Matrix4::from_matrix3(ocs_matrix(viewport.view_direction).transpose())))
+                    .then(&Transform::from_matrix(Matrix4::rotation_z(-viewport.twist_angle)))
+                    .then(&Transform::from_translation(Vector3::new(-viewport.view_center.x, -viewport.view_center.y, 0.0)))
+                    .then(&Transform::from_scale(scale))
+                    .then(&Transform::from_translation(Vector3::new(c.x, c.y, 0.0)));
+
+                let frozen: HashSet<String> = viewport
+                    .frozen_layers
+                    .iter()
+                    .filter_map(|h| layer_by_handle(self.doc, *h))
+                    .map(|l| l.name.to_uppercase())
+                    .collect();
+
+                let mut model_builder = Builder::new(self.doc, self.options, view);
+                model_builder.viewport_frozen = frozen;
+                let mut children = Vec::new();
+                model_builder.draw_block(model, &Inherited::default(), &mut children, 0);
+                if !children.is_empty() {
+                    out.push(Primitive {
+                        kind: PrimitiveKind::Clip { boundary: boundary.clone(), children },
+                        color: aci_to_rgb(7),
+                        color_index: None,
+                        line_weight: None,
+                        layer: viewport.common.layer.clone(),
+                    });
+                }
+            }
+        }
+
+        if let Some(style) = self.style(viewport, &Inherited::default()) {
+            let points = boundary.iter().map(|p| self.project(Vector3::new(p.x, p.y, 0.0))).collect();
+            self.polyline(out, &style, points, true);
+        }
+    }
+}
+
+fn layer_by_handle(doc: &CadDocument, handle: Handle) -> Option<&Layer> {
+    doc.layers.iter().find(|l| l.handle == handle)
+}
+
+/// Split an OCS point into its in-plane coordinates and elevation
+/// Block to WCS transform of one cell of an INSERT's array
+pub(crate) fn insert_transform(insert: &Insert, row: u16, column: u16) -> Transform {
+    let offset = Vector3::new(
+        column as f64 * insert.column_spacing,
+        row as f64 * insert.row_spacing,
+        0.0,
+    );
+    let (origin, elevation) = split_elevation(insert.insert_point);
+    Transform::from_scaling(Vector3::new(insert.x_scale, insert.y_scale, insert.z_scale))
+        .then(&Transform::from_translation(offset))
+        .then(&Transform::from_matrix(Matrix4::rotation_z(insert.rotation)))
+        .then(&Transform::from_translation(Vector3::new(origin.x, origin.y, elevation)))
+        .then(&Transform::from_matrix(Matrix4::from_matrix3(ocs_matrix(insert.normal))))
+}
+
+fn split_elevation(point: Vector3) -> (Vector2, f64) {
+    (Vector2::new(point.x, point.y), point.z)
+}
+
+/// MTEXT content with inline formatting removed, one paragraph per line
+pub fn plain_mtext(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('P') | Some('X') => out.push('\n'),
+                Some('~') => out.push(' '),
+                Some(c @ ('\\' | '{' | '}')) => out.push(c),
+                // Codes with an argument run to ';'
+                Some('A' | 'C' | 'c' | 'F' | 'f' | 'H' | 'Q' | 'T' | 'W' | 'p') => {
+                    for c in chars.by_ref() {
+                        if c == ';' {
+                            break;
+                        }
+                    }
+                }
+                Some('S') => {
+                    // Stacked fraction: show as a/b
+                    for c in chars.by_ref() {
+                        match c {
+                            ';' => break,
+                            '^' | '#' => out.push('/'),
+                            c => out.push(c),
+                        }
+                    }
+                }
+                // Toggles (\L, \l, \O, \o, \K, \k, ...)
+                Some(_) | None => {}
+            },
+            '{' | '}' => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::{Circle, Line};
+
+    #[test]
+    fn test_plain_mtext() {
+        assert_eq!(plain_mtext("{\\fArial|b1;Bold}\\Psecond \\S1/2;"), "Bold\nsecond 1/2");
+    }
+
+    #[test]
+    fn test_model_space_skips_hidden_layers() {
+        let mut doc = CadDocument::new();
+        let mut off = Layer::new("OFF");
+        off.turn_off();
+        doc.layers.add(off).unwrap();
+        doc.add_entity(EntityType::Line(Line::from_coords(0.0, 0.0, 0.0, 10.0, 0.0, 0.0))).unwrap();
+        let mut hidden = Line::from_coords(0.0, 0.0, 0.0, 0.0, 50.0, 0.0);
+        hidden.common.layer = "OFF".to_string();
+        doc.add_entity(EntityType::Line(hidden)).unwrap();
+
+        let scene = Scene::model_space(&doc, &SceneOptions::default());
+        assert_eq!(scene.primitives.len(), 1);
+        let bounds = scene.bounds().unwrap();
+        assert_eq!(bounds.max, Vector2::new(10.0, 0.0));
+    }
+
+    #[test]
+    fn test_insert_expands_block_with_byblock_color() {
+        let mut doc = CadDocument::new();
+        let mut record = BlockRecord::new("DOT");
+        let mut circle = Circle::from_coords(0.0, 0.0, 0.0, 1.0);
+        circle.common.color = Color::ByBlock;
+        record.entities.push(EntityType::Circle(circle));
+        doc.block_records.add(record).unwrap();
+
+        let mut insert = Insert::new("DOT", Vector3::new(100.0, 0.0, 0.0));
+        insert.x_scale = 2.0;
+        insert.y_scale = 2.0;
+        insert.common.color = Color::RED;
+        doc.add_entity(EntityType::Insert(insert)).unwrap();
+
+        let scene = Scene::model_space(&doc, &SceneOptions::default());
+        assert_eq!(scene.primitives.len(), 1);
+        assert_eq!(scene.primitives[0].color, (255, 0, 0));
+        let bounds = scene.bounds().unwrap();
+        assert!((bounds.min.x - 98.0).abs() < 1e-6 && (bounds.max.x - 102.0).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,338 @@
+//! Curve tessellation
+//!
+//! Functions that approximate arcs, bulged segments, ellipses, splines and
+//! hatch boundaries with polylines. `tolerance` is the largest allowed
+//! distance between the curve and its chords, in drawing units.
+
+use crate::entities::hatch::{BoundaryEdge, BoundaryPath};
+use crate::types::{Vector2, Vector3};
+pub use crate::types::geometry::{signed_area, triangulate};
+use std::f64::consts::TAU;
+
+/// Upper bound on segments per curve, to keep degenerate input in check
+const MAX_SEGMENTS: usize = 4096;
+
+/// Number of chords needed to approximate `sweep` radians of a circle of
+/// `radius` within `tolerance`
+pub fn arc_segments(radius: f64, sweep: f64, tolerance: f64) -> usize {
+    let sweep = sweep.abs();
+    if radius <= 0.0 || sweep == 0.0 {
+        return 1;
+    }
+    let tolerance = tolerance.max(radius * 1e-6);
+    let step = if tolerance >= radius {
+        std::f64::consts::PI
+    } else {
+        2.0 * (1.0 - tolerance / radius).acos()
+    };
+    let min = if sweep >= TAU - 1e-9 { 8 } else { 1 };
+    ((sweep / step).ceil() as usize).clamp(min, MAX_SEGMENTS)
+}
+
+/// Points of a circular arc from `start` sweeping `sweep` radians
+/// (negative for clockwise), including both end points
+pub fn arc_points(center: Vector2, radius: f64, start: f64, sweep: f64, tolerance: f64) -> Vec<Vector2> {
+    let n = arc_segments(radius, sweep, tolerance);
+    (0..=n)
+        .map(|i| {
+            let a = start + sweep * i as f64 / n as f64;
+            Vector2::new(center.x + radius * a.cos(), center.y + radius * a.sin())
+        })
+        .collect()
+}
+
+/// Points of a full circle; the first point is not repeated at the end
+pub fn circle_points(center: Vector2, radius: f64, tolerance: f64) -> Vec<Vector2> {
+    let mut points = arc_points(center, radius, 0.0, TAU, tolerance);
+    points.pop();
+    points
+}
+
+/// Counter-clockwise sweep from `start` to `end`, in `(0, 2π]`
+pub fn ccw_sweep(start: f64, end: f64) -> f64 {
+    let sweep = (end - start).rem_euclid(TAU);
+    if sweep == 0.0 {
+        TAU
+    } else {
+        sweep
+    }
+}
+
+/// Points of a polyline segment with a bulge, excluding `p0` and
+/// including `p1`
+///
+/// The bulge is the tangent of a quarter of the included angle; positive
+/// bulges curve counter-clockwise.
+pub fn bulge_points(p0: Vector2, p1: Vector2, bulge: f64, tolerance: f64) -> Vec<Vector2> {
+    let chord = p1 - p0;
+    let length = chord.length();
+    if bulge.abs() < 1e-12 || length < 1e-12 {
+        return vec![p1];
+    }
+    let sweep = 4.0 * bulge.atan();
+    let radius = length / (2.0 * (sweep / 2.0).sin()).abs();
+    // Distance from the chord midpoint to the center, signed by direction
+    let sagitta_offset = radius * (sweep / 2.0).cos();
+    let normal = Vector2::new(-chord.y, chord.x) / length;
+    let mid = (p0 + p1) / 2.0;
+    let center = if bulge > 0.0 {
+        mid + normal * sagitta_offset
+    } else {
+        mid - normal * sagitta_offset
+    };
+    let start = (p0.y - center.y).atan2(p0.x - center.x);
+    let mut points = arc_points(center, radius, start, sweep, tolerance);
+    points.remove(0);
+    if let Some(last) = points.last_mut() {
+        *last = p1;
+    }
+    points
+}
+
+/// Points along vertices joined by bulged segments
+///
+/// `vertices` are `(point, bulge)` pairs; the bulge applies to the segment
+/// leaving that vertex. A closed polyline gets its closing segment, but the
+/// first point is not repeated.
+pub fn bulge_polyline(vertices: &[(Vector2, f64)], closed: bool, tolerance: f64) -> Vec<Vector2> {
+    let Some(&(first, _)) = vertices.first() else {
+        return Vec::new();
+    };
+    let mut points = vec![first];
+    for pair in vertices.windows(2) {
+        points.extend(bulge_points(pair[0].0, pair[1].0, pair[0].1, tolerance));
+    }
+    if closed && vertices.len() > 1 {
+        let &(last, bulge) = vertices.last().unwrap();
+        if bulge.abs() >= 1e-12 {
+            let mut closing = bulge_points(last, first, bulge, tolerance);
+            closing.pop();
+            points.extend(closing);
+        }
+    }
+    points
+}
+
+/// Points of an elliptical arc between two parameters
+///
+/// `major_axis` is the vector from the center to the end of the major
+/// axis; parameters are measured from it and run counter-clockwise.
+pub fn ellipse_points(
+    center: Vector3,
+    major_axis: Vector3,
+    normal: Vector3,
+    ratio: f64,
+    start_param: f64,
+    end_param: f64,
+    tolerance: f64,
+) -> Vec<Vector3> {
+    let normal = if normal.length() < 1e-12 { Vector3::UNIT_Z } else { normal.normalize() };
+    let minor_axis = normal.cross(&major_axis) * ratio;
+    let sweep = ccw_sweep(start_param, end_param);
+    let n = arc_segments(major_axis.length(), sweep, tolerance);
+    (0..=n)
+        .map(|i| {
+            let t = start_param + sweep * i as f64 / n as f64;
+            center + major_axis * t.cos() + minor_axis * t.sin()
+        })
+        .collect()
+}
+
+/// Points of a (possibly rational) B-spline
+///
+/// Falls back to the control polygon when the knot vector does not match
+/// the control points. `weights` may be empty for non-rational splines.
+pub fn spline_points(
+    degree: usize,
+    knots: &[f64],
+    control_points: &[Vector3],
+    weights: &[f64],
+    tolerance: f64,
+) -> Vec<Vector3> {
+    let n = control_points.len();
+    if n < 2 || degree == 0 || knots.len() != n + degree + 1 {
+        return control_points.to_vec();
+    }
+    let t0 = knots[degree];
+    let t1 = knots[n];
+    if t1 <= t0 {
+        return control_points.to_vec();
+    }
+
+    // Sample densely enough for the control polygon's length
+    let polygon: f64 = control_points.windows(2).map(|w| w[0].distance(&w[1])).sum();
+    let samples = ((polygon / tolerance.max(1e-9)).sqrt().ceil() as usize * degree)
+        .clamp(n * 4, MAX_SEGMENTS);
+
+    let weight = |i: usize| weights.get(i).copied().filter(|w| *w > 0.0).unwrap_or(1.0);
+    (0..=samples)
+        .map(|s| {
+            let t = t0 + (t1 - t0) * s as f64 / samples as f64;
+            de_boor(degree, knots, control_points, &weight, t)
+        })
+        .collect()
+}
+
+/// Points around a hatch boundary path, in the hatch's OCS
+///
+/// The ring is not closed explicitly; consecutive duplicate points where
+/// edges meet are removed.
+pub fn hatch_path_points(path: &BoundaryPath, tolerance: f64) -> Vec<Vector2> {
+    let mut ring: Vec<Vector2> = Vec::new();
+    for edge in &path.edges {
+        match edge {
+            BoundaryEdge::Line(line) => {
+                ring.push(line.start);
+                ring.push(line.end);
+            }
+            BoundaryEdge::CircularArc(arc) => {
+                // Clockwise arcs store their angles mirrored
+                let (start, end) = if arc.counter_clockwise {
+                    (arc.start_angle, arc.end_angle)
+                } else {
+                    (-arc.start_angle, -arc.end_angle)
+                };
+                let sweep = ccw_sweep(start, end);
+                let sweep = if arc.counter_clockwise { sweep } else { -sweep };
+                ring.extend(arc_points(arc.center, arc.radius, start, sweep, tolerance));
+            }
+            BoundaryEdge::EllipticArc(arc) => {
+                let center = Vector3::new(arc.center.x, arc.center.y, 0.0);
+                let major = Vector3::new(arc.major_axis_endpoint.x, arc.major_axis_endpoint.y, 0.0);
+                let mut points = ellipse_points(
+                    center,
+                    major,
+                    Vector3::UNIT_Z,
+                    arc.minor_axis_ratio,
+                    arc.start_angle,
+                    arc.end_angle,
+                    tolerance,
+                );
+                if !arc.counter_clockwise {
+                    points.reverse();
+                }
+                ring.extend(points.into_iter().map(|p| Vector2::new(p.x, p.y)));
+            }
+            BoundaryEdge::Spline(spline) => {
+                let points = if spline.control_points.is_empty() {
+                    spline.fit_points.iter().map(|p| Vector3::new(p.x, p.y, 0.0)).collect()
+                } else {
+                    // Hatch spline control points carry the weight in z
+                    let control: Vec<Vector3> =
+                        spline.control_points.iter().map(|p| Vector3::new(p.x, p.y, 0.0)).collect();
+                    let weights: Vec<f64> = if spline.rational {
+                        spline.control_points.iter().map(|p| p.z).collect()
+                    } else {
+                        Vec::new()
+                    };
+                    spline_points(spline.degree.max(1) as usize, &spline.knots, &control, &weights, tolerance)
+                };
+                ring.extend(points.into_iter().map(|p| Vector2::new(p.x, p.y)));
+            }
+            BoundaryEdge::Polyline(poly) => {
+                let vertices: Vec<(Vector2, f64)> =
+                    poly.vertices.iter().map(|v| (Vector2::new(v.x, v.y), v.z)).collect();
+                ring.extend(bulge_polyline(&vertices, poly.is_closed, tolerance));
+            }
+        }
+    }
+    ring.dedup();
+    if ring.len() > 1 && ring.first() == ring.last() {
+        ring.pop();
+    }
+    ring
+}
+
+/// Evaluate a spline at `t` with de Boor's algorithm in homogeneous
+/// coordinates
+fn de_boor(
+    degree: usize,
+    knots: &[f64],
+    control_points: &[Vector3],
+    weight: &dyn Fn(usize) -> f64,
+    t: f64,
+) -> Vector3 {
+    let n = control_points.len();
+    // Knot span k with knots[k] <= t < knots[k + 1], clamped to the valid range
+    let mut k = degree;
+    while k < n - 1 && t >= knots[k + 1] {
+        k += 1;
+    }
+
+    let mut d: Vec<(Vector3, f64)> = (0..=degree)
+        .map(|j| {
+            let i = k - degree + j;
+            let w = weight(i);
+            (control_points[i] * w, w)
+        })
+        .collect();
+    for r in 1..=degree {
+        for j in (r..=degree).rev() {
+            let i = k - degree + j;
+            let denom = knots[i + degree + 1 - r] - knots[i];
+            let alpha = if denom.abs() < 1e-12 { 0.0 } else { (t - knots[i]) / denom };
+            d[j] = (
+                d[j - 1].0 * (1.0 - alpha) + d[j].0 * alpha,
+                d[j - 1].1 * (1.0 - alpha) + d[j].1 * alpha,
+            );
+        }
+    }
+    let (p, w) = d[degree];
+    if w.abs() < 1e-12 {
+        p
+    } else {
+        p / w
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arc_points_within_tolerance() {
+        let tolerance = 0.01;
+        let points = arc_points(Vector2::ZERO, 10.0, 0.0, std::f64::consts::PI, tolerance);
+        for pair in points.windows(2) {
+            let mid = (pair[0] + pair[1]) / 2.0;
+            assert!(10.0 - mid.length() <= tolerance + 1e-9);
+        }
+        assert!((points.last().unwrap().x + 10.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_bulge_semicircle() {
+        // Bulge 1 is a half circle, counter-clockwise from p0 to p1
+        let points = bulge_points(Vector2::new(0.0, 0.0), Vector2::new(2.0, 0.0), 1.0, 0.001);
+        assert_eq!(*points.last().unwrap(), Vector2::new(2.0, 0.0));
+        let lowest = points.iter().map(|p| p.y).fold(f64::MAX, f64::min);
+        assert!((lowest + 1.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_closed_bulge_polyline_adds_closing_arc() {
+        let square = [(Vector2::new(0.0, 0.0), 0.0), (Vector2::new(1.0, 0.0), 0.0), (Vector2::new(1.0, 1.0), 1.0)];
+        let points = bulge_polyline(&square, true, 0.01);
+        assert!(points.len() > 4);
+        assert_ne!(points.last(), points.first());
+    }
+
+    #[test]
+    fn test_spline_interpolates_clamped_ends() {
+        let control = [Vector3::new(0.0, 0.0, 0.0), Vector3::new(1.0, 2.0, 0.0), Vector3::new(3.0, 2.0, 0.0), Vector3::new(4.0, 0.0, 0.0)];
+        let knots = [0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0];
+        let points = spline_points(3, &knots, &control, &[], 0.01);
+        assert_eq!(points[0], control[0]);
+        assert!(points.last().unwrap().distance(&control[3]) < 1e-9);
+        // Symmetric curve peaks at t = 0.5 with y = 1.5
+        let peak = points.iter().map(|p| p.y).fold(0.0, f64::max);
+        assert!((peak - 1.5).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_ellipse_quarter() {
+        let points = ellipse_points(Vector3::ZERO, Vector3::new(2.0, 0.0, 0.0), Vector3::UNIT_Z, 0.5, 0.0, std::f64::consts::FRAC_PI_2, 0.01);
+        assert!(points[0].distance(&Vector3::new(2.0, 0.0, 0.0)) < 1e-9);
+        assert!(points.last().unwrap().distance(&Vector3::new(0.0, 1.0, 0.0)) < 1e-9);
+    }
+}
@@ -0,0 +1,378 @@
+//! Object snap points
+//!
+//! [`EntityType::snap_points`] lists the points an editor snaps to on an
+//! entity, each typed with its [`ObjectSnapType`]: end points, midpoints,
+//! centers, quadrants, nodes and insertion points, all in WCS. A snap
+//! point also makes the [`OsnapPointRef`] that attaches a dimension point
+//! to the entity.
+
+use std::f64::consts::{FRAC_PI_2, TAU};
+
+use crate::entities::{AttributeEntity, Dimension, EntityType, Text, TextHorizontalAlignment, TextVerticalAlignment};
+use crate::entities::{HorizontalAlignment, VerticalAlignment};
+use crate::measure::spline_curve;
+use crate::objects::{ObjectSnapType, OsnapPointRef};
+use crate::types::geometry::ocs_matrix;
+use crate::types::{Handle, Vector2, Vector3};
+
+/// A point an editor snaps to
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SnapPoint {
+    /// What the point is on its entity
+    pub kind: ObjectSnapType,
+    /// The point in WCS
+    pub point: Vector3,
+}
+
+impl SnapPoint {
+    /// Create a snap point
+    pub fn new(kind: ObjectSnapType, point: Vector3) -> Self {
+        SnapPoint { kind, point }
+    }
+
+    /// Reference attaching a dimension point to this point of `entity`
+    pub fn reference(&self, entity: Handle) -> OsnapPointRef {
+        OsnapPointRef::new(self.kind, entity, self.point)
+    }
+}
+
+impl EntityType {
+    /// The object snap points of the entity, in WCS.
+    ///
+    /// Curves give their end points and the midpoints of their segments,
+    /// circular and elliptical shapes their centers and the quadrants on
+    /// them, points and dimension definition points are nodes, and text,
+    /// block references and other placed objects give their insertion
+    /// point. Entities without such points (hatches, solids, unknown
+    /// entities) give none.
+    pub fn snap_points(&self) -> Vec<SnapPoint> {
+        use ObjectSnapType::{Center, Endpoint, Insertion, Midpoint, Node};
+        let mut snaps = Snaps::default();
+        match self {
+            EntityType::Point(point) => snaps.push(Node, point.location),
+            EntityType::Line(line) => {
+                snaps.push(Endpoint, line.start);
+                snaps.push(Endpoint, line.end);
+                snaps.push(Midpoint, line.midpoint());
+            }
+            EntityType::Circle(circle) => {
+                let ocs = ocs_matrix(circle.normal);
+                snaps.push(Center, ocs.transform_point(circle.center));
+                for angle in [0.0, FRAC_PI_2, 2.0 * FRAC_PI_2, 3.0 * FRAC_PI_2] {
+                    let point = circle.center + Vector3::new(angle.cos(), angle.sin(), 0.0) * circle.radius;
+                    snaps.push(ObjectSnapType::Quadrant, ocs.transform_point(point));
+                }
+            }
+            EntityType::Arc(arc) => {
+                let ocs = ocs_matrix(arc.normal);
+                snaps.push(Endpoint, ocs.transform_point(arc.start_point()));
+                snaps.push(Endpoint, ocs.transform_point(arc.end_point()));
+                snaps.push(Midpoint, ocs.transform_point(arc.midpoint()));
+                snaps.push(Center, ocs.transform_point(arc.center));
+                for angle in quadrant_angles(arc.start_angle, arc.sweep_angle()) {
+                    let point = arc.center + Vector3::new(angle.cos(), angle.sin(), 0.0) * arc.radius;
+                    snaps.push(ObjectSnapType::Quadrant, ocs.transform_point(point));
+                }
+            }
+            EntityType::Ellipse(ellipse) => {
+                let minor = ellipse.normal.normalize().cross(&ellipse.major_axis) * ellipse.minor_axis_ratio;
+                let at = |t: f64| ellipse.center + ellipse.major_axis * t.cos() + minor * t.sin();
+                let start = ellipse.start_parameter;
+                let sweep = (ellipse.end_parameter - start).rem_euclid(TAU);
+                snaps.push(Center, ellipse.center);
+                if ellipse.is_full() {
+                    snaps.extend(quadrant_angles(0.0, TAU).map(|t| SnapPoint::new(ObjectSnapType::Quadrant, at(t))));
+                } else {
+                    snaps.push(Endpoint, at(start));
+                    snaps.push(Endpoint, at(start + sweep));
+                    snaps.push(Midpoint, at(start + sweep / 2.0));
+                    snaps.extend(quadrant_angles(start, sweep).map(|t| SnapPoint::new(ObjectSnapType::Quadrant, at(t))));
+                }
+            }
+            EntityType::LwPolyline(poly) => {
+                let vertices: Vec<(Vector2, f64)> = poly.vertices.iter().map(|v| (v.location, v.bulge)).collect();
+                snaps.bulge_polyline(&vertices, poly.is_closed, poly.elevation, poly.normal);
+            }
+            EntityType::Polyline2D(poly) => {
+                let vertices: Vec<(Vector2, f64)> = poly
+                    .vertices
+                    .iter()
+                    .filter(|v| v.flags.bits() & 16 == 0)
+                    .map(|v| (Vector2::new(v.location.x, v.location.y), v.bulge))
+                    .collect();
+                snaps.bulge_polyline(&vertices, poly.is_closed(), poly.elevation, poly.normal);
+            }
+            EntityType::Polyline(poly) => {
+                let points: Vec<Vector3> =
+                    poly.vertices.iter().filter(|v| v.flags.bits() & 16 == 0).map(|v| v.location).collect();
+                snaps.polyline(&points, poly.is_closed());
+            }
+            EntityType::Polyline3D(poly) => {
+                let points: Vec<Vector3> =
+                    poly.vertices.iter().filter(|v| v.flags & 16 == 0).map(|v| v.position).collect();
+                snaps.polyline(&points, poly.is_closed());
+            }
+            EntityType::Spline(spline) => {
+                let points = spline_curve(spline);
+                if let (Some(&first), Some(&last)) = (points.first(), points.last()) {
+                    snaps.push(Endpoint, first);
+                    if !spline.flags.closed {
+                        snaps.push(Endpoint, last);
+                    }
+                }
+            }
+            EntityType::Text(text) => {
+                snaps.push(Insertion, ocs_matrix(text.normal).transform_point(text_point(text)));
+            }
+            EntityType::AttributeDefinition(attdef) => {
+                let point = attribute_point(
+                    attdef.horizontal_alignment,
+                    attdef.vertical_alignment,
+                    attdef.insertion_point,
+                    attdef.alignment_point,
+                );
+                snaps.push(Insertion, ocs_matrix(attdef.normal).transform_point(point));
+            }
+            EntityType::AttributeEntity(attribute) => snaps.attribute(attribute),
+            EntityType::MText(mtext) => snaps.push(Insertion, mtext.insertion_point),
+            EntityType::Insert(insert) => {
+                snaps.push(Insertion, ocs_matrix(insert.normal).transform_point(insert.insert_point));
+                for attribute in &insert.attributes {
+                    snaps.attribute(attribute);
+                }
+            }
+            EntityType::Shape(shape) => {
+                snaps.push(Insertion, ocs_matrix(shape.normal).transform_point(shape.insertion_point));
+            }
+            EntityType::Tolerance(tolerance) => snaps.push(Insertion, tolerance.insertion_point),
+            EntityType::Table(table) => snaps.push(Insertion, table.insertion_point),
+            EntityType::Underlay(underlay) => snaps.push(Insertion, underlay.insertion_point),
+            EntityType::Dimension(dimension) => {
+                let base = dimension.base();
+                for point in definition_points(dimension) {
+                    snaps.push(Node, point);
+                }
+                snaps.push(Insertion, ocs_matrix(base.normal).transform_point(base.text_middle_point));
+            }
+            EntityType::Face3D(face) => snaps.polyline(&face.corners(), true),
+            EntityType::Solid(solid) => {
+                let ocs = ocs_matrix(solid.normal);
+                for corner in solid.corners() {
+                    snaps.push(Endpoint, ocs.transform_point(corner));
+                }
+            }
+            EntityType::Ray(ray) => snaps.push(Endpoint, ray.base_point),
+            // The root point of a construction line snaps as its midpoint
+            EntityType::XLine(xline) => snaps.push(Midpoint, xline.base_point),
+            EntityType::Leader(leader) => snaps.polyline(&leader.vertices, false),
+            EntityType::MultiLeader(multileader) => {
+                for root in &multileader.context.leader_roots {
+                    for line in &root.lines {
+                        let mut points = line.points.clone();
+                        points.push(root.connection_point);
+                        snaps.polyline(&points, false);
+                    }
+                }
+            }
+            EntityType::MLine(mline) => {
+                let points: Vec<Vector3> = mline.vertices.iter().map(|v| v.position).collect();
+                snaps.polyline(&points, mline.is_closed());
+            }
+            EntityType::RasterImage(image) => snaps.polyline(&image.corners(), true),
+            EntityType::Wipeout(wipeout) => snaps.polyline(&wipeout.corners(), true),
+            EntityType::Viewport(viewport) => {
+                let (dx, dy) = (viewport.width / 2.0, viewport.height / 2.0);
+                for (x, y) in [(-dx, -dy), (dx, -dy), (dx, dy), (-dx, dy)] {
+                    snaps.push(Endpoint, viewport.center + Vector3::new(x, y, 0.0));
+                }
+            }
+            EntityType::Mesh(mesh) => {
+                for &vertex in &mesh.vertices {
+                    snaps.push(Endpoint, vertex);
+                }
+            }
+            EntityType::PolyfaceMesh(mesh) => {
+                for vertex in &mesh.vertices {
+                    snaps.push(Endpoint, vertex.location);
+                }
+            }
+            EntityType::PolygonMesh(mesh) => {
+                for vertex in &mesh.vertices {
+                    snaps.push(Endpoint, vertex.location);
+                }
+            }
+            _ => {}
+        }
+        snaps.0
+    }
+}
+
+#[derive(Default)]
+struct Snaps(Vec<SnapPoint>);
+
+impl Snaps {
+    fn push(&mut self, kind: ObjectSnapType, point: Vector3) {
+        self.0.push(SnapPoint::new(kind, point));
+    }
+
+    fn extend(&mut self, points: impl Iterator<Item = SnapPoint>) {
+        self.0.extend(points);
+    }
+
+    /// End points and segment midpoints of straight segments
+    fn polyline(&mut self, points: &[Vector3], closed: bool) {
+        for &point in points {
+            self.push(ObjectSnapType::Endpoint, point);
+        }
+        let n = points.len();
+        let segments = if closed && n > 2 { n } else { n.saturating_sub(1) };
+        for i in 0..segments {
+            self.push(ObjectSnapType::Midpoint, (points[i] + points[(i + 1) % n]) * 0.5);
+        }
+    }
+
+    /// End points, segment midpoints and arc segment centers of a
+    /// polyline with bulges, in the OCS of `normal`
+    fn bulge_polyline(&mut self, vertices: &[(Vector2, f64)], closed: bool, elevation: f64, normal: Vector3) {
+        let ocs = ocs_matrix(normal);
+        let wcs = |p: Vector2| ocs.transform_point(Vector3::new(p.x, p.y, elevation));
+        for &(point, _) in vertices {
+            self.push(ObjectSnapType::Endpoint, wcs(point));
+        }
+        let n = vertices.len();
+        let segments = if closed && n > 2 { n } else { n.saturating_sub(1) };
+        for i in 0..segments {
+            let ((start, bulge), (end, _)) = (vertices[i], vertices[(i + 1) % n]);
+            let chord = end - start;
+            let middle = (start + end) * 0.5;
+            // Right of the chord, towards the arc of a positive bulge
+            let right = Vector2::new(chord.y, -chord.x);
+            self.push(ObjectSnapType::Midpoint, wcs(middle + right * (bulge / 2.0)));
+            if bulge.abs() > 1e-12 && chord.length() > 0.0 {
+                let apothem = (bulge * bulge - 1.0) / (4.0 * bulge);
+                self.push(ObjectSnapType::Center, wcs(middle + right * apothem));
+            }
+        }
+    }
+
+    fn attribute(&mut self, attribute: &AttributeEntity) {
+        let point = attribute_point(
+            attribute.horizontal_alignment,
+            attribute.vertical_alignment,
+            attribute.insertion_point,
+            attribute.alignment_point,
+        );
+        self.push(ObjectSnapType::Insertion, ocs_matrix(attribute.normal).transform_point(point));
+    }
+}
+
+/// Angles of the quadrant points on an arc from `start` sweeping `sweep`
+/// counterclockwise
+fn quadrant_angles(start: f64, sweep: f64) -> impl Iterator<Item = f64> {
+    (0..4)
+        .map(|quarter| quarter as f64 * FRAC_PI_2)
+        .filter(move |&angle| (angle - start).rem_euclid(TAU) <= sweep + 1e-12 || sweep >= TAU)
+}
+
+/// The justification point of a text, in OCS: the alignment point unless
+/// the text is left aligned on its baseline or fitted between its points
+fn text_point(text: &Text) -> Vector3 {
+    let by_insertion = matches!(
+        (text.horizontal_alignment, text.vertical_alignment),
+        (TextHorizontalAlignment::Left, TextVerticalAlignment::Baseline)
+            | (TextHorizontalAlignment::Aligned | TextHorizontalAlignment::Fit, _)
+    );
+    match text.alignment_point {
+        Some(point) if !by_insertion => point,
+        _ => text.insertion_point,
+    }
+}
+
+fn attribute_point(
+    horizontal: HorizontalAlignment,
+    vertical: VerticalAlignment,
+    insertion: Vector3,
+    alignment: Vector3,
+) -> Vector3 {
+    match (horizontal, vertical) {
+        (HorizontalAlignment::Left, VerticalAlignment::Baseline)
+        | (HorizontalAlignment::Aligned | HorizontalAlignment::Fit, _) => insertion,
+        _ => alignment,
+    }
+}
+
+/// Definition points of a dimension, in WCS
+fn definition_points(dimension: &Dimension) -> Vec<Vector3> {
+    match dimension {
+        Dimension::Aligned(d) => vec![d.first_point, d.second_point, d.definition_point],
+        Dimension::Linear(d) => vec![d.first_point, d.second_point, d.definition_point],
+        Dimension::Radius(d) => vec![d.angle_vertex, d.definition_point],
+        Dimension::Diameter(d) => vec![d.angle_vertex, d.definition_point],
+        Dimension::Angular2Ln(d) => {
+            vec![d.first_point, d.second_point, d.angle_vertex, d.definition_point, d.dimension_arc]
+        }
+        Dimension::Angular3Pt(d) => vec![d.first_point, d.second_point, d.angle_vertex, d.definition_point],
+        Dimension::Ordinate(d) => vec![d.feature_location, d.leader_endpoint, d.definition_point],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::{Arc, Circle, Line, LwPolyline, LwVertex};
+    use ObjectSnapType::*;
+
+    fn points_of(entity: &EntityType, kind: ObjectSnapType) -> Vec<Vector3> {
+        entity.snap_points().into_iter().filter(|s| s.kind == kind).map(|s| s.point).collect()
+    }
+
+    fn assert_near(actual: &[Vector3], expected: &[Vector3]) {
+        assert_eq!(actual.len(), expected.len(), "{actual:?}");
+        for (a, e) in actual.iter().zip(expected) {
+            assert!(a.distance(e) < 1e-9, "{a:?} != {e:?}");
+        }
+    }
+
+    #[test]
+    fn test_line_and_arc() {
+        let line = EntityType::Line(Line::from_coords(0.0, 0.0, 0.0, 4.0, 2.0, 0.0));
+        assert_near(&points_of(&line, Endpoint), &[Vector3::ZERO, Vector3::new(4.0, 2.0, 0.0)]);
+        assert_near(&points_of(&line, Midpoint), &[Vector3::new(2.0, 1.0, 0.0)]);
+
+        // Quarter arc from 45° to 135°: one quadrant, at the top
+        let arc = EntityType::Arc(Arc::from_coords(1.0, 1.0, 0.0, 2.0, FRAC_PI_2 / 2.0, 3.0 * FRAC_PI_2 / 2.0));
+        assert_near(&points_of(&arc, Center), &[Vector3::new(1.0, 1.0, 0.0)]);
+        assert_near(&points_of(&arc, Quadrant), &[Vector3::new(1.0, 3.0, 0.0)]);
+        assert_near(&points_of(&arc, Midpoint), &[Vector3::new(1.0, 3.0, 0.0)]);
+    }
+
+    #[test]
+    fn test_circle_in_ocs() {
+        let mut circle = Circle::from_coords(0.0, 0.0, 5.0, 1.0);
+        circle.normal = Vector3::new(0.0, 0.0, -1.0);
+        let circle = EntityType::Circle(circle);
+        assert_near(&points_of(&circle, Center), &[Vector3::new(0.0, 0.0, -5.0)]);
+        assert_eq!(points_of(&circle, Quadrant)[0], Vector3::new(-1.0, 0.0, -5.0));
+    }
+
+    #[test]
+    fn test_bulged_polyline() {
+        let mut poly = LwPolyline::new();
+        poly.vertices.push(LwVertex::with_bulge(Vector2::new(0.0, 0.0), -1.0));
+        poly.vertices.push(LwVertex::new(Vector2::new(2.0, 0.0)));
+        poly.vertices.push(LwVertex::new(Vector2::new(2.0, -3.0)));
+        poly.elevation = 1.0;
+        let poly = EntityType::LwPolyline(poly);
+        assert_eq!(points_of(&poly, Endpoint).len(), 3);
+        assert_near(
+            &points_of(&poly, Midpoint),
+            &[Vector3::new(1.0, 1.0, 1.0), Vector3::new(2.0, -1.5, 1.0)],
+        );
+        assert_near(&points_of(&poly, Center), &[Vector3::new(1.0, 0.0, 1.0)]);
+
+        let reference = poly.snap_points()[0].reference(Handle::new(0x2A));
+        assert_eq!(reference.osnap_type, Endpoint);
+        assert_eq!(reference.object(), Some(Handle::new(0x2A)));
+    }
+}
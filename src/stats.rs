@@ -0,0 +1,351 @@
+//! Document statistics
+//!
+//! [`CadDocument::stats`] counts what a document holds: entities by type
+//! and layer, the vertices of polylines, splines, meshes and boundaries,
+//! objects by type, and the range of handles in use. It also estimates
+//! the memory the entities and objects take, to find what makes a drawing
+//! large. The estimate counts the inline size of each item and its main
+//! heap data (vertex lists, strings, ACIS and OLE data, XDATA); allocator
+//! overhead and the document's indexes are left out.
+//!
+//! ```rust,ignore
+//! let stats = doc.stats();
+//! println!("{stats}");
+//! ```
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::mem::size_of;
+
+use crate::document::CadDocument;
+use crate::entities::{BoundaryEdge, EntityType};
+use crate::objects::ObjectType;
+use crate::types::Handle;
+
+/// Totals of one entity type in [`DocumentStats`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TypeStats {
+    /// Number of entities
+    pub count: usize,
+    /// Vertices they hold, see [`EntityType::vertex_count`]
+    pub vertices: usize,
+    /// Approximate memory they take, in bytes
+    pub memory: usize,
+}
+
+/// Handles in use in [`DocumentStats`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HandleStats {
+    /// Number of distinct non-null handles of entities, objects and table
+    /// entries
+    pub count: usize,
+    /// Lowest handle in use
+    pub lowest: Option<Handle>,
+    /// Highest handle in use
+    pub highest: Option<Handle>,
+    /// Next handle the document hands out, written as `$HANDSEED`
+    pub next: u64,
+}
+
+/// Result of [`CadDocument::stats`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DocumentStats {
+    /// Number of entities in model space, layouts and blocks
+    pub entity_count: usize,
+    /// Totals by entity type name
+    pub by_type: BTreeMap<String, TypeStats>,
+    /// Number of entities by layer name
+    pub by_layer: BTreeMap<String, usize>,
+    /// Vertices of all entities
+    pub vertex_count: usize,
+    /// Approximate memory of all entities, in bytes
+    pub entity_memory: usize,
+    /// Number of objects
+    pub object_count: usize,
+    /// Number of objects by type name
+    pub objects_by_type: BTreeMap<String, usize>,
+    /// Approximate memory of all objects, in bytes
+    pub object_memory: usize,
+    /// Number of entries in the symbol tables
+    pub table_entry_count: usize,
+    /// Handles in use
+    pub handles: HandleStats,
+}
+
+impl DocumentStats {
+    /// Approximate memory of entities and objects, in bytes
+    pub fn memory(&self) -> usize {
+        self.entity_memory + self.object_memory
+    }
+}
+
+impl fmt::Display for DocumentStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} entities, {} vertices, {} objects, {} table entries, about {} KiB",
+            self.entity_count,
+            self.vertex_count,
+            self.object_count,
+            self.table_entry_count,
+            self.memory().div_ceil(1024)
+        )?;
+        let mut types: Vec<(&String, &TypeStats)> = self.by_type.iter().collect();
+        types.sort_by(|a, b| b.1.memory.cmp(&a.1.memory).then(a.0.cmp(b.0)));
+        for (name, stats) in types {
+            writeln!(
+                f,
+                "  {name:<20} {:>8} entities {:>10} vertices {:>10} KiB",
+                stats.count,
+                stats.vertices,
+                stats.memory.div_ceil(1024)
+            )?;
+        }
+        let range = |h: Option<Handle>| h.map_or("-".to_string(), |h| format!("{:X}", h.value()));
+        write!(
+            f,
+            "{} handles from {} to {}, next {:X}",
+            self.handles.count,
+            range(self.handles.lowest),
+            range(self.handles.highest),
+            self.handles.next
+        )
+    }
+}
+
+impl CadDocument {
+    /// Count the entities, vertices, objects and handles of the document
+    /// and estimate the memory they take
+    pub fn stats(&self) -> DocumentStats {
+        let mut stats = DocumentStats::default();
+        let mut handles: HashSet<Handle> = HashSet::new();
+
+        for entity in self.all_entities() {
+            let vertices = entity.vertex_count();
+            let memory = entity.approximate_size();
+            let totals = stats.by_type.entry(entity.as_entity().entity_type().to_string()).or_default();
+            totals.count += 1;
+            totals.vertices += vertices;
+            totals.memory += memory;
+            *stats.by_layer.entry(entity.common().layer.clone()).or_default() += 1;
+            stats.entity_count += 1;
+            stats.vertex_count += vertices;
+            stats.entity_memory += memory;
+            handles.insert(entity.common().handle);
+        }
+
+        for object in self.objects.values() {
+            *stats.objects_by_type.entry(object.type_name().to_string()).or_default() += 1;
+            stats.object_count += 1;
+            stats.object_memory += object_size(object);
+            handles.insert(object.handle());
+        }
+
+        let table_handles: Vec<Handle> = self
+            .layers
+            .iter()
+            .map(|e| e.handle)
+            .chain(self.line_types.iter().map(|e| e.handle))
+            .chain(self.text_styles.iter().map(|e| e.handle))
+            .chain(self.block_records.iter().map(|e| e.handle))
+            .chain(self.dim_styles.iter().map(|e| e.handle))
+            .chain(self.app_ids.iter().map(|e| e.handle))
+            .chain(self.views.iter().map(|e| e.handle))
+            .chain(self.vports.iter().map(|e| e.handle))
+            .chain(self.ucss.iter().map(|e| e.handle))
+            .collect();
+        stats.table_entry_count = table_handles.len();
+        handles.extend(table_handles);
+
+        handles.remove(&Handle::NULL);
+        stats.handles = HandleStats {
+            count: handles.len(),
+            lowest: handles.iter().min().copied(),
+            highest: handles.iter().max().copied(),
+            next: self.next_handle(),
+        };
+        stats
+    }
+}
+
+impl EntityType {
+    /// Number of vertices the entity stores: those of polylines, meshes,
+    /// leaders and multilines, the control and fit points of splines, the
+    /// points of hatch boundaries and the clip boundary of images, wipeouts
+    /// and underlays. Entities defined by a fixed set of points count 0.
+    pub fn vertex_count(&self) -> usize {
+        match self {
+            EntityType::Polyline(e) => e.vertices.len(),
+            EntityType::Polyline2D(e) => e.vertices.len(),
+            EntityType::Polyline3D(e) => e.vertices.len(),
+            EntityType::LwPolyline(e) => e.vertices.len(),
+            EntityType::Spline(e) => e.control_points.len() + e.fit_points.len(),
+            EntityType::Hatch(hatch) => hatch
+                .paths
+                .iter()
+                .flat_map(|path| &path.edges)
+                .map(|edge| match edge {
+                    BoundaryEdge::Line(_) => 2,
+                    BoundaryEdge::Polyline(polyline) => polyline.vertices.len(),
+                    BoundaryEdge::Spline(spline) => spline.control_points.len() + spline.fit_points.len(),
+                    _ => 0,
+                })
+                .sum(),
+            EntityType::Leader(e) => e.vertices.len(),
+            EntityType::MultiLeader(e) => e
+                .context
+                .leader_roots
+                .iter()
+                .flat_map(|root| &root.lines)
+                .map(|line| line.points.len())
+                .sum(),
+            EntityType::MLine(e) => e.vertices.len(),
+            EntityType::Mesh(e) => e.vertices.len(),
+            EntityType::PolyfaceMesh(e) => e.vertices.len(),
+            EntityType::PolygonMesh(e) => e.vertices.len(),
+            EntityType::RasterImage(e) => e.clip_boundary.vertices.len(),
+            EntityType::Wipeout(e) => e.clip_boundary_vertices.len(),
+            EntityType::Underlay(e) => e.clip_boundary_vertices.len(),
+            _ => 0,
+        }
+    }
+
+    /// Approximate memory the entity takes, in bytes: its inline size and
+    /// boxed data, its vertices and its larger strings and binary data
+    pub fn approximate_size(&self) -> usize {
+        let common = self.common();
+        let xdata: usize = common
+            .extended_data
+            .records()
+            .iter()
+            .map(|r| r.application_name.len() + size_of_val(&r.values[..]))
+            .sum();
+        let base = size_of::<EntityType>() + common.layer.len() + vec_size(&common.reactors) + xdata;
+        base + match self {
+            EntityType::Polyline(e) => vec_size(&e.vertices),
+            EntityType::Polyline2D(e) => vec_size(&e.vertices),
+            EntityType::Polyline3D(e) => {
+                vec_size(&e.vertices) + e.vertices.iter().map(|v| v.layer.len()).sum::<usize>()
+            }
+            EntityType::LwPolyline(e) => vec_size(&e.vertices),
+            EntityType::Text(e) => e.value.len(),
+            EntityType::MText(e) => e.value.len(),
+            EntityType::Spline(e) => {
+                vec_size(&e.knots) + vec_size(&e.control_points) + vec_size(&e.weights) + vec_size(&e.fit_points)
+            }
+            EntityType::Dimension(e) => size_of_val(e.as_ref()),
+            EntityType::Hatch(e) => {
+                let edges: usize = e
+                    .paths
+                    .iter()
+                    .map(|path| {
+                        vec_size(&path.edges)
+                            + path
+                                .edges
+                                .iter()
+                                .map(|edge| match edge {
+                                    BoundaryEdge::Polyline(p) => vec_size(&p.vertices),
+                                    BoundaryEdge::Spline(s) => {
+                                        vec_size(&s.knots) + vec_size(&s.control_points) + vec_size(&s.fit_points)
+                                    }
+                                    _ => 0,
+                                })
+                                .sum::<usize>()
+                    })
+                    .sum();
+                size_of_val(e.as_ref()) + vec_size(&e.paths) + edges
+            }
+            EntityType::Insert(e) => {
+                e.block_name.len()
+                    + vec_size(&e.attributes)
+                    + e.attributes.iter().map(|a| a.tag.len() + a.value.len()).sum::<usize>()
+            }
+            EntityType::Viewport(e) => size_of_val(e.as_ref()),
+            EntityType::AttributeDefinition(e) => size_of_val(e.as_ref()) + e.tag.len() + e.prompt.len() + e.default_value.len(),
+            EntityType::AttributeEntity(e) => size_of_val(e.as_ref()) + e.tag.len() + e.value.len(),
+            EntityType::Leader(e) => size_of_val(e.as_ref()) + vec_size(&e.vertices),
+            EntityType::MultiLeader(e) => size_of_val(e.as_ref()) + self.vertex_count() * size_of::<crate::types::Vector3>(),
+            EntityType::MLine(e) => vec_size(&e.vertices),
+            EntityType::Mesh(e) => {
+                vec_size(&e.vertices)
+                    + vec_size(&e.faces)
+                    + e.faces.iter().map(|f| vec_size(&f.vertices)).sum::<usize>()
+                    + vec_size(&e.edges)
+            }
+            EntityType::RasterImage(e) => size_of_val(e.as_ref()) + vec_size(&e.clip_boundary.vertices),
+            EntityType::Solid3D(e) => e.acis_data.sat_data.len() + e.acis_data.sab_data.len() + vec_size(&e.wires),
+            EntityType::Region(e) => e.acis_data.sat_data.len() + e.acis_data.sab_data.len() + vec_size(&e.wires),
+            EntityType::Body(e) => e.acis_data.sat_data.len() + e.acis_data.sab_data.len() + vec_size(&e.wires),
+            EntityType::Table(e) => size_of_val(e.as_ref()) + vec_size(&e.rows) + vec_size(&e.columns),
+            EntityType::PolyfaceMesh(e) => vec_size(&e.vertices) + vec_size(&e.faces),
+            EntityType::PolygonMesh(e) => vec_size(&e.vertices),
+            EntityType::Wipeout(e) => vec_size(&e.clip_boundary_vertices),
+            EntityType::Underlay(e) => vec_size(&e.clip_boundary_vertices),
+            EntityType::Ole2Frame(e) => e.binary_data.len(),
+            EntityType::Unknown(e) => e.raw_pairs.iter().map(|(_, value)| size_of::<(i32, String)>() + value.len()).sum(),
+            _ => 0,
+        }
+    }
+}
+
+/// Approximate memory of an object: its inline size and the data of
+/// XRecords and unknown objects
+fn object_size(object: &ObjectType) -> usize {
+    size_of::<ObjectType>()
+        + match object {
+            ObjectType::XRecord(xrecord) => vec_size(&xrecord.entries),
+            ObjectType::Unknown { raw_pairs, .. } => {
+                raw_pairs.iter().map(|(_, value)| size_of::<(i32, String)>() + value.len()).sum()
+            }
+            _ => 0,
+        }
+}
+
+fn vec_size<T>(items: &[T]) -> usize {
+    size_of_val(items)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::{Circle, Line, LwPolyline, Spline};
+    use crate::types::{Vector2, Vector3};
+
+    #[test]
+    fn test_counts_and_vertices() {
+        let mut doc = CadDocument::new();
+        let mut wall = Line::from_coords(0.0, 0.0, 0.0, 1.0, 0.0, 0.0);
+        wall.common.layer = "WALLS".to_string();
+        doc.add_entity(EntityType::Line(wall)).unwrap();
+        doc.add_entity(EntityType::Circle(Circle::from_coords(0.0, 0.0, 0.0, 1.0))).unwrap();
+        let outline = LwPolyline::from_points((0..100).map(|i| Vector2::new(i as f64, 0.0)).collect());
+        doc.add_entity(EntityType::LwPolyline(outline)).unwrap();
+        let mut spline = Spline::new();
+        spline.control_points = vec![Vector3::ZERO; 4];
+        spline.fit_points = vec![Vector3::ZERO; 2];
+        doc.add_entity(EntityType::Spline(Box::new(spline))).unwrap();
+
+        let stats = doc.stats();
+        assert_eq!(stats.entity_count, 4);
+        assert_eq!(stats.by_type["LINE"].count, 1);
+        assert_eq!(stats.by_type["LWPOLYLINE"].vertices, 100);
+        assert_eq!(stats.vertex_count, 106);
+        assert_eq!(stats.by_layer["WALLS"], 1);
+        assert_eq!(stats.by_layer["0"], 3);
+        assert!(stats.by_type["LWPOLYLINE"].memory > stats.by_type["LINE"].memory + 99 * size_of::<Vector2>());
+        assert!(stats.table_entry_count > 0);
+        assert!(stats.object_count > 0);
+        assert!(stats.memory() > stats.entity_memory);
+    }
+
+    #[test]
+    fn test_handle_range() {
+        let mut doc = CadDocument::new();
+        let line = doc.add_entity(EntityType::Line(Line::new())).unwrap();
+        let stats = doc.stats();
+        assert!(stats.handles.highest.unwrap() >= line);
+        assert!(stats.handles.lowest.unwrap() <= line);
+        assert!(stats.handles.next > stats.handles.highest.unwrap().value());
+        assert!(stats.to_string().contains("1 entities"));
+    }
+}
@@ -2,10 +2,15 @@
 
 use super::TableEntry;
 use crate::entities::EntityType;
+use crate::error::Result;
 use crate::types::Handle;
+use crate::xdata::{ExtendedData, XDataValue};
+use std::fmt;
+use std::sync::Arc;
 
 /// Block record flags
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BlockFlags {
     /// Block is anonymous
     pub anonymous: bool,
@@ -38,8 +43,37 @@ impl Default for BlockFlags {
     }
 }
 
+/// Loader for block entities that have not been decoded yet.
+///
+/// Set by readers running in a lazy mode (see
+/// [`DwgReader::with_lazy_blocks`](crate::io::dwg::DwgReader::with_lazy_blocks));
+/// invoked by [`BlockRecord::load_entities`].
+#[derive(Clone)]
+pub struct PendingEntities(Arc<dyn Fn() -> Result<Vec<EntityType>> + Send + Sync>);
+
+impl PendingEntities {
+    /// Wrap a function that decodes the block's entities
+    pub fn new<F>(load: F) -> Self
+    where
+        F: Fn() -> Result<Vec<EntityType>> + Send + Sync + 'static,
+    {
+        PendingEntities(Arc::new(load))
+    }
+
+    fn load(&self) -> Result<Vec<EntityType>> {
+        (self.0)()
+    }
+}
+
+impl fmt::Debug for PendingEntities {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("PendingEntities")
+    }
+}
+
 /// A block record table entry
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BlockRecord {
     /// Unique handle for the block record table entry
     pub handle: Handle,
@@ -59,8 +93,21 @@ pub struct BlockRecord {
     pub explodable: bool,
     /// Can scale uniformly
     pub scale_uniformly: bool,
-    /// Entities owned by this block
+    /// Extension dictionary handle ({ACAD_XDICTIONARY}); model space keeps
+    /// the drawing's GEODATA here
+    pub xdictionary_handle: Option<Handle>,
+    /// Extended data (XDATA); an anonymous block made from a dynamic block
+    /// points back to it here, see [`Self::dynamic_block_handle`]
+    pub extended_data: ExtendedData,
+    /// Entities owned by this block.
+    ///
+    /// Empty until [`Self::load_entities`] is called if the block was read
+    /// lazily.
     pub entities: Vec<EntityType>,
+    /// Entities still to be decoded, for lazily read blocks; not
+    /// serialized, so load them first
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pending: Option<PendingEntities>,
 }
 
 impl BlockRecord {
@@ -76,7 +123,10 @@ impl BlockRecord {
             units: 0,
             explodable: true,
             scale_uniformly: false,
+            xdictionary_handle: None,
+            extended_data: ExtendedData::new(),
             entities: Vec::new(),
+            pending: None,
         }
     }
 
@@ -92,7 +142,10 @@ impl BlockRecord {
             units: 0,
             explodable: true,
             scale_uniformly: false,
+            xdictionary_handle: None,
+            extended_data: ExtendedData::new(),
             entities: Vec::new(),
+            pending: None,
         }
     }
 
@@ -108,7 +161,10 @@ impl BlockRecord {
             units: 0,
             explodable: true,
             scale_uniformly: false,
+            xdictionary_handle: None,
+            extended_data: ExtendedData::new(),
             entities: Vec::new(),
+            pending: None,
         }
     }
 
@@ -131,6 +187,53 @@ impl BlockRecord {
     pub fn is_anonymous(&self) -> bool {
         self.flags.anonymous || self.name.starts_with('*')
     }
+
+    /// Check if this is a dynamic block definition: one whose extension
+    /// dictionary holds the `ACAD_ENHANCEDBLOCK` evaluation graph
+    pub fn is_dynamic(&self, document: &crate::document::CadDocument) -> bool {
+        use crate::objects::ObjectType;
+        matches!(
+            self.xdictionary_handle.and_then(|h| document.objects.get(&h)),
+            Some(ObjectType::Dictionary(dictionary)) if dictionary.get("ACAD_ENHANCEDBLOCK").is_some()
+        )
+    }
+
+    /// Handle of the dynamic block this anonymous block represents, from
+    /// its `AcDbBlockRepBTag` extended data
+    pub fn dynamic_block_handle(&self) -> Option<Handle> {
+        self.extended_data
+            .get_record("AcDbBlockRepBTag")?
+            .values
+            .iter()
+            .find_map(|value| match value {
+                XDataValue::Handle(handle) => Some(*handle),
+                _ => None,
+            })
+    }
+
+    /// Check if the block's entities have been decoded
+    pub fn is_loaded(&self) -> bool {
+        self.pending.is_none()
+    }
+
+    /// Defer decoding of the block's entities until [`Self::load_entities`]
+    pub fn set_pending_entities(&mut self, pending: PendingEntities) {
+        self.pending = Some(pending);
+    }
+
+    /// Decode the block's entities if it was read lazily, and return them.
+    ///
+    /// Entities added before loading are kept after the decoded ones. On
+    /// error the block stays unloaded.
+    pub fn load_entities(&mut self) -> Result<&mut Vec<EntityType>> {
+        if let Some(pending) = &self.pending {
+            let mut entities = pending.load()?;
+            entities.append(&mut self.entities);
+            self.entities = entities;
+            self.pending = None;
+        }
+        Ok(&mut self.entities)
+    }
 }
 
 impl TableEntry for BlockRecord {
@@ -181,6 +284,18 @@ mod tests {
         assert!(block.is_standard());
         assert!(!block.is_model_space());
     }
-}
 
+    #[test]
+    fn test_pending_entities_loaded_on_demand() {
+        use crate::entities::Line;
+
+        let mut block = BlockRecord::new("Lazy");
+        block.set_pending_entities(PendingEntities::new(|| Ok(vec![EntityType::Line(Line::new())])));
+        block.entities.push(EntityType::Line(Line::new()));
+        assert!(!block.is_loaded());
 
+        assert_eq!(block.load_entities().unwrap().len(), 2);
+        assert!(block.is_loaded());
+        assert_eq!(block.load_entities().unwrap().len(), 2);
+    }
+}
@@ -1,10 +1,12 @@
 //! Dimension style table entry
 
 use super::TableEntry;
-use crate::types::Handle;
+use crate::document::HeaderVariables;
+use crate::types::{Color, Handle};
 
 /// A dimension style table entry — maps to ACadSharp's DimensionStyle
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DimStyle {
     /// Unique handle
     pub handle: Handle,
@@ -288,6 +290,181 @@ impl DimStyle {
     pub fn standard() -> Self {
         Self::new("Standard")
     }
+
+    /// Create a dimension style holding the header `$DIM*` variables
+    pub fn from_header(name: impl Into<String>, header: &HeaderVariables) -> Self {
+        let mut style = Self::new(name);
+        style.copy_from_header(header);
+        style.dimclrd = header.dim_line_color.approximate_index();
+        style.dimclre = header.dim_ext_line_color.approximate_index();
+        style.dimclrt = header.dim_text_color.approximate_index();
+        style.dimdsep = header.dim_decimal_separator as i16;
+        style
+    }
+
+    /// Copy the style values into the header `$DIM*` variables
+    pub fn apply_to_header(&self, header: &mut HeaderVariables) {
+        self.copy_to_header(header);
+        header.dim_line_color = Color::from_index(self.dimclrd);
+        header.dim_ext_line_color = Color::from_index(self.dimclre);
+        header.dim_text_color = Color::from_index(self.dimclrt);
+        header.dim_decimal_separator = decimal_separator(self.dimdsep);
+    }
+
+    /// Whether the header `$DIM*` variables hold the style values
+    pub fn matches_header(&self, header: &HeaderVariables) -> bool {
+        self.fields_match_header(header)
+            && header.dim_line_color == Color::from_index(self.dimclrd)
+            && header.dim_ext_line_color == Color::from_index(self.dimclre)
+            && header.dim_text_color == Color::from_index(self.dimclrt)
+            && header.dim_decimal_separator == decimal_separator(self.dimdsep)
+    }
+
+    /// Copy the style values into the header `$DIM*` variables that still
+    /// hold the value of `baseline`, keeping those set otherwise
+    pub(crate) fn apply_to_unedited_header(&self, baseline: &DimStyle, header: &mut HeaderVariables) {
+        self.copy_to_unedited_header(baseline, header);
+        let colors = [
+            (&mut header.dim_line_color, baseline.dimclrd, self.dimclrd),
+            (&mut header.dim_ext_line_color, baseline.dimclre, self.dimclre),
+            (&mut header.dim_text_color, baseline.dimclrt, self.dimclrt),
+        ];
+        for (color, old, new) in colors {
+            if *color == Color::from_index(old) {
+                *color = Color::from_index(new);
+            }
+        }
+        if header.dim_decimal_separator == decimal_separator(baseline.dimdsep) {
+            header.dim_decimal_separator = decimal_separator(self.dimdsep);
+        }
+    }
+
+    /// Whether [`Self::apply_to_unedited_header`] would leave the header as
+    /// it is
+    pub(crate) fn matches_unedited_header(&self, baseline: &DimStyle, header: &HeaderVariables) -> bool {
+        let kept = |color: Color, old: i16, new: i16| color != Color::from_index(old) || color == Color::from_index(new);
+        let separator = header.dim_decimal_separator;
+        self.unedited_fields_match_header(baseline, header)
+            && kept(header.dim_line_color, baseline.dimclrd, self.dimclrd)
+            && kept(header.dim_ext_line_color, baseline.dimclre, self.dimclre)
+            && kept(header.dim_text_color, baseline.dimclrt, self.dimclrt)
+            && (separator != decimal_separator(baseline.dimdsep) || separator == decimal_separator(self.dimdsep))
+    }
+}
+
+pub(super) fn decimal_separator(dimdsep: i16) -> char {
+    u8::try_from(dimdsep).map(char::from).unwrap_or('.')
+}
+
+/// Style fields mirrored one-to-one by header variables of the same type
+macro_rules! header_fields {
+    (copy { $($style:ident => $header:ident),* $(,)? } clone { $($style_c:ident => $header_c:ident),* $(,)? }) => {
+        impl DimStyle {
+            fn copy_to_header(&self, header: &mut HeaderVariables) {
+                $(header.$header = self.$style;)*
+                $(header.$header_c = self.$style_c.clone();)*
+            }
+
+            fn copy_from_header(&mut self, header: &HeaderVariables) {
+                $(self.$style = header.$header;)*
+                $(self.$style_c = header.$header_c.clone();)*
+            }
+
+            fn fields_match_header(&self, header: &HeaderVariables) -> bool {
+                true $(&& self.$style == header.$header)* $(&& self.$style_c == header.$header_c)*
+            }
+
+            fn copy_to_unedited_header(&self, baseline: &DimStyle, header: &mut HeaderVariables) {
+                $(if header.$header == baseline.$style {
+                    header.$header = self.$style;
+                })*
+                $(if header.$header_c == baseline.$style_c {
+                    header.$header_c = self.$style_c.clone();
+                })*
+            }
+
+            fn unedited_fields_match_header(&self, baseline: &DimStyle, header: &HeaderVariables) -> bool {
+                true $(&& (header.$header != baseline.$style || header.$header == self.$style))*
+                    $(&& (header.$header_c != baseline.$style_c || header.$header_c == self.$style_c))*
+            }
+        }
+    };
+}
+
+header_fields! {
+    copy {
+        dimscale => dim_scale,
+        dimasz => dim_arrow_size,
+        dimexo => dim_ext_line_offset,
+        dimdli => dim_line_increment,
+        dimexe => dim_ext_line_extension,
+        dimrnd => dim_rounding,
+        dimdle => dim_line_extension,
+        dimtp => dim_tolerance_plus,
+        dimtm => dim_tolerance_minus,
+        dimtxt => dim_text_height,
+        dimcen => dim_center_mark,
+        dimtsz => dim_tick_size,
+        dimaltf => dim_alt_scale,
+        dimlfac => dim_linear_scale,
+        dimtvp => dim_text_vertical_pos,
+        dimtfac => dim_tolerance_scale,
+        dimgap => dim_line_gap,
+        dimaltrnd => dim_alt_rounding,
+        dimtol => dim_tolerance,
+        dimlim => dim_limits,
+        dimtih => dim_text_inside_horizontal,
+        dimtoh => dim_text_outside_horizontal,
+        dimse1 => dim_suppress_ext1,
+        dimse2 => dim_suppress_ext2,
+        dimtad => dim_text_above,
+        dimzin => dim_zero_suppression,
+        dimazin => dim_alt_zero_suppression,
+        dimalt => dim_alternate_units,
+        dimaltd => dim_alt_decimal_places,
+        dimtofl => dim_force_line_inside,
+        dimsah => dim_separate_arrows,
+        dimtix => dim_force_text_inside,
+        dimsoxd => dim_suppress_outside_ext,
+        dimadec => dim_angular_decimal_places,
+        dimdec => dim_decimal_places,
+        dimtdec => dim_tolerance_decimal_places,
+        dimaltu => dim_alt_units_format,
+        dimalttd => dim_alt_tolerance_decimal_places,
+        dimaunit => dim_angular_units,
+        dimfrac => dim_fraction_format,
+        dimlunit => dim_linear_unit_format,
+        dimtmove => dim_text_movement,
+        dimjust => dim_horizontal_justification,
+        dimsd1 => dim_suppress_line1,
+        dimsd2 => dim_suppress_line2,
+        dimtolj => dim_tolerance_justification,
+        dimtzin => dim_tolerance_zero_suppression,
+        dimaltz => dim_alt_tolerance_zero_suppression,
+        dimalttz => dim_alt_tolerance_zero_tight,
+        dimatfit => dim_fit,
+        dimupt => dim_user_positioned_text,
+        dimlwd => dim_line_weight,
+        dimlwe => dim_ext_line_weight,
+        dimfxl => dim_fixed_ext_line_length,
+        dimfxlon => dim_ext_line_length_fixed,
+        dimjogang => dim_jog_angle,
+        dimtfill => dim_text_fill_mode,
+        dimarcsym => dim_arc_symbol_position,
+        dimtxtdirection => dim_text_direction,
+        dimtxsty_handle => dim_text_style_handle,
+        dimltex_handle => dim_linetype_handle,
+        dimltex1_handle => dim_linetype1_handle,
+        dimltex2_handle => dim_linetype2_handle,
+        dimblk => dim_arrow_block_handle,
+        dimblk1 => dim_arrow_block1_handle,
+        dimblk2 => dim_arrow_block2_handle,
+        dimldrblk => dim_leader_block_handle,
+    }
+    clone {
+        dimpost => dim_post,
+        dimapost => dim_alt_post,
+    }
 }
 
 impl TableEntry for DimStyle {
@@ -311,5 +488,3 @@ impl TableEntry for DimStyle {
         self.name == "Standard"
     }
 }
-
-
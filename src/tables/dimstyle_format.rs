@@ -0,0 +1,328 @@
+//! Dimension text formatting
+//!
+//! Turns a measured value into the text AutoCAD shows on a dimension, from
+//! the unit, precision, rounding and zero suppression settings of a
+//! [`DimStyle`]. The result is MText: fractions use the stacking codes
+//! AutoCAD writes into dimension blocks (`6\S1/2;`) and symbols their
+//! control codes (`%%d`, `%%c`).
+
+use super::dimstyle::{decimal_separator, DimStyle};
+
+impl DimStyle {
+    /// Primary units text of a linear measurement.
+    ///
+    /// The value is scaled by `DIMLFAC`, rounded to `DIMRND` and formatted
+    /// per `DIMLUNIT`, `DIMDEC`, `DIMZIN`, `DIMFRAC` and `DIMDSEP`.
+    pub fn format_linear(&self, value: f64) -> String {
+        let units = LinearUnits {
+            format: self.dimlunit,
+            precision: self.dimdec,
+            zeros: self.dimzin,
+            fraction: self.dimfrac,
+            separator: decimal_separator(self.dimdsep),
+        };
+        units.format(round_to(value * self.dimlfac, self.dimrnd))
+    }
+
+    /// Alternate units text of a linear measurement, scaled by `DIMLFAC`
+    /// and `DIMALTF` and formatted per the `DIMALT*` variables
+    pub fn format_alternate(&self, value: f64) -> String {
+        let units = LinearUnits {
+            format: self.dimaltu,
+            precision: self.dimaltd,
+            zeros: self.dimaltz,
+            fraction: self.dimfrac,
+            separator: decimal_separator(self.dimdsep),
+        };
+        units.format(round_to(value * self.dimlfac * self.dimaltf, self.dimaltrnd))
+    }
+
+    /// Text of an angle given in degrees, per `DIMAUNIT`, `DIMADEC`
+    /// (`DIMDEC` when -1) and `DIMAZIN`
+    pub fn format_angular(&self, degrees: f64) -> String {
+        let precision = if self.dimadec < 0 { self.dimdec } else { self.dimadec };
+        let precision = precision.clamp(0, 8) as usize;
+        let leading = self.dimazin & 1 != 0;
+        let trailing = self.dimazin & 2 != 0;
+        let separator = decimal_separator(self.dimdsep);
+        match self.dimaunit {
+            1 => degrees_minutes_seconds(degrees, precision, separator),
+            2 => decimal(degrees / 0.9, precision, leading, trailing, separator) + "g",
+            3 => decimal(degrees.to_radians(), precision, leading, trailing, separator) + "r",
+            _ => decimal(degrees, precision, leading, trailing, separator) + "%%d",
+        }
+    }
+
+    /// Text of a linear dimension: the primary units with the `DIMPOST`
+    /// prefix and suffix, followed by the alternate units in brackets when
+    /// `DIMALT` is on.
+    ///
+    /// `default_prefix` is what the dimension type puts in front of the
+    /// value, `R` for radius and `%%c` for diameter dimensions; a `DIMPOST`
+    /// prefix replaces it.
+    pub fn format_dimension_text(&self, value: f64, default_prefix: &str) -> String {
+        let mut text = with_postfix(&self.dimpost, "<>", &self.format_linear(value), default_prefix);
+        if self.dimalt {
+            let alternate = with_postfix(&self.dimapost, "[]", &self.format_alternate(value), "");
+            text = format!("{text} [{alternate}]");
+        }
+        text
+    }
+}
+
+/// Place `value` in a `DIMPOST`/`DIMAPOST` template: at `placeholder`
+/// when it has one, otherwise before the template as a suffix
+fn with_postfix(template: &str, placeholder: &str, value: &str, default_prefix: &str) -> String {
+    match template.split_once(placeholder) {
+        Some(("", suffix)) => format!("{default_prefix}{value}{suffix}"),
+        Some((prefix, suffix)) => format!("{prefix}{value}{suffix}"),
+        None => format!("{default_prefix}{value}{template}"),
+    }
+}
+
+fn round_to(value: f64, step: f64) -> f64 {
+    if step > 0.0 {
+        (value / step).round() * step
+    } else {
+        value
+    }
+}
+
+/// Settings of one of the two linear unit systems
+struct LinearUnits {
+    /// `DIMLUNIT`/`DIMALTU`: 1 scientific, 2 decimal, 3 engineering,
+    /// 4 architectural, 5 fractional, 6 Windows desktop
+    format: i16,
+    /// `DIMDEC`/`DIMALTD`: decimal places, or the power of two of the
+    /// smallest fraction
+    precision: i16,
+    /// `DIMZIN`/`DIMALTZ`
+    zeros: i16,
+    /// `DIMFRAC`: 0 horizontal, 1 diagonal, 2 not stacked
+    fraction: i16,
+    separator: char,
+}
+
+impl LinearUnits {
+    fn format(&self, value: f64) -> String {
+        let precision = self.precision.clamp(0, 8) as usize;
+        let leading = self.zeros & 4 != 0;
+        let trailing = self.zeros & 8 != 0;
+        match self.format {
+            1 => scientific(value, precision, trailing, self.separator),
+            3 | 4 => self.feet_and_inches(value, precision),
+            5 => {
+                let text = fraction(value.abs(), precision, self.fraction);
+                if value < 0.0 && text != "0" { format!("-{text}") } else { text }
+            }
+            _ => decimal(value, precision, leading, trailing, self.separator),
+        }
+    }
+
+    /// Engineering (decimal inches) or architectural (fractional inches)
+    /// text of a length in inches.
+    ///
+    /// The low two bits of the zero suppression decide whether zero feet
+    /// and zero inches show: 0 neither, 1 both, 2 only zero feet, 3 only
+    /// zero inches.
+    fn feet_and_inches(&self, value: f64, precision: usize) -> String {
+        let architectural = self.format == 4;
+        let step = if architectural { 1.0 / (1u32 << precision) as f64 } else { 10f64.powi(-(precision as i32)) };
+        let total = round_to(value.abs(), step);
+        let mut feet = (total / 12.0).floor();
+        let mut inches = total - feet * 12.0;
+        if inches > 12.0 - step / 2.0 {
+            feet += 1.0;
+            inches = 0.0;
+        }
+        let inches_zero = inches < step / 2.0;
+
+        let suppression = self.zeros & 3;
+        let show_feet = feet > 0.0 || matches!(suppression, 1 | 2);
+        let show_inches = !inches_zero || matches!(suppression, 1 | 3) || !show_feet;
+        let inches = if architectural {
+            fraction(inches, precision, self.fraction)
+        } else {
+            decimal(inches, precision, self.zeros & 4 != 0, self.zeros & 8 != 0, self.separator)
+        };
+
+        let text = match (show_feet, show_inches) {
+            (true, true) => format!("{feet}'-{inches}\""),
+            (true, false) => format!("{feet}'"),
+            _ => format!("{inches}\""),
+        };
+        if value < 0.0 && total > 0.0 { format!("-{text}") } else { text }
+    }
+}
+
+/// `value` with `precision` decimal places
+fn decimal(value: f64, precision: usize, leading: bool, trailing: bool, separator: char) -> String {
+    let text = format!("{value:.precision$}");
+    let (sign, mut digits) = match text.strip_prefix('-') {
+        Some(digits) if digits.bytes().any(|b| matches!(b, b'1'..=b'9')) => ("-", digits.to_string()),
+        Some(digits) => ("", digits.to_string()),
+        None => ("", text),
+    };
+    if trailing && digits.contains('.') {
+        digits.truncate(digits.trim_end_matches('0').trim_end_matches('.').len());
+    }
+    if leading && digits.starts_with("0.") {
+        digits.remove(0);
+    }
+    format!("{sign}{}", digits.replace('.', &separator.to_string()))
+}
+
+/// `value` as mantissa and two-digit exponent, e.g. `1.50E+01`
+fn scientific(value: f64, precision: usize, trailing: bool, separator: char) -> String {
+    let mut exponent = if value == 0.0 { 0 } else { value.abs().log10().floor() as i32 };
+    let mut mantissa = value / 10f64.powi(exponent);
+    // Rounding can carry the mantissa to 10
+    if (mantissa.abs() * 10f64.powi(precision as i32)).round() >= 10f64.powi(precision as i32 + 1) {
+        exponent += 1;
+        mantissa /= 10.0;
+    }
+    let sign = if exponent < 0 { '-' } else { '+' };
+    let mantissa = decimal(mantissa, precision, false, trailing, separator);
+    format!("{mantissa}E{sign}{:02}", exponent.abs())
+}
+
+/// Whole number and fraction of a non-negative `value`, to the nearest
+/// `1 / 2^precision`, e.g. `6\S1/2;`
+fn fraction(value: f64, precision: usize, format: i16) -> String {
+    let denominator = 1u64 << precision;
+    let units = (value * denominator as f64).round() as u64;
+    let whole = units / denominator;
+    let numerator = units % denominator;
+    if numerator == 0 {
+        return whole.to_string();
+    }
+    let shift = numerator.trailing_zeros();
+    let (numerator, denominator) = (numerator >> shift, denominator >> shift);
+    let fraction = match format {
+        1 => format!("\\S{numerator}#{denominator};"),
+        2 => format!("{numerator}/{denominator}"),
+        _ => format!("\\S{numerator}/{denominator};"),
+    };
+    match (whole, format) {
+        (0, _) => fraction,
+        (_, 2) => format!("{whole} {fraction}"),
+        _ => format!("{whole}{fraction}"),
+    }
+}
+
+/// Degrees, minutes and seconds: `precision` 0 shows degrees, 1–2 adds
+/// minutes, 3–4 seconds and higher values decimals of seconds
+fn degrees_minutes_seconds(degrees: f64, precision: usize, separator: char) -> String {
+    let sign = if degrees < 0.0 { "-" } else { "" };
+    let seconds_decimals = precision.saturating_sub(4);
+    let step = match precision {
+        0 => 3600.0,
+        1 | 2 => 60.0,
+        _ => 10f64.powi(-(seconds_decimals as i32)),
+    };
+    let total = round_to(degrees.abs() * 3600.0, step);
+    let whole_degrees = (total / 3600.0).floor();
+    let minutes = ((total - whole_degrees * 3600.0) / 60.0).floor();
+    let seconds = total - whole_degrees * 3600.0 - minutes * 60.0;
+    if total == 0.0 {
+        return "0%%d".to_string();
+    }
+
+    let mut text = format!("{sign}{whole_degrees}%%d");
+    if precision >= 1 {
+        text.push_str(&format!("{minutes}'"));
+    }
+    if precision >= 3 {
+        text.push_str(&decimal(seconds, seconds_decimals, false, false, separator));
+        text.push('"');
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::{Dimension, DimensionDiameter};
+    use crate::types::Vector3;
+
+    #[test]
+    fn test_linear_formats() {
+        let mut style = DimStyle::new("Test");
+        assert_eq!(style.format_linear(12.5), "12.5");
+        style.dimzin = 0;
+        assert_eq!(style.format_linear(12.5), "12.50");
+        style.dimzin = 4;
+        assert_eq!(style.format_linear(0.5), ".50");
+        style.dimdsep = b',' as i16;
+        style.dimrnd = 0.25;
+        assert_eq!(style.format_linear(3.1), "3,00");
+
+        style = DimStyle::new("Scientific");
+        style.dimlunit = 1;
+        style.dimzin = 0;
+        assert_eq!(style.format_linear(150.0), "1.50E+02");
+        assert_eq!(style.format_linear(0.0996), "9.96E-02");
+
+        style.dimlunit = 3;
+        assert_eq!(style.format_linear(150.5), "12'-6.50\"");
+        assert_eq!(style.format_linear(6.0), "6.00\"");
+        style.dimzin = 1;
+        assert_eq!(style.format_linear(144.0), "12'-0.00\"");
+
+        style.dimlunit = 4;
+        style.dimdec = 4;
+        style.dimzin = 0;
+        assert_eq!(style.format_linear(150.5), "12'-6\\S1/2;\"");
+        assert_eq!(style.format_linear(144.0), "12'");
+        style.dimfrac = 2;
+        assert_eq!(style.format_linear(-0.0625), "-1/16\"");
+
+        style.dimlunit = 5;
+        assert_eq!(style.format_linear(2.75), "2 3/4");
+        style.dimfrac = 1;
+        assert_eq!(style.format_linear(2.75), "2\\S3#4;");
+    }
+
+    #[test]
+    fn test_angular_formats() {
+        let mut style = DimStyle::new("Test");
+        style.dimadec = 1;
+        assert_eq!(style.format_angular(45.0), "45.0%%d");
+        style.dimazin = 2;
+        assert_eq!(style.format_angular(45.0), "45%%d");
+
+        style.dimaunit = 1;
+        style.dimadec = 4;
+        assert_eq!(style.format_angular(45.504166), "45%%d30'15\"");
+        style.dimadec = 2;
+        assert_eq!(style.format_angular(45.999), "46%%d0'");
+
+        style.dimaunit = 3;
+        style.dimadec = 2;
+        style.dimazin = 0;
+        assert_eq!(style.format_angular(90.0), "1.57r");
+    }
+
+    #[test]
+    fn test_dimension_text() {
+        let mut style = DimStyle::new("Test");
+        assert_eq!(style.format_dimension_text(5.0, "R"), "R5");
+        style.dimpost = "<> mm".to_string();
+        assert_eq!(style.format_dimension_text(5.0, "R"), "R5 mm");
+        style.dimpost = "Rad <>".to_string();
+        assert_eq!(style.format_dimension_text(5.0, "R"), "Rad 5");
+
+        style.dimpost.clear();
+        style.dimalt = true;
+        style.dimaltd = 1;
+        style.dimapost = "[]mm".to_string();
+        assert_eq!(style.format_dimension_text(1.0, ""), "1 [25.4mm]");
+
+        let style = DimStyle::new("Standard");
+        let diameter = DimensionDiameter::new(Vector3::ZERO, Vector3::new(2.5, 0.0, 0.0));
+        let mut dimension = Dimension::Diameter(diameter);
+        assert_eq!(dimension.formatted_measurement(&style), "%%c5");
+        dimension.base_mut().text = "<> THRU".to_string();
+        assert_eq!(dimension.display_text(&style), "%%c5 THRU");
+    }
+}
@@ -1,10 +1,12 @@
 //! Layer table entry
 
 use super::TableEntry;
-use crate::types::{Color, Handle, LineWeight};
+use crate::objects::{XRecord, XRecordEntry, XRecordValue};
+use crate::types::{Color, Handle, LineWeight, Transparency};
 
 /// Layer flags
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LayerFlags {
     /// Layer is frozen
     pub frozen: bool,
@@ -36,8 +38,175 @@ impl Default for LayerFlags {
     }
 }
 
+/// Properties of a layer overridden in one layout viewport
+///
+/// Whether the layer is frozen in a viewport is not an override of the
+/// layer but kept by the viewport, in
+/// [`Viewport::frozen_layers`](crate::entities::Viewport::frozen_layers).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LayerViewportOverride {
+    /// The VIEWPORT entity the overrides apply in
+    pub viewport: Handle,
+    /// Color in the viewport
+    pub color: Option<Color>,
+    /// Line type name in the viewport
+    pub line_type: Option<String>,
+    /// Line weight in the viewport
+    pub line_weight: Option<LineWeight>,
+    /// Transparency in the viewport
+    pub transparency: Option<Transparency>,
+}
+
+impl LayerViewportOverride {
+    /// No overrides in `viewport`
+    pub fn new(viewport: Handle) -> Self {
+        LayerViewportOverride {
+            viewport,
+            color: None,
+            line_type: None,
+            line_weight: None,
+            transparency: None,
+        }
+    }
+
+    /// Check if no property is overridden
+    pub fn is_empty(&self) -> bool {
+        self.color.is_none() && self.line_type.is_none() && self.line_weight.is_none() && self.transparency.is_none()
+    }
+}
+
+/// One kind of viewport override, stored by AutoCAD as an XRECORD in the
+/// layer's extension dictionary with a group per viewport:
+/// `102 {ADSK_LYR_..._OVERRIDE`, `335` viewport, the value, `102 }`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum OverrideKind {
+    Color,
+    LineType,
+    LineWeight,
+    Transparency,
+}
+
+impl OverrideKind {
+    pub(crate) const ALL: [OverrideKind; 4] =
+        [OverrideKind::Color, OverrideKind::LineType, OverrideKind::LineWeight, OverrideKind::Transparency];
+
+    /// Key of the XRECORD in the layer's extension dictionary
+    pub(crate) fn key(self) -> &'static str {
+        match self {
+            OverrideKind::Color => "ADSK_XREC_LAYER_COLOR_OVR",
+            OverrideKind::LineType => "ADSK_XREC_LAYER_LINETYPE_OVR",
+            OverrideKind::LineWeight => "ADSK_XREC_LAYER_LINEWT_OVR",
+            OverrideKind::Transparency => "ADSK_XREC_LAYER_ALPHA_OVR",
+        }
+    }
+
+    fn group(self) -> &'static str {
+        match self {
+            OverrideKind::Color => "{ADSK_LYR_COLOR_OVERRIDE",
+            OverrideKind::LineType => "{ADSK_LYR_LINETYPE_OVERRIDE",
+            OverrideKind::LineWeight => "{ADSK_LYR_LINEWT_OVERRIDE",
+            OverrideKind::Transparency => "{ADSK_LYR_ALPHA_OVERRIDE",
+        }
+    }
+
+    fn code(self) -> i32 {
+        match self {
+            OverrideKind::Color => 420,
+            OverrideKind::LineType => 343,
+            OverrideKind::LineWeight => 91,
+            OverrideKind::Transparency => 440,
+        }
+    }
+
+    /// Entries of the XRECORD holding this kind of override of `overrides`,
+    /// with line type names turned into handles by `line_type`; empty when
+    /// no viewport overrides it
+    pub(crate) fn entries(
+        self,
+        overrides: &[LayerViewportOverride],
+        line_type: impl Fn(&str) -> Option<Handle>,
+    ) -> Vec<XRecordEntry> {
+        let mut entries = Vec::new();
+        for overridden in overrides {
+            let value = match self {
+                OverrideKind::Color => overridden.color.map(|c| XRecordValue::Int32(c.cm_value())),
+                OverrideKind::LineType => overridden.line_type.as_deref().and_then(&line_type).map(XRecordValue::Handle),
+                OverrideKind::LineWeight => overridden.line_weight.map(|w| XRecordValue::Int32(w.value() as i32)),
+                OverrideKind::Transparency => {
+                    overridden.transparency.map(|t| XRecordValue::Int32(t.to_alpha_value()))
+                }
+            };
+            if let Some(value) = value {
+                entries.push(XRecordEntry::string(102, self.group()));
+                entries.push(XRecordEntry::handle(335, overridden.viewport));
+                entries.push(XRecordEntry::new(self.code(), value));
+                entries.push(XRecordEntry::string(102, "}"));
+            }
+        }
+        entries
+    }
+
+    /// Apply the overrides of an XRECORD of this kind to `overrides`, with
+    /// line type handles turned into names by `line_type`
+    pub(crate) fn apply(
+        self,
+        xrecord: &XRecord,
+        overrides: &mut Vec<LayerViewportOverride>,
+        line_type: impl Fn(Handle) -> Option<String>,
+    ) {
+        let mut viewport = None;
+        for entry in xrecord.iter() {
+            if entry.code == 335 {
+                viewport = entry_handle(&entry.value);
+                continue;
+            }
+            let Some(viewport) = viewport.filter(|_| entry.code == self.code()) else {
+                continue;
+            };
+            let overridden = override_in(overrides, viewport);
+            match self {
+                OverrideKind::Color => overridden.color = entry_i32(&entry.value).map(Color::from_cm_value),
+                OverrideKind::LineType => overridden.line_type = entry_handle(&entry.value).and_then(&line_type),
+                OverrideKind::LineWeight => {
+                    overridden.line_weight = entry_i32(&entry.value).map(|v| LineWeight::from_value(v as i16))
+                }
+                OverrideKind::Transparency => {
+                    overridden.transparency = entry_i32(&entry.value).map(|v| Transparency::from_alpha_value(v as u32))
+                }
+            }
+        }
+        overrides.retain(|o| !o.is_empty());
+    }
+}
+
+fn override_in(overrides: &mut Vec<LayerViewportOverride>, viewport: Handle) -> &mut LayerViewportOverride {
+    match overrides.iter().position(|o| o.viewport == viewport) {
+        Some(index) => &mut overrides[index],
+        None => {
+            overrides.push(LayerViewportOverride::new(viewport));
+            overrides.last_mut().unwrap()
+        }
+    }
+}
+
+fn entry_i32(value: &XRecordValue) -> Option<i32> {
+    match value {
+        XRecordValue::String(s) => s.trim().parse().ok(),
+        value => value.as_i32(),
+    }
+}
+
+fn entry_handle(value: &XRecordValue) -> Option<Handle> {
+    match value {
+        XRecordValue::String(s) => u64::from_str_radix(s.trim(), 16).ok().map(Handle::new),
+        value => value.as_handle(),
+    }
+}
+
 /// A layer table entry
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Layer {
     /// Unique handle
     pub handle: Handle,
@@ -57,6 +226,11 @@ pub struct Layer {
     pub is_plottable: bool,
     /// Material handle
     pub material: Handle,
+    /// Extended dictionary handle ({ACAD_XDICTIONARY})
+    pub xdictionary_handle: Option<Handle>,
+    /// Property overrides per layout viewport, kept in the extension
+    /// dictionary when the document is written
+    pub overrides: Vec<LayerViewportOverride>,
 }
 
 impl Layer {
@@ -72,6 +246,8 @@ impl Layer {
             plot_style: String::new(),
             is_plottable: true,
             material: Handle::NULL,
+            xdictionary_handle: None,
+            overrides: Vec::new(),
         }
     }
 
@@ -87,6 +263,8 @@ impl Layer {
             plot_style: String::new(),
             is_plottable: true,
             material: Handle::NULL,
+            xdictionary_handle: None,
+            overrides: Vec::new(),
         }
     }
 
@@ -147,6 +325,22 @@ impl Layer {
     pub fn is_visible(&self) -> bool {
         !self.flags.off && !self.flags.frozen
     }
+
+    /// Property overrides of the layer in the VIEWPORT entity `viewport`
+    pub fn viewport_overrides(&self, viewport: Handle) -> Option<&LayerViewportOverride> {
+        self.overrides.iter().find(|o| o.viewport == viewport)
+    }
+
+    /// Mutable property overrides of the layer in `viewport`, added
+    /// without overrides when there are none yet
+    pub fn viewport_overrides_mut(&mut self, viewport: Handle) -> &mut LayerViewportOverride {
+        override_in(&mut self.overrides, viewport)
+    }
+
+    /// Remove the property overrides of the layer in `viewport`
+    pub fn clear_viewport_overrides(&mut self, viewport: Handle) {
+        self.overrides.retain(|o| o.viewport != viewport);
+    }
 }
 
 impl TableEntry for Layer {
@@ -172,3 +366,42 @@ impl TableEntry for Layer {
 }
 
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_override_records_roundtrip() {
+        let viewport = Handle::new(0x40);
+        let dashed = Handle::new(0x2A);
+        let mut layer = Layer::new("Walls");
+        let overrides = layer.viewport_overrides_mut(viewport);
+        overrides.color = Some(Color::Rgb { r: 10, g: 20, b: 30 });
+        overrides.line_type = Some("DASHED".to_string());
+        layer.viewport_overrides_mut(Handle::new(0x41)).line_weight = Some(LineWeight::Value(35));
+        assert_eq!(layer.overrides.len(), 2);
+
+        let mut read = Vec::new();
+        for kind in OverrideKind::ALL {
+            let mut xrecord = XRecord::new();
+            xrecord.entries = kind.entries(&layer.overrides, |name| (name == "DASHED").then_some(dashed));
+            assert_eq!(xrecord.entries.is_empty(), kind == OverrideKind::Transparency);
+            kind.apply(&xrecord, &mut read, |h| (h == dashed).then(|| "DASHED".to_string()));
+        }
+        assert_eq!(read, layer.overrides);
+
+        // Values read from text, as a DXF reader may leave them
+        let mut xrecord = XRecord::new();
+        xrecord.add_string(102, "{ADSK_LYR_LINEWT_OVERRIDE");
+        xrecord.add_string(335, "40");
+        xrecord.add_string(91, "50");
+        xrecord.add_string(102, "}");
+        let mut read = Vec::new();
+        OverrideKind::LineWeight.apply(&xrecord, &mut read, |_| None);
+        assert_eq!(read[0].line_weight, Some(LineWeight::Value(50)));
+
+        layer.clear_viewport_overrides(viewport);
+        assert!(layer.viewport_overrides(viewport).is_none());
+    }
+}
@@ -8,15 +8,16 @@ pub mod linetype;
 pub mod textstyle;
 pub mod block_record;
 pub mod dimstyle;
+mod dimstyle_format;
 pub mod appid;
 pub mod view;
 pub mod vport;
 pub mod ucs;
 
-pub use layer::{Layer, LayerFlags};
+pub use layer::{Layer, LayerFlags, LayerViewportOverride};
 pub use linetype::{LineType, LineTypeElement};
 pub use textstyle::{TextStyle, TextGenerationFlags};
-pub use block_record::BlockRecord;
+pub use block_record::{BlockRecord, PendingEntities};
 pub use dimstyle::DimStyle;
 pub use appid::AppId;
 pub use view::View;
@@ -45,6 +46,7 @@ pub trait TableEntry {
 
 /// Generic table for storing named entries
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Table<T: TableEntry> {
     /// Entries stored by name (case-insensitive)
     entries: IndexMap<String, T>,
@@ -1,10 +1,11 @@
 //! View table entry
 
 use super::TableEntry;
-use crate::types::{Handle, Vector3};
+use crate::types::{Angle, Handle, Vector3};
 
 /// A view table entry
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct View {
     /// Unique handle
     pub handle: Handle,
@@ -26,7 +27,7 @@ pub struct View {
     pub front_clip: f64,
     /// Back clipping plane offset
     pub back_clip: f64,
-    /// Twist angle
+    /// Twist angle in radians
     pub twist_angle: f64,
 }
 
@@ -47,6 +48,16 @@ impl View {
             twist_angle: 0.0,
         }
     }
+
+    /// Twist angle
+    pub fn twist_angle(&self) -> Angle {
+        Angle::from_radians(self.twist_angle)
+    }
+
+    /// Set the twist angle
+    pub fn set_twist_angle(&mut self, angle: Angle) {
+        self.twist_angle = angle.radians();
+    }
 }
 
 impl TableEntry for View {
@@ -5,6 +5,7 @@ use crate::types::{Handle, Vector2, Vector3};
 
 /// A viewport table entry
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct VPort {
     /// Unique handle
     pub handle: Handle,
@@ -32,6 +33,12 @@ pub struct VPort {
     pub aspect_ratio: f64,
     /// Lens length
     pub lens_length: f64,
+    /// Background object (code 332)
+    pub background_handle: Handle,
+    /// Visual style object (code 348)
+    pub visual_style_handle: Handle,
+    /// SUN object, owned by the viewport (code 361)
+    pub sun_handle: Handle,
 }
 
 impl VPort {
@@ -51,6 +58,9 @@ impl VPort {
             view_height: 10.0,
             aspect_ratio: 1.0,
             lens_length: 50.0,
+            background_handle: Handle::NULL,
+            visual_style_handle: Handle::NULL,
+            sun_handle: Handle::NULL,
         }
     }
 
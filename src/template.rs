@@ -0,0 +1,401 @@
+//! Drawing templates
+//!
+//! [`CadDocument::from_template`] starts a drawing the way a DWT does: units,
+//! layers, a text style and dimension style suited to the sheet's standard,
+//! and a paper space layout sized to the sheet with a border and a title
+//! block. The title block is a block reference with attributes, ready for
+//! [`CadDocument::update_title_block`].
+//!
+//! ```rust,ignore
+//! use acadrust::template::Template;
+//!
+//! let mut doc = CadDocument::from_template(Template::IsoA1);
+//! doc.update_title_block(&HashMap::from([("TITLE".into(), "Gearbox housing".into())]));
+//! ```
+
+use crate::document::CadDocument;
+use crate::entities::{AttributeDefinition, AttributeEntity, EntityType, Insert, Line, LwPolyline, Text};
+use crate::objects::{ObjectType, PlotPaperUnits, PlotType, ScaledType};
+use crate::tables::{BlockRecord, DimStyle, Layer, LineType, LineTypeElement, TableEntry, TextStyle};
+use crate::types::{Color, Handle, LineWeight, Vector2, Vector3};
+
+/// Sheet a template document is laid out for, in landscape orientation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Template {
+    /// ISO A0, 1189 × 841 mm
+    IsoA0,
+    /// ISO A1, 841 × 594 mm
+    IsoA1,
+    /// ISO A2, 594 × 420 mm
+    IsoA2,
+    /// ISO A3, 420 × 297 mm
+    IsoA3,
+    /// ISO A4, 297 × 210 mm
+    IsoA4,
+    /// ANSI A, 11 × 8.5 in
+    AnsiA,
+    /// ANSI B, 17 × 11 in
+    AnsiB,
+    /// ANSI C, 22 × 17 in
+    AnsiC,
+    /// ANSI D, 34 × 22 in
+    AnsiD,
+    /// ANSI E, 44 × 34 in
+    AnsiE,
+}
+
+impl Template {
+    /// Every template
+    pub const ALL: [Template; 10] = [
+        Template::IsoA0,
+        Template::IsoA1,
+        Template::IsoA2,
+        Template::IsoA3,
+        Template::IsoA4,
+        Template::AnsiA,
+        Template::AnsiB,
+        Template::AnsiC,
+        Template::AnsiD,
+        Template::AnsiE,
+    ];
+
+    /// Name of the block holding the title block
+    pub const TITLE_BLOCK: &'static str = "TITLE_BLOCK";
+
+    /// Whether the sheet is metric (millimeters) rather than inches
+    pub fn is_metric(self) -> bool {
+        matches!(
+            self,
+            Template::IsoA0 | Template::IsoA1 | Template::IsoA2 | Template::IsoA3 | Template::IsoA4
+        )
+    }
+
+    /// Sheet name, such as `A1` or `ANSI B`
+    pub fn sheet_name(self) -> &'static str {
+        match self {
+            Template::IsoA0 => "A0",
+            Template::IsoA1 => "A1",
+            Template::IsoA2 => "A2",
+            Template::IsoA3 => "A3",
+            Template::IsoA4 => "A4",
+            Template::AnsiA => "ANSI A",
+            Template::AnsiB => "ANSI B",
+            Template::AnsiC => "ANSI C",
+            Template::AnsiD => "ANSI D",
+            Template::AnsiE => "ANSI E",
+        }
+    }
+
+    /// Width and height of the landscape sheet in its own units
+    /// (millimeters or inches)
+    pub fn paper_size(self) -> (f64, f64) {
+        match self {
+            Template::IsoA0 => (1189.0, 841.0),
+            Template::IsoA1 => (841.0, 594.0),
+            Template::IsoA2 => (594.0, 420.0),
+            Template::IsoA3 => (420.0, 297.0),
+            Template::IsoA4 => (297.0, 210.0),
+            Template::AnsiA => (11.0, 8.5),
+            Template::AnsiB => (17.0, 11.0),
+            Template::AnsiC => (22.0, 17.0),
+            Template::AnsiD => (34.0, 22.0),
+            Template::AnsiE => (44.0, 34.0),
+        }
+    }
+
+    /// Media name of the sheet, as the plot device lists it
+    pub fn media_name(self) -> String {
+        let (width, height) = self.paper_size();
+        match self.is_metric() {
+            true => format!("ISO_full_bleed_{}_({width:.2}_x_{height:.2}_MM)", self.sheet_name()),
+            false => format!("ANSI_full_bleed_{}_({width:.2}_x_{height:.2}_Inches)", &self.sheet_name()[5..]),
+        }
+    }
+
+    /// One millimeter in the units of the sheet
+    fn millimeter(self) -> f64 {
+        if self.is_metric() { 1.0 } else { 1.0 / 25.4 }
+    }
+
+    /// Left, bottom, right and top distance of the border from the sheet
+    /// edge: ISO 5457 keeps a 20 mm filing margin on the left
+    fn border_margins(self) -> [f64; 4] {
+        match self.is_metric() {
+            true => [20.0, 10.0, 10.0, 10.0],
+            false => [0.5; 4],
+        }
+    }
+}
+
+impl CadDocument {
+    /// Create a document laid out for the sheet of `template`.
+    ///
+    /// Besides the defaults of [`CadDocument::new`] the document gets:
+    ///
+    /// - metric or inch units and limits
+    /// - `HIDDEN` and `CENTER` linetypes and layers for outlines, hidden and
+    ///   center lines, dimensions, text, hatching, viewports and the title
+    ///   block
+    /// - an `ISOCP` or `ROMANS` text style, and for ISO sheets the `ISO-25`
+    ///   dimension style, made current
+    /// - the paper space layout set to the sheet, plotted 1:1, with a border
+    ///   and a [`Template::TITLE_BLOCK`] reference whose attributes are
+    ///   `TITLE`, `DWGNO`, `REV`, `SCALE`, `DATE` and `DRAWN`
+    pub fn from_template(template: Template) -> Self {
+        let mut doc = CadDocument::new();
+        let metric = template.is_metric();
+        let (width, height) = template.paper_size();
+
+        doc.header.measurement = metric as i16;
+        doc.header.insertion_units = if metric { 4 } else { 1 };
+        doc.header.text_height = if metric { 2.5 } else { 0.2 };
+        doc.header.model_space_limits_max = if metric { Vector2::new(420.0, 297.0) } else { Vector2::new(12.0, 9.0) };
+        doc.header.paper_space_limits_min = Vector2::new(0.0, 0.0);
+        doc.header.paper_space_limits_max = Vector2::new(width, height);
+
+        // acadiso.lin patterns are acad.lin's in millimeters
+        let length = |inches: f64| if metric { inches * 25.4 } else { inches };
+        for (name, description, pattern) in [
+            ("HIDDEN", "Hidden __ __ __ __ __ __ __ __ __ __ __ __ __ __", &[0.25, -0.125][..]),
+            ("CENTER", "Center ____ _ ____ _ ____ _ ____ _ ____ _ ____", &[1.25, -0.25, 0.25, -0.25][..]),
+        ] {
+            let mut line_type = LineType::new(name);
+            line_type.description = description.to_string();
+            for &element in pattern {
+                line_type.add_element(match element > 0.0 {
+                    true => LineTypeElement::dash(length(element)),
+                    false => LineTypeElement::space(length(element)),
+                });
+            }
+            line_type.pattern_length = pattern.iter().map(|e| length(e.abs())).sum();
+            line_type.set_handle(doc.allocate_handle());
+            doc.line_types.add(line_type).ok();
+        }
+
+        for (name, color, line_type, weight) in [
+            ("Outline", Color::WHITE, "Continuous", 50),
+            ("Hidden", Color::YELLOW, "HIDDEN", 25),
+            ("Center", Color::RED, "CENTER", 18),
+            ("Dimensions", Color::GREEN, "Continuous", 18),
+            ("Text", Color::CYAN, "Continuous", 25),
+            ("Hatch", Color::GRAY, "Continuous", 18),
+            ("Viewports", Color::from_index(9), "Continuous", 18),
+            ("Title Block", Color::WHITE, "Continuous", 35),
+        ] {
+            let mut layer = Layer::with_color(name, color);
+            layer.line_type = line_type.to_string();
+            layer.line_weight = LineWeight::Value(weight);
+            layer.is_plottable = name != "Viewports";
+            layer.set_handle(doc.allocate_handle());
+            doc.layers.add(layer).ok();
+        }
+
+        let (style_name, font) = if metric { ("ISOCP", "isocp.shx") } else { ("ROMANS", "romans.shx") };
+        let mut text_style = TextStyle::new(style_name);
+        text_style.font_file = font.to_string();
+        text_style.set_handle(doc.allocate_handle());
+        let text_style_handle = text_style.handle;
+        doc.text_styles.add(text_style).ok();
+
+        if metric {
+            let mut style = DimStyle::new("ISO-25");
+            style.dimasz = 2.5;
+            style.dimtxt = 2.5;
+            style.dimcen = 2.5;
+            style.dimexe = 1.25;
+            style.dimexo = 0.625;
+            style.dimgap = 0.625;
+            style.dimdli = 3.75;
+            style.dimtad = 1;
+            style.dimtih = false;
+            style.dimtoh = false;
+            style.dimtofl = true;
+            style.dimdec = 2;
+            style.dimzin = 8;
+            style.dimdsep = b',' as i16;
+            style.dimtxsty = style_name.to_string();
+            style.dimtxsty_handle = text_style_handle;
+            style.set_handle(doc.allocate_handle());
+            doc.header.current_dimstyle_handle = style.handle;
+            doc.dim_styles.add(style).ok();
+            doc.sync_dim_variables();
+        }
+
+        let layout_block = doc.header.paper_space_block_handle;
+        for object in doc.objects.values_mut() {
+            let ObjectType::Layout(layout) = object else { continue };
+            if layout.block_record != layout_block {
+                continue;
+            }
+            layout.min_limits = (0.0, 0.0);
+            layout.max_limits = (width, height);
+            layout.min_extents = (0.0, 0.0, 0.0);
+            layout.max_extents = (width, height, 0.0);
+            let plot = &mut layout.plot_settings;
+            plot.page_name = layout.name.clone();
+            plot.printer_name = "DWG To PDF.pc3".to_string();
+            plot.paper_size = template.media_name();
+            // Paper sizes are in millimeters whatever the plot units
+            let to_mm = if metric { 1.0 } else { 25.4 };
+            plot.set_paper_size(width * to_mm, height * to_mm);
+            plot.paper_units = if metric { PlotPaperUnits::Millimeters } else { PlotPaperUnits::Inches };
+            plot.plot_type = PlotType::Layout;
+            plot.set_standard_scale(ScaledType::OneToOne);
+        }
+
+        let definitions = title_block(&mut doc, template, style_name);
+        let mut insert = Insert::new(Template::TITLE_BLOCK, Vector3::ZERO);
+        insert.common.layer = "Title Block".to_string();
+        insert.common.owner_handle = layout_block;
+        for definition in &definitions {
+            let mut attribute = AttributeEntity::from_definition(definition, None);
+            attribute.common.handle = doc.allocate_handle();
+            attribute.common.owner_handle = Handle::NULL;
+            insert.attributes.push(attribute);
+        }
+        doc.add_entity(EntityType::Insert(insert)).ok();
+        doc
+    }
+}
+
+/// Add the block with the border and title block of `template`, returning
+/// its attribute definitions
+fn title_block(doc: &mut CadDocument, template: Template, text_style: &str) -> Vec<AttributeDefinition> {
+    let mm = template.millimeter();
+    let (width, height) = template.paper_size();
+    let [left, bottom, right, top] = template.border_margins();
+    let rectangle = |x1: f64, y1: f64, x2: f64, y2: f64| {
+        let mut polyline = LwPolyline::from_points(vec![
+            Vector2::new(x1, y1),
+            Vector2::new(x2, y1),
+            Vector2::new(x2, y2),
+            Vector2::new(x1, y2),
+        ]);
+        polyline.close();
+        polyline
+    };
+    let mut entities = Vec::new();
+    entities.push(EntityType::LwPolyline(rectangle(left, bottom, width - right, height - top)));
+
+    // 180 × 36 mm in the lower right corner, three rows of cells
+    let (x2, y1) = (width - right, bottom);
+    let (x1, y2) = (x2 - 180.0 * mm, y1 + 36.0 * mm);
+    let row = 12.0 * mm;
+    entities.push(EntityType::LwPolyline(rectangle(x1, y1, x2, y2)));
+    for y in [y1 + row, y1 + 2.0 * row] {
+        entities.push(EntityType::Line(Line::from_points(Vector3::new(x1, y, 0.0), Vector3::new(x2, y, 0.0))));
+    }
+    for (x, from, to) in [
+        (x1 + 100.0 * mm, y1 + row, y1 + 2.0 * row),
+        (x1 + 60.0 * mm, y1, y1 + row),
+        (x1 + 120.0 * mm, y1, y1 + row),
+    ] {
+        entities.push(EntityType::Line(Line::from_points(Vector3::new(x, from, 0.0), Vector3::new(x, to, 0.0))));
+    }
+
+    let cells = [
+        ("TITLE", "Title", "Drawing title", x1, y1 + 2.0 * row, 5.0),
+        ("DWGNO", "Drawing no.", "Drawing number", x1, y1 + row, 3.5),
+        ("REV", "Rev.", "Revision", x1 + 100.0 * mm, y1 + row, 3.5),
+        ("SCALE", "Scale", "Scale", x1, y1, 3.5),
+        ("DATE", "Date", "Date", x1 + 60.0 * mm, y1, 3.5),
+        ("DRAWN", "Drawn", "Drawn by", x1 + 120.0 * mm, y1, 3.5),
+    ];
+    let mut definitions = Vec::new();
+    for (tag, label, prompt, x, y, value_height) in cells {
+        let mut text = Text::with_value(label, Vector3::new(x + 1.5 * mm, y + row - 3.5 * mm, 0.0))
+            .with_height(2.0 * mm);
+        text.style = text_style.to_string();
+        entities.push(EntityType::Text(text));
+
+        let mut definition = AttributeDefinition::new(tag.to_string(), prompt.to_string(), String::new());
+        definition.insertion_point = Vector3::new(x + 2.0 * mm, y + 2.0 * mm, 0.0);
+        definition.height = value_height * mm;
+        definition.text_style = text_style.to_string();
+        definition.common.layer = "Title Block".to_string();
+        definitions.push(definition);
+    }
+
+    let mut record = BlockRecord::new(Template::TITLE_BLOCK);
+    record.set_handle(doc.allocate_handle());
+    record.block_entity_handle = doc.allocate_handle();
+    record.block_end_handle = doc.allocate_handle();
+    record.flags.has_attributes = true;
+    record.units = if template.is_metric() { 4 } else { 1 };
+    for definition in &mut definitions {
+        definition.common.handle = doc.allocate_handle();
+    }
+    entities.extend(definitions.iter().cloned().map(|d| EntityType::AttributeDefinition(Box::new(d))));
+    for mut entity in entities {
+        let common = entity.common_mut();
+        if common.handle.is_null() {
+            common.handle = doc.allocate_handle();
+        }
+        common.owner_handle = record.handle;
+        if common.layer == "0" {
+            common.layer = "Title Block".to_string();
+        }
+        record.entities.push(entity);
+    }
+    doc.block_records.add(record).ok();
+    definitions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_media_names() {
+        assert_eq!(Template::IsoA1.media_name(), "ISO_full_bleed_A1_(841.00_x_594.00_MM)");
+        assert_eq!(Template::AnsiB.media_name(), "ANSI_full_bleed_B_(17.00_x_11.00_Inches)");
+    }
+
+    #[test]
+    fn test_from_template() {
+        for template in Template::ALL {
+            let doc = CadDocument::from_template(template);
+            let (width, height) = template.paper_size();
+            let layout = doc
+                .objects
+                .values()
+                .find_map(|o| match o {
+                    ObjectType::Layout(l) if l.block_record == doc.header.paper_space_block_handle => Some(l),
+                    _ => None,
+                })
+                .unwrap();
+            assert_eq!(layout.max_limits, (width, height), "{template:?}");
+            assert_eq!(layout.plot_settings.paper_size, template.media_name());
+            assert_eq!(doc.header.measurement, template.is_metric() as i16);
+            assert!(doc.layers.get("Title Block").is_some());
+            assert!(doc.line_types.get("CENTER").is_some());
+
+            let record = doc.block_records.get(Template::TITLE_BLOCK).unwrap();
+            let border = record.entities.iter().find_map(|e| match e {
+                EntityType::LwPolyline(p) => Some(p),
+                _ => None,
+            });
+            let [left, bottom, right, top] = template.border_margins();
+            let corners: Vec<Vector2> = border.unwrap().vertices.iter().map(|v| v.location).collect();
+            assert_eq!(corners[0], Vector2::new(left, bottom));
+            assert_eq!(corners[2], Vector2::new(width - right, height - top));
+
+            let insert = doc
+                .entities()
+                .find_map(|e| match e {
+                    EntityType::Insert(i) if i.block_name == Template::TITLE_BLOCK => Some(i),
+                    _ => None,
+                })
+                .unwrap();
+            assert_eq!(insert.common.owner_handle, doc.header.paper_space_block_handle);
+            let tags: Vec<&str> = insert.attributes.iter().map(|a| a.tag.as_str()).collect();
+            assert_eq!(tags, ["TITLE", "DWGNO", "REV", "SCALE", "DATE", "DRAWN"]);
+        }
+
+        let iso = CadDocument::from_template(Template::IsoA3);
+        assert_eq!(iso.current_dim_style().unwrap().name, "ISO-25");
+        assert_eq!(iso.header.dim_arrow_size, 2.5);
+        let ansi = CadDocument::from_template(Template::AnsiD);
+        assert_eq!(ansi.current_dim_style().unwrap().name, "Standard");
+    }
+}
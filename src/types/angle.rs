@@ -0,0 +1,164 @@
+//! Angles with an explicit unit
+//!
+//! Every angle of the document model is stored in radians, counter-clockwise
+//! from the X axis of the entity's coordinate system: arc angles, text,
+//! insert, shape and underlay rotations, oblique angles, viewport snap and
+//! twist angles. DXF files give most of them in degrees; the readers and
+//! writers convert. The raw `f64` fields stay for compatibility, and
+//! entities offer [`Angle`] accessors next to them so the unit is never
+//! guessed:
+//!
+//! ```rust,ignore
+//! text.set_rotation(Angle::from_degrees(30.0));
+//! assert_eq!(text.rotation, std::f64::consts::PI / 6.0);
+//! let sweep = arc.end_angle() - arc.start_angle();
+//! ```
+//!
+//! Ellipse start and end parameters are not angles to the end points but
+//! parameters of `center + major·cos(t) + minor·sin(t)`, which only match
+//! the angle on circles; they are radians as well.
+
+use std::f64::consts::TAU;
+use std::fmt;
+use std::ops::{Add, Mul, Neg, Sub};
+
+/// An angle, held in radians
+#[derive(Debug, Clone, Copy, Default, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Angle(f64);
+
+impl Angle {
+    /// Zero angle
+    pub const ZERO: Angle = Angle(0.0);
+
+    /// Right angle
+    pub const RIGHT: Angle = Angle(std::f64::consts::FRAC_PI_2);
+
+    /// Half turn
+    pub const STRAIGHT: Angle = Angle(std::f64::consts::PI);
+
+    /// Full turn
+    pub const FULL: Angle = Angle(TAU);
+
+    /// Angle of `radians`
+    pub const fn from_radians(radians: f64) -> Self {
+        Angle(radians)
+    }
+
+    /// Angle of `degrees`
+    pub fn from_degrees(degrees: f64) -> Self {
+        Angle(degrees.to_radians())
+    }
+
+    /// Value in radians
+    pub const fn radians(self) -> f64 {
+        self.0
+    }
+
+    /// Value in degrees
+    pub fn degrees(self) -> f64 {
+        self.0.to_degrees()
+    }
+
+    /// The same direction in `[0, 2π)`
+    pub fn normalized(self) -> Self {
+        let radians = self.0.rem_euclid(TAU);
+        // rem_euclid rounds tiny negative angles up to 2π
+        Angle(if radians >= TAU { 0.0 } else { radians })
+    }
+
+    /// Sine
+    pub fn sin(self) -> f64 {
+        self.0.sin()
+    }
+
+    /// Cosine
+    pub fn cos(self) -> f64 {
+        self.0.cos()
+    }
+}
+
+impl Add for Angle {
+    type Output = Angle;
+
+    fn add(self, other: Angle) -> Angle {
+        Angle(self.0 + other.0)
+    }
+}
+
+impl Sub for Angle {
+    type Output = Angle;
+
+    fn sub(self, other: Angle) -> Angle {
+        Angle(self.0 - other.0)
+    }
+}
+
+impl Neg for Angle {
+    type Output = Angle;
+
+    fn neg(self) -> Angle {
+        Angle(-self.0)
+    }
+}
+
+impl Mul<f64> for Angle {
+    type Output = Angle;
+
+    fn mul(self, factor: f64) -> Angle {
+        Angle(self.0 * factor)
+    }
+}
+
+impl fmt::Display for Angle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}°", self.degrees())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_angle_units() {
+        let angle = Angle::from_degrees(90.0);
+        assert_eq!(angle, Angle::RIGHT);
+        assert_eq!(angle.radians(), std::f64::consts::FRAC_PI_2);
+        assert!((angle.degrees() - 90.0).abs() < 1e-12);
+        assert_eq!((Angle::RIGHT + Angle::RIGHT), Angle::STRAIGHT);
+        assert_eq!(Angle::STRAIGHT * 2.0, Angle::FULL);
+        assert_eq!(Angle::from_degrees(180.0).to_string(), "180°");
+    }
+
+    #[test]
+    fn test_angle_normalized() {
+        assert_eq!(Angle::FULL.normalized(), Angle::ZERO);
+        assert_eq!((-Angle::RIGHT).normalized(), Angle::from_radians(3.0 * std::f64::consts::FRAC_PI_2));
+        assert_eq!(Angle::from_radians(-1e-20).normalized(), Angle::ZERO);
+        assert!((Angle::from_degrees(450.0).normalized().degrees() - 90.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_entity_angle_accessors() {
+        use crate::entities::{Text, Viewport};
+        use crate::tables::View;
+        use crate::types::Vector3;
+        use std::f64::consts::PI;
+
+        let mut text = Text::with_value("A", Vector3::ZERO);
+        text.set_rotation(Angle::from_degrees(45.0));
+        assert!((text.rotation - PI / 4.0).abs() < 1e-15);
+        assert_eq!(text.rotation(), Angle::from_radians(text.rotation));
+
+        let mut viewport = Viewport::with_size(Vector3::ZERO, 10.0, 10.0);
+        viewport.set_snap_angle(Angle::from_degrees(15.0));
+        viewport.set_twist_angle(Angle::from_degrees(30.0));
+        assert!((viewport.snap_angle - PI / 12.0).abs() < 1e-15);
+        assert!((viewport.twist_angle().degrees() - 30.0).abs() < 1e-12);
+
+        let mut view = View::new("TWISTED");
+        view.set_twist_angle(Angle::STRAIGHT);
+        assert_eq!(view.twist_angle, PI);
+    }
+}
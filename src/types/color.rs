@@ -10,6 +10,7 @@ use std::fmt;
 /// - By layer: Use the layer's color
 /// - By block: Use the block's color
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Color {
     /// Color by layer (index 256)
     #[default]
@@ -57,6 +58,40 @@ impl Color {
         }
     }
 
+    /// RGB value for display, using the standard ACI palette for indexed
+    /// colors. `ByLayer` and `ByBlock` have no color of their own.
+    pub fn to_rgb(&self) -> Option<(u8, u8, u8)> {
+        match self {
+            Color::Index(i) => Some(aci_to_rgb(*i)),
+            Color::Rgb { r, g, b } => Some((*r, *g, *b)),
+            Color::ByLayer | Color::ByBlock => None,
+        }
+    }
+
+    /// Packed AcCmColor value as stored by objects such as MLEADERSTYLE:
+    /// the method in the high byte (0xC0 by layer, 0xC1 by block, 0xC2
+    /// true color, 0xC3 index) and the index or RGB value below it
+    pub fn cm_value(&self) -> i32 {
+        let value = match self {
+            Color::ByLayer => 0xC000_0000,
+            Color::ByBlock => 0xC100_0000,
+            Color::Rgb { r, g, b } => 0xC200_0000 | (*r as u32) << 16 | (*g as u32) << 8 | *b as u32,
+            Color::Index(i) => 0xC300_0000 | *i as u32,
+        };
+        value as i32
+    }
+
+    /// Decode a packed AcCmColor value, see [`Color::cm_value`]
+    pub fn from_cm_value(value: i32) -> Self {
+        let value = value as u32;
+        match value >> 24 {
+            0xC1 => Color::ByBlock,
+            0xC2 => Color::from_rgb((value >> 16) as u8, (value >> 8) as u8, value as u8),
+            0xC3 => Color::from_index((value & 0xFF) as i16),
+            _ => Color::ByLayer,
+        }
+    }
+
     /// Common color constants
     pub const RED: Color = Color::Index(1);
     pub const YELLOW: Color = Color::Index(2);
@@ -102,6 +137,57 @@ impl Color {
     }
 }
 
+/// RGB value of an AutoCAD Color Index in the standard palette.
+///
+/// Indices 10–249 cycle through 24 hues in 15° steps; each hue has five
+/// brightness levels, alternating full and half saturation. 250–255 are
+/// grays. Index 7 is white (drawn black on white paper by plotters).
+pub fn aci_to_rgb(index: u8) -> (u8, u8, u8) {
+    const BASE: [(u8, u8, u8); 10] = [
+        (0, 0, 0),
+        (255, 0, 0),
+        (255, 255, 0),
+        (0, 255, 0),
+        (0, 255, 255),
+        (0, 0, 255),
+        (255, 0, 255),
+        (255, 255, 255),
+        (128, 128, 128),
+        (192, 192, 192),
+    ];
+    const GRAYS: [u8; 6] = [51, 80, 105, 130, 190, 255];
+    const LEVELS: [f64; 5] = [255.0, 204.0, 153.0, 127.0, 76.0];
+
+    match index {
+        0..=9 => BASE[index as usize],
+        250..=255 => {
+            let v = GRAYS[(index - 250) as usize];
+            (v, v, v)
+        }
+        _ => {
+            let hue = ((index / 10 - 1) as f64) * 15.0;
+            let shade = (index % 10) as usize;
+            let max = LEVELS[shade / 2];
+            let min = if shade % 2 == 1 { max / 2.0 } else { 0.0 };
+
+            // HSV with value `max` and floor `min`
+            let sector = hue / 60.0;
+            let f = sector.fract();
+            let rise = min + (max - min) * f;
+            let fall = max - (max - min) * f;
+            let (r, g, b) = match sector as u32 {
+                0 => (max, rise, min),
+                1 => (fall, max, min),
+                2 => (min, max, rise),
+                3 => (min, fall, max),
+                4 => (rise, min, max),
+                _ => (max, min, fall),
+            };
+            (r as u8, g as u8, b as u8)
+        }
+    }
+}
+
 impl fmt::Display for Color {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -131,6 +217,20 @@ mod tests {
         assert_eq!(color.index(), None);
     }
 
+    #[test]
+    fn test_aci_palette() {
+        assert_eq!(aci_to_rgb(1), (255, 0, 0));
+        assert_eq!(aci_to_rgb(10), (255, 0, 0));
+        assert_eq!(aci_to_rgb(11), (255, 127, 127));
+        assert_eq!(aci_to_rgb(12), (204, 0, 0));
+        assert_eq!(aci_to_rgb(30), (255, 127, 0));
+        assert_eq!(aci_to_rgb(90), (0, 255, 0));
+        assert_eq!(aci_to_rgb(170), (0, 0, 255));
+        assert_eq!(aci_to_rgb(250), (51, 51, 51));
+        assert_eq!(Color::ByLayer.to_rgb(), None);
+        assert_eq!(Color::from_rgb(1, 2, 3).to_rgb(), Some((1, 2, 3)));
+    }
+
     #[test]
     fn test_color_index() {
         let color = Color::Index(5);
@@ -138,6 +238,14 @@ mod tests {
         assert_eq!(color.rgb(), None);
     }
 
+    #[test]
+    fn test_color_cm_value() {
+        assert_eq!(Color::ByBlock.cm_value(), -1056964608);
+        for color in [Color::ByLayer, Color::ByBlock, Color::Index(30), Color::from_rgb(10, 20, 30)] {
+            assert_eq!(Color::from_cm_value(color.cm_value()), color);
+        }
+    }
+
     #[test]
     fn test_color_constants() {
         assert_eq!(Color::RED, Color::Index(1));
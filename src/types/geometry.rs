@@ -0,0 +1,72 @@
+//! Planar geometry helpers shared by entities, rendering and export
+
+use crate::types::{Matrix3, Vector2, Vector3};
+
+/// OCS → WCS rotation for an extrusion direction
+pub(crate) fn ocs_matrix(normal: Vector3) -> Matrix3 {
+    if normal.length() < 1e-12 || (normal.x.abs() < 1e-12 && normal.y.abs() < 1e-12 && normal.z > 0.0) {
+        Matrix3::identity()
+    } else {
+        Matrix3::arbitrary_axis(normal)
+    }
+}
+
+/// Signed area of a ring, positive when counter-clockwise
+pub fn signed_area(ring: &[Vector2]) -> f64 {
+    let n = ring.len();
+    (0..n)
+        .map(|i| {
+            let (a, b) = (ring[i], ring[(i + 1) % n]);
+            a.x * b.y - b.x * a.y
+        })
+        .sum::<f64>()
+        / 2.0
+}
+
+/// Even-odd point in polygon test
+pub(crate) fn point_in_polygon(ring: &[Vector2], p: Vector2) -> bool {
+    let n = ring.len();
+    let mut inside = false;
+    for i in 0..n {
+        let (a, b) = (ring[i], ring[(i + 1) % n]);
+        if (a.y > p.y) != (b.y > p.y) && p.x < a.x + (p.y - a.y) * (b.x - a.x) / (b.y - a.y) {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+/// Ear-clipping triangulation of a counter-clockwise simple polygon
+///
+/// Whatever cannot be clipped (self-intersecting or degenerate input) is
+/// fanned.
+pub fn triangulate(points: &[Vector2]) -> Vec<[usize; 3]> {
+    let cross = |a: Vector2, b: Vector2, c: Vector2| (b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x);
+    let inside = |p: Vector2, a: Vector2, b: Vector2, c: Vector2| {
+        cross(a, b, p) >= 0.0 && cross(b, c, p) >= 0.0 && cross(c, a, p) >= 0.0
+    };
+
+    let mut remaining: Vec<usize> = (0..points.len()).collect();
+    let mut triangles = Vec::with_capacity(points.len().saturating_sub(2));
+    while remaining.len() > 3 {
+        let m = remaining.len();
+        let ear = (0..m).find(|&k| {
+            let (a, b, c) = (remaining[(k + m - 1) % m], remaining[k], remaining[(k + 1) % m]);
+            let (pa, pb, pc) = (points[a], points[b], points[c]);
+            let blocked = remaining.iter().any(|&j| {
+                let p = points[j];
+                p != pa && p != pb && p != pc && inside(p, pa, pb, pc)
+            });
+            cross(pa, pb, pc) > 1e-12 && !blocked
+        });
+        let Some(k) = ear else {
+            break;
+        };
+        triangles.push([remaining[(k + m - 1) % m], remaining[k], remaining[(k + 1) % m]]);
+        remaining.remove(k);
+    }
+    for i in 1..remaining.len().saturating_sub(1) {
+        triangles.push([remaining[0], remaining[i], remaining[i + 1]]);
+    }
+    triangles
+}
@@ -8,6 +8,9 @@ use std::fmt;
 ///
 /// Handles are 64-bit unsigned integers that uniquely identify
 /// objects within a CAD document. Handle 0 is reserved and invalid.
+///
+/// With the `serde` feature a handle serializes as an upper-case hex
+/// string, as in DXF.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct Handle(u64);
 
@@ -46,6 +49,23 @@ impl Default for Handle {
     }
 }
 
+#[cfg(feature = "serde")]
+impl serde::Serialize for Handle {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&format_args!("{:X}", self.0))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Handle {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let hex = std::borrow::Cow::<str>::deserialize(deserializer)?;
+        u64::from_str_radix(&hex, 16)
+            .map(Handle)
+            .map_err(|_| serde::de::Error::custom(format!("invalid handle '{hex}'")))
+    }
+}
+
 impl From<u64> for Handle {
     fn from(value: u64) -> Self {
         Handle(value)
@@ -1,15 +1,18 @@
 //! Core types used throughout acadrust
 
+pub mod angle;
 pub mod bounds;
 pub mod color;
+pub(crate) mod geometry;
 pub mod handle;
 pub mod line_weight;
 pub mod transform;
 pub mod transparency;
 pub mod vector;
 
+pub use angle::Angle;
 pub use bounds::{BoundingBox2D, BoundingBox3D};
-pub use color::Color;
+pub use color::{aci_to_rgb, Color};
 pub use handle::Handle;
 pub use line_weight::LineWeight;
 pub use transform::{Matrix3, Matrix4, Transform, rotate_point_2d, is_zero_angle};
@@ -18,6 +21,7 @@ pub use vector::{Vector2, Vector3};
 
 /// DXF version enumeration
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DxfVersion {
     /// Unknown version
     Unknown,
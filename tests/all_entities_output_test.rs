@@ -90,7 +90,7 @@ fn create_all_entities_document() -> CadDocument {
     ];
     spline.degree = 3;
     spline.common.color = Color::from_rgb(255, 0, 255);
-    doc.add_entity(EntityType::Spline(spline)).unwrap();
+    doc.add_entity(EntityType::Spline(Box::new(spline))).unwrap();
     x += spacing;
 
     // Next row
@@ -113,7 +113,7 @@ fn create_all_entities_document() -> CadDocument {
     mtext.height = 2.5;
     mtext.rectangle_width = 15.0;
     mtext.common.color = Color::BLUE;
-    doc.add_entity(EntityType::MText(mtext)).unwrap();
+    doc.add_entity(EntityType::MText(Box::new(mtext))).unwrap();
     x += spacing;
 
     // Next row
@@ -193,7 +193,7 @@ fn create_all_entities_document() -> CadDocument {
     
     hatch.paths.push(boundary);
     hatch.common.color = Color::from_rgb(150, 150, 200);
-    doc.add_entity(EntityType::Hatch(hatch)).unwrap();
+    doc.add_entity(EntityType::Hatch(Box::new(hatch))).unwrap();
     x += spacing;
 
     // Next row
@@ -221,7 +221,7 @@ fn create_all_entities_document() -> CadDocument {
     attdef.insertion_point = Vector3::new(x, y, 0.0);
     attdef.height = 2.0;
     attdef.common.color = Color::YELLOW;
-    doc.add_entity(EntityType::AttributeDefinition(attdef)).unwrap();
+    doc.add_entity(EntityType::AttributeDefinition(Box::new(attdef))).unwrap();
     x += spacing;
 
     // 18. Attribute Entity
@@ -229,7 +229,7 @@ fn create_all_entities_document() -> CadDocument {
     attrib.insertion_point = Vector3::new(x, y, 0.0);
     attrib.height = 2.0;
     attrib.common.color = Color::CYAN;
-    doc.add_entity(EntityType::AttributeEntity(attrib)).unwrap();
+    doc.add_entity(EntityType::AttributeEntity(Box::new(attrib))).unwrap();
     x += spacing;
 
     // Next row
@@ -247,7 +247,7 @@ fn create_all_entities_document() -> CadDocument {
     ];
     leader.arrow_enabled = true;
     leader.common.color = Color::from_rgb(255, 100, 0);
-    doc.add_entity(EntityType::Leader(leader)).unwrap();
+    doc.add_entity(EntityType::Leader(Box::new(leader))).unwrap();
     x += spacing;
 
     // 20. MultiLeader
@@ -264,7 +264,7 @@ fn create_all_entities_document() -> CadDocument {
     
     multileader.context.text_location = Vector3::new(x + 5.0, y + 5.0, 0.0);
     multileader.common.color = Color::from_rgb(100, 255, 100);
-    doc.add_entity(EntityType::MultiLeader(multileader)).unwrap();
+    doc.add_entity(EntityType::MultiLeader(Box::new(multileader))).unwrap();
     x += spacing;
 
     // 21. MLine
@@ -340,7 +340,7 @@ fn create_all_entities_document() -> CadDocument {
     table.insertion_point = Vector3::new(x, y, 0.0);
     table.horizontal_direction = Vector3::UNIT_X;
     table.common.color = Color::from_rgb(200, 255, 200);
-    doc.add_entity(EntityType::Table(table)).unwrap();
+    doc.add_entity(EntityType::Table(Box::new(table))).unwrap();
     x += spacing;
 
     // 27. Tolerance
@@ -398,7 +398,7 @@ fn create_all_entities_document() -> CadDocument {
     viewport.view_center = Vector3::new(0.0, 0.0, 0.0);
     viewport.view_height = 100.0;
     viewport.common.color = Color::from_rgb(150, 150, 150);
-    doc.add_entity(EntityType::Viewport(viewport)).unwrap();
+    doc.add_entity(EntityType::Viewport(Box::new(viewport))).unwrap();
 
     doc
 }
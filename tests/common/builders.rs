@@ -111,7 +111,7 @@ pub fn create_all_entities_document() -> CadDocument {
     ];
     spline.degree = 3;
     spline.knots = vec![0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0];
-    doc.add_entity(EntityType::Spline(spline)).unwrap();
+    doc.add_entity(EntityType::Spline(Box::new(spline))).unwrap();
 
     // Row 3 — text
     x = 0.0;
@@ -130,7 +130,7 @@ pub fn create_all_entities_document() -> CadDocument {
     mtext.height = 2.5;
     mtext.rectangle_width = 15.0;
     mtext.common.color = Color::BLUE;
-    doc.add_entity(EntityType::MText(mtext)).unwrap();
+    doc.add_entity(EntityType::MText(Box::new(mtext))).unwrap();
 
     // Row 4 — solids / faces
     x = 0.0;
@@ -177,7 +177,7 @@ pub fn create_all_entities_document() -> CadDocument {
         Vector3::new(x, y, 0.0),
         Vector3::new(x + 10.0, y, 0.0),
     ));
-    doc.add_entity(EntityType::Dimension(dim_al)).unwrap();
+    doc.add_entity(EntityType::Dimension(Box::new(dim_al))).unwrap();
     x += sp;
 
     // 18. DimensionLinear
@@ -185,7 +185,7 @@ pub fn create_all_entities_document() -> CadDocument {
         Vector3::new(x, y, 0.0),
         Vector3::new(x + 10.0, y + 5.0, 0.0),
     ));
-    doc.add_entity(EntityType::Dimension(dim_lin)).unwrap();
+    doc.add_entity(EntityType::Dimension(Box::new(dim_lin))).unwrap();
     x += sp;
 
     // 19. DimensionRadius
@@ -193,7 +193,7 @@ pub fn create_all_entities_document() -> CadDocument {
         Vector3::new(x + 5.0, y + 5.0, 0.0),
         Vector3::new(x + 10.0, y + 5.0, 0.0),
     ));
-    doc.add_entity(EntityType::Dimension(dim_rad)).unwrap();
+    doc.add_entity(EntityType::Dimension(Box::new(dim_rad))).unwrap();
     x += sp;
 
     // 20. DimensionDiameter
@@ -201,7 +201,7 @@ pub fn create_all_entities_document() -> CadDocument {
         Vector3::new(x + 5.0, y + 5.0, 0.0),
         Vector3::new(x + 10.0, y + 5.0, 0.0),
     ));
-    doc.add_entity(EntityType::Dimension(dim_dia)).unwrap();
+    doc.add_entity(EntityType::Dimension(Box::new(dim_dia))).unwrap();
     x += sp;
 
     // 21. DimensionAngular2Ln
@@ -210,7 +210,7 @@ pub fn create_all_entities_document() -> CadDocument {
         Vector3::new(x + 5.0, y + 5.0, 0.0),
         Vector3::new(x + 10.0, y, 0.0),
     ));
-    doc.add_entity(EntityType::Dimension(dim_a2)).unwrap();
+    doc.add_entity(EntityType::Dimension(Box::new(dim_a2))).unwrap();
 
     x = 0.0;
     y += sp;
@@ -221,7 +221,7 @@ pub fn create_all_entities_document() -> CadDocument {
         Vector3::new(x + 5.0, y + 5.0, 0.0),
         Vector3::new(x + 10.0, y, 0.0),
     ));
-    doc.add_entity(EntityType::Dimension(dim_a3)).unwrap();
+    doc.add_entity(EntityType::Dimension(Box::new(dim_a3))).unwrap();
     x += sp;
 
     // 23. DimensionOrdinate
@@ -229,7 +229,7 @@ pub fn create_all_entities_document() -> CadDocument {
         Vector3::new(x, y, 0.0),
         Vector3::new(x + 5.0, y + 3.0, 0.0),
     ));
-    doc.add_entity(EntityType::Dimension(dim_ord)).unwrap();
+    doc.add_entity(EntityType::Dimension(Box::new(dim_ord))).unwrap();
 
     // Row 7 — hatch
     x = 0.0;
@@ -257,7 +257,7 @@ pub fn create_all_entities_document() -> CadDocument {
         end: Vector2::new(x, y),
     }));
     hatch.paths.push(boundary);
-    doc.add_entity(EntityType::Hatch(hatch)).unwrap();
+    doc.add_entity(EntityType::Hatch(Box::new(hatch))).unwrap();
 
     // Row 8 — block reference & attributes
     x = 0.0;
@@ -285,7 +285,7 @@ pub fn create_all_entities_document() -> CadDocument {
     );
     attdef.insertion_point = Vector3::new(x, y, 0.0);
     attdef.height = 2.0;
-    doc.add_entity(EntityType::AttributeDefinition(attdef))
+    doc.add_entity(EntityType::AttributeDefinition(Box::new(attdef)))
         .unwrap();
 
     // 27. (ATTRIB skipped — only valid as INSERT sub-entity)
@@ -303,7 +303,7 @@ pub fn create_all_entities_document() -> CadDocument {
     ];
     leader.arrow_enabled = true;
     leader.creation_type = LeaderCreationType::NoAnnotation;
-    doc.add_entity(EntityType::Leader(leader)).unwrap();
+    doc.add_entity(EntityType::Leader(Box::new(leader))).unwrap();
     x += sp;
 
     // 29. MultiLeader
@@ -315,7 +315,7 @@ pub fn create_all_entities_document() -> CadDocument {
         ])
         .build();
     mleader.line_type_handle = Some(doc.header.bylayer_linetype_handle);
-    doc.add_entity(EntityType::MultiLeader(mleader)).unwrap();
+    doc.add_entity(EntityType::MultiLeader(Box::new(mleader))).unwrap();
     x += sp;
 
     // 30. MLine — create Standard MLineStyle in OBJECTS
@@ -370,7 +370,7 @@ pub fn create_all_entities_document() -> CadDocument {
     table.insertion_point = Vector3::new(x, y, 0.0);
     table.horizontal_direction = Vector3::UNIT_X;
     table.block_record_handle = Some(table_block_handle);
-    doc.add_entity(EntityType::Table(table)).unwrap();
+    doc.add_entity(EntityType::Table(Box::new(table))).unwrap();
     x += sp;
 
     // 33. Tolerance
@@ -383,7 +383,7 @@ pub fn create_all_entities_document() -> CadDocument {
 
     // 34. RasterImage
     let raster = RasterImage::new("sample.png", Vector3::new(x, y, 0.0), 640.0, 480.0);
-    doc.add_entity(EntityType::RasterImage(raster)).unwrap();
+    doc.add_entity(EntityType::RasterImage(Box::new(raster))).unwrap();
 
     // Row 12 — polyface, wipeout, shape, viewport, underlay
     x = 0.0;
@@ -422,7 +422,7 @@ pub fn create_all_entities_document() -> CadDocument {
     viewport.height = 10.0;
     viewport.view_center = Vector3::new(0.0, 0.0, 0.0);
     viewport.view_height = 100.0;
-    doc.add_entity(EntityType::Viewport(viewport)).unwrap();
+    doc.add_entity(EntityType::Viewport(Box::new(viewport))).unwrap();
     x += sp;
 
     // 39. Underlay (PDF)
@@ -508,7 +508,7 @@ pub fn create_single_entity_doc(entity_name: &str) -> Option<CadDocument> {
             m.insertion_point = Vector3::new(x, y, 0.0);
             m.height = 2.5;
             m.rectangle_width = 15.0;
-            doc.add_entity(EntityType::MText(m)).ok()?;
+            doc.add_entity(EntityType::MText(Box::new(m))).ok()?;
         }
         "LWPOLYLINE" => {
             let mut lw = LwPolyline::new();
@@ -553,7 +553,7 @@ pub fn create_single_entity_doc(entity_name: &str) -> Option<CadDocument> {
             ];
             l.arrow_enabled = true;
             l.creation_type = LeaderCreationType::NoAnnotation;
-            doc.add_entity(EntityType::Leader(l)).ok()?;
+            doc.add_entity(EntityType::Leader(Box::new(l))).ok()?;
         }
         "TOLERANCE" => {
             let mut t = Tolerance::new();
@@ -572,7 +572,7 @@ pub fn create_single_entity_doc(entity_name: &str) -> Option<CadDocument> {
             ];
             s.degree = 3;
             s.knots = vec![0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0];
-            doc.add_entity(EntityType::Spline(s)).ok()?;
+            doc.add_entity(EntityType::Spline(Box::new(s))).ok()?;
         }
         _ => return None,
     }
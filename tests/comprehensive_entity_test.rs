@@ -105,7 +105,7 @@ fn create_all_entities_document() -> CadDocument {
         Vector3::new(x + 10.0, y + 7.0, 0.0),
     ];
     spline.degree = 3;
-    doc.add_entity(EntityType::Spline(spline)).unwrap();
+    doc.add_entity(EntityType::Spline(Box::new(spline))).unwrap();
 
     // row 3 — text
     x = 0.0; y += sp;
@@ -124,7 +124,7 @@ fn create_all_entities_document() -> CadDocument {
     mtext.height = 2.5;
     mtext.rectangle_width = 15.0;
     mtext.common.color = Color::BLUE;
-    doc.add_entity(EntityType::MText(mtext)).unwrap();
+    doc.add_entity(EntityType::MText(Box::new(mtext))).unwrap();
 
     // row 4 — solids / faces
     x = 0.0; y += sp;
@@ -168,7 +168,7 @@ fn create_all_entities_document() -> CadDocument {
         Vector3::new(x, y, 0.0),
         Vector3::new(x + 10.0, y, 0.0),
     ));
-    doc.add_entity(EntityType::Dimension(dim_al)).unwrap();
+    doc.add_entity(EntityType::Dimension(Box::new(dim_al))).unwrap();
     x += sp;
 
     // --- 18. DimensionLinear ---
@@ -176,7 +176,7 @@ fn create_all_entities_document() -> CadDocument {
         Vector3::new(x, y, 0.0),
         Vector3::new(x + 10.0, y + 5.0, 0.0),
     ));
-    doc.add_entity(EntityType::Dimension(dim_lin)).unwrap();
+    doc.add_entity(EntityType::Dimension(Box::new(dim_lin))).unwrap();
     x += sp;
 
     // --- 19. DimensionRadius ---
@@ -184,7 +184,7 @@ fn create_all_entities_document() -> CadDocument {
         Vector3::new(x + 5.0, y + 5.0, 0.0),
         Vector3::new(x + 10.0, y + 5.0, 0.0),
     ));
-    doc.add_entity(EntityType::Dimension(dim_rad)).unwrap();
+    doc.add_entity(EntityType::Dimension(Box::new(dim_rad))).unwrap();
     x += sp;
 
     // --- 20. DimensionDiameter ---
@@ -192,7 +192,7 @@ fn create_all_entities_document() -> CadDocument {
         Vector3::new(x + 5.0, y + 5.0, 0.0),
         Vector3::new(x + 10.0, y + 5.0, 0.0),
     ));
-    doc.add_entity(EntityType::Dimension(dim_dia)).unwrap();
+    doc.add_entity(EntityType::Dimension(Box::new(dim_dia))).unwrap();
     x += sp;
 
     // --- 21. DimensionAngular2Ln ---
@@ -201,7 +201,7 @@ fn create_all_entities_document() -> CadDocument {
         Vector3::new(x + 5.0, y + 5.0, 0.0),
         Vector3::new(x + 10.0, y, 0.0),
     ));
-    doc.add_entity(EntityType::Dimension(dim_a2)).unwrap();
+    doc.add_entity(EntityType::Dimension(Box::new(dim_a2))).unwrap();
 
     x = 0.0; y += sp;
 
@@ -211,7 +211,7 @@ fn create_all_entities_document() -> CadDocument {
         Vector3::new(x + 5.0, y + 5.0, 0.0),
         Vector3::new(x + 10.0, y, 0.0),
     ));
-    doc.add_entity(EntityType::Dimension(dim_a3)).unwrap();
+    doc.add_entity(EntityType::Dimension(Box::new(dim_a3))).unwrap();
     x += sp;
 
     // --- 23. DimensionOrdinate ---
@@ -219,7 +219,7 @@ fn create_all_entities_document() -> CadDocument {
         Vector3::new(x, y, 0.0),
         Vector3::new(x + 5.0, y + 3.0, 0.0),
     ));
-    doc.add_entity(EntityType::Dimension(dim_ord)).unwrap();
+    doc.add_entity(EntityType::Dimension(Box::new(dim_ord))).unwrap();
 
     // row 7 — hatch
     x = 0.0; y += sp;
@@ -242,7 +242,7 @@ fn create_all_entities_document() -> CadDocument {
         start: Vector2::new(x, y + 10.0), end: Vector2::new(x, y),
     }));
     hatch.paths.push(boundary);
-    doc.add_entity(EntityType::Hatch(hatch)).unwrap();
+    doc.add_entity(EntityType::Hatch(Box::new(hatch))).unwrap();
 
     // row 8 — block reference & attributes
     x = 0.0; y += sp;
@@ -263,14 +263,14 @@ fn create_all_entities_document() -> CadDocument {
     );
     attdef.insertion_point = Vector3::new(x, y, 0.0);
     attdef.height = 2.0;
-    doc.add_entity(EntityType::AttributeDefinition(attdef)).unwrap();
+    doc.add_entity(EntityType::AttributeDefinition(Box::new(attdef))).unwrap();
     x += sp;
 
     // --- 27. AttributeEntity ---
     let mut attrib = AttributeEntity::new("TAGVAL".to_string(), "Attr Value".to_string());
     attrib.insertion_point = Vector3::new(x, y, 0.0);
     attrib.height = 2.0;
-    doc.add_entity(EntityType::AttributeEntity(attrib)).unwrap();
+    doc.add_entity(EntityType::AttributeEntity(Box::new(attrib))).unwrap();
 
     // row 9 — leaders
     x = 0.0; y += sp;
@@ -283,7 +283,7 @@ fn create_all_entities_document() -> CadDocument {
         Vector3::new(x + 8.0, y + 3.0, 0.0),
     ];
     leader.arrow_enabled = true;
-    doc.add_entity(EntityType::Leader(leader)).unwrap();
+    doc.add_entity(EntityType::Leader(Box::new(leader))).unwrap();
     x += sp;
 
     // --- 29. MultiLeader ---
@@ -294,7 +294,7 @@ fn create_all_entities_document() -> CadDocument {
             Vector3::new(x + 5.0, y + 5.0, 0.0),
         ])
         .build();
-    doc.add_entity(EntityType::MultiLeader(mleader)).unwrap();
+    doc.add_entity(EntityType::MultiLeader(Box::new(mleader))).unwrap();
     x += sp;
 
     // --- 30. MLine ---
@@ -346,7 +346,7 @@ fn create_all_entities_document() -> CadDocument {
     let mut table = TableBuilder::new(2, 2).build();
     table.insertion_point = Vector3::new(x, y, 0.0);
     table.horizontal_direction = Vector3::UNIT_X;
-    doc.add_entity(EntityType::Table(table)).unwrap();
+    doc.add_entity(EntityType::Table(Box::new(table))).unwrap();
     x += sp;
 
     // --- 36. Tolerance ---
@@ -364,7 +364,7 @@ fn create_all_entities_document() -> CadDocument {
         640.0,
         480.0,
     );
-    doc.add_entity(EntityType::RasterImage(raster)).unwrap();
+    doc.add_entity(EntityType::RasterImage(Box::new(raster))).unwrap();
 
     // row 12 — polyface, wipeout, shape, viewport, underlay
     x = 0.0; y += sp;
@@ -406,7 +406,7 @@ fn create_all_entities_document() -> CadDocument {
     viewport.height = 10.0;
     viewport.view_center = Vector3::new(0.0, 0.0, 0.0);
     viewport.view_height = 100.0;
-    doc.add_entity(EntityType::Viewport(viewport)).unwrap();
+    doc.add_entity(EntityType::Viewport(Box::new(viewport))).unwrap();
     x += sp;
 
     // --- 42. Underlay (PDF) ---
@@ -70,7 +70,7 @@ fn build_test_document(version: DxfVersion) -> CadDocument {
     mtext.insertion_point = Vector3::new(100.0, 200.0, 0.0);
     mtext.height = 5.0;
     mtext.rectangle_width = 40.0;
-    doc.add_entity(EntityType::MText(mtext)).unwrap();
+    doc.add_entity(EntityType::MText(Box::new(mtext))).unwrap();
 
     // 8. LwPolyline (closed rectangle)
     let mut lwpoly = LwPolyline::new();
@@ -91,7 +91,7 @@ fn build_test_document(version: DxfVersion) -> CadDocument {
     ];
     spline.degree = 3;
     spline.knots = vec![0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0];
-    doc.add_entity(EntityType::Spline(spline)).unwrap();
+    doc.add_entity(EntityType::Spline(Box::new(spline))).unwrap();
 
     // 10. Solid (2D)
     let solid = Solid::new(
@@ -124,14 +124,14 @@ fn build_test_document(version: DxfVersion) -> CadDocument {
         Vector3::new(0.0, 400.0, 0.0),
         Vector3::new(50.0, 400.0, 0.0),
     ));
-    doc.add_entity(EntityType::Dimension(dim)).unwrap();
+    doc.add_entity(EntityType::Dimension(Box::new(dim))).unwrap();
 
     // 15. DimensionLinear
     let dim_lin = Dimension::Linear(DimensionLinear::new(
         Vector3::new(100.0, 400.0, 0.0),
         Vector3::new(150.0, 425.0, 0.0),
     ));
-    doc.add_entity(EntityType::Dimension(dim_lin)).unwrap();
+    doc.add_entity(EntityType::Dimension(Box::new(dim_lin))).unwrap();
 
     doc
 }
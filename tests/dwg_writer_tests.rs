@@ -718,7 +718,7 @@ mod phase4_hatch {
     fn make_solid_hatch() -> EntityType {
         let mut hatch = Hatch::solid();
         hatch.add_path(rect_polyline_path(0.0, 0.0, 10.0, 10.0));
-        EntityType::Hatch(hatch)
+        EntityType::Hatch(Box::new(hatch))
     }
 
     fn make_pattern_hatch() -> EntityType {
@@ -734,7 +734,7 @@ mod phase4_hatch {
         hatch_ent.pattern_scale = 1.0;
         hatch_ent.pattern_type = HatchPatternType::Predefined;
         hatch_ent.add_path(rect_polyline_path(0.0, 0.0, 20.0, 15.0));
-        EntityType::Hatch(hatch_ent)
+        EntityType::Hatch(Box::new(hatch_ent))
     }
 
     fn make_gradient_hatch() -> EntityType {
@@ -759,7 +759,7 @@ mod phase4_hatch {
             name: "LINEAR".to_string(),
         };
         hatch_ent.add_path(rect_polyline_path(0.0, 0.0, 30.0, 20.0));
-        EntityType::Hatch(hatch_ent)
+        EntityType::Hatch(Box::new(hatch_ent))
     }
 
     // -----------------------------------------------------------------------
@@ -840,7 +840,7 @@ mod phase4_hatch {
         hatch.add_path(rect_polyline_path(0.0, 0.0, 10.0, 10.0));
         assert!(hatch.paths[0].is_polyline());
 
-        let rdoc = roundtrip_entity(EntityType::Hatch(hatch), "hatch_polyline_boundary");
+        let rdoc = roundtrip_entity(EntityType::Hatch(Box::new(hatch)), "hatch_polyline_boundary");
         assert!(common::entity_count(&rdoc) >= 1);
     }
 
@@ -849,7 +849,7 @@ mod phase4_hatch {
         let mut hatch = Hatch::solid();
         hatch.add_path(triangle_line_path());
 
-        let rdoc = roundtrip_entity(EntityType::Hatch(hatch), "hatch_line_edge");
+        let rdoc = roundtrip_entity(EntityType::Hatch(Box::new(hatch)), "hatch_line_edge");
         assert!(common::entity_count(&rdoc) >= 1);
     }
 
@@ -872,7 +872,7 @@ mod phase4_hatch {
         let mut hatch = Hatch::solid();
         hatch.add_path(path);
 
-        let rdoc = roundtrip_entity(EntityType::Hatch(hatch), "hatch_arc_edge");
+        let rdoc = roundtrip_entity(EntityType::Hatch(Box::new(hatch)), "hatch_arc_edge");
         assert!(common::entity_count(&rdoc) >= 1);
     }
 
@@ -891,7 +891,7 @@ mod phase4_hatch {
         let mut hatch = Hatch::solid();
         hatch.add_path(path);
 
-        let rdoc = roundtrip_entity(EntityType::Hatch(hatch), "hatch_ellipse_edge");
+        let rdoc = roundtrip_entity(EntityType::Hatch(Box::new(hatch)), "hatch_ellipse_edge");
         assert!(common::entity_count(&rdoc) >= 1);
     }
 
@@ -922,7 +922,7 @@ mod phase4_hatch {
         let mut hatch = Hatch::solid();
         hatch.add_path(path);
 
-        let rdoc = roundtrip_entity(EntityType::Hatch(hatch), "hatch_spline_edge");
+        let rdoc = roundtrip_entity(EntityType::Hatch(Box::new(hatch)), "hatch_spline_edge");
         assert!(common::entity_count(&rdoc) >= 1);
     }
 
@@ -956,7 +956,7 @@ mod phase4_hatch {
         let mut hatch = Hatch::solid();
         hatch.add_path(path);
 
-        let rdoc = roundtrip_entity(EntityType::Hatch(hatch), "hatch_mixed_edge");
+        let rdoc = roundtrip_entity(EntityType::Hatch(Box::new(hatch)), "hatch_mixed_edge");
         assert!(common::entity_count(&rdoc) >= 1);
     }
 
@@ -980,7 +980,7 @@ mod phase4_hatch {
 
         assert_eq!(hatch.path_count(), 2);
 
-        let rdoc = roundtrip_entity(EntityType::Hatch(hatch), "hatch_multi_boundary");
+        let rdoc = roundtrip_entity(EntityType::Hatch(Box::new(hatch)), "hatch_multi_boundary");
         assert!(common::entity_count(&rdoc) >= 1);
     }
 
@@ -1002,7 +1002,7 @@ mod phase4_hatch {
         let mut hatch = Hatch::solid();
         hatch.add_path(path);
 
-        let rdoc = roundtrip_entity(EntityType::Hatch(hatch), "hatch_with_bulge");
+        let rdoc = roundtrip_entity(EntityType::Hatch(Box::new(hatch)), "hatch_with_bulge");
         assert!(common::entity_count(&rdoc) >= 1);
     }
 
@@ -1022,7 +1022,7 @@ mod phase4_hatch {
 
         assert!(hatch.is_double);
 
-        let rdoc = roundtrip_entity(EntityType::Hatch(hatch), "hatch_double");
+        let rdoc = roundtrip_entity(EntityType::Hatch(Box::new(hatch)), "hatch_double");
         assert!(common::entity_count(&rdoc) >= 1);
     }
 
@@ -1036,7 +1036,7 @@ mod phase4_hatch {
 
         assert_eq!(hatch.seed_points.len(), 3);
 
-        let rdoc = roundtrip_entity(EntityType::Hatch(hatch), "hatch_seed_points");
+        let rdoc = roundtrip_entity(EntityType::Hatch(Box::new(hatch)), "hatch_seed_points");
         assert!(common::entity_count(&rdoc) >= 1);
     }
 
@@ -1048,7 +1048,7 @@ mod phase4_hatch {
 
         assert!(hatch.is_associative);
 
-        let rdoc = roundtrip_entity(EntityType::Hatch(hatch), "hatch_associative");
+        let rdoc = roundtrip_entity(EntityType::Hatch(Box::new(hatch)), "hatch_associative");
         assert!(common::entity_count(&rdoc) >= 1);
     }
 
@@ -1101,7 +1101,7 @@ mod phase4_hatch {
 
         assert!((hatch.pattern_scale - 2.5).abs() < 1e-10);
 
-        let rdoc = roundtrip_entity(EntityType::Hatch(hatch), "hatch_scale_preserved");
+        let rdoc = roundtrip_entity(EntityType::Hatch(Box::new(hatch)), "hatch_scale_preserved");
         assert!(common::entity_count(&rdoc) >= 1);
     }
 
@@ -1127,7 +1127,7 @@ mod phase4_hatch {
         }));
         hatch.add_path(path);
 
-        let rdoc = roundtrip_entity(EntityType::Hatch(hatch), "hatch_boundary_coords");
+        let rdoc = roundtrip_entity(EntityType::Hatch(Box::new(hatch)), "hatch_boundary_coords");
         assert!(common::entity_count(&rdoc) >= 1);
     }
 
@@ -1140,7 +1140,7 @@ mod phase4_hatch {
 
         assert_eq!(hatch.seed_points.len(), 2);
 
-        let rdoc = roundtrip_entity(EntityType::Hatch(hatch), "hatch_seeds_preserved");
+        let rdoc = roundtrip_entity(EntityType::Hatch(Box::new(hatch)), "hatch_seeds_preserved");
         assert!(common::entity_count(&rdoc) >= 1);
     }
 
@@ -1170,7 +1170,7 @@ mod phase4_hatch {
             hatch.add_path(rect_polyline_path(0.0, 0.0, 10.0, 10.0));
 
             let rdoc = roundtrip_entity(
-                EntityType::Hatch(hatch),
+                EntityType::Hatch(Box::new(hatch)),
                 &format!("hatch_style_{:?}", style),
             );
             assert!(common::entity_count(&rdoc) >= 1);
@@ -1190,7 +1190,7 @@ mod phase4_hatch {
         hatch.pattern_type = HatchPatternType::UserDefined;
         hatch.add_path(rect_polyline_path(0.0, 0.0, 50.0, 50.0));
 
-        let rdoc = roundtrip_entity(EntityType::Hatch(hatch), "hatch_dashes");
+        let rdoc = roundtrip_entity(EntityType::Hatch(Box::new(hatch)), "hatch_dashes");
         assert!(common::entity_count(&rdoc) >= 1);
     }
 
@@ -1215,7 +1215,7 @@ mod phase4_hatch {
 
         assert_eq!(hatch.pattern.lines.len(), 2);
 
-        let rdoc = roundtrip_entity(EntityType::Hatch(hatch), "hatch_crosshatch");
+        let rdoc = roundtrip_entity(EntityType::Hatch(Box::new(hatch)), "hatch_crosshatch");
         assert!(common::entity_count(&rdoc) >= 1);
     }
 
@@ -1239,7 +1239,7 @@ mod phase4_hatch {
         };
         hatch.add_path(rect_polyline_path(0.0, 0.0, 15.0, 15.0));
 
-        let rdoc = roundtrip_entity(EntityType::Hatch(hatch), "hatch_gradient_single");
+        let rdoc = roundtrip_entity(EntityType::Hatch(Box::new(hatch)), "hatch_gradient_single");
         assert!(common::entity_count(&rdoc) >= 1);
     }
 
@@ -1252,7 +1252,7 @@ mod phase4_hatch {
 
         assert!((hatch.elevation - 5.0).abs() < 1e-10);
 
-        let rdoc = roundtrip_entity(EntityType::Hatch(hatch), "hatch_elevation_normal");
+        let rdoc = roundtrip_entity(EntityType::Hatch(Box::new(hatch)), "hatch_elevation_normal");
         assert!(common::entity_count(&rdoc) >= 1);
     }
 
@@ -1323,7 +1323,7 @@ mod phase4_hatch {
 
         let mut doc = CadDocument::new();
         doc.version = DxfVersion::AC1024; // R2010
-        doc.add_entity(EntityType::Hatch(hatch)).unwrap();
+        doc.add_entity(EntityType::Hatch(Box::new(hatch))).unwrap();
         let rdoc = common::roundtrip_dxf(&doc, "hatch_spline_fit_r2010");
         assert!(common::entity_count(&rdoc) >= 1);
     }
@@ -1375,7 +1375,7 @@ mod phase6_multileader_images {
                 Vector3::new(5.0, 10.0, 0.0),
             ],
         );
-        EntityType::MultiLeader(mleader)
+        EntityType::MultiLeader(Box::new(mleader))
     }
 
     fn make_multileader_block() -> EntityType {
@@ -1390,7 +1390,7 @@ mod phase6_multileader_images {
             Vector3::new(0.0, 0.0, 0.0),
             Vector3::new(8.0, 12.0, 0.0),
         ]);
-        EntityType::MultiLeader(mleader)
+        EntityType::MultiLeader(Box::new(mleader))
     }
 
     #[test]
@@ -1480,7 +1480,7 @@ mod phase6_multileader_images {
         assert_eq!(mleader.total_leader_line_count(), 2);
 
         let rdoc = roundtrip_entity(
-            EntityType::MultiLeader(mleader),
+            EntityType::MultiLeader(Box::new(mleader)),
             "mleader_multi_root",
         );
         assert!(common::entity_count(&rdoc) >= 1);
@@ -1514,7 +1514,7 @@ mod phase6_multileader_images {
         ));
 
         let rdoc = roundtrip_entity(
-            EntityType::MultiLeader(mleader),
+            EntityType::MultiLeader(Box::new(mleader)),
             "mleader_override_flags",
         );
         assert!(common::entity_count(&rdoc) >= 1);
@@ -1542,7 +1542,7 @@ mod phase6_multileader_images {
         assert_eq!(mleader.block_attributes.len(), 2);
 
         let rdoc = roundtrip_entity(
-            EntityType::MultiLeader(mleader),
+            EntityType::MultiLeader(Box::new(mleader)),
             "mleader_block_attrs",
         );
         assert!(common::entity_count(&rdoc) >= 1);
@@ -1559,7 +1559,7 @@ mod phase6_multileader_images {
             640.0,
             480.0,
         );
-        EntityType::RasterImage(image)
+        EntityType::RasterImage(Box::new(image))
     }
 
     fn make_raster_image_clipped() -> EntityType {
@@ -1574,7 +1574,7 @@ mod phase6_multileader_images {
             Vector2::new(10.0, 10.0),
             Vector2::new(400.0, 300.0),
         );
-        EntityType::RasterImage(image)
+        EntityType::RasterImage(Box::new(image))
     }
 
     #[test]
@@ -1616,7 +1616,7 @@ mod phase6_multileader_images {
         assert_eq!(image.clip_boundary.vertex_count(), 2);
 
         let rdoc = roundtrip_entity(
-            EntityType::RasterImage(image),
+            EntityType::RasterImage(Box::new(image)),
             "raster_clip_rect",
         );
         assert!(common::entity_count(&rdoc) >= 1);
@@ -1641,7 +1641,7 @@ mod phase6_multileader_images {
         assert_eq!(image.clip_boundary.vertex_count(), 4);
 
         let rdoc = roundtrip_entity(
-            EntityType::RasterImage(image),
+            EntityType::RasterImage(Box::new(image)),
             "raster_clip_poly",
         );
         assert!(common::entity_count(&rdoc) >= 1);
@@ -1664,7 +1664,7 @@ mod phase6_multileader_images {
         assert!((image.insertion_point.y - 99.1).abs() < 1e-10);
 
         let rdoc = roundtrip_entity(
-            EntityType::RasterImage(image),
+            EntityType::RasterImage(Box::new(image)),
             "raster_insertion_pt",
         );
         assert!(common::entity_count(&rdoc) >= 1);
@@ -1684,7 +1684,7 @@ mod phase6_multileader_images {
         assert!((image.height() - 1080.0).abs() < 1e-10);
 
         let rdoc = roundtrip_entity(
-            EntityType::RasterImage(image),
+            EntityType::RasterImage(Box::new(image)),
             "raster_size",
         );
         assert!(common::entity_count(&rdoc) >= 1);
@@ -1703,7 +1703,7 @@ mod phase6_multileader_images {
         image.fade = 25;
 
         let rdoc = roundtrip_entity(
-            EntityType::RasterImage(image),
+            EntityType::RasterImage(Box::new(image)),
             "raster_brightness",
         );
         assert!(common::entity_count(&rdoc) >= 1);
@@ -1723,7 +1723,7 @@ mod phase6_multileader_images {
         assert!((image.height() - 37.5).abs() < 0.1);
 
         let rdoc = roundtrip_entity(
-            EntityType::RasterImage(image),
+            EntityType::RasterImage(Box::new(image)),
             "raster_world_size",
         );
         assert!(common::entity_count(&rdoc) >= 1);
@@ -3295,6 +3295,717 @@ mod phase8_remaining_objects {
         }
     }
 
+    // =======================================================================
+    // UNDERLAY DEFINITION
+    // =======================================================================
+
+    #[test]
+    fn test_underlay_definition_dwg_roundtrip() {
+        use acadrust::io::dwg::{DwgReader, DwgWriter};
+        use acadrust::types::Vector3;
+
+        let mut doc = CadDocument::new();
+        let underlay = doc.attach_pdf_underlay("site.pdf", "1", Vector3::ZERO, 1.0).unwrap();
+
+        let bytes = DwgWriter::write(&doc).unwrap();
+        let read = DwgReader::from_reader(std::io::Cursor::new(bytes)).unwrap().read().unwrap();
+        let definition = read
+            .objects
+            .values()
+            .find_map(|o| match o {
+                ObjectType::UnderlayDefinition(d) => Some(d.clone()),
+                _ => None,
+            })
+            .expect("PDFDEFINITION after round-trip");
+        assert_eq!(definition.file_path, "site.pdf");
+        assert_eq!(definition.page_name, "1");
+        assert_eq!(definition.reactors.last(), Some(&underlay));
+    }
+
+    #[test]
+    fn test_image_and_wipeout_variables_dwg_roundtrip() {
+        use acadrust::io::dwg::{DwgReader, DwgWriter};
+        use acadrust::types::DxfVersion;
+
+        for version in [DxfVersion::AC1015, DxfVersion::AC1018, DxfVersion::AC1032] {
+            let mut doc = CadDocument::with_version(version);
+            let mut raster = RasterVariables::new();
+            raster.display_image_frame = 0;
+            raster.units = 2;
+            doc.set_raster_variables(raster).unwrap();
+            doc.set_wipeout_variables(WipeoutVariables { display_frame: 1, ..Default::default() })
+                .unwrap();
+
+            let bytes = DwgWriter::write(&doc).unwrap();
+            let read = DwgReader::from_reader(std::io::Cursor::new(bytes)).unwrap().read().unwrap();
+            let raster = read.raster_variables().expect("RASTERVARIABLES after round-trip");
+            assert_eq!((raster.display_image_frame, raster.image_quality, raster.units), (0, 1, 2));
+            assert_eq!(read.wipeout_variables().map(|w| w.display_frame), Some(1), "{version:?}");
+        }
+    }
+
+    #[test]
+    fn test_multileader_style_dwg_roundtrip() {
+        use acadrust::io::dwg::{DwgReader, DwgWriter};
+        use acadrust::types::DxfVersion;
+
+        for version in [DxfVersion::AC1015, DxfVersion::AC1024, DxfVersion::AC1032] {
+            let mut doc = CadDocument::with_version(version);
+            let handle = doc.multileader_style("Standard").unwrap().handle;
+            let Some(ObjectType::MultiLeaderStyle(style)) = doc.objects.get_mut(&handle) else {
+                unreachable!()
+            };
+            style.line_color = Color::Index(1);
+            style.default_text = "NOTE".to_string();
+            style.landing_gap = 0.5;
+            style.text_bottom_attachment = TextAttachmentType::MiddleOfText;
+
+            let bytes = DwgWriter::write(&doc).unwrap();
+            let read = DwgReader::from_reader(std::io::Cursor::new(bytes)).unwrap().read().unwrap();
+            let styles: Vec<_> = read
+                .objects
+                .values()
+                .filter_map(|o| match o {
+                    ObjectType::MultiLeaderStyle(s) => Some(s),
+                    _ => None,
+                })
+                .collect();
+            assert_eq!(styles.len(), 1, "{version:?}");
+            let style = styles[0];
+            assert_eq!(style.name, "Standard");
+            assert_eq!(style.line_color, Color::Index(1));
+            assert_eq!(style.default_text, "NOTE");
+            assert_eq!(style.landing_gap, 0.5);
+            let bottom = if version >= DxfVersion::AC1024 {
+                TextAttachmentType::MiddleOfText
+            } else {
+                TextAttachmentType::BottomOfBottomLine
+            };
+            assert_eq!(style.text_bottom_attachment, bottom);
+        }
+    }
+
+    #[test]
+    fn test_raster_image_definitions_dwg_roundtrip() {
+        use acadrust::entities::{EntityType, RasterImage};
+        use acadrust::io::dwg::{DwgReader, DwgWriter};
+        use acadrust::types::{DxfVersion, Vector3};
+
+        for version in [DxfVersion::AC1015, DxfVersion::AC1018, DxfVersion::AC1032] {
+            let mut doc = CadDocument::with_version(version);
+            for x in [0.0, 10.0] {
+                let image = RasterImage::new("C:\\images\\site.png", Vector3::new(x, 0.0, 0.0), 640.0, 480.0);
+                doc.add_entity(EntityType::RasterImage(Box::new(image))).unwrap();
+            }
+
+            let bytes = DwgWriter::write(&doc).unwrap();
+            let read = DwgReader::from_reader(std::io::Cursor::new(bytes)).unwrap().read().unwrap();
+
+            let images: Vec<_> = read
+                .entities()
+                .filter_map(|e| match e {
+                    EntityType::RasterImage(image) => Some(image),
+                    _ => None,
+                })
+                .collect();
+            assert_eq!(images.len(), 2, "{version:?}");
+            let definition_handle = images[0].definition_handle.expect("IMAGEDEF handle");
+            assert_eq!(images[1].definition_handle, Some(definition_handle));
+            let Some(ObjectType::ImageDefinition(definition)) = read.objects.get(&definition_handle) else {
+                panic!("{version:?}: IMAGEDEF missing");
+            };
+            assert_eq!(definition.file_name, "C:\\images\\site.png");
+            assert_eq!(definition.size_in_pixels, (640, 480));
+            assert_eq!(definition.reactors.len(), 3, "{version:?}");
+            assert_eq!(definition.reactors[0], definition.owner);
+
+            for image in images {
+                let reactor = image.definition_reactor_handle.expect("IMAGEDEF_REACTOR handle");
+                assert!(definition.reactors.contains(&reactor));
+                let Some(ObjectType::ImageDefinitionReactor(reactor)) = read.objects.get(&reactor) else {
+                    panic!("{version:?}: IMAGEDEF_REACTOR missing");
+                };
+                assert_eq!(reactor.image_handle, image.common.handle);
+            }
+            assert!(read.raster_variables().is_some());
+        }
+    }
+
+    #[test]
+    fn test_scale_list_dwg_roundtrip() {
+        use acadrust::io::dwg::{DwgReader, DwgWriter};
+        use acadrust::types::DxfVersion;
+
+        for version in [DxfVersion::AC1018, DxfVersion::AC1024, DxfVersion::AC1032] {
+            let mut doc = CadDocument::with_version(version);
+            doc.add_scale(Scale::unit_scale()).unwrap();
+            let handle = doc.add_scale(Scale::scale_1_100()).unwrap();
+
+            let bytes = DwgWriter::write(&doc).unwrap();
+            let read = DwgReader::from_reader(std::io::Cursor::new(bytes)).unwrap().read().unwrap();
+            let scale = read.scale("1:100").unwrap_or_else(|| panic!("{version:?}: SCALE missing"));
+            assert_eq!(scale.handle, handle);
+            assert_eq!((scale.paper_units, scale.drawing_units), (1.0, 100.0));
+            assert!(read.scale("1:1").is_some_and(|s| s.is_unit_scale));
+        }
+    }
+
+    #[test]
+    fn test_dynamic_block_records_dwg_roundtrip() {
+        use acadrust::io::dwg::{DwgReader, DwgWriter};
+        use acadrust::tables::BlockRecord;
+        use acadrust::types::DxfVersion;
+
+        for version in [DxfVersion::AC1015, DxfVersion::AC1018, DxfVersion::AC1032] {
+            let mut doc = CadDocument::with_version(version);
+            let mut door = BlockRecord::new("DOOR");
+            door.handle = doc.allocate_handle();
+            door.block_entity_handle = doc.allocate_handle();
+            door.block_end_handle = doc.allocate_handle();
+            let mut xdictionary = Dictionary::new();
+            xdictionary.handle = doc.allocate_handle();
+            xdictionary.owner = door.handle;
+            xdictionary.hard_owner = true;
+            door.xdictionary_handle = Some(xdictionary.handle);
+            doc.objects.insert(xdictionary.handle, ObjectType::Dictionary(xdictionary.clone()));
+            doc.block_records.add(door).unwrap();
+
+            let mut representation = BlockRecord::new("*U2");
+            representation.handle = doc.allocate_handle();
+            representation.block_entity_handle = doc.allocate_handle();
+            representation.block_end_handle = doc.allocate_handle();
+            representation.flags.anonymous = true;
+            doc.block_records.add(representation).unwrap();
+
+            let bytes = DwgWriter::write(&doc).unwrap();
+            let read = DwgReader::from_reader(std::io::Cursor::new(bytes)).unwrap().read().unwrap();
+            let door = read.block_records.get("DOOR").unwrap_or_else(|| panic!("{version:?}: DOOR missing"));
+            assert_eq!(door.xdictionary_handle, Some(xdictionary.handle), "{version:?}");
+            assert!(!door.flags.anonymous, "{version:?}");
+            let representation = read.block_records.get("*U2").unwrap();
+            assert!(representation.flags.anonymous, "{version:?}");
+            assert!(representation.xdictionary_handle.is_none(), "{version:?}");
+        }
+    }
+
+    /// Plot style dictionary as AutoCAD writes it: ACDBDICTIONARYWDFLT with
+    /// the Normal placeholder as its default entry
+    fn plot_style_dictionary(doc: &mut CadDocument) -> (Handle, Handle) {
+        let psn = doc.header.acad_plotstylename_dict_handle;
+        let mut normal = PlaceHolder::new();
+        normal.handle = doc.allocate_handle();
+        normal.owner = psn;
+        let mut dictionary = DictionaryWithDefault::new();
+        dictionary.handle = psn;
+        dictionary.owner = doc.header.named_objects_dict_handle;
+        dictionary.add_entry("Normal", normal.handle);
+        assert!(dictionary.set_default("Normal"));
+        doc.objects.insert(normal.handle, ObjectType::PlaceHolder(normal.clone()));
+        doc.objects.insert(psn, ObjectType::DictionaryWithDefault(dictionary));
+        (psn, normal.handle)
+    }
+
+    #[test]
+    fn test_dictionary_with_default_dwg_roundtrip() {
+        use acadrust::io::dwg::{DwgReader, DwgWriter};
+        use acadrust::types::DxfVersion;
+
+        for version in [DxfVersion::AC1015, DxfVersion::AC1018, DxfVersion::AC1032] {
+            let mut doc = CadDocument::with_version(version);
+            let (psn, normal) = plot_style_dictionary(&mut doc);
+
+            let bytes = DwgWriter::write(&doc).unwrap();
+            let read = DwgReader::from_reader(std::io::Cursor::new(bytes)).unwrap().read().unwrap();
+            match read.objects.get(&psn) {
+                Some(ObjectType::DictionaryWithDefault(dictionary)) => {
+                    assert_eq!(dictionary.default_handle, normal, "{version:?}");
+                    assert_eq!(dictionary.default_key(), Some("Normal"), "{version:?}");
+                }
+                other => panic!("{version:?}: expected ACDBDICTIONARYWDFLT, got {other:?}"),
+            }
+            assert!(matches!(read.objects.get(&normal), Some(ObjectType::PlaceHolder(_))), "{version:?}");
+            assert_eq!(read.plot_style_handle("Screening 50%"), Some(normal), "{version:?}");
+        }
+    }
+
+    #[test]
+    fn test_dictionary_with_default_dxf_roundtrip() {
+        let mut doc = CadDocument::new();
+        let (psn, normal) = plot_style_dictionary(&mut doc);
+        let read = common::roundtrip_dxf(&doc, "phase8_dict_with_default");
+        match read.objects.get(&psn) {
+            Some(ObjectType::DictionaryWithDefault(dictionary)) => {
+                assert_eq!(dictionary.get("Normal"), Some(normal));
+                assert_eq!(dictionary.resolve("Other"), Some(normal));
+            }
+            other => panic!("expected ACDBDICTIONARYWDFLT, got {other:?}"),
+        }
+        assert!(matches!(read.objects.get(&normal), Some(ObjectType::PlaceHolder(_))));
+    }
+
+    /// Aligned dimension linked to both ends of a line, returning the
+    /// handles of the line, the dimension and the DIMASSOC
+    fn associative_dimension(doc: &mut CadDocument) -> (Handle, Handle, Handle) {
+        use acadrust::entities::{Dimension, DimensionAligned, EntityType, Line};
+        use acadrust::types::Vector3;
+
+        let end = Vector3::new(10.0, 0.0, 0.0);
+        let line = doc.add_entity(EntityType::Line(Line::from_points(Vector3::ZERO, end))).unwrap();
+        let aligned = DimensionAligned::new(Vector3::ZERO, end);
+        let dimension = doc.add_entity(EntityType::Dimension(Box::new(Dimension::Aligned(aligned)))).unwrap();
+        let mut second = OsnapPointRef::new(ObjectSnapType::Endpoint, line, end);
+        second.gs_marker = 7;
+        second.near_parameter = 10.0;
+        let association = DimAssoc::new(dimension)
+            .with_point(0, OsnapPointRef::new(ObjectSnapType::Endpoint, line, Vector3::ZERO))
+            .with_point(1, second);
+        let assoc = doc.associate_dimension(dimension, association).unwrap();
+        (line, dimension, assoc)
+    }
+
+    fn assert_dim_assoc(read: &CadDocument, expected: &CadDocument, handles: (Handle, Handle, Handle), label: &str) {
+        use acadrust::entities::{Dimension, EntityType};
+        use acadrust::types::Vector3;
+
+        let (line, dimension, assoc) = handles;
+        let Some(ObjectType::DimAssoc(original)) = expected.objects.get(&assoc) else { unreachable!() };
+        match read.objects.get(&assoc) {
+            Some(ObjectType::DimAssoc(association)) => {
+                assert_eq!(association.dimension, dimension, "{label}");
+                assert_eq!(association.associativity(), 0b11, "{label}");
+                assert_eq!(association.points, original.points, "{label}");
+            }
+            other => panic!("{label}: expected DIMASSOC, got {other:?}"),
+        }
+        assert_eq!(read.dimension_association(dimension).map(|a| a.handle), Some(assoc), "{label}");
+        assert!(read.get_entity(line).unwrap().common().reactors.contains(&assoc), "{label}");
+
+        let mut read = read.clone();
+        if let Some(EntityType::Line(l)) = read.get_entity_mut(line) {
+            l.end = Vector3::new(10.0, 5.0, 0.0);
+        }
+        let Some(EntityType::Dimension(mut dim)) = read.get_entity(dimension).cloned() else {
+            panic!("{label}: dimension missing");
+        };
+        assert!(dim.reassociate(&read), "{label}");
+        let Dimension::Aligned(aligned) = &*dim else { unreachable!() };
+        assert_eq!(aligned.second_point, Vector3::new(10.0, 5.0, 0.0), "{label}");
+    }
+
+    #[test]
+    fn test_dim_assoc_dwg_roundtrip() {
+        use acadrust::io::dwg::{DwgReader, DwgWriter};
+        use acadrust::types::DxfVersion;
+
+        for version in [DxfVersion::AC1015, DxfVersion::AC1018, DxfVersion::AC1032] {
+            let mut doc = CadDocument::with_version(version);
+            let handles = associative_dimension(&mut doc);
+
+            let bytes = DwgWriter::write(&doc).unwrap();
+            let read = DwgReader::from_reader(std::io::Cursor::new(bytes)).unwrap().read().unwrap();
+            assert_dim_assoc(&read, &doc, handles, &format!("{version:?}"));
+        }
+    }
+
+    #[test]
+    fn test_dim_assoc_dxf_roundtrip() {
+        let mut doc = CadDocument::new();
+        let handles = associative_dimension(&mut doc);
+        let read = common::roundtrip_dxf(&doc, "phase8_dim_assoc");
+        assert_dim_assoc(&read, &doc, handles, "DXF");
+    }
+
+    /// Paper space viewport with layer "Frozen" frozen in it and layer
+    /// "Walls" shown red, dashed, heavier and half transparent
+    fn viewport_layer_overrides(doc: &mut CadDocument) -> Handle {
+        use acadrust::entities::{EntityType, Viewport};
+        use acadrust::tables::{Layer, LineType, LineTypeElement};
+        use acadrust::types::{LineWeight, Transparency, Vector3};
+
+        let mut dashed = LineType::new("DASHED");
+        dashed.handle = doc.allocate_handle();
+        dashed.elements = vec![LineTypeElement::dash(0.5), LineTypeElement::space(0.25)];
+        dashed.pattern_length = 0.75;
+        doc.line_types.add(dashed).unwrap();
+        for name in ["Frozen", "Walls"] {
+            let mut layer = Layer::new(name);
+            layer.handle = doc.allocate_handle();
+            doc.layers.add(layer).unwrap();
+        }
+
+        let mut viewport = Viewport::with_size(Vector3::new(150.0, 100.0, 0.0), 200.0, 150.0);
+        viewport.common.owner_handle = doc.header.paper_space_block_handle;
+        viewport.freeze_layer(doc.layers.get("Frozen").unwrap().handle);
+        let viewport = doc.add_entity(EntityType::Viewport(Box::new(viewport))).unwrap();
+
+        let overrides = doc.layers.get_mut("Walls").unwrap().viewport_overrides_mut(viewport);
+        overrides.color = Some(Color::RED);
+        overrides.line_type = Some("DASHED".to_string());
+        overrides.line_weight = Some(LineWeight::Value(50));
+        overrides.transparency = Some(Transparency::T_50);
+        viewport
+    }
+
+    fn assert_viewport_layer_overrides(read: &CadDocument, viewport: Handle, label: &str) {
+        use acadrust::entities::EntityType;
+        use acadrust::types::{LineWeight, Transparency};
+
+        let frozen = read.layers.get("Frozen").unwrap().handle;
+        match read.get_entity(viewport) {
+            Some(EntityType::Viewport(vp)) => assert_eq!(vp.frozen_layers, vec![frozen], "{label}"),
+            other => panic!("{label}: expected the viewport, got {other:?}"),
+        }
+        let walls = read.layers.get("Walls").unwrap();
+        let overrides = walls.viewport_overrides(viewport).unwrap_or_else(|| panic!("{label}: no overrides"));
+        assert_eq!(overrides.color, Some(Color::RED), "{label}");
+        assert_eq!(overrides.line_type.as_deref(), Some("DASHED"), "{label}");
+        assert_eq!(overrides.line_weight, Some(LineWeight::Value(50)), "{label}");
+        assert_eq!(overrides.transparency, Some(Transparency::T_50), "{label}");
+        assert!(read.layers.get("Frozen").unwrap().overrides.is_empty(), "{label}");
+    }
+
+    #[test]
+    fn test_viewport_layer_overrides_dwg_roundtrip() {
+        use acadrust::io::dwg::{DwgReader, DwgWriter};
+        use acadrust::types::DxfVersion;
+
+        for version in [DxfVersion::AC1015, DxfVersion::AC1018, DxfVersion::AC1032] {
+            let mut doc = CadDocument::with_version(version);
+            let viewport = viewport_layer_overrides(&mut doc);
+
+            let bytes = DwgWriter::write(&doc).unwrap();
+            let read = DwgReader::from_reader(std::io::Cursor::new(bytes)).unwrap().read().unwrap();
+            assert_viewport_layer_overrides(&read, viewport, &format!("{version:?}"));
+        }
+    }
+
+    #[test]
+    fn test_viewport_layer_overrides_dxf_roundtrip() {
+        let mut doc = CadDocument::new();
+        let viewport = viewport_layer_overrides(&mut doc);
+        let read = common::roundtrip_dxf(&doc, "phase8_layer_overrides");
+        assert_viewport_layer_overrides(&read, viewport, "DXF");
+
+        // Dropping the overrides removes their records on the next write
+        let mut cleared = read.clone();
+        cleared.layers.get_mut("Walls").unwrap().clear_viewport_overrides(viewport);
+        let read = common::roundtrip_dxf(&cleared, "phase8_layer_overrides_cleared");
+        assert!(read.layers.get("Walls").unwrap().overrides.is_empty());
+        assert!(!read.objects.values().any(|o| matches!(o, ObjectType::XRecord(_))));
+    }
+
+    #[test]
+    fn test_entities_by_space_dwg_roundtrip() {
+        use acadrust::entities::{Circle, EntityType, Line, Point};
+        use acadrust::io::dwg::{DwgReader, DwgWriter};
+        use acadrust::tables::BlockRecord;
+        use acadrust::types::{DxfVersion, Vector3};
+
+        let mut doc = CadDocument::with_version(DxfVersion::AC1018);
+        doc.add_entity(EntityType::Line(Line::from_points(Vector3::ZERO, Vector3::UNIT_X))).unwrap();
+        let mut block = BlockRecord::new("Marker");
+        block.handle = doc.allocate_handle();
+        block.block_entity_handle = doc.allocate_handle();
+        block.block_end_handle = doc.allocate_handle();
+        for entity in [
+            EntityType::Circle(Circle::from_center_radius(Vector3::ZERO, 1.0)),
+            EntityType::Point(Point::at(Vector3::ZERO)),
+        ] {
+            let mut entity = entity;
+            entity.as_entity_mut().set_handle(doc.allocate_handle());
+            block.entities.push(entity);
+        }
+        doc.block_records.add(block).unwrap();
+
+        let bytes = DwgWriter::write(&doc).unwrap();
+        let read = DwgReader::from_reader(std::io::Cursor::new(bytes)).unwrap().read().unwrap();
+        // Block entities read from DWG are also document entities; each is
+        // still seen once, in its own block
+        assert_eq!(read.model_space().count(), 1);
+        assert_eq!(read.block_entities("Marker").unwrap().count(), 2);
+        assert_eq!(read.paper_space("Layout1").unwrap().count(), 0);
+        assert_eq!(read.all_entities().count(), 3);
+    }
+
+    #[test]
+    fn test_mtext_columns_and_background_dwg_roundtrip() {
+        use acadrust::entities::{BackgroundFillFlags, ColumnType, EntityType, MText};
+        use acadrust::io::dwg::{DwgReader, DwgWriter};
+        use acadrust::types::{DxfVersion, Transparency, Vector3};
+
+        let mut mtext = MText::with_value("First\\NSecond", Vector3::ZERO).with_width(40.0);
+        mtext.background_fill = BackgroundFillFlags::USE_BACKGROUND_FILL;
+        mtext.background_color = Color::from_index(2);
+        mtext.background_scale = 1.25;
+        mtext.background_transparency = Transparency::new(64);
+        mtext.columns.column_type = ColumnType::Dynamic;
+        mtext.columns.count = 2;
+        mtext.columns.width = 18.0;
+        mtext.columns.gutter = 4.0;
+        mtext.columns.heights = vec![30.0, 12.5];
+
+        for version in [DxfVersion::AC1015, DxfVersion::AC1018, DxfVersion::AC1032] {
+            let mut doc = CadDocument::with_version(version);
+            doc.add_entity(EntityType::MText(Box::new(mtext.clone()))).unwrap();
+            let bytes = DwgWriter::write(&doc).unwrap();
+            let read = DwgReader::from_reader(std::io::Cursor::new(bytes)).unwrap().read().unwrap();
+            let copy = read
+                .entities()
+                .find_map(|e| match e {
+                    EntityType::MText(m) => Some(m.clone()),
+                    _ => None,
+                })
+                .expect("MTEXT after round-trip");
+            assert_eq!(copy.column_values(), ["First", "Second"]);
+            if version < DxfVersion::AC1018 {
+                assert!(!copy.has_background_fill(), "{version:?}");
+                continue;
+            }
+            assert_eq!(copy.background_fill, BackgroundFillFlags::USE_BACKGROUND_FILL);
+            assert_eq!(copy.background_color, Color::from_index(2));
+            assert_eq!(copy.background_scale, 1.25);
+            assert_eq!(copy.background_transparency, Transparency::new(64));
+            if version >= DxfVersion::AC1032 {
+                assert_eq!(copy.columns, mtext.columns);
+            } else {
+                assert_eq!(copy.columns.column_type, ColumnType::NoColumns);
+            }
+        }
+    }
+
+    #[test]
+    fn test_leader_annotation_dwg_roundtrip() {
+        use acadrust::entities::{EntityType, HooklineDirection, Leader, MText};
+        use acadrust::io::dwg::{DwgReader, DwgWriter};
+        use acadrust::types::{DxfVersion, Vector3};
+
+        for version in [DxfVersion::AC1015, DxfVersion::AC1018, DxfVersion::AC1032] {
+            let mut doc = CadDocument::with_version(version);
+            let note = doc
+                .add_entity(EntityType::MText(Box::new(MText::with_value("Note", Vector3::new(12.0, 4.0, 0.0)))))
+                .unwrap();
+            let mut leader = Leader::from_vertices(vec![Vector3::ZERO, Vector3::new(10.0, 4.0, 0.0)])
+                .with_spline_path()
+                .with_hookline()
+                .with_annotation(note);
+            leader.hookline_direction = HooklineDirection::Same;
+            leader.text_height = 2.0;
+            leader.text_width = 7.5;
+            leader.annotation_offset = Vector3::new(1.0, 0.5, 0.0);
+            doc.add_entity(EntityType::Leader(Box::new(leader.clone()))).unwrap();
+
+            let bytes = DwgWriter::write(&doc).unwrap();
+            let read = DwgReader::from_reader(std::io::Cursor::new(bytes)).unwrap().read().unwrap();
+            let copy = read
+                .entities()
+                .find_map(|e| match e {
+                    EntityType::Leader(l) => Some(l.clone()),
+                    _ => None,
+                })
+                .expect("LEADER after round-trip");
+            assert_eq!(copy.annotation_handle, note, "{version:?}");
+            assert_eq!(copy.vertices, leader.vertices);
+            assert_eq!(copy.path_type, leader.path_type);
+            assert_eq!((copy.hookline_enabled, copy.hookline_direction), (true, HooklineDirection::Same));
+            assert_eq!((copy.text_height, copy.text_width), (2.0, 7.5));
+            assert_eq!(copy.annotation_offset, leader.annotation_offset);
+        }
+    }
+
+    #[test]
+    fn test_spline_scenarios_dwg_roundtrip() {
+        use acadrust::entities::{EntityType, Spline};
+        use acadrust::io::dwg::{DwgReader, DwgWriter};
+        use acadrust::types::{DxfVersion, Vector3};
+
+        let mut fitted = Spline::from_fit_points(vec![Vector3::ZERO, Vector3::new(5.0, 3.0, 0.0), Vector3::new(10.0, 0.0, 0.0)]);
+        fitted.fit_tolerance = 0.01;
+        fitted.start_tangent = Vector3::new(0.0, 1.0, 0.0);
+        let mut weighted = Spline::from_control_points(2, vec![Vector3::new(1.0, 0.0, 0.0), Vector3::new(1.0, 1.0, 0.0), Vector3::new(0.0, 1.0, 0.0)]);
+        weighted.knots = vec![0.0, 0.0, 0.0, 1.0, 1.0, 1.0];
+        weighted.weights = vec![1.0, 0.5, 1.0];
+        weighted.flags.rational = true;
+        weighted.knot_tolerance = 1e-9;
+
+        for version in [DxfVersion::AC1015, DxfVersion::AC1018, DxfVersion::AC1032] {
+            let mut doc = CadDocument::with_version(version);
+            doc.add_entity(EntityType::Spline(Box::new(fitted.clone()))).unwrap();
+            doc.add_entity(EntityType::Spline(Box::new(weighted.clone()))).unwrap();
+
+            let bytes = DwgWriter::write(&doc).unwrap();
+            let read = DwgReader::from_reader(std::io::Cursor::new(bytes)).unwrap().read().unwrap();
+            let splines: Vec<&Spline> = read
+                .entities()
+                .filter_map(|e| match e {
+                    EntityType::Spline(s) => Some(s.as_ref()),
+                    _ => None,
+                })
+                .collect();
+            assert_eq!(splines.len(), 2, "{version:?}");
+            let fit = splines.iter().find(|s| !s.fit_points.is_empty()).expect("fit point spline");
+            assert_eq!(fit.fit_points, fitted.fit_points, "{version:?}");
+            assert_eq!((fit.fit_tolerance, fit.start_tangent), (0.01, fitted.start_tangent));
+            let control = splines.iter().find(|s| s.fit_points.is_empty()).expect("control point spline");
+            assert_eq!(control.control_points, weighted.control_points, "{version:?}");
+            assert_eq!((&control.knots, &control.weights), (&weighted.knots, &weighted.weights));
+            assert!(control.flags.rational);
+            assert_eq!(control.knot_tolerance, 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_frame_display_dwg_roundtrip() {
+        use acadrust::io::dwg::{DwgReader, DwgWriter};
+        use acadrust::objects::FrameDisplay;
+        use acadrust::types::DxfVersion;
+
+        let mut doc = CadDocument::with_version(DxfVersion::AC1018);
+        doc.set_image_frame(FrameDisplay::DisplayedNotPlotted).unwrap();
+        doc.set_wipeout_frame(FrameDisplay::Displayed).unwrap();
+
+        let bytes = DwgWriter::write(&doc).unwrap();
+        let read = DwgReader::from_reader(std::io::Cursor::new(bytes)).unwrap().read().unwrap();
+        assert_eq!(read.image_frame(), FrameDisplay::DisplayedNotPlotted);
+        assert_eq!(read.wipeout_frame(), FrameDisplay::Displayed);
+    }
+
+    #[test]
+    fn test_entity_filter_dwg_read() {
+        use acadrust::entities::{Circle, EntityType, Insert, Line, Polyline2D, Vertex2D};
+        use acadrust::io::dwg::{DwgReader, DwgWriter};
+        use acadrust::io::EntityFilter;
+        use acadrust::tables::BlockRecord;
+        use acadrust::types::{DxfVersion, Vector3};
+
+        for version in [DxfVersion::AC1015, DxfVersion::AC1018, DxfVersion::AC1032] {
+            let mut doc = CadDocument::with_version(version);
+            let mut block = BlockRecord::new("PART");
+            block.handle = doc.allocate_handle();
+            block.block_entity_handle = doc.allocate_handle();
+            block.block_end_handle = doc.allocate_handle();
+            for mut entity in [
+                EntityType::Circle(Circle::from_coords(0.0, 0.0, 0.0, 1.0)),
+                EntityType::Line(Line::from_coords(0.0, 0.0, 0.0, 1.0, 0.0, 0.0)),
+            ] {
+                entity.as_entity_mut().set_handle(doc.allocate_handle());
+                block.entities.push(entity);
+            }
+            doc.block_records.add(block).unwrap();
+            doc.add_entity(EntityType::Line(Line::from_coords(0.0, 0.0, 0.0, 5.0, 5.0, 0.0))).unwrap();
+            doc.add_entity(EntityType::Circle(Circle::from_coords(2.0, 2.0, 0.0, 1.0))).unwrap();
+            let mut polyline = Polyline2D::new();
+            for location in [Vector3::ZERO, Vector3::new(3.0, 0.0, 0.0)] {
+                let mut vertex = Vertex2D::new(location);
+                vertex.handle = doc.allocate_handle();
+                polyline.add_vertex(vertex);
+            }
+            polyline.seqend_handle = Some(doc.allocate_handle());
+            doc.add_entity(EntityType::Polyline2D(polyline)).unwrap();
+            doc.add_entity(EntityType::Line(Line::from_coords(1.0, 0.0, 0.0, 5.0, 0.0, 0.0))).unwrap();
+            doc.add_entity(EntityType::Insert(Insert::new("PART", Vector3::ZERO))).unwrap();
+
+            let bytes = DwgWriter::write(&doc).unwrap();
+            let read = DwgReader::from_reader(std::io::Cursor::new(bytes))
+                .unwrap()
+                .with_entity_filter(EntityFilter::except(["CIRCLE", "POLYLINE"]))
+                .read()
+                .unwrap();
+            let model = read.block_records.get("*Model_Space").unwrap();
+            let mut names: Vec<&str> = model.entities.iter().map(|e| e.as_entity().entity_type()).collect();
+            names.sort();
+            assert_eq!(names, vec!["INSERT", "LINE", "LINE"], "{version:?}");
+            let block = read.block_records.get("PART").unwrap();
+            assert_eq!(block.entities.len(), 1, "{version:?}");
+            assert!(matches!(block.entities[0], EntityType::Line(_)));
+        }
+    }
+
+    #[test]
+    fn test_nested_dictionaries_dwg_owners() {
+        use acadrust::entities::{EntityType, Line};
+        use acadrust::io::dwg::{DwgReader, DwgWriter};
+        use acadrust::types::{DxfVersion, Vector3};
+
+        for version in [DxfVersion::AC1015, DxfVersion::AC1018, DxfVersion::AC1032] {
+            let mut doc = CadDocument::with_version(version);
+            let root = doc.header.named_objects_dict_handle;
+
+            // MYAPP under the root and SETTINGS under MYAPP, neither with
+            // an owner handle set
+            let mut settings = XRecord::new();
+            settings.handle = doc.allocate_handle();
+            settings.add_string(1, "nested");
+            let mut nested = Dictionary::new();
+            nested.handle = doc.allocate_handle();
+            nested.add_entry("SETTINGS", settings.handle);
+            let mut app = Dictionary::new();
+            app.handle = doc.allocate_handle();
+            app.hard_owner = true;
+            app.add_entry("NESTED", nested.handle);
+            let (settings_h, nested_h, app_h) = (settings.handle, nested.handle, app.handle);
+            if let Some(ObjectType::Dictionary(dict)) = doc.objects.get_mut(&root) {
+                dict.add_entry("MYAPP", app_h);
+            }
+            doc.objects.insert(settings_h, ObjectType::XRecord(settings));
+            doc.objects.insert(nested_h, ObjectType::Dictionary(nested));
+            doc.objects.insert(app_h, ObjectType::Dictionary(app));
+
+            // An entity extension dictionary, which the root does not reach
+            let mut line = Line::from_points(Vector3::ZERO, Vector3::new(5.0, 0.0, 0.0));
+            line.common.handle = doc.allocate_handle();
+            let mut xdictionary = Dictionary::new();
+            xdictionary.handle = doc.allocate_handle();
+            xdictionary.hard_owner = true;
+            line.common.xdictionary_handle = Some(xdictionary.handle);
+            let (line_h, xdictionary_h) = (line.common.handle, xdictionary.handle);
+            doc.objects.insert(xdictionary_h, ObjectType::Dictionary(xdictionary));
+            doc.add_entity(EntityType::Line(line)).unwrap();
+
+            let owners = doc.ownership_graph().resolved_owners();
+            assert_eq!(owners[&app_h], root);
+            assert_eq!(owners[&nested_h], app_h);
+            assert_eq!(owners[&settings_h], nested_h);
+            assert_eq!(owners[&xdictionary_h], line_h);
+
+            let bytes = DwgWriter::write(&doc).unwrap();
+            let read = DwgReader::from_reader(std::io::Cursor::new(bytes)).unwrap().read().unwrap();
+            let model = read.block_records.get("*Model_Space").unwrap();
+            let line = model.entities.iter().find(|e| e.common().handle == line_h);
+            assert_eq!(line.and_then(|e| e.common().xdictionary_handle), Some(xdictionary_h), "{version:?}");
+        }
+    }
+
+    #[test]
+    fn test_identical_documents_write_identical_dwg() {
+        use acadrust::entities::{Circle, EntityType, Line};
+        use acadrust::io::dwg::DwgWriter;
+        use acadrust::types::{DxfVersion, Vector3};
+
+        fn build(version: DxfVersion) -> CadDocument {
+            let mut doc = CadDocument::new();
+            doc.version = version;
+            for i in 0..30 {
+                let x = i as f64;
+                doc.add_entity(EntityType::Line(Line::from_points(Vector3::ZERO, Vector3::new(x, 1.0, 0.0)))).unwrap();
+                doc.add_entity(EntityType::Circle(Circle::from_coords(x, 0.0, 0.0, 1.0))).unwrap();
+            }
+            for i in 0..20 {
+                let mut record = XRecord::named(format!("R{i}"));
+                record.handle = doc.allocate_handle();
+                record.owner = doc.header.named_objects_dict_handle;
+                record.add_int32(90, i);
+                doc.objects.insert(record.handle, ObjectType::XRecord(record));
+            }
+            doc
+        }
+
+        for version in [DxfVersion::AC1015, DxfVersion::AC1018, DxfVersion::AC1021, DxfVersion::AC1032] {
+            let first = DwgWriter::write(&build(version)).unwrap();
+            let second = DwgWriter::write(&build(version)).unwrap();
+            assert!(first == second, "{version:?}");
+        }
+    }
+
     // =======================================================================
     // REFERENCE SAMPLES — verify objects can be read from sample files
     // =======================================================================
@@ -3897,6 +4608,42 @@ mod phase9_tables_sections {
         let _count = doc2.ucss.iter().count();
         // No crash = success
     }
+
+    #[test]
+    fn test_r2000_block_entity_chain() {
+        use acadrust::entities::{Circle, EntityType, Line, Point};
+        use acadrust::io::dwg::DwgReader;
+        use acadrust::tables::BlockRecord;
+        use acadrust::types::DxfVersion;
+
+        let mut doc = CadDocument::with_version(DxfVersion::AC1015);
+        for x in [0.0, 10.0, 20.0] {
+            let line = Line::from_points(Vector3::new(x, 0.0, 0.0), Vector3::new(x, 5.0, 0.0));
+            doc.add_entity(EntityType::Line(line)).unwrap();
+        }
+        let mut block = BlockRecord::new("Chain");
+        block.handle = doc.allocate_handle();
+        block.block_entity_handle = doc.allocate_handle();
+        block.block_end_handle = doc.allocate_handle();
+        for entity in [
+            EntityType::Circle(Circle::from_center_radius(Vector3::ZERO, 1.0)),
+            EntityType::Point(Point::at(Vector3::new(2.0, 0.0, 0.0))),
+            EntityType::Circle(Circle::from_center_radius(Vector3::ZERO, 3.0)),
+        ] {
+            let mut entity = entity;
+            entity.as_entity_mut().set_handle(doc.allocate_handle());
+            block.entities.push(entity);
+        }
+        doc.block_records.add(block).unwrap();
+
+        let bytes = DwgWriter::write(&doc).unwrap();
+        let read = DwgReader::from_reader(std::io::Cursor::new(bytes)).unwrap().read().unwrap();
+        let lines = read.entities().filter(|e| matches!(e, EntityType::Line(_))).count();
+        assert_eq!(lines, 3);
+        let block = read.block_records.get("Chain").unwrap();
+        assert_eq!(block.entities.len(), 3);
+        assert!(matches!(block.entities[1], EntityType::Point(_)));
+    }
 }
 
 // ===========================================================================
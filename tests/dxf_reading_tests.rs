@@ -263,4 +263,611 @@ EOF
     assert!(doc.layers.get("MyLayer").is_some());
 }
 
+/// Test that pre-2007 text is transcoded through $DWGCODEPAGE on write and read
+#[test]
+fn test_code_page_roundtrip_pre2007() {
+    use acadrust::entities::{EntityType, Text};
+    use acadrust::{CadDocument, DxfVersion, DxfWriter, Vector3};
+    use std::io::Cursor;
+
+    let mut doc = CadDocument::with_version(DxfVersion::AC1015);
+    doc.header.code_page = "ANSI_1251".to_string();
+    doc.add_entity(EntityType::Text(Text::with_value("Привет 中文", Vector3::new(0.0, 0.0, 0.0))))
+        .unwrap();
+
+    for binary in [false, true] {
+        let mut writer = DxfWriter::new(doc.clone());
+        writer.set_binary(binary);
+        let bytes = writer.write_to_vec().unwrap();
+
+        // Cyrillic is stored in Windows-1251, CJK falls back to \U+ escapes
+        assert!(bytes.windows(6).any(|w| w == [0xCF, 0xF0, 0xE8, 0xE2, 0xE5, 0xF2]));
+        assert!(bytes.windows(7).any(|w| w == b"\\U+4E2D"));
+
+        let read = DxfReader::from_reader(Cursor::new(bytes)).unwrap().read().unwrap();
+        let text = read
+            .entities()
+            .find_map(|e| match e {
+                EntityType::Text(t) => Some(t.value.clone()),
+                _ => None,
+            })
+            .expect("text entity");
+        assert_eq!(text, "Привет 中文");
+    }
+}
+
+/// Test that failsafe mode recovers from malformed pairs and missing markers
+#[test]
+fn test_failsafe_recovers_from_malformed_dxf() {
+    use acadrust::entities::EntityType;
+    use acadrust::io::dxf::DxfReaderConfiguration;
+    use acadrust::notification::NotificationType;
+    use std::io::Cursor;
+
+    // Stray blank line, a non-numeric group code inside the first LINE,
+    // missing ENDSEC and missing EOF
+    let dxf_content = "  0\nSECTION\n  2\nENTITIES\n\n  0\nLINE\n  8\n0\n 10\n0.0\nxx\n1.0\n  0\nLINE\n  8\n0\n 10\n5.0\n 20\n5.0\n 11\n6.0\n 21\n6.0\n  0\nCIRCLE\n  8\n0\n 10\n1.0\n 20\n1.0\n 40\n2.0\n";
+
+    let strict = DxfReader::from_reader(Cursor::new(dxf_content.as_bytes().to_vec()))
+        .unwrap()
+        .read();
+    let err = strict.unwrap_err();
+    // Strict mode stops at the stray blank line
+    let location = err.location().expect("error location");
+    assert_eq!(location.position, 5);
+    assert_eq!(location.section.as_deref(), Some("ENTITIES"));
+
+    let doc = DxfReader::from_reader(Cursor::new(dxf_content.as_bytes().to_vec()))
+        .unwrap()
+        .with_configuration(DxfReaderConfiguration { failsafe: true })
+        .read()
+        .unwrap();
+
+    let lines = doc.entities().filter(|e| matches!(e, EntityType::Line(_))).count();
+    let circles = doc.entities().filter(|e| matches!(e, EntityType::Circle(_))).count();
+    assert_eq!(lines, 2);
+    assert_eq!(circles, 1);
+    assert!(doc
+        .notifications
+        .of_type(NotificationType::Error)
+        .iter()
+        .any(|n| n.message.contains("line 12")));
+}
+
+/// Test that only failsafe mode accepts a section boundary in place of a
+/// missing ENDSEC or ENDTAB
+#[test]
+fn test_missing_terminators_are_errors_in_strict_mode() {
+    use acadrust::io::dxf::DxfReaderConfiguration;
+    use std::io::Cursor;
+
+    let missing_endsec = "  0\nSECTION\n  2\nENTITIES\n  0\nLINE\n  8\n0\n  0\nSECTION\n  2\nOBJECTS\n  0\nENDSEC\n  0\nEOF\n";
+    let missing_endtab = "  0\nSECTION\n  2\nTABLES\n  0\nTABLE\n  2\nLAYER\n  0\nLAYER\n  2\nWALLS\n  0\nENDSEC\n  0\nEOF\n";
+
+    for (content, message) in [(missing_endsec, "Missing ENDSEC"), (missing_endtab, "Missing ENDTAB")] {
+        let err = DxfReader::from_reader(Cursor::new(content.as_bytes().to_vec()))
+            .unwrap()
+            .read()
+            .unwrap_err();
+        assert!(err.to_string().contains(message), "{}", err);
+        assert!(err.location().is_some());
+
+        DxfReader::from_reader(Cursor::new(content.as_bytes().to_vec()))
+            .unwrap()
+            .with_configuration(DxfReaderConfiguration { failsafe: true })
+            .read()
+            .unwrap();
+    }
+}
+
+/// Test that malformed THUMBNAILIMAGE data is an error in strict mode and
+/// drops the preview with a warning in failsafe mode
+#[test]
+fn test_malformed_thumbnail_dropped_in_failsafe_mode() {
+    use acadrust::io::dxf::DxfReaderConfiguration;
+    use acadrust::notification::NotificationCode;
+    use std::io::Cursor;
+
+    // A bad hex digit, and a line truncated to an odd number of digits
+    for data in ["0A0B0G", "0A0B0"] {
+        let content = format!(
+            "  0\nSECTION\n  2\nTHUMBNAILIMAGE\n 90\n3\n310\n{}\n  0\nENDSEC\n  0\nEOF\n",
+            data
+        );
+
+        let err = DxfReader::from_reader(Cursor::new(content.as_bytes().to_vec()))
+            .unwrap()
+            .read()
+            .unwrap_err();
+        assert!(err.to_string().contains("Invalid thumbnail data"), "{}", err);
+
+        let doc = DxfReader::from_reader(Cursor::new(content.as_bytes().to_vec()))
+            .unwrap()
+            .with_configuration(DxfReaderConfiguration { failsafe: true })
+            .read()
+            .unwrap();
+        assert!(doc.preview.is_none());
+        assert!(doc.notifications.iter().any(|n| n.code == NotificationCode::MalformedPair));
+    }
+}
+
+#[test]
+fn test_entities_iter_streams_without_document() {
+    use acadrust::entities::EntityType;
+    use acadrust::DxfWriter;
+    use std::io::Cursor;
+
+    let mut doc = acadrust::CadDocument::new();
+    for i in 0..5 {
+        let line = acadrust::entities::Line::from_coords(0.0, 0.0, 0.0, i as f64, 1.0, 0.0);
+        doc.add_entity(EntityType::Line(line)).unwrap();
+    }
+    doc.add_entity(EntityType::Circle(acadrust::entities::Circle::new())).unwrap();
+    let bytes = DxfWriter::new(doc).write_to_vec().unwrap();
+
+    let iter = DxfReader::from_reader(Cursor::new(bytes.clone()))
+        .unwrap()
+        .entities_iter()
+        .unwrap();
+    let entities: Vec<EntityType> = iter.collect::<acadrust::Result<_>>().unwrap();
+    assert_eq!(entities.len(), 6);
+    assert_eq!(entities.iter().filter(|e| matches!(e, EntityType::Line(_))).count(), 5);
+
+    // Lazy: stopping early does not read the rest of the file
+    let mut iter = DxfReader::from_reader(Cursor::new(bytes))
+        .unwrap()
+        .entities_iter()
+        .unwrap();
+    assert!(matches!(iter.next(), Some(Ok(EntityType::Line(_) | EntityType::Circle(_)))));
+}
+
+#[test]
+fn test_read_pairs_visits_requested_sections_only() {
+    use std::io::Cursor;
+    use std::ops::ControlFlow;
+
+    let dxf_content = "  0\nSECTION\n  2\nHEADER\n  9\n$ACADVER\n  1\nAC1015\n  0\nENDSEC\n  0\nSECTION\n  2\nENTITIES\n  0\nLINE\n  8\nWalls\n 10\n0.0\n  0\nPOINT\n  8\nMarks\n  0\nENDSEC\n  0\nEOF\n";
+
+    let mut seen = Vec::new();
+    DxfReader::from_reader(Cursor::new(dxf_content.as_bytes().to_vec()))
+        .unwrap()
+        .read_pairs(&["ENTITIES"], |section, pair| {
+            seen.push((section.to_string(), pair.code, pair.value_string.to_string()));
+            ControlFlow::Continue(())
+        })
+        .unwrap();
+    assert_eq!(seen.len(), 5);
+    assert!(seen.iter().all(|(section, _, _)| section == "ENTITIES"));
+    assert_eq!(seen[1], ("ENTITIES".to_string(), 8, "Walls".to_string()));
+
+    // Breaking stops the visit
+    let mut count = 0;
+    DxfReader::from_reader(Cursor::new(dxf_content.as_bytes().to_vec()))
+        .unwrap()
+        .read_pairs(&[], |_, _| {
+            count += 1;
+            ControlFlow::Break(())
+        })
+        .unwrap();
+    assert_eq!(count, 1);
+}
+
+#[test]
+fn test_progress_and_cancellation() {
+    use acadrust::entities::{EntityType, Line};
+    use acadrust::io::{CancellationToken, ReadPhase};
+    use acadrust::notification::NotificationType;
+    use acadrust::DxfWriter;
+    use std::cell::RefCell;
+    use std::io::Cursor;
+    use std::rc::Rc;
+
+    let mut doc = acadrust::CadDocument::new();
+    for i in 0..500 {
+        let line = Line::from_coords(0.0, 0.0, 0.0, i as f64, 1.0, 0.0);
+        doc.add_entity(EntityType::Line(line)).unwrap();
+    }
+    let bytes = DxfWriter::new(doc).write_to_vec().unwrap();
+
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let sink = seen.clone();
+    let read = DxfReader::from_reader(Cursor::new(bytes.clone()))
+        .unwrap()
+        .with_progress(move |p| sink.borrow_mut().push(*p))
+        .read()
+        .unwrap();
+    assert_eq!(read.entity_count(), 500);
+
+    let seen = seen.borrow();
+    assert!(seen.iter().any(|p| p.phase == ReadPhase::Entities));
+    assert!(seen.windows(2).all(|w| w[0].percent <= w[1].percent));
+    assert_eq!(seen.last().unwrap().percent, 100.0);
+    assert!(seen.last().unwrap().objects_read >= 500);
+
+    // Cancel as soon as the ENTITIES section starts
+    let token = CancellationToken::new();
+    let trigger = token.clone();
+    let partial = DxfReader::from_reader(Cursor::new(bytes))
+        .unwrap()
+        .with_progress(move |p| {
+            if p.phase == ReadPhase::Entities && p.objects_read > 0 {
+                trigger.cancel();
+            }
+        })
+        .with_cancellation(token)
+        .read()
+        .unwrap();
+    assert!(partial.entity_count() < 500);
+    assert!(partial.notifications.has_type(NotificationType::Warning));
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn test_parallel_entities_match_sequential() {
+    use acadrust::entities::{EntityType, Line, LwPolyline, Polyline, Text};
+    use acadrust::io::{CancellationToken, ReadPhase};
+    use acadrust::notification::NotificationType;
+    use acadrust::types::{Vector2, Vector3};
+    use acadrust::DxfWriter;
+    use std::cell::RefCell;
+    use std::collections::BTreeMap;
+    use std::io::Cursor;
+    use std::rc::Rc;
 
+    let mut doc = acadrust::CadDocument::new();
+    for i in 0..2000 {
+        let x = i as f64;
+        let entity = match i % 4 {
+            0 => EntityType::Line(Line::from_coords(x, 0.0, 0.0, x, 1.0, 0.0)),
+            1 => {
+                let mut poly = Polyline::new();
+                poly.add_point(Vector3::new(x, 0.0, 0.0));
+                poly.add_point(Vector3::new(x, 2.0, 0.0));
+                EntityType::Polyline(poly)
+            }
+            2 => {
+                let mut lw = LwPolyline::new();
+                lw.add_point(Vector2::new(x, 0.0));
+                lw.add_point(Vector2::new(x, 3.0));
+                EntityType::LwPolyline(lw)
+            }
+            _ => EntityType::Text(Text::with_value(format!("T{}", i), Vector3::new(x, 0.0, 0.0))),
+        };
+        doc.add_entity(entity).unwrap();
+    }
+    let bytes = DxfWriter::new(doc).write_to_vec().unwrap();
+
+    let histogram = |doc: &acadrust::CadDocument| {
+        let mut counts = BTreeMap::new();
+        for e in doc.entities() {
+            *counts.entry(format!("{:?}", std::mem::discriminant(e))).or_insert(0) += 1;
+        }
+        counts
+    };
+
+    let sequential = DxfReader::from_reader(Cursor::new(bytes.clone())).unwrap().read().unwrap();
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let sink = seen.clone();
+    let parallel = DxfReader::from_reader(Cursor::new(bytes.clone()))
+        .unwrap()
+        .with_parallel(true)
+        .with_progress(move |p| sink.borrow_mut().push(*p))
+        .read()
+        .unwrap();
+
+    assert_eq!(parallel.entity_count(), 2000);
+    assert_eq!(histogram(&parallel), histogram(&sequential));
+    let seen = seen.borrow();
+    assert!(seen.iter().any(|p| p.phase == ReadPhase::Entities && p.objects_read > 0));
+    assert!(seen.windows(2).all(|w| w[0].percent <= w[1].percent));
+    assert!(seen.last().unwrap().objects_read >= 2000);
+
+    // Cancelling while the chunks are merged keeps the entities merged so far
+    let token = CancellationToken::new();
+    let trigger = token.clone();
+    let partial = DxfReader::from_reader(Cursor::new(bytes))
+        .unwrap()
+        .with_parallel(true)
+        .with_progress(move |p| {
+            if p.phase == ReadPhase::Entities && p.objects_read > 0 {
+                trigger.cancel();
+            }
+        })
+        .with_cancellation(token)
+        .read()
+        .unwrap();
+    assert!(partial.entity_count() < 2000);
+    assert!(partial.notifications.has_type(NotificationType::Warning));
+}
+
+#[cfg(feature = "mmap")]
+#[test]
+fn test_from_file_mapped_matches_from_file() {
+    use acadrust::entities::{EntityType, Line, Text};
+    use acadrust::types::Vector3;
+    use acadrust::DxfWriter;
+
+    let mut doc = acadrust::CadDocument::new();
+    doc.add_entity(EntityType::Line(Line::from_coords(0.0, 0.0, 0.0, 1.0, 1.0, 0.0))).unwrap();
+    doc.add_entity(EntityType::Text(Text::with_value("Mapped", Vector3::new(2.0, 0.0, 0.0)))).unwrap();
+    let temp_path = "test_mapped.dxf";
+    fs::write(temp_path, DxfWriter::new(doc).write_to_vec().unwrap()).unwrap();
+
+    let read = DxfReader::from_file(temp_path).and_then(|r| r.read());
+    // SAFETY: the file is not modified until the reader is dropped
+    let mapped = unsafe { DxfReader::from_file_mapped(temp_path) }.and_then(|r| r.read());
+    let _ = fs::remove_file(temp_path);
+
+    let (read, mapped) = (read.unwrap(), mapped.unwrap());
+    assert_eq!(mapped.entity_count(), 2);
+    assert_eq!(mapped.entity_count(), read.entity_count());
+    assert!(mapped.entities().any(|e| matches!(e, EntityType::Text(t) if t.value == "Mapped")));
+}
+
+/// R12-era binary DXF uses 1-byte group codes; reading it and writing it back
+/// keeps that dialect
+#[test]
+fn test_legacy_binary_dxf_roundtrip() {
+    use acadrust::entities::EntityType;
+    use acadrust::io::dxf::{BinaryDxfDialect, DxfBinaryWriter, DxfStreamWriter};
+    use acadrust::{DxfVersion, DxfWriter};
+
+    let mut bytes = Vec::new();
+    {
+        let mut w = DxfBinaryWriter::with_dialect(&mut bytes, BinaryDxfDialect::Legacy).unwrap();
+        w.write_string(999, "exported by an R12 tool").unwrap();
+        for (code, value) in [(0, "SECTION"), (2, "HEADER"), (9, "$ACADVER"), (1, "AC1009"), (0, "ENDSEC")] {
+            w.write_string(code, value).unwrap();
+        }
+        for (code, value) in [(0, "SECTION"), (2, "ENTITIES"), (0, "LINE"), (8, "WALLS")] {
+            w.write_string(code, value).unwrap();
+        }
+        for (code, value) in [(10, 1.0), (20, 2.0), (30, 0.0), (11, 4.0), (21, 6.0), (31, 0.0)] {
+            w.write_double(code, value).unwrap();
+        }
+        for (code, value) in [(0, "ENDSEC"), (0, "EOF")] {
+            w.write_string(code, value).unwrap();
+        }
+    }
+    assert_eq!(bytes[22], 255, "999 takes the escape byte");
+
+    let doc = DxfReader::from_bytes(bytes).unwrap().read().unwrap();
+    assert_eq!(doc.version, DxfVersion::Unknown);
+    let line = doc
+        .entities()
+        .find_map(|e| match e {
+            EntityType::Line(l) => Some(l.clone()),
+            _ => None,
+        })
+        .expect("line entity");
+    assert_eq!((line.common.layer.as_str(), line.end.y), ("WALLS", 6.0));
+
+    let rewritten = DxfWriter::new_binary(doc).write_to_vec().unwrap();
+    // Code 0 followed directly by "SECTION"
+    assert_eq!(&rewritten[22..24], b"\0S");
+    let doc = DxfReader::from_bytes(rewritten).unwrap().read().unwrap();
+    assert_eq!(doc.entities().filter(|e| matches!(e, EntityType::Line(_))).count(), 1);
+}
+
+/// An entity filter keeps rejected entities, with their vertices, out of
+/// the entities and blocks of a read
+#[test]
+fn test_entity_filter_dxf_read() {
+    use acadrust::entities::{Circle, EntityType, Insert, Line, Polyline2D, Vertex2D};
+    use acadrust::io::EntityFilter;
+    use acadrust::tables::BlockRecord;
+    use acadrust::types::Vector3;
+    use acadrust::{CadDocument, DxfReader, DxfWriter};
+    use std::io::Cursor;
+
+    let mut doc = CadDocument::new();
+    let mut block = BlockRecord::new("PART");
+    block.entities.push(EntityType::Circle(Circle::from_coords(0.0, 0.0, 0.0, 1.0)));
+    block.entities.push(EntityType::Line(Line::from_coords(0.0, 0.0, 0.0, 1.0, 0.0, 0.0)));
+    doc.block_records.add(block).unwrap();
+    doc.add_entity(EntityType::Line(Line::from_coords(0.0, 0.0, 0.0, 5.0, 5.0, 0.0))).unwrap();
+    doc.add_entity(EntityType::Circle(Circle::from_coords(2.0, 2.0, 0.0, 1.0))).unwrap();
+    let mut polyline = Polyline2D::new();
+    polyline.add_vertex(Vertex2D::new(Vector3::ZERO));
+    polyline.add_vertex(Vertex2D::new(Vector3::new(3.0, 0.0, 0.0)));
+    doc.add_entity(EntityType::Polyline2D(polyline)).unwrap();
+    doc.add_entity(EntityType::Insert(Insert::new("PART", Vector3::ZERO))).unwrap();
+    let bytes = DxfWriter::new(doc).write_to_vec().unwrap();
+
+    let read = |filter: EntityFilter| {
+        DxfReader::from_reader(Cursor::new(bytes.clone()))
+            .unwrap()
+            .with_entity_filter(filter)
+            .read()
+            .unwrap()
+    };
+
+    let doc = read(EntityFilter::except(["circle", "POLYLINE"]));
+    let mut names: Vec<&str> = doc.entities().map(|e| e.as_entity().entity_type()).collect();
+    names.sort();
+    assert_eq!(names, vec!["INSERT", "LINE"]);
+    let part = doc.block_records.get("PART").unwrap();
+    assert_eq!(part.entities.len(), 1);
+    assert!(matches!(part.entities[0], EntityType::Line(_)));
+
+    let doc = read(EntityFilter::predicate(|name| name == "POLYLINE"));
+    let names: Vec<&str> = doc.entities().map(|e| e.as_entity().entity_type()).collect();
+    assert_eq!(names, vec!["POLYLINE"]);
+    assert!(doc.block_records.get("PART").unwrap().entities.is_empty());
+
+    let streamed: Vec<EntityType> = DxfReader::from_reader(Cursor::new(bytes.clone()))
+        .unwrap()
+        .with_entity_filter(EntityFilter::only(["CIRCLE"]))
+        .entities_iter()
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert!(streamed.iter().all(|e| matches!(e, EntityType::Circle(_))));
+    assert_eq!(streamed.len(), 1);
+}
+
+/// Layers, styles and blocks named by entities but missing from the file
+/// are listed after a strict read and created by a failsafe one
+#[test]
+fn test_missing_references_resolved_in_failsafe_mode() {
+    use acadrust::io::dxf::DxfReaderConfiguration;
+    use acadrust::notification::NotificationCode;
+    use acadrust::references::ReferenceKind;
+    use std::io::Cursor;
+
+    let dxf_content = "  0\nSECTION\n  2\nENTITIES\n  0\nTEXT\n  5\nA1\n  8\nWALLS\n  7\nNOTES\n 10\n0.0\n 20\n0.0\n 40\n1.0\n  1\nHello\n  0\nINSERT\n  5\nA2\n  8\n0\n  2\nDOOR\n 10\n1.0\n 20\n1.0\n  0\nENDSEC\n  0\nEOF\n";
+
+    let strict = DxfReader::from_reader(Cursor::new(dxf_content.as_bytes().to_vec()))
+        .unwrap()
+        .read()
+        .unwrap();
+    let missing: Vec<(ReferenceKind, String, u64)> = strict
+        .missing_references()
+        .into_iter()
+        .map(|m| (m.kind, m.name, m.referenced_by.value()))
+        .collect();
+    assert_eq!(
+        missing,
+        vec![
+            (ReferenceKind::Layer, "WALLS".to_string(), 0xA1),
+            (ReferenceKind::TextStyle, "NOTES".to_string(), 0xA1),
+            (ReferenceKind::Block, "DOOR".to_string(), 0xA2),
+        ]
+    );
+    assert!(strict.layers.get("WALLS").is_none());
+
+    let mut doc = DxfReader::from_reader(Cursor::new(dxf_content.as_bytes().to_vec()))
+        .unwrap()
+        .with_configuration(DxfReaderConfiguration { failsafe: true })
+        .read()
+        .unwrap();
+    assert!(doc.missing_references().is_empty());
+    assert!(doc.layers.get("WALLS").is_some());
+    assert!(doc.text_styles.get("NOTES").is_some());
+    assert!(doc.block_records.get("DOOR").unwrap().entities.is_empty());
+    assert_eq!(doc.notifications.with_code(NotificationCode::MissingReference).count(), 3);
+    // Placeholders get handles of their own
+    assert!(doc.layers.get("WALLS").unwrap().handle.value() > 0xA2);
+    assert!(doc.add_missing_references().is_empty());
+}
+
+/// Entity and object types of an application are read and written through
+/// custom readers and writers registered by DXF name
+#[test]
+fn test_custom_type_readers_and_writers() {
+    use acadrust::entities::{EntityType, UnknownEntity};
+    use acadrust::io::dxf::{
+        DxfCustomTypes, DxfStreamReader, DxfStreamWriter, DxfStreamWriterExt, DxfTextReader,
+        DxfTextWriter,
+    };
+    use acadrust::objects::{ObjectType, XRecord};
+    use acadrust::types::{Handle, Vector3};
+    use acadrust::{CadDocument, DxfWriter};
+    use std::io::{BufReader, Cursor};
+
+    // Pairs written with the stream writer read back with the stream reader
+    let mut writer = DxfTextWriter::new(Vec::new());
+    writer.write_string(0, "MY_MARKER").unwrap();
+    writer.write_handle(5, Handle::new(0x2A)).unwrap();
+    writer.write_point3d(10, Vector3::new(1.0, 2.0, 3.0)).unwrap();
+    writer.flush().unwrap();
+    let mut reader = DxfTextReader::new(BufReader::new(Cursor::new(writer.into_inner()))).unwrap();
+    let pairs: Vec<(i32, String)> =
+        std::iter::from_fn(|| reader.read_pair().unwrap()).map(|p| (p.code, p.value_string.into_string())).collect();
+    assert_eq!(pairs[0], (0, "MY_MARKER".to_string()));
+    assert_eq!(pairs[1], (5, "2A".to_string()));
+    assert_eq!(pairs.iter().filter(|(code, _)| [10, 20, 30].contains(code)).count(), 3);
+
+    let mut doc = CadDocument::new();
+    let mut marker = UnknownEntity::new("MY_MARKER");
+    marker.common.handle = doc.allocate_handle();
+    let marker = doc.add_entity(EntityType::Unknown(marker)).unwrap();
+    let settings = doc.allocate_handle();
+    let root = doc.header.named_objects_dict_handle;
+    doc.objects.insert(settings, ObjectType::Unknown {
+        type_name: "MY_SETTINGS".to_string(),
+        handle: settings,
+        raw_pairs: Vec::new(),
+    });
+
+    // Records built in code have no raw pairs; the writers provide them
+    let writers = DxfCustomTypes::new()
+        .with_entity_writer("my_marker", |entity, owner, writer| {
+            writer.write_handle(5, entity.common().handle)?;
+            writer.write_handle(330, owner)?;
+            writer.write_subclass("AcDbEntity")?;
+            writer.write_string(8, "0")?;
+            writer.write_point3d(10, Vector3::new(4.0, 5.0, 0.0))
+        })
+        .with_object_writer("MY_SETTINGS", move |object, writer| {
+            writer.write_handle(5, object.handle())?;
+            writer.write_handle(330, root)?;
+            writer.write_string(1, "units=mm")
+        });
+    let mut dxf_writer = DxfWriter::new(doc);
+    dxf_writer.set_custom_types(writers);
+    let bytes = dxf_writer.write_to_vec().unwrap();
+
+    // Without readers both come back as unknown records
+    let plain = DxfReader::from_bytes(bytes.clone()).unwrap().read().unwrap();
+    match plain.get_entity(marker) {
+        Some(EntityType::Unknown(entity)) => assert!(entity.raw_pairs.iter().any(|(code, v)| *code == 10 && v.starts_with('4'))),
+        other => panic!("expected the unknown marker, got {other:?}"),
+    }
+    assert!(matches!(plain.objects.get(&settings), Some(ObjectType::Unknown { .. })));
+
+    // The readers turn them into a point and an xrecord
+    let readers = DxfCustomTypes::new()
+        .with_entity_reader("MY_MARKER", |_, pairs| {
+            let value = |code| pairs.iter().find(|p| p.code == code).and_then(|p| p.as_double()).unwrap_or(0.0);
+            let mut point = acadrust::entities::Point::new();
+            point.common.handle = Handle::new(pairs.iter().find(|p| p.code == 5).and_then(|p| p.as_handle()).unwrap_or(0));
+            point.location = Vector3::new(value(10), value(20), value(30));
+            Ok(Some(EntityType::Point(point)))
+        })
+        .with_object_reader("MY_SETTINGS", |_, pairs| {
+            let mut record = XRecord::named("MY_SETTINGS");
+            record.handle = Handle::new(pairs.iter().find(|p| p.code == 5).and_then(|p| p.as_handle()).unwrap_or(0));
+            for pair in pairs.iter().filter(|p| p.code == 1) {
+                record.add_string(1, pair.value_string.clone());
+            }
+            Ok(Some(ObjectType::XRecord(record)))
+        });
+    let read = DxfReader::from_bytes(bytes.clone()).unwrap().with_custom_types(readers).read().unwrap();
+    match read.get_entity(marker) {
+        Some(EntityType::Point(point)) => assert_eq!(point.location, Vector3::new(4.0, 5.0, 0.0)),
+        other => panic!("expected the marker as a point, got {other:?}"),
+    }
+    match read.objects.get(&settings) {
+        Some(ObjectType::XRecord(record)) => assert_eq!(record.entries.len(), 1),
+        other => panic!("expected the settings as an xrecord, got {other:?}"),
+    }
+
+    // A reader declining a record keeps it as unknown
+    let declining = DxfCustomTypes::new().with_entity_reader("MY_MARKER", |_, _| Ok(None));
+    let read = DxfReader::from_bytes(bytes).unwrap().with_custom_types(declining).read().unwrap();
+    assert!(matches!(read.get_entity(marker), Some(EntityType::Unknown(_))));
+}
+
+/// MText rotation is read from DXF degrees into radians and survives
+/// repeated round-trips unchanged
+#[test]
+fn test_mtext_rotation_roundtrip() {
+    use acadrust::entities::{EntityType, MText};
+    use acadrust::types::{Angle, Vector3};
+    use acadrust::{CadDocument, DxfWriter};
+
+    let mut doc = CadDocument::new();
+    let mut mtext = MText::with_value("Rotated", Vector3::ZERO);
+    mtext.set_rotation(Angle::from_degrees(30.0));
+    let handle = doc.add_entity(EntityType::MText(Box::new(mtext))).unwrap();
+
+    for _ in 0..2 {
+        let bytes = DxfWriter::new(doc).write_to_vec().unwrap();
+        doc = DxfReader::from_bytes(bytes).unwrap().read().unwrap();
+        match doc.get_entity(handle) {
+            Some(EntityType::MText(mtext)) => assert!((mtext.rotation().degrees() - 30.0).abs() < 1e-9),
+            other => panic!("expected the mtext, got {other:?}"),
+        }
+    }
+}
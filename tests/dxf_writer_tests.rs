@@ -0,0 +1,2299 @@
+//! Integration tests for DXF writing and round-trips
+
+mod common;
+
+use acadrust::DxfReader;
+
+/// Test that unknown entities and objects survive a DXF round-trip
+#[test]
+fn test_unknown_entity_and_object_roundtrip() {
+    use acadrust::entities::EntityType;
+    use acadrust::objects::ObjectType;
+    use acadrust::DxfWriter;
+    use std::io::Cursor;
+
+    let dxf_content = "  0\nSECTION\n  2\nENTITIES\n  0\nACME_WIDGET\n  5\n1A0\n330\n1F\n100\nAcDbEntity\n  8\nParts\n 62\n3\n100\nAcmeWidget\n 10\n1.5\n 20\n2.5\n 30\n0.0\n 70\n3\n  1\nGear\n  0\nENDSEC\n  0\nSECTION\n  2\nOBJECTS\n  0\nACME_DATA\n  5\n1B0\n330\n0\n100\nAcmeData\n 90\n42\n  1\npayload\n  0\nENDSEC\n  0\nEOF\n";
+
+    let doc = DxfReader::from_reader(Cursor::new(dxf_content.as_bytes().to_vec()))
+        .unwrap()
+        .read()
+        .unwrap();
+
+    let widget = doc
+        .entities()
+        .find_map(|e| match e {
+            EntityType::Unknown(u) if u.dxf_name == "ACME_WIDGET" => Some(u.clone()),
+            _ => None,
+        })
+        .expect("unknown entity");
+    assert_eq!(widget.common.layer, "Parts");
+    assert_eq!(widget.raw_pairs.len(), 11);
+    assert!(widget.raw_pairs.contains(&(1, "Gear".to_string())));
+
+    let bytes = DxfWriter::new(doc).write_to_vec().unwrap();
+    let reread = DxfReader::from_reader(Cursor::new(bytes)).unwrap().read().unwrap();
+
+    let widget2 = reread
+        .entities()
+        .find_map(|e| match e {
+            EntityType::Unknown(u) if u.dxf_name == "ACME_WIDGET" => Some(u.clone()),
+            _ => None,
+        })
+        .expect("unknown entity after round-trip");
+    // Pairs are written verbatim; only the owner is rewritten to the model space record
+    let without_owner = |pairs: &[(i32, String)]| -> Vec<(i32, String)> {
+        pairs.iter().filter(|(code, _)| *code != 330).cloned().collect()
+    };
+    assert_eq!(without_owner(&widget2.raw_pairs), without_owner(&widget.raw_pairs));
+
+    let data = reread
+        .objects
+        .values()
+        .find_map(|o| match o {
+            ObjectType::Unknown { type_name, raw_pairs, .. } if type_name == "ACME_DATA" => {
+                Some(raw_pairs.clone())
+            }
+            _ => None,
+        })
+        .expect("unknown object after round-trip");
+    assert!(data.contains(&(90, "42".to_string())));
+    assert!(data.contains(&(1, "payload".to_string())));
+}
+
+/// Edits to the common data of an unknown entity are written, not the
+/// values it was read with
+#[test]
+fn test_unknown_entity_common_data_edits_written() {
+    use acadrust::entities::EntityType;
+    use acadrust::types::{Color, Handle, LineWeight};
+    use acadrust::DxfWriter;
+
+    // The widget references another entity with a 330 of its own data
+    let dxf_content = "  0\nSECTION\n  2\nENTITIES\n  0\nACME_WIDGET\n  5\n1A0\n102\n{ACAD_REACTORS\n330\n1C0\n102\n}\n330\n1F\n100\nAcDbEntity\n  8\nParts\n  6\nDASHED\n 62\n3\n100\nAcmeWidget\n330\n1D0\n  8\nNotTheLayer\n 70\n3\n  0\nENDSEC\n  0\nEOF\n";
+    let widget = |doc: &acadrust::CadDocument| {
+        doc.entities()
+            .find_map(|e| match e {
+                EntityType::Unknown(u) if u.dxf_name == "ACME_WIDGET" => Some(u.clone()),
+                _ => None,
+            })
+            .expect("unknown entity")
+    };
+
+    let mut doc = DxfReader::from_bytes(dxf_content.as_bytes().to_vec()).unwrap().read().unwrap();
+    let read = widget(&doc);
+    assert_eq!(read.common.owner_handle, Handle::new(0x1F));
+    assert_eq!(read.common.layer, "Parts");
+    assert_eq!(read.common.reactors, vec![Handle::new(0x1C0)]);
+
+    for entity in doc.entities_mut() {
+        if let EntityType::Unknown(u) = entity {
+            u.common.layer = "Edited".to_string();
+            u.common.color = Color::Index(5);
+            u.common.line_weight = LineWeight::Value(35);
+            u.common.invisible = true;
+            u.common.reactors.clear();
+        }
+    }
+
+    let bytes = DxfWriter::new(doc).write_to_vec().unwrap();
+    let written = widget(&DxfReader::from_bytes(bytes).unwrap().read().unwrap());
+    assert_eq!(written.common.layer, "Edited");
+    assert_eq!(written.common.color, Color::Index(5));
+    assert_eq!(written.common.line_weight, LineWeight::Value(35));
+    assert!(written.common.invisible);
+    assert!(written.common.reactors.is_empty());
+    assert_ne!(written.common.owner_handle, Handle::new(0x1D0));
+    // Unmodelled common data and the widget's own data are kept
+    let has = |code: i32, value: &str| written.raw_pairs.iter().any(|(c, v)| *c == code && v.trim() == value);
+    assert!(has(6, "DASHED"));
+    assert!(has(330, "1D0"));
+    assert!(has(8, "NotTheLayer"));
+    assert!(!has(8, "Parts") && !has(62, "3"));
+}
+
+/// A raw pair whose value does not match the type of its group code is an
+/// error rather than a string in a numeric slot
+#[test]
+fn test_unknown_object_invalid_raw_value_rejected() {
+    use acadrust::error::DxfError;
+    use acadrust::objects::ObjectType;
+    use acadrust::{CadDocument, DxfWriter};
+
+    let document = |value: &str| {
+        let mut doc = CadDocument::new();
+        let handle = doc.allocate_handle();
+        let raw_pairs = vec![(100, "AcmeData".to_string()), (90, value.to_string())];
+        doc.objects.insert(handle, ObjectType::Unknown { type_name: "ACME_DATA".to_string(), handle, raw_pairs });
+        doc
+    };
+
+    assert!(DxfWriter::new_binary(document("42")).write_to_vec().is_ok());
+    for writer in [DxfWriter::new(document("many")), DxfWriter::new_binary(document("99999999999"))] {
+        assert!(matches!(writer.write_to_vec(), Err(DxfError::InvalidFormat(_))));
+    }
+}
+
+#[test]
+fn test_unknown_header_variables_roundtrip() {
+    use acadrust::DxfWriter;
+    use std::io::Cursor;
+
+    let dxf_content = "  0\nSECTION\n  2\nHEADER\n  9\n$ACADVER\n  1\nAC1015\n  9\n$ACME_SETTING\n 70\n7\n  9\n$ACME_ORIGIN\n 10\n1.5\n 20\n-2.0\n 30\n0.0\n  9\n$LTSCALE\n 40\n2.0\n  0\nENDSEC\n  0\nEOF\n";
+
+    let doc = DxfReader::from_reader(Cursor::new(dxf_content.as_bytes().to_vec()))
+        .unwrap()
+        .read()
+        .unwrap();
+
+    let vars = &doc.header.unknown_variables;
+    assert_eq!(vars.get("$ACME_SETTING"), Some(&vec![(70, "7".to_string())]));
+    assert_eq!(vars.get("$ACME_ORIGIN").map(|v| v.len()), Some(3));
+    assert!(!vars.contains_key("$LTSCALE"));
+    assert_eq!(doc.header.linetype_scale, 2.0);
+
+    let bytes = DxfWriter::new(doc.clone()).write_to_vec().unwrap();
+    let reread = DxfReader::from_reader(Cursor::new(bytes)).unwrap().read().unwrap();
+
+    assert_eq!(reread.header.unknown_variables.get("$ACME_SETTING"), vars.get("$ACME_SETTING"));
+    let origin = &reread.header.unknown_variables["$ACME_ORIGIN"];
+    assert_eq!(origin[0].0, 10);
+    assert_eq!(origin[0].1.parse::<f64>().unwrap(), 1.5);
+    assert_eq!(origin[1].1.parse::<f64>().unwrap(), -2.0);
+}
+
+#[test]
+fn test_geodata_roundtrip() {
+    use acadrust::objects::{GeoData, ObjectType};
+    use acadrust::types::{Vector2, Vector3};
+    use acadrust::{CadDocument, DxfWriter};
+    use std::io::Cursor;
+
+    let mut doc = CadDocument::new();
+    let mut geo = GeoData::new();
+    geo.handle = doc.allocate_handle();
+    geo.coordinate_type = 3;
+    geo.design_point = Vector3::new(100.0, 200.0, 0.0);
+    geo.reference_point = Vector3::new(13.4, 52.5, 34.0);
+    geo.horizontal_unit_scale = 0.001;
+    geo.north_direction = Vector2::new(0.5, 0.5);
+    geo.coordinate_system = "<Alias id=\"LL84\"/>".repeat(40);
+    doc.objects.insert(geo.handle, ObjectType::GeoData(geo.clone()));
+
+    let bytes = DxfWriter::new(doc).write_to_vec().unwrap();
+    let reread = DxfReader::from_reader(Cursor::new(bytes)).unwrap().read().unwrap();
+
+    let read = reread
+        .objects
+        .values()
+        .find_map(|o| match o {
+            ObjectType::GeoData(g) => Some(g.clone()),
+            _ => None,
+        })
+        .expect("GEODATA after round-trip");
+    assert_eq!(read.coordinate_type, 3);
+    assert_eq!(read.design_point, geo.design_point);
+    assert_eq!(read.reference_point, geo.reference_point);
+    assert_eq!(read.horizontal_unit_scale, 0.001);
+    assert_eq!(read.north_direction, geo.north_direction);
+    assert_eq!(read.coordinate_system, geo.coordinate_system);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_json_roundtrip() {
+    use acadrust::entities::{Circle, EntityType, Line, LwPolyline, Text};
+    use acadrust::types::{Vector2, Vector3};
+    use acadrust::{CadDocument, DxfWriter};
+    use std::io::Cursor;
+
+    let mut doc = CadDocument::new();
+    doc.add_entity(EntityType::Line(Line::from_coords(0.0, 0.0, 0.0, 10.0, 5.0, 0.0))).unwrap();
+    doc.add_entity(EntityType::Circle(Circle::from_coords(3.0, 4.0, 0.0, 2.5))).unwrap();
+    let mut lw = LwPolyline::new();
+    lw.add_point(Vector2::new(0.0, 0.0));
+    lw.add_point(Vector2::new(4.0, 1.0));
+    doc.add_entity(EntityType::LwPolyline(lw)).unwrap();
+    doc.add_entity(EntityType::Text(Text::with_value("Hello", Vector3::new(1.0, 2.0, 0.0)))).unwrap();
+
+    // Go through DXF first so the document carries the usual objects too
+    let bytes = DxfWriter::new(doc).write_to_vec().unwrap();
+    let doc = DxfReader::from_reader(Cursor::new(bytes)).unwrap().read().unwrap();
+
+    let json = serde_json::to_string(&doc).unwrap();
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(value["schema_version"], acadrust::document::SERDE_SCHEMA_VERSION);
+
+    let back: CadDocument = serde_json::from_str(&json).unwrap();
+    assert_eq!(back.entity_count(), doc.entity_count());
+    assert_eq!(back.objects.len(), doc.objects.len());
+    assert_eq!(back.layers.len(), doc.layers.len());
+    assert_eq!(serde_json::to_string(&back).unwrap(), json);
+
+    let mut newer = value;
+    newer["schema_version"] = serde_json::json!(acadrust::document::SERDE_SCHEMA_VERSION + 1);
+    assert!(serde_json::from_value::<CadDocument>(newer).is_err());
+}
+
+/// THUMBNAILIMAGE is read into the document preview and written back
+#[test]
+fn test_thumbnail_roundtrip() {
+    use acadrust::preview::{DwgPreview, PreviewType};
+    use acadrust::{CadDocument, DxfWriter};
+
+    // Longer than one 127-byte 310 chunk
+    let image: Vec<u8> = (0..300u32).map(|i| (i * 7) as u8).collect();
+    let mut doc = CadDocument::new();
+    doc.preview = Some(DwgPreview::from_image(image.clone()));
+
+    for binary in [false, true] {
+        let mut writer = DxfWriter::new(doc.clone());
+        writer.set_binary(binary);
+        let read = DxfReader::from_bytes(writer.write_to_vec().unwrap()).unwrap().read().unwrap();
+        let preview = read.preview.expect("preview");
+        assert_eq!(preview.code, PreviewType::Bmp);
+        assert_eq!(preview.raw_image, image);
+        assert!(read.notifications.is_empty(), "{:?}", read.notifications);
+    }
+
+    let text = String::from_utf8(DxfWriter::new(CadDocument::new()).write_to_vec().unwrap()).unwrap();
+    assert!(!text.contains("THUMBNAILIMAGE"));
+}
+
+/// Header $DIM* values follow the current dimension style on write, except
+/// those set explicitly
+#[test]
+fn test_dim_style_header_sync() {
+    use acadrust::io::dwg::{DwgReader, DwgWriter};
+    use acadrust::{CadDocument, DimStyle, DxfVersion, DxfWriter, TableEntry};
+    use std::io::Cursor;
+
+    let mut doc = CadDocument::with_version(DxfVersion::AC1018);
+    let mut style = DimStyle::new("Metric");
+    style.set_handle(doc.allocate_handle());
+    style.dimtxt = 2.5;
+    style.dimasz = 3.0;
+    style.dimclrd = 1;
+    style.dimdsep = b',' as i16;
+    doc.dim_styles.add(style).unwrap();
+    doc.header.current_dimstyle_name = "Metric".to_string();
+    doc.header.current_dimstyle_handle = acadrust::Handle::NULL;
+    doc.header.dim_text_height = 9.0;
+    assert!(!doc.dim_variables_in_sync());
+
+    let dxf = DxfReader::from_bytes(DxfWriter::new(doc.clone()).write_to_vec().unwrap()).unwrap().read().unwrap();
+    let dwg = DwgReader::from_reader(Cursor::new(DwgWriter::write(&doc).unwrap())).unwrap().read().unwrap();
+    for (label, read) in [("dxf", dxf.clone()), ("dwg", dwg)] {
+        let current = read.current_dim_style().unwrap();
+        assert_eq!(current.name, "Metric", "{label}");
+        assert_eq!(read.header.current_dimstyle_name, "Metric");
+        assert_eq!(read.header.current_dimstyle_handle, current.handle);
+        // The text height set on the header overrides the style
+        assert_eq!(read.header.dim_text_height, 9.0, "{label}");
+        assert_eq!(read.header.dim_arrow_size, 3.0);
+        assert_eq!(read.header.dim_line_color.index(), Some(1));
+        assert_eq!(read.header.dim_decimal_separator, ',');
+    }
+
+    assert_eq!(dxf.current_dim_style().unwrap().dimtxt, 2.5);
+
+    // After reading, style edits reach the header values the file did not
+    // override, and header edits are kept
+    let mut doc = dxf;
+    assert!(doc.dim_variables_in_sync());
+    let metric = doc.dim_styles.get_mut("Metric").unwrap();
+    metric.dimasz = 4.0;
+    metric.dimtxt = 3.5;
+    doc.header.dim_line_gap = 1.25;
+    let read = DxfReader::from_bytes(DxfWriter::new(doc).write_to_vec().unwrap()).unwrap().read().unwrap();
+    assert_eq!(read.header.dim_arrow_size, 4.0);
+    assert_eq!(read.header.dim_text_height, 9.0);
+    assert_eq!(read.header.dim_line_gap, 1.25);
+    assert_ne!(read.current_dim_style().unwrap().dimgap, 1.25);
+
+    // A current style the table lacks is rebuilt from the header
+    let mut doc = CadDocument::new();
+    doc.dim_styles.get_mut("Standard").unwrap().dimtxt = 4.0;
+    let text = String::from_utf8(DxfWriter::new(doc).write_to_vec().unwrap()).unwrap();
+    let text = text.replacen("$DIMSTYLE\n  2\nStandard", "$DIMSTYLE\n  2\nLegacy", 1);
+    assert!(text.contains("Legacy"));
+    let read = DxfReader::from_bytes(text.into_bytes()).unwrap().read().unwrap();
+    let legacy = read.dim_styles.get("Legacy").expect("style created from header");
+    assert_eq!(legacy.dimtxt, 4.0);
+    assert_eq!(read.header.current_dimstyle_handle, legacy.handle);
+}
+
+/// The OBJECTS section starts with a complete named object dictionary
+#[test]
+fn test_required_object_tree() {
+    use acadrust::objects::ObjectType;
+    use acadrust::{CadDocument, DxfWriter};
+
+    let mut bare = CadDocument::new();
+    bare.objects.clear();
+    for doc in [CadDocument::new(), bare] {
+        let read = DxfReader::from_bytes(DxfWriter::new(doc).write_to_vec().unwrap()).unwrap().read().unwrap();
+        let dictionary = |handle| match read.objects.get(&handle) {
+            Some(ObjectType::Dictionary(dict)) => dict,
+            other => panic!("expected a dictionary, got {other:?}"),
+        };
+        let root = dictionary(read.header.named_objects_dict_handle);
+        assert!(root.get("ACAD_GROUP").is_some());
+        let layouts = dictionary(root.get("ACAD_LAYOUT").expect("ACAD_LAYOUT"));
+        for name in ["Model", "Layout1"] {
+            let handle = layouts.get(name).expect(name);
+            assert!(matches!(read.objects.get(&handle), Some(ObjectType::Layout(l)) if l.name == name));
+        }
+    }
+}
+
+/// Paper space viewports get ids, a status and their layout's link
+#[test]
+fn test_paper_space_viewports() {
+    use acadrust::entities::{EntityType, Viewport};
+    use acadrust::objects::ObjectType;
+    use acadrust::{CadDocument, DxfWriter, Vector3};
+
+    let mut doc = CadDocument::new();
+    let paper = doc.block_records.get("*Paper_Space").unwrap().handle;
+    for center in [Vector3::new(148.5, 105.0, 0.0), Vector3::new(60.0, 50.0, 0.0)] {
+        let mut vp = Viewport::with_size(center, 100.0, 80.0);
+        vp.common.owner_handle = paper;
+        doc.add_entity(EntityType::Viewport(Box::new(vp))).unwrap();
+    }
+
+    let text = String::from_utf8(DxfWriter::new(doc).write_to_vec().unwrap()).unwrap();
+    assert!(text.contains("AcDbEntity\n 67\n1\n"));
+    let read = DxfReader::from_bytes(text.into_bytes()).unwrap().read().unwrap();
+
+    let mut viewports: Vec<&Viewport> = read
+        .entities()
+        .filter_map(|e| match e {
+            EntityType::Viewport(vp) => Some(vp.as_ref()),
+            _ => None,
+        })
+        .collect();
+    viewports.sort_by_key(|vp| vp.id);
+    assert_eq!(viewports.iter().map(|vp| vp.id).collect::<Vec<_>>(), [1, 2]);
+    assert!(viewports.iter().all(|vp| vp.common.owner_handle == paper));
+
+    let layout = read
+        .objects
+        .values()
+        .find_map(|o| match o {
+            ObjectType::Layout(l) if l.name == "Layout1" => Some(l),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(layout.block_record, paper);
+    assert_eq!(layout.viewport, viewports[0].common.handle);
+}
+
+/// Conflicting handles are moved to fresh ones on write
+#[test]
+fn test_handle_conflicts_repaired_on_write() {
+    use acadrust::entities::{Circle, EntityType, Line};
+    use acadrust::handles;
+    use acadrust::tables::BlockRecord;
+    use acadrust::{CadDocument, DxfWriter, Handle, Vector3};
+
+    let mut doc = CadDocument::new();
+    let line = |handle: u64, x: f64| {
+        let mut line = Line::from_points(Vector3::new(x, 0.0, 0.0), Vector3::new(x, 1.0, 0.0));
+        line.common.handle = Handle::new(handle);
+        EntityType::Line(line)
+    };
+    doc.add_entity(line(0x400, 0.0)).unwrap();
+    doc.add_entity(line(0x5000, 1.0)).unwrap();
+    let mut block = BlockRecord::new("B");
+    block.handle = doc.allocate_handle();
+    let mut circle = Circle::new();
+    circle.common.handle = Handle::new(0x400);
+    block.entities.push(EntityType::Circle(circle));
+    doc.block_records.add(block).unwrap();
+
+    let report = handles::check(&doc);
+    assert_eq!(report.issues.len(), 1);
+    assert_eq!(report.seed, 0x5002);
+    assert!(report.to_string().contains("duplicate handle 400 -> 5001"));
+
+    let text = String::from_utf8(DxfWriter::new(doc).write_to_vec().unwrap()).unwrap();
+    let read = DxfReader::from_bytes(text.into_bytes()).unwrap().read().unwrap();
+    let block_circle = &read.block_records.get("B").unwrap().entities[0];
+    assert_eq!(block_circle.common().handle, Handle::new(0x5001));
+    assert!(read.get_entity(Handle::new(0x400)).is_some());
+    assert!(read.header.handle_seed > 0x5001);
+}
+
+#[test]
+fn test_pdf_underlay_roundtrip() {
+    use acadrust::entities::EntityType;
+    use acadrust::objects::ObjectType;
+    use acadrust::types::Vector3;
+    use acadrust::{CadDocument, DxfWriter};
+    use std::io::Cursor;
+
+    let mut doc = CadDocument::new();
+    let first = doc.attach_pdf_underlay("plans/site.pdf", "2", Vector3::new(10.0, 20.0, 0.0), 0.5).unwrap();
+    let second = doc.attach_pdf_underlay("plans/site.pdf", "2", Vector3::ZERO, 1.0).unwrap();
+
+    let bytes = DxfWriter::new(doc).write_to_vec().unwrap();
+    let reread = DxfReader::from_reader(Cursor::new(bytes)).unwrap().read().unwrap();
+
+    let root = match &reread.objects[&reread.header.named_objects_dict_handle] {
+        ObjectType::Dictionary(d) => d.clone(),
+        _ => panic!("root dictionary"),
+    };
+    let definitions = match &reread.objects[&root.get("ACAD_PDFDEFINITIONS").unwrap()] {
+        ObjectType::Dictionary(d) => d.clone(),
+        _ => panic!("ACAD_PDFDEFINITIONS dictionary"),
+    };
+    assert_eq!(definitions.entries.len(), 1);
+    assert_eq!(definitions.entries[0].0, "site - 2");
+
+    let definition_handle = definitions.entries[0].1;
+    let ObjectType::UnderlayDefinition(definition) = &reread.objects[&definition_handle] else {
+        panic!("PDFDEFINITION");
+    };
+    assert_eq!(definition.file_path, "plans/site.pdf");
+    assert_eq!(definition.page_name, "2");
+    assert_eq!(definition.owner_handle, definitions.handle);
+    assert_eq!(definition.reactors, vec![definitions.handle, first, second]);
+
+    let Some(EntityType::Underlay(underlay)) = reread.get_entity(first) else {
+        panic!("PDFUNDERLAY");
+    };
+    assert_eq!(underlay.definition_handle, definition_handle);
+    assert_eq!(underlay.insertion_point, Vector3::new(10.0, 20.0, 0.0));
+    assert_eq!(underlay.x_scale, 0.5);
+    assert!(underlay.is_on());
+}
+
+#[test]
+fn test_image_and_wipeout_variables_roundtrip() {
+    use acadrust::objects::{ObjectType, RasterVariables, WipeoutVariables};
+    use acadrust::{CadDocument, DxfWriter};
+    use std::io::Cursor;
+
+    let mut doc = CadDocument::new();
+    assert!(doc.raster_variables().is_none());
+    let mut raster = RasterVariables::new();
+    raster.display_image_frame = 0;
+    raster.image_quality = 0;
+    raster.units = 5;
+    doc.set_raster_variables(raster.clone()).unwrap();
+    raster.image_quality = 1;
+    doc.set_raster_variables(raster).unwrap();
+    doc.set_wipeout_variables(WipeoutVariables { display_frame: 1, ..Default::default() }).unwrap();
+
+    let bytes = DxfWriter::new(doc).write_to_vec().unwrap();
+    let reread = DxfReader::from_reader(Cursor::new(bytes)).unwrap().read().unwrap();
+
+    let raster = reread.raster_variables().expect("RASTERVARIABLES");
+    assert_eq!((raster.display_image_frame, raster.image_quality, raster.units), (0, 1, 5));
+    assert_eq!(reread.wipeout_variables().map(|w| w.display_frame), Some(1));
+
+    let ObjectType::Dictionary(root) = &reread.objects[&reread.header.named_objects_dict_handle] else {
+        panic!("root dictionary");
+    };
+    assert_eq!(root.get("ACAD_IMAGE_VARS"), Some(raster.handle));
+    assert_eq!(root.entries.iter().filter(|(k, _)| k == "ACAD_IMAGE_VARS").count(), 1);
+}
+
+#[test]
+fn test_multileader_style_roundtrip() {
+    use acadrust::entities::{EntityType, MultiLeader};
+    use acadrust::objects::{ObjectType, TextAttachmentType};
+    use acadrust::types::Color;
+    use acadrust::{CadDocument, DxfWriter};
+    use std::io::Cursor;
+
+    let mut doc = CadDocument::new();
+    let standard = doc.multileader_style("Standard").expect("seeded Standard style").handle;
+    let leader = doc.add_entity(EntityType::MultiLeader(Box::new(MultiLeader::new()))).unwrap();
+    let Some(EntityType::MultiLeader(multileader)) = doc.get_entity(leader) else { unreachable!() };
+    assert_eq!(multileader.style_handle, Some(standard));
+
+    let Some(ObjectType::MultiLeaderStyle(style)) = doc.objects.get_mut(&standard) else { unreachable!() };
+    style.line_color = Color::from_rgb(10, 20, 30);
+    style.text_color = Color::Index(3);
+    style.default_text = "NOTE".to_string();
+    style.text_top_attachment = TextAttachmentType::MiddleOfText;
+    style.enable_block_rotation = true;
+    style.break_gap_size = 0.25;
+    let expected = style.clone();
+
+    let bytes = DxfWriter::new(doc).write_to_vec().unwrap();
+    let reread = DxfReader::from_reader(Cursor::new(bytes)).unwrap().read().unwrap();
+
+    let style = reread.multileader_style("Standard").expect("MLEADERSTYLE");
+    assert_eq!(style, &expected);
+    let Some(EntityType::MultiLeader(multileader)) = reread.get_entity(leader) else {
+        panic!("MULTILEADER");
+    };
+    assert_eq!(multileader.style_handle, Some(standard));
+}
+
+#[test]
+fn test_table_style_roundtrip() {
+    use acadrust::entities::{EntityType, Table};
+    use acadrust::objects::{CellAlignment, TableCellBorder, TableStyle};
+    use acadrust::types::{Color, LineWeight, Vector3};
+    use acadrust::{CadDocument, DxfWriter};
+    use std::io::Cursor;
+
+    let mut doc = CadDocument::new();
+    let mut standard = TableStyle::standard();
+    standard.description = "Bordered".to_string();
+    standard.set_margins(0.1);
+    standard.set_header_visible(false);
+    standard.title_row_style.fill_enabled = true;
+    standard.title_row_style.fill_color = Color::Index(8);
+    standard.header_row_style.alignment = CellAlignment::BottomLeft;
+    standard.data_row_style.format_string = "%lu2%pr3".to_string();
+    standard.data_row_style.set_all_borders(TableCellBorder::with_style(Color::Index(1), LineWeight::W0_50));
+    standard.data_row_style.vertical_inside_border = TableCellBorder::invisible();
+    let style_handle = doc.add_table_style(standard).unwrap();
+    doc.add_table_style(TableStyle::new("Plain")).unwrap();
+
+    let table = doc
+        .add_entity(EntityType::Table(Box::new(Table::new(Vector3::zero(), 2, 2))))
+        .unwrap();
+    let Some(EntityType::Table(linked)) = doc.get_entity(table) else { unreachable!() };
+    assert_eq!(linked.table_style_handle, Some(style_handle));
+    let expected = doc.table_style("Standard").unwrap().clone();
+
+    let bytes = DxfWriter::new(doc).write_to_vec().unwrap();
+    let reread = DxfReader::from_reader(Cursor::new(bytes)).unwrap().read().unwrap();
+
+    assert_eq!(reread.table_style("Standard"), Some(&expected));
+    assert_eq!(reread.table_style("Plain").map(|s| s.name.as_str()), Some("Plain"));
+    let Some(EntityType::Table(linked)) = reread.get_entity(table) else {
+        panic!("ACAD_TABLE");
+    };
+    assert_eq!(linked.table_style_handle, Some(style_handle));
+}
+
+#[test]
+fn test_raster_image_definition_roundtrip() {
+    use acadrust::entities::{EntityType, RasterImage};
+    use acadrust::objects::ObjectType;
+    use acadrust::types::Vector3;
+    use acadrust::{CadDocument, DxfWriter};
+    use std::io::Cursor;
+
+    let mut doc = CadDocument::new();
+    let image = RasterImage::new("scans/plan.jpg", Vector3::zero(), 1024.0, 768.0);
+    let handle = doc.add_entity(EntityType::RasterImage(Box::new(image))).unwrap();
+
+    let bytes = DxfWriter::new(doc).write_to_vec().unwrap();
+    let reread = DxfReader::from_reader(Cursor::new(bytes)).unwrap().read().unwrap();
+
+    let Some(EntityType::RasterImage(image)) = reread.get_entity(handle) else {
+        panic!("IMAGE");
+    };
+    let Some(ObjectType::ImageDefinition(definition)) = reread.objects.get(&image.definition_handle.unwrap()) else {
+        panic!("IMAGEDEF");
+    };
+    assert_eq!(definition.file_name, "scans/plan.jpg");
+    assert!(definition.is_loaded);
+    let Some(ObjectType::Dictionary(dictionary)) = reread.objects.get(&definition.owner) else {
+        panic!("ACAD_IMAGE_DICT");
+    };
+    assert_eq!(dictionary.get("plan"), Some(definition.handle));
+
+    let reactor_handle = image.definition_reactor_handle.unwrap();
+    assert_eq!(definition.reactors, vec![dictionary.handle, reactor_handle]);
+    let Some(ObjectType::ImageDefinitionReactor(reactor)) = reread.objects.get(&reactor_handle) else {
+        panic!("IMAGEDEF_REACTOR");
+    };
+    assert_eq!((reactor.owner, reactor.image_handle), (handle, handle));
+    assert!(reread.raster_variables().is_some());
+}
+
+#[test]
+fn test_scale_list_roundtrip() {
+    use acadrust::objects::Scale;
+    use acadrust::{CadDocument, DxfWriter};
+    use std::io::Cursor;
+
+    let mut doc = CadDocument::new();
+    let unit = doc.add_scale(Scale::unit_scale()).unwrap();
+    let fifty = doc.add_scale(Scale::scale_1_50()).unwrap();
+    assert_eq!(doc.add_scale(Scale::new("1:50", 1.0, 50.0)).unwrap(), fifty);
+    doc.add_scale(Scale::scale_2_1()).unwrap();
+
+    let bytes = DxfWriter::new(doc).write_to_vec().unwrap();
+    let reread = DxfReader::from_reader(Cursor::new(bytes)).unwrap().read().unwrap();
+
+    let names: Vec<&str> = reread.scales().iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(names, ["1:1", "1:50", "2:1"]);
+    let scale = reread.scale("1:50").unwrap();
+    assert_eq!(scale.handle, fifty);
+    assert_eq!((scale.paper_units, scale.drawing_units, scale.is_unit_scale), (1.0, 50.0, false));
+    assert!(reread.scale("1:1").is_some_and(|s| s.handle == unit && s.is_unit_scale));
+}
+
+#[test]
+fn test_geodata_catalog_roundtrip() {
+    use acadrust::objects::{CoordinateSystem, GeoData, ObjectType};
+    use acadrust::types::{Vector2, Vector3};
+    use acadrust::{CadDocument, DxfWriter};
+    use std::io::Cursor;
+
+    let mut doc = CadDocument::new();
+    let mut geo = GeoData::new();
+    geo.set_coordinate_system(&CoordinateSystem::from_epsg(32633).unwrap());
+    geo.design_point = Vector3::new(100.0, 200.0, 0.0);
+    geo.reference_point = Vector3::new(389_000.0, 5_820_000.0, 34.0);
+    geo.north_direction = Vector2::new(0.1, 0.99);
+    let handle = doc.set_geodata(geo.clone()).unwrap();
+    geo.reference_point.z = 35.0;
+    assert_eq!(doc.set_geodata(geo).unwrap(), handle);
+
+    let bytes = DxfWriter::new(doc).write_to_vec().unwrap();
+    let reread = DxfReader::from_reader(Cursor::new(bytes)).unwrap().read().unwrap();
+
+    let geo = reread.geodata().unwrap();
+    assert_eq!(geo.handle, handle);
+    assert_eq!(geo.coordinate_type, 2);
+    assert_eq!(geo.coordinate_system_code(), Some("UTM84-33N"));
+    assert_eq!(geo.coordinate_system, CoordinateSystem::from_code("UTM84-33N").unwrap().to_xml());
+    assert_eq!(geo.reference_point, Vector3::new(389_000.0, 5_820_000.0, 35.0));
+    assert_eq!(geo.north_direction, Vector2::new(0.1, 0.99));
+
+    let model_space = reread.block_records.get("*Model_Space").unwrap();
+    assert_eq!(geo.host_block, model_space.handle);
+    let xdictionary = model_space.xdictionary_handle.unwrap();
+    assert_eq!(geo.owner, xdictionary);
+    match reread.objects.get(&xdictionary) {
+        Some(ObjectType::Dictionary(dictionary)) => assert_eq!(dictionary.get("ACAD_GEOGRAPHICDATA"), Some(handle)),
+        other => panic!("expected the model space extension dictionary, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_dynamic_block_roundtrip() {
+    use acadrust::entities::{EntityType, Insert};
+    use acadrust::objects::{Dictionary, ObjectType};
+    use acadrust::tables::{AppId, BlockRecord};
+    use acadrust::xdata::{ExtendedDataRecord, XDataValue};
+    use acadrust::{CadDocument, DxfWriter, Vector3};
+    use std::io::Cursor;
+
+    let mut doc = CadDocument::new();
+    let mut app_id = AppId::new("AcDbBlockRepBTag");
+    app_id.handle = doc.allocate_handle();
+    doc.app_ids.add(app_id).unwrap();
+
+    // DOOR carries the evaluation graph in its extension dictionary
+    let mut door = BlockRecord::new("DOOR");
+    door.handle = doc.allocate_handle();
+    door.block_entity_handle = doc.allocate_handle();
+    door.block_end_handle = doc.allocate_handle();
+    let mut xdictionary = Dictionary::new();
+    xdictionary.handle = doc.allocate_handle();
+    xdictionary.owner = door.handle;
+    xdictionary.hard_owner = true;
+    let graph = doc.allocate_handle();
+    let parameter = doc.allocate_handle();
+    xdictionary.add_entry("ACAD_ENHANCEDBLOCK", graph);
+    door.xdictionary_handle = Some(xdictionary.handle);
+    let raw = |pairs: &[(i32, String)]| pairs.to_vec();
+    doc.objects.insert(graph, ObjectType::Unknown {
+        type_name: "ACAD_EVALUATION_GRAPH".to_string(),
+        handle: graph,
+        raw_pairs: raw(&[
+            (5, format!("{:X}", graph.value())),
+            (330, format!("{:X}", xdictionary.handle.value())),
+            (100, "AcDbEvalGraph".to_string()),
+            (96, "1".to_string()),
+            (97, "1".to_string()),
+            (91, "0".to_string()),
+            (93, "32".to_string()),
+            (95, "-1".to_string()),
+            (360, format!("{:X}", parameter.value())),
+        ]),
+    });
+    doc.objects.insert(parameter, ObjectType::Unknown {
+        type_name: "BLOCKVISIBILITYPARAMETER".to_string(),
+        handle: parameter,
+        raw_pairs: raw(&[
+            (5, format!("{:X}", parameter.value())),
+            (330, format!("{:X}", graph.value())),
+            (100, "AcDbEvalExpr".to_string()),
+            (90, "0".to_string()),
+            (100, "AcDbBlockVisibilityParameter".to_string()),
+            (301, "Visibility".to_string()),
+            (92, "2".to_string()),
+        ]),
+    });
+    doc.objects.insert(xdictionary.handle, ObjectType::Dictionary(xdictionary.clone()));
+    let door_handle = door.handle;
+    doc.block_records.add(door).unwrap();
+
+    // *U2 is the anonymous representation inserted in model space
+    let mut representation = BlockRecord::new("*U2");
+    representation.handle = doc.allocate_handle();
+    representation.block_entity_handle = doc.allocate_handle();
+    representation.block_end_handle = doc.allocate_handle();
+    representation.flags.anonymous = true;
+    let mut tag = ExtendedDataRecord::new("AcDbBlockRepBTag");
+    tag.add_value(XDataValue::Integer16(1));
+    tag.add_value(XDataValue::Handle(door_handle));
+    representation.extended_data.add_record(tag);
+    let representation_handle = representation.handle;
+    doc.block_records.add(representation).unwrap();
+    doc.add_entity(EntityType::Insert(Insert::new("*U2", Vector3::new(5.0, 0.0, 0.0)))).unwrap();
+
+    let bytes = DxfWriter::new(doc).write_to_vec().unwrap();
+    let reread = DxfReader::from_reader(Cursor::new(bytes)).unwrap().read().unwrap();
+
+    let door = reread.block_records.get("DOOR").unwrap();
+    assert_eq!(door.handle, door_handle);
+    assert!(door.is_dynamic(&reread));
+    assert_eq!(door.xdictionary_handle, Some(xdictionary.handle));
+
+    let representation = reread.block_records.get("*U2").unwrap();
+    assert_eq!(representation.handle, representation_handle);
+    assert!(representation.flags.anonymous);
+    assert!(!representation.is_dynamic(&reread));
+    assert_eq!(reread.dynamic_block_definition("*U2").map(|b| b.name.as_str()), Some("DOOR"));
+
+    match reread.objects.get(&graph) {
+        Some(ObjectType::Unknown { type_name, raw_pairs, .. }) => {
+            assert_eq!(type_name, "ACAD_EVALUATION_GRAPH");
+            assert!(raw_pairs.contains(&(360, format!("{:X}", parameter.value()))));
+        }
+        other => panic!("expected the evaluation graph, got {other:?}"),
+    }
+    assert!(matches!(
+        reread.objects.get(&parameter),
+        Some(ObjectType::Unknown { type_name, .. }) if type_name == "BLOCKVISIBILITYPARAMETER"
+    ));
+}
+
+#[test]
+fn test_render_objects_roundtrip() {
+    use acadrust::objects::{Background, BackgroundKind, ObjectType, RenderSettings, Sun};
+    use acadrust::types::Color;
+    use acadrust::{CadDocument, DxfWriter};
+    use std::io::Cursor;
+
+    let mut doc = CadDocument::new();
+    let mut sun = Sun::new();
+    sun.color = Color::from_rgb(255, 240, 200);
+    sun.time = 9 * 3600 + 30 * 60;
+    sun.shadow_type = 1;
+    let sun_handle = doc.set_viewport_sun("*Active", sun).unwrap();
+    let sky = doc.add_background("Sky", Background::sky(sun_handle)).unwrap();
+    let solid = doc.add_background("Blue", Background::solid(Color::Index(5))).unwrap();
+    assert_eq!(doc.add_background("Blue", Background::solid(Color::Index(4))).unwrap(), solid);
+    let ground = doc
+        .add_background(
+            "Ground",
+            Background::new(BackgroundKind::Other {
+                type_name: "GROUND_PLANE_BACKGROUND".to_string(),
+                subclass: "AcDbGroundPlaneBackground".to_string(),
+                data: vec![(90, "1".to_string()), (90, "-1023410171".to_string())],
+            }),
+        )
+        .unwrap();
+    doc.vports.get_mut("*Active").unwrap().background_handle = sky;
+
+    let mut settings = RenderSettings::new("Medium");
+    settings.type_name = "MENTALRAYRENDERSETTINGS".to_string();
+    settings.handle = doc.allocate_handle();
+    settings.owner = doc.header.named_objects_dict_handle;
+    settings.description = "Medium quality".to_string();
+    settings.predefined = true;
+    settings.subclass = "AcDbMentalRayRenderSettings".to_string();
+    settings.data = vec![(90, "2".to_string()), (90, "-2".to_string()), (40, "0.5".to_string())];
+    let settings_handle = settings.handle;
+    doc.objects.insert(settings_handle, ObjectType::RenderSettings(settings));
+    if let Some(ObjectType::Dictionary(root)) = doc.objects.get_mut(&doc.header.named_objects_dict_handle) {
+        root.add_entry("ACAD_RENDER_ACTIVE_SETTINGS", settings_handle);
+    }
+
+    let bytes = DxfWriter::new(doc).write_to_vec().unwrap();
+    let reread = DxfReader::from_reader(Cursor::new(bytes)).unwrap().read().unwrap();
+
+    let vport = reread.vports.get("*Active").unwrap();
+    assert_eq!(vport.sun_handle, sun_handle);
+    assert_eq!(vport.background_handle, sky);
+
+    let sun = reread.sun(sun_handle).unwrap();
+    assert_eq!(sun.owner, vport.handle);
+    assert_eq!(sun.color, Color::from_rgb(255, 240, 200));
+    assert_eq!(sun.time, 34_200);
+    assert_eq!(sun.shadow_type, 1);
+    assert!(sun.is_on && sun.has_shadow);
+
+    assert_eq!(reread.background(sky).unwrap().kind, BackgroundKind::Sky { sun: sun_handle });
+    assert_eq!(reread.background(solid).unwrap().kind, BackgroundKind::Solid { color: Color::Index(4) });
+    match &reread.background(ground).unwrap().kind {
+        BackgroundKind::Other { type_name, data, .. } => {
+            assert_eq!(type_name, "GROUND_PLANE_BACKGROUND");
+            assert_eq!(data[1], (90, "-1023410171".to_string()));
+        }
+        other => panic!("expected a ground plane background, got {other:?}"),
+    }
+
+    match reread.objects.get(&settings_handle) {
+        Some(ObjectType::RenderSettings(settings)) => {
+            assert_eq!(settings.type_name, "MENTALRAYRENDERSETTINGS");
+            assert_eq!(settings.name, "Medium");
+            assert_eq!(settings.description, "Medium quality");
+            assert!(settings.predefined && settings.back_faces_enabled && !settings.fog_enabled);
+            assert_eq!(settings.subclass, "AcDbMentalRayRenderSettings");
+            assert_eq!(settings.data.len(), 3);
+            assert_eq!(settings.data[1], (90, "-2".to_string()));
+        }
+        other => panic!("expected render settings, got {other:?}"),
+    }
+}
+
+/// Reactors and extension dictionaries keep an associative hatch linked to
+/// its boundary
+#[test]
+fn test_reactor_groups_roundtrip() {
+    use acadrust::entities::hatch::{BoundaryEdge, BoundaryPath, CircularArcEdge};
+    use acadrust::entities::{Circle, EntityType, Hatch};
+    use acadrust::objects::{Dictionary, ObjectType};
+    use acadrust::types::{Vector2, Vector3};
+    use acadrust::{CadDocument, DxfWriter};
+    use std::io::Cursor;
+
+    let mut doc = CadDocument::new();
+    let circle = doc.add_entity(EntityType::Circle(Circle::from_center_radius(Vector3::ZERO, 2.0))).unwrap();
+
+    let mut path = BoundaryPath::external();
+    path.add_edge(BoundaryEdge::CircularArc(CircularArcEdge {
+        center: Vector2::new(0.0, 0.0),
+        radius: 2.0,
+        start_angle: 0.0,
+        end_angle: std::f64::consts::TAU,
+        counter_clockwise: true,
+    }));
+    path.add_boundary_handle(circle);
+    let mut hatch = Hatch::solid();
+    hatch.is_associative = true;
+    hatch.add_path(path);
+    let hatch = doc.add_entity(EntityType::Hatch(Box::new(hatch))).unwrap();
+
+    let mut xdict = Dictionary::new();
+    xdict.handle = doc.allocate_handle();
+    xdict.owner = circle;
+    xdict.hard_owner = true;
+    xdict.reactors = vec![circle];
+    let xdict_handle = xdict.handle;
+    doc.objects.insert(xdict_handle, ObjectType::Dictionary(xdict));
+
+    let common = doc.get_entity_mut(circle).unwrap().common_mut();
+    common.reactors = vec![hatch];
+    common.xdictionary_handle = Some(xdict_handle);
+
+    let bytes = DxfWriter::new(doc).write_to_vec().unwrap();
+    let reread = DxfReader::from_reader(Cursor::new(bytes)).unwrap().read().unwrap();
+
+    let common = reread.get_entity(circle).unwrap().common();
+    assert_eq!(common.reactors, vec![hatch]);
+    assert_eq!(common.xdictionary_handle, Some(xdict_handle));
+
+    match reread.get_entity(hatch) {
+        Some(EntityType::Hatch(hatch)) => {
+            assert!(hatch.is_associative);
+            assert_eq!(hatch.paths[0].boundary_handles, vec![circle]);
+            assert_ne!(hatch.common.owner_handle, circle);
+        }
+        other => panic!("expected a hatch, got {other:?}"),
+    }
+
+    match reread.objects.get(&xdict_handle) {
+        Some(ObjectType::Dictionary(dictionary)) => {
+            assert_eq!(dictionary.owner, circle);
+            assert_eq!(dictionary.reactors, vec![circle]);
+        }
+        other => panic!("expected the extension dictionary, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_update_title_block() {
+    use acadrust::entities::{AttributeEntity, EntityType, Insert, MText};
+    use acadrust::objects::{Dictionary, ObjectType};
+    use acadrust::types::Vector3;
+    use acadrust::{CadDocument, DxfWriter, Handle};
+    use std::collections::HashMap;
+    use std::io::Cursor;
+
+    let mut doc = CadDocument::new();
+    let number = doc.allocate_handle();
+    let note = doc.add_entity(EntityType::MText(Box::new(MText::new()))).unwrap();
+    let other = doc.add_entity(EntityType::MText(Box::new(MText::new()))).unwrap();
+
+    // MTEXT showing the drawing number through a field, as AutoCAD stores it
+    let hex = |h: Handle| format!("{:X}", h.value());
+    let (xdict, fields, text_field, field) =
+        (doc.allocate_handle(), doc.allocate_handle(), doc.allocate_handle(), doc.allocate_handle());
+    let mut xdictionary = Dictionary::new();
+    xdictionary.handle = xdict;
+    xdictionary.owner = note;
+    xdictionary.add_entry("ACAD_FIELD", fields);
+    let mut field_dictionary = Dictionary::new();
+    field_dictionary.handle = fields;
+    field_dictionary.owner = xdict;
+    field_dictionary.add_entry("TEXT", text_field);
+    doc.objects.insert(xdict, ObjectType::Dictionary(xdictionary));
+    doc.objects.insert(fields, ObjectType::Dictionary(field_dictionary));
+    let pairs = |owner: Handle, handle: Handle, rest: &[(i32, String)]| {
+        let mut pairs = vec![(5, hex(handle)), (330, hex(owner)), (100, "AcDbField".to_string())];
+        pairs.extend_from_slice(rest);
+        pairs
+    };
+    let text_pairs = pairs(fields, text_field, &[
+        (1, "_text".to_string()),
+        (2, "%<\\_FldIdx 0>%".to_string()),
+        (90, "1".to_string()),
+        (360, hex(field)),
+    ]);
+    let field_pairs = pairs(text_field, field, &[
+        (1, "AcObjProp".to_string()),
+        (2, "\\AcObjProp Object(%<\\_ObjId 0>%).TextString".to_string()),
+        (90, "0".to_string()),
+        (97, "1".to_string()),
+        (331, hex(number)),
+    ]);
+    for (handle, raw_pairs) in [(text_field, text_pairs), (field, field_pairs)] {
+        doc.objects.insert(handle, ObjectType::Unknown { type_name: "FIELD".to_string(), handle, raw_pairs });
+    }
+    doc.get_entity_mut(note).unwrap().common_mut().xdictionary_handle = Some(xdict);
+
+    let bytes = DxfWriter::new(doc).write_to_vec().unwrap();
+    let mut doc = DxfReader::from_reader(Cursor::new(bytes)).unwrap().read().unwrap();
+
+    let mut title = Insert::new("TITLE", Vector3::ZERO);
+    for tag in ["DWGNO", "REV", "SCALE"] {
+        let mut attribute = AttributeEntity::simple(tag, "-");
+        attribute.common.handle = if tag == "DWGNO" { number } else { doc.allocate_handle() };
+        title.attributes.push(attribute);
+    }
+    doc.add_entity(EntityType::Insert(title)).unwrap();
+
+    let values = HashMap::from([("dwgno".to_string(), "A-101".to_string()), ("REV".to_string(), "C".to_string())]);
+    assert_eq!(doc.update_title_block(&values), 2);
+
+    let title = doc
+        .entities()
+        .find_map(|e| match e {
+            EntityType::Insert(insert) if insert.block_name == "TITLE" => Some(insert),
+            _ => None,
+        })
+        .unwrap();
+    let value = |tag: &str| title.attributes.iter().find(|a| a.tag == tag).unwrap().value.clone();
+    assert_eq!(value("DWGNO"), "A-101");
+    assert_eq!(value("REV"), "C");
+    assert_eq!(value("SCALE"), "-");
+
+    match (doc.get_entity(note), doc.get_entity(other)) {
+        (Some(EntityType::MText(note)), Some(EntityType::MText(other))) => {
+            assert_eq!(note.value, "A-101");
+            assert_eq!(other.value, "");
+        }
+        other => panic!("expected the notes, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_template_document_roundtrip() {
+    use acadrust::objects::{ObjectType, PlotPaperUnits};
+    use acadrust::template::Template;
+    use acadrust::{CadDocument, DxfWriter};
+    use std::io::Cursor;
+
+    let doc = CadDocument::from_template(Template::IsoA1);
+    let bytes = DxfWriter::new(doc.clone()).write_to_vec().unwrap();
+    let reread = DxfReader::from_reader(Cursor::new(bytes)).unwrap().read().unwrap();
+
+    assert_eq!(reread.header.measurement, 1);
+    assert_eq!(reread.header.insertion_units, 4);
+    assert_eq!(reread.layers.get("Hidden").unwrap().line_type, "HIDDEN");
+    assert!((reread.line_types.get("CENTER").unwrap().pattern_length - 50.8).abs() < 1e-9);
+    assert_eq!(reread.text_styles.get("ISOCP").unwrap().font_file, "isocp.shx");
+    assert_eq!(reread.current_dim_style().unwrap().name, "ISO-25");
+
+    let original = doc.block_records.get(Template::TITLE_BLOCK).unwrap();
+    let record = reread.block_records.get(Template::TITLE_BLOCK).unwrap();
+    assert_eq!(record.entities.len(), original.entities.len());
+
+    let layout = reread
+        .objects
+        .values()
+        .find_map(|o| match o {
+            ObjectType::Layout(l) if l.name == "Layout1" => Some(l),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(layout.plot_settings.paper_size, "ISO_full_bleed_A1_(841.00_x_594.00_MM)");
+    assert_eq!(layout.plot_settings.paper_units, PlotPaperUnits::Millimeters);
+    assert_eq!((layout.plot_settings.paper_width, layout.plot_settings.paper_height), (841.0, 594.0));
+    assert!(reread.entities().any(|e| matches!(
+        e,
+        acadrust::EntityType::Insert(i) if i.block_name == Template::TITLE_BLOCK
+            && i.common.owner_handle == reread.header.paper_space_block_handle
+    )));
+}
+
+#[test]
+fn test_entities_by_space() {
+    use acadrust::entities::{Circle, EntityType, Insert, Line};
+    use acadrust::tables::BlockRecord;
+    use acadrust::types::Vector3;
+    use acadrust::{CadDocument, DxfWriter};
+    use std::io::Cursor;
+
+    let mut doc = CadDocument::new();
+    doc.add_entity(EntityType::Line(Line::from_points(Vector3::ZERO, Vector3::UNIT_X))).unwrap();
+    doc.add_entity(EntityType::Insert(Insert::new("Bolt", Vector3::ZERO))).unwrap();
+    let mut sheet_frame = Circle::from_center_radius(Vector3::ZERO, 5.0);
+    sheet_frame.common.owner_handle = doc.header.paper_space_block_handle;
+    doc.add_entity(EntityType::Circle(sheet_frame)).unwrap();
+    let mut block = BlockRecord::new("Bolt");
+    block.handle = doc.allocate_handle();
+    block.block_entity_handle = doc.allocate_handle();
+    block.block_end_handle = doc.allocate_handle();
+    let mut head = EntityType::Circle(Circle::from_center_radius(Vector3::ZERO, 1.0));
+    head.as_entity_mut().set_handle(doc.allocate_handle());
+    block.entities.push(head);
+    doc.block_records.add(block).unwrap();
+
+    let bytes = DxfWriter::new(doc.clone()).write_to_vec().unwrap();
+    let reread = DxfReader::from_reader(Cursor::new(bytes)).unwrap().read().unwrap();
+    for doc in [&doc, &reread] {
+        let model: Vec<_> = doc.model_space().map(|e| e.as_entity().entity_type()).collect();
+        assert_eq!(model, ["LINE", "INSERT"]);
+        let paper: Vec<_> = doc.paper_space("Layout1").unwrap().collect();
+        assert!(matches!(paper[..], [EntityType::Circle(c)] if c.radius == 5.0));
+        let bolt: Vec<_> = doc.block_entities("Bolt").unwrap().collect();
+        assert!(matches!(bolt[..], [EntityType::Circle(c)] if c.radius == 1.0));
+        assert_eq!(doc.all_entities().count(), 4);
+        assert!(doc.paper_space("Layout9").is_none());
+        assert!(doc.block_entities("Nut").is_none());
+    }
+}
+
+#[test]
+fn test_polyline_vertex_handles_roundtrip() {
+    use acadrust::entities::{EntityType, Polyline};
+    use acadrust::types::Handle;
+    use acadrust::DxfWriter;
+    use std::io::Cursor;
+
+    let vertex = |handle: &str, x: f64, flags: i16| {
+        format!("  0\nVERTEX\n  5\n{handle}\n330\n1C0\n100\nAcDbEntity\n  8\n0\n100\nAcDbVertex\n100\nAcDb3dPolylineVertex\n 10\n{x}\n 20\n0.0\n 30\n1.0\n 70\n{flags}\n")
+    };
+    let dxf_content = format!(
+        "  0\nSECTION\n  2\nENTITIES\n  0\nPOLYLINE\n  5\n1C0\n100\nAcDbEntity\n  8\n0\n100\nAcDb3dPolyline\n 66\n1\n 10\n0.0\n 20\n0.0\n 30\n0.0\n 70\n13\n{}{}{}  0\nSEQEND\n  5\n1C4\n330\n1C0\n100\nAcDbEntity\n  8\n0\n  0\nLINE\n  5\n1C5\n100\nAcDbEntity\n  8\n0\n100\nAcDbLine\n 10\n0.0\n 20\n0.0\n 30\n0.0\n 11\n1.0\n 21\n0.0\n 31\n0.0\n  0\nENDSEC\n  0\nEOF\n",
+        vertex("1C1", 0.0, 48),
+        vertex("1C2", 5.0, 40),
+        vertex("1C3", 10.0, 48),
+    );
+
+    let polyline = |doc: &acadrust::CadDocument| -> Polyline {
+        doc.entities()
+            .find_map(|e| match e {
+                EntityType::Polyline(p) => Some(p.clone()),
+                _ => None,
+            })
+            .expect("polyline")
+    };
+    let doc = DxfReader::from_reader(Cursor::new(dxf_content.into_bytes())).unwrap().read().unwrap();
+    let original = polyline(&doc);
+    assert_eq!(doc.entities().count(), 2);
+    assert!(original.is_closed() && original.flags.is_spline_fit());
+    assert_eq!(original.seqend_handle, Some(Handle::new(0x1C4)));
+    let handles: Vec<u64> = original.vertices.iter().map(|v| v.handle.value()).collect();
+    assert_eq!(handles, [0x1C1, 0x1C2, 0x1C3]);
+    assert_eq!(original.vertices[1].flags.bits(), 40);
+
+    let bytes = DxfWriter::new(doc).write_to_vec().unwrap();
+    let reread = DxfReader::from_reader(Cursor::new(bytes)).unwrap().read().unwrap();
+    let copy = polyline(&reread);
+    assert_eq!(copy.common.handle, Handle::new(0x1C0));
+    assert_eq!(copy.seqend_handle, original.seqend_handle);
+    assert_eq!(copy.vertices, original.vertices);
+    assert_eq!(copy.flags.bits(), original.flags.bits());
+}
+
+#[test]
+fn test_polyline_2d_vertex_record_roundtrip() {
+    use acadrust::entities::{EntityType, Polyline2D, PolylineFlags, SmoothSurfaceType, Vertex2D, VertexFlags};
+    use acadrust::types::{Handle, Vector2, Vector3};
+    use acadrust::{CadDocument, DxfWriter};
+    use std::io::Cursor;
+
+    let mut polyline = Polyline2D::new();
+    polyline.flags = PolylineFlags::CURVE_FIT;
+    polyline.elevation = 2.0;
+    polyline.thickness = 0.5;
+    polyline.start_width = 0.1;
+    polyline.end_width = 0.2;
+    polyline.normal = Vector3::new(0.0, 0.0, -1.0);
+    polyline.smooth_surface = SmoothSurfaceType::CubicBSpline;
+    let mut tangent = Vertex2D::from_point(Vector2::new(0.0, 0.0)).with_bulge(0.5).with_width(0.3, 0.4);
+    tangent.location.z = 2.0;
+    tangent.flags = VertexFlags::CURVE_FIT_TANGENT;
+    tangent.curve_tangent = 0.75;
+    tangent.id = 7;
+    let mut extra = Vertex2D::new(Vector3::new(5.0, 5.0, 2.0)).with_bulge(-1.0);
+    extra.flags = VertexFlags::EXTRA_VERTEX;
+    polyline.add_vertex(tangent);
+    polyline.add_vertex(extra);
+    polyline.add_vertex(Vertex2D::new(Vector3::new(10.0, 0.0, 2.0)));
+
+    let mut doc = CadDocument::new();
+    doc.add_entity(EntityType::Polyline2D(polyline.clone())).unwrap();
+    let bytes = DxfWriter::new(doc).write_to_vec().unwrap();
+    let reread = DxfReader::from_reader(Cursor::new(bytes)).unwrap().read().unwrap();
+    let copy = reread
+        .entities()
+        .find_map(|e| match e {
+            EntityType::Polyline2D(p) => Some(p.clone()),
+            _ => None,
+        })
+        .expect("2D polyline");
+
+    assert_eq!(copy.flags, polyline.flags);
+    assert_eq!(copy.elevation, 2.0);
+    assert_eq!(copy.thickness, 0.5);
+    assert_eq!((copy.start_width, copy.end_width), (0.1, 0.2));
+    assert_eq!(copy.normal, polyline.normal);
+    assert_eq!(copy.smooth_surface, SmoothSurfaceType::CubicBSpline);
+    assert!(copy.seqend_handle.is_some());
+    assert_eq!(copy.vertices.len(), 3);
+    for (read, written) in copy.vertices.iter().zip(&polyline.vertices) {
+        assert_ne!(read.handle, Handle::NULL);
+        let curve_tangent = read.curve_tangent;
+        assert!((curve_tangent - written.curve_tangent).abs() < 1e-12);
+        assert_eq!(
+            Vertex2D { handle: Handle::NULL, curve_tangent: written.curve_tangent, ..read.clone() },
+            *written
+        );
+    }
+}
+
+#[test]
+fn test_mtext_columns_and_background_roundtrip() {
+    use acadrust::entities::{BackgroundFillFlags, ColumnType, EntityType, MText};
+    use acadrust::types::{Color, Transparency, Vector3};
+    use acadrust::{CadDocument, DxfWriter};
+    use std::io::Cursor;
+
+    let mut mtext = MText::with_value("Notes\\Pline two\\NColumn \\\\N two", Vector3::ZERO);
+    mtext.background_fill = BackgroundFillFlags::USE_BACKGROUND_FILL;
+    mtext.background_color = Color::from_rgb(255, 240, 200);
+    mtext.background_scale = 1.2;
+    mtext.background_transparency = Transparency::new(32);
+    mtext.columns.column_type = ColumnType::Dynamic;
+    mtext.columns.count = 2;
+    mtext.columns.width = 25.0;
+    mtext.columns.gutter = 2.5;
+    mtext.columns.flow_reversed = true;
+    mtext.columns.heights = vec![40.0, 10.0];
+    assert_eq!(mtext.column_values(), ["Notes\\Pline two", "Column \\\\N two"]);
+
+    let mut doc = CadDocument::new();
+    doc.add_entity(EntityType::MText(Box::new(mtext.clone()))).unwrap();
+    let bytes = DxfWriter::new(doc).write_to_vec().unwrap();
+    let reread = DxfReader::from_reader(Cursor::new(bytes)).unwrap().read().unwrap();
+    let copy = reread
+        .entities()
+        .find_map(|e| match e {
+            EntityType::MText(m) => Some(m.clone()),
+            _ => None,
+        })
+        .expect("MTEXT");
+
+    assert_eq!(copy.value, mtext.value);
+    assert!(copy.has_background_fill());
+    assert_eq!(copy.background_color, mtext.background_color);
+    assert_eq!(copy.background_scale, 1.2);
+    assert_eq!(copy.background_transparency, mtext.background_transparency);
+    assert_eq!(copy.columns, mtext.columns);
+    // Column heights share code 50 with the rotation
+    assert_eq!(copy.rotation, 0.0);
+}
+
+#[test]
+fn test_text_alignment_roundtrip() {
+    use acadrust::entities::{
+        AttributeDefinition, AttributeEntity, EntityType, HorizontalAlignment, Text,
+        TextHorizontalAlignment, TextVerticalAlignment, VerticalAlignment,
+    };
+    use acadrust::types::Vector3;
+    use acadrust::{CadDocument, DxfReader, DxfWriter};
+    use std::io::Cursor;
+
+    let mut text = Text::with_value("Right", Vector3::new(1.0, 2.0, 0.0)).with_rotation(30f64.to_radians());
+    text.alignment_point = Some(Vector3::new(10.0, 2.0, 0.0));
+    text.horizontal_alignment = TextHorizontalAlignment::Right;
+    text.vertical_alignment = TextVerticalAlignment::Top;
+    text.oblique_angle = 15f64.to_radians();
+    text.normal = Vector3::new(0.0, 0.0, -1.0);
+
+    let mut attdef = AttributeDefinition::new("PART".into(), "Part number".into(), "P-1".into());
+    attdef.alignment_point = Vector3::new(5.0, 5.0, 0.0);
+    attdef.horizontal_alignment = HorizontalAlignment::Center;
+    attdef.vertical_alignment = VerticalAlignment::Middle;
+    attdef.rotation = 90f64.to_radians();
+    attdef.field_length = 12;
+
+    let mut attrib = AttributeEntity::new("PART".into(), "P-7".into());
+    attrib.alignment_point = Vector3::new(7.0, 3.0, 0.0);
+    attrib.horizontal_alignment = HorizontalAlignment::Fit;
+    attrib.vertical_alignment = VerticalAlignment::Bottom;
+    attrib.rotation = 45f64.to_radians();
+    attrib.field_length = 8;
+
+    let mut doc = CadDocument::new();
+    doc.add_entity(EntityType::Text(text.clone())).unwrap();
+    doc.add_entity(EntityType::AttributeDefinition(Box::new(attdef.clone()))).unwrap();
+    doc.add_entity(EntityType::AttributeEntity(Box::new(attrib.clone()))).unwrap();
+    let bytes = DxfWriter::new(doc).write_to_vec().unwrap();
+    let reread = DxfReader::from_reader(Cursor::new(bytes)).unwrap().read().unwrap();
+
+    let close = |a: f64, b: f64| (a - b).abs() < 1e-9;
+    let mut found = 0;
+    for entity in reread.entities() {
+        match entity {
+            EntityType::Text(copy) => {
+                assert_eq!(copy.alignment_point, text.alignment_point);
+                assert_eq!(copy.horizontal_alignment, TextHorizontalAlignment::Right);
+                assert_eq!(copy.vertical_alignment, TextVerticalAlignment::Top);
+                assert!(close(copy.rotation, text.rotation));
+                assert!(close(copy.oblique_angle, text.oblique_angle));
+                assert_eq!(copy.normal, text.normal);
+                found += 1;
+            }
+            EntityType::AttributeDefinition(copy) => {
+                assert_eq!(copy.alignment_point, attdef.alignment_point);
+                assert_eq!(copy.horizontal_alignment, HorizontalAlignment::Center);
+                assert_eq!(copy.vertical_alignment, VerticalAlignment::Middle);
+                assert!(close(copy.rotation, attdef.rotation));
+                assert_eq!((copy.tag.as_str(), copy.prompt.as_str()), ("PART", "Part number"));
+                assert_eq!(copy.field_length, 12);
+                found += 1;
+            }
+            EntityType::AttributeEntity(read) => {
+                assert_eq!(read.alignment_point, attrib.alignment_point);
+                assert_eq!(read.horizontal_alignment, HorizontalAlignment::Fit);
+                assert_eq!(read.vertical_alignment, VerticalAlignment::Bottom);
+                assert!(close(read.rotation, attrib.rotation));
+                assert_eq!(read.field_length, 8);
+                found += 1;
+            }
+            _ => {}
+        }
+    }
+    assert_eq!(found, 3);
+}
+
+#[test]
+fn test_insert_attributes_and_array_roundtrip() {
+    use acadrust::entities::{AttributeEntity, EntityType, Insert, Line};
+    use acadrust::tables::BlockRecord;
+    use acadrust::types::Vector3;
+    use acadrust::{CadDocument, DxfReader, DxfWriter};
+    use std::io::Cursor;
+
+    let mut doc = CadDocument::new();
+    let mut block = BlockRecord::new("TAG");
+    block.entities.push(EntityType::Line(Line::from_coords(0.0, 0.0, 0.0, 1.0, 0.0, 0.0)));
+    doc.block_records.add(block).unwrap();
+
+    let mut tagged = Insert::new("TAG", Vector3::new(5.0, 5.0, 0.0)).with_rotation(30f64.to_radians());
+    tagged.normal = Vector3::new(0.0, 0.0, -1.0);
+    tagged.attributes.push(AttributeEntity::new("NUMBER".into(), "42".into()));
+    tagged.attributes.push(AttributeEntity::new("NAME".into(), "Pump".into()));
+    doc.add_entity(EntityType::Insert(tagged.clone())).unwrap();
+
+    let mut array = Insert::new("TAG", Vector3::ZERO);
+    array.column_count = 3;
+    array.row_count = 2;
+    array.column_spacing = 4.0;
+    array.row_spacing = 2.5;
+    doc.add_entity(EntityType::Insert(array.clone())).unwrap();
+
+    let bytes = DxfWriter::new(doc).write_to_vec().unwrap();
+    let reread = DxfReader::from_reader(Cursor::new(bytes)).unwrap().read().unwrap();
+
+    // The attributes come back on their insert, not as separate entities
+    assert!(!reread.entities().any(|e| matches!(e, EntityType::AttributeEntity(_))));
+    let inserts: Vec<&Insert> = reread
+        .entities()
+        .filter_map(|e| match e {
+            EntityType::Insert(i) => Some(i),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(inserts.len(), 2);
+
+    let copy = inserts.iter().find(|i| !i.attributes.is_empty()).expect("insert with attributes");
+    let tags: Vec<_> = copy.attributes.iter().map(|a| (a.tag.as_str(), a.value.as_str())).collect();
+    assert_eq!(tags, vec![("NUMBER", "42"), ("NAME", "Pump")]);
+    assert!(copy.attributes.iter().all(|a| a.common.owner_handle == copy.common.handle));
+    assert_eq!(copy.normal, tagged.normal);
+    assert!((copy.rotation - tagged.rotation).abs() < 1e-12);
+
+    let copy = inserts.iter().find(|i| i.is_array()).expect("array insert");
+    assert_eq!((copy.column_count, copy.row_count), (3, 2));
+    assert_eq!((copy.column_spacing, copy.row_spacing), (4.0, 2.5));
+}
+
+#[test]
+fn test_dimension_subclass_roundtrip() {
+    use acadrust::entities::dimension::*;
+    use acadrust::entities::EntityType;
+    use acadrust::types::Vector3;
+    use acadrust::{CadDocument, DxfReader, DxfWriter};
+    use std::io::Cursor;
+
+    let p = |x: f64, y: f64| Vector3::new(x, y, 0.0);
+    let mut linear = DimensionLinear::rotated(p(0.0, 0.0), p(10.0, 5.0), 30f64.to_radians());
+    linear.definition_point = p(10.0, 12.0);
+    linear.ext_line_rotation = 15f64.to_radians();
+    linear.base.text_rotation = 45f64.to_radians();
+    linear.base.block_name = "*D1".to_string();
+    linear.base.insertion_point = p(1.0, 1.0);
+    linear.base.attachment_point = AttachmentPointType::TopCenter;
+    let mut aligned = DimensionAligned::new(p(0.0, 0.0), p(3.0, 4.0));
+    aligned.definition_point = p(-4.0, 3.0);
+    let mut radius = DimensionRadius::new(p(5.0, 5.0), p(8.0, 5.0));
+    radius.leader_length = 2.0;
+    let diameter = DimensionDiameter::new(p(1.0, 1.0), p(1.0, 4.0));
+    let mut angular = DimensionAngular2Ln::new(p(0.0, 0.0), p(5.0, 0.0), p(0.0, 5.0));
+    angular.dimension_arc = p(3.0, 3.0);
+    angular.definition_point = p(0.0, 7.0);
+    let mut angular_3pt = DimensionAngular3Pt::new(p(2.0, 2.0), p(6.0, 2.0), p(2.0, 6.0));
+    angular_3pt.definition_point = p(5.0, 5.0);
+    let mut ordinate = DimensionOrdinate::y_ordinate(p(4.0, 9.0), p(8.0, 9.0));
+    ordinate.definition_point = p(0.0, 0.0);
+
+    let dimensions = vec![
+        Dimension::Linear(linear),
+        Dimension::Aligned(aligned),
+        Dimension::Radius(radius),
+        Dimension::Diameter(diameter),
+        Dimension::Angular2Ln(angular),
+        Dimension::Angular3Pt(angular_3pt),
+        Dimension::Ordinate(ordinate),
+    ];
+    let mut doc = CadDocument::new();
+    for dimension in &dimensions {
+        doc.add_entity(EntityType::Dimension(Box::new(dimension.clone()))).unwrap();
+    }
+    let bytes = DxfWriter::new(doc).write_to_vec().unwrap();
+    let reread = DxfReader::from_reader(Cursor::new(bytes)).unwrap().read().unwrap();
+    let copies: Vec<Dimension> = reread
+        .entities()
+        .filter_map(|e| match e {
+            EntityType::Dimension(d) => Some(d.as_ref().clone()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(copies.len(), dimensions.len());
+
+    let close = |a: f64, b: f64| (a - b).abs() < 1e-12;
+    for written in dimensions {
+        let mut copy = copies
+            .iter()
+            .find(|c| c.base().dimension_type == written.base().dimension_type)
+            .cloned()
+            .expect("dimension of the same type");
+        assert!(close(copy.base().text_rotation, written.base().text_rotation));
+        if let (Dimension::Linear(read), Dimension::Linear(written)) = (&mut copy, &written) {
+            assert!(close(read.rotation, written.rotation));
+            assert!(close(read.ext_line_rotation, written.ext_line_rotation));
+            read.rotation = written.rotation;
+            read.ext_line_rotation = written.ext_line_rotation;
+        }
+        let base = copy.base_mut();
+        base.text_rotation = written.base().text_rotation;
+        base.common = written.base().common.clone();
+        // Group 10 is the subclass definition point
+        base.definition_point = written.base().definition_point;
+        assert_eq!(copy, written);
+    }
+}
+
+#[test]
+fn test_leader_annotation_and_hookline_roundtrip() {
+    use acadrust::entities::{EntityType, HooklineDirection, Leader, LeaderCreationType, MText};
+    use acadrust::types::{Color, Vector3};
+    use acadrust::{CadDocument, DxfReader, DxfWriter};
+    use std::io::Cursor;
+
+    let mut doc = CadDocument::new();
+    let note = doc
+        .add_entity(EntityType::MText(Box::new(MText::with_value("Weld here", Vector3::new(20.0, 10.0, 0.0)))))
+        .unwrap();
+    let mut leader = Leader::from_vertices(vec![
+        Vector3::new(0.0, 0.0, 0.0),
+        Vector3::new(8.0, 6.0, 0.0),
+        Vector3::new(18.0, 10.0, 0.0),
+    ])
+    .with_spline_path()
+    .with_hookline()
+    .with_annotation(note)
+    .with_creation_type(LeaderCreationType::WithText);
+    leader.hookline_direction = HooklineDirection::Same;
+    leader.override_color = Color::from_index(3);
+    leader.text_height = 2.5;
+    leader.text_width = 18.0;
+    leader.annotation_offset = Vector3::new(0.5, 0.0, 0.0);
+    doc.add_entity(EntityType::Leader(Box::new(leader.clone()))).unwrap();
+
+    let bytes = DxfWriter::new(doc).write_to_vec().unwrap();
+    let reread = DxfReader::from_reader(Cursor::new(bytes)).unwrap().read().unwrap();
+    let copy = reread
+        .entities()
+        .find_map(|e| match e {
+            EntityType::Leader(l) => Some(l.clone()),
+            _ => None,
+        })
+        .expect("leader");
+
+    assert_eq!(copy.annotation_handle, note);
+    assert!(matches!(reread.get_entity(note), Some(EntityType::MText(_))));
+    assert_eq!(copy.vertices, leader.vertices);
+    assert_eq!(copy.path_type, leader.path_type);
+    assert_eq!((copy.hookline_enabled, copy.hookline_direction), (true, HooklineDirection::Same));
+    assert_eq!(copy.override_color, Color::from_index(3));
+    assert_eq!((copy.text_height, copy.text_width), (2.5, 18.0));
+    assert_eq!(copy.annotation_offset, leader.annotation_offset);
+}
+
+#[test]
+fn test_spline_weights_tolerances_and_flags_roundtrip() {
+    use acadrust::entities::{EntityType, Spline};
+    use acadrust::types::Vector3;
+    use acadrust::{CadDocument, DxfReader, DxfWriter};
+    use std::io::Cursor;
+
+    // A rational quadratic arc
+    let mut arc = Spline::from_control_points(
+        2,
+        vec![Vector3::new(1.0, 0.0, 0.0), Vector3::new(1.0, 1.0, 0.0), Vector3::new(0.0, 1.0, 0.0)],
+    );
+    arc.knots = vec![0.0, 0.0, 0.0, 1.0, 1.0, 1.0];
+    arc.weights = vec![1.0, 0.5, 1.0];
+    arc.flags.planar = true;
+    arc.knot_tolerance = 1e-9;
+    arc.control_point_tolerance = 1e-8;
+
+    // A periodic fit point spline with end tangents
+    let mut ring = Spline::from_fit_points(vec![
+        Vector3::new(0.0, 0.0, 0.0),
+        Vector3::new(5.0, 3.0, 0.0),
+        Vector3::new(10.0, 0.0, 0.0),
+    ]);
+    ring.flags.periodic = true;
+    ring.fit_tolerance = 0.01;
+    ring.start_tangent = Vector3::new(0.0, 1.0, 0.0);
+    ring.end_tangent = Vector3::new(0.0, -1.0, 0.0);
+    ring.normal = Vector3::new(0.0, 0.0, -1.0);
+
+    let mut doc = CadDocument::new();
+    doc.add_entity(EntityType::Spline(Box::new(arc.clone()))).unwrap();
+    doc.add_entity(EntityType::Spline(Box::new(ring.clone()))).unwrap();
+
+    let bytes = DxfWriter::new(doc).write_to_vec().unwrap();
+    let reread = DxfReader::from_reader(Cursor::new(bytes)).unwrap().read().unwrap();
+    let splines: Vec<Spline> = reread
+        .entities()
+        .filter_map(|e| match e {
+            EntityType::Spline(s) => Some(s.as_ref().clone()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(splines.len(), 2);
+    let (arc_copy, ring_copy) = if splines[0].fit_points.is_empty() {
+        (&splines[0], &splines[1])
+    } else {
+        (&splines[1], &splines[0])
+    };
+
+    assert_eq!(arc_copy.weights, arc.weights);
+    assert!(arc_copy.flags.rational && arc_copy.flags.planar && !arc_copy.flags.closed);
+    assert_eq!(arc_copy.knots, arc.knots);
+    assert_eq!((arc_copy.knot_tolerance, arc_copy.control_point_tolerance), (1e-9, 1e-8));
+
+    // Periodic splines are written closed
+    assert!(ring_copy.flags.periodic && ring_copy.flags.closed);
+    assert_eq!(ring_copy.fit_points, ring.fit_points);
+    assert_eq!(ring_copy.fit_tolerance, 0.01);
+    assert_eq!((ring_copy.start_tangent, ring_copy.end_tangent), (ring.start_tangent, ring.end_tangent));
+    assert_eq!(ring_copy.normal, ring.normal);
+}
+
+/// The splines of the reference samples keep their complete record through
+/// a write and read back
+#[test]
+fn test_reference_sample_splines_roundtrip() {
+    use acadrust::entities::EntityType;
+    use acadrust::DxfWriter;
+    use common::comparison::{assert_f64_eq, assert_vec3_eq, TOL};
+
+    let mut checked = 0;
+    for version in common::DXF_SAMPLE_VERSIONS {
+        for format in ["ascii", "binary"] {
+            let doc = common::read_sample_dxf(version, format);
+            let splines: Vec<_> = doc
+                .entities()
+                .filter_map(|e| match e {
+                    EntityType::Spline(s) => Some(s.as_ref().clone()),
+                    _ => None,
+                })
+                .collect();
+            if splines.is_empty() {
+                continue;
+            }
+
+            let bytes = DxfWriter::new(doc).write_to_vec().unwrap();
+            let reread = DxfReader::from_bytes(bytes).unwrap().read().unwrap();
+            for spline in &splines {
+                let context = format!("{version} {format} spline {:X}", spline.common.handle.value());
+                let Some(EntityType::Spline(copy)) = reread.get_entity(spline.common.handle) else {
+                    panic!("{context}: missing after round-trip");
+                };
+                assert_eq!(copy.degree, spline.degree, "{context}");
+                assert_eq!(copy.flags, spline.flags, "{context}");
+                assert_eq!(copy.knots.len(), spline.knots.len(), "{context}");
+                assert_eq!(copy.weights.len(), spline.weights.len(), "{context}");
+                assert_eq!(copy.control_points.len(), spline.control_points.len(), "{context}");
+                assert_eq!(copy.fit_points.len(), spline.fit_points.len(), "{context}");
+                for (a, b) in copy.knots.iter().zip(&spline.knots).chain(copy.weights.iter().zip(&spline.weights)) {
+                    assert_f64_eq(*a, *b, TOL);
+                }
+                for (a, b) in copy.control_points.iter().zip(&spline.control_points).chain(copy.fit_points.iter().zip(&spline.fit_points)) {
+                    assert_vec3_eq(a, b, TOL);
+                }
+                assert_f64_eq(copy.knot_tolerance, spline.knot_tolerance, TOL);
+                assert_f64_eq(copy.control_point_tolerance, spline.control_point_tolerance, TOL);
+                assert_f64_eq(copy.fit_tolerance, spline.fit_tolerance, TOL);
+                assert_vec3_eq(&copy.start_tangent, &spline.start_tangent, TOL);
+                assert_vec3_eq(&copy.end_tangent, &spline.end_tangent, TOL);
+                assert_vec3_eq(&copy.normal, &spline.normal, TOL);
+                checked += 1;
+            }
+        }
+    }
+    assert!(checked > 0, "no splines in the reference samples");
+}
+
+#[test]
+fn test_hatch_paths_pattern_and_seeds_roundtrip() {
+    use acadrust::entities::hatch::{
+        BoundaryEdge, BoundaryPath, CircularArcEdge, EllipticArcEdge, HatchPatternType, HatchStyleType, LineEdge,
+        PolylineEdge, SplineEdge,
+    };
+    use acadrust::entities::{EntityType, Hatch, HatchPattern, HatchPatternLine};
+    use acadrust::types::{Vector2, Vector3};
+    use acadrust::{CadDocument, DxfReader, DxfWriter};
+    use std::io::Cursor;
+
+    let mut outer = PolylineEdge::new(Vec::new(), true);
+    outer.add_vertex(Vector2::new(0.0, 0.0), 0.0);
+    outer.add_vertex(Vector2::new(20.0, 0.0), 0.5);
+    outer.add_vertex(Vector2::new(20.0, 10.0), 0.0);
+    outer.add_vertex(Vector2::new(0.0, 10.0), 0.0);
+    let mut outer_path = BoundaryPath::external();
+    outer_path.add_edge(BoundaryEdge::Polyline(outer));
+
+    let mut island = BoundaryPath::new();
+    island.add_edge(BoundaryEdge::Line(LineEdge { start: Vector2::new(2.0, 2.0), end: Vector2::new(6.0, 2.0) }));
+    island.add_edge(BoundaryEdge::CircularArc(CircularArcEdge {
+        center: Vector2::new(6.0, 4.0),
+        radius: 2.0,
+        start_angle: -std::f64::consts::FRAC_PI_2,
+        end_angle: std::f64::consts::FRAC_PI_2,
+        counter_clockwise: true,
+    }));
+    island.add_edge(BoundaryEdge::EllipticArc(EllipticArcEdge {
+        center: Vector2::new(4.0, 6.0),
+        major_axis_endpoint: Vector2::new(2.0, 0.0),
+        minor_axis_ratio: 0.5,
+        start_angle: 0.0,
+        end_angle: std::f64::consts::PI,
+        counter_clockwise: true,
+    }));
+    island.add_edge(BoundaryEdge::Spline(SplineEdge {
+        degree: 2,
+        rational: true,
+        periodic: false,
+        knots: vec![0.0, 0.0, 0.0, 1.0, 1.0, 1.0],
+        control_points: vec![Vector3::new(2.0, 6.0, 1.0), Vector3::new(1.0, 4.0, 0.5), Vector3::new(2.0, 2.0, 1.0)],
+        fit_points: vec![Vector2::new(2.0, 6.0), Vector2::new(2.0, 2.0)],
+        start_tangent: Vector2::new(-1.0, -1.0),
+        end_tangent: Vector2::new(1.0, -1.0),
+    }));
+
+    let mut pattern = HatchPattern::new("ANSI31");
+    pattern.add_line(HatchPatternLine {
+        angle: std::f64::consts::FRAC_PI_4,
+        base_point: Vector2::new(0.0, 0.0),
+        offset: Vector2::new(-2.245, 2.245),
+        dash_lengths: vec![1.5, -0.5],
+    });
+    let mut hatch = Hatch::with_pattern(pattern);
+    hatch.pattern_type = HatchPatternType::Custom;
+    hatch.pattern_angle = std::f64::consts::FRAC_PI_6;
+    hatch.pattern_scale = 2.0;
+    hatch.is_double = true;
+    hatch.is_associative = true;
+    hatch.style = HatchStyleType::Outer;
+    hatch.elevation = 3.0;
+    hatch.add_path(outer_path);
+    hatch.add_path(island);
+    hatch.seed_points = vec![Vector2::new(1.0, 1.0), Vector2::new(15.0, 5.0)];
+
+    let mut doc = CadDocument::new();
+    let handle = doc.add_entity(EntityType::Hatch(Box::new(hatch.clone()))).unwrap();
+    let bytes = DxfWriter::new(doc).write_to_vec().unwrap();
+    let reread = DxfReader::from_reader(Cursor::new(bytes)).unwrap().read().unwrap();
+    let Some(EntityType::Hatch(copy)) = reread.get_entity(handle) else { panic!("hatch not read back") };
+
+    assert!(copy.is_associative && !copy.is_solid && copy.is_double);
+    assert_eq!(copy.style, HatchStyleType::Outer);
+    assert_eq!(copy.pattern_type, HatchPatternType::Custom);
+    assert_eq!(copy.pattern.name, "ANSI31");
+    assert!((copy.pattern_angle - hatch.pattern_angle).abs() < 1e-12);
+    assert_eq!((copy.pattern_scale, copy.elevation), (2.0, 3.0));
+    assert_eq!(copy.seed_points, hatch.seed_points);
+    assert_eq!(copy.pattern.lines.len(), 1);
+    assert!((copy.pattern.lines[0].angle - std::f64::consts::FRAC_PI_4).abs() < 1e-12);
+    assert_eq!(copy.pattern.lines[0].dash_lengths, vec![1.5, -0.5]);
+
+    assert_eq!(copy.paths.len(), 2);
+    assert_eq!(copy.paths[0].flags.bits() & 3, 3);
+    assert_eq!(copy.paths[0].edges, hatch.paths[0].edges);
+    let edges = &copy.paths[1].edges;
+    assert_eq!(edges.len(), 4);
+    assert_eq!(edges[0], hatch.paths[1].edges[0]);
+    match (&edges[1], &edges[2], &edges[3]) {
+        (BoundaryEdge::CircularArc(arc), BoundaryEdge::EllipticArc(ellipse), BoundaryEdge::Spline(spline)) => {
+            assert!((arc.start_angle + std::f64::consts::FRAC_PI_2).abs() < 1e-12);
+            assert!((ellipse.end_angle - std::f64::consts::PI).abs() < 1e-12);
+            assert_eq!(ellipse.minor_axis_ratio, 0.5);
+            assert_eq!((spline.degree, spline.rational, &spline.knots), (2, true, &vec![0.0, 0.0, 0.0, 1.0, 1.0, 1.0]));
+            assert_eq!(spline.control_points[1], Vector3::new(1.0, 4.0, 0.5));
+            assert_eq!(spline.fit_points.len(), 2);
+            assert_eq!(spline.end_tangent, Vector2::new(1.0, -1.0));
+        }
+        other => panic!("unexpected edges {other:?}"),
+    }
+}
+
+#[test]
+fn test_image_clip_and_display_roundtrip() {
+    use acadrust::entities::raster_image::{ClipMode, ImageDisplayFlags};
+    use acadrust::entities::{EntityType, RasterImage};
+    use acadrust::types::{Vector2, Vector3};
+    use acadrust::{CadDocument, DxfReader, DxfWriter};
+    use std::io::Cursor;
+
+    let mut image = RasterImage::new("site.png", Vector3::new(5.0, 5.0, 0.0), 640.0, 480.0);
+    image.set_clip_polygon(vec![
+        Vector2::new(10.0, 10.0),
+        Vector2::new(600.0, 20.0),
+        Vector2::new(320.0, 450.0),
+    ]);
+    image.clip_boundary.clip_mode = ClipMode::Inside;
+    image.flags = ImageDisplayFlags::SHOW_IMAGE | ImageDisplayFlags::USE_CLIPPING_BOUNDARY | ImageDisplayFlags::TRANSPARENCY_ON;
+    image.brightness = 70;
+    image.contrast = 35;
+    image.fade = 20;
+
+    let mut doc = CadDocument::new();
+    let handle = doc.add_entity(EntityType::RasterImage(Box::new(image.clone()))).unwrap();
+    let bytes = DxfWriter::new(doc).write_to_vec().unwrap();
+    let reread = DxfReader::from_reader(Cursor::new(bytes)).unwrap().read().unwrap();
+    let Some(EntityType::RasterImage(copy)) = reread.get_entity(handle) else { panic!("image not read back") };
+
+    assert!(copy.clipping_enabled);
+    assert_eq!(copy.clip_boundary, image.clip_boundary);
+    assert_eq!(copy.flags, image.flags);
+    assert_eq!((copy.brightness, copy.contrast, copy.fade), (70, 35, 20));
+}
+
+/// A file whose IMAGE lacks the reactor pointer still links the image to
+/// its IMAGEDEF_REACTOR and takes the file path of the IMAGEDEF
+#[test]
+fn test_image_reactor_relinked_after_read() {
+    use acadrust::entities::{EntityType, RasterImage};
+    use acadrust::objects::ObjectType;
+    use acadrust::types::Vector3;
+    use acadrust::{CadDocument, DxfReader, DxfWriter};
+    use std::io::Cursor;
+
+    let mut doc = CadDocument::new();
+    let image = doc
+        .add_entity(EntityType::RasterImage(Box::new(RasterImage::new("plan.png", Vector3::ZERO, 100.0, 50.0))))
+        .unwrap();
+    let text = String::from_utf8(DxfWriter::new(doc).write_to_vec().unwrap()).unwrap();
+
+    // Drop the 360 pair of the IMAGE entity
+    let lines: Vec<&str> = text.lines().collect();
+    let start = lines.iter().position(|l| l.trim() == "AcDbRasterImage").unwrap();
+    let at = start + lines[start..].iter().position(|l| l.trim() == "360").unwrap();
+    let edited = [&lines[..at], &lines[at + 2..]].concat().join("\n");
+
+    let reread = DxfReader::from_reader(Cursor::new(edited.into_bytes())).unwrap().read().unwrap();
+    let Some(EntityType::RasterImage(copy)) = reread.get_entity(image) else { panic!("image not read back") };
+    assert_eq!(copy.file_path, "plan.png");
+    let reactor = copy.definition_reactor_handle.expect("reactor relinked");
+    match reread.objects.get(&reactor) {
+        Some(ObjectType::ImageDefinitionReactor(object)) => assert_eq!(object.image_handle, image),
+        other => panic!("expected the image reactor, got {other:?}"),
+    }
+    match copy.definition_handle.and_then(|h| reread.objects.get(&h)) {
+        Some(ObjectType::ImageDefinition(definition)) => assert!(definition.reactors.contains(&reactor)),
+        other => panic!("expected the image definition, got {other:?}"),
+    }
+
+    // Writing again adds no second reactor
+    let count = |doc: &CadDocument| doc.objects.values().filter(|o| matches!(o, ObjectType::ImageDefinitionReactor(_))).count();
+    let before = count(&reread);
+    let again = DxfWriter::new(reread).write_to_vec().unwrap();
+    let again = DxfReader::from_reader(Cursor::new(again)).unwrap().read().unwrap();
+    assert_eq!(count(&again), before);
+}
+
+/// A table written to DXF gets the `*T` block with its grid and texts, and
+/// reads back with its sizes, cell texts and merged cells
+#[test]
+fn test_table_block_and_cells_roundtrip() {
+    use acadrust::entities::{CellRange, EntityType, Table};
+    use acadrust::types::Vector3;
+    use acadrust::{CadDocument, DxfReader, DxfWriter};
+    use std::io::Cursor;
+
+    let mut table = Table::new(Vector3::new(10.0, 20.0, 0.0), 3, 2);
+    table.set_row_height(0, 1.5);
+    table.set_column_width(1, 4.0);
+    table.merge_cells(CellRange::new(0, 0, 0, 1));
+    table.set_cell_text(0, 0, "Parts");
+    table.set_cell_text(1, 0, "Bolt");
+    table.set_cell_text(2, 1, "12");
+
+    let mut doc = CadDocument::new();
+    let handle = doc.add_entity(EntityType::Table(Box::new(table))).unwrap();
+    let bytes = DxfWriter::new(doc).write_to_vec().unwrap();
+    let reread = DxfReader::from_reader(Cursor::new(bytes)).unwrap().read().unwrap();
+    let Some(EntityType::Table(copy)) = reread.get_entity(handle) else { panic!("table not read back") };
+
+    assert_eq!((copy.row_count(), copy.column_count()), (3, 2));
+    assert_eq!(copy.insertion_point, Vector3::new(10.0, 20.0, 0.0));
+    assert_eq!(copy.rows[0].height, 1.5);
+    assert_eq!(copy.columns[1].width, 4.0);
+    assert_eq!(copy.cell_text(0, 0), Some("Parts"));
+    assert_eq!(copy.cell_text(1, 0), Some("Bolt"));
+    assert_eq!(copy.cell_text(2, 1), Some("12"));
+    assert_eq!(copy.cell(0, 0).unwrap().merge_width, 2);
+
+    let block = copy.block_record_handle.expect("table block linked");
+    let record = reread.block_records.iter().find(|r| r.handle == block).expect("table block written");
+    assert!(record.name.starts_with("*T") && record.is_anonymous());
+    let entities: Vec<_> = reread.block_entities(&record.name).unwrap().collect();
+    assert_eq!(entities.iter().filter(|e| matches!(e, EntityType::MText(_))).count(), 3);
+    assert!(entities.iter().any(|e| matches!(e, EntityType::Line(_))));
+
+    // Writing again keeps the one block
+    let again = DxfWriter::new(reread).write_to_vec().unwrap();
+    let again = DxfReader::from_reader(Cursor::new(again)).unwrap().read().unwrap();
+    assert_eq!(again.block_records.iter().filter(|r| r.name.starts_with("*T")).count(), 1);
+}
+
+/// MESH vertices, faces, edges and creases survive a DXF round trip
+#[test]
+fn test_mesh_faces_and_creases_roundtrip() {
+    use acadrust::entities::{EntityType, Mesh};
+    use acadrust::types::Vector3;
+    use acadrust::{CadDocument, DxfReader, DxfWriter};
+    use std::io::Cursor;
+
+    let vertices = vec![
+        Vector3::new(0.0, 0.0, 0.0),
+        Vector3::new(1.0, 0.0, 0.0),
+        Vector3::new(1.0, 1.0, 0.0),
+        Vector3::new(0.0, 1.0, 0.0),
+        Vector3::new(0.5, 0.5, 1.0),
+    ];
+    let mut mesh = Mesh::from_triangles(vertices, &[(0, 1, 4), (1, 2, 4), (2, 3, 4), (3, 0, 4)]);
+    mesh.add_quad(0, 3, 2, 1);
+    mesh.compute_edges();
+    mesh.set_edge_crease(1, 2.5);
+    mesh.set_edge_crease(3, -1.0);
+    mesh.subdivision_level = 2;
+    mesh.blend_crease = false;
+
+    let mut doc = CadDocument::new();
+    let handle = doc.add_entity(EntityType::Mesh(mesh.clone())).unwrap();
+    let bytes = DxfWriter::new(doc).write_to_vec().unwrap();
+    let reread = DxfReader::from_reader(Cursor::new(bytes)).unwrap().read().unwrap();
+    let Some(EntityType::Mesh(copy)) = reread.get_entity(handle) else { panic!("mesh not read back") };
+
+    assert_eq!(copy.vertices, mesh.vertices);
+    assert_eq!(copy.faces, mesh.faces);
+    assert_eq!(copy.edges, mesh.edges);
+    assert_eq!(copy.edges[1].crease, Some(2.5));
+    assert_eq!(copy.edges[0].crease, None);
+    assert_eq!((copy.subdivision_level, copy.blend_crease), (2, false));
+}
+
+/// Image and wipeout frame settings are kept in their variables objects
+#[test]
+fn test_image_and_wipeout_frame_roundtrip() {
+    use acadrust::objects::FrameDisplay;
+    use acadrust::{CadDocument, DxfReader, DxfWriter};
+    use std::io::Cursor;
+
+    let mut doc = CadDocument::new();
+    assert_eq!(doc.image_frame(), FrameDisplay::Displayed);
+    assert_eq!(doc.wipeout_frame(), FrameDisplay::Hidden);
+    doc.set_image_frame(FrameDisplay::DisplayedNotPlotted).unwrap();
+    doc.set_wipeout_frame(FrameDisplay::Displayed).unwrap();
+    doc.set_image_frame(FrameDisplay::Hidden).unwrap();
+
+    let bytes = DxfWriter::new(doc).write_to_vec().unwrap();
+    let reread = DxfReader::from_reader(Cursor::new(bytes)).unwrap().read().unwrap();
+    assert_eq!(reread.image_frame(), FrameDisplay::Hidden);
+    assert_eq!(reread.wipeout_frame(), FrameDisplay::Displayed);
+}
+
+/// PDF underlay clip boundary, inverted clip, contrast and fade survive a
+/// DXF round trip, with the definition listed in ACAD_PDFDEFINITIONS
+#[test]
+fn test_underlay_clip_and_definition_roundtrip() {
+    use acadrust::entities::{EntityType, Underlay, UnderlayDefinition, UnderlayDisplayFlags};
+    use acadrust::objects::ObjectType;
+    use acadrust::types::{Handle, Vector2, Vector3};
+    use acadrust::{CadDocument, DxfReader, DxfWriter};
+    use std::io::Cursor;
+
+    let mut doc = CadDocument::new();
+    // A definition added directly, listed in no dictionary
+    let mut definition = UnderlayDefinition::pdf("plans/site.pdf", "3");
+    definition.handle = Handle::new(0x500);
+    doc.objects.insert(definition.handle, ObjectType::UnderlayDefinition(definition));
+
+    let mut underlay = Underlay::pdf_at(Vector3::new(1.0, 2.0, 0.0));
+    underlay.definition_handle = Handle::new(0x500);
+    underlay.flags |= UnderlayDisplayFlags::CLIPPING | UnderlayDisplayFlags::MONOCHROME;
+    underlay.contrast = 40;
+    underlay.fade = 25;
+    underlay.clip_boundary_vertices = vec![Vector2::new(0.0, 0.0), Vector2::new(3.0, 0.0), Vector2::new(3.0, 2.0)];
+    underlay.clip_inverted = true;
+    let handle = doc.add_entity(EntityType::Underlay(underlay.clone())).unwrap();
+
+    let bytes = DxfWriter::new(doc).write_to_vec().unwrap();
+    let reread = DxfReader::from_reader(Cursor::new(bytes)).unwrap().read().unwrap();
+    let Some(EntityType::Underlay(copy)) = reread.get_entity(handle) else { panic!("underlay not read back") };
+    assert_eq!(copy.clip_boundary_vertices, underlay.clip_boundary_vertices);
+    assert!(copy.clip_inverted);
+    assert!(copy.flags.contains(UnderlayDisplayFlags::MONOCHROME));
+    assert_eq!((copy.contrast, copy.fade), (40, 25));
+
+    let dictionary = reread.objects.values().find_map(|o| match o {
+        ObjectType::Dictionary(d) if d.get("site - 3").is_some() => Some(d),
+        _ => None,
+    });
+    assert_eq!(dictionary.and_then(|d| d.get("site - 3")), Some(Handle::new(0x500)));
+    match reread.objects.get(&Handle::new(0x500)) {
+        Some(ObjectType::UnderlayDefinition(definition)) => {
+            assert_eq!(definition.name, "site - 3");
+            assert!(definition.reactors.contains(&handle));
+        }
+        other => panic!("expected the PDF definition, got {other:?}"),
+    }
+}
+
+/// Subclass markers of written entities match the classes AutoCAD writes
+/// for each entity and version
+#[test]
+fn test_entity_subclass_markers_by_version() {
+    use acadrust::entities::*;
+    use acadrust::types::{DxfVersion, Vector3};
+    use acadrust::{CadDocument, DxfWriter};
+
+    // The markers of each record as AutoCAD writes them in the reference DXFs
+    let expected = |name: &str, version: DxfVersion| -> Vec<&str> {
+        match name {
+            "ARC" => vec!["AcDbEntity", "AcDbCircle", "AcDbArc"],
+            "TEXT" => vec!["AcDbEntity", "AcDbText", "AcDbText"],
+            "DIMENSION" => vec!["AcDbEntity", "AcDbDimension", "AcDbAlignedDimension", "AcDbRotatedDimension"],
+            "3DSOLID" if version >= DxfVersion::AC1021 => vec!["AcDbEntity", "AcDbModelerGeometry", "AcDb3dSolid"],
+            "3DSOLID" => vec!["AcDbEntity", "AcDbModelerGeometry"],
+            "POLYLINE" => vec!["AcDbEntity", "AcDb2dPolyline"],
+            "VERTEX" => vec!["AcDbEntity", "AcDbVertex", "AcDb2dVertex"],
+            "SEQEND" => vec!["AcDbEntity"],
+            "MULTILEADER" => vec!["AcDbEntity", "AcDbMLeader"],
+            "WIPEOUT" => vec!["AcDbEntity", "AcDbWipeout"],
+            _ => panic!("unexpected entity {name}"),
+        }
+    };
+
+    let mut doc = CadDocument::new();
+    doc.add_entity(EntityType::Arc(Arc::from_coords(0.0, 0.0, 0.0, 5.0, 0.0, 1.0))).unwrap();
+    doc.add_entity(EntityType::Text(Text::with_value("A", Vector3::ZERO))).unwrap();
+    doc.add_entity(EntityType::Dimension(Box::new(Dimension::Linear(DimensionLinear::new(
+        Vector3::ZERO,
+        Vector3::new(10.0, 5.0, 0.0),
+    )))))
+    .unwrap();
+    doc.add_entity(EntityType::Solid3D(Solid3D::new())).unwrap();
+    let mut polyline = Polyline2D::new();
+    polyline.add_vertex(Vertex2D::new(Vector3::ZERO));
+    polyline.add_vertex(Vertex2D::new(Vector3::new(1.0, 1.0, 0.0)));
+    doc.add_entity(EntityType::Polyline2D(polyline)).unwrap();
+    doc.add_entity(EntityType::MultiLeader(Box::new(
+        MultiLeaderBuilder::new().text("Note", Vector3::new(5.0, 5.0, 0.0)).leader_line(vec![Vector3::ZERO]).build(),
+    )))
+    .unwrap();
+    doc.add_entity(EntityType::Wipeout(Wipeout::rectangular(Vector3::ZERO, 2.0, 1.0))).unwrap();
+
+    for version in [DxfVersion::AC1014, DxfVersion::AC1015, DxfVersion::AC1018, DxfVersion::AC1024, DxfVersion::AC1032] {
+        let mut doc = doc.clone();
+        doc.version = version;
+        let bytes = DxfWriter::new(doc.clone()).write_to_vec().unwrap();
+        let text = String::from_utf8_lossy(&bytes);
+        let lines: Vec<&str> = text.lines().map(str::trim).collect();
+
+        // Entity name, handle and markers of every record
+        let mut records: Vec<(&str, &str, Vec<&str>)> = Vec::new();
+        for pair in lines.chunks(2) {
+            match pair {
+                ["0", name] => records.push((name, "", Vec::new())),
+                ["5", handle] => {
+                    if let Some(record) = records.last_mut().filter(|r| r.1.is_empty()) {
+                        record.1 = handle;
+                    }
+                }
+                ["100", marker] => {
+                    if let Some(record) = records.last_mut() {
+                        record.2.push(marker);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        for entity in doc.entities() {
+            let handle = format!("{:X}", entity.common().handle.value());
+            let (name, _, markers) = records.iter().find(|r| r.1 == handle).expect("entity written");
+            assert_eq!(markers, &expected(name, version), "{name} in {version:?}");
+        }
+        for (name, _, markers) in &records {
+            if matches!(*name, "SEQEND" | "VERTEX") {
+                assert_eq!(markers, &expected(name, version), "{name} in {version:?}");
+            }
+        }
+        assert!(!records.iter().any(|r| r.2.contains(&"CONTEXT_DATA{")));
+    }
+}
+
+/// Elevation, thickness and extrusion of a lightweight polyline survive a
+/// DXF round trip and place it in WCS
+#[test]
+fn test_lwpolyline_thickness_and_extrusion_roundtrip() {
+    use acadrust::entities::{Entity, EntityType, LwPolyline};
+    use acadrust::types::{Vector2, Vector3};
+    use acadrust::{CadDocument, DxfReader, DxfWriter};
+    use std::io::Cursor;
+
+    // Drawn in the plane x = 3 (OCS of a normal along +X), 2 units thick
+    let mut polyline = LwPolyline::from_points(vec![Vector2::new(0.0, 0.0), Vector2::new(4.0, 1.0)]);
+    polyline.elevation = 3.0;
+    polyline.thickness = 2.0;
+    polyline.normal = Vector3::UNIT_X;
+
+    let bounds = polyline.bounding_box();
+    assert!((bounds.min - Vector3::new(3.0, 0.0, 0.0)).length() < 1e-9);
+    assert!((bounds.max - Vector3::new(5.0, 4.0, 1.0)).length() < 1e-9);
+
+    let mut doc = CadDocument::new();
+    let handle = doc.add_entity(EntityType::LwPolyline(polyline.clone())).unwrap();
+    let bytes = DxfWriter::new(doc).write_to_vec().unwrap();
+    let reread = DxfReader::from_reader(Cursor::new(bytes)).unwrap().read().unwrap();
+    let Some(EntityType::LwPolyline(copy)) = reread.get_entity(handle) else { panic!("polyline not read back") };
+    assert_eq!(copy.elevation, 3.0);
+    assert_eq!(copy.thickness, 2.0);
+    assert_eq!(copy.normal, Vector3::UNIT_X);
+
+    // Moving along WCS +X raises the elevation
+    let mut moved = copy.clone();
+    moved.translate(Vector3::new(1.0, 0.0, 0.0));
+    assert!((moved.elevation - 4.0).abs() < 1e-9);
+    assert_eq!(moved.vertices[1].location, copy.vertices[1].location);
+}
+
+/// The writers add the mandatory table entries a hand-built document
+/// lacks, unless told not to
+#[test]
+fn test_writers_ensure_default_entries() {
+    use acadrust::entities::{EntityType, Line};
+    use acadrust::io::dwg::{DwgReader, DwgWriter};
+    use acadrust::objects::ObjectType;
+    use acadrust::{CadDocument, DxfReader, DxfWriter};
+    use std::io::Cursor;
+
+    let mut doc = CadDocument::new();
+    doc.add_entity(EntityType::Line(Line::from_coords(0.0, 0.0, 0.0, 1.0, 1.0, 0.0))).unwrap();
+    doc.layers.remove("0");
+    doc.line_types.remove("Continuous");
+    doc.text_styles.remove("Standard");
+    doc.app_ids.remove("ACAD");
+    doc.block_records.remove("*Paper_Space");
+
+    let check = |read: &CadDocument| {
+        assert!(read.layers.get("0").is_some());
+        assert!(read.line_types.get("Continuous").is_some());
+        assert!(read.text_styles.get("Standard").is_some());
+        assert!(read.app_ids.get("ACAD").is_some());
+        assert!(read.block_records.get("*Paper_Space").is_some());
+        assert_eq!(read.entity_count(), 1);
+    };
+
+    let bytes = DxfWriter::new(doc.clone()).write_to_vec().unwrap();
+    check(&DxfReader::from_reader(Cursor::new(bytes)).unwrap().read().unwrap());
+    let bytes = DwgWriter::write(&doc).unwrap();
+    check(&DwgReader::from_reader(Cursor::new(bytes)).unwrap().read().unwrap());
+
+    // The new paper space block takes over the layout left without one
+    let mut fixed = doc.clone();
+    fixed.ensure_defaults();
+    let paper = fixed.block_records.get("*Paper_Space").unwrap();
+    assert_eq!(fixed.header.paper_space_block_handle, paper.handle);
+    let layout = fixed.objects.values().find_map(|o| match o {
+        ObjectType::Layout(layout) if layout.name == "Layout1" => Some(layout),
+        _ => None,
+    });
+    assert_eq!(layout.unwrap().block_record, paper.handle);
+    assert_eq!(paper.layout, layout.unwrap().handle);
+
+    // Reading starts from a new document, so look at the output itself
+    let mut writer = DxfWriter::new(doc.clone());
+    writer.set_ensure_defaults(false);
+    let text = String::from_utf8(writer.write_to_vec().unwrap()).unwrap();
+    assert!(!text.contains("\nContinuous\r\n") && !text.contains("\nContinuous\n"));
+    let text = String::from_utf8(DxfWriter::new(doc).write_to_vec().unwrap()).unwrap();
+    assert!(text.contains("\nContinuous\r\n") || text.contains("\nContinuous\n"));
+}
+
+/// Paper, margins, rotation and scale of a layout survive a round trip
+#[test]
+fn test_layout_plot_settings_roundtrip() {
+    use acadrust::objects::{ObjectType, PaperMargin, PlotPaperUnits, PlotRotation};
+    use acadrust::{CadDocument, DxfReader, DxfWriter};
+    use std::io::Cursor;
+
+    let mut doc = CadDocument::new();
+    for object in doc.objects.values_mut() {
+        let ObjectType::Layout(layout) = object else { continue };
+        if layout.name != "Layout1" {
+            continue;
+        }
+        let plot = &mut layout.plot_settings;
+        plot.paper_units = PlotPaperUnits::Millimeters;
+        plot.set_paper_with_margins(210.0, 297.0, PaperMargin::new(5.0, 10.0, 5.0, 20.0));
+        plot.rotation = PlotRotation::Degrees90;
+        plot.set_custom_scale(1.0, 50.0);
+    }
+
+    let bytes = DxfWriter::new(doc).write_to_vec().unwrap();
+    let read = DxfReader::from_reader(Cursor::new(bytes)).unwrap().read().unwrap();
+    let names: Vec<&str> = read.layouts().iter().map(|l| l.name.as_str()).collect();
+    assert_eq!(names, vec!["Model", "Layout1"]);
+
+    let plot = &read.layout("layout1").unwrap().plot_settings;
+    assert_eq!((plot.paper_width, plot.paper_height), (210.0, 297.0));
+    assert_eq!(plot.margins, PaperMargin::new(5.0, 10.0, 5.0, 20.0));
+    assert_eq!(plot.rotation, PlotRotation::Degrees90);
+    assert!((plot.plot_scale() - 0.02).abs() < 1e-12);
+}
+
+/// Reactors and extension dictionaries of groups and xrecords survive a
+/// round trip and show in the ownership graph
+#[test]
+fn test_object_reactors_and_ownership() {
+    use acadrust::entities::{EntityType, Line};
+    use acadrust::objects::{Dictionary, Group, ObjectType, XRecord};
+    use acadrust::ownership::{OwnershipKind, OwnershipNodeKind};
+    use acadrust::types::{Handle, Vector3};
+    use acadrust::{CadDocument, DxfWriter};
+    use std::io::Cursor;
+
+    let mut doc = CadDocument::new();
+    let line = doc
+        .add_entity(EntityType::Line(Line::from_points(Vector3::zero(), Vector3::new(1.0, 0.0, 0.0))))
+        .unwrap();
+
+    // A line with an extension dictionary holding an xrecord
+    let mut xdictionary = Dictionary::new();
+    xdictionary.handle = doc.allocate_handle();
+    xdictionary.owner = line;
+    xdictionary.hard_owner = true;
+    xdictionary.reactors.push(line);
+    let mut xrecord = XRecord::new();
+    xrecord.handle = doc.allocate_handle();
+    xrecord.owner = xdictionary.handle;
+    xrecord.reactors.push(xdictionary.handle);
+    xrecord.add_string(1, "payload");
+    xdictionary.add_entry("DATA", xrecord.handle);
+    let (dictionary, record) = (xdictionary.handle, xrecord.handle);
+    doc.objects.insert(dictionary, ObjectType::Dictionary(xdictionary));
+    doc.objects.insert(record, ObjectType::XRecord(xrecord));
+    doc.get_entity_mut(line).unwrap().common_mut().xdictionary_handle = Some(dictionary);
+
+    // A group in ACAD_GROUP, with the dictionary as its reactor
+    let groups = doc.header.acad_group_dict_handle;
+    let mut group = Group::new("PARTS");
+    group.handle = doc.allocate_handle();
+    group.owner = groups;
+    group.reactors.push(groups);
+    group.add_entity(line);
+    let group_handle = group.handle;
+    doc.objects.insert(group_handle, ObjectType::Group(group));
+    if let Some(ObjectType::Dictionary(dict)) = doc.objects.get_mut(&groups) {
+        dict.add_entry("PARTS", group_handle);
+    }
+
+    let bytes = DxfWriter::new(doc).write_to_vec().unwrap();
+    let read = DxfReader::from_reader(Cursor::new(bytes)).unwrap().read().unwrap();
+
+    match read.objects.get(&group_handle) {
+        Some(ObjectType::Group(group)) => {
+            assert_eq!(group.owner, groups);
+            assert_eq!(group.reactors, vec![groups]);
+            assert_eq!(group.entities, vec![line]);
+        }
+        other => panic!("expected the group, got {other:?}"),
+    }
+    match read.objects.get(&record) {
+        Some(ObjectType::XRecord(xrecord)) => {
+            assert_eq!(xrecord.owner, dictionary);
+            assert_eq!(xrecord.reactors, vec![dictionary]);
+            assert_eq!(xrecord.entries.len(), 1);
+        }
+        other => panic!("expected the xrecord, got {other:?}"),
+    }
+    match read.objects.get(&dictionary) {
+        Some(ObjectType::Dictionary(dict)) => {
+            assert!(dict.hard_owner);
+            assert_eq!(dict.reactors, vec![line]);
+        }
+        other => panic!("expected the extension dictionary, got {other:?}"),
+    }
+
+    let graph = read.ownership_graph();
+    assert_eq!(graph.node(line).unwrap().kind, OwnershipNodeKind::Entity);
+    assert_eq!(graph.node(line).unwrap().xdictionary, Some(dictionary));
+    let entry = &graph.owned(dictionary)[0];
+    assert_eq!((entry.handle, entry.kind, entry.key.as_deref()), (record, OwnershipKind::Hard, Some("DATA")));
+    assert!(graph.owned(groups).iter().any(|e| e.handle == group_handle && e.kind == OwnershipKind::Soft));
+    assert_eq!(&graph.owner_chain(record)[..2], &[dictionary, line]);
+    assert_eq!(graph.reacting_to(groups), vec![group_handle]);
+    assert_ne!(graph.node(group_handle).unwrap().owner, Handle::NULL);
+}
+
+#[test]
+fn test_identical_documents_write_identical_files() {
+    use acadrust::entities::{Circle, EntityType, Line, Text};
+    use acadrust::objects::{Dictionary, ObjectType, XRecord};
+    use acadrust::types::Vector3;
+    use acadrust::{CadDocument, DxfWriter};
+
+    fn build() -> CadDocument {
+        let mut doc = CadDocument::new();
+        for i in 0..30 {
+            let x = i as f64;
+            doc.add_entity(EntityType::Line(Line::from_points(Vector3::ZERO, Vector3::new(x, 1.0, 0.0)))).unwrap();
+            doc.add_entity(EntityType::Circle(Circle::from_coords(x, 0.0, 0.0, 1.0))).unwrap();
+            doc.add_entity(EntityType::Text(Text::with_value(format!("T{i}"), Vector3::new(x, 2.0, 0.0)))).unwrap();
+        }
+        let mut dictionary = Dictionary::new();
+        dictionary.handle = doc.allocate_handle();
+        dictionary.owner = doc.header.named_objects_dict_handle;
+        for i in 0..20 {
+            let mut record = XRecord::named(format!("R{i}"));
+            record.handle = doc.allocate_handle();
+            record.owner = dictionary.handle;
+            record.add_int32(90, i);
+            dictionary.add_entry(format!("R{i}"), record.handle);
+            doc.objects.insert(record.handle, ObjectType::XRecord(record));
+        }
+        let handle = dictionary.handle;
+        doc.objects.insert(handle, ObjectType::Dictionary(dictionary));
+        if let Some(ObjectType::Dictionary(root)) = doc.objects.get_mut(&doc.header.named_objects_dict_handle) {
+            root.add_entry("MYAPP", handle);
+        }
+        doc
+    }
+
+    let (first, second) = (build(), build());
+    assert_eq!(
+        DxfWriter::new(first.clone()).write_to_vec().unwrap(),
+        DxfWriter::new(second.clone()).write_to_vec().unwrap()
+    );
+    assert_eq!(
+        DxfWriter::new_binary(first).write_to_vec().unwrap(),
+        DxfWriter::new_binary(second).write_to_vec().unwrap()
+    );
+}
+
+/// Angles are radians in the model and degrees in DXF
+#[test]
+fn test_angles_converted_between_radians_and_dxf_degrees() {
+    use acadrust::entities::{EntityType, Text, Viewport};
+    use acadrust::tables::View;
+    use acadrust::types::Vector3;
+    use acadrust::{CadDocument, DxfWriter};
+    use std::f64::consts::PI;
+
+    let mut doc = CadDocument::new();
+    let mut text = Text::with_value("A", Vector3::ZERO);
+    text.rotation = PI / 4.0;
+    let text = doc.add_entity(EntityType::Text(text)).unwrap();
+    let mut viewport = Viewport::with_size(Vector3::new(5.0, 5.0, 0.0), 10.0, 10.0);
+    viewport.snap_angle = PI / 12.0;
+    viewport.twist_angle = PI / 6.0;
+    let viewport = doc.add_entity(EntityType::Viewport(Box::new(viewport))).unwrap();
+    let mut view = View::new("TWISTED");
+    view.twist_angle = PI / 3.0;
+    doc.views.add(view).unwrap();
+
+    let bytes = DxfWriter::new(doc).write_to_vec().unwrap();
+    let content = String::from_utf8_lossy(&bytes);
+    let lines: Vec<&str> = content.lines().map(str::trim).collect();
+    let written = |code: &str, degrees: f64| {
+        lines.windows(2).any(|w| w[0] == code && w[1].parse::<f64>().is_ok_and(|v| (v - degrees).abs() < 1e-9))
+    };
+    assert!(written("50", 45.0));
+    assert!(written("50", 15.0));
+    assert!(written("51", 30.0));
+    assert!(written("50", 60.0));
+
+    let read = DxfReader::from_bytes(bytes).unwrap().read().unwrap();
+    let close = |radians: f64, expected: f64| (radians - expected).abs() < 1e-12;
+    match read.get_entity(text) {
+        Some(EntityType::Text(text)) => assert!(close(text.rotation, PI / 4.0)),
+        other => panic!("expected the text, got {other:?}"),
+    }
+    match read.get_entity(viewport) {
+        Some(EntityType::Viewport(viewport)) => {
+            assert!(close(viewport.snap_angle, PI / 12.0));
+            assert!(close(viewport.twist_angle, PI / 6.0));
+        }
+        other => panic!("expected the viewport, got {other:?}"),
+    }
+    assert!(close(read.views.get("TWISTED").unwrap().twist_angle, PI / 3.0));
+}
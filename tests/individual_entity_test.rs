@@ -73,7 +73,7 @@ fn create_single_entity_doc(entity_name: &str) -> Option<CadDocument> {
             // Add proper knot vector for a cubic spline with 4 control points
             // Knot vector length should be: degree + control_points.len() + 1 = 3 + 4 + 1 = 8
             spline.knots = vec![0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0];
-            doc.add_entity(EntityType::Spline(spline)).ok()?;
+            doc.add_entity(EntityType::Spline(Box::new(spline))).ok()?;
         }
         "TEXT" => {
             let text = Text::with_value("Test", Vector3::new(x, y, 0.0))
@@ -85,7 +85,7 @@ fn create_single_entity_doc(entity_name: &str) -> Option<CadDocument> {
             mtext.value = "Multi\\PLine".to_string();
             mtext.insertion_point = Vector3::new(x, y, 0.0);
             mtext.height = 2.5;
-            doc.add_entity(EntityType::MText(mtext)).ok()?;
+            doc.add_entity(EntityType::MText(Box::new(mtext))).ok()?;
         }
         "SOLID" => {
             let solid = Solid::new(
@@ -135,7 +135,7 @@ fn create_single_entity_doc(entity_name: &str) -> Option<CadDocument> {
                 end: Vector2::new(x, y),
             }));
             hatch.paths.push(boundary);
-            doc.add_entity(EntityType::Hatch(hatch)).ok()?;
+            doc.add_entity(EntityType::Hatch(Box::new(hatch))).ok()?;
         }
         "INSERT" => {
             let insert = Insert::new("TestBlock", Vector3::new(x, y, 0.0));
@@ -147,11 +147,11 @@ fn create_single_entity_doc(entity_name: &str) -> Option<CadDocument> {
                 "Prompt".to_string(),
                 "Default".to_string(),
             );
-            doc.add_entity(EntityType::AttributeDefinition(attdef)).ok()?;
+            doc.add_entity(EntityType::AttributeDefinition(Box::new(attdef))).ok()?;
         }
         "ATTRIB" => {
             let attrib = AttributeEntity::new("TAG".to_string(), "Value".to_string());
-            doc.add_entity(EntityType::AttributeEntity(attrib)).ok()?;
+            doc.add_entity(EntityType::AttributeEntity(Box::new(attrib))).ok()?;
         }
         "LEADER" => {
             let mut leader = Leader::new();
@@ -160,7 +160,7 @@ fn create_single_entity_doc(entity_name: &str) -> Option<CadDocument> {
                 Vector3::new(x + 5.0, y + 3.0, 0.0),
                 Vector3::new(x + 8.0, y + 3.0, 0.0),
             ];
-            doc.add_entity(EntityType::Leader(leader)).ok()?;
+            doc.add_entity(EntityType::Leader(Box::new(leader))).ok()?;
         }
         "MULTILEADER" => {
             let mut multileader = MultiLeaderBuilder::new().build();
@@ -172,7 +172,7 @@ fn create_single_entity_doc(entity_name: &str) -> Option<CadDocument> {
             ];
             root.lines.push(line);
             multileader.context.leader_roots.push(root);
-            doc.add_entity(EntityType::MultiLeader(multileader)).ok()?;
+            doc.add_entity(EntityType::MultiLeader(Box::new(multileader))).ok()?;
         }
         "MLINE" => {
             let mut mline = MLineBuilder::new()
@@ -214,7 +214,7 @@ fn create_single_entity_doc(entity_name: &str) -> Option<CadDocument> {
         "TABLE" => {
             let mut table = TableBuilder::new(2, 2).build();
             table.insertion_point = Vector3::new(x, y, 0.0);
-            doc.add_entity(EntityType::Table(table)).ok()?;
+            doc.add_entity(EntityType::Table(Box::new(table))).ok()?;
         }
         "TOLERANCE" => {
             let mut tolerance = Tolerance::new();
@@ -243,7 +243,7 @@ fn create_single_entity_doc(entity_name: &str) -> Option<CadDocument> {
             viewport.center = Vector3::new(x, y, 0.0);
             viewport.width = 10.0;
             viewport.height = 10.0;
-            doc.add_entity(EntityType::Viewport(viewport)).ok()?;
+            doc.add_entity(EntityType::Viewport(Box::new(viewport))).ok()?;
         }
         _ => return None,
     }